@@ -4,7 +4,7 @@
 //! Uses PostgreSQL functions to transform data deterministically based on a seed.
 
 use anyhow::{bail, Result};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
 /// Known anonymization strategies
 pub const STRATEGIES: &[&str] = &[
@@ -16,6 +16,9 @@ pub const STRATEGIES: &[&str] = &[
     "null",
     "zero",
     "fake_uuid",
+    "hash_email_local",
+    "fake_phone",
+    "shift_date",
     "skip",
     "preserve",
 ];
@@ -26,6 +29,9 @@ pub struct AnonymizeRule {
     pub table_schema: String,
     pub table_name: String,
     pub column_name: Option<String>, // None = table-level rule (skip)
+    /// Keys inside a jsonb column (`profile->'address'->>'street'`); empty
+    /// for the whole column
+    pub json_path: Vec<String>,
     pub strategy: String,
 }
 
@@ -37,10 +43,21 @@ impl AnonymizeRule {
             table_schema: schema.to_string(),
             table_name: table.to_string(),
             column_name: Some(column.to_string()),
+            json_path: Vec::new(),
             strategy: strategy.to_string(),
         }
     }
 
+    /// Create a rule from a `columns` key: a column, or a path into a jsonb
+    /// column such as `profile->'email'`
+    pub fn from_key(schema: &str, table: &str, key: &str, strategy: &str) -> Result<Self> {
+        let (column, json_path) = parse_column_key(key)?;
+        Ok(Self {
+            json_path,
+            ..Self::column(schema, table, &column, strategy)
+        })
+    }
+
     /// Create a new table-level skip rule
     #[allow(dead_code)]
     pub fn skip_table(schema: &str, table: &str) -> Self {
@@ -48,6 +65,7 @@ impl AnonymizeRule {
             table_schema: schema.to_string(),
             table_name: table.to_string(),
             column_name: None,
+            json_path: Vec::new(),
             strategy: "skip".to_string(),
         }
     }
@@ -61,6 +79,79 @@ impl AnonymizeRule {
     pub fn qualified_table(&self) -> String {
         format!("{}.{}", self.table_schema, self.table_name)
     }
+
+    /// The column (and jsonb path) as written in rules
+    pub fn column_key(&self) -> Option<String> {
+        let column = self.column_name.as_ref()?;
+        let path: String = self
+            .json_path
+            .iter()
+            .map(|k| format!("->'{}'", k.replace('\'', "''")))
+            .collect();
+        Some(format!("{}{}", column, path))
+    }
+}
+
+/// Split a `columns` key into the column and its jsonb path:
+/// `metadata->'address'->>'street'` is (`metadata`, [`address`, `street`]).
+/// Keys are quoted with `'` or bare words/array indexes; the column may be
+/// double-quoted.
+pub fn parse_column_key(key: &str) -> Result<(String, Vec<String>)> {
+    let Some(arrow) = key.find("->") else {
+        return Ok((key.to_string(), Vec::new()));
+    };
+    let column = key[..arrow].trim();
+    let column = match column.strip_prefix('"').and_then(|c| c.strip_suffix('"')) {
+        Some(quoted) => quoted.replace("\"\"", "\""),
+        None => column.to_string(),
+    };
+    if column.is_empty() {
+        bail!("Invalid column key \"{}\": missing column before ->", key);
+    }
+
+    let mut path = Vec::new();
+    let mut rest = key[arrow..].trim();
+    while !rest.is_empty() {
+        let after_arrow = rest
+            .strip_prefix("->>")
+            .or_else(|| rest.strip_prefix("->"))
+            .ok_or_else(|| anyhow::anyhow!("Invalid column key \"{}\": expected -> or ->>", key))?
+            .trim_start();
+        let (segment, remaining) = if let Some(quoted) = after_arrow.strip_prefix('\'') {
+            // '' is an escaped quote inside the key
+            let mut segment = String::new();
+            let mut chars = quoted.char_indices().peekable();
+            let mut end = None;
+            while let Some((i, c)) = chars.next() {
+                if c == '\'' {
+                    if chars.peek().map(|(_, n)| *n) == Some('\'') {
+                        segment.push('\'');
+                        chars.next();
+                    } else {
+                        end = Some(i + 1);
+                        break;
+                    }
+                } else {
+                    segment.push(c);
+                }
+            }
+            let end = end.ok_or_else(|| {
+                anyhow::anyhow!("Invalid column key \"{}\": unterminated quote", key)
+            })?;
+            (segment, &quoted[end..])
+        } else {
+            let end = after_arrow
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after_arrow.len());
+            if end == 0 {
+                bail!("Invalid column key \"{}\": expected a key after ->", key);
+            }
+            (after_arrow[..end].to_string(), &after_arrow[end..])
+        };
+        path.push(segment);
+        rest = remaining.trim();
+    }
+    Ok((column, path))
 }
 
 /// Validate a strategy name
@@ -87,79 +178,156 @@ pub fn parse_table_name(name: &str) -> (String, String) {
     }
 }
 
-/// Build the SQL expression for a column transformation
-pub fn build_column_expression(column: &str, strategy: &str, seed: &str) -> String {
+/// SQL expression identifying a row for per-row strategies (`shift_date`),
+/// from its primary key columns
+pub fn row_key_expression(key_columns: &[String]) -> Option<String> {
+    if key_columns.is_empty() {
+        return None;
+    }
+    let parts: Vec<String> = key_columns
+        .iter()
+        .map(|c| format!("{}::text", crate::sql::quote_ident(c)))
+        .collect();
+    Some(format!("concat_ws('|', {})", parts.join(", ")))
+}
+
+/// Build the SQL value of `input` (a column or other text expression) after
+/// a strategy is applied
+///
+/// `row_key` keys per-row strategies; without one they key off the value.
+fn build_value(input: &str, strategy: &str, seed: &str, row_key: Option<&str>) -> String {
     let quoted_seed = format!("'{}'", seed.replace('\'', "''"));
     match strategy {
-        "null" => format!("NULL AS {}", crate::sql::quote_ident(column)),
-        "zero" => format!("0 AS {}", crate::sql::quote_ident(column)),
-        "preserve" => crate::sql::quote_ident(column),
-        "fake_email" => format!(
-            "pgcrate.anon_fake_email({}, {}) AS {}",
-            crate::sql::quote_ident(column),
-            quoted_seed,
-            crate::sql::quote_ident(column)
-        ),
-        "fake_name" => format!(
-            "pgcrate.anon_fake_name({}, {}) AS {}",
-            crate::sql::quote_ident(column),
-            quoted_seed,
-            crate::sql::quote_ident(column)
-        ),
-        "fake_first_name" => format!(
-            "pgcrate.anon_fake_first_name({}, {}) AS {}",
-            crate::sql::quote_ident(column),
-            quoted_seed,
-            crate::sql::quote_ident(column)
-        ),
-        "fake_last_name" => format!(
-            "pgcrate.anon_fake_last_name({}, {}) AS {}",
-            crate::sql::quote_ident(column),
-            quoted_seed,
-            crate::sql::quote_ident(column)
-        ),
-        "redact" => format!(
-            "pgcrate.anon_redact({}) AS {}",
-            crate::sql::quote_ident(column),
-            crate::sql::quote_ident(column)
-        ),
+        "null" => "NULL".to_string(),
+        "zero" => "0".to_string(),
+        "fake_email" => format!("pgcrate.anon_fake_email({}, {})", input, quoted_seed),
+        "fake_name" => format!("pgcrate.anon_fake_name({}, {})", input, quoted_seed),
+        "fake_first_name" => format!("pgcrate.anon_fake_first_name({}, {})", input, quoted_seed),
+        "fake_last_name" => format!("pgcrate.anon_fake_last_name({}, {})", input, quoted_seed),
+        "redact" => format!("pgcrate.anon_redact({})", input),
         "fake_uuid" => format!(
-            "pgcrate.anon_fake_uuid({}::text, {})::uuid AS {}",
-            crate::sql::quote_ident(column),
-            quoted_seed,
-            crate::sql::quote_ident(column)
+            "pgcrate.anon_fake_uuid({}::text, {})::uuid",
+            input, quoted_seed
         ),
-        // skip and unknown strategies preserve the column
-        _ => crate::sql::quote_ident(column),
+        "hash_email_local" => format!("pgcrate.anon_hash_email_local({}, {})", input, quoted_seed),
+        "fake_phone" => format!("pgcrate.anon_fake_phone({}, {})", input, quoted_seed),
+        "shift_date" => format!(
+            "pgcrate.anon_shift_date({}, {}, {})",
+            input,
+            row_key
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{}::text", input)),
+            quoted_seed
+        ),
+        // preserve, skip and unknown strategies keep the value
+        _ => input.to_string(),
+    }
+}
+
+/// Build the SQL value of a column after its transformation (used by
+/// `anonymize apply` as the UPDATE assignment)
+pub fn build_column_value(
+    column: &str,
+    strategy: &str,
+    seed: &str,
+    row_key: Option<&str>,
+) -> String {
+    build_value(&crate::sql::quote_ident(column), strategy, seed, row_key)
+}
+
+/// New value of a column under all of its rules, or `None` when it's kept.
+/// A whole-column rule wins over jsonb path rules; path rules rewrite only
+/// their keys, leaving documents without the key untouched.
+pub fn build_rules_value(
+    column: &str,
+    rules: &[&AnonymizeRule],
+    seed: &str,
+    row_key: Option<&str>,
+) -> Option<String> {
+    let col = crate::sql::quote_ident(column);
+    let applies = |r: &&&AnonymizeRule| {
+        r.column_name.as_deref() == Some(column) && r.strategy != "preserve" && r.strategy != "skip"
+    };
+
+    if let Some(rule) = rules
+        .iter()
+        .filter(applies)
+        .find(|r| r.json_path.is_empty())
+    {
+        return Some(build_column_value(column, &rule.strategy, seed, row_key));
+    }
+
+    let mut value: Option<String> = None;
+    for rule in rules.iter().filter(applies) {
+        let path = format!(
+            "ARRAY[{}]::text[]",
+            rule.json_path
+                .iter()
+                .map(|k| format!("'{}'", k.replace('\'', "''")))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let current = format!("({} #>> {})", col, path);
+        value = Some(format!(
+            "pgcrate.anon_jsonb_set({}, {}, to_jsonb({}))",
+            value.unwrap_or_else(|| format!("{}::jsonb", col)),
+            path,
+            build_value(&current, &rule.strategy, seed, row_key)
+        ));
+    }
+    value
+}
+
+/// Build the SQL expression for a column transformation, as a SELECT item
+/// aliased back to the column name (bare when the strategy keeps the value)
+pub fn build_column_expression(
+    column: &str,
+    strategy: &str,
+    seed: &str,
+    row_key: Option<&str>,
+) -> String {
+    let col = crate::sql::quote_ident(column);
+    let value = build_column_value(column, strategy, seed, row_key);
+    if value == col {
+        col
+    } else {
+        format!("{} AS {}", value, col)
     }
 }
 
 /// Build a SELECT query for anonymized data from a table
+///
+/// `key_columns` (the primary key) keys per-row strategies so that every
+/// `shift_date` column of a row moves by the same offset.
 pub fn build_anonymized_select(
     schema: &str,
     table: &str,
     columns: &[String],
+    key_columns: &[String],
     rules: &[AnonymizeRule],
     seed: &str,
 ) -> String {
-    // Build a map of column -> strategy for this table
-    let rule_map: HashMap<&str, &str> = rules
+    let table_rules: Vec<&AnonymizeRule> = rules
         .iter()
-        .filter(|r| r.table_schema == schema && r.table_name == table && r.column_name.is_some())
-        .map(|r| {
-            (
-                r.column_name.as_ref().unwrap().as_str(),
-                r.strategy.as_str(),
-            )
-        })
+        .filter(|r| r.table_schema == schema && r.table_name == table)
         .collect();
 
     // Build column expressions
+    let row_key = row_key_expression(key_columns);
     let col_exprs: Vec<String> = columns
         .iter()
         .map(|col| {
-            let strategy = rule_map.get(col.as_str()).copied().unwrap_or("preserve");
-            build_column_expression(col, strategy, seed)
+            let column_rule = table_rules
+                .iter()
+                .filter(|r| r.column_name.as_deref() == Some(col) && r.json_path.is_empty())
+                .find(|r| r.strategy != "preserve");
+            if let Some(rule) = column_rule {
+                return build_column_expression(col, &rule.strategy, seed, row_key.as_deref());
+            }
+            match build_rules_value(col, &table_rules, seed, row_key.as_deref()) {
+                Some(value) => format!("{} AS {}", value, crate::sql::quote_ident(col)),
+                None => crate::sql::quote_ident(col),
+            }
         })
         .collect();
 
@@ -179,6 +347,22 @@ pub fn is_excluded_schema(schema: &str) -> bool {
     EXCLUDED_SCHEMAS.contains(&schema) || schema.starts_with("pg_")
 }
 
+/// Setup function a strategy depends on, if any
+pub fn strategy_function(strategy: &str) -> Option<&'static str> {
+    match strategy {
+        "fake_email" => Some("anon_fake_email"),
+        "fake_name" => Some("anon_fake_name"),
+        "fake_first_name" => Some("anon_fake_first_name"),
+        "fake_last_name" => Some("anon_fake_last_name"),
+        "redact" => Some("anon_redact"),
+        "fake_uuid" => Some("anon_fake_uuid"),
+        "hash_email_local" => Some("anon_hash_email_local"),
+        "fake_phone" => Some("anon_fake_phone"),
+        "shift_date" => Some("anon_shift_date"),
+        _ => None,
+    }
+}
+
 /// Tables that should be skipped (based on rules)
 pub fn get_skipped_tables(rules: &[AnonymizeRule]) -> HashSet<String> {
     rules
@@ -188,6 +372,242 @@ pub fn get_skipped_tables(rules: &[AnonymizeRule]) -> HashSet<String> {
         .collect()
 }
 
+/// Rows of a table to include in a dump, from `sample` and `where` in the
+/// rules file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RowFilter {
+    /// Fraction of rows to keep
+    pub sample: Option<f64>,
+    pub predicate: Option<String>,
+}
+
+impl RowFilter {
+    /// SQL condition on the rows of `alias`, or `None` when every row is kept
+    ///
+    /// Sampling hashes the row's key (the whole row without a primary key)
+    /// with the seed, so a row is in or out of the sample in every query
+    /// that checks it, which is what keeps child tables consistent.
+    pub fn condition(&self, alias: &str, key_columns: &[String], seed: &str) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(predicate) = &self.predicate {
+            parts.push(format!("({})", predicate));
+        }
+        if let Some(sample) = self.sample.filter(|s| *s < 1.0) {
+            let key = if key_columns.is_empty() {
+                format!("ROW({}.*)::text", alias)
+            } else {
+                format!(
+                    "concat_ws('|', {})",
+                    key_columns
+                        .iter()
+                        .map(|c| format!("{}.{}::text", alias, crate::sql::quote_ident(c)))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+            // First 32 bits of the hash, compared against the sampled share
+            parts.push(format!(
+                "('x' || substr(md5('{}' || {}), 1, 8))::bit(32)::bigint < {}",
+                seed.replace('\'', "''"),
+                key,
+                (sample * 4_294_967_296.0).round() as i64
+            ));
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" AND "))
+        }
+    }
+
+    /// Short description for previews, e.g. `sample 10%, where id > 5`
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(sample) = self.sample {
+            parts.push(format!("sample {}%", sample * 100.0));
+        }
+        if let Some(predicate) = &self.predicate {
+            parts.push(format!("where {}", predicate));
+        }
+        parts.join(", ")
+    }
+}
+
+// =============================================================================
+// PII Detection (anonymize scan)
+// =============================================================================
+
+/// Kinds of personal data `anonymize scan` looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PiiKind {
+    Email,
+    Phone,
+    Ssn,
+    FullName,
+    FirstName,
+    LastName,
+    Address,
+    IpAddress,
+    BirthDate,
+}
+
+/// Tables whose `name` column is a person's name
+const PERSON_TABLES: &[&str] = &[
+    "users",
+    "user",
+    "customers",
+    "customer",
+    "people",
+    "person",
+    "persons",
+    "employees",
+    "employee",
+    "contacts",
+    "contact",
+    "members",
+    "member",
+    "patients",
+    "patient",
+    "students",
+    "student",
+    "accounts",
+    "account",
+];
+
+const TEXT_TYPES: &[&str] = &["text", "character varying", "character", "citext", "name"];
+const DATE_TYPES: &[&str] = &[
+    "date",
+    "timestamp without time zone",
+    "timestamp with time zone",
+];
+
+impl PiiKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PiiKind::Email => "email",
+            PiiKind::Phone => "phone",
+            PiiKind::Ssn => "ssn",
+            PiiKind::FullName => "name",
+            PiiKind::FirstName => "first name",
+            PiiKind::LastName => "last name",
+            PiiKind::Address => "address",
+            PiiKind::IpAddress => "ip address",
+            PiiKind::BirthDate => "birth date",
+        }
+    }
+
+    /// Suggested strategy for a column of this kind and type
+    /// (`data_type` as in information_schema.columns)
+    pub fn strategy(&self, data_type: &str) -> &'static str {
+        let is_text = TEXT_TYPES.contains(&data_type);
+        match self {
+            PiiKind::BirthDate if DATE_TYPES.contains(&data_type) => "shift_date",
+            // Text-only strategies can't produce other types
+            _ if !is_text => "null",
+            PiiKind::Email => "fake_email",
+            PiiKind::Phone => "fake_phone",
+            PiiKind::FullName => "fake_name",
+            PiiKind::FirstName => "fake_first_name",
+            PiiKind::LastName => "fake_last_name",
+            PiiKind::Ssn | PiiKind::Address | PiiKind::IpAddress | PiiKind::BirthDate => "redact",
+        }
+    }
+}
+
+/// Likely PII from a column's name (and, for bare `name`, its table's)
+pub fn detect_pii_by_name(table: &str, column: &str) -> Option<PiiKind> {
+    let column = column.to_lowercase();
+    let tokens: Vec<&str> = column
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect();
+    let has = |words: &[&str]| tokens.iter().any(|t| words.contains(t));
+    let joined = tokens.join("_");
+    let contains = |parts: &[&str]| parts.iter().any(|p| joined.contains(p));
+
+    if has(&["ip", "ipaddr", "ipaddress"]) || contains(&["remote_addr", "client_addr"]) {
+        Some(PiiKind::IpAddress)
+    } else if contains(&["email", "e_mail"]) {
+        Some(PiiKind::Email)
+    } else if has(&["ssn"]) || contains(&["social_security", "tax_id", "national_id"]) {
+        Some(PiiKind::Ssn)
+    } else if has(&[
+        "phone",
+        "mobile",
+        "cell",
+        "fax",
+        "tel",
+        "telephone",
+        "msisdn",
+    ]) || contains(&["phone"])
+    {
+        Some(PiiKind::Phone)
+    } else if has(&["dob", "birthday"]) || contains(&["birth"]) {
+        Some(PiiKind::BirthDate)
+    } else if has(&["firstname", "fname"]) || contains(&["first_name", "given_name"]) {
+        Some(PiiKind::FirstName)
+    } else if has(&["lastname", "lname", "surname"]) || contains(&["last_name", "family_name"]) {
+        Some(PiiKind::LastName)
+    } else if has(&["fullname"])
+        || contains(&["full_name", "display_name", "contact_name", "legal_name"])
+        || (joined == "name" && PERSON_TABLES.contains(&table.to_lowercase().as_str()))
+    {
+        Some(PiiKind::FullName)
+    } else if has(&[
+        "address", "addr", "street", "zip", "zipcode", "postcode", "postal",
+    ]) {
+        Some(PiiKind::Address)
+    } else {
+        None
+    }
+}
+
+/// Likely PII from one sampled value
+pub fn detect_pii_value(value: &str) -> Option<PiiKind> {
+    use regex::Regex;
+    use std::sync::OnceLock;
+
+    static PATTERNS: OnceLock<[(Regex, Option<PiiKind>); 5]> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        let re = |p: &str| Regex::new(p).expect("valid PII pattern");
+        [
+            (re(r"^\d{3}-\d{2}-\d{4}$"), Some(PiiKind::Ssn)),
+            (re(r"^(\d{1,3}\.){3}\d{1,3}$"), Some(PiiKind::IpAddress)),
+            (re(r"^[^@\s]+@[^@\s]+\.[A-Za-z]{2,}$"), Some(PiiKind::Email)),
+            // Dates would otherwise look like phone numbers
+            (re(r"^\d{4}-\d{2}-\d{2}"), None),
+            (re(r"^\+?[\d\s().-]+$"), Some(PiiKind::Phone)),
+        ]
+    });
+
+    let value = value.trim();
+    let (_, kind) = patterns.iter().find(|(re, _)| re.is_match(value))?;
+    if *kind == Some(PiiKind::Phone) {
+        // Plain numbers are more likely ids or amounts than phone numbers
+        let digits = value.chars().filter(|c| c.is_ascii_digit()).count();
+        let formatted = value.starts_with('+') || value.chars().any(|c| !c.is_ascii_digit());
+        if !(7..=15).contains(&digits) || !formatted {
+            return None;
+        }
+    }
+    *kind
+}
+
+/// The kind most sampled values look like, when at least 80% of them do
+pub fn detect_pii_in_samples(values: &[String]) -> Option<(PiiKind, usize)> {
+    let mut counts: Vec<(PiiKind, usize)> = Vec::new();
+    for kind in values.iter().filter_map(|v| detect_pii_value(v)) {
+        match counts.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((kind, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|(_, n)| *n)
+        .filter(|(_, n)| *n * 5 >= values.len() * 4)
+}
+
 // =============================================================================
 // SQL Definitions for Setup
 // =============================================================================
@@ -315,6 +735,98 @@ END;
 $$ LANGUAGE plpgsql IMMUTABLE
 "#;
 
+/// SQL for the anon_hash_email_local function: hashes the local part, keeps the domain
+pub const CREATE_ANON_HASH_EMAIL_LOCAL: &str = r#"
+CREATE OR REPLACE FUNCTION pgcrate.anon_hash_email_local(val TEXT, seed TEXT) RETURNS TEXT AS $$
+DECLARE
+    hash_val TEXT;
+BEGIN
+    IF val IS NULL THEN RETURN NULL; END IF;
+    -- Case-insensitive, so differently cased copies of an address still join
+    hash_val := substring(encode(sha256((lower(val) || seed)::bytea), 'hex'), 1, 12);
+    IF strpos(val, '@') = 0 THEN
+        RETURN 'user_' || hash_val;
+    END IF;
+    RETURN 'user_' || hash_val || substring(val, length(val) - strpos(reverse(val), '@') + 1);
+END;
+$$ LANGUAGE plpgsql IMMUTABLE
+"#;
+
+/// SQL for the anon_fake_phone function: replaces digits, keeping the
+/// formatting, the length and the country code of `+` numbers
+pub const CREATE_ANON_FAKE_PHONE: &str = r#"
+CREATE OR REPLACE FUNCTION pgcrate.anon_fake_phone(val TEXT, seed TEXT) RETURNS TEXT AS $$
+DECLARE
+    hash BYTEA;
+    result TEXT := '';
+    keep INT := 0;
+    digits INT := 0;
+    c TEXT;
+BEGIN
+    IF val IS NULL THEN RETURN NULL; END IF;
+    hash := sha256((val || seed)::bytea) || sha256((seed || val)::bytea);
+    IF left(val, 1) = '+' THEN
+        -- "+44 20 ..." has a separator after the country code; "+4420..." doesn't
+        keep := length(substring(val FROM '^\+(\d{1,3})\D'));
+        IF keep IS NULL THEN
+            keep := CASE WHEN substring(val, 2, 1) IN ('1', '7') THEN 1 ELSE 2 END;
+        END IF;
+    END IF;
+    FOR i IN 1..length(val) LOOP
+        c := substring(val, i, 1);
+        IF c ~ '[0-9]' THEN
+            digits := digits + 1;
+            IF digits > keep THEN
+                c := (get_byte(hash, (digits - 1) % 64) % 10)::text;
+            END IF;
+        END IF;
+        result := result || c;
+    END LOOP;
+    RETURN result;
+END;
+$$ LANGUAGE plpgsql IMMUTABLE
+"#;
+
+/// SQL for the anon_shift_date functions: moves a date or timestamp by
+/// 1-30 days either way, keyed by the row so a row's dates keep their order
+pub const CREATE_ANON_SHIFT_DATE: &str = r#"
+CREATE OR REPLACE FUNCTION pgcrate.anon_date_offset(key TEXT, seed TEXT) RETURNS INT AS $$
+    SELECT CASE WHEN h % 30 >= 0 THEN h % 30 + 1 ELSE h % 30 - 1 END
+    FROM (SELECT ('x' || substring(encode(sha256((key || seed)::bytea), 'hex'), 1, 8))::bit(32)::int AS h) s
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION pgcrate.anon_shift_date(val DATE, key TEXT, seed TEXT) RETURNS DATE AS $$
+    SELECT val + pgcrate.anon_date_offset(key, seed)
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION pgcrate.anon_shift_date(val TIMESTAMP, key TEXT, seed TEXT) RETURNS TIMESTAMP AS $$
+    SELECT val + make_interval(days => pgcrate.anon_date_offset(key, seed))
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION pgcrate.anon_shift_date(val TIMESTAMPTZ, key TEXT, seed TEXT) RETURNS TIMESTAMPTZ AS $$
+    SELECT val + make_interval(days => pgcrate.anon_date_offset(key, seed))
+$$ LANGUAGE sql STABLE;
+
+-- Dates and timestamps stored as text, e.g. inside jsonb
+CREATE OR REPLACE FUNCTION pgcrate.anon_shift_date(val TEXT, key TEXT, seed TEXT) RETURNS TEXT AS $$
+    SELECT CASE
+        WHEN val ~ '^\d{4}-\d{2}-\d{2}$' THEN (val::date + pgcrate.anon_date_offset(key, seed))::text
+        ELSE to_jsonb(val::timestamptz + make_interval(days => pgcrate.anon_date_offset(key, seed))) #>> '{}'
+    END
+$$ LANGUAGE sql STABLE
+"#;
+
+/// SQL for the anon_jsonb_set function: replaces the value at a path inside
+/// a jsonb document, leaving documents without that path unchanged
+pub const CREATE_ANON_JSONB_SET: &str = r#"
+CREATE OR REPLACE FUNCTION pgcrate.anon_jsonb_set(doc JSONB, path TEXT[], val JSONB) RETURNS JSONB AS $$
+    SELECT CASE
+        WHEN doc #> path IS NULL THEN doc
+        ELSE jsonb_set(doc, path, COALESCE(val, 'null'::jsonb), false)
+    END
+$$ LANGUAGE sql IMMUTABLE
+"#;
+
 /// All function creation SQL statements
 pub const ALL_FUNCTION_SQL: &[&str] = &[
     CREATE_ANON_FAKE_EMAIL,
@@ -323,6 +835,10 @@ pub const ALL_FUNCTION_SQL: &[&str] = &[
     CREATE_ANON_FAKE_LAST_NAME,
     CREATE_ANON_REDACT,
     CREATE_ANON_FAKE_UUID,
+    CREATE_ANON_HASH_EMAIL_LOCAL,
+    CREATE_ANON_FAKE_PHONE,
+    CREATE_ANON_SHIFT_DATE,
+    CREATE_ANON_JSONB_SET,
 ];
 
 #[cfg(test)]
@@ -363,29 +879,57 @@ mod tests {
 
     #[test]
     fn test_build_column_expression_null() {
-        let expr = build_column_expression("email", "null", "test");
+        let expr = build_column_expression("email", "null", "test", None);
         assert_eq!(expr, "NULL AS \"email\"");
     }
 
     #[test]
     fn test_build_column_expression_zero() {
-        let expr = build_column_expression("count", "zero", "test");
+        let expr = build_column_expression("count", "zero", "test", None);
         assert_eq!(expr, "0 AS \"count\"");
     }
 
     #[test]
     fn test_build_column_expression_preserve() {
-        let expr = build_column_expression("id", "preserve", "test");
+        let expr = build_column_expression("id", "preserve", "test", None);
         assert_eq!(expr, "\"id\"");
     }
 
     #[test]
     fn test_build_column_expression_fake_email() {
-        let expr = build_column_expression("email", "fake_email", "my-seed");
+        let expr = build_column_expression("email", "fake_email", "my-seed", None);
         assert!(expr.contains("pgcrate.anon_fake_email"));
         assert!(expr.contains("'my-seed'"));
     }
 
+    #[test]
+    fn test_build_column_expression_shift_date() {
+        let expr = build_column_expression("born_on", "shift_date", "s", None);
+        assert_eq!(
+            expr,
+            "pgcrate.anon_shift_date(\"born_on\", \"born_on\"::text, 's') AS \"born_on\""
+        );
+
+        let key = row_key_expression(&["tenant".to_string(), "id".to_string()]).unwrap();
+        assert_eq!(key, "concat_ws('|', \"tenant\"::text, \"id\"::text)");
+        let expr = build_column_expression("born_on", "shift_date", "s", Some(&key));
+        assert!(expr.starts_with("pgcrate.anon_shift_date(\"born_on\", concat_ws("));
+        assert!(row_key_expression(&[]).is_none());
+    }
+
+    #[test]
+    fn test_strategies_have_functions() {
+        for strategy in STRATEGIES {
+            if let Some(function) = strategy_function(strategy) {
+                let installs = ALL_FUNCTION_SQL
+                    .iter()
+                    .any(|sql| sql.contains(&format!("pgcrate.{}(", function)));
+                assert!(installs, "{} is not installed by setup", function);
+            }
+        }
+        assert_eq!(strategy_function("preserve"), None);
+    }
+
     #[test]
     fn test_build_anonymized_select() {
         let rules = vec![
@@ -393,7 +937,7 @@ mod tests {
             AnonymizeRule::column("public", "users", "name", "fake_name"),
         ];
         let columns = vec!["id".to_string(), "email".to_string(), "name".to_string()];
-        let sql = build_anonymized_select("public", "users", &columns, &rules, "my-seed");
+        let sql = build_anonymized_select("public", "users", &columns, &[], &rules, "my-seed");
 
         assert!(sql.starts_with("SELECT"));
         assert!(sql.contains("\"id\"")); // preserved
@@ -428,6 +972,158 @@ mod tests {
         assert_eq!(skipped.len(), 2);
     }
 
+    #[test]
+    fn test_detect_pii_by_name() {
+        assert_eq!(detect_pii_by_name("users", "email"), Some(PiiKind::Email));
+        assert_eq!(
+            detect_pii_by_name("users", "billing_email_address"),
+            Some(PiiKind::Email)
+        );
+        assert_eq!(
+            detect_pii_by_name("sessions", "ip_address"),
+            Some(PiiKind::IpAddress)
+        );
+        assert_eq!(
+            detect_pii_by_name("users", "mobile_phone"),
+            Some(PiiKind::Phone)
+        );
+        assert_eq!(
+            detect_pii_by_name("users", "firstName"),
+            Some(PiiKind::FirstName)
+        );
+        assert_eq!(
+            detect_pii_by_name("users", "surname"),
+            Some(PiiKind::LastName)
+        );
+        assert_eq!(
+            detect_pii_by_name("people", "date_of_birth"),
+            Some(PiiKind::BirthDate)
+        );
+        assert_eq!(
+            detect_pii_by_name("orders", "shipping_address"),
+            Some(PiiKind::Address)
+        );
+        assert_eq!(
+            detect_pii_by_name("customers", "name"),
+            Some(PiiKind::FullName)
+        );
+        assert_eq!(detect_pii_by_name("products", "name"), None);
+        assert_eq!(detect_pii_by_name("hotels", "hotel_id"), None);
+        assert_eq!(detect_pii_by_name("users", "created_at"), None);
+    }
+
+    #[test]
+    fn test_detect_pii_value() {
+        assert_eq!(detect_pii_value("jane@example.com"), Some(PiiKind::Email));
+        assert_eq!(detect_pii_value("123-45-6789"), Some(PiiKind::Ssn));
+        assert_eq!(detect_pii_value("10.0.0.1"), Some(PiiKind::IpAddress));
+        assert_eq!(detect_pii_value("+44 20 7946 0958"), Some(PiiKind::Phone));
+        assert_eq!(detect_pii_value("(555) 123-4567"), Some(PiiKind::Phone));
+        assert_eq!(detect_pii_value("5551234567"), None);
+        assert_eq!(detect_pii_value("2024-01-10"), None);
+        assert_eq!(detect_pii_value("hello world"), None);
+
+        let samples: Vec<String> = ["a@x.io", "b@y.io", "c@z.io", "d@w.io", "unknown"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(detect_pii_in_samples(&samples), Some((PiiKind::Email, 4)));
+        assert_eq!(detect_pii_in_samples(&samples[3..]), None);
+        assert_eq!(detect_pii_in_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_pii_strategy_fits_type() {
+        assert_eq!(PiiKind::Email.strategy("text"), "fake_email");
+        assert_eq!(PiiKind::Phone.strategy("character varying"), "fake_phone");
+        assert_eq!(PiiKind::Phone.strategy("bigint"), "null");
+        assert_eq!(PiiKind::IpAddress.strategy("inet"), "null");
+        assert_eq!(PiiKind::BirthDate.strategy("date"), "shift_date");
+        assert_eq!(PiiKind::BirthDate.strategy("text"), "redact");
+    }
+
+    #[test]
+    fn test_parse_column_key() {
+        assert_eq!(
+            parse_column_key("email").unwrap(),
+            ("email".to_string(), vec![])
+        );
+        assert_eq!(
+            parse_column_key("metadata->'address'->>'street'").unwrap(),
+            (
+                "metadata".to_string(),
+                vec!["address".to_string(), "street".to_string()]
+            )
+        );
+        assert_eq!(
+            parse_column_key("\"Profile\" -> 'it''s' -> 0").unwrap(),
+            (
+                "Profile".to_string(),
+                vec!["it's".to_string(), "0".to_string()]
+            )
+        );
+        assert!(parse_column_key("->'email'").is_err());
+        assert!(parse_column_key("profile->'email").is_err());
+        assert!(parse_column_key("profile->").is_err());
+
+        let rule =
+            AnonymizeRule::from_key("public", "users", "profile->>'email'", "fake_email").unwrap();
+        assert_eq!(rule.column_key().unwrap(), "profile->'email'");
+    }
+
+    #[test]
+    fn test_build_rules_value_jsonb_paths() {
+        let rules = [
+            AnonymizeRule::from_key("public", "users", "profile->'email'", "fake_email").unwrap(),
+            AnonymizeRule::from_key("public", "users", "profile->'a'->'b'", "redact").unwrap(),
+            AnonymizeRule::from_key("public", "users", "profile->'keep'", "preserve").unwrap(),
+        ];
+        let refs: Vec<&AnonymizeRule> = rules.iter().collect();
+        assert_eq!(
+            build_rules_value("profile", &refs, "s", None).unwrap(),
+            "pgcrate.anon_jsonb_set(pgcrate.anon_jsonb_set(\"profile\"::jsonb, ARRAY['email']::text[], \
+             to_jsonb(pgcrate.anon_fake_email((\"profile\" #>> ARRAY['email']::text[]), 's'))), \
+             ARRAY['a', 'b']::text[], to_jsonb(pgcrate.anon_redact((\"profile\" #>> ARRAY['a', 'b']::text[]))))"
+        );
+        assert_eq!(build_rules_value("email", &refs, "s", None), None);
+
+        // A whole-column rule replaces the path rules
+        let null_rule = AnonymizeRule::column("public", "users", "profile", "null");
+        let mut with_column = refs.clone();
+        with_column.push(&null_rule);
+        assert_eq!(
+            build_rules_value("profile", &with_column, "s", None).unwrap(),
+            "NULL"
+        );
+    }
+
+    #[test]
+    fn test_row_filter_condition() {
+        assert_eq!(RowFilter::default().condition("t", &[], "s"), None);
+        assert_eq!(
+            RowFilter {
+                sample: Some(1.0),
+                predicate: None
+            }
+            .condition("t", &[], "s"),
+            None
+        );
+
+        let filter = RowFilter {
+            sample: Some(0.25),
+            predicate: Some("active".to_string()),
+        };
+        assert_eq!(
+            filter.condition("p1", &["id".to_string()], "it's").unwrap(),
+            "(active) AND ('x' || substr(md5('it''s' || concat_ws('|', p1.\"id\"::text)), 1, 8))::bit(32)::bigint < 1073741824"
+        );
+        assert!(filter
+            .condition("p1", &[], "s")
+            .unwrap()
+            .contains("md5('s' || ROW(p1.*)::text)"));
+        assert_eq!(filter.describe(), "sample 25%, where active");
+    }
+
     #[test]
     fn test_anonymize_rule_is_skip() {
         let skip_rule = AnonymizeRule::skip_table("public", "audit_logs");
@@ -1,6 +1,7 @@
 //! Anonymize commands for pgcrate CLI.
 
 use crate::config::{url_matches_production_patterns, Config};
+use crate::introspect::IntrospectOptions;
 use crate::sql::quote_ident;
 use anyhow::{bail, Result};
 use colored::Colorize;
@@ -9,6 +10,9 @@ use std::io::{self, Write};
 use tokio_postgres::Client;
 
 use super::connect;
+use super::native_dump::{write_copy_block, DumpSchema};
+use super::subset::{load_foreign_keys, quote_table, ForeignKey};
+use crate::anonymize::RowFilter;
 
 /// Setup anonymization: install functions
 pub async fn anonymize_setup(
@@ -76,43 +80,19 @@ pub async fn anonymize_dump(
     anonymize_config_path: Option<&std::path::Path>,
     seed_override: Option<&str>,
     output: Option<&std::path::Path>,
+    with_schema: bool,
     dry_run: bool,
     quiet: bool,
     _verbose: bool,
 ) -> Result<(), anyhow::Error> {
-    use crate::anonymize::{get_skipped_tables, AnonymizeRule};
-    use crate::config::AnonymizeConfig;
+    use crate::anonymize::get_skipped_tables;
 
     let client = connect(database_url).await?;
 
-    // Check if anonymize is set up
-    check_anonymize_setup(&client).await?;
+    let (seed, rules, filters) = load_anonymize_rules(anonymize_config_path, seed_override)?;
 
-    // Load config
-    let anon_config = AnonymizeConfig::load(anonymize_config_path)?;
-
-    // Resolve seed: CLI > Env > File
-    let seed = seed_override
-        .map(|s| s.to_string())
-        .or_else(|| std::env::var("PGCRATE_ANONYMIZE_SEED").ok())
-        .or_else(|| anon_config.seed.clone())
-        .ok_or_else(|| {
-            anyhow::anyhow!("No anonymization seed provided. Use --seed flag, PGCRATE_ANONYMIZE_SEED env var, or 'seed' in pgcrate.anonymize.toml")
-        })?;
-
-    // Convert config rules to anonymize::AnonymizeRule
-    let mut rules = Vec::new();
-    for rule in anon_config.rules {
-        let (schema, table) = crate::anonymize::parse_table_name(&rule.table);
-        if rule.skip {
-            rules.push(AnonymizeRule::skip_table(&schema, &table));
-        } else if let Some(columns) = rule.columns {
-            for (col, strategy) in columns {
-                crate::anonymize::validate_strategy(&strategy)?;
-                rules.push(AnonymizeRule::column(&schema, &table, &col, &strategy));
-            }
-        }
-    }
+    // Check if anonymize is set up
+    check_anonymize_setup(&client, &rules).await?;
 
     // Warn about production patterns
     if url_matches_production_patterns(database_url, config) && !quiet {
@@ -129,10 +109,26 @@ pub async fn anonymize_dump(
 
     if dry_run {
         // Dry run mode - show what would happen
-        print_dry_run_preview(&client, &tables, &rules, &seed, &skipped_tables, quiet).await?;
+        print_dry_run_preview(
+            &client,
+            &tables,
+            &rules,
+            &filters,
+            &seed,
+            &skipped_tables,
+            quiet,
+        )
+        .await?;
         return Ok(());
     }
 
+    // Skipped tables are still created, just left empty
+    let schema = if with_schema {
+        Some(DumpSchema::load(&client, &IntrospectOptions::default()).await?)
+    } else {
+        None
+    };
+
     // Dump to file or stdout
     let is_stdout = output.is_none() || output == Some(std::path::Path::new("-"));
     let mut writer: Box<dyn Write> = if is_stdout {
@@ -141,7 +137,17 @@ pub async fn anonymize_dump(
         Box::new(std::fs::File::create(output.unwrap())?)
     };
 
-    execute_anonymize_dump(&client, &tables, &rules, &seed, &mut *writer, quiet).await?;
+    execute_anonymize_dump(
+        &client,
+        &tables,
+        &rules,
+        &filters,
+        &seed,
+        schema.as_ref(),
+        &mut *writer,
+        quiet,
+    )
+    .await?;
 
     if !quiet && !is_stdout {
         println!();
@@ -154,20 +160,592 @@ pub async fn anonymize_dump(
     Ok(())
 }
 
-/// Check if anonymize setup has been run (functions exist)
-async fn check_anonymize_setup(client: &Client) -> Result<(), anyhow::Error> {
-    let exists = client
-        .query_opt(
-            "SELECT 1 FROM pg_proc p JOIN pg_namespace n ON p.pronamespace = n.oid
-             WHERE n.nspname = 'pgcrate' AND p.proname = 'anon_fake_email'",
+/// A column `anonymize scan` flagged as likely PII
+struct PiiFinding {
+    schema: String,
+    table: String,
+    column: String,
+    strategy: &'static str,
+    kind: crate::anonymize::PiiKind,
+    reason: String,
+}
+
+/// Scan columns for likely PII and write suggested rules for review
+pub async fn anonymize_scan(
+    database_url: &str,
+    output: &std::path::Path,
+    sample_size: i64,
+    force: bool,
+    quiet: bool,
+) -> Result<(), anyhow::Error> {
+    use crate::anonymize::{detect_pii_by_name, detect_pii_in_samples, is_excluded_schema};
+
+    let is_stdout = output == std::path::Path::new("-");
+    if !is_stdout && output.exists() && !force {
+        bail!(
+            "{} already exists.\nHint: Use --force to overwrite it, or --output - to print the suggestions.",
+            output.display()
+        );
+    }
+
+    let client = connect(database_url).await?;
+    let rows = client
+        .query(
+            "SELECT c.table_schema::text, c.table_name::text, c.column_name::text, c.data_type::text
+             FROM information_schema.columns c
+             JOIN information_schema.tables t
+               ON t.table_schema = c.table_schema AND t.table_name = c.table_name
+             WHERE t.table_type = 'BASE TABLE'
+             ORDER BY c.table_schema, c.table_name, c.ordinal_position",
             &[],
         )
         .await?;
 
-    if exists.is_none() {
+    let text_types = ["text", "character varying", "character", "citext"];
+    let mut findings: Vec<PiiFinding> = Vec::new();
+    let mut tables: HashSet<String> = HashSet::new();
+    for row in &rows {
+        let schema: String = row.get(0);
+        let table: String = row.get(1);
+        let column: String = row.get(2);
+        let data_type: String = row.get(3);
+        if is_excluded_schema(&schema) {
+            continue;
+        }
+        tables.insert(format!("{}.{}", schema, table));
+
+        let detected = if let Some(kind) = detect_pii_by_name(&table, &column) {
+            Some((kind, format!("column name ({})", kind.label())))
+        } else if data_type == "inet" || data_type == "cidr" {
+            Some((
+                crate::anonymize::PiiKind::IpAddress,
+                format!("column type ({})", data_type),
+            ))
+        } else if text_types.contains(&data_type.as_str()) {
+            let samples: Vec<String> = client
+                .query(
+                    &format!(
+                        "SELECT {col}::text FROM {}.{} WHERE {col} IS NOT NULL LIMIT {}",
+                        quote_ident(&schema),
+                        quote_ident(&table),
+                        sample_size,
+                        col = quote_ident(&column)
+                    ),
+                    &[],
+                )
+                .await?
+                .iter()
+                .map(|r| r.get(0))
+                .collect();
+            detect_pii_in_samples(&samples).map(|(kind, matched)| {
+                (
+                    kind,
+                    format!(
+                        "{} of {} sampled values look like {}",
+                        matched,
+                        samples.len(),
+                        kind.label()
+                    ),
+                )
+            })
+        } else {
+            None
+        };
+
+        if let Some((kind, reason)) = detected {
+            findings.push(PiiFinding {
+                strategy: kind.strategy(&data_type),
+                schema,
+                table,
+                column,
+                kind,
+                reason,
+            });
+        }
+    }
+
+    let toml = suggested_rules_toml(&findings);
+    if is_stdout {
+        print!("{}", toml);
+    } else {
+        std::fs::write(output, &toml)?;
+    }
+
+    if !quiet {
+        let flagged: HashSet<(&str, &str)> = findings
+            .iter()
+            .map(|f| (f.schema.as_str(), f.table.as_str()))
+            .collect();
+        for f in &findings {
+            eprintln!(
+                "  {:<40} {:<12} {:<16} {}",
+                format!("{}.{}.{}", f.schema, f.table, f.column),
+                f.kind.label(),
+                f.strategy,
+                f.reason
+            );
+        }
+        eprintln!(
+            "Scanned {} tables: {} likely PII columns in {} tables.",
+            tables.len(),
+            findings.len(),
+            flagged.len()
+        );
+        if !is_stdout {
+            eprintln!(
+                "{}",
+                format!(
+                    "Wrote {}. Review every rule, then preview with: pgcrate anonymize dump --dry-run",
+                    output.display()
+                )
+                .green()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// `pgcrate.anonymize.toml` contents for the findings, one rule per table
+fn suggested_rules_toml(findings: &[PiiFinding]) -> String {
+    let key = |name: &str| {
+        if !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            name.to_string()
+        } else {
+            toml::Value::String(name.to_string()).to_string()
+        }
+    };
+
+    let mut out = String::new();
+    out.push_str(
+        "# Suggested by `pgcrate anonymize scan`. Detection is heuristic:
+",
+    );
+    out.push_str(
+        "# review every rule, and check columns it didn't flag.
+",
+    );
+    out.push_str(
+        "#
+",
+    );
+    out.push_str(
+        "# Set the seed here or with PGCRATE_ANONYMIZE_SEED (keep it secret).
+",
+    );
+    out.push_str("# seed = \"\"\n");
+
+    let mut i = 0;
+    while i < findings.len() {
+        let table: Vec<&PiiFinding> = findings[i..]
+            .iter()
+            .take_while(|f| f.schema == findings[i].schema && f.table == findings[i].table)
+            .collect();
+        i += table.len();
+
+        out.push_str("\n[[rules]]\n");
+        out.push_str(&format!(
+            "table = {}\n",
+            toml::Value::String(format!("{}.{}", table[0].schema, table[0].table))
+        ));
+        for f in &table {
+            out.push_str(&format!("# {}: {}\n", f.column, f.reason));
+        }
+        let columns: Vec<String> = table
+            .iter()
+            .map(|f| format!("{} = \"{}\"", key(&f.column), f.strategy))
+            .collect();
+        out.push_str(&format!("columns = {{ {} }}\n", columns.join(", ")));
+    }
+    out
+}
+
+/// Seed, rules and row filters from pgcrate.anonymize.toml; the seed comes from the CLI,
+/// then PGCRATE_ANONYMIZE_SEED, then the file
+#[allow(clippy::type_complexity)]
+fn load_anonymize_rules(
+    anonymize_config_path: Option<&std::path::Path>,
+    seed_override: Option<&str>,
+) -> Result<
+    (
+        String,
+        Vec<crate::anonymize::AnonymizeRule>,
+        HashMap<String, RowFilter>,
+    ),
+    anyhow::Error,
+> {
+    use crate::config::AnonymizeConfig;
+
+    let anon_config = AnonymizeConfig::load(anonymize_config_path)?;
+
+    // Resolve seed: CLI > Env > File
+    let seed = seed_override
+        .map(|s| s.to_string())
+        .or_else(|| std::env::var("PGCRATE_ANONYMIZE_SEED").ok())
+        .or_else(|| anon_config.seed.clone())
+        .ok_or_else(|| {
+            anyhow::anyhow!("No anonymization seed provided. Use --seed flag, PGCRATE_ANONYMIZE_SEED env var, or 'seed' in pgcrate.anonymize.toml")
+        })?;
+
+    let (rules, filters) = convert_anonymize_rules(anon_config.rules)?;
+    Ok((seed, rules, filters))
+}
+
+/// Convert config rules to column rules plus row filters per qualified table
+pub fn convert_anonymize_rules(
+    config_rules: Vec<crate::config::AnonymizeRule>,
+) -> Result<
+    (
+        Vec<crate::anonymize::AnonymizeRule>,
+        HashMap<String, RowFilter>,
+    ),
+    anyhow::Error,
+> {
+    use crate::anonymize::AnonymizeRule;
+
+    let mut rules = Vec::new();
+    let mut filters = HashMap::new();
+    for rule in config_rules {
+        let (schema, table) = crate::anonymize::parse_table_name(&rule.table);
+        if rule.skip {
+            rules.push(AnonymizeRule::skip_table(&schema, &table));
+            continue;
+        }
+        if let Some(columns) = rule.columns {
+            for (col, strategy) in columns {
+                crate::anonymize::validate_strategy(&strategy)?;
+                rules.push(AnonymizeRule::from_key(&schema, &table, &col, &strategy)?);
+            }
+        }
+        if let Some(sample) = rule.sample {
+            if !(sample > 0.0 && sample <= 1.0) {
+                bail!(
+                    "Invalid sample {} for {}: must be greater than 0 and at most 1",
+                    sample,
+                    rule.table
+                );
+            }
+        }
+        if rule.sample.is_some() || rule.where_clause.is_some() {
+            filters.insert(
+                format!("{}.{}", schema, table),
+                RowFilter {
+                    sample: rule.sample,
+                    predicate: rule.where_clause,
+                },
+            );
+        }
+    }
+    Ok((rules, filters))
+}
+
+/// Anonymize the database in place: UPDATE every ruled column in batches,
+/// and empty skipped tables
+#[allow(clippy::too_many_arguments)]
+pub async fn anonymize_apply(
+    database_url: &str,
+    config: &Config,
+    anonymize_config_path: Option<&std::path::Path>,
+    seed_override: Option<&str>,
+    batch_size: i64,
+    dry_run: bool,
+    yes: bool,
+    quiet: bool,
+) -> Result<(), anyhow::Error> {
+    use crate::anonymize::{build_rules_value, row_key_expression};
+
+    if url_matches_production_patterns(database_url, config) {
+        bail!(
+            "Refusing to anonymize in place: the database URL matches production patterns.\n\
+             Hint: anonymize apply is for scrubbing a restored copy (staging, dev). \
+             Use `pgcrate anonymize dump` to extract data from production."
+        );
+    }
+    if !yes && !dry_run {
+        bail!(
+            "anonymize apply requires --yes to confirm. This overwrites data in place and cannot be undone."
+        );
+    }
+
+    let (seed, rules, filters) = load_anonymize_rules(anonymize_config_path, seed_override)?;
+    if !filters.is_empty() && !quiet {
+        eprintln!(
+            "{}",
+            "Note: sample and where only shape dumps; anonymize apply updates every row.".yellow()
+        );
+    }
+    let client = connect(database_url).await?;
+    check_anonymize_setup(&client, &rules).await?;
+
+    // Group column rules by table, in rule order
+    let mut tables: Vec<(String, String)> = Vec::new();
+    for rule in &rules {
+        let key = (rule.table_schema.clone(), rule.table_name.clone());
+        if !tables.contains(&key) {
+            tables.push(key);
+        }
+    }
+
+    let mut total_rows: u64 = 0;
+    for (schema, table) in &tables {
+        let qualified = format!("{}.{}", schema, table);
+        let table_sql = format!("{}.{}", quote_ident(schema), quote_ident(table));
+        let table_rules: Vec<&crate::anonymize::AnonymizeRule> = rules
+            .iter()
+            .filter(|r| &r.table_schema == schema && &r.table_name == table)
+            .collect();
+
+        if table_rules.iter().any(|r| r.is_skip()) {
+            if dry_run {
+                println!(
+                    "  {} Would empty skipped table {}",
+                    "[dry-run]".blue(),
+                    qualified
+                );
+            } else {
+                if !quiet {
+                    eprintln!("  Emptying skipped table {}", qualified);
+                }
+                client
+                    .batch_execute(&format!("TRUNCATE {}", table_sql))
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to empty {}: {}", qualified, e))?;
+            }
+            continue;
+        }
+
+        let key_columns = get_primary_key_columns(&client, schema, table).await?;
+        let row_key = row_key_expression(&key_columns);
+        let mut columns: Vec<&str> = Vec::new();
+        for column in table_rules.iter().filter_map(|r| r.column_name.as_deref()) {
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+        let assignments: Vec<String> = columns
+            .iter()
+            .filter_map(|column| {
+                build_rules_value(column, &table_rules, &seed, row_key.as_deref())
+                    .map(|value| format!("{} = {}", quote_ident(column), value))
+            })
+            .collect();
+        if assignments.is_empty() {
+            continue;
+        }
+        let row_count = get_row_count(&client, schema, table).await?;
+
+        if dry_run {
+            println!(
+                "  {} Would update {} rows in {}: {}",
+                "[dry-run]".blue(),
+                format_number(row_count),
+                qualified,
+                table_rules
+                    .iter()
+                    .filter_map(|r| r.column_key())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            continue;
+        }
+
+        // Batch by primary key, unless the key itself is being rewritten
+        let batchable = !key_columns.is_empty()
+            && !table_rules.iter().any(|r| {
+                r.column_name
+                    .as_ref()
+                    .is_some_and(|c| key_columns.contains(c))
+            });
+        let update = format!("UPDATE {} SET {}", table_sql, assignments.join(", "));
+        let updated = if batchable {
+            update_in_batches(
+                &client,
+                &table_sql,
+                &key_columns,
+                &update,
+                batch_size,
+                &qualified,
+                row_count,
+                quiet,
+            )
+            .await?
+        } else {
+            if !quiet {
+                eprint!(
+                    "  Anonymizing {} ({} rows)...",
+                    qualified,
+                    format_number(row_count)
+                );
+                io::stderr().flush()?;
+            }
+            client.execute(&update, &[]).await?
+        };
+        if !quiet {
+            eprintln!(" done");
+        }
+        total_rows += updated;
+    }
+
+    if !quiet && !dry_run {
+        println!();
+        println!(
+            "{}",
+            format!(
+                "Anonymized {} rows in place.",
+                format_number(total_rows as i64)
+            )
+            .green()
+        );
+    }
+
+    Ok(())
+}
+
+/// Run `update` over the table in primary-key order, `batch_size` rows per
+/// transaction, returning the number of rows updated
+#[allow(clippy::too_many_arguments)]
+async fn update_in_batches(
+    client: &Client,
+    table_sql: &str,
+    key_columns: &[String],
+    update: &str,
+    batch_size: i64,
+    qualified: &str,
+    row_count: i64,
+    quiet: bool,
+) -> Result<u64, anyhow::Error> {
+    let key_types: Vec<String> = client
+        .query(
+            "SELECT format_type(a.atttypid, a.atttypmod)
+             FROM unnest($2::text[]) WITH ORDINALITY k(name, i)
+             JOIN pg_attribute a ON a.attrelid = $1::text::regclass AND a.attname = k.name
+             ORDER BY k.i",
+            &[&table_sql, &key_columns],
+        )
+        .await?
+        .iter()
+        .map(|r| r.get(0))
+        .collect();
+    let key_list = key_columns
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let key = format!("ROW({})", key_list);
+    let key_text = key_columns
+        .iter()
+        .map(|c| format!("{}::text", quote_ident(c)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Primary key values can't be NULL
+    let literal = |values: &[String]| {
+        let parts: Vec<String> = values
+            .iter()
+            .zip(&key_types)
+            .map(|(v, ty)| format!("'{}'::{}", v.replace('\'', "''"), ty))
+            .collect();
+        format!("ROW({})", parts.join(", "))
+    };
+
+    let mut last: Option<Vec<String>> = None;
+    let mut updated: u64 = 0;
+    loop {
+        let after = last.as_ref().map(|l| format!("{} > {}", key, literal(l)));
+        // Upper key of this batch; none means the rest fits in one batch
+        let upper: Option<Vec<String>> = client
+            .query_opt(
+                &format!(
+                    "SELECT {} FROM {} {} ORDER BY {} OFFSET {} LIMIT 1",
+                    key_text,
+                    table_sql,
+                    after
+                        .as_ref()
+                        .map(|a| format!("WHERE {}", a))
+                        .unwrap_or_default(),
+                    key_list,
+                    batch_size - 1
+                ),
+                &[],
+            )
+            .await?
+            .map(|row| (0..key_columns.len()).map(|i| row.get(i)).collect());
+
+        let mut conditions: Vec<String> = after.into_iter().collect();
+        if let Some(upper) = &upper {
+            conditions.push(format!("{} <= {}", key, literal(upper)));
+        }
+        let sql = if conditions.is_empty() {
+            update.to_string()
+        } else {
+            format!("{} WHERE {}", update, conditions.join(" AND "))
+        };
+        updated += client.execute(&sql, &[]).await?;
+
+        if !quiet {
+            eprint!(
+                "\r  Anonymizing {} ({}/{} rows)...",
+                qualified,
+                format_number(updated as i64),
+                format_number(row_count)
+            );
+            io::stderr().flush()?;
+        }
+
+        match upper {
+            Some(upper) => last = Some(upper),
+            None => break,
+        }
+    }
+    Ok(updated)
+}
+
+/// Check if anonymize setup has been run (functions exist), including the
+/// functions of every strategy the rules use
+async fn check_anonymize_setup(
+    client: &Client,
+    rules: &[crate::anonymize::AnonymizeRule],
+) -> Result<(), anyhow::Error> {
+    use crate::anonymize::strategy_function;
+
+    let installed: HashSet<String> = client
+        .query(
+            "SELECT p.proname::text FROM pg_proc p JOIN pg_namespace n ON p.pronamespace = n.oid
+             WHERE n.nspname = 'pgcrate' AND p.proname LIKE 'anon\\_%'",
+            &[],
+        )
+        .await?
+        .iter()
+        .map(|r| r.get(0))
+        .collect();
+
+    if !installed.contains("anon_fake_email") {
         bail!("Anonymize functions not found.\nHint: Run `pgcrate anonymize setup` first.");
     }
 
+    let mut missing: Vec<&str> = rules
+        .iter()
+        .filter_map(|r| strategy_function(&r.strategy))
+        .chain(
+            rules
+                .iter()
+                .any(|r| !r.json_path.is_empty())
+                .then_some("anon_jsonb_set"),
+        )
+        .filter(|f| !installed.contains(*f))
+        .collect();
+    missing.sort();
+    missing.dedup();
+    if !missing.is_empty() {
+        bail!(
+            "Anonymize functions missing: {}\nHint: Run `pgcrate anonymize setup` again to install newer strategies.",
+            missing.join(", ")
+        );
+    }
+
     Ok(())
 }
 
@@ -241,6 +819,26 @@ pub async fn get_table_columns(
     Ok(rows.iter().map(|r| r.get("column_name")).collect())
 }
 
+/// Primary key columns of a table, in key order (empty without a primary key)
+pub async fn get_primary_key_columns(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<String>, anyhow::Error> {
+    let rows = client
+        .query(
+            "SELECT a.attname::text
+             FROM pg_index i
+             JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+             WHERE i.indrelid = $1::text::regclass AND i.indisprimary
+             ORDER BY array_position(i.indkey::int2[], a.attnum)",
+            &[&format!("{}.{}", quote_ident(schema), quote_ident(table))],
+        )
+        .await?;
+
+    Ok(rows.iter().map(|r| r.get(0)).collect())
+}
+
 /// Get row count for a table
 pub async fn get_row_count(
     client: &Client,
@@ -266,6 +864,7 @@ async fn print_dry_run_preview(
     client: &Client,
     tables: &[TableInfo],
     rules: &[crate::anonymize::AnonymizeRule],
+    filters: &HashMap<String, RowFilter>,
     seed: &str,
     skipped_tables: &HashSet<String>,
     quiet: bool,
@@ -288,22 +887,17 @@ async fn print_dry_run_preview(
     println!("  Seed: {}", masked_seed);
     println!();
 
-    // Build rule map for quick lookup
-    let rule_map: HashMap<String, &crate::anonymize::AnonymizeRule> = rules
-        .iter()
-        .filter(|r| r.column_name.is_some())
-        .map(|r| {
-            (
-                format!(
-                    "{}.{}.{}",
-                    r.table_schema,
-                    r.table_name,
-                    r.column_name.as_ref().unwrap()
-                ),
-                r,
-            )
-        })
-        .collect();
+    // Build rule map for quick lookup; jsonb path rules list under their column
+    let mut rule_map: HashMap<String, Vec<&crate::anonymize::AnonymizeRule>> = HashMap::new();
+    for r in rules.iter().filter(|r| r.column_name.is_some()) {
+        let key = format!(
+            "{}.{}.{}",
+            r.table_schema,
+            r.table_name,
+            r.column_name.as_ref().unwrap()
+        );
+        rule_map.entry(key).or_default().push(r);
+    }
 
     let mut total_rows: i64 = 0;
     let mut preserved_columns: Vec<String> = Vec::new();
@@ -318,19 +912,37 @@ async fn print_dry_run_preview(
             table.qualified(),
             format_number(row_count)
         );
+        if let Some(filter) = filters.get(&table.qualified()) {
+            println!("  rows: {}", filter.describe());
+        }
 
         for col in &columns {
             let key = format!("{}.{}.{}", table.schema, table.name, col);
-            let strategy = rule_map
-                .get(&key)
+            let column_rules = rule_map.get(&key).cloned().unwrap_or_default();
+            let path_rules: Vec<_> = column_rules
+                .iter()
+                .filter(|r| !r.json_path.is_empty())
+                .collect();
+            let strategy = column_rules
+                .iter()
+                .find(|r| r.json_path.is_empty())
                 .map(|r| r.strategy.as_str())
-                .unwrap_or("preserve");
+                .unwrap_or(if path_rules.is_empty() {
+                    "preserve"
+                } else {
+                    "(paths)"
+                });
 
             if strategy == "preserve" {
                 preserved_columns.push(key.clone());
             }
 
             println!("  {:<16} {}", col, strategy);
+            for rule in path_rules {
+                if let Some(path) = rule.column_key() {
+                    println!("    {:<14} {}", &path[col.len()..], rule.strategy);
+                }
+            }
         }
         println!();
     }
@@ -384,34 +996,130 @@ pub fn format_number(n: i64) -> String {
     result
 }
 
-/// Internal execution engine for anonymization dump
+/// Builds each table's anonymized `COPY ... TO STDOUT` query from the column
+/// rules and row filters; shared by `anonymize dump` and `bootstrap`
+pub struct AnonymizedCopy<'a> {
+    rules: &'a [crate::anonymize::AnonymizeRule],
+    filters: &'a HashMap<String, RowFilter>,
+    seed: &'a str,
+    foreign_keys: Vec<ForeignKey>,
+    filter_keys: HashMap<String, Vec<String>>,
+}
+
+impl<'a> AnonymizedCopy<'a> {
+    pub async fn new(
+        client: &Client,
+        tables: &[TableInfo],
+        rules: &'a [crate::anonymize::AnonymizeRule],
+        filters: &'a HashMap<String, RowFilter>,
+        seed: &'a str,
+    ) -> Result<Self, anyhow::Error> {
+        // Filtered tables: sampling keys off the primary key, and children
+        // only keep rows whose parents are dumped
+        let (foreign_keys, filter_keys) = if filters.is_empty() {
+            (Vec::new(), HashMap::new())
+        } else {
+            let table_set: HashSet<String> = tables.iter().map(|t| t.qualified()).collect();
+            let mut filter_keys = HashMap::new();
+            for qualified in filters.keys() {
+                let (schema, name) = crate::anonymize::parse_table_name(qualified);
+                filter_keys.insert(
+                    qualified.clone(),
+                    get_primary_key_columns(client, &schema, &name).await?,
+                );
+            }
+            (load_foreign_keys(client, &table_set).await?, filter_keys)
+        };
+        Ok(Self {
+            rules,
+            filters,
+            seed,
+            foreign_keys,
+            filter_keys,
+        })
+    }
+
+    /// The table's columns and the COPY query streaming its anonymized rows
+    pub async fn copy_out_sql(
+        &self,
+        client: &Client,
+        table: &TableInfo,
+    ) -> Result<(Vec<String>, String), anyhow::Error> {
+        use crate::anonymize::build_anonymized_select;
+
+        let columns = get_table_columns(client, &table.schema, &table.name).await?;
+
+        // Per-row strategies key off the primary key
+        let key_columns = if self.rules.iter().any(|r| {
+            r.strategy == "shift_date"
+                && r.table_schema == table.schema
+                && r.table_name == table.name
+        }) {
+            get_primary_key_columns(client, &table.schema, &table.name).await?
+        } else {
+            Vec::new()
+        };
+
+        // Build the SELECT query
+        let mut select_sql = build_anonymized_select(
+            &table.schema,
+            &table.name,
+            &columns,
+            &key_columns,
+            self.rules,
+            self.seed,
+        );
+        let qualified = table.qualified();
+        if let Some(condition) = dump_row_condition(
+            &qualified,
+            &quote_table(&qualified),
+            self.filters,
+            &self.filter_keys,
+            &self.foreign_keys,
+            self.seed,
+            &mut Vec::new(),
+        ) {
+            select_sql = format!("{} WHERE {}", select_sql, condition);
+        }
+
+        Ok((columns, format!("COPY ({}) TO STDOUT", select_sql)))
+    }
+}
+
+/// Internal execution engine for anonymization dump; with `schema`, the
+/// data is wrapped in the DDL to load it into an empty database
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_anonymize_dump(
     client: &Client,
     tables: &[TableInfo],
     rules: &[crate::anonymize::AnonymizeRule],
+    filters: &HashMap<String, RowFilter>,
     seed: &str,
+    schema: Option<&DumpSchema>,
     writer: &mut dyn Write,
     quiet: bool,
 ) -> Result<(), anyhow::Error> {
-    use crate::anonymize::build_anonymized_select;
-    use futures_util::StreamExt;
-
     // Sort tables by FK dependency order
     let ordered_tables = order_tables_by_fk(client, tables).await?;
+    let copy = AnonymizedCopy::new(client, tables, rules, filters, seed).await?;
 
     // Write header
-    writeln!(writer, "-- pgcrate anonymized dump")?;
-    writeln!(writer, "-- Generated: {}", chrono::Utc::now().to_rfc3339())?;
-    writeln!(writer, "-- pgcrate version: {}", env!("CARGO_PKG_VERSION"))?;
-    writeln!(writer, "--")?;
-    writeln!(writer)?;
+    match schema {
+        Some(schema) => schema.write_pre_data(writer, "pgcrate anonymized dump")?,
+        None => {
+            writeln!(writer, "-- pgcrate anonymized dump")?;
+            writeln!(writer, "-- Generated: {}", chrono::Utc::now().to_rfc3339())?;
+            writeln!(writer, "-- pgcrate version: {}", env!("CARGO_PKG_VERSION"))?;
+            writeln!(writer, "--")?;
+            writeln!(writer)?;
+        }
+    }
 
     if !quiet {
         eprintln!("Dumping anonymized data...");
     }
 
     for table in &ordered_tables {
-        let columns = get_table_columns(client, &table.schema, &table.name).await?;
         let row_count = get_row_count(client, &table.schema, &table.name).await?;
 
         if !quiet {
@@ -423,41 +1131,83 @@ pub async fn execute_anonymize_dump(
             io::stderr().flush()?;
         }
 
-        // Build the SELECT query
-        let select_sql = build_anonymized_select(&table.schema, &table.name, &columns, rules, seed);
-
-        // Write COPY header
-        let col_list: Vec<String> = columns.iter().map(|c| quote_ident(c)).collect();
-        writeln!(
-            writer,
-            "COPY {}.{} ({}) FROM stdin;",
+        let (columns, copy_sql) = copy.copy_out_sql(client, table).await?;
+        let qualified = format!(
+            "{}.{}",
             quote_ident(&table.schema),
-            quote_ident(&table.name),
-            col_list.join(", ")
-        )?;
-
-        // Execute COPY TO STDOUT with our SELECT
-        let copy_sql = format!("COPY ({}) TO STDOUT", select_sql);
-
-        // Use streaming for COPY
-        let copy_stream = client.copy_out(&copy_sql).await?;
-        tokio::pin!(copy_stream);
-        while let Some(result) = copy_stream.next().await {
-            let chunk = result?;
-            writer.write_all(&chunk)?;
-        }
-
-        writeln!(writer, "\\.")?;
-        writeln!(writer)?;
+            quote_ident(&table.name)
+        );
+        write_copy_block(client, &qualified, &columns, &copy_sql, writer).await?;
 
         if !quiet {
             eprintln!(" done");
         }
     }
 
+    if let Some(schema) = schema {
+        schema.write_post_data(client, writer).await?;
+    }
+
     Ok(())
 }
 
+/// Condition selecting the rows of `table` (referred to as `alias`) to dump:
+/// its own filter, and for each foreign key to a filtered table, only rows
+/// whose parent row is dumped too (or whose key is NULL). `visiting` guards
+/// against FK cycles; self-references aren't followed.
+fn dump_row_condition(
+    table: &str,
+    alias: &str,
+    filters: &HashMap<String, RowFilter>,
+    filter_keys: &HashMap<String, Vec<String>>,
+    foreign_keys: &[ForeignKey],
+    seed: &str,
+    visiting: &mut Vec<String>,
+) -> Option<String> {
+    visiting.push(table.to_string());
+    let mut parts = Vec::new();
+    if let Some(filter) = filters.get(table) {
+        let key_columns = filter_keys.get(table).map(Vec::as_slice).unwrap_or(&[]);
+        parts.extend(filter.condition(alias, key_columns, seed));
+    }
+    for fk in foreign_keys.iter().filter(|fk| fk.child == table) {
+        if visiting.contains(&fk.parent) {
+            continue;
+        }
+        let parent_alias = format!("p{}", visiting.len());
+        let Some(parent_condition) = dump_row_condition(
+            &fk.parent,
+            &parent_alias,
+            filters,
+            filter_keys,
+            foreign_keys,
+            seed,
+            visiting,
+        ) else {
+            continue;
+        };
+        let nulls: Vec<String> = fk
+            .child_columns
+            .iter()
+            .map(|c| format!("{}.{} IS NULL", alias, quote_ident(c)))
+            .collect();
+        parts.push(format!(
+            "({} OR EXISTS (SELECT 1 FROM {} {} WHERE {} AND {}))",
+            nulls.join(" OR "),
+            quote_table(&fk.parent),
+            parent_alias,
+            fk.join_condition(alias, &parent_alias),
+            parent_condition
+        ));
+    }
+    visiting.pop();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" AND "))
+    }
+}
+
 /// Order tables by foreign key dependencies (best effort)
 pub async fn order_tables_by_fk(
     client: &Client,
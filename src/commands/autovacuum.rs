@@ -0,0 +1,716 @@
+//! Autovacuum command: Per-table autovacuum tuning advice.
+//!
+//! Autovacuum vacuums a table once its dead tuples pass
+//! `autovacuum_vacuum_threshold + autovacuum_vacuum_scale_factor * reltuples`.
+//! The default scale factor of 0.2 lets a large, busy table collect millions
+//! of dead tuples between vacuums. This command estimates how fast each table
+//! accumulates dead tuples, works out how often autovacuum will visit it with
+//! its current settings, and proposes a per-table scale factor where that is
+//! less than once a day.
+
+use anyhow::Result;
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use crate::sql::quote_ident;
+
+/// Tables smaller than this are cheap to vacuum whatever the settings
+const MIN_LIVE_TUPLES: i64 = 10_000;
+/// Tables changing less than this fraction of their rows a day are left alone
+const MIN_DAILY_CHURN: f64 = 0.01;
+/// Busy tables should be vacuumed at least this often
+const TARGET_HOURS_BETWEEN: f64 = 24.0;
+/// Lowest scale factor proposed
+const MIN_SCALE_FACTOR: f64 = 0.005;
+/// Shortest window a rate is estimated over
+const MIN_RATE_WINDOW_SECS: f64 = 3600.0;
+/// Dead tuples past the trigger point, as a multiple of it, before autovacuum
+/// counts as falling behind
+const BEHIND_WARNING: f64 = 2.0;
+const BEHIND_CRITICAL: f64 = 10.0;
+
+/// Autovacuum tuning status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutovacuumStatus {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl AutovacuumStatus {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            AutovacuumStatus::Healthy => "✓",
+            AutovacuumStatus::Warning => "⚠",
+            AutovacuumStatus::Critical => "✗",
+        }
+    }
+}
+
+/// Where a table's dead tuple rate was measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateSource {
+    /// Dead tuples now, over the time since the last vacuum
+    SinceLastVacuum,
+    /// Rows updated and deleted, over the time since statistics were reset
+    SinceStatsReset,
+}
+
+/// Server-wide autovacuum settings
+#[derive(Debug, Clone, Serialize)]
+pub struct AutovacuumDefaults {
+    pub autovacuum: bool,
+    pub scale_factor: f64,
+    pub threshold: i64,
+}
+
+/// Per-table autovacuum reloptions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableOverrides {
+    pub enabled: Option<bool>,
+    pub scale_factor: Option<f64>,
+    pub threshold: Option<i64>,
+}
+
+impl TableOverrides {
+    /// Parse pg_class.reloptions entries like `autovacuum_enabled=false`
+    pub fn parse(reloptions: &[String]) -> TableOverrides {
+        let mut overrides = TableOverrides::default();
+        for option in reloptions {
+            let Some((name, value)) = option.split_once('=') else {
+                continue;
+            };
+            match name {
+                "autovacuum_enabled" => {
+                    overrides.enabled = match value.to_lowercase().as_str() {
+                        "true" | "on" | "1" | "yes" => Some(true),
+                        "false" | "off" | "0" | "no" => Some(false),
+                        _ => None,
+                    }
+                }
+                "autovacuum_vacuum_scale_factor" => overrides.scale_factor = value.parse().ok(),
+                "autovacuum_vacuum_threshold" => overrides.threshold = value.parse().ok(),
+                _ => {}
+            }
+        }
+        overrides
+    }
+}
+
+/// A proposed per-table setting
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendation {
+    pub scale_factor: f64,
+    /// Expected hours between autovacuums with the new scale factor
+    pub hours_between_vacuums: f64,
+    pub sql: String,
+}
+
+/// Autovacuum state of one table
+#[derive(Debug, Clone, Serialize)]
+pub struct TableAutovacuum {
+    pub schema: String,
+    pub table: String,
+    pub live_tuples: i64,
+    pub dead_tuples: i64,
+    /// Effective settings: the table's reloptions, else the server defaults
+    pub scale_factor: f64,
+    pub threshold: i64,
+    pub autovacuum_enabled: bool,
+    /// Whether the table has its own scale factor or threshold
+    pub per_table_settings: bool,
+    /// Dead tuples at which autovacuum starts
+    pub trigger_tuples: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_tuples_per_hour: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_source: Option<RateSource>,
+    /// Expected hours between autovacuums at the current rate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hours_between_vacuums: Option<f64>,
+    pub autovacuum_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_vacuumed: Option<String>,
+    pub status: AutovacuumStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<Recommendation>,
+}
+
+/// Full autovacuum tuning results
+#[derive(Debug, Serialize)]
+pub struct AutovacuumResult {
+    pub defaults: AutovacuumDefaults,
+    pub tables: Vec<TableAutovacuum>,
+    pub overall_status: AutovacuumStatus,
+    /// Seconds covered by the cumulative statistics
+    pub stats_window_secs: f64,
+}
+
+/// Raw statistics for one table, as read from pg_stat_user_tables
+struct TableStats {
+    schema: String,
+    table: String,
+    live_tuples: i64,
+    dead_tuples: i64,
+    /// n_tup_upd + n_tup_del since the stats reset
+    churn: i64,
+    autovacuum_count: i64,
+    secs_since_vacuum: Option<f64>,
+    last_vacuumed: Option<String>,
+    overrides: TableOverrides,
+}
+
+async fn get_defaults(client: &Client) -> Result<AutovacuumDefaults> {
+    let row = client
+        .query_one(
+            "SELECT current_setting('autovacuum') = 'on',
+                    current_setting('autovacuum_vacuum_scale_factor')::float8,
+                    current_setting('autovacuum_vacuum_threshold')::int8",
+            &[],
+        )
+        .await?;
+    Ok(AutovacuumDefaults {
+        autovacuum: row.get(0),
+        scale_factor: row.get(1),
+        threshold: row.get(2),
+    })
+}
+
+async fn get_stats_window_secs(client: &Client) -> Result<f64> {
+    let row = client
+        .query_one(
+            "SELECT extract(epoch FROM now() - coalesce(
+                 (SELECT stats_reset FROM pg_stat_database WHERE datname = current_database()),
+                 pg_postmaster_start_time()))::float8",
+            &[],
+        )
+        .await?;
+    Ok(row.get(0))
+}
+
+async fn get_table_stats(client: &Client, table: Option<(&str, &str)>) -> Result<Vec<TableStats>> {
+    let query = r#"
+        SELECT
+            s.schemaname::text,
+            s.relname::text,
+            s.n_live_tup,
+            s.n_dead_tup,
+            s.n_tup_upd + s.n_tup_del AS churn,
+            s.autovacuum_count,
+            extract(epoch FROM now() - greatest(s.last_vacuum, s.last_autovacuum))::float8
+                AS secs_since_vacuum,
+            greatest(s.last_vacuum, s.last_autovacuum) AS last_vacuumed,
+            coalesce(c.reloptions, '{}') AS reloptions
+        FROM pg_stat_user_tables s
+        JOIN pg_class c ON c.oid = s.relid
+        WHERE ($1::text IS NULL OR s.schemaname = $1)
+          AND ($2::text IS NULL OR s.relname = $2)
+    "#;
+    let (schema, name) = match table {
+        Some((s, n)) => (Some(s), Some(n)),
+        None => (None, None),
+    };
+
+    let rows = client.query(query, &[&schema, &name]).await?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let last_vacuumed: Option<chrono::DateTime<chrono::Utc>> = row.get("last_vacuumed");
+            let reloptions: Vec<String> = row.get("reloptions");
+            TableStats {
+                schema: row.get(0),
+                table: row.get(1),
+                live_tuples: row.get("n_live_tup"),
+                dead_tuples: row.get("n_dead_tup"),
+                churn: row.get("churn"),
+                autovacuum_count: row.get("autovacuum_count"),
+                secs_since_vacuum: row.get("secs_since_vacuum"),
+                last_vacuumed: last_vacuumed.map(|t| t.to_rfc3339()),
+                overrides: TableOverrides::parse(&reloptions),
+            }
+        })
+        .collect())
+}
+
+/// Dead tuples per hour: what has piled up since the last vacuum if that
+/// covers long enough, else the update/delete rate since the stats reset
+fn dead_tuple_rate(
+    dead_tuples: i64,
+    secs_since_vacuum: Option<f64>,
+    churn: i64,
+    stats_window_secs: f64,
+) -> Option<(f64, RateSource)> {
+    if let Some(secs) = secs_since_vacuum.filter(|s| *s >= MIN_RATE_WINDOW_SECS) {
+        return Some((
+            dead_tuples as f64 * 3600.0 / secs,
+            RateSource::SinceLastVacuum,
+        ));
+    }
+    if stats_window_secs >= MIN_RATE_WINDOW_SECS {
+        return Some((
+            churn as f64 * 3600.0 / stats_window_secs,
+            RateSource::SinceStatsReset,
+        ));
+    }
+    None
+}
+
+/// Round down to two significant digits, so proposals read like 0.012
+/// rather than 0.01234
+fn round_scale_factor(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 0.0;
+    }
+    let decimals = (1.0 - value.log10().floor()).max(0.0) as usize;
+    let scale = 10f64.powi(decimals as i32);
+    format!("{:.*}", decimals, ((value * scale) + 1e-9).floor() / scale)
+        .parse()
+        .unwrap_or(value)
+}
+
+/// SQL setting a table's autovacuum scale factor (and threshold, if given)
+pub fn alter_table_sql(
+    schema: &str,
+    table: &str,
+    scale_factor: f64,
+    threshold: Option<i64>,
+) -> String {
+    let mut options = vec![format!("autovacuum_vacuum_scale_factor = {}", scale_factor)];
+    if let Some(threshold) = threshold {
+        options.push(format!("autovacuum_vacuum_threshold = {}", threshold));
+    }
+    format!(
+        "ALTER TABLE {}.{} SET ({});",
+        quote_ident(schema),
+        quote_ident(table),
+        options.join(", ")
+    )
+}
+
+/// Propose a scale factor that gets autovacuum to the table about once
+/// every TARGET_HOURS_BETWEEN hours, when the current one is at least twice
+/// as high
+fn recommend(
+    schema: &str,
+    table: &str,
+    live_tuples: i64,
+    scale_factor: f64,
+    threshold: i64,
+    rate_per_hour: f64,
+) -> Option<Recommendation> {
+    if live_tuples < MIN_LIVE_TUPLES || rate_per_hour <= 0.0 {
+        return None;
+    }
+    let live = live_tuples as f64;
+    if rate_per_hour * 24.0 < live * MIN_DAILY_CHURN {
+        return None;
+    }
+    let trigger = threshold as f64 + scale_factor * live;
+    if trigger / rate_per_hour <= TARGET_HOURS_BETWEEN {
+        return None;
+    }
+
+    let target = (rate_per_hour * TARGET_HOURS_BETWEEN - threshold as f64).max(0.0) / live;
+    let proposed = round_scale_factor(target).max(MIN_SCALE_FACTOR);
+    if proposed * 2.0 > scale_factor {
+        return None;
+    }
+
+    Some(Recommendation {
+        scale_factor: proposed,
+        hours_between_vacuums: (threshold as f64 + proposed * live) / rate_per_hour,
+        sql: alter_table_sql(schema, table, proposed, None),
+    })
+}
+
+fn analyze_table(
+    stats: TableStats,
+    defaults: &AutovacuumDefaults,
+    stats_window_secs: f64,
+) -> TableAutovacuum {
+    let overrides = &stats.overrides;
+    let scale_factor = overrides.scale_factor.unwrap_or(defaults.scale_factor);
+    let threshold = overrides.threshold.unwrap_or(defaults.threshold);
+    let autovacuum_enabled = defaults.autovacuum && overrides.enabled.unwrap_or(true);
+    let trigger = threshold as f64 + scale_factor * stats.live_tuples.max(0) as f64;
+
+    let rate = dead_tuple_rate(
+        stats.dead_tuples,
+        stats.secs_since_vacuum,
+        stats.churn,
+        stats_window_secs,
+    );
+    let hours_between_vacuums = rate
+        .map(|(per_hour, _)| per_hour)
+        .filter(|per_hour| *per_hour > 0.0)
+        .map(|per_hour| trigger / per_hour);
+
+    let dead = stats.dead_tuples as f64;
+    let (mut status, mut message) = (AutovacuumStatus::Healthy, None);
+    if !autovacuum_enabled {
+        if dead > trigger {
+            status = AutovacuumStatus::Warning;
+            message = Some(format!(
+                "autovacuum is disabled and {} dead tuples are past the {:.0} trigger point",
+                stats.dead_tuples, trigger
+            ));
+        }
+    } else if dead > trigger * BEHIND_CRITICAL {
+        status = AutovacuumStatus::Critical;
+        message = Some(format!(
+            "{} dead tuples, {:.0}x the trigger point: autovacuum is not keeping up",
+            stats.dead_tuples,
+            dead / trigger
+        ));
+    } else if dead > trigger * BEHIND_WARNING {
+        status = AutovacuumStatus::Warning;
+        message = Some(format!(
+            "{} dead tuples, {:.0}x the trigger point: autovacuum is falling behind",
+            stats.dead_tuples,
+            dead / trigger
+        ));
+    }
+
+    let recommendation = match rate {
+        Some((per_hour, _)) if autovacuum_enabled => recommend(
+            &stats.schema,
+            &stats.table,
+            stats.live_tuples,
+            scale_factor,
+            threshold,
+            per_hour,
+        ),
+        _ => None,
+    };
+    if let Some(ref rec) = recommendation {
+        status = status.max(AutovacuumStatus::Warning);
+        if message.is_none() {
+            message = Some(format!(
+                "autovacuum runs about every {} at scale factor {}; {} brings that to {}",
+                format_hours(hours_between_vacuums.unwrap_or_default()),
+                scale_factor,
+                rec.scale_factor,
+                format_hours(rec.hours_between_vacuums)
+            ));
+        }
+    }
+
+    TableAutovacuum {
+        schema: stats.schema,
+        table: stats.table,
+        live_tuples: stats.live_tuples,
+        dead_tuples: stats.dead_tuples,
+        scale_factor,
+        threshold,
+        autovacuum_enabled,
+        per_table_settings: overrides.scale_factor.is_some() || overrides.threshold.is_some(),
+        trigger_tuples: trigger.round() as i64,
+        dead_tuples_per_hour: rate.map(|(per_hour, _)| per_hour),
+        rate_source: rate.map(|(_, source)| source),
+        hours_between_vacuums,
+        autovacuum_count: stats.autovacuum_count,
+        last_vacuumed: stats.last_vacuumed,
+        status,
+        message,
+        recommendation,
+    }
+}
+
+/// Run autovacuum analysis, for one table or the `limit` busiest tables
+/// worth tuning
+pub async fn run_autovacuum(
+    client: &Client,
+    table: Option<(&str, &str)>,
+    limit: usize,
+) -> Result<AutovacuumResult> {
+    let defaults = get_defaults(client).await?;
+    let stats_window_secs = get_stats_window_secs(client).await?;
+
+    let mut tables: Vec<TableAutovacuum> = get_table_stats(client, table)
+        .await?
+        .into_iter()
+        .filter(|t| {
+            table.is_some()
+                || t.live_tuples + t.dead_tuples >= MIN_LIVE_TUPLES
+                || t.overrides != TableOverrides::default()
+        })
+        .map(|t| analyze_table(t, &defaults, stats_window_secs))
+        .collect();
+
+    // Problems first, then the busiest tables
+    tables.sort_by(|a, b| {
+        b.status.cmp(&a.status).then(
+            b.dead_tuples_per_hour
+                .unwrap_or_default()
+                .total_cmp(&a.dead_tuples_per_hour.unwrap_or_default()),
+        )
+    });
+    if table.is_none() {
+        tables.truncate(limit);
+    }
+
+    let overall_status = tables
+        .iter()
+        .map(|t| t.status)
+        .max()
+        .unwrap_or(AutovacuumStatus::Healthy);
+
+    Ok(AutovacuumResult {
+        defaults,
+        tables,
+        overall_status,
+        stats_window_secs,
+    })
+}
+
+fn format_hours(hours: f64) -> String {
+    if hours >= 48.0 {
+        format!("{:.0}d", hours / 24.0)
+    } else if hours >= 1.0 {
+        format!("{:.1}h", hours)
+    } else {
+        format!("{:.0}m", hours * 60.0)
+    }
+}
+
+fn format_count(n: f64) -> String {
+    if n >= 1_000_000.0 {
+        format!("{:.1}M", n / 1_000_000.0)
+    } else if n >= 1_000.0 {
+        format!("{:.1}K", n / 1_000.0)
+    } else {
+        format!("{:.0}", n)
+    }
+}
+
+/// Print autovacuum analysis in human-readable format
+pub fn print_human(result: &AutovacuumResult, quiet: bool) {
+    println!("AUTOVACUUM TUNING");
+    println!("=================");
+    println!();
+    println!(
+        "Defaults: autovacuum {}, scale factor {}, threshold {}",
+        if result.defaults.autovacuum {
+            "on"
+        } else {
+            "OFF"
+        },
+        result.defaults.scale_factor,
+        result.defaults.threshold
+    );
+    println!();
+
+    if result.tables.is_empty() {
+        if !quiet {
+            println!(
+                "No tables with {} or more rows or per-table autovacuum settings.",
+                MIN_LIVE_TUPLES
+            );
+        }
+        return;
+    }
+
+    println!(
+        "  {:3} {:40} {:>8} {:>8} {:>8} {:>7} {:>8}",
+        "", "TABLE", "LIVE", "DEAD", "DEAD/H", "SCALE", "EVERY"
+    );
+    println!("  {}", "-".repeat(88));
+    for t in &result.tables {
+        let full_name = format!("{}.{}", t.schema, t.table);
+        println!(
+            "  {} {:40} {:>8} {:>8} {:>8} {:>7} {:>8}",
+            t.status.emoji(),
+            if full_name.len() > 40 {
+                format!("{}...", &full_name[..37])
+            } else {
+                full_name
+            },
+            format_count(t.live_tuples as f64),
+            format_count(t.dead_tuples as f64),
+            t.dead_tuples_per_hour
+                .map(format_count)
+                .unwrap_or_else(|| "-".to_string()),
+            format!(
+                "{}{}",
+                t.scale_factor,
+                if t.per_table_settings { "*" } else { "" }
+            ),
+            if !t.autovacuum_enabled {
+                "off".to_string()
+            } else {
+                t.hours_between_vacuums
+                    .map(format_hours)
+                    .unwrap_or_else(|| "-".to_string())
+            }
+        );
+        if let Some(ref message) = t.message {
+            println!("      {}", message);
+        }
+    }
+    if result.tables.iter().any(|t| t.per_table_settings) && !quiet {
+        println!();
+        println!("  * per-table setting");
+    }
+
+    let recommendations: Vec<_> = result
+        .tables
+        .iter()
+        .filter_map(|t| t.recommendation.as_ref().map(|r| (t, r)))
+        .collect();
+    if !recommendations.is_empty() {
+        println!();
+        println!("RECOMMENDED SETTINGS:");
+        println!();
+        for (_, rec) in &recommendations {
+            println!("  {}", rec.sql);
+        }
+        if !quiet {
+            println!();
+            println!("Apply with: pgcrate dba fix autovacuum <schema.table> --dry-run");
+        }
+    }
+}
+
+/// Print autovacuum analysis as JSON with schema versioning.
+pub fn print_json(
+    result: &AutovacuumResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = match result.overall_status {
+        AutovacuumStatus::Healthy => Severity::Healthy,
+        AutovacuumStatus::Warning => Severity::Warning,
+        AutovacuumStatus::Critical => Severity::Critical,
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::AUTOVACUUM, result, severity, t),
+        None => DiagnosticOutput::new(schema::AUTOVACUUM, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> AutovacuumDefaults {
+        AutovacuumDefaults {
+            autovacuum: true,
+            scale_factor: 0.2,
+            threshold: 50,
+        }
+    }
+
+    fn stats(live: i64, dead: i64, hours_since_vacuum: f64) -> TableStats {
+        TableStats {
+            schema: "public".to_string(),
+            table: "events".to_string(),
+            live_tuples: live,
+            dead_tuples: dead,
+            churn: 0,
+            autovacuum_count: 3,
+            secs_since_vacuum: Some(hours_since_vacuum * 3600.0),
+            last_vacuumed: None,
+            overrides: TableOverrides::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_reloptions() {
+        let overrides = TableOverrides::parse(&[
+            "fillfactor=90".to_string(),
+            "autovacuum_enabled=false".to_string(),
+            "autovacuum_vacuum_scale_factor=0.05".to_string(),
+            "autovacuum_vacuum_threshold=1000".to_string(),
+        ]);
+        assert_eq!(
+            overrides,
+            TableOverrides {
+                enabled: Some(false),
+                scale_factor: Some(0.05),
+                threshold: Some(1000),
+            }
+        );
+    }
+
+    #[test]
+    fn test_dead_tuple_rate_sources() {
+        assert_eq!(
+            dead_tuple_rate(1000, Some(7200.0), 50_000, 86_400.0),
+            Some((500.0, RateSource::SinceLastVacuum))
+        );
+        // A vacuum a minute ago says little; fall back to the long-run churn
+        assert_eq!(
+            dead_tuple_rate(10, Some(60.0), 48_000, 86_400.0),
+            Some((2000.0, RateSource::SinceStatsReset))
+        );
+        assert_eq!(dead_tuple_rate(10, None, 100, 600.0), None);
+    }
+
+    #[test]
+    fn test_round_scale_factor() {
+        assert_eq!(round_scale_factor(0.01234), 0.012);
+        assert_eq!(round_scale_factor(0.123), 0.12);
+        assert_eq!(round_scale_factor(0.0059), 0.0059);
+        assert_eq!(round_scale_factor(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_recommends_lower_scale_factor_for_busy_table() {
+        // 10M rows, 40K dead tuples an hour: the default 0.2 means a vacuum
+        // every ~50 hours
+        let table = analyze_table(stats(10_000_000, 200_000, 5.0), &defaults(), 86_400.0);
+        assert_eq!(table.status, AutovacuumStatus::Warning);
+        assert_eq!(table.trigger_tuples, 2_000_050);
+        let rec = table.recommendation.expect("should recommend");
+        assert_eq!(rec.scale_factor, 0.095);
+        assert!(rec.hours_between_vacuums <= TARGET_HOURS_BETWEEN);
+        assert_eq!(
+            rec.sql,
+            "ALTER TABLE \"public\".\"events\" SET (autovacuum_vacuum_scale_factor = 0.095);"
+        );
+    }
+
+    #[test]
+    fn test_no_recommendation_for_quiet_or_small_tables() {
+        // 1M rows changing 0.1% a day
+        let quiet = analyze_table(stats(1_000_000, 400, 10.0), &defaults(), 86_400.0);
+        assert!(quiet.recommendation.is_none());
+        assert_eq!(quiet.status, AutovacuumStatus::Healthy);
+
+        let small = analyze_table(stats(5_000, 900, 10.0), &defaults(), 86_400.0);
+        assert!(small.recommendation.is_none());
+
+        // Already vacuumed often enough
+        let busy = analyze_table(stats(100_000, 10_000, 1.0), &defaults(), 86_400.0);
+        assert!(busy.recommendation.is_none());
+    }
+
+    #[test]
+    fn test_falling_behind() {
+        let behind = analyze_table(stats(100_000, 100_000, 0.5), &defaults(), 86_400.0);
+        assert_eq!(behind.status, AutovacuumStatus::Warning);
+        assert!(behind.message.unwrap().contains("falling behind"));
+
+        let far_behind = analyze_table(stats(100_000, 500_000, 0.5), &defaults(), 86_400.0);
+        assert_eq!(far_behind.status, AutovacuumStatus::Critical);
+    }
+
+    #[test]
+    fn test_disabled_autovacuum() {
+        let mut table_stats = stats(100_000, 50_000, 10.0);
+        table_stats.overrides.enabled = Some(false);
+        let table = analyze_table(table_stats, &defaults(), 86_400.0);
+        assert!(!table.autovacuum_enabled);
+        assert!(table.recommendation.is_none());
+        assert_eq!(table.status, AutovacuumStatus::Warning);
+        assert!(table.message.unwrap().contains("disabled"));
+    }
+}
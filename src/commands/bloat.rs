@@ -7,6 +7,8 @@ use anyhow::Result;
 use serde::Serialize;
 use tokio_postgres::Client;
 
+use crate::config::AlertIgnore;
+
 const WARNING_PCT: f64 = 20.0;
 const CRITICAL_PCT: f64 = 50.0;
 
@@ -66,6 +68,9 @@ pub struct BloatResult {
     pub total_table_bloat_bytes: i64,
     pub total_index_bloat_bytes: i64,
     pub overall_status: BloatStatus,
+    /// Changes against recorded metrics (when --compare is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<super::trend::Comparison>,
 }
 
 /// Statistical index bloat estimation (ioguix-style).
@@ -195,9 +200,14 @@ LIMIT $1
     Ok(results)
 }
 
-pub async fn get_bloat(client: &Client, limit: usize) -> Result<BloatResult> {
-    let tables = get_table_bloat(client, limit).await?;
-    let indexes = get_index_bloat(client, limit).await?;
+pub async fn get_bloat(client: &Client, limit: usize, ignore: &AlertIgnore) -> Result<BloatResult> {
+    let fetch = limit + ignore.count();
+    let mut tables = get_table_bloat(client, fetch).await?;
+    tables.retain(|t| !ignore.table(&t.schema, &t.table));
+    tables.truncate(limit);
+    let mut indexes = get_index_bloat(client, fetch).await?;
+    indexes.retain(|i| !ignore.index(&i.schema, &i.index) && !ignore.table(&i.schema, &i.table));
+    indexes.truncate(limit);
 
     let total_table_bloat: i64 = tables.iter().map(|t| t.bloat_bytes).sum();
     let total_index_bloat: i64 = indexes.iter().map(|i| i.bloat_bytes).sum();
@@ -234,6 +244,7 @@ pub async fn get_bloat(client: &Client, limit: usize) -> Result<BloatResult> {
         total_table_bloat_bytes: total_table_bloat,
         total_index_bloat_bytes: total_index_bloat,
         overall_status,
+        comparison: None,
     })
 }
 
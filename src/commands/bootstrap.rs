@@ -1,11 +1,17 @@
 //! Bootstrap command for pgcrate CLI.
 
 use crate::config::Config;
+use crate::snapshot::format_bytes;
+use crate::sql::quote_ident;
 use anyhow::{Context, Result};
 use colored::Colorize;
+use futures_util::{pin_mut, SinkExt, StreamExt};
+use std::collections::HashSet;
 use std::path::Path;
-use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tokio_postgres::Client;
 
+use super::anonymize::{format_number, AnonymizedCopy, TableInfo};
 use super::{anonymize_setup, db_create, up};
 
 /// Bootstrap a new environment with anonymized data from a source
@@ -19,6 +25,9 @@ pub async fn bootstrap(
     verbose: bool,
     dry_run: bool,
     yes: bool,
+    resume: bool,
+    schema_only: bool,
+    only_tables: &[String],
 ) -> Result<(), anyhow::Error> {
     if !yes && !dry_run {
         anyhow::bail!(
@@ -40,11 +49,28 @@ pub async fn bootstrap(
             "  {} Would install anonymization functions",
             "[dry-run]".blue()
         );
-        println!(
-            "  {} Would stream anonymized data from {}",
-            "[dry-run]".blue(),
-            from_url
-        );
+        if schema_only {
+            println!("  {} Would skip copying data", "[dry-run]".blue());
+        } else if !only_tables.is_empty() {
+            println!(
+                "  {} Would stream anonymized data for {} from {}",
+                "[dry-run]".blue(),
+                only_tables.join(", "),
+                from_url
+            );
+        } else {
+            println!(
+                "  {} Would stream anonymized data from {}",
+                "[dry-run]".blue(),
+                from_url
+            );
+        }
+        if resume {
+            println!(
+                "  {} Would skip tables an interrupted bootstrap already transferred",
+                "[dry-run]".blue()
+            );
+        }
         println!("\nRun with --yes to proceed.");
         return Ok(());
     }
@@ -59,7 +85,7 @@ pub async fn bootstrap(
     if !quiet {
         println!("  2. Applying migrations...");
     }
-    up(database_url, config, quiet, verbose, false).await?;
+    up(database_url, config, quiet, verbose, false, false, false).await?;
 
     // 3. Ensure anonymize functions exist locally
     if !quiet {
@@ -67,14 +93,26 @@ pub async fn bootstrap(
     }
     anonymize_setup(database_url, quiet, verbose).await?;
 
+    if schema_only {
+        if !quiet {
+            println!(
+                "\n{}",
+                "Bootstrap complete (schema only, no data copied)."
+                    .green()
+                    .bold()
+            );
+        }
+        return Ok(());
+    }
+
     // 4. Stream anonymized data
     if !quiet {
         println!("  4. Streaming anonymized data from source...");
     }
 
-    use super::anonymize::{execute_anonymize_dump, get_tables_for_dump};
+    use super::anonymize::{convert_anonymize_rules, get_tables_for_dump, order_tables_by_fk};
     use super::connect;
-    use crate::anonymize::{get_skipped_tables, parse_table_name, AnonymizeRule};
+    use crate::anonymize::{get_skipped_tables, parse_table_name};
     use crate::config::AnonymizeConfig;
 
     // Connect to source
@@ -92,48 +130,101 @@ pub async fn bootstrap(
             anyhow::anyhow!("No anonymization seed provided. Use PGCRATE_ANONYMIZE_SEED env var, or 'seed' in pgcrate.anonymize.toml")
         })?;
 
-    // Convert config rules to anonymize::AnonymizeRule
-    let mut rules = Vec::new();
-    for rule in anon_config.rules {
-        let (schema, table) = parse_table_name(&rule.table);
-        if rule.skip {
-            rules.push(AnonymizeRule::skip_table(&schema, &table));
-        } else if let Some(columns) = rule.columns {
-            for (col, strategy) in columns {
-                crate::anonymize::validate_strategy(&strategy)?;
-                rules.push(AnonymizeRule::column(&schema, &table, &col, &strategy));
-            }
-        }
-    }
+    let (rules, filters) = convert_anonymize_rules(anon_config.rules)?;
 
     let skipped_tables = get_skipped_tables(&rules);
-    let tables = get_tables_for_dump(&source_client, &skipped_tables).await?;
-
-    // We'll use a child psql process for the local side to handle the SQL stream
-    let psql_path = config.tool_path("psql");
-    let mut child = Command::new(&psql_path)
-        .arg(database_url)
-        .arg("-q")
-        .stdin(Stdio::piped())
-        .spawn()
-        .context("Failed to spawn psql process for data loading")?;
-
-    let mut stdin = child.stdin.take().unwrap();
-
-    // Stream from source via anonymizer into local psql
-    execute_anonymize_dump(&source_client, &tables, &rules, &seed, &mut stdin, quiet).await?;
-
-    // Close stdin and wait for psql to finish
-    drop(stdin);
-    let status = child.wait()?;
+    let mut tables = get_tables_for_dump(&source_client, &skipped_tables).await?;
+    if !only_tables.is_empty() {
+        let wanted: Vec<String> = only_tables
+            .iter()
+            .map(|t| {
+                let (schema, table) = parse_table_name(t.trim());
+                format!("{}.{}", schema, table)
+            })
+            .collect();
+        let available: HashSet<String> = tables.iter().map(|t| t.qualified()).collect();
+        if let Some(missing) = wanted.iter().find(|t| !available.contains(*t)) {
+            anyhow::bail!(
+                "Table not found in source (or skipped by anonymize rules): {}",
+                missing
+            );
+        }
+        tables.retain(|t| wanted.contains(&t.qualified()));
+    }
+    let tables = order_tables_by_fk(&source_client, &tables).await?;
+    let copy = AnonymizedCopy::new(&source_client, &tables, &rules, &filters, &seed).await?;
 
-    if !status.success() {
+    let target_client = connect(database_url).await?;
+    let transferred = load_checkpoints(&target_client).await?;
+    if !transferred.is_empty() && !resume {
         anyhow::bail!(
-            "psql failed during data loading (exit code {})",
-            status.code().unwrap_or(-1)
+            "A previous bootstrap into this database stopped after {} table(s).\n\
+             Hint: Use --resume to continue it, or drop the database to start over.",
+            transferred.len()
         );
     }
 
+    // Row estimates drive the ETA
+    let mut estimates = Vec::with_capacity(tables.len());
+    for table in &tables {
+        estimates.push(estimated_rows(&source_client, table).await?);
+    }
+    let mut progress = Progress::new(
+        tables.len(),
+        estimates
+            .iter()
+            .zip(&tables)
+            .filter(|(_, t)| !transferred.contains(&t.qualified()))
+            .map(|(rows, _)| rows)
+            .sum(),
+    );
+
+    for (table, estimate) in tables.iter().zip(estimates) {
+        let qualified = table.qualified();
+        progress.tables_done += 1;
+        if transferred.contains(&qualified) {
+            if !quiet {
+                eprintln!(
+                    "    {} {}: already transferred",
+                    progress.counter(),
+                    qualified
+                );
+            }
+            continue;
+        }
+        let (rows, bytes) = transfer_table(
+            &source_client,
+            &target_client,
+            &copy,
+            table,
+            &mut progress,
+            quiet,
+        )
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to transfer {}. Completed tables are saved; rerun with --resume to continue",
+                qualified
+            )
+        })?;
+        progress.finish_table(estimate, rows);
+        if !quiet {
+            eprintln!(
+                "    {} {}: {} rows, {} ({} total{})",
+                progress.counter(),
+                qualified,
+                format_number(rows as i64),
+                format_bytes(bytes),
+                format_bytes(progress.bytes),
+                progress.eta()
+            );
+        }
+    }
+
+    target_client
+        .batch_execute(&format!("DROP TABLE IF EXISTS {}", CHECKPOINT_TABLE))
+        .await?;
+
     if !quiet {
         println!(
             "\n{}",
@@ -143,3 +234,202 @@ pub async fn bootstrap(
 
     Ok(())
 }
+
+/// Tables transferred so far are recorded here, in the same transaction as
+/// their rows, so `--resume` skips exactly the tables that committed
+const CHECKPOINT_TABLE: &str = "pgcrate.bootstrap_progress";
+
+/// Tables already transferred by an earlier, interrupted bootstrap
+async fn load_checkpoints(client: &Client) -> Result<HashSet<String>> {
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                table_name TEXT PRIMARY KEY,
+                row_count BIGINT NOT NULL,
+                bytes BIGINT NOT NULL,
+                transferred_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            )",
+            CHECKPOINT_TABLE
+        ))
+        .await?;
+    let rows = client
+        .query(&format!("SELECT table_name FROM {}", CHECKPOINT_TABLE), &[])
+        .await?;
+    Ok(rows.iter().map(|r| r.get(0)).collect())
+}
+
+/// Planner row estimate; exact counts are too slow on large sources
+async fn estimated_rows(client: &Client, table: &TableInfo) -> Result<u64> {
+    let row = client
+        .query_one(
+            "SELECT GREATEST(c.reltuples, 0)::bigint FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2",
+            &[&table.schema, &table.name],
+        )
+        .await?;
+    Ok(row.get::<_, i64>(0) as u64)
+}
+
+/// Stream one table's anonymized rows from the source into the target and
+/// checkpoint it, all in one target transaction; returns (rows, bytes)
+async fn transfer_table(
+    source: &Client,
+    target: &Client,
+    copy: &AnonymizedCopy<'_>,
+    table: &TableInfo,
+    progress: &mut Progress,
+    quiet: bool,
+) -> Result<(u64, u64)> {
+    let (columns, copy_sql) = copy.copy_out_sql(source, table).await?;
+    let col_list: Vec<String> = columns.iter().map(|c| quote_ident(c)).collect();
+
+    target.batch_execute("BEGIN").await?;
+    let result = async {
+        let stream = source.copy_out(&copy_sql).await?;
+        let sink = target
+            .copy_in(&format!(
+                "COPY {}.{} ({}) FROM STDIN",
+                quote_ident(&table.schema),
+                quote_ident(&table.name),
+                col_list.join(", ")
+            ))
+            .await?;
+        pin_mut!(stream, sink);
+
+        let (mut rows, mut bytes) = (0u64, 0u64);
+        let mut last_report = Instant::now();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            // COPY text format escapes newlines in values, so each is a row
+            rows += chunk.iter().filter(|b| **b == b'\n').count() as u64;
+            bytes += chunk.len() as u64;
+            sink.send(chunk).await?;
+            if !quiet && last_report.elapsed() >= REPORT_EVERY {
+                last_report = Instant::now();
+                eprintln!(
+                    "      {}: {} rows, {} so far{}",
+                    table.qualified(),
+                    format_number(rows as i64),
+                    format_bytes(bytes),
+                    progress.eta_with(rows)
+                );
+            }
+        }
+        sink.finish().await?;
+
+        target
+            .execute(
+                &format!(
+                    "INSERT INTO {} (table_name, row_count, bytes) VALUES ($1, $2, $3)",
+                    CHECKPOINT_TABLE
+                ),
+                &[&table.qualified(), &(rows as i64), &(bytes as i64)],
+            )
+            .await?;
+        Ok::<_, anyhow::Error>((rows, bytes))
+    }
+    .await;
+
+    match result {
+        Ok((rows, bytes)) => {
+            target.batch_execute("COMMIT").await?;
+            progress.bytes += bytes;
+            Ok((rows, bytes))
+        }
+        Err(e) => {
+            let _ = target.batch_execute("ROLLBACK").await;
+            Err(e)
+        }
+    }
+}
+
+/// How often a long table transfer reports its progress
+const REPORT_EVERY: Duration = Duration::from_secs(5);
+
+/// Tables, bytes and rows transferred so far in this run
+struct Progress {
+    started: Instant,
+    tables_total: usize,
+    tables_done: usize,
+    bytes: u64,
+    rows_done: u64,
+    rows_expected: u64,
+}
+
+impl Progress {
+    fn new(tables_total: usize, rows_expected: u64) -> Self {
+        Self {
+            started: Instant::now(),
+            tables_total,
+            tables_done: 0,
+            bytes: 0,
+            rows_done: 0,
+            rows_expected,
+        }
+    }
+
+    /// `[3/12]`
+    fn counter(&self) -> String {
+        format!("[{}/{}]", self.tables_done, self.tables_total)
+    }
+
+    /// Count a table as done; its rows replace its estimate
+    fn finish_table(&mut self, estimate: u64, rows: u64) {
+        self.rows_done += rows;
+        self.rows_expected = (self.rows_expected + rows)
+            .saturating_sub(estimate)
+            .max(self.rows_done);
+    }
+
+    fn eta(&self) -> String {
+        self.eta_with(0)
+    }
+
+    /// `, ETA 2m 10s` given `in_flight` rows of the current table, or empty
+    /// before there is a rate to go on
+    fn eta_with(&self, in_flight: u64) -> String {
+        let done = self.rows_done + in_flight;
+        let elapsed = self.started.elapsed().as_secs_f64();
+        if done == 0 || elapsed < 1.0 {
+            return String::new();
+        }
+        let remaining = self.rows_expected.saturating_sub(done) as f64;
+        format!(
+            ", ETA {}",
+            format_eta((remaining * elapsed / done as f64) as u64)
+        )
+    }
+}
+
+/// `45s`, `2m 10s`, `1h 05m`
+fn format_eta(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_eta() {
+        assert_eq!(format_eta(45), "45s");
+        assert_eq!(format_eta(130), "2m 10s");
+        assert_eq!(format_eta(3900), "1h 05m");
+    }
+
+    #[test]
+    fn test_progress_replaces_estimates() {
+        // Estimates of 600 and 400 rows
+        let mut progress = Progress::new(2, 1000);
+        progress.finish_table(600, 650);
+        assert_eq!(progress.rows_done, 650);
+        assert_eq!(progress.rows_expected, 1050);
+        progress.finish_table(400, 10);
+        assert_eq!(progress.rows_expected, 660);
+    }
+}
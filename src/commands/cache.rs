@@ -7,10 +7,18 @@ use anyhow::{Context, Result};
 use serde::Serialize;
 use tokio_postgres::Client;
 
+use crate::config::{AlertsConfig, Thresholds};
+
 /// Status thresholds (percentage)
 const CACHE_CRITICAL_PCT: f64 = 90.0;
 const CACHE_WARNING_PCT: f64 = 95.0;
 
+/// Defaults for `[cache]` in pgcrate.alerts.toml (alerts below these ratios)
+pub const DEFAULT_THRESHOLDS: Thresholds = Thresholds {
+    warn: CACHE_WARNING_PCT,
+    crit: CACHE_CRITICAL_PCT,
+};
+
 /// Cache hit status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -21,10 +29,10 @@ pub enum CacheStatus {
 }
 
 impl CacheStatus {
-    pub fn from_hit_ratio(ratio_pct: f64) -> Self {
-        if ratio_pct < CACHE_CRITICAL_PCT {
+    pub fn from_hit_ratio(ratio_pct: f64, thresholds: Thresholds) -> Self {
+        if ratio_pct < thresholds.crit {
             CacheStatus::Critical
-        } else if ratio_pct < CACHE_WARNING_PCT {
+        } else if ratio_pct < thresholds.warn {
             CacheStatus::Warning
         } else {
             CacheStatus::Healthy
@@ -70,10 +78,16 @@ pub struct CacheResult {
     pub database_stats: DatabaseCacheStats,
     pub tables: Vec<TableCacheStats>,
     pub overall_status: CacheStatus,
+    /// Levels the statuses were judged against
+    #[serde(skip)]
+    pub thresholds: Thresholds,
 }
 
 /// Get database-level cache statistics
-async fn get_database_cache_stats(client: &Client) -> Result<DatabaseCacheStats> {
+pub(crate) async fn get_database_cache_stats(
+    client: &Client,
+    thresholds: Thresholds,
+) -> Result<DatabaseCacheStats> {
     let query = r#"
         SELECT
             datname as database,
@@ -99,12 +113,17 @@ async fn get_database_cache_stats(client: &Client) -> Result<DatabaseCacheStats>
         blks_hit: row.get("blks_hit"),
         blks_read: row.get("blks_read"),
         hit_ratio_pct,
-        status: CacheStatus::from_hit_ratio(hit_ratio_pct),
+        status: CacheStatus::from_hit_ratio(hit_ratio_pct, thresholds),
     })
 }
 
 /// Get per-table cache statistics
-async fn get_table_cache_stats(client: &Client, limit: usize) -> Result<Vec<TableCacheStats>> {
+async fn get_table_cache_stats(
+    client: &Client,
+    limit: usize,
+    alerts: &AlertsConfig,
+) -> Result<Vec<TableCacheStats>> {
+    let thresholds = alerts.cache.or(DEFAULT_THRESHOLDS);
     // Get tables with lowest cache hit ratios (most likely to benefit from more RAM)
     let query = r#"
         SELECT
@@ -133,35 +152,46 @@ async fn get_table_cache_stats(client: &Client, limit: usize) -> Result<Vec<Tabl
     "#;
 
     let rows = client
-        .query(query, &[&(limit as i64)])
+        .query(query, &[&((limit + alerts.ignore.count()) as i64)])
         .await
         .context("Failed to query table cache stats")?;
 
     let mut tables = Vec::new();
     for row in rows {
+        let schema: String = row.get("schema");
+        let table: String = row.get("table");
+        if alerts.ignore.table(&schema, &table) {
+            continue;
+        }
         let heap_hit_ratio: Option<f64> = row.get("heap_hit_ratio_pct");
         let hit_ratio_pct = heap_hit_ratio.unwrap_or(100.0);
 
         tables.push(TableCacheStats {
-            schema: row.get("schema"),
-            table: row.get("table"),
+            schema,
+            table,
             heap_blks_hit: row.get("heap_blks_hit"),
             heap_blks_read: row.get("heap_blks_read"),
             hit_ratio_pct,
             idx_blks_hit: row.get("idx_blks_hit"),
             idx_blks_read: row.get("idx_blks_read"),
             idx_hit_ratio_pct: row.get("idx_hit_ratio_pct"),
-            status: CacheStatus::from_hit_ratio(hit_ratio_pct),
+            status: CacheStatus::from_hit_ratio(hit_ratio_pct, thresholds),
         });
     }
+    tables.truncate(limit);
 
     Ok(tables)
 }
 
 /// Run full cache analysis
-pub async fn run_cache(client: &Client, limit: usize) -> Result<CacheResult> {
-    let database_stats = get_database_cache_stats(client).await?;
-    let tables = get_table_cache_stats(client, limit).await?;
+pub async fn run_cache(
+    client: &Client,
+    limit: usize,
+    alerts: &AlertsConfig,
+) -> Result<CacheResult> {
+    let thresholds = alerts.cache.or(DEFAULT_THRESHOLDS);
+    let database_stats = get_database_cache_stats(client, thresholds).await?;
+    let tables = get_table_cache_stats(client, limit, alerts).await?;
 
     // Overall status is worst of database and table statuses
     let mut overall_status = database_stats.status;
@@ -178,6 +208,7 @@ pub async fn run_cache(client: &Client, limit: usize) -> Result<CacheResult> {
         database_stats,
         tables,
         overall_status,
+        thresholds,
     })
 }
 
@@ -252,20 +283,20 @@ pub fn print_human(result: &CacheResult, quiet: bool) {
     if critical_count > 0 {
         println!(
             "  ✗ {} tables with <{}% cache hit ratio (CRITICAL)",
-            critical_count, CACHE_CRITICAL_PCT
+            critical_count, result.thresholds.crit
         );
         println!("    Consider increasing shared_buffers or adding more RAM.");
     }
     if warning_count > 0 {
         println!(
             "  ⚠ {} tables with <{}% cache hit ratio (WARNING)",
-            warning_count, CACHE_WARNING_PCT
+            warning_count, result.thresholds.warn
         );
     }
     if critical_count == 0 && warning_count == 0 {
         println!(
             "  ✓ All tables have healthy cache hit ratios (>={}%)",
-            CACHE_WARNING_PCT
+            result.thresholds.warn
         );
     }
 }
@@ -310,20 +341,38 @@ mod tests {
 
     #[test]
     fn test_cache_status_healthy() {
-        assert_eq!(CacheStatus::from_hit_ratio(99.0), CacheStatus::Healthy);
-        assert_eq!(CacheStatus::from_hit_ratio(95.0), CacheStatus::Healthy);
+        assert_eq!(
+            CacheStatus::from_hit_ratio(99.0, DEFAULT_THRESHOLDS),
+            CacheStatus::Healthy
+        );
+        assert_eq!(
+            CacheStatus::from_hit_ratio(95.0, DEFAULT_THRESHOLDS),
+            CacheStatus::Healthy
+        );
     }
 
     #[test]
     fn test_cache_status_warning() {
-        assert_eq!(CacheStatus::from_hit_ratio(94.9), CacheStatus::Warning);
-        assert_eq!(CacheStatus::from_hit_ratio(90.0), CacheStatus::Warning);
+        assert_eq!(
+            CacheStatus::from_hit_ratio(94.9, DEFAULT_THRESHOLDS),
+            CacheStatus::Warning
+        );
+        assert_eq!(
+            CacheStatus::from_hit_ratio(90.0, DEFAULT_THRESHOLDS),
+            CacheStatus::Warning
+        );
     }
 
     #[test]
     fn test_cache_status_critical() {
-        assert_eq!(CacheStatus::from_hit_ratio(89.9), CacheStatus::Critical);
-        assert_eq!(CacheStatus::from_hit_ratio(50.0), CacheStatus::Critical);
+        assert_eq!(
+            CacheStatus::from_hit_ratio(89.9, DEFAULT_THRESHOLDS),
+            CacheStatus::Critical
+        );
+        assert_eq!(
+            CacheStatus::from_hit_ratio(50.0, DEFAULT_THRESHOLDS),
+            CacheStatus::Critical
+        );
     }
 
     #[test]
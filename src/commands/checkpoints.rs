@@ -12,8 +12,8 @@ use serde::Serialize;
 use tokio_postgres::Client;
 
 /// Thresholds for checkpoint health
-const REQUESTED_PCT_WARNING: f64 = 20.0;
-const REQUESTED_PCT_CRITICAL: f64 = 50.0;
+pub(crate) const REQUESTED_PCT_WARNING: f64 = 20.0;
+pub(crate) const REQUESTED_PCT_CRITICAL: f64 = 50.0;
 const BACKEND_WRITE_PCT_WARNING: f64 = 10.0;
 
 /// Checkpoint status level
@@ -0,0 +1,379 @@
+//! Cluster triage: XID age, sequences, and bloat for every database.
+//!
+//! Transaction ID wraparound is a cluster-level problem: the oldest
+//! datfrozenxid in any database decides when the whole server stops
+//! accepting writes, and a per-database triage never looks at the others.
+//! `dba triage --all-databases` reads database XID ages once, then connects
+//! to each database that accepts connections and checks its oldest table,
+//! fullest sequence, and estimated bloat. Template databases are reported
+//! from pg_database only; connecting to them would block CREATE DATABASE.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::bloat::{get_bloat, BloatStatus};
+use super::sequences::{run_sequences, SeqStatus, SequenceInfo};
+use super::triage::{CheckStatus, SkippedCheck};
+use super::xid::{get_table_xid, TableXid, XidStatus, DEFAULT_THRESHOLDS};
+use crate::config::{parse_database_url, AlertsConfig};
+use crate::diagnostic::{DiagnosticSession, TimeoutConfig};
+use crate::reason_codes::ReasonCode;
+
+/// Bloated objects looked at per database
+const BLOAT_LIMIT: usize = 10;
+
+/// Findings for one database
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseTriage {
+    pub database: String,
+    /// Worst status across the checks that ran
+    pub status: CheckStatus,
+    pub xid_age: i64,
+    pub xid_pct: f64,
+    pub xid_status: CheckStatus,
+    /// False for template databases and databases that refused the connection
+    pub connected: bool,
+    /// Table holding back this database's datfrozenxid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_table: Option<TableXid>,
+    /// Fullest sequence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_sequence: Option<SequenceInfo>,
+    pub sequence_status: CheckStatus,
+    /// Estimated table and index bloat
+    pub bloat_bytes: i64,
+    pub bloat_status: CheckStatus,
+    pub skipped_checks: Vec<SkippedCheck>,
+}
+
+/// Cluster-wide triage results
+#[derive(Debug, Serialize)]
+pub struct ClusterTriageResult {
+    /// Databases, worst status first
+    pub databases: Vec<DatabaseTriage>,
+    pub overall_status: CheckStatus,
+    /// Database with the oldest datfrozenxid (wraparound is counted from here)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_xid_database: Option<String>,
+    pub max_xid_age: i64,
+    pub max_xid_pct: f64,
+    pub total_bloat_bytes: i64,
+    pub databases_checked: usize,
+    pub databases_skipped: usize,
+}
+
+fn rank(status: CheckStatus) -> u8 {
+    match status {
+        CheckStatus::Healthy => 0,
+        CheckStatus::Warning => 1,
+        CheckStatus::Critical => 2,
+    }
+}
+
+fn worst(statuses: &[CheckStatus]) -> CheckStatus {
+    statuses
+        .iter()
+        .copied()
+        .max_by_key(|s| rank(*s))
+        .unwrap_or(CheckStatus::Healthy)
+}
+
+fn from_xid(status: XidStatus) -> CheckStatus {
+    match status {
+        XidStatus::Healthy => CheckStatus::Healthy,
+        XidStatus::Warning => CheckStatus::Warning,
+        XidStatus::Critical => CheckStatus::Critical,
+    }
+}
+
+fn from_seq(status: SeqStatus) -> CheckStatus {
+    match status {
+        SeqStatus::Healthy => CheckStatus::Healthy,
+        SeqStatus::Warning => CheckStatus::Warning,
+        SeqStatus::Critical => CheckStatus::Critical,
+    }
+}
+
+fn from_bloat(status: BloatStatus) -> CheckStatus {
+    match status {
+        BloatStatus::Healthy => CheckStatus::Healthy,
+        BloatStatus::Warning => CheckStatus::Warning,
+        BloatStatus::Critical => CheckStatus::Critical,
+    }
+}
+
+fn skipped(check_id: &'static str, err: &anyhow::Error) -> SkippedCheck {
+    let reason_code = err
+        .chain()
+        .find_map(|e| e.downcast_ref::<tokio_postgres::Error>())
+        .map(ReasonCode::from_postgres_error)
+        .unwrap_or(ReasonCode::InternalError);
+    SkippedCheck {
+        check_id,
+        reason_code,
+        reason_human: format!("{:#}", err),
+    }
+}
+
+/// Run the per-database checks on one connected database
+async fn check_database(client: &Client, alerts: &AlertsConfig, db: &mut DatabaseTriage) {
+    match get_table_xid(client, 1, alerts).await {
+        Ok(tables) => db.oldest_table = tables.into_iter().next(),
+        Err(e) => db.skipped_checks.push(skipped("xid_age", &e)),
+    }
+
+    match run_sequences(client, None, None, alerts).await {
+        Ok(result) => {
+            db.sequence_status = from_seq(result.overall_status);
+            db.top_sequence = result.sequences.into_iter().next();
+        }
+        Err(e) => db.skipped_checks.push(skipped("sequences", &e)),
+    }
+
+    match get_bloat(client, BLOAT_LIMIT, &alerts.ignore).await {
+        Ok(result) => {
+            db.bloat_status = from_bloat(result.overall_status);
+            db.bloat_bytes = result.total_table_bloat_bytes + result.total_index_bloat_bytes;
+        }
+        Err(e) => db.skipped_checks.push(skipped("bloat", &e)),
+    }
+}
+
+/// Triage every database in the cluster reachable from `database_url`
+pub async fn run_cluster_triage(
+    client: &Client,
+    database_url: &str,
+    timeouts: &TimeoutConfig,
+    alerts: &AlertsConfig,
+) -> Result<ClusterTriageResult> {
+    let parsed = parse_database_url(database_url)
+        .context("--all-databases needs a URL-style connection string")?;
+    let thresholds = alerts.xid.or(DEFAULT_THRESHOLDS);
+
+    let query = r#"
+        SELECT
+            datname,
+            age(datfrozenxid)::bigint AS xid_age,
+            (100.0 * age(datfrozenxid)::double precision / 2147483647.0)::double precision AS pct_used,
+            datistemplate
+        FROM pg_database
+        WHERE datallowconn
+        ORDER BY age(datfrozenxid) DESC
+    "#;
+    let rows = client
+        .query(query, &[])
+        .await
+        .context("Failed to list databases")?;
+
+    let mut databases = Vec::with_capacity(rows.len());
+    for row in rows {
+        let database: String = row.get("datname");
+        let xid_age: i64 = row.get("xid_age");
+        let is_template: bool = row.get("datistemplate");
+
+        let mut db = DatabaseTriage {
+            database,
+            status: CheckStatus::Healthy,
+            xid_age,
+            xid_pct: row.get("pct_used"),
+            xid_status: from_xid(XidStatus::from_age(xid_age, thresholds)),
+            connected: false,
+            oldest_table: None,
+            top_sequence: None,
+            sequence_status: CheckStatus::Healthy,
+            bloat_bytes: 0,
+            bloat_status: CheckStatus::Healthy,
+            skipped_checks: Vec::new(),
+        };
+
+        if !is_template {
+            let url = parsed.with_database(&db.database);
+            match DiagnosticSession::connect(&url, timeouts.clone()).await {
+                Ok(session) => {
+                    db.connected = true;
+                    check_database(session.client(), alerts, &mut db).await;
+                }
+                Err(e) => db.skipped_checks.push(skipped("connect", &e)),
+            }
+        }
+
+        db.status = worst(&[db.xid_status, db.sequence_status, db.bloat_status]);
+        databases.push(db);
+    }
+
+    // Rows arrive oldest datfrozenxid first
+    let oldest = databases.first();
+    let oldest_xid_database = oldest.map(|d| d.database.clone());
+    let max_xid_age = oldest.map(|d| d.xid_age).unwrap_or(0);
+    let max_xid_pct = oldest.map(|d| d.xid_pct).unwrap_or(0.0);
+
+    databases.sort_by_key(|d| std::cmp::Reverse(rank(d.status)));
+
+    let statuses: Vec<CheckStatus> = databases.iter().map(|d| d.status).collect();
+    let databases_checked = databases.iter().filter(|d| d.connected).count();
+
+    Ok(ClusterTriageResult {
+        overall_status: worst(&statuses),
+        oldest_xid_database,
+        max_xid_age,
+        max_xid_pct,
+        total_bloat_bytes: databases.iter().map(|d| d.bloat_bytes).sum(),
+        databases_checked,
+        databases_skipped: databases.len() - databases_checked,
+        databases,
+    })
+}
+
+/// Format bytes for display
+fn format_bytes(bytes: i64) -> String {
+    if bytes >= 1_073_741_824 {
+        format!("{:.1} GB", bytes as f64 / 1_073_741_824.0)
+    } else if bytes >= 1_048_576 {
+        format!("{:.1} MB", bytes as f64 / 1_048_576.0)
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Print cluster triage in human-readable format
+pub fn print_human(result: &ClusterTriageResult, quiet: bool) {
+    if !quiet {
+        println!(
+            "CLUSTER: {} database(s), {} checked",
+            result.databases.len(),
+            result.databases_checked
+        );
+        if let Some(ref db) = result.oldest_xid_database {
+            println!(
+                "  Oldest XID: {:.1}B / 2.1B ({:.1}%) in {}",
+                result.max_xid_age as f64 / 1_000_000_000.0,
+                result.max_xid_pct,
+                db
+            );
+        }
+        println!(
+            "  Estimated bloat: {}",
+            format_bytes(result.total_bloat_bytes)
+        );
+        println!();
+    }
+
+    let max_name = result
+        .databases
+        .iter()
+        .map(|d| d.database.len())
+        .max()
+        .unwrap_or(0);
+
+    for db in &result.databases {
+        if quiet && db.status == CheckStatus::Healthy && db.skipped_checks.is_empty() {
+            continue;
+        }
+        let sequence = db
+            .top_sequence
+            .as_ref()
+            .map(|s| format!("{:.0}% {}.{}", s.pct_used, s.schema, s.name))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{} {:width$}  xid {:>5.1}%  seq {:20}  bloat {}",
+            db.status.emoji(),
+            db.database,
+            db.xid_pct,
+            sequence,
+            format_bytes(db.bloat_bytes),
+            width = max_name
+        );
+        if let Some(ref t) = db.oldest_table {
+            if db.xid_status != CheckStatus::Healthy {
+                println!(
+                    "    oldest table: {}.{} (age {})",
+                    t.schema, t.table, t.xid_age
+                );
+            }
+        }
+        if !db.connected && db.skipped_checks.is_empty() {
+            println!("    template database: XID age only");
+        }
+        for skip in &db.skipped_checks {
+            println!(
+                "    {} skipped: {}",
+                skip.check_id,
+                skip.reason_code.description()
+            );
+        }
+    }
+
+    let worst_dbs: Vec<&str> = result
+        .databases
+        .iter()
+        .filter(|d| d.status != CheckStatus::Healthy)
+        .map(|d| d.database.as_str())
+        .collect();
+    if !quiet && !worst_dbs.is_empty() {
+        println!();
+        println!("NEXT ACTIONS:");
+        for db in worst_dbs {
+            println!("  {} → pgcrate dba triage (connected to {})", db, db);
+        }
+    }
+}
+
+/// Print cluster triage as JSON
+pub fn print_json(
+    result: &ClusterTriageResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+    use crate::reason_codes::ReasonInfo;
+
+    let severity = Severity::from_check_status(&result.overall_status);
+
+    let warnings: Vec<ReasonInfo> = result
+        .databases
+        .iter()
+        .flat_map(|db| {
+            db.skipped_checks.iter().map(move |skip| {
+                ReasonInfo::new(
+                    skip.reason_code,
+                    format!("{}: {}: {}", db.database, skip.check_id, skip.reason_human),
+                )
+            })
+        })
+        .collect();
+    let partial = !warnings.is_empty();
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::CLUSTER_TRIAGE, result, severity, t),
+        None => DiagnosticOutput::new(schema::CLUSTER_TRIAGE, result, severity),
+    };
+    output
+        .with_partial(partial)
+        .with_warnings(warnings)
+        .print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worst_status() {
+        assert_eq!(worst(&[]), CheckStatus::Healthy);
+        assert_eq!(
+            worst(&[
+                CheckStatus::Warning,
+                CheckStatus::Healthy,
+                CheckStatus::Critical
+            ]),
+            CheckStatus::Critical
+        );
+        assert_eq!(
+            worst(&[CheckStatus::Healthy, CheckStatus::Warning]),
+            CheckStatus::Warning
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! Comment commands: Set or clear COMMENT ON TABLE / COLUMN.
+//!
+//! Comments are stored in the database, shown by `inspect table`, and
+//! emitted by `generate`, so documentation travels with the schema.
+
+use anyhow::{bail, Result};
+
+use super::connect;
+use crate::describe;
+use crate::output::{CommentResponse, Output};
+use crate::sql::{quote_ident, quote_literal};
+
+/// Split `[schema.]table[.column]` into the table part and optional column.
+/// Two parts always mean schema.table; a column needs the schema too.
+fn parse_target(target: &str) -> Result<(String, Option<String>)> {
+    let parts: Vec<&str> = target.split('.').collect();
+    if parts.iter().any(|p| p.is_empty()) {
+        bail!(
+            "Invalid target \"{}\". Use schema.table or schema.table.column",
+            target
+        );
+    }
+    match parts.as_slice() {
+        [table] => Ok((table.to_string(), None)),
+        [schema, table] => Ok((format!("{}.{}", schema, table), None)),
+        [schema, table, column] => Ok((format!("{}.{}", schema, table), Some(column.to_string()))),
+        _ => bail!(
+            "Invalid target \"{}\". Use schema.table or schema.table.column",
+            target
+        ),
+    }
+}
+
+/// COMMENT ON statement; `None` text removes the comment
+pub fn comment_sql(schema: &str, table: &str, column: Option<&str>, text: Option<&str>) -> String {
+    let value = text
+        .map(quote_literal)
+        .unwrap_or_else(|| "NULL".to_string());
+    match column {
+        Some(column) => format!(
+            "COMMENT ON COLUMN {}.{}.{} IS {};",
+            quote_ident(schema),
+            quote_ident(table),
+            quote_ident(column),
+            value
+        ),
+        None => format!(
+            "COMMENT ON TABLE {}.{} IS {};",
+            quote_ident(schema),
+            quote_ident(table),
+            value
+        ),
+    }
+}
+
+/// Set (or with `text` None, clear) the comment on a table or column
+pub async fn comment_set(
+    database_url: &str,
+    target: &str,
+    text: Option<&str>,
+    dry_run: bool,
+    output: &Output,
+) -> Result<()> {
+    let (table, column) = parse_target(target)?;
+    // An empty comment is stored as no comment, so treat it as --clear
+    let text = text.filter(|t| !t.is_empty());
+
+    let client = connect(database_url).await?;
+    let resolved = describe::resolve_table(&client, &table).await?;
+
+    if let Some(ref column) = column {
+        let exists = client
+            .query_opt(
+                "SELECT 1 FROM pg_attribute
+                 WHERE attrelid = $1::bigint::oid AND attname = $2
+                   AND attnum > 0 AND NOT attisdropped",
+                &[&resolved.oid, column],
+            )
+            .await?
+            .is_some();
+        if !exists {
+            bail!(
+                "Column {} not found in {}.{}",
+                quote_ident(column),
+                quote_ident(&resolved.schema),
+                quote_ident(&resolved.name)
+            );
+        }
+    }
+
+    let sql = comment_sql(&resolved.schema, &resolved.name, column.as_deref(), text);
+    output.verbose(&sql);
+    if !dry_run {
+        client.batch_execute(&sql).await?;
+    }
+
+    if output.is_json() {
+        output.json(&CommentResponse {
+            ok: true,
+            schema: resolved.schema,
+            table: resolved.name,
+            column,
+            comment: text.map(str::to_string),
+            sql,
+            executed: !dry_run,
+        })?;
+        return Ok(());
+    }
+
+    if dry_run {
+        output.data(&sql);
+    } else {
+        let object = match column {
+            Some(ref c) => format!(
+                "column {}.{}.{}",
+                quote_ident(&resolved.schema),
+                quote_ident(&resolved.name),
+                quote_ident(c)
+            ),
+            None => format!(
+                "table {}.{}",
+                quote_ident(&resolved.schema),
+                quote_ident(&resolved.name)
+            ),
+        };
+        let action = if text.is_some() { "Set" } else { "Cleared" };
+        output.info(&format!("{} comment on {}", action, object));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(parse_target("users").unwrap(), ("users".to_string(), None));
+        assert_eq!(
+            parse_target("app.users").unwrap(),
+            ("app.users".to_string(), None)
+        );
+        assert_eq!(
+            parse_target("app.users.email").unwrap(),
+            ("app.users".to_string(), Some("email".to_string()))
+        );
+        assert!(parse_target("app..email").is_err());
+        assert!(parse_target("a.b.c.d").is_err());
+    }
+
+    #[test]
+    fn test_comment_sql() {
+        assert_eq!(
+            comment_sql("app", "users", None, Some("Accounts")),
+            "COMMENT ON TABLE \"app\".\"users\" IS 'Accounts';"
+        );
+        assert_eq!(
+            comment_sql("app", "users", Some("email"), Some("Owner's login")),
+            "COMMENT ON COLUMN \"app\".\"users\".\"email\" IS 'Owner''s login';"
+        );
+        assert_eq!(
+            comment_sql("app", "users", Some("email"), None),
+            "COMMENT ON COLUMN \"app\".\"users\".\"email\" IS NULL;"
+        );
+    }
+}
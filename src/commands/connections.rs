@@ -8,10 +8,19 @@ use serde::Serialize;
 use std::collections::HashMap;
 use tokio_postgres::Client;
 
+use super::pooler::PoolerDetection;
+use crate::config::{AlertsConfig, Thresholds};
+
 /// Status thresholds (percentage of max_connections)
 const CONN_WARNING_PCT: f64 = 75.0;
 const CONN_CRITICAL_PCT: f64 = 90.0;
 
+/// Defaults for `[connections]` in pgcrate.alerts.toml
+pub const DEFAULT_THRESHOLDS: Thresholds = Thresholds {
+    warn: CONN_WARNING_PCT,
+    crit: CONN_CRITICAL_PCT,
+};
+
 /// Connection status level
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -22,10 +31,10 @@ pub enum ConnectionStatus {
 }
 
 impl ConnectionStatus {
-    pub fn from_pct(pct: f64) -> Self {
-        if pct >= CONN_CRITICAL_PCT {
+    pub fn from_pct(pct: f64, thresholds: Thresholds) -> Self {
+        if pct >= thresholds.crit {
             ConnectionStatus::Critical
-        } else if pct >= CONN_WARNING_PCT {
+        } else if pct >= thresholds.warn {
             ConnectionStatus::Warning
         } else {
             ConnectionStatus::Healthy
@@ -88,6 +97,13 @@ pub struct ConnectionsResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub by_application: Option<Vec<ApplicationConnections>>,
     pub overall_status: ConnectionStatus,
+    /// Set when the connection appears to go through pgbouncer, in which case
+    /// these counts are server-side backends, not application clients
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pooler: Option<PoolerDetection>,
+    /// Levels the status was judged against
+    #[serde(skip)]
+    pub thresholds: Thresholds,
 }
 
 /// Get max_connections setting
@@ -278,7 +294,9 @@ pub async fn run_connections(
     include_by_user: bool,
     include_by_database: bool,
     include_by_application: bool,
+    alerts: &AlertsConfig,
 ) -> Result<ConnectionsResult> {
+    let thresholds = alerts.connections.or(DEFAULT_THRESHOLDS);
     let max_connections = get_max_connections(client).await?;
     let reserved_connections = get_reserved_connections(client).await?;
     let total = get_total_connections(client).await?;
@@ -290,7 +308,7 @@ pub async fn run_connections(
     } else {
         0.0
     };
-    let status = ConnectionStatus::from_pct(usage_pct);
+    let status = ConnectionStatus::from_pct(usage_pct, thresholds);
 
     let stats = ConnectionStats {
         total,
@@ -326,6 +344,8 @@ pub async fn run_connections(
         by_database,
         by_application,
         overall_status: status,
+        pooler: None,
+        thresholds,
     })
 }
 
@@ -347,6 +367,11 @@ pub fn print_human(result: &ConnectionsResult, quiet: bool) {
     println!("    superuser_reserved: {}", stats.reserved_connections);
     println!("    available: {}", stats.available);
     println!("    in use: {}", stats.total);
+    if let Some(ref pooler) = result.pooler {
+        println!();
+        println!("  Through a pooler: {}", pooler.reason);
+        println!("    Client-side pool usage: pgcrate dba pooler");
+    }
 
     // State breakdown
     if !stats.by_state.is_empty() {
@@ -425,14 +450,17 @@ pub fn print_human(result: &ConnectionsResult, quiet: bool) {
         match stats.status {
             ConnectionStatus::Critical => {
                 println!();
-                println!("  ✗ CRITICAL: Connection usage >{}%", CONN_CRITICAL_PCT);
+                println!(
+                    "  ✗ CRITICAL: Connection usage >{}%",
+                    result.thresholds.crit
+                );
                 println!(
                     "    Consider increasing max_connections or investigating connection leaks"
                 );
             }
             ConnectionStatus::Warning => {
                 println!();
-                println!("  ⚠ WARNING: Connection usage >{}%", CONN_WARNING_PCT);
+                println!("  ⚠ WARNING: Connection usage >{}%", result.thresholds.warn);
                 println!("    Monitor for potential connection exhaustion");
             }
             ConnectionStatus::Healthy => {}
@@ -478,24 +506,45 @@ mod tests {
 
     #[test]
     fn test_connection_status_healthy() {
-        assert_eq!(ConnectionStatus::from_pct(50.0), ConnectionStatus::Healthy);
+        assert_eq!(
+            ConnectionStatus::from_pct(50.0, DEFAULT_THRESHOLDS),
+            ConnectionStatus::Healthy
+        );
     }
 
     #[test]
     fn test_connection_status_warning() {
-        assert_eq!(ConnectionStatus::from_pct(80.0), ConnectionStatus::Warning);
+        assert_eq!(
+            ConnectionStatus::from_pct(80.0, DEFAULT_THRESHOLDS),
+            ConnectionStatus::Warning
+        );
     }
 
     #[test]
     fn test_connection_status_critical() {
-        assert_eq!(ConnectionStatus::from_pct(95.0), ConnectionStatus::Critical);
+        assert_eq!(
+            ConnectionStatus::from_pct(95.0, DEFAULT_THRESHOLDS),
+            ConnectionStatus::Critical
+        );
     }
 
     #[test]
     fn test_connection_status_boundary() {
-        assert_eq!(ConnectionStatus::from_pct(74.9), ConnectionStatus::Healthy);
-        assert_eq!(ConnectionStatus::from_pct(75.0), ConnectionStatus::Warning);
-        assert_eq!(ConnectionStatus::from_pct(89.9), ConnectionStatus::Warning);
-        assert_eq!(ConnectionStatus::from_pct(90.0), ConnectionStatus::Critical);
+        assert_eq!(
+            ConnectionStatus::from_pct(74.9, DEFAULT_THRESHOLDS),
+            ConnectionStatus::Healthy
+        );
+        assert_eq!(
+            ConnectionStatus::from_pct(75.0, DEFAULT_THRESHOLDS),
+            ConnectionStatus::Warning
+        );
+        assert_eq!(
+            ConnectionStatus::from_pct(89.9, DEFAULT_THRESHOLDS),
+            ConnectionStatus::Warning
+        );
+        assert_eq!(
+            ConnectionStatus::from_pct(90.0, DEFAULT_THRESHOLDS),
+            ConnectionStatus::Critical
+        );
     }
 }
@@ -9,6 +9,8 @@ use serde::Serialize;
 use std::collections::HashMap;
 use tokio_postgres::Client;
 
+use super::pooler::{detect_pooler, PoolerDetection};
+
 /// Connection target information
 #[derive(Debug, Serialize)]
 pub struct TargetInfo {
@@ -66,6 +68,9 @@ pub struct ContextData {
     pub extensions: HashMap<String, String>,
     /// Effective privileges
     pub privileges: PrivilegeInfo,
+    /// Set when the connection appears to go through a pooler such as pgbouncer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pooler: Option<PoolerDetection>,
 }
 
 /// Context results wrapper
@@ -270,6 +275,7 @@ pub async fn run_context(
     let server = get_server_info(client, no_redact).await?;
     let extensions = get_extensions(client).await?;
     let privileges = get_privileges(client).await?;
+    let pooler = detect_pooler(client, connection_url).await;
 
     Ok(ContextResult {
         context: ContextData {
@@ -277,6 +283,7 @@ pub async fn run_context(
             server,
             extensions,
             privileges,
+            pooler,
         },
     })
 }
@@ -298,6 +305,9 @@ pub fn print_human(result: &ContextResult) {
             "read-write"
         }
     );
+    if let Some(ref pooler) = ctx.pooler {
+        println!("  Pooler:   likely ({})", pooler.reason);
+    }
 
     println!();
     println!("SERVER:");
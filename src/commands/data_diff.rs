@@ -0,0 +1,384 @@
+//! Data diff: Compare a table's rows between two databases.
+//!
+//! Rows are spread over 256 buckets by a hash of their key, and each side
+//! returns one row count and checksum per bucket, so matching tables cost a
+//! single aggregate query per side. Only rows in buckets whose checksums
+//! differ are fetched, as key and row hash, to find what was added, removed,
+//! or changed. Nothing but hashes crosses the wire for changed rows.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tokio_postgres::Client;
+
+use super::anonymize::get_primary_key_columns;
+use super::connect;
+use super::schema::extract_db_name;
+use crate::describe;
+use crate::output::Output;
+use crate::sql::quote_ident;
+
+/// Buckets are the first byte of the key's md5
+const BUCKETS: usize = 256;
+
+/// Session settings that make row text identical across servers
+const SESSION_SETTINGS: &str = "SET TimeZone = 'UTC'; SET DateStyle = 'ISO, MDY'; \
+     SET IntervalStyle = 'postgres'; SET extra_float_digits = 3; SET bytea_output = 'hex'";
+
+/// Row-level differences for one table. Keys are row literals such as `(42)`.
+#[derive(Debug, Serialize)]
+pub struct DataDiff {
+    pub schema: String,
+    pub table: String,
+    pub key: Vec<String>,
+    pub source_rows: i64,
+    pub target_rows: i64,
+    pub buckets_differing: usize,
+    /// Rows only in the target
+    pub added: usize,
+    /// Rows only in the source
+    pub removed: usize,
+    /// Rows in both whose contents differ
+    pub changed: usize,
+    pub added_keys: Vec<String>,
+    pub removed_keys: Vec<String>,
+    pub changed_keys: Vec<String>,
+    /// Key lists were cut off at --limit
+    pub truncated: bool,
+}
+
+impl DataDiff {
+    pub fn is_identical(&self) -> bool {
+        self.added == 0 && self.removed == 0 && self.changed == 0
+    }
+}
+
+/// JSON response for inspect data-diff
+#[derive(Debug, Serialize)]
+struct DataDiffResponse<'a> {
+    ok: bool,
+    identical: bool,
+    #[serde(flatten)]
+    diff: &'a DataDiff,
+}
+
+/// Per-row source: key literal and md5 of the whole row
+fn rows_subquery(schema: &str, table: &str, key: &[String], columns: &[String]) -> String {
+    let list = |cols: &[String]| {
+        cols.iter()
+            .map(|c| quote_ident(c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!(
+        "SELECT ROW({})::text AS k, md5(ROW({})::text) AS h FROM {}.{}",
+        list(key),
+        list(columns),
+        quote_ident(schema),
+        quote_ident(table)
+    )
+}
+
+/// Row count and order-independent checksum per bucket
+pub fn bucket_sql(schema: &str, table: &str, key: &[String], columns: &[String]) -> String {
+    format!(
+        "SELECT get_byte(decode(md5(k), 'hex'), 0) AS bucket, count(*) AS n, \
+         md5(string_agg(k || ':' || h, ',' ORDER BY k COLLATE \"C\")) AS checksum \
+         FROM ({}) r GROUP BY 1",
+        rows_subquery(schema, table, key, columns)
+    )
+}
+
+/// Keys and row hashes in the given buckets ($1)
+pub fn bucket_rows_sql(schema: &str, table: &str, key: &[String], columns: &[String]) -> String {
+    format!(
+        "SELECT k, h FROM ({}) r WHERE get_byte(decode(md5(k), 'hex'), 0) = ANY($1::int[])",
+        rows_subquery(schema, table, key, columns)
+    )
+}
+
+async fn table_columns(client: &Client, schema: &str, table: &str) -> Result<Vec<String>> {
+    let rows = client
+        .query(
+            "SELECT a.attname::text
+             FROM pg_attribute a
+             JOIN pg_class c ON c.oid = a.attrelid
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2
+               AND a.attnum > 0 AND NOT a.attisdropped
+             ORDER BY a.attnum",
+            &[&schema, &table],
+        )
+        .await?;
+    Ok(rows.iter().map(|r| r.get(0)).collect())
+}
+
+async fn bucket_checksums(client: &Client, sql: &str) -> Result<HashMap<i32, (i64, String)>> {
+    let rows = client.query(sql, &[]).await?;
+    Ok(rows
+        .iter()
+        .map(|r| (r.get("bucket"), (r.get("n"), r.get("checksum"))))
+        .collect())
+}
+
+async fn bucket_rows(
+    client: &Client,
+    sql: &str,
+    buckets: &[i32],
+    key: &[String],
+) -> Result<BTreeMap<String, String>> {
+    let mut rows = BTreeMap::new();
+    for row in client.query(sql, &[&buckets]).await? {
+        let k: String = row.get(0);
+        if rows.insert(k.clone(), row.get(1)).is_some() {
+            bail!(
+                "Key ({}) is not unique: {} appears more than once. Pass a unique --key",
+                key.join(", "),
+                k
+            );
+        }
+    }
+    Ok(rows)
+}
+
+/// Compare one table's rows between a source and a target connection
+pub async fn compare(
+    source: &Client,
+    target: &Client,
+    object: &str,
+    key: Option<&str>,
+    limit: usize,
+) -> Result<DataDiff> {
+    let resolved = describe::resolve_table(source, object).await?;
+    let (schema, table) = (resolved.schema, resolved.name);
+
+    let columns = table_columns(source, &schema, &table).await?;
+    let target_columns = table_columns(target, &schema, &table).await?;
+    if target_columns.is_empty() {
+        bail!(
+            "Table {}.{} not found in target",
+            quote_ident(&schema),
+            quote_ident(&table)
+        );
+    }
+    if columns != target_columns {
+        bail!(
+            "Columns of {}.{} differ between source and target; compare schemas with `pgcrate inspect diff` first",
+            quote_ident(&schema),
+            quote_ident(&table)
+        );
+    }
+
+    let key: Vec<String> = match key {
+        Some(key) => key.split(',').map(|c| c.trim().to_string()).collect(),
+        None => get_primary_key_columns(source, &schema, &table).await?,
+    };
+    if key.is_empty() {
+        bail!(
+            "{}.{} has no primary key; pass --key with columns that identify a row",
+            quote_ident(&schema),
+            quote_ident(&table)
+        );
+    }
+    if let Some(missing) = key.iter().find(|k| !columns.contains(k)) {
+        bail!(
+            "Key column {} not found in {}.{}",
+            quote_ident(missing),
+            quote_ident(&schema),
+            quote_ident(&table)
+        );
+    }
+
+    source.batch_execute(SESSION_SETTINGS).await?;
+    target.batch_execute(SESSION_SETTINGS).await?;
+
+    let sql = bucket_sql(&schema, &table, &key, &columns);
+    let source_buckets = bucket_checksums(source, &sql).await?;
+    let target_buckets = bucket_checksums(target, &sql).await?;
+
+    let differing: Vec<i32> = (0..BUCKETS as i32)
+        .filter(|b| source_buckets.get(b) != target_buckets.get(b))
+        .collect();
+
+    let mut diff = DataDiff {
+        source_rows: source_buckets.values().map(|(n, _)| n).sum(),
+        target_rows: target_buckets.values().map(|(n, _)| n).sum(),
+        schema,
+        table,
+        key,
+        buckets_differing: differing.len(),
+        added: 0,
+        removed: 0,
+        changed: 0,
+        added_keys: Vec::new(),
+        removed_keys: Vec::new(),
+        changed_keys: Vec::new(),
+        truncated: false,
+    };
+    if differing.is_empty() {
+        return Ok(diff);
+    }
+
+    let sql = bucket_rows_sql(&diff.schema, &diff.table, &diff.key, &columns);
+    let source_rows = bucket_rows(source, &sql, &differing, &diff.key).await?;
+    let target_rows = bucket_rows(target, &sql, &differing, &diff.key).await?;
+
+    let keep = |keys: &mut Vec<String>, count: &mut usize, k: &String| {
+        *count += 1;
+        if keys.len() < limit {
+            keys.push(k.clone());
+        }
+    };
+    for (k, h) in &source_rows {
+        match target_rows.get(k) {
+            None => keep(&mut diff.removed_keys, &mut diff.removed, k),
+            Some(t) if t != h => keep(&mut diff.changed_keys, &mut diff.changed, k),
+            Some(_) => {}
+        }
+    }
+    for k in target_rows.keys() {
+        if !source_rows.contains_key(k) {
+            keep(&mut diff.added_keys, &mut diff.added, k);
+        }
+    }
+    diff.truncated = diff.added > limit || diff.removed > limit || diff.changed > limit;
+
+    Ok(diff)
+}
+
+/// Human-readable report
+pub fn format(diff: &DataDiff, from_label: &str, to_label: &str) -> String {
+    let mut out = vec![
+        format!(
+            "\nData diff: {}.{}  ({} → {}, key: {})",
+            quote_ident(&diff.schema),
+            quote_ident(&diff.table),
+            from_label,
+            to_label,
+            diff.key.join(", ")
+        ),
+        "─".repeat(64),
+        format!(
+            "  Rows: {} in source, {} in target",
+            diff.source_rows, diff.target_rows
+        ),
+    ];
+
+    if diff.is_identical() {
+        out.push(String::new());
+        out.push(format!("  {}", "Rows are identical.".green()));
+        return out.join("\n");
+    }
+
+    out.push(format!(
+        "  Buckets differing: {} of {}",
+        diff.buckets_differing, BUCKETS
+    ));
+    let sections = [
+        ("+".green(), "in target only", diff.added, &diff.added_keys),
+        (
+            "-".red(),
+            "in source only",
+            diff.removed,
+            &diff.removed_keys,
+        ),
+        ("~".yellow(), "changed", diff.changed, &diff.changed_keys),
+    ];
+    for (mark, label, count, keys) in sections {
+        if count == 0 {
+            continue;
+        }
+        out.push(String::new());
+        out.push(format!(
+            "  {} {} row{} {}",
+            mark,
+            count,
+            if count == 1 { "" } else { "s" },
+            label
+        ));
+        for k in keys.iter() {
+            out.push(format!("      {}", k));
+        }
+        if count > keys.len() {
+            out.push(format!("      ... {} more", count - keys.len()));
+        }
+    }
+    out.join("\n")
+}
+
+/// Compare a table's rows between two databases.
+/// Returns exit code: 0 = identical, 1 = differs
+pub async fn data_diff(
+    from_url: &str,
+    to_url: &str,
+    table: &str,
+    key: Option<&str>,
+    limit: usize,
+    output: &Output,
+) -> Result<i32> {
+    let source = connect(from_url).await?;
+    let target = connect(to_url).await?;
+    let diff = compare(&source, &target, table, key, limit).await?;
+    let exit_code = if diff.is_identical() { 0 } else { 1 };
+
+    if output.is_json() {
+        output.json(&DataDiffResponse {
+            ok: true,
+            identical: diff.is_identical(),
+            diff: &diff,
+        })?;
+        return Ok(exit_code);
+    }
+
+    if !output.is_quiet() {
+        output.data(&format(
+            &diff,
+            &extract_db_name(from_url),
+            &extract_db_name(to_url),
+        ));
+    }
+    Ok(exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_sql() {
+        let key = vec!["id".to_string()];
+        let columns = vec!["id".to_string(), "Name".to_string()];
+        let sql = bucket_sql("app", "users", &key, &columns);
+        assert!(sql.contains(
+            "SELECT ROW(\"id\")::text AS k, md5(ROW(\"id\", \"Name\")::text) AS h FROM \"app\".\"users\""
+        ));
+        assert!(sql.contains("GROUP BY 1"));
+        let sql = bucket_rows_sql("app", "users", &key, &columns);
+        assert!(sql.ends_with("= ANY($1::int[])"));
+    }
+
+    #[test]
+    fn test_format_lists_keys() {
+        let diff = DataDiff {
+            schema: "app".to_string(),
+            table: "users".to_string(),
+            key: vec!["id".to_string()],
+            source_rows: 10,
+            target_rows: 9,
+            buckets_differing: 2,
+            added: 0,
+            removed: 3,
+            changed: 1,
+            added_keys: vec![],
+            removed_keys: vec!["(1)".to_string(), "(2)".to_string()],
+            changed_keys: vec!["(5)".to_string()],
+            truncated: true,
+        };
+        let text = format(&diff, "prod", "restore");
+        assert!(text.contains("(prod → restore, key: id)"));
+        assert!(text.contains("3 rows in source only\n      (1)\n      (2)\n      ... 1 more"));
+        assert!(text.contains("1 row changed\n      (5)"));
+        assert!(!text.contains("in target only"));
+    }
+}
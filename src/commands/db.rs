@@ -148,7 +148,7 @@ pub async fn reset(
         db_create(database_url, None, config, quiet).await?;
 
         // Run migrations
-        super::up(database_url, config, quiet, verbose, false).await?;
+        super::up(database_url, config, quiet, verbose, false, false, false).await?;
     } else {
         // Standard reset: down all, up
         if !quiet {
@@ -176,7 +176,7 @@ pub async fn reset(
         }
 
         // Run migrations
-        super::up(database_url, config, quiet, verbose, false).await?;
+        super::up(database_url, config, quiet, verbose, false, false, false).await?;
     }
 
     if !quiet {
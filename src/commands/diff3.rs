@@ -0,0 +1,358 @@
+//! Three-way schema diff: What changed on each side since a common base.
+//!
+//! Ours and theirs are each diffed against the base with the schema diff
+//! engine. An object changed on one side only can be promoted as is; one
+//! changed on both sides is a conflict, unless both sides ended up with the
+//! same definition. Columns are compared individually, so changes to
+//! different columns of one table don't conflict.
+
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use super::connect;
+use super::schema::extract_db_name;
+use crate::diff::{diff_schemas, ChangeKind};
+use crate::introspect::{self, DatabaseSchema, IntrospectOptions};
+use crate::output::Output;
+
+/// Where a difference comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Diff3Side {
+    OnlyOurs,
+    OnlyTheirs,
+    /// Changed differently on both sides
+    Conflict,
+}
+
+/// One object that differs from the base
+#[derive(Debug, Clone, Serialize)]
+pub struct Diff3Entry {
+    pub side: Diff3Side,
+    /// table, column, index, policy, ...
+    pub kind: String,
+    pub name: String,
+    /// Change in ours relative to the base
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ours: Option<ChangeKind>,
+    /// Change in theirs relative to the base
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theirs: Option<ChangeKind>,
+}
+
+/// Three-way diff results
+#[derive(Debug, Default, Serialize)]
+pub struct Diff3 {
+    /// Only in ours, then only in theirs, then conflicts
+    pub entries: Vec<Diff3Entry>,
+    pub only_ours: usize,
+    pub only_theirs: usize,
+    pub conflicts: usize,
+    /// Changed the same way on both sides
+    pub converged: usize,
+}
+
+impl Diff3 {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// JSON response for inspect diff3
+#[derive(Debug, Serialize)]
+struct Diff3Response<'a> {
+    ok: bool,
+    identical: bool,
+    #[serde(flatten)]
+    diff: &'a Diff3,
+}
+
+/// Classify every difference from `base` in `ours` and `theirs`
+pub fn diff3_schemas(
+    base: &DatabaseSchema,
+    ours: &DatabaseSchema,
+    theirs: &DatabaseSchema,
+) -> Diff3 {
+    let ours_changes = diff_schemas(base, ours).changes();
+    let theirs_changes = diff_schemas(base, theirs).changes();
+    let between = diff_schemas(ours, theirs).changes();
+
+    let mut diff = Diff3::default();
+    let keys: BTreeSet<_> = ours_changes.keys().chain(theirs_changes.keys()).collect();
+    for key in keys {
+        let ours = ours_changes.get(key).copied();
+        let theirs = theirs_changes.get(key).copied();
+        let side = match (ours, theirs) {
+            (Some(_), None) => Diff3Side::OnlyOurs,
+            (None, Some(_)) => Diff3Side::OnlyTheirs,
+            _ if !between.contains_key(key) => {
+                diff.converged += 1;
+                continue;
+            }
+            _ => Diff3Side::Conflict,
+        };
+        match side {
+            Diff3Side::OnlyOurs => diff.only_ours += 1,
+            Diff3Side::OnlyTheirs => diff.only_theirs += 1,
+            Diff3Side::Conflict => diff.conflicts += 1,
+        }
+        diff.entries.push(Diff3Entry {
+            side,
+            kind: key.0.to_string(),
+            name: key.1.clone(),
+            ours,
+            theirs,
+        });
+    }
+    // Stable sort keeps kind and name order within each side
+    diff.entries.sort_by_key(|e| e.side);
+    diff
+}
+
+/// Format a three-way diff for display
+pub fn format_diff3(diff: &Diff3, base: &str, ours: &str, theirs: &str) -> String {
+    let mut output = vec![format!(
+        "Three-way diff: base {} → ours {}, theirs {}",
+        base, ours, theirs
+    )];
+
+    let sections = [
+        (Diff3Side::OnlyOurs, "Only in ours:"),
+        (Diff3Side::OnlyTheirs, "Only in theirs:"),
+        (
+            Diff3Side::Conflict,
+            "Conflicts (changed differently on both sides):",
+        ),
+    ];
+    for (side, header) in sections {
+        let entries: Vec<&Diff3Entry> = diff.entries.iter().filter(|e| e.side == side).collect();
+        if entries.is_empty() {
+            continue;
+        }
+        output.push(String::new());
+        output.push(header.to_string());
+        for e in entries {
+            let line = match (e.ours, e.theirs) {
+                (Some(o), Some(t)) => format!(
+                    "  ! {} {} (ours: {}, theirs: {})",
+                    e.kind,
+                    e.name,
+                    change_name(o),
+                    change_name(t)
+                ),
+                (Some(c), None) | (None, Some(c)) => {
+                    format!("  {} {} {}", c.symbol(), e.kind, e.name)
+                }
+                (None, None) => continue,
+            };
+            output.push(line);
+        }
+    }
+
+    output.push(String::new());
+    let mut summary = format!(
+        "{} only in ours, {} only in theirs, {} conflict{}",
+        diff.only_ours,
+        diff.only_theirs,
+        diff.conflicts,
+        if diff.conflicts == 1 { "" } else { "s" }
+    );
+    if diff.converged > 0 {
+        summary.push_str(&format!(
+            " ({} changed the same on both sides)",
+            diff.converged
+        ));
+    }
+    output.push(summary);
+    output.join("\n")
+}
+
+fn change_name(change: ChangeKind) -> &'static str {
+    match change {
+        ChangeKind::Added => "added",
+        ChangeKind::Removed => "removed",
+        ChangeKind::Modified => "modified",
+    }
+}
+
+/// Compare two databases against a common base.
+/// Returns exit code: 0 = no differences, 1 = differences, 2 = conflicts
+pub async fn diff3(
+    base_url: &str,
+    ours_url: &str,
+    theirs_url: &str,
+    include_schemas: &[String],
+    exclude_schemas: &[String],
+    include_privileges: bool,
+    output: &Output,
+) -> Result<i32> {
+    let options = IntrospectOptions {
+        include_schemas: include_schemas.to_vec(),
+        exclude_schemas: exclude_schemas.to_vec(),
+    };
+
+    let mut schemas = Vec::with_capacity(3);
+    for (label, url) in [
+        ("base", base_url),
+        ("ours", ours_url),
+        ("theirs", theirs_url),
+    ] {
+        output.verbose(
+            &format!("Introspecting {} database...", label)
+                .dimmed()
+                .to_string(),
+        );
+        let client = connect(url).await?;
+        let mut schema = introspect::introspect(&client, &options).await?;
+        if include_privileges {
+            schema.privileges = introspect::introspect_privileges(&client, &options).await?;
+        }
+        schemas.push(schema);
+    }
+
+    let diff = diff3_schemas(&schemas[0], &schemas[1], &schemas[2]);
+    let exit_code = if diff.conflicts > 0 {
+        2
+    } else if diff.is_empty() {
+        0
+    } else {
+        1
+    };
+
+    if output.is_json() {
+        output.json(&Diff3Response {
+            ok: true,
+            identical: diff.is_empty(),
+            diff: &diff,
+        })?;
+        return Ok(exit_code);
+    }
+    if output.is_quiet() {
+        return Ok(exit_code);
+    }
+
+    if diff.is_empty() {
+        let mut message = "No differences from the base.".to_string();
+        if diff.converged > 0 {
+            message.push_str(&format!(
+                " {} object(s) changed the same on both sides.",
+                diff.converged
+            ));
+        }
+        println!("{}", message.green());
+        return Ok(exit_code);
+    }
+    println!(
+        "{}",
+        format_diff3(
+            &diff,
+            &extract_db_name(base_url),
+            &extract_db_name(ours_url),
+            &extract_db_name(theirs_url)
+        )
+    );
+    Ok(exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::introspect::{Column, Table};
+
+    fn table(name: &str, columns: &[(&str, &str)]) -> Table {
+        Table {
+            schema: "app".to_string(),
+            name: name.to_string(),
+            columns: columns
+                .iter()
+                .map(|(name, data_type)| Column {
+                    name: name.to_string(),
+                    data_type: data_type.to_string(),
+                    nullable: true,
+                    default: None,
+                    generated: false,
+                    identity: None,
+                    is_serial: false,
+                    is_primary_key: false,
+                    comment: None,
+                })
+                .collect(),
+            primary_key: None,
+            partition_info: None,
+            is_partition: false,
+            parent_schema: None,
+            parent_name: None,
+            partition_bound: None,
+            comment: None,
+            rls_enabled: false,
+            rls_forced: false,
+        }
+    }
+
+    fn schema(tables: Vec<Table>) -> DatabaseSchema {
+        DatabaseSchema {
+            tables,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff3_schemas() {
+        let base = schema(vec![
+            table(
+                "users",
+                &[("id", "integer"), ("name", "text"), ("email", "text")],
+            ),
+            table("audit", &[("id", "integer")]),
+        ]);
+        let ours = schema(vec![
+            table(
+                "users",
+                &[
+                    ("id", "bigint"),
+                    ("name", "varchar(50)"),
+                    ("email", "citext"),
+                ],
+            ),
+            table("audit", &[("id", "integer")]),
+            table("invoices", &[("id", "integer")]),
+        ]);
+        let theirs = schema(vec![
+            table("users", &[("id", "bigint"), ("name", "text")]),
+            table("legacy", &[("id", "integer")]),
+        ]);
+
+        let diff = diff3_schemas(&base, &ours, &theirs);
+        let entries: Vec<(Diff3Side, &str)> = diff
+            .entries
+            .iter()
+            .map(|e| (e.side, e.name.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (Diff3Side::OnlyOurs, "app.users.name"),
+                (Diff3Side::OnlyOurs, "app.invoices"),
+                (Diff3Side::OnlyTheirs, "app.audit"),
+                (Diff3Side::OnlyTheirs, "app.legacy"),
+                (Diff3Side::Conflict, "app.users.email"),
+            ]
+        );
+        // users.id became bigint on both sides
+        assert_eq!(diff.converged, 1);
+        assert_eq!(diff.entries[4].ours, Some(ChangeKind::Modified));
+        assert_eq!(diff.entries[4].theirs, Some(ChangeKind::Removed));
+
+        let formatted = format_diff3(&diff, "base", "staging", "prod");
+        assert!(formatted.contains("  + table app.invoices"));
+        assert!(formatted.contains("  - table app.audit"));
+        assert!(formatted.contains("  ! column app.users.email (ours: modified, theirs: removed)"));
+        assert!(formatted.ends_with(
+            "2 only in ours, 2 only in theirs, 1 conflict (1 changed the same on both sides)"
+        ));
+
+        assert!(diff3_schemas(&base, &base, &base).is_empty());
+    }
+}
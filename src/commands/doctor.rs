@@ -1,6 +1,6 @@
 //! Doctor command for pgcrate CLI.
 
-use crate::config::Config;
+use crate::config::{AlertsConfig, Config};
 use crate::doctor::{mask_database_url, DoctorItem, DoctorReport};
 use crate::migrations::discover_migrations;
 use anyhow::{bail, Result};
@@ -55,6 +55,7 @@ pub async fn doctor(
 
     add_config_checks(&config, &config_file, &mut report);
     add_schema_checks(&client, &mut report).await;
+    add_persistence_checks(&client, &mut report).await;
     add_migrations_checks(&client, &config, defaults_mode, &mut report).await;
     emit_doctor_report(report, quiet, json, verbose, strict)
 }
@@ -184,6 +185,76 @@ async fn add_schema_checks(client: &Client, report: &mut DoctorReport) {
     }
 }
 
+/// Unlogged and key-less tables are warnings; published tables whose UPDATE
+/// or DELETE will fail for lack of a replica identity are errors
+async fn add_persistence_checks(client: &Client, report: &mut DoctorReport) {
+    use super::persistence::{get_persistence_issues, PersistenceIssue};
+
+    // Honor [ignore] tables from ./pgcrate.alerts.toml like `dba persistence` does
+    let ignore = AlertsConfig::load(None).unwrap_or_default().ignore;
+    let tables = match get_persistence_issues(client, &ignore).await {
+        Ok(tables) => tables,
+        Err(e) => {
+            report.schema.push(DoctorItem::warning(format!(
+                "Failed to check table persistence: {}",
+                e
+            )));
+            return;
+        }
+    };
+
+    let names = |issue: PersistenceIssue| {
+        let names: Vec<String> = tables
+            .iter()
+            .filter(|t| t.issues.contains(&issue))
+            .map(|t| format!("{}.{}", t.schema, t.table))
+            .collect();
+        let preview = names.iter().take(5).cloned().collect::<Vec<_>>().join(", ");
+        let suffix = if names.len() > 5 {
+            format!(" (+{} more)", names.len() - 5)
+        } else {
+            String::new()
+        };
+        (names.len(), format!("{}{}", preview, suffix))
+    };
+
+    let (count, list) = names(PersistenceIssue::NoReplicaIdentity);
+    if count > 0 {
+        report.schema.push(DoctorItem::error(format!(
+            "{} published table(s) without replica identity [no_replica_identity]: {}",
+            count, list
+        )));
+    }
+    let (count, list) = names(PersistenceIssue::Unlogged);
+    if count > 0 {
+        report.schema.push(DoctorItem::warning(format!(
+            "{} unlogged table(s) [unlogged]: {}",
+            count, list
+        )));
+    }
+    let (count, list) = names(PersistenceIssue::NoPrimaryKey);
+    if count > 0 {
+        report.schema.push(DoctorItem::warning(format!(
+            "{} table(s) without primary key [no_primary_key]: {}",
+            count, list
+        )));
+    }
+    if !tables.iter().any(|t| {
+        t.issues.iter().any(|i| {
+            matches!(
+                i,
+                PersistenceIssue::NoReplicaIdentity
+                    | PersistenceIssue::Unlogged
+                    | PersistenceIssue::NoPrimaryKey
+            )
+        })
+    }) {
+        report
+            .schema
+            .push(DoctorItem::pass("All tables logged with primary keys"));
+    }
+}
+
 async fn add_migrations_checks(
     client: &Client,
     config: &Config,
@@ -9,9 +9,10 @@ use serde::{Deserialize, Serialize};
 use tokio_postgres::Client;
 
 use super::fix::common::{ActionGates, ActionType, Risk, StructuredAction};
+use super::plan_baseline::BaselineOutcome;
 
 /// Issue severity levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum IssueSeverity {
     Info,
@@ -88,6 +89,18 @@ pub struct ExplainResult {
     /// Structured fix actions (when --include-actions is used)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub actions: Option<Vec<StructuredAction>>,
+    /// Comparison against a stored plan (when --baseline is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<BaselineOutcome>,
+}
+
+impl ExplainResult {
+    /// Worst severity across plan issues and baseline changes
+    pub fn max_severity(&self) -> Option<IssueSeverity> {
+        let issues = self.issues.iter().map(|i| i.severity).max();
+        let baseline = self.baseline.as_ref().and_then(|b| b.max_severity());
+        issues.max(baseline)
+    }
 }
 
 /// PostgreSQL EXPLAIN JSON format (simplified)
@@ -309,6 +322,7 @@ pub async fn run_explain(client: &Client, query: &str, analyze: bool) -> Result<
         recommendations,
         stats,
         actions: None,
+        baseline: None,
     })
 }
 
@@ -590,6 +604,13 @@ pub fn print_human(result: &ExplainResult, verbose: bool) {
             }
         }
     }
+
+    if let Some(ref baseline) = result.baseline {
+        if !result.recommendations.is_empty() {
+            println!();
+        }
+        super::plan_baseline::print_human(baseline);
+    }
 }
 
 /// Print explain as JSON with schema versioning
@@ -599,22 +620,12 @@ pub fn print_json(
 ) -> Result<()> {
     use crate::output::{schema, DiagnosticOutput, Severity};
 
-    // Determine severity from issues
-    let severity = result
-        .issues
-        .iter()
-        .map(|i| match i.severity {
-            IssueSeverity::Critical => 3,
-            IssueSeverity::Warning => 2,
-            IssueSeverity::Info => 1,
-        })
-        .max()
-        .map(|s| match s {
-            3 => Severity::Critical,
-            2 => Severity::Warning,
-            _ => Severity::Healthy,
-        })
-        .unwrap_or(Severity::Healthy);
+    // Determine severity from issues and baseline changes
+    let severity = match result.max_severity() {
+        Some(IssueSeverity::Critical) => Severity::Critical,
+        Some(IssueSeverity::Warning) => Severity::Warning,
+        _ => Severity::Healthy,
+    };
 
     let output = match timeouts {
         Some(t) => DiagnosticOutput::with_timeouts(schema::EXPLAIN, result, severity, t),
@@ -829,6 +840,7 @@ mod tests {
                 actual_loops: None,
             },
             actions: None,
+            baseline: None,
         }
     }
 
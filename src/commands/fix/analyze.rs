@@ -0,0 +1,255 @@
+//! Fix analyze command: Refresh planner statistics.
+//!
+//! Runs ANALYZE on one table, or with `--all-stale` on every table
+//! `dba stats-age` reports as stale (never analyzed, heavily modified since
+//! the last ANALYZE, or analyzed too long ago), several tables per statement.
+//! ANALYZE takes a SHARE UPDATE EXCLUSIVE lock, which blocks neither reads
+//! nor writes.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::common::{
+    print_fix_result, ActionGates, ActionType, FixResult, Risk, StructuredAction, VerifyStep,
+};
+use crate::commands::stats_age::{run_stats_age, StatsStatus, TableStatsAge};
+use crate::sql::quote_ident;
+
+/// Tables per ANALYZE statement with --all-stale
+pub const DEFAULT_BATCH_SIZE: usize = 10;
+
+/// Result of an analyze fix
+#[derive(Debug, Serialize)]
+pub struct AnalyzeFixResult {
+    #[serde(flatten)]
+    pub fix: FixResult,
+    /// Stale tables found (empty when a single table was named)
+    pub tables: Vec<TableStatsAge>,
+    pub batch_size: usize,
+}
+
+/// Generate one ANALYZE statement covering `tables`
+pub fn generate_analyze_sql(tables: &[(&str, &str)]) -> String {
+    let names: Vec<String> = tables
+        .iter()
+        .map(|(schema, table)| format!("{}.{}", quote_ident(schema), quote_ident(table)))
+        .collect();
+    format!("ANALYZE {};", names.join(", "))
+}
+
+/// Tables with statistics `dba stats-age` flags as warning or critical
+pub async fn stale_tables(client: &Client) -> Result<Vec<TableStatsAge>> {
+    let result = run_stats_age(client, None, usize::MAX).await?;
+    Ok(result
+        .tables
+        .into_iter()
+        .filter(|t| t.status != StatsStatus::Healthy)
+        .collect())
+}
+
+/// ANALYZE one table, or every stale table when `table` is None
+pub async fn execute_analyze(
+    client: &Client,
+    table: Option<(&str, &str)>,
+    batch_size: usize,
+    dry_run: bool,
+) -> Result<AnalyzeFixResult> {
+    if batch_size == 0 {
+        bail!("--batch-size must be at least 1");
+    }
+
+    let (tables, sql) = match table {
+        Some((schema, name)) => {
+            let query = r#"
+                SELECT 1
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('r', 'p', 'm')
+            "#;
+            client
+                .query_opt(query, &[&schema, &name])
+                .await
+                .context("Failed to query table")?
+                .ok_or_else(|| anyhow::anyhow!("Table {}.{} not found", schema, name))?;
+            (Vec::new(), vec![generate_analyze_sql(&[(schema, name)])])
+        }
+        None => {
+            let tables = stale_tables(client).await?;
+            let sql = tables
+                .chunks(batch_size)
+                .map(|batch| {
+                    let names: Vec<(&str, &str)> = batch
+                        .iter()
+                        .map(|t| (t.schema.as_str(), t.table.as_str()))
+                        .collect();
+                    generate_analyze_sql(&names)
+                })
+                .collect();
+            (tables, sql)
+        }
+    };
+
+    let target = match table {
+        Some((schema, name)) => format!("{}.{}", schema, name),
+        None => format!("{} stale table(s) in {} batch(es)", tables.len(), sql.len()),
+    };
+
+    let mut fix = FixResult {
+        executed: !dry_run,
+        success: true,
+        sql,
+        summary: String::new(),
+        error: None,
+        verification: None,
+    };
+
+    if fix.sql.is_empty() {
+        fix.executed = true;
+        fix.summary = "No stale tables to analyze".to_string();
+    } else if dry_run {
+        fix.summary = format!("Would ANALYZE {}", target);
+    } else {
+        // Keep going past a failed batch; one dropped table shouldn't leave
+        // the rest unanalyzed
+        let mut errors = Vec::new();
+        for stmt in &fix.sql {
+            if let Err(e) = client.batch_execute(stmt).await {
+                errors.push(format!("{}: {}", stmt, e));
+            }
+        }
+        if errors.is_empty() {
+            fix.summary = format!("Analyzed {}", target);
+        } else {
+            fix.success = false;
+            fix.summary = format!(
+                "{} of {} ANALYZE statement(s) failed",
+                errors.len(),
+                fix.sql.len()
+            );
+            fix.error = Some(errors.join("\n"));
+        }
+    }
+
+    Ok(AnalyzeFixResult {
+        fix,
+        tables,
+        batch_size,
+    })
+}
+
+/// Get verification steps for analyze.
+pub fn get_verify_steps(table: Option<&str>) -> Vec<VerifyStep> {
+    let (description, expected) = match table {
+        Some(table) => (
+            format!("Verify {} no longer has stale statistics", table),
+            format!("$.data.tables[?(@.table=='{}')] == null", table),
+        ),
+        None => (
+            "Verify no tables have stale statistics".to_string(),
+            "$.data.stale_count == 0".to_string(),
+        ),
+    };
+    vec![VerifyStep {
+        description,
+        command: "pgcrate dba stats-age --json".to_string(),
+        expected,
+    }]
+}
+
+/// Create a structured action analyzing every stale table
+pub fn create_analyze_action(
+    tables: &[TableStatsAge],
+    read_write: bool,
+    is_primary: bool,
+    confirmed: bool,
+) -> StructuredAction {
+    let sql: Vec<String> = tables
+        .chunks(DEFAULT_BATCH_SIZE)
+        .map(|batch| {
+            let names: Vec<(&str, &str)> = batch
+                .iter()
+                .map(|t| (t.schema.as_str(), t.table.as_str()))
+                .collect();
+            generate_analyze_sql(&names)
+        })
+        .collect();
+
+    StructuredAction::builder("fix.analyze.all_stale", ActionType::Fix)
+        .command("pgcrate")
+        .args(vec![
+            "fix".to_string(),
+            "analyze".to_string(),
+            "--all-stale".to_string(),
+        ])
+        .description(format!(
+            "ANALYZE {} table(s) with stale statistics",
+            tables.len()
+        ))
+        .mutates(true)
+        .risk(Risk::Low)
+        .gates(ActionGates::write_primary())
+        .sql_preview(sql)
+        .evidence(serde_json::to_value(tables).unwrap_or_default())
+        .verify(get_verify_steps(None))
+        .build(read_write, is_primary, confirmed)
+}
+
+/// Print fix result in human-readable format
+pub fn print_human(result: &AnalyzeFixResult, quiet: bool) {
+    if !quiet && !result.tables.is_empty() {
+        println!("STALE TABLES:");
+        println!();
+        for t in &result.tables {
+            let why = match t.days_since_analyze {
+                None => "never analyzed".to_string(),
+                Some(days) => format!("{:.0}% modified, {:.1} days old", t.mod_pct, days),
+            };
+            println!("  {} {}.{} ({})", t.status.emoji(), t.schema, t.table, why);
+        }
+        println!();
+    }
+    print_fix_result(
+        &result.fix,
+        quiet,
+        Some("ANALYZE does not block reads or writes."),
+    );
+}
+
+/// Print fix result as JSON
+pub fn print_json(
+    result: &AnalyzeFixResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{DiagnosticOutput, Severity};
+
+    let severity = if result.fix.success {
+        Severity::Healthy
+    } else {
+        Severity::Error
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts("pgcrate.fix.analyze", result, severity, t),
+        None => DiagnosticOutput::new("pgcrate.fix.analyze", result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_analyze_sql() {
+        assert_eq!(
+            generate_analyze_sql(&[("public", "orders")]),
+            "ANALYZE \"public\".\"orders\";"
+        );
+        assert_eq!(
+            generate_analyze_sql(&[("app", "order items"), ("public", "users")]),
+            "ANALYZE \"app\".\"order items\", \"public\".\"users\";"
+        );
+    }
+}
@@ -0,0 +1,580 @@
+//! Apply command: Execute an approved subset of a structured fix plan.
+//!
+//! `dba triage --include-fixes --json` emits StructuredActions; saved to a
+//! file, that output is a fix plan. `dba apply --plan FILE` runs the plan's
+//! actions one at a time by invoking the action's own pgcrate command, so
+//! each fix keeps its own safety checks, dry-run output, and SQL. Execution
+//! stops at the first failure and the remaining actions are reported as
+//! skipped. Actions that need explicit confirmation only run when named with
+//! `--only`; which ones do is decided from what the action runs, never from
+//! the `risk` or `gates` recorded in the plan file.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+use super::common::{VerificationResult, VerifyStep};
+use super::verify::run_verification;
+
+/// One action read from a plan file
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlanAction {
+    pub action_id: String,
+    #[serde(default)]
+    pub action_type: String,
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub sql_preview: Option<Vec<String>>,
+    #[serde(default)]
+    pub verify: Option<Vec<VerifyStep>>,
+}
+
+impl PlanAction {
+    /// Arguments after `fix` for fix actions (`fix ...` or `dba fix ...`)
+    fn fix_args(&self) -> Option<&[String]> {
+        match self.args.as_slice() {
+            [first, rest @ ..] if first == "fix" => Some(rest),
+            [first, second, rest @ ..] if first == "dba" && second == "fix" => Some(rest),
+            _ => None,
+        }
+    }
+
+    /// Whether this action must be approved by name
+    pub fn needs_approval(&self) -> bool {
+        match self.fix_args() {
+            // Non-blocking fixes; vacuum only when it is not FULL
+            Some([fix, rest @ ..]) => match fix.as_str() {
+                "analyze" | "sequence" => false,
+                "vacuum" => rest.iter().any(|a| a == "--full"),
+                _ => true,
+            },
+            // Anything else runs SQL taken verbatim from the plan file
+            _ => true,
+        }
+    }
+
+    /// Arguments for running this action as a pgcrate subcommand, or None
+    /// when the action has no SQL to run
+    pub fn command_args(&self, dry_run: bool) -> Option<Vec<String>> {
+        if let Some(fix_args) = self.fix_args() {
+            let mut args = Vec::with_capacity(fix_args.len() + 3);
+            args.extend(["dba".to_string(), "fix".to_string()]);
+            args.extend(fix_args.iter().cloned());
+            args.push(if dry_run { "--dry-run" } else { "--yes" }.to_string());
+            return Some(args);
+        }
+        // SQL actions run their previewed statements; only fixes have any
+        if !self.is_fix_type() {
+            return None;
+        }
+        let sql = self.sql_preview.as_ref().filter(|s| !s.is_empty())?;
+        Some(vec![
+            "sql".to_string(),
+            "--allow-write".to_string(),
+            "-c".to_string(),
+            sql.join("\n"),
+        ])
+    }
+
+    fn is_sql(&self) -> bool {
+        self.fix_args().is_none()
+    }
+
+    fn is_fix_type(&self) -> bool {
+        self.action_type == "fix"
+    }
+}
+
+/// Outcome of one action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApplyStatus {
+    /// Dry run succeeded
+    Planned,
+    Applied,
+    Failed,
+    Skipped,
+}
+
+impl ApplyStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            ApplyStatus::Planned => "PLANNED",
+            ApplyStatus::Applied => "APPLIED",
+            ApplyStatus::Failed => "FAILED",
+            ApplyStatus::Skipped => "SKIPPED",
+        }
+    }
+}
+
+/// Result of one action
+#[derive(Debug, Clone, Serialize)]
+pub struct ApplyActionResult {
+    pub action_id: String,
+    pub description: String,
+    pub status: ApplyStatus,
+    pub sql: Vec<String>,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<VerificationResult>,
+}
+
+/// Result of applying a plan
+#[derive(Debug, Serialize)]
+pub struct ApplyResult {
+    pub plan: String,
+    pub dry_run: bool,
+    pub success: bool,
+    pub actions: Vec<ApplyActionResult>,
+    pub planned: usize,
+    pub applied: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+/// Parse a plan: a `dba triage --include-fixes --json` envelope, an object
+/// with an `actions` array, or a bare array of actions
+pub fn parse_plan(content: &str) -> Result<Vec<PlanAction>> {
+    let value: serde_json::Value =
+        serde_json::from_str(content).context("Plan file is not valid JSON")?;
+    let actions = if value.is_array() {
+        value
+    } else if let Some(actions) = value.pointer("/data/actions") {
+        actions.clone()
+    } else if let Some(actions) = value.get("actions") {
+        actions.clone()
+    } else {
+        bail!("Plan file has no actions (expected `dba triage --include-fixes --json` output)");
+    };
+    if actions.is_null() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_value(actions).context("Plan file has malformed actions")
+}
+
+/// Split the plan into actions to run and actions skipped with a reason
+pub fn select_actions<'a>(
+    plan: &'a [PlanAction],
+    only: &[String],
+) -> Result<Vec<(&'a PlanAction, Option<String>)>> {
+    let unknown: Vec<&str> = only
+        .iter()
+        .filter(|id| !plan.iter().any(|a| &a.action_id == *id))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        bail!("Unknown action id(s) in --only: {}", unknown.join(", "));
+    }
+
+    Ok(plan
+        .iter()
+        .map(|action| {
+            let reason = if !only.is_empty() {
+                (!only.contains(&action.action_id)).then(|| "Not selected by --only".to_string())
+            } else if action.needs_approval() {
+                Some("Requires confirmation; name it with --only to run".to_string())
+            } else {
+                None
+            };
+            (action, reason)
+        })
+        .collect())
+}
+
+/// Run one action as a pgcrate subprocess
+fn run_action(
+    action: &PlanAction,
+    database_url: &str,
+    global_args: &[String],
+    dry_run: bool,
+) -> ApplyActionResult {
+    let mut result = ApplyActionResult {
+        action_id: action.action_id.clone(),
+        description: action.description.clone(),
+        status: ApplyStatus::Failed,
+        sql: action.sql_preview.clone().unwrap_or_default(),
+        summary: String::new(),
+        error: None,
+        verification: None,
+    };
+
+    if action.is_sql() && !action.is_fix_type() {
+        result.summary = format!(
+            "Refusing to run SQL for a non-fix action (action_type: {:?})",
+            action.action_type
+        );
+        return result;
+    }
+
+    let Some(args) = action.command_args(dry_run) else {
+        result.summary = "Action has no command or SQL to run".to_string();
+        return result;
+    };
+
+    // SQL actions have no dry-run of their own; the preview is the plan
+    if dry_run && action.is_sql() {
+        result.status = ApplyStatus::Planned;
+        result.summary = format!("Would run {} SQL statement(s)", result.sql.len());
+        return result;
+    }
+
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            result.summary = "Failed to locate the pgcrate executable".to_string();
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+    // The URL goes through the environment to keep passwords out of argv
+    let output = match Command::new(exe)
+        .env("DATABASE_URL", database_url)
+        .args(global_args)
+        .arg("--json")
+        .args(&args)
+        .output()
+    {
+        Ok(o) => o,
+        Err(e) => {
+            result.summary = "Failed to run action".to_string();
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = match serde_json::from_str(&stdout) {
+        Ok(v) => v,
+        Err(_) => {
+            result.summary = "Action produced no JSON output".to_string();
+            result.error = Some(String::from_utf8_lossy(&output.stderr).trim().to_string());
+            return result;
+        }
+    };
+
+    if json.get("ok").and_then(|v| v.as_bool()) == Some(false) {
+        result.summary = "Action failed".to_string();
+        result.error = json
+            .pointer("/errors/0/message")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        return result;
+    }
+
+    let success = match json.get("data") {
+        // Fix commands report a FixResult
+        Some(data) => {
+            if let Some(sql) = data.get("sql").and_then(|v| v.as_array()) {
+                result.sql = sql
+                    .iter()
+                    .filter_map(|s| s.as_str().map(String::from))
+                    .collect();
+            }
+            result.summary = data
+                .get("summary")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string();
+            result.error = data.get("error").and_then(|v| v.as_str()).map(String::from);
+            data.get("success").and_then(|v| v.as_bool()) == Some(true)
+        }
+        None => {
+            result.summary = format!("Ran {} SQL statement(s)", result.sql.len());
+            output.status.success()
+        }
+    };
+
+    result.status = match (success, dry_run) {
+        (false, _) => ApplyStatus::Failed,
+        (true, true) => ApplyStatus::Planned,
+        (true, false) => ApplyStatus::Applied,
+    };
+    result
+}
+
+/// Apply the selected plan actions in order
+pub fn execute_apply(
+    plan_path: &Path,
+    only: &[String],
+    database_url: &str,
+    global_args: &[String],
+    dry_run: bool,
+    verify: bool,
+) -> Result<ApplyResult> {
+    let content = std::fs::read_to_string(plan_path)
+        .with_context(|| format!("Failed to read plan file {}", plan_path.display()))?;
+    let plan = parse_plan(&content)?;
+    let selected = select_actions(&plan, only)?;
+
+    let mut actions = Vec::with_capacity(selected.len());
+    let mut halted = false;
+    for (action, skip_reason) in selected {
+        let skip_reason = skip_reason
+            .or_else(|| halted.then(|| "Not run because an earlier action failed".to_string()));
+        if let Some(reason) = skip_reason {
+            actions.push(ApplyActionResult {
+                action_id: action.action_id.clone(),
+                description: action.description.clone(),
+                status: ApplyStatus::Skipped,
+                sql: action.sql_preview.clone().unwrap_or_default(),
+                summary: reason,
+                error: None,
+                verification: None,
+            });
+            continue;
+        }
+
+        let mut result = run_action(action, database_url, global_args, dry_run);
+        if verify && result.status == ApplyStatus::Applied {
+            if let Some(steps) = action.verify.as_ref().filter(|s| !s.is_empty()) {
+                let verification = run_verification(steps);
+                if !verification.passed {
+                    result.status = ApplyStatus::Failed;
+                    result.error = Some("Verification failed".to_string());
+                }
+                result.verification = Some(verification);
+            }
+        }
+        // Dry runs change nothing, so keep previewing past a failure
+        if result.status == ApplyStatus::Failed && !dry_run {
+            halted = true;
+        }
+        actions.push(result);
+    }
+
+    let count = |status| actions.iter().filter(|a| a.status == status).count();
+    let planned = count(ApplyStatus::Planned);
+    let applied = count(ApplyStatus::Applied);
+    let failed = count(ApplyStatus::Failed);
+    let skipped = count(ApplyStatus::Skipped);
+
+    Ok(ApplyResult {
+        plan: plan_path.display().to_string(),
+        dry_run,
+        success: failed == 0,
+        actions,
+        planned,
+        applied,
+        failed,
+        skipped,
+    })
+}
+
+/// Print apply result in human-readable format
+pub fn print_human(result: &ApplyResult, quiet: bool) {
+    if quiet {
+        for action in result
+            .actions
+            .iter()
+            .filter(|a| a.status == ApplyStatus::Failed)
+        {
+            eprintln!(
+                "Error: {}: {}",
+                action.action_id,
+                action.error.as_deref().unwrap_or(&action.summary)
+            );
+        }
+        return;
+    }
+
+    let mode = if result.dry_run { " (dry run)" } else { "" };
+    println!("APPLY PLAN: {}{}", result.plan, mode);
+    println!();
+
+    if result.actions.is_empty() {
+        println!("No actions in plan.");
+        return;
+    }
+
+    for action in &result.actions {
+        println!("  {} {}", action.status.label(), action.action_id);
+        if !action.description.is_empty() {
+            println!("    {}", action.description);
+        }
+        if !action.summary.is_empty() {
+            println!("    {}", action.summary);
+        }
+        if action.status != ApplyStatus::Skipped {
+            for sql in &action.sql {
+                println!("    SQL: {}", sql);
+            }
+        }
+        if let Some(err) = &action.error {
+            println!("    Error: {}", err);
+        }
+        if let Some(verification) = &action.verification {
+            for step in &verification.steps {
+                let status = if step.passed { "✓" } else { "✗" };
+                println!("    {} {}", status, step.description);
+            }
+        }
+        println!();
+    }
+
+    println!(
+        "Summary: {} planned, {} applied, {} failed, {} skipped",
+        result.planned, result.applied, result.failed, result.skipped
+    );
+    if result.dry_run && result.planned > 0 {
+        println!("To execute, add --yes flag.");
+    }
+}
+
+/// Print apply result as JSON
+pub fn print_json(
+    result: &ApplyResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{DiagnosticOutput, Severity};
+
+    let severity = if result.success {
+        Severity::Healthy
+    } else {
+        Severity::Error
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts("pgcrate.fix.apply", result, severity, t),
+        None => DiagnosticOutput::new("pgcrate.fix.apply", result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = r#"{
+        "ok": true,
+        "data": {
+            "checks": [],
+            "actions": [
+                {
+                    "action_id": "fix.analyze.all_stale",
+                    "action_type": "fix",
+                    "command": "pgcrate",
+                    "args": ["fix", "analyze", "--all-stale"],
+                    "description": "ANALYZE 1 table(s) with stale statistics",
+                    "available": false,
+                    "mutates": true,
+                    "risk": "low",
+                    "gates": {"requires_write": true, "requires_primary": true}
+                },
+                {
+                    "action_id": "fix.bloat.reindex.public.idx",
+                    "action_type": "fix",
+                    "command": "pgcrate",
+                    "args": ["dba", "fix", "bloat", "public.idx"],
+                    "description": "REINDEX CONCURRENTLY",
+                    "available": false,
+                    "mutates": true,
+                    "risk": "high",
+                    "gates": {"requires_write": true, "requires_primary": true, "requires_confirmation": true}
+                },
+                {
+                    "action_id": "indexes.fk.create.public.orders.user_id",
+                    "action_type": "fix",
+                    "command": "pgcrate",
+                    "args": ["sql", "--allow-write", "-c", "CREATE INDEX CONCURRENTLY i ON orders (user_id);"],
+                    "available": false,
+                    "mutates": true,
+                    "risk": "low",
+                    "gates": {},
+                    "sql_preview": ["CREATE INDEX CONCURRENTLY i ON orders (user_id);"]
+                }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_plan_formats() {
+        let plan = parse_plan(PLAN).unwrap();
+        assert_eq!(plan.len(), 3);
+        assert!(plan[1].needs_approval());
+
+        let actions = serde_json::json!({"actions": [{
+            "action_id": "a", "args": ["fix", "analyze", "public.t"], "risk": "medium"
+        }]});
+        assert_eq!(parse_plan(&actions.to_string()).unwrap().len(), 1);
+        let bare = actions["actions"].to_string();
+        assert_eq!(parse_plan(&bare).unwrap()[0].action_id, "a");
+
+        assert!(parse_plan(r#"{"ok": true, "data": {"actions": null}}"#)
+            .unwrap()
+            .is_empty());
+        assert!(parse_plan(r#"{"ok": true}"#).is_err());
+    }
+
+    #[test]
+    fn test_select_actions() {
+        let plan = parse_plan(PLAN).unwrap();
+
+        let all = select_actions(&plan, &[]).unwrap();
+        assert!(all[0].1.is_none());
+        assert!(all[1].1.is_some(), "reindex needs approval");
+        assert!(all[2].1.is_some(), "SQL from the plan needs approval");
+
+        let only = select_actions(&plan, &["fix.bloat.reindex.public.idx".to_string()]).unwrap();
+        assert!(only[0].1.is_some());
+        assert!(only[1].1.is_none());
+
+        let err = select_actions(&plan, &["nope".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn test_approval_ignores_declared_risk() {
+        let action = |args: &[&str]| {
+            let plan = serde_json::json!([{
+                "action_id": "a", "action_type": "fix", "args": args,
+                "risk": "low", "gates": {}
+            }]);
+            parse_plan(&plan.to_string()).unwrap().remove(0)
+        };
+        assert!(!action(&["fix", "analyze", "public.t"]).needs_approval());
+        assert!(!action(&["dba", "fix", "vacuum", "public.t"]).needs_approval());
+        assert!(action(&["fix", "vacuum", "public.t", "--full"]).needs_approval());
+        assert!(action(&["dba", "fix", "prepared", "--rollback", "tx"]).needs_approval());
+        assert!(action(&["dba", "locks", "--kill", "1"]).needs_approval());
+    }
+
+    #[test]
+    fn test_command_args_refuses_non_fix_sql() {
+        let plan = serde_json::json!([
+            {"action_id": "a", "action_type": "diagnostic", "args": ["sql"],
+             "sql_preview": ["DROP TABLE users"]},
+            {"action_id": "b", "action_type": "fix", "args": ["dba", "locks", "--kill", "1"]}
+        ]);
+        let plan = parse_plan(&plan.to_string()).unwrap();
+        assert!(plan[0].command_args(false).is_none());
+        assert!(plan[1].command_args(false).is_none());
+    }
+
+    #[test]
+    fn test_command_args() {
+        let plan = parse_plan(PLAN).unwrap();
+        assert_eq!(
+            plan[0].command_args(true).unwrap(),
+            vec!["dba", "fix", "analyze", "--all-stale", "--dry-run"]
+        );
+        assert_eq!(
+            plan[1].command_args(false).unwrap(),
+            vec!["dba", "fix", "bloat", "public.idx", "--yes"]
+        );
+        assert_eq!(
+            plan[2].command_args(false).unwrap(),
+            vec![
+                "sql",
+                "--allow-write",
+                "-c",
+                "CREATE INDEX CONCURRENTLY i ON orders (user_id);"
+            ]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Fix autovacuum command: Set per-table autovacuum scale factor.
+//!
+//! Applies the `ALTER TABLE ... SET (autovacuum_vacuum_scale_factor = ...)`
+//! that `dba autovacuum` proposes, or an explicit scale factor and threshold.
+//! Changing reloptions takes a SHARE UPDATE EXCLUSIVE lock, which does not
+//! block reads or writes.
+
+use anyhow::{bail, Result};
+use tokio_postgres::Client;
+
+use super::common::{print_fix_result, FixResult, VerifyStep};
+use crate::commands::autovacuum::{alter_table_sql, run_autovacuum};
+
+/// Execute an autovacuum settings change; without `scale_factor`, use the
+/// advisor's recommendation for the table
+pub async fn execute_tune(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    scale_factor: Option<f64>,
+    threshold: Option<i64>,
+    dry_run: bool,
+) -> Result<FixResult> {
+    let result = run_autovacuum(client, Some((schema, table)), 1).await?;
+    let Some(current) = result.tables.into_iter().next() else {
+        bail!("Table {}.{} not found", schema, table);
+    };
+
+    let scale_factor = match (scale_factor, &current.recommendation) {
+        (Some(scale_factor), _) => scale_factor,
+        (None, Some(rec)) => rec.scale_factor,
+        (None, None) => bail!(
+            "No autovacuum change recommended for {}.{} (scale factor {}). Use --scale-factor to set one.",
+            schema,
+            table,
+            current.scale_factor
+        ),
+    };
+    if !(0.0..=100.0).contains(&scale_factor) {
+        bail!(
+            "Scale factor must be between 0 and 100, got {}",
+            scale_factor
+        );
+    }
+
+    let sql = alter_table_sql(schema, table, scale_factor, threshold);
+    let change = format!(
+        "autovacuum_vacuum_scale_factor {} -> {}",
+        current.scale_factor, scale_factor
+    );
+
+    if dry_run {
+        return Ok(FixResult {
+            executed: false,
+            success: true,
+            sql: vec![sql],
+            summary: format!("Would set {} on {}.{}", change, schema, table),
+            error: None,
+            verification: None,
+        });
+    }
+
+    match client.batch_execute(&sql).await {
+        Ok(_) => Ok(FixResult {
+            executed: true,
+            success: true,
+            sql: vec![sql],
+            summary: format!("Set {} on {}.{}", change, schema, table),
+            error: None,
+            verification: None,
+        }),
+        Err(e) => Ok(FixResult {
+            executed: true,
+            success: false,
+            sql: vec![sql],
+            summary: format!(
+                "Failed to change autovacuum settings on {}.{}",
+                schema, table
+            ),
+            error: Some(e.to_string()),
+            verification: None,
+        }),
+    }
+}
+
+/// Get verification steps for an autovacuum settings change.
+pub fn get_verify_steps(schema: &str, table: &str) -> Vec<VerifyStep> {
+    vec![VerifyStep {
+        description: format!(
+            "Verify {}.{} has per-table autovacuum settings",
+            schema, table
+        ),
+        command: format!("pgcrate dba autovacuum --table {}.{} --json", schema, table),
+        expected: "$.data.tables[0].per_table_settings == true".to_string(),
+    }]
+}
+
+/// Print fix result in human-readable format
+pub fn print_human(result: &FixResult, quiet: bool) {
+    print_fix_result(result, quiet, None);
+}
+
+/// Print fix result as JSON
+pub fn print_json(
+    result: &FixResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{DiagnosticOutput, Severity};
+
+    let severity = if result.success {
+        Severity::Healthy
+    } else {
+        Severity::Error
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts("pgcrate.fix.autovacuum", result, severity, t),
+        None => DiagnosticOutput::new("pgcrate.fix.autovacuum", result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
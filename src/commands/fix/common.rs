@@ -2,7 +2,7 @@
 //!
 //! These types match the action.schema.json contract for structured actions.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Action type classification
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -13,7 +13,7 @@ pub enum ActionType {
 }
 
 /// Risk level for an action
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Risk {
     /// Low risk (non-blocking, reversible)
@@ -25,7 +25,8 @@ pub enum Risk {
 }
 
 /// Gates that must be satisfied before an action can execute
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ActionGates {
     /// Requires --read-write flag (not in read-only mode)
     #[serde(skip_serializing_if = "std::ops::Not::not")]
@@ -57,7 +58,7 @@ impl ActionGates {
 }
 
 /// A verification step to run after a fix
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerifyStep {
     /// Human-readable description
     pub description: String,
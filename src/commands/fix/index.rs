@@ -1,8 +1,12 @@
-//! Fix index command: Safely drop unused/duplicate indexes.
+//! Fix index command: Safely drop unused/duplicate indexes, or create missing ones.
 //!
 //! Indexes that are never used waste disk space and slow down writes.
 //! This command provides safe index dropping with comprehensive evidence
-//! and safety checks.
+//! and safety checks. For tables `dba indexes` flags as missing an index,
+//! `--create` builds one with CREATE INDEX CONCURRENTLY; a failed concurrent
+//! build leaves an INVALID index behind, which is detected and dropped.
+//! `--dedupe` drops the duplicate and prefix-covered indexes `dba indexes`
+//! reports, keeping primary key and constraint-backed indexes.
 
 use anyhow::{bail, Context, Result};
 use serde::Serialize;
@@ -11,6 +15,9 @@ use tokio_postgres::Client;
 use super::common::{
     print_fix_result, ActionGates, ActionType, FixResult, Risk, StructuredAction, VerifyStep,
 };
+use crate::commands::indexes::{
+    dedupe_plan, get_duplicate_indexes, get_redundant_indexes, DedupeCandidate,
+};
 use crate::sql::quote_ident;
 
 /// Evidence for index drop action
@@ -224,6 +231,310 @@ pub async fn execute_drop(
     }
 }
 
+/// Result of consolidating duplicate and redundant indexes
+#[derive(Debug, Serialize)]
+pub struct DedupeFixResult {
+    #[serde(flatten)]
+    pub fix: FixResult,
+    /// Every proposed drop; entries with `blocked_by` are kept
+    pub plan: Vec<DedupeCandidate>,
+    /// Estimated space reclaimed by the droppable entries
+    pub reclaimable_bytes: i64,
+    pub reclaimable_size: String,
+}
+
+/// Drop duplicate and redundant indexes, keeping those the drop safety
+/// checks reject
+pub async fn execute_dedupe(client: &Client, dry_run: bool) -> Result<DedupeFixResult> {
+    let duplicates = get_duplicate_indexes(client).await?;
+    let redundant = get_redundant_indexes(client).await?;
+    let mut plan = dedupe_plan(&duplicates, &redundant);
+
+    // Same checks as a single --drop (replica identity isn't in the plan)
+    for candidate in plan.iter_mut().filter(|c| c.blocked_by.is_none()) {
+        let evidence = get_index_info(client, &candidate.schema, &candidate.index).await?;
+        if let Some(reason) = check_safety(&evidence).reason {
+            candidate.blocked_by = Some(reason);
+        }
+    }
+
+    let droppable: Vec<&DedupeCandidate> = plan.iter().filter(|c| c.blocked_by.is_none()).collect();
+    let kept = plan.len() - droppable.len();
+    let reclaimable_bytes: i64 = droppable.iter().map(|c| c.size_bytes).sum();
+    let reclaimable_size = format_bytes(reclaimable_bytes);
+    let mut sql: Vec<String> = droppable
+        .iter()
+        .map(|c| generate_drop_sql(&c.schema, &c.index, true))
+        .collect();
+    let kept_note = if kept > 0 {
+        format!("; keeping {} constraint-backed index(es)", kept)
+    } else {
+        String::new()
+    };
+
+    let mut fix = FixResult {
+        executed: !dry_run,
+        success: true,
+        sql: sql.clone(),
+        summary: String::new(),
+        error: None,
+        verification: None,
+    };
+
+    if droppable.is_empty() {
+        fix.executed = true;
+        fix.summary = format!("No duplicate or redundant indexes to drop{}", kept_note);
+    } else if dry_run {
+        fix.summary = format!(
+            "Would drop {} duplicate/redundant index(es), reclaiming ~{}{}",
+            droppable.len(),
+            reclaimable_size,
+            kept_note
+        );
+    } else {
+        // Note: DROP INDEX CONCURRENTLY cannot run in a transaction, so stop
+        // at the first failure and report what already ran
+        let mut dropped = 0;
+        for stmt in &sql {
+            if let Err(e) = client.batch_execute(stmt).await {
+                fix.success = false;
+                fix.error = Some(format!("{}: {}", stmt, e));
+                break;
+            }
+            dropped += 1;
+        }
+        sql.truncate(dropped + usize::from(!fix.success));
+        fix.sql = sql;
+        fix.summary = if fix.success {
+            format!(
+                "Dropped {} duplicate/redundant index(es), reclaiming ~{}{}",
+                dropped, reclaimable_size, kept_note
+            )
+        } else {
+            format!(
+                "Dropped {} of {} duplicate/redundant index(es) before a failure",
+                dropped,
+                droppable.len()
+            )
+        };
+    }
+
+    Ok(DedupeFixResult {
+        fix,
+        plan,
+        reclaimable_bytes,
+        reclaimable_size,
+    })
+}
+
+/// Format bytes for human display
+fn format_bytes(bytes: i64) -> String {
+    if bytes >= 1_073_741_824 {
+        format!("{:.1} GB", bytes as f64 / 1_073_741_824.0)
+    } else if bytes >= 1_048_576 {
+        format!("{:.1} MB", bytes as f64 / 1_048_576.0)
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Get verification steps for index consolidation.
+pub fn get_dedupe_verify_steps() -> Vec<VerifyStep> {
+    vec![VerifyStep {
+        description: "Verify no droppable duplicate indexes remain".to_string(),
+        command: "pgcrate dba fix index --dedupe --dry-run --json".to_string(),
+        expected: "$.data.reclaimable_bytes == 0".to_string(),
+    }]
+}
+
+/// Default name for a new index; cut to Postgres's 63-byte identifier limit
+pub fn default_index_name(table: &str, columns: &[String]) -> String {
+    let mut name = format!("idx_{}_{}", table, columns.join("_"));
+    while name.len() > 63 {
+        name.pop();
+    }
+    name
+}
+
+/// Generate SQL for creating an index without blocking writes
+pub fn generate_create_sql(schema: &str, table: &str, name: &str, columns: &[String]) -> String {
+    let cols: Vec<String> = columns.iter().map(|c| quote_ident(c)).collect();
+    format!(
+        "CREATE INDEX CONCURRENTLY {} ON {}.{} ({});",
+        quote_ident(name),
+        quote_ident(schema),
+        quote_ident(table),
+        cols.join(", ")
+    )
+}
+
+/// Whether `schema.name` is an index, and if so whether it is valid
+async fn index_validity(client: &Client, schema: &str, name: &str) -> Result<Option<bool>> {
+    let query = r#"
+        SELECT ix.indisvalid
+        FROM pg_class i
+        JOIN pg_index ix ON ix.indexrelid = i.oid
+        JOIN pg_namespace n ON n.oid = i.relnamespace
+        WHERE n.nspname = $1 AND i.relname = $2
+    "#;
+    Ok(client
+        .query_opt(query, &[&schema, &name])
+        .await
+        .context("Failed to query index")?
+        .map(|row| row.get(0)))
+}
+
+/// Execute index creation
+pub async fn execute_create(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    columns: &[String],
+    name: Option<&str>,
+    dry_run: bool,
+) -> Result<FixResult> {
+    let table_query = r#"
+        SELECT
+            COALESCE(s.seq_scan, 0) AS seq_scan,
+            pg_size_pretty(pg_total_relation_size(c.oid)) AS table_size,
+            ARRAY(
+                SELECT a.attname::text FROM pg_attribute a
+                WHERE a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+            ) AS columns
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_stat_user_tables s ON s.relid = c.oid
+        WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('r', 'p', 'm')
+    "#;
+    let row = client
+        .query_opt(table_query, &[&schema, &table])
+        .await
+        .context("Failed to query table")?
+        .ok_or_else(|| anyhow::anyhow!("Table {}.{} not found", schema, table))?;
+    let seq_scan: i64 = row.get("seq_scan");
+    let table_size: String = row.get("table_size");
+    let existing: Vec<String> = row.get("columns");
+
+    let unknown: Vec<&str> = columns
+        .iter()
+        .filter(|c| !existing.contains(c))
+        .map(|c| c.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        bail!(
+            "Column(s) {} not found on {}.{}",
+            unknown.join(", "),
+            schema,
+            table
+        );
+    }
+
+    let name = name
+        .map(str::to_string)
+        .unwrap_or_else(|| default_index_name(table, columns));
+
+    // A leftover INVALID index from an earlier failed build is dropped first
+    let mut sql = Vec::new();
+    let mut replaces_invalid = false;
+    match index_validity(client, schema, &name).await? {
+        Some(true) => bail!("Index {}.{} already exists", schema, name),
+        Some(false) => {
+            replaces_invalid = true;
+            sql.push(generate_drop_sql(schema, &name, true));
+        }
+        None => {}
+    }
+    sql.push(generate_create_sql(schema, table, &name, columns));
+
+    let target = format!(
+        "{}.{} on {}.{} ({})",
+        schema,
+        name,
+        schema,
+        table,
+        columns.join(", ")
+    );
+
+    if dry_run {
+        let mut summary = format!(
+            "Would create index {} ({}, {} seq scans)",
+            target, table_size, seq_scan
+        );
+        if replaces_invalid {
+            summary.push_str(" after dropping the INVALID index of the same name");
+        }
+        return Ok(FixResult {
+            executed: false,
+            success: true,
+            sql,
+            summary,
+            error: None,
+            verification: None,
+        });
+    }
+
+    // Note: CREATE/DROP INDEX CONCURRENTLY cannot run in a transaction
+    for stmt in sql.clone() {
+        if let Err(e) = client.batch_execute(&stmt).await {
+            let mut summary = format!("Failed to create index {}", target);
+            if index_validity(client, schema, &name).await? == Some(false) {
+                let cleanup = generate_drop_sql(schema, &name, true);
+                match client.batch_execute(&cleanup).await {
+                    Ok(_) => summary.push_str("; dropped the INVALID index it left behind"),
+                    Err(_) => summary.push_str(&format!(
+                        "; INVALID index {}.{} remains, drop it with: {}",
+                        schema, name, cleanup
+                    )),
+                }
+                sql.push(cleanup);
+            }
+            return Ok(FixResult {
+                executed: true,
+                success: false,
+                sql,
+                summary,
+                error: Some(e.to_string()),
+                verification: None,
+            });
+        }
+    }
+
+    if index_validity(client, schema, &name).await? != Some(true) {
+        return Ok(FixResult {
+            executed: true,
+            success: false,
+            sql,
+            summary: format!("Index {} was built but is not valid", target),
+            error: None,
+            verification: None,
+        });
+    }
+
+    Ok(FixResult {
+        executed: true,
+        success: true,
+        sql,
+        summary: format!(
+            "Created index {}; idx_scan starts at 0, check `pgcrate dba indexes` once queries use it",
+            target
+        ),
+        error: None,
+        verification: None,
+    })
+}
+
+/// Get verification steps for index creation: the index must have been
+/// scanned, so it no longer shows up as unused.
+pub fn get_create_verify_steps(index_name: &str) -> Vec<VerifyStep> {
+    vec![VerifyStep {
+        description: format!("Verify index {} has idx_scan > 0", index_name),
+        command: "pgcrate dba indexes --json".to_string(),
+        expected: format!("$.data.unused[?(@.index=='{}')] == null", index_name),
+    }]
+}
+
 /// Get verification steps for index drop.
 pub fn get_verify_steps(index_name: &str) -> Vec<VerifyStep> {
     vec![VerifyStep {
@@ -282,13 +593,65 @@ pub fn create_drop_action(
 
 /// Print fix result in human-readable format
 pub fn print_human(result: &FixResult, quiet: bool) {
+    let note = if result.sql.iter().any(|s| s.starts_with("CREATE")) {
+        "Note: Uses CREATE INDEX CONCURRENTLY to avoid blocking writes."
+    } else {
+        "Note: Uses DROP INDEX CONCURRENTLY to avoid blocking."
+    };
+    print_fix_result(result, quiet, Some(note));
+}
+
+/// Print dedupe result in human-readable format
+pub fn print_dedupe_human(result: &DedupeFixResult, quiet: bool) {
+    if !quiet && !result.plan.is_empty() {
+        println!("INDEX CONSOLIDATION PLAN:");
+        println!();
+        for c in &result.plan {
+            let action = if c.blocked_by.is_some() {
+                "KEEP"
+            } else {
+                "DROP"
+            };
+            println!(
+                "  {} {}.{} {:>10}  -- {}",
+                action, c.schema, c.index, c.size, c.reason
+            );
+            if let Some(reason) = &c.blocked_by {
+                println!("       {}", reason);
+            }
+        }
+        println!();
+        println!("  Estimated reclaimable: {}", result.reclaimable_size);
+        println!();
+    }
     print_fix_result(
-        result,
+        &result.fix,
         quiet,
         Some("Note: Uses DROP INDEX CONCURRENTLY to avoid blocking."),
     );
 }
 
+/// Print dedupe result as JSON
+pub fn print_dedupe_json(
+    result: &DedupeFixResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{DiagnosticOutput, Severity};
+
+    let severity = if result.fix.success {
+        Severity::Healthy
+    } else {
+        Severity::Error
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts("pgcrate.fix.index", result, severity, t),
+        None => DiagnosticOutput::new("pgcrate.fix.index", result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
 /// Print fix result as JSON
 pub fn print_json(
     result: &FixResult,
@@ -373,6 +736,19 @@ mod tests {
         assert_eq!(sql, "DROP INDEX CONCURRENTLY \"public\".\"idx_test\";");
     }
 
+    #[test]
+    fn test_generate_create_sql() {
+        let columns = vec!["status".to_string(), "created_at".to_string()];
+        let name = default_index_name("orders", &columns);
+        assert_eq!(name, "idx_orders_status_created_at");
+        assert_eq!(
+            generate_create_sql("public", "orders", &name, &columns),
+            "CREATE INDEX CONCURRENTLY \"idx_orders_status_created_at\" ON \"public\".\"orders\" (\"status\", \"created_at\");"
+        );
+        let long = vec!["a".repeat(40), "b".repeat(40)];
+        assert_eq!(default_index_name("orders", &long).len(), 63);
+    }
+
     #[test]
     fn test_generate_drop_sql_blocking() {
         let sql = generate_drop_sql("public", "idx_test", false);
@@ -3,12 +3,18 @@
 //! Fix commands are separate from diagnostic commands because they mutate state.
 //! They follow a diagnose → fix → verify workflow with proper gating.
 
+pub mod analyze;
+pub mod apply;
+pub mod autovacuum;
 pub mod bloat;
 pub mod common;
 pub mod index;
+pub mod partition;
+pub mod prepared;
 pub mod sequence;
 pub mod vacuum;
 pub mod verify;
+pub mod xid;
 
 // Re-export StructuredAction for triage --include-fixes
 pub use common::StructuredAction;
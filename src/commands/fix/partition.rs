@@ -0,0 +1,241 @@
+//! Fix partition command: Routine maintenance for time-range partitioned tables.
+//!
+//! Creates the next partitions that `dba partitions` reports as missing and,
+//! with a retention window, detaches (and optionally drops) partitions that
+//! ended before it. This replaces the cron psql scripts teams use for manual
+//! partition management. All statements run in one transaction; creating or
+//! detaching a partition takes an ACCESS EXCLUSIVE lock on the parent.
+
+use anyhow::{bail, Result};
+use tokio_postgres::Client;
+
+use super::common::{print_fix_result, FixResult, VerifyStep};
+use crate::commands::partitions::{parse_range_bound, run_partitions, DEFAULT_SKEW_RATIO};
+use crate::config::AlertIgnore;
+use crate::sql::quote_ident;
+
+/// Partitions of `schema.table` that ended more than `retain` intervals ago
+async fn expired_partitions(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    key_type: &str,
+    interval: &str,
+    retain: i64,
+) -> Result<Vec<(String, String)>> {
+    let children_query = r#"
+        SELECT n.nspname, c.relname, pg_get_expr(c.relpartbound, c.oid) AS bound
+        FROM pg_inherits i
+        JOIN pg_class c ON c.oid = i.inhrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_class p ON p.oid = i.inhparent
+        JOIN pg_namespace pn ON pn.oid = p.relnamespace
+        WHERE pn.nspname = $1 AND p.relname = $2
+    "#;
+
+    let mut schemas = Vec::new();
+    let mut names = Vec::new();
+    let mut uppers = Vec::new();
+    for row in client.query(children_query, &[&schema, &table]).await? {
+        let bound: String = row.get("bound");
+        if let Some((_, upper)) = parse_range_bound(&bound) {
+            if upper != "MAXVALUE" {
+                schemas.push(row.get::<_, String>("nspname"));
+                names.push(row.get::<_, String>("relname"));
+                uppers.push(upper);
+            }
+        }
+    }
+
+    let query = format!(
+        r#"
+        SELECT s, n
+        FROM unnest($1::text[], $2::text[], $3::text[]) AS u(s, n, hi)
+        WHERE hi::{ty}::timestamptz <= now() - $4::text::interval * $5::int8
+        ORDER BY hi::{ty}
+        "#,
+        ty = key_type
+    );
+    let rows = client
+        .query(&query, &[&schemas, &names, &uppers, &interval, &retain])
+        .await?;
+    Ok(rows.iter().map(|r| (r.get(0), r.get(1))).collect())
+}
+
+/// Create missing partitions and apply the retention window
+pub async fn execute_maintenance(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    create_ahead: i64,
+    retain: Option<i64>,
+    drop: bool,
+    dry_run: bool,
+) -> Result<FixResult> {
+    if create_ahead < 0 {
+        bail!("--create-ahead must be 0 or more, got {}", create_ahead);
+    }
+    if retain.is_some_and(|r| r < 1) {
+        bail!("--retain must be at least 1");
+    }
+
+    let result = run_partitions(
+        client,
+        Some((schema, table)),
+        create_ahead,
+        DEFAULT_SKEW_RATIO,
+        &AlertIgnore::default(),
+    )
+    .await?;
+    let Some(current) = result.tables.into_iter().next() else {
+        bail!("Table {}.{} is not a partitioned table", schema, table);
+    };
+    let (Some(coverage), Some(key_type)) = (current.coverage, current.key_type) else {
+        bail!(
+            "{}.{} is not RANGE partitioned on a single date/timestamp column",
+            schema,
+            table
+        );
+    };
+
+    let mut sql: Vec<String> = coverage.missing.iter().map(|m| m.sql.clone()).collect();
+    let created = sql.len();
+
+    let expired = match retain {
+        Some(retain) => {
+            expired_partitions(client, schema, table, &key_type, &coverage.interval, retain).await?
+        }
+        None => Vec::new(),
+    };
+    for (child_schema, child) in &expired {
+        sql.push(format!(
+            "ALTER TABLE {}.{} DETACH PARTITION {}.{};",
+            quote_ident(schema),
+            quote_ident(table),
+            quote_ident(child_schema),
+            quote_ident(child)
+        ));
+        if drop {
+            sql.push(format!(
+                "DROP TABLE {}.{};",
+                quote_ident(child_schema),
+                quote_ident(child)
+            ));
+        }
+    }
+
+    let mut changes = vec![format!("create {} partition(s)", created)];
+    if retain.is_some() {
+        changes.push(format!(
+            "{} {} expired partition(s)",
+            if drop { "detach and drop" } else { "detach" },
+            expired.len()
+        ));
+    }
+    let changes = changes.join(", ");
+
+    if sql.is_empty() {
+        return Ok(FixResult {
+            executed: true,
+            success: true,
+            sql,
+            summary: format!(
+                "No partition changes needed for {}.{} ({} future partition(s), interval {})",
+                schema, table, coverage.future_partitions, coverage.interval
+            ),
+            error: None,
+            verification: None,
+        });
+    }
+
+    if dry_run {
+        return Ok(FixResult {
+            executed: false,
+            success: true,
+            sql,
+            summary: format!("Would {} on {}.{}", changes, schema, table),
+            error: None,
+            verification: None,
+        });
+    }
+
+    // One transaction, so a failure (e.g. default partition rows overlapping
+    // a new range) leaves the table as it was
+    client.batch_execute("BEGIN").await?;
+    let mut failure = None;
+    for stmt in &sql {
+        if let Err(e) = client.batch_execute(stmt).await {
+            failure = Some(format!("{}: {}", stmt, e));
+            break;
+        }
+    }
+    if let Some(error) = failure {
+        client.batch_execute("ROLLBACK").await?;
+        return Ok(FixResult {
+            executed: true,
+            success: false,
+            sql,
+            summary: format!(
+                "Failed to maintain partitions of {}.{}; rolled back",
+                schema, table
+            ),
+            error: Some(error),
+            verification: None,
+        });
+    }
+    client.batch_execute("COMMIT").await?;
+
+    Ok(FixResult {
+        executed: true,
+        success: true,
+        sql,
+        summary: format!("Did {} on {}.{}", changes, schema, table),
+        error: None,
+        verification: None,
+    })
+}
+
+/// Get verification steps for partition maintenance.
+pub fn get_verify_steps(schema: &str, table: &str, create_ahead: i64) -> Vec<VerifyStep> {
+    vec![VerifyStep {
+        description: format!(
+            "Verify {}.{} has {} future partition(s)",
+            schema, table, create_ahead
+        ),
+        command: format!(
+            "pgcrate dba partitions --table {}.{} --ahead {} --json",
+            schema, table, create_ahead
+        ),
+        expected: "$.data.tables[0].coverage.missing.length() == 0".to_string(),
+    }]
+}
+
+/// Print fix result in human-readable format
+pub fn print_human(result: &FixResult, quiet: bool) {
+    print_fix_result(
+        result,
+        quiet,
+        Some("Statements run in one transaction and lock the parent table briefly."),
+    );
+}
+
+/// Print fix result as JSON
+pub fn print_json(
+    result: &FixResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{DiagnosticOutput, Severity};
+
+    let severity = if result.success {
+        Severity::Healthy
+    } else {
+        Severity::Error
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts("pgcrate.fix.partition", result, severity, t),
+        None => DiagnosticOutput::new("pgcrate.fix.partition", result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//! Fix prepared command: Roll back an orphaned prepared transaction.
+//!
+//! ROLLBACK PREPARED discards the transaction's work for good and releases
+//! its locks and xmin. It must run in the database the transaction was
+//! prepared in, by its owner or a superuser, so the fix checks the database
+//! first rather than failing halfway.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::common::{
+    print_fix_result, ActionGates, ActionType, FixResult, Risk, StructuredAction, VerifyStep,
+};
+use crate::commands::prepared::{get_prepared_xacts, PreparedXact, DEFAULT_THRESHOLD};
+
+/// Result of a prepared transaction fix
+#[derive(Debug, Serialize)]
+pub struct PreparedFixResult {
+    #[serde(flatten)]
+    pub fix: FixResult,
+    pub transaction: PreparedXact,
+}
+
+/// Generate SQL rolling back a prepared transaction
+pub fn generate_rollback_sql(gid: &str) -> String {
+    format!("ROLLBACK PREPARED '{}';", gid.replace('\'', "''"))
+}
+
+/// Roll back the prepared transaction `gid`
+pub async fn execute_rollback(
+    client: &Client,
+    gid: &str,
+    dry_run: bool,
+) -> Result<PreparedFixResult> {
+    let transaction = get_prepared_xacts(client, DEFAULT_THRESHOLD.as_secs_f64())
+        .await
+        .context("Failed to query pg_prepared_xacts")?
+        .into_iter()
+        .find(|t| t.gid == gid)
+        .ok_or_else(|| anyhow::anyhow!("Prepared transaction '{}' not found", gid))?;
+
+    let current: String = client
+        .query_one("SELECT current_database()::text", &[])
+        .await?
+        .get(0);
+    if transaction.database != current {
+        bail!(
+            "Prepared transaction '{}' belongs to database '{}', not '{}'. \
+             ROLLBACK PREPARED must run in the same database; connect to '{}' and retry.",
+            gid,
+            transaction.database,
+            current,
+            transaction.database
+        );
+    }
+
+    let sql = generate_rollback_sql(gid);
+    let mut fix = FixResult {
+        executed: !dry_run,
+        success: true,
+        sql: vec![sql.clone()],
+        summary: String::new(),
+        error: None,
+        verification: None,
+    };
+
+    if dry_run {
+        fix.summary = format!(
+            "Would roll back prepared transaction '{}' (owner {}, prepared {})",
+            gid, transaction.owner, transaction.prepared_at
+        );
+    } else {
+        match client.batch_execute(&sql).await {
+            Ok(()) => {
+                fix.summary = format!("Rolled back prepared transaction '{}'", gid);
+            }
+            Err(e) => {
+                fix.success = false;
+                fix.summary = format!("Failed to roll back prepared transaction '{}'", gid);
+                fix.error = Some(e.to_string());
+            }
+        }
+    }
+
+    Ok(PreparedFixResult { fix, transaction })
+}
+
+/// Get verification steps for a rollback
+pub fn get_verify_steps(gid: &str) -> Vec<VerifyStep> {
+    vec![VerifyStep {
+        description: format!("Verify prepared transaction '{}' is gone", gid),
+        command: "pgcrate dba prepared --json".to_string(),
+        expected: format!("$.data.transactions[?(@.gid=='{}')] == null", gid),
+    }]
+}
+
+/// Create a structured action rolling back one prepared transaction
+pub fn create_rollback_action(
+    transaction: &PreparedXact,
+    read_write: bool,
+    is_primary: bool,
+    confirmed: bool,
+) -> StructuredAction {
+    StructuredAction::builder(
+        format!("fix.prepared.rollback.{}", transaction.gid),
+        ActionType::Fix,
+    )
+    .command("pgcrate")
+    .args(vec![
+        "dba".to_string(),
+        "fix".to_string(),
+        "prepared".to_string(),
+        "--rollback".to_string(),
+        transaction.gid.clone(),
+    ])
+    .description(format!(
+        "Roll back prepared transaction '{}' in {} (discards its work)",
+        transaction.gid, transaction.database
+    ))
+    .mutates(true)
+    .risk(Risk::High)
+    .gates(ActionGates::write_primary_confirm())
+    .sql_preview(vec![generate_rollback_sql(&transaction.gid)])
+    .evidence(serde_json::to_value(transaction).unwrap_or_default())
+    .verify(get_verify_steps(&transaction.gid))
+    .build(read_write, is_primary, confirmed)
+}
+
+/// Print fix result in human-readable format
+pub fn print_human(result: &PreparedFixResult, quiet: bool) {
+    print_fix_result(
+        &result.fix,
+        quiet,
+        Some("ROLLBACK PREPARED cannot be undone; the transaction's changes are discarded."),
+    );
+}
+
+/// Print fix result as JSON
+pub fn print_json(
+    result: &PreparedFixResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{DiagnosticOutput, Severity};
+
+    let severity = if result.fix.success {
+        Severity::Healthy
+    } else {
+        Severity::Error
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts("pgcrate.fix.prepared", result, severity, t),
+        None => DiagnosticOutput::new("pgcrate.fix.prepared", result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_rollback_sql() {
+        assert_eq!(generate_rollback_sql("tx-42"), "ROLLBACK PREPARED 'tx-42';");
+        assert_eq!(generate_rollback_sql("it's"), "ROLLBACK PREPARED 'it''s';");
+    }
+}
@@ -286,7 +286,11 @@ pub fn create_vacuum_action(
 
 /// Print fix result in human-readable format
 pub fn print_human(result: &FixResult, quiet: bool) {
-    print_fix_result(result, quiet, None);
+    print_fix_result(
+        result,
+        quiet,
+        Some("Watch a long run from another session with: pgcrate dba vacuum --progress"),
+    );
 }
 
 /// Print fix result as JSON
@@ -0,0 +1,428 @@
+//! Fix xid command: Emergency freeze plan for tables nearing wraparound.
+//!
+//! Builds a list of `VACUUM (FREEZE, VERBOSE)` statements for the tables with
+//! the oldest relfrozenxid (counting their TOAST tables), oldest first, and
+//! with --yes runs them one at a time. A maintenance window stops new work
+//! once it has elapsed and caps the running VACUUM through statement_timeout;
+//! tables that did not fit are reported as skipped. While a VACUUM runs,
+//! progress from pg_stat_progress_vacuum is printed to stderr.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::common::{print_fix_result, FixResult, VerifyStep};
+use crate::sql::quote_ident;
+
+/// Default number of tables in the plan
+pub const DEFAULT_LIMIT: usize = 10;
+
+/// How often pg_stat_progress_vacuum is polled
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Outcome of one freeze step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreezeOutcome {
+    /// Not run (dry run)
+    Planned,
+    Frozen,
+    Failed,
+    /// Not run to completion because the maintenance window ran out
+    Skipped,
+}
+
+/// One table in the freeze plan
+#[derive(Debug, Clone, Serialize)]
+pub struct FreezeStep {
+    pub schema: String,
+    pub table: String,
+    pub xid_age: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xid_age_after: Option<i64>,
+    pub size_bytes: i64,
+    pub size: String,
+    pub sql: String,
+    pub outcome: FreezeOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Freeze plan plus the common fix result
+#[derive(Debug, Clone, Serialize)]
+pub struct XidFixResult {
+    #[serde(flatten)]
+    pub fix: FixResult,
+    /// Minimum table XID age included in the plan
+    pub min_age: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_secs: Option<u64>,
+    pub plan: Vec<FreezeStep>,
+}
+
+/// Generate SQL for freezing a table
+pub fn generate_freeze_sql(schema: &str, table: &str) -> String {
+    format!(
+        "VACUUM (FREEZE, VERBOSE) {}.{};",
+        quote_ident(schema),
+        quote_ident(table)
+    )
+}
+
+/// Tables at or above `min_age` (default: autovacuum_freeze_max_age), oldest first
+async fn get_plan(
+    client: &Client,
+    min_age: Option<i64>,
+    limit: usize,
+) -> Result<(i64, Vec<FreezeStep>)> {
+    let min_age = match min_age {
+        Some(age) => age,
+        None => client
+            .query_one(
+                "SELECT current_setting('autovacuum_freeze_max_age')::int8",
+                &[],
+            )
+            .await?
+            .get(0),
+    };
+
+    let query = r#"
+        SELECT
+            n.nspname,
+            c.relname,
+            GREATEST(age(c.relfrozenxid), COALESCE(age(t.relfrozenxid), 0))::int8 AS xid_age,
+            pg_total_relation_size(c.oid) AS size_bytes,
+            pg_size_pretty(pg_total_relation_size(c.oid)) AS size
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_class t ON t.oid = c.reltoastrelid
+        WHERE c.relkind IN ('r', 'm')
+          AND c.relpersistence <> 't'
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+          AND n.nspname NOT LIKE 'pg_toast%'
+          AND GREATEST(age(c.relfrozenxid), COALESCE(age(t.relfrozenxid), 0)) >= $1::int8
+        ORDER BY xid_age DESC, size_bytes
+        LIMIT $2
+    "#;
+
+    let rows = client
+        .query(query, &[&min_age, &(limit as i64)])
+        .await
+        .context("Failed to query table XID ages")?;
+
+    let plan = rows
+        .iter()
+        .map(|row| {
+            let schema: String = row.get("nspname");
+            let table: String = row.get("relname");
+            FreezeStep {
+                sql: generate_freeze_sql(&schema, &table),
+                schema,
+                table,
+                xid_age: row.get("xid_age"),
+                xid_age_after: None,
+                size_bytes: row.get("size_bytes"),
+                size: row.get("size"),
+                outcome: FreezeOutcome::Planned,
+                duration_secs: None,
+                error: None,
+            }
+        })
+        .collect();
+
+    Ok((min_age, plan))
+}
+
+/// Run one VACUUM, printing pg_stat_progress_vacuum for it until it finishes
+async fn vacuum_with_progress(
+    client: &Client,
+    monitor: Option<&Client>,
+    pid: i32,
+    step: &FreezeStep,
+    label: &str,
+) -> std::result::Result<(), tokio_postgres::Error> {
+    let progress_query = r#"
+        SELECT
+            phase,
+            heap_blks_total,
+            heap_blks_scanned,
+            CASE WHEN heap_blks_total > 0
+                 THEN round(100.0 * heap_blks_scanned / heap_blks_total, 1)::float8
+                 ELSE 0::float8 END AS pct_scanned
+        FROM pg_stat_progress_vacuum
+        WHERE pid = $1
+    "#;
+
+    let vacuum = client.batch_execute(&step.sql);
+    tokio::pin!(vacuum);
+    let mut tick = tokio::time::interval(PROGRESS_INTERVAL);
+    tick.tick().await;
+
+    loop {
+        tokio::select! {
+            result = &mut vacuum => return result,
+            _ = tick.tick() => {
+                let Some(monitor) = monitor else { continue };
+                if let Ok(Some(row)) = monitor.query_opt(progress_query, &[&pid]).await {
+                    let phase: String = row.get("phase");
+                    let total: i64 = row.get("heap_blks_total");
+                    let scanned: i64 = row.get("heap_blks_scanned");
+                    let pct: f64 = row.get("pct_scanned");
+                    eprintln!(
+                        "pgcrate: {} {}.{}: {} {:.1}% ({}/{} blocks)",
+                        label, step.schema, step.table, phase, pct, scanned, total
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Build the freeze plan and, unless `dry_run`, run it within `window`
+pub async fn execute_freeze(
+    client: &Client,
+    database_url: &str,
+    min_age: Option<i64>,
+    limit: usize,
+    window: Option<Duration>,
+    dry_run: bool,
+    show_progress: bool,
+) -> Result<XidFixResult> {
+    let (min_age, mut plan) = get_plan(client, min_age, limit).await?;
+    let sql: Vec<String> = plan.iter().map(|s| s.sql.clone()).collect();
+    let window_secs = window.map(|w| w.as_secs());
+
+    if plan.is_empty() {
+        return Ok(XidFixResult {
+            fix: FixResult {
+                executed: true,
+                success: true,
+                sql,
+                summary: format!("No tables with XID age >= {}", min_age),
+                error: None,
+                verification: None,
+            },
+            min_age,
+            window_secs,
+            plan,
+        });
+    }
+
+    if dry_run {
+        return Ok(XidFixResult {
+            fix: FixResult {
+                executed: false,
+                success: true,
+                sql,
+                summary: format!(
+                    "Would freeze {} table(s), oldest XID age {}",
+                    plan.len(),
+                    plan[0].xid_age
+                ),
+                error: None,
+                verification: None,
+            },
+            min_age,
+            window_secs,
+            plan,
+        });
+    }
+
+    let pid: i32 = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await?
+        .get(0);
+    // Progress is best effort; a failed second connection only loses the output
+    let monitor = if show_progress {
+        crate::commands::connect(database_url).await.ok()
+    } else {
+        None
+    };
+
+    let started = Instant::now();
+    let total = plan.len();
+    for (i, step) in plan.iter_mut().enumerate() {
+        // VACUUM can run for hours, so the diagnostic statement_timeout does
+        // not apply; the window (if any) bounds it instead
+        let timeout_ms = match window {
+            Some(window) => {
+                let remaining = window.saturating_sub(started.elapsed());
+                if remaining.is_zero() {
+                    step.outcome = FreezeOutcome::Skipped;
+                    continue;
+                }
+                remaining.as_millis().max(1)
+            }
+            None => 0,
+        };
+        client
+            .batch_execute(&format!("SET statement_timeout = '{}ms'", timeout_ms))
+            .await?;
+
+        let label = format!("[{}/{}]", i + 1, total);
+        if show_progress {
+            eprintln!("pgcrate: {} {}", label, step.sql);
+        }
+        let step_started = Instant::now();
+        let result = vacuum_with_progress(client, monitor.as_ref(), pid, step, &label).await;
+        step.duration_secs = Some((step_started.elapsed().as_secs_f64() * 10.0).round() / 10.0);
+
+        match result {
+            Ok(()) => {
+                step.outcome = FreezeOutcome::Frozen;
+                let row = client
+                    .query_opt(
+                        "SELECT GREATEST(age(c.relfrozenxid), COALESCE(age(t.relfrozenxid), 0))::int8
+                         FROM pg_class c
+                         JOIN pg_namespace n ON n.oid = c.relnamespace
+                         LEFT JOIN pg_class t ON t.oid = c.reltoastrelid
+                         WHERE n.nspname = $1 AND c.relname = $2",
+                        &[&step.schema, &step.table],
+                    )
+                    .await?;
+                step.xid_age_after = row.map(|r| r.get(0));
+            }
+            // statement_timeout fired: the window ended mid-VACUUM
+            Err(e)
+                if window.is_some()
+                    && e.code() == Some(&tokio_postgres::error::SqlState::QUERY_CANCELED) =>
+            {
+                step.outcome = FreezeOutcome::Skipped;
+                step.error = Some("maintenance window ended during VACUUM".to_string());
+            }
+            Err(e) => {
+                step.outcome = FreezeOutcome::Failed;
+                step.error = Some(e.to_string());
+            }
+        }
+    }
+
+    let frozen = plan
+        .iter()
+        .filter(|s| s.outcome == FreezeOutcome::Frozen)
+        .count();
+    let failed: Vec<&FreezeStep> = plan
+        .iter()
+        .filter(|s| s.outcome == FreezeOutcome::Failed)
+        .collect();
+    let skipped = plan
+        .iter()
+        .filter(|s| s.outcome == FreezeOutcome::Skipped)
+        .count();
+
+    let mut summary = format!("Froze {} of {} table(s)", frozen, total);
+    if !failed.is_empty() {
+        summary.push_str(&format!(", {} failed", failed.len()));
+    }
+    if skipped > 0 {
+        summary.push_str(&format!(", {} skipped (maintenance window ended)", skipped));
+    }
+
+    Ok(XidFixResult {
+        fix: FixResult {
+            executed: true,
+            success: failed.is_empty(),
+            sql,
+            summary,
+            error: failed.first().map(|s| {
+                format!(
+                    "{}.{}: {}",
+                    s.schema,
+                    s.table,
+                    s.error.as_deref().unwrap_or("")
+                )
+            }),
+            verification: None,
+        },
+        min_age,
+        window_secs,
+        plan,
+    })
+}
+
+/// Get verification steps for an emergency freeze.
+pub fn get_verify_steps() -> Vec<VerifyStep> {
+    vec![VerifyStep {
+        description: "Verify XID status is not critical".to_string(),
+        command: "pgcrate dba xid --json".to_string(),
+        expected: "$.data.overall_status != 'critical'".to_string(),
+    }]
+}
+
+/// Print fix result in human-readable format
+pub fn print_human(result: &XidFixResult, quiet: bool) {
+    if !quiet && !result.plan.is_empty() {
+        println!("FREEZE PLAN (XID age >= {}):", result.min_age);
+        println!();
+        println!(
+            "  {:3} {:40} {:>14} {:>14} {:>10}",
+            "#", "TABLE", "XID AGE", "AFTER", "SIZE"
+        );
+        println!("  {}", "-".repeat(85));
+        for (i, step) in result.plan.iter().enumerate() {
+            let after = match step.outcome {
+                FreezeOutcome::Planned => String::new(),
+                FreezeOutcome::Skipped => "skipped".to_string(),
+                FreezeOutcome::Failed => "failed".to_string(),
+                FreezeOutcome::Frozen => step
+                    .xid_age_after
+                    .map(|a| a.to_string())
+                    .unwrap_or_default(),
+            };
+            println!(
+                "  {:3} {:40} {:>14} {:>14} {:>10}",
+                i + 1,
+                format!("{}.{}", step.schema, step.table),
+                step.xid_age,
+                after,
+                step.size
+            );
+        }
+        println!();
+    }
+    let note = result.window_secs.map(|secs| {
+        format!(
+            "Maintenance window: {}s; tables that do not fit are skipped.",
+            secs
+        )
+    });
+    print_fix_result(&result.fix, quiet, note.as_deref());
+}
+
+/// Print fix result as JSON
+pub fn print_json(
+    result: &XidFixResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{DiagnosticOutput, Severity};
+
+    let severity = if result.fix.success {
+        Severity::Healthy
+    } else {
+        Severity::Error
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts("pgcrate.fix.xid", result, severity, t),
+        None => DiagnosticOutput::new("pgcrate.fix.xid", result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_freeze_sql_quotes_identifiers() {
+        assert_eq!(
+            generate_freeze_sql("public", "Order Items"),
+            "VACUUM (FREEZE, VERBOSE) \"public\".\"Order Items\";"
+        );
+    }
+}
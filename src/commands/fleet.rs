@@ -0,0 +1,237 @@
+//! Fleet triage: Run triage against many named connections at once.
+//!
+//! `dba triage --fleet PATTERN` selects connections from pgcrate.toml, either
+//! a `[fleets]` entry or a `*`/`?` glob over `[connections]` names, and
+//! triages each one concurrently on its own session. A target that cannot
+//! be resolved or reached is reported with its error and counts as
+//! critical, so an unreachable host is never mistaken for a healthy one.
+
+use anyhow::Result;
+use futures_util::{stream, StreamExt};
+use serde::Serialize;
+
+use super::triage::{run_triage, CheckStatus, TriageResults};
+use crate::config::{AlertsConfig, Config};
+use crate::connection;
+use crate::diagnostic::{DiagnosticSession, TimeoutConfig};
+
+/// Targets triaged at the same time
+const MAX_CONCURRENT: usize = 8;
+
+/// Triage outcome for one connection
+#[derive(Debug, Serialize)]
+pub struct FleetTarget {
+    /// Connection name from pgcrate.toml
+    pub name: String,
+    /// host:port/database (never includes credentials)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// Worst triage status, or None when triage could not run
+    pub status: Option<CheckStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub triage: Option<TriageResults>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Combined fleet results
+#[derive(Debug, Serialize)]
+pub struct FleetResult {
+    pub fleet: String,
+    /// Targets in connection name order
+    pub targets: Vec<FleetTarget>,
+    /// Worst status across targets; failed targets count as critical
+    pub overall_status: CheckStatus,
+    pub healthy: usize,
+    pub warning: usize,
+    pub critical: usize,
+    pub failed: usize,
+}
+
+/// Resolve, connect to, and triage one named connection
+async fn triage_target(
+    config: &Config,
+    name: String,
+    allow_primary: bool,
+    timeouts: &TimeoutConfig,
+    alerts: &AlertsConfig,
+) -> FleetTarget {
+    let mut target = FleetTarget {
+        name,
+        host: None,
+        status: None,
+        triage: None,
+        error: None,
+    };
+
+    let resolved = connection::resolve_and_validate(
+        config,
+        None,
+        Some(&target.name),
+        None,
+        allow_primary,
+        false,
+        true,
+    );
+    let conn_result = match resolved {
+        Ok(r) => r,
+        Err(e) => {
+            target.error = Some(format!("{:#}", e));
+            return target;
+        }
+    };
+    target.host = conn_result
+        .connection
+        .as_ref()
+        .map(|c| format!("{}:{}/{}", c.host, c.port, c.database));
+
+    match DiagnosticSession::connect(&conn_result.url, timeouts.clone()).await {
+        Ok(session) => {
+            let results = run_triage(session.client(), alerts).await;
+            target.status = Some(results.overall_status);
+            target.triage = Some(results);
+        }
+        Err(e) => target.error = Some(format!("{:#}", e)),
+    }
+    target
+}
+
+/// Triage every connection selected by `fleet`
+pub async fn run_fleet_triage(
+    config: &Config,
+    fleet: &str,
+    allow_primary: bool,
+    timeouts: &TimeoutConfig,
+    alerts: &AlertsConfig,
+) -> Result<FleetResult> {
+    let names = config.fleet_connections(fleet)?;
+
+    let targets: Vec<FleetTarget> = stream::iter(names)
+        .map(|name| triage_target(config, name, allow_primary, timeouts, alerts))
+        .buffered(MAX_CONCURRENT)
+        .collect()
+        .await;
+
+    let count = |status| targets.iter().filter(|t| t.status == Some(status)).count();
+    let healthy = count(CheckStatus::Healthy);
+    let warning = count(CheckStatus::Warning);
+    let critical = count(CheckStatus::Critical);
+    let failed = targets.iter().filter(|t| t.status.is_none()).count();
+
+    let overall_status = if critical > 0 || failed > 0 {
+        CheckStatus::Critical
+    } else if warning > 0 {
+        CheckStatus::Warning
+    } else {
+        CheckStatus::Healthy
+    };
+
+    Ok(FleetResult {
+        fleet: fleet.to_string(),
+        targets,
+        overall_status,
+        healthy,
+        warning,
+        critical,
+        failed,
+    })
+}
+
+/// Non-healthy checks for one target, as "LABEL: summary"
+fn findings(triage: &TriageResults) -> String {
+    triage
+        .checks
+        .iter()
+        .filter(|c| c.status != CheckStatus::Healthy)
+        .map(|c| format!("{}: {}", c.label, c.summary))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Print fleet results as one row per target
+pub fn print_human(result: &FleetResult, quiet: bool) {
+    let rows: Vec<(&str, &str, String, String)> = result
+        .targets
+        .iter()
+        .filter(|t| !quiet || t.status != Some(CheckStatus::Healthy))
+        .map(|t| {
+            let status = match t.status {
+                Some(CheckStatus::Healthy) => format!("{} healthy", CheckStatus::Healthy.emoji()),
+                Some(CheckStatus::Warning) => format!("{} WARNING", CheckStatus::Warning.emoji()),
+                Some(CheckStatus::Critical) => {
+                    format!("{} CRITICAL", CheckStatus::Critical.emoji())
+                }
+                None => "- ERROR".to_string(),
+            };
+            let detail = match (&t.triage, &t.error) {
+                (_, Some(err)) => err.lines().next().unwrap_or_default().to_string(),
+                (Some(triage), None) => findings(triage),
+                (None, None) => String::new(),
+            };
+            (
+                t.name.as_str(),
+                t.host.as_deref().unwrap_or("-"),
+                status,
+                detail,
+            )
+        })
+        .collect();
+
+    if !quiet {
+        println!(
+            "FLEET: {} ({} target(s))",
+            result.fleet,
+            result.targets.len()
+        );
+        println!();
+    }
+
+    let name_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(6);
+    let host_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(4);
+
+    if !quiet && !rows.is_empty() {
+        println!(
+            "{:nw$}  {:hw$}  {:12} FINDINGS",
+            "TARGET",
+            "HOST",
+            "STATUS",
+            nw = name_width,
+            hw = host_width
+        );
+    }
+    for (name, host, status, detail) in &rows {
+        println!(
+            "{:nw$}  {:hw$}  {:12} {}",
+            name,
+            host,
+            status,
+            detail,
+            nw = name_width,
+            hw = host_width
+        );
+    }
+
+    if !quiet {
+        println!();
+        println!(
+            "Summary: {} healthy, {} warning, {} critical, {} failed",
+            result.healthy, result.warning, result.critical, result.failed
+        );
+    }
+}
+
+/// Print fleet results as JSON
+pub fn print_json(
+    result: &FleetResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = Severity::from_check_status(&result.overall_status);
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::FLEET_TRIAGE, result, severity, t),
+        None => DiagnosticOutput::new(schema::FLEET_TRIAGE, result, severity),
+    };
+    output.with_partial(result.failed > 0).print()?;
+    Ok(())
+}
@@ -1,15 +1,21 @@
-//! Indexes command: Identify missing, unused, and duplicate indexes.
+//! Indexes command: Identify missing, unused, duplicate, and redundant indexes.
 //!
 //! Indexes are critical for query performance but come with costs:
 //! - Missing indexes cause slow sequential scans
 //! - Unused indexes waste space and slow writes
 //! - Duplicate indexes provide no benefit over their counterparts
+//! - Redundant indexes are a leading prefix of another index, which serves
+//!   the same lookups
 //! - Foreign keys without indexes cause slow DELETEs and JOINs
 
 use anyhow::Result;
 use serde::Serialize;
 use tokio_postgres::Client;
 
+use super::fix::common::{ActionGates, ActionType, Risk, StructuredAction};
+use crate::config::AlertIgnore;
+use crate::sql::quote_ident;
+
 /// Thresholds for index recommendations
 const MIN_SEQ_SCANS_FOR_MISSING: i64 = 1000;
 const MIN_TABLE_SIZE_BYTES: i64 = 10 * 1024 * 1024; // 10MB
@@ -41,6 +47,17 @@ impl FkIndexStatus {
         }
     }
 
+    /// Status from the referencing table's size, one level lower when the
+    /// parent has seen no deletes or updates (nothing makes Postgres scan
+    /// the child for matching rows)
+    pub fn from_activity(rows: i64, parent_writes: i64) -> Self {
+        match (Self::from_row_count(rows), parent_writes) {
+            (FkIndexStatus::Critical, 0) => FkIndexStatus::Warning,
+            (_, 0) => FkIndexStatus::Info,
+            (status, _) => status,
+        }
+    }
+
     pub fn emoji(&self) -> &'static str {
         match self {
             FkIndexStatus::Info => "ℹ",
@@ -62,7 +79,17 @@ pub struct FkWithoutIndex {
     pub ref_columns: Vec<String>,
     pub constraint_name: String,
     pub table_rows: i64,
+    /// Deletes and updates on the referenced table since stats reset; each
+    /// one looks up matching rows in this table
+    pub parent_deletes: i64,
+    pub parent_updates: i64,
+    /// ON DELETE action (no action, restrict, cascade, set null, set default)
+    pub on_delete: String,
+    /// Rows of this table scanned by those lookups without an index
+    pub estimated_rows_scanned: i64,
     pub status: FkIndexStatus,
+    /// Ready-to-run statement adding the index
+    pub sql: String,
 }
 
 /// A table that may need an index
@@ -125,6 +152,42 @@ pub struct DuplicateIndexInfo {
     pub is_unique: bool,
     pub is_primary: bool,
     pub idx_scan: i64,
+    /// Constraint(s) this index backs; it can't be dropped while they exist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backing_constraint: Option<String>,
+}
+
+/// A non-unique index whose key columns are a leading prefix of another
+/// index on the same table
+#[derive(Debug, Clone, Serialize)]
+pub struct RedundantIndex {
+    pub schema: String,
+    pub table: String,
+    pub index: String,
+    pub columns: String,
+    pub size: String,
+    pub size_bytes: i64,
+    pub idx_scan: i64,
+    /// The wider index that covers this one
+    pub covered_by: String,
+    pub covered_by_columns: String,
+}
+
+/// An index proposed for removal when consolidating duplicate and redundant
+/// indexes
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupeCandidate {
+    pub schema: String,
+    pub table: String,
+    pub index: String,
+    pub size: String,
+    pub size_bytes: i64,
+    /// Index that stays and serves the same lookups
+    pub keep: String,
+    pub reason: String,
+    /// Why the index can't be dropped (primary key or backing constraint)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked_by: Option<String>,
 }
 
 /// Full index analysis results
@@ -133,11 +196,17 @@ pub struct IndexesResult {
     pub missing: Vec<MissingIndexCandidate>,
     pub unused: Vec<UnusedIndex>,
     pub duplicates: Vec<DuplicateIndexSet>,
+    pub redundant: Vec<RedundantIndex>,
     pub fk_without_indexes: Vec<FkWithoutIndex>,
     pub total_unused_bytes: i64,
     pub total_unused_size: String,
     pub total_duplicate_bytes: i64,
     pub total_duplicate_size: String,
+    pub total_redundant_bytes: i64,
+    pub total_redundant_size: String,
+    /// Structured fix actions (when --include-actions is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actions: Option<Vec<StructuredAction>>,
 }
 
 /// Get tables that may benefit from indexes
@@ -272,6 +341,8 @@ pub async fn get_duplicate_indexes(client: &Client) -> Result<Vec<DuplicateIndex
                 pg_relation_size(i.oid) as index_size,
                 pg_size_pretty(pg_relation_size(i.oid)) as index_size_pretty,
                 COALESCE(s.idx_scan, 0) as idx_scan,
+                (SELECT string_agg(c.conname, ', ' ORDER BY c.conname)
+                 FROM pg_constraint c WHERE c.conindid = i.oid) as backing_constraint,
                 array_to_string(
                     array_agg(a.attname ORDER BY array_position(ix.indkey, a.attnum)),
                     ', '
@@ -308,7 +379,8 @@ pub async fn get_duplicate_indexes(client: &Client) -> Result<Vec<DuplicateIndex
             ic.index_size_pretty,
             ic.indisunique,
             ic.indisprimary,
-            ic.idx_scan
+            ic.idx_scan,
+            ic.backing_constraint
         FROM index_cols ic
         JOIN duplicates d ON ic.schema_name = d.schema_name
                          AND ic.table_name = d.table_name
@@ -336,6 +408,7 @@ pub async fn get_duplicate_indexes(client: &Client) -> Result<Vec<DuplicateIndex
             is_unique: row.get("indisunique"),
             is_primary: row.get("indisprimary"),
             idx_scan: row.get("idx_scan"),
+            backing_constraint: row.get("backing_constraint"),
         };
 
         sets.entry(key).or_default().push(info);
@@ -366,6 +439,169 @@ pub async fn get_duplicate_indexes(client: &Client) -> Result<Vec<DuplicateIndex
     Ok(results)
 }
 
+/// Get non-unique indexes covered by a wider index on the same table.
+///
+/// Index A is redundant when its key columns, with the same operator
+/// classes, are a strict leading prefix of index B's key columns and both use
+/// the same access method. Unique indexes are never redundant: they enforce a
+/// stricter constraint than the wider index. Partial and expression indexes
+/// are skipped.
+pub async fn get_redundant_indexes(client: &Client) -> Result<Vec<RedundantIndex>> {
+    let query = r#"
+        WITH idx AS (
+            SELECT
+                ix.indexrelid,
+                ix.indrelid,
+                n.nspname AS schema_name,
+                t.relname AS table_name,
+                i.relname AS index_name,
+                i.relam,
+                ix.indisunique,
+                ix.indisprimary,
+                (string_to_array(ix.indkey::text, ' '))[1:ix.indnkeyatts] AS keys,
+                (string_to_array(ix.indclass::text, ' '))[1:ix.indnkeyatts] AS opclasses,
+                pg_relation_size(i.oid) AS index_size,
+                COALESCE(s.idx_scan, 0) AS idx_scan
+            FROM pg_index ix
+            JOIN pg_class t ON t.oid = ix.indrelid
+            JOIN pg_class i ON i.oid = ix.indexrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            LEFT JOIN pg_stat_user_indexes s ON s.indexrelid = ix.indexrelid
+            WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+              AND ix.indisvalid
+              AND NOT ix.indisexclusion
+              AND NOT (0 = ANY(ix.indkey))  -- exclude expression indexes
+              AND ix.indpred IS NULL        -- exclude partial indexes
+        )
+        SELECT DISTINCT ON (a.indexrelid)
+            a.schema_name,
+            a.table_name,
+            a.index_name,
+            a.index_size,
+            pg_size_pretty(a.index_size) AS index_size_pretty,
+            a.idx_scan,
+            b.index_name AS covered_by,
+            (SELECT string_agg(att.attname, ', ' ORDER BY k.ord)
+             FROM unnest(a.keys::int2[]) WITH ORDINALITY AS k(attnum, ord)
+             JOIN pg_attribute att ON att.attrelid = a.indrelid AND att.attnum = k.attnum
+            ) AS columns,
+            (SELECT string_agg(att.attname, ', ' ORDER BY k.ord)
+             FROM unnest(b.keys::int2[]) WITH ORDINALITY AS k(attnum, ord)
+             JOIN pg_attribute att ON att.attrelid = b.indrelid AND att.attnum = k.attnum
+            ) AS covered_by_columns
+        FROM idx a
+        JOIN idx b ON b.indrelid = a.indrelid
+                  AND b.indexrelid <> a.indexrelid
+                  AND b.relam = a.relam
+                  AND array_length(b.keys, 1) > array_length(a.keys, 1)
+                  AND b.keys[1:array_length(a.keys, 1)] = a.keys
+                  AND b.opclasses[1:array_length(a.keys, 1)] = a.opclasses
+        WHERE NOT a.indisunique
+          AND NOT a.indisprimary
+        ORDER BY a.indexrelid, array_length(b.keys, 1), b.index_name
+    "#;
+
+    let rows = client.query(query, &[]).await?;
+
+    let mut results: Vec<RedundantIndex> = rows
+        .iter()
+        .map(|row| RedundantIndex {
+            schema: row.get("schema_name"),
+            table: row.get("table_name"),
+            index: row.get("index_name"),
+            columns: row.get("columns"),
+            size: row.get("index_size_pretty"),
+            size_bytes: row.get("index_size"),
+            idx_scan: row.get("idx_scan"),
+            covered_by: row.get("covered_by"),
+            covered_by_columns: row.get("covered_by_columns"),
+        })
+        .collect();
+
+    results.sort_by_key(|r| std::cmp::Reverse(r.size_bytes));
+
+    Ok(results)
+}
+
+/// Index to keep from a duplicate set: the primary key, then constraint-backed
+/// and unique indexes, then the most scanned; ties keep the largest (listed first)
+fn duplicate_keeper(set: &DuplicateIndexSet) -> Option<&DuplicateIndexInfo> {
+    set.indexes.iter().rev().max_by_key(|i| {
+        let priority = if i.is_primary {
+            3
+        } else if i.backing_constraint.is_some() {
+            2
+        } else if i.is_unique {
+            1
+        } else {
+            0
+        };
+        (priority, i.idx_scan)
+    })
+}
+
+/// Propose which duplicate and redundant indexes to drop.
+///
+/// Each duplicate set keeps one index; every other member is proposed for
+/// removal unless it is a primary key or backs a constraint, in which case it
+/// is listed with `blocked_by` set. Redundant indexes follow, skipping any
+/// already proposed as duplicates.
+pub fn dedupe_plan(
+    duplicates: &[DuplicateIndexSet],
+    redundant: &[RedundantIndex],
+) -> Vec<DedupeCandidate> {
+    let mut plan: Vec<DedupeCandidate> = Vec::new();
+
+    for set in duplicates {
+        let Some(keep) = duplicate_keeper(set) else {
+            continue;
+        };
+        for idx in set.indexes.iter().filter(|i| i.name != keep.name) {
+            let blocked_by = if idx.is_primary {
+                Some("primary key".to_string())
+            } else {
+                idx.backing_constraint
+                    .as_ref()
+                    .map(|c| format!("constraint {}", c))
+            };
+            plan.push(DedupeCandidate {
+                schema: set.schema.clone(),
+                table: set.table.clone(),
+                index: idx.name.clone(),
+                size: idx.size.clone(),
+                size_bytes: idx.size_bytes,
+                keep: keep.name.clone(),
+                reason: format!("duplicate of {} ({})", keep.name, set.columns),
+                blocked_by,
+            });
+        }
+    }
+
+    for r in redundant {
+        if plan
+            .iter()
+            .any(|p| p.schema == r.schema && p.index == r.index)
+        {
+            continue;
+        }
+        plan.push(DedupeCandidate {
+            schema: r.schema.clone(),
+            table: r.table.clone(),
+            index: r.index.clone(),
+            size: r.size.clone(),
+            size_bytes: r.size_bytes,
+            keep: r.covered_by.clone(),
+            reason: format!(
+                "({}) is a prefix of {} ({})",
+                r.columns, r.covered_by, r.covered_by_columns
+            ),
+            blocked_by: None,
+        });
+    }
+
+    plan
+}
+
 /// Get foreign keys that don't have supporting indexes.
 ///
 /// A FK needs an index on the referencing columns for:
@@ -373,12 +609,9 @@ pub async fn get_duplicate_indexes(client: &Client) -> Result<Vec<DuplicateIndex
 /// - Efficient JOINs on FK columns
 /// - Avoiding lock contention during cascading operations
 ///
-/// Handles composite FKs by checking if any index covers the FK columns as a prefix.
+/// An index supports the FK when its leading key columns are exactly the FK
+/// columns, in any order, and it is valid and not partial.
 pub async fn get_fk_without_indexes(client: &Client) -> Result<Vec<FkWithoutIndex>> {
-    // This query:
-    // 1. Gets all FK constraints with their columns (handles composite FKs)
-    // 2. Checks if any index covers those columns as a leading prefix
-    // 3. Returns only FKs where no suitable index exists
     let query = r#"
         WITH fk_info AS (
             SELECT
@@ -387,6 +620,9 @@ pub async fn get_fk_without_indexes(client: &Client) -> Result<Vec<FkWithoutInde
                 t.relname AS table_name,
                 nf.nspname AS ref_schema,
                 tf.relname AS ref_table,
+                c.conrelid,
+                c.confrelid,
+                c.confdeltype::text AS on_delete,
                 -- Get FK column names in order
                 array_agg(a.attname ORDER BY x.ordinality) AS fk_columns,
                 -- Get referenced column names in order
@@ -403,21 +639,8 @@ pub async fn get_fk_without_indexes(client: &Client) -> Result<Vec<FkWithoutInde
             JOIN pg_attribute af ON af.attrelid = c.confrelid AND af.attnum = x.ref_attnum
             WHERE c.contype = 'f'
               AND n.nspname NOT IN ('pg_catalog', 'information_schema')
-            GROUP BY c.conname, n.nspname, t.relname, nf.nspname, tf.relname, c.conkey
-        ),
-        indexed_fks AS (
-            -- Check if any index covers the FK columns as a leading prefix
-            SELECT DISTINCT
-                fk.constraint_name
-            FROM fk_info fk
-            JOIN pg_class t2 ON t2.relname = fk.table_name
-            JOIN pg_namespace n2 ON n2.oid = t2.relnamespace AND n2.nspname = fk.schema_name
-            JOIN pg_index ix ON ix.indrelid = t2.oid
-            WHERE
-                -- Check that FK columns are a prefix of index columns
-                -- Convert int2vector to array for comparison, then slice to FK length
-                (SELECT array_agg(unnest) FROM unnest(ix.indkey) LIMIT array_length(fk.fk_attnum_array, 1))
-                    = fk.fk_attnum_array
+            GROUP BY c.oid, c.conname, n.nspname, t.relname, nf.nspname, tf.relname,
+                     c.conrelid, c.confrelid, c.confdeltype, c.conkey
         )
         SELECT
             fk.schema_name,
@@ -427,11 +650,28 @@ pub async fn get_fk_without_indexes(client: &Client) -> Result<Vec<FkWithoutInde
             fk.ref_table,
             fk.ref_columns,
             fk.constraint_name,
-            COALESCE(s.n_live_tup, 0) AS table_rows
+            fk.on_delete,
+            COALESCE(s.n_live_tup, 0) AS table_rows,
+            COALESCE(ps.n_tup_del, 0) AS parent_deletes,
+            COALESCE(ps.n_tup_upd, 0) AS parent_updates
         FROM fk_info fk
-        LEFT JOIN indexed_fks ifk ON ifk.constraint_name = fk.constraint_name
-        LEFT JOIN pg_stat_user_tables s ON s.schemaname = fk.schema_name AND s.relname = fk.table_name
-        WHERE ifk.constraint_name IS NULL  -- Only show FKs without indexes
+        LEFT JOIN pg_stat_user_tables s ON s.relid = fk.conrelid
+        LEFT JOIN pg_stat_user_tables ps ON ps.relid = fk.confrelid
+        WHERE NOT EXISTS (
+            -- Leading key columns of a usable index, compared as sets
+            SELECT 1
+            FROM pg_index ix
+            CROSS JOIN LATERAL (
+                SELECT array_agg(k.attnum) AS leading
+                FROM unnest(ix.indkey) WITH ORDINALITY AS k(attnum, ord)
+                WHERE k.ord <= least(ix.indnkeyatts, array_length(fk.fk_attnum_array, 1))
+            ) lead
+            WHERE ix.indrelid = fk.conrelid
+              AND ix.indisvalid
+              AND ix.indpred IS NULL
+              AND lead.leading @> fk.fk_attnum_array
+              AND lead.leading <@ fk.fk_attnum_array
+        )
         ORDER BY COALESCE(s.n_live_tup, 0) DESC
     "#;
 
@@ -439,49 +679,180 @@ pub async fn get_fk_without_indexes(client: &Client) -> Result<Vec<FkWithoutInde
 
     let mut results = Vec::new();
     for row in rows {
+        let schema: String = row.get("schema_name");
+        let table: String = row.get("table_name");
+        let columns: Vec<String> = row.get("fk_columns");
         let table_rows: i64 = row.get("table_rows");
-        let fk_columns: Vec<String> = row.get("fk_columns");
-        let ref_columns: Vec<String> = row.get("ref_columns");
+        let parent_deletes: i64 = row.get("parent_deletes");
+        let parent_updates: i64 = row.get("parent_updates");
+        let on_delete: String = row.get("on_delete");
+        let parent_writes = parent_deletes.saturating_add(parent_updates);
 
         results.push(FkWithoutIndex {
-            schema: row.get("schema_name"),
-            table: row.get("table_name"),
-            columns: fk_columns,
+            sql: fk_index_sql(&schema, &table, &columns),
+            schema,
+            table,
+            columns,
             ref_schema: row.get("ref_schema"),
             ref_table: row.get("ref_table"),
-            ref_columns,
+            ref_columns: row.get("ref_columns"),
             constraint_name: row.get("constraint_name"),
             table_rows,
-            status: FkIndexStatus::from_row_count(table_rows),
+            parent_deletes,
+            parent_updates,
+            on_delete: on_delete_action(&on_delete).to_string(),
+            estimated_rows_scanned: parent_writes.saturating_mul(table_rows),
+            status: FkIndexStatus::from_activity(table_rows, parent_writes),
         });
     }
 
     Ok(results)
 }
 
+/// Name an FK action from pg_constraint.confdeltype
+fn on_delete_action(code: &str) -> &'static str {
+    match code {
+        "r" => "restrict",
+        "c" => "cascade",
+        "n" => "set null",
+        "d" => "set default",
+        _ => "no action",
+    }
+}
+
+/// `CREATE INDEX CONCURRENTLY` statement covering FK columns; the name is
+/// cut to Postgres's 63-byte identifier limit
+fn fk_index_sql(schema: &str, table: &str, columns: &[String]) -> String {
+    let mut name = format!("idx_{}_{}_fk", table, columns.join("_"));
+    while name.len() > 63 {
+        name.pop();
+    }
+    let cols: Vec<String> = columns.iter().map(|c| quote_ident(c)).collect();
+    format!(
+        "CREATE INDEX CONCURRENTLY {} ON {}.{} ({});",
+        quote_ident(&name),
+        quote_ident(schema),
+        quote_ident(table),
+        cols.join(", ")
+    )
+}
+
+/// One-line summary of what a missing FK index costs
+fn fk_impact(fk: &FkWithoutIndex) -> String {
+    if fk.parent_deletes == 0 && fk.parent_updates == 0 {
+        format!(
+            "no deletes or updates on {}.{} since stats reset",
+            fk.ref_schema, fk.ref_table
+        )
+    } else {
+        format!(
+            "{} deletes, {} updates on {}.{}; each scans ~{} rows (ON DELETE {})",
+            format_number(fk.parent_deletes),
+            format_number(fk.parent_updates),
+            fk.ref_schema,
+            fk.ref_table,
+            format_number(fk.table_rows),
+            fk.on_delete.to_uppercase()
+        )
+    }
+}
+
+/// Structured actions creating the missing FK indexes that matter
+/// (warning or critical)
+pub fn generate_fk_actions(
+    result: &IndexesResult,
+    read_write: bool,
+    is_primary: bool,
+) -> Vec<StructuredAction> {
+    result
+        .fk_without_indexes
+        .iter()
+        .filter(|fk| fk.status != FkIndexStatus::Info)
+        .map(|fk| {
+            let action_id = format!(
+                "indexes.fk.create.{}.{}.{}",
+                fk.schema, fk.table, fk.constraint_name
+            );
+            StructuredAction::builder(action_id, ActionType::Fix)
+                .command("pgcrate")
+                .args(vec![
+                    "sql".to_string(),
+                    "--allow-write".to_string(),
+                    "-c".to_string(),
+                    fk.sql.clone(),
+                ])
+                .description(format!(
+                    "Index {}.{}({}) for foreign key {}: {}",
+                    fk.schema,
+                    fk.table,
+                    fk.columns.join(", "),
+                    fk.constraint_name,
+                    fk_impact(fk)
+                ))
+                .mutates(true)
+                .risk(Risk::Low)
+                .gates(ActionGates::write_primary())
+                .sql_preview(vec![fk.sql.clone()])
+                .evidence(serde_json::to_value(fk).unwrap_or_default())
+                .build(read_write, is_primary, false)
+        })
+        .collect()
+}
+
 /// Run full index analysis
+/// Run full index analysis, or only the foreign key check when `fk_only`
 pub async fn run_indexes(
     client: &Client,
     missing_limit: usize,
     unused_limit: usize,
+    fk_only: bool,
+    ignore: &AlertIgnore,
 ) -> Result<IndexesResult> {
-    let missing = get_missing_index_candidates(client, missing_limit).await?;
-    let unused = get_unused_indexes(client, unused_limit).await?;
-    let duplicates = get_duplicate_indexes(client).await?;
-    let fk_without_indexes = get_fk_without_indexes(client).await?;
+    let mut fk_without_indexes = get_fk_without_indexes(client).await?;
+    fk_without_indexes.retain(|fk| !ignore.table(&fk.schema, &fk.table));
+    let (missing, unused, duplicates, redundant) = if fk_only {
+        (vec![], vec![], vec![], vec![])
+    } else {
+        let mut missing =
+            get_missing_index_candidates(client, missing_limit + ignore.count()).await?;
+        missing.retain(|m| !ignore.table(&m.schema, &m.table));
+        missing.truncate(missing_limit);
+
+        let mut unused = get_unused_indexes(client, unused_limit + ignore.count()).await?;
+        unused.retain(|u| !ignore.index(&u.schema, &u.index) && !ignore.table(&u.schema, &u.table));
+        unused.truncate(unused_limit);
+
+        // Ignoring any index in a duplicate set drops the whole finding
+        let mut duplicates = get_duplicate_indexes(client).await?;
+        duplicates.retain(|d| {
+            !ignore.table(&d.schema, &d.table)
+                && !d.indexes.iter().any(|i| ignore.index(&d.schema, &i.name))
+        });
+
+        let mut redundant = get_redundant_indexes(client).await?;
+        redundant
+            .retain(|r| !ignore.table(&r.schema, &r.table) && !ignore.index(&r.schema, &r.index));
+
+        (missing, unused, duplicates, redundant)
+    };
 
     let total_unused_bytes: i64 = unused.iter().map(|u| u.index_size_bytes).sum();
     let total_duplicate_bytes: i64 = duplicates.iter().map(|d| d.wasted_bytes).sum();
+    let total_redundant_bytes: i64 = redundant.iter().map(|r| r.size_bytes).sum();
 
     Ok(IndexesResult {
         missing,
         unused,
         duplicates,
+        redundant,
         fk_without_indexes,
         total_unused_bytes,
         total_unused_size: format_bytes(total_unused_bytes),
         total_duplicate_bytes,
         total_duplicate_size: format_bytes(total_duplicate_bytes),
+        total_redundant_bytes,
+        total_redundant_size: format_bytes(total_redundant_bytes),
+        actions: None,
     })
 }
 
@@ -633,6 +1004,8 @@ pub fn print_human(result: &IndexesResult, verbose: bool) {
             for idx in &dup.indexes {
                 let marker = if idx.is_primary {
                     " [PK]"
+                } else if idx.backing_constraint.is_some() {
+                    " [CNST]"
                 } else if idx.is_unique {
                     " [UNIQ]"
                 } else {
@@ -651,6 +1024,26 @@ pub fn print_human(result: &IndexesResult, verbose: bool) {
         }
     }
 
+    // Redundant (prefix-covered) indexes
+    if !result.redundant.is_empty() {
+        has_output = true;
+        println!("REDUNDANT INDEXES:");
+        println!();
+
+        for r in &result.redundant {
+            println!(
+                "  {}.{} ({}) {:>10} {:>8} scans",
+                r.schema,
+                r.index,
+                r.columns,
+                r.size,
+                format_number(r.idx_scan)
+            );
+            println!("    covered by {} ({})", r.covered_by, r.covered_by_columns);
+        }
+        println!();
+    }
+
     // Foreign keys without indexes
     if !result.fk_without_indexes.is_empty() {
         has_output = true;
@@ -675,15 +1068,10 @@ pub fn print_human(result: &IndexesResult, verbose: bool) {
                 format_number(fk.table_rows),
                 fk.constraint_name
             );
+            println!("       {}", fk_impact(fk));
 
             if verbose {
-                // Show the CREATE INDEX statement
-                let idx_name = format!("idx_{}_{}_fk", fk.table, fk.columns.join("_"));
-                let cols = fk.columns.join(", ");
-                println!(
-                    "       suggested: CREATE INDEX {} ON {}.{} ({});",
-                    idx_name, fk.schema, fk.table, cols
-                );
+                println!("       suggested: {}", fk.sql);
             }
         }
         println!();
@@ -733,6 +1121,13 @@ pub fn print_human(result: &IndexesResult, verbose: bool) {
                 result.total_duplicate_size
             );
         }
+        if !result.redundant.is_empty() {
+            println!(
+                "  Redundant indexes: {} ({} total)",
+                result.redundant.len(),
+                result.total_redundant_size
+            );
+        }
         if !result.missing.is_empty() {
             println!("  Missing candidates: {}", result.missing.len());
         }
@@ -755,37 +1150,32 @@ pub fn print_human(result: &IndexesResult, verbose: bool) {
         })
         .collect();
 
-    if !droppable_unused.is_empty() || !result.duplicates.is_empty() {
+    let fk_creates: Vec<_> = result
+        .fk_without_indexes
+        .iter()
+        .filter(|fk| fk.status != FkIndexStatus::Info)
+        .collect();
+
+    let dedupe: Vec<_> = dedupe_plan(&result.duplicates, &result.redundant)
+        .into_iter()
+        .filter(|d| d.blocked_by.is_none())
+        .collect();
+
+    if !droppable_unused.is_empty() || !dedupe.is_empty() || !fk_creates.is_empty() {
         println!();
         println!("RECOMMENDED ACTIONS:");
         println!();
 
-        // Collect indexes to drop as duplicates (so we can dedupe against unused)
-        let mut duplicate_drops: std::collections::HashSet<(String, String)> =
-            std::collections::HashSet::new();
-        for dup in &result.duplicates {
-            let keeper = dup.indexes.iter().max_by_key(|i| {
-                let priority = if i.is_primary {
-                    2
-                } else if i.is_unique {
-                    1
-                } else {
-                    0
-                };
-                (priority, i.idx_scan)
-            });
-            if let Some(keep) = keeper {
-                for idx in &dup.indexes {
-                    if idx.name != keep.name && !idx.is_primary {
-                        duplicate_drops.insert((dup.schema.clone(), idx.name.clone()));
-                    }
-                }
-            }
+        for fk in &fk_creates {
+            println!("  {}  -- FK {}", fk.sql, fk.constraint_name);
         }
 
-        // Show drop commands for unused indexes (excluding those already in duplicates)
+        // Show drop commands for unused indexes (excluding those proposed below)
         for u in droppable_unused.iter().take(5) {
-            if !duplicate_drops.contains(&(u.schema.clone(), u.index.clone())) {
+            if !dedupe
+                .iter()
+                .any(|d| d.schema == u.schema && d.index == u.index)
+            {
                 println!(
                     "  DROP INDEX {}.{};  -- {} unused",
                     u.schema, u.index, u.index_size
@@ -793,34 +1183,22 @@ pub fn print_human(result: &IndexesResult, verbose: bool) {
             }
         }
 
-        // Show drop commands for duplicates (keep the one with most scans or PK/unique)
-        for dup in result.duplicates.iter().take(3) {
-            let keeper = dup.indexes.iter().max_by_key(|i| {
-                let priority = if i.is_primary {
-                    2
-                } else if i.is_unique {
-                    1
-                } else {
-                    0
-                };
-                (priority, i.idx_scan)
-            });
-
-            if let Some(keep) = keeper {
-                for idx in &dup.indexes {
-                    if idx.name != keep.name && !idx.is_primary {
-                        println!(
-                            "  DROP INDEX {}.{};  -- duplicate of {}",
-                            dup.schema, idx.name, keep.name
-                        );
-                    }
-                }
-            }
+        // Show drop commands for duplicate and redundant indexes
+        for d in dedupe.iter().take(5) {
+            println!("  DROP INDEX {}.{};  -- {}", d.schema, d.index, d.reason);
+        }
+        if dedupe.len() > 5 {
+            println!(
+                "  ... {} more; see `pgcrate dba fix index --dedupe --dry-run`",
+                dedupe.len() - 5
+            );
         }
 
-        println!();
-        println!("  Note: Verify indexes aren't needed for specific queries before dropping.");
-        println!("  Check pg_stat_statements for query patterns if available.");
+        if !droppable_unused.is_empty() || !dedupe.is_empty() {
+            println!();
+            println!("  Note: Verify indexes aren't needed for specific queries before dropping.");
+            println!("  Check pg_stat_statements for query patterns if available.");
+        }
     }
 }
 
@@ -866,6 +1244,7 @@ pub fn print_json(
     } else if !result.missing.is_empty()
         || !result.unused.is_empty()
         || !result.duplicates.is_empty()
+        || !result.redundant.is_empty()
         || !result.fk_without_indexes.is_empty()
     {
         // Some findings - report as warning so automation knows there's something to review
@@ -944,6 +1323,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fk_index_status_from_activity() {
+        assert_eq!(
+            FkIndexStatus::from_activity(1_000_000, 50),
+            FkIndexStatus::Critical
+        );
+        // A parent that is never deleted from or updated never scans the child
+        assert_eq!(
+            FkIndexStatus::from_activity(1_000_000, 0),
+            FkIndexStatus::Warning
+        );
+        assert_eq!(FkIndexStatus::from_activity(50_000, 0), FkIndexStatus::Info);
+    }
+
+    #[test]
+    fn test_fk_index_sql() {
+        assert_eq!(
+            fk_index_sql("app", "order items", &["tenant_id".to_string(), "order_id".to_string()]),
+            "CREATE INDEX CONCURRENTLY \"idx_order items_tenant_id_order_id_fk\" ON \"app\".\"order items\" (\"tenant_id\", \"order_id\");"
+        );
+        let long = "x".repeat(80);
+        let sql = fk_index_sql("public", &long, &["id".to_string()]);
+        assert!(sql.contains(&format!("\"idx_{}\"", "x".repeat(59))));
+    }
+
     #[test]
     fn test_fk_index_status_critical() {
         assert_eq!(
@@ -955,4 +1359,58 @@ mod tests {
             FkIndexStatus::Critical
         );
     }
+
+    fn dup_info(name: &str, constraint: Option<&str>, idx_scan: i64) -> DuplicateIndexInfo {
+        DuplicateIndexInfo {
+            name: name.to_string(),
+            size: "8 KB".to_string(),
+            size_bytes: 8192,
+            is_unique: constraint.is_some(),
+            is_primary: false,
+            idx_scan,
+            backing_constraint: constraint.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_dedupe_plan() {
+        let duplicates = vec![DuplicateIndexSet {
+            schema: "public".to_string(),
+            table: "accounts".to_string(),
+            columns: "email".to_string(),
+            indexes: vec![
+                dup_info("accounts_email_idx", None, 500),
+                dup_info("accounts_email_key", Some("accounts_email_key"), 0),
+                dup_info("accounts_email_key2", Some("accounts_email_key2"), 0),
+            ],
+            wasted_bytes: 16384,
+            wasted_size: "16 KB".to_string(),
+        }];
+        let redundant = vec![RedundantIndex {
+            schema: "public".to_string(),
+            table: "accounts".to_string(),
+            index: "accounts_org_idx".to_string(),
+            columns: "org_id".to_string(),
+            size: "8 KB".to_string(),
+            size_bytes: 8192,
+            idx_scan: 3,
+            covered_by: "accounts_org_created_idx".to_string(),
+            covered_by_columns: "org_id, created_at".to_string(),
+        }];
+
+        let plan = dedupe_plan(&duplicates, &redundant);
+        let summary: Vec<(&str, &str, bool)> = plan
+            .iter()
+            .map(|c| (c.index.as_str(), c.keep.as_str(), c.blocked_by.is_some()))
+            .collect();
+        // Constraint-backed index is kept over the more-scanned plain one
+        assert_eq!(
+            summary,
+            vec![
+                ("accounts_email_idx", "accounts_email_key", false),
+                ("accounts_email_key2", "accounts_email_key", true),
+                ("accounts_org_idx", "accounts_org_created_idx", false),
+            ]
+        );
+    }
 }
@@ -0,0 +1,518 @@
+//! IO command: Buffer I/O breakdown by backend type and context.
+//!
+//! On PostgreSQL 16+ this reads pg_stat_io: reads, writes, extends, hits,
+//! evictions and fsyncs per backend type (client backend, checkpointer,
+//! background writer, autovacuum, ...), object and context (normal, bulkread,
+//! bulkwrite, vacuum). Older servers fall back to pg_statio_all_tables for
+//! reads and hits in the current database and pg_stat_bgwriter for who wrote
+//! the buffers. The summary answers "is my workload read-heavy or
+//! checkpoint-bound".
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use crate::snapshot::format_bytes;
+
+/// Shared buffer hit ratio below which reads are mostly going to disk
+const HIT_RATIO_WARNING: f64 = 90.0;
+/// Share of buffer writes done by client backends themselves
+const BACKEND_WRITE_PCT_WARNING: f64 = 10.0;
+/// Share of writes done by the checkpointer for a write-heavy workload to
+/// count as checkpoint-bound
+const CHECKPOINT_BOUND_PCT: f64 = 50.0;
+
+/// I/O status level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IoStatus {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl IoStatus {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            IoStatus::Healthy => "✓",
+            IoStatus::Warning => "⚠",
+            IoStatus::Critical => "✗",
+        }
+    }
+}
+
+/// Where the numbers came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IoSource {
+    /// pg_stat_io (PG16+), cluster-wide
+    PgStatIo,
+    /// pg_statio_all_tables (current database) and pg_stat_bgwriter
+    PgStatio,
+}
+
+/// Dominant kind of physical I/O
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IoProfile {
+    Idle,
+    ReadHeavy,
+    WriteHeavy,
+    Mixed,
+}
+
+impl IoProfile {
+    fn label(&self) -> &'static str {
+        match self {
+            IoProfile::Idle => "idle",
+            IoProfile::ReadHeavy => "read-heavy",
+            IoProfile::WriteHeavy => "write-heavy",
+            IoProfile::Mixed => "mixed",
+        }
+    }
+}
+
+/// I/O counters for one backend type, object and context
+#[derive(Debug, Clone, Serialize)]
+pub struct IoRow {
+    pub backend_type: String,
+    pub object: String,
+    pub context: String,
+    pub reads: i64,
+    pub writes: i64,
+    pub extends: i64,
+    pub hits: i64,
+    pub evictions: i64,
+    pub fsyncs: i64,
+    pub read_bytes: i64,
+    pub write_bytes: i64,
+    pub extend_bytes: i64,
+}
+
+/// Totals across all rows
+#[derive(Debug, Clone, Serialize)]
+pub struct IoSummary {
+    pub reads: i64,
+    pub hits: i64,
+    pub writes: i64,
+    pub extends: i64,
+    pub read_bytes: i64,
+    pub write_bytes: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_ratio_pct: Option<f64>,
+    /// Shares of buffer writes by who did them
+    pub checkpointer_write_pct: f64,
+    pub bgwriter_write_pct: f64,
+    pub backend_write_pct: f64,
+    /// fsyncs client backends had to do themselves
+    pub backend_fsyncs: i64,
+    pub profile: IoProfile,
+    pub checkpoint_bound: bool,
+}
+
+/// Full I/O results
+#[derive(Debug, Serialize)]
+pub struct IoResult {
+    pub source: IoSource,
+    pub server_version_num: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats_reset: Option<String>,
+    pub rows: Vec<IoRow>,
+    pub summary: IoSummary,
+    pub findings: Vec<String>,
+    pub overall_status: IoStatus,
+}
+
+async fn get_pg_stat_io(client: &Client, version_num: i32) -> Result<Vec<IoRow>> {
+    // PG18 replaced op_bytes with per-operation byte columns
+    let bytes = if version_num >= 180000 {
+        "coalesce(read_bytes, 0)::bigint, coalesce(write_bytes, 0)::bigint, coalesce(extend_bytes, 0)::bigint"
+    } else {
+        "coalesce(reads * op_bytes, 0)::bigint, coalesce(writes * op_bytes, 0)::bigint, coalesce(extends * op_bytes, 0)::bigint"
+    };
+    let query = format!(
+        r#"
+        SELECT
+            backend_type::text,
+            object::text,
+            context::text,
+            coalesce(reads, 0)::bigint,
+            coalesce(writes, 0)::bigint,
+            coalesce(extends, 0)::bigint,
+            coalesce(hits, 0)::bigint,
+            coalesce(evictions, 0)::bigint,
+            coalesce(fsyncs, 0)::bigint,
+            {}
+        FROM pg_stat_io
+        WHERE coalesce(reads, 0) + coalesce(writes, 0) + coalesce(extends, 0)
+              + coalesce(hits, 0) + coalesce(fsyncs, 0) > 0
+        ORDER BY coalesce(reads, 0) + coalesce(writes, 0) + coalesce(extends, 0) DESC,
+                 backend_type, object, context
+        "#,
+        bytes
+    );
+    let rows = client
+        .query(query.as_str(), &[])
+        .await
+        .context("Failed to query pg_stat_io")?;
+
+    Ok(rows
+        .iter()
+        .map(|row| IoRow {
+            backend_type: row.get(0),
+            object: row.get(1),
+            context: row.get(2),
+            reads: row.get(3),
+            writes: row.get(4),
+            extends: row.get(5),
+            hits: row.get(6),
+            evictions: row.get(7),
+            fsyncs: row.get(8),
+            read_bytes: row.get(9),
+            write_bytes: row.get(10),
+            extend_bytes: row.get(11),
+        })
+        .collect())
+}
+
+/// Pre-16 fallback, shaped like pg_stat_io rows for the three writers
+async fn get_pg_statio(client: &Client) -> Result<Vec<IoRow>> {
+    let row = client
+        .query_one(
+            r#"
+            SELECT
+                current_setting('block_size')::bigint AS block_size,
+                (SELECT coalesce(sum(coalesce(heap_blks_read, 0) + coalesce(idx_blks_read, 0)
+                         + coalesce(toast_blks_read, 0) + coalesce(tidx_blks_read, 0)), 0)::bigint
+                 FROM pg_statio_all_tables) AS reads,
+                (SELECT coalesce(sum(coalesce(heap_blks_hit, 0) + coalesce(idx_blks_hit, 0)
+                         + coalesce(toast_blks_hit, 0) + coalesce(tidx_blks_hit, 0)), 0)::bigint
+                 FROM pg_statio_all_tables) AS hits,
+                buffers_checkpoint,
+                buffers_clean,
+                buffers_backend,
+                buffers_backend_fsync
+            FROM pg_stat_bgwriter
+            "#,
+            &[],
+        )
+        .await
+        .context("Failed to query pg_statio_all_tables and pg_stat_bgwriter")?;
+
+    let block_size: i64 = row.get("block_size");
+    let writer = |backend_type: &str, writes: i64, reads: i64, hits: i64, fsyncs: i64| IoRow {
+        backend_type: backend_type.to_string(),
+        object: "relation".to_string(),
+        context: "normal".to_string(),
+        reads,
+        writes,
+        extends: 0,
+        hits,
+        evictions: 0,
+        fsyncs,
+        read_bytes: reads * block_size,
+        write_bytes: writes * block_size,
+        extend_bytes: 0,
+    };
+
+    Ok(vec![
+        writer(
+            "client backend",
+            row.get("buffers_backend"),
+            row.get("reads"),
+            row.get("hits"),
+            row.get("buffers_backend_fsync"),
+        ),
+        writer("checkpointer", row.get("buffers_checkpoint"), 0, 0, 0),
+        writer("background writer", row.get("buffers_clean"), 0, 0, 0),
+    ])
+}
+
+fn pct(part: i64, total: i64) -> f64 {
+    if total > 0 {
+        100.0 * part as f64 / total as f64
+    } else {
+        0.0
+    }
+}
+
+/// Physical reads vs. writes plus extends; a side needs twice the other's
+/// volume to dominate
+fn classify(reads: i64, writes: i64) -> IoProfile {
+    if reads == 0 && writes == 0 {
+        IoProfile::Idle
+    } else if reads >= writes * 2 {
+        IoProfile::ReadHeavy
+    } else if writes >= reads * 2 {
+        IoProfile::WriteHeavy
+    } else {
+        IoProfile::Mixed
+    }
+}
+
+fn summarize(rows: &[IoRow]) -> IoSummary {
+    let sum = |f: fn(&IoRow) -> i64| rows.iter().map(f).sum::<i64>();
+    let writes_by = |backend_type: &str| {
+        rows.iter()
+            .filter(|r| r.backend_type == backend_type)
+            .map(|r| r.writes)
+            .sum::<i64>()
+    };
+
+    let reads = sum(|r| r.reads);
+    let hits = sum(|r| r.hits);
+    let writes = sum(|r| r.writes);
+    let extends = sum(|r| r.extends);
+    let checkpointer_write_pct = pct(writes_by("checkpointer"), writes);
+    let profile = classify(reads, writes + extends);
+
+    IoSummary {
+        reads,
+        hits,
+        writes,
+        extends,
+        read_bytes: sum(|r| r.read_bytes),
+        write_bytes: sum(|r| r.write_bytes + r.extend_bytes),
+        hit_ratio_pct: (reads + hits > 0).then(|| pct(hits, reads + hits)),
+        checkpointer_write_pct,
+        bgwriter_write_pct: pct(writes_by("background writer"), writes),
+        backend_write_pct: pct(writes_by("client backend"), writes),
+        backend_fsyncs: rows
+            .iter()
+            .filter(|r| r.backend_type == "client backend")
+            .map(|r| r.fsyncs)
+            .sum(),
+        profile,
+        checkpoint_bound: profile != IoProfile::ReadHeavy
+            && profile != IoProfile::Idle
+            && checkpointer_write_pct >= CHECKPOINT_BOUND_PCT,
+    }
+}
+
+fn assess(summary: &IoSummary) -> (IoStatus, Vec<String>) {
+    let mut status = IoStatus::Healthy;
+    let mut findings = Vec::new();
+
+    if let Some(ratio) = summary.hit_ratio_pct {
+        if ratio < HIT_RATIO_WARNING {
+            status = IoStatus::Warning;
+            findings.push(format!(
+                "Only {:.1}% of buffer reads hit shared_buffers; consider raising shared_buffers or check `pgcrate dba cache`",
+                ratio
+            ));
+        }
+    }
+    if summary.backend_write_pct >= BACKEND_WRITE_PCT_WARNING {
+        status = IoStatus::Warning;
+        findings.push(format!(
+            "Client backends wrote {:.1}% of buffers themselves; the background writer is not keeping up (raise bgwriter_lru_maxpages)",
+            summary.backend_write_pct
+        ));
+    }
+    if summary.backend_fsyncs > 0 {
+        status = IoStatus::Warning;
+        findings.push(format!(
+            "Client backends ran {} fsyncs themselves; the checkpointer's fsync queue overflowed",
+            summary.backend_fsyncs
+        ));
+    }
+    if summary.checkpoint_bound {
+        findings.push(format!(
+            "Writes are checkpoint-bound ({:.0}% by the checkpointer); see `pgcrate dba checkpoints` and `pgcrate dba wal`",
+            summary.checkpointer_write_pct
+        ));
+    }
+
+    (status, findings)
+}
+
+/// Run I/O analysis
+pub async fn run_io(client: &Client) -> Result<IoResult> {
+    let version_num: i32 = client
+        .query_one("SELECT current_setting('server_version_num')::int", &[])
+        .await?
+        .get(0);
+
+    let (source, rows, stats_reset) = if version_num >= 160000 {
+        let reset: Option<chrono::DateTime<chrono::Utc>> = client
+            .query_one("SELECT min(stats_reset) FROM pg_stat_io", &[])
+            .await?
+            .get(0);
+        (
+            IoSource::PgStatIo,
+            get_pg_stat_io(client, version_num).await?,
+            reset,
+        )
+    } else {
+        let reset: Option<chrono::DateTime<chrono::Utc>> = client
+            .query_one("SELECT stats_reset FROM pg_stat_bgwriter", &[])
+            .await?
+            .get(0);
+        (IoSource::PgStatio, get_pg_statio(client).await?, reset)
+    };
+
+    let summary = summarize(&rows);
+    let (overall_status, findings) = assess(&summary);
+
+    Ok(IoResult {
+        source,
+        server_version_num: version_num,
+        stats_reset: stats_reset.map(|t| t.to_rfc3339()),
+        rows,
+        summary,
+        findings,
+        overall_status,
+    })
+}
+
+/// Print I/O analysis in human-readable format
+pub fn print_human(result: &IoResult, quiet: bool) {
+    let s = &result.summary;
+
+    println!("I/O");
+    println!("===");
+    println!();
+    if !quiet {
+        match result.source {
+            IoSource::PgStatIo => println!("  Source: pg_stat_io (cluster-wide)"),
+            IoSource::PgStatio => println!(
+                "  Source: pg_statio_all_tables (current database) + pg_stat_bgwriter (PG16+ adds pg_stat_io)"
+            ),
+        }
+        if let Some(ref reset) = result.stats_reset {
+            println!("  Since:  {}", reset);
+        }
+        println!();
+    }
+
+    println!(
+        "  {} Workload: {}{}",
+        result.overall_status.emoji(),
+        s.profile.label(),
+        if s.checkpoint_bound {
+            ", checkpoint-bound"
+        } else {
+            ""
+        }
+    );
+    println!(
+        "    Reads:  {} ({} blocks), hit ratio {}",
+        format_bytes(s.read_bytes.max(0) as u64),
+        s.reads,
+        s.hit_ratio_pct
+            .map(|r| format!("{:.1}%", r))
+            .unwrap_or_else(|| "-".to_string())
+    );
+    println!(
+        "    Writes: {} ({} blocks written, {} extended)",
+        format_bytes(s.write_bytes.max(0) as u64),
+        s.writes,
+        s.extends
+    );
+    println!(
+        "    Written by: checkpointer {:.1}%, background writer {:.1}%, backends {:.1}%",
+        s.checkpointer_write_pct, s.bgwriter_write_pct, s.backend_write_pct
+    );
+    println!();
+
+    if !result.rows.is_empty() {
+        println!(
+            "  {:20} {:14} {:10} {:>12} {:>12} {:>12} {:>14}",
+            "BACKEND", "OBJECT", "CONTEXT", "READS", "WRITES", "EXTENDS", "HITS"
+        );
+        for r in &result.rows {
+            println!(
+                "  {:20} {:14} {:10} {:>12} {:>12} {:>12} {:>14}",
+                r.backend_type, r.object, r.context, r.reads, r.writes, r.extends, r.hits
+            );
+        }
+        println!();
+    }
+
+    for finding in &result.findings {
+        println!("  → {}", finding);
+    }
+}
+
+/// Print I/O analysis as JSON with schema versioning.
+pub fn print_json(
+    result: &IoResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = match result.overall_status {
+        IoStatus::Healthy => Severity::Healthy,
+        IoStatus::Warning => Severity::Warning,
+        IoStatus::Critical => Severity::Critical,
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::IO, result, severity, t),
+        None => DiagnosticOutput::new(schema::IO, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(backend_type: &str, reads: i64, writes: i64, hits: i64) -> IoRow {
+        IoRow {
+            backend_type: backend_type.to_string(),
+            object: "relation".to_string(),
+            context: "normal".to_string(),
+            reads,
+            writes,
+            extends: 0,
+            hits,
+            evictions: 0,
+            fsyncs: 0,
+            read_bytes: reads * 8192,
+            write_bytes: writes * 8192,
+            extend_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(0, 0), IoProfile::Idle);
+        assert_eq!(classify(1000, 100), IoProfile::ReadHeavy);
+        assert_eq!(classify(100, 1000), IoProfile::WriteHeavy);
+        assert_eq!(classify(100, 150), IoProfile::Mixed);
+    }
+
+    #[test]
+    fn test_summarize_checkpoint_bound() {
+        let rows = vec![
+            row("client backend", 100, 50, 10_000),
+            row("checkpointer", 0, 800, 0),
+            row("background writer", 0, 150, 0),
+        ];
+        let s = summarize(&rows);
+        assert_eq!(s.profile, IoProfile::WriteHeavy);
+        assert!(s.checkpoint_bound);
+        assert!((s.checkpointer_write_pct - 80.0).abs() < 0.01);
+        assert!((s.backend_write_pct - 5.0).abs() < 0.01);
+        assert!(s.hit_ratio_pct.unwrap() > 99.0);
+        let (status, findings) = assess(&s);
+        assert_eq!(status, IoStatus::Healthy);
+        assert!(findings[0].contains("checkpoint-bound"));
+    }
+
+    #[test]
+    fn test_assess_flags_misses_and_backend_writes() {
+        let rows = vec![
+            row("client backend", 5_000, 300, 5_000),
+            row("checkpointer", 0, 700, 0),
+        ];
+        let s = summarize(&rows);
+        assert_eq!(s.profile, IoProfile::ReadHeavy);
+        assert!(!s.checkpoint_bound);
+        let (status, findings) = assess(&s);
+        assert_eq!(status, IoStatus::Warning);
+        assert_eq!(findings.len(), 2);
+    }
+}
@@ -0,0 +1,478 @@
+//! Lock wait-for graph for `dba locks --blocking --format dot|mermaid|json-graph`.
+//!
+//! `dba locks` groups waiters under their root blocker, which flattens
+//! multi-level pileups (A waits on B, which waits on C). The graph keeps
+//! every waiter -> holder edge from `pg_blocking_pids()`, labels each edge
+//! with the lock being waited for, and marks cycles: sessions that wait on
+//! each other until the deadlock detector aborts one of them.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+/// Graph output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+    JsonGraph,
+}
+
+impl GraphFormat {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "dot" => Some(GraphFormat::Dot),
+            "mermaid" => Some(GraphFormat::Mermaid),
+            "json-graph" => Some(GraphFormat::JsonGraph),
+            _ => None,
+        }
+    }
+}
+
+/// A session that waits for or holds a contended lock
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub pid: i32,
+    pub usename: Option<String>,
+    pub application_name: Option<String>,
+    pub state: Option<String>,
+    pub duration_seconds: Option<i64>,
+    pub query: Option<String>,
+    /// The lock this session is waiting for, if any
+    pub waiting_for: Option<String>,
+    /// Sessions waiting directly on this one
+    pub blocking: usize,
+    /// Blocks others without waiting itself
+    pub root: bool,
+    /// Distance from the nearest root (None for sessions only reachable in a cycle)
+    pub depth: Option<usize>,
+    pub in_cycle: bool,
+}
+
+/// `waiter` is waiting for a lock held (or queued ahead) by `holder`
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub waiter: i32,
+    pub holder: i32,
+    pub lock: Option<String>,
+}
+
+/// Full wait-for graph
+#[derive(Debug, Serialize)]
+pub struct WaitForGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    pub roots: Vec<i32>,
+    /// Sessions that wait on each other (deadlock-prone)
+    pub cycles: Vec<Vec<i32>>,
+    pub max_depth: usize,
+}
+
+/// Collect every waiter -> holder edge
+pub async fn get_wait_graph(client: &Client, redact: bool) -> Result<WaitForGraph> {
+    let query = r#"
+        WITH waiters AS (
+            SELECT pid, pg_blocking_pids(pid) AS holders
+            FROM pg_stat_activity
+            WHERE wait_event_type = 'Lock'
+        ),
+        involved AS (
+            SELECT pid FROM waiters WHERE cardinality(holders) > 0
+            UNION
+            SELECT unnest(holders) FROM waiters
+        )
+        SELECT
+            i.pid,
+            a.usename::text AS usename,
+            a.application_name,
+            a.state,
+            extract(epoch from now() - coalesce(a.xact_start, a.query_start))::bigint
+                AS duration_seconds,
+            left(a.query, 500) AS query,
+            coalesce(w.holders, '{}') AS holders,
+            (
+                SELECT l.mode || ' on ' || CASE
+                    WHEN l.relation IS NOT NULL THEN l.relation::regclass::text
+                    WHEN l.locktype = 'transactionid' THEN 'transaction ' || l.transactionid
+                    ELSE l.locktype
+                END
+                FROM pg_locks l
+                WHERE l.pid = i.pid AND NOT l.granted
+                LIMIT 1
+            ) AS waiting_for
+        FROM involved i
+        LEFT JOIN pg_stat_activity a ON a.pid = i.pid
+        LEFT JOIN waiters w ON w.pid = i.pid
+        ORDER BY i.pid
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .context("Failed to build lock wait-for graph")?;
+
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    for row in rows {
+        let pid: i32 = row.get("pid");
+        let holders: Vec<i32> = row.get("holders");
+        let waiting_for: Option<String> = row.get("waiting_for");
+        for holder in holders {
+            edges.push(GraphEdge {
+                waiter: pid,
+                holder,
+                lock: waiting_for.clone(),
+            });
+        }
+        let mut query: Option<String> = row.get("query");
+        if redact {
+            query = query.map(|q| crate::redact::redact_query(&q));
+        }
+        nodes.push(GraphNode {
+            pid,
+            usename: row.get("usename"),
+            application_name: row.get("application_name"),
+            state: row.get("state"),
+            duration_seconds: row.get("duration_seconds"),
+            query,
+            waiting_for,
+            blocking: 0,
+            root: false,
+            depth: None,
+            in_cycle: false,
+        });
+    }
+
+    Ok(build_graph(nodes, edges))
+}
+
+/// Fill in roots, depths, and cycles from the raw nodes and edges
+pub fn build_graph(mut nodes: Vec<GraphNode>, edges: Vec<GraphEdge>) -> WaitForGraph {
+    let mut waiters_of: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    let mut holders_of: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    for e in &edges {
+        waiters_of.entry(e.holder).or_default().push(e.waiter);
+        holders_of.entry(e.waiter).or_default().push(e.holder);
+    }
+
+    let cycles = find_cycles(&nodes, &holders_of);
+    let in_cycle: std::collections::HashSet<i32> = cycles.iter().flatten().copied().collect();
+
+    let roots: Vec<i32> = nodes
+        .iter()
+        .map(|n| n.pid)
+        .filter(|pid| waiters_of.contains_key(pid) && !holders_of.contains_key(pid))
+        .collect();
+
+    // Longest-known distance from a root, walking waiter edges breadth-first
+    let mut depth: HashMap<i32, usize> = roots.iter().map(|&pid| (pid, 0)).collect();
+    let mut queue: VecDeque<i32> = roots.iter().copied().collect();
+    while let Some(pid) = queue.pop_front() {
+        let d = depth[&pid];
+        for &waiter in waiters_of.get(&pid).into_iter().flatten() {
+            // Bounded by the node count so cycles reachable from a root terminate
+            if d < nodes.len() && depth.get(&waiter).is_none_or(|&old| old < d + 1) {
+                depth.insert(waiter, d + 1);
+                queue.push_back(waiter);
+            }
+        }
+    }
+
+    for node in &mut nodes {
+        node.blocking = waiters_of.get(&node.pid).map(Vec::len).unwrap_or(0);
+        node.root = roots.contains(&node.pid);
+        node.depth = depth.get(&node.pid).copied();
+        node.in_cycle = in_cycle.contains(&node.pid);
+    }
+    let max_depth = depth.values().copied().max().unwrap_or(0);
+
+    WaitForGraph {
+        nodes,
+        edges,
+        roots,
+        cycles,
+        max_depth,
+    }
+}
+
+/// Strongly connected components with more than one session (Tarjan)
+fn find_cycles(nodes: &[GraphNode], holders_of: &BTreeMap<i32, Vec<i32>>) -> Vec<Vec<i32>> {
+    struct Tarjan<'a> {
+        edges: &'a BTreeMap<i32, Vec<i32>>,
+        index: HashMap<i32, usize>,
+        low: HashMap<i32, usize>,
+        stack: Vec<i32>,
+        on_stack: std::collections::HashSet<i32>,
+        next: usize,
+        components: Vec<Vec<i32>>,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, v: i32) {
+            self.index.insert(v, self.next);
+            self.low.insert(v, self.next);
+            self.next += 1;
+            self.stack.push(v);
+            self.on_stack.insert(v);
+
+            for &w in self.edges.get(&v).into_iter().flatten() {
+                if !self.index.contains_key(&w) {
+                    self.visit(w);
+                    let low = self.low[&v].min(self.low[&w]);
+                    self.low.insert(v, low);
+                } else if self.on_stack.contains(&w) {
+                    let low = self.low[&v].min(self.index[&w]);
+                    self.low.insert(v, low);
+                }
+            }
+
+            if self.low[&v] == self.index[&v] {
+                let mut component = Vec::new();
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack.remove(&w);
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                if component.len() > 1 {
+                    component.sort_unstable();
+                    self.components.push(component);
+                }
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        edges: holders_of,
+        index: HashMap::new(),
+        low: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: std::collections::HashSet::new(),
+        next: 0,
+        components: Vec::new(),
+    };
+    for node in nodes {
+        if !tarjan.index.contains_key(&node.pid) {
+            tarjan.visit(node.pid);
+        }
+    }
+    tarjan.components.sort();
+    tarjan.components
+}
+
+fn node_label(node: &GraphNode, line_break: &str) -> String {
+    let mut lines = vec![format!("pid {}", node.pid)];
+    match (&node.usename, &node.application_name) {
+        (Some(user), Some(app)) if !app.is_empty() => lines.push(format!("{} ({})", user, app)),
+        (Some(user), _) => lines.push(user.clone()),
+        _ => {}
+    }
+    if let Some(ref state) = node.state {
+        match node.duration_seconds {
+            Some(secs) => lines.push(format!("{}, {}s", state, secs)),
+            None => lines.push(state.clone()),
+        }
+    }
+    if let Some(ref query) = node.query {
+        let clean = query.split_whitespace().collect::<Vec<_>>().join(" ");
+        let short: String = if clean.chars().count() > 40 {
+            format!("{}...", clean.chars().take(37).collect::<String>())
+        } else {
+            clean
+        };
+        lines.push(short);
+    }
+    lines.join(line_break)
+}
+
+fn is_cycle_edge(graph: &WaitForGraph, edge: &GraphEdge) -> bool {
+    graph
+        .cycles
+        .iter()
+        .any(|c| c.contains(&edge.waiter) && c.contains(&edge.holder))
+}
+
+/// Graphviz DOT; edges point from waiter to holder
+pub fn print_dot(graph: &WaitForGraph) {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    println!("digraph locks {{");
+    println!("    rankdir=LR;");
+    println!("    node [shape=box];");
+    for node in &graph.nodes {
+        let style = if node.in_cycle {
+            ", style=filled, fillcolor=\"#f8d7da\", color=red"
+        } else if node.root {
+            ", style=filled, fillcolor=\"#fff3cd\""
+        } else {
+            ""
+        };
+        println!(
+            "    \"{}\" [label=\"{}\"{}];",
+            node.pid,
+            escape(&node_label(node, "\n")).replace('\n', "\\n"),
+            style
+        );
+    }
+    for edge in &graph.edges {
+        let mut attrs = Vec::new();
+        if let Some(ref lock) = edge.lock {
+            attrs.push(format!("label=\"{}\"", escape(lock)));
+        }
+        if is_cycle_edge(graph, edge) {
+            attrs.push("color=red, penwidth=2".to_string());
+        }
+        if attrs.is_empty() {
+            println!("    \"{}\" -> \"{}\";", edge.waiter, edge.holder);
+        } else {
+            println!(
+                "    \"{}\" -> \"{}\" [{}];",
+                edge.waiter,
+                edge.holder,
+                attrs.join(", ")
+            );
+        }
+    }
+    println!("}}");
+}
+
+/// Mermaid flowchart; edges point from waiter to holder
+pub fn print_mermaid(graph: &WaitForGraph) {
+    let escape = |s: &str| s.replace('"', "#quot;").replace('|', "#124;");
+    println!("graph LR");
+    if graph.nodes.is_empty() {
+        println!("    none[\"No blocked sessions\"]");
+        return;
+    }
+    for node in &graph.nodes {
+        println!(
+            "    p{}[\"{}\"]",
+            node.pid,
+            escape(&node_label(node, "<br/>"))
+        );
+    }
+    for edge in &graph.edges {
+        match edge.lock {
+            Some(ref lock) => println!(
+                "    p{} -->|\"{}\"| p{}",
+                edge.waiter,
+                escape(lock),
+                edge.holder
+            ),
+            None => println!("    p{} --> p{}", edge.waiter, edge.holder),
+        }
+    }
+    println!("    classDef root fill:#fff3cd");
+    println!("    classDef cycle fill:#f8d7da,stroke:#c00");
+    let class_list = |pred: &dyn Fn(&GraphNode) -> bool| {
+        graph
+            .nodes
+            .iter()
+            .filter(|n| pred(n))
+            .map(|n| format!("p{}", n.pid))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let roots = class_list(&|n| n.root && !n.in_cycle);
+    if !roots.is_empty() {
+        println!("    class {} root", roots);
+    }
+    let cycle = class_list(&|n| n.in_cycle);
+    if !cycle.is_empty() {
+        println!("    class {} cycle", cycle);
+    }
+}
+
+/// Graph as a JSON envelope
+pub fn print_json(
+    graph: &WaitForGraph,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = if !graph.cycles.is_empty() {
+        Severity::Critical
+    } else if !graph.edges.is_empty() {
+        Severity::Warning
+    } else {
+        Severity::Healthy
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::LOCKS_GRAPH, graph, severity, t),
+        None => DiagnosticOutput::new(schema::LOCKS_GRAPH, graph, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(pid: i32) -> GraphNode {
+        GraphNode {
+            pid,
+            usename: Some("app".to_string()),
+            application_name: None,
+            state: Some("active".to_string()),
+            duration_seconds: Some(5),
+            query: Some("UPDATE t SET x = 1".to_string()),
+            waiting_for: None,
+            blocking: 0,
+            root: false,
+            depth: None,
+            in_cycle: false,
+        }
+    }
+
+    fn edge(waiter: i32, holder: i32) -> GraphEdge {
+        GraphEdge {
+            waiter,
+            holder,
+            lock: Some("ShareLock on transaction 7".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_multi_level_pileup() {
+        // 3 waits on 2, 2 and 4 wait on 1
+        let graph = build_graph(
+            vec![node(1), node(2), node(3), node(4)],
+            vec![edge(2, 1), edge(3, 2), edge(4, 1)],
+        );
+        assert_eq!(graph.roots, vec![1]);
+        assert_eq!(graph.max_depth, 2);
+        assert!(graph.cycles.is_empty());
+        let by_pid: HashMap<i32, &GraphNode> = graph.nodes.iter().map(|n| (n.pid, n)).collect();
+        assert_eq!(by_pid[&1].blocking, 2);
+        assert_eq!(by_pid[&3].depth, Some(2));
+        assert!(by_pid[&1].root);
+    }
+
+    #[test]
+    fn test_detects_cycle() {
+        // 1 <-> 2 deadlock, 3 waits on 1
+        let graph = build_graph(
+            vec![node(1), node(2), node(3)],
+            vec![edge(1, 2), edge(2, 1), edge(3, 1)],
+        );
+        assert_eq!(graph.cycles, vec![vec![1, 2]]);
+        assert!(graph.roots.is_empty());
+        assert!(graph.nodes.iter().filter(|n| n.in_cycle).count() == 2);
+        assert!(is_cycle_edge(&graph, &graph.edges[0]));
+        assert!(!is_cycle_edge(&graph, &graph.edges[2]));
+    }
+
+    #[test]
+    fn test_node_label() {
+        let mut n = node(42);
+        n.application_name = Some("psql".to_string());
+        assert_eq!(
+            node_label(&n, "|"),
+            "pid 42|app (psql)|active, 5s|UPDATE t SET x = 1"
+        );
+    }
+}
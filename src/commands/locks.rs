@@ -66,6 +66,51 @@ impl LockProcess {
     }
 }
 
+/// Which lock views to collect
+#[derive(Debug, Clone, Copy)]
+pub struct LocksOptions {
+    pub blocking: bool,
+    /// Minimum age in minutes for long transactions (None = not shown)
+    pub long_tx: Option<u64>,
+    pub idle_in_tx: bool,
+}
+
+impl LocksOptions {
+    /// Blocking chains are shown when no view is requested explicitly
+    pub fn new(blocking: bool, long_tx: Option<u64>, idle_in_tx: bool) -> Self {
+        Self {
+            blocking: blocking || (long_tx.is_none() && !idle_in_tx),
+            long_tx,
+            idle_in_tx,
+        }
+    }
+}
+
+/// Collect the requested lock views
+pub async fn run_locks(client: &Client, opts: LocksOptions, redact: bool) -> Result<LocksResult> {
+    let mut result = LocksResult {
+        blocking_chains: vec![],
+        long_transactions: vec![],
+        idle_in_transaction: vec![],
+    };
+
+    if opts.blocking {
+        result.blocking_chains = get_blocking_chains(client).await?;
+    }
+    if let Some(min_minutes) = opts.long_tx {
+        result.long_transactions = get_long_transactions(client, min_minutes).await?;
+    }
+    if opts.idle_in_tx {
+        result.idle_in_transaction = get_idle_in_transaction(client).await?;
+    }
+
+    if redact {
+        result.redact();
+    }
+
+    Ok(result)
+}
+
 /// Get all blocking chains
 pub async fn get_blocking_chains(client: &Client) -> Result<Vec<BlockingChain>> {
     // First, get all blocked processes and their blockers
@@ -314,6 +359,17 @@ pub async fn get_pid_info(client: &Client, pid: i32) -> Result<LockProcess> {
     })
 }
 
+/// Send pg_cancel_backend or pg_terminate_backend to a PID; false when the
+/// process was already gone
+pub async fn signal_backend(client: &Client, pid: i32, terminate: bool) -> Result<bool> {
+    let sql = if terminate {
+        "SELECT pg_terminate_backend($1)"
+    } else {
+        "SELECT pg_cancel_backend($1)"
+    };
+    Ok(client.query_one(sql, &[&pid]).await?.get(0))
+}
+
 /// Cancel a query (pg_cancel_backend)
 pub async fn cancel_query(client: &Client, pid: i32, execute: bool, redact: bool) -> Result<bool> {
     let mut info = get_pid_info(client, pid).await?;
@@ -330,10 +386,7 @@ pub async fn cancel_query(client: &Client, pid: i32, execute: bool, redact: bool
         return Ok(false);
     }
 
-    let result: bool = client
-        .query_one("SELECT pg_cancel_backend($1)", &[&pid])
-        .await?
-        .get(0);
+    let result = signal_backend(client, pid, false).await?;
 
     if result {
         eprintln!("✓ Query cancelled");
@@ -372,10 +425,7 @@ pub async fn terminate_connection(
         return Ok(false);
     }
 
-    let result: bool = client
-        .query_one("SELECT pg_terminate_backend($1)", &[&pid])
-        .await?
-        .get(0);
+    let result = signal_backend(client, pid, true).await?;
 
     if result {
         eprintln!("✓ Connection terminated");
@@ -386,6 +436,252 @@ pub async fn terminate_connection(
     Ok(result)
 }
 
+/// Sessions selected by `--kill-idle`
+#[derive(Debug, Clone)]
+pub struct KillIdleOptions {
+    pub min_idle: std::time::Duration,
+    /// Glob (`*`, `?`) matched against application_name
+    pub application: Option<String>,
+    /// Glob (`*`, `?`) matched against the session user
+    pub user: Option<String>,
+    pub execute: bool,
+}
+
+/// An idle-in-transaction session matched by `--kill-idle`
+#[derive(Debug, Clone, Serialize)]
+pub struct IdleSession {
+    pub pid: i32,
+    pub usename: String,
+    pub application_name: String,
+    pub database: Option<String>,
+    pub client_addr: Option<String>,
+    pub state: String,
+    pub idle_seconds: i64,
+    /// Start of the idle period; termination only applies while it's unchanged
+    #[serde(skip)]
+    pub state_change: chrono::DateTime<chrono::Utc>,
+    pub query: String,
+    /// Whether pg_terminate_backend succeeded (None in dry-run mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terminated: Option<bool>,
+}
+
+/// Result of `dba locks --kill-idle`
+#[derive(Debug, Serialize)]
+pub struct KillIdleResult {
+    pub min_idle_seconds: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub executed: bool,
+    pub sessions: Vec<IdleSession>,
+    pub terminated: usize,
+    /// PIDs left alone because they ended or left the idle transaction
+    /// after they were listed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<i32>,
+}
+
+/// Convert a `*`/`?` glob into a LIKE pattern
+fn glob_to_like(glob: &str) -> String {
+    let mut like = String::with_capacity(glob.len());
+    for c in glob.chars() {
+        match c {
+            '*' => like.push('%'),
+            '?' => like.push('_'),
+            '%' | '_' | '\\' => {
+                like.push('\\');
+                like.push(c);
+            }
+            _ => like.push(c),
+        }
+    }
+    like
+}
+
+/// Find idle-in-transaction sessions older than `min_idle` matching the
+/// patterns, and terminate them when `execute` is set
+pub async fn kill_idle(
+    client: &Client,
+    opts: &KillIdleOptions,
+    redact: bool,
+) -> Result<KillIdleResult> {
+    let query = r#"
+        SELECT
+            pid,
+            usename::text AS usename,
+            application_name,
+            datname::text AS database,
+            client_addr::text,
+            state,
+            extract(epoch from now() - state_change)::bigint as idle_seconds,
+            state_change,
+            left(query, 500) as query
+        FROM pg_stat_activity
+        WHERE state IN ('idle in transaction', 'idle in transaction (aborted)')
+          AND pid <> pg_backend_pid()
+          AND now() - state_change >= make_interval(secs => $1)
+          AND ($2::text IS NULL OR application_name LIKE $2)
+          AND ($3::text IS NULL OR usename LIKE $3)
+        ORDER BY state_change
+    "#;
+
+    let min_secs = opts.min_idle.as_secs_f64();
+    let application = opts.application.as_deref().map(glob_to_like);
+    let user = opts.user.as_deref().map(glob_to_like);
+    let rows = client
+        .query(query, &[&min_secs, &application, &user])
+        .await?;
+
+    let mut sessions = Vec::new();
+    for row in rows {
+        let mut query: String = row.get::<_, Option<String>>("query").unwrap_or_default();
+        if redact {
+            query = crate::redact::redact_query(&query);
+        }
+        sessions.push(IdleSession {
+            pid: row.get("pid"),
+            usename: row.get::<_, Option<String>>("usename").unwrap_or_default(),
+            application_name: row
+                .get::<_, Option<String>>("application_name")
+                .unwrap_or_default(),
+            database: row.get("database"),
+            client_addr: row.get("client_addr"),
+            state: row.get("state"),
+            idle_seconds: row.get::<_, Option<i64>>("idle_seconds").unwrap_or(0),
+            state_change: row.get("state_change"),
+            query,
+            terminated: None,
+        });
+    }
+
+    let mut terminated = 0;
+    let mut skipped = Vec::new();
+    if opts.execute {
+        // The pid may have moved on (or been reused) since it was listed, so
+        // only terminate it while it is still in the same idle transaction
+        let terminate = r#"
+            SELECT pg_terminate_backend(pid)
+            FROM pg_stat_activity
+            WHERE pid = $1 AND state = $2 AND state_change = $3
+        "#;
+        for session in &mut sessions {
+            let ok = client
+                .query_opt(
+                    terminate,
+                    &[&session.pid, &session.state, &session.state_change],
+                )
+                .await?
+                .is_some_and(|row| row.get(0));
+            if ok {
+                terminated += 1;
+            } else {
+                skipped.push(session.pid);
+            }
+            session.terminated = Some(ok);
+        }
+    }
+
+    Ok(KillIdleResult {
+        min_idle_seconds: opts.min_idle.as_secs(),
+        application: opts.application.clone(),
+        user: opts.user.clone(),
+        executed: opts.execute,
+        sessions,
+        terminated,
+        skipped,
+    })
+}
+
+/// Print `--kill-idle` results
+pub fn print_kill_idle(result: &KillIdleResult) {
+    let mut filters = vec![format!(
+        "idle in transaction >= {}",
+        format_duration(result.min_idle_seconds as i64)
+    )];
+    if let Some(ref app) = result.application {
+        filters.push(format!("application '{}'", app));
+    }
+    if let Some(ref user) = result.user {
+        filters.push(format!("user '{}'", user));
+    }
+
+    if result.sessions.is_empty() {
+        println!("No sessions match: {}", filters.join(", "));
+        return;
+    }
+
+    if result.executed {
+        println!("KILL IDLE ({}):", filters.join(", "));
+    } else {
+        println!("KILL IDLE - DRY RUN ({}):", filters.join(", "));
+    }
+    println!();
+    for s in &result.sessions {
+        let mark = match s.terminated {
+            Some(true) => "✓",
+            Some(false) => "✗",
+            None => "•",
+        };
+        println!(
+            "  {} PID {} - {} / {} - idle {}",
+            mark,
+            s.pid,
+            s.usename,
+            if s.application_name.is_empty() {
+                "-"
+            } else {
+                &s.application_name
+            },
+            format_duration(s.idle_seconds)
+        );
+        println!("      {}", truncate_query(&s.query, 70));
+    }
+    println!();
+
+    if result.executed {
+        if !result.skipped.is_empty() {
+            let pids: Vec<String> = result.skipped.iter().map(|p| p.to_string()).collect();
+            println!(
+                "Terminated {} of {} sessions; skipped PIDs {} (no longer idle in the same transaction)",
+                result.terminated,
+                result.sessions.len(),
+                pids.join(", ")
+            );
+        } else {
+            println!("Terminated {} sessions", result.terminated);
+        }
+    } else {
+        println!(
+            "Would terminate {} sessions. Add --execute --read-write --primary to terminate them.",
+            result.sessions.len()
+        );
+    }
+}
+
+/// Print `--kill-idle` results as JSON
+pub fn print_kill_idle_json(
+    result: &KillIdleResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    // Matches left running are the finding; terminated ones are resolved
+    let severity = if result.sessions.iter().any(|s| s.terminated != Some(true)) {
+        Severity::Warning
+    } else {
+        Severity::Healthy
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::KILL_IDLE, result, severity, t),
+        None => DiagnosticOutput::new(schema::KILL_IDLE, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
 /// Print info about a PID
 fn print_pid_info(info: &LockProcess) {
     eprintln!("PID:   {}", info.pid);
@@ -420,6 +716,27 @@ fn truncate_query(query: &str, max_len: usize) -> String {
     }
 }
 
+/// Print the requested lock views in human-readable format
+pub fn print_human(result: &LocksResult, opts: LocksOptions, quiet: bool) {
+    if opts.blocking {
+        print_blocking_chains(&result.blocking_chains, quiet);
+    }
+    if opts.long_tx.is_some() {
+        if opts.blocking && !result.blocking_chains.is_empty() {
+            println!();
+        }
+        print_long_transactions(&result.long_transactions, quiet);
+    }
+    if opts.idle_in_tx {
+        if (opts.blocking && !result.blocking_chains.is_empty())
+            || (opts.long_tx.is_some() && !result.long_transactions.is_empty())
+        {
+            println!();
+        }
+        print_idle_in_transaction(&result.idle_in_transaction, quiet);
+    }
+}
+
 /// Print blocking chains in human-readable format
 pub fn print_blocking_chains(chains: &[BlockingChain], quiet: bool) {
     if chains.is_empty() {
@@ -590,6 +907,13 @@ pub fn print_json(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_glob_to_like() {
+        assert_eq!(glob_to_like("worker-*"), "worker-%");
+        assert_eq!(glob_to_like("app?"), "app_");
+        assert_eq!(glob_to_like("my_app%"), "my\\_app\\%");
+    }
+
     #[test]
     fn test_format_duration_seconds() {
         assert_eq!(format_duration(45), "45s");
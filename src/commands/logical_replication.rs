@@ -0,0 +1,449 @@
+//! Logical replication inspection: Publications and subscriptions.
+//!
+//! Publications list what they publish as pg_publication_tables expands it
+//! (FOR ALL TABLES and FOR TABLES IN SCHEMA included), with the row filter
+//! and column list of tables added explicitly (PostgreSQL 15+).
+//! Subscriptions reuse the apply-lag grading of `dba slots` and add their
+//! publications, slot, and per-table sync state. The connection string is
+//! not shown: it can hold a password and only superusers may read it.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::connect;
+use super::slots::{get_subscriptions, SubscriptionLag};
+use crate::output::{Output, PublicationsResponse, SubscriptionsResponse};
+
+/// Tables listed per publication in human output
+const DISPLAY_LIMIT: usize = 20;
+
+/// A table as published
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishedTable {
+    pub schema: String,
+    pub table: String,
+    /// WHERE clause limiting the published rows
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_filter: Option<String>,
+    /// Published columns when limited by a column list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+}
+
+/// One publication in the current database
+#[derive(Debug, Clone, Serialize)]
+pub struct Publication {
+    pub name: String,
+    pub owner: String,
+    pub all_tables: bool,
+    /// Published operations: insert, update, delete, truncate
+    pub operations: Vec<String>,
+    /// Partition changes are published as their root table's
+    pub via_root: bool,
+    /// FOR TABLES IN SCHEMA schemas
+    pub schemas: Vec<String>,
+    pub tables: Vec<PublishedTable>,
+}
+
+/// Initial-sync state of one subscribed table
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscribedTable {
+    pub schema: String,
+    pub table: String,
+    /// init, data copy, finished copy, synchronized, or ready
+    pub state: String,
+}
+
+/// One subscription in the current database
+#[derive(Debug, Clone, Serialize)]
+pub struct Subscription {
+    #[serde(flatten)]
+    pub lag: SubscriptionLag,
+    pub owner: String,
+    pub publications: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot_name: Option<String>,
+    pub tables: Vec<SubscribedTable>,
+}
+
+fn sync_state_name(state: &str) -> &'static str {
+    match state {
+        "i" => "init",
+        "d" => "data copy",
+        "f" => "finished copy",
+        "s" => "synchronized",
+        "r" => "ready",
+        _ => "unknown",
+    }
+}
+
+/// Publications in the current database, optionally only `name`
+pub async fn get_publications(client: &Client, name: Option<&str>) -> Result<Vec<Publication>> {
+    let version: i32 = client
+        .query_one("SELECT current_setting('server_version_num')::int", &[])
+        .await?
+        .get(0);
+    // Row filters, column lists, and schema publications arrived in 15
+    let pg15 = version >= 150000;
+
+    let schemas_sql = if pg15 {
+        "ARRAY(SELECT n.nspname::text FROM pg_publication_namespace pn
+               JOIN pg_namespace n ON n.oid = pn.pnnspid
+               WHERE pn.pnpubid = p.oid ORDER BY 1)"
+    } else {
+        "ARRAY[]::text[]"
+    };
+    let rows = client
+        .query(
+            &format!(
+                r#"
+                SELECT p.oid::bigint AS oid,
+                       p.pubname::text AS name,
+                       pg_get_userbyid(p.pubowner)::text AS owner,
+                       p.puballtables AS all_tables,
+                       p.pubinsert, p.pubupdate, p.pubdelete, p.pubtruncate,
+                       p.pubviaroot AS via_root,
+                       {} AS schemas
+                FROM pg_publication p
+                WHERE $1::text IS NULL OR p.pubname = $1
+                ORDER BY p.pubname
+                "#,
+                schemas_sql
+            ),
+            &[&name],
+        )
+        .await
+        .context("Failed to query pg_publication")?;
+
+    let tables_sql = if pg15 {
+        r#"
+        SELECT pt.schemaname::text, pt.tablename::text,
+               pg_get_expr(pr.prqual, pr.prrelid) AS row_filter,
+               CASE WHEN pr.prattrs IS NOT NULL THEN pt.attnames::text[] END AS columns
+        FROM pg_publication_tables pt
+        JOIN pg_namespace n ON n.nspname = pt.schemaname
+        JOIN pg_class c ON c.relnamespace = n.oid AND c.relname = pt.tablename
+        LEFT JOIN pg_publication_rel pr
+          ON pr.prpubid = $1::bigint::oid AND pr.prrelid = c.oid
+        WHERE pt.pubname = $2
+        ORDER BY 1, 2
+        "#
+    } else {
+        r#"
+        SELECT pt.schemaname::text, pt.tablename::text,
+               NULL::text AS row_filter, NULL::text[] AS columns
+        FROM pg_publication_tables pt
+        WHERE $1::bigint IS NOT NULL AND pt.pubname = $2
+        ORDER BY 1, 2
+        "#
+    };
+
+    let mut publications = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let oid: i64 = row.get("oid");
+        let name: String = row.get("name");
+        let tables = client
+            .query(tables_sql, &[&oid, &name])
+            .await
+            .context("Failed to query pg_publication_tables")?
+            .iter()
+            .map(|t| PublishedTable {
+                schema: t.get(0),
+                table: t.get(1),
+                row_filter: t.get(2),
+                columns: t.get(3),
+            })
+            .collect();
+
+        let operations = [
+            ("insert", row.get::<_, bool>("pubinsert")),
+            ("update", row.get("pubupdate")),
+            ("delete", row.get("pubdelete")),
+            ("truncate", row.get("pubtruncate")),
+        ]
+        .into_iter()
+        .filter(|(_, on)| *on)
+        .map(|(op, _)| op.to_string())
+        .collect();
+
+        publications.push(Publication {
+            name,
+            owner: row.get("owner"),
+            all_tables: row.get("all_tables"),
+            operations,
+            via_root: row.get("via_root"),
+            schemas: row.get("schemas"),
+            tables,
+        });
+    }
+    Ok(publications)
+}
+
+/// Subscriptions in the current database, optionally only `name`
+pub async fn get_subscription_details(
+    client: &Client,
+    name: Option<&str>,
+) -> Result<Vec<Subscription>> {
+    let mut subscriptions = Vec::new();
+    for lag in get_subscriptions(client).await? {
+        if name.is_some_and(|n| n != lag.subscription) {
+            continue;
+        }
+        let row = client
+            .query_one(
+                r#"
+                SELECT s.oid::bigint AS oid,
+                       pg_get_userbyid(s.subowner)::text AS owner,
+                       s.subpublications::text[] AS publications,
+                       s.subslotname::text AS slot_name
+                FROM pg_subscription s
+                WHERE s.subname = $1
+                  AND s.subdbid = (SELECT oid FROM pg_database WHERE datname = current_database())
+                "#,
+                &[&lag.subscription],
+            )
+            .await
+            .context("Failed to query pg_subscription")?;
+        let oid: i64 = row.get("oid");
+
+        let tables = client
+            .query(
+                r#"
+                SELECT n.nspname::text, c.relname::text, r.srsubstate::text
+                FROM pg_subscription_rel r
+                JOIN pg_class c ON c.oid = r.srrelid
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE r.srsubid = $1::bigint::oid
+                ORDER BY 1, 2
+                "#,
+                &[&oid],
+            )
+            .await
+            .context("Failed to query pg_subscription_rel")?
+            .iter()
+            .map(|t| SubscribedTable {
+                schema: t.get(0),
+                table: t.get(1),
+                state: sync_state_name(t.get(2)).to_string(),
+            })
+            .collect();
+
+        subscriptions.push(Subscription {
+            lag,
+            owner: row.get("owner"),
+            publications: row.get("publications"),
+            slot_name: row.get("slot_name"),
+            tables,
+        });
+    }
+    Ok(subscriptions)
+}
+
+/// Format publications for display
+pub fn format_publications(publications: &[Publication]) -> String {
+    let mut output = Vec::new();
+    for (i, p) in publications.iter().enumerate() {
+        if i > 0 {
+            output.push(String::new());
+        }
+        let mut header = format!(
+            "{}  (owner {}; {}",
+            p.name,
+            p.owner,
+            p.operations.join(", ")
+        );
+        if p.via_root {
+            header.push_str("; via root");
+        }
+        header.push(')');
+        output.push(header);
+
+        if p.all_tables {
+            output.push("  FOR ALL TABLES".to_string());
+        }
+        if !p.schemas.is_empty() {
+            output.push(format!("  FOR TABLES IN SCHEMA {}", p.schemas.join(", ")));
+        }
+        if p.tables.is_empty() {
+            output.push("  (no tables)".to_string());
+        }
+        for t in p.tables.iter().take(DISPLAY_LIMIT) {
+            let mut line = format!("  {}.{}", t.schema, t.table);
+            if let Some(ref columns) = t.columns {
+                line.push_str(&format!(" ({})", columns.join(", ")));
+            }
+            if let Some(ref filter) = t.row_filter {
+                line.push_str(&format!(" WHERE {}", filter));
+            }
+            output.push(line);
+        }
+        if p.tables.len() > DISPLAY_LIMIT {
+            output.push(format!(
+                "  ... and {} more (--json lists all)",
+                p.tables.len() - DISPLAY_LIMIT
+            ));
+        }
+    }
+    output.join("\n")
+}
+
+/// Format subscriptions for display
+pub fn format_subscriptions(subscriptions: &[Subscription]) -> String {
+    let mut output = Vec::new();
+    for (i, s) in subscriptions.iter().enumerate() {
+        if i > 0 {
+            output.push(String::new());
+        }
+        let state = if !s.lag.enabled {
+            "disabled".to_string()
+        } else {
+            match (s.lag.pid, s.lag.lag_secs) {
+                (Some(pid), Some(secs)) => {
+                    format!("worker {}, {:.0}s since last publisher update", pid, secs)
+                }
+                (Some(pid), None) => format!("worker {}", pid),
+                (None, _) => "no worker".to_string(),
+            }
+        };
+        output.push(format!(
+            "{} {}  (owner {}; {})",
+            s.lag.status.emoji(),
+            s.lag.subscription,
+            s.owner,
+            state
+        ));
+        output.push(format!(
+            "    publications: {}   slot: {}",
+            s.publications.join(", "),
+            s.slot_name.as_deref().unwrap_or("none")
+        ));
+        if let Some(ref lsn) = s.lag.received_lsn {
+            output.push(format!("    received up to {}", lsn));
+        }
+
+        let ready = s.tables.iter().filter(|t| t.state == "ready").count();
+        if s.tables.is_empty() {
+            output.push("    tables: none".to_string());
+        } else if ready == s.tables.len() {
+            output.push(format!("    tables: {} ready", ready));
+        } else {
+            output.push(format!(
+                "    tables: {} ready, {} syncing",
+                ready,
+                s.tables.len() - ready
+            ));
+        }
+        for t in s.tables.iter().filter(|t| t.state != "ready") {
+            output.push(format!("      {}.{} ({})", t.schema, t.table, t.state));
+        }
+        for issue in &s.lag.issues {
+            output.push(format!("    → {}", issue));
+        }
+    }
+    output.join("\n")
+}
+
+/// Show publications and what they publish
+pub async fn publications(database_url: &str, name: Option<&str>, output: &Output) -> Result<()> {
+    let client = connect(database_url).await?;
+    let publications = get_publications(&client, name).await?;
+    if let (Some(name), true) = (name, publications.is_empty()) {
+        bail!("Publication \"{}\" not found", name);
+    }
+
+    if output.is_json() {
+        output.json(&PublicationsResponse {
+            ok: true,
+            publications,
+        })?;
+        return Ok(());
+    }
+    if output.is_quiet() {
+        return Ok(());
+    }
+
+    if publications.is_empty() {
+        output.data("No publications in this database.");
+    } else {
+        output.data(&format!(
+            "\nPublications:\n\n{}",
+            format_publications(&publications)
+        ));
+    }
+    Ok(())
+}
+
+/// Show subscriptions with their status, lag, and table sync state
+pub async fn subscriptions(database_url: &str, name: Option<&str>, output: &Output) -> Result<()> {
+    let client = connect(database_url).await?;
+    let subscriptions = get_subscription_details(&client, name).await?;
+    if let (Some(name), true) = (name, subscriptions.is_empty()) {
+        bail!("Subscription \"{}\" not found", name);
+    }
+
+    if output.is_json() {
+        output.json(&SubscriptionsResponse {
+            ok: true,
+            subscriptions,
+        })?;
+        return Ok(());
+    }
+    if output.is_quiet() {
+        return Ok(());
+    }
+
+    if subscriptions.is_empty() {
+        output.data("No subscriptions in this database.");
+    } else {
+        output.data(&format!(
+            "\nSubscriptions:\n\n{}",
+            format_subscriptions(&subscriptions)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_publications() {
+        let publications = vec![Publication {
+            name: "app_pub".to_string(),
+            owner: "postgres".to_string(),
+            all_tables: false,
+            operations: vec!["insert".to_string(), "update".to_string()],
+            via_root: false,
+            schemas: vec![],
+            tables: vec![
+                PublishedTable {
+                    schema: "app".to_string(),
+                    table: "orders".to_string(),
+                    row_filter: Some("(status <> 'draft'::text)".to_string()),
+                    columns: None,
+                },
+                PublishedTable {
+                    schema: "app".to_string(),
+                    table: "users".to_string(),
+                    row_filter: None,
+                    columns: Some(vec!["id".to_string(), "email".to_string()]),
+                },
+            ],
+        }];
+        assert_eq!(
+            format_publications(&publications),
+            "app_pub  (owner postgres; insert, update)\n\
+             \x20 app.orders WHERE (status <> 'draft'::text)\n\
+             \x20 app.users (id, email)"
+        );
+    }
+
+    #[test]
+    fn test_sync_state_name() {
+        assert_eq!(sync_state_name("r"), "ready");
+        assert_eq!(sync_state_name("d"), "data copy");
+        assert_eq!(sync_state_name("?"), "unknown");
+    }
+}
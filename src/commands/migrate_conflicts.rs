@@ -0,0 +1,444 @@
+//! `migrate conflicts`: detect migrations that will collide when a branch is merged.
+//!
+//! Compares migration files added on the current branch (committed, staged,
+//! or untracked) against migrations added on the base branch since the
+//! merge-base, and flags:
+//! - duplicate versions (critical)
+//! - branch migrations that sort before the newest base migration (warning)
+//! - DDL on the same table in both branches (warning)
+
+use crate::config::Config;
+use crate::exit_codes;
+use crate::migrations::{
+    parse_migration_content, parse_migration_filename, strip_comments_and_literals,
+};
+use crate::output::Output;
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+/// A migration file as seen on one side of the comparison.
+#[derive(Debug, Clone)]
+struct MigrationFile {
+    version: String,
+    name: String,
+    /// Tables targeted by DDL in the up section (schema-qualified, normalized)
+    tables: BTreeSet<String>,
+}
+
+impl MigrationFile {
+    fn label(&self) -> String {
+        format!("{}_{}", self.version, self.name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    DuplicateVersion,
+    OutOfOrder,
+    SameTable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictSeverity {
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MigrationConflict {
+    pub kind: ConflictKind,
+    pub severity: ConflictSeverity,
+    /// Migration added on the current branch
+    pub migration: String,
+    /// Conflicting migration on the base branch
+    pub other: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConflictsResponse {
+    pub ok: bool,
+    pub base: String,
+    pub merge_base: String,
+    pub branch_migrations: Vec<String>,
+    pub base_migrations: Vec<String>,
+    pub conflicts: Vec<MigrationConflict>,
+}
+
+impl ConflictsResponse {
+    fn has_critical(&self) -> bool {
+        self.conflicts
+            .iter()
+            .any(|c| c.severity == ConflictSeverity::Critical)
+    }
+}
+
+/// Run `migrate conflicts` and return the process exit code.
+pub fn migrate_conflicts(config: &Config, base: &str, output: &Output) -> Result<i32> {
+    let dir = config.migrations_dir();
+
+    git(&["rev-parse", "--is-inside-work-tree"])
+        .context("migrate conflicts requires a git repository")?;
+    git(&[
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        &format!("{base}^{{commit}}"),
+    ])
+    .with_context(|| format!("Base ref '{}' not found", base))?;
+
+    let merge_base = git(&["merge-base", base, "HEAD"])
+        .with_context(|| format!("No common ancestor between HEAD and '{}'", base))?;
+    let merge_base = merge_base.trim().to_string();
+
+    // Added on this branch: merge-base vs working tree, plus untracked files
+    let mut branch_paths = lines(&git(&[
+        "diff",
+        "--name-only",
+        "--relative",
+        "--diff-filter=A",
+        &merge_base,
+        "--",
+        dir,
+    ])?);
+    branch_paths.extend(lines(&git(&[
+        "ls-files",
+        "--others",
+        "--exclude-standard",
+        "--",
+        dir,
+    ])?));
+    branch_paths.sort();
+    branch_paths.dedup();
+
+    let branch: Vec<MigrationFile> = branch_paths
+        .iter()
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(path).ok()?;
+            migration_file(path, &content)
+        })
+        .collect();
+
+    // Added on base since the branch point
+    let base_paths = lines(&git(&[
+        "diff",
+        "--name-only",
+        "--relative",
+        "--diff-filter=A",
+        &merge_base,
+        base,
+        "--",
+        dir,
+    ])?);
+    let mut base_added = Vec::new();
+    for path in &base_paths {
+        let content = git(&["show", &format!("{base}:./{path}")])?;
+        if let Some(file) = migration_file(path, &content) {
+            base_added.push(file);
+        }
+    }
+
+    // Newest migration anywhere on base, for ordering checks
+    let base_latest = lines(&git(&["ls-tree", "-r", "--name-only", base, "--", dir])?)
+        .iter()
+        .filter_map(|path| version_of(path))
+        .max();
+
+    let conflicts = analyze(&branch, &base_added, base_latest.as_deref(), base);
+
+    let response = ConflictsResponse {
+        ok: true,
+        base: base.to_string(),
+        merge_base,
+        branch_migrations: branch.iter().map(MigrationFile::label).collect(),
+        base_migrations: base_added.iter().map(MigrationFile::label).collect(),
+        conflicts,
+    };
+
+    if output.is_json() {
+        output.json(&response)?;
+    } else {
+        print_human(&response, output.is_quiet());
+    }
+
+    let has_critical = response.has_critical();
+    let has_warning = !response.conflicts.is_empty();
+    Ok(exit_codes::for_finding(output.is_json(), has_critical, has_warning).unwrap_or(0))
+}
+
+fn print_human(response: &ConflictsResponse, quiet: bool) {
+    if !quiet {
+        println!(
+            "Comparing migrations against {} (merge-base {})",
+            response.base.bold(),
+            &response.merge_base[..response.merge_base.len().min(12)]
+        );
+        println!(
+            "  This branch adds {} migration(s); {} added {} since the merge-base",
+            response.branch_migrations.len(),
+            response.base,
+            response.base_migrations.len()
+        );
+        println!();
+    }
+
+    if response.conflicts.is_empty() {
+        if !quiet {
+            println!("{}", "No migration conflicts found.".green());
+        }
+        return;
+    }
+
+    for conflict in &response.conflicts {
+        let marker = match conflict.severity {
+            ConflictSeverity::Critical => "✗".red(),
+            ConflictSeverity::Warning => "⚠".yellow(),
+        };
+        println!("{} {}", marker, conflict.message);
+    }
+}
+
+/// Compare branch migrations against base migrations and collect conflicts.
+fn analyze(
+    branch: &[MigrationFile],
+    base_added: &[MigrationFile],
+    base_latest: Option<&str>,
+    base: &str,
+) -> Vec<MigrationConflict> {
+    let mut conflicts = Vec::new();
+
+    for mine in branch {
+        for theirs in base_added.iter().filter(|t| t.version == mine.version) {
+            conflicts.push(MigrationConflict {
+                kind: ConflictKind::DuplicateVersion,
+                severity: ConflictSeverity::Critical,
+                migration: mine.label(),
+                other: theirs.label(),
+                table: None,
+                message: format!(
+                    "duplicate version {}: {} (this branch) and {} ({}). Regenerate with `pgcrate migrate new`.",
+                    mine.version,
+                    mine.label(),
+                    theirs.label(),
+                    base
+                ),
+            });
+        }
+
+        if let Some(latest) = base_latest {
+            if mine.version.as_str() < latest
+                && !base_added.iter().any(|t| t.version == mine.version)
+            {
+                conflicts.push(MigrationConflict {
+                    kind: ConflictKind::OutOfOrder,
+                    severity: ConflictSeverity::Warning,
+                    migration: mine.label(),
+                    other: latest.to_string(),
+                    table: None,
+                    message: format!(
+                        "out of order: {} sorts before {} already on {}; environments that applied {} will run it out of order. Rename it with a newer timestamp.",
+                        mine.label(),
+                        latest,
+                        base,
+                        base
+                    ),
+                });
+            }
+        }
+
+        for theirs in base_added {
+            for table in mine.tables.intersection(&theirs.tables) {
+                conflicts.push(MigrationConflict {
+                    kind: ConflictKind::SameTable,
+                    severity: ConflictSeverity::Warning,
+                    migration: mine.label(),
+                    other: theirs.label(),
+                    table: Some(table.clone()),
+                    message: format!(
+                        "same table {}: {} (this branch) and {} ({}) both change it",
+                        table,
+                        mine.label(),
+                        theirs.label(),
+                        base
+                    ),
+                });
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Build a MigrationFile from a path and its contents; non-migration files are skipped.
+fn migration_file(path: &str, content: &str) -> Option<MigrationFile> {
+    let filename = Path::new(path).file_name()?.to_string_lossy().to_string();
+    let (version, name) = parse_migration_filename(&filename).ok()?;
+    let up_sql = parse_migration_content(content, &path)
+        .map(|(up, _)| up)
+        .unwrap_or_else(|_| content.to_string());
+    Some(MigrationFile {
+        version,
+        name,
+        tables: ddl_tables(&up_sql),
+    })
+}
+
+fn version_of(path: &str) -> Option<String> {
+    let filename = Path::new(path).file_name()?.to_string_lossy().to_string();
+    parse_migration_filename(&filename).ok().map(|(v, _)| v)
+}
+
+/// Tables targeted by CREATE/ALTER/DROP TABLE and CREATE INDEX ... ON statements.
+fn ddl_tables(sql: &str) -> BTreeSet<String> {
+    const IDENT: &str =
+        r#"((?:"[^"]+"|[a-z_][a-z0-9_$]*)(?:\s*\.\s*(?:"[^"]+"|[a-z_][a-z0-9_$]*))?)"#;
+    let patterns = [
+        format!(
+            r"(?is)\b(?:create|alter|drop)\s+(?:(?:global|local)\s+)?(?:unlogged\s+|temp\s+|temporary\s+)?table\s+(?:if\s+(?:not\s+)?exists\s+)?(?:only\s+)?{IDENT}"
+        ),
+        format!(
+            r"(?is)\bcreate\s+(?:unique\s+)?index\s+(?:concurrently\s+)?(?:if\s+not\s+exists\s+)?(?:[^\s(]+\s+)?on\s+(?:only\s+)?{IDENT}"
+        ),
+    ];
+
+    let stripped = strip_comments_and_literals(sql);
+    let mut tables = BTreeSet::new();
+    for pattern in &patterns {
+        let re = Regex::new(pattern).expect("valid pattern");
+        for cap in re.captures_iter(&stripped) {
+            tables.insert(normalize_table(&cap[1]));
+        }
+    }
+    tables
+}
+
+/// Normalize a possibly-quoted, possibly-qualified name to `schema.table`.
+fn normalize_table(raw: &str) -> String {
+    let parts: Vec<String> = raw
+        .split('.')
+        .map(|p| {
+            let p = p.trim();
+            if p.starts_with('"') && p.ends_with('"') && p.len() >= 2 {
+                p[1..p.len() - 1].to_string()
+            } else {
+                p.to_lowercase()
+            }
+        })
+        .collect();
+    match parts.as_slice() {
+        [table] => format!("public.{}", table),
+        _ => parts.join("."),
+    }
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("Failed to run git. Is git installed?")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn lines(s: &str) -> Vec<String> {
+    s.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(version: &str, name: &str, sql: &str) -> MigrationFile {
+        MigrationFile {
+            version: version.to_string(),
+            name: name.to_string(),
+            tables: ddl_tables(sql),
+        }
+    }
+
+    #[test]
+    fn test_ddl_tables_extracts_targets() {
+        let tables = ddl_tables(
+            r#"CREATE TABLE IF NOT EXISTS app.users (id int);
+               ALTER TABLE ONLY "Orders" ADD COLUMN total numeric;
+               CREATE UNIQUE INDEX CONCURRENTLY users_email_idx ON app.users (email);
+               -- DROP TABLE commented_out;
+               INSERT INTO audit VALUES ('drop table not_ddl');"#,
+        );
+        assert_eq!(
+            tables.into_iter().collect::<Vec<_>>(),
+            vec!["app.users".to_string(), "public.Orders".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_analyze_duplicate_version_is_critical() {
+        let branch = vec![file("20250102000000", "add_a", "SELECT 1;")];
+        let base = vec![file("20250102000000", "add_b", "SELECT 1;")];
+        let conflicts = analyze(&branch, &base, Some("20250102000000"), "main");
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::DuplicateVersion);
+        assert_eq!(conflicts[0].severity, ConflictSeverity::Critical);
+    }
+
+    #[test]
+    fn test_analyze_out_of_order() {
+        let branch = vec![file("20250101000000", "mine", "SELECT 1;")];
+        let conflicts = analyze(&branch, &[], Some("20250103000000"), "main");
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::OutOfOrder);
+        assert_eq!(conflicts[0].other, "20250103000000");
+    }
+
+    #[test]
+    fn test_analyze_same_table() {
+        let branch = vec![file(
+            "20250105000000",
+            "add_email",
+            "ALTER TABLE users ADD COLUMN email text;",
+        )];
+        let base = vec![file(
+            "20250104000000",
+            "add_name",
+            "alter table public.users add column name text;",
+        )];
+        let conflicts = analyze(&branch, &base, Some("20250104000000"), "main");
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::SameTable);
+        assert_eq!(conflicts[0].table.as_deref(), Some("public.users"));
+    }
+
+    #[test]
+    fn test_analyze_clean_branch() {
+        let branch = vec![file(
+            "20250105000000",
+            "add_posts",
+            "CREATE TABLE posts (id int);",
+        )];
+        let base = vec![file(
+            "20250104000000",
+            "add_name",
+            "ALTER TABLE users ADD COLUMN name text;",
+        )];
+        assert!(analyze(&branch, &base, Some("20250104000000"), "main").is_empty());
+    }
+}
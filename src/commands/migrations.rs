@@ -1,17 +1,25 @@
 //! Migration commands for pgcrate CLI.
 
 use crate::config::{url_matches_production_patterns, Config};
-use crate::migrations::{discover_migrations, load_migrations, Migration};
-use crate::output::{MigrationInfo, Output, StatusCounts, StatusResponse};
+use crate::migrations::{
+    discover_migrations, load_migrations, non_transactional_statements, Migration,
+};
+use crate::output::{
+    MigrationExecution, MigrationInfo, Output, StatusCounts, StatusResponse, TOOL_VERSION,
+};
 use anyhow::{bail, Result};
 use chrono::Utc;
 use colored::Colorize;
 use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
+use std::time::Instant;
 use tokio_postgres::Client;
 
-use super::{connect, get_applied_versions, run_migration, SCHEMA_MIGRATIONS_TABLE};
+use super::{
+    connect, db_error_message, get_applied_versions, get_migration_records, record_migration,
+    run_migration, SCHEMA_MIGRATIONS_TABLE,
+};
 
 pub async fn up(
     database_url: &str,
@@ -19,6 +27,8 @@ pub async fn up(
     quiet: bool,
     verbose: bool,
     dry_run: bool,
+    single_transaction: bool,
+    snapshot_before: bool,
 ) -> Result<(), anyhow::Error> {
     let client = connect(database_url).await?;
 
@@ -40,6 +50,10 @@ pub async fn up(
         return Ok(());
     }
 
+    if single_transaction {
+        check_transactional(&pending)?;
+    }
+
     if !quiet {
         println!(
             "{}",
@@ -47,6 +61,43 @@ pub async fn up(
         );
     }
 
+    // Saved before anything is applied; a failure prints how to get back
+    let snapshot = if snapshot_before {
+        let name = pre_migrate_snapshot_name(&pending[0].version, config)?;
+        if dry_run {
+            if !quiet {
+                println!("  {} snapshot {}", "[dry-run]".blue(), name);
+            }
+        } else {
+            save_pre_migrate_snapshot(database_url, &name, &pending, config, verbose).await?;
+            if !quiet {
+                println!("  Snapshot saved: {}", name);
+            }
+        }
+        Some(name)
+    } else {
+        None
+    };
+    let restore_hint = |e: anyhow::Error| {
+        if let Some(ref name) = snapshot {
+            eprintln!(
+                "{}",
+                format!(
+                    "Restore the pre-migration state with: pgcrate snapshot restore {} --yes",
+                    name
+                )
+                .yellow()
+            );
+        }
+        e
+    };
+
+    if single_transaction && !dry_run {
+        return up_single_transaction(&client, &pending, quiet, verbose)
+            .await
+            .map_err(restore_hint);
+    }
+
     for migration in pending {
         if dry_run {
             if !quiet {
@@ -67,7 +118,12 @@ pub async fn up(
             if verbose {
                 println!("\n{}", migration.up_sql);
             }
-            run_migration(&client, &migration).await?;
+            if let Err(e) = run_migration(&client, &migration).await {
+                if !quiet {
+                    println!(" {}", "failed".red());
+                }
+                return Err(restore_hint(e));
+            }
             if !quiet {
                 println!(" {}", "done".green());
             }
@@ -85,6 +141,135 @@ pub async fn up(
     Ok(())
 }
 
+/// `pre-migrate-<version>` for the first pending migration, with a timestamp
+/// suffix when a snapshot of that name is already kept
+fn pre_migrate_snapshot_name(version: &str, config: &Config) -> Result<String> {
+    let name = format!("pre-migrate-{}", version);
+    if super::snapshot::snapshot_name_taken(&name, config)? {
+        return Ok(format!("{}-{}", name, Utc::now().format("%Y%m%d%H%M%S")));
+    }
+    Ok(name)
+}
+
+async fn save_pre_migrate_snapshot(
+    database_url: &str,
+    name: &str,
+    pending: &[Migration],
+    config: &Config,
+    verbose: bool,
+) -> Result<()> {
+    let format = config
+        .snapshot
+        .as_ref()
+        .and_then(|s| s.default_format.as_deref())
+        .unwrap_or("custom");
+    let message = format!(
+        "Before migrate up to {}",
+        pending
+            .last()
+            .map(|m| m.version.as_str())
+            .unwrap_or_default()
+    );
+    super::snapshot_save(
+        database_url,
+        name,
+        Some(&message),
+        None,
+        None,
+        format,
+        false,
+        false,
+        &[],
+        false,
+        config,
+        true,
+        verbose,
+        false,
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!("Pre-migration snapshot failed: {}", e))
+}
+
+/// Refuse to start a single-transaction batch containing statements that
+/// PostgreSQL cannot run inside a transaction block.
+fn check_transactional(pending: &[Migration]) -> Result<()> {
+    let offenders: Vec<String> = pending
+        .iter()
+        .filter_map(|m| {
+            let found = non_transactional_statements(&m.up_sql);
+            (!found.is_empty()).then(|| format!("  {}_{}: {}", m.version, m.name, found.join(", ")))
+        })
+        .collect();
+
+    if !offenders.is_empty() {
+        bail!(
+            "--single-transaction cannot be used: these migrations contain statements that \
+             cannot run inside a transaction block:\n{}\n\
+             Apply them without --single-transaction, or move those statements into their own migration.",
+            offenders.join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Apply all pending migrations inside one transaction: either every migration
+/// is applied or none are.
+async fn up_single_transaction(
+    client: &Client,
+    pending: &[Migration],
+    quiet: bool,
+    verbose: bool,
+) -> Result<()> {
+    client.batch_execute("BEGIN").await?;
+
+    for migration in pending {
+        if !quiet {
+            print!("  {} {}...", migration.version, migration.name);
+        }
+        if verbose {
+            println!("\n{}", migration.up_sql);
+        }
+
+        let started = Instant::now();
+        if let Err(e) = client.batch_execute(&migration.up_sql).await {
+            client.batch_execute("ROLLBACK").await?;
+            if !quiet {
+                println!(" {}", "failed".red());
+            }
+            let _ = record_migration(client, migration, started, Some(&db_error_message(&e))).await;
+            return Err(anyhow::Error::from(e).context(format!(
+                "Migration {}_{} failed; rolled back all {} migration(s) in this transaction",
+                migration.version,
+                migration.name,
+                pending.len()
+            )));
+        }
+
+        if let Err(e) = record_migration(client, migration, started, None).await {
+            client.batch_execute("ROLLBACK").await?;
+            return Err(e.into());
+        }
+        if !quiet {
+            println!(" {}", "done".green());
+        }
+    }
+
+    client.batch_execute("COMMIT").await?;
+
+    if !quiet {
+        println!(
+            "{}",
+            format!(
+                "\nAll {} migration(s) applied in a single transaction.",
+                pending.len()
+            )
+            .green()
+        );
+    }
+
+    Ok(())
+}
+
 pub async fn down(
     database_url: &str,
     config: &Config,
@@ -290,35 +475,40 @@ pub async fn status(
     let migrations_dir = config.migrations_dir();
     let migrations = discover_migrations(Path::new(migrations_dir))?;
     let applied = get_applied_versions(&client).await?;
+    let records = get_migration_records(&client).await?;
 
     // Separate applied and pending migrations
     let (applied_migrations, pending_migrations): (Vec<_>, Vec<_>) = migrations
         .iter()
         .partition(|m| applied.contains(&m.version));
+    let failed_count = pending_migrations
+        .iter()
+        .filter(|m| records.get(&m.version).is_some_and(|r| !r.success))
+        .count();
 
     // JSON mode: output structured data
     if output.is_json() {
+        let info = |m: &&Migration| MigrationInfo {
+            version: m.version.clone(),
+            name: m.name.clone(),
+            has_down: m.down_sql.is_some(),
+            execution: records.get(&m.version).map(|r| MigrationExecution {
+                success: r.success,
+                applied_at: r.applied_at.map(|t| t.to_rfc3339()),
+                applied_by: r.applied_by.clone(),
+                duration_ms: r.duration_ms,
+                pgcrate_version: r.pgcrate_version.clone(),
+                error: r.error.clone(),
+            }),
+        };
         let response = StatusResponse {
             ok: true,
-            applied: applied_migrations
-                .iter()
-                .map(|m| MigrationInfo {
-                    version: m.version.clone(),
-                    name: m.name.clone(),
-                    has_down: m.down_sql.is_some(),
-                })
-                .collect(),
-            pending: pending_migrations
-                .iter()
-                .map(|m| MigrationInfo {
-                    version: m.version.clone(),
-                    name: m.name.clone(),
-                    has_down: m.down_sql.is_some(),
-                })
-                .collect(),
+            applied: applied_migrations.iter().map(info).collect(),
+            pending: pending_migrations.iter().map(info).collect(),
             counts: StatusCounts {
                 applied: applied_migrations.len(),
                 pending: pending_migrations.len(),
+                failed: failed_count,
                 total: migrations.len(),
             },
         };
@@ -346,12 +536,18 @@ pub async fn status(
                 } else {
                     "down: no".dimmed()
                 };
+                let timing = records
+                    .get(&mf.version)
+                    .and_then(|r| r.duration_ms)
+                    .map(|ms| format!(", {}ms", ms).dimmed())
+                    .unwrap_or_default();
                 println!(
-                    "  {} {}_{} ({})",
+                    "  {} {}_{} ({}{})",
                     "✓".green(),
                     mf.version,
                     mf.name,
-                    down_status
+                    down_status,
+                    timing
                 );
             }
         }
@@ -367,13 +563,27 @@ pub async fn status(
                 } else {
                     "down: no".dimmed()
                 };
-                println!(
-                    "  {} {}_{} ({})",
-                    "·".yellow(),
-                    mf.version,
-                    mf.name,
-                    down_status
-                );
+                match records.get(&mf.version).filter(|r| !r.success) {
+                    Some(record) => println!(
+                        "  {} {}_{} ({}) {}",
+                        "✗".red(),
+                        mf.version,
+                        mf.name,
+                        down_status,
+                        format!(
+                            "last run failed: {}",
+                            record.error.as_deref().unwrap_or("unknown error")
+                        )
+                        .red()
+                    ),
+                    None => println!(
+                        "  {} {}_{} ({})",
+                        "·".yellow(),
+                        mf.version,
+                        mf.name,
+                        down_status
+                    ),
+                }
             }
         }
     }
@@ -511,8 +721,10 @@ pub async fn baseline(
             } else {
                 client
                     .execute(
-                        "INSERT INTO pgcrate.schema_migrations (version) VALUES ($1) ON CONFLICT (version) DO NOTHING",
-                        &[&migration.version],
+                        "INSERT INTO pgcrate.schema_migrations (version, applied_by, pgcrate_version)
+                         VALUES ($1, current_user, $2)
+                         ON CONFLICT (version) DO UPDATE SET success = true, error = NULL",
+                        &[&migration.version, &TOOL_VERSION],
                     )
                     .await?;
                 if !quiet {
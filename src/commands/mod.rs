@@ -3,76 +3,124 @@
 //! Each submodule contains related command functions.
 
 mod anonymize;
+pub mod autovacuum;
 pub mod autovacuum_progress;
 pub mod bloat;
 mod bootstrap;
 pub mod cache;
 pub mod capabilities;
 pub mod checkpoints;
+pub mod cluster_triage;
+mod comment;
 pub mod config;
 pub mod connections;
 pub mod context;
+pub mod data_diff;
 mod db;
+pub mod diff3;
 mod doctor;
 pub mod explain;
 mod extension;
 pub mod fix;
+pub mod fleet;
 pub mod indexes;
+pub mod io;
+pub mod lock_graph;
 pub mod locks;
+pub mod logical_replication;
+mod migrate_conflicts;
 mod migrations;
 pub mod model;
+mod native_dump;
+pub mod partitions;
+pub mod persistence;
+mod plan;
+pub mod plan_baseline;
+pub mod pooler;
+pub mod prepared;
+pub mod profile;
 pub mod queries;
 pub mod replication;
-mod role;
+pub mod role;
 mod schema;
+pub mod schema_graph;
+pub mod security_audit;
 mod seed;
 pub mod sequences;
+pub mod slots;
 mod snapshot;
+mod snapshot_schedule;
 mod sql_cmd;
+pub mod statements;
 pub mod stats_age;
 pub mod storage;
+mod subset;
+pub mod temp;
+pub mod top;
+pub mod trend;
 pub mod triage;
 pub mod vacuum;
+pub mod vacuum_progress;
+pub mod wal;
+pub mod watch;
 pub mod xid;
 
 // Re-export snapshot commands from new module
 pub use snapshot::{
-    snapshot_delete, snapshot_info, snapshot_list, snapshot_restore, snapshot_save,
+    snapshot_delete, snapshot_diff, snapshot_info, snapshot_list, snapshot_preflight,
+    snapshot_prune, snapshot_restore, snapshot_save,
 };
+pub use snapshot_schedule::{snapshot_schedule, ScheduleOptions};
 
 // Re-export anonymize commands from new module
-pub use anonymize::{anonymize_dump, anonymize_setup};
+pub use anonymize::{anonymize_apply, anonymize_dump, anonymize_scan, anonymize_setup};
 
 // Re-export bootstrap command
 pub use bootstrap::bootstrap;
 
+// Re-export subset command
+pub use subset::subset;
+
 // Re-export doctor command from new module
 pub use doctor::doctor;
 
 // Re-export migration commands from new module
+pub use migrate_conflicts::migrate_conflicts;
 pub use migrations::{baseline, down, new_migration, status, up};
 
 // Re-export db commands from new module
 pub use db::{db_create, db_drop, reset};
 
 // Re-export schema commands from new module
-pub use schema::{describe, diff, generate, init};
+pub use schema::{
+    describe, describe_schema, describe_sequence, describe_type, diff, find, generate, init,
+};
+
+// Re-export plan command
+pub use plan::plan;
 
 // Re-export seed commands from new module
-pub use seed::{seed_diff, seed_list, seed_run, seed_validate};
+pub use seed::{seed_diff, seed_export, seed_generate, seed_list, seed_run, seed_validate};
 
 // Re-export sql/query command
 pub use sql_cmd::sql;
 
+// Re-export comment command
+pub use comment::comment_set;
+
 // Re-export extension commands from new module
 pub use extension::extension_list;
 
 // Re-export role and grants commands from new module
-pub use role::{grants, role_describe, role_list};
+pub use role::{grants, grants_matrix, role_describe, role_list};
 
 // Shared utilities used by command modules
 use crate::migrations::Migration;
+use crate::output::TOOL_VERSION;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::Instant;
 use tokio_postgres::{Client, NoTls};
 
 pub(crate) const SCHEMA_MIGRATIONS_TABLE: &str = r#"
@@ -80,9 +128,26 @@ CREATE SCHEMA IF NOT EXISTS pgcrate;
 CREATE TABLE IF NOT EXISTS pgcrate.schema_migrations (
     version TEXT PRIMARY KEY,
     applied_at TIMESTAMPTZ DEFAULT now()
-)
+);
+ALTER TABLE pgcrate.schema_migrations
+    ADD COLUMN IF NOT EXISTS applied_by TEXT,
+    ADD COLUMN IF NOT EXISTS duration_ms BIGINT,
+    ADD COLUMN IF NOT EXISTS pgcrate_version TEXT,
+    ADD COLUMN IF NOT EXISTS success BOOLEAN NOT NULL DEFAULT true,
+    ADD COLUMN IF NOT EXISTS error TEXT
 "#;
 
+/// Execution metadata recorded for a migration in pgcrate.schema_migrations.
+#[derive(Debug, Clone)]
+pub(crate) struct MigrationRecord {
+    pub applied_at: Option<DateTime<Utc>>,
+    pub applied_by: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub pgcrate_version: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
 pub(crate) async fn connect(database_url: &str) -> Result<Client> {
     let (client, connection) = tokio_postgres::connect(database_url, NoTls).await?;
 
@@ -94,12 +159,18 @@ pub(crate) async fn connect(database_url: &str) -> Result<Client> {
     Ok(client)
 }
 
+/// Versions of successfully applied migrations, in version order.
+///
+/// Reads `success` through `to_jsonb` so read-only callers (doctor) still work
+/// against tables created before the metadata columns existed.
 pub(crate) async fn get_applied_versions(
     client: &Client,
 ) -> Result<Vec<String>, tokio_postgres::Error> {
     let rows = client
         .query(
-            "SELECT version FROM pgcrate.schema_migrations ORDER BY version",
+            "SELECT version FROM pgcrate.schema_migrations m
+             WHERE (to_jsonb(m) ->> 'success') IS DISTINCT FROM 'false'
+             ORDER BY version",
             &[],
         )
         .await?;
@@ -107,17 +178,92 @@ pub(crate) async fn get_applied_versions(
     Ok(rows.iter().map(|r| r.get("version")).collect())
 }
 
+/// All recorded migrations (including failed attempts) keyed by version.
+/// Requires SCHEMA_MIGRATIONS_TABLE to have been applied.
+pub(crate) async fn get_migration_records(
+    client: &Client,
+) -> Result<HashMap<String, MigrationRecord>, tokio_postgres::Error> {
+    let rows = client
+        .query(
+            "SELECT version, applied_at, applied_by, duration_ms, pgcrate_version, success, error
+             FROM pgcrate.schema_migrations",
+            &[],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|r| {
+            (
+                r.get("version"),
+                MigrationRecord {
+                    applied_at: r.get("applied_at"),
+                    applied_by: r.get("applied_by"),
+                    duration_ms: r.get("duration_ms"),
+                    pgcrate_version: r.get("pgcrate_version"),
+                    success: r.get("success"),
+                    error: r.get("error"),
+                },
+            )
+        })
+        .collect())
+}
+
 pub(crate) async fn run_migration(client: &Client, migration: &Migration) -> Result<()> {
+    let started = Instant::now();
+
     // Run migration SQL
-    client.batch_execute(&migration.up_sql).await?;
+    if let Err(e) = client.batch_execute(&migration.up_sql).await {
+        // Best effort: record the failed attempt so `migrate status` can surface it.
+        // A migration that opened its own transaction leaves the session aborted,
+        // so clear that first.
+        let _ = client.batch_execute("ROLLBACK").await;
+        let _ = record_migration(client, migration, started, Some(&db_error_message(&e))).await;
+        return Err(e.into());
+    }
 
     // Record in schema_migrations
+    record_migration(client, migration, started, None).await?;
+
+    Ok(())
+}
+
+/// Server-side message for a database error, falling back to the client error text.
+pub(crate) fn db_error_message(e: &tokio_postgres::Error) -> String {
+    e.as_db_error()
+        .map(|db| db.message().to_string())
+        .unwrap_or_else(|| e.to_string())
+}
+
+/// Upsert the execution metadata for a migration run.
+pub(crate) async fn record_migration(
+    client: &Client,
+    migration: &Migration,
+    started: Instant,
+    error: Option<&str>,
+) -> Result<(), tokio_postgres::Error> {
+    let duration_ms = started.elapsed().as_millis() as i64;
+    let success = error.is_none();
     client
         .execute(
-            "INSERT INTO pgcrate.schema_migrations (version) VALUES ($1)",
-            &[&migration.version],
+            "INSERT INTO pgcrate.schema_migrations
+                 (version, applied_by, duration_ms, pgcrate_version, success, error)
+             VALUES ($1, current_user, $2, $3, $4, $5)
+             ON CONFLICT (version) DO UPDATE SET
+                 applied_at = now(),
+                 applied_by = EXCLUDED.applied_by,
+                 duration_ms = EXCLUDED.duration_ms,
+                 pgcrate_version = EXCLUDED.pgcrate_version,
+                 success = EXCLUDED.success,
+                 error = EXCLUDED.error",
+            &[
+                &migration.version,
+                &duration_ms,
+                &TOOL_VERSION,
+                &success,
+                &error,
+            ],
         )
         .await?;
-
     Ok(())
 }
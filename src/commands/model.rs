@@ -1,16 +1,29 @@
 use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
 use colored::Colorize;
+use futures_util::{stream, StreamExt};
 use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::io::IsTerminal;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
 
 use crate::config::Config;
+use crate::introspect;
 use crate::model::{
-    apply_selectors, compile_model, ensure_schema, execute_model, generate_first_run_sql,
-    generate_merge_sql, generate_upsert_sql, lint_deps as model_lint_deps, load_project,
-    qualify_model_sql, rewrite_deps_line, rewrite_model_body_sql, topo_sort, topo_sort_layers,
-    Model, Project, Relation, Test,
+    add_sources, apply_selectors, apply_selectors_with_state, clone_sql, compile_model,
+    contract_violations, ensure_schema, execute_model, foreign_sources, generate_first_run_sql,
+    generate_insert_sql, generate_merge_sql, generate_replace_sql, generate_upsert_sql,
+    has_template_placeholders, init_foreign_sources, inline_ephemeral, is_unchanged,
+    lint_deps as model_lint_deps, load_model_state, load_project, load_project_with_vars,
+    page_path, plan_models, project_column_lineage, qualify_model_sql, record_model_state,
+    render_index, render_model, rewrite_deps_line, rewrite_model_body_sql, staging_model_sql,
+    topo_sort, topo_sort_layers, CloneKind, ColumnLineage, DocColumn, ExecuteResult, Manifest,
+    Model, ModelRunResult, Project, Relation, RunInvocation, RunResults, RunStatus, Test,
+    TestOutcome, TestSeverity,
 };
 use crate::tips::{show_tip, TipContext};
 
@@ -51,11 +64,13 @@ pub fn compile(
     config: &Config,
     selectors: &[String],
     excludes: &[String],
+    vars: &[(String, String)],
     init_models_dir: bool,
     quiet: bool,
 ) -> Result<()> {
     maybe_init_models(root, config, init_models_dir, quiet)?;
-    let project = load_project(root, config).context("load project")?;
+    let project =
+        inline_ephemeral(&load_project_with_vars(root, config, vars).context("load project")?);
 
     let models_to_compile = apply_selectors(&project, selectors, excludes)?;
 
@@ -98,16 +113,59 @@ pub async fn run(
     database_url: &str,
     selectors: &[String],
     excludes: &[String],
+    vars: &[(String, String)],
     dry_run: bool,
     full_refresh: bool,
+    enforce_contracts: bool,
+    force: bool,
+    threads: Option<usize>,
+    keep_going: bool,
+    state: Option<&Path>,
+    defer: bool,
+    init_sources: bool,
     init_models_dir: bool,
     quiet: bool,
     verbose: bool,
 ) -> Result<()> {
     maybe_init_models(root, config, init_models_dir, quiet)?;
-    let project = load_project(root, config).context("load project")?;
+    let foreign = if init_sources {
+        let foreign = foreign_sources(config)?;
+        if foreign.is_empty() {
+            bail!("--init-sources: no [model.foreign_sources] configured in pgcrate.toml");
+        }
+        foreign
+    } else {
+        Vec::new()
+    };
+    let project =
+        inline_ephemeral(&load_project_with_vars(root, config, vars).context("load project")?);
+
+    let state = state.map(Manifest::load).transpose()?;
+    let mut models_to_run =
+        apply_selectors_with_state(&project, selectors, excludes, state.as_ref())?;
+    let deferred = match (&state, defer) {
+        (Some(manifest), true) => defer_upstream(&project, &mut models_to_run, manifest)?,
+        (None, true) => bail!("--defer requires --state PATH"),
+        _ => Vec::new(),
+    };
+    let (snapshots, rest): (Vec<Relation>, Vec<Relation>) =
+        models_to_run.into_iter().partition(|rel| {
+            matches!(
+                project.models[rel].header.materialized,
+                crate::model::Materialized::Snapshot
+            )
+        });
+    models_to_run = rest;
 
-    let models_to_run = apply_selectors(&project, selectors, excludes)?;
+    if !quiet {
+        for rel in &snapshots {
+            println!(
+                "{} {} (snapshot; run `pgcrate model snapshot`)",
+                "Skipped".dimmed(),
+                rel
+            );
+        }
+    }
 
     if models_to_run.is_empty() {
         if !quiet {
@@ -116,6 +174,12 @@ pub async fn run(
         return Ok(());
     }
 
+    if !quiet {
+        for rel in &deferred {
+            println!("{} {} (deferred to --state)", "Skipped".dimmed(), rel);
+        }
+    }
+
     if dry_run {
         println!("{}", "Compiled SQL (dry-run):".bold());
         for rel in &models_to_run {
@@ -131,81 +195,235 @@ pub async fn run(
         println!("{}", "Running with --full-refresh".yellow());
     }
 
+    let threads = threads.unwrap_or_else(|| config.model_threads()).max(1);
+    let layers = execution_layers(&project, &models_to_run)?;
+    let started = Instant::now();
+
     let client = connect(database_url).await?;
+    if init_sources {
+        init_foreign_sources(&client, &foreign, quiet).await?;
+    }
+    let mut missing = Vec::new();
+    for rel in &deferred {
+        if !relation_exists(&client, rel).await? {
+            missing.push(rel.to_string());
+        }
+    }
+    if !missing.is_empty() {
+        bail!(
+            "deferred model(s) not found in the target database: {}\n\
+             Build them first, or run without --defer to include them in the selection",
+            missing.join(", ")
+        );
+    }
 
-    for rel in &models_to_run {
-        let model = project.models.get(rel).unwrap();
+    // Views whose SQL and upstream are unchanged since their last build are left alone
+    let build_state = if force || full_refresh {
+        HashMap::new()
+    } else {
+        load_model_state(&client).await?
+    };
 
-        if ensure_schema(&client, &model.id.schema).await? && !quiet {
-            println!("{} schema '{}'", "Created".green(), model.id.schema);
+    // Create schemas up front so concurrent models never race on CREATE SCHEMA.
+    let schemas: BTreeSet<&str> = models_to_run
+        .iter()
+        .filter_map(|rel| project.models.get(rel))
+        .filter(|m| m.header.materialized.is_materialized())
+        .map(|m| m.id.schema.as_str())
+        .collect();
+    for schema in schemas {
+        if ensure_schema(&client, schema).await? && !quiet {
+            println!("{} schema '{}'", "Created".green(), schema);
         }
+    }
 
-        if !quiet {
-            print!("{} {}... ", "Running".cyan(), rel);
-        }
-        if verbose {
-            eprintln!("\n{}", dry_run_sql(model, full_refresh));
-        }
-        let exec = execute_model(&client, model, full_refresh).await?;
-        if !quiet {
-            let mut extra: Vec<String> = Vec::new();
-            if !model.header.tests.is_empty() {
-                extra.push(format!(
-                    "{} {}",
-                    model.header.tests.len(),
-                    pluralize(model.header.tests.len(), "test", "tests")
-                ));
-            }
+    // One connection per concurrent model, capped by the widest layer.
+    let widest = layers.iter().map(Vec::len).max().unwrap_or(1);
+    let mut pool = vec![client];
+    for _ in 1..threads.min(widest) {
+        pool.push(connect(database_url).await?);
+    }
+    let serial = pool.len() == 1;
+    let free = Mutex::new((0..pool.len()).rev().collect::<Vec<_>>());
+    let stop = AtomicBool::new(false);
 
-            let status = if let Some(inc) = exec.incremental {
-                let (action, verb) = match inc.action {
-                    crate::model::IncrementalAction::CreatedTable => ("created table", "inserted"),
-                    crate::model::IncrementalAction::Merged => ("merged", "affected"),
-                    crate::model::IncrementalAction::Upserted => ("upserted", "affected"),
-                };
-                let mut s = format!(
-                    "{} {}; {} {} {}",
-                    action,
-                    model.id,
-                    verb,
-                    inc.inserted,
-                    pluralize_u64(inc.inserted, "row", "rows")
-                );
-                if matches!(
-                    inc.action,
-                    crate::model::IncrementalAction::Merged
-                        | crate::model::IncrementalAction::Upserted
-                ) && inc.inserted == 0
-                {
-                    s.push_str(" (no new keys; existing keys may have been updated)");
+    let mut executed = 0;
+    let mut failures: Vec<anyhow::Error> = Vec::new();
+    let mut blocked: HashSet<Relation> = HashSet::new();
+    let mut succeeded: HashSet<Relation> = HashSet::new();
+    let mut unchanged: HashSet<Relation> = HashSet::new();
+    let mut errors: HashMap<Relation, String> = HashMap::new();
+    for layer in &layers {
+        let mut ready: Vec<&Model> = Vec::new();
+        for rel in layer {
+            let model = project.models.get(rel).unwrap();
+            if model.header.deps.iter().any(|d| blocked.contains(d)) {
+                blocked.insert(rel.clone());
+                if !quiet {
+                    println!("{} {} (upstream model failed)", "Skipped".yellow(), rel);
                 }
-                s
-            } else {
-                let mut s = format!("ok ({})", model.header.materialized.as_str());
-                if let Some(rows) = exec.rows_affected {
-                    s.push_str(&format!(
-                        " ({} {})",
-                        rows,
-                        pluralize_u64(rows, "row", "rows")
-                    ));
+                continue;
+            }
+            if !model.header.materialized.is_materialized() {
+                if !quiet {
+                    println!(
+                        "{} {} (ephemeral; inlined into downstream models)",
+                        "Skipped".dimmed(),
+                        rel
+                    );
                 }
-                s
-            };
+                continue;
+            }
+            if is_unchanged(&project, model, &build_state, &succeeded) {
+                unchanged.insert(rel.clone());
+                if !quiet {
+                    println!("{} {} (unchanged)", "Skipped".dimmed(), rel);
+                }
+                continue;
+            }
+            ready.push(model);
+        }
 
-            if !extra.is_empty() {
-                println!("{} {} ({})", "ok".green(), status, extra.join(", "));
-            } else {
-                println!("{} {}", "ok".green(), status);
+        let results: Vec<(&Model, Option<Result<ExecuteResult>>)> = stream::iter(ready)
+            .map(|model| {
+                let (pool, free, stop) = (&pool, &free, &stop);
+                async move {
+                    if stop.load(Ordering::SeqCst) {
+                        return (model, None);
+                    }
+                    let slot = free.lock().unwrap().pop().expect("connection available");
+                    if serial && !quiet {
+                        print!("{} {}... ", "Running".cyan(), model.id);
+                    }
+                    if verbose {
+                        eprintln!("\n{}", dry_run_sql(model, full_refresh));
+                    }
+                    let model_started = Instant::now();
+                    let result =
+                        execute_model(&pool[slot], model, full_refresh, enforce_contracts).await;
+                    free.lock().unwrap().push(slot);
+                    if result.is_err() && !keep_going {
+                        stop.store(true, Ordering::SeqCst);
+                    }
+                    if !quiet {
+                        let elapsed = format!("[{:.2}s]", model_started.elapsed().as_secs_f64());
+                        let line = match &result {
+                            Ok(exec) => format!(
+                                "{} {} {}",
+                                "ok".green(),
+                                run_status(model, exec),
+                                elapsed.dimmed()
+                            ),
+                            Err(_) => format!("{} {}", "failed".red(), elapsed.dimmed()),
+                        };
+                        if serial {
+                            println!("{}", line);
+                        } else {
+                            println!("{} {}... {}", "Running".cyan(), model.id, line);
+                        }
+                    }
+                    (model, Some(result))
+                }
+            })
+            .buffer_unordered(pool.len())
+            .collect()
+            .await;
+
+        for (model, result) in results {
+            match result {
+                Some(Ok(_)) => {
+                    executed += 1;
+                    succeeded.insert(model.id.clone());
+                    record_model_state(&pool[0], model).await?;
+                }
+                Some(Err(e)) => {
+                    blocked.insert(model.id.clone());
+                    if keep_going {
+                        eprintln!("{} {}: {:#}", "Error:".red().bold(), model.id, e);
+                    }
+                    errors.insert(model.id.clone(), format!("{:#}", e));
+                    failures.push(e);
+                }
+                None => {}
             }
         }
+        if !failures.is_empty() && !keep_going {
+            break;
+        }
+    }
+
+    // Record every materialized model; anything that didn't run counts as skipped
+    let run_results = RunResults::new(
+        RunInvocation {
+            selectors: selectors.to_vec(),
+            excludes: excludes.to_vec(),
+            vars: vars.to_vec(),
+            full_refresh,
+            enforce_contracts,
+            threads: Some(threads),
+            keep_going,
+            force,
+        },
+        layers
+            .iter()
+            .flatten()
+            .filter(|rel| project.models[*rel].header.materialized.is_materialized())
+            .map(|rel| {
+                let (status, message) = if succeeded.contains(rel) {
+                    (RunStatus::Success, None)
+                } else if unchanged.contains(rel) {
+                    (RunStatus::Unchanged, None)
+                } else if let Some(message) = errors.get(rel) {
+                    (RunStatus::Error, Some(message.clone()))
+                } else {
+                    (RunStatus::Skipped, None)
+                };
+                ModelRunResult {
+                    model: rel.to_string(),
+                    status,
+                    message,
+                }
+            })
+            .collect(),
+    );
+    let run_results_path = run_results.write(root)?;
+    if verbose {
+        eprintln!("Wrote {}", run_results_path.display());
+    }
+    if !failures.is_empty() && !keep_going {
+        return Err(failures.remove(0));
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+    if !failures.is_empty() {
+        let skipped = blocked.len() - failures.len();
+        bail!(
+            "{} model(s) failed, {} skipped due to upstream failures ({} executed in {:.2}s)",
+            failures.len(),
+            skipped,
+            executed,
+            elapsed
+        );
     }
 
+    let manifest_path = Manifest::from_project(&project).write(root)?;
+
     if !quiet {
+        let unchanged_note = if unchanged.is_empty() {
+            String::new()
+        } else {
+            format!(", {} unchanged", unchanged.len())
+        };
         println!(
-            "\n{} {} model(s) executed",
+            "\n{} {} model(s) executed{} in {:.2}s",
             "Done.".green().bold(),
-            models_to_run.len()
+            executed,
+            unchanged_note,
+            elapsed
         );
+        if verbose {
+            eprintln!("Wrote {}", manifest_path.display());
+        }
     }
 
     // Show contextual tip
@@ -223,7 +441,527 @@ pub async fn run(
     });
     show_tip(TipContext::RunSuccess { had_incremental }, quiet);
 
-    Ok(())
+    Ok(())
+}
+
+/// Re-run the models that failed or were skipped in the last `model run`.
+///
+/// Reads `target/run_results.json` and reuses that invocation's vars and
+/// flags; models that succeeded are not rebuilt.
+pub async fn retry(
+    root: &Path,
+    config: &Config,
+    database_url: &str,
+    quiet: bool,
+    verbose: bool,
+) -> Result<()> {
+    let previous = RunResults::load(root)?;
+    let unfinished = previous.unfinished();
+    if unfinished.is_empty() {
+        if !quiet {
+            println!("Nothing to retry: every model in the last run succeeded.");
+        }
+        return Ok(());
+    }
+    if !quiet {
+        println!("Retrying {} model(s) from the last run", unfinished.len());
+    }
+    let invocation = previous.invocation;
+    run(
+        root,
+        config,
+        database_url,
+        &unfinished,
+        &[],
+        &invocation.vars,
+        false,
+        invocation.full_refresh,
+        invocation.enforce_contracts,
+        invocation.force,
+        invocation.threads,
+        invocation.keep_going,
+        None,
+        false,
+        false,
+        false,
+        quiet,
+        verbose,
+    )
+    .await
+}
+
+#[derive(Serialize)]
+struct PlanRow {
+    model: String,
+    materialized: String,
+    needs_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upstream: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    last_built_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+struct PlanOutput {
+    models: Vec<PlanRow>,
+    /// Models that need to run, in DAG order
+    run: Vec<String>,
+}
+
+/// Show which models need to run, from `pgcrate.model_state` build times
+/// and source load times (`[model.freshness]` columns).
+pub async fn plan(
+    root: &Path,
+    config: &Config,
+    database_url: &str,
+    selectors: &[String],
+    excludes: &[String],
+    quiet: bool,
+    json: bool,
+) -> Result<()> {
+    let project = inline_ephemeral(&load_project(root, config).context("load project")?);
+    let selected: HashSet<Relation> = apply_selectors(&project, selectors, excludes)?
+        .into_iter()
+        .collect();
+
+    let client = connect(database_url).await?;
+    let state = load_model_state(&client).await?;
+    let mut loaded = HashMap::new();
+    for (source, column) in config.model_freshness() {
+        let rel = Relation::parse(&source).context("[model.freshness]")?;
+        let row = client
+            .query_one(
+                &format!(
+                    "SELECT max({})::timestamptz FROM {}.{}",
+                    crate::sql::quote_ident(&column),
+                    crate::sql::quote_ident(&rel.schema),
+                    crate::sql::quote_ident(&rel.name)
+                ),
+                &[],
+            )
+            .await
+            .with_context(|| format!("read freshness of {} ({})", rel, column))?;
+        // An empty source has never been loaded, which is known to be fresh
+        let at: Option<DateTime<Utc>> = row.get(0);
+        loaded.insert(rel, at.unwrap_or(DateTime::<Utc>::MIN_UTC));
+    }
+
+    let planned: Vec<_> = plan_models(&project, &state, &loaded)?
+        .into_iter()
+        .filter(|p| selected.contains(&p.id))
+        .collect();
+    let run: Vec<String> = planned
+        .iter()
+        .filter(|p| p.reason.is_some())
+        .map(|p| p.id.to_string())
+        .collect();
+
+    if json {
+        let output = PlanOutput {
+            models: planned
+                .iter()
+                .map(|p| PlanRow {
+                    model: p.id.to_string(),
+                    materialized: p.materialized.as_str().to_string(),
+                    needs_run: p.reason.is_some(),
+                    reason: p.reason.as_ref().map(|r| r.code().to_string()),
+                    upstream: p
+                        .reason
+                        .as_ref()
+                        .and_then(|r| r.relation())
+                        .map(|r| r.to_string()),
+                    detail: p.reason.as_ref().map(|r| r.to_string()),
+                    last_built_at: p.built_at,
+                })
+                .collect(),
+            run,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+    if quiet {
+        return Ok(());
+    }
+
+    if planned.is_empty() {
+        println!("No models found");
+        return Ok(());
+    }
+    println!(
+        "Plan: {} of {} model(s) need to run",
+        run.len(),
+        planned.len()
+    );
+    let width = planned
+        .iter()
+        .map(|p| p.id.to_string().len())
+        .max()
+        .unwrap_or(0);
+    for p in &planned {
+        let name = format!("{:width$}", p.id.to_string(), width = width);
+        let mat = p.materialized.as_str();
+        match &p.reason {
+            Some(reason) => println!("  {}  {:<12}  {}  {}", name, mat, "run".yellow(), reason),
+            None => println!(
+                "  {}  {:<12}  {}   {}",
+                name,
+                mat,
+                "ok".green(),
+                "up to date".dimmed()
+            ),
+        }
+    }
+    if !run.is_empty() {
+        println!("\nRun them with: pgcrate model run {}", run.join(" "));
+    }
+    Ok(())
+}
+
+/// Clone models built in `from_schema` into `to_schema`: views over the
+/// originals, or table copies for tables and materialized views with `copy`.
+/// Existing relations in `to_schema` with the same name are replaced.
+#[allow(clippy::too_many_arguments)]
+pub async fn clone_schema(
+    root: &Path,
+    config: &Config,
+    database_url: &str,
+    from_schema: &str,
+    to_schema: &str,
+    selectors: &[String],
+    excludes: &[String],
+    copy: bool,
+    dry_run: bool,
+    quiet: bool,
+) -> Result<()> {
+    if from_schema == to_schema {
+        bail!("--from-schema and --to-schema must differ");
+    }
+    let project = load_project(root, config).context("load project")?;
+    let models: Vec<Relation> = apply_selectors(&project, selectors, excludes)?
+        .into_iter()
+        .filter(|rel| rel.schema == from_schema)
+        .filter(|rel| project.models[rel].header.materialized.is_materialized())
+        .collect();
+    if models.is_empty() {
+        if !quiet {
+            println!("No models found in schema '{}'", from_schema);
+        }
+        return Ok(());
+    }
+
+    let client = connect(database_url).await?;
+    if !dry_run && ensure_schema(&client, to_schema).await? && !quiet {
+        println!("{} schema '{}'", "Created".green(), to_schema);
+    }
+    let mut cloned = 0;
+    for rel in &models {
+        let Some(relkind) = relation_kind(&client, rel).await? else {
+            if !quiet {
+                println!("{} {} (not built)", "Skipped".yellow(), rel);
+            }
+            continue;
+        };
+        let target = Relation {
+            schema: to_schema.to_string(),
+            name: rel.name.clone(),
+        };
+        let kind = CloneKind::for_relkind(&relkind, copy);
+        let existing = relation_kind(&client, &target).await?;
+        let sql = clone_sql(rel, &target, kind, existing.as_deref());
+        if dry_run {
+            println!("-- {} -> {}\n{}\n", rel, target, sql);
+            continue;
+        }
+        client
+            .batch_execute(&sql)
+            .await
+            .with_context(|| format!("clone {} to {}", rel, target))?;
+        cloned += 1;
+        if !quiet {
+            println!(
+                "{} {} -> {} ({})",
+                "Cloned".green(),
+                rel,
+                target,
+                kind.as_str()
+            );
+        }
+    }
+    if !dry_run && !quiet {
+        println!("\n{} model(s) cloned into {}", cloned, to_schema);
+    }
+    Ok(())
+}
+
+/// Capture new versions for snapshot models, in DAG order.
+///
+/// Snapshot models are skipped by `model run`; this is the only command that
+/// writes to their history tables.
+#[allow(clippy::too_many_arguments)]
+pub async fn snapshot(
+    root: &Path,
+    config: &Config,
+    database_url: &str,
+    selectors: &[String],
+    excludes: &[String],
+    vars: &[(String, String)],
+    dry_run: bool,
+    init_models_dir: bool,
+    quiet: bool,
+    verbose: bool,
+) -> Result<()> {
+    maybe_init_models(root, config, init_models_dir, quiet)?;
+    let project =
+        inline_ephemeral(&load_project_with_vars(root, config, vars).context("load project")?);
+
+    let mut snapshots = apply_selectors(&project, selectors, excludes)?;
+    snapshots.retain(|rel| {
+        matches!(
+            project.models[rel].header.materialized,
+            crate::model::Materialized::Snapshot
+        )
+    });
+    if snapshots.is_empty() {
+        if !quiet {
+            println!("No snapshot models found");
+        }
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("{}", "Compiled SQL (dry-run):".bold());
+        for rel in &snapshots {
+            println!("\n-- {}", rel);
+            println!("{}", dry_run_sql(&project.models[rel], false));
+        }
+        println!("\n{} snapshots would be captured", snapshots.len());
+        return Ok(());
+    }
+
+    let started = Instant::now();
+    let client = connect(database_url).await?;
+    for rel in &snapshots {
+        let model = &project.models[rel];
+        if ensure_schema(&client, &rel.schema).await? && !quiet {
+            println!("{} schema '{}'", "Created".green(), rel.schema);
+        }
+        if !quiet {
+            print!("{} {}... ", "Snapshotting".cyan(), rel);
+        }
+        if verbose {
+            eprintln!("\n{}", dry_run_sql(model, false));
+        }
+        let model_started = Instant::now();
+        match execute_model(&client, model, false, false).await {
+            Ok(exec) => {
+                if !quiet {
+                    let elapsed = format!("[{:.2}s]", model_started.elapsed().as_secs_f64());
+                    println!(
+                        "{} {} {}",
+                        "ok".green(),
+                        run_status(model, &exec),
+                        elapsed.dimmed()
+                    );
+                }
+            }
+            Err(e) => {
+                if !quiet {
+                    println!("{}", "failed".red());
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    if !quiet {
+        println!(
+            "\n{} {} snapshot(s) captured in {:.2}s",
+            "Done.".green().bold(),
+            snapshots.len(),
+            started.elapsed().as_secs_f64()
+        );
+    }
+    Ok(())
+}
+
+/// Group the selected models into DAG layers; models within a layer are independent.
+fn execution_layers(project: &Project, selected: &[Relation]) -> Result<Vec<Vec<Relation>>> {
+    let selected: HashSet<&Relation> = selected.iter().collect();
+    Ok(topo_sort_layers(project)?
+        .into_iter()
+        .map(|layer| {
+            layer
+                .into_iter()
+                .filter(|rel| selected.contains(rel))
+                .collect::<Vec<_>>()
+        })
+        .filter(|layer| !layer.is_empty())
+        .collect())
+}
+
+/// Resolve unselected upstream models against the `--state` manifest.
+///
+/// Upstream models recorded in the manifest are deferred: they are assumed to be
+/// built already and are not run. Upstream models the manifest has never seen
+/// cannot be deferred, so they are added to the selection. Returns the deferred
+/// models in DAG order.
+fn defer_upstream(
+    project: &Project,
+    selected: &mut Vec<Relation>,
+    manifest: &Manifest,
+) -> Result<Vec<Relation>> {
+    let mut run: HashSet<Relation> = selected.iter().cloned().collect();
+    let mut deferred: HashSet<Relation> = HashSet::new();
+    let mut pending: Vec<Relation> = selected.clone();
+    while let Some(rel) = pending.pop() {
+        for dep in &project.models[&rel].header.deps {
+            if run.contains(dep) || deferred.contains(dep) {
+                continue;
+            }
+            let Some(upstream) = project.models.get(dep) else {
+                continue; // source table
+            };
+            if manifest.contains(dep) && upstream.header.materialized.is_materialized() {
+                deferred.insert(dep.clone());
+            } else {
+                run.insert(dep.clone());
+                pending.push(dep.clone());
+            }
+        }
+    }
+
+    let order = topo_sort(project)?;
+    *selected = order.iter().filter(|r| run.contains(r)).cloned().collect();
+    Ok(order.into_iter().filter(|r| deferred.contains(r)).collect())
+}
+
+/// Check whether a table, view or materialized view exists
+async fn relation_exists(client: &tokio_postgres::Client, rel: &Relation) -> Result<bool> {
+    let row = client
+        .query_one(
+            "SELECT EXISTS (
+                SELECT 1 FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = $1 AND c.relname = $2
+            )",
+            &[&rel.schema, &rel.name],
+        )
+        .await
+        .with_context(|| format!("check {} exists", rel))?;
+    Ok(row.get(0))
+}
+
+/// pg_class relkind of a relation ("r", "v", "m", ...), or None if it doesn't exist
+async fn relation_kind(client: &tokio_postgres::Client, rel: &Relation) -> Result<Option<String>> {
+    let row = client
+        .query_opt(
+            "SELECT c.relkind::text FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2",
+            &[&rel.schema, &rel.name],
+        )
+        .await
+        .with_context(|| format!("check {} exists", rel))?;
+    Ok(row.map(|row| row.get(0)))
+}
+
+/// One-line summary of what running a model did, e.g. "ok (table) (3 rows)".
+fn run_status(model: &Model, exec: &ExecuteResult) -> String {
+    let status = if let Some(inc) = &exec.incremental {
+        let (action, verb) = match inc.action {
+            crate::model::IncrementalAction::CreatedTable => ("created table", "inserted"),
+            crate::model::IncrementalAction::Merged => ("merged", "affected"),
+            crate::model::IncrementalAction::Upserted => ("upserted", "affected"),
+            crate::model::IncrementalAction::Appended => ("appended to", "inserted"),
+            crate::model::IncrementalAction::DeleteInserted => ("replaced keys in", "inserted"),
+            crate::model::IncrementalAction::Overwritten => ("overwrote partitions in", "inserted"),
+        };
+        let mut s = format!(
+            "{} {}; {} {} {}",
+            action,
+            model.id,
+            verb,
+            inc.inserted,
+            pluralize_u64(inc.inserted, "row", "rows")
+        );
+        if matches!(
+            inc.action,
+            crate::model::IncrementalAction::Merged | crate::model::IncrementalAction::Upserted
+        ) && inc.inserted == 0
+        {
+            s.push_str(" (no new keys; existing keys may have been updated)");
+        }
+        if !inc.schema_changes.is_empty() {
+            s.push_str(&format!("; schema: {}", inc.schema_changes.join(", ")));
+        }
+        s
+    } else if let Some(action) = &exec.matview {
+        match action {
+            crate::model::MatviewAction::Created => format!("created {}", model.id),
+            crate::model::MatviewAction::Refreshed => format!("refreshed {}", model.id),
+            crate::model::MatviewAction::RefreshedConcurrently => {
+                format!("refreshed {} concurrently", model.id)
+            }
+        }
+    } else if let Some(snap) = &exec.snapshot {
+        if snap.created {
+            format!(
+                "created snapshot {}; inserted {} {}",
+                model.id,
+                snap.inserted,
+                pluralize_u64(snap.inserted, "row", "rows")
+            )
+        } else {
+            let mut s = format!(
+                "snapshotted {}; {} new, {} changed",
+                model.id, snap.inserted, snap.changed
+            );
+            if model
+                .header
+                .snapshot
+                .as_ref()
+                .is_some_and(|c| c.invalidate_hard_deletes)
+            {
+                s.push_str(&format!(", {} deleted", snap.invalidated));
+            }
+            s
+        }
+    } else {
+        let mut s = format!("ok ({})", model.header.materialized.as_str());
+        if let Some(rows) = exec.rows_affected {
+            s.push_str(&format!(
+                " ({} {})",
+                rows,
+                pluralize_u64(rows, "row", "rows")
+            ));
+        }
+        s
+    };
+
+    let status = if exec.grant_changes > 0 {
+        format!(
+            "{}; {} grant {}",
+            status,
+            exec.grant_changes,
+            pluralize(exec.grant_changes, "change", "changes")
+        )
+    } else {
+        status
+    };
+
+    if model.header.tests.is_empty() {
+        status
+    } else {
+        format!(
+            "{} ({} {})",
+            status,
+            model.header.tests.len(),
+            pluralize(model.header.tests.len(), "test", "tests")
+        )
+    }
 }
 
 /// Create a new model file at models/<schema>/<name>.sql
@@ -275,6 +1013,9 @@ pub fn new_model(
         crate::model::Materialized::View => "-- materialized: view\n-- deps: staging.source_table\n-- tests: not_null(id), unique(id)\n-- Tip: Run `pgcrate model status` before `pgcrate model run`\n-- Tip: Common layout is models/staging, models/intermediate, models/marts\n\n".to_string(),
         crate::model::Materialized::Table => "-- materialized: table\n-- deps: staging.source_table\n-- tests: not_null(id), unique(id)\n-- Tip: Run `pgcrate model status` before `pgcrate model run`\n-- Tip: Common layout is models/staging, models/intermediate, models/marts\n\n".to_string(),
         crate::model::Materialized::Incremental => "-- materialized: incremental\n-- unique_key: id\n-- watermark: updated_at\n-- deps: staging.source_table\n-- tests: not_null(id), unique(id)\n-- Tip: watermark filters to only new rows; remove it for full scan each run\n-- Tip: Add '-- lookback: 2 days' to reprocess recent data (late arrivals)\n-- Tip: For custom logic, use @base/@incremental sections instead\n\n".to_string(),
+        crate::model::Materialized::Matview => "-- materialized: materialized_view\n-- unique_index: id\n-- deps: staging.source_table\n-- tests: not_null(id), unique(id)\n-- Tip: unique_index enables REFRESH MATERIALIZED VIEW CONCURRENTLY (readers aren't blocked)\n-- Tip: Changing the query recreates the view; otherwise runs just refresh it\n\n".to_string(),
+        crate::model::Materialized::Snapshot => "-- materialized: snapshot\n-- unique_key: id\n-- updated_at: updated_at\n-- deps: staging.source_table\n-- Tip: Use '-- check_cols: name, email' (or 'all') when the source has no updated_at column\n-- Tip: Snapshots are built by `pgcrate model snapshot`, not `pgcrate model run`\n\n".to_string(),
+        crate::model::Materialized::Ephemeral => "-- materialized: ephemeral\n-- deps: staging.source_table\n-- Tip: Ephemeral models are not created; downstream models inline them as a CTE\n-- Tip: Add tests to the downstream models that use this one\n\n".to_string(),
     };
 
     let body = "SELECT 1 AS id;\n";
@@ -294,6 +1035,89 @@ pub fn new_model(
     Ok(())
 }
 
+/// Declare the tables in `schemas` as sources in `<models_dir>/sources.toml`,
+/// optionally writing a staging view stub per table
+/// (`staging/<schema>_<table>.sql`, kept if it exists unless `force`)
+pub async fn import_sources(
+    root: &Path,
+    config: &Config,
+    database_url: &str,
+    schemas: &[String],
+    staging: bool,
+    force: bool,
+    quiet: bool,
+) -> Result<()> {
+    let client = connect(database_url).await?;
+    let options = introspect::IntrospectOptions {
+        include_schemas: schemas.to_vec(),
+        exclude_schemas: Vec::new(),
+    };
+    let schema = introspect::introspect(&client, &options).await?;
+    // Partitions are read through their parent
+    let tables: Vec<&introspect::Table> =
+        schema.tables.iter().filter(|t| !t.is_partition).collect();
+    if tables.is_empty() {
+        bail!("No tables found in schema(s): {}", schemas.join(", "));
+    }
+
+    let models_dir = root.join(config.models_dir());
+    fs::create_dir_all(&models_dir).with_context(|| format!("create {}", models_dir.display()))?;
+    let relations: Vec<Relation> = tables
+        .iter()
+        .map(|t| Relation {
+            schema: t.schema.clone(),
+            name: t.name.clone(),
+        })
+        .collect();
+    let (path, added) = add_sources(&models_dir, &relations)?;
+    if !quiet {
+        println!(
+            "{} {} source(s) in {} ({} new)",
+            "Declared".green(),
+            relations.len(),
+            path.strip_prefix(root).unwrap_or(&path).display(),
+            added
+        );
+    }
+
+    if !staging {
+        return Ok(());
+    }
+    let staging_dir = models_dir.join("staging");
+    fs::create_dir_all(&staging_dir)
+        .with_context(|| format!("create {}", staging_dir.display()))?;
+    let mut skipped = 0;
+    for (table, rel) in tables.iter().zip(&relations) {
+        let path = staging_dir.join(format!("{}_{}.sql", rel.schema, rel.name));
+        if path.exists() && !force {
+            skipped += 1;
+            continue;
+        }
+        let columns: Vec<String> = table.columns.iter().map(|c| c.name.clone()).collect();
+        fs::write(&path, staging_model_sql(rel, &columns))
+            .with_context(|| format!("write {}", path.display()))?;
+        if !quiet {
+            println!(
+                "{} {}",
+                "Created".green(),
+                path.strip_prefix(root).unwrap_or(&path).display()
+            );
+        }
+    }
+    if skipped > 0 && !quiet {
+        println!(
+            "{}",
+            format!(
+                "Kept {} existing staging model(s) (use --force to overwrite)",
+                skipped
+            )
+            .dimmed()
+        );
+    }
+
+    Ok(())
+}
+
 #[derive(Serialize)]
 struct ModelShowJson {
     ok: bool,
@@ -328,7 +1152,7 @@ pub async fn show(
     quiet: bool,
     json: bool,
 ) -> Result<()> {
-    let project = load_project(root, config).context("load project")?;
+    let project = inline_ephemeral(&load_project(root, config).context("load project")?);
     let rel = Relation::parse(id)?;
     let model = project
         .models
@@ -362,9 +1186,34 @@ pub async fn show(
                 .map(|c| c.name().to_string())
                 .collect();
 
-            let merge_sql = generate_merge_sql(model, &cols, incr_body, &model.header.unique_key);
-            let upsert_sql = generate_upsert_sql(model, &cols, incr_body, &model.header.unique_key);
-            (Some(create_sql), Some(merge_sql), Some(upsert_sql), None)
+            match model
+                .header
+                .strategy
+                .unwrap_or(crate::model::IncrementalStrategy::Merge)
+            {
+                crate::model::IncrementalStrategy::Merge => {
+                    let merge_sql =
+                        generate_merge_sql(model, &cols, incr_body, &model.header.unique_key);
+                    let upsert_sql =
+                        generate_upsert_sql(model, &cols, incr_body, &model.header.unique_key);
+                    (Some(create_sql), Some(merge_sql), Some(upsert_sql), None)
+                }
+                crate::model::IncrementalStrategy::Append => {
+                    let insert_sql = generate_insert_sql(model, &cols, incr_body) + ";";
+                    (Some(create_sql), None, None, Some(insert_sql))
+                }
+                crate::model::IncrementalStrategy::DeleteInsert
+                | crate::model::IncrementalStrategy::InsertOverwrite => {
+                    let match_cols = if model.header.partition_by.is_empty() {
+                        &model.header.unique_key
+                    } else {
+                        &model.header.partition_by
+                    };
+                    let replace_sql =
+                        generate_replace_sql(model, &cols, incr_body, match_cols).join(";\n") + ";";
+                    (Some(create_sql), None, None, Some(replace_sql))
+                }
+            }
         }
         _ => {
             let mut sql = crate::model::generate_run_sql(model);
@@ -421,7 +1270,15 @@ pub async fn show(
         );
     }
     if let Some(sql) = &run_sql {
-        println!("\n{}\n", sql);
+        if let Some(strategy) = model.header.strategy.filter(|_| create_sql.is_some()) {
+            println!(
+                "\n=== Incremental Run (@incremental) - strategy: {} ===\n{}\n",
+                strategy.as_str(),
+                sql
+            );
+        } else {
+            println!("\n{}\n", sql);
+        }
     }
 
     Ok(())
@@ -458,7 +1315,9 @@ pub async fn status(
     json: bool,
 ) -> Result<i32> {
     let project = load_project(root, config).context("load project")?;
-    let models = apply_selectors(&project, selectors, excludes)?;
+    let mut models = apply_selectors(&project, selectors, excludes)?;
+    // Ephemeral models have no database object to compare against
+    models.retain(|rel| project.models[rel].header.materialized.is_materialized());
 
     if models.is_empty() {
         if !quiet && !json {
@@ -475,17 +1334,24 @@ pub async fn status(
         let model = project.models.get(rel).unwrap();
         let expected_type = match model.header.materialized {
             crate::model::Materialized::View => "VIEW",
-            crate::model::Materialized::Table | crate::model::Materialized::Incremental => {
-                "BASE TABLE"
-            }
+            crate::model::Materialized::Table
+            | crate::model::Materialized::Incremental
+            | crate::model::Materialized::Snapshot => "BASE TABLE",
+            crate::model::Materialized::Ephemeral => "NONE",
+            crate::model::Materialized::Matview => "MATERIALIZED VIEW",
         }
         .to_string();
 
+        // information_schema.tables doesn't list materialized views
         let db_rows = client
             .query(
                 "SELECT table_type
                  FROM information_schema.tables
-                 WHERE table_schema = $1 AND table_name = $2",
+                 WHERE table_schema = $1 AND table_name = $2
+                 UNION ALL
+                 SELECT 'MATERIALIZED VIEW'
+                 FROM pg_matviews
+                 WHERE schemaname = $1 AND matviewname = $2",
                 &[&rel.schema, &rel.name],
             )
             .await
@@ -549,9 +1415,11 @@ pub async fn status(
                 let model = project.models.get(rel).unwrap();
                 let expected_type = match model.header.materialized {
                     crate::model::Materialized::View => "VIEW",
-                    crate::model::Materialized::Table | crate::model::Materialized::Incremental => {
-                        "BASE TABLE"
-                    }
+                    crate::model::Materialized::Table
+                    | crate::model::Materialized::Incremental
+                    | crate::model::Materialized::Snapshot => "BASE TABLE",
+                    crate::model::Materialized::Ephemeral => "NONE",
+                    crate::model::Materialized::Matview => "MATERIALIZED VIEW",
                 }
                 .to_string();
 
@@ -658,18 +1526,45 @@ pub async fn status(
     Ok(exit_code)
 }
 
+#[derive(Serialize)]
+struct ModelTestJson {
+    ok: bool,
+    passed: usize,
+    warned: usize,
+    failed: usize,
+    tests: Vec<ModelTestResultJson>,
+}
+
+#[derive(Serialize)]
+struct ModelTestResultJson {
+    model: String,
+    test: String,
+    /// pass, warn, fail or error (the test query itself failed)
+    status: &'static str,
+    severity: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failures: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stored_in: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
 /// Run data tests defined in model headers
-/// Returns exit code: 0=all pass, 1=failures, 2=error
+/// Returns exit code: 0=all pass (warnings allowed), 1=failures
+#[allow(clippy::too_many_arguments)]
 pub async fn test(
     root: &Path,
     config: &Config,
     database_url: &str,
     selectors: &[String],
     excludes: &[String],
+    store_failures: bool,
     init_models_dir: bool,
     quiet: bool,
+    json: bool,
 ) -> Result<i32> {
-    maybe_init_models(root, config, init_models_dir, quiet)?;
+    maybe_init_models(root, config, init_models_dir, quiet || json)?;
     let project = load_project(root, config).context("load project")?;
 
     let selected = apply_selectors(&project, selectors, excludes)?;
@@ -684,8 +1579,18 @@ pub async fn test(
         .filter(|m| !m.header.tests.is_empty())
         .collect();
 
+    let human = !quiet && !json;
     if models_with_tests.is_empty() {
-        if !quiet {
+        if json {
+            let payload = ModelTestJson {
+                ok: true,
+                passed: 0,
+                warned: 0,
+                failed: 0,
+                tests: Vec::new(),
+            };
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        } else if !quiet {
             println!("No tests found");
         }
         return Ok(0);
@@ -694,43 +1599,112 @@ pub async fn test(
     let client = connect(database_url).await?;
 
     let mut passed = 0;
+    let mut warned = 0;
     let mut failed = 0;
+    let mut results = Vec::new();
 
     for model in &models_with_tests {
-        if !quiet {
+        if human {
             println!("Testing {}...", model.id);
         }
 
-        for test in &model.header.tests {
+        for (index, test) in model.header.tests.iter().enumerate() {
+            let test_config = model.header.test_config(index);
             let result = run_single_test(&client, model, test).await;
+            let outcome = result.as_ref().ok().map(|n| test_config.outcome(*n));
+            let stored = match (outcome, store_failures) {
+                (Some(outcome), true) => {
+                    store_test_failures(&client, model, test, outcome != TestOutcome::Pass).await?
+                }
+                _ => None,
+            };
 
-            match result {
-                Ok(true) => {
+            let status = match (&result, outcome) {
+                (Ok(_), Some(TestOutcome::Pass)) => {
                     passed += 1;
-                    if !quiet {
+                    if human {
                         println!("  {}     {}", test.description(), "PASS".green());
                     }
+                    "pass"
                 }
-                Ok(false) => {
+                (Ok(n), Some(TestOutcome::Warn)) => {
+                    warned += 1;
+                    if human {
+                        println!(
+                            "  {}     {} ({} {})",
+                            test.description(),
+                            "WARN".yellow(),
+                            n,
+                            pluralize(*n as usize, "failure", "failures")
+                        );
+                    }
+                    "warn"
+                }
+                (Ok(n), _) => {
                     failed += 1;
-                    if !quiet {
-                        println!("  {}     {}", test.description(), "FAIL".red());
+                    if human {
+                        println!(
+                            "  {}     {} ({} {})",
+                            test.description(),
+                            "FAIL".red(),
+                            n,
+                            pluralize(*n as usize, "failure", "failures")
+                        );
                     }
+                    "fail"
                 }
-                Err(e) => {
+                (Err(e), _) => {
                     failed += 1;
-                    if !quiet {
+                    if human {
                         println!("  {}     {} ({})", test.description(), "ERROR".red(), e);
                     }
+                    "error"
+                }
+            };
+            if human {
+                if let Some((table, rows)) = &stored {
+                    println!(
+                        "    stored {} failing {} in {}",
+                        rows,
+                        pluralize_u64(*rows, "row", "rows"),
+                        table
+                    );
                 }
             }
+
+            results.push(ModelTestResultJson {
+                model: model.id.to_string(),
+                test: test.description(),
+                status,
+                severity: match test_config.severity {
+                    TestSeverity::Error => "error",
+                    TestSeverity::Warn => "warn",
+                },
+                failures: result.as_ref().ok().copied(),
+                stored_in: stored.map(|(table, _)| table),
+                error: result.as_ref().err().map(|e| format!("{:#}", e)),
+            });
         }
     }
 
-    if !quiet {
+    if json {
+        let payload = ModelTestJson {
+            ok: failed == 0,
+            passed,
+            warned,
+            failed,
+            tests: results,
+        };
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else if !quiet {
         println!(
-            "\nResults: {} passed, {} failed",
+            "\nResults: {} passed, {} warned, {} failed",
             passed.to_string().green(),
+            if warned > 0 {
+                warned.to_string().yellow()
+            } else {
+                warned.to_string().normal()
+            },
             if failed > 0 {
                 failed.to_string().red()
             } else {
@@ -742,34 +1716,75 @@ pub async fn test(
     Ok(if failed > 0 { 1 } else { 0 })
 }
 
+/// Schema holding the audit tables written by `model test --store-failures`
+const TEST_FAILURES_SCHEMA: &str = "pgcrate_test_failures";
+
+/// Replace a test's audit table with its failing rows. A passing test only drops
+/// the table left by an earlier failure. Returns the table and rows stored.
+async fn store_test_failures(
+    client: &tokio_postgres::Client,
+    model: &Model,
+    test: &Test,
+    failed: bool,
+) -> Result<Option<(String, u64)>> {
+    let table_name = test.failures_table_name(&model.id);
+    let table = format!(
+        "{}.{}",
+        crate::sql::quote_ident(TEST_FAILURES_SCHEMA),
+        crate::sql::quote_ident(&table_name)
+    );
+    client
+        .batch_execute(&format!("DROP TABLE IF EXISTS {}", table))
+        .await
+        .with_context(|| format!("drop {}", table))?;
+    if !failed {
+        return Ok(None);
+    }
+
+    ensure_schema(client, TEST_FAILURES_SCHEMA).await?;
+    let rows = client
+        .execute(
+            &format!("CREATE TABLE {} AS {}", table, test.failures_sql(&model.id)),
+            &[],
+        )
+        .await
+        .with_context(|| format!("store failures of {} on {}", test.description(), model.id))?;
+    Ok(Some((
+        format!("{}.{}", TEST_FAILURES_SCHEMA, table_name),
+        rows,
+    )))
+}
+
+/// Run a test and return its failure count (violating rows; duplicated keys for unique)
 async fn run_single_test(
     client: &tokio_postgres::Client,
     model: &Model,
     test: &Test,
-) -> Result<bool> {
+) -> Result<i64> {
     let sql = test.to_sql(&model.id);
     let rows = client
         .query(&sql, &[])
         .await
         .with_context(|| format!("execute test {} on {}", test.description(), model.id))?;
 
-    // For not_null/accepted_values/relationships: check if violations count > 0
-    // For unique: check if any rows returned (duplicates exist)
+    // For not_null/accepted_values/relationships: the violations count
+    // For unique: one row per duplicated key
     match test {
         Test::NotNull { .. } | Test::AcceptedValues { .. } | Test::Relationships { .. } => {
-            let count: i64 = rows[0].get("violations");
-            Ok(count == 0)
+            Ok(rows[0].get("violations"))
         }
-        Test::Unique { .. } => Ok(rows.is_empty()),
+        Test::Unique { .. } => Ok(rows.len() as i64),
     }
 }
 
 /// Generate markdown documentation for models
-pub fn docs(
+pub async fn docs(
     root: &Path,
     config: &Config,
+    database_url: Option<&str>,
     selectors: &[String],
     excludes: &[String],
+    format: &str,
     quiet: bool,
 ) -> Result<()> {
     let project = load_project(root, config).context("load project")?;
@@ -792,6 +1807,22 @@ pub fn docs(
     let docs_dir = root.join("target/docs");
     fs::create_dir_all(&docs_dir).context("create target/docs")?;
 
+    match format {
+        "markdown" => {}
+        "html" => {
+            return docs_html(
+                &project,
+                &selected,
+                &models_to_doc,
+                &docs_dir,
+                database_url,
+                quiet,
+            )
+            .await
+        }
+        _ => bail!("Unknown format: {}. Use: markdown, html", format),
+    }
+
     // Generate index.md with mermaid DAG (only when documenting all models)
     if documenting_all {
         let sorted = topo_sort(&project)?;
@@ -812,6 +1843,18 @@ pub fn docs(
         for rel in &sorted {
             index.push_str(&format!("- [{rel}]({}/{}.md)\n", rel.schema, rel.name));
         }
+        if !project.exposures.is_empty() {
+            index.push_str("\n## Exposures\n\n");
+            for exposure in &project.exposures {
+                let deps: Vec<String> = exposure.depends_on.iter().map(|d| d.to_string()).collect();
+                index.push_str(&format!(
+                    "- **{}** ({}) depends on {}\n",
+                    exposure.name,
+                    exposure.kind,
+                    deps.join(", ")
+                ));
+            }
+        }
         fs::write(docs_dir.join("index.md"), &index).context("write index.md")?;
     }
 
@@ -825,6 +1868,9 @@ pub fn docs(
             "**Materialized as:** {}\n\n",
             model.header.materialized.as_str()
         ));
+        if let Some(description) = &model.header.description {
+            doc.push_str(&format!("{description}\n\n"));
+        }
 
         if !model.header.deps.is_empty() {
             doc.push_str("## Dependencies\n\n");
@@ -842,6 +1888,15 @@ pub fn docs(
             doc.push('\n');
         }
 
+        let exposures = project.exposures_for(std::slice::from_ref(&model.id));
+        if !exposures.is_empty() {
+            doc.push_str("## Exposures\n\n");
+            for exposure in exposures {
+                doc.push_str(&format!("- {} ({})\n", exposure.name, exposure.kind));
+            }
+            doc.push('\n');
+        }
+
         doc.push_str("## SQL\n\n```sql\n");
         doc.push_str(&model.body_sql);
         doc.push_str("\n```\n");
@@ -865,6 +1920,83 @@ pub fn docs(
     Ok(())
 }
 
+/// Write the HTML site: index.html plus one page per model, with columns from
+/// the database when DATABASE_URL is set (falling back to columns: contracts)
+async fn docs_html(
+    project: &Project,
+    selected: &[Relation],
+    models_to_doc: &[&Model],
+    docs_dir: &Path,
+    database_url: Option<&str>,
+    quiet: bool,
+) -> Result<()> {
+    let client = match database_url {
+        Some(url) => Some(connect(url).await?),
+        None => {
+            if !quiet {
+                eprintln!(
+                    "{} DATABASE_URL not set; model pages show only declared columns: contracts",
+                    "Note:".yellow()
+                );
+            }
+            None
+        }
+    };
+
+    let index_path = docs_dir.join("index.html");
+    fs::write(&index_path, render_index(project, selected)?).context("write index.html")?;
+    if !quiet {
+        println!("{} {}", "Generated".green(), index_path.display());
+    }
+
+    for model in models_to_doc {
+        let mut columns: Vec<DocColumn> = match &client {
+            Some(client) => introspect::relation_columns(client, &model.id.schema, &model.id.name)
+                .await
+                .with_context(|| format!("read columns of {}", model.id))?
+                .into_iter()
+                .map(|c| DocColumn {
+                    name: c.name,
+                    data_type: c.data_type,
+                    nullable: Some(c.nullable),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        if columns.is_empty() {
+            columns = model
+                .header
+                .columns
+                .iter()
+                .map(|c| DocColumn {
+                    name: c.name.clone(),
+                    data_type: c.data_type.clone(),
+                    nullable: None,
+                })
+                .collect();
+        }
+
+        let path = docs_dir.join(page_path(&model.id));
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("create schema dir")?;
+        }
+        fs::write(&path, render_model(project, model, Some(&columns)))
+            .context("write model page")?;
+        if !quiet {
+            println!("{} {}", "Generated".green(), path.display());
+        }
+    }
+
+    if !quiet {
+        println!(
+            "\n{} Documentation written to {}",
+            "Done.".green().bold(),
+            index_path.display()
+        );
+    }
+    Ok(())
+}
+
 /// Show model dependency graph
 pub fn graph(
     root: &Path,
@@ -872,6 +2004,7 @@ pub fn graph(
     selectors: &[String],
     excludes: &[String],
     format: &str,
+    columns: bool,
     quiet: bool,
 ) -> Result<()> {
     let project = load_project(root, config).context("load project")?;
@@ -885,6 +2018,10 @@ pub fn graph(
         return Ok(());
     }
 
+    if columns {
+        return print_column_graph(&project, &models, format, quiet);
+    }
+
     match format {
         "ascii" => print_ascii_graph(&project, &models),
         "dot" => print_dot_graph(&project, &models),
@@ -896,7 +2033,7 @@ pub fn graph(
     Ok(())
 }
 
-fn print_ascii_graph(project: &crate::model::Project, _models: &[Relation]) {
+fn print_ascii_graph(project: &crate::model::Project, models: &[Relation]) {
     // Use layer-aware output for better execution order visibility
     let layers = match topo_sort_layers(project) {
         Ok(l) => l,
@@ -918,6 +2055,20 @@ fn print_ascii_graph(project: &crate::model::Project, _models: &[Relation]) {
             }
         }
     }
+
+    let exposures = project.exposures_for(models);
+    if !exposures.is_empty() {
+        println!("Exposures:");
+        for exposure in exposures {
+            let deps: Vec<_> = exposure.depends_on.iter().map(|d| d.to_string()).collect();
+            println!(
+                "  {} ({}) <- [{}]",
+                exposure.name,
+                exposure.kind,
+                deps.join(", ")
+            );
+        }
+    }
 }
 
 fn print_dot_graph(project: &crate::model::Project, models: &[Relation]) {
@@ -929,10 +2080,19 @@ fn print_dot_graph(project: &crate::model::Project, models: &[Relation]) {
             println!("    \"{}\" -> \"{}\";", dep, rel);
         }
     }
+    for exposure in project.exposures_for(models) {
+        println!(
+            "    \"exposure:{}\" [shape=box, style=dashed];",
+            exposure.name
+        );
+        for dep in &exposure.depends_on {
+            println!("    \"{}\" -> \"exposure:{}\";", dep, exposure.name);
+        }
+    }
     println!("}}");
 }
 
-fn print_json_graph(project: &crate::model::Project, _models: &[Relation]) {
+fn print_json_graph(project: &crate::model::Project, models: &[Relation]) {
     let layers = match topo_sort_layers(project) {
         Ok(l) => l,
         Err(e) => {
@@ -966,7 +2126,21 @@ fn print_json_graph(project: &crate::model::Project, _models: &[Relation]) {
             from, to, comma
         );
     }
-    println!("  ]");
+    println!("  ],");
+    let exposures: Vec<serde_json::Value> = project
+        .exposures_for(models)
+        .into_iter()
+        .map(|e| {
+            serde_json::json!({
+                "name": e.name,
+                "type": e.kind,
+                "owner": e.owner,
+                "url": e.url,
+                "depends_on": e.depends_on.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    println!("  \"exposures\": {}", serde_json::Value::Array(exposures));
     println!("}}");
 }
 
@@ -982,6 +2156,117 @@ fn print_mermaid_graph(project: &crate::model::Project, models: &[Relation]) {
             }
         }
     }
+    for exposure in project.exposures_for(models) {
+        for dep in &exposure.depends_on {
+            println!(
+                "    {} --> exposure_{}>{}]",
+                dep.name, exposure.name, exposure.name
+            );
+        }
+    }
+}
+
+/// Print column-level lineage edges (upstream.column -> model.column)
+fn print_column_graph(
+    project: &crate::model::Project,
+    models: &[Relation],
+    format: &str,
+    quiet: bool,
+) -> Result<()> {
+    if !matches!(format, "ascii" | "dot" | "json" | "mermaid") {
+        bail!("Unknown format: {}. Use: ascii, dot, json, mermaid", format);
+    }
+    let (lineage, errors) = project_column_lineage(project)?;
+    if !quiet {
+        for (rel, error) in &errors {
+            if models.contains(rel) {
+                eprintln!(
+                    "{} {}: column lineage unavailable: {}",
+                    "Warning:".yellow().bold(),
+                    rel,
+                    error
+                );
+            }
+        }
+    }
+    let selected: Vec<(&Relation, &Vec<ColumnLineage>)> = models
+        .iter()
+        .filter_map(|rel| lineage.get(rel).map(|columns| (rel, columns)))
+        .collect();
+    let edges: Vec<(String, String)> = selected
+        .iter()
+        .flat_map(|(rel, columns)| {
+            columns.iter().flat_map(move |column| {
+                column
+                    .sources
+                    .iter()
+                    .map(move |source| (source.to_string(), format!("{}.{}", rel, column.column)))
+            })
+        })
+        .collect();
+
+    match format {
+        "ascii" => {
+            for (rel, columns) in &selected {
+                println!("{}", rel);
+                for column in columns.iter() {
+                    let sources: Vec<String> =
+                        column.sources.iter().map(|s| s.to_string()).collect();
+                    if sources.is_empty() {
+                        println!("  {}", column.column);
+                    } else {
+                        println!("  {} <- [{}]", column.column, sources.join(", "));
+                    }
+                }
+            }
+        }
+        "dot" => {
+            println!("digraph columns {{");
+            println!("    rankdir=LR;");
+            for (from, to) in &edges {
+                println!("    \"{}\" -> \"{}\";", from, to);
+            }
+            println!("}}");
+        }
+        "json" => {
+            let models: Vec<serde_json::Value> = selected
+                .iter()
+                .map(|(rel, columns)| {
+                    serde_json::json!({
+                        "model": rel.to_string(),
+                        "columns": columns
+                            .iter()
+                            .map(|c| serde_json::json!({
+                                "name": c.column,
+                                "sources": c.sources.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+                            }))
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            let edges: Vec<serde_json::Value> = edges
+                .iter()
+                .map(|(from, to)| serde_json::json!({"from": from, "to": to}))
+                .collect();
+            let out = serde_json::json!({"models": models, "edges": edges});
+            println!("{}", serde_json::to_string_pretty(&out)?);
+        }
+        _ => {
+            // Mermaid node ids can't contain dots; label nodes with the full name
+            let node = |name: &str| {
+                let id: String = name
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                    .collect();
+                format!("{}[\"{}\"]", id, name)
+            };
+            println!("graph LR");
+            for (from, to) in &edges {
+                println!("    {} --> {}", node(from), node(to));
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Lint model dependencies - check declared deps match inferred deps
@@ -1136,7 +2421,15 @@ pub fn lint_qualify(
             model_issues.push(format!("unknown: {}", result.unknown.join(", ")));
         }
 
-        if fix && result.changed {
+        // Rewriting would bake resolved variable values into the file
+        let templated = fs::read_to_string(&model.path)
+            .map(|text| has_template_placeholders(&text))
+            .unwrap_or(false);
+        if fix && result.changed && templated {
+            model_issues.push(
+                "uses ${var:...}/${macro:...} placeholders; fix references manually".to_string(),
+            );
+        } else if fix && result.changed {
             if let Some(sql) = new_sql {
                 rewrite_model_body_sql(&model.path, &sql)?;
                 if !quiet {
@@ -1172,16 +2465,19 @@ pub fn lint_qualify(
     Ok(if issues > 0 { 1 } else { 0 })
 }
 
-/// Run all lints (deps + qualify)
+/// Run all lints (deps + qualify), plus `columns:` contracts when a database is available
 /// Returns exit code: 0=ok, 1=issues found
-pub fn check(
+pub async fn check(
     root: &Path,
     config: &Config,
+    database_url: Option<&str>,
     selectors: &[String],
     excludes: &[String],
     quiet: bool,
 ) -> Result<i32> {
     let project = load_project(root, config).context("load project")?;
+    // Contracts are checked against the SQL as it will run, with ephemeral deps inlined
+    let inlined = inline_ephemeral(&project);
 
     let selected = apply_selectors(&project, selectors, excludes)?;
     let models_to_check: Vec<&Model> = selected
@@ -1196,6 +2492,23 @@ pub fn check(
         return Ok(0);
     }
 
+    let with_contracts = models_to_check
+        .iter()
+        .filter(|m| !m.header.columns.is_empty())
+        .count();
+    let client = match database_url {
+        Some(url) if with_contracts > 0 => Some(connect(url).await?),
+        None if with_contracts > 0 && !quiet => {
+            eprintln!(
+                "{} DATABASE_URL not set; skipped columns: contracts on {} model(s)",
+                "Note:".yellow(),
+                with_contracts
+            );
+            None
+        }
+        _ => None,
+    };
+
     let mut total_issues = 0;
 
     for model in &models_to_check {
@@ -1259,6 +2572,17 @@ pub fn check(
             ));
         }
 
+        // Check columns: contract against what the database says the SELECT returns
+        if let Some(client) = &client {
+            if !model.header.columns.is_empty() {
+                match contract_violations(client, &inlined.models[&model.id]).await {
+                    Ok(violations) => model_issues
+                        .extend(violations.into_iter().map(|v| format!("contract: {}", v))),
+                    Err(e) => model_issues.push(format!("contract: {:#}", e)),
+                }
+            }
+        }
+
         if !model_issues.is_empty() {
             total_issues += 1;
             if !quiet {
@@ -1383,13 +2707,100 @@ SELECT 1 AS id, 'Hello from pgcrate models!' AS message;
 }
 
 fn dry_run_sql(model: &Model, full_refresh: bool) -> String {
+    let mut sql = String::new();
+    if !model.header.columns.is_empty() {
+        let columns: Vec<String> = model
+            .header
+            .columns
+            .iter()
+            .map(|c| format!("{} {}", c.name, c.data_type))
+            .collect();
+        sql.push_str(&format!(
+            "-- columns: {} (checked with --enforce-contracts)\n",
+            columns.join(", ")
+        ));
+    }
+    for hook in model.pre_hook_sql() {
+        sql.push_str(&format!("-- pre_hook\n{};\n", hook.trim_end_matches(';')));
+    }
+    sql.push_str(&dry_run_build_sql(model, full_refresh));
+    for (privilege, roles) in &model.header.grants {
+        sql.push_str(&format!(
+            "-- grants: {} to {} (missing grants added, others revoked)\n",
+            privilege,
+            if roles.is_empty() {
+                "no roles".to_string()
+            } else {
+                roles.join(", ")
+            }
+        ));
+    }
+    for hook in model.post_hook_sql() {
+        sql.push_str(&format!("-- post_hook\n{};\n", hook.trim_end_matches(';')));
+    }
+    sql
+}
+
+fn dry_run_build_sql(model: &Model, full_refresh: bool) -> String {
     match model.header.materialized {
         crate::model::Materialized::Incremental if !full_refresh => {
             let body = model.body_sql.trim().trim_end_matches(';').trim();
-            let uk = model.header.unique_key.join(", ");
+            let strategy = model
+                .header
+                .strategy
+                .unwrap_or(crate::model::IncrementalStrategy::Merge);
+            let uk = match strategy {
+                crate::model::IncrementalStrategy::InsertOverwrite => {
+                    format!("partition_by: {}", model.header.partition_by.join(", "))
+                }
+                _ => format!("unique_key: {}", model.header.unique_key.join(", ")),
+            };
+            let schema_change = model
+                .header
+                .on_schema_change
+                .map(|p| format!("\n-- on_schema_change: {}", p.as_str()))
+                .unwrap_or_default();
+            format!(
+                "-- incremental model: {} (strategy: {}, {}){}\n-- Note: {} SQL is generated at runtime based on existing table state\n{};\n",
+                model.id,
+                strategy.as_str(),
+                uk,
+                schema_change,
+                strategy.as_str(),
+                body
+            )
+        }
+        crate::model::Materialized::Snapshot => {
+            let strategy = match model.header.snapshot.as_ref().map(|c| &c.strategy) {
+                Some(crate::model::SnapshotStrategy::Timestamp { updated_at }) => {
+                    format!("timestamp on {}", updated_at)
+                }
+                Some(crate::model::SnapshotStrategy::Check { columns }) if columns.is_empty() => {
+                    "check on all columns".to_string()
+                }
+                Some(crate::model::SnapshotStrategy::Check { columns }) => {
+                    format!("check on {}", columns.join(", "))
+                }
+                None => "unknown".to_string(),
+            };
+            format!(
+                "-- snapshot model: {} (strategy: {}, unique_key: {})\n-- First run:\n{};\n-- Later runs close changed rows and insert new versions (generated at runtime)\n",
+                model.id,
+                strategy,
+                model.header.unique_key.join(", "),
+                crate::model::generate_create_sql(model)
+            )
+        }
+        crate::model::Materialized::Matview if !full_refresh => {
+            let mut run_sql = crate::model::generate_run_sql(model);
+            if !run_sql.trim_end().ends_with(';') {
+                run_sql.push(';');
+            }
             format!(
-                "-- incremental model: {} (unique_key: {})\n-- Note: MERGE is generated at runtime based on existing table state\n{};\n",
-                model.id, uk, body
+                "-- materialized view: {} (refreshed when the definition is unchanged)\n{};\n-- otherwise recreated:\n{}\n",
+                model.id,
+                crate::model::generate_refresh_sql(model),
+                run_sql
             )
         }
         _ => {
@@ -1482,8 +2893,28 @@ pub async fn move_model(
                 )
                 .await
                 .context("check if table exists")?;
+            let matview_row = client
+                .query_opt(
+                    "SELECT 1 FROM pg_matviews WHERE schemaname = $1 AND matviewname = $2",
+                    &[&source_rel.schema, &source_rel.name],
+                )
+                .await
+                .context("check if materialized view exists")?;
 
-            if view_row.is_some() {
+            if matview_row.is_some() {
+                let drop_sql = format!(
+                    "DROP MATERIALIZED VIEW {}.{} CASCADE",
+                    crate::sql::quote_ident(&source_rel.schema),
+                    crate::sql::quote_ident(&source_rel.name)
+                );
+                client
+                    .batch_execute(&drop_sql)
+                    .await
+                    .with_context(|| format!("drop materialized view {}", source_rel))?;
+                if !quiet {
+                    println!("{} materialized view {}", "Dropped".yellow(), source_rel);
+                }
+            } else if view_row.is_some() {
                 let drop_sql = format!(
                     "DROP VIEW {}.{} CASCADE",
                     crate::sql::quote_ident(&source_rel.schema),
@@ -0,0 +1,334 @@
+//! Plain SQL dumps without pg_dump, for `snapshot save --native` and
+//! `anonymize dump --schema`.
+//!
+//! The schema comes from `introspect` and the rows stream out with COPY, so
+//! these work in containers that only have pgcrate. Object ordering follows
+//! `introspect`, not pg_dump's dependency sort: functions come first (with
+//! body checks off), then tables and views, and everything that would slow
+//! down or block the load (indexes, constraints, triggers, materialized
+//! views) after the data.
+
+use anyhow::Result;
+use futures_util::StreamExt;
+use std::io::Write;
+use tokio_postgres::Client;
+
+use super::SCHEMA_MIGRATIONS_TABLE;
+use crate::introspect::{introspect, schema_to_sql, DatabaseSchema, IntrospectOptions};
+use crate::sql::quote_ident;
+
+/// Session settings at the top of a native dump
+const DUMP_SETTINGS: &str = "SET client_encoding = 'UTF8';
+SET standard_conforming_strings = on;
+SET check_function_bodies = false;
+";
+
+/// Schema DDL split around the data
+pub struct DumpSchema {
+    /// Extensions, schemas, types, sequences, functions, tables and views
+    pub pre_data: String,
+    /// Indexes, constraints, foreign keys, triggers and materialized views
+    pub post_data: String,
+    /// Tables that hold rows, as (schema, name); partitioned parents hold none
+    pub tables: Vec<(String, String)>,
+    /// Schemas whose sequences are reset after the data
+    pub schemas: Vec<String>,
+}
+
+impl DumpSchema {
+    pub async fn load(client: &Client, options: &IntrospectOptions) -> Result<DumpSchema> {
+        Ok(DumpSchema::from_schema(introspect(client, options).await?))
+    }
+
+    fn from_schema(schema: DatabaseSchema) -> DumpSchema {
+        let tables = schema
+            .tables
+            .iter()
+            .filter(|t| t.partition_info.is_none())
+            .map(|t| (t.schema.clone(), t.name.clone()))
+            .collect();
+        let schemas = schema.schemas.iter().map(|s| s.name.clone()).collect();
+
+        // Functions before tables, so views and defaults can call them;
+        // pg_get_functiondef leaves off the terminating semicolon
+        let functions = schema
+            .functions
+            .into_iter()
+            .map(|mut f| {
+                if !f.definition.trim_end().ends_with(';') {
+                    f.definition = format!("{};", f.definition.trim_end());
+                }
+                f
+            })
+            .collect();
+        let (types, _) = schema_to_sql(&DatabaseSchema {
+            extensions: schema.extensions,
+            schemas: schema.schemas,
+            collations: schema.collations,
+            enums: schema.enums,
+            domains: schema.domains,
+            composite_types: schema.composite_types,
+            sequences: schema.sequences,
+            functions,
+            ..Default::default()
+        });
+        let (relations, _) = schema_to_sql(&DatabaseSchema {
+            tables: schema.tables,
+            views: schema.views,
+            ..Default::default()
+        });
+        let (post_data, _) = schema_to_sql(&DatabaseSchema {
+            indexes: schema.indexes,
+            constraints: schema.constraints,
+            triggers: schema.triggers,
+            materialized_views: schema.materialized_views,
+            ..Default::default()
+        });
+
+        DumpSchema {
+            pre_data: format!("{}\n{}", types, relations),
+            post_data,
+            tables,
+            schemas,
+        }
+    }
+
+    /// Header, session settings and everything created before the data
+    pub fn write_pre_data(&self, writer: &mut dyn Write, title: &str) -> Result<()> {
+        writeln!(writer, "-- {}", title)?;
+        writeln!(writer, "-- Generated: {}", chrono::Utc::now().to_rfc3339())?;
+        writeln!(writer, "-- pgcrate version: {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(writer, "--")?;
+        writeln!(writer)?;
+        writeln!(writer, "{}", DUMP_SETTINGS)?;
+        writeln!(writer, "{}", self.pre_data)?;
+        Ok(())
+    }
+
+    /// Everything created after the data, then the sequence values
+    pub async fn write_post_data(&self, client: &Client, writer: &mut dyn Write) -> Result<()> {
+        writeln!(writer, "{}", self.post_data)?;
+        let sequences = sequence_values(client, &self.schemas).await?;
+        if !sequences.is_empty() {
+            writeln!(writer, "-- Sequence values")?;
+            writeln!(writer, "{}", sequences)?;
+        }
+        Ok(())
+    }
+}
+
+/// Columns COPY can load, in attnum order (generated columns are left out)
+pub async fn copy_columns(client: &Client, schema: &str, table: &str) -> Result<Vec<String>> {
+    let rows = client
+        .query(
+            "SELECT a.attname
+             FROM pg_attribute a
+             JOIN pg_class c ON c.oid = a.attrelid
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2
+               AND a.attnum > 0 AND NOT a.attisdropped AND a.attgenerated = ''
+             ORDER BY a.attnum",
+            &[&schema, &table],
+        )
+        .await?;
+    Ok(rows.iter().map(|r| r.get(0)).collect())
+}
+
+/// Stream `copy_sql` (a `COPY ... TO STDOUT`) into `writer` as a COPY block
+/// loading `table (columns)`; returns the bytes of row data written
+pub async fn write_copy_block(
+    client: &Client,
+    table: &str,
+    columns: &[String],
+    copy_sql: &str,
+    writer: &mut dyn Write,
+) -> Result<u64> {
+    let col_list: Vec<String> = columns.iter().map(|c| quote_ident(c)).collect();
+    writeln!(
+        writer,
+        "COPY {} ({}) FROM stdin;",
+        table,
+        col_list.join(", ")
+    )?;
+
+    let stream = client.copy_out(copy_sql).await?;
+    tokio::pin!(stream);
+    let mut bytes = 0;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        bytes += chunk.len() as u64;
+        writer.write_all(&chunk)?;
+    }
+
+    writeln!(writer, "\\.")?;
+    writeln!(writer)?;
+    Ok(bytes)
+}
+
+/// `setval` statements restoring the current value of every sequence used
+/// so far in `schemas`
+async fn sequence_values(client: &Client, schemas: &[String]) -> Result<String> {
+    let rows = client
+        .query(
+            "SELECT schemaname::text, sequencename::text, last_value
+             FROM pg_sequences
+             WHERE schemaname = ANY($1) AND last_value IS NOT NULL
+             ORDER BY 1, 2",
+            &[&schemas],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let sequence = format!("{}.{}", quote_ident(row.get(0)), quote_ident(row.get(1)));
+            format!(
+                "SELECT pg_catalog.setval('{}', {}, true);\n",
+                sequence.replace('\'', "''"),
+                row.get::<_, i64>(2)
+            )
+        })
+        .collect())
+}
+
+/// A plain SQL dump of the schemas `options` selects, with their rows unless
+/// `data` is false, and pgcrate's migration history so restores keep it
+pub async fn write_native_dump(
+    client: &Client,
+    options: &IntrospectOptions,
+    data: bool,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    let schema = DumpSchema::load(client, options).await?;
+    schema.write_pre_data(writer, "pgcrate native dump")?;
+
+    let has_migrations: bool = client
+        .query_one(
+            "SELECT to_regclass('pgcrate.schema_migrations') IS NOT NULL",
+            &[],
+        )
+        .await?
+        .get(0);
+    if has_migrations {
+        writeln!(writer, "-- Migration history")?;
+        writeln!(writer, "{};", SCHEMA_MIGRATIONS_TABLE.trim())?;
+        writeln!(writer)?;
+    }
+
+    if data {
+        writeln!(writer, "-- Data")?;
+        for (schema_name, table) in &schema.tables {
+            let qualified = format!("{}.{}", quote_ident(schema_name), quote_ident(table));
+            let columns = copy_columns(client, schema_name, table).await?;
+            let copy_sql = format!(
+                "COPY {} ({}) TO STDOUT",
+                qualified,
+                columns
+                    .iter()
+                    .map(|c| quote_ident(c))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            write_copy_block(client, &qualified, &columns, &copy_sql, writer).await?;
+        }
+    }
+    if has_migrations {
+        let table = "pgcrate.schema_migrations";
+        let columns = copy_columns(client, "pgcrate", "schema_migrations").await?;
+        let copy_sql = format!("COPY {} TO STDOUT", table);
+        write_copy_block(client, table, &columns, &copy_sql, writer).await?;
+    }
+
+    if data {
+        schema.write_post_data(client, writer).await?;
+    } else {
+        writeln!(writer, "{}", schema.post_data)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::introspect::{
+        Column, Constraint, ConstraintType, Function, FunctionKind, Index, SchemaInfo, Table,
+    };
+
+    fn table(name: &str) -> Table {
+        Table {
+            schema: "app".to_string(),
+            name: name.to_string(),
+            columns: vec![Column {
+                name: "id".to_string(),
+                data_type: "integer".to_string(),
+                nullable: false,
+                default: None,
+                generated: false,
+                identity: None,
+                is_serial: true,
+                is_primary_key: true,
+                comment: None,
+            }],
+            primary_key: None,
+            partition_info: None,
+            is_partition: false,
+            parent_schema: None,
+            parent_name: None,
+            partition_bound: None,
+            comment: None,
+            rls_enabled: false,
+            rls_forced: false,
+        }
+    }
+
+    #[test]
+    fn test_dump_schema_sections() {
+        let schema = DatabaseSchema {
+            schemas: vec![SchemaInfo {
+                name: "app".to_string(),
+            }],
+            tables: vec![table("users"), table("orders")],
+            indexes: vec![Index {
+                schema: "app".to_string(),
+                table_name: "orders".to_string(),
+                name: "orders_idx".to_string(),
+                definition: "CREATE INDEX orders_idx ON app.orders USING btree (id)".to_string(),
+            }],
+            constraints: vec![Constraint {
+                schema: "app".to_string(),
+                table_name: "orders".to_string(),
+                name: "orders_user_fk".to_string(),
+                constraint_type: ConstraintType::ForeignKey,
+                definition: "FOREIGN KEY (id) REFERENCES app.users(id)".to_string(),
+            }],
+            functions: vec![Function {
+                schema: "app".to_string(),
+                identity: "app.answer()".to_string(),
+                definition: "CREATE FUNCTION app.answer() RETURNS int LANGUAGE sql AS 'SELECT 42'"
+                    .to_string(),
+                kind: FunctionKind::Function,
+            }],
+            ..Default::default()
+        };
+        let dump = DumpSchema::from_schema(schema);
+
+        let function = dump.pre_data.find("CREATE FUNCTION").unwrap();
+        let table = dump
+            .pre_data
+            .find("CREATE TABLE \"app\".\"users\"")
+            .unwrap();
+        assert!(function < table);
+        assert!(dump.pre_data.contains("AS 'SELECT 42';"));
+        assert!(!dump.pre_data.contains("orders_idx"));
+        assert!(!dump.pre_data.contains("FOREIGN KEY"));
+        assert!(dump.post_data.contains("CREATE INDEX orders_idx"));
+        assert!(dump.post_data.contains("ADD CONSTRAINT \"orders_user_fk\""));
+        assert_eq!(
+            dump.tables,
+            vec![
+                ("app".to_string(), "users".to_string()),
+                ("app".to_string(), "orders".to_string())
+            ]
+        );
+        assert_eq!(dump.schemas, vec!["app".to_string()]);
+    }
+}
@@ -0,0 +1,744 @@
+//! Partitions command: Health of declaratively partitioned tables.
+//!
+//! Time-series tables partitioned by RANGE on a date/timestamp column need
+//! partitions created ahead of time; once the newest partition ends, inserts
+//! land in the default partition (or fail without one). Rows piling up in a
+//! default partition also block creating the partitions they belong to.
+//! Finally, one partition far larger than its siblings usually means a bad
+//! partition key or a stalled rotation job.
+
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::fix::common::{ActionGates, ActionType, Risk, StructuredAction};
+use crate::config::AlertIgnore;
+use crate::sql::quote_ident;
+
+/// Default number of future partitions expected to exist
+pub const DEFAULT_AHEAD: i64 = 2;
+/// Default largest/median partition size ratio that counts as skew
+pub const DEFAULT_SKEW_RATIO: f64 = 10.0;
+/// Partitions smaller than this are never reported as skewed
+const MIN_SKEW_BYTES: i64 = 8 * 1024 * 1024;
+
+/// Key types whose RANGE partitions are checked for future coverage
+const TIME_KEY_TYPES: &[&str] = &[
+    "date",
+    "timestamp without time zone",
+    "timestamp with time zone",
+];
+
+/// Partition status level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PartitionStatus {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl PartitionStatus {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            PartitionStatus::Healthy => "✓",
+            PartitionStatus::Warning => "⚠",
+            PartitionStatus::Critical => "✗",
+        }
+    }
+}
+
+/// Kind of partition problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// Not enough partitions exist ahead of the current time
+    MissingFuture,
+    /// The default partition holds rows
+    DefaultRows,
+    /// One partition is far larger than the median
+    Skew,
+}
+
+/// A single finding on a partitioned table
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionIssue {
+    pub kind: IssueKind,
+    pub status: PartitionStatus,
+    pub message: String,
+}
+
+/// The default partition of a table
+#[derive(Debug, Clone, Serialize)]
+pub struct DefaultPartition {
+    pub schema: String,
+    pub name: String,
+    pub has_rows: bool,
+    pub estimated_rows: i64,
+    pub bytes: i64,
+}
+
+/// A partition that should be created
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingPartition {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+    pub sql: String,
+}
+
+/// Future coverage of a time-range partitioned table
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeCoverage {
+    /// Interval between the bounds of the newest partition (e.g. "1 mon")
+    pub interval: String,
+    /// Upper bound of the newest partition
+    pub covered_until: String,
+    /// Whether some partition accepts rows for the current time
+    pub covers_now: bool,
+    /// Partitions whose lower bound is in the future
+    pub future_partitions: i64,
+    pub missing: Vec<MissingPartition>,
+}
+
+/// Largest partition compared with the median partition
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionSkew {
+    pub largest: String,
+    pub largest_bytes: i64,
+    pub median_bytes: i64,
+    pub ratio: f64,
+}
+
+/// A partitioned table and its findings
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionedTable {
+    pub schema: String,
+    pub name: String,
+    /// range, list or hash
+    pub strategy: String,
+    pub partition_key: String,
+    /// Type of a single-column partition key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<String>,
+    pub partition_count: usize,
+    pub total_bytes: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_partition: Option<DefaultPartition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<RangeCoverage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skew: Option<PartitionSkew>,
+    pub issues: Vec<PartitionIssue>,
+    pub status: PartitionStatus,
+}
+
+/// Full partitions results
+#[derive(Debug, Serialize)]
+pub struct PartitionsResult {
+    pub tables: Vec<PartitionedTable>,
+    pub overall_status: PartitionStatus,
+    pub ahead: i64,
+    pub skew_ratio: f64,
+    /// Structured fix actions (when --include-actions is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actions: Option<Vec<StructuredAction>>,
+}
+
+/// A child partition as read from the catalog
+struct Child {
+    schema: String,
+    name: String,
+    bound: String,
+    bytes: i64,
+    heap_bytes: i64,
+    estimated_rows: i64,
+}
+
+/// Parse a single-column `FOR VALUES FROM ('a') TO ('b')` bound into its
+/// literal lower and upper values. MINVALUE/MAXVALUE come back unquoted.
+pub fn parse_range_bound(bound: &str) -> Option<(String, String)> {
+    let re = Regex::new(r"^FOR VALUES FROM \((.+)\) TO \((.+)\)$").ok()?;
+    let caps = re.captures(bound)?;
+    let lower = parse_bound_value(&caps[1])?;
+    let upper = parse_bound_value(&caps[2])?;
+    Some((lower, upper))
+}
+
+fn parse_bound_value(value: &str) -> Option<String> {
+    if value == "MINVALUE" || value == "MAXVALUE" {
+        return Some(value.to_string());
+    }
+    let inner = value.strip_prefix('\'')?.strip_suffix('\'')?;
+    // Multi-column bounds look like 'a', 'b'; only single literals are handled
+    if inner.replace("''", "").contains('\'') {
+        return None;
+    }
+    Some(inner.replace("''", "'"))
+}
+
+/// to_char() pattern used to name new partitions, picked from the interval
+pub fn name_suffix_format(interval: &str) -> &'static str {
+    if interval.contains(':') {
+        "YYYY_MM_DD_HH24MI"
+    } else if interval.contains("day") {
+        "YYYY_MM_DD"
+    } else if interval.contains("mon") {
+        "YYYY_MM"
+    } else {
+        "YYYY"
+    }
+}
+
+/// Largest partition vs. the median of the non-empty ones
+fn compute_skew(children: &[(String, i64)], ratio: f64) -> Option<PartitionSkew> {
+    let mut sizes: Vec<&(String, i64)> = children.iter().collect();
+    if sizes.len() < 3 {
+        return None;
+    }
+    sizes.sort_by_key(|(_, bytes)| *bytes);
+    let median_bytes = sizes[sizes.len() / 2].1.max(1);
+    let (largest, largest_bytes) = sizes[sizes.len() - 1];
+    let actual = *largest_bytes as f64 / median_bytes as f64;
+    if *largest_bytes < MIN_SKEW_BYTES || actual < ratio {
+        return None;
+    }
+    Some(PartitionSkew {
+        largest: largest.clone(),
+        largest_bytes: *largest_bytes,
+        median_bytes,
+        ratio: (actual * 10.0).round() / 10.0,
+    })
+}
+
+/// Work out future coverage and the partitions needed to restore it
+async fn get_coverage(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    key_type: &str,
+    bounds: &[(String, String)],
+    ahead: i64,
+) -> Result<RangeCoverage> {
+    let (lowers, uppers): (Vec<String>, Vec<String>) = bounds.iter().cloned().unzip();
+    let query = format!(
+        r#"
+        WITH b AS (
+            SELECT lo::{ty}::timestamptz AS lo, hi::{ty}::timestamptz AS hi
+            FROM unnest($1::text[], $2::text[]) AS u(lo, hi)
+        ), newest AS (
+            SELECT lo, hi FROM b ORDER BY hi DESC LIMIT 1
+        )
+        SELECT
+            newest.hi::{ty}::text AS covered_until,
+            age(newest.hi, newest.lo)::text AS interval,
+            EXISTS (SELECT 1 FROM b WHERE b.lo <= now() AND b.hi > now()) AS covers_now,
+            (SELECT count(*) FROM b WHERE b.lo > now()) AS future_partitions
+        FROM newest
+        "#,
+        ty = key_type
+    );
+    let row = client.query_one(&query, &[&lowers, &uppers]).await?;
+    let covered_until: String = row.get("covered_until");
+    let interval: String = row.get("interval");
+    let covers_now: bool = row.get("covers_now");
+    let future_partitions: i64 = row.get("future_partitions");
+
+    let mut missing = Vec::new();
+    let needed = (ahead - future_partitions).max(0);
+    if !covers_now || needed > 0 {
+        // Step forward from the newest bound: bridge up to now, then add
+        // enough future partitions to get back to `ahead`.
+        let query = format!(
+            r#"
+            WITH s AS (
+                SELECT g,
+                       $1::text::{ty}::timestamptz + $2::text::interval * g AS lo,
+                       $1::text::{ty}::timestamptz + $2::text::interval * (g + 1) AS hi
+                FROM generate_series(0, 999) g
+            )
+            SELECT lo::{ty}::text AS lo, hi::{ty}::text AS hi, to_char(lo, $3::text) AS suffix
+            FROM s
+            WHERE hi > now()
+              AND g < (SELECT count(*) FROM s WHERE lo <= now()) + $4
+            ORDER BY g
+            "#,
+            ty = key_type
+        );
+        let rows = client
+            .query(
+                &query,
+                &[
+                    &covered_until,
+                    &interval,
+                    &name_suffix_format(&interval),
+                    &needed,
+                ],
+            )
+            .await?;
+        for row in rows {
+            let from: String = row.get("lo");
+            let to: String = row.get("hi");
+            let suffix: String = row.get("suffix");
+            let name = format!("{}_{}", table, suffix);
+            let sql = format!(
+                "CREATE TABLE {}.{} PARTITION OF {}.{} FOR VALUES FROM ('{}') TO ('{}');",
+                quote_ident(schema),
+                quote_ident(&name),
+                quote_ident(schema),
+                quote_ident(table),
+                from.replace('\'', "''"),
+                to.replace('\'', "''")
+            );
+            missing.push(MissingPartition {
+                name,
+                from,
+                to,
+                sql,
+            });
+        }
+    }
+
+    Ok(RangeCoverage {
+        interval,
+        covered_until,
+        covers_now,
+        future_partitions,
+        missing,
+    })
+}
+
+/// Check every partitioned table, or only `table`
+pub async fn run_partitions(
+    client: &Client,
+    table: Option<(&str, &str)>,
+    ahead: i64,
+    skew_ratio: f64,
+    ignore: &AlertIgnore,
+) -> Result<PartitionsResult> {
+    let parents_query = r#"
+        SELECT
+            c.oid,
+            n.nspname,
+            c.relname,
+            CASE pt.partstrat WHEN 'r' THEN 'range' WHEN 'l' THEN 'list' ELSE 'hash' END AS strategy,
+            pg_get_partkeydef(c.oid) AS partition_key,
+            CASE WHEN pt.partnatts = 1 AND a.attnum IS NOT NULL
+                 THEN format_type(a.atttypid, NULL) END AS key_type
+        FROM pg_partitioned_table pt
+        JOIN pg_class c ON c.oid = pt.partrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = pt.partattrs[0]
+        WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+          AND n.nspname NOT LIKE 'pg_toast%'
+          AND ($1::text IS NULL OR n.nspname = $1)
+          AND ($2::text IS NULL OR c.relname = $2)
+        ORDER BY n.nspname, c.relname
+    "#;
+
+    let children_query = r#"
+        SELECT
+            n.nspname,
+            c.relname,
+            pg_get_expr(c.relpartbound, c.oid) AS bound,
+            COALESCE((SELECT sum(pg_total_relation_size(t.relid))
+                      FROM pg_partition_tree(c.oid) t), 0)::int8 AS bytes,
+            COALESCE((SELECT sum(pg_relation_size(t.relid))
+                      FROM pg_partition_tree(c.oid) t), 0)::int8 AS heap_bytes,
+            GREATEST(c.reltuples, COALESCE(s.n_live_tup, 0), 0)::int8 AS estimated_rows
+        FROM pg_inherits i
+        JOIN pg_class c ON c.oid = i.inhrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_stat_user_tables s ON s.relid = c.oid
+        WHERE i.inhparent = $1
+        ORDER BY c.relname
+    "#;
+
+    let (filter_schema, filter_name) = match table {
+        Some((s, n)) => (Some(s), Some(n)),
+        None => (None, None),
+    };
+    let mut tables = Vec::new();
+    for parent in client
+        .query(parents_query, &[&filter_schema, &filter_name])
+        .await?
+    {
+        let oid: u32 = parent.get("oid");
+        let schema: String = parent.get("nspname");
+        let name: String = parent.get("relname");
+        if ignore.table(&schema, &name) {
+            continue;
+        }
+        let strategy: String = parent.get("strategy");
+        let key_type: Option<String> = parent.get("key_type");
+
+        let children: Vec<Child> = client
+            .query(children_query, &[&oid])
+            .await?
+            .iter()
+            .map(|r| Child {
+                schema: r.get("nspname"),
+                name: r.get("relname"),
+                bound: r.get("bound"),
+                bytes: r.get("bytes"),
+                heap_bytes: r.get("heap_bytes"),
+                estimated_rows: r.get("estimated_rows"),
+            })
+            .collect();
+
+        let mut issues = Vec::new();
+
+        let default_partition = match children.iter().find(|c| c.bound == "DEFAULT") {
+            Some(c) => {
+                let has_rows: bool = client
+                    .query_one(
+                        &format!(
+                            "SELECT EXISTS (SELECT 1 FROM {}.{})",
+                            quote_ident(&c.schema),
+                            quote_ident(&c.name)
+                        ),
+                        &[],
+                    )
+                    .await?
+                    .get(0);
+                if has_rows {
+                    issues.push(PartitionIssue {
+                        kind: IssueKind::DefaultRows,
+                        status: PartitionStatus::Warning,
+                        message: format!(
+                            "Default partition {}.{} holds rows (~{} estimated); partitions overlapping them cannot be created until they are moved out",
+                            c.schema, c.name, c.estimated_rows
+                        ),
+                    });
+                }
+                Some(DefaultPartition {
+                    schema: c.schema.clone(),
+                    name: c.name.clone(),
+                    has_rows,
+                    estimated_rows: c.estimated_rows,
+                    bytes: c.bytes,
+                })
+            }
+            None => None,
+        };
+
+        let coverage = match key_type.as_deref() {
+            Some(ty) if strategy == "range" && TIME_KEY_TYPES.contains(&ty) => {
+                let bounds: Option<Vec<(String, String)>> = children
+                    .iter()
+                    .filter(|c| c.bound != "DEFAULT")
+                    .map(|c| parse_range_bound(&c.bound))
+                    .collect();
+                // MINVALUE/MAXVALUE catch-alls have no interval to extend
+                let bounds: Vec<(String, String)> = bounds
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|(lo, hi)| lo != "MINVALUE" && hi != "MAXVALUE")
+                    .collect();
+                let unbounded = children.iter().any(|c| c.bound.ends_with("TO (MAXVALUE)"));
+                if bounds.is_empty() || unbounded {
+                    None
+                } else {
+                    Some(get_coverage(client, &schema, &name, ty, &bounds, ahead).await?)
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(ref cov) = coverage {
+            if !cov.covers_now {
+                issues.push(PartitionIssue {
+                    kind: IssueKind::MissingFuture,
+                    status: PartitionStatus::Critical,
+                    message: format!(
+                        "No partition covers the current time (newest ends {}); rows are going to {}",
+                        cov.covered_until,
+                        if default_partition.is_some() {
+                            "the default partition"
+                        } else {
+                            "nowhere and inserts fail"
+                        }
+                    ),
+                });
+            } else if cov.future_partitions < ahead {
+                let status = if cov.future_partitions == 0 {
+                    PartitionStatus::Critical
+                } else {
+                    PartitionStatus::Warning
+                };
+                issues.push(PartitionIssue {
+                    kind: IssueKind::MissingFuture,
+                    status,
+                    message: format!(
+                        "Only {} future partition(s) (expected {}); newest ends {}",
+                        cov.future_partitions, ahead, cov.covered_until
+                    ),
+                });
+            }
+        }
+
+        let sized: Vec<(String, i64)> = children
+            .iter()
+            .filter(|c| c.bound != "DEFAULT" && c.heap_bytes > 0)
+            .map(|c| (c.name.clone(), c.bytes))
+            .collect();
+        let skew = compute_skew(&sized, skew_ratio);
+        if let Some(ref s) = skew {
+            issues.push(PartitionIssue {
+                kind: IssueKind::Skew,
+                status: PartitionStatus::Warning,
+                message: format!(
+                    "Partition {} is {:.1}x the median partition size ({} vs {})",
+                    s.largest,
+                    s.ratio,
+                    format_bytes(s.largest_bytes),
+                    format_bytes(s.median_bytes)
+                ),
+            });
+        }
+
+        let status = issues
+            .iter()
+            .map(|i| i.status)
+            .max()
+            .unwrap_or(PartitionStatus::Healthy);
+
+        tables.push(PartitionedTable {
+            schema,
+            name,
+            strategy,
+            partition_key: parent.get("partition_key"),
+            key_type,
+            partition_count: children.len(),
+            total_bytes: children.iter().map(|c| c.bytes).sum(),
+            default_partition,
+            coverage,
+            skew,
+            issues,
+            status,
+        });
+    }
+
+    let overall_status = tables
+        .iter()
+        .map(|t| t.status)
+        .max()
+        .unwrap_or(PartitionStatus::Healthy);
+
+    Ok(PartitionsResult {
+        tables,
+        overall_status,
+        ahead,
+        skew_ratio,
+        actions: None,
+    })
+}
+
+/// Structured actions creating the missing future partitions
+pub fn generate_actions(
+    result: &PartitionsResult,
+    read_write: bool,
+    is_primary: bool,
+) -> Vec<StructuredAction> {
+    let mut actions = Vec::new();
+    for table in &result.tables {
+        let Some(ref cov) = table.coverage else {
+            continue;
+        };
+        let blocked_by_default = table.default_partition.as_ref().is_some_and(|d| d.has_rows);
+        for part in &cov.missing {
+            let action_id = format!("partitions.create.{}.{}", table.schema, part.name);
+            let mut description = format!(
+                "Create partition {}.{} for [{}, {})",
+                table.schema, part.name, part.from, part.to
+            );
+            if blocked_by_default {
+                description
+                    .push_str("; fails if the default partition already holds rows in this range");
+            }
+            actions.push(
+                StructuredAction::builder(action_id, ActionType::Fix)
+                    .command("pgcrate")
+                    .args(vec![
+                        "sql".to_string(),
+                        "--allow-write".to_string(),
+                        "-c".to_string(),
+                        part.sql.clone(),
+                    ])
+                    .description(description)
+                    .mutates(true)
+                    .risk(if blocked_by_default {
+                        Risk::Medium
+                    } else {
+                        Risk::Low
+                    })
+                    .gates(ActionGates::write_primary())
+                    .sql_preview(vec![part.sql.clone()])
+                    .evidence(serde_json::json!({
+                        "table": format!("{}.{}", table.schema, table.name),
+                        "covered_until": cov.covered_until,
+                        "interval": cov.interval,
+                        "future_partitions": cov.future_partitions,
+                    }))
+                    .build(read_write, is_primary, false),
+            );
+        }
+    }
+    actions
+}
+
+/// Format bytes for display
+fn format_bytes(bytes: i64) -> String {
+    if bytes >= 1024 * 1024 * 1024 {
+        format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    } else if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} bytes", bytes)
+    }
+}
+
+/// Print partitions in human-readable format
+pub fn print_human(result: &PartitionsResult, quiet: bool) {
+    if result.tables.is_empty() {
+        if !quiet {
+            println!("No partitioned tables found");
+        }
+        return;
+    }
+
+    let unhealthy: Vec<_> = result
+        .tables
+        .iter()
+        .filter(|t| t.status != PartitionStatus::Healthy)
+        .collect();
+    if unhealthy.is_empty() && quiet {
+        return;
+    }
+
+    println!("PARTITIONED TABLES:");
+    println!();
+    println!(
+        "  {:3} {:40} {:>6} {:>6} {:>10}",
+        "", "TABLE", "TYPE", "PARTS", "SIZE"
+    );
+    println!("  {}", "-".repeat(70));
+    for table in &result.tables {
+        let full_name = format!("{}.{}", table.schema, table.name);
+        let display_name = if full_name.chars().count() > 40 {
+            format!("{}...", full_name.chars().take(37).collect::<String>())
+        } else {
+            full_name
+        };
+        println!(
+            "  {} {:40} {:>6} {:>6} {:>10}",
+            table.status.emoji(),
+            display_name,
+            table.strategy,
+            table.partition_count,
+            format_bytes(table.total_bytes)
+        );
+        for issue in &table.issues {
+            println!("      {} {}", issue.status.emoji(), issue.message);
+        }
+    }
+
+    let missing: Vec<_> = result
+        .tables
+        .iter()
+        .filter_map(|t| t.coverage.as_ref())
+        .flat_map(|c| c.missing.iter())
+        .collect();
+    if !missing.is_empty() {
+        println!();
+        println!("RECOMMENDED ACTIONS:");
+        println!();
+        for part in missing {
+            println!("  {}", part.sql);
+        }
+    }
+}
+
+/// Print partitions as JSON with schema versioning.
+pub fn print_json(
+    result: &PartitionsResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = match result.overall_status {
+        PartitionStatus::Healthy => Severity::Healthy,
+        PartitionStatus::Warning => Severity::Warning,
+        PartitionStatus::Critical => Severity::Critical,
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::PARTITIONS, result, severity, t),
+        None => DiagnosticOutput::new(schema::PARTITIONS, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range_bound() {
+        assert_eq!(
+            parse_range_bound("FOR VALUES FROM ('2024-01-01') TO ('2024-02-01')"),
+            Some(("2024-01-01".to_string(), "2024-02-01".to_string()))
+        );
+        assert_eq!(
+            parse_range_bound("FOR VALUES FROM (MINVALUE) TO ('2024-01-01 00:00:00+00')"),
+            Some(("MINVALUE".to_string(), "2024-01-01 00:00:00+00".to_string()))
+        );
+        // Multi-column bounds and non-range bounds are not handled
+        assert_eq!(
+            parse_range_bound("FOR VALUES FROM ('a', 1) TO ('b', 2)"),
+            None
+        );
+        assert_eq!(parse_range_bound("FOR VALUES IN ('x')"), None);
+        assert_eq!(parse_range_bound("DEFAULT"), None);
+    }
+
+    #[test]
+    fn test_name_suffix_format() {
+        assert_eq!(name_suffix_format("1 mon"), "YYYY_MM");
+        assert_eq!(name_suffix_format("7 days"), "YYYY_MM_DD");
+        assert_eq!(name_suffix_format("1 year"), "YYYY");
+        assert_eq!(name_suffix_format("01:00:00"), "YYYY_MM_DD_HH24MI");
+    }
+
+    #[test]
+    fn test_compute_skew() {
+        let mb = 1024 * 1024;
+        let even = vec![
+            ("p0".to_string(), 10 * mb),
+            ("p1".to_string(), 11 * mb),
+            ("p2".to_string(), 12 * mb),
+        ];
+        assert!(compute_skew(&even, DEFAULT_SKEW_RATIO).is_none());
+
+        let skewed = vec![
+            ("p0".to_string(), mb),
+            ("p1".to_string(), mb),
+            ("p2".to_string(), 50 * mb),
+        ];
+        let skew = compute_skew(&skewed, DEFAULT_SKEW_RATIO).unwrap();
+        assert_eq!(skew.largest, "p2");
+        assert_eq!(skew.ratio, 50.0);
+
+        // Small tables are never reported, whatever the ratio
+        let tiny = vec![
+            ("p0".to_string(), 8192),
+            ("p1".to_string(), 8192),
+            ("p2".to_string(), 5 * mb),
+        ];
+        assert!(compute_skew(&tiny, DEFAULT_SKEW_RATIO).is_none());
+    }
+}
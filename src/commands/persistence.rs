@@ -0,0 +1,385 @@
+//! Persistence command: Tables that silently break crash recovery or replication.
+//!
+//! Lists unlogged tables (emptied after a crash, never replicated), tables
+//! without a primary key, and replica identity problems. A table in a
+//! publication that publishes UPDATE or DELETE needs a usable replica
+//! identity; without one those statements fail on the publisher, so that case
+//! is critical. Everything else is a warning. Each finding carries a stable
+//! issue code.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use crate::config::AlertIgnore;
+
+/// Tables listed in human output
+const DISPLAY_LIMIT: usize = 20;
+
+/// Persistence status level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PersistenceStatus {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl PersistenceStatus {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            PersistenceStatus::Healthy => "✓",
+            PersistenceStatus::Warning => "⚠",
+            PersistenceStatus::Critical => "✗",
+        }
+    }
+}
+
+/// Why a table is listed (stable identifiers for automation)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PersistenceIssue {
+    /// UNLOGGED: truncated by crash recovery and absent on replicas
+    Unlogged,
+    NoPrimaryKey,
+    /// Published, but UPDATE/DELETE have no replica identity to use
+    NoReplicaIdentity,
+    /// REPLICA IDENTITY FULL on a published table: whole old rows are logged
+    /// and the subscriber matches rows without an index
+    ReplicaIdentityFull,
+    /// REPLICA IDENTITY USING INDEX whose index no longer exists
+    ReplicaIdentityIndexMissing,
+}
+
+impl PersistenceIssue {
+    pub fn description(&self) -> &'static str {
+        match self {
+            PersistenceIssue::Unlogged => "unlogged (emptied on crash, not replicated)",
+            PersistenceIssue::NoPrimaryKey => "no primary key",
+            PersistenceIssue::NoReplicaIdentity => {
+                "published without replica identity (UPDATE/DELETE fail)"
+            }
+            PersistenceIssue::ReplicaIdentityFull => "published with REPLICA IDENTITY FULL",
+            PersistenceIssue::ReplicaIdentityIndexMissing => "replica identity index missing",
+        }
+    }
+
+    fn status(&self) -> PersistenceStatus {
+        match self {
+            PersistenceIssue::NoReplicaIdentity => PersistenceStatus::Critical,
+            _ => PersistenceStatus::Warning,
+        }
+    }
+}
+
+/// A table with at least one persistence issue
+#[derive(Debug, Clone, Serialize)]
+pub struct TablePersistence {
+    pub schema: String,
+    pub table: String,
+    pub issues: Vec<PersistenceIssue>,
+    /// In a publication that publishes UPDATE or DELETE
+    pub published: bool,
+    /// default, nothing, full, or index
+    pub replica_identity: String,
+    pub row_estimate: i64,
+    pub size_bytes: i64,
+    pub status: PersistenceStatus,
+}
+
+/// Full persistence results
+#[derive(Debug, Serialize)]
+pub struct PersistenceResult {
+    /// Tables with issues, critical first
+    pub tables: Vec<TablePersistence>,
+    pub unlogged_count: usize,
+    pub no_primary_key_count: usize,
+    /// Published tables whose UPDATE/DELETE will fail or replicate poorly
+    pub replica_identity_count: usize,
+    pub overall_status: PersistenceStatus,
+}
+
+/// Issues for one table, from its catalog flags
+fn classify(
+    unlogged: bool,
+    has_pk: bool,
+    replident: &str,
+    has_replident_index: bool,
+    published: bool,
+) -> Vec<PersistenceIssue> {
+    let mut issues = Vec::new();
+    if unlogged {
+        issues.push(PersistenceIssue::Unlogged);
+    }
+    if !has_pk {
+        issues.push(PersistenceIssue::NoPrimaryKey);
+    }
+    let index_missing = replident == "i" && !has_replident_index;
+    if index_missing {
+        issues.push(PersistenceIssue::ReplicaIdentityIndexMissing);
+    }
+    if published {
+        // A missing identity index behaves like NOTHING
+        let no_identity = replident == "n" || (replident == "d" && !has_pk) || index_missing;
+        if no_identity {
+            issues.push(PersistenceIssue::NoReplicaIdentity);
+        } else if replident == "f" {
+            issues.push(PersistenceIssue::ReplicaIdentityFull);
+        }
+    }
+    issues
+}
+
+fn replica_identity_name(replident: &str) -> &'static str {
+    match replident {
+        "n" => "nothing",
+        "f" => "full",
+        "i" => "index",
+        _ => "default",
+    }
+}
+
+/// Tables with persistence issues, skipping pgcrate's own schema and `ignore`d tables
+pub async fn get_persistence_issues(
+    client: &Client,
+    ignore: &AlertIgnore,
+) -> Result<Vec<TablePersistence>, tokio_postgres::Error> {
+    // Partitions are covered by their parent; pg_publication_tables lists
+    // FOR ALL TABLES and schema-level publications too
+    let query = r#"
+        SELECT
+            n.nspname AS schema,
+            c.relname AS table,
+            c.relpersistence = 'u' AS unlogged,
+            EXISTS (
+                SELECT 1 FROM pg_constraint k
+                WHERE k.conrelid = c.oid AND k.contype = 'p'
+            ) AS has_pk,
+            c.relreplident::text AS replident,
+            EXISTS (
+                SELECT 1 FROM pg_index i
+                WHERE i.indrelid = c.oid AND i.indisreplident
+            ) AS has_replident_index,
+            EXISTS (
+                SELECT 1
+                FROM pg_publication_tables pt
+                JOIN pg_publication p ON p.pubname = pt.pubname
+                WHERE pt.schemaname = n.nspname
+                  AND pt.tablename = c.relname
+                  AND (p.pubupdate OR p.pubdelete)
+            ) AS published,
+            GREATEST(c.reltuples, 0)::bigint AS row_estimate,
+            pg_total_relation_size(c.oid) AS size_bytes
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.relkind IN ('r', 'p')
+          AND NOT c.relispartition
+          AND c.relpersistence IN ('p', 'u')
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'pgcrate')
+          AND n.nspname NOT LIKE 'pg_toast%'
+        ORDER BY n.nspname, c.relname
+    "#;
+
+    let rows = client.query(query, &[]).await?;
+
+    let mut tables: Vec<TablePersistence> = rows
+        .iter()
+        .filter_map(|row| {
+            let schema: String = row.get("schema");
+            let table: String = row.get("table");
+            if ignore.table(&schema, &table) {
+                return None;
+            }
+            let replident: String = row.get("replident");
+            let published: bool = row.get("published");
+            let issues = classify(
+                row.get("unlogged"),
+                row.get("has_pk"),
+                &replident,
+                row.get("has_replident_index"),
+                published,
+            );
+            let status = issues.iter().map(|i| i.status()).max()?;
+            Some(TablePersistence {
+                schema,
+                table,
+                issues,
+                published,
+                replica_identity: replica_identity_name(&replident).to_string(),
+                row_estimate: row.get("row_estimate"),
+                size_bytes: row.get("size_bytes"),
+                status,
+            })
+        })
+        .collect();
+
+    tables.sort_by_key(|t| std::cmp::Reverse(t.status));
+    Ok(tables)
+}
+
+/// Run the persistence audit
+pub async fn run_persistence(client: &Client, ignore: &AlertIgnore) -> Result<PersistenceResult> {
+    let tables = get_persistence_issues(client, ignore)
+        .await
+        .context("Failed to audit table persistence")?;
+    Ok(summarize(tables))
+}
+
+/// Count issues and derive the overall status
+pub fn summarize(tables: Vec<TablePersistence>) -> PersistenceResult {
+    let count = |issue: &[PersistenceIssue]| {
+        tables
+            .iter()
+            .filter(|t| t.issues.iter().any(|i| issue.contains(i)))
+            .count()
+    };
+    let unlogged_count = count(&[PersistenceIssue::Unlogged]);
+    let no_primary_key_count = count(&[PersistenceIssue::NoPrimaryKey]);
+    let replica_identity_count = count(&[
+        PersistenceIssue::NoReplicaIdentity,
+        PersistenceIssue::ReplicaIdentityFull,
+    ]);
+    let overall_status = tables
+        .iter()
+        .map(|t| t.status)
+        .max()
+        .unwrap_or(PersistenceStatus::Healthy);
+
+    PersistenceResult {
+        tables,
+        unlogged_count,
+        no_primary_key_count,
+        replica_identity_count,
+        overall_status,
+    }
+}
+
+impl PersistenceResult {
+    /// One-line count of findings, e.g. "2 unlogged, 1 without primary key"
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.replica_identity_count > 0 {
+            parts.push(format!(
+                "{} published with replica identity issues",
+                self.replica_identity_count
+            ));
+        }
+        if self.unlogged_count > 0 {
+            parts.push(format!("{} unlogged", self.unlogged_count));
+        }
+        if self.no_primary_key_count > 0 {
+            parts.push(format!("{} without primary key", self.no_primary_key_count));
+        }
+        if parts.is_empty() {
+            "All tables logged with primary keys".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Print persistence results in human-readable format
+pub fn print_human(result: &PersistenceResult, quiet: bool) {
+    if result.tables.is_empty() {
+        if !quiet {
+            println!("PERSISTENCE: {}", result.summary());
+        }
+        return;
+    }
+
+    println!("PERSISTENCE: {}", result.summary());
+    println!();
+    for t in result.tables.iter().take(DISPLAY_LIMIT) {
+        let issues: Vec<&str> = t.issues.iter().map(|i| i.description()).collect();
+        println!(
+            "  {} {}.{}: {}",
+            t.status.emoji(),
+            t.schema,
+            t.table,
+            issues.join("; ")
+        );
+    }
+    if result.tables.len() > DISPLAY_LIMIT {
+        println!(
+            "  ... and {} more (--json lists all)",
+            result.tables.len() - DISPLAY_LIMIT
+        );
+    }
+
+    if !quiet {
+        if result.replica_identity_count > 0 {
+            println!();
+            println!("  Add a primary key, or ALTER TABLE ... REPLICA IDENTITY USING INDEX <unique index>");
+        }
+        if result.unlogged_count > 0 {
+            println!();
+            println!("  ALTER TABLE ... SET LOGGED makes a table crash-safe (rewrites it under an exclusive lock)");
+        }
+    }
+}
+
+/// Print persistence results as JSON
+pub fn print_json(
+    result: &PersistenceResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = match result.overall_status {
+        PersistenceStatus::Healthy => Severity::Healthy,
+        PersistenceStatus::Warning => Severity::Warning,
+        PersistenceStatus::Critical => Severity::Critical,
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::PERSISTENCE, result, severity, t),
+        None => DiagnosticOutput::new(schema::PERSISTENCE, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert!(classify(false, true, "d", false, true).is_empty());
+        assert_eq!(
+            classify(true, true, "d", false, false),
+            vec![PersistenceIssue::Unlogged]
+        );
+        assert_eq!(
+            classify(false, false, "d", false, false),
+            vec![PersistenceIssue::NoPrimaryKey]
+        );
+        assert_eq!(
+            classify(false, false, "d", false, true),
+            vec![
+                PersistenceIssue::NoPrimaryKey,
+                PersistenceIssue::NoReplicaIdentity
+            ]
+        );
+        // A unique index as replica identity covers a key-less published table
+        assert_eq!(
+            classify(false, false, "i", true, true),
+            vec![PersistenceIssue::NoPrimaryKey]
+        );
+        assert_eq!(
+            classify(false, true, "i", false, true),
+            vec![
+                PersistenceIssue::ReplicaIdentityIndexMissing,
+                PersistenceIssue::NoReplicaIdentity
+            ]
+        );
+        assert_eq!(
+            classify(false, true, "f", false, true),
+            vec![PersistenceIssue::ReplicaIdentityFull]
+        );
+        assert_eq!(
+            classify(false, true, "n", false, true),
+            vec![PersistenceIssue::NoReplicaIdentity]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Plan: the migration that brings the database in line with a declarative
+//! schema file.
+//!
+//! The file (written by `generate --declarative` and edited by hand) is
+//! loaded into a scratch database on the same server, both are introspected,
+//! and the schema diff engine turns the difference into SQL.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tokio_postgres::Client;
+
+use super::connect;
+use super::schema::{extract_db_name, generate_options};
+use crate::config::{parse_database_url, Config};
+use crate::diff::{diff_schemas, diff_to_sql, format_diff};
+use crate::introspect::{self, DatabaseSchema, IntrospectOptions};
+use crate::output::{DiffSummaryJson, Output};
+use crate::sql::quote_ident;
+
+/// Empty database on the same server as `database_url`, for loading SQL to
+/// introspect. `database_url` must be read-write (callers require
+/// --read-write). Call `drop` when done; it removes the database.
+struct ScratchDatabase {
+    admin: Client,
+    name: String,
+    url: String,
+}
+
+impl ScratchDatabase {
+    async fn create(database_url: &str, purpose: &str) -> Result<Self> {
+        let parsed = parse_database_url(database_url)?;
+        let admin = connect(&parsed.admin_url).await?;
+        let name = format!("pgcrate_{}_{}", purpose, std::process::id());
+        admin
+            .batch_execute(&format!("CREATE DATABASE {}", quote_ident(&name)))
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to create scratch database {} (requires CREATEDB)",
+                    name
+                )
+            })?;
+        Ok(Self {
+            url: parsed.with_database(&name),
+            admin,
+            name,
+        })
+    }
+
+    async fn drop(self) {
+        let drop_sql = format!(
+            "DROP DATABASE IF EXISTS {} WITH (FORCE)",
+            quote_ident(&self.name)
+        );
+        if let Err(e) = self.admin.batch_execute(&drop_sql).await {
+            eprintln!(
+                "{}",
+                format!("Warning: failed to drop {}: {}", self.name, e).yellow()
+            );
+        }
+    }
+}
+
+/// JSON response for plan
+#[derive(Debug, Serialize)]
+struct PlanResponse {
+    ok: bool,
+    identical: bool,
+    summary: DiffSummaryJson,
+    /// Statements that bring the database in line with the file
+    sql: String,
+    /// Migration file written with --name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    migration: Option<String>,
+}
+
+/// Run `scripts` (label, SQL) in order in a scratch database on the same
+/// server as `database_url`, and introspect the result. Scratch objects
+/// belong to whoever runs this, so owners can't be compared.
+pub(crate) async fn introspect_scripts(
+    database_url: &str,
+    purpose: &str,
+    scripts: &[(String, &str)],
+    options: &IntrospectOptions,
+    include_privileges: bool,
+) -> Result<DatabaseSchema> {
+    let scratch = ScratchDatabase::create(database_url, purpose).await?;
+    let loaded = async {
+        let client = connect(&scratch.url).await?;
+        // Like pg_dump: function bodies may refer to objects defined later
+        client
+            .batch_execute("SET check_function_bodies = false")
+            .await?;
+        for (label, sql) in scripts {
+            client
+                .batch_execute(sql)
+                .await
+                .with_context(|| format!("Failed to load {}", label))?;
+        }
+        let mut schema = introspect::introspect(&client, options).await?;
+        if include_privileges {
+            schema.privileges = introspect::introspect_privileges(&client, options).await?;
+        }
+        Ok::<_, anyhow::Error>(schema)
+    }
+    .await;
+    scratch.drop().await;
+    loaded
+}
+
+/// Propose the migration from the database to a declarative schema file,
+/// optionally writing it to the migrations directory as `<timestamp>_<name>.sql`.
+/// Returns exit code: 0 = in sync or migration written, 1 = differs
+#[allow(clippy::too_many_arguments)] // CLI handler - each arg maps to a CLI flag
+pub async fn plan(
+    database_url: &str,
+    config: &Config,
+    file: &Path,
+    name: Option<&str>,
+    include_schemas: &[String],
+    exclude_schemas: &[String],
+    safe_only: bool,
+    no_privileges: bool,
+    output: &Output,
+) -> Result<i32> {
+    let options = generate_options(config, include_schemas, exclude_schemas);
+
+    output.verbose(
+        &format!("Loading {} into a scratch database...", file.display())
+            .dimmed()
+            .to_string(),
+    );
+    let sql =
+        fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let scripts = [(file.display().to_string(), sql.as_str())];
+    let mut desired =
+        introspect_scripts(database_url, "plan", &scripts, &options, !no_privileges).await?;
+
+    output.verbose(&"Introspecting database...".dimmed().to_string());
+    let client = connect(database_url).await?;
+    let mut current = introspect::introspect(&client, &options).await?;
+    if !no_privileges {
+        current.privileges = introspect::introspect_privileges(&client, &options).await?;
+        // The file sets no owners
+        desired.privileges.owners.clear();
+        current.privileges.owners.clear();
+    }
+
+    let forward = diff_schemas(&desired, &current);
+    let identical = forward.is_empty();
+    let up_sql = diff_to_sql(&forward, &desired, safe_only);
+
+    let mut migration = None;
+    if let (Some(name), false) = (name, identical) {
+        let down_sql = diff_to_sql(&diff_schemas(&current, &desired), &current, safe_only);
+        let dir = Path::new(config.migrations_dir());
+        fs::create_dir_all(dir)?;
+        let timestamp = Utc::now().format("%Y%m%d%H%M%S");
+        let path = dir.join(format!("{}_{}.sql", timestamp, name));
+        let contents = format!(
+            "-- Migration: {}\n-- Planned by pgcrate plan from {}\n-- Created at: {}\n\n\
+             -- up\n\n{}\n\n-- down\n\n{}\n",
+            name,
+            file.display(),
+            timestamp,
+            up_sql.trim(),
+            down_sql.trim()
+        );
+        fs::write(&path, contents)?;
+        migration = Some(path.display().to_string());
+    }
+    let exit_code = if identical || migration.is_some() {
+        0
+    } else {
+        1
+    };
+
+    if output.is_json() {
+        output.json(&PlanResponse {
+            ok: true,
+            identical,
+            summary: DiffSummaryJson::from(&forward.summary()),
+            sql: up_sql,
+            migration,
+        })?;
+        return Ok(exit_code);
+    }
+    if output.is_quiet() {
+        return Ok(exit_code);
+    }
+
+    if identical {
+        println!(
+            "{}",
+            format!("Database matches {}.", file.display()).green()
+        );
+        return Ok(exit_code);
+    }
+    println!(
+        "{}",
+        format_diff(
+            &forward,
+            &file.display().to_string(),
+            &extract_db_name(database_url)
+        )
+    );
+    println!();
+    match migration {
+        Some(path) => println!("Created: {}", path.green()),
+        None => {
+            println!("{}", "Proposed migration:".bold());
+            println!();
+            output.data(&up_sql);
+        }
+    }
+    Ok(exit_code)
+}
@@ -0,0 +1,621 @@
+//! Plan baselines for `dba explain --baseline FILE`.
+//!
+//! The first run writes a normalized summary of the plan (node types, join
+//! strategies, relations, indexes, and root estimates) to FILE. Later runs
+//! compare a fresh plan against it and flag regressions: a sequential scan
+//! appearing, a scan switching index, a join strategy flipping, estimated
+//! cost growth, and row estimate drift. Commit the file next to the query
+//! and run the command in CI to gate on critical queries.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::explain::IssueSeverity;
+
+const BASELINE_VERSION: u32 = 1;
+
+/// Cost growth (new / old) flagged as a warning
+const COST_WARNING_RATIO: f64 = 1.5;
+/// Cost growth (new / old) flagged as critical
+const COST_CRITICAL_RATIO: f64 = 3.0;
+/// Row estimate change (either direction) flagged as drift
+const ROW_DRIFT_RATIO: f64 = 10.0;
+
+const JOIN_NODES: &[&str] = &["Nested Loop", "Hash Join", "Merge Join"];
+
+/// One plan node with the fields that define the plan's shape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanNodeSummary {
+    pub depth: usize,
+    pub node_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub join_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    /// "schema.table" or "schema.table alias" when the alias differs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<String>,
+    pub total_cost: f64,
+    pub rows: i64,
+}
+
+/// Baseline file contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanBaseline {
+    pub version: u32,
+    pub query: String,
+    pub recorded_at: String,
+    pub fingerprint: String,
+    pub total_cost: f64,
+    pub rows: i64,
+    pub nodes: Vec<PlanNodeSummary>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BaselineStatus {
+    Created,
+    Updated,
+    Compared,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanChangeKind {
+    SeqScanAppeared,
+    ScanChanged,
+    ScanAdded,
+    ScanRemoved,
+    JoinStrategyChanged,
+    NodeAppeared,
+    NodeRemoved,
+    CostIncreased,
+    CostDecreased,
+    RowEstimateDrift,
+}
+
+/// A difference between the baseline plan and the current plan
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanChange {
+    pub kind: PlanChangeKind,
+    pub severity: IssueSeverity,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+/// Outcome of `--baseline`, included in the explain result
+#[derive(Debug, Clone, Serialize)]
+pub struct BaselineOutcome {
+    pub file: String,
+    pub status: BaselineStatus,
+    pub fingerprint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_fingerprint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_recorded_at: Option<String>,
+    pub plan_changed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_total_cost: Option<f64>,
+    pub total_cost: f64,
+    pub changes: Vec<PlanChange>,
+}
+
+impl BaselineOutcome {
+    pub fn max_severity(&self) -> Option<IssueSeverity> {
+        self.changes.iter().map(|c| c.severity).max()
+    }
+}
+
+/// Flatten a plan tree (EXPLAIN FORMAT JSON) into pre-order node summaries
+pub fn summarize(plan_json: &Value) -> Vec<PlanNodeSummary> {
+    let mut nodes = Vec::new();
+    if let Some(root) = plan_json.get(0).and_then(|p| p.get("Plan")) {
+        walk(root, 0, &mut nodes);
+    }
+    nodes
+}
+
+fn walk(node: &Value, depth: usize, nodes: &mut Vec<PlanNodeSummary>) {
+    let text = |key: &str| node.get(key).and_then(|v| v.as_str()).map(String::from);
+    let relation = text("Relation Name").map(|rel| {
+        let qualified = match text("Schema") {
+            Some(schema) => format!("{}.{}", schema, rel),
+            None => rel.clone(),
+        };
+        match text("Alias") {
+            Some(alias) if alias != rel => format!("{} {}", qualified, alias),
+            _ => qualified,
+        }
+    });
+    nodes.push(PlanNodeSummary {
+        depth,
+        node_type: text("Node Type").unwrap_or_default(),
+        join_type: text("Join Type"),
+        strategy: text("Strategy"),
+        relation,
+        index: text("Index Name"),
+        total_cost: node
+            .get("Total Cost")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0),
+        rows: node.get("Plan Rows").and_then(|v| v.as_i64()).unwrap_or(0),
+    });
+    if let Some(children) = node.get("Plans").and_then(|v| v.as_array()) {
+        for child in children {
+            walk(child, depth + 1, nodes);
+        }
+    }
+}
+
+/// Hash of the plan shape; costs and row estimates are not included
+pub fn fingerprint(nodes: &[PlanNodeSummary]) -> String {
+    let mut hasher = Sha256::new();
+    for node in nodes {
+        hasher.update(format!(
+            "{}|{}|{}|{}|{}|{}\n",
+            node.depth,
+            node.node_type,
+            node.join_type.as_deref().unwrap_or(""),
+            node.strategy.as_deref().unwrap_or(""),
+            node.relation.as_deref().unwrap_or(""),
+            node.index.as_deref().unwrap_or(""),
+        ));
+    }
+    hex::encode(hasher.finalize())[..16].to_string()
+}
+
+/// Collapse whitespace so formatting changes don't invalidate a baseline
+fn normalize_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(';')
+        .trim()
+        .to_string()
+}
+
+fn build_baseline(query: &str, nodes: Vec<PlanNodeSummary>) -> PlanBaseline {
+    let (total_cost, rows) = nodes
+        .first()
+        .map(|n| (n.total_cost, n.rows))
+        .unwrap_or((0.0, 0));
+    PlanBaseline {
+        version: BASELINE_VERSION,
+        query: normalize_query(query),
+        recorded_at: chrono::Utc::now().to_rfc3339(),
+        fingerprint: fingerprint(&nodes),
+        total_cost,
+        rows,
+        nodes,
+    }
+}
+
+/// Write a new baseline, or compare against the existing one.
+///
+/// With `update`, an existing file is overwritten instead of compared.
+pub fn check(path: &Path, query: &str, plan_json: &Value, update: bool) -> Result<BaselineOutcome> {
+    let current = build_baseline(query, summarize(plan_json));
+    let file = path.display().to_string();
+
+    if update || !path.exists() {
+        let status = if path.exists() {
+            BaselineStatus::Updated
+        } else {
+            BaselineStatus::Created
+        };
+        let contents = serde_json::to_string_pretty(&current)?;
+        std::fs::write(path, contents + "\n")
+            .with_context(|| format!("Failed to write plan baseline: {}", file))?;
+        return Ok(BaselineOutcome {
+            file,
+            status,
+            fingerprint: current.fingerprint,
+            baseline_fingerprint: None,
+            baseline_recorded_at: None,
+            plan_changed: false,
+            baseline_total_cost: None,
+            total_cost: current.total_cost,
+            changes: Vec::new(),
+        });
+    }
+
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read plan baseline: {}", file))?;
+    let baseline: PlanBaseline = serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid plan baseline: {}", file))?;
+    if baseline.version != BASELINE_VERSION {
+        bail!(
+            "Plan baseline {} has unsupported version {} (expected {})",
+            file,
+            baseline.version,
+            BASELINE_VERSION
+        );
+    }
+    if baseline.query != current.query {
+        bail!(
+            "Plan baseline {} was recorded for a different query. Use --update-baseline to replace it.",
+            file
+        );
+    }
+
+    let changes = compare(&baseline, &current);
+    Ok(BaselineOutcome {
+        file,
+        status: BaselineStatus::Compared,
+        plan_changed: baseline.fingerprint != current.fingerprint,
+        fingerprint: current.fingerprint,
+        baseline_fingerprint: Some(baseline.fingerprint),
+        baseline_recorded_at: Some(baseline.recorded_at),
+        baseline_total_cost: Some(baseline.total_cost),
+        total_cost: current.total_cost,
+        changes,
+    })
+}
+
+/// "Index Scan using orders_pkey", "Seq Scan", ...
+fn scan_description(nodes: &[PlanNodeSummary], at: usize) -> String {
+    let node = &nodes[at];
+    let mut indexes: Vec<&str> = node.index.as_deref().into_iter().collect();
+    // Bitmap heap scans name their indexes on the child Bitmap Index Scans
+    if indexes.is_empty() {
+        indexes.extend(
+            nodes[at + 1..]
+                .iter()
+                .take_while(|n| n.depth > node.depth)
+                .filter_map(|n| n.index.as_deref()),
+        );
+    }
+    if indexes.is_empty() {
+        node.node_type.clone()
+    } else {
+        format!("{} using {}", node.node_type, indexes.join(", "))
+    }
+}
+
+/// Scans keyed by relation (in plan order for repeated relations)
+fn scans(nodes: &[PlanNodeSummary]) -> BTreeMap<String, Vec<(String, i64, bool)>> {
+    let mut scans: BTreeMap<String, Vec<(String, i64, bool)>> = BTreeMap::new();
+    for (i, node) in nodes.iter().enumerate() {
+        if let Some(ref relation) = node.relation {
+            scans.entry(relation.clone()).or_default().push((
+                scan_description(nodes, i),
+                node.rows,
+                node.node_type == "Seq Scan",
+            ));
+        }
+    }
+    scans
+}
+
+fn ratio(a: f64, b: f64) -> f64 {
+    a.max(b) / a.min(b).max(1.0)
+}
+
+fn node_type_counts(nodes: &[PlanNodeSummary]) -> BTreeMap<&str, usize> {
+    let mut counts = BTreeMap::new();
+    for node in nodes {
+        if node.relation.is_none()
+            && !JOIN_NODES.contains(&node.node_type.as_str())
+            && !node.node_type.starts_with("Bitmap Index")
+        {
+            *counts.entry(node.node_type.as_str()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Differences between a baseline and a fresh plan, most severe first
+pub fn compare(baseline: &PlanBaseline, current: &PlanBaseline) -> Vec<PlanChange> {
+    let mut changes = Vec::new();
+
+    // Scans per relation
+    let before = scans(&baseline.nodes);
+    let after = scans(&current.nodes);
+    for (relation, old) in &before {
+        let new = after.get(relation).map(Vec::as_slice).unwrap_or_default();
+        for (i, (old_desc, old_rows, old_seq)) in old.iter().enumerate() {
+            let Some((new_desc, new_rows, new_seq)) = new.get(i) else {
+                changes.push(PlanChange {
+                    kind: PlanChangeKind::ScanRemoved,
+                    severity: IssueSeverity::Info,
+                    message: format!("{} no longer scanned ({})", relation, old_desc),
+                    relation: Some(relation.clone()),
+                    before: Some(old_desc.clone()),
+                    after: None,
+                });
+                continue;
+            };
+            if old_desc != new_desc {
+                let (kind, severity) = if *new_seq && !old_seq {
+                    (PlanChangeKind::SeqScanAppeared, IssueSeverity::Critical)
+                } else {
+                    (PlanChangeKind::ScanChanged, IssueSeverity::Warning)
+                };
+                changes.push(PlanChange {
+                    kind,
+                    severity,
+                    message: format!("{}: {} -> {}", relation, old_desc, new_desc),
+                    relation: Some(relation.clone()),
+                    before: Some(old_desc.clone()),
+                    after: Some(new_desc.clone()),
+                });
+            }
+            if ratio(*old_rows as f64, *new_rows as f64) >= ROW_DRIFT_RATIO {
+                changes.push(PlanChange {
+                    kind: PlanChangeKind::RowEstimateDrift,
+                    severity: IssueSeverity::Warning,
+                    message: format!("{}: row estimate {} -> {}", relation, old_rows, new_rows),
+                    relation: Some(relation.clone()),
+                    before: Some(old_rows.to_string()),
+                    after: Some(new_rows.to_string()),
+                });
+            }
+        }
+    }
+    for (relation, new) in &after {
+        let old_len = before.get(relation).map(Vec::len).unwrap_or(0);
+        for (desc, _, seq) in new.iter().skip(old_len) {
+            let (kind, severity) = if *seq {
+                (PlanChangeKind::SeqScanAppeared, IssueSeverity::Critical)
+            } else {
+                (PlanChangeKind::ScanAdded, IssueSeverity::Info)
+            };
+            changes.push(PlanChange {
+                kind,
+                severity,
+                message: format!("{}: new {}", relation, desc),
+                relation: Some(relation.clone()),
+                before: None,
+                after: Some(desc.clone()),
+            });
+        }
+    }
+
+    // Join strategies in plan order
+    let joins = |nodes: &[PlanNodeSummary]| -> Vec<String> {
+        nodes
+            .iter()
+            .filter(|n| JOIN_NODES.contains(&n.node_type.as_str()))
+            .map(|n| n.node_type.clone())
+            .collect()
+    };
+    let (old_joins, new_joins) = (joins(&baseline.nodes), joins(&current.nodes));
+    if old_joins != new_joins {
+        let list = |j: &[String]| {
+            if j.is_empty() {
+                "none".to_string()
+            } else {
+                j.join(", ")
+            }
+        };
+        changes.push(PlanChange {
+            kind: PlanChangeKind::JoinStrategyChanged,
+            severity: IssueSeverity::Warning,
+            message: format!(
+                "Join strategy changed: {} -> {}",
+                list(&old_joins),
+                list(&new_joins)
+            ),
+            relation: None,
+            before: Some(list(&old_joins)),
+            after: Some(list(&new_joins)),
+        });
+    }
+
+    // Other node types (Sort, Hash, Aggregate, ...)
+    let old_counts = node_type_counts(&baseline.nodes);
+    let new_counts = node_type_counts(&current.nodes);
+    for (node_type, &count) in &new_counts {
+        if count > old_counts.get(node_type).copied().unwrap_or(0) {
+            changes.push(PlanChange {
+                kind: PlanChangeKind::NodeAppeared,
+                severity: IssueSeverity::Info,
+                message: format!("{} node appeared", node_type),
+                relation: None,
+                before: None,
+                after: Some(node_type.to_string()),
+            });
+        }
+    }
+    for (node_type, &count) in &old_counts {
+        if count > new_counts.get(node_type).copied().unwrap_or(0) {
+            changes.push(PlanChange {
+                kind: PlanChangeKind::NodeRemoved,
+                severity: IssueSeverity::Info,
+                message: format!("{} node removed", node_type),
+                relation: None,
+                before: Some(node_type.to_string()),
+                after: None,
+            });
+        }
+    }
+
+    // Root estimates
+    let cost_ratio = ratio(baseline.total_cost, current.total_cost);
+    if current.total_cost > baseline.total_cost && cost_ratio >= COST_WARNING_RATIO {
+        changes.push(PlanChange {
+            kind: PlanChangeKind::CostIncreased,
+            severity: if cost_ratio >= COST_CRITICAL_RATIO {
+                IssueSeverity::Critical
+            } else {
+                IssueSeverity::Warning
+            },
+            message: format!(
+                "Estimated cost {:.2} -> {:.2} ({:.1}x)",
+                baseline.total_cost, current.total_cost, cost_ratio
+            ),
+            relation: None,
+            before: Some(format!("{:.2}", baseline.total_cost)),
+            after: Some(format!("{:.2}", current.total_cost)),
+        });
+    } else if current.total_cost < baseline.total_cost && cost_ratio >= COST_WARNING_RATIO {
+        changes.push(PlanChange {
+            kind: PlanChangeKind::CostDecreased,
+            severity: IssueSeverity::Info,
+            message: format!(
+                "Estimated cost {:.2} -> {:.2} (improved)",
+                baseline.total_cost, current.total_cost
+            ),
+            relation: None,
+            before: Some(format!("{:.2}", baseline.total_cost)),
+            after: Some(format!("{:.2}", current.total_cost)),
+        });
+    }
+    if ratio(baseline.rows as f64, current.rows as f64) >= ROW_DRIFT_RATIO {
+        changes.push(PlanChange {
+            kind: PlanChangeKind::RowEstimateDrift,
+            severity: IssueSeverity::Warning,
+            message: format!("Result row estimate {} -> {}", baseline.rows, current.rows),
+            relation: None,
+            before: Some(baseline.rows.to_string()),
+            after: Some(current.rows.to_string()),
+        });
+    }
+
+    changes.sort_by_key(|c| std::cmp::Reverse(c.severity));
+    changes
+}
+
+/// Print the baseline section of `dba explain`
+pub fn print_human(outcome: &BaselineOutcome) {
+    println!("BASELINE ({}):", outcome.file);
+    match outcome.status {
+        BaselineStatus::Created => {
+            println!("  Recorded plan fingerprint {}", outcome.fingerprint);
+        }
+        BaselineStatus::Updated => {
+            println!(
+                "  Replaced baseline with fingerprint {}",
+                outcome.fingerprint
+            );
+        }
+        BaselineStatus::Compared => {
+            if outcome.plan_changed {
+                println!(
+                    "  Plan changed: {} -> {}",
+                    outcome.baseline_fingerprint.as_deref().unwrap_or("?"),
+                    outcome.fingerprint
+                );
+            } else {
+                println!("  Plan shape matches baseline ({})", outcome.fingerprint);
+            }
+            if let Some(ref at) = outcome.baseline_recorded_at {
+                println!("  Baseline recorded: {}", at);
+            }
+            for change in &outcome.changes {
+                let icon = match change.severity {
+                    IssueSeverity::Info => "ℹ",
+                    IssueSeverity::Warning => "⚠",
+                    IssueSeverity::Critical => "✗",
+                };
+                println!("  {} {}", icon, change.message);
+            }
+            if outcome.changes.is_empty() {
+                println!("  No regressions detected.");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plan(root: Value) -> Value {
+        json!([{ "Plan": root }])
+    }
+
+    fn index_plan(cost: f64) -> Value {
+        plan(json!({
+            "Node Type": "Nested Loop", "Join Type": "Inner",
+            "Total Cost": cost, "Plan Rows": 10,
+            "Plans": [
+                {"Node Type": "Index Scan", "Relation Name": "orders", "Schema": "public",
+                 "Alias": "orders", "Index Name": "orders_pkey", "Total Cost": 8.0, "Plan Rows": 1},
+                {"Node Type": "Index Scan", "Relation Name": "users", "Schema": "public",
+                 "Alias": "u", "Index Name": "users_pkey", "Total Cost": 8.0, "Plan Rows": 1}
+            ]
+        }))
+    }
+
+    #[test]
+    fn test_summarize_and_fingerprint_ignore_costs() {
+        let a = summarize(&index_plan(16.0));
+        let b = summarize(&index_plan(20.0));
+        assert_eq!(a.len(), 3);
+        assert_eq!(a[2].relation.as_deref(), Some("public.users u"));
+        assert_eq!(a[1].relation.as_deref(), Some("public.orders"));
+        assert_eq!(fingerprint(&a), fingerprint(&b));
+        assert_eq!(fingerprint(&a).len(), 16);
+    }
+
+    #[test]
+    fn test_compare_flags_seq_scan_and_join_flip() {
+        let baseline = build_baseline("select 1", summarize(&index_plan(16.0)));
+        let current = build_baseline(
+            "select 1",
+            summarize(&plan(json!({
+                "Node Type": "Hash Join", "Join Type": "Inner",
+                "Total Cost": 1200.0, "Plan Rows": 10,
+                "Plans": [
+                    {"Node Type": "Seq Scan", "Relation Name": "orders", "Schema": "public",
+                     "Alias": "orders", "Total Cost": 1000.0, "Plan Rows": 50000},
+                    {"Node Type": "Hash", "Total Cost": 8.0, "Plan Rows": 1, "Plans": [
+                        {"Node Type": "Index Scan", "Relation Name": "users", "Schema": "public",
+                         "Alias": "u", "Index Name": "users_pkey", "Total Cost": 8.0, "Plan Rows": 1}
+                    ]}
+                ]
+            }))),
+        );
+        assert_ne!(baseline.fingerprint, current.fingerprint);
+
+        let changes = compare(&baseline, &current);
+        let kinds: Vec<_> = changes.iter().map(|c| c.kind).collect();
+        assert_eq!(changes[0].severity, IssueSeverity::Critical);
+        assert!(kinds.contains(&PlanChangeKind::SeqScanAppeared));
+        assert!(kinds.contains(&PlanChangeKind::JoinStrategyChanged));
+        assert!(kinds.contains(&PlanChangeKind::CostIncreased));
+        assert!(kinds.contains(&PlanChangeKind::RowEstimateDrift));
+        assert!(kinds.contains(&PlanChangeKind::NodeAppeared));
+        // users is still scanned the same way
+        assert!(!changes
+            .iter()
+            .any(|c| c.relation.as_deref() == Some("public.users u")));
+    }
+
+    #[test]
+    fn test_compare_identical_plans_has_no_changes() {
+        let baseline = build_baseline("select 1", summarize(&index_plan(16.0)));
+        let current = build_baseline("select  1;", summarize(&index_plan(17.0)));
+        assert_eq!(baseline.query, current.query);
+        assert!(compare(&baseline, &current).is_empty());
+    }
+
+    #[test]
+    fn test_bitmap_scan_names_child_index() {
+        let nodes = summarize(&plan(json!({
+            "Node Type": "Bitmap Heap Scan", "Relation Name": "orders", "Schema": "public",
+            "Alias": "orders", "Total Cost": 50.0, "Plan Rows": 100,
+            "Plans": [{"Node Type": "Bitmap Index Scan", "Index Name": "orders_status_idx",
+                       "Total Cost": 5.0, "Plan Rows": 100}]
+        })));
+        assert_eq!(
+            scan_description(&nodes, 0),
+            "Bitmap Heap Scan using orders_status_idx"
+        );
+    }
+}
@@ -0,0 +1,575 @@
+//! Pooler command: pgbouncer pool saturation and wait times.
+//!
+//! Connects to the pgbouncer admin console (the virtual `pgbouncer`
+//! database) and reads SHOW POOLS, SHOW DATABASES, and SHOW STATS. Each pool
+//! is reported with its client/server counts, how full it is against
+//! pool_size, how long clients have waited for a server, and how many
+//! backends Postgres itself sees for the same database and user, so pooler
+//! numbers can be read next to `dba connections`.
+//!
+//! The admin console only speaks the simple query protocol and rejects
+//! session SETs, so it is queried on a bare connection rather than a
+//! DiagnosticSession.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use tokio_postgres::{Client, NoTls, SimpleQueryMessage};
+
+use crate::config::parse_database_url;
+use crate::diagnostic::TimeoutConfig;
+
+/// Pool usage (% of pool_size with an active server) that warns
+const SATURATION_WARNING_PCT: f64 = 80.0;
+/// Oldest client wait (seconds) that warns / is critical
+const WAIT_WARNING_SECS: f64 = 1.0;
+const WAIT_CRITICAL_SECS: f64 = 5.0;
+
+/// pgbouncer's default listen port, used as a detection hint
+const PGBOUNCER_PORT: u16 = 6432;
+
+/// Pool status level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PoolStatus {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl PoolStatus {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            PoolStatus::Healthy => "✓",
+            PoolStatus::Warning => "⚠",
+            PoolStatus::Critical => "✗",
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            PoolStatus::Healthy => 0,
+            PoolStatus::Warning => 1,
+            PoolStatus::Critical => 2,
+        }
+    }
+}
+
+/// Evidence that the database connection goes through a pooler
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolerDetection {
+    /// Port pgcrate connected to
+    pub client_port: u16,
+    /// Port the Postgres server listens on
+    pub server_port: i32,
+    pub reason: String,
+}
+
+/// One pgbouncer pool (database + user)
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStats {
+    /// Database name as configured in pgbouncer
+    pub database: String,
+    pub user: String,
+    /// Database name on the Postgres server, when it differs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_database: Option<String>,
+    pub pool_mode: String,
+    pub cl_active: i64,
+    pub cl_waiting: i64,
+    pub sv_active: i64,
+    pub sv_idle: i64,
+    pub sv_used: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_size: Option<i64>,
+    /// sv_active as a percentage of pool_size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saturation_pct: Option<f64>,
+    /// How long the oldest waiting client has waited
+    pub maxwait_secs: f64,
+    /// Average time clients waited for a server (per database, from SHOW STATS)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_wait_us: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_query_us: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_xact_us: Option<i64>,
+    /// Client backends pg_stat_activity shows for this database and user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postgres_backends: Option<i64>,
+    pub status: PoolStatus,
+}
+
+/// Full pooler results
+#[derive(Debug, Serialize)]
+pub struct PoolerResult {
+    /// Admin console address (host:port, never includes credentials)
+    pub admin_host: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detection: Option<PoolerDetection>,
+    pub pools: Vec<PoolStats>,
+    pub clients_active: i64,
+    pub clients_waiting: i64,
+    /// Server connections pgbouncer holds open
+    pub server_connections: i64,
+    /// Client backends on the Postgres server, pooled or not
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postgres_backends: Option<i64>,
+    pub overall_status: PoolStatus,
+}
+
+/// An admin console row, column name to text value
+type AdminRow = HashMap<String, String>;
+
+/// Guess whether `client` reached Postgres through a pooler.
+///
+/// pgbouncer forwards queries to a server listening elsewhere, so the
+/// server's `port` setting differs from the port in the connection URL.
+/// Returns None when the ports match or the URL has no parseable port.
+pub async fn detect_pooler(client: &Client, database_url: &str) -> Option<PoolerDetection> {
+    let url = url::Url::parse(database_url).ok()?;
+    let client_port = url.port().unwrap_or(5432);
+    let row = client
+        .query_one("SELECT current_setting('port')::int", &[])
+        .await
+        .ok()?;
+    let server_port: i32 = row.get(0);
+    detection_from_ports(client_port, server_port)
+}
+
+fn detection_from_ports(client_port: u16, server_port: i32) -> Option<PoolerDetection> {
+    if i32::from(client_port) == server_port {
+        return None;
+    }
+    let mut reason = format!(
+        "connected on port {} but the server listens on {}",
+        client_port, server_port
+    );
+    if client_port == PGBOUNCER_PORT {
+        reason.push_str(" (6432 is pgbouncer's default port)");
+    }
+    Some(PoolerDetection {
+        client_port,
+        server_port,
+        reason,
+    })
+}
+
+/// Admin console URL for a detected pooler: same host and port, database `pgbouncer`
+pub fn admin_url_from(database_url: &str) -> Result<String> {
+    let parsed = parse_database_url(database_url)
+        .context("Cannot derive a pgbouncer admin URL from the connection string")?;
+    Ok(parsed.with_database("pgbouncer"))
+}
+
+/// host:port of a URL, for display
+fn display_host(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .map(|u| {
+            format!(
+                "{}:{}",
+                u.host_str().unwrap_or("localhost"),
+                u.port().unwrap_or(PGBOUNCER_PORT)
+            )
+        })
+        .unwrap_or_else(|| "pgbouncer".to_string())
+}
+
+/// Run a SHOW command on the admin console
+async fn show(admin: &Client, command: &str, timeouts: &TimeoutConfig) -> Result<Vec<AdminRow>> {
+    let messages = tokio::time::timeout(timeouts.statement_timeout, admin.simple_query(command))
+        .await
+        .with_context(|| format!("{} timed out", command))?
+        .with_context(|| format!("{} failed on the pgbouncer admin console", command))?;
+
+    Ok(messages
+        .into_iter()
+        .filter_map(|m| match m {
+            SimpleQueryMessage::Row(row) => Some(
+                row.columns()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        (
+                            c.name().to_string(),
+                            row.get(i).unwrap_or_default().to_string(),
+                        )
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Integer column, 0 when missing (column sets vary by pgbouncer version)
+fn int(row: &AdminRow, column: &str) -> i64 {
+    row.get(column).and_then(|v| v.parse().ok()).unwrap_or(0)
+}
+
+fn text(row: &AdminRow, column: &str) -> String {
+    row.get(column).cloned().unwrap_or_default()
+}
+
+fn pool_status(cl_waiting: i64, saturation_pct: Option<f64>, maxwait_secs: f64) -> PoolStatus {
+    if maxwait_secs >= WAIT_CRITICAL_SECS {
+        PoolStatus::Critical
+    } else if cl_waiting > 0
+        || maxwait_secs >= WAIT_WARNING_SECS
+        || saturation_pct.is_some_and(|p| p >= SATURATION_WARNING_PCT)
+    {
+        PoolStatus::Warning
+    } else {
+        PoolStatus::Healthy
+    }
+}
+
+/// Combine SHOW POOLS, SHOW DATABASES, and SHOW STATS into per-pool stats
+fn build_pools(pools: &[AdminRow], databases: &[AdminRow], stats: &[AdminRow]) -> Vec<PoolStats> {
+    let databases: HashMap<String, &AdminRow> =
+        databases.iter().map(|r| (text(r, "name"), r)).collect();
+    let stats: HashMap<String, &AdminRow> =
+        stats.iter().map(|r| (text(r, "database"), r)).collect();
+
+    let mut result: Vec<PoolStats> = pools
+        .iter()
+        .filter(|r| text(r, "database") != "pgbouncer")
+        .map(|r| {
+            let database = text(r, "database");
+            let db = databases.get(&database);
+            let st = stats.get(&database);
+
+            let pool_size = db.map(|d| int(d, "pool_size")).filter(|s| *s > 0);
+            let sv_active = int(r, "sv_active");
+            let saturation_pct = pool_size.map(|s| 100.0 * sv_active as f64 / s as f64);
+            let maxwait_secs = int(r, "maxwait") as f64 + int(r, "maxwait_us") as f64 / 1e6;
+            let cl_waiting = int(r, "cl_waiting");
+            let server_database = db
+                .map(|d| text(d, "database"))
+                .filter(|name| !name.is_empty() && *name != database);
+
+            PoolStats {
+                user: text(r, "user"),
+                server_database,
+                pool_mode: text(r, "pool_mode"),
+                cl_active: int(r, "cl_active"),
+                cl_waiting,
+                sv_active,
+                sv_idle: int(r, "sv_idle"),
+                sv_used: int(r, "sv_used"),
+                pool_size,
+                saturation_pct,
+                maxwait_secs,
+                avg_wait_us: st.map(|s| int(s, "avg_wait_time")),
+                avg_query_us: st.map(|s| int(s, "avg_query_time")),
+                avg_xact_us: st.map(|s| int(s, "avg_xact_time")),
+                postgres_backends: None,
+                status: pool_status(cl_waiting, saturation_pct, maxwait_secs),
+                database,
+            }
+        })
+        .collect();
+
+    result.sort_by(|a, b| {
+        b.status
+            .rank()
+            .cmp(&a.status.rank())
+            .then(b.cl_waiting.cmp(&a.cl_waiting))
+            .then(a.database.cmp(&b.database))
+            .then(a.user.cmp(&b.user))
+    });
+    result
+}
+
+/// Fill in pg_stat_activity backend counts per pool
+async fn add_postgres_backends(client: &Client, pools: &mut [PoolStats]) -> Result<i64> {
+    let query = r#"
+        SELECT
+            COALESCE(datname, '') AS datname,
+            COALESCE(usename, '') AS usename,
+            COUNT(*)::bigint AS count
+        FROM pg_stat_activity
+        WHERE backend_type = 'client backend'
+          AND pid != pg_backend_pid()
+        GROUP BY datname, usename
+    "#;
+    let rows = client
+        .query(query, &[])
+        .await
+        .context("Failed to count backends")?;
+
+    let mut counts: HashMap<(String, String), i64> = HashMap::new();
+    for row in &rows {
+        counts.insert((row.get("datname"), row.get("usename")), row.get("count"));
+    }
+
+    for pool in pools.iter_mut() {
+        let database = pool.server_database.as_ref().unwrap_or(&pool.database);
+        pool.postgres_backends = Some(
+            counts
+                .get(&(database.clone(), pool.user.clone()))
+                .copied()
+                .unwrap_or(0),
+        );
+    }
+    Ok(counts.values().sum())
+}
+
+/// Read pool stats from the admin console at `admin_url`
+pub async fn run_pooler(
+    client: &Client,
+    admin_url: &str,
+    detection: Option<PoolerDetection>,
+    timeouts: &TimeoutConfig,
+) -> Result<PoolerResult> {
+    let admin_host = display_host(admin_url);
+    let (admin, connection) = tokio::time::timeout(
+        timeouts.connect_timeout,
+        tokio_postgres::connect(admin_url, NoTls),
+    )
+    .await
+    .with_context(|| format!("Connection to {} timed out", admin_host))?
+    .with_context(|| {
+        format!(
+            "Failed to connect to pgbouncer admin console at {}",
+            admin_host
+        )
+    })?;
+    tokio::spawn(async move {
+        let _ = connection.await;
+    });
+
+    let version = show(&admin, "SHOW VERSION", timeouts)
+        .await
+        .ok()
+        .and_then(|rows| rows.into_iter().next())
+        .and_then(|r| r.into_values().next());
+    let pool_rows = show(&admin, "SHOW POOLS", timeouts).await?;
+    if pool_rows.is_empty() {
+        bail!(
+            "{} returned no pools; is it a pgbouncer admin console?",
+            admin_host
+        );
+    }
+    let database_rows = show(&admin, "SHOW DATABASES", timeouts).await?;
+    let stats_rows = show(&admin, "SHOW STATS", timeouts).await?;
+
+    let mut pools = build_pools(&pool_rows, &database_rows, &stats_rows);
+    // Postgres-side counts are a correlation aid; missing them isn't fatal
+    let postgres_backends = add_postgres_backends(client, &mut pools).await.ok();
+
+    let overall_status = pools
+        .iter()
+        .map(|p| p.status)
+        .max_by_key(|s| s.rank())
+        .unwrap_or(PoolStatus::Healthy);
+
+    Ok(PoolerResult {
+        admin_host,
+        version,
+        detection,
+        clients_active: pools.iter().map(|p| p.cl_active).sum(),
+        clients_waiting: pools.iter().map(|p| p.cl_waiting).sum(),
+        server_connections: pools
+            .iter()
+            .map(|p| p.sv_active + p.sv_idle + p.sv_used)
+            .sum(),
+        postgres_backends,
+        pools,
+        overall_status,
+    })
+}
+
+fn format_us(us: i64) -> String {
+    if us >= 1_000_000 {
+        format!("{:.1}s", us as f64 / 1e6)
+    } else {
+        format!("{:.1}ms", us as f64 / 1e3)
+    }
+}
+
+/// Print pooler results in human-readable format
+pub fn print_human(result: &PoolerResult, quiet: bool) {
+    if !quiet {
+        println!(
+            "PGBOUNCER: {}{}",
+            result.admin_host,
+            result
+                .version
+                .as_ref()
+                .map(|v| format!(" ({})", v))
+                .unwrap_or_default()
+        );
+        if let Some(ref d) = result.detection {
+            println!("  Detected: {}", d.reason);
+        }
+        println!(
+            "  Clients: {} active, {} waiting; server connections: {}",
+            result.clients_active, result.clients_waiting, result.server_connections
+        );
+        if let Some(backends) = result.postgres_backends {
+            println!("  Postgres client backends: {}", backends);
+        }
+        println!();
+    }
+
+    let width = result
+        .pools
+        .iter()
+        .map(|p| p.database.len() + p.user.len() + 1)
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    if !quiet && !result.pools.is_empty() {
+        println!(
+            "  {:width$}  {:11}  {:>9}  {:>11}  {:>8}  {:>8}  {:>8}",
+            "POOL",
+            "MODE",
+            "CL ACT/WT",
+            "SV ACT/SIZE",
+            "MAXWAIT",
+            "AVG WAIT",
+            "PG",
+            width = width
+        );
+    }
+    for pool in &result.pools {
+        if quiet && pool.status == PoolStatus::Healthy {
+            continue;
+        }
+        let size = pool
+            .pool_size
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{} {:width$}  {:11}  {:>9}  {:>11}  {:>7.1}s  {:>8}  {:>8}",
+            pool.status.emoji(),
+            format!("{}/{}", pool.database, pool.user),
+            pool.pool_mode,
+            format!("{}/{}", pool.cl_active, pool.cl_waiting),
+            format!("{}/{}", pool.sv_active, size),
+            pool.maxwait_secs,
+            pool.avg_wait_us.map(format_us).unwrap_or_default(),
+            pool.postgres_backends
+                .map(|b| b.to_string())
+                .unwrap_or_default(),
+            width = width
+        );
+    }
+
+    if !quiet && result.clients_waiting > 0 {
+        println!();
+        println!(
+            "  ⚠ {} client(s) waiting for a server connection; raise pool_size or find long transactions (pgcrate dba locks --long-tx)",
+            result.clients_waiting
+        );
+    }
+}
+
+/// Print pooler results as JSON
+pub fn print_json(
+    result: &PoolerResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = match result.overall_status {
+        PoolStatus::Healthy => Severity::Healthy,
+        PoolStatus::Warning => Severity::Warning,
+        PoolStatus::Critical => Severity::Critical,
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::POOLER, result, severity, t),
+        None => DiagnosticOutput::new(schema::POOLER, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pairs: &[(&str, &str)]) -> AdminRow {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_detection_from_ports() {
+        assert!(detection_from_ports(5432, 5432).is_none());
+        let d = detection_from_ports(6432, 5432).unwrap();
+        assert!(d.reason.contains("pgbouncer's default port"));
+        let d = detection_from_ports(7000, 5432).unwrap();
+        assert!(!d.reason.contains("pgbouncer"));
+    }
+
+    #[test]
+    fn test_build_pools() {
+        let pools = vec![
+            row(&[("database", "pgbouncer"), ("user", "pgbouncer")]),
+            row(&[
+                ("database", "app"),
+                ("user", "web"),
+                ("cl_active", "40"),
+                ("cl_waiting", "3"),
+                ("sv_active", "20"),
+                ("sv_idle", "0"),
+                ("maxwait", "2"),
+                ("maxwait_us", "500000"),
+                ("pool_mode", "transaction"),
+            ]),
+            row(&[
+                ("database", "reports"),
+                ("user", "bi"),
+                ("cl_active", "1"),
+                ("sv_active", "1"),
+                ("pool_mode", "session"),
+            ]),
+        ];
+        let databases = vec![
+            row(&[
+                ("name", "app"),
+                ("database", "app_prod"),
+                ("pool_size", "20"),
+            ]),
+            row(&[
+                ("name", "reports"),
+                ("database", "reports"),
+                ("pool_size", "10"),
+            ]),
+        ];
+        let stats = vec![row(&[("database", "app"), ("avg_wait_time", "1500")])];
+
+        let result = build_pools(&pools, &databases, &stats);
+        assert_eq!(result.len(), 2);
+
+        let app = &result[0];
+        assert_eq!(app.database, "app");
+        assert_eq!(app.server_database.as_deref(), Some("app_prod"));
+        assert_eq!(app.saturation_pct, Some(100.0));
+        assert_eq!(app.maxwait_secs, 2.5);
+        assert_eq!(app.avg_wait_us, Some(1500));
+        assert_eq!(app.status, PoolStatus::Warning);
+
+        let reports = &result[1];
+        assert_eq!(reports.server_database, None);
+        assert_eq!(reports.avg_wait_us, None);
+        assert_eq!(reports.status, PoolStatus::Healthy);
+    }
+
+    #[test]
+    fn test_pool_status() {
+        assert_eq!(pool_status(0, Some(50.0), 0.0), PoolStatus::Healthy);
+        assert_eq!(pool_status(0, Some(80.0), 0.0), PoolStatus::Warning);
+        assert_eq!(pool_status(1, None, 0.0), PoolStatus::Warning);
+        assert_eq!(pool_status(1, None, 5.0), PoolStatus::Critical);
+    }
+}
@@ -0,0 +1,296 @@
+//! Prepared command: Orphaned two-phase-commit transactions.
+//!
+//! A transaction left behind by PREPARE TRANSACTION survives disconnects and
+//! restarts until something runs COMMIT PREPARED or ROLLBACK PREPARED. Until
+//! then it holds its locks and its xmin, so VACUUM cannot remove dead rows
+//! newer than it and datfrozenxid cannot advance past it, which ends in
+//! wraparound. Transaction managers normally resolve them within seconds, so
+//! anything older than the threshold is likely orphaned.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::fix::common::StructuredAction;
+
+/// Age at which a prepared transaction is reported (overridden by --older-than)
+pub const DEFAULT_THRESHOLD: Duration = Duration::from_secs(5 * 60);
+
+/// Age at which it is critical (or the threshold, if larger)
+const CRITICAL_SECS: f64 = 3600.0;
+
+/// Prepared transaction status level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreparedStatus {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl PreparedStatus {
+    pub fn from_age(age_secs: f64, threshold_secs: f64) -> Self {
+        if age_secs >= CRITICAL_SECS.max(threshold_secs) {
+            PreparedStatus::Critical
+        } else if age_secs >= threshold_secs {
+            PreparedStatus::Warning
+        } else {
+            PreparedStatus::Healthy
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            PreparedStatus::Healthy => "✓",
+            PreparedStatus::Warning => "⚠",
+            PreparedStatus::Critical => "✗",
+        }
+    }
+}
+
+/// One row of pg_prepared_xacts
+#[derive(Debug, Clone, Serialize)]
+pub struct PreparedXact {
+    pub gid: String,
+    pub database: String,
+    pub owner: String,
+    pub prepared_at: String,
+    pub age_secs: f64,
+    /// XID age of the prepared transaction (how far it holds back freezing)
+    pub xid_age: i64,
+    pub status: PreparedStatus,
+}
+
+/// Full prepared transaction results
+#[derive(Debug, Serialize)]
+pub struct PreparedResult {
+    /// 0 means PREPARE TRANSACTION is disabled on this server
+    pub max_prepared_transactions: i32,
+    /// Every prepared transaction in the cluster, oldest first
+    pub transactions: Vec<PreparedXact>,
+    /// Transactions at or past the threshold
+    pub stale_count: usize,
+    pub threshold_secs: f64,
+    pub overall_status: PreparedStatus,
+    /// Structured fix actions (when --include-actions is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actions: Option<Vec<StructuredAction>>,
+}
+
+/// Prepared transactions in every database, oldest first
+pub async fn get_prepared_xacts(
+    client: &Client,
+    threshold_secs: f64,
+) -> Result<Vec<PreparedXact>, tokio_postgres::Error> {
+    let query = r#"
+        SELECT
+            gid,
+            database::text AS database,
+            owner::text AS owner,
+            prepared,
+            EXTRACT(EPOCH FROM now() - prepared)::float8 AS age_secs,
+            age(transaction)::int8 AS xid_age
+        FROM pg_prepared_xacts
+        ORDER BY prepared
+    "#;
+
+    let rows = client.query(query, &[]).await?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let prepared_at: chrono::DateTime<chrono::Utc> = row.get("prepared");
+            let age_secs: f64 = row.get("age_secs");
+            PreparedXact {
+                gid: row.get("gid"),
+                database: row.get("database"),
+                owner: row.get("owner"),
+                prepared_at: prepared_at.to_rfc3339(),
+                age_secs,
+                xid_age: row.get("xid_age"),
+                status: PreparedStatus::from_age(age_secs, threshold_secs),
+            }
+        })
+        .collect())
+}
+
+/// Run prepared transaction analysis
+pub async fn run_prepared(client: &Client, threshold: Duration) -> Result<PreparedResult> {
+    let threshold_secs = threshold.as_secs_f64();
+    let max_prepared_transactions: i32 = client
+        .query_one(
+            "SELECT current_setting('max_prepared_transactions')::int",
+            &[],
+        )
+        .await
+        .context("Failed to read max_prepared_transactions")?
+        .get(0);
+    let transactions = get_prepared_xacts(client, threshold_secs)
+        .await
+        .context("Failed to query pg_prepared_xacts")?;
+
+    let stale_count = transactions
+        .iter()
+        .filter(|t| t.status != PreparedStatus::Healthy)
+        .count();
+    let overall_status = transactions
+        .iter()
+        .map(|t| t.status)
+        .max()
+        .unwrap_or(PreparedStatus::Healthy);
+
+    Ok(PreparedResult {
+        max_prepared_transactions,
+        transactions,
+        stale_count,
+        threshold_secs,
+        overall_status,
+        actions: None,
+    })
+}
+
+/// Rollback actions for each stale prepared transaction
+pub fn generate_actions(
+    result: &PreparedResult,
+    read_write: bool,
+    is_primary: bool,
+) -> Vec<StructuredAction> {
+    result
+        .transactions
+        .iter()
+        .filter(|t| t.status != PreparedStatus::Healthy)
+        .map(|t| super::fix::prepared::create_rollback_action(t, read_write, is_primary, false))
+        .collect()
+}
+
+fn format_age(secs: f64) -> String {
+    if secs >= 86400.0 {
+        format!("{:.1}d", secs / 86400.0)
+    } else if secs >= 3600.0 {
+        format!("{:.1}h", secs / 3600.0)
+    } else if secs >= 60.0 {
+        format!("{:.0}m", secs / 60.0)
+    } else {
+        format!("{:.0}s", secs)
+    }
+}
+
+/// Print prepared transactions in human-readable format
+pub fn print_human(result: &PreparedResult, quiet: bool) {
+    if result.transactions.is_empty() {
+        if !quiet {
+            if result.max_prepared_transactions == 0 {
+                println!("PREPARED TRANSACTIONS: none (max_prepared_transactions = 0)");
+            } else {
+                println!("PREPARED TRANSACTIONS: none");
+            }
+        }
+        return;
+    }
+
+    println!(
+        "PREPARED TRANSACTIONS: {} ({} older than {})",
+        result.transactions.len(),
+        result.stale_count,
+        format_age(result.threshold_secs)
+    );
+    println!();
+
+    let gid_width = result
+        .transactions
+        .iter()
+        .map(|t| t.gid.len())
+        .max()
+        .unwrap_or(0)
+        .clamp(3, 40);
+    println!(
+        "  {:gw$}  {:>7}  {:>12}  {:20} OWNER",
+        "GID",
+        "AGE",
+        "XID AGE",
+        "DATABASE",
+        gw = gid_width
+    );
+    for t in &result.transactions {
+        if quiet && t.status == PreparedStatus::Healthy {
+            continue;
+        }
+        println!(
+            "{} {:gw$}  {:>7}  {:>12}  {:20} {}",
+            t.status.emoji(),
+            t.gid,
+            format_age(t.age_secs),
+            t.xid_age,
+            t.database,
+            t.owner,
+            gw = gid_width
+        );
+    }
+
+    if !quiet && result.stale_count > 0 {
+        println!();
+        println!("  Old prepared transactions hold locks and block VACUUM and freezing.");
+        println!("  Confirm with the transaction manager that they are abandoned, then:");
+        println!("    pgcrate --read-write --primary dba fix prepared --rollback <GID> --yes");
+        println!("  (run while connected to the transaction's database)");
+    }
+
+    if let Some(ref actions) = result.actions {
+        if !actions.is_empty() && !quiet {
+            println!();
+            println!("ACTIONS:");
+            for action in actions {
+                println!("  {}", action.description);
+            }
+        }
+    }
+}
+
+/// Print prepared transactions as JSON
+pub fn print_json(
+    result: &PreparedResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = match result.overall_status {
+        PreparedStatus::Healthy => Severity::Healthy,
+        PreparedStatus::Warning => Severity::Warning,
+        PreparedStatus::Critical => Severity::Critical,
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::PREPARED, result, severity, t),
+        None => DiagnosticOutput::new(schema::PREPARED, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_from_age() {
+        let threshold = DEFAULT_THRESHOLD.as_secs_f64();
+        assert_eq!(
+            PreparedStatus::from_age(10.0, threshold),
+            PreparedStatus::Healthy
+        );
+        assert_eq!(
+            PreparedStatus::from_age(300.0, threshold),
+            PreparedStatus::Warning
+        );
+        assert_eq!(
+            PreparedStatus::from_age(3600.0, threshold),
+            PreparedStatus::Critical
+        );
+        // A threshold past an hour is critical as soon as it is crossed
+        assert_eq!(
+            PreparedStatus::from_age(7200.0, 7200.0),
+            PreparedStatus::Critical
+        );
+    }
+}
@@ -0,0 +1,371 @@
+//! Table profile: Per-column data shape from a bounded sample.
+//!
+//! Reads at most `--sample` rows. Large tables are sampled with TABLESAMPLE
+//! SYSTEM, which picks whole pages, so clustered data can skew the numbers;
+//! REPEATABLE keeps every column's query on the same pages. Distinct counts
+//! are for the sample; `distinct_estimate` is the planner's whole-table
+//! estimate from pg_stats (present once the table has been analyzed).
+
+use anyhow::Result;
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::connect;
+use super::queries::{format_number, truncate_query};
+use crate::describe::{self, ResolvedTable};
+use crate::output::{Output, ProfileResponse};
+use crate::sql::quote_ident;
+
+/// Longest value kept in top values
+const MAX_VALUE_CHARS: i32 = 200;
+
+/// Type categories whose min/max are meaningful: numeric, string, date/time,
+/// interval, enum
+const ORDERABLE_CATEGORIES: &[&str] = &["N", "S", "D", "T", "E"];
+
+/// A value and how many sampled rows hold it
+#[derive(Debug, Clone, Serialize)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: i64,
+}
+
+/// Shape of one column within the sample
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub data_type: String,
+    pub non_null: i64,
+    pub null_fraction: f64,
+    /// Distinct non-null values in the sample
+    pub distinct: i64,
+    /// Whole-table estimate from pg_stats
+    pub distinct_estimate: Option<i64>,
+    /// Only for numeric, text, date/time, interval, and enum columns
+    pub min: Option<String>,
+    pub max: Option<String>,
+    /// Most common values; empty when every sampled value is unique
+    pub top_values: Vec<ValueCount>,
+}
+
+/// Profile of a table
+#[derive(Debug, Serialize)]
+pub struct TableProfile {
+    pub schema: String,
+    pub table: String,
+    /// pg_class.reltuples; None if the table has never been analyzed
+    pub row_estimate: Option<i64>,
+    pub rows_sampled: i64,
+    /// False when every row was read, so the numbers are exact
+    pub sampled: bool,
+    pub columns: Vec<ColumnProfile>,
+}
+
+struct ColumnMeta {
+    name: String,
+    data_type: String,
+    orderable: bool,
+    n_distinct: Option<f32>,
+}
+
+/// Percentage of pages to sample so that about `sample_rows` rows are read;
+/// None to read the table up to the limit. LIMIT keeps the bound but favors
+/// pages early in the table, so the sample aims at the limit rather than
+/// overshooting it.
+fn sample_percent(row_estimate: Option<i64>, sample_rows: i64) -> Option<f64> {
+    let rows = row_estimate.filter(|&r| r > sample_rows)?;
+    let pct = sample_rows as f64 * 100.0 / rows as f64;
+    (pct < 100.0).then_some(pct.max(0.0001))
+}
+
+/// Subquery yielding the sampled rows
+fn sample_sql(relation: &str, columns: &str, percent: Option<f64>, sample_rows: i64) -> String {
+    match percent {
+        Some(pct) => format!(
+            "SELECT {} FROM {} TABLESAMPLE SYSTEM ({:.4}) REPEATABLE (0) LIMIT {}",
+            columns, relation, pct, sample_rows
+        ),
+        None => format!("SELECT {} FROM {} LIMIT {}", columns, relation, sample_rows),
+    }
+}
+
+/// Planner distinct estimate: pg_stats stores a negative fraction of rows
+/// when the count scales with the table
+fn distinct_estimate(n_distinct: Option<f32>, row_estimate: Option<i64>) -> Option<i64> {
+    let n = n_distinct? as f64;
+    if n >= 0.0 {
+        Some(n as i64)
+    } else {
+        row_estimate.map(|rows| (-n * rows as f64).round() as i64)
+    }
+}
+
+async fn get_column_meta(
+    client: &Client,
+    resolved: &ResolvedTable,
+) -> Result<Vec<ColumnMeta>, tokio_postgres::Error> {
+    let categories: Vec<String> = ORDERABLE_CATEGORIES.iter().map(|c| c.to_string()).collect();
+    let rows = client
+        .query(
+            r#"
+            SELECT a.attname::text AS name,
+                   pg_catalog.format_type(a.atttypid, a.atttypmod) AS data_type,
+                   bt.typcategory::text = ANY($4) AS orderable,
+                   s.n_distinct
+            FROM pg_attribute a
+            JOIN pg_type t ON t.oid = a.atttypid
+            JOIN pg_type bt ON bt.oid = CASE WHEN t.typtype = 'd' THEN t.typbasetype ELSE t.oid END
+            LEFT JOIN pg_stats s
+              ON s.schemaname = $2 AND s.tablename = $3 AND s.attname = a.attname
+            WHERE a.attrelid = $1::bigint::oid
+              AND a.attnum > 0
+              AND NOT a.attisdropped
+            ORDER BY a.attnum
+            "#,
+            &[&resolved.oid, &resolved.schema, &resolved.name, &categories],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ColumnMeta {
+            name: row.get("name"),
+            data_type: row.get("data_type"),
+            orderable: row.get("orderable"),
+            n_distinct: row.get("n_distinct"),
+        })
+        .collect())
+}
+
+/// Profile every column of a table from at most `sample_rows` rows
+pub async fn profile_table(
+    client: &Client,
+    resolved: &ResolvedTable,
+    sample_rows: i64,
+    top: i64,
+) -> Result<TableProfile> {
+    let relation = format!(
+        "{}.{}",
+        quote_ident(&resolved.schema),
+        quote_ident(&resolved.name)
+    );
+    let reltuples: f32 = client
+        .query_one(
+            "SELECT reltuples FROM pg_class WHERE oid = $1::bigint::oid",
+            &[&resolved.oid],
+        )
+        .await?
+        .get(0);
+    // -1 until the first VACUUM or ANALYZE (PG14+); 0 before that on older versions
+    let row_estimate = (reltuples >= 0.0).then_some(reltuples as i64);
+    let mut percent = sample_percent(row_estimate, sample_rows);
+
+    let meta = get_column_meta(client, resolved).await?;
+
+    // One pass over the sample for counts, distincts, and min/max
+    let mut aggregates = vec!["count(*)::bigint".to_string()];
+    for col in &meta {
+        let c = quote_ident(&col.name);
+        aggregates.push(format!("count({})::bigint", c));
+        aggregates.push(format!("count(DISTINCT {}::text)::bigint", c));
+        if col.orderable {
+            aggregates.push(format!("min({})::text", c));
+            aggregates.push(format!("max({})::text", c));
+        } else {
+            aggregates.push("NULL::text".to_string());
+            aggregates.push("NULL::text".to_string());
+        }
+    }
+    let aggregate_sql = |percent| {
+        format!(
+            "WITH s AS ({}) SELECT {} FROM s",
+            sample_sql(&relation, "*", percent, sample_rows),
+            aggregates.join(", ")
+        )
+    };
+    let mut row = client.query_one(&aggregate_sql(percent), &[]).await?;
+    // A small percentage can miss every page; fall back to the first rows
+    if percent.is_some() && row.get::<_, i64>(0) == 0 {
+        percent = None;
+        row = client.query_one(&aggregate_sql(percent), &[]).await?;
+    }
+    let rows_sampled: i64 = row.get(0);
+
+    let mut columns = Vec::with_capacity(meta.len());
+    for (i, col) in meta.into_iter().enumerate() {
+        let base = 1 + i * 4;
+        let non_null: i64 = row.get(base);
+        let distinct: i64 = row.get(base + 1);
+
+        let top_values = if top > 0 && distinct > 0 && distinct < non_null {
+            let c = quote_ident(&col.name);
+            let query = format!(
+                "WITH s AS ({}) SELECT left(v, {}), count(*)::bigint FROM \
+                 (SELECT {}::text AS v FROM s) x WHERE v IS NOT NULL \
+                 GROUP BY v ORDER BY 2 DESC, 1 LIMIT {}",
+                sample_sql(&relation, &c, percent, sample_rows),
+                MAX_VALUE_CHARS,
+                c,
+                top
+            );
+            client
+                .query(&query, &[])
+                .await?
+                .iter()
+                .map(|r| ValueCount {
+                    value: r.get(0),
+                    count: r.get(1),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        columns.push(ColumnProfile {
+            null_fraction: if rows_sampled > 0 {
+                (rows_sampled - non_null) as f64 / rows_sampled as f64
+            } else {
+                0.0
+            },
+            distinct_estimate: distinct_estimate(col.n_distinct, row_estimate),
+            name: col.name,
+            data_type: col.data_type,
+            non_null,
+            distinct,
+            min: row.get(base + 2),
+            max: row.get(base + 3),
+            top_values,
+        });
+    }
+
+    Ok(TableProfile {
+        schema: resolved.schema.clone(),
+        table: resolved.name.clone(),
+        row_estimate,
+        rows_sampled,
+        sampled: percent.is_some() || rows_sampled >= sample_rows,
+        columns,
+    })
+}
+
+impl TableProfile {
+    /// Format the profile for display
+    pub fn format(&self) -> String {
+        let mut output = Vec::new();
+        let estimate = match self.row_estimate {
+            Some(rows) => format!("~{} (estimate)", format_number(rows)),
+            None => "unknown (never analyzed)".to_string(),
+        };
+        if self.sampled {
+            output.push(format!(
+                "Rows: {}, profiled from a sample of {}",
+                estimate,
+                format_number(self.rows_sampled)
+            ));
+        } else {
+            output.push(format!(
+                "Rows: {} (all rows profiled)",
+                format_number(self.rows_sampled)
+            ));
+        }
+
+        for col in &self.columns {
+            output.push(String::new());
+            output.push(format!("  {} ({})", col.name, col.data_type));
+            let mut stats = vec![
+                format!("nulls {:.1}%", col.null_fraction * 100.0),
+                format!("distinct {}", format_number(col.distinct)),
+            ];
+            if let Some(estimate) = col.distinct_estimate {
+                stats.push(format!("(~{} in table)", format_number(estimate)));
+            }
+            output.push(format!("    {}", stats.join("  ")));
+            if let (Some(min), Some(max)) = (&col.min, &col.max) {
+                output.push(format!(
+                    "    min {}  max {}",
+                    truncate_query(min, 40),
+                    truncate_query(max, 40)
+                ));
+            }
+            if !col.top_values.is_empty() {
+                let top: Vec<String> = col
+                    .top_values
+                    .iter()
+                    .map(|v| format!("{} ({})", truncate_query(&v.value, 30), v.count))
+                    .collect();
+                output.push(format!("    top  {}", top.join(", ")));
+            }
+        }
+        output.join("\n")
+    }
+}
+
+/// Show per-column null fraction, distinct counts, min/max, and top values
+pub async fn profile(
+    database_url: &str,
+    object: &str,
+    sample_rows: i64,
+    top: i64,
+    output: &Output,
+) -> Result<()> {
+    let client = connect(database_url).await?;
+    let resolved = describe::resolve_table(&client, object).await?;
+    let profile = profile_table(&client, &resolved, sample_rows, top).await?;
+
+    if output.is_json() {
+        output.json(&ProfileResponse { ok: true, profile })?;
+        return Ok(());
+    }
+    if output.is_quiet() {
+        return Ok(());
+    }
+
+    let mut result = String::new();
+    result.push('\n');
+    result.push_str(&format!(
+        "Profile: {}.{}\n",
+        quote_ident(&resolved.schema),
+        quote_ident(&resolved.name)
+    ));
+    result.push_str(&"─".repeat(64));
+    result.push('\n');
+    result.push('\n');
+    result.push_str(&profile.format());
+    output.data(&result);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_percent() {
+        // Small or unanalyzed tables are read up to the limit
+        assert_eq!(sample_percent(None, 10_000), None);
+        assert_eq!(sample_percent(Some(5_000), 10_000), None);
+        assert_eq!(sample_percent(Some(1_000_000), 10_000), Some(1.0));
+        assert_eq!(sample_percent(Some(20_000), 10_000), Some(50.0));
+    }
+
+    #[test]
+    fn test_sample_sql() {
+        assert_eq!(
+            sample_sql("\"app\".\"t\"", "*", None, 100),
+            "SELECT * FROM \"app\".\"t\" LIMIT 100"
+        );
+        assert_eq!(
+            sample_sql("\"app\".\"t\"", "\"id\"", Some(2.0), 100),
+            "SELECT \"id\" FROM \"app\".\"t\" TABLESAMPLE SYSTEM (2.0000) REPEATABLE (0) LIMIT 100"
+        );
+    }
+
+    #[test]
+    fn test_distinct_estimate() {
+        assert_eq!(distinct_estimate(None, Some(100)), None);
+        assert_eq!(distinct_estimate(Some(42.0), Some(1000)), Some(42));
+        assert_eq!(distinct_estimate(Some(-0.5), Some(1000)), Some(500));
+        assert_eq!(distinct_estimate(Some(-1.0), None), None);
+    }
+}
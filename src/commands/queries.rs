@@ -246,7 +246,7 @@ pub async fn run_queries(
 }
 
 /// Format duration in human-readable form
-fn format_duration_ms(ms: f64) -> String {
+pub(crate) fn format_duration_ms(ms: f64) -> String {
     if ms >= 60000.0 {
         format!("{:.1}m", ms / 60000.0)
     } else if ms >= 1000.0 {
@@ -257,7 +257,7 @@ fn format_duration_ms(ms: f64) -> String {
 }
 
 /// Format large numbers for display
-fn format_number(n: i64) -> String {
+pub(crate) fn format_number(n: i64) -> String {
     if n >= 1_000_000_000 {
         format!("{:.1}B", n as f64 / 1_000_000_000.0)
     } else if n >= 1_000_000 {
@@ -270,7 +270,7 @@ fn format_number(n: i64) -> String {
 }
 
 /// Truncate query for display
-fn truncate_query(query: &str, max_len: usize) -> String {
+pub(crate) fn truncate_query(query: &str, max_len: usize) -> String {
     let clean = query.replace('\n', " ").replace("  ", " ");
     // Use chars for UTF-8 safe truncation
     if clean.chars().count() <= max_len {
@@ -7,8 +7,16 @@ use anyhow::Result;
 use serde::Serialize;
 use tokio_postgres::Client;
 
+use crate::config::{ReplicationAlerts, Thresholds};
+
 const LAG_WARNING_SECS: f64 = 30.0;
 const LAG_CRITICAL_SECS: f64 = 300.0; // 5 minutes
+
+/// Default replay lag levels for `[replication]` in pgcrate.alerts.toml
+pub const DEFAULT_LAG_THRESHOLDS: Thresholds = Thresholds {
+    warn: LAG_WARNING_SECS,
+    crit: LAG_CRITICAL_SECS,
+};
 const SLOT_RETAINED_WARNING_BYTES: i64 = 1_073_741_824; // 1GB
 const SLOT_RETAINED_CRITICAL_BYTES: i64 = 10_737_418_240; // 10GB
 
@@ -85,7 +93,35 @@ async fn get_server_role(client: &Client) -> Result<ServerRole> {
     })
 }
 
-async fn get_replicas(client: &Client) -> Result<Vec<ReplicaInfo>> {
+/// Replica status from replay lag, and from unreplayed bytes when configured
+pub(crate) fn replica_status(
+    replay_lag_secs: Option<f64>,
+    lag_bytes: Option<i64>,
+    alerts: &ReplicationAlerts,
+) -> ReplicationStatus {
+    let lag = alerts.lag_secs(DEFAULT_LAG_THRESHOLDS);
+    let by_time = match replay_lag_secs {
+        Some(secs) if secs >= lag.crit => ReplicationStatus::Critical,
+        Some(secs) if secs >= lag.warn => ReplicationStatus::Warning,
+        _ => ReplicationStatus::Healthy,
+    };
+    let by_bytes = match lag_bytes {
+        Some(b) if alerts.crit_lag_bytes.is_some_and(|c| b >= c) => ReplicationStatus::Critical,
+        Some(b) if alerts.warn_lag_bytes.is_some_and(|w| b >= w) => ReplicationStatus::Warning,
+        _ => ReplicationStatus::Healthy,
+    };
+    match (by_time, by_bytes) {
+        (ReplicationStatus::Critical, _) | (_, ReplicationStatus::Critical) => {
+            ReplicationStatus::Critical
+        }
+        (ReplicationStatus::Warning, _) | (_, ReplicationStatus::Warning) => {
+            ReplicationStatus::Warning
+        }
+        _ => ReplicationStatus::Healthy,
+    }
+}
+
+async fn get_replicas(client: &Client, alerts: &ReplicationAlerts) -> Result<Vec<ReplicaInfo>> {
     let query = r#"
 SELECT
     application_name,
@@ -109,11 +145,8 @@ ORDER BY application_name
 
     for row in rows {
         let replay_lag_secs: Option<f64> = row.get("replay_lag_secs");
-        let status = match replay_lag_secs {
-            Some(lag) if lag >= LAG_CRITICAL_SECS => ReplicationStatus::Critical,
-            Some(lag) if lag >= LAG_WARNING_SECS => ReplicationStatus::Warning,
-            _ => ReplicationStatus::Healthy,
-        };
+        let lag_bytes: Option<i64> = row.get("lag_bytes");
+        let status = replica_status(replay_lag_secs, lag_bytes, alerts);
 
         results.push(ReplicaInfo {
             application_name: row.get("application_name"),
@@ -127,7 +160,7 @@ ORDER BY application_name
             write_lag_secs: row.get("write_lag_secs"),
             flush_lag_secs: row.get("flush_lag_secs"),
             replay_lag_secs,
-            lag_bytes: row.get("lag_bytes"),
+            lag_bytes,
             status,
         });
     }
@@ -218,11 +251,14 @@ LIMIT 1
     }))
 }
 
-pub async fn get_replication(client: &Client) -> Result<ReplicationResult> {
+pub async fn get_replication(
+    client: &Client,
+    alerts: &ReplicationAlerts,
+) -> Result<ReplicationResult> {
     let server_role = get_server_role(client).await?;
 
     let replicas = if server_role == ServerRole::Primary {
-        get_replicas(client).await?
+        get_replicas(client, alerts).await?
     } else {
         vec![]
     };
@@ -443,6 +479,38 @@ pub fn print_json(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_replica_status_by_time_and_bytes() {
+        let defaults = ReplicationAlerts::default();
+        assert_eq!(
+            replica_status(Some(31.0), Some(1 << 40), &defaults),
+            ReplicationStatus::Warning
+        );
+        assert_eq!(
+            replica_status(None, Some(1 << 40), &defaults),
+            ReplicationStatus::Healthy
+        );
+
+        let alerts = ReplicationAlerts {
+            warn_lag_secs: Some(120.0),
+            warn_lag_bytes: Some(1 << 20),
+            crit_lag_bytes: Some(1 << 30),
+            ..Default::default()
+        };
+        assert_eq!(
+            replica_status(Some(31.0), Some(0), &alerts),
+            ReplicationStatus::Healthy
+        );
+        assert_eq!(
+            replica_status(Some(0.0), Some(2 << 20), &alerts),
+            ReplicationStatus::Warning
+        );
+        assert_eq!(
+            replica_status(Some(0.0), Some(2 << 30), &alerts),
+            ReplicationStatus::Critical
+        );
+    }
+
     #[test]
     fn test_format_lag() {
         assert_eq!(format_lag(Some(5.0)), "5.0s");
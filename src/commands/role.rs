@@ -1,9 +1,11 @@
 //! Role and grants commands for pgcrate CLI.
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use serde::Serialize;
 
 use super::connect;
+use crate::output::Output;
 
 /// List database roles
 pub async fn role_list(
@@ -384,6 +386,49 @@ pub async fn grants(
     Ok(())
 }
 
+/// A table-level privilege held by a role
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableGrant {
+    /// Role name, or `PUBLIC`
+    pub grantee: String,
+    /// Privilege keyword, e.g. `SELECT`
+    pub privilege: String,
+    pub grantable: bool,
+}
+
+/// Privileges on a table, view or materialized view, including the owner's defaults.
+/// Returns an empty list if the relation does not exist.
+pub async fn table_grants(
+    client: &tokio_postgres::Client,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<TableGrant>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE pg_get_userbyid(a.grantee) END::text AS grantee,
+                a.privilege_type::text AS privilege_type,
+                a.is_grantable
+            FROM pg_class c
+            JOIN pg_namespace n ON c.relnamespace = n.oid
+            CROSS JOIN LATERAL aclexplode(coalesce(c.relacl, acldefault('r', c.relowner))) a
+            WHERE n.nspname = $1 AND c.relname = $2
+            ORDER BY 1, 2
+            "#,
+            &[&schema, &table],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| TableGrant {
+            grantee: row.get("grantee"),
+            privilege: row.get("privilege_type"),
+            grantable: row.get("is_grantable"),
+        })
+        .collect())
+}
+
 /// Show grants on a specific table
 async fn show_object_grants(
     client: &tokio_postgres::Client,
@@ -415,20 +460,7 @@ async fn show_object_grants(
     }
 
     // Get grants
-    let rows = client
-        .query(
-            r#"
-            SELECT
-                grantee,
-                privilege_type,
-                is_grantable
-            FROM information_schema.table_privileges
-            WHERE table_schema = $1 AND table_name = $2
-            ORDER BY grantee, privilege_type
-            "#,
-            &[&schema, &table],
-        )
-        .await?;
+    let grants = table_grants(client, schema, table).await?;
 
     if quiet {
         return Ok(());
@@ -436,7 +468,7 @@ async fn show_object_grants(
 
     println!("\nGrants on {}.{}:\n", schema, table);
 
-    if rows.is_empty() {
+    if grants.is_empty() {
         println!("{}", "No explicit grants found.".dimmed());
         return Ok(());
     }
@@ -445,15 +477,11 @@ async fn show_object_grants(
     let mut grants_by_role: std::collections::BTreeMap<String, Vec<(String, bool)>> =
         std::collections::BTreeMap::new();
 
-    for row in &rows {
-        let grantee: String = row.get("grantee");
-        let privilege: String = row.get("privilege_type");
-        let with_grant_str: String = row.get("is_grantable");
-        let with_grant = with_grant_str == "YES";
+    for grant in grants {
         grants_by_role
-            .entry(grantee)
+            .entry(grant.grantee)
             .or_default()
-            .push((privilege, with_grant));
+            .push((grant.privilege, grant.grantable));
     }
 
     // Print header
@@ -718,6 +746,243 @@ async fn show_role_grants(client: &tokio_postgres::Client, role: &str, quiet: bo
     Ok(())
 }
 
+/// Matrix columns: schema privileges first, then relation privileges
+const MATRIX_PRIVILEGES: &[&str] = &[
+    "USAGE",
+    "CREATE",
+    "SELECT",
+    "INSERT",
+    "UPDATE",
+    "DELETE",
+    "TRUNCATE",
+    "REFERENCES",
+    "TRIGGER",
+];
+
+/// Privileges one grantee holds on one object
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixRow {
+    /// Role name, or `PUBLIC`
+    pub grantee: String,
+    pub object: String,
+    /// schema, table, view, materialized view, foreign table, or sequence
+    pub kind: String,
+    pub privileges: Vec<String>,
+    /// Privileges held WITH GRANT OPTION
+    pub grantable: Vec<String>,
+}
+
+/// Role × privilege grid for a schema and everything in it
+#[derive(Debug, Serialize)]
+pub struct GrantsMatrix {
+    pub schema: String,
+    pub rows: Vec<MatrixRow>,
+}
+
+/// JSON response for inspect grants --matrix
+#[derive(Debug, Serialize)]
+struct GrantsMatrixResponse<'a> {
+    ok: bool,
+    #[serde(flatten)]
+    matrix: &'a GrantsMatrix,
+}
+
+impl MatrixRow {
+    /// Grid cell: G with grant option, Y granted, empty otherwise
+    fn cell(&self, privilege: &str) -> &'static str {
+        if self.grantable.iter().any(|p| p == privilege) {
+            "G"
+        } else if self.privileges.iter().any(|p| p == privilege) {
+            "Y"
+        } else {
+            ""
+        }
+    }
+}
+
+/// Build the grants matrix, including owners' implicit privileges
+pub async fn get_grants_matrix(
+    client: &tokio_postgres::Client,
+    schema: &str,
+) -> Result<GrantsMatrix> {
+    let exists = client
+        .query_opt("SELECT 1 FROM pg_namespace WHERE nspname = $1", &[&schema])
+        .await?;
+    if exists.is_none() {
+        bail!("Schema '{}' not found", schema);
+    }
+
+    // Partitions share their parent's grants in practice; list the parent
+    let rows = client
+        .query(
+            r#"
+            WITH objects AS (
+                SELECT 0 AS ord, n.nspname::text AS object, 'schema' AS kind,
+                       coalesce(n.nspacl, acldefault('n', n.nspowner)) AS acl
+                FROM pg_namespace n
+                WHERE n.nspname = $1
+                UNION ALL
+                SELECT 1, c.relname::text,
+                       CASE c.relkind
+                           WHEN 'v' THEN 'view'
+                           WHEN 'm' THEN 'materialized view'
+                           WHEN 'f' THEN 'foreign table'
+                           WHEN 'S' THEN 'sequence'
+                           ELSE 'table'
+                       END,
+                       coalesce(c.relacl, acldefault(
+                           CASE WHEN c.relkind = 'S' THEN 's' ELSE 'r' END::"char",
+                           c.relowner))
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = $1
+                  AND c.relkind IN ('r', 'p', 'v', 'm', 'f', 'S')
+                  AND NOT c.relispartition
+            )
+            SELECT
+                CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE pg_get_userbyid(a.grantee) END::text
+                    AS grantee,
+                o.object,
+                o.kind,
+                array_agg(a.privilege_type::text ORDER BY a.privilege_type) AS privileges,
+                array_agg(a.privilege_type::text ORDER BY a.privilege_type)
+                    FILTER (WHERE a.is_grantable) AS grantable
+            FROM objects o
+            CROSS JOIN LATERAL aclexplode(o.acl) a
+            GROUP BY 1, o.ord, o.object, o.kind
+            ORDER BY 1, o.ord, o.object
+            "#,
+            &[&schema],
+        )
+        .await?;
+
+    Ok(GrantsMatrix {
+        schema: schema.to_string(),
+        rows: rows
+            .iter()
+            .map(|row| MatrixRow {
+                grantee: row.get("grantee"),
+                object: row.get("object"),
+                kind: row.get("kind"),
+                privileges: row.get("privileges"),
+                grantable: row
+                    .get::<_, Option<Vec<String>>>("grantable")
+                    .unwrap_or_default(),
+            })
+            .collect(),
+    })
+}
+
+/// Grid with one line per grantee and object
+pub fn format_matrix_table(matrix: &GrantsMatrix) -> String {
+    let role_width = matrix
+        .rows
+        .iter()
+        .map(|r| r.grantee.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let object_width = matrix
+        .rows
+        .iter()
+        .map(|r| r.object.len())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+
+    let mut header = format!(
+        "{:rw$}  {:ow$}",
+        "ROLE",
+        "OBJECT",
+        rw = role_width,
+        ow = object_width
+    );
+    for p in MATRIX_PRIVILEGES {
+        header.push_str(&format!("  {}", p));
+    }
+    let mut output = vec![header];
+
+    let mut last_grantee = "";
+    for row in &matrix.rows {
+        let grantee = if row.grantee == last_grantee {
+            ""
+        } else {
+            row.grantee.as_str()
+        };
+        last_grantee = &row.grantee;
+        let mut line = format!(
+            "{:rw$}  {:ow$}",
+            grantee,
+            row.object,
+            rw = role_width,
+            ow = object_width
+        );
+        for p in MATRIX_PRIVILEGES {
+            let mark = match row.cell(p) {
+                "G" => "G",
+                "Y" => "✓",
+                _ => "-",
+            };
+            line.push_str(&format!("  {:^w$}", mark, w = p.len()));
+        }
+        output.push(line.trim_end().to_string());
+    }
+    output.join("\n")
+}
+
+/// CSV with a column per privilege (Y, G for grant option, or empty)
+pub fn format_matrix_csv(matrix: &GrantsMatrix) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut header = vec!["grantee", "object", "kind"];
+    header.extend(MATRIX_PRIVILEGES);
+    writer.write_record(&header)?;
+    for row in &matrix.rows {
+        let mut record = vec![row.grantee.as_str(), row.object.as_str(), row.kind.as_str()];
+        record.extend(MATRIX_PRIVILEGES.iter().map(|p| row.cell(p)));
+        writer.write_record(&record)?;
+    }
+    let data = writer.into_inner().context("write CSV")?;
+    Ok(String::from_utf8(data)?)
+}
+
+/// Show a role × privilege matrix for a schema and its tables, views, and sequences
+pub async fn grants_matrix(
+    database_url: &str,
+    schema: &str,
+    format: &str,
+    output: &Output,
+) -> Result<()> {
+    if !matches!(format, "table" | "csv" | "json") {
+        bail!("Unknown format: {}. Use: table, csv, json", format);
+    }
+
+    let client = connect(database_url).await?;
+    let matrix = get_grants_matrix(&client, schema).await?;
+
+    if output.is_json() || format == "json" {
+        output.json(&GrantsMatrixResponse {
+            ok: true,
+            matrix: &matrix,
+        })?;
+        return Ok(());
+    }
+
+    match format {
+        "csv" => print!("{}", format_matrix_csv(&matrix)?),
+        _ => {
+            if output.is_quiet() {
+                return Ok(());
+            }
+            output.data(&format!(
+                "\nGrants matrix for schema: {}  (✓ granted, G with grant option)\n\n{}",
+                schema,
+                format_matrix_table(&matrix)
+            ));
+        }
+    }
+    Ok(())
+}
+
 trait Capitalize {
     fn capitalize(&self) -> String;
 }
@@ -731,3 +996,57 @@ impl Capitalize for str {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> GrantsMatrix {
+        let row = |grantee: &str, object: &str, kind: &str, privs: &[&str], grantable: &[&str]| {
+            MatrixRow {
+                grantee: grantee.to_string(),
+                object: object.to_string(),
+                kind: kind.to_string(),
+                privileges: privs.iter().map(|p| p.to_string()).collect(),
+                grantable: grantable.iter().map(|p| p.to_string()).collect(),
+            }
+        };
+        GrantsMatrix {
+            schema: "app".to_string(),
+            rows: vec![
+                row("app_ro", "app", "schema", &["USAGE"], &[]),
+                row("app_ro", "orders", "table", &["SELECT"], &[]),
+                row(
+                    "owner",
+                    "orders",
+                    "table",
+                    &["INSERT", "SELECT"],
+                    &["SELECT"],
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_format_matrix_table() {
+        let table = format_matrix_table(&sample());
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("ROLE    OBJECT  USAGE  CREATE  SELECT"));
+        assert!(lines[1].starts_with("app_ro  app       ✓      -       -"));
+        // Grantee printed once per group
+        assert!(lines[2].starts_with("        orders    -      -       ✓"));
+        assert!(lines[3].starts_with("owner   orders    -      -       G       ✓"));
+    }
+
+    #[test]
+    fn test_format_matrix_csv() {
+        let csv = format_matrix_csv(&sample()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "grantee,object,kind,USAGE,CREATE,SELECT,INSERT,UPDATE,DELETE,TRUNCATE,REFERENCES,TRIGGER"
+        );
+        assert_eq!(lines[1], "app_ro,app,schema,Y,,,,,,,,");
+        assert_eq!(lines[3], "owner,orders,table,,,G,Y,,,,,");
+    }
+}
@@ -0,0 +1,372 @@
+//! Schema graph: Dependency graph of every table and view in a schema.
+//!
+//! Built from the same per-table queries `inspect table --dependents` and
+//! `--dependencies` use: foreign keys, views, trigger functions, and column
+//! types. Edges point from the dependent object to what it depends on, so
+//! everything with an edge into a table is in the blast radius of dropping
+//! it. Objects in other schemas appear when something in this schema
+//! depends on them (or they depend on it).
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tokio_postgres::Client;
+
+use super::connect;
+use crate::describe;
+use crate::output::Output;
+
+/// Object in the graph, keyed by schema-qualified name
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    /// table, view, materialized view, function, enum, domain, or composite
+    pub kind: String,
+    /// Whether the object lives in the graphed schema
+    pub in_schema: bool,
+}
+
+/// `from` depends on `to`
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// foreign_key, view, trigger_function, or type
+    pub kind: String,
+    /// FK columns or trigger name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Whole-schema dependency graph
+#[derive(Debug, Serialize)]
+pub struct SchemaGraph {
+    pub schema: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// JSON response for inspect graph
+#[derive(Debug, Serialize)]
+struct GraphResponse<'a> {
+    ok: bool,
+    #[serde(flatten)]
+    graph: &'a SchemaGraph,
+}
+
+#[derive(Default)]
+struct GraphBuilder {
+    schema: String,
+    nodes: BTreeMap<String, GraphNode>,
+    edges: Vec<GraphEdge>,
+}
+
+impl GraphBuilder {
+    fn node(&mut self, schema: &str, name: &str, kind: &str) -> String {
+        let id = format!("{}.{}", schema, name);
+        self.nodes.entry(id.clone()).or_insert_with(|| GraphNode {
+            id: id.clone(),
+            kind: kind.to_string(),
+            in_schema: schema == self.schema,
+        });
+        id
+    }
+
+    fn edge(&mut self, from: String, to: String, kind: &str, label: Option<String>) {
+        // A view's rewrite rule depends on the view itself
+        if from == to {
+            return;
+        }
+        self.edges.push(GraphEdge {
+            from,
+            to,
+            kind: kind.to_string(),
+            label,
+        });
+    }
+}
+
+/// Build the dependency graph for every table and view in `schema`
+pub async fn build_graph(client: &Client, schema: &str) -> Result<SchemaGraph> {
+    let exists = client
+        .query_opt("SELECT 1 FROM pg_namespace WHERE nspname = $1", &[&schema])
+        .await?
+        .is_some();
+    if !exists {
+        bail!("Schema {} not found", crate::sql::quote_ident(schema));
+    }
+
+    // Partitions are covered by their parent
+    let relations = client
+        .query(
+            r#"
+            SELECT c.relname::text, c.relkind
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1
+              AND c.relkind IN ('r', 'p', 'v', 'm')
+              AND NOT c.relispartition
+            ORDER BY c.relname
+            "#,
+            &[&schema],
+        )
+        .await?;
+
+    let mut graph = GraphBuilder {
+        schema: schema.to_string(),
+        ..Default::default()
+    };
+
+    for row in &relations {
+        let name: String = row.get(0);
+        let relkind: i8 = row.get(1);
+        let kind = match relkind as u8 as char {
+            'v' => "view",
+            'm' => "materialized view",
+            _ => "table",
+        };
+        let id = graph.node(schema, &name, kind);
+
+        if kind == "table" {
+            let deps = describe::get_dependencies(client, schema, &name).await?;
+            let dependents = describe::get_dependents(client, schema, &name).await?;
+            // Both directions, so FKs from other schemas show up; edges
+            // within the schema are found twice and deduplicated below
+            for fk in deps.foreign_keys.iter().chain(&dependents.foreign_keys) {
+                let from = graph.node(&fk.from_schema, &fk.from_table, "table");
+                let to = graph.node(&fk.to_schema, &fk.to_table, "table");
+                let label = format!(
+                    "{} -> {}",
+                    fk.from_columns.join(", "),
+                    fk.to_columns.join(", ")
+                );
+                graph.edge(from, to, "foreign_key", Some(label));
+            }
+            for f in deps.trigger_functions {
+                let to = graph.node(&f.function_schema, &f.function_name, "function");
+                graph.edge(id.clone(), to, "trigger_function", Some(f.trigger_name));
+            }
+            for t in deps.types {
+                let to = graph.node(&t.schema, &t.name, &t.kind);
+                graph.edge(id.clone(), to, "type", None);
+            }
+        }
+
+        for view in describe::get_dependent_views(client, schema, &name).await? {
+            let kind = if view.is_materialized {
+                "materialized view"
+            } else {
+                "view"
+            };
+            let from = graph.node(&view.schema, &view.name, kind);
+            graph.edge(from, id.clone(), "view", None);
+        }
+    }
+
+    // Several triggers can share a function; keep one edge per pair and kind
+    // (foreign keys stay distinct by their columns)
+    graph.edges.sort_by(|a, b| {
+        (&a.from, &a.to, &a.kind, &a.label).cmp(&(&b.from, &b.to, &b.kind, &b.label))
+    });
+    graph.edges.dedup_by(|a, b| {
+        a.from == b.from
+            && a.to == b.to
+            && a.kind == b.kind
+            && (a.kind != "foreign_key" || a.label == b.label)
+    });
+
+    Ok(SchemaGraph {
+        schema: schema.to_string(),
+        nodes: graph.nodes.into_values().collect(),
+        edges: graph.edges,
+    })
+}
+
+/// Graphviz DOT; edges point from dependent to dependency
+pub fn format_dot(graph: &SchemaGraph) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut out = vec![
+        format!("digraph \"{}\" {{", escape(&graph.schema)),
+        "    rankdir=LR;".to_string(),
+    ];
+    for node in &graph.nodes {
+        let shape = match node.kind.as_str() {
+            "table" => "box",
+            "view" | "materialized view" => "box, style=rounded",
+            "function" => "ellipse",
+            _ => "note",
+        };
+        let external = if node.in_schema { "" } else { ", color=gray" };
+        out.push(format!(
+            "    \"{}\" [shape={}{}];",
+            escape(&node.id),
+            shape,
+            external
+        ));
+    }
+    for edge in &graph.edges {
+        let mut attrs = Vec::new();
+        if let Some(ref label) = edge.label {
+            attrs.push(format!("label=\"{}\"", escape(label)));
+        }
+        match edge.kind.as_str() {
+            "view" => attrs.push("style=dashed".to_string()),
+            "trigger_function" | "type" => attrs.push("style=dotted".to_string()),
+            _ => {}
+        }
+        if attrs.is_empty() {
+            out.push(format!(
+                "    \"{}\" -> \"{}\";",
+                escape(&edge.from),
+                escape(&edge.to)
+            ));
+        } else {
+            out.push(format!(
+                "    \"{}\" -> \"{}\" [{}];",
+                escape(&edge.from),
+                escape(&edge.to),
+                attrs.join(", ")
+            ));
+        }
+    }
+    out.push("}".to_string());
+    out.join("\n")
+}
+
+/// Mermaid flowchart; edges point from dependent to dependency
+pub fn format_mermaid(graph: &SchemaGraph) -> String {
+    let escape = |s: &str| s.replace('"', "#quot;").replace('|', "#124;");
+    let ids: BTreeMap<&str, String> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.id.as_str(), format!("n{}", i)))
+        .collect();
+
+    let mut out = vec!["graph LR".to_string()];
+    if graph.nodes.is_empty() {
+        out.push("    empty[\"No tables or views\"]".to_string());
+        return out.join("\n");
+    }
+    for node in &graph.nodes {
+        let id = &ids[node.id.as_str()];
+        let label = escape(&node.id);
+        out.push(match node.kind.as_str() {
+            "table" => format!("    {}[\"{}\"]", id, label),
+            "view" | "materialized view" => format!("    {}(\"{}\")", id, label),
+            "function" => format!("    {}([\"{}\"])", id, label),
+            _ => format!("    {}>\"{}\"]", id, label),
+        });
+    }
+    for edge in &graph.edges {
+        let arrow = match edge.kind.as_str() {
+            "foreign_key" => "-->",
+            _ => "-.->",
+        };
+        let (from, to) = (&ids[edge.from.as_str()], &ids[edge.to.as_str()]);
+        match edge.label {
+            Some(ref label) => out.push(format!(
+                "    {} {}|\"{}\"| {}",
+                from,
+                arrow,
+                escape(label),
+                to
+            )),
+            None => out.push(format!("    {} {} {}", from, arrow, to)),
+        }
+    }
+    let external: Vec<&str> = graph
+        .nodes
+        .iter()
+        .filter(|n| !n.in_schema)
+        .map(|n| ids[n.id.as_str()].as_str())
+        .collect();
+    if !external.is_empty() {
+        out.push("    classDef external stroke-dasharray:3,fill:#eee".to_string());
+        out.push(format!("    class {} external", external.join(",")));
+    }
+    out.join("\n")
+}
+
+/// Print the dependency graph for a schema
+pub async fn schema_graph(
+    database_url: &str,
+    schema: &str,
+    format: &str,
+    output: &Output,
+) -> Result<()> {
+    if !matches!(format, "dot" | "mermaid" | "json") {
+        bail!("Unknown format: {}. Use: dot, mermaid, json", format);
+    }
+
+    let client = connect(database_url).await?;
+    let graph = build_graph(&client, schema).await?;
+
+    if output.is_json() || format == "json" {
+        output.json(&GraphResponse {
+            ok: true,
+            graph: &graph,
+        })?;
+        return Ok(());
+    }
+
+    match format {
+        "dot" => output.data(&format_dot(&graph)),
+        _ => output.data(&format_mermaid(&graph)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> SchemaGraph {
+        let node = |id: &str, kind: &str, in_schema: bool| GraphNode {
+            id: id.to_string(),
+            kind: kind.to_string(),
+            in_schema,
+        };
+        SchemaGraph {
+            schema: "app".to_string(),
+            nodes: vec![
+                node("app.orders", "table", true),
+                node("app.order_totals", "view", true),
+                node("auth.users", "table", false),
+            ],
+            edges: vec![
+                GraphEdge {
+                    from: "app.orders".to_string(),
+                    to: "auth.users".to_string(),
+                    kind: "foreign_key".to_string(),
+                    label: Some("user_id -> id".to_string()),
+                },
+                GraphEdge {
+                    from: "app.order_totals".to_string(),
+                    to: "app.orders".to_string(),
+                    kind: "view".to_string(),
+                    label: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_format_dot() {
+        let dot = format_dot(&sample());
+        assert!(dot.starts_with("digraph \"app\" {"));
+        assert!(dot.contains("\"auth.users\" [shape=box, color=gray];"));
+        assert!(dot.contains("\"app.orders\" -> \"auth.users\" [label=\"user_id -> id\"];"));
+        assert!(dot.contains("\"app.order_totals\" -> \"app.orders\" [style=dashed];"));
+    }
+
+    #[test]
+    fn test_format_mermaid() {
+        let mermaid = format_mermaid(&sample());
+        assert!(mermaid.contains("    n0[\"app.orders\"]"));
+        assert!(mermaid.contains("    n1(\"app.order_totals\")"));
+        assert!(mermaid.contains("    n0 -->|\"user_id -> id\"| n2"));
+        assert!(mermaid.contains("    n1 -.-> n0"));
+        assert!(mermaid.contains("    class n2 external"));
+    }
+}
@@ -0,0 +1,458 @@
+//! Security audit: Role and privilege findings for compliance reviews.
+//!
+//! Flags superuser roles, login roles whose password never expires, privileges
+//! granted to PUBLIC on schemas, objects owned by login roles, and declared
+//! sensitive tables without row-level security. Password presence is only
+//! visible to superusers (pg_authid); otherwise every login role without
+//! VALID UNTIL is listed. Extension members and system schemas are skipped.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::connect;
+use crate::config::glob_matches;
+use crate::output::{Output, SecurityAuditResponse};
+
+/// Severity of a finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl AuditSeverity {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            AuditSeverity::Info => "ℹ",
+            AuditSeverity::Warning => "⚠",
+            AuditSeverity::Critical => "✗",
+        }
+    }
+}
+
+/// What a finding is about (stable identifiers for automation)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityCheck {
+    Superuser,
+    PasswordNeverExpires,
+    PublicSchemaGrant,
+    OwnedByLoginRole,
+    SensitiveWithoutRls,
+}
+
+/// One audit finding
+#[derive(Debug, Clone, Serialize)]
+pub struct SecurityFinding {
+    pub check: SecurityCheck,
+    pub severity: AuditSeverity,
+    /// Role, schema, or schema.table the finding is about
+    pub object: String,
+    pub detail: String,
+}
+
+/// Full audit results
+#[derive(Debug, Serialize)]
+pub struct SecurityAudit {
+    /// Findings, most severe first
+    pub findings: Vec<SecurityFinding>,
+    /// Declared sensitive table patterns checked for row-level security
+    pub sensitive_tables: Vec<String>,
+    /// pg_authid was readable, so roles without a password were skipped
+    pub passwords_visible: bool,
+    /// Worst finding severity, or None when there are no findings
+    pub overall: Option<AuditSeverity>,
+}
+
+/// Role attributes relevant to the audit
+struct RoleRow {
+    name: String,
+    /// The role created by initdb, which cannot lose superuser
+    bootstrap: bool,
+    superuser: bool,
+    password_never_expires: bool,
+}
+
+fn role_findings(roles: &[RoleRow]) -> Vec<SecurityFinding> {
+    let mut findings = Vec::new();
+    for role in roles {
+        if role.superuser {
+            let (severity, detail) = if role.bootstrap {
+                (AuditSeverity::Info, "bootstrap superuser")
+            } else {
+                (AuditSeverity::Critical, "superuser role")
+            };
+            findings.push(SecurityFinding {
+                check: SecurityCheck::Superuser,
+                severity,
+                object: role.name.clone(),
+                detail: detail.to_string(),
+            });
+        }
+        if role.password_never_expires {
+            findings.push(SecurityFinding {
+                check: SecurityCheck::PasswordNeverExpires,
+                severity: AuditSeverity::Warning,
+                object: role.name.clone(),
+                detail: "login role with no password expiry (VALID UNTIL)".to_string(),
+            });
+        }
+    }
+    findings
+}
+
+/// CREATE lets any role plant objects that shadow others' in search_path
+fn public_grant_finding(schema: &str, privilege: &str) -> SecurityFinding {
+    let severity = if privilege == "CREATE" {
+        AuditSeverity::Warning
+    } else {
+        AuditSeverity::Info
+    };
+    SecurityFinding {
+        check: SecurityCheck::PublicSchemaGrant,
+        severity,
+        object: schema.to_string(),
+        detail: format!("{} granted to PUBLIC", privilege),
+    }
+}
+
+/// Whether schema.table matches a declared pattern; a pattern without a
+/// schema matches the table name in any schema
+fn is_sensitive(patterns: &[String], schema: &str, table: &str) -> bool {
+    let qualified = format!("{}.{}", schema, table);
+    patterns.iter().any(|p| {
+        if p.contains('.') {
+            glob_matches(p, &qualified)
+        } else {
+            glob_matches(p, table)
+        }
+    })
+}
+
+async fn get_roles(client: &Client) -> Result<(Vec<RoleRow>, bool)> {
+    let passwords_visible: bool = client
+        .query_one(
+            "SELECT has_table_privilege('pg_catalog.pg_authid', 'SELECT')",
+            &[],
+        )
+        .await?
+        .get(0);
+
+    // pg_roles masks rolpassword, so without pg_authid every login role counts
+    let has_password = if passwords_visible {
+        "a.rolpassword IS NOT NULL"
+    } else {
+        "true"
+    };
+    let source = if passwords_visible {
+        "pg_authid"
+    } else {
+        "pg_roles"
+    };
+    let rows = client
+        .query(
+            &format!(
+                r#"
+                SELECT a.rolname::text AS name,
+                       a.oid = 10 AS bootstrap,
+                       a.rolsuper AS superuser,
+                       a.rolcanlogin AND {}
+                         AND (a.rolvaliduntil IS NULL OR a.rolvaliduntil = 'infinity')
+                         AS password_never_expires
+                FROM {} a
+                WHERE a.rolname NOT LIKE 'pg\_%'
+                ORDER BY a.rolname
+                "#,
+                has_password, source
+            ),
+            &[],
+        )
+        .await?;
+
+    let roles = rows
+        .iter()
+        .map(|row| RoleRow {
+            name: row.get("name"),
+            bootstrap: row.get("bootstrap"),
+            superuser: row.get("superuser"),
+            password_never_expires: row.get("password_never_expires"),
+        })
+        .collect();
+    Ok((roles, passwords_visible))
+}
+
+async fn get_public_schema_grants(client: &Client) -> Result<Vec<SecurityFinding>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT n.nspname::text AS schema, a.privilege_type::text AS privilege
+            FROM pg_namespace n
+            CROSS JOIN LATERAL aclexplode(coalesce(n.nspacl, acldefault('n', n.nspowner))) a
+            WHERE a.grantee = 0
+              AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'pgcrate')
+              AND n.nspname NOT LIKE 'pg\_%'
+            ORDER BY 1, 2
+            "#,
+            &[],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| public_grant_finding(row.get("schema"), row.get("privilege")))
+        .collect())
+}
+
+async fn get_login_owned(client: &Client) -> Result<Vec<SecurityFinding>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT r.rolname::text AS owner, o.schema, count(*) AS objects
+            FROM (
+                SELECT c.relowner AS owner, n.nspname::text AS schema,
+                       'pg_class'::regclass AS classid, c.oid AS objid
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE c.relkind IN ('r', 'p', 'v', 'm', 'S', 'f')
+                UNION ALL
+                SELECT p.proowner, n.nspname::text, 'pg_proc'::regclass, p.oid
+                FROM pg_proc p
+                JOIN pg_namespace n ON n.oid = p.pronamespace
+            ) o
+            JOIN pg_roles r ON r.oid = o.owner
+            WHERE r.rolcanlogin
+              AND o.schema NOT IN ('pg_catalog', 'information_schema', 'pgcrate')
+              AND o.schema NOT LIKE 'pg\_%'
+              AND NOT EXISTS (
+                  SELECT 1 FROM pg_depend d
+                  WHERE d.classid = o.classid AND d.objid = o.objid AND d.deptype = 'e'
+              )
+            GROUP BY 1, 2
+            ORDER BY 1, 2
+            "#,
+            &[],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let owner: String = row.get("owner");
+            let schema: String = row.get("schema");
+            let objects: i64 = row.get("objects");
+            SecurityFinding {
+                check: SecurityCheck::OwnedByLoginRole,
+                severity: AuditSeverity::Warning,
+                object: owner,
+                detail: format!("owns {} object(s) in schema {}", objects, schema),
+            }
+        })
+        .collect())
+}
+
+async fn get_sensitive_without_rls(
+    client: &Client,
+    patterns: &[String],
+) -> Result<Vec<SecurityFinding>> {
+    if patterns.is_empty() {
+        return Ok(Vec::new());
+    }
+    let rows = client
+        .query(
+            r#"
+            SELECT n.nspname::text AS schema, c.relname::text AS table
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relkind IN ('r', 'p')
+              AND NOT c.relispartition
+              AND NOT c.relrowsecurity
+              AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'pgcrate')
+              AND n.nspname NOT LIKE 'pg\_%'
+            ORDER BY 1, 2
+            "#,
+            &[],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let schema: String = row.get("schema");
+            let table: String = row.get("table");
+            is_sensitive(patterns, &schema, &table).then(|| SecurityFinding {
+                check: SecurityCheck::SensitiveWithoutRls,
+                severity: AuditSeverity::Critical,
+                object: format!("{}.{}", schema, table),
+                detail: "sensitive table without row-level security".to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Run every audit check
+pub async fn get_security_audit(client: &Client, sensitive: &[String]) -> Result<SecurityAudit> {
+    let (roles, passwords_visible) = get_roles(client).await.context("Failed to audit roles")?;
+    let mut findings = role_findings(&roles);
+    findings.extend(
+        get_public_schema_grants(client)
+            .await
+            .context("Failed to audit schema grants")?,
+    );
+    findings.extend(
+        get_login_owned(client)
+            .await
+            .context("Failed to audit object ownership")?,
+    );
+    findings.extend(
+        get_sensitive_without_rls(client, sensitive)
+            .await
+            .context("Failed to audit row-level security")?,
+    );
+
+    // Stable sort keeps each check's order within a severity
+    findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+    let overall = findings.iter().map(|f| f.severity).max();
+    Ok(SecurityAudit {
+        findings,
+        sensitive_tables: sensitive.to_vec(),
+        passwords_visible,
+        overall,
+    })
+}
+
+impl SecurityAudit {
+    /// One-line count of findings, e.g. "1 critical, 2 warning"
+    pub fn summary(&self) -> String {
+        let count = |s: AuditSeverity| self.findings.iter().filter(|f| f.severity == s).count();
+        let parts: Vec<String> = [
+            (AuditSeverity::Critical, "critical"),
+            (AuditSeverity::Warning, "warning"),
+            (AuditSeverity::Info, "info"),
+        ]
+        .into_iter()
+        .filter(|(s, _)| count(*s) > 0)
+        .map(|(s, label)| format!("{} {}", count(s), label))
+        .collect();
+        if parts.is_empty() {
+            "No findings".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Format audit findings for display
+pub fn format_audit(audit: &SecurityAudit) -> String {
+    let mut output = vec![format!("SECURITY AUDIT: {}", audit.summary())];
+    if !audit.findings.is_empty() {
+        output.push(String::new());
+    }
+    for f in &audit.findings {
+        output.push(format!(
+            "  {} {}: {}",
+            f.severity.emoji(),
+            f.object,
+            f.detail
+        ));
+    }
+    if audit.sensitive_tables.is_empty() {
+        output.push(String::new());
+        output.push(
+            "  No sensitive tables declared ([security] sensitive_tables or --sensitive)"
+                .to_string(),
+        );
+    }
+    if !audit.passwords_visible {
+        output.push(String::new());
+        output.push(
+            "  Not a superuser: login roles are listed whether or not they have a password"
+                .to_string(),
+        );
+    }
+    output.join("\n")
+}
+
+/// Audit roles and privileges
+pub async fn security_audit(
+    database_url: &str,
+    sensitive: &[String],
+    output: &Output,
+) -> Result<()> {
+    let client = connect(database_url).await?;
+    let audit = get_security_audit(&client, sensitive).await?;
+
+    if output.is_json() {
+        output.json(&SecurityAuditResponse { ok: true, audit })?;
+        return Ok(());
+    }
+    if output.is_quiet() {
+        return Ok(());
+    }
+    output.data(&format!("\n{}", format_audit(&audit)));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(name: &str, bootstrap: bool, superuser: bool, never_expires: bool) -> RoleRow {
+        RoleRow {
+            name: name.to_string(),
+            bootstrap,
+            superuser,
+            password_never_expires: never_expires,
+        }
+    }
+
+    #[test]
+    fn test_role_findings() {
+        let findings = role_findings(&[
+            role("postgres", true, true, false),
+            role("admin", false, true, true),
+            role("app", false, false, false),
+        ]);
+        let summary: Vec<(SecurityCheck, AuditSeverity, &str)> = findings
+            .iter()
+            .map(|f| (f.check, f.severity, f.object.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SecurityCheck::Superuser, AuditSeverity::Info, "postgres"),
+                (SecurityCheck::Superuser, AuditSeverity::Critical, "admin"),
+                (
+                    SecurityCheck::PasswordNeverExpires,
+                    AuditSeverity::Warning,
+                    "admin"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_public_grant_severity() {
+        assert_eq!(
+            public_grant_finding("public", "CREATE").severity,
+            AuditSeverity::Warning
+        );
+        assert_eq!(
+            public_grant_finding("public", "USAGE").severity,
+            AuditSeverity::Info
+        );
+    }
+
+    #[test]
+    fn test_is_sensitive() {
+        let patterns = vec![
+            "app.users".to_string(),
+            "billing.*".to_string(),
+            "ssn_*".to_string(),
+        ];
+        assert!(is_sensitive(&patterns, "app", "users"));
+        assert!(!is_sensitive(&patterns, "public", "users"));
+        assert!(is_sensitive(&patterns, "billing", "invoices"));
+        assert!(is_sensitive(&patterns, "public", "ssn_records"));
+        assert!(!is_sensitive(&patterns, "app", "orders"));
+    }
+}
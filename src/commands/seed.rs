@@ -3,15 +3,20 @@
 use anyhow::{bail, Context, Result};
 use bytes::Bytes;
 use colored::Colorize;
-use futures_util::pin_mut;
+use futures_util::{pin_mut, StreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
 use std::path::Path;
 use std::time::Instant;
 use tokio_postgres::{Client, CopyInSink};
 
 use crate::config::Config;
 use crate::seed::{
-    discover_seeds, parse_seed, ParsedCsvSeed, ParsedSeed, SeedFile, SeedSchema, SeedType,
+    apply_seed_config, discover_seeds, load_seed_configs, parse_seed, ParsedCsvSeed, ParsedSeed,
+    SeedColumn, SeedConfig, SeedFile, SeedSchema, SeedStrategy, SeedType, SEEDS_CONFIG_FILE,
 };
+use crate::seed_generate::{generate_rows, GenColumn, ValueKind};
 use crate::sql::quote_ident;
 
 use super::connect;
@@ -143,7 +148,7 @@ fn print_no_seeds_hint(seeds_dir: &Path, quiet: bool) -> Result<()> {
                         continue;
                     }
                     let ext = sub_path.extension().and_then(|e| e.to_str());
-                    if matches!(ext, Some("csv") | Some("sql")) {
+                    if ext.and_then(SeedType::from_extension).is_some() {
                         schema_seed_files += 1;
                     }
                 }
@@ -151,7 +156,7 @@ fn print_no_seeds_hint(seeds_dir: &Path, quiet: bool) -> Result<()> {
             }
 
             let ext = path.extension().and_then(|e| e.to_str());
-            if matches!(ext, Some("csv") | Some("sql")) {
+            if ext.and_then(SeedType::from_extension).is_some() {
                 root_seed_files += 1;
             }
         }
@@ -159,9 +164,7 @@ fn print_no_seeds_hint(seeds_dir: &Path, quiet: bool) -> Result<()> {
 
     if !seeds_dir.exists() {
         println!("No seeds found in {}", seeds_dir.display());
-        println!(
-            "Expected seeds under `seeds/<schema>/<name>.csv` or `seeds/<schema>/<name>.sql`."
-        );
+        println!("Expected seeds under `seeds/<schema>/<name>.csv` (or .jsonl, .parquet, .sql).");
         return Ok(());
     }
 
@@ -173,18 +176,14 @@ fn print_no_seeds_hint(seeds_dir: &Path, quiet: bool) -> Result<()> {
             seeds_dir.display()
         );
     } else if schema_dirs == 0 {
-        println!(
-            "Expected seeds under `seeds/<schema>/<name>.csv` or `seeds/<schema>/<name>.sql`."
-        );
+        println!("Expected seeds under `seeds/<schema>/<name>.csv` (or .jsonl, .parquet, .sql).");
     } else if schema_seed_files == 0 {
         println!(
-            "Found {} schema director(ies), but no seed files. Expected `seeds/<schema>/<name>.csv` or `seeds/<schema>/<name>.sql`.",
+            "Found {} schema director(ies), but no seed files. Expected `seeds/<schema>/<name>.csv` (or .jsonl, .parquet, .sql).",
             schema_dirs
         );
     } else {
-        println!(
-            "Expected seeds under `seeds/<schema>/<name>.csv` or `seeds/<schema>/<name>.sql`."
-        );
+        println!("Expected seeds under `seeds/<schema>/<name>.csv` (or .jsonl, .parquet, .sql).");
     }
 
     Ok(())
@@ -199,24 +198,30 @@ pub fn seed_list(config: &Config, quiet: bool) -> Result<()> {
         print_no_seeds_hint(seeds_dir, quiet)?;
         return Ok(());
     }
+    let seed_configs = load_seed_configs(seeds_dir)?;
 
     if !quiet {
         println!("{}", "Available seeds:".bold());
         for seed in &seeds {
-            let type_label = match seed.seed_type {
-                SeedType::Csv => "csv",
-                SeedType::Sql => "sql",
-            };
+            let type_label = seed.seed_type.label();
             let schema_marker = if seed.schema_path.is_some() {
                 " (with schema)"
             } else {
                 ""
             };
+            let env_marker = match seed_configs
+                .get(&seed.qualified_name())
+                .and_then(|c| c.env.as_ref())
+            {
+                Some(envs) => format!(" (env: {})", envs.join(", ")),
+                None => String::new(),
+            };
             println!(
-                "  {} [{}]{}",
+                "  {} [{}]{}{}",
                 seed.qualified_name(),
                 type_label,
-                schema_marker
+                schema_marker,
+                env_marker
             );
         }
         println!("\n{} seed(s) found", seeds.len());
@@ -235,6 +240,7 @@ pub async fn seed_validate(
     let seeds_dir = Path::new(config.seeds_dir());
 
     let all_seeds = discover_seeds(seeds_dir)?;
+    let seed_configs = load_seed_configs(seeds_dir)?;
 
     if all_seeds.is_empty() {
         print_no_seeds_hint(seeds_dir, quiet)?;
@@ -266,7 +272,7 @@ pub async fn seed_validate(
         let prefix = format!("  {}: ", seed_file.qualified_name());
 
         // Try to parse the seed
-        match parse_seed(seed_file) {
+        match parse_configured_seed(seed_file, &seed_configs) {
             Ok(parsed) => {
                 match &parsed {
                     ParsedSeed::Csv(csv) => {
@@ -363,18 +369,74 @@ pub async fn seed_validate(
     Ok(())
 }
 
-/// Compare seed files to database state
+/// Row-level drift for one seed in `seed diff --json`
+#[derive(Serialize)]
+struct SeedDiffJson {
+    seed: String,
+    /// in_sync, drift, missing_table, sql or error
+    status: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    key: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_rows: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    db_rows: Option<i64>,
+    added: Vec<RowDiff>,
+    removed: Vec<RowDiff>,
+    changed: Vec<RowChange>,
+    applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl SeedDiffJson {
+    fn new(seed: String, status: &'static str) -> Self {
+        SeedDiffJson {
+            seed,
+            status,
+            key: Vec::new(),
+            file_rows: None,
+            db_rows: None,
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+            applied: false,
+            error: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SeedDiffOutput {
+    in_sync: bool,
+    seeds: Vec<SeedDiffJson>,
+}
+
+/// Compare seed files to database state row by row, optionally reconciling
+/// each table to match its file
 pub async fn seed_diff(
     database_url: &str,
     config: &Config,
     filter: Vec<String>,
+    apply: bool,
+    json: bool,
     quiet: bool,
 ) -> Result<()> {
     let seeds_dir = Path::new(config.seeds_dir());
+    let quiet = quiet || json;
 
     let all_seeds = discover_seeds(seeds_dir)?;
+    let seed_configs = load_seed_configs(seeds_dir)?;
 
     if all_seeds.is_empty() {
+        if json {
+            let output = SeedDiffOutput {
+                in_sync: true,
+                seeds: Vec::new(),
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+            return Ok(());
+        }
         print_no_seeds_hint(seeds_dir, quiet)?;
         return Ok(());
     }
@@ -394,8 +456,10 @@ pub async fn seed_diff(
         println!("{}", "Comparing seeds to database...".bold());
     }
 
+    let mut results = Vec::new();
     for seed_file in &seeds {
-        let prefix = format!("  {}: ", seed_file.qualified_name());
+        let name = seed_file.qualified_name();
+        let prefix = format!("  {}: ", name);
 
         let table_name = format!(
             "{}.{}",
@@ -407,13 +471,22 @@ pub async fn seed_diff(
             if !quiet {
                 println!("{}table does not exist", prefix.yellow());
             }
+            results.push(SeedDiffJson::new(name, "missing_table"));
             continue;
         }
 
         // Parse seed
-        match parse_seed(seed_file) {
+        match parse_configured_seed(seed_file, &seed_configs) {
             Ok(ParsedSeed::Csv(csv_seed)) => {
-                diff_csv_seed(&client, &csv_seed, &table_name, &prefix, quiet).await?;
+                let key =
+                    diff_key(&client, &csv_seed, seed_configs.get(&name), &table_name).await?;
+                let mut result =
+                    diff_csv_seed(&client, &csv_seed, &key, &table_name, apply).await?;
+                result.seed = name;
+                if !quiet {
+                    print_seed_drift(&result, &prefix);
+                }
+                results.push(result);
             }
             Ok(ParsedSeed::Sql(_)) => {
                 // SQL seed - can only show row count
@@ -423,132 +496,687 @@ pub async fn seed_diff(
                 if !quiet {
                     println!("{}SQL seed, {} rows in db", prefix.cyan(), db_count);
                 }
+                let mut result = SeedDiffJson::new(name, "sql");
+                result.db_rows = Some(db_count);
+                results.push(result);
             }
             Err(e) => {
                 if !quiet {
                     println!("{}parse error: {}", prefix.red(), e);
                 }
+                let mut result = SeedDiffJson::new(name, "error");
+                result.error = Some(format!("{:#}", e));
+                results.push(result);
             }
         }
     }
 
+    if json {
+        let output = SeedDiffOutput {
+            in_sync: results.iter().all(|r| r.status != "drift" || r.applied),
+            seeds: results,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    }
+
     Ok(())
 }
 
-/// Compare a CSV seed to database and show row-level differences
-async fn diff_csv_seed(
-    client: &tokio_postgres::Client,
-    csv_seed: &ParsedCsvSeed,
-    table_name: &str,
-    prefix: &str,
+/// Export a table's current rows to `<seeds>/<schema>/<table>.csv`, keeping the
+/// column order of an existing CSV seed so edits round-trip as small diffs
+pub async fn seed_export(
+    database_url: &str,
+    config: &Config,
+    target: &str,
+    where_clause: Option<&str>,
     quiet: bool,
 ) -> Result<()> {
-    use std::collections::HashSet;
+    let seeds_dir = Path::new(config.seeds_dir());
+    let (schema, table) = match parse_seed_selector(target) {
+        SeedSelector::Qualified { schema, table } => (schema, table),
+        SeedSelector::Table { table } => ("public".to_string(), table),
+    };
+    let path = seeds_dir.join(&schema).join(format!("{}.csv", table));
+
+    let existing = discover_seeds(seeds_dir)?
+        .into_iter()
+        .find(|s| s.schema == schema && s.table == table);
+    let existing_columns: Option<Vec<String>> = match existing {
+        Some(seed_file) if seed_file.seed_type != SeedType::Csv => bail!(
+            "seed '{}' is a {} seed; export only writes CSV",
+            seed_file.qualified_name(),
+            seed_file.seed_type.label()
+        ),
+        Some(seed_file)
+            if load_seed_configs(seeds_dir)?
+                .get(&seed_file.qualified_name())
+                .is_some_and(|c| {
+                    c.columns.is_some() || c.defaults.is_some() || c.transforms.is_some()
+                }) =>
+        {
+            bail!(
+                "seed '{}' has columns, defaults or transforms in {}; export can't reverse them",
+                seed_file.qualified_name(),
+                SEEDS_CONFIG_FILE
+            )
+        }
+        Some(seed_file) => match parse_seed(&seed_file)
+            .with_context(|| format!("parse seed: {}", seed_file.qualified_name()))?
+        {
+            ParsedSeed::Csv(csv) => Some(csv.columns.into_iter().map(|c| c.name).collect()),
+            ParsedSeed::Sql(_) => None,
+        },
+        None => None,
+    };
 
-    if csv_seed.columns.is_empty() {
-        if !quiet {
-            println!("{}no columns in seed file", prefix.yellow());
+    let client = connect(database_url).await?;
+    let t = require_table_exact(&client, &schema, &table).await?;
+    let table_name = format!("{}.{}", quote_ident(&t.schema), quote_ident(&t.name));
+
+    // Table columns in attnum order (generated columns can't be loaded back)
+    let rows = client
+        .query(
+            "SELECT attname, atttypid = 'boolean'::regtype
+             FROM pg_attribute
+             WHERE attrelid = $1::text::regclass AND attnum > 0
+               AND NOT attisdropped AND attgenerated = ''
+             ORDER BY attnum",
+            &[&table_name],
+        )
+        .await
+        .with_context(|| format!("read columns of {}", table_name))?;
+    let table_columns: Vec<(String, bool)> =
+        rows.iter().map(|row| (row.get(0), row.get(1))).collect();
+    let columns: Vec<(String, bool)> = match existing_columns {
+        Some(names) => names
+            .into_iter()
+            .map(|name| {
+                table_columns
+                    .iter()
+                    .find(|(c, _)| *c == name)
+                    .cloned()
+                    .with_context(|| {
+                        format!(
+                            "seed column '{}' is not a column of {}.{}",
+                            name, schema, table
+                        )
+                    })
+            })
+            .collect::<Result<_>>()?,
+        None => table_columns,
+    };
+
+    let primary_key: Vec<String> = client
+        .query(
+            "SELECT a.attname
+             FROM pg_index i
+             JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+             WHERE i.indrelid = $1::text::regclass AND i.indisprimary
+             ORDER BY array_position(i.indkey::int2[], a.attnum)",
+            &[&table_name],
+        )
+        .await
+        .with_context(|| format!("read primary key of {}", table_name))?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+
+    let select_list: Vec<String> = columns
+        .iter()
+        .map(|(name, is_bool)| {
+            // COPY writes booleans as t/f; seeds spell them out
+            if *is_bool {
+                format!("{0}::text AS {0}", quote_ident(name))
+            } else {
+                quote_ident(name)
+            }
+        })
+        .collect();
+    let order_by: Vec<String> = if primary_key.is_empty() {
+        (1..=columns.len()).map(|i| i.to_string()).collect()
+    } else {
+        primary_key.iter().map(|c| quote_ident(c)).collect()
+    };
+    let where_sql = where_clause
+        .map(|w| format!(" WHERE ({})", w))
+        .unwrap_or_default();
+    let copy_sql = format!(
+        "COPY (SELECT {} FROM {}{} ORDER BY {}) TO STDOUT WITH (FORMAT csv, HEADER true)",
+        select_list.join(", "),
+        table_name,
+        where_sql,
+        order_by.join(", ")
+    );
+
+    // Stable timestamptz output regardless of the server's TimeZone
+    client.batch_execute("SET TIME ZONE 'UTC'").await?;
+    let copy_stream = client
+        .copy_out(&copy_sql)
+        .await
+        .with_context(|| format!("export {}", table_name))?;
+    pin_mut!(copy_stream);
+    let mut data: Vec<u8> = Vec::new();
+    while let Some(chunk) = copy_stream.next().await {
+        data.extend_from_slice(&chunk.with_context(|| format!("export {}", table_name))?);
+    }
+    let row_count = csv::Reader::from_reader(data.as_slice()).records().count();
+
+    std::fs::create_dir_all(seeds_dir.join(&schema))
+        .with_context(|| format!("create directory: {}", seeds_dir.join(&schema).display()))?;
+    std::fs::write(&path, &data).with_context(|| format!("write {}", path.display()))?;
+
+    if !quiet {
+        println!(
+            "{} {} row(s) from {}.{} to {}",
+            "Exported".green(),
+            row_count,
+            schema,
+            table,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Generate deterministic fake rows for a table, written as a CSV seed or
+/// inserted directly. Foreign keys draw from rows already in the database.
+#[allow(clippy::too_many_arguments)]
+pub async fn seed_generate(
+    database_url: &str,
+    config: &Config,
+    target: &str,
+    rows: usize,
+    seed: &str,
+    insert: bool,
+    force: bool,
+    quiet: bool,
+) -> Result<()> {
+    let seeds_dir = Path::new(config.seeds_dir());
+    let (schema, table) = match parse_seed_selector(target) {
+        SeedSelector::Qualified { schema, table } => (schema, table),
+        SeedSelector::Table { table } => ("public".to_string(), table),
+    };
+    let path = seeds_dir.join(&schema).join(format!("{}.csv", table));
+    if !insert {
+        let existing = discover_seeds(seeds_dir)?
+            .into_iter()
+            .find(|s| s.schema == schema && s.table == table);
+        match existing {
+            Some(seed_file) if seed_file.seed_type != SeedType::Csv => bail!(
+                "seed '{}' is a {} seed; generate only writes CSV",
+                seed_file.qualified_name(),
+                seed_file.seed_type.label()
+            ),
+            Some(seed_file) if !force => bail!(
+                "seed '{}' already exists; use --force to overwrite it",
+                seed_file.qualified_name()
+            ),
+            _ => {}
         }
-        return Ok(());
     }
 
-    let file_count = csv_seed.rows.len();
+    let client = connect(database_url).await?;
+    let t = require_table_exact(&client, &schema, &table).await?;
+    let table_name = format!("{}.{}", quote_ident(&t.schema), quote_ident(&t.name));
+
+    let column_rows = client
+        .query(
+            "SELECT a.attname,
+                    format_type(CASE WHEN t.typtype = 'd' THEN t.typbasetype ELSE a.atttypid END,
+                                a.atttypmod),
+                    a.attnotnull,
+                    CASE WHEN a.atttypmod > 4 AND t.typname IN ('varchar', 'bpchar')
+                         THEN a.atttypmod - 4 END,
+                    ARRAY(SELECT e.enumlabel::text FROM pg_enum e
+                          WHERE e.enumtypid = a.atttypid ORDER BY e.enumsortorder),
+                    a.attidentity::text,
+                    a.attgenerated::text,
+                    EXISTS (SELECT 1 FROM pg_index i
+                            WHERE i.indrelid = a.attrelid AND i.indisunique
+                              AND i.indnatts = 1 AND i.indkey[0] = a.attnum)
+             FROM pg_attribute a
+             JOIN pg_type t ON t.oid = a.atttypid
+             WHERE a.attrelid = $1::text::regclass AND a.attnum > 0 AND NOT a.attisdropped
+             ORDER BY a.attnum",
+            &[&table_name],
+        )
+        .await
+        .with_context(|| format!("read columns of {}", table_name))?;
+
+    let fk_rows = client
+        .query(
+            "SELECT ca.attname, k.confrelid::regclass::text, fa.attname, array_length(k.conkey, 1)
+             FROM pg_constraint k
+             JOIN pg_attribute ca ON ca.attrelid = k.conrelid AND ca.attnum = k.conkey[1]
+             JOIN pg_attribute fa ON fa.attrelid = k.confrelid AND fa.attnum = k.confkey[1]
+             WHERE k.contype = 'f' AND k.conrelid = $1::text::regclass",
+            &[&table_name],
+        )
+        .await
+        .with_context(|| format!("read foreign keys of {}", table_name))?;
+    let mut references: HashMap<String, Vec<String>> = HashMap::new();
+    for row in &fk_rows {
+        let column: String = row.get(0);
+        let (parent, parent_column): (String, String) = (row.get(1), row.get(2));
+        let width: i32 = row.get(3);
+        if width > 1 {
+            bail!(
+                "{}.{} has a multi-column foreign key to {}; generate can't keep it consistent",
+                schema,
+                table,
+                parent
+            );
+        }
+        let values_sql = format!(
+            "SELECT DISTINCT {0}::text FROM {1} WHERE {0} IS NOT NULL ORDER BY 1 LIMIT 10000",
+            quote_ident(&parent_column),
+            parent
+        );
+        let values = client
+            .query(&values_sql, &[])
+            .await
+            .with_context(|| format!("read values of {}.{}", parent, parent_column))?
+            .iter()
+            .map(|r| r.get(0))
+            .collect();
+        references.insert(column, values);
+    }
 
-    // Get database row count
-    let count_sql = format!("SELECT COUNT(*) FROM {}", table_name);
-    let count_row = client.query_one(&count_sql, &[]).await?;
-    let db_count: i64 = count_row.get(0);
+    // Generated and GENERATED ALWAYS columns can't be written
+    let mut columns: Vec<GenColumn> = Vec::new();
+    for row in &column_rows {
+        let name: String = row.get(0);
+        let identity: String = row.get(5);
+        let generated: String = row.get(6);
+        if !generated.is_empty() || identity == "a" {
+            continue;
+        }
+        let data_type: String = row.get(1);
+        let max_length: Option<i32> = row.get(3);
+        columns.push(GenColumn {
+            kind: ValueKind::from_type(&data_type, row.get(4)),
+            data_type,
+            not_null: row.get(2),
+            unique: row.get(7),
+            max_length: max_length.map(|m| m as usize),
+            references: references.remove(&name),
+            name,
+        });
+    }
+    if columns.is_empty() {
+        bail!("{}.{} has no columns to generate", schema, table);
+    }
 
-    if file_count == db_count as usize {
+    // Unique integer columns continue after existing rows when inserting
+    let mut start = 1u64;
+    if insert {
+        for column in columns
+            .iter()
+            .filter(|c| c.unique && c.kind == ValueKind::Integer)
+        {
+            let max_sql = format!(
+                "SELECT coalesce(max({}), 0)::bigint FROM {}",
+                quote_ident(&column.name),
+                table_name
+            );
+            let max: i64 = client.query_one(&max_sql, &[]).await?.get(0);
+            start = start.max(max as u64 + 1);
+        }
+    }
+
+    let qualified = format!("{}.{}", schema, table);
+    let generated = generate_rows(&qualified, &columns, rows, start, seed)
+        .with_context(|| format!("generate rows for {}", qualified))?;
+
+    if insert {
+        let parsed = ParsedCsvSeed {
+            schema: schema.clone(),
+            table: table.clone(),
+            name: qualified.clone(),
+            columns: columns
+                .iter()
+                .map(|c| SeedColumn {
+                    name: c.name.clone(),
+                    pg_type: c.data_type.clone(),
+                })
+                .collect(),
+            rows: generated,
+            schema_def: None,
+            csv_content: String::new(),
+        };
+        let progress = CopyProgress {
+            label: String::new(),
+            total: rows,
+            enabled: false,
+            start: Instant::now(),
+        };
+        let inserted = copy_rows(&client, &table_name, &parsed, 10_000, &progress).await?;
         if !quiet {
-            println!("{}{} rows (in sync)", prefix.green(), file_count);
+            println!(
+                "{} {} row(s) into {}",
+                "Inserted".green(),
+                inserted,
+                qualified
+            );
         }
         return Ok(());
     }
 
-    // Determine key column: use first primary_key column from schema, or fall back to first column
-    let key_col_name: &str = csv_seed
-        .schema_def
-        .as_ref()
-        .and_then(|s| s.primary_key.as_ref())
-        .and_then(|pk| pk.first())
-        .map(|s| s.as_str())
-        .unwrap_or(&csv_seed.columns[0].name);
-
-    let key_col_idx = csv_seed
-        .columns
-        .iter()
-        .position(|c| c.name == key_col_name)
-        .unwrap_or(0);
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(columns.iter().map(|c| c.name.as_str()))?;
+    for row in &generated {
+        writer.write_record(row.iter().map(|v| v.as_deref().unwrap_or("")))?;
+    }
+    let data = writer.into_inner().context("write CSV")?;
+    std::fs::create_dir_all(seeds_dir.join(&schema))
+        .with_context(|| format!("create directory: {}", seeds_dir.join(&schema).display()))?;
+    std::fs::write(&path, data).with_context(|| format!("write {}", path.display()))?;
+    if !quiet {
+        println!(
+            "{} {} row(s) for {} to {}",
+            "Generated".green(),
+            rows,
+            qualified,
+            path.display()
+        );
+    }
+    Ok(())
+}
 
-    // Build set of keys from file
-    let file_keys: HashSet<String> = csv_seed
-        .rows
+/// Key columns for diffing a data seed: the `seeds.toml` key, the sidecar's
+/// primary_key, the table's primary key, or else the first column
+async fn diff_key(
+    client: &Client,
+    seed: &ParsedCsvSeed,
+    config: Option<&SeedConfig>,
+    table_name: &str,
+) -> Result<Vec<String>> {
+    if let Some(key) = config
+        .and_then(|c| c.key.clone())
+        .or_else(|| seed.schema_def.as_ref().and_then(|s| s.primary_key.clone()))
+        .filter(|key| !key.is_empty())
+    {
+        return Ok(key);
+    }
+    let primary_key: Vec<String> = client
+        .query(
+            "SELECT a.attname
+             FROM pg_index i
+             JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+             WHERE i.indrelid = $1::text::regclass AND i.indisprimary
+             ORDER BY array_position(i.indkey::int2[], a.attnum)",
+            &[&table_name],
+        )
+        .await
+        .with_context(|| format!("read primary key of {}", table_name))?
         .iter()
-        .filter_map(|row| row.get(key_col_idx).and_then(|v| v.clone()))
+        .map(|row| row.get(0))
         .collect();
+    let in_seed = |c: &String| seed.columns.iter().any(|sc| &sc.name == c);
+    if !primary_key.is_empty() && primary_key.iter().all(in_seed) {
+        return Ok(primary_key);
+    }
+    Ok(seed
+        .columns
+        .first()
+        .map(|c| vec![c.name.clone()])
+        .unwrap_or_default())
+}
+
+/// A row only in the file (added) or only in the database (removed)
+#[derive(Serialize)]
+struct RowDiff {
+    key: serde_json::Map<String, serde_json::Value>,
+    row: serde_json::Value,
+}
+
+/// A row whose non-key columns differ between database and file
+#[derive(Serialize)]
+struct RowChange {
+    key: serde_json::Map<String, serde_json::Value>,
+    columns: std::collections::BTreeMap<String, ColumnChange>,
+}
+
+#[derive(Serialize)]
+struct ColumnChange {
+    db: serde_json::Value,
+    file: serde_json::Value,
+}
+
+fn row_key(row: &serde_json::Value, key: &[String]) -> serde_json::Map<String, serde_json::Value> {
+    key.iter()
+        .map(|k| (k.clone(), row.get(k).cloned().unwrap_or_default()))
+        .collect()
+}
 
-    // Fetch keys from database
-    let key_sql = format!(
-        "SELECT {}::text FROM {}",
-        quote_ident(key_col_name),
-        table_name
+fn format_key(key: &serde_json::Map<String, serde_json::Value>) -> String {
+    key.iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Print one seed's drift (at most a few rows of each kind)
+fn print_seed_drift(result: &SeedDiffJson, prefix: &str) {
+    const MAX_SHOW: usize = 5;
+
+    let file_rows = result.file_rows.unwrap_or(0);
+    if result.status == "in_sync" {
+        println!("{}{} rows (in sync)", prefix.green(), file_rows);
+        return;
+    }
+    println!(
+        "{}{} rows in file, {} in db (+{} added, -{} removed, ~{} changed)",
+        prefix.yellow(),
+        file_rows,
+        result.db_rows.unwrap_or(0),
+        result.added.len(),
+        result.removed.len(),
+        result.changed.len()
     );
-    let db_rows = client.query(&key_sql, &[]).await?;
-    let db_keys: HashSet<String> = db_rows
+    for row in result.added.iter().take(MAX_SHOW) {
+        println!("    {} {} {}", "+".green(), format_key(&row.key), row.row);
+    }
+    if result.added.len() > MAX_SHOW {
+        println!(
+            "    {} ...and {} more added",
+            "+".green(),
+            result.added.len() - MAX_SHOW
+        );
+    }
+    for row in result.removed.iter().take(MAX_SHOW) {
+        println!("    {} {} {}", "-".red(), format_key(&row.key), row.row);
+    }
+    if result.removed.len() > MAX_SHOW {
+        println!(
+            "    {} ...and {} more removed",
+            "-".red(),
+            result.removed.len() - MAX_SHOW
+        );
+    }
+    for row in result.changed.iter().take(MAX_SHOW) {
+        let columns: Vec<String> = row
+            .columns
+            .iter()
+            .map(|(c, change)| format!("{}: {} -> {}", c, change.db, change.file))
+            .collect();
+        println!(
+            "    {} {} {}",
+            "~".yellow(),
+            format_key(&row.key),
+            columns.join(", ")
+        );
+    }
+    if result.changed.len() > MAX_SHOW {
+        println!(
+            "    {} ...and {} more changed",
+            "~".yellow(),
+            result.changed.len() - MAX_SHOW
+        );
+    }
+    if result.applied {
+        println!("    {}", "reconciled table to match the file".green());
+    }
+}
+
+/// Compare a data seed to its table row by row, keyed by `key`. The file is
+/// staged in a temp table so values compare with the table's own types.
+/// With `apply`, the table is made to match the file in one transaction.
+async fn diff_csv_seed(
+    client: &tokio_postgres::Client,
+    csv_seed: &ParsedCsvSeed,
+    key: &[String],
+    table_name: &str,
+    apply: bool,
+) -> Result<SeedDiffJson> {
+    let mut result = SeedDiffJson::new(csv_seed.name.clone(), "in_sync");
+    result.key = key.to_vec();
+    result.file_rows = Some(csv_seed.rows.len());
+    let count_sql = format!("SELECT COUNT(*) FROM {}", table_name);
+    result.db_rows = Some(client.query_one(&count_sql, &[]).await?.get(0));
+
+    if csv_seed.columns.is_empty() {
+        return Ok(result);
+    }
+
+    let staging = "pg_temp.pgcrate_seed_diff";
+    let columns: Vec<String> = csv_seed
+        .columns
         .iter()
-        .filter_map(|row| row.get::<_, Option<String>>(0))
+        .map(|c| quote_ident(&c.name))
         .collect();
+    client
+        .batch_execute(&format!(
+            "DROP TABLE IF EXISTS {staging};
+             CREATE TEMP TABLE pgcrate_seed_diff AS SELECT {} FROM {} WITH NO DATA",
+            columns.join(", "),
+            table_name
+        ))
+        .await
+        .with_context(|| format!("create staging table for: {}", table_name))?;
+    let progress = CopyProgress {
+        label: String::new(),
+        total: csv_seed.rows.len(),
+        enabled: false,
+        start: Instant::now(),
+    };
+    copy_rows(client, staging, csv_seed, 10_000, &progress).await?;
 
-    // Find differences
-    let missing_in_db: Vec<_> = file_keys.difference(&db_keys).collect();
-    let extra_in_db: Vec<_> = db_keys.difference(&file_keys).collect();
+    let matches = key
+        .iter()
+        .map(|k| format!("f.{0} = t.{0}", quote_ident(k)))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let order = key
+        .iter()
+        .map(|k| quote_ident(k))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let t_columns: Vec<String> = columns.iter().map(|c| format!("t.{}", c)).collect();
+    let db_row = format!(
+        "(SELECT to_jsonb(x) FROM (SELECT {}) x)",
+        t_columns.join(", ")
+    );
+    let parse = |text: String| -> Result<serde_json::Value> {
+        serde_json::from_str(&text).context("parse row JSON")
+    };
 
-    if !quiet {
-        println!(
-            "{}{} rows in file, {} in db",
-            prefix.yellow(),
-            file_count,
-            db_count
-        );
+    let added_sql = format!(
+        "SELECT to_jsonb(f)::text FROM {staging} f
+         WHERE NOT EXISTS (SELECT 1 FROM {table_name} t WHERE {matches}) ORDER BY {order}"
+    );
+    for row in client.query(&added_sql, &[]).await? {
+        let row = parse(row.get(0))?;
+        result.added.push(RowDiff {
+            key: row_key(&row, key),
+            row,
+        });
+    }
 
-        const MAX_SHOW: usize = 5;
+    let removed_sql = format!(
+        "SELECT {db_row}::text FROM {table_name} t
+         WHERE NOT EXISTS (SELECT 1 FROM {staging} f WHERE {matches}) ORDER BY {order}"
+    );
+    for row in client.query(&removed_sql, &[]).await? {
+        let row = parse(row.get(0))?;
+        result.removed.push(RowDiff {
+            key: row_key(&row, key),
+            row,
+        });
+    }
 
-        if !missing_in_db.is_empty() {
-            let show_count = missing_in_db.len().min(MAX_SHOW);
-            for key in missing_in_db.iter().take(show_count) {
-                println!(
-                    "    {} {}={} (missing in db)",
-                    "+".green(),
-                    key_col_name,
-                    key
-                );
-            }
-            if missing_in_db.len() > MAX_SHOW {
-                println!(
-                    "    {} ...and {} more missing",
-                    "+".green(),
-                    missing_in_db.len() - MAX_SHOW
-                );
-            }
-        }
+    let changed_sql = format!(
+        "SELECT {db_row}::text, to_jsonb(f)::text FROM {staging} f
+         JOIN {table_name} t ON {matches}
+         WHERE {db_row} IS DISTINCT FROM to_jsonb(f) ORDER BY {}",
+        key.iter()
+            .map(|k| format!("f.{}", quote_ident(k)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    for row in client.query(&changed_sql, &[]).await? {
+        let (db, file) = (parse(row.get(0))?, parse(row.get(1))?);
+        let columns = csv_seed
+            .columns
+            .iter()
+            .filter(|c| db.get(&c.name) != file.get(&c.name))
+            .map(|c| {
+                (
+                    c.name.clone(),
+                    ColumnChange {
+                        db: db.get(&c.name).cloned().unwrap_or_default(),
+                        file: file.get(&c.name).cloned().unwrap_or_default(),
+                    },
+                )
+            })
+            .collect();
+        result.changed.push(RowChange {
+            key: row_key(&file, key),
+            columns,
+        });
+    }
 
-        if !extra_in_db.is_empty() {
-            let show_count = extra_in_db.len().min(MAX_SHOW);
-            for key in extra_in_db.iter().take(show_count) {
-                println!("    {} {}={} (extra in db)", "-".red(), key_col_name, key);
-            }
-            if extra_in_db.len() > MAX_SHOW {
-                println!(
-                    "    {} ...and {} more extra",
-                    "-".red(),
-                    extra_in_db.len() - MAX_SHOW
-                );
-            }
+    let drift =
+        !(result.added.is_empty() && result.removed.is_empty() && result.changed.is_empty());
+    if drift {
+        result.status = "drift";
+    }
+
+    if apply && drift {
+        let updates: Vec<String> = csv_seed
+            .columns
+            .iter()
+            .filter(|c| !key.contains(&c.name))
+            .map(|c| format!("{0} = f.{0}", quote_ident(&c.name)))
+            .collect();
+        let mut statements = vec![format!(
+            "DELETE FROM {table_name} t WHERE NOT EXISTS (SELECT 1 FROM {staging} f WHERE {matches})"
+        )];
+        if !updates.is_empty() {
+            statements.push(format!(
+                "UPDATE {table_name} t SET {} FROM {staging} f
+                 WHERE {matches} AND {db_row} IS DISTINCT FROM to_jsonb(f)",
+                updates.join(", ")
+            ));
         }
+        statements.push(format!(
+            "INSERT INTO {table_name} ({cols}) SELECT {cols} FROM {staging} f
+             WHERE NOT EXISTS (SELECT 1 FROM {table_name} t WHERE {matches})",
+            cols = columns.join(", ")
+        ));
+        // One simple-query batch runs as a single transaction
+        client
+            .batch_execute(&statements.join(";\n"))
+            .await
+            .with_context(|| format!("reconcile {} with its seed", table_name))?;
+        result.applied = true;
     }
 
-    Ok(())
+    client
+        .batch_execute(&format!("DROP TABLE {staging}"))
+        .await
+        .with_context(|| format!("drop staging table for: {}", table_name))?;
+    Ok(result)
 }
 
 /// Load seed data into database
@@ -556,7 +1184,9 @@ pub async fn seed_run(
     database_url: &str,
     config: &Config,
     filter: Vec<String>,
+    environment: Option<&str>,
     dry_run: bool,
+    chunk_size: usize,
     quiet: bool,
 ) -> Result<()> {
     let seeds_dir = Path::new(config.seeds_dir());
@@ -569,6 +1199,13 @@ pub async fn seed_run(
         return Ok(());
     }
 
+    let seed_configs = load_seed_configs(seeds_dir)?;
+    for name in seed_configs.keys() {
+        if !all_seeds.iter().any(|s| &s.qualified_name() == name) {
+            bail!("{}: unknown seed '{}'", SEEDS_CONFIG_FILE, name);
+        }
+    }
+
     // Filter seeds if specified
     let seeds: Vec<SeedFile> = if filter.is_empty() {
         all_seeds
@@ -579,6 +1216,28 @@ pub async fn seed_run(
             .collect()
     };
 
+    // Skip seeds scoped to other environments
+    let (seeds, skipped): (Vec<SeedFile>, Vec<SeedFile>) = seeds.into_iter().partition(|s| {
+        seed_configs
+            .get(&s.qualified_name())
+            .is_none_or(|c| c.loads_in(environment))
+    });
+    if !skipped.is_empty() && !quiet {
+        let names: Vec<String> = skipped.iter().map(|s| s.qualified_name()).collect();
+        println!(
+            "{}",
+            format!(
+                "Skipping {} seed(s) not scoped to {}: {}",
+                skipped.len(),
+                environment
+                    .map(|e| format!("environment '{}'", e))
+                    .unwrap_or_else(|| "all environments".to_string()),
+                names.join(", ")
+            )
+            .dimmed()
+        );
+    }
+
     if seeds.is_empty() {
         if !quiet {
             println!("No matching seeds found");
@@ -589,13 +1248,32 @@ pub async fn seed_run(
     // Parse all seeds first (to validate before making changes)
     let mut parsed_seeds: Vec<(SeedFile, ParsedSeed)> = Vec::new();
     for seed_file in seeds {
-        let parsed = parse_seed(&seed_file)
+        let parsed = parse_configured_seed(&seed_file, &seed_configs)
             .with_context(|| format!("parse seed: {}", seed_file.qualified_name()))?;
         parsed_seeds.push((seed_file, parsed));
     }
 
-    // Sort by dependencies (CSV seeds with FK refs come after their dependencies)
-    let ordered_seeds = order_by_dependencies(&parsed_seeds);
+    // Alphabetical for now; reordered by foreign keys once connected
+    let mut ordered_seeds = order_by_dependencies(&parsed_seeds);
+
+    // Load strategy (and upsert key) per data seed
+    let mut strategies: HashMap<String, (SeedStrategy, Vec<String>)> = HashMap::new();
+    for (_, parsed) in &ordered_seeds {
+        let config = seed_configs.get(parsed.name());
+        match parsed {
+            ParsedSeed::Csv(csv) => {
+                strategies.insert(csv.name.clone(), seed_strategy(csv, config)?);
+            }
+            ParsedSeed::Sql(sql) if config.is_some_and(|c| c.has_data_settings()) => {
+                bail!(
+                    "{}: seed '{}' is a SQL seed; only env applies to SQL seeds",
+                    SEEDS_CONFIG_FILE,
+                    sql.name
+                )
+            }
+            ParsedSeed::Sql(_) => {}
+        }
+    }
 
     if dry_run {
         let client: Option<Client> = if !database_url.is_empty() {
@@ -603,11 +1281,23 @@ pub async fn seed_run(
         } else {
             None
         };
+        if let Some(client) = client.as_ref() {
+            let fks = seed_foreign_keys(client, &ordered_seeds).await?;
+            ordered_seeds = order_by_foreign_keys(ordered_seeds, &fks);
+        }
         println!("{}", "Would load (in order):".bold());
         for (seed_file, parsed) in &ordered_seeds {
-            let type_label = match seed_file.seed_type {
-                SeedType::Csv => "csv",
-                SeedType::Sql => "sql",
+            let type_label = match strategies.get(parsed.name()) {
+                Some((SeedStrategy::Truncate, _)) | None => seed_file.seed_type.label().to_string(),
+                Some((strategy, key)) if key.is_empty() => {
+                    format!("{}, {}", seed_file.seed_type.label(), strategy.as_str())
+                }
+                Some((strategy, key)) => format!(
+                    "{}, {} on {}",
+                    seed_file.seed_type.label(),
+                    strategy.as_str(),
+                    key.join(", ")
+                ),
             };
             let missing_note = if let Some(client) = client.as_ref() {
                 if table_exists(client, parsed.schema(), parsed.table()).await? {
@@ -639,6 +1329,11 @@ pub async fn seed_run(
     // Connect to database
     let client = connect(database_url).await?;
 
+    // Parents before children, so loads don't depend on disabling FK triggers
+    // (which needs superuser) and SQL seeds see their referenced rows
+    let fks = seed_foreign_keys(&client, &ordered_seeds).await?;
+    let ordered_seeds = order_by_foreign_keys(ordered_seeds, &fks);
+
     // Resolve CSV seed targets up-front (and fail early with clear errors)
     let mut ordered_with_targets: Vec<(SeedFile, ParsedSeed, Option<TargetTable>)> = Vec::new();
     for (seed_file, parsed) in ordered_seeds {
@@ -654,7 +1349,7 @@ pub async fn seed_run(
     // Collect all table names for FK handling
     let mut csv_tables: Vec<String> = Vec::new();
     for (seed_file, parsed, target) in &ordered_with_targets {
-        if seed_file.seed_type == SeedType::Csv {
+        if seed_file.seed_type != SeedType::Sql {
             if let (ParsedSeed::Csv(_), Some(t)) = (parsed, target) {
                 csv_tables.push(format!(
                     "{}.{}",
@@ -676,16 +1371,60 @@ pub async fn seed_run(
             let disable_sql = format!("ALTER TABLE IF EXISTS {} DISABLE TRIGGER ALL", table);
             let _ = client.batch_execute(&disable_sql).await; // Ignore errors if table doesn't exist
         }
+    }
+
+    // Truncate once up-front so later seeds can't wipe earlier loaded tables via CASCADE.
+    // Append/upsert seeds keep their rows, so none may reference a truncated table.
+    let mut truncate_tables: Vec<String> = Vec::new();
+    let mut kept_tables: Vec<String> = Vec::new();
+    for (_, parsed, target) in &ordered_with_targets {
+        if let (ParsedSeed::Csv(csv), Some(t)) = (parsed, target) {
+            let table = format!("{}.{}", quote_ident(&t.schema), quote_ident(&t.name));
+            match strategies[&csv.name].0 {
+                SeedStrategy::Truncate => truncate_tables.push(table),
+                SeedStrategy::Append | SeedStrategy::Upsert => kept_tables.push(table),
+            }
+        }
+    }
+    truncate_tables.sort();
+    truncate_tables.dedup();
+    if !truncate_tables.is_empty() {
+        for table in &kept_tables {
+            let row = client
+                .query_opt(
+                    "SELECT k.conrelid::regclass::text, n.nspname || '.' || c.relname
+                     FROM pg_constraint k
+                     JOIN pg_class c ON c.oid = k.confrelid
+                     JOIN pg_namespace n ON n.oid = c.relnamespace
+                     WHERE k.contype = 'f' AND k.conrelid = $1::text::regclass
+                       AND k.confrelid = ANY($2::text[]::regclass[])
+                     LIMIT 1",
+                    &[table, &truncate_tables],
+                )
+                .await
+                .with_context(|| format!("check foreign keys of {}", table))?;
+            if let Some(row) = row {
+                let (table, referenced): (String, String) = (row.get(0), row.get(1));
+                enable_triggers(&client, &csv_tables).await;
+                bail!(
+                    "{} keeps its rows but references {}, which is truncated with CASCADE; \
+                     set strategy append or upsert for {} in {}",
+                    table,
+                    referenced,
+                    referenced,
+                    SEEDS_CONFIG_FILE
+                );
+            }
+        }
 
-        // Truncate once up-front so later seeds can't wipe earlier loaded tables via CASCADE.
         if !quiet {
             println!("{}", "Truncating seed tables...".dimmed());
         }
-        let truncate_sql = format!("TRUNCATE {} CASCADE", csv_tables.join(", "));
+        let truncate_sql = format!("TRUNCATE {} CASCADE", truncate_tables.join(", "));
         client
             .batch_execute(&truncate_sql)
             .await
-            .with_context(|| format!("truncate seed tables: {}", csv_tables.join(", ")))?;
+            .with_context(|| format!("truncate seed tables: {}", truncate_tables.join(", ")))?;
     }
 
     // Load each seed
@@ -693,23 +1432,27 @@ pub async fn seed_run(
     let mut total_rows = 0;
     let mut loaded_count = 0;
 
+    let interactive = !quiet && std::io::stdout().is_terminal();
     for (seed_file, parsed, target) in &ordered_with_targets {
+        let label = match (parsed, target) {
+            (ParsedSeed::Csv(_), Some(t)) => format!(
+                "{} {} \u{2192} {}.{}... ",
+                "Loading".cyan(),
+                parsed.name(),
+                t.schema,
+                t.name
+            ),
+            _ => format!("{} {}... ", "Loading".cyan(), parsed.name()),
+        };
         if !quiet {
-            match (parsed, target) {
-                (ParsedSeed::Csv(_), Some(t)) => {
-                    print!(
-                        "{} {} \u{2192} {}.{}... ",
-                        "Loading".cyan(),
-                        parsed.name(),
-                        t.schema,
-                        t.name
-                    );
-                }
-                _ => {
-                    print!("{} {}... ", "Loading".cyan(), parsed.name());
-                }
-            }
+            print!("{}", label);
         }
+        let progress = CopyProgress {
+            label,
+            total: parsed.row_count().unwrap_or(0),
+            enabled: interactive && parsed.row_count().unwrap_or(0) > chunk_size,
+            start: Instant::now(),
+        };
 
         let load_start = Instant::now();
         let result = match parsed {
@@ -717,7 +1460,12 @@ pub async fn seed_run(
                 let t = target
                     .as_ref()
                     .context("missing target table for CSV seed")?;
-                load_csv_seed(&client, t, csv).await
+                match &strategies[&csv.name] {
+                    (SeedStrategy::Upsert, key) => {
+                        upsert_csv_seed(&client, t, csv, key, chunk_size, &progress).await
+                    }
+                    _ => load_csv_seed(&client, t, csv, chunk_size, &progress).await,
+                }
             }
             ParsedSeed::Sql(sql) => load_sql_seed(&client, &sql.name, &sql.sql).await,
         };
@@ -729,7 +1477,12 @@ pub async fn seed_run(
                 let elapsed = load_start.elapsed();
                 if !quiet {
                     if rows > 0 {
-                        println!("{} rows ({:.2}s)", rows, elapsed.as_secs_f64());
+                        println!(
+                            "{} rows ({:.2}s, {:.0} rows/s)",
+                            rows,
+                            elapsed.as_secs_f64(),
+                            rows as f64 / elapsed.as_secs_f64().max(0.001)
+                        );
                     } else {
                         println!("done ({:.2}s)", elapsed.as_secs_f64());
                     }
@@ -740,10 +1493,7 @@ pub async fn seed_run(
                     println!("{}", "FAILED".red());
                 }
                 // Re-enable triggers before returning error
-                for table in &csv_tables {
-                    let enable_sql = format!("ALTER TABLE IF EXISTS {} ENABLE TRIGGER ALL", table);
-                    let _ = client.batch_execute(&enable_sql).await;
-                }
+                enable_triggers(&client, &csv_tables).await;
                 return Err(e)
                     .with_context(|| format!("load seed: {}", seed_file.qualified_name()));
             }
@@ -778,8 +1528,62 @@ pub async fn seed_run(
     Ok(())
 }
 
+/// Parse a seed and apply its `seeds.toml` column settings
+fn parse_configured_seed(
+    seed_file: &SeedFile,
+    seed_configs: &HashMap<String, SeedConfig>,
+) -> Result<ParsedSeed> {
+    let mut parsed = parse_seed(seed_file)?;
+    if let (ParsedSeed::Csv(csv), Some(config)) =
+        (&mut parsed, seed_configs.get(&seed_file.qualified_name()))
+    {
+        apply_seed_config(csv, config)?;
+    }
+    Ok(parsed)
+}
+
+/// Re-enable triggers after a failed load (errors ignored)
+async fn enable_triggers(client: &Client, tables: &[String]) {
+    for table in tables {
+        let enable_sql = format!("ALTER TABLE IF EXISTS {} ENABLE TRIGGER ALL", table);
+        let _ = client.batch_execute(&enable_sql).await;
+    }
+}
+
+/// Load strategy and upsert key for a data seed. Upsert keys come from
+/// `seeds.toml` or the sidecar's primary_key and must be seed columns.
+fn seed_strategy(
+    seed: &ParsedCsvSeed,
+    config: Option<&SeedConfig>,
+) -> Result<(SeedStrategy, Vec<String>)> {
+    let strategy = config.map(|c| c.strategy).unwrap_or_default();
+    if strategy != SeedStrategy::Upsert {
+        return Ok((strategy, Vec::new()));
+    }
+    let key = config
+        .and_then(|c| c.key.clone())
+        .or_else(|| seed.schema_def.as_ref().and_then(|s| s.primary_key.clone()))
+        .filter(|key| !key.is_empty())
+        .with_context(|| {
+            format!(
+                "seed '{}': strategy upsert needs key = [...] in {} or primary_key in its .schema.toml",
+                seed.name, SEEDS_CONFIG_FILE
+            )
+        })?;
+    for column in &key {
+        if !seed.columns.iter().any(|c| &c.name == column) {
+            bail!(
+                "seed '{}': upsert key column '{}' is not in the seed",
+                seed.name,
+                column
+            );
+        }
+    }
+    Ok((strategy, key))
+}
+
 /// Order seeds alphabetically for consistent loading order.
-/// FK constraints are handled by disabling triggers during load.
+/// `order_by_foreign_keys` then moves referenced tables first.
 fn order_by_dependencies(seeds: &[(SeedFile, ParsedSeed)]) -> Vec<(SeedFile, ParsedSeed)> {
     let mut result: Vec<_> = seeds
         .iter()
@@ -791,6 +1595,55 @@ fn order_by_dependencies(seeds: &[(SeedFile, ParsedSeed)]) -> Vec<(SeedFile, Par
     result
 }
 
+/// Foreign keys between seed tables, as (referencing, referenced) seed names.
+/// Self-references are left out.
+async fn seed_foreign_keys(
+    client: &Client,
+    seeds: &[(SeedFile, ParsedSeed)],
+) -> Result<Vec<(String, String)>> {
+    let names: Vec<String> = seeds.iter().map(|(sf, _)| sf.qualified_name()).collect();
+    let rows = client
+        .query(
+            "SELECT cn.nspname || '.' || c.relname, pn.nspname || '.' || p.relname
+             FROM pg_constraint k
+             JOIN pg_class c ON c.oid = k.conrelid
+             JOIN pg_namespace cn ON cn.oid = c.relnamespace
+             JOIN pg_class p ON p.oid = k.confrelid
+             JOIN pg_namespace pn ON pn.oid = p.relnamespace
+             WHERE k.contype = 'f' AND k.conrelid <> k.confrelid
+               AND cn.nspname || '.' || c.relname = ANY($1)
+               AND pn.nspname || '.' || p.relname = ANY($1)",
+            &[&names],
+        )
+        .await
+        .context("read foreign keys between seed tables")?;
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+/// Stable topological order: each seed goes after the seeds it references,
+/// otherwise keeping the incoming order. Cycles are broken in incoming order
+/// (FK triggers are disabled during data seed loads).
+fn order_by_foreign_keys(
+    seeds: Vec<(SeedFile, ParsedSeed)>,
+    fks: &[(String, String)],
+) -> Vec<(SeedFile, ParsedSeed)> {
+    let mut remaining = seeds;
+    let mut result = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|(sf, _)| {
+            let name = sf.qualified_name();
+            !fks.iter().any(|(child, parent)| {
+                *child == name
+                    && remaining
+                        .iter()
+                        .any(|(other, _)| other.qualified_name() == *parent)
+            })
+        });
+        result.push(remaining.remove(ready.unwrap_or(0)));
+    }
+    result
+}
+
 fn clone_parsed_seed(p: &ParsedSeed) -> ParsedSeed {
     match p {
         ParsedSeed::Csv(csv) => ParsedSeed::Csv(ParsedCsvSeed {
@@ -819,6 +1672,8 @@ async fn load_csv_seed(
     client: &tokio_postgres::Client,
     target: &TargetTable,
     seed: &ParsedCsvSeed,
+    chunk_size: usize,
+    progress: &CopyProgress,
 ) -> Result<usize> {
     let table_name = format!(
         "{}.{}",
@@ -830,6 +1685,112 @@ async fn load_csv_seed(
         return Ok(0);
     }
 
+    copy_rows(client, &table_name, seed, chunk_size, progress).await
+}
+
+/// Upsert a data seed: COPY into a temp table, then
+/// INSERT ... ON CONFLICT (key) DO UPDATE so rows not in the seed are kept
+async fn upsert_csv_seed(
+    client: &tokio_postgres::Client,
+    target: &TargetTable,
+    seed: &ParsedCsvSeed,
+    key: &[String],
+    chunk_size: usize,
+    progress: &CopyProgress,
+) -> Result<usize> {
+    let table_name = format!(
+        "{}.{}",
+        quote_ident(&target.schema),
+        quote_ident(&target.name)
+    );
+
+    if seed.rows.is_empty() {
+        return Ok(0);
+    }
+
+    let staging = "pg_temp.pgcrate_seed_upsert";
+    let columns: Vec<String> = seed.columns.iter().map(|c| quote_ident(&c.name)).collect();
+    client
+        .batch_execute(&format!(
+            "DROP TABLE IF EXISTS {staging};
+             CREATE TEMP TABLE pgcrate_seed_upsert AS SELECT {} FROM {} WITH NO DATA",
+            columns.join(", "),
+            table_name
+        ))
+        .await
+        .with_context(|| format!("create staging table for: {}", table_name))?;
+    copy_rows(client, staging, seed, chunk_size, progress).await?;
+
+    let updates: Vec<String> = seed
+        .columns
+        .iter()
+        .filter(|c| !key.contains(&c.name))
+        .map(|c| format!("{0} = EXCLUDED.{0}", quote_ident(&c.name)))
+        .collect();
+    let conflict_action = if updates.is_empty() {
+        "DO NOTHING".to_string()
+    } else {
+        format!("DO UPDATE SET {}", updates.join(", "))
+    };
+    let key_cols: Vec<String> = key.iter().map(|k| quote_ident(k)).collect();
+    let upsert_sql = format!(
+        "INSERT INTO {table} ({cols}) SELECT {cols} FROM {staging} ON CONFLICT ({key}) {action}",
+        table = table_name,
+        cols = columns.join(", "),
+        key = key_cols.join(", "),
+        action = conflict_action
+    );
+    let rows = client
+        .execute(&upsert_sql, &[])
+        .await
+        .with_context(|| format!("upsert into: {}", table_name))?;
+    client
+        .batch_execute(&format!("DROP TABLE {staging}"))
+        .await
+        .with_context(|| format!("drop staging table for: {}", table_name))?;
+    Ok(rows as usize)
+}
+
+/// In-place row count and rate while a seed loads over several COPY chunks
+struct CopyProgress {
+    /// The "Loading ..." prefix already printed on the current line
+    label: String,
+    total: usize,
+    /// Only for interactive output and seeds larger than one chunk
+    enabled: bool,
+    start: Instant,
+}
+
+impl CopyProgress {
+    fn update(&self, done: usize) {
+        if !self.enabled {
+            return;
+        }
+        let rate = done as f64 / self.start.elapsed().as_secs_f64().max(0.001);
+        print!(
+            "\r\x1b[K{}{}/{} rows ({:.0} rows/s)",
+            self.label, done, self.total, rate
+        );
+        let _ = std::io::stdout().flush();
+    }
+
+    /// Restore the bare label so the final result prints after it
+    fn finish(&self) {
+        if self.enabled {
+            print!("\r\x1b[K{}", self.label);
+            let _ = std::io::stdout().flush();
+        }
+    }
+}
+
+/// COPY a data seed's rows into `table_name`, streaming `chunk_size` rows per message
+async fn copy_rows(
+    client: &tokio_postgres::Client,
+    table_name: &str,
+    seed: &ParsedCsvSeed,
+    chunk_size: usize,
+    progress: &CopyProgress,
+) -> Result<usize> {
     // Use COPY for bulk loading
     let col_names: Vec<String> = seed.columns.iter().map(|c| quote_ident(&c.name)).collect();
     let copy_sql = format!(
@@ -838,20 +1799,6 @@ async fn load_csv_seed(
         col_names.join(", ")
     );
 
-    // Build CSV data without header (COPY expects raw data)
-    let mut csv_data = String::new();
-    for row in &seed.rows {
-        let values: Vec<String> = row
-            .iter()
-            .map(|v| match v {
-                Some(s) => escape_csv_value(s),
-                None => String::new(),
-            })
-            .collect();
-        csv_data.push_str(&values.join(","));
-        csv_data.push('\n');
-    }
-
     // Execute COPY
     let sink: CopyInSink<Bytes> = client
         .copy_in(&copy_sql)
@@ -862,15 +1809,33 @@ async fn load_csv_seed(
 
     use futures_util::SinkExt;
 
-    // Write CSV data to sink
-    sink.send(Bytes::from(csv_data.into_bytes()))
-        .await
-        .with_context(|| format!("write data for: {}", table_name))?;
+    // Write CSV data (no header) one chunk at a time
+    let mut sent = 0;
+    for chunk in seed.rows.chunks(chunk_size.max(1)) {
+        let mut csv_data = String::new();
+        for row in chunk {
+            let values: Vec<String> = row
+                .iter()
+                .map(|v| match v {
+                    Some(s) => escape_csv_value(s),
+                    None => String::new(),
+                })
+                .collect();
+            csv_data.push_str(&values.join(","));
+            csv_data.push('\n');
+        }
+        sink.send(Bytes::from(csv_data.into_bytes()))
+            .await
+            .with_context(|| format!("write data for: {}", table_name))?;
+        sent += chunk.len();
+        progress.update(sent);
+    }
 
     let rows_copied = sink
         .finish()
         .await
         .with_context(|| format!("finish COPY for: {}", table_name))?;
+    progress.finish();
 
     Ok(rows_copied as usize)
 }
@@ -895,3 +1860,56 @@ async fn load_sql_seed(client: &tokio_postgres::Client, name: &str, sql: &str) -
     // SQL seeds don't have a row count
     Ok(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::ParsedSqlSeed;
+    use std::path::PathBuf;
+
+    fn sql_seed(table: &str) -> (SeedFile, ParsedSeed) {
+        let seed_file = SeedFile {
+            schema: "public".to_string(),
+            table: table.to_string(),
+            path: PathBuf::from(format!("{table}.sql")),
+            seed_type: SeedType::Sql,
+            schema_path: None,
+        };
+        let parsed = ParsedSeed::Sql(ParsedSqlSeed {
+            schema: "public".to_string(),
+            table: table.to_string(),
+            name: seed_file.qualified_name(),
+            sql: String::new(),
+        });
+        (seed_file, parsed)
+    }
+
+    fn names(seeds: &[(SeedFile, ParsedSeed)]) -> Vec<String> {
+        seeds.iter().map(|(sf, _)| sf.table.clone()).collect()
+    }
+
+    fn fk(child: &str, parent: &str) -> (String, String) {
+        (format!("public.{child}"), format!("public.{parent}"))
+    }
+
+    #[test]
+    fn test_order_by_foreign_keys() {
+        let seeds = vec![
+            sql_seed("comments"),
+            sql_seed("posts"),
+            sql_seed("tags"),
+            sql_seed("users"),
+        ];
+        let fks = vec![fk("comments", "posts"), fk("posts", "users")];
+        let ordered = order_by_foreign_keys(seeds, &fks);
+        assert_eq!(names(&ordered), vec!["tags", "users", "posts", "comments"]);
+    }
+
+    #[test]
+    fn test_order_by_foreign_keys_breaks_cycles_in_order() {
+        let seeds = vec![sql_seed("a"), sql_seed("b"), sql_seed("c")];
+        let fks = vec![fk("a", "b"), fk("b", "a"), fk("a", "c")];
+        let ordered = order_by_foreign_keys(seeds, &fks);
+        assert_eq!(names(&ordered), vec!["c", "a", "b"]);
+    }
+}
@@ -7,10 +7,18 @@ use anyhow::Result;
 use serde::Serialize;
 use tokio_postgres::Client;
 
+use crate::config::{AlertIgnore, AlertsConfig, Thresholds};
+
 /// Default warning threshold (percentage)
 const DEFAULT_WARNING_PCT: i32 = 70;
 const DEFAULT_CRITICAL_PCT: i32 = 85;
 
+/// Defaults for `[sequences]` in pgcrate.alerts.toml
+pub const DEFAULT_THRESHOLDS: Thresholds = Thresholds {
+    warn: DEFAULT_WARNING_PCT as f64,
+    crit: DEFAULT_CRITICAL_PCT as f64,
+};
+
 /// Sequence information
 #[derive(Debug, Clone, Serialize)]
 pub struct SequenceInfo {
@@ -61,6 +69,9 @@ pub struct SequencesResult {
     pub overall_status: SeqStatus,
     pub warning_threshold: i32,
     pub critical_threshold: i32,
+    /// Changes against recorded metrics (when --compare is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<super::trend::Comparison>,
 }
 
 /// Get all sequences with their usage
@@ -68,6 +79,7 @@ pub async fn get_sequences(
     client: &Client,
     warn_threshold: i32,
     crit_threshold: i32,
+    ignore: &AlertIgnore,
 ) -> Result<SequencesResult> {
     // Query sequences with type-aware max value calculation
     let query = r#"
@@ -90,12 +102,17 @@ pub async fn get_sequences(
     let mut sequences = Vec::new();
 
     for row in rows {
+        let schema: String = row.get("schemaname");
+        let name: String = row.get("sequencename");
+        if ignore.sequence(&schema, &name) {
+            continue;
+        }
         let pct_used: f64 = row.get("pct_used");
         let status = SeqStatus::from_pct(pct_used, warn_threshold, crit_threshold);
 
         sequences.push(SequenceInfo {
-            schema: row.get("schemaname"),
-            name: row.get("sequencename"),
+            schema,
+            name,
             data_type: row.get("data_type"),
             last_value: row.get("last_value"),
             max_value: row.get("max_value"),
@@ -121,6 +138,7 @@ pub async fn get_sequences(
         overall_status,
         warning_threshold: warn_threshold,
         critical_threshold: crit_threshold,
+        comparison: None,
     })
 }
 
@@ -276,15 +294,18 @@ pub fn print_json(
     Ok(())
 }
 
-/// Run sequences check with default thresholds
+/// Run sequences check. `--warn`/`--crit` win over pgcrate.alerts.toml,
+/// which wins over the defaults.
 pub async fn run_sequences(
     client: &Client,
     warn_threshold: Option<i32>,
     crit_threshold: Option<i32>,
+    alerts: &AlertsConfig,
 ) -> Result<SequencesResult> {
-    let warn = warn_threshold.unwrap_or(DEFAULT_WARNING_PCT);
-    let crit = crit_threshold.unwrap_or(DEFAULT_CRITICAL_PCT);
-    get_sequences(client, warn, crit).await
+    let configured = alerts.sequences.or(DEFAULT_THRESHOLDS);
+    let warn = warn_threshold.unwrap_or(configured.warn.round() as i32);
+    let crit = crit_threshold.unwrap_or(configured.crit.round() as i32);
+    get_sequences(client, warn, crit, &alerts.ignore).await
 }
 
 #[cfg(test)]
@@ -0,0 +1,574 @@
+//! Slots command: Replication slot and logical decoding health.
+//!
+//! A replication slot pins WAL (restart_lsn) and, for hot_standby_feedback
+//! and logical decoding, the xmin horizon (xmin, catalog_xmin) until its
+//! consumer confirms progress. A slot whose consumer is gone fills the disk
+//! with WAL and stops VACUUM from freezing, heading toward XID wraparound.
+//! This command reports both per slot, plus the apply lag of this database's
+//! logical subscriptions from pg_stat_subscription.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use crate::snapshot::format_bytes;
+
+const GB: i64 = 1024 * 1024 * 1024;
+
+/// WAL held back by an inactive slot
+const INACTIVE_RETAINED_WARNING: i64 = GB;
+const INACTIVE_RETAINED_CRITICAL: i64 = 10 * GB;
+/// WAL held back by an active slot whose consumer is falling behind
+const ACTIVE_RETAINED_WARNING: i64 = 10 * GB;
+/// xmin horizon age at which VACUUM can no longer freeze past the slot
+/// becomes critical (half of the ~2.1B XID space)
+const XMIN_AGE_CRITICAL: i64 = 1_000_000_000;
+/// Seconds since a subscription last heard from its publisher
+const SUBSCRIPTION_LAG_WARNING_SECS: f64 = 300.0;
+const SUBSCRIPTION_LAG_CRITICAL_SECS: f64 = 3600.0;
+
+/// Slot health status level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlotStatus {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl SlotStatus {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            SlotStatus::Healthy => "✓",
+            SlotStatus::Warning => "⚠",
+            SlotStatus::Critical => "✗",
+        }
+    }
+}
+
+/// One replication slot
+#[derive(Debug, Clone, Serialize)]
+pub struct SlotHealth {
+    pub slot_name: String,
+    /// "physical" or "logical"
+    pub slot_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database: Option<String>,
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_pid: Option<i32>,
+    pub temporary: bool,
+    /// reserved, extended, unreserved or lost
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wal_status: Option<String>,
+    /// WAL that can still be written before the slot is invalidated
+    /// (None when max_slot_wal_keep_size is unlimited)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safe_wal_size_bytes: Option<i64>,
+    /// WAL kept on disk for this slot (current LSN - restart_lsn)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retained_bytes: Option<i64>,
+    /// WAL not yet confirmed by a logical consumer (current LSN - confirmed_flush_lsn)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmed_lag_bytes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xmin_age: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub catalog_xmin_age: Option<i64>,
+    pub status: SlotStatus,
+    pub issues: Vec<String>,
+}
+
+impl SlotHealth {
+    /// Oldest of xmin and catalog_xmin, the horizon this slot holds VACUUM to
+    pub fn horizon_age(&self) -> Option<i64> {
+        self.xmin_age.max(self.catalog_xmin_age)
+    }
+}
+
+/// One logical subscription in the current database
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriptionLag {
+    pub subscription: String,
+    pub enabled: bool,
+    /// Apply worker pid (None when no worker is running)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub received_lsn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_end_lsn: Option<String>,
+    /// Seconds since the publisher last reported a position to this worker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lag_secs: Option<f64>,
+    /// Tables still in initial sync
+    pub syncing_tables: i64,
+    pub status: SlotStatus,
+    pub issues: Vec<String>,
+}
+
+/// Full slot results
+#[derive(Debug, Serialize)]
+pub struct SlotsResult {
+    pub in_recovery: bool,
+    pub autovacuum_freeze_max_age: i64,
+    /// -1 when slots may retain unlimited WAL
+    pub max_slot_wal_keep_size_mb: i64,
+    pub slots: Vec<SlotHealth>,
+    pub subscriptions: Vec<SubscriptionLag>,
+    pub overall_status: SlotStatus,
+}
+
+/// Grade a slot and explain why. Inactive slots are graded on the WAL they
+/// retain, active ones only when their consumer falls far behind. A slot
+/// holding the xmin horizon past autovacuum_freeze_max_age blocks freezing.
+fn assess_slot(slot: &SlotHealth, freeze_max_age: i64) -> (SlotStatus, Vec<String>) {
+    let mut status = SlotStatus::Healthy;
+    let mut issues = Vec::new();
+    let retained = slot.retained_bytes.unwrap_or(0);
+    let retained_str = format_bytes(retained.max(0) as u64);
+
+    match slot.wal_status.as_deref() {
+        Some("lost") => {
+            status = SlotStatus::Critical;
+            issues.push(
+                "WAL needed by this slot was removed; its consumer must be rebuilt".to_string(),
+            );
+        }
+        Some("unreserved") => {
+            status = SlotStatus::Critical;
+            issues.push(
+                "Past max_slot_wal_keep_size; the slot will be invalidated at the next checkpoint"
+                    .to_string(),
+            );
+        }
+        Some("extended") => {
+            status = SlotStatus::Warning;
+            issues.push(format!(
+                "Retains {} of WAL, beyond max_wal_size",
+                retained_str
+            ));
+        }
+        _ => {}
+    }
+
+    if !slot.active && !slot.temporary {
+        let level = if retained >= INACTIVE_RETAINED_CRITICAL {
+            SlotStatus::Critical
+        } else if retained >= INACTIVE_RETAINED_WARNING {
+            SlotStatus::Warning
+        } else {
+            SlotStatus::Healthy
+        };
+        if level > SlotStatus::Healthy {
+            status = status.max(level);
+            issues.push(format!(
+                "Inactive and retaining {} of WAL; drop it with pg_drop_replication_slot('{}') if its consumer is gone",
+                retained_str, slot.slot_name
+            ));
+        }
+    } else if slot.active && retained >= ACTIVE_RETAINED_WARNING {
+        status = status.max(SlotStatus::Warning);
+        issues.push(format!(
+            "Consumer is {} behind; check that it keeps up",
+            retained_str
+        ));
+    }
+
+    if let Some(age) = slot.horizon_age() {
+        let level = if age >= XMIN_AGE_CRITICAL {
+            SlotStatus::Critical
+        } else if age >= freeze_max_age {
+            SlotStatus::Warning
+        } else {
+            SlotStatus::Healthy
+        };
+        if level > SlotStatus::Healthy {
+            status = status.max(level);
+            issues.push(format!(
+                "Holds the xmin horizon {} transactions back; VACUUM cannot freeze past it (wraparound risk)",
+                age
+            ));
+        }
+    }
+
+    (status, issues)
+}
+
+/// Grade a subscription: disabled, missing its apply worker, or not hearing
+/// from the publisher
+fn assess_subscription(sub: &SubscriptionLag) -> (SlotStatus, Vec<String>) {
+    if !sub.enabled {
+        return (
+            SlotStatus::Warning,
+            vec![
+                "Disabled; the publisher's slot keeps retaining WAL until it is re-enabled or dropped"
+                    .to_string(),
+            ],
+        );
+    }
+    if sub.pid.is_none() {
+        return (
+            SlotStatus::Critical,
+            vec!["Enabled but no apply worker is running; check the server log".to_string()],
+        );
+    }
+    match sub.lag_secs {
+        Some(secs) if secs >= SUBSCRIPTION_LAG_CRITICAL_SECS => (
+            SlotStatus::Critical,
+            vec![format!("No progress from the publisher for {:.0}s", secs)],
+        ),
+        Some(secs) if secs >= SUBSCRIPTION_LAG_WARNING_SECS => (
+            SlotStatus::Warning,
+            vec![format!("No progress from the publisher for {:.0}s", secs)],
+        ),
+        _ => (SlotStatus::Healthy, vec![]),
+    }
+}
+
+async fn get_slots(client: &Client, freeze_max_age: i64) -> Result<Vec<SlotHealth>> {
+    let rows = client
+        .query(
+            r#"
+            WITH lsn AS (
+                SELECT CASE WHEN pg_is_in_recovery()
+                            THEN pg_last_wal_receive_lsn()
+                            ELSE pg_current_wal_lsn() END AS current_lsn
+            )
+            SELECT
+                s.slot_name::text,
+                s.slot_type,
+                s.plugin::text,
+                s.database::text,
+                s.active,
+                s.active_pid,
+                s.temporary,
+                s.wal_status,
+                s.safe_wal_size,
+                pg_wal_lsn_diff(lsn.current_lsn, s.restart_lsn)::bigint AS retained_bytes,
+                pg_wal_lsn_diff(lsn.current_lsn, s.confirmed_flush_lsn)::bigint
+                    AS confirmed_lag_bytes,
+                age(s.xmin)::bigint AS xmin_age,
+                age(s.catalog_xmin)::bigint AS catalog_xmin_age
+            FROM pg_replication_slots s
+            CROSS JOIN lsn
+            ORDER BY s.slot_name
+            "#,
+            &[],
+        )
+        .await
+        .context("Failed to query pg_replication_slots")?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let mut slot = SlotHealth {
+                slot_name: row.get("slot_name"),
+                slot_type: row.get("slot_type"),
+                plugin: row.get("plugin"),
+                database: row.get("database"),
+                active: row.get("active"),
+                active_pid: row.get("active_pid"),
+                temporary: row.get("temporary"),
+                wal_status: row.get("wal_status"),
+                safe_wal_size_bytes: row.get("safe_wal_size"),
+                retained_bytes: row.get("retained_bytes"),
+                confirmed_lag_bytes: row.get("confirmed_lag_bytes"),
+                xmin_age: row.get("xmin_age"),
+                catalog_xmin_age: row.get("catalog_xmin_age"),
+                status: SlotStatus::Healthy,
+                issues: vec![],
+            };
+            (slot.status, slot.issues) = assess_slot(&slot, freeze_max_age);
+            slot
+        })
+        .collect())
+}
+
+/// Subscriptions in the current database with apply-worker lag, graded
+pub async fn get_subscriptions(client: &Client) -> Result<Vec<SubscriptionLag>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                s.subname::text AS subscription,
+                s.subenabled AS enabled,
+                st.pid,
+                st.received_lsn::text,
+                st.latest_end_lsn::text,
+                extract(epoch FROM now() - st.latest_end_time)::float8 AS lag_secs,
+                (SELECT count(*) FROM pg_subscription_rel r
+                 WHERE r.srsubid = s.oid AND r.srsubstate <> 'r') AS syncing_tables
+            FROM pg_subscription s
+            LEFT JOIN pg_stat_subscription st
+                ON st.subid = s.oid AND st.relid IS NULL
+            WHERE s.subdbid = (SELECT oid FROM pg_database WHERE datname = current_database())
+            ORDER BY s.subname
+            "#,
+            &[],
+        )
+        .await
+        .context("Failed to query pg_stat_subscription")?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let mut sub = SubscriptionLag {
+                subscription: row.get("subscription"),
+                enabled: row.get("enabled"),
+                pid: row.get("pid"),
+                received_lsn: row.get("received_lsn"),
+                latest_end_lsn: row.get("latest_end_lsn"),
+                lag_secs: row.get("lag_secs"),
+                syncing_tables: row.get("syncing_tables"),
+                status: SlotStatus::Healthy,
+                issues: vec![],
+            };
+            (sub.status, sub.issues) = assess_subscription(&sub);
+            sub
+        })
+        .collect())
+}
+
+/// Run replication slot and subscription analysis
+pub async fn run_slots(client: &Client) -> Result<SlotsResult> {
+    let row = client
+        .query_one(
+            "SELECT
+                 pg_is_in_recovery(),
+                 current_setting('autovacuum_freeze_max_age')::bigint,
+                 (SELECT setting::bigint FROM pg_settings WHERE name = 'max_slot_wal_keep_size')",
+            &[],
+        )
+        .await
+        .context("Failed to read slot settings")?;
+    let in_recovery: bool = row.get(0);
+    let autovacuum_freeze_max_age: i64 = row.get(1);
+    let max_slot_wal_keep_size_mb: i64 = row.get(2);
+
+    let slots = get_slots(client, autovacuum_freeze_max_age).await?;
+    let subscriptions = get_subscriptions(client).await?;
+
+    let overall_status = slots
+        .iter()
+        .map(|s| s.status)
+        .chain(subscriptions.iter().map(|s| s.status))
+        .max()
+        .unwrap_or(SlotStatus::Healthy);
+
+    Ok(SlotsResult {
+        in_recovery,
+        autovacuum_freeze_max_age,
+        max_slot_wal_keep_size_mb,
+        slots,
+        subscriptions,
+        overall_status,
+    })
+}
+
+fn bytes_or_dash(bytes: Option<i64>) -> String {
+    bytes
+        .map(|b| format_bytes(b.max(0) as u64))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Print slot analysis in human-readable format
+pub fn print_human(result: &SlotsResult, quiet: bool) {
+    println!("REPLICATION SLOTS");
+    println!("=================");
+    println!();
+    if !quiet {
+        println!(
+            "  max_slot_wal_keep_size:     {}",
+            if result.max_slot_wal_keep_size_mb < 0 {
+                "unlimited".to_string()
+            } else {
+                format_bytes(result.max_slot_wal_keep_size_mb as u64 * 1024 * 1024)
+            }
+        );
+        println!(
+            "  autovacuum_freeze_max_age:  {}",
+            result.autovacuum_freeze_max_age
+        );
+        println!();
+    }
+
+    if result.slots.is_empty() {
+        println!("  No replication slots.");
+    } else {
+        println!(
+            "  {:3} {:30} {:9} {:7} {:>11} {:>10} {:>11} {:>12}",
+            "", "SLOT", "TYPE", "ACTIVE", "WAL STATUS", "RETAINED", "UNCONFIRMED", "XMIN AGE"
+        );
+        for s in &result.slots {
+            println!(
+                "  {} {:30} {:9} {:7} {:>11} {:>10} {:>11} {:>12}",
+                s.status.emoji(),
+                s.slot_name,
+                s.slot_type,
+                if s.active { "yes" } else { "no" },
+                s.wal_status.as_deref().unwrap_or("-"),
+                bytes_or_dash(s.retained_bytes),
+                bytes_or_dash(s.confirmed_lag_bytes),
+                s.horizon_age()
+                    .map(|a| a.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            );
+            for issue in &s.issues {
+                println!("      → {}", issue);
+            }
+        }
+    }
+    println!();
+
+    println!("Subscriptions:");
+    if result.subscriptions.is_empty() {
+        println!("  No logical subscriptions in this database.");
+    } else {
+        for sub in &result.subscriptions {
+            let lag = sub
+                .lag_secs
+                .map(|s| format!("{:.0}s since last publisher update", s))
+                .unwrap_or_else(|| "no worker".to_string());
+            println!(
+                "  {} {} ({}){}",
+                sub.status.emoji(),
+                sub.subscription,
+                if sub.enabled {
+                    lag
+                } else {
+                    "disabled".to_string()
+                },
+                if sub.syncing_tables > 0 {
+                    format!(", {} tables syncing", sub.syncing_tables)
+                } else {
+                    String::new()
+                }
+            );
+            for issue in &sub.issues {
+                println!("      → {}", issue);
+            }
+        }
+    }
+}
+
+/// Print slot analysis as JSON with schema versioning.
+pub fn print_json(
+    result: &SlotsResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = match result.overall_status {
+        SlotStatus::Healthy => Severity::Healthy,
+        SlotStatus::Warning => Severity::Warning,
+        SlotStatus::Critical => Severity::Critical,
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::SLOTS, result, severity, t),
+        None => DiagnosticOutput::new(schema::SLOTS, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(active: bool, retained: i64) -> SlotHealth {
+        SlotHealth {
+            slot_name: "s".to_string(),
+            slot_type: "logical".to_string(),
+            plugin: Some("pgoutput".to_string()),
+            database: Some("app".to_string()),
+            active,
+            active_pid: None,
+            temporary: false,
+            wal_status: Some("reserved".to_string()),
+            safe_wal_size_bytes: None,
+            retained_bytes: Some(retained),
+            confirmed_lag_bytes: None,
+            xmin_age: None,
+            catalog_xmin_age: None,
+            status: SlotStatus::Healthy,
+            issues: vec![],
+        }
+    }
+
+    #[test]
+    fn test_inactive_slot_graded_on_retained_wal() {
+        let freeze = 200_000_000;
+        assert_eq!(
+            assess_slot(&slot(false, 1024), freeze).0,
+            SlotStatus::Healthy
+        );
+        assert_eq!(
+            assess_slot(&slot(false, 2 * GB), freeze).0,
+            SlotStatus::Warning
+        );
+        assert_eq!(
+            assess_slot(&slot(false, 20 * GB), freeze).0,
+            SlotStatus::Critical
+        );
+        // An active consumer a few GB behind is fine
+        assert_eq!(
+            assess_slot(&slot(true, 2 * GB), freeze).0,
+            SlotStatus::Healthy
+        );
+        assert_eq!(
+            assess_slot(&slot(true, 20 * GB), freeze).0,
+            SlotStatus::Warning
+        );
+    }
+
+    #[test]
+    fn test_lost_slot_is_critical() {
+        let mut s = slot(true, 0);
+        s.wal_status = Some("lost".to_string());
+        let (status, issues) = assess_slot(&s, 200_000_000);
+        assert_eq!(status, SlotStatus::Critical);
+        assert_eq!(issues.len(), 1);
+    }
+
+    #[test]
+    fn test_stuck_catalog_xmin_is_wraparound_risk() {
+        let mut s = slot(false, 0);
+        s.xmin_age = Some(10);
+        s.catalog_xmin_age = Some(300_000_000);
+        assert_eq!(s.horizon_age(), Some(300_000_000));
+        let (status, issues) = assess_slot(&s, 200_000_000);
+        assert_eq!(status, SlotStatus::Warning);
+        assert!(issues[0].contains("wraparound"));
+
+        s.catalog_xmin_age = Some(1_200_000_000);
+        assert_eq!(assess_slot(&s, 200_000_000).0, SlotStatus::Critical);
+    }
+
+    #[test]
+    fn test_assess_subscription() {
+        let mut sub = SubscriptionLag {
+            subscription: "sub".to_string(),
+            enabled: true,
+            pid: Some(42),
+            received_lsn: None,
+            latest_end_lsn: None,
+            lag_secs: Some(5.0),
+            syncing_tables: 0,
+            status: SlotStatus::Healthy,
+            issues: vec![],
+        };
+        assert_eq!(assess_subscription(&sub).0, SlotStatus::Healthy);
+        sub.lag_secs = Some(600.0);
+        assert_eq!(assess_subscription(&sub).0, SlotStatus::Warning);
+        sub.lag_secs = Some(7200.0);
+        assert_eq!(assess_subscription(&sub).0, SlotStatus::Critical);
+        sub.pid = None;
+        assert_eq!(assess_subscription(&sub).0, SlotStatus::Critical);
+        sub.enabled = false;
+        assert_eq!(assess_subscription(&sub).0, SlotStatus::Warning);
+    }
+}
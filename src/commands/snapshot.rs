@@ -1,22 +1,32 @@
+use crate::commands::schema::report_diff;
 use crate::config::{parse_database_url, url_matches_production_patterns, Config};
+use crate::introspect::{self, IntrospectOptions};
+use crate::output::Output;
 use crate::snapshot::{
-    self, check_pg_dump, check_pg_restore, check_psql, extract_host, get_pg_dump_version,
-    should_warn_version_downgrade, snapshot_dir, snapshot_exists, snapshots_dir,
-    validate_snapshot_name, SnapshotFormat, SnapshotMetadata,
+    self, check_pg_dump, check_pg_restore, check_psql, check_tool_version, extract_host,
+    get_pg_dump_version, get_tool_version, should_warn_version_downgrade, snapshot_dir,
+    snapshot_exists, snapshots_dir, validate_snapshot_name, SnapshotFormat, SnapshotMetadata,
+    FILTERED_ROWS_FILE,
 };
+use crate::snapshot_encryption::{snapshot_encryption, Decryption, Encryption};
+use crate::snapshot_storage::{snapshot_storage, SnapshotStorage};
 use crate::sql::quote_ident;
 use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
 use colored::Colorize;
+use futures_util::{pin_mut, StreamExt};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
 use std::path::Path;
+use std::process::Stdio;
 use tokio::process::Command;
 use tokio_postgres::Client;
 
 use super::connect;
+use super::native_dump::write_native_dump;
 
 #[derive(Deserialize, Default, Debug)]
 struct SnapshotProfilesFile {
@@ -90,6 +100,8 @@ pub async fn snapshot_save(
     format_str: &str,
     no_owner: bool,
     no_privileges: bool,
+    encrypt_keys: &[String],
+    native: bool,
     config: &Config,
     quiet: bool,
     verbose: bool,
@@ -140,9 +152,24 @@ pub async fn snapshot_save(
 
     // Get snapshot directory override
     let snap_dir_override = Some(config.snapshot_dir());
+    let storage = snapshot_storage(config)?;
+    let encryption = snapshot_encryption(encrypt_keys, config)?;
+
+    // Snapshots in remote storage: any prefix with this name, complete or not
+    if let Some(ref storage) = storage {
+        if storage.names()?.iter().any(|n| n == name) {
+            bail!(
+                "Snapshot \"{}\" already exists in {}.\n\
+                 Hint: Delete it first with: pgcrate snapshot delete {}",
+                name,
+                storage.url(),
+                name
+            );
+        }
+    }
 
     // Check if snapshot already exists (complete snapshot)
-    if snapshot_exists(name, snap_dir_override) {
+    if storage.is_none() && snapshot_exists(name, snap_dir_override) {
         bail!(
             "Snapshot \"{}\" already exists.\n\
              Hint: Delete it first with: pgcrate snapshot delete {}",
@@ -153,7 +180,7 @@ pub async fn snapshot_save(
 
     // Check if snapshot directory exists but is incomplete (from failed save)
     let snap_dir = snapshot_dir(name, snap_dir_override);
-    if snap_dir.exists() {
+    if storage.is_none() && snap_dir.exists() {
         bail!(
             "Snapshot directory \"{}\" exists but is incomplete.\n\
              Hint: Delete it first with: pgcrate snapshot delete {}",
@@ -162,9 +189,38 @@ pub async fn snapshot_save(
         );
     }
 
-    // Check pg_dump binary exists
+    // Check pg_dump binary exists; plain snapshots can do without it
     let pg_dump_path = config.tool_path("pg_dump");
-    check_pg_dump(&pg_dump_path)?;
+    let native = match check_pg_dump(&pg_dump_path) {
+        Ok(()) => native,
+        Err(_) if native => true,
+        Err(_) if format == SnapshotFormat::Plain => {
+            if !quiet {
+                eprintln!(
+                    "{}",
+                    "pg_dump not found; saving with pgcrate's built-in dump.".yellow()
+                );
+            }
+            true
+        }
+        Err(e) => return Err(e.context("Hint: --format plain (or --native) saves without pg_dump")),
+    };
+    if native {
+        if format != SnapshotFormat::Plain {
+            bail!("--native only writes plain dumps; use --format plain");
+        }
+        if let Some(p) = effective_profile.as_ref() {
+            if p.tables.is_some()
+                || p.exclude_tables.is_some()
+                || p.row_filters.as_ref().is_some_and(|f| !f.is_empty())
+            {
+                bail!(
+                    "The built-in dump supports profile schemas and data = false only; \
+                     tables, exclude_tables and [where] need pg_dump"
+                );
+            }
+        }
+    }
 
     // Warn about production patterns
     if url_matches_production_patterns(database_url, config) && !quiet {
@@ -180,9 +236,18 @@ pub async fn snapshot_save(
         println!("Saving snapshot...");
         println!("  Database: {}", parsed.database_name);
         println!("  Format:   {}", format);
+        if native {
+            println!("  Dump:     built-in (no pg_dump)");
+        }
         if let Some(p) = profile_name {
             println!("  Profile:  {}", p);
         }
+        if let Some(ref storage) = storage {
+            println!("  Storage:  {}", storage.url());
+        }
+        if let Some(ref encryption) = encryption {
+            println!("  Encrypt:  {}", encryption.cipher);
+        }
     }
 
     // Connect to database to get migration state and metadata
@@ -199,7 +264,25 @@ pub async fn snapshot_save(
     let pg_version = get_pg_version(&client).await.ok();
 
     // Get pg_dump version
-    let pg_dump_version = get_pg_dump_version(&pg_dump_path).ok();
+    let pg_dump_version = if native {
+        None
+    } else {
+        get_pg_dump_version(&pg_dump_path).ok()
+    };
+
+    // pg_dump refuses servers newer than itself; say so before it runs
+    if let (Some(server), Some(tool)) = (&pg_version, &pg_dump_version) {
+        check_tool_version(
+            "pg_dump",
+            &pg_dump_path,
+            tool,
+            "server",
+            version_number(server),
+        )?;
+    }
+
+    // The built-in dump has no owners or privileges
+    let (no_owner, no_privileges) = (no_owner || native, no_privileges || native);
 
     // Get owner roles (for pre-flight checking on restore)
     let owner_roles = if !no_owner {
@@ -211,12 +294,35 @@ pub async fn snapshot_save(
     // Extract source host
     let source_host = extract_host(database_url);
 
+    // Row filters: pg_dump skips these tables' data; the kept rows are copied
+    // separately, from the same exported snapshot as the dump
+    let row_filters = match effective_profile.as_ref() {
+        Some(p) => resolve_row_filters(&client, p).await?,
+        None => Vec::new(),
+    };
+    if !quiet {
+        for filter in &row_filters {
+            println!("  Filter:   {} WHERE {}", filter.table, filter.condition);
+        }
+    }
+
     // Determine dump filename based on format
     let dump_filename = format.dump_filename();
     let dump_path = snap_dir.join(dump_filename);
 
+    // Filtered rows are copied inside a snapshot that pg_dump then shares
+    let export_snapshot = if row_filters.is_empty() || dry_run {
+        None
+    } else {
+        client
+            .batch_execute("BEGIN ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .await?;
+        let row = client.query_one("SELECT pg_export_snapshot()", &[]).await?;
+        Some(row.get::<_, String>(0))
+    };
+
     // Build pg_dump command
-    let mut cmd = Command::new(&pg_dump_path);
+    let mut cmd = std::process::Command::new(&pg_dump_path);
 
     match format {
         SnapshotFormat::Custom => {
@@ -227,7 +333,10 @@ pub async fn snapshot_save(
         }
     }
 
-    cmd.arg("--file").arg(&dump_path);
+    // Remote and encrypted snapshots stream the dump from stdout
+    if storage.is_none() && encryption.is_none() {
+        cmd.arg("--file").arg(&dump_path);
+    }
 
     if no_owner {
         cmd.arg("--no-owner");
@@ -262,6 +371,12 @@ pub async fn snapshot_save(
             cmd.arg("--schema-only");
         }
     }
+    for filter in &row_filters {
+        cmd.arg(format!("--exclude-table-data={}", filter.quoted));
+    }
+    if let Some(ref id) = export_snapshot {
+        cmd.arg(format!("--snapshot={}", id));
+    }
 
     if verbose {
         cmd.arg("--verbose");
@@ -269,7 +384,7 @@ pub async fn snapshot_save(
 
     cmd.arg(database_url);
 
-    if verbose && !quiet {
+    if verbose && !quiet && !native {
         let mut args_display = vec!["pg_dump".to_string(), format!("--format={}", format)];
         if no_owner {
             args_display.push("--no-owner".to_string());
@@ -288,40 +403,120 @@ pub async fn snapshot_save(
         return Ok(());
     }
 
-    // Create snapshot directory
-    fs::create_dir_all(&snap_dir)?;
+    let filtered_rows = if row_filters.is_empty() {
+        None
+    } else {
+        Some(copy_filtered_rows(&client, &row_filters, quiet).await?)
+    };
 
-    let output = cmd.output().await?;
+    // Removes a partially saved snapshot
+    let discard = || match storage {
+        Some(ref storage) => {
+            let _ = storage.delete(name);
+        }
+        None => {
+            let _ = fs::remove_dir_all(&snap_dir);
+        }
+    };
 
-    if !output.status.success() {
-        // Cleanup on failure
-        let _ = fs::remove_dir_all(&snap_dir);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("pg_dump failed:\n{}", stderr);
-    }
+    // Writes the (encrypted) dump to remote storage or the dump file
+    let upload = |out: &mut dyn Read| match storage {
+        Some(ref storage) => storage.upload(name, dump_filename, out),
+        None => Ok(io::copy(out, &mut fs::File::create(&dump_path)?)?),
+    };
 
-    if verbose && !quiet {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if !stdout.is_empty() {
-            println!("{}", stdout);
+    let mut size_bytes = if native {
+        if storage.is_none() {
+            fs::create_dir_all(&snap_dir)?;
+        }
+        let saved = save_native_dump(
+            &client,
+            name,
+            effective_profile.as_ref(),
+            &dump_path,
+            encryption.as_ref(),
+            storage.is_some(),
+            upload,
+        )
+        .await;
+        match saved {
+            Ok(size) => size,
+            Err(e) => {
+                discard();
+                return Err(e);
+            }
         }
-        if !stderr.is_empty() {
-            // pg_dump --verbose writes to stderr
-            eprintln!("{}", stderr);
+    } else if storage.is_some() || encryption.is_some() {
+        if storage.is_none() {
+            fs::create_dir_all(&snap_dir)?;
         }
-    }
+        let streamed = stream_dump(cmd, encryption.as_ref(), verbose && !quiet, upload);
+        match streamed {
+            Ok(size) => size,
+            Err(e) => {
+                discard();
+                return Err(e);
+            }
+        }
+    } else {
+        // Create snapshot directory
+        fs::create_dir_all(&snap_dir)?;
+
+        let output = Command::from(cmd).output().await?;
 
-    // Get dump file size
-    let size_bytes = match fs::metadata(&dump_path) {
-        Ok(m) => m.len(),
-        Err(e) => {
+        if !output.status.success() {
+            // Cleanup on failure
             let _ = fs::remove_dir_all(&snap_dir);
-            bail!("Failed to get dump file size: {}", e);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("pg_dump failed:\n{}", stderr);
+        }
+
+        if verbose && !quiet {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !stdout.is_empty() {
+                println!("{}", stdout);
+            }
+            if !stderr.is_empty() {
+                // pg_dump --verbose writes to stderr
+                eprintln!("{}", stderr);
+            }
+        }
+
+        // Get dump file size
+        match fs::metadata(&dump_path) {
+            Ok(m) => m.len(),
+            Err(e) => {
+                let _ = fs::remove_dir_all(&snap_dir);
+                bail!("Failed to get dump file size: {}", e);
+            }
         }
     };
+    if export_snapshot.is_some() {
+        client.batch_execute("COMMIT").await?;
+    }
 
-    let metadata = SnapshotMetadata::new(
+    if let Some(rows) = filtered_rows {
+        let rows = match encryption {
+            Some(ref encryption) => encryption.encrypt(&rows),
+            None => Ok(rows),
+        };
+        let written = rows.and_then(|rows| match storage {
+            Some(ref storage) => storage.upload(name, FILTERED_ROWS_FILE, &mut rows.as_slice()),
+            None => fs::write(snap_dir.join(FILTERED_ROWS_FILE), &rows)
+                .map(|_| rows.len() as u64)
+                .map_err(Into::into),
+        });
+        match written {
+            Ok(bytes) => size_bytes += bytes,
+            Err(e) => {
+                discard();
+                bail!("Failed to save filtered rows: {}", e);
+            }
+        }
+    }
+
+    let mut metadata = SnapshotMetadata::new(
         name,
         &parsed.database_name,
         latest_version,
@@ -346,14 +541,19 @@ pub async fn snapshot_save(
             .and_then(|p| p.exclude_tables.clone()),
         effective_profile.as_ref().map(|p| p.data).unwrap_or(true),
     );
+    metadata.row_filters = row_filters
+        .iter()
+        .map(|f| (f.table.clone(), f.condition.clone()))
+        .collect();
+    metadata.encryption = encryption.as_ref().map(|e| e.cipher);
 
-    if let Err(e) = metadata.save(&snap_dir) {
-        let _ = fs::remove_dir_all(&snap_dir);
-        bail!("Failed to save snapshot metadata: {}", e);
-    }
-
-    if let Err(e) = metadata.save(&snap_dir) {
-        let _ = fs::remove_dir_all(&snap_dir);
+    // Written last: remote snapshots with metadata are complete
+    let saved = match storage {
+        Some(ref storage) => storage.save_metadata(&metadata),
+        None => metadata.save(&snap_dir),
+    };
+    if let Err(e) = saved {
+        discard();
         bail!("Failed to save snapshot metadata: {}", e);
     }
 
@@ -367,7 +567,288 @@ pub async fn snapshot_save(
     Ok(())
 }
 
+/// Run pg_dump with its output streamed, through the encryptor if any, into
+/// `sink` (remote storage or the dump file); returns the bytes written
+fn stream_dump(
+    mut cmd: std::process::Command,
+    encryption: Option<&Encryption>,
+    verbose: bool,
+    sink: impl FnOnce(&mut dyn Read) -> Result<u64>,
+) -> Result<u64> {
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let mut stdout = child.stdout.take().expect("piped stdout");
+    let mut stderr = child.stderr.take().expect("piped stderr");
+    // Drain stderr alongside the stream so pg_dump --verbose can't block on it
+    let stderr_reader = std::thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text
+    });
+
+    let (written, encrypted) = match encryption {
+        Some(encryption) => {
+            let mut encryptor = match encryption.spawn(stdout.into()) {
+                Ok(encryptor) => encryptor,
+                Err(e) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(e);
+                }
+            };
+            let mut ciphertext = encryptor.stdout.take().expect("piped stdout");
+            let written = sink(&mut ciphertext);
+            drop(ciphertext);
+            (written, encryption.finish(encryptor))
+        }
+        None => {
+            let written = sink(&mut stdout);
+            drop(stdout);
+            (written, Ok(()))
+        }
+    };
+    let status = child.wait()?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    // A failure downstream makes pg_dump fail on a closed pipe; report the cause
+    let size_bytes = written?;
+    encrypted?;
+    if !status.success() {
+        bail!("pg_dump failed:\n{}", stderr);
+    }
+    if verbose && !stderr.is_empty() {
+        // pg_dump --verbose writes to stderr
+        eprintln!("{}", stderr);
+    }
+    Ok(size_bytes)
+}
+
+/// Write the built-in plain dump to `dump_path`, or through a temporary file
+/// into `upload` when it's encrypted or stored remotely; returns its size
+async fn save_native_dump(
+    client: &Client,
+    name: &str,
+    profile: Option<&crate::config::SnapshotProfile>,
+    dump_path: &Path,
+    encryption: Option<&Encryption>,
+    remote: bool,
+    upload: impl FnOnce(&mut dyn Read) -> Result<u64>,
+) -> Result<u64> {
+    let options = IntrospectOptions {
+        include_schemas: profile.and_then(|p| p.schemas.clone()).unwrap_or_default(),
+        exclude_schemas: profile
+            .and_then(|p| p.exclude_schemas.clone())
+            .unwrap_or_default(),
+    };
+    let data = profile.map(|p| p.data).unwrap_or(true);
+
+    if encryption.is_none() && !remote {
+        let mut writer = io::BufWriter::new(fs::File::create(dump_path)?);
+        write_native_dump(client, &options, data, &mut writer).await?;
+        writer.flush()?;
+        return Ok(fs::metadata(dump_path)?.len());
+    }
+
+    let temp_path =
+        std::env::temp_dir().join(format!("pgcrate-{}-{}.sql", name, std::process::id()));
+    let written = async {
+        let mut writer = io::BufWriter::new(fs::File::create(&temp_path)?);
+        write_native_dump(client, &options, data, &mut writer).await?;
+        writer.flush()?;
+        drop(writer);
+
+        let mut plain = fs::File::open(&temp_path)?;
+        match encryption {
+            Some(encryption) => {
+                let mut encryptor = encryption.spawn(plain.into())?;
+                let mut ciphertext = encryptor.stdout.take().expect("piped stdout");
+                let written = upload(&mut ciphertext);
+                drop(ciphertext);
+                let finished = encryption.finish(encryptor);
+                let written = written?;
+                finished?;
+                Ok(written)
+            }
+            None => upload(&mut plain),
+        }
+    }
+    .await;
+    let _ = fs::remove_file(&temp_path);
+    written
+}
+
+/// A profile row filter resolved against the database
+#[derive(Debug, Clone)]
+struct RowFilter {
+    /// `schema.table`
+    table: String,
+    /// Quoted `"schema"."table"`
+    quoted: String,
+    condition: String,
+    /// Columns to copy (generated columns are left out)
+    columns: Vec<String>,
+    oid: u32,
+}
+
+/// Resolve a profile's `where` filters to tables the profile dumps, in
+/// foreign key order (referenced tables first)
+async fn resolve_row_filters(
+    client: &Client,
+    profile: &crate::config::SnapshotProfile,
+) -> Result<Vec<RowFilter>> {
+    let Some(ref filters) = profile.row_filters else {
+        return Ok(Vec::new());
+    };
+    if !filters.is_empty() && !profile.data {
+        bail!("Snapshot profile has row filters ([where]) but data = false");
+    }
+
+    let mut resolved = Vec::new();
+    for (key, condition) in filters {
+        let row = client
+            .query_opt(
+                "SELECT n.nspname::text, c.relname::text, c.oid,
+                        array(SELECT a.attname::text FROM pg_attribute a
+                              WHERE a.attrelid = c.oid AND a.attnum > 0
+                                AND NOT a.attisdropped AND a.attgenerated = ''
+                              ORDER BY a.attnum)
+                 FROM pg_class c
+                 JOIN pg_namespace n ON n.oid = c.relnamespace
+                 WHERE c.oid = to_regclass($1) AND c.relkind = 'r'",
+                &[key],
+            )
+            .await?;
+        let Some(row) = row else {
+            bail!(
+                "Row filter table \"{}\" not found (filters apply to plain tables)",
+                key
+            );
+        };
+        let schema: String = row.get(0);
+        let name: String = row.get(1);
+        let table = format!("{}.{}", schema, name);
+
+        let listed = |names: &Option<Vec<String>>, value: &str| {
+            names
+                .as_ref()
+                .is_some_and(|names| names.iter().any(|n| n == key || n == value))
+        };
+        let included = (profile.tables.is_none() || listed(&profile.tables, &table))
+            && (profile.schemas.is_none() || listed(&profile.schemas, &schema))
+            && !listed(&profile.exclude_tables, &table)
+            && !listed(&profile.exclude_schemas, &schema);
+        if !included {
+            bail!(
+                "Row filter table \"{}\" is not part of the snapshot profile's tables",
+                key
+            );
+        }
+
+        resolved.push(RowFilter {
+            quoted: format!("{}.{}", quote_ident(&schema), quote_ident(&name)),
+            table,
+            condition: condition.clone(),
+            columns: row.get(3),
+            oid: row.get(2),
+        });
+    }
+
+    let oids: Vec<u32> = resolved.iter().map(|f| f.oid).collect();
+    let edges: Vec<(u32, u32)> = client
+        .query(
+            "SELECT conrelid, confrelid FROM pg_constraint
+             WHERE contype = 'f' AND conrelid = ANY($1) AND confrelid = ANY($1)
+               AND conrelid <> confrelid",
+            &[&oids],
+        )
+        .await?
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect();
+    Ok(order_row_filters(resolved, &edges))
+}
+
+/// Order filters so referenced tables load before the tables referencing
+/// them; `edges` are (referencing, referenced) oids. Cycles keep config order.
+fn order_row_filters(mut pending: Vec<RowFilter>, edges: &[(u32, u32)]) -> Vec<RowFilter> {
+    let mut ordered: Vec<RowFilter> = Vec::new();
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|f| {
+                !edges
+                    .iter()
+                    .any(|(from, to)| *from == f.oid && pending.iter().any(|p| p.oid == *to))
+            })
+            .unwrap_or(0);
+        ordered.push(pending.remove(ready));
+    }
+    ordered
+}
+
+/// psql script that loads one table's filtered rows (COPY text format)
+fn filtered_copy_block(filter: &RowFilter, rows: &[u8]) -> Vec<u8> {
+    let columns: Vec<String> = filter.columns.iter().map(|c| quote_ident(c)).collect();
+    let mut block = format!(
+        "-- {} WHERE {}\n\
+         ALTER TABLE {} DISABLE TRIGGER USER;\n\
+         COPY {} ({}) FROM stdin;\n",
+        filter.table,
+        filter.condition.replace('\n', " "),
+        filter.quoted,
+        filter.quoted,
+        columns.join(", ")
+    )
+    .into_bytes();
+    block.extend_from_slice(rows);
+    block.extend_from_slice(
+        format!(
+            "\\.\nALTER TABLE {} ENABLE TRIGGER USER;\n\n",
+            filter.quoted
+        )
+        .as_bytes(),
+    );
+    block
+}
+
+/// Copy the rows each filter keeps into one psql script
+async fn copy_filtered_rows(
+    client: &Client,
+    filters: &[RowFilter],
+    quiet: bool,
+) -> Result<Vec<u8>> {
+    let mut script = b"SET client_encoding = 'UTF8';\n\n".to_vec();
+    for filter in filters {
+        let columns: Vec<String> = filter.columns.iter().map(|c| quote_ident(c)).collect();
+        let sql = format!(
+            "COPY (SELECT {} FROM {} WHERE {}) TO STDOUT",
+            columns.join(", "),
+            filter.quoted,
+            filter.condition
+        );
+        let stream = client
+            .copy_out(sql.as_str())
+            .await
+            .map_err(|e| anyhow::anyhow!("Row filter on {} failed: {}", filter.table, e))?;
+        pin_mut!(stream);
+        let mut rows = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            rows.extend_from_slice(&chunk?);
+        }
+        if !quiet {
+            let count = rows.iter().filter(|b| **b == b'\n').count();
+            println!("  Filtered: {} ({} rows kept)", filter.table, count);
+        }
+        script.extend(filtered_copy_block(filter, &rows));
+    }
+    Ok(script)
+}
+
 /// Get PostgreSQL server version
+/// `16.4` from `16.4 (Debian 16.4-1.pgdg120+2)`
+fn version_number(version: &str) -> &str {
+    version.split_whitespace().next().unwrap_or(version)
+}
+
 async fn get_pg_version(client: &Client) -> Result<String> {
     let row = client.query_one("SHOW server_version", &[]).await?;
     Ok(row.get(0))
@@ -393,10 +874,57 @@ async fn get_owner_roles(client: &Client) -> Result<Vec<String>> {
     Ok(rows.iter().map(|r| r.get(0)).collect())
 }
 
+/// Whether a snapshot (complete or not) already uses `name`
+pub(crate) fn snapshot_name_taken(name: &str, config: &Config) -> Result<bool> {
+    match snapshot_storage(config)? {
+        Some(storage) => Ok(storage.names()?.iter().any(|n| n == name)),
+        None => Ok(snapshot_dir(name, Some(config.snapshot_dir())).exists()),
+    }
+}
+
+/// Error for a missing snapshot, listing the ones that exist
+fn snapshot_not_found(name: &str, available: &[String]) -> anyhow::Error {
+    let hint = if available.is_empty() {
+        "No snapshots available.".to_string()
+    } else {
+        format!("Available snapshots: {}", available.join(", "))
+    };
+    anyhow::anyhow!("Snapshot \"{}\" not found.\n{}", name, hint)
+}
+
+/// Metadata of a complete snapshot, from remote storage when configured
+pub(crate) fn load_snapshot(
+    name: &str,
+    snap_dir_override: Option<&str>,
+    storage: Option<&SnapshotStorage>,
+) -> Result<SnapshotMetadata> {
+    if let Some(storage) = storage {
+        if !storage.names()?.iter().any(|n| n == name) {
+            let available: Vec<String> = storage.list()?.into_iter().map(|m| m.name).collect();
+            return Err(snapshot_not_found(name, &available));
+        }
+        return storage.load_metadata(name).map_err(|e| {
+            anyhow::anyhow!(
+                "Snapshot \"{}\" is incomplete: {}\n\
+                 Delete it with: pgcrate snapshot delete {}",
+                name,
+                e,
+                name
+            )
+        });
+    }
+
+    if !snapshot_exists(name, snap_dir_override) {
+        let available = snapshot::available_snapshots(snap_dir_override);
+        return Err(snapshot_not_found(name, &available));
+    }
+    SnapshotMetadata::load(&snapshot_dir(name, snap_dir_override))
+}
+
 /// Restore database from a snapshot
 #[allow(clippy::too_many_arguments)]
 pub async fn snapshot_restore(
-    target_database_url: &str,
+    database_url: &str,
     name: &str,
     config: &Config,
     quiet: bool,
@@ -404,40 +932,68 @@ pub async fn snapshot_restore(
     yes: bool,
     dry_run: bool,
     no_owner: bool,
+    as_new_db: Option<&str>,
+    ttl: Option<std::time::Duration>,
 ) -> Result<()> {
     // Validate snapshot name (prevents path traversal)
     validate_snapshot_name(name)?;
 
     // Get snapshot directory override
     let snap_dir_override = Some(config.snapshot_dir());
+    let storage = snapshot_storage(config)?;
 
-    // Check if snapshot exists
-    if !snapshot_exists(name, snap_dir_override) {
-        let available = snapshot::available_snapshots(snap_dir_override);
-        let hint = if available.is_empty() {
-            "No snapshots available.".to_string()
-        } else {
-            format!("Available snapshots: {}", available.join(", "))
-        };
-        bail!("Snapshot \"{}\" not found.\n{}", name, hint);
-    }
-
-    // Load metadata
     let snap_dir = snapshot_dir(name, snap_dir_override);
-    let metadata = SnapshotMetadata::load(&snap_dir)?;
+    let metadata = load_snapshot(name, snap_dir_override, storage.as_ref())?;
 
     // Check appropriate restore tool exists based on format
     let pg_restore_path = config.tool_path("pg_restore");
     let psql_path = config.tool_path("psql");
     match metadata.format {
-        SnapshotFormat::Custom => check_pg_restore(&pg_restore_path)?,
+        SnapshotFormat::Custom => {
+            check_pg_restore(&pg_restore_path)?;
+            // pg_restore can't read archives from a newer pg_dump
+            if let (Some(archive), Ok(tool)) = (
+                &metadata.pg_dump_version,
+                get_tool_version(&pg_restore_path),
+            ) {
+                check_tool_version(
+                    "pg_restore",
+                    &pg_restore_path,
+                    &tool,
+                    "archive from pg_dump",
+                    version_number(archive),
+                )?;
+            }
+        }
         SnapshotFormat::Plain => check_psql(&psql_path)?,
     }
+    if !metadata.row_filters.is_empty() {
+        check_psql(&psql_path)?;
+    }
+    let decryption = match metadata.encryption {
+        Some(cipher) => Some(Decryption::new(cipher, config)?),
+        None => None,
+    };
 
+    // --as-new-db restores into a new database on the same server
+    let new_db_url = match as_new_db {
+        Some(db) => {
+            if db.is_empty() || db.len() > 63 {
+                bail!("Invalid database name \"{}\" (1-63 characters)", db);
+            }
+            Some(parse_database_url(database_url)?.with_database(db))
+        }
+        None => None,
+    };
+    let target_database_url = new_db_url.as_deref().unwrap_or(database_url);
     let parsed = parse_database_url(target_database_url)?;
+    let expires_at = match ttl {
+        Some(ttl) => Some(Utc::now() + chrono::Duration::from_std(ttl)?),
+        None => None,
+    };
 
     // Connect to target to check version and roles (for dry-run and pre-flight)
-    let target_client = connect(target_database_url).await.ok();
+    let target_client = connect(database_url).await.ok();
     let target_pg_version = if let Some(ref client) = target_client {
         get_pg_version(client).await.ok()
     } else {
@@ -496,6 +1052,9 @@ pub async fn snapshot_restore(
             metadata.created_at.format("%Y-%m-%d %H:%M:%S")
         );
         println!("  Format:      {}", metadata.format);
+        if let Some(cipher) = metadata.encryption {
+            println!("  Encrypted:   {}", cipher);
+        }
         println!("  Size:        {}", metadata.format_size());
         println!("  Migrations:  {} applied", metadata.applied_migrations);
         if let Some(ref msg) = metadata.message {
@@ -540,6 +1099,16 @@ pub async fn snapshot_restore(
             println!();
         }
 
+        if as_new_db.is_some() {
+            println!("This will create database: {}", parsed.database_name);
+            if let Some(expires_at) = expires_at {
+                println!(
+                    "  Expires:     {}",
+                    expires_at.format("%Y-%m-%d %H:%M:%S UTC")
+                );
+            }
+            return Ok(());
+        }
         println!(
             "{}",
             format!(
@@ -568,8 +1137,8 @@ pub async fn snapshot_restore(
         );
     }
 
-    // Require --yes flag
-    if !yes {
+    // Require --yes flag (a new database clobbers nothing)
+    if !yes && as_new_db.is_none() {
         bail!(
             "Restoring a snapshot requires --yes flag to confirm.\n\
              This will DROP and recreate database: {}",
@@ -578,7 +1147,7 @@ pub async fn snapshot_restore(
     }
 
     // Warn about production patterns
-    if url_matches_production_patterns(target_database_url, config) {
+    if as_new_db.is_none() && url_matches_production_patterns(target_database_url, config) {
         eprintln!(
             "{}",
             "⚠️  WARNING: URL matches production patterns. This is destructive!".yellow()
@@ -604,25 +1173,42 @@ pub async fn snapshot_restore(
     // Connect to admin database (postgres)
     let admin_client = connect(&parsed.admin_url).await?;
 
-    // Terminate active connections to target database
-    if !quiet {
-        println!("  Terminating connections to {}...", parsed.database_name);
-    }
+    if as_new_db.is_some() {
+        let exists = admin_client
+            .query_opt(
+                "SELECT 1 FROM pg_database WHERE datname = $1",
+                &[&parsed.database_name],
+            )
+            .await?
+            .is_some();
+        if exists {
+            bail!(
+                "Database \"{}\" already exists.\n\
+                 Hint: Choose another name with --as-new-db, or drop it first.",
+                parsed.database_name
+            );
+        }
+    } else {
+        // Terminate active connections to target database
+        if !quiet {
+            println!("  Terminating connections to {}...", parsed.database_name);
+        }
 
-    let terminated = terminate_connections(&admin_client, &parsed.database_name).await?;
-    if verbose && !quiet && terminated > 0 {
-        println!("    Terminated {} connection(s)", terminated);
-    }
+        let terminated = terminate_connections(&admin_client, &parsed.database_name).await?;
+        if verbose && !quiet && terminated > 0 {
+            println!("    Terminated {} connection(s)", terminated);
+        }
 
-    // Drop existing database
-    if !quiet {
-        println!("  Dropping database {}...", parsed.database_name);
+        // Drop existing database
+        if !quiet {
+            println!("  Dropping database {}...", parsed.database_name);
+        }
+        let drop_sql = format!(
+            "DROP DATABASE IF EXISTS {}",
+            quote_ident(&parsed.database_name)
+        );
+        admin_client.batch_execute(&drop_sql).await?;
     }
-    let drop_sql = format!(
-        "DROP DATABASE IF EXISTS {}",
-        quote_ident(&parsed.database_name)
-    );
-    admin_client.batch_execute(&drop_sql).await?;
 
     // Create fresh database
     if !quiet {
@@ -631,53 +1217,136 @@ pub async fn snapshot_restore(
     let create_sql = format!("CREATE DATABASE {}", quote_ident(&parsed.database_name));
     admin_client.batch_execute(&create_sql).await?;
 
-    // Restore based on format
-    if !quiet {
-        println!("  Restoring data...");
-    }
+    let restored = async {
+        // Restore based on format
+        if !quiet {
+            println!("  Restoring data...");
+        }
 
-    let dump_path = snap_dir.join(metadata.format.dump_filename());
-    if !dump_path.exists() {
-        // Check for legacy dump.pgdump if format says custom but metadata might be old
-        let legacy_path = snap_dir.join("dump.pgdump");
-        if legacy_path.exists() {
-            // Use legacy path
-            restore_custom_format(
-                &legacy_path,
-                target_database_url,
-                verbose,
-                quiet,
-                no_owner,
-                &pg_restore_path,
-            )
-            .await?;
-        } else {
-            bail!(
-                "Snapshot \"{}\" is incomplete: {} is missing.\n\
-                 Delete it with: pgcrate snapshot delete {}",
+        let dump_file = metadata.format.dump_filename();
+        let dump_path = snap_dir.join(dump_file);
+        if storage.is_some() || (decryption.is_some() && dump_path.exists()) {
+            // Stream the dump from storage and/or the decryptor into pg_restore/psql
+            let (input, reader) = SnapshotFile::open(
                 name,
-                metadata.format.dump_filename(),
-                name
-            );
-        }
-    } else {
-        match metadata.format {
-            SnapshotFormat::Custom => {
+                dump_file,
+                &dump_path,
+                storage.as_ref(),
+                decryption.as_ref(),
+            )?;
+            let restored = match metadata.format {
+                SnapshotFormat::Custom => {
+                    restore_custom_format(
+                        input,
+                        target_database_url,
+                        verbose,
+                        quiet,
+                        no_owner,
+                        false,
+                        &pg_restore_path,
+                    )
+                    .await
+                }
+                SnapshotFormat::Plain => {
+                    restore_plain_format(input, target_database_url, verbose, quiet, &psql_path)
+                        .await
+                }
+            };
+            reader.finish()?;
+            restored?;
+        } else if !dump_path.exists() {
+            // Check for legacy dump.pgdump if format says custom but metadata might be old
+            let legacy_path = snap_dir.join("dump.pgdump");
+            if legacy_path.exists() {
+                // Use legacy path
                 restore_custom_format(
-                    &dump_path,
+                    DumpInput::File(&legacy_path),
                     target_database_url,
                     verbose,
                     quiet,
                     no_owner,
+                    false,
                     &pg_restore_path,
                 )
                 .await?;
+            } else {
+                bail!(
+                    "Snapshot \"{}\" is incomplete: {} is missing.\n\
+                     Delete it with: pgcrate snapshot delete {}",
+                    name,
+                    metadata.format.dump_filename(),
+                    name
+                );
             }
-            SnapshotFormat::Plain => {
-                restore_plain_format(&dump_path, target_database_url, verbose, quiet, &psql_path)
+        } else {
+            match metadata.format {
+                SnapshotFormat::Custom => {
+                    restore_custom_format(
+                        DumpInput::File(&dump_path),
+                        target_database_url,
+                        verbose,
+                        quiet,
+                        no_owner,
+                        false,
+                        &pg_restore_path,
+                    )
+                    .await?;
+                }
+                SnapshotFormat::Plain => {
+                    restore_plain_format(
+                        DumpInput::File(&dump_path),
+                        target_database_url,
+                        verbose,
+                        quiet,
+                        &psql_path,
+                    )
                     .await?;
+                }
             }
         }
+
+        // Rows kept by profile row filters load once the rest of the dump is in
+        if !metadata.row_filters.is_empty() {
+            if !quiet {
+                println!("  Loading filtered rows...");
+            }
+            let path = snap_dir.join(FILTERED_ROWS_FILE);
+            let (input, reader) = SnapshotFile::open(
+                name,
+                FILTERED_ROWS_FILE,
+                &path,
+                storage.as_ref(),
+                decryption.as_ref(),
+            )?;
+            let loaded = load_filtered_rows(input, target_database_url, &psql_path).await;
+            reader.finish()?;
+            loaded?;
+        }
+        Ok::<(), anyhow::Error>(())
+    }
+    .await;
+
+    if as_new_db.is_some() {
+        // A half-restored throwaway database is of no use; don't leave it behind
+        if restored.is_err() {
+            let _ = admin_client
+                .batch_execute(&format!(
+                    "DROP DATABASE IF EXISTS {} WITH (FORCE)",
+                    quote_ident(&parsed.database_name)
+                ))
+                .await;
+        }
+        restored?;
+        // Marks the database for snapshot prune
+        admin_client
+            .batch_execute(&format!(
+                "COMMENT ON DATABASE {} IS '{}'",
+                quote_ident(&parsed.database_name),
+                throwaway_comment(name, expires_at).replace('\'', "''")
+            ))
+            .await?;
+    } else {
+        restored?;
     }
 
     // Report success
@@ -692,18 +1361,190 @@ pub async fn snapshot_restore(
         } else {
             println!("Migration state: {} applied", metadata.applied_migrations);
         }
+        if as_new_db.is_some() {
+            println!("Connection URL: {}", target_database_url);
+            if let Some(expires_at) = expires_at {
+                println!(
+                    "Expires: {} (drop with: pgcrate snapshot prune)",
+                    expires_at.format("%Y-%m-%d %H:%M:%S UTC")
+                );
+            }
+        }
+    } else if as_new_db.is_some() {
+        // Quiet: just the URL, for scripts
+        println!("{}", target_database_url);
+    }
+
+    Ok(())
+}
+
+/// Prefix of the database comment marking a `restore --as-new-db` database
+const THROWAWAY_COMMENT: &str = "pgcrate:snapshot=";
+
+/// Comment recording which snapshot a throwaway database holds and when it
+/// expires
+fn throwaway_comment(name: &str, expires_at: Option<DateTime<Utc>>) -> String {
+    match expires_at {
+        Some(at) => format!(
+            "{}{} expires={}",
+            THROWAWAY_COMMENT,
+            name,
+            at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        ),
+        None => format!("{}{}", THROWAWAY_COMMENT, name),
+    }
+}
+
+/// Snapshot name and expiry from a throwaway database's comment
+fn parse_throwaway_comment(comment: &str) -> Option<(String, Option<DateTime<Utc>>)> {
+    let rest = comment.strip_prefix(THROWAWAY_COMMENT)?;
+    match rest.split_once(" expires=") {
+        Some((name, at)) => {
+            let at = DateTime::parse_from_rfc3339(at).ok()?.with_timezone(&Utc);
+            Some((name.to_string(), Some(at)))
+        }
+        None => Some((rest.to_string(), None)),
     }
+}
+
+/// Drop databases created by `snapshot restore --as-new-db` whose --ttl has
+/// passed
+pub async fn snapshot_prune(database_url: &str, quiet: bool, dry_run: bool) -> Result<()> {
+    let parsed = parse_database_url(database_url)?;
+    let admin_client = connect(&parsed.admin_url).await?;
+    let rows = admin_client
+        .query(
+            "SELECT datname::text, shobj_description(oid, 'pg_database')
+             FROM pg_database
+             WHERE shobj_description(oid, 'pg_database') LIKE 'pgcrate:snapshot=%'
+             ORDER BY datname",
+            &[],
+        )
+        .await?;
 
+    let now = Utc::now();
+    let mut kept = 0;
+    let mut expired = Vec::new();
+    for row in &rows {
+        let db: String = row.get(0);
+        let comment: String = row.get(1);
+        match parse_throwaway_comment(&comment) {
+            Some((snapshot, Some(at))) if at <= now => expired.push((db, snapshot, at)),
+            _ => kept += 1,
+        }
+    }
+
+    for (db, snapshot, at) in &expired {
+        let detail = format!(
+            "{} (snapshot {}, expired {})",
+            db,
+            snapshot,
+            at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        if dry_run {
+            println!("Would drop {}", detail);
+            continue;
+        }
+        admin_client
+            .batch_execute(&format!(
+                "DROP DATABASE IF EXISTS {} WITH (FORCE)",
+                quote_ident(db)
+            ))
+            .await?;
+        if !quiet {
+            println!("Dropped {}", detail);
+        }
+    }
+    if !quiet {
+        if expired.is_empty() {
+            println!("No expired snapshot databases.");
+        }
+        if kept > 0 {
+            println!("{} snapshot database(s) not expired.", kept);
+        }
+    }
     Ok(())
 }
 
+/// Where pg_restore/psql read the dump from
+enum DumpInput<'a> {
+    File(&'a Path),
+    /// Streamed from remote storage or the decryptor
+    Stdin(Stdio),
+}
+
+/// A snapshot file being read from disk or remote storage, decrypted on the
+/// way when the snapshot is encrypted
+struct SnapshotFile<'a> {
+    name: &'a str,
+    file: &'a str,
+    storage: Option<&'a SnapshotStorage>,
+    download: Option<std::process::Child>,
+    decryption: Option<&'a Decryption>,
+    decrypt: Option<std::process::Child>,
+}
+
+impl<'a> SnapshotFile<'a> {
+    /// Start reading `file`; `path` is its location in the local snapshot
+    fn open(
+        name: &'a str,
+        file: &'a str,
+        path: &'a Path,
+        storage: Option<&'a SnapshotStorage>,
+        decryption: Option<&'a Decryption>,
+    ) -> Result<(DumpInput<'a>, Self)> {
+        let mut reader = SnapshotFile {
+            name,
+            file,
+            storage,
+            download: None,
+            decryption,
+            decrypt: None,
+        };
+        let source = match storage {
+            Some(storage) => {
+                let download = reader.download.insert(storage.download(name, file)?);
+                Some(Stdio::from(download.stdout.take().expect("piped stdout")))
+            }
+            None => None,
+        };
+        let input = match decryption {
+            Some(decryption) => {
+                let source = match source {
+                    Some(source) => source,
+                    None => Stdio::from(fs::File::open(path)?),
+                };
+                let decrypt = reader.decrypt.insert(decryption.spawn(source)?);
+                DumpInput::Stdin(decrypt.stdout.take().expect("piped stdout").into())
+            }
+            None => match source {
+                Some(source) => DumpInput::Stdin(source),
+                None => DumpInput::File(path),
+            },
+        };
+        Ok((input, reader))
+    }
+
+    /// Wait for the download and decryption, reporting their failures
+    fn finish(self) -> Result<()> {
+        if let (Some(storage), Some(download)) = (self.storage, self.download) {
+            storage.finish_download(download, self.name, self.file)?;
+        }
+        if let (Some(decryption), Some(decrypt)) = (self.decryption, self.decrypt) {
+            decryption.finish(decrypt)?;
+        }
+        Ok(())
+    }
+}
+
 /// Restore from custom format using pg_restore
 async fn restore_custom_format(
-    dump_path: &Path,
+    input: DumpInput<'_>,
     database_url: &str,
     verbose: bool,
     quiet: bool,
     no_owner: bool,
+    schema_only: bool,
     pg_restore_path: &str,
 ) -> Result<()> {
     let mut cmd = Command::new(pg_restore_path);
@@ -713,11 +1554,22 @@ async fn restore_custom_format(
         cmd.arg("--no-owner");
     }
 
+    if schema_only {
+        cmd.arg("--schema-only").arg("--no-privileges");
+    }
+
     if verbose {
         cmd.arg("--verbose");
     }
 
-    cmd.arg(dump_path);
+    match input {
+        DumpInput::File(path) => {
+            cmd.arg(path);
+        }
+        DumpInput::Stdin(stdin) => {
+            cmd.stdin(stdin);
+        }
+    }
 
     let output = cmd.output().await?;
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -761,14 +1613,22 @@ async fn restore_custom_format(
 
 /// Restore from plain format using psql
 async fn restore_plain_format(
-    dump_path: &Path,
+    input: DumpInput<'_>,
     database_url: &str,
     verbose: bool,
     quiet: bool,
     psql_path: &str,
 ) -> Result<()> {
     let mut cmd = Command::new(psql_path);
-    cmd.arg(database_url).arg("-f").arg(dump_path);
+    cmd.arg(database_url).arg("-f");
+    match input {
+        DumpInput::File(path) => {
+            cmd.arg(path);
+        }
+        DumpInput::Stdin(stdin) => {
+            cmd.arg("-").stdin(stdin);
+        }
+    }
 
     if !verbose {
         cmd.arg("-q"); // Quiet mode unless verbose
@@ -792,6 +1652,40 @@ async fn restore_plain_format(
     Ok(())
 }
 
+/// Load a snapshot's filtered rows with psql, stopping at the first error
+async fn load_filtered_rows(
+    input: DumpInput<'_>,
+    database_url: &str,
+    psql_path: &str,
+) -> Result<()> {
+    let mut cmd = Command::new(psql_path);
+    cmd.arg(database_url).args([
+        "-X",
+        "-q",
+        "-v",
+        "ON_ERROR_STOP=1",
+        "--single-transaction",
+        "-f",
+    ]);
+    match input {
+        DumpInput::File(path) => {
+            cmd.arg(path);
+        }
+        DumpInput::Stdin(stdin) => {
+            cmd.arg("-").stdin(stdin);
+        }
+    }
+
+    let output = cmd.output().await?;
+    if !output.status.success() {
+        bail!(
+            "Loading filtered rows failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
 /// Check which roles from the list are missing on the target database
 async fn check_missing_roles(client: &Client, roles: &[String]) -> Result<Vec<String>> {
     if roles.is_empty() {
@@ -816,9 +1710,101 @@ async fn check_missing_roles(client: &Client, roles: &[String]) -> Result<Vec<St
 }
 
 /// List all snapshots
+/// Check that pg_dump and pg_restore exist and are at least the server's
+/// major version, before a save or restore finds out the hard way
+pub async fn snapshot_preflight(
+    database_url: &str,
+    config: &Config,
+    quiet: bool,
+    json: bool,
+) -> Result<()> {
+    #[derive(serde::Serialize)]
+    struct ToolCheck {
+        tool: &'static str,
+        path: String,
+        version: Option<String>,
+        compatible: bool,
+    }
+
+    let client = connect(database_url).await?;
+    let server = get_pg_version(&client).await?;
+    let server_version = version_number(&server).to_string();
+
+    let mut checks = Vec::new();
+    let mut mismatch = None;
+    for tool in ["pg_dump", "pg_restore"] {
+        let path = config.tool_path(tool);
+        let version = get_tool_version(&path).ok();
+        let compatible = match &version {
+            Some(v) => match check_tool_version(tool, &path, v, "server", &server_version) {
+                Ok(()) => true,
+                Err(e) => {
+                    mismatch.get_or_insert(e);
+                    false
+                }
+            },
+            None => false,
+        };
+        checks.push(ToolCheck {
+            tool,
+            path,
+            version,
+            compatible,
+        });
+    }
+
+    if !json && !quiet {
+        println!("Server:      PostgreSQL {}", server_version);
+        for check in &checks {
+            let status = if check.compatible {
+                "ok".green()
+            } else {
+                "incompatible".red()
+            };
+            println!(
+                "{:<12} {:<8} {} ({})",
+                format!("{}:", check.tool),
+                check.version.as_deref().unwrap_or("missing"),
+                status,
+                check.path
+            );
+        }
+    }
+
+    if let Some(e) = mismatch {
+        return Err(e.into());
+    }
+    for check in checks.iter().filter(|c| c.version.is_none()) {
+        match check.tool {
+            "pg_dump" => check_pg_dump(&check.path)?,
+            _ => check_pg_restore(&check.path)?,
+        }
+    }
+
+    if json {
+        #[derive(serde::Serialize)]
+        struct PreflightResponse {
+            ok: bool,
+            server_version: String,
+            tools: Vec<ToolCheck>,
+        }
+        let response = PreflightResponse {
+            ok: true,
+            server_version,
+            tools: checks,
+        };
+        println!("{}", serde_json::to_string_pretty(&response)?);
+    }
+    Ok(())
+}
+
 pub fn snapshot_list(config: &Config, quiet: bool, json: bool) -> Result<()> {
     let snap_dir_override = Some(config.snapshot_dir());
-    let snapshots = snapshot::list_snapshots(snap_dir_override)?;
+    let storage = snapshot_storage(config)?;
+    let snapshots = match storage {
+        Some(ref storage) => storage.list()?,
+        None => snapshot::list_snapshots(snap_dir_override)?,
+    };
 
     // JSON output
     if json {
@@ -865,9 +1851,12 @@ pub fn snapshot_list(config: &Config, quiet: bool, json: bool) -> Result<()> {
         return Ok(());
     }
 
-    let snapshots_path = snapshots_dir(snap_dir_override);
+    let location = match storage {
+        Some(ref storage) => storage.url().to_string(),
+        None => snapshots_dir(snap_dir_override).display().to_string(),
+    };
 
-    println!("Snapshots ({}/)", snapshots_path.display());
+    println!("Snapshots ({}/)", location);
     println!();
 
     // Calculate column widths
@@ -930,21 +1919,9 @@ pub fn snapshot_info(name: &str, config: &Config, quiet: bool, json: bool) -> Re
 
     // Get snapshot directory override
     let snap_dir_override = Some(config.snapshot_dir());
+    let storage = snapshot_storage(config)?;
 
-    // Check if snapshot exists
-    if !snapshot_exists(name, snap_dir_override) {
-        let available = snapshot::available_snapshots(snap_dir_override);
-        let hint = if available.is_empty() {
-            "No snapshots available.".to_string()
-        } else {
-            format!("Available snapshots: {}", available.join(", "))
-        };
-        bail!("Snapshot \"{}\" not found.\n{}", name, hint);
-    }
-
-    // Load metadata
-    let snap_dir = snapshot_dir(name, snap_dir_override);
-    let metadata = SnapshotMetadata::load(&snap_dir)?;
+    let metadata = load_snapshot(name, snap_dir_override, storage.as_ref())?;
 
     if json {
         // JSON output: print the full metadata
@@ -968,6 +1945,9 @@ pub fn snapshot_info(name: &str, config: &Config, quiet: bool, json: bool) -> Re
             println!("Source:      {}", host);
         }
         println!("Format:      {}", metadata.format);
+        if let Some(cipher) = metadata.encryption {
+            println!("Encrypted:   {}", cipher);
+        }
         println!("Size:        {}", metadata.format_size());
         if let Some(ref msg) = metadata.message {
             println!("Message:     {}", msg);
@@ -1009,6 +1989,15 @@ pub fn snapshot_info(name: &str, config: &Config, quiet: bool, json: bool) -> Re
         );
         println!();
 
+        // Row filters (if any)
+        if !metadata.row_filters.is_empty() {
+            println!("Row Filters:");
+            for (table, condition) in &metadata.row_filters {
+                println!("  {} WHERE {}", table, condition);
+            }
+            println!();
+        }
+
         // Owner roles (if any)
         if !metadata.owner_roles.is_empty() {
             println!("Owner Roles:");
@@ -1027,6 +2016,145 @@ pub fn snapshot_info(name: &str, config: &Config, quiet: bool, json: bool) -> Re
     Ok(())
 }
 
+/// Compare a snapshot's schema with the current database, or with another
+/// snapshot. Each snapshot is restored (schema only for custom dumps) into a
+/// temporary database that is dropped afterwards.
+/// Returns exit code: 0 = identical, 1 = differs
+pub async fn snapshot_diff(
+    database_url: &str,
+    name: &str,
+    other: Option<&str>,
+    config: &Config,
+    output: &Output,
+    include_schemas: &[String],
+    exclude_schemas: &[String],
+) -> Result<i32> {
+    validate_snapshot_name(name)?;
+    if let Some(other) = other {
+        validate_snapshot_name(other)?;
+    }
+    let snap_dir_override = Some(config.snapshot_dir());
+    let storage = snapshot_storage(config)?;
+
+    let mut snapshots = vec![(
+        name,
+        load_snapshot(name, snap_dir_override, storage.as_ref())?,
+    )];
+    if let Some(other) = other {
+        snapshots.push((
+            other,
+            load_snapshot(other, snap_dir_override, storage.as_ref())?,
+        ));
+    }
+    for (_, metadata) in &snapshots {
+        match metadata.format {
+            SnapshotFormat::Custom => check_pg_restore(&config.tool_path("pg_restore"))?,
+            SnapshotFormat::Plain => check_psql(&config.tool_path("psql"))?,
+        }
+    }
+
+    // Without --schema, compare only what a profile snapshot dumped
+    let options = IntrospectOptions {
+        include_schemas: if include_schemas.is_empty() && exclude_schemas.is_empty() {
+            snapshots[0].1.included_schemas.clone().unwrap_or_default()
+        } else {
+            include_schemas.to_vec()
+        },
+        exclude_schemas: exclude_schemas.to_vec(),
+    };
+
+    let parsed = parse_database_url(database_url)?;
+    let admin_client = connect(&parsed.admin_url).await?;
+    let mut temp_dbs = Vec::new();
+    let mut schemas = Vec::new();
+    let compared = async {
+        for (i, (snap, metadata)) in snapshots.iter().enumerate() {
+            let temp_db = format!("pgcrate_snapshot_diff_{}_{}", std::process::id(), i);
+            output.verbose(&format!("Restoring snapshot {} into {}...", snap, temp_db));
+            admin_client
+                .batch_execute(&format!("CREATE DATABASE {}", quote_ident(&temp_db)))
+                .await?;
+            temp_dbs.push(temp_db.clone());
+
+            let temp_url = parsed.with_database(&temp_db);
+            restore_schema(snap, metadata, &temp_url, config, storage.as_ref()).await?;
+            let client = connect(&temp_url).await?;
+            schemas.push(introspect::introspect(&client, &options).await?);
+        }
+        if other.is_none() {
+            let client = connect(database_url).await?;
+            schemas.push(introspect::introspect(&client, &options).await?);
+        }
+        Ok::<(), anyhow::Error>(())
+    }
+    .await;
+
+    for temp_db in &temp_dbs {
+        let drop_sql = format!(
+            "DROP DATABASE IF EXISTS {} WITH (FORCE)",
+            quote_ident(temp_db)
+        );
+        if let Err(e) = admin_client.batch_execute(&drop_sql).await {
+            eprintln!(
+                "{}",
+                format!("Warning: failed to drop {}: {}", temp_db, e).yellow()
+            );
+        }
+    }
+    compared?;
+
+    let to_label = match other {
+        Some(other) => format!("snapshot {}", other),
+        None => parsed.database_name.clone(),
+    };
+    report_diff(
+        &schemas[0],
+        &schemas[1],
+        &format!("snapshot {}", name),
+        &to_label,
+        output,
+    )
+}
+
+/// Restore a snapshot's schema into an empty database. Plain dumps are
+/// loaded whole, data included.
+async fn restore_schema(
+    name: &str,
+    metadata: &SnapshotMetadata,
+    database_url: &str,
+    config: &Config,
+    storage: Option<&SnapshotStorage>,
+) -> Result<()> {
+    let dump_file = metadata.format.dump_filename();
+    let dump_path = snapshot_dir(name, Some(config.snapshot_dir())).join(dump_file);
+    let decryption = match metadata.encryption {
+        Some(cipher) => Some(Decryption::new(cipher, config)?),
+        None => None,
+    };
+    let (input, reader) =
+        SnapshotFile::open(name, dump_file, &dump_path, storage, decryption.as_ref())?;
+
+    let restored = match metadata.format {
+        SnapshotFormat::Custom => {
+            restore_custom_format(
+                input,
+                database_url,
+                false,
+                true,
+                true,
+                true,
+                &config.tool_path("pg_restore"),
+            )
+            .await
+        }
+        SnapshotFormat::Plain => {
+            restore_plain_format(input, database_url, false, true, &config.tool_path("psql")).await
+        }
+    };
+    reader.finish()?;
+    restored
+}
+
 /// Delete a snapshot
 pub fn snapshot_delete(name: &str, config: &Config, quiet: bool, yes: bool) -> Result<()> {
     // Validate snapshot name (prevents path traversal)
@@ -1034,21 +2162,24 @@ pub fn snapshot_delete(name: &str, config: &Config, quiet: bool, yes: bool) -> R
 
     // Get snapshot directory override
     let snap_dir_override = Some(config.snapshot_dir());
+    let storage = snapshot_storage(config)?;
 
     // Check if snapshot directory exists (allow deleting incomplete snapshots)
     let snap_dir = snapshot_dir(name, snap_dir_override);
-    if !snap_dir.exists() {
-        let available = snapshot::available_snapshots(snap_dir_override);
-        let hint = if available.is_empty() {
-            "No snapshots available.".to_string()
-        } else {
-            format!("Available snapshots: {}", available.join(", "))
-        };
-        bail!("Snapshot \"{}\" not found.\n{}", name, hint);
-    }
-
-    // Try to load metadata (may not exist for incomplete snapshots)
-    let metadata = SnapshotMetadata::load(&snap_dir).ok();
+    let metadata = if let Some(ref storage) = storage {
+        if !storage.names()?.iter().any(|n| n == name) {
+            let available: Vec<String> = storage.list()?.into_iter().map(|m| m.name).collect();
+            return Err(snapshot_not_found(name, &available));
+        }
+        storage.load_metadata(name).ok()
+    } else {
+        if !snap_dir.exists() {
+            let available = snapshot::available_snapshots(snap_dir_override);
+            return Err(snapshot_not_found(name, &available));
+        }
+        // Try to load metadata (may not exist for incomplete snapshots)
+        SnapshotMetadata::load(&snap_dir).ok()
+    };
     let is_incomplete = metadata.is_none();
 
     // Confirm deletion unless --yes provided
@@ -1071,8 +2202,11 @@ pub fn snapshot_delete(name: &str, config: &Config, quiet: bool, yes: bool) -> R
         }
     }
 
-    // Delete snapshot directory
-    fs::remove_dir_all(&snap_dir)?;
+    // Delete snapshot directory (or remote prefix)
+    match storage {
+        Some(ref storage) => storage.delete(name)?,
+        None => fs::remove_dir_all(&snap_dir)?,
+    }
 
     if !quiet {
         if is_incomplete {
@@ -1117,12 +2251,13 @@ async fn get_migration_state(client: &Client) -> Result<(usize, Option<String>)>
         return Ok((0, None));
     }
 
-    // Get count and latest version
+    // Get count and latest version of successfully applied migrations
     let row = client
         .query_one(
             "SELECT COUNT(*) AS count,
                     MAX(version) AS latest
-             FROM pgcrate.schema_migrations",
+             FROM pgcrate.schema_migrations m
+             WHERE (to_jsonb(m) ->> 'success') IS DISTINCT FROM 'false'",
             &[],
         )
         .await?;
@@ -1162,6 +2297,70 @@ mod tests {
         assert!(!is_harmless_restore_warning(line));
     }
 
+    fn row_filter(table: &str, oid: u32) -> RowFilter {
+        RowFilter {
+            table: table.to_string(),
+            quoted: format!("\"app\".\"{}\"", table.trim_start_matches("app.")),
+            condition: "true".to_string(),
+            columns: vec!["id".to_string(), "user_id".to_string()],
+            oid,
+        }
+    }
+
+    #[test]
+    fn test_order_row_filters_loads_referenced_tables_first() {
+        let filters = vec![
+            row_filter("app.items", 3),
+            row_filter("app.orders", 2),
+            row_filter("app.users", 1),
+        ];
+        // items -> orders -> users
+        let ordered = order_row_filters(filters.clone(), &[(3, 2), (2, 1)]);
+        let tables: Vec<&str> = ordered.iter().map(|f| f.table.as_str()).collect();
+        assert_eq!(tables, vec!["app.users", "app.orders", "app.items"]);
+
+        // A cycle keeps config order
+        let ordered = order_row_filters(filters, &[(3, 2), (2, 3)]);
+        assert_eq!(ordered[0].table, "app.users");
+    }
+
+    #[test]
+    fn test_throwaway_comment_roundtrip() {
+        let at = DateTime::parse_from_rfc3339("2026-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let comment = throwaway_comment("nightly", Some(at));
+        assert_eq!(
+            comment,
+            "pgcrate:snapshot=nightly expires=2026-01-02T03:04:05Z"
+        );
+        assert_eq!(
+            parse_throwaway_comment(&comment),
+            Some(("nightly".to_string(), Some(at)))
+        );
+        assert_eq!(
+            parse_throwaway_comment(&throwaway_comment("nightly", None)),
+            Some(("nightly".to_string(), None))
+        );
+        assert_eq!(parse_throwaway_comment("team database"), None);
+    }
+
+    #[test]
+    fn test_filtered_copy_block() {
+        let mut filter = row_filter("app.orders", 1);
+        filter.condition = "total > 20\n  AND user_id < 5".to_string();
+        let block = filtered_copy_block(&filter, b"4\t4\n");
+        assert_eq!(
+            String::from_utf8(block).unwrap(),
+            "-- app.orders WHERE total > 20   AND user_id < 5\n\
+             ALTER TABLE \"app\".\"orders\" DISABLE TRIGGER USER;\n\
+             COPY \"app\".\"orders\" (\"id\", \"user_id\") FROM stdin;\n\
+             4\t4\n\
+             \\.\n\
+             ALTER TABLE \"app\".\"orders\" ENABLE TRIGGER USER;\n\n"
+        );
+    }
+
     #[test]
     fn test_is_harmless_restore_warning_idle_session_timeout() {
         let line = "pg_restore: error: could not execute query: ERROR:  unrecognized configuration parameter \"idle_session_timeout\"";
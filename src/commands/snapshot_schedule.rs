@@ -0,0 +1,229 @@
+//! `snapshot schedule`: saves snapshots on a cron schedule, keeps the newest
+//! few, and logs each run as one JSON object per line (NDJSON) on stdout.
+//!
+//! Runs as a long-lived process, or with `--systemd` prints a service and
+//! timer that run `snapshot schedule --now` instead.
+
+use anyhow::{bail, Result};
+use chrono::{Local, Utc};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Write;
+use std::time::Instant;
+
+use super::snapshot::load_snapshot;
+use super::snapshot_save;
+use crate::config::Config;
+use crate::cron::CronSchedule;
+use crate::snapshot::{self, snapshot_dir, validate_snapshot_name};
+use crate::snapshot_storage::snapshot_storage;
+
+/// What each scheduled run saves and keeps
+pub struct ScheduleOptions<'a> {
+    /// Snapshots are named `<prefix>-<YYYYmmdd-HHMMSS>`
+    pub prefix: &'a str,
+    /// Keep only the newest N snapshots with the prefix
+    pub keep: Option<usize>,
+    pub profile: Option<&'a str>,
+    pub format: Option<&'a str>,
+}
+
+/// Print one NDJSON log line
+fn log(event: &str, mut fields: Value) {
+    fields["ts"] = json!(Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    fields["event"] = json!(event);
+    println!("{}", fields);
+    let _ = std::io::stdout().flush();
+}
+
+/// Save snapshots on `cron`, or once with `now`, or print systemd units
+pub async fn snapshot_schedule(
+    database_url: &str,
+    config: &Config,
+    cron: Option<&str>,
+    options: &ScheduleOptions<'_>,
+    now: bool,
+    systemd: bool,
+    verbose: bool,
+) -> Result<()> {
+    validate_snapshot_name(&format!("{}-20260101-000000", options.prefix))
+        .map_err(|e| anyhow::anyhow!("Invalid --prefix: {}", e))?;
+    if options.keep == Some(0) {
+        bail!("--keep must be at least 1");
+    }
+    let schedule = cron.map(CronSchedule::parse).transpose()?;
+
+    if systemd {
+        let schedule = schedule.ok_or_else(|| anyhow::anyhow!("--systemd requires --cron"))?;
+        print!("{}", systemd_units(&schedule, options)?);
+        return Ok(());
+    }
+
+    if now {
+        return if run(database_url, config, options, verbose).await {
+            Ok(())
+        } else {
+            bail!("Scheduled snapshot failed")
+        };
+    }
+
+    let schedule = schedule.ok_or_else(|| anyhow::anyhow!("--cron is required"))?;
+    log("started", json!({ "cron": schedule.to_string() }));
+    loop {
+        let Some(next) = schedule.next_after(Local::now()) else {
+            bail!("cron expression '{}' never runs", schedule);
+        };
+        log("scheduled", json!({ "next_run": next.to_rfc3339() }));
+        let wait = (next - Local::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+        run(database_url, config, options, verbose).await;
+    }
+}
+
+/// One scheduled save plus retention; returns whether the save succeeded
+async fn run(
+    database_url: &str,
+    config: &Config,
+    options: &ScheduleOptions<'_>,
+    verbose: bool,
+) -> bool {
+    let name = format!(
+        "{}-{}",
+        options.prefix,
+        Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let format = options
+        .format
+        .or_else(|| {
+            config
+                .snapshot
+                .as_ref()
+                .and_then(|s| s.default_format.as_deref())
+        })
+        .unwrap_or("custom");
+    let started = Instant::now();
+    let saved = snapshot_save(
+        database_url,
+        &name,
+        Some("Scheduled snapshot"),
+        options.profile,
+        None,
+        format,
+        false,
+        false,
+        &[],
+        false,
+        config,
+        true,
+        verbose,
+        false,
+    )
+    .await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    if let Err(e) = saved {
+        log(
+            "failed",
+            json!({ "snapshot": name, "error": e.to_string(), "duration_ms": duration_ms }),
+        );
+        return false;
+    }
+    let storage = snapshot_storage(config).ok().flatten();
+    let size_bytes = load_snapshot(&name, Some(config.snapshot_dir()), storage.as_ref())
+        .map(|m| m.size_bytes)
+        .ok();
+    log(
+        "saved",
+        json!({ "snapshot": name, "size_bytes": size_bytes, "duration_ms": duration_ms }),
+    );
+
+    if let Some(keep) = options.keep {
+        if let Err(e) = prune_scheduled(config, options.prefix, keep) {
+            log("retention_failed", json!({ "error": e.to_string() }));
+        }
+    }
+    true
+}
+
+/// Delete all but the newest `keep` snapshots named `<prefix>-...`
+fn prune_scheduled(config: &Config, prefix: &str, keep: usize) -> Result<()> {
+    let snap_dir_override = Some(config.snapshot_dir());
+    let storage = snapshot_storage(config)?;
+    let snapshots = match storage {
+        Some(ref storage) => storage.list()?,
+        None => snapshot::list_snapshots(snap_dir_override)?,
+    };
+    let pattern = format!("{}-", prefix);
+    // Newest first
+    for old in snapshots
+        .iter()
+        .filter(|m| m.name.starts_with(&pattern))
+        .skip(keep)
+    {
+        let deleted = match storage {
+            Some(ref storage) => storage.delete(&old.name),
+            None => {
+                fs::remove_dir_all(snapshot_dir(&old.name, snap_dir_override)).map_err(Into::into)
+            }
+        };
+        match deleted {
+            Ok(()) => log("deleted", json!({ "snapshot": old.name })),
+            Err(e) => log(
+                "delete_failed",
+                json!({ "snapshot": old.name, "error": e.to_string() }),
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// systemd service + timer running one scheduled save per `OnCalendar` event
+fn systemd_units(schedule: &CronSchedule, options: &ScheduleOptions<'_>) -> Result<String> {
+    let exe = std::env::current_exe()?;
+    let cwd = std::env::current_dir()?;
+    let unit = format!("pgcrate-snapshot-{}", options.prefix);
+
+    let mut command = format!(
+        "{} snapshot schedule --now --prefix {}",
+        exe.display(),
+        options.prefix
+    );
+    if let Some(keep) = options.keep {
+        command.push_str(&format!(" --keep {}", keep));
+    }
+    if let Some(profile) = options.profile {
+        command.push_str(&format!(" --profile {}", profile));
+    }
+    if let Some(format) = options.format {
+        command.push_str(&format!(" --format {}", format));
+    }
+
+    Ok(format!(
+        "# {unit}.service\n\
+         [Unit]\n\
+         Description=pgcrate scheduled snapshot ({prefix})\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         # DATABASE_URL comes from pgcrate.toml here, or add Environment=DATABASE_URL=...\n\
+         WorkingDirectory={cwd}\n\
+         ExecStart={command}\n\
+         \n\
+         # {unit}.timer\n\
+         [Unit]\n\
+         Description=Run {unit}.service on cron \"{cron}\"\n\
+         \n\
+         [Timer]\n\
+         OnCalendar={calendar}\n\
+         Persistent=true\n\
+         \n\
+         [Install]\n\
+         WantedBy=timers.target\n",
+        unit = unit,
+        prefix = options.prefix,
+        cwd = cwd.display(),
+        command = command,
+        cron = schedule,
+        calendar = schedule.to_on_calendar()?,
+    ))
+}
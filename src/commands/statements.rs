@@ -0,0 +1,573 @@
+//! Statements command: pg_stat_statements management.
+//!
+//! Goes beyond `dba queries`: filters by user, database, and query regex,
+//! sorts by temp file usage, WAL generated, or rows, measures counter deltas
+//! over a window (`--since 30s` takes two snapshots), and resets the counters
+//! (`--reset`, gated on --read-write and --primary). When the extension is
+//! missing, reports which step of the setup is missing and how to fix it.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::queries::{format_duration_ms, format_number, truncate_query, QueryStatus};
+use crate::snapshot::format_bytes;
+
+/// Sort order for statements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementSort {
+    #[default]
+    TotalTime,
+    MeanTime,
+    Calls,
+    Rows,
+    Temp,
+    Wal,
+}
+
+impl StatementSort {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "total" | "total_time" => Some(StatementSort::TotalTime),
+            "mean" | "mean_time" | "avg" => Some(StatementSort::MeanTime),
+            "calls" | "count" => Some(StatementSort::Calls),
+            "rows" => Some(StatementSort::Rows),
+            "temp" | "temp_bytes" => Some(StatementSort::Temp),
+            "wal" | "wal_bytes" => Some(StatementSort::Wal),
+            _ => None,
+        }
+    }
+
+    fn key(&self, s: &StatementInfo) -> f64 {
+        match self {
+            StatementSort::TotalTime => s.total_exec_time_ms,
+            StatementSort::MeanTime => s.mean_exec_time_ms,
+            StatementSort::Calls => s.calls as f64,
+            StatementSort::Rows => s.rows as f64,
+            StatementSort::Temp => s.temp_bytes as f64,
+            StatementSort::Wal => s.wal_bytes as f64,
+        }
+    }
+}
+
+/// Which statements to show
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatementFilters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matching: Option<String>,
+}
+
+/// pg_stat_statements setup state
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtensionCheck {
+    /// CREATE EXTENSION has been run in this database
+    pub installed: bool,
+    /// Listed in shared_preload_libraries
+    pub preloaded: bool,
+    /// The extension files are present on the server
+    pub available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Steps to make pg_stat_statements usable (empty when it is)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fix: Vec<String>,
+}
+
+impl ExtensionCheck {
+    pub fn usable(&self) -> bool {
+        self.installed && self.preloaded
+    }
+}
+
+/// One statement (or its change over the --since window)
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementInfo {
+    pub queryid: i64,
+    pub user: String,
+    pub database: String,
+    pub query: String,
+    pub calls: i64,
+    pub total_exec_time_ms: f64,
+    pub mean_exec_time_ms: f64,
+    pub rows: i64,
+    pub temp_bytes: i64,
+    pub wal_bytes: i64,
+    pub cache_hit_ratio: Option<f64>,
+    pub status: QueryStatus,
+    #[serde(skip)]
+    blks_hit: i64,
+    #[serde(skip)]
+    blks_read: i64,
+}
+
+/// Full statements results
+#[derive(Debug, Serialize)]
+pub struct StatementsResult {
+    pub extension: ExtensionCheck,
+    pub sort_by: StatementSort,
+    pub filters: StatementFilters,
+    /// Length of the --since window; counters are deltas when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_secs: Option<u64>,
+    pub stats_since: Option<String>,
+    /// True when this run reset the counters
+    pub reset: bool,
+    pub statements: Vec<StatementInfo>,
+    pub overall_status: QueryStatus,
+}
+
+/// Check whether pg_stat_statements is installed, preloaded, and available
+pub async fn check_extension(client: &Client) -> Result<ExtensionCheck> {
+    let row = client
+        .query_one(
+            r#"
+            SELECT
+                (SELECT extversion FROM pg_extension WHERE extname = 'pg_stat_statements') AS version,
+                EXISTS (
+                    SELECT 1 FROM pg_available_extensions WHERE name = 'pg_stat_statements'
+                ) AS available,
+                current_setting('shared_preload_libraries') ~ '(^|[\s,])pg_stat_statements($|[\s,])'
+                    AS preloaded
+            "#,
+            &[],
+        )
+        .await
+        .context("Failed to check pg_stat_statements extension")?;
+
+    let version: Option<String> = row.get("version");
+    let available: bool = row.get("available");
+    let preloaded: bool = row.get("preloaded");
+    let installed = version.is_some();
+
+    let mut fix = Vec::new();
+    if !available {
+        fix.push(
+            "Install the PostgreSQL contrib package (pg_stat_statements is not available on the server)"
+                .to_string(),
+        );
+    }
+    if !preloaded {
+        fix.push(
+            "Add to postgresql.conf: shared_preload_libraries = 'pg_stat_statements'".to_string(),
+        );
+        fix.push("Restart PostgreSQL".to_string());
+    }
+    if !installed {
+        fix.push("Run: CREATE EXTENSION pg_stat_statements;".to_string());
+    }
+
+    Ok(ExtensionCheck {
+        installed,
+        preloaded,
+        available,
+        version,
+        fix,
+    })
+}
+
+type StatementKey = (String, String, i64);
+
+/// Read current counters, one entry per (user, database, queryid)
+async fn snapshot(
+    client: &Client,
+    filters: &StatementFilters,
+    matching: Option<&Regex>,
+) -> Result<HashMap<StatementKey, StatementInfo>> {
+    // Top-level and nested executions of a statement share a queryid (PG14+)
+    let query = r#"
+        SELECT
+            COALESCE(r.rolname, s.userid::text) AS username,
+            COALESCE(d.datname, s.dbid::text) AS database,
+            s.queryid,
+            LEFT(MAX(s.query), 500) AS query,
+            SUM(s.calls)::bigint AS calls,
+            SUM(s.total_exec_time)::float8 AS total_exec_time_ms,
+            SUM(s.rows)::bigint AS rows,
+            SUM(s.shared_blks_hit)::bigint AS blks_hit,
+            SUM(s.shared_blks_read)::bigint AS blks_read,
+            (SUM(s.temp_blks_written) * current_setting('block_size')::bigint)::bigint
+                AS temp_bytes,
+            SUM(s.wal_bytes)::bigint AS wal_bytes
+        FROM pg_stat_statements s
+        LEFT JOIN pg_roles r ON r.oid = s.userid
+        LEFT JOIN pg_database d ON d.oid = s.dbid
+        WHERE s.queryid IS NOT NULL
+          AND s.query NOT LIKE '%pg_stat_statements%'
+          AND ($1::text IS NULL OR r.rolname = $1)
+          AND ($2::text IS NULL OR d.datname = $2)
+        GROUP BY 1, 2, 3
+    "#;
+
+    let rows = client
+        .query(query, &[&filters.user, &filters.database])
+        .await
+        .context("Failed to query pg_stat_statements")?;
+
+    let mut statements = HashMap::new();
+    for row in rows {
+        let text: String = row.get("query");
+        if matching.is_some_and(|re| !re.is_match(&text)) {
+            continue;
+        }
+        let info = StatementInfo {
+            queryid: row.get("queryid"),
+            user: row.get("username"),
+            database: row.get("database"),
+            query: text,
+            calls: row.get("calls"),
+            total_exec_time_ms: row.get("total_exec_time_ms"),
+            mean_exec_time_ms: 0.0,
+            rows: row.get("rows"),
+            temp_bytes: row.get::<_, Option<i64>>("temp_bytes").unwrap_or(0),
+            wal_bytes: row.get::<_, Option<i64>>("wal_bytes").unwrap_or(0),
+            cache_hit_ratio: None,
+            status: QueryStatus::Healthy,
+            blks_hit: row.get("blks_hit"),
+            blks_read: row.get("blks_read"),
+        };
+        statements.insert(
+            (info.user.clone(), info.database.clone(), info.queryid),
+            finish(info),
+        );
+    }
+    Ok(statements)
+}
+
+/// Fill in the derived fields (mean time, cache hit ratio, status)
+fn finish(mut info: StatementInfo) -> StatementInfo {
+    info.mean_exec_time_ms = if info.calls > 0 {
+        info.total_exec_time_ms / info.calls as f64
+    } else {
+        0.0
+    };
+    let blocks = info.blks_hit + info.blks_read;
+    info.cache_hit_ratio = (blocks > 0).then(|| 100.0 * info.blks_hit as f64 / blocks as f64);
+    info.status = QueryStatus::from_mean_time(info.mean_exec_time_ms);
+    info
+}
+
+/// Activity between two snapshots. Statements first seen (or reset) during
+/// the window count from zero; statements without new calls are dropped.
+fn deltas(
+    before: &HashMap<StatementKey, StatementInfo>,
+    after: HashMap<StatementKey, StatementInfo>,
+) -> Vec<StatementInfo> {
+    after
+        .into_iter()
+        .filter_map(|(key, now)| {
+            let delta = match before.get(&key) {
+                Some(prev) if prev.calls <= now.calls => StatementInfo {
+                    calls: now.calls - prev.calls,
+                    total_exec_time_ms: (now.total_exec_time_ms - prev.total_exec_time_ms).max(0.0),
+                    rows: now.rows - prev.rows,
+                    temp_bytes: now.temp_bytes - prev.temp_bytes,
+                    wal_bytes: now.wal_bytes - prev.wal_bytes,
+                    blks_hit: now.blks_hit - prev.blks_hit,
+                    blks_read: now.blks_read - prev.blks_read,
+                    ..now
+                },
+                _ => now,
+            };
+            (delta.calls > 0).then(|| finish(delta))
+        })
+        .collect()
+}
+
+async fn get_stats_since(client: &Client) -> Option<String> {
+    client
+        .query_opt(
+            "SELECT stats_reset::text FROM pg_stat_statements_info LIMIT 1",
+            &[],
+        )
+        .await
+        .ok()
+        .flatten()
+        .and_then(|row| row.get::<_, Option<String>>(0))
+}
+
+/// Reset counters, scoped to --user/--database when given
+async fn reset_counters(client: &Client, filters: &StatementFilters) -> Result<()> {
+    let row = client
+        .query_one(
+            r#"
+            SELECT
+                (SELECT oid FROM pg_roles WHERE rolname = $1) AS userid,
+                (SELECT oid FROM pg_database WHERE datname = $2) AS dbid
+            "#,
+            &[&filters.user, &filters.database],
+        )
+        .await?;
+    let userid: Option<u32> = row.get("userid");
+    let dbid: Option<u32> = row.get("dbid");
+    // An oid of 0 means "all", so an unknown name must not fall through to it
+    if let (Some(user), None) = (&filters.user, userid) {
+        bail!("Unknown role: {}", user);
+    }
+    if let (Some(database), None) = (&filters.database, dbid) {
+        bail!("Unknown database: {}", database);
+    }
+
+    client
+        .execute(
+            "SELECT pg_stat_statements_reset($1::oid, $2::oid, 0)",
+            &[&userid.unwrap_or(0), &dbid.unwrap_or(0)],
+        )
+        .await
+        .context("Failed to reset pg_stat_statements (requires superuser or an explicit GRANT)")?;
+    Ok(())
+}
+
+/// Run statements analysis (or reset)
+pub async fn run_statements(
+    client: &Client,
+    sort_by: StatementSort,
+    limit: usize,
+    filters: StatementFilters,
+    since: Option<Duration>,
+    reset: bool,
+) -> Result<StatementsResult> {
+    let extension = check_extension(client).await?;
+    let mut result = StatementsResult {
+        extension,
+        sort_by,
+        filters,
+        window_secs: since.map(|d| d.as_secs()),
+        stats_since: None,
+        reset: false,
+        statements: vec![],
+        overall_status: QueryStatus::Warning,
+    };
+    if !result.extension.usable() {
+        if reset {
+            bail!("pg_stat_statements is not installed; nothing to reset");
+        }
+        return Ok(result);
+    }
+
+    if reset {
+        reset_counters(client, &result.filters).await?;
+        result.reset = true;
+        result.stats_since = get_stats_since(client).await;
+        result.overall_status = QueryStatus::Healthy;
+        return Ok(result);
+    }
+
+    let matching = result
+        .filters
+        .matching
+        .as_deref()
+        .map(|p| Regex::new(&format!("(?i){}", p)))
+        .transpose()
+        .context("Invalid --matching regex")?;
+
+    let current = snapshot(client, &result.filters, matching.as_ref()).await?;
+    let mut statements = match since {
+        Some(window) => {
+            tokio::time::sleep(window).await;
+            let later = snapshot(client, &result.filters, matching.as_ref()).await?;
+            deltas(&current, later)
+        }
+        None => current.into_values().collect(),
+    };
+    statements.sort_by(|a, b| {
+        sort_by
+            .key(b)
+            .total_cmp(&sort_by.key(a))
+            .then(b.total_exec_time_ms.total_cmp(&a.total_exec_time_ms))
+    });
+    statements.truncate(limit);
+
+    result.overall_status = statements
+        .iter()
+        .map(|s| s.status)
+        .max_by_key(|s| match s {
+            QueryStatus::Healthy => 0,
+            QueryStatus::Warning => 1,
+            QueryStatus::Critical => 2,
+        })
+        .unwrap_or(QueryStatus::Healthy);
+    result.stats_since = get_stats_since(client).await;
+    result.statements = statements;
+    Ok(result)
+}
+
+/// Print statements in human-readable format
+pub fn print_human(result: &StatementsResult, quiet: bool) {
+    let ext = &result.extension;
+    if !ext.usable() {
+        println!("pg_stat_statements is not ready:");
+        println!(
+            "  available: {}  preloaded: {}  installed: {}",
+            yes_no(ext.available),
+            yes_no(ext.preloaded),
+            yes_no(ext.installed)
+        );
+        println!();
+        println!("To fix:");
+        for (i, step) in ext.fix.iter().enumerate() {
+            println!("  {}. {}", i + 1, step);
+        }
+        return;
+    }
+
+    if result.reset {
+        let scope = match (&result.filters.user, &result.filters.database) {
+            (None, None) => String::new(),
+            (user, database) => {
+                let mut parts = Vec::new();
+                if let Some(u) = user {
+                    parts.push(format!("user {}", u));
+                }
+                if let Some(d) = database {
+                    parts.push(format!("database {}", d));
+                }
+                format!(" for {}", parts.join(", "))
+            }
+        };
+        println!("✓ pg_stat_statements counters reset{}", scope);
+        return;
+    }
+
+    if result.statements.is_empty() {
+        if !quiet {
+            match result.window_secs {
+                Some(secs) => println!("No matching statements ran in the last {}s.", secs),
+                None => println!("No matching statements recorded in pg_stat_statements."),
+            }
+        }
+        return;
+    }
+
+    match result.window_secs {
+        Some(secs) => println!("STATEMENTS (activity over {}s):", secs),
+        None => println!("STATEMENTS:"),
+    }
+    if let Some(ref since) = result.stats_since {
+        println!("  Stats since: {}", since);
+    }
+    println!();
+
+    println!(
+        "  {:3} {:>8} {:>9} {:>9} {:>8} {:>9} {:>9} {:12}  QUERY",
+        "", "CALLS", "TOTAL", "MEAN", "ROWS", "TEMP", "WAL", "USER"
+    );
+    println!("  {}", "-".repeat(100));
+    for s in &result.statements {
+        println!(
+            "  {} {:>8} {:>9} {:>9} {:>8} {:>9} {:>9} {:12}  {}",
+            s.status.emoji(),
+            format_number(s.calls),
+            format_duration_ms(s.total_exec_time_ms),
+            format_duration_ms(s.mean_exec_time_ms),
+            format_number(s.rows),
+            format_bytes(s.temp_bytes.max(0) as u64),
+            format_bytes(s.wal_bytes.max(0) as u64),
+            truncate_query(&s.user, 12),
+            truncate_query(&s.query, 45)
+        );
+    }
+}
+
+fn yes_no(b: bool) -> &'static str {
+    if b {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Print statements as JSON with schema versioning
+pub fn print_json(
+    result: &StatementsResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = match result.overall_status {
+        QueryStatus::Healthy => Severity::Healthy,
+        QueryStatus::Warning => Severity::Warning,
+        QueryStatus::Critical => Severity::Critical,
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::STATEMENTS, result, severity, t),
+        None => DiagnosticOutput::new(schema::STATEMENTS, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(queryid: i64, calls: i64, total_ms: f64, temp: i64) -> StatementInfo {
+        finish(StatementInfo {
+            queryid,
+            user: "app".to_string(),
+            database: "db".to_string(),
+            query: "SELECT 1".to_string(),
+            calls,
+            total_exec_time_ms: total_ms,
+            mean_exec_time_ms: 0.0,
+            rows: calls,
+            temp_bytes: temp,
+            wal_bytes: 0,
+            cache_hit_ratio: None,
+            status: QueryStatus::Healthy,
+            blks_hit: 90,
+            blks_read: 10,
+        })
+    }
+
+    fn map(items: Vec<StatementInfo>) -> HashMap<StatementKey, StatementInfo> {
+        items
+            .into_iter()
+            .map(|i| ((i.user.clone(), i.database.clone(), i.queryid), i))
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_from_str() {
+        assert_eq!(StatementSort::from_str("temp"), Some(StatementSort::Temp));
+        assert_eq!(StatementSort::from_str("WAL"), Some(StatementSort::Wal));
+        assert_eq!(StatementSort::from_str("rows"), Some(StatementSort::Rows));
+        assert_eq!(StatementSort::from_str("bogus"), None);
+    }
+
+    #[test]
+    fn test_finish_derives_mean_and_hit_ratio() {
+        let s = info(1, 4, 8000.0, 0);
+        assert_eq!(s.mean_exec_time_ms, 2000.0);
+        assert_eq!(s.cache_hit_ratio, Some(90.0));
+        assert_eq!(s.status, QueryStatus::Warning);
+    }
+
+    #[test]
+    fn test_deltas() {
+        let before = map(vec![info(1, 10, 100.0, 0), info(2, 5, 50.0, 0)]);
+        let after = map(vec![
+            info(1, 15, 600.0, 8192),
+            info(2, 5, 50.0, 0), // no new calls: dropped
+            info(3, 2, 20.0, 0), // new statement: counted from zero
+        ]);
+        let mut out = deltas(&before, after);
+        out.sort_by_key(|s| s.queryid);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].calls, 5);
+        assert_eq!(out[0].total_exec_time_ms, 500.0);
+        assert_eq!(out[0].mean_exec_time_ms, 100.0);
+        assert_eq!(out[0].temp_bytes, 8192);
+        assert_eq!(out[1].queryid, 3);
+        assert_eq!(out[1].calls, 2);
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! PostgreSQL's query planner relies on table statistics to estimate row counts
 //! and choose optimal join strategies. Stale statistics lead to poor query plans.
+//!
+//! A table is stale when it was never analyzed, when its last ANALYZE is older
+//! than the age threshold, or when the rows modified since that ANALYZE
+//! (n_mod_since_analyze) are a large share of its estimated size (reltuples).
 
 use anyhow::Result;
 use serde::Serialize;
@@ -12,8 +16,13 @@ const STATS_WARNING_DAYS: f64 = 7.0;
 const STATS_CRITICAL_DAYS: f64 = 30.0;
 const MIN_ROWS_TO_CARE: i64 = 1000;
 
+/// Rows modified since ANALYZE, as a percentage of reltuples. Autovacuum
+/// analyzes at 10% by default, so 20% means it is falling behind.
+pub const MOD_WARNING_PCT: f64 = 20.0;
+pub const MOD_CRITICAL_PCT: f64 = 50.0;
+
 /// Statistics freshness status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum StatsStatus {
     Healthy,
@@ -31,6 +40,16 @@ impl StatsStatus {
         }
     }
 
+    pub fn from_mod_pct(pct: f64) -> Self {
+        if pct >= MOD_CRITICAL_PCT {
+            StatsStatus::Critical
+        } else if pct >= MOD_WARNING_PCT {
+            StatsStatus::Warning
+        } else {
+            StatsStatus::Healthy
+        }
+    }
+
     pub fn emoji(&self) -> &'static str {
         match self {
             StatsStatus::Healthy => "✓",
@@ -40,6 +59,17 @@ impl StatsStatus {
     }
 }
 
+/// Why a table's statistics are stale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleReason {
+    NeverAnalyzed,
+    /// Many rows modified since the last ANALYZE
+    Modified,
+    /// Last ANALYZE older than the age threshold
+    Age,
+}
+
 /// Information about a table's statistics age
 #[derive(Debug, Clone, Serialize)]
 pub struct TableStatsAge {
@@ -52,6 +82,12 @@ pub struct TableStatsAge {
     pub last_autoanalyze: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub days_since_analyze: Option<f64>,
+    /// Rows inserted, updated, or deleted since the last ANALYZE
+    pub n_mod_since_analyze: i64,
+    /// n_mod_since_analyze as a percentage of reltuples
+    pub mod_pct: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<StaleReason>,
     pub status: StatsStatus,
 }
 
@@ -72,33 +108,51 @@ pub async fn run_stats_age(
 ) -> Result<StatsAgeResult> {
     let threshold = threshold_days.unwrap_or(STATS_WARNING_DAYS);
 
+    // reltuples is -1 before the first ANALYZE (PG14+), so fall back to n_live_tup
     let query = r#"
         SELECT
-            schemaname,
-            relname,
-            n_live_tup AS row_estimate,
-            last_analyze,
-            last_autoanalyze,
-            (EXTRACT(EPOCH FROM (now() - GREATEST(last_analyze, last_autoanalyze))) / 86400.0)::float8 AS days_since_analyze
-        FROM pg_stat_user_tables
-        WHERE n_live_tup > $1  -- Only tables with meaningful data
+            s.schemaname,
+            s.relname,
+            s.n_live_tup AS row_estimate,
+            s.last_analyze,
+            s.last_autoanalyze,
+            (EXTRACT(EPOCH FROM (now() - GREATEST(s.last_analyze, s.last_autoanalyze))) / 86400.0)::float8 AS days_since_analyze,
+            s.n_mod_since_analyze,
+            (100.0 * s.n_mod_since_analyze
+                / GREATEST(c.reltuples::float8, s.n_live_tup::float8, 1))::float8 AS mod_pct
+        FROM pg_stat_user_tables s
+        JOIN pg_class c ON c.oid = s.relid
+        WHERE s.n_live_tup > $1  -- Only tables with meaningful data
         ORDER BY
-            CASE WHEN GREATEST(last_analyze, last_autoanalyze) IS NULL THEN 0 ELSE 1 END,
-            GREATEST(last_analyze, last_autoanalyze) ASC NULLS FIRST
-        LIMIT $2
+            CASE WHEN GREATEST(s.last_analyze, s.last_autoanalyze) IS NULL THEN 0 ELSE 1 END,
+            GREATEST(s.last_analyze, s.last_autoanalyze) ASC NULLS FIRST
     "#;
 
-    let rows = client
-        .query(query, &[&MIN_ROWS_TO_CARE, &(limit as i64)])
-        .await?;
+    let rows = client.query(query, &[&MIN_ROWS_TO_CARE]).await?;
 
     let mut tables = Vec::new();
     for row in rows {
         let last_analyze: Option<chrono::DateTime<chrono::Utc>> = row.get("last_analyze");
         let last_autoanalyze: Option<chrono::DateTime<chrono::Utc>> = row.get("last_autoanalyze");
         let days_since: Option<f64> = row.get("days_since_analyze");
+        let mod_pct: f64 = row.get("mod_pct");
 
-        let status = StatsStatus::from_days(days_since);
+        let age_status = if days_since.is_some_and(|d| d < threshold) {
+            StatsStatus::Healthy
+        } else {
+            StatsStatus::from_days(days_since)
+        };
+        let mod_status = StatsStatus::from_mod_pct(mod_pct);
+        let status = age_status.max(mod_status);
+        let reason = if days_since.is_none() {
+            Some(StaleReason::NeverAnalyzed)
+        } else if mod_status != StatsStatus::Healthy {
+            Some(StaleReason::Modified)
+        } else if days_since.is_some_and(|d| d >= threshold) {
+            Some(StaleReason::Age)
+        } else {
+            None
+        };
 
         tables.push(TableStatsAge {
             schema: row.get("schemaname"),
@@ -107,15 +161,17 @@ pub async fn run_stats_age(
             last_analyze: last_analyze.map(|t| t.to_rfc3339()),
             last_autoanalyze: last_autoanalyze.map(|t| t.to_rfc3339()),
             days_since_analyze: days_since,
+            n_mod_since_analyze: row.get("n_mod_since_analyze"),
+            mod_pct,
+            reason,
             status,
         });
     }
 
-    // Filter to only show tables exceeding threshold (or never analyzed)
-    let tables: Vec<_> = tables
-        .into_iter()
-        .filter(|t| t.days_since_analyze.is_none_or(|d| d >= threshold))
-        .collect();
+    // Only show stale tables, worst first (stable, so oldest first within a status)
+    let mut tables: Vec<_> = tables.into_iter().filter(|t| t.reason.is_some()).collect();
+    tables.sort_by_key(|t| std::cmp::Reverse(t.status));
+    tables.truncate(limit);
 
     let stale_count = tables
         .iter()
@@ -129,13 +185,8 @@ pub async fn run_stats_age(
 
     let overall_status = tables
         .iter()
-        .map(|t| &t.status)
-        .max_by_key(|s| match s {
-            StatsStatus::Healthy => 0,
-            StatsStatus::Warning => 1,
-            StatsStatus::Critical => 2,
-        })
-        .cloned()
+        .map(|t| t.status)
+        .max()
         .unwrap_or(StatsStatus::Healthy);
 
     Ok(StatsAgeResult {
@@ -178,12 +229,18 @@ pub fn print_human(result: &StatsAgeResult, quiet: bool) {
             StatsStatus::Warning => "",
             StatsStatus::Healthy => "",
         };
+        let modified = if t.reason == Some(StaleReason::Modified) {
+            format!(", {:.0}% modified since", t.mod_pct)
+        } else {
+            String::new()
+        };
         println!(
-            "  {} {}.{:<30} last analyzed: {}{}",
+            "  {} {}.{:<30} last analyzed: {}{}{}",
             t.status.emoji(),
             t.schema,
             t.table,
             age_str,
+            modified,
             status_label
         );
     }
@@ -196,10 +253,21 @@ pub fn print_human(result: &StatsAgeResult, quiet: bool) {
             result.never_analyzed_count
         );
     }
-    if result.stale_count > result.never_analyzed_count {
+    let modified_count = result
+        .tables
+        .iter()
+        .filter(|t| t.reason == Some(StaleReason::Modified))
+        .count();
+    if modified_count > 0 {
+        println!(
+            "  ⚠ {} tables modified >{:.0}% since their last ANALYZE",
+            modified_count, MOD_WARNING_PCT
+        );
+    }
+    if result.stale_count > result.never_analyzed_count + modified_count {
         println!(
             "  ⚠ {} tables have stale statistics (>{:.0} days)",
-            result.stale_count - result.never_analyzed_count,
+            result.stale_count - result.never_analyzed_count - modified_count,
             STATS_WARNING_DAYS
         );
     }
@@ -220,6 +288,9 @@ pub fn print_human(result: &StatsAgeResult, quiet: bool) {
         if critical_tables.len() > 5 {
             println!("  ... and {} more tables", critical_tables.len() - 5);
         }
+        println!();
+        println!("Or analyze every stale table in batches:");
+        println!("  pgcrate --read-write --primary dba fix analyze --all-stale --dry-run");
     }
 }
 
@@ -276,6 +347,19 @@ mod tests {
         assert_eq!(StatsStatus::from_days(Some(30.0)), StatsStatus::Critical);
     }
 
+    #[test]
+    fn test_stats_status_from_mod_pct() {
+        assert_eq!(StatsStatus::from_mod_pct(5.0), StatsStatus::Healthy);
+        assert_eq!(StatsStatus::from_mod_pct(20.0), StatsStatus::Warning);
+        assert_eq!(StatsStatus::from_mod_pct(49.9), StatsStatus::Warning);
+        assert_eq!(StatsStatus::from_mod_pct(150.0), StatsStatus::Critical);
+        // Freshly analyzed but heavily modified is still critical
+        assert_eq!(
+            StatsStatus::from_days(Some(0.1)).max(StatsStatus::from_mod_pct(80.0)),
+            StatsStatus::Critical
+        );
+    }
+
     #[test]
     fn test_format_days_never() {
         assert_eq!(format_days(None), "never");
@@ -0,0 +1,483 @@
+//! Subset command for pgcrate CLI.
+//!
+//! Extracts a referentially consistent slice of a database: rows matching the
+//! anchor filters, every row they reference (transitively), and the rows that
+//! reference them. Rows pulled in only as parents don't pull in their own
+//! children, which keeps the subset from growing into the whole database.
+//!
+//! Rows are tracked by ctid inside one REPEATABLE READ READ ONLY transaction,
+//! so the walk sees a single snapshot and works against read replicas.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{self, Write};
+use tokio_postgres::Client;
+
+use super::anonymize::{
+    format_number, get_table_columns, get_tables_for_dump, order_tables_by_fk, TableInfo,
+};
+use super::connect;
+use crate::anonymize::parse_table_name;
+use crate::sql::quote_ident;
+
+/// A foreign key between two tables, by qualified name
+#[derive(Debug, Clone)]
+pub(crate) struct ForeignKey {
+    pub child: String,
+    pub parent: String,
+    pub child_columns: Vec<String>,
+    pub parent_columns: Vec<String>,
+}
+
+impl ForeignKey {
+    /// `child.a = parent.x AND ...` for the given table aliases
+    pub fn join_condition(&self, child_alias: &str, parent_alias: &str) -> String {
+        self.child_columns
+            .iter()
+            .zip(&self.parent_columns)
+            .map(|(c, p)| {
+                format!(
+                    "{}.{} = {}.{}",
+                    child_alias,
+                    quote_ident(c),
+                    parent_alias,
+                    quote_ident(p)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+}
+
+/// Rows selected so far, as ctids per qualified table name
+#[derive(Default)]
+struct Selection {
+    rows: HashMap<String, HashSet<String>>,
+    /// Rows whose children have been pulled in
+    expanded: HashMap<String, HashSet<String>>,
+}
+
+impl Selection {
+    /// Add rows to a table, returning the ones that weren't selected yet
+    fn add(&mut self, table: &str, ctids: Vec<String>) -> Vec<String> {
+        let rows = self.rows.entry(table.to_string()).or_default();
+        ctids
+            .into_iter()
+            .filter(|c| rows.insert(c.clone()))
+            .collect()
+    }
+
+    /// Mark rows as expanded, returning the ones that weren't yet
+    fn expand(&mut self, table: &str, ctids: &[String]) -> Vec<String> {
+        let expanded = self.expanded.entry(table.to_string()).or_default();
+        ctids
+            .iter()
+            .filter(|c| expanded.insert((*c).clone()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Extract a referentially consistent subset of `from_url` into a SQL dump
+///
+/// `filters[i]` is the WHERE clause for `anchors[i]`; anchors without one
+/// contribute all of their rows.
+pub async fn subset(
+    from_url: &str,
+    anchors: &[String],
+    filters: &[String],
+    output: Option<&std::path::Path>,
+    dry_run: bool,
+    quiet: bool,
+) -> Result<()> {
+    if filters.len() > anchors.len() {
+        bail!(
+            "{} --where filters for {} --anchor tables. Each --where applies to the --anchor at the same position.",
+            filters.len(),
+            anchors.len()
+        );
+    }
+
+    let client = connect(from_url)
+        .await
+        .context("Failed to connect to source database")?;
+    client
+        .batch_execute("BEGIN ISOLATION LEVEL REPEATABLE READ READ ONLY")
+        .await?;
+
+    let tables = get_tables_for_dump(&client, &HashSet::new()).await?;
+    let known: HashSet<String> = tables.iter().map(|t| t.qualified()).collect();
+    let foreign_keys = load_foreign_keys(&client, &known).await?;
+
+    let mut selection = Selection::default();
+    let mut queue: VecDeque<(String, Vec<String>, bool)> = VecDeque::new();
+    for (i, anchor) in anchors.iter().enumerate() {
+        let (schema, name) = parse_table_name(anchor);
+        let table = format!("{}.{}", schema, name);
+        if !known.contains(&table) {
+            bail!("Anchor table not found: {}", table);
+        }
+        let filter = filters.get(i).map(String::as_str).unwrap_or("true");
+        let sql = format!(
+            "SELECT ctid::text FROM {}.{} WHERE {}",
+            quote_ident(&schema),
+            quote_ident(&name),
+            filter
+        );
+        let ctids: Vec<String> = client
+            .query(&sql, &[])
+            .await
+            .with_context(|| format!("Failed to select anchor rows from {}", table))?
+            .iter()
+            .map(|r| r.get(0))
+            .collect();
+        selection.add(&table, ctids.clone());
+        queue.push_back((table, ctids, true));
+    }
+
+    if !quiet {
+        eprintln!("Walking foreign keys...");
+    }
+    while let Some((table, ctids, down)) = queue.pop_front() {
+        // Parents are always required
+        for fk in foreign_keys.iter().filter(|fk| fk.child == table) {
+            let parents = related_rows(
+                &client,
+                &table,
+                &ctids,
+                &fk.parent,
+                &fk.join_condition("s", "t"),
+            )
+            .await?;
+            let new = selection.add(&fk.parent, parents);
+            if !new.is_empty() {
+                queue.push_back((fk.parent.clone(), new, false));
+            }
+        }
+
+        // Children only for anchor rows and rows reached through children
+        if !down {
+            continue;
+        }
+        let ctids = selection.expand(&table, &ctids);
+        if ctids.is_empty() {
+            continue;
+        }
+        for fk in foreign_keys.iter().filter(|fk| fk.parent == table) {
+            let children = related_rows(
+                &client,
+                &table,
+                &ctids,
+                &fk.child,
+                &fk.join_condition("t", "s"),
+            )
+            .await?;
+            selection.add(&fk.child, children.clone());
+            let unexpanded: Vec<String> = children
+                .into_iter()
+                .filter(|c| {
+                    !selection
+                        .expanded
+                        .get(&fk.child)
+                        .is_some_and(|e| e.contains(c))
+                })
+                .collect();
+            if !unexpanded.is_empty() {
+                queue.push_back((fk.child.clone(), unexpanded, true));
+            }
+        }
+    }
+
+    let selected: Vec<TableInfo> = tables
+        .into_iter()
+        .filter(|t| {
+            selection
+                .rows
+                .get(&t.qualified())
+                .is_some_and(|r| !r.is_empty())
+        })
+        .collect();
+    let ordered = order_tables_by_fk(&client, &selected).await?;
+    let total_rows: usize = selection.rows.values().map(|r| r.len()).sum();
+
+    if dry_run {
+        if !quiet {
+            println!("Subset Preview");
+            println!("{}", "─".repeat(53));
+            for table in &ordered {
+                println!(
+                    "  {:<40} {:>10} rows",
+                    table.qualified(),
+                    format_number(selection.rows[&table.qualified()].len() as i64)
+                );
+            }
+            println!();
+            println!(
+                "Summary: {} rows across {} tables",
+                format_number(total_rows as i64),
+                ordered.len()
+            );
+        }
+        client.batch_execute("COMMIT").await?;
+        return Ok(());
+    }
+
+    let is_stdout = output.is_none() || output == Some(std::path::Path::new("-"));
+    let mut writer: Box<dyn Write> = if is_stdout {
+        Box::new(io::stdout())
+    } else {
+        Box::new(std::fs::File::create(output.unwrap())?)
+    };
+
+    writeln!(writer, "-- pgcrate subset dump")?;
+    writeln!(writer, "-- Generated: {}", chrono::Utc::now().to_rfc3339())?;
+    writeln!(writer, "-- pgcrate version: {}", env!("CARGO_PKG_VERSION"))?;
+    for (i, anchor) in anchors.iter().enumerate() {
+        match filters.get(i) {
+            Some(filter) => writeln!(writer, "-- Anchor: {} WHERE {}", anchor, filter)?,
+            None => writeln!(writer, "-- Anchor: {}", anchor)?,
+        }
+    }
+    writeln!(writer, "--")?;
+    writeln!(writer)?;
+
+    for table in &ordered {
+        let ctids = &selection.rows[&table.qualified()];
+        if !quiet {
+            eprintln!(
+                "  {} ({} rows)",
+                table.qualified(),
+                format_number(ctids.len() as i64)
+            );
+        }
+        write_table(&client, table, ctids, &mut *writer).await?;
+    }
+    for table in &ordered {
+        write_sequence_resets(&client, table, &mut *writer).await?;
+    }
+    writer.flush()?;
+    client.batch_execute("COMMIT").await?;
+
+    if !quiet {
+        let summary = format!(
+            "{} rows across {} tables",
+            format_number(total_rows as i64),
+            ordered.len()
+        );
+        if is_stdout {
+            eprintln!("Subset complete: {}", summary);
+        } else {
+            println!();
+            println!(
+                "{}",
+                format!(
+                    "Subset dump saved: {} ({})",
+                    output.unwrap().display(),
+                    summary
+                )
+                .green()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Foreign keys between the given tables, with their column pairs
+pub(crate) async fn load_foreign_keys(
+    client: &Client,
+    tables: &HashSet<String>,
+) -> Result<Vec<ForeignKey>> {
+    let rows = client
+        .query(
+            "SELECT
+                cn.nspname AS child_schema,
+                cc.relname AS child_table,
+                pn.nspname AS parent_schema,
+                pc.relname AS parent_table,
+                ARRAY(SELECT a.attname::text
+                      FROM unnest(c.conkey) WITH ORDINALITY k(attnum, i)
+                      JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+                      ORDER BY k.i) AS child_columns,
+                ARRAY(SELECT a.attname::text
+                      FROM unnest(c.confkey) WITH ORDINALITY k(attnum, i)
+                      JOIN pg_attribute a ON a.attrelid = c.confrelid AND a.attnum = k.attnum
+                      ORDER BY k.i) AS parent_columns
+             FROM pg_constraint c
+             JOIN pg_class cc ON cc.oid = c.conrelid
+             JOIN pg_namespace cn ON cn.oid = cc.relnamespace
+             JOIN pg_class pc ON pc.oid = c.confrelid
+             JOIN pg_namespace pn ON pn.oid = pc.relnamespace
+             WHERE c.contype = 'f'
+             ORDER BY cn.nspname, cc.relname, c.conname",
+            &[],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ForeignKey {
+            child: format!(
+                "{}.{}",
+                row.get::<_, String>("child_schema"),
+                row.get::<_, String>("child_table")
+            ),
+            parent: format!(
+                "{}.{}",
+                row.get::<_, String>("parent_schema"),
+                row.get::<_, String>("parent_table")
+            ),
+            child_columns: row.get("child_columns"),
+            parent_columns: row.get("parent_columns"),
+        })
+        .filter(|fk| tables.contains(&fk.child) && tables.contains(&fk.parent))
+        .collect())
+}
+
+/// `schema.table` quoted for SQL
+pub(crate) fn quote_table(qualified: &str) -> String {
+    let (schema, name) = parse_table_name(qualified);
+    format!("{}.{}", quote_ident(&schema), quote_ident(&name))
+}
+
+/// ctids of rows in `to` joined (by `condition`, aliases `s` and `t`) to the
+/// given rows of `from`
+async fn related_rows(
+    client: &Client,
+    from: &str,
+    ctids: &[String],
+    to: &str,
+    condition: &str,
+) -> Result<Vec<String>> {
+    let sql = format!(
+        "SELECT t.ctid::text FROM {} t
+         WHERE EXISTS (SELECT 1 FROM {} s WHERE s.ctid = ANY($1::text[]::tid[]) AND {})",
+        quote_table(to),
+        quote_table(from),
+        condition
+    );
+    let rows = client.query(&sql, &[&ctids]).await?;
+    Ok(rows.iter().map(|r| r.get(0)).collect())
+}
+
+/// COPY block with the selected rows of one table
+async fn write_table(
+    client: &Client,
+    table: &TableInfo,
+    ctids: &HashSet<String>,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    use futures_util::StreamExt;
+
+    let columns = get_table_columns(client, &table.schema, &table.name).await?;
+    let col_list: Vec<String> = columns.iter().map(|c| quote_ident(c)).collect();
+    let table_sql = format!(
+        "{}.{}",
+        quote_ident(&table.schema),
+        quote_ident(&table.name)
+    );
+    writeln!(
+        writer,
+        "COPY {} ({}) FROM stdin;",
+        table_sql,
+        col_list.join(", ")
+    )?;
+
+    // ctids look like (0,1): safe to inline as quoted array elements
+    let tid_array = ctids
+        .iter()
+        .map(|c| format!("\"{}\"", c))
+        .collect::<Vec<_>>()
+        .join(",");
+    let copy_sql = format!(
+        "COPY (SELECT {} FROM {} WHERE ctid = ANY('{{{}}}'::tid[]) ORDER BY ctid) TO STDOUT",
+        col_list.join(", "),
+        table_sql,
+        tid_array
+    );
+    let copy_stream = client.copy_out(&copy_sql).await?;
+    tokio::pin!(copy_stream);
+    while let Some(chunk) = copy_stream.next().await {
+        writer.write_all(&chunk?)?;
+    }
+
+    writeln!(writer, "\\.")?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Move serial/identity sequences past the loaded rows
+async fn write_sequence_resets(
+    client: &Client,
+    table: &TableInfo,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    let table_sql = format!(
+        "{}.{}",
+        quote_ident(&table.schema),
+        quote_ident(&table.name)
+    );
+    let rows = client
+        .query(
+            "SELECT attname::text
+             FROM pg_attribute
+             WHERE attrelid = $1::text::regclass
+               AND attnum > 0
+               AND NOT attisdropped
+               AND pg_get_serial_sequence($1, attname) IS NOT NULL
+             ORDER BY attnum",
+            &[&table_sql],
+        )
+        .await?;
+    for row in rows {
+        let column: String = row.get(0);
+        writeln!(
+            writer,
+            "SELECT setval(pg_get_serial_sequence('{}', '{}'), COALESCE(MAX({}), 0) + 1, false) FROM {};",
+            table_sql.replace('\'', "''"),
+            column.replace('\'', "''"),
+            quote_ident(&column),
+            table_sql
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_condition() {
+        let fk = ForeignKey {
+            child: "app.order_items".to_string(),
+            parent: "app.orders".to_string(),
+            child_columns: vec!["order_id".to_string(), "tenant".to_string()],
+            parent_columns: vec!["id".to_string(), "tenant".to_string()],
+        };
+        assert_eq!(
+            fk.join_condition("s", "t"),
+            r#"s."order_id" = t."id" AND s."tenant" = t."tenant""#
+        );
+    }
+
+    #[test]
+    fn test_selection_tracks_new_rows() {
+        let mut selection = Selection::default();
+        let rows = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            selection.add("app.users", rows(&["(0,1)", "(0,2)"])),
+            rows(&["(0,1)", "(0,2)"])
+        );
+        assert_eq!(
+            selection.add("app.users", rows(&["(0,2)", "(0,3)"])),
+            rows(&["(0,3)"])
+        );
+        assert_eq!(
+            selection.expand("app.users", &rows(&["(0,1)"])),
+            rows(&["(0,1)"])
+        );
+        assert!(selection.expand("app.users", &rows(&["(0,1)"])).is_empty());
+    }
+}
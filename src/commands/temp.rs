@@ -0,0 +1,430 @@
+//! Temp command: Temp file usage and work_mem spills.
+//!
+//! Sorts, hashes and materializations that outgrow work_mem spill to temp
+//! files on disk. This command sums temp file writes per database from
+//! pg_stat_database and per query from pg_stat_statements, and says for each
+//! spilling query whether a larger work_mem would keep it in memory or the
+//! query itself needs an index or a rewrite.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio_postgres::Client;
+
+use super::queries::check_extension;
+use crate::snapshot::format_bytes;
+
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+const MB: f64 = 1024.0 * 1024.0;
+
+/// Temp bytes written per hour, per database
+const DATABASE_RATE_WARNING: f64 = GB;
+const DATABASE_RATE_CRITICAL: f64 = 10.0 * GB;
+/// Temp bytes written by one query, in total and per call
+const QUERY_TOTAL_WARNING: f64 = GB;
+const QUERY_PER_CALL_CRITICAL: f64 = GB;
+/// Largest work_mem suggested; bigger spills need a different plan
+const MAX_SUGGESTED_WORK_MEM: f64 = 256.0 * MB;
+/// Shortest window a rate is estimated over
+const MIN_RATE_WINDOW_SECS: f64 = 3600.0;
+
+/// Temp usage status level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TempStatus {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl TempStatus {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            TempStatus::Healthy => "✓",
+            TempStatus::Warning => "⚠",
+            TempStatus::Critical => "✗",
+        }
+    }
+
+    fn from_database_rate(bytes_per_hour: f64) -> Self {
+        if bytes_per_hour >= DATABASE_RATE_CRITICAL {
+            TempStatus::Critical
+        } else if bytes_per_hour >= DATABASE_RATE_WARNING {
+            TempStatus::Warning
+        } else {
+            TempStatus::Healthy
+        }
+    }
+}
+
+/// Settings that govern temp file use
+#[derive(Debug, Clone, Serialize)]
+pub struct TempSettings {
+    pub work_mem_bytes: i64,
+    /// Per-session temp file cap (None = unlimited)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temp_file_limit_bytes: Option<i64>,
+    /// Temp files at least this large are logged (None = off)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_temp_files_bytes: Option<i64>,
+}
+
+/// Temp files written in one database since its stats reset
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseTemp {
+    pub database: String,
+    pub temp_files: i64,
+    pub temp_bytes: i64,
+    pub bytes_per_hour: f64,
+    pub since_secs: f64,
+    pub current: bool,
+    pub status: TempStatus,
+}
+
+/// A query that spills to temp files
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryTemp {
+    pub queryid: i64,
+    pub query: String,
+    pub calls: i64,
+    pub mean_exec_time_ms: f64,
+    pub temp_bytes_written: i64,
+    pub temp_bytes_per_call: f64,
+    /// work_mem that would likely keep one call in memory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_work_mem_bytes: Option<i64>,
+    pub suggestion: String,
+    pub status: TempStatus,
+}
+
+/// Full temp usage results
+#[derive(Debug, Serialize)]
+pub struct TempResult {
+    pub settings: TempSettings,
+    pub databases: Vec<DatabaseTemp>,
+    pub queries: Vec<QueryTemp>,
+    /// Whether per-query data came from pg_stat_statements
+    pub statements_available: bool,
+    pub overall_status: TempStatus,
+}
+
+async fn get_settings(client: &Client) -> Result<TempSettings> {
+    let row = client
+        .query_one(
+            "SELECT
+                 (SELECT setting::bigint * 1024 FROM pg_settings WHERE name = 'work_mem'),
+                 (SELECT setting::bigint FROM pg_settings WHERE name = 'temp_file_limit'),
+                 (SELECT setting::bigint FROM pg_settings WHERE name = 'log_temp_files')",
+            &[],
+        )
+        .await
+        .context("Failed to read temp file settings")?;
+    let temp_file_limit: i64 = row.get(1);
+    let log_temp_files: i64 = row.get(2);
+    Ok(TempSettings {
+        work_mem_bytes: row.get(0),
+        temp_file_limit_bytes: (temp_file_limit >= 0).then_some(temp_file_limit * 1024),
+        log_temp_files_bytes: (log_temp_files >= 0).then_some(log_temp_files * 1024),
+    })
+}
+
+/// Databases that have written temp files, busiest first
+pub(crate) async fn get_database_temp(client: &Client) -> Result<Vec<DatabaseTemp>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                datname::text,
+                temp_files,
+                temp_bytes,
+                extract(epoch FROM now() - coalesce(stats_reset, pg_postmaster_start_time()))::float8
+                    AS since_secs,
+                datname = current_database() AS current
+            FROM pg_stat_database
+            WHERE datname IS NOT NULL AND temp_files > 0
+            ORDER BY temp_bytes DESC
+            "#,
+            &[],
+        )
+        .await
+        .context("Failed to query pg_stat_database")?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let temp_bytes: i64 = row.get("temp_bytes");
+            let since_secs: f64 = row.get("since_secs");
+            let bytes_per_hour = temp_bytes as f64 * 3600.0 / since_secs.max(MIN_RATE_WINDOW_SECS);
+            DatabaseTemp {
+                database: row.get(0),
+                temp_files: row.get("temp_files"),
+                temp_bytes,
+                bytes_per_hour,
+                since_secs,
+                current: row.get("current"),
+                status: TempStatus::from_database_rate(bytes_per_hour),
+            }
+        })
+        .collect())
+}
+
+async fn get_query_temp(client: &Client, work_mem: i64, limit: usize) -> Result<Vec<QueryTemp>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                queryid,
+                LEFT(query, 500) AS query,
+                calls,
+                mean_exec_time,
+                (temp_blks_written * current_setting('block_size')::bigint)::bigint
+                    AS temp_bytes_written
+            FROM pg_stat_statements
+            WHERE temp_blks_written > 0
+              AND calls > 0
+              AND query NOT LIKE '%pg_stat_statements%'
+            ORDER BY temp_blks_written DESC
+            LIMIT $1
+            "#,
+            &[&(limit as i64)],
+        )
+        .await
+        .context("Failed to query pg_stat_statements")?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let calls: i64 = row.get("calls");
+            let temp_bytes_written: i64 = row.get("temp_bytes_written");
+            let per_call = temp_bytes_written as f64 / calls as f64;
+            let suggested_work_mem_bytes = suggest_work_mem(per_call, work_mem);
+            QueryTemp {
+                queryid: row.get("queryid"),
+                query: row.get("query"),
+                calls,
+                mean_exec_time_ms: row.get("mean_exec_time"),
+                temp_bytes_written,
+                temp_bytes_per_call: per_call,
+                suggested_work_mem_bytes,
+                suggestion: suggestion_text(per_call, suggested_work_mem_bytes),
+                status: query_status(temp_bytes_written as f64, per_call),
+            }
+        })
+        .collect())
+}
+
+fn query_status(total_bytes: f64, per_call: f64) -> TempStatus {
+    if per_call >= QUERY_PER_CALL_CRITICAL {
+        TempStatus::Critical
+    } else if total_bytes >= QUERY_TOTAL_WARNING {
+        TempStatus::Warning
+    } else {
+        TempStatus::Healthy
+    }
+}
+
+/// A work_mem that fits one call's spill: twice the bytes written (in-memory
+/// sorts and hashes take more room than their temp files), rounded up to a
+/// power-of-two number of MB. None when that exceeds MAX_SUGGESTED_WORK_MEM.
+fn suggest_work_mem(per_call: f64, work_mem: i64) -> Option<i64> {
+    let needed = (per_call * 2.0).max(work_mem as f64 * 2.0);
+    if needed > MAX_SUGGESTED_WORK_MEM {
+        return None;
+    }
+    let mb = (needed / MB).ceil().max(1.0) as u64;
+    Some(mb.next_power_of_two() as i64 * MB as i64)
+}
+
+fn suggestion_text(per_call: f64, suggested_work_mem: Option<i64>) -> String {
+    let spill = format_bytes(per_call.max(0.0) as u64);
+    match suggested_work_mem {
+        Some(bytes) => format!(
+            "Spills {} per call; SET work_mem = '{}MB' for the role or session running it",
+            spill,
+            bytes / MB as i64
+        ),
+        None => format!(
+            "Spills {} per call, too much for work_mem; add an index matching its ORDER BY or GROUP BY, or cut the rows it sorts or hashes",
+            spill
+        ),
+    }
+}
+
+/// Run temp file analysis
+pub async fn run_temp(client: &Client, limit: usize) -> Result<TempResult> {
+    let settings = get_settings(client).await?;
+    let databases = get_database_temp(client).await?;
+
+    let statements_available = check_extension(client).await?;
+    let queries = if statements_available {
+        get_query_temp(client, settings.work_mem_bytes, limit).await?
+    } else {
+        vec![]
+    };
+
+    let overall_status = databases
+        .iter()
+        .map(|d| d.status)
+        .chain(queries.iter().map(|q| q.status))
+        .max()
+        .unwrap_or(TempStatus::Healthy);
+
+    Ok(TempResult {
+        settings,
+        databases,
+        queries,
+        statements_available,
+        overall_status,
+    })
+}
+
+/// Truncate query for display
+fn truncate_query(query: &str, max_len: usize) -> String {
+    let clean = query.split_whitespace().collect::<Vec<_>>().join(" ");
+    if clean.chars().count() <= max_len {
+        clean
+    } else {
+        format!("{}...", clean.chars().take(max_len - 3).collect::<String>())
+    }
+}
+
+/// Print temp file analysis in human-readable format
+pub fn print_human(result: &TempResult, quiet: bool) {
+    let settings = &result.settings;
+
+    println!("TEMP FILES");
+    println!("==========");
+    println!();
+    println!(
+        "  work_mem:              {}",
+        format_bytes(settings.work_mem_bytes.max(0) as u64)
+    );
+    println!(
+        "  temp_file_limit:       {}",
+        settings
+            .temp_file_limit_bytes
+            .map(|b| format_bytes(b as u64))
+            .unwrap_or_else(|| "unlimited".to_string())
+    );
+    println!(
+        "  log_temp_files:        {}",
+        settings
+            .log_temp_files_bytes
+            .map(|b| format!("files of {} or more", format_bytes(b as u64)))
+            .unwrap_or_else(|| "off".to_string())
+    );
+    println!();
+
+    println!("By database:");
+    if result.databases.is_empty() {
+        println!("  No temp files written since stats reset.");
+    } else {
+        println!(
+            "  {:3} {:30} {:>10} {:>12} {:>12}",
+            "", "DATABASE", "FILES", "WRITTEN", "PER HOUR"
+        );
+        for db in &result.databases {
+            println!(
+                "  {} {:30} {:>10} {:>12} {:>12}",
+                db.status.emoji(),
+                if db.current {
+                    format!("{} (current)", db.database)
+                } else {
+                    db.database.clone()
+                },
+                db.temp_files,
+                format_bytes(db.temp_bytes.max(0) as u64),
+                format_bytes(db.bytes_per_hour as u64)
+            );
+        }
+    }
+    println!();
+
+    println!("By query:");
+    if !result.statements_available {
+        println!(
+            "  pg_stat_statements is not installed; run `pgcrate dba queries` for setup steps."
+        );
+    } else if result.queries.is_empty() {
+        println!("  No queries have spilled to temp files.");
+    } else {
+        for q in &result.queries {
+            println!(
+                "  {} {:>10} written, {} calls, {} per call",
+                q.status.emoji(),
+                format_bytes(q.temp_bytes_written.max(0) as u64),
+                q.calls,
+                format_bytes(q.temp_bytes_per_call as u64)
+            );
+            println!("      {}", truncate_query(&q.query, 100));
+            if !quiet {
+                println!("      → {}", q.suggestion);
+            }
+        }
+    }
+}
+
+/// Print temp file analysis as JSON with schema versioning.
+pub fn print_json(
+    result: &TempResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = match result.overall_status {
+        TempStatus::Healthy => Severity::Healthy,
+        TempStatus::Warning => Severity::Warning,
+        TempStatus::Critical => Severity::Critical,
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::TEMP, result, severity, t),
+        None => DiagnosticOutput::new(schema::TEMP, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_status_from_rate() {
+        assert_eq!(
+            TempStatus::from_database_rate(100.0 * MB),
+            TempStatus::Healthy
+        );
+        assert_eq!(
+            TempStatus::from_database_rate(2.0 * GB),
+            TempStatus::Warning
+        );
+        assert_eq!(
+            TempStatus::from_database_rate(20.0 * GB),
+            TempStatus::Critical
+        );
+    }
+
+    #[test]
+    fn test_query_status() {
+        assert_eq!(query_status(10.0 * MB, MB), TempStatus::Healthy);
+        assert_eq!(query_status(5.0 * GB, MB), TempStatus::Warning);
+        assert_eq!(query_status(5.0 * GB, 2.0 * GB), TempStatus::Critical);
+    }
+
+    #[test]
+    fn test_suggest_work_mem() {
+        let work_mem = 4 * MB as i64;
+        // 3MB spilled needs ~6MB, at least twice the current 4MB → 8MB
+        assert_eq!(suggest_work_mem(3.0 * MB, work_mem), Some(8 * MB as i64));
+        // 20MB spilled → 40MB → 64MB
+        assert_eq!(suggest_work_mem(20.0 * MB, work_mem), Some(64 * MB as i64));
+        // 500MB spilled is past what work_mem should cover
+        assert_eq!(suggest_work_mem(500.0 * MB, work_mem), None);
+    }
+
+    #[test]
+    fn test_suggestion_text() {
+        assert!(suggestion_text(20.0 * MB, Some(64 * MB as i64)).contains("work_mem = '64MB'"));
+        assert!(suggestion_text(500.0 * MB, None).contains("add an index"));
+    }
+}
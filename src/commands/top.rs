@@ -0,0 +1,564 @@
+//! Top command: Live terminal monitor for sessions, locks, and throughput.
+//!
+//! Redraws every `--interval` seconds with active sessions from
+//! pg_stat_activity, blocking chains (`dba locks`), TPS and cache hit ratio
+//! over the last interval (`dba cache`), and replica lag (`dba replication`).
+//! With --read-write and --primary, the selected backend can be cancelled or
+//! terminated from the keyboard. `--once` prints a single frame for scripts and pipes.
+
+use std::io::Write;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use console::{Key, Term};
+use tokio_postgres::Client;
+
+use super::cache::get_database_cache_stats;
+use super::locks::{get_blocking_chains, signal_backend};
+use super::replication::{get_replication, ReplicationResult, ServerRole};
+
+/// How often the key channel is polled between refreshes
+const KEY_POLL: Duration = Duration::from_millis(50);
+/// Frame size when not drawing to a terminal
+const DEFAULT_SIZE: (usize, usize) = (40, 120);
+
+/// An active client session
+#[derive(Debug, Clone)]
+pub struct TopSession {
+    pub pid: i32,
+    pub usename: String,
+    pub application_name: String,
+    pub state: String,
+    pub wait: Option<String>,
+    pub duration_seconds: i64,
+    pub query: String,
+    pub blocked_by: Vec<i32>,
+}
+
+/// Cumulative counters, diffed between refreshes
+#[derive(Debug, Clone, Copy)]
+struct Counters {
+    xacts: i64,
+    blks_hit: i64,
+    blks_read: i64,
+    at: Instant,
+}
+
+/// One refresh worth of data
+#[derive(Debug)]
+pub struct TopSnapshot {
+    pub database: String,
+    pub sessions: Vec<TopSession>,
+    pub connections: i64,
+    pub max_connections: i64,
+    pub blocked_sessions: usize,
+    pub blocking_roots: Vec<i32>,
+    pub tps: Option<f64>,
+    pub cache_hit_pct: f64,
+    pub interval_cache_hit_pct: Option<f64>,
+    pub replication: ReplicationResult,
+    counters: Counters,
+}
+
+async fn get_sessions(client: &Client, redact: bool) -> Result<Vec<TopSession>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                pid,
+                coalesce(usename::text, '') AS usename,
+                coalesce(application_name, '') AS application_name,
+                coalesce(state, '') AS state,
+                wait_event_type || ':' || wait_event AS wait,
+                extract(epoch FROM now() - coalesce(query_start, backend_start))::bigint
+                    AS duration_seconds,
+                left(query, 500) AS query,
+                pg_blocking_pids(pid) AS blocked_by
+            FROM pg_stat_activity
+            WHERE backend_type = 'client backend'
+              AND pid <> pg_backend_pid()
+              AND state IS DISTINCT FROM 'idle'
+            ORDER BY query_start NULLS LAST
+            "#,
+            &[],
+        )
+        .await
+        .context("Failed to query pg_stat_activity")?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let query: String = row.get::<_, Option<String>>("query").unwrap_or_default();
+            TopSession {
+                pid: row.get("pid"),
+                usename: row.get("usename"),
+                application_name: row.get("application_name"),
+                state: row.get("state"),
+                wait: row.get("wait"),
+                duration_seconds: row.get::<_, Option<i64>>("duration_seconds").unwrap_or(0),
+                query: if redact {
+                    crate::redact::redact_query(&query)
+                } else {
+                    query
+                },
+                blocked_by: row.get("blocked_by"),
+            }
+        })
+        .collect())
+}
+
+/// Collect one snapshot; rates are computed against `prev` when given
+pub async fn collect(
+    client: &Client,
+    prev: Option<&TopSnapshot>,
+    redact: bool,
+) -> Result<TopSnapshot> {
+    let sessions = get_sessions(client, redact).await?;
+    let chains = get_blocking_chains(client).await?;
+    // Statuses aren't shown, so the alert levels don't matter here
+    let cache = get_database_cache_stats(client, super::cache::DEFAULT_THRESHOLDS).await?;
+    let replication = get_replication(client, &Default::default()).await?;
+
+    let row = client
+        .query_one(
+            r#"
+            SELECT
+                (SELECT coalesce(xact_commit + xact_rollback, 0)::bigint
+                 FROM pg_stat_database WHERE datname = current_database()),
+                (SELECT count(*) FROM pg_stat_activity WHERE backend_type = 'client backend'),
+                current_setting('max_connections')::bigint
+            "#,
+            &[],
+        )
+        .await
+        .context("Failed to query pg_stat_database")?;
+
+    let counters = Counters {
+        xacts: row.get(0),
+        blks_hit: cache.blks_hit,
+        blks_read: cache.blks_read,
+        at: Instant::now(),
+    };
+    let (tps, interval_cache_hit_pct) = match prev {
+        Some(p) => rates(&p.counters, &counters),
+        None => (None, None),
+    };
+
+    Ok(TopSnapshot {
+        database: cache.database,
+        sessions,
+        connections: row.get(1),
+        max_connections: row.get(2),
+        blocked_sessions: chains.iter().map(|c| c.total_blocked as usize).sum(),
+        blocking_roots: chains.iter().map(|c| c.root.pid).collect(),
+        tps,
+        cache_hit_pct: cache.hit_ratio_pct,
+        interval_cache_hit_pct,
+        replication,
+        counters,
+    })
+}
+
+/// TPS and cache hit ratio between two samples
+fn rates(prev: &Counters, now: &Counters) -> (Option<f64>, Option<f64>) {
+    let secs = now.at.duration_since(prev.at).as_secs_f64();
+    if secs <= 0.0 {
+        return (None, None);
+    }
+    let tps = (now.xacts - prev.xacts).max(0) as f64 / secs;
+    let hits = (now.blks_hit - prev.blks_hit).max(0);
+    let reads = (now.blks_read - prev.blks_read).max(0);
+    let hit_pct = (hits + reads > 0).then(|| 100.0 * hits as f64 / (hits + reads) as f64);
+    (Some(tps), hit_pct)
+}
+
+/// What a key press asks for
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    None,
+    Quit,
+    Cancel(i32),
+    Terminate(i32),
+}
+
+/// Selection, pending confirmation, and the status line
+#[derive(Debug, Default)]
+struct UiState {
+    selected: usize,
+    pending_terminate: Option<i32>,
+    message: Option<String>,
+    allow_signals: bool,
+}
+
+impl UiState {
+    fn handle_key(&mut self, key: &Key, sessions: &[TopSession]) -> Action {
+        if let Some(pid) = self.pending_terminate.take() {
+            self.message = None;
+            return match key {
+                Key::Char('y') | Key::Char('Y') => Action::Terminate(pid),
+                _ => Action::None,
+            };
+        }
+
+        let selected = sessions.get(self.selected).map(|s| s.pid);
+        match key {
+            Key::Char('q') | Key::Escape => Action::Quit,
+            Key::ArrowUp => {
+                self.selected = self.selected.saturating_sub(1);
+                Action::None
+            }
+            Key::ArrowDown => {
+                if self.selected + 1 < sessions.len() {
+                    self.selected += 1;
+                }
+                Action::None
+            }
+            Key::Char('c') | Key::Char('k') if !self.allow_signals => {
+                self.message = Some("Cancel and terminate need --read-write and --primary".into());
+                Action::None
+            }
+            Key::Char('c') => selected.map_or(Action::None, Action::Cancel),
+            Key::Char('k') => {
+                if let Some(pid) = selected {
+                    self.pending_terminate = Some(pid);
+                    self.message = Some(format!("Terminate backend {}? (y/n)", pid));
+                }
+                Action::None
+            }
+            _ => Action::None,
+        }
+    }
+}
+
+fn format_duration(seconds: i64) -> String {
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3600 {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h{:02}m", seconds / 3600, (seconds % 3600) / 60)
+    }
+}
+
+fn fit(s: &str, width: usize) -> String {
+    let clean = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    if clean.chars().count() <= width {
+        clean
+    } else {
+        clean
+            .chars()
+            .take(width.saturating_sub(1))
+            .collect::<String>()
+            + "…"
+    }
+}
+
+fn replication_line(r: &ReplicationResult) -> String {
+    match r.server_role {
+        ServerRole::Standby => match &r.wal_receiver {
+            Some(wr) => format!("Replication: standby, receiver {}", wr.status),
+            None => "Replication: standby, no WAL receiver".to_string(),
+        },
+        ServerRole::Primary if r.replicas.is_empty() => {
+            format!("Replication: primary, no replicas, {} slots", r.slots.len())
+        }
+        ServerRole::Primary => {
+            let max_lag = r
+                .replicas
+                .iter()
+                .filter_map(|rep| rep.replay_lag_secs)
+                .fold(0.0_f64, f64::max);
+            format!(
+                "Replication: primary, {} replicas, max replay lag {:.1}s, {} slots",
+                r.replicas.len(),
+                max_lag,
+                r.slots.len()
+            )
+        }
+    }
+}
+
+/// Lay out one frame, `height` lines of at most `width` characters
+fn render(
+    snap: &TopSnapshot,
+    ui: &UiState,
+    interval: Duration,
+    size: (usize, usize),
+) -> Vec<String> {
+    let (height, width) = size;
+    let pct = |v: Option<f64>| {
+        v.map(|p| format!("{:.1}%", p))
+            .unwrap_or_else(|| "-".into())
+    };
+
+    let mut lines = vec![
+        format!(
+            "pgcrate dba top — {} — every {}s — {}",
+            snap.database,
+            interval.as_secs(),
+            chrono::Local::now().format("%H:%M:%S")
+        ),
+        format!(
+            "TPS: {}   Cache hit: {} (since reset {:.1}%)   Connections: {}/{}   Blocked: {}",
+            snap.tps
+                .map(|t| format!("{:.1}", t))
+                .unwrap_or_else(|| "-".into()),
+            pct(snap.interval_cache_hit_pct),
+            snap.cache_hit_pct,
+            snap.connections,
+            snap.max_connections,
+            snap.blocked_sessions
+        ),
+        replication_line(&snap.replication),
+        String::new(),
+        format!(
+            "  {:>7} {:12} {:14} {:20} {:22} {:>8}  QUERY",
+            "PID", "USER", "APPLICATION", "STATE", "WAIT", "TIME"
+        ),
+    ];
+
+    // Leave room for the two footer lines
+    let rows = height.saturating_sub(lines.len() + 2);
+    let start = ui.selected.saturating_sub(rows.saturating_sub(1));
+    for (i, s) in snap.sessions.iter().enumerate().skip(start).take(rows) {
+        let marker = if snap.blocking_roots.contains(&s.pid) {
+            "B"
+        } else if !s.blocked_by.is_empty() {
+            "W"
+        } else {
+            " "
+        };
+        let cursor = if i == ui.selected && ui.allow_signals {
+            ">"
+        } else {
+            " "
+        };
+        lines.push(format!(
+            "{}{}{:>7} {:12} {:14} {:20} {:22} {:>8}  {}",
+            cursor,
+            marker,
+            s.pid,
+            fit(&s.usename, 12),
+            fit(&s.application_name, 14),
+            fit(&s.state, 20),
+            fit(s.wait.as_deref().unwrap_or(""), 22),
+            format_duration(s.duration_seconds),
+            fit(&s.query, width.saturating_sub(96).max(20))
+        ));
+    }
+    if snap.sessions.is_empty() {
+        lines.push("  No active sessions.".to_string());
+    }
+
+    while lines.len() + 2 < height {
+        lines.push(String::new());
+    }
+    lines.push(ui.message.clone().unwrap_or_default());
+    lines.push(if ui.allow_signals {
+        "↑/↓ select  c cancel query  k terminate backend  q quit   (B blocking, W waiting)".into()
+    } else {
+        "q quit   (B blocking, W waiting; --read-write --primary enables cancel/terminate)".into()
+    });
+
+    lines.iter().map(|l| fit_line(l, width)).collect()
+}
+
+fn fit_line(line: &str, width: usize) -> String {
+    if line.chars().count() <= width {
+        line.to_string()
+    } else {
+        line.chars().take(width).collect()
+    }
+}
+
+/// Leaves the alternate screen and shows the cursor again when dropped, so
+/// the terminal is restored on quit and on errors alike
+struct AlternateScreen(Term);
+
+impl Drop for AlternateScreen {
+    fn drop(&mut self) {
+        let _ = write!(self.0, "\x1b[?25h\x1b[?1049l");
+        let _ = self.0.flush();
+    }
+}
+
+/// Run the monitor. `allow_signals` enables cancel/terminate keys.
+pub async fn run_top(
+    client: &Client,
+    interval: Duration,
+    allow_signals: bool,
+    redact: bool,
+    once: bool,
+) -> Result<()> {
+    let term = Term::stdout();
+
+    if once || !term.is_term() {
+        // Two samples so TPS and the interval hit ratio have a value
+        let first = collect(client, None, redact).await?;
+        tokio::time::sleep(interval).await;
+        let snap = collect(client, Some(&first), redact).await?;
+        let ui = UiState::default();
+        let height = 5 + snap.sessions.len().max(1) + 2;
+        for line in render(&snap, &ui, interval, (height, DEFAULT_SIZE.1)) {
+            println!("{}", line.trim_end());
+        }
+        return Ok(());
+    }
+
+    let mut snap = collect(client, None, redact).await?;
+    let mut ui = UiState {
+        allow_signals,
+        ..Default::default()
+    };
+
+    // read_key blocks, so keys come from a thread; it stops after a quit key
+    // so the terminal is not left in raw mode
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let term = Term::stdout();
+        loop {
+            let key = term.read_key().unwrap_or(Key::Escape);
+            let quit = matches!(key, Key::Char('q') | Key::Escape);
+            if tx.send(key).is_err() || quit {
+                break;
+            }
+        }
+    });
+
+    let mut out = term.clone();
+    write!(out, "\x1b[?1049h\x1b[?25l")?;
+    let _screen = AlternateScreen(term.clone());
+    let mut next_refresh = Instant::now() + interval;
+    let mut dirty = true;
+
+    loop {
+        while let Ok(key) = rx.try_recv() {
+            dirty = true;
+            let action = ui.handle_key(&key, &snap.sessions);
+            let (pid, terminate) = match action {
+                Action::Quit => return Ok(()),
+                Action::None => continue,
+                Action::Cancel(pid) => (pid, false),
+                Action::Terminate(pid) => (pid, true),
+            };
+            let verb = if terminate { "Terminated" } else { "Cancelled" };
+            ui.message = Some(match signal_backend(client, pid, terminate).await {
+                Ok(true) => format!("{} backend {}", verb, pid),
+                Ok(false) => format!("Backend {} is already gone", pid),
+                Err(e) => format!("Failed to signal {}: {}", pid, e),
+            });
+            next_refresh = Instant::now();
+        }
+
+        if Instant::now() >= next_refresh {
+            match collect(client, Some(&snap), redact).await {
+                Ok(s) => {
+                    snap = s;
+                    ui.selected = ui.selected.min(snap.sessions.len().saturating_sub(1));
+                }
+                Err(e) => ui.message = Some(format!("Refresh failed: {}", e)),
+            }
+            next_refresh = Instant::now() + interval;
+            dirty = true;
+        }
+
+        if dirty {
+            let (rows, cols) = term.size();
+            let frame = render(&snap, &ui, interval, (rows as usize, cols as usize));
+            // Raw mode (while a key read is pending) needs explicit carriage returns
+            write!(out, "\x1b[H\x1b[2J{}", frame.join("\r\n"))?;
+            out.flush()?;
+            dirty = false;
+        }
+
+        tokio::time::sleep(KEY_POLL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(pid: i32) -> TopSession {
+        TopSession {
+            pid,
+            usename: "app".to_string(),
+            application_name: "web".to_string(),
+            state: "active".to_string(),
+            wait: None,
+            duration_seconds: 5,
+            query: "SELECT 1".to_string(),
+            blocked_by: vec![],
+        }
+    }
+
+    #[test]
+    fn test_rates() {
+        let at = Instant::now();
+        let prev = Counters {
+            xacts: 100,
+            blks_hit: 900,
+            blks_read: 100,
+            at,
+        };
+        let now = Counters {
+            xacts: 300,
+            blks_hit: 1_890,
+            blks_read: 110,
+            at: at + Duration::from_secs(2),
+        };
+        let (tps, hit) = rates(&prev, &now);
+        assert!((tps.unwrap() - 100.0).abs() < 0.001);
+        assert!((hit.unwrap() - 99.0).abs() < 0.001);
+
+        // No block access in the interval: no ratio
+        let idle = Counters { xacts: 100, ..prev };
+        let later = Counters {
+            at: at + Duration::from_secs(1),
+            ..prev
+        };
+        assert_eq!(rates(&idle, &later), (Some(0.0), None));
+    }
+
+    #[test]
+    fn test_keys_need_read_write() {
+        let sessions = vec![session(10), session(20)];
+        let mut ui = UiState::default();
+        assert_eq!(ui.handle_key(&Key::Char('c'), &sessions), Action::None);
+        assert!(ui.message.as_deref().unwrap().contains("--read-write"));
+        assert_eq!(ui.handle_key(&Key::Char('q'), &sessions), Action::Quit);
+    }
+
+    #[test]
+    fn test_select_cancel_and_confirm_terminate() {
+        let sessions = vec![session(10), session(20)];
+        let mut ui = UiState {
+            allow_signals: true,
+            ..Default::default()
+        };
+        ui.handle_key(&Key::ArrowDown, &sessions);
+        ui.handle_key(&Key::ArrowDown, &sessions);
+        assert_eq!(ui.selected, 1);
+        assert_eq!(
+            ui.handle_key(&Key::Char('c'), &sessions),
+            Action::Cancel(20)
+        );
+
+        // Terminate asks first; anything but y backs out
+        assert_eq!(ui.handle_key(&Key::Char('k'), &sessions), Action::None);
+        assert_eq!(ui.handle_key(&Key::Char('n'), &sessions), Action::None);
+        assert_eq!(ui.handle_key(&Key::Char('k'), &sessions), Action::None);
+        assert_eq!(
+            ui.handle_key(&Key::Char('y'), &sessions),
+            Action::Terminate(20)
+        );
+    }
+
+    #[test]
+    fn test_fit() {
+        assert_eq!(fit("SELECT\n  1", 20), "SELECT 1");
+        assert_eq!(fit("abcdefgh", 5), "abcd…");
+        assert_eq!(format_duration(3_725), "1h02m");
+    }
+}
@@ -0,0 +1,811 @@
+//! Trend commands: Record diagnostic metrics and compare them over time.
+//!
+//! `dba record` appends triage, bloat, sequence, and XID metrics to
+//! `pgcrate.metrics` in the target database. `dba trend` shows how a metric
+//! moved over a window ("table bloat grew 12% this week"), and `--baseline` /
+//! `--compare` let triage, bloat, sequences, and xid save or diff against a
+//! recording directly.
+//!
+//! Metrics live in the database itself so every host that runs pgcrate sees
+//! the same history. Recording connects read-write, like `dba fix`.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tokio_postgres::Client;
+
+use super::bloat::BloatResult;
+use super::sequences::SequencesResult;
+use super::triage::{CheckStatus, TriageResults};
+use super::xid::XidResult;
+use crate::config::AlertsConfig;
+
+const METRICS_TABLE: &str = r#"
+CREATE SCHEMA IF NOT EXISTS pgcrate;
+CREATE TABLE IF NOT EXISTS pgcrate.metrics (
+    recorded_at TIMESTAMPTZ NOT NULL,
+    baseline TEXT,
+    metric TEXT NOT NULL,
+    object TEXT NOT NULL DEFAULT '',
+    value DOUBLE PRECISION NOT NULL
+);
+CREATE INDEX IF NOT EXISTS metrics_metric_recorded_at_idx
+    ON pgcrate.metrics (metric, recorded_at);
+"#;
+
+/// Bloat rows and tables with the oldest XIDs kept per recording
+const RECORD_LIMIT: usize = 50;
+
+/// How a metric's values are displayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Bytes,
+    Pct,
+    Count,
+}
+
+/// Metrics written by `dba record`: (name, description, unit)
+const METRICS: &[(&str, &str, Unit)] = &[
+    (
+        "triage_status",
+        "triage check status (0 healthy, 1 warning, 2 critical)",
+        Unit::Count,
+    ),
+    ("table_bloat_bytes", "table bloat", Unit::Bytes),
+    ("index_bloat_bytes", "index bloat", Unit::Bytes),
+    ("total_bloat_bytes", "total bloat", Unit::Bytes),
+    ("sequence_pct_used", "sequence usage", Unit::Pct),
+    ("database_xid_age", "database XID age", Unit::Count),
+    ("table_xid_age", "table XID age", Unit::Count),
+];
+
+fn metric_info(metric: &str) -> Option<(&'static str, Unit)> {
+    METRICS
+        .iter()
+        .find(|(name, _, _)| *name == metric)
+        .map(|(_, description, unit)| (*description, *unit))
+}
+
+/// One metric value for one object (table, sequence, database, check)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Sample {
+    pub metric: &'static str,
+    /// Empty for database-wide metrics
+    pub object: String,
+    pub value: f64,
+}
+
+impl Sample {
+    fn new(metric: &'static str, object: impl Into<String>, value: f64) -> Self {
+        Self {
+            metric,
+            object: object.into(),
+            value,
+        }
+    }
+}
+
+pub fn triage_samples(results: &TriageResults) -> Vec<Sample> {
+    results
+        .checks
+        .iter()
+        .map(|c| {
+            let level = match c.status {
+                CheckStatus::Healthy => 0.0,
+                CheckStatus::Warning => 1.0,
+                CheckStatus::Critical => 2.0,
+            };
+            Sample::new("triage_status", c.name, level)
+        })
+        .collect()
+}
+
+pub fn bloat_samples(result: &BloatResult) -> Vec<Sample> {
+    let mut samples: Vec<Sample> = result
+        .tables
+        .iter()
+        .map(|t| {
+            Sample::new(
+                "table_bloat_bytes",
+                format!("{}.{}", t.schema, t.table),
+                t.bloat_bytes as f64,
+            )
+        })
+        .collect();
+    samples.extend(result.indexes.iter().map(|i| {
+        Sample::new(
+            "index_bloat_bytes",
+            format!("{}.{}", i.schema, i.index),
+            i.bloat_bytes as f64,
+        )
+    }));
+    samples.push(Sample::new(
+        "total_bloat_bytes",
+        "",
+        (result.total_table_bloat_bytes + result.total_index_bloat_bytes) as f64,
+    ));
+    samples
+}
+
+pub fn sequence_samples(result: &SequencesResult) -> Vec<Sample> {
+    result
+        .sequences
+        .iter()
+        .map(|s| {
+            Sample::new(
+                "sequence_pct_used",
+                format!("{}.{}", s.schema, s.name),
+                s.pct_used,
+            )
+        })
+        .collect()
+}
+
+pub fn xid_samples(result: &XidResult) -> Vec<Sample> {
+    let mut samples: Vec<Sample> = result
+        .databases
+        .iter()
+        .map(|d| Sample::new("database_xid_age", d.datname.clone(), d.xid_age as f64))
+        .collect();
+    samples.extend(result.tables.iter().map(|t| {
+        Sample::new(
+            "table_xid_age",
+            format!("{}.{}", t.schema, t.table),
+            t.xid_age as f64,
+        )
+    }));
+    samples
+}
+
+/// What a comparison is measured against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reference {
+    /// The first recording inside the last N seconds
+    Since { label: String, secs: u64 },
+    /// The latest recording tagged with this baseline name
+    Baseline(String),
+}
+
+impl Reference {
+    /// "7d" and other durations are windows; anything else names a baseline
+    pub fn parse(spec: &str) -> Self {
+        match crate::diagnostic::parse_duration(spec) {
+            Ok(d) => Reference::Since {
+                label: spec.trim().to_string(),
+                secs: d.as_secs(),
+            },
+            Err(_) => Reference::Baseline(spec.to_string()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Reference::Since { label, .. } => format!("last {}", label),
+            Reference::Baseline(name) => format!("baseline '{}'", name),
+        }
+    }
+}
+
+/// How one metric changed between two points
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricChange {
+    pub metric: String,
+    pub object: String,
+    pub previous: f64,
+    pub previous_at: DateTime<Utc>,
+    pub current: f64,
+    /// When the current value was recorded (absent for a live run)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_at: Option<DateTime<Utc>>,
+    pub delta: f64,
+    /// Relative change; absent when the previous value was zero
+    pub delta_pct: Option<f64>,
+}
+
+impl MetricChange {
+    fn new(
+        metric: String,
+        object: String,
+        (previous, previous_at): (f64, DateTime<Utc>),
+        (current, current_at): (f64, Option<DateTime<Utc>>),
+    ) -> Self {
+        let delta = current - previous;
+        let delta_pct = (previous != 0.0).then(|| delta / previous.abs() * 100.0);
+        Self {
+            metric,
+            object,
+            previous,
+            previous_at,
+            current,
+            current_at,
+            delta,
+            delta_pct,
+        }
+    }
+
+    /// "table bloat on public.orders grew 12.0% (1.2 GB -> 1.3 GB)"
+    pub fn sentence(&self) -> String {
+        let (description, unit) = metric_info(&self.metric).unwrap_or(("value", Unit::Count));
+        let subject = if self.object.is_empty() {
+            description.to_string()
+        } else {
+            format!("{} on {}", description, self.object)
+        };
+        let verb = if self.delta > 0.0 {
+            "grew"
+        } else if self.delta < 0.0 {
+            "shrank"
+        } else {
+            return format!(
+                "{} unchanged at {}",
+                subject,
+                format_value(unit, self.current)
+            );
+        };
+        let amount = match self.delta_pct {
+            Some(pct) => format!("{:.1}%", pct.abs()),
+            None => format_value(unit, self.delta.abs()),
+        };
+        format!(
+            "{} {} {} ({} -> {})",
+            subject,
+            verb,
+            amount,
+            format_value(unit, self.previous),
+            format_value(unit, self.current)
+        )
+    }
+}
+
+/// A live run compared against recorded metrics (`--compare`)
+#[derive(Debug, Clone, Serialize)]
+pub struct Comparison {
+    /// "last 7d" or "baseline 'pre-deploy'"
+    pub against: String,
+    pub changes: Vec<MetricChange>,
+    /// Objects in this run with no recorded value to compare against
+    pub new_objects: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecordResult {
+    pub recorded_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<String>,
+    /// Samples written per metric
+    pub metrics: BTreeMap<String, usize>,
+    /// Sources that failed (e.g. permission denied); the rest were recorded
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrendResult {
+    pub metric: String,
+    pub against: String,
+    pub changes: Vec<MetricChange>,
+}
+
+/// A recorded metric and how much history it has
+#[derive(Debug, Serialize)]
+pub struct MetricSummary {
+    pub metric: String,
+    pub description: Option<&'static str>,
+    pub samples: i64,
+    pub first_at: DateTime<Utc>,
+    pub last_at: DateTime<Utc>,
+}
+
+async fn require_metrics_table(client: &Client) -> Result<()> {
+    let exists: bool = client
+        .query_one("SELECT to_regclass('pgcrate.metrics') IS NOT NULL", &[])
+        .await?
+        .get(0);
+    if !exists {
+        bail!("No metrics recorded yet. Run `pgcrate dba record` first.");
+    }
+    Ok(())
+}
+
+/// Append samples to `pgcrate.metrics` under one timestamp
+pub async fn record_samples(
+    client: &Client,
+    samples: &[Sample],
+    baseline: Option<&str>,
+) -> Result<DateTime<Utc>> {
+    client
+        .batch_execute(METRICS_TABLE)
+        .await
+        .context("create pgcrate.metrics")?;
+    let recorded_at: DateTime<Utc> = client.query_one("SELECT now()", &[]).await?.get(0);
+
+    let metrics: Vec<&str> = samples.iter().map(|s| s.metric).collect();
+    let objects: Vec<&str> = samples.iter().map(|s| s.object.as_str()).collect();
+    let values: Vec<f64> = samples.iter().map(|s| s.value).collect();
+    client
+        .execute(
+            "INSERT INTO pgcrate.metrics (recorded_at, baseline, metric, object, value)
+             SELECT $1, $2, m, o, v
+             FROM unnest($3::text[], $4::text[], $5::float8[]) AS t(m, o, v)",
+            &[&recorded_at, &baseline, &metrics, &objects, &values],
+        )
+        .await
+        .context("write pgcrate.metrics")?;
+    Ok(recorded_at)
+}
+
+/// Run triage, bloat, sequences, and xid and record their metrics
+pub async fn run_record(
+    client: &Client,
+    baseline: Option<&str>,
+    alerts: &AlertsConfig,
+) -> Result<RecordResult> {
+    let mut samples = triage_samples(&super::triage::run_triage(client, alerts).await);
+    let mut skipped = Vec::new();
+
+    match super::bloat::get_bloat(client, RECORD_LIMIT, &alerts.ignore).await {
+        Ok(r) => samples.extend(bloat_samples(&r)),
+        Err(e) => skipped.push(format!("bloat: {}", e)),
+    }
+    match super::sequences::run_sequences(client, None, None, alerts).await {
+        Ok(r) => samples.extend(sequence_samples(&r)),
+        Err(e) => skipped.push(format!("sequences: {}", e)),
+    }
+    match super::xid::run_xid(client, RECORD_LIMIT, alerts).await {
+        Ok(r) => samples.extend(xid_samples(&r)),
+        Err(e) => skipped.push(format!("xid: {}", e)),
+    }
+
+    let recorded_at = record_samples(client, &samples, baseline).await?;
+
+    let mut metrics = BTreeMap::new();
+    for s in &samples {
+        *metrics.entry(s.metric.to_string()).or_insert(0) += 1;
+    }
+    Ok(RecordResult {
+        recorded_at,
+        baseline: baseline.map(str::to_string),
+        metrics,
+        skipped,
+    })
+}
+
+/// Recorded (value, time) per (metric, object) at the reference point
+async fn load_reference(
+    client: &Client,
+    metrics: &[&str],
+    reference: &Reference,
+) -> Result<BTreeMap<(String, String), (f64, DateTime<Utc>)>> {
+    require_metrics_table(client).await?;
+    let rows = match reference {
+        Reference::Since { secs, .. } => {
+            client
+                .query(
+                    "SELECT DISTINCT ON (metric, object) metric, object, value, recorded_at
+                     FROM pgcrate.metrics
+                     WHERE metric = ANY($1)
+                       AND recorded_at >= now() - make_interval(secs => $2)
+                     ORDER BY metric, object, recorded_at",
+                    &[&metrics, &(*secs as f64)],
+                )
+                .await?
+        }
+        Reference::Baseline(name) => {
+            client
+                .query(
+                    "SELECT DISTINCT ON (metric, object) metric, object, value, recorded_at
+                     FROM pgcrate.metrics
+                     WHERE metric = ANY($1) AND baseline = $2
+                     ORDER BY metric, object, recorded_at DESC",
+                    &[&metrics, name],
+                )
+                .await?
+        }
+    };
+    if rows.is_empty() {
+        bail!("No recorded metrics for the {}", reference.describe());
+    }
+    Ok(rows
+        .iter()
+        .map(|r| ((r.get(0), r.get(1)), (r.get(2), r.get(3))))
+        .collect())
+}
+
+/// Compare a live run's samples against a recording
+pub async fn compare(
+    client: &Client,
+    samples: &[Sample],
+    reference: &Reference,
+) -> Result<Comparison> {
+    let mut metrics: Vec<&str> = samples.iter().map(|s| s.metric).collect();
+    metrics.sort_unstable();
+    metrics.dedup();
+    let recorded = load_reference(client, &metrics, reference).await?;
+    Ok(build_comparison(samples, &recorded, reference))
+}
+
+fn build_comparison(
+    samples: &[Sample],
+    recorded: &BTreeMap<(String, String), (f64, DateTime<Utc>)>,
+    reference: &Reference,
+) -> Comparison {
+    let mut changes = Vec::new();
+    let mut new_objects = 0;
+    for s in samples {
+        match recorded.get(&(s.metric.to_string(), s.object.clone())) {
+            Some(&previous) => changes.push(MetricChange::new(
+                s.metric.to_string(),
+                s.object.clone(),
+                previous,
+                (s.value, None),
+            )),
+            None => new_objects += 1,
+        }
+    }
+    sort_changes(&mut changes);
+    Comparison {
+        against: reference.describe(),
+        changes,
+        new_objects,
+    }
+}
+
+/// Biggest growth first, unchanged values last
+fn sort_changes(changes: &mut [MetricChange]) {
+    changes.sort_by(|a, b| {
+        let key = |c: &MetricChange| {
+            c.delta_pct
+                .unwrap_or(if c.delta > 0.0 { f64::MAX } else { 0.0 })
+        };
+        key(b)
+            .total_cmp(&key(a))
+            .then_with(|| a.object.cmp(&b.object))
+    });
+}
+
+/// Run `--baseline` and `--compare` for a diagnostic's samples.
+///
+/// The comparison runs first so `--compare NAME --baseline NAME` shows the
+/// change since the previous recording of that baseline.
+pub async fn baseline_and_compare(
+    client: &Client,
+    samples: &[Sample],
+    baseline: Option<&str>,
+    compare_spec: Option<&str>,
+) -> Result<Option<Comparison>> {
+    let comparison = match compare_spec {
+        Some(spec) => Some(compare(client, samples, &Reference::parse(spec)).await?),
+        None => None,
+    };
+    if let Some(name) = baseline {
+        record_samples(client, samples, Some(name)).await?;
+    }
+    Ok(comparison)
+}
+
+/// How each object's metric moved from the reference point to its latest recording
+pub async fn run_trend(
+    client: &Client,
+    metric: &str,
+    reference: &Reference,
+) -> Result<TrendResult> {
+    if metric_info(metric).is_none() {
+        let names: Vec<&str> = METRICS.iter().map(|(name, _, _)| *name).collect();
+        bail!(
+            "Unknown metric '{}'. Available: {}",
+            metric,
+            names.join(", ")
+        );
+    }
+    let recorded = load_reference(client, &[metric], reference).await?;
+    let latest = client
+        .query(
+            "SELECT DISTINCT ON (object) object, value, recorded_at
+             FROM pgcrate.metrics
+             WHERE metric = $1
+             ORDER BY object, recorded_at DESC",
+            &[&metric],
+        )
+        .await?;
+
+    let mut changes: Vec<MetricChange> = latest
+        .iter()
+        .filter_map(|r| {
+            let object: String = r.get(0);
+            let previous = *recorded.get(&(metric.to_string(), object.clone()))?;
+            Some(MetricChange::new(
+                metric.to_string(),
+                object,
+                previous,
+                (r.get(1), Some(r.get(2))),
+            ))
+        })
+        .collect();
+    sort_changes(&mut changes);
+
+    Ok(TrendResult {
+        metric: metric.to_string(),
+        against: reference.describe(),
+        changes,
+    })
+}
+
+/// Recorded metrics with sample counts (`dba trend` without a metric)
+pub async fn list_metrics(client: &Client) -> Result<Vec<MetricSummary>> {
+    require_metrics_table(client).await?;
+    let rows = client
+        .query(
+            "SELECT metric, count(*), min(recorded_at), max(recorded_at)
+             FROM pgcrate.metrics
+             GROUP BY metric
+             ORDER BY metric",
+            &[],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|r| {
+            let metric: String = r.get(0);
+            MetricSummary {
+                description: metric_info(&metric).map(|(d, _)| d),
+                metric,
+                samples: r.get(1),
+                first_at: r.get(2),
+                last_at: r.get(3),
+            }
+        })
+        .collect())
+}
+
+fn format_value(unit: Unit, value: f64) -> String {
+    match unit {
+        Unit::Bytes => crate::snapshot::format_bytes(value.abs() as u64),
+        Unit::Pct => format!("{:.1}%", value),
+        Unit::Count => format!("{:.0}", value),
+    }
+}
+
+fn format_delta(change: &MetricChange) -> String {
+    let unit = metric_info(&change.metric).map_or(Unit::Count, |(_, u)| u);
+    if change.delta == 0.0 {
+        return "unchanged".to_string();
+    }
+    let sign = if change.delta < 0.0 { "-" } else { "+" };
+    match change.delta_pct {
+        Some(pct) => format!("{}{:.1}%", sign, pct.abs()),
+        None => format!("{}{}", sign, format_value(unit, change.delta.abs())),
+    }
+}
+
+fn display_object(object: &str) -> &str {
+    if object.is_empty() {
+        "(database)"
+    } else {
+        object
+    }
+}
+
+fn print_changes(changes: &[MetricChange], show_metric: bool) {
+    println!(
+        "  {:40} {:>14} {:>14} {:>10}",
+        if show_metric {
+            "METRIC / OBJECT"
+        } else {
+            "OBJECT"
+        },
+        "PREVIOUS",
+        "CURRENT",
+        "CHANGE"
+    );
+    println!("  {}", "-".repeat(81));
+    for c in changes {
+        let unit = metric_info(&c.metric).map_or(Unit::Count, |(_, u)| u);
+        let name = if show_metric {
+            format!("{} {}", c.metric, display_object(&c.object))
+        } else {
+            display_object(&c.object).to_string()
+        };
+        let name = if name.chars().count() > 40 {
+            format!("{}...", name.chars().take(37).collect::<String>())
+        } else {
+            name
+        };
+        println!(
+            "  {:40} {:>14} {:>14} {:>10}",
+            name,
+            format_value(unit, c.previous),
+            format_value(unit, c.current),
+            format_delta(c)
+        );
+    }
+}
+
+/// Print a `--compare` section after a diagnostic's own output
+pub fn print_comparison(comparison: &Comparison, quiet: bool) {
+    let changed: Vec<MetricChange> = comparison
+        .changes
+        .iter()
+        .filter(|c| c.delta != 0.0)
+        .cloned()
+        .collect();
+    println!();
+    if changed.is_empty() {
+        if !quiet {
+            println!(
+                "COMPARED TO {}: no changes",
+                comparison.against.to_uppercase()
+            );
+        }
+        return;
+    }
+    println!("COMPARED TO {}:", comparison.against.to_uppercase());
+    println!();
+    print_changes(&changed, true);
+    if comparison.new_objects > 0 && !quiet {
+        println!();
+        println!(
+            "  {} new object(s) not in the recording",
+            comparison.new_objects
+        );
+    }
+}
+
+pub fn print_record_human(result: &RecordResult, quiet: bool) {
+    let total: usize = result.metrics.values().sum();
+    match &result.baseline {
+        Some(name) => println!(
+            "Recorded {} samples as baseline '{}' at {}",
+            total,
+            name,
+            result.recorded_at.format("%Y-%m-%d %H:%M:%S UTC")
+        ),
+        None => println!(
+            "Recorded {} samples at {}",
+            total,
+            result.recorded_at.format("%Y-%m-%d %H:%M:%S UTC")
+        ),
+    }
+    if !quiet {
+        for (metric, count) in &result.metrics {
+            println!("  {:20} {}", metric, count);
+        }
+    }
+    for skip in &result.skipped {
+        eprintln!("pgcrate: skipped {}", skip);
+    }
+}
+
+pub fn print_trend_human(result: &TrendResult, quiet: bool) {
+    if result.changes.is_empty() {
+        if !quiet {
+            println!("No {} history for the {}.", result.metric, result.against);
+        }
+        return;
+    }
+    println!("TREND: {} over the {}", result.metric, result.against);
+    println!();
+    if let Some(top) = result.changes.iter().find(|c| c.delta != 0.0) {
+        println!("  {}", top.sentence());
+        println!();
+    }
+    print_changes(&result.changes, false);
+}
+
+pub fn print_metrics_human(metrics: &[MetricSummary]) {
+    println!("RECORDED METRICS:");
+    println!();
+    for m in metrics {
+        println!(
+            "  {:20} {:>6} samples  {} .. {}",
+            m.metric,
+            m.samples,
+            m.first_at.format("%Y-%m-%d %H:%M"),
+            m.last_at.format("%Y-%m-%d %H:%M")
+        );
+    }
+}
+
+pub fn print_record_json(
+    result: &RecordResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::RECORD, result, Severity::Healthy, t),
+        None => DiagnosticOutput::new(schema::RECORD, result, Severity::Healthy),
+    };
+    output.with_partial(!result.skipped.is_empty()).print()?;
+    Ok(())
+}
+
+pub fn print_trend_json<T: Serialize>(
+    result: &T,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::TREND, result, Severity::Healthy, t),
+        None => DiagnosticOutput::new(schema::TREND, result, Severity::Healthy),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, day, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_reference_parse() {
+        assert_eq!(
+            Reference::parse("7d"),
+            Reference::Since {
+                label: "7d".to_string(),
+                secs: 7 * 86_400
+            }
+        );
+        assert_eq!(
+            Reference::parse("pre-deploy"),
+            Reference::Baseline("pre-deploy".to_string())
+        );
+    }
+
+    #[test]
+    fn test_change_sentence() {
+        let grew = MetricChange::new(
+            "table_bloat_bytes".to_string(),
+            "public.orders".to_string(),
+            (100.0 * 1024.0 * 1024.0, at(1)),
+            (112.0 * 1024.0 * 1024.0, Some(at(8))),
+        );
+        assert_eq!(grew.delta_pct, Some(12.0));
+        assert_eq!(
+            grew.sentence(),
+            "table bloat on public.orders grew 12.0% (100.0 MB -> 112.0 MB)"
+        );
+        assert_eq!(format_delta(&grew), "+12.0%");
+
+        let from_zero = MetricChange::new(
+            "triage_status".to_string(),
+            "sequences".to_string(),
+            (0.0, at(1)),
+            (1.0, None),
+        );
+        assert_eq!(from_zero.delta_pct, None);
+        assert_eq!(format_delta(&from_zero), "+1");
+    }
+
+    #[test]
+    fn test_build_comparison_orders_growth_and_counts_new_objects() {
+        let samples = vec![
+            Sample::new("sequence_pct_used", "public.a", 50.0),
+            Sample::new("sequence_pct_used", "public.b", 90.0),
+            Sample::new("sequence_pct_used", "public.c", 10.0),
+        ];
+        let mut recorded = BTreeMap::new();
+        recorded.insert(
+            ("sequence_pct_used".to_string(), "public.a".to_string()),
+            (50.0, at(1)),
+        );
+        recorded.insert(
+            ("sequence_pct_used".to_string(), "public.b".to_string()),
+            (60.0, at(1)),
+        );
+        let comparison = build_comparison(&samples, &recorded, &Reference::parse("7d"));
+        assert_eq!(comparison.against, "last 7d");
+        assert_eq!(comparison.new_objects, 1);
+        let objects: Vec<&str> = comparison
+            .changes
+            .iter()
+            .map(|c| c.object.as_str())
+            .collect();
+        assert_eq!(objects, vec!["public.b", "public.a"]);
+    }
+}
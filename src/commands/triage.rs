@@ -7,8 +7,15 @@ use anyhow::Result;
 use serde::Serialize;
 use tokio_postgres::Client;
 
+use crate::config::{AlertsConfig, ReplicationAlerts, Thresholds};
 use crate::reason_codes::ReasonCode;
 
+/// Triage's connection levels (% of max_connections) unless pgcrate.alerts.toml sets them
+const CONNECTIONS_DEFAULT: Thresholds = Thresholds {
+    warn: 80.0,
+    crit: 95.0,
+};
+
 /// A check that could not be executed.
 #[derive(Debug, Clone, Serialize)]
 pub struct SkippedCheck {
@@ -109,6 +116,9 @@ pub struct TriageResults {
     /// Structured fix actions (when --include-fixes is used)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub actions: Option<Vec<super::fix::StructuredAction>>,
+    /// Changes against recorded metrics (when --compare is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<super::trend::Comparison>,
 }
 
 impl TriageResults {
@@ -144,6 +154,7 @@ impl TriageResults {
             skipped_checks,
             overall_status,
             actions,
+            comparison: None,
         }
     }
 
@@ -166,20 +177,26 @@ enum CheckOutcome {
 
 /// Run all triage checks and return aggregated results.
 /// Checks that fail due to permissions/timeouts are captured as skipped, not errors.
-pub async fn run_triage(client: &Client) -> TriageResults {
+pub async fn run_triage(client: &Client, alerts: &AlertsConfig) -> TriageResults {
     let mut checks = Vec::new();
     let mut skipped = Vec::new();
 
     // Run checks sequentially (sharing connection).
-    let outcomes = vec![
+    let mut outcomes = vec![
         check_blocking_locks(client).await,
         check_long_transactions(client).await,
-        check_xid_age(client).await,
-        check_sequences(client).await,
-        check_connections(client).await,
-        check_replication_lag(client).await,
+        check_xid_age(client, alerts.xid.or(super::xid::DEFAULT_THRESHOLDS)).await,
+        check_sequences(client, alerts).await,
+        check_connections(client, alerts.connections.or(CONNECTIONS_DEFAULT)).await,
+        check_replication_lag(client, &alerts.replication).await,
         check_stats_age(client).await,
+        check_table_stats(client).await,
+        check_persistence(client, alerts).await,
     ];
+    // Temp file spills only make the list when critical
+    outcomes.extend(check_temp_files(client).await);
+    // Prepared transactions only make the list when some are stale
+    outcomes.extend(check_prepared_xacts(client).await);
 
     for outcome in outcomes {
         match outcome {
@@ -314,7 +331,7 @@ async fn check_long_transactions(client: &Client) -> CheckOutcome {
 }
 
 /// Check transaction ID (XID) age for wraparound risk
-async fn check_xid_age(client: &Client) -> CheckOutcome {
+async fn check_xid_age(client: &Client, thresholds: Thresholds) -> CheckOutcome {
     let name = "xid_age";
     let label = "XID AGE";
 
@@ -338,9 +355,9 @@ async fn check_xid_age(client: &Client) -> CheckOutcome {
             let max_xid: i64 = 2_147_483_648;
             let pct = (xid_age as f64 / max_xid as f64 * 100.0) as i32;
 
-            let status = if xid_age > 1_800_000_000 {
+            let status = if xid_age as f64 > thresholds.crit {
                 CheckStatus::Critical
-            } else if xid_age > 1_500_000_000 {
+            } else if xid_age as f64 > thresholds.warn {
                 CheckStatus::Warning
             } else {
                 CheckStatus::Healthy
@@ -383,14 +400,17 @@ async fn check_xid_age(client: &Client) -> CheckOutcome {
 }
 
 /// Check sequence exhaustion risk
-async fn check_sequences(client: &Client) -> CheckOutcome {
+async fn check_sequences(client: &Client, alerts: &AlertsConfig) -> CheckOutcome {
     let name = "sequences";
     let label = "SEQUENCES";
+    let thresholds = alerts.sequences.or(super::sequences::DEFAULT_THRESHOLDS);
 
-    // Check sequences that are >70% exhausted
+    // Check the most exhausted sequences (default: >70% warning, >85% critical)
     // Use same calculation as sequences.rs for consistency (2 decimal places)
     let query = r#"
         SELECT
+            schemaname,
+            sequencename,
             schemaname || '.' || sequencename as seq_name,
             COALESCE(last_value, 0) as last_value,
             CASE
@@ -400,16 +420,27 @@ async fn check_sequences(client: &Client) -> CheckOutcome {
             END as pct_used
         FROM pg_sequences
         ORDER BY pct_used DESC
-        LIMIT 5
+        LIMIT $1
     "#;
 
-    match client.query(query, &[]).await {
+    let limit = (5 + alerts.ignore.count()) as i64;
+    match client.query(query, &[&limit]).await {
         Ok(rows) => {
+            let rows: Vec<_> = rows
+                .into_iter()
+                .filter(|r| {
+                    !alerts
+                        .ignore
+                        .sequence(r.get("schemaname"), r.get("sequencename"))
+                })
+                .take(5)
+                .collect();
+
             let critical: Vec<_> = rows
                 .iter()
                 .filter(|r| {
                     let pct: f64 = r.get("pct_used");
-                    pct > 85.0
+                    pct > thresholds.crit
                 })
                 .collect();
 
@@ -417,7 +448,7 @@ async fn check_sequences(client: &Client) -> CheckOutcome {
                 .iter()
                 .filter(|r| {
                     let pct: f64 = r.get("pct_used");
-                    pct > 70.0 && pct <= 85.0
+                    pct > thresholds.warn && pct <= thresholds.crit
                 })
                 .collect();
 
@@ -477,7 +508,7 @@ async fn check_sequences(client: &Client) -> CheckOutcome {
 }
 
 /// Check connection usage
-async fn check_connections(client: &Client) -> CheckOutcome {
+async fn check_connections(client: &Client, thresholds: Thresholds) -> CheckOutcome {
     let name = "connections";
     let label = "CONNECTIONS";
 
@@ -493,9 +524,9 @@ async fn check_connections(client: &Client) -> CheckOutcome {
             let max: i32 = row.get("max");
             let pct = (current as f64 / max as f64 * 100.0) as i32;
 
-            let status = if pct > 95 {
+            let status = if pct as f64 > thresholds.crit {
                 CheckStatus::Critical
-            } else if pct > 80 {
+            } else if pct as f64 > thresholds.warn {
                 CheckStatus::Warning
             } else {
                 CheckStatus::Healthy
@@ -531,7 +562,9 @@ async fn check_connections(client: &Client) -> CheckOutcome {
 }
 
 /// Check replication lag
-async fn check_replication_lag(client: &Client) -> CheckOutcome {
+async fn check_replication_lag(client: &Client, alerts: &ReplicationAlerts) -> CheckOutcome {
+    use super::replication::{replica_status, ReplicationStatus};
+
     let name = "replication";
     let label = "REPLICATION";
 
@@ -544,24 +577,39 @@ async fn check_replication_lag(client: &Client) -> CheckOutcome {
                 extract(epoch from replay_lag)::int,
                 extract(epoch from write_lag)::int,
                 0
-            ) as lag_seconds
+            ) as lag_seconds,
+            pg_wal_lsn_diff(sent_lsn, replay_lsn)::bigint as lag_bytes
         FROM pg_stat_replication
         ORDER BY lag_seconds DESC
-        LIMIT 1
     "#;
 
-    match client.query_opt(query, &[]).await {
-        Ok(Some(row)) => {
+    // The replica in the worst state (by lag time or unreplayed bytes); on
+    // ties, the one with the most lag
+    let worst = client.query(query, &[]).await.map(|rows| {
+        rows.into_iter()
+            .rev()
+            .map(|row| {
+                let lag_seconds: i32 = row.get("lag_seconds");
+                let status = replica_status(Some(lag_seconds as f64), row.get("lag_bytes"), alerts);
+                (status, row)
+            })
+            .max_by_key(|(status, _)| match status {
+                ReplicationStatus::Healthy => 0,
+                ReplicationStatus::Warning => 1,
+                ReplicationStatus::Critical => 2,
+            })
+    });
+
+    match worst {
+        Ok(Some((worst_status, row))) => {
             let client_addr: Option<std::net::IpAddr> = row.get("client_addr");
             let state: String = row.get("state");
             let lag_seconds: i32 = row.get("lag_seconds");
 
-            let status = if lag_seconds > 300 {
-                CheckStatus::Critical
-            } else if lag_seconds > 30 {
-                CheckStatus::Warning
-            } else {
-                CheckStatus::Healthy
+            let status = match worst_status {
+                ReplicationStatus::Healthy => CheckStatus::Healthy,
+                ReplicationStatus::Warning => CheckStatus::Warning,
+                ReplicationStatus::Critical => CheckStatus::Critical,
             };
 
             let addr_str = client_addr
@@ -687,6 +735,188 @@ async fn check_stats_age(client: &Client) -> CheckOutcome {
     }
 }
 
+/// Check for tables whose planner statistics are stale: never analyzed, or
+/// modified well past autovacuum's analyze threshold since the last ANALYZE.
+/// Stale statistics degrade plans gradually, so this is at most a warning.
+async fn check_table_stats(client: &Client) -> CheckOutcome {
+    use super::stats_age::MOD_WARNING_PCT;
+
+    let name = "table_stats";
+    let label = "TABLE STATS";
+
+    let query = r#"
+        SELECT
+            count(*) FILTER (
+                WHERE s.last_analyze IS NULL AND s.last_autoanalyze IS NULL
+            ) AS never_analyzed,
+            count(*) FILTER (
+                WHERE GREATEST(s.last_analyze, s.last_autoanalyze) IS NOT NULL
+                  AND 100.0 * s.n_mod_since_analyze
+                      / GREATEST(c.reltuples::float8, s.n_live_tup::float8, 1) >= $1
+            ) AS modified
+        FROM pg_stat_user_tables s
+        JOIN pg_class c ON c.oid = s.relid
+        WHERE s.n_live_tup > 1000
+    "#;
+
+    match client.query_one(query, &[&MOD_WARNING_PCT]).await {
+        Ok(row) => {
+            let never_analyzed: i64 = row.get("never_analyzed");
+            let modified: i64 = row.get("modified");
+
+            if never_analyzed == 0 && modified == 0 {
+                return CheckOutcome::Ok(CheckResult {
+                    name,
+                    label,
+                    status: CheckStatus::Healthy,
+                    summary: "Table statistics fresh".to_string(),
+                    details: None,
+                    next_actions: vec![],
+                });
+            }
+
+            let mut parts = Vec::new();
+            if never_analyzed > 0 {
+                parts.push(format!("{} never analyzed", never_analyzed));
+            }
+            if modified > 0 {
+                parts.push(format!(
+                    "{} modified >{:.0}% since ANALYZE",
+                    modified, MOD_WARNING_PCT
+                ));
+            }
+            CheckOutcome::Ok(CheckResult {
+                name,
+                label,
+                status: CheckStatus::Warning,
+                summary: format!("Stale stats: {}", parts.join(", ")),
+                details: Some("Planner row estimates may be off for these tables".to_string()),
+                next_actions: vec![NextAction::pgcrate(
+                    &["dba", "stats-age"],
+                    "List tables with stale statistics",
+                )],
+            })
+        }
+        Err(e) => {
+            let (reason_code, reason_human) = classify_error(&e);
+            CheckOutcome::Skip(SkippedCheck {
+                check_id: name,
+                reason_code,
+                reason_human,
+            })
+        }
+    }
+}
+
+/// Check for unlogged tables, tables without primary keys, and published
+/// tables whose replica identity breaks logical replication
+async fn check_persistence(client: &Client, alerts: &AlertsConfig) -> CheckOutcome {
+    use super::persistence::{get_persistence_issues, summarize, PersistenceStatus};
+
+    let name = "persistence";
+    let label = "PERSISTENCE";
+
+    match get_persistence_issues(client, &alerts.ignore).await {
+        Ok(tables) => {
+            let result = summarize(tables);
+            let status = match result.overall_status {
+                PersistenceStatus::Healthy => CheckStatus::Healthy,
+                PersistenceStatus::Warning => CheckStatus::Warning,
+                PersistenceStatus::Critical => CheckStatus::Critical,
+            };
+            let next_actions = if status == CheckStatus::Healthy {
+                vec![]
+            } else {
+                vec![NextAction::pgcrate(
+                    &["dba", "persistence"],
+                    "List unlogged, key-less, and replica identity problem tables",
+                )]
+            };
+            CheckOutcome::Ok(CheckResult {
+                name,
+                label,
+                status,
+                summary: result.summary(),
+                details: None,
+                next_actions,
+            })
+        }
+        Err(e) => {
+            let (reason_code, reason_human) = classify_error(&e);
+            CheckOutcome::Skip(SkippedCheck {
+                check_id: name,
+                reason_code,
+                reason_human,
+            })
+        }
+    }
+}
+
+/// Check temp file writes per database; reported only when critical
+async fn check_temp_files(client: &Client) -> Option<CheckOutcome> {
+    use super::temp::{get_database_temp, TempStatus};
+
+    let databases = get_database_temp(client).await.ok()?;
+    let worst = databases
+        .iter()
+        .filter(|d| d.status == TempStatus::Critical)
+        .max_by(|a, b| a.bytes_per_hour.total_cmp(&b.bytes_per_hour))?;
+
+    Some(CheckOutcome::Ok(CheckResult {
+        name: "temp_files",
+        label: "TEMP FILES",
+        status: CheckStatus::Critical,
+        summary: format!(
+            "{}/hour spilled to disk in {}",
+            crate::snapshot::format_bytes(worst.bytes_per_hour as u64),
+            worst.database
+        ),
+        details: None,
+        next_actions: vec![NextAction::pgcrate(
+            &["dba", "temp"],
+            "Find the queries spilling past work_mem",
+        )],
+    }))
+}
+
+/// Check for orphaned prepared transactions; reported only when one is stale
+async fn check_prepared_xacts(client: &Client) -> Option<CheckOutcome> {
+    use super::prepared::{get_prepared_xacts, PreparedStatus, DEFAULT_THRESHOLD};
+
+    let xacts = get_prepared_xacts(client, DEFAULT_THRESHOLD.as_secs_f64())
+        .await
+        .ok()?;
+    let stale: Vec<_> = xacts
+        .iter()
+        .filter(|x| x.status != PreparedStatus::Healthy)
+        .collect();
+    // Oldest first, so the first stale one is the oldest
+    let oldest = stale.first()?;
+    let status = if stale.iter().any(|x| x.status == PreparedStatus::Critical) {
+        CheckStatus::Critical
+    } else {
+        CheckStatus::Warning
+    };
+
+    Some(CheckOutcome::Ok(CheckResult {
+        name: "prepared_xacts",
+        label: "PREPARED XACTS",
+        status,
+        summary: format!(
+            "{} stale prepared transaction(s), oldest '{}' ({:.0} min, XID age {})",
+            stale.len(),
+            oldest.gid,
+            oldest.age_secs / 60.0,
+            oldest.xid_age
+        ),
+        details: None,
+        next_actions: vec![NextAction::pgcrate(
+            &["dba", "prepared"],
+            "List prepared transactions holding back VACUUM",
+        )],
+    }))
+}
+
 /// Print triage results in human-readable format
 pub fn print_human(results: &TriageResults, quiet: bool) {
     if quiet {
@@ -781,6 +1011,7 @@ pub async fn generate_fix_actions(
     read_write: bool,
     is_primary: bool,
 ) -> Vec<super::fix::StructuredAction> {
+    use super::fix::analyze::{create_analyze_action, stale_tables};
     use super::fix::index::{create_drop_action, get_index_info};
     use super::fix::sequence::{create_upgrade_action, get_sequence_info, SequenceType};
     use super::fix::vacuum::{create_vacuum_action, get_table_vacuum_info, VacuumOptions};
@@ -854,6 +1085,19 @@ pub async fn generate_fix_actions(
         }
     }
 
+    // Generate one batched ANALYZE action for tables with stale statistics
+    if let Some(check) = results.checks.iter().find(|c| c.name == "table_stats") {
+        if check.status != CheckStatus::Healthy {
+            if let Ok(tables) = stale_tables(client).await {
+                if !tables.is_empty() {
+                    actions.push(create_analyze_action(
+                        &tables, read_write, is_primary, false,
+                    ));
+                }
+            }
+        }
+    }
+
     // Generate actions for tables needing vacuum
     // Query for tables with high dead tuple percentage
     let vacuum_query = r#"
@@ -11,6 +11,8 @@ use anyhow::Result;
 use serde::Serialize;
 use tokio_postgres::Client;
 
+use crate::config::AlertIgnore;
+
 /// Default thresholds for vacuum warnings
 const DEFAULT_WARNING_PCT: f64 = 10.0;
 const DEFAULT_CRITICAL_PCT: f64 = 25.0;
@@ -249,12 +251,14 @@ pub async fn run_vacuum(
     schema: Option<&str>,
     table: Option<&str>,
     threshold: Option<f64>,
+    ignore: &AlertIgnore,
 ) -> Result<VacuumResult> {
     let threshold = threshold.unwrap_or(DEFAULT_WARNING_PCT);
     let pgstattuple_available = check_pgstattuple(client).await;
     let stats_since = get_stats_since(client).await;
 
     let mut tables = get_tables_heuristic(client, schema, table, threshold).await?;
+    tables.retain(|t| !ignore.table(&t.schema, &t.table));
 
     // If pgstattuple is available and we're looking at specific tables, get accurate info
     if pgstattuple_available && (schema.is_some() || table.is_some()) {
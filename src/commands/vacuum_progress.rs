@@ -0,0 +1,261 @@
+//! Vacuum progress: Show running VACUUM, VACUUM FULL, and CLUSTER commands.
+//!
+//! Reads pg_stat_progress_vacuum and pg_stat_progress_cluster (PostgreSQL
+//! 12+) for manual and autovacuum runs alike, so a `dba fix vacuum` started
+//! on a large table can be watched from a second session. The ETA is a
+//! linear estimate from elapsed time and heap blocks scanned, and is only
+//! given while the heap is being scanned.
+//!
+//! This is purely informational - no status thresholds.
+
+use anyhow::Result;
+use serde::Serialize;
+use tokio_postgres::Client;
+
+/// A running vacuum or cluster operation
+#[derive(Debug, Clone, Serialize)]
+pub struct VacuumProgress {
+    pub pid: i32,
+    pub database: String,
+    pub schema: String,
+    pub table: String,
+    /// VACUUM, VACUUM FULL, or CLUSTER
+    pub command: String,
+    pub autovacuum: bool,
+    pub phase: String,
+    pub heap_blks_total: i64,
+    pub heap_blks_scanned: i64,
+    pub progress_pct: f64,
+    /// Index vacuum passes so far (VACUUM only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_vacuum_count: Option<i64>,
+    pub running_seconds: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_seconds: Option<f64>,
+}
+
+/// Full vacuum progress results
+#[derive(Debug, Serialize)]
+pub struct VacuumProgressResult {
+    pub operations: Vec<VacuumProgress>,
+    pub count: usize,
+}
+
+/// Seconds left at the current rate, given `done` of `total` blocks after
+/// `elapsed` seconds
+pub fn estimate_eta(elapsed: f64, done: i64, total: i64) -> Option<f64> {
+    if done <= 0 || total <= 0 || elapsed <= 0.0 {
+        return None;
+    }
+    let remaining = (total - done).max(0) as f64;
+    Some(elapsed * remaining / done as f64)
+}
+
+/// Run vacuum progress check, optionally for one table
+pub async fn run_vacuum_progress(
+    client: &Client,
+    schema: Option<&str>,
+    table: Option<&str>,
+) -> Result<VacuumProgressResult> {
+    let version_query = "SELECT current_setting('server_version_num')::int";
+    let version_num: i32 = client.query_one(version_query, &[]).await?.get(0);
+
+    // pg_stat_progress_cluster (and so VACUUM FULL progress) is PG12+
+    let cluster_part = if version_num >= 120000 {
+        r#"
+        UNION ALL
+        SELECT
+            p.pid, p.datname::text, p.relid, p.command, p.phase,
+            p.heap_blks_total, p.heap_blks_scanned, NULL::int8,
+            p.phase = 'seq scanning heap'
+        FROM pg_stat_progress_cluster p
+        "#
+    } else {
+        ""
+    };
+
+    let query = format!(
+        r#"
+        WITH progress (pid, database, relid, command, phase, heap_blks_total,
+                       heap_blks_scanned, index_vacuum_count, scanning) AS (
+            SELECT
+                p.pid, p.datname::text, p.relid, 'VACUUM', p.phase,
+                p.heap_blks_total, p.heap_blks_scanned, p.index_vacuum_count,
+                p.phase = 'scanning heap'
+            FROM pg_stat_progress_vacuum p
+            {}
+        )
+        SELECT
+            pr.pid,
+            pr.database,
+            n.nspname AS schema_name,
+            c.relname AS table_name,
+            pr.command,
+            COALESCE(a.backend_type = 'autovacuum worker', false) AS autovacuum,
+            pr.phase,
+            pr.heap_blks_total,
+            pr.heap_blks_scanned,
+            pr.index_vacuum_count,
+            pr.scanning,
+            COALESCE(EXTRACT(EPOCH FROM (now() - a.query_start))::float8, 0) AS running_seconds
+        FROM progress pr
+        JOIN pg_class c ON c.oid = pr.relid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_stat_activity a ON a.pid = pr.pid
+        WHERE ($1::text IS NULL OR n.nspname = $1)
+          AND ($2::text IS NULL OR c.relname = $2)
+        ORDER BY a.query_start
+        "#,
+        cluster_part
+    );
+
+    let rows = client.query(&query, &[&schema, &table]).await?;
+
+    let operations: Vec<VacuumProgress> = rows
+        .iter()
+        .map(|row| {
+            let heap_blks_total: i64 = row.get("heap_blks_total");
+            let heap_blks_scanned: i64 = row.get("heap_blks_scanned");
+            let running_seconds: f64 = row.get("running_seconds");
+            let scanning: bool = row.get("scanning");
+
+            let progress_pct = if heap_blks_total > 0 {
+                (100.0 * heap_blks_scanned as f64) / heap_blks_total as f64
+            } else {
+                0.0
+            };
+            let eta_seconds = if scanning {
+                estimate_eta(running_seconds, heap_blks_scanned, heap_blks_total)
+            } else {
+                None
+            };
+
+            VacuumProgress {
+                pid: row.get("pid"),
+                database: row.get("database"),
+                schema: row.get("schema_name"),
+                table: row.get("table_name"),
+                command: row.get("command"),
+                autovacuum: row.get("autovacuum"),
+                phase: row.get("phase"),
+                heap_blks_total,
+                heap_blks_scanned,
+                progress_pct,
+                index_vacuum_count: row.get("index_vacuum_count"),
+                running_seconds,
+                eta_seconds,
+            }
+        })
+        .collect();
+
+    let count = operations.len();
+    Ok(VacuumProgressResult { operations, count })
+}
+
+/// Format duration for display
+fn format_duration(seconds: f64) -> String {
+    if seconds >= 3600.0 {
+        let hours = (seconds / 3600.0).floor();
+        let mins = ((seconds % 3600.0) / 60.0).floor();
+        format!("{:.0}h {:.0}m", hours, mins)
+    } else if seconds >= 60.0 {
+        let mins = (seconds / 60.0).floor();
+        let secs = seconds % 60.0;
+        format!("{:.0}m {:.0}s", mins, secs)
+    } else {
+        format!("{:.0}s", seconds)
+    }
+}
+
+/// Format large numbers
+fn format_number(n: i64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.1}K", n as f64 / 1_000.0)
+    } else {
+        format!("{}", n)
+    }
+}
+
+/// Print vacuum progress in human-readable format
+pub fn print_human(result: &VacuumProgressResult, quiet: bool) {
+    if result.operations.is_empty() {
+        if !quiet {
+            println!("No VACUUM, VACUUM FULL, or CLUSTER running.");
+        }
+        return;
+    }
+
+    println!("VACUUM PROGRESS:");
+    println!();
+
+    for op in &result.operations {
+        let command = if op.autovacuum {
+            "autovacuum".to_string()
+        } else {
+            op.command.clone()
+        };
+        println!("  {}.{} ({}, pid {})", op.schema, op.table, command, op.pid);
+        println!("    Phase: {}", op.phase);
+        println!(
+            "    Heap blocks: {} / {} scanned ({:.0}%)",
+            format_number(op.heap_blks_scanned),
+            format_number(op.heap_blks_total),
+            op.progress_pct
+        );
+        if let Some(passes) = op.index_vacuum_count.filter(|p| *p > 0) {
+            println!("    Index vacuum passes: {}", passes);
+        }
+        println!("    Running for: {}", format_duration(op.running_seconds));
+        match op.eta_seconds {
+            Some(eta) => println!("    ETA: ~{} (heap scan)", format_duration(eta)),
+            None => println!("    ETA: unknown in this phase"),
+        }
+        println!();
+    }
+}
+
+/// Print vacuum progress as JSON with schema versioning
+pub fn print_json(
+    result: &VacuumProgressResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    // This is purely informational, always healthy
+    let severity = Severity::Healthy;
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::VACUUM_PROGRESS, result, severity, t),
+        None => DiagnosticOutput::new(schema::VACUUM_PROGRESS, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_eta() {
+        // A quarter done after 60s leaves three quarters: 180s
+        assert_eq!(estimate_eta(60.0, 250, 1000), Some(180.0));
+        assert_eq!(estimate_eta(60.0, 1000, 1000), Some(0.0));
+    }
+
+    #[test]
+    fn test_estimate_eta_unknown() {
+        assert_eq!(estimate_eta(60.0, 0, 1000), None);
+        assert_eq!(estimate_eta(0.0, 10, 1000), None);
+        assert_eq!(estimate_eta(60.0, 10, 0), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(45.0), "45s");
+        assert_eq!(format_duration(125.0), "2m 5s");
+        assert_eq!(format_duration(3900.0), "1h 5m");
+    }
+}
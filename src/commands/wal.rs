@@ -0,0 +1,675 @@
+//! WAL command: WAL generation, checkpoint pressure, and archiving health.
+//!
+//! Reports how fast the server writes WAL, whether checkpoints run on
+//! schedule or are forced because max_wal_size fills up first, and whether
+//! WAL archiving keeps up. Each problem comes with the setting to change.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::time::Duration;
+use tokio_postgres::Client;
+
+use super::checkpoints::{REQUESTED_PCT_CRITICAL, REQUESTED_PCT_WARNING};
+use crate::snapshot::format_bytes;
+
+/// WAL written per checkpoint_timeout, as a fraction of max_wal_size
+const PRESSURE_WARNING: f64 = 0.8;
+/// Segments waiting to be archived
+const ARCHIVE_LAG_WARNING: i64 = 10;
+const ARCHIVE_LAG_CRITICAL: i64 = 100;
+
+/// WAL health status level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WalStatus {
+    Healthy,
+    Warning,
+    Critical,
+}
+
+impl WalStatus {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            WalStatus::Healthy => "✓",
+            WalStatus::Warning => "⚠",
+            WalStatus::Critical => "✗",
+        }
+    }
+}
+
+/// WAL-related settings
+#[derive(Debug, Clone, Serialize)]
+pub struct WalSettings {
+    pub max_wal_size_bytes: i64,
+    pub min_wal_size_bytes: i64,
+    pub checkpoint_timeout_secs: i64,
+    pub wal_segment_size_bytes: i64,
+    pub archive_mode: String,
+}
+
+/// WAL written since pg_stat_wal was reset (PostgreSQL 14+)
+#[derive(Debug, Clone, Serialize)]
+pub struct WalGeneration {
+    pub wal_bytes: f64,
+    pub wal_records: i64,
+    /// Full page images, written for the first change to a page after a checkpoint
+    pub wal_fpi: i64,
+    /// Times WAL had to be written because wal_buffers was full
+    pub wal_buffers_full: i64,
+    pub bytes_per_sec: f64,
+    pub since_secs: f64,
+}
+
+/// Checkpoint counts and frequency
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointFrequency {
+    pub timed: i64,
+    pub requested: i64,
+    pub requested_pct: f64,
+    pub per_hour: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_interval_secs: Option<f64>,
+}
+
+/// WAL archiving progress from pg_stat_archiver
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiverStats {
+    pub archived_count: i64,
+    pub failed_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_archived_wal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_archived_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failed_wal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failed_time: Option<String>,
+    /// The most recent attempt failed
+    pub failing: bool,
+    /// Completed segments not yet archived
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lag_segments: Option<i64>,
+}
+
+/// A problem found, and how to fix it
+#[derive(Debug, Clone, Serialize)]
+pub struct WalWarning {
+    pub status: WalStatus,
+    pub message: String,
+    pub fix: String,
+}
+
+/// Full WAL analysis results
+#[derive(Debug, Serialize)]
+pub struct WalResult {
+    pub settings: WalSettings,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation: Option<WalGeneration>,
+    /// WAL rate measured over --sample seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampled_bytes_per_sec: Option<f64>,
+    pub checkpoints: CheckpointFrequency,
+    /// WAL written per checkpoint_timeout as a fraction of max_wal_size;
+    /// above 1.0, max_wal_size forces checkpoints before the timeout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_wal_size_pressure: Option<f64>,
+    /// None when archive_mode is off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archiver: Option<ArchiverStats>,
+    pub warnings: Vec<WalWarning>,
+    pub overall_status: WalStatus,
+}
+
+/// Run WAL analysis; with `sample`, also measure the current WAL rate
+pub async fn run_wal(client: &Client, sample: Option<Duration>) -> Result<WalResult> {
+    let version_num: i32 = client
+        .query_one("SELECT current_setting('server_version_num')::int", &[])
+        .await?
+        .get(0);
+
+    let row = client
+        .query_one(
+            "SELECT
+                pg_size_bytes(current_setting('max_wal_size')) AS max_wal_size,
+                pg_size_bytes(current_setting('min_wal_size')) AS min_wal_size,
+                extract(epoch FROM current_setting('checkpoint_timeout')::interval)::bigint
+                    AS checkpoint_timeout,
+                pg_size_bytes(current_setting('wal_segment_size')) AS wal_segment_size,
+                current_setting('archive_mode') AS archive_mode,
+                pg_is_in_recovery() AS in_recovery",
+            &[],
+        )
+        .await?;
+    let settings = WalSettings {
+        max_wal_size_bytes: row.get("max_wal_size"),
+        min_wal_size_bytes: row.get("min_wal_size"),
+        checkpoint_timeout_secs: row.get("checkpoint_timeout"),
+        wal_segment_size_bytes: row.get("wal_segment_size"),
+        archive_mode: row.get("archive_mode"),
+    };
+    let in_recovery: bool = row.get("in_recovery");
+
+    let generation = if version_num >= 140000 {
+        let row = client
+            .query_one(
+                "SELECT wal_records, wal_fpi, wal_bytes::float8 AS wal_bytes, wal_buffers_full,
+                        extract(epoch FROM now() - stats_reset)::float8 AS since_secs
+                 FROM pg_stat_wal",
+                &[],
+            )
+            .await?;
+        let wal_bytes: f64 = row.get("wal_bytes");
+        let since_secs: Option<f64> = row.get("since_secs");
+        since_secs.map(|since_secs| WalGeneration {
+            wal_bytes,
+            wal_records: row.get("wal_records"),
+            wal_fpi: row.get("wal_fpi"),
+            wal_buffers_full: row.get("wal_buffers_full"),
+            bytes_per_sec: wal_bytes / since_secs.max(1.0),
+            since_secs,
+        })
+    } else {
+        None
+    };
+
+    // Standbys replay WAL rather than write it
+    let sampled_bytes_per_sec = match sample {
+        Some(interval) if !in_recovery => {
+            let lsn_query = "SELECT pg_current_wal_lsn()::text";
+            let start: String = client.query_one(lsn_query, &[]).await?.get(0);
+            tokio::time::sleep(interval).await;
+            let bytes: f64 = client
+                .query_one(
+                    "SELECT pg_wal_lsn_diff(pg_current_wal_lsn(), $1::text::pg_lsn)::float8",
+                    &[&start],
+                )
+                .await?
+                .get(0);
+            Some(bytes / interval.as_secs_f64().max(0.001))
+        }
+        _ => None,
+    };
+
+    let checkpoint_query = if version_num >= 170000 {
+        "SELECT num_timed AS timed, num_requested AS requested,
+                extract(epoch FROM now() - stats_reset)::float8 AS since_secs
+         FROM pg_stat_checkpointer"
+    } else {
+        "SELECT checkpoints_timed AS timed, checkpoints_req AS requested,
+                extract(epoch FROM now() - stats_reset)::float8 AS since_secs
+         FROM pg_stat_bgwriter"
+    };
+    let row = client.query_one(checkpoint_query, &[]).await?;
+    let checkpoints = checkpoint_frequency(
+        row.get("timed"),
+        row.get("requested"),
+        row.get::<_, Option<f64>>("since_secs"),
+    );
+
+    let archiver = if settings.archive_mode == "off" {
+        None
+    } else {
+        let row = client
+            .query_one(
+                "SELECT archived_count, failed_count, last_archived_wal,
+                        last_archived_time::text AS last_archived_time,
+                        last_failed_wal, last_failed_time::text AS last_failed_time,
+                        COALESCE(last_failed_time > last_archived_time,
+                                 last_failed_time IS NOT NULL) AS failing,
+                        CASE WHEN pg_is_in_recovery() THEN NULL
+                             ELSE pg_walfile_name(pg_current_wal_lsn()) END AS current_wal
+                 FROM pg_stat_archiver",
+                &[],
+            )
+            .await?;
+        let last_archived_wal: Option<String> = row.get("last_archived_wal");
+        let current_wal: Option<String> = row.get("current_wal");
+        // The current segment is still being written; everything before it
+        // and after the last archived one is waiting
+        let lag_segments = match (&last_archived_wal, &current_wal) {
+            (Some(archived), Some(current)) => {
+                segments_between(archived, current, settings.wal_segment_size_bytes)
+                    .map(|n| (n - 1).max(0))
+            }
+            _ => None,
+        };
+        Some(ArchiverStats {
+            archived_count: row.get("archived_count"),
+            failed_count: row.get("failed_count"),
+            last_archived_wal,
+            last_archived_time: row.get("last_archived_time"),
+            last_failed_wal: row.get("last_failed_wal"),
+            last_failed_time: row.get("last_failed_time"),
+            failing: row.get("failing"),
+            lag_segments,
+        })
+    };
+
+    let rate = sampled_bytes_per_sec.or(generation.as_ref().map(|g| g.bytes_per_sec));
+    let max_wal_size_pressure = rate.map(|rate| {
+        rate * settings.checkpoint_timeout_secs as f64 / settings.max_wal_size_bytes.max(1) as f64
+    });
+
+    let warnings = analyze(
+        &settings,
+        &checkpoints,
+        max_wal_size_pressure,
+        rate,
+        archiver.as_ref(),
+    );
+    let overall_status = warnings
+        .iter()
+        .map(|w| w.status)
+        .max()
+        .unwrap_or(WalStatus::Healthy);
+
+    Ok(WalResult {
+        settings,
+        generation,
+        sampled_bytes_per_sec,
+        checkpoints,
+        max_wal_size_pressure,
+        archiver,
+        warnings,
+        overall_status,
+    })
+}
+
+fn checkpoint_frequency(
+    timed: i64,
+    requested: i64,
+    since_secs: Option<f64>,
+) -> CheckpointFrequency {
+    let total = timed + requested;
+    let requested_pct = if total > 0 {
+        100.0 * requested as f64 / total as f64
+    } else {
+        0.0
+    };
+    let since_secs = since_secs.unwrap_or(0.0).max(1.0);
+    CheckpointFrequency {
+        timed,
+        requested,
+        requested_pct,
+        per_hour: total as f64 * 3600.0 / since_secs,
+        avg_interval_secs: (total > 0).then(|| since_secs / total as f64),
+    }
+}
+
+/// Segment number of a WAL file name (timeline, log, segment in hex)
+fn segment_number(walfile: &str, segment_size: i64) -> Option<i64> {
+    if walfile.len() < 24 || segment_size <= 0 {
+        return None;
+    }
+    let log = i64::from_str_radix(&walfile[8..16], 16).ok()?;
+    let seg = i64::from_str_radix(&walfile[16..24], 16).ok()?;
+    Some(log * (0x1_0000_0000 / segment_size) + seg)
+}
+
+/// Segments from `from` to `to`, ignoring timelines
+fn segments_between(from: &str, to: &str, segment_size: i64) -> Option<i64> {
+    Some(segment_number(to, segment_size)? - segment_number(from, segment_size)?)
+}
+
+/// Suggested max_wal_size: room for 1.5x the WAL of one checkpoint_timeout,
+/// and at least double the current size, rounded up to whole GB
+fn suggested_max_wal_size(rate: f64, checkpoint_timeout_secs: i64, current_bytes: i64) -> String {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    let needed = rate * checkpoint_timeout_secs as f64 * 1.5;
+    let gb = (needed.max(current_bytes as f64 * 2.0) / GB).ceil();
+    format!("{}GB", gb.max(1.0) as i64)
+}
+
+fn analyze(
+    settings: &WalSettings,
+    checkpoints: &CheckpointFrequency,
+    pressure: Option<f64>,
+    rate: Option<f64>,
+    archiver: Option<&ArchiverStats>,
+) -> Vec<WalWarning> {
+    let mut warnings = Vec::new();
+    let raise_max_wal_size = || match rate {
+        Some(rate) => format!(
+            "ALTER SYSTEM SET max_wal_size = '{}'; SELECT pg_reload_conf();",
+            suggested_max_wal_size(
+                rate,
+                settings.checkpoint_timeout_secs,
+                settings.max_wal_size_bytes
+            )
+        ),
+        None => {
+            "Increase max_wal_size (ALTER SYSTEM SET max_wal_size = ...; SELECT pg_reload_conf();)"
+                .to_string()
+        }
+    };
+
+    if checkpoints.requested_pct >= REQUESTED_PCT_WARNING {
+        warnings.push(WalWarning {
+            status: if checkpoints.requested_pct >= REQUESTED_PCT_CRITICAL {
+                WalStatus::Critical
+            } else {
+                WalStatus::Warning
+            },
+            message: format!(
+                "{:.0}% of checkpoints are requested (forced) rather than timed",
+                checkpoints.requested_pct
+            ),
+            fix: raise_max_wal_size(),
+        });
+    }
+
+    if let Some(pressure) = pressure {
+        if pressure >= PRESSURE_WARNING {
+            warnings.push(WalWarning {
+                status: WalStatus::Warning,
+                message: format!(
+                    "WAL written per checkpoint_timeout ({}s) is {:.0}% of max_wal_size ({})",
+                    settings.checkpoint_timeout_secs,
+                    pressure * 100.0,
+                    format_bytes(settings.max_wal_size_bytes.max(0) as u64)
+                ),
+                fix: raise_max_wal_size(),
+            });
+        }
+    }
+
+    if let Some(archiver) = archiver {
+        if archiver.failing {
+            warnings.push(WalWarning {
+                status: WalStatus::Critical,
+                message: format!(
+                    "WAL archiving is failing (last failure: {})",
+                    archiver
+                        .last_failed_wal
+                        .as_deref()
+                        .unwrap_or("unknown segment")
+                ),
+                fix: "Check archive_command / archive_library and the server log; \
+                      unarchived WAL stays in pg_wal until it succeeds"
+                    .to_string(),
+            });
+        }
+        if let Some(lag) = archiver.lag_segments {
+            if lag >= ARCHIVE_LAG_WARNING {
+                warnings.push(WalWarning {
+                    status: if lag >= ARCHIVE_LAG_CRITICAL {
+                        WalStatus::Critical
+                    } else {
+                        WalStatus::Warning
+                    },
+                    message: format!(
+                        "Archiving is {} segments ({}) behind",
+                        lag,
+                        format_bytes((lag * settings.wal_segment_size_bytes).max(0) as u64)
+                    ),
+                    fix: "Make archive_command faster (compress, archive in parallel) \
+                          or check the archive destination"
+                        .to_string(),
+                });
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Format a duration in seconds for display
+fn format_interval(secs: f64) -> String {
+    if secs >= 3600.0 {
+        format!("{:.1} hours", secs / 3600.0)
+    } else if secs >= 60.0 {
+        format!("{:.1} min", secs / 60.0)
+    } else {
+        format!("{:.0} sec", secs)
+    }
+}
+
+fn format_rate(bytes_per_sec: f64) -> String {
+    format!("{}/s", format_bytes(bytes_per_sec.max(0.0) as u64))
+}
+
+/// Print WAL analysis in human-readable format
+pub fn print_human(result: &WalResult, _quiet: bool) {
+    let settings = &result.settings;
+
+    println!("WAL ANALYSIS");
+    println!("============");
+    println!();
+
+    println!("WAL Generation:");
+    if let Some(ref generation) = result.generation {
+        println!(
+            "  Written:               {} over {}",
+            format_bytes(generation.wal_bytes.max(0.0) as u64),
+            format_interval(generation.since_secs)
+        );
+        println!(
+            "  Average rate:          {}",
+            format_rate(generation.bytes_per_sec)
+        );
+        println!(
+            "  Full page images:      {} of {} records",
+            generation.wal_fpi, generation.wal_records
+        );
+    }
+    if let Some(rate) = result.sampled_bytes_per_sec {
+        println!("  Current rate:          {}", format_rate(rate));
+    }
+    if result.generation.is_none() && result.sampled_bytes_per_sec.is_none() {
+        println!("  (pg_stat_wal needs PostgreSQL 14+; use --sample to measure)");
+    }
+    println!();
+
+    let checkpoints = &result.checkpoints;
+    println!("Checkpoints:");
+    println!(
+        "  Timed / requested:     {} / {} ({:.0}% requested)",
+        checkpoints.timed, checkpoints.requested, checkpoints.requested_pct
+    );
+    println!("  Per hour:              {:.1}", checkpoints.per_hour);
+    if let Some(interval) = checkpoints.avg_interval_secs {
+        println!(
+            "  Average interval:      {} (checkpoint_timeout {})",
+            format_interval(interval),
+            format_interval(settings.checkpoint_timeout_secs as f64)
+        );
+    }
+    println!();
+
+    println!("max_wal_size:");
+    println!(
+        "  max_wal_size:          {}",
+        format_bytes(settings.max_wal_size_bytes.max(0) as u64)
+    );
+    if let Some(pressure) = result.max_wal_size_pressure {
+        println!(
+            "  Used per timeout:      {:.0}%{}",
+            pressure * 100.0,
+            if pressure >= PRESSURE_WARNING {
+                " ⚠"
+            } else {
+                ""
+            }
+        );
+    }
+    println!();
+
+    println!("Archiving:");
+    match result.archiver {
+        None => println!("  archive_mode is off"),
+        Some(ref archiver) => {
+            println!(
+                "  Archived / failed:     {} / {}",
+                archiver.archived_count, archiver.failed_count
+            );
+            if let Some(ref wal) = archiver.last_archived_wal {
+                println!(
+                    "  Last archived:         {} ({})",
+                    wal,
+                    archiver.last_archived_time.as_deref().unwrap_or("-")
+                );
+            }
+            if let Some(lag) = archiver.lag_segments {
+                println!("  Lag:                   {} segments", lag);
+            }
+        }
+    }
+
+    println!();
+    if result.warnings.is_empty() {
+        println!("{} WAL health looks good", result.overall_status.emoji());
+    } else {
+        println!("{} Warnings:", result.overall_status.emoji());
+        for warning in &result.warnings {
+            println!("  - {}", warning.message);
+            println!("    Fix: {}", warning.fix);
+        }
+    }
+}
+
+/// Print WAL analysis as JSON with schema versioning
+pub fn print_json(
+    result: &WalResult,
+    timeouts: Option<crate::diagnostic::EffectiveTimeouts>,
+) -> Result<()> {
+    use crate::output::{schema, DiagnosticOutput, Severity};
+
+    let severity = match result.overall_status {
+        WalStatus::Healthy => Severity::Healthy,
+        WalStatus::Warning => Severity::Warning,
+        WalStatus::Critical => Severity::Critical,
+    };
+
+    let output = match timeouts {
+        Some(t) => DiagnosticOutput::with_timeouts(schema::WAL, result, severity, t),
+        None => DiagnosticOutput::new(schema::WAL, result, severity),
+    };
+    output.print()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEGMENT: i64 = 16 * 1024 * 1024;
+
+    fn settings() -> WalSettings {
+        WalSettings {
+            max_wal_size_bytes: 1024 * 1024 * 1024,
+            min_wal_size_bytes: 80 * 1024 * 1024,
+            checkpoint_timeout_secs: 300,
+            wal_segment_size_bytes: SEGMENT,
+            archive_mode: "on".to_string(),
+        }
+    }
+
+    fn archiver(failing: bool, lag: Option<i64>) -> ArchiverStats {
+        ArchiverStats {
+            archived_count: 10,
+            failed_count: if failing { 1 } else { 0 },
+            last_archived_wal: None,
+            last_archived_time: None,
+            last_failed_wal: failing.then(|| "000000010000000000000007".to_string()),
+            last_failed_time: None,
+            failing,
+            lag_segments: lag,
+        }
+    }
+
+    #[test]
+    fn test_segments_between() {
+        assert_eq!(
+            segments_between(
+                "000000010000000000000007",
+                "00000001000000000000000A",
+                SEGMENT
+            ),
+            Some(3)
+        );
+        // 256 16MB segments per log file
+        assert_eq!(
+            segments_between(
+                "0000000100000000000000FF",
+                "000000010000000100000001",
+                SEGMENT
+            ),
+            Some(2)
+        );
+        assert_eq!(
+            segments_between("bogus", "000000010000000100000001", SEGMENT),
+            None
+        );
+    }
+
+    #[test]
+    fn test_suggested_max_wal_size() {
+        let gb = 1024 * 1024 * 1024;
+        assert_eq!(suggested_max_wal_size(0.0, 300, gb), "2GB");
+        assert_eq!(
+            suggested_max_wal_size(10.0 * 1024.0 * 1024.0, 300, gb),
+            "5GB"
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_frequency() {
+        let frequency = checkpoint_frequency(9, 3, Some(7200.0));
+        assert_eq!(frequency.requested_pct, 25.0);
+        assert_eq!(frequency.per_hour, 6.0);
+        assert_eq!(frequency.avg_interval_secs, Some(600.0));
+        assert_eq!(checkpoint_frequency(0, 0, None).avg_interval_secs, None);
+    }
+
+    #[test]
+    fn test_analyze_healthy() {
+        let checkpoints = checkpoint_frequency(100, 2, Some(36000.0));
+        let warnings = analyze(
+            &settings(),
+            &checkpoints,
+            Some(0.2),
+            Some(1e5),
+            Some(&archiver(false, Some(1))),
+        );
+        assert!(warnings.is_empty(), "{:?}", warnings);
+    }
+
+    #[test]
+    fn test_analyze_max_wal_size_pressure() {
+        let checkpoints = checkpoint_frequency(10, 30, Some(3600.0));
+        // 5 MB/s for 300s = 1.5 GB per timeout against 1 GB
+        let rate = 5.0 * 1024.0 * 1024.0;
+        let warnings = analyze(&settings(), &checkpoints, Some(1.46), Some(rate), None);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].status, WalStatus::Critical);
+        assert!(warnings[0].fix.contains("max_wal_size = '3GB'"));
+        assert_eq!(warnings[1].status, WalStatus::Warning);
+        assert!(warnings[1].message.contains("146%"));
+    }
+
+    #[test]
+    fn test_analyze_archiver() {
+        let checkpoints = checkpoint_frequency(10, 0, Some(3600.0));
+        let warnings = analyze(
+            &settings(),
+            &checkpoints,
+            None,
+            None,
+            Some(&archiver(true, Some(150))),
+        );
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().all(|w| w.status == WalStatus::Critical));
+        assert!(warnings[0].message.contains("000000010000000000000007"));
+        assert!(warnings[1].message.contains("150 segments (2.3 GB)"));
+
+        let warnings = analyze(
+            &settings(),
+            &checkpoints,
+            None,
+            None,
+            Some(&archiver(false, Some(12))),
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].status, WalStatus::Warning);
+    }
+}
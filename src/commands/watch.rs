@@ -0,0 +1,138 @@
+//! Watch mode: Re-run a read-only diagnostic on an interval.
+//!
+//! `--watch` repeats `dba triage`, `locks`, `connections` or `replication`
+//! every INTERVAL seconds. Human output redraws the screen like watch(1);
+//! JSON output prints one envelope per line (NDJSON) so a stream can be piped
+//! into jq or a log shipper. Findings do not end the loop; Ctrl+C does.
+
+use std::io::{IsTerminal, Write};
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio_postgres::Client;
+
+use super::locks::LocksOptions;
+use crate::config::AlertsConfig;
+use crate::diagnostic::EffectiveTimeouts;
+
+/// A diagnostic that supports `--watch`
+#[derive(Debug, Clone, Copy)]
+pub enum WatchTarget {
+    Triage,
+    Locks(LocksOptions),
+    Connections {
+        by_user: bool,
+        by_database: bool,
+        by_application: bool,
+    },
+    Replication,
+}
+
+impl WatchTarget {
+    fn name(&self) -> &'static str {
+        match self {
+            WatchTarget::Triage => "triage",
+            WatchTarget::Locks(_) => "locks",
+            WatchTarget::Connections { .. } => "connections",
+            WatchTarget::Replication => "replication",
+        }
+    }
+}
+
+/// Output settings shared by every iteration
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    pub interval: Duration,
+    pub json: bool,
+    pub quiet: bool,
+    pub redact: bool,
+    pub timeouts: Option<EffectiveTimeouts>,
+    pub alerts: AlertsConfig,
+}
+
+/// Run one iteration and print its output
+async fn run_once(client: &Client, target: WatchTarget, opts: &WatchOptions) -> Result<()> {
+    match target {
+        WatchTarget::Triage => {
+            let results = super::triage::run_triage(client, &opts.alerts).await;
+            if opts.json {
+                super::triage::print_json(&results, opts.timeouts.clone())?;
+            } else {
+                super::triage::print_human(&results, opts.quiet);
+            }
+        }
+        WatchTarget::Locks(locks) => {
+            let result = super::locks::run_locks(client, locks, opts.redact).await?;
+            if opts.json {
+                super::locks::print_json(&result, opts.timeouts.clone())?;
+            } else {
+                super::locks::print_human(&result, locks, opts.quiet);
+            }
+        }
+        WatchTarget::Connections {
+            by_user,
+            by_database,
+            by_application,
+        } => {
+            let result = super::connections::run_connections(
+                client,
+                by_user,
+                by_database,
+                by_application,
+                &opts.alerts,
+            )
+            .await?;
+            if opts.json {
+                super::connections::print_json(&result, opts.timeouts.clone())?;
+            } else {
+                super::connections::print_human(&result, opts.quiet);
+            }
+        }
+        WatchTarget::Replication => {
+            let result =
+                super::replication::get_replication(client, &opts.alerts.replication).await?;
+            if opts.json {
+                super::replication::print_json(&result, opts.timeouts.clone())?;
+            } else {
+                super::replication::print_human(&result, opts.quiet);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Repeat `target` until interrupted
+pub async fn run_watch(client: &Client, target: WatchTarget, opts: WatchOptions) -> Result<()> {
+    if opts.json {
+        crate::output::set_ndjson(true);
+    }
+    let clear = !opts.json && std::io::stdout().is_terminal();
+
+    loop {
+        if !opts.json {
+            if clear {
+                print!("\x1b[H\x1b[2J");
+            }
+            if !opts.quiet {
+                println!(
+                    "Every {}s: pgcrate dba {}    {}",
+                    opts.interval.as_secs(),
+                    target.name(),
+                    chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+                );
+                println!();
+            }
+        }
+
+        // A failed iteration (e.g. a statement timeout) should not end the watch
+        if let Err(e) = run_once(client, target, &opts).await {
+            eprintln!("pgcrate: watch: {:#}", e);
+        }
+        if !opts.json && !clear {
+            println!();
+        }
+        std::io::stdout().flush()?;
+
+        tokio::time::sleep(opts.interval).await;
+    }
+}
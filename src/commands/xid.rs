@@ -8,10 +8,18 @@ use anyhow::{Context, Result};
 use serde::Serialize;
 use tokio_postgres::Client;
 
+use crate::config::{AlertsConfig, Thresholds};
+
 /// XID status thresholds (in transactions)
 const XID_WARNING: i64 = 1_500_000_000; // 1.5 billion
 const XID_CRITICAL: i64 = 1_800_000_000; // 1.8 billion
 
+/// Defaults for `[xid]` in pgcrate.alerts.toml
+pub const DEFAULT_THRESHOLDS: Thresholds = Thresholds {
+    warn: XID_WARNING as f64,
+    crit: XID_CRITICAL as f64,
+};
+
 /// Database-level XID information
 #[derive(Debug, Clone, Serialize)]
 pub struct DatabaseXid {
@@ -49,10 +57,10 @@ pub enum XidStatus {
 }
 
 impl XidStatus {
-    pub fn from_age(age: i64) -> Self {
-        if age >= XID_CRITICAL {
+    pub fn from_age(age: i64, thresholds: Thresholds) -> Self {
+        if age as f64 >= thresholds.crit {
             XidStatus::Critical
-        } else if age >= XID_WARNING {
+        } else if age as f64 >= thresholds.warn {
             XidStatus::Warning
         } else {
             XidStatus::Healthy
@@ -75,10 +83,13 @@ pub struct XidResult {
     pub tables: Vec<TableXid>,
     pub vacuum_progress: Vec<VacuumProgress>,
     pub overall_status: XidStatus,
+    /// Changes against recorded metrics (when --compare is used)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<super::trend::Comparison>,
 }
 
 /// Get database-level XID ages
-pub async fn get_database_xid(client: &Client) -> Result<Vec<DatabaseXid>> {
+pub async fn get_database_xid(client: &Client, thresholds: Thresholds) -> Result<Vec<DatabaseXid>> {
     // Use explicit double precision cast to avoid deserialization issues
     let query = r#"
         SELECT
@@ -104,7 +115,7 @@ pub async fn get_database_xid(client: &Client) -> Result<Vec<DatabaseXid>> {
             datname: row.get("datname"),
             xid_age,
             pct_used,
-            status: XidStatus::from_age(xid_age),
+            status: XidStatus::from_age(xid_age, thresholds),
         });
     }
 
@@ -112,7 +123,12 @@ pub async fn get_database_xid(client: &Client) -> Result<Vec<DatabaseXid>> {
 }
 
 /// Get table-level XID ages (oldest unfrozen tables)
-pub async fn get_table_xid(client: &Client, limit: usize) -> Result<Vec<TableXid>> {
+pub async fn get_table_xid(
+    client: &Client,
+    limit: usize,
+    alerts: &AlertsConfig,
+) -> Result<Vec<TableXid>> {
+    let thresholds = alerts.xid.or(DEFAULT_THRESHOLDS);
     // Query user tables with XID age. Returns empty vec for databases with no tables.
     let query = r#"
         SELECT
@@ -128,22 +144,28 @@ pub async fn get_table_xid(client: &Client, limit: usize) -> Result<Vec<TableXid
     "#;
 
     let rows = client
-        .query(query, &[&(limit as i64)])
+        .query(query, &[&((limit + alerts.ignore.count()) as i64)])
         .await
         .context("Failed to query table XID ages (database may have no user tables)")?;
 
     let mut results = Vec::new();
 
     for row in rows {
+        let schema: String = row.get("schemaname");
+        let table: String = row.get("relname");
+        if alerts.ignore.table(&schema, &table) {
+            continue;
+        }
         let xid_age: i64 = row.get("xid_age");
         results.push(TableXid {
-            schema: row.get("schemaname"),
-            table: row.get("relname"),
+            schema,
+            table,
             xid_age,
             size: row.get("size"),
-            status: XidStatus::from_age(xid_age),
+            status: XidStatus::from_age(xid_age, thresholds),
         });
     }
+    results.truncate(limit);
 
     Ok(results)
 }
@@ -178,9 +200,13 @@ pub async fn get_vacuum_progress(client: &Client) -> Result<Vec<VacuumProgress>>
 }
 
 /// Run full XID analysis
-pub async fn run_xid(client: &Client, table_limit: usize) -> Result<XidResult> {
-    let databases = get_database_xid(client).await?;
-    let tables = get_table_xid(client, table_limit).await?;
+pub async fn run_xid(
+    client: &Client,
+    table_limit: usize,
+    alerts: &AlertsConfig,
+) -> Result<XidResult> {
+    let databases = get_database_xid(client, alerts.xid.or(DEFAULT_THRESHOLDS)).await?;
+    let tables = get_table_xid(client, table_limit, alerts).await?;
     let vacuum_progress = get_vacuum_progress(client).await?;
 
     // Overall status is worst of database statuses
@@ -200,6 +226,7 @@ pub async fn run_xid(client: &Client, table_limit: usize) -> Result<XidResult> {
         tables,
         vacuum_progress,
         overall_status,
+        comparison: None,
     })
 }
 
@@ -317,19 +344,34 @@ mod tests {
 
     #[test]
     fn test_xid_status_healthy() {
-        assert_eq!(XidStatus::from_age(1_000_000_000), XidStatus::Healthy);
+        assert_eq!(
+            XidStatus::from_age(1_000_000_000, DEFAULT_THRESHOLDS),
+            XidStatus::Healthy
+        );
     }
 
     #[test]
     fn test_xid_status_warning() {
-        assert_eq!(XidStatus::from_age(1_500_000_000), XidStatus::Warning);
-        assert_eq!(XidStatus::from_age(1_700_000_000), XidStatus::Warning);
+        assert_eq!(
+            XidStatus::from_age(1_500_000_000, DEFAULT_THRESHOLDS),
+            XidStatus::Warning
+        );
+        assert_eq!(
+            XidStatus::from_age(1_700_000_000, DEFAULT_THRESHOLDS),
+            XidStatus::Warning
+        );
     }
 
     #[test]
     fn test_xid_status_critical() {
-        assert_eq!(XidStatus::from_age(1_800_000_000), XidStatus::Critical);
-        assert_eq!(XidStatus::from_age(2_000_000_000), XidStatus::Critical);
+        assert_eq!(
+            XidStatus::from_age(1_800_000_000, DEFAULT_THRESHOLDS),
+            XidStatus::Critical
+        );
+        assert_eq!(
+            XidStatus::from_age(2_000_000_000, DEFAULT_THRESHOLDS),
+            XidStatus::Critical
+        );
     }
 
     #[test]
@@ -1,6 +1,6 @@
 use anyhow::{bail, Result};
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 
@@ -12,17 +12,26 @@ pub struct Config {
     pub database: Option<DatabaseConfig>,
     pub paths: Option<PathsConfig>,
     pub defaults: Option<DefaultsConfig>,
+    pub migrate: Option<MigrateConfig>,
     pub production: Option<ProductionConfig>,
     pub generate: Option<GenerateConfig>,
     pub snapshot: Option<SnapshotConfig>,
     pub model: Option<ModelConfig>,
     pub seeds: Option<SeedsConfig>,
     pub tools: Option<ToolsConfig>,
+    pub pooler: Option<PoolerConfig>,
+    pub security: Option<SecurityConfig>,
     /// Named database connections
     #[serde(default)]
     pub connections: HashMap<String, ConnectionConfig>,
+    /// Named groups of connections for `dba triage --fleet` (entries may be globs)
+    #[serde(default)]
+    pub fleets: HashMap<String, Vec<String>>,
     /// Policy restrictions for connections
     pub policy: Option<PolicyConfig>,
+    /// Named connection selected with -C, whose tool paths take precedence
+    #[serde(skip)]
+    pub active_connection: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -42,6 +51,13 @@ pub struct DefaultsConfig {
     pub with_down: Option<bool>,
 }
 
+#[derive(Deserialize, Debug, Default)]
+pub struct MigrateConfig {
+    /// Save a `pre-migrate-<version>` snapshot before `migrate up` applies anything
+    #[serde(default)]
+    pub snapshot_before: bool,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ProductionConfig {
     pub patterns: Option<Vec<String>>,
@@ -59,10 +75,33 @@ pub struct GenerateConfig {
 pub struct SnapshotConfig {
     pub directory: Option<String>,
     pub default_format: Option<String>,
+    /// Remote object storage for snapshots (`[snapshot.storage]`)
+    pub storage: Option<SnapshotStorageConfig>,
+    /// Encryption of snapshot files (`[snapshot.encryption]`)
+    pub encryption: Option<SnapshotEncryptionConfig>,
     #[serde(flatten)]
     pub profiles: HashMap<String, SnapshotProfile>,
 }
 
+/// Where snapshots are kept when not in the local snapshots directory
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotStorageConfig {
+    /// `s3://bucket/prefix` or `gs://bucket/prefix`
+    pub url: String,
+}
+
+/// Keys used to encrypt new snapshots and decrypt age-encrypted ones
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotEncryptionConfig {
+    /// age recipients (`age1...`) or GPG key ids
+    #[serde(default)]
+    pub recipients: Vec<String>,
+    /// age identity file used on restore
+    pub identity: Option<String>,
+}
+
 #[derive(Deserialize, Debug, Clone, Default)]
 pub struct SnapshotProfile {
     pub schemas: Option<Vec<String>>,
@@ -71,12 +110,39 @@ pub struct SnapshotProfile {
     pub exclude_tables: Option<Vec<String>>,
     #[serde(default = "default_true")]
     pub data: bool,
+    /// Row filters: table -> SQL condition (`[snapshot.<profile>.where]`)
+    #[serde(rename = "where")]
+    pub row_filters: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Deserialize, Debug, Default)]
 pub struct ModelConfig {
     /// Source tables that models can reference (schema.table format)
     pub sources: Option<Vec<String>>,
+    /// Values for `${var:name}` placeholders in model SQL
+    pub vars: Option<HashMap<String, toml::Value>>,
+    /// Number of models `model run` may execute concurrently (default: 1)
+    pub threads: Option<usize>,
+    /// Default grants for every materialized model: privilege -> roles
+    pub grants: Option<HashMap<String, Vec<String>>>,
+    /// Loaded-at column per source (schema.table -> column) for `model plan`
+    pub freshness: Option<HashMap<String, String>>,
+    /// Tables in other Postgres databases, imported with postgres_fdw
+    pub foreign_sources: Option<BTreeMap<String, ForeignSourceConfig>>,
+}
+
+/// A remote Postgres schema exposed to models as a local schema of foreign tables
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ForeignSourceConfig {
+    /// Remote database URL (can contain `${VAR}` for env var expansion)
+    pub url: String,
+    /// Schema to import from the remote database (default: public)
+    pub remote_schema: Option<String>,
+    /// Local schema that receives the foreign tables (default: the source name)
+    pub schema: Option<String>,
+    /// Tables to import (default: every table in the remote schema)
+    pub tables: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Debug, Default)]
@@ -84,12 +150,33 @@ pub struct SeedsConfig {
     pub directory: Option<String>,
 }
 
+/// pgbouncer admin console for `dba pooler`
+#[derive(Deserialize, Debug, Default)]
+pub struct PoolerConfig {
+    /// Admin console URL (can contain `${VAR}` for env var expansion)
+    pub url: Option<String>,
+}
+
+/// Declared compliance scope for `inspect security-audit`
+#[derive(Deserialize, Debug, Default)]
+pub struct SecurityConfig {
+    /// Tables that must have row-level security, as schema.table (globs allowed)
+    #[serde(default)]
+    pub sensitive_tables: Vec<String>,
+}
+
 /// PostgreSQL tool paths configuration
 #[derive(Deserialize, Debug, Default)]
 pub struct ToolsConfig {
+    #[serde(alias = "pg_dump_path")]
     pub pg_dump: Option<String>,
+    #[serde(alias = "pg_restore_path")]
     pub pg_restore: Option<String>,
     pub psql: Option<String>,
+    pub aws: Option<String>,
+    pub gcloud: Option<String>,
+    pub age: Option<String>,
+    pub gpg: Option<String>,
 }
 
 /// Anonymization configuration (pgcrate.anonymize.toml)
@@ -106,6 +193,11 @@ pub struct AnonymizeRule {
     pub columns: Option<HashMap<String, String>>,
     #[serde(default)]
     pub skip: bool,
+    /// Fraction of rows to dump (0 < sample <= 1)
+    pub sample: Option<f64>,
+    /// Only dump rows matching this SQL condition
+    #[serde(rename = "where")]
+    pub where_clause: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -139,6 +231,181 @@ impl AnonymizeConfig {
     }
 }
 
+/// Alert thresholds and suppressions (pgcrate.alerts.toml).
+///
+/// Unset values fall back to each command's built-in defaults.
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AlertsConfig {
+    /// Sequence capacity used (%)
+    #[serde(default)]
+    pub sequences: AlertThresholds,
+    /// Database and table XID age (transactions)
+    #[serde(default)]
+    pub xid: AlertThresholds,
+    #[serde(default)]
+    pub replication: ReplicationAlerts,
+    /// Connection usage (% of available connections)
+    #[serde(default)]
+    pub connections: AlertThresholds,
+    /// Buffer cache hit ratio (%); alerts when the ratio drops below
+    #[serde(default)]
+    pub cache: AlertThresholds,
+    #[serde(default)]
+    pub ignore: AlertIgnore,
+}
+
+#[derive(Deserialize, Default, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct AlertThresholds {
+    pub warn: Option<f64>,
+    pub crit: Option<f64>,
+}
+
+/// Warning and critical levels after applying pgcrate.alerts.toml
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    pub warn: f64,
+    pub crit: f64,
+}
+
+impl AlertThresholds {
+    /// Configured levels, falling back to a command's defaults
+    pub fn or(self, defaults: Thresholds) -> Thresholds {
+        Thresholds {
+            warn: self.warn.unwrap_or(defaults.warn),
+            crit: self.crit.unwrap_or(defaults.crit),
+        }
+    }
+}
+
+/// Replica lag alerts: by time, and optionally by bytes not yet replayed
+#[derive(Deserialize, Default, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct ReplicationAlerts {
+    pub warn_lag_secs: Option<f64>,
+    pub crit_lag_secs: Option<f64>,
+    pub warn_lag_bytes: Option<i64>,
+    pub crit_lag_bytes: Option<i64>,
+}
+
+impl ReplicationAlerts {
+    pub fn lag_secs(&self, defaults: Thresholds) -> Thresholds {
+        AlertThresholds {
+            warn: self.warn_lag_secs,
+            crit: self.crit_lag_secs,
+        }
+        .or(defaults)
+    }
+}
+
+/// Objects left out of findings. Entries are `schema.name`, a bare `name`
+/// (any schema), or `schema.*`.
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AlertIgnore {
+    #[serde(default)]
+    pub tables: Vec<String>,
+    #[serde(default)]
+    pub indexes: Vec<String>,
+    #[serde(default)]
+    pub sequences: Vec<String>,
+}
+
+impl AlertIgnore {
+    pub fn table(&self, schema: &str, name: &str) -> bool {
+        Self::matches(&self.tables, schema, name)
+    }
+
+    pub fn index(&self, schema: &str, name: &str) -> bool {
+        Self::matches(&self.indexes, schema, name)
+    }
+
+    pub fn sequence(&self, schema: &str, name: &str) -> bool {
+        Self::matches(&self.sequences, schema, name)
+    }
+
+    /// Extra rows to fetch so a LIMIT still fills after ignored objects are dropped
+    pub fn count(&self) -> usize {
+        self.tables.len() + self.indexes.len() + self.sequences.len()
+    }
+
+    fn matches(patterns: &[String], schema: &str, name: &str) -> bool {
+        patterns.iter().any(|p| match p.split_once('.') {
+            Some((s, "*")) => s == schema,
+            Some((s, n)) => s == schema && n == name,
+            None => p == name,
+        })
+    }
+}
+
+impl AlertsConfig {
+    /// Load from an explicit path (must exist) or ./pgcrate.alerts.toml if present.
+    pub fn load(path: Option<&Path>) -> Result<Self, anyhow::Error> {
+        let config_path = match path {
+            Some(p) => {
+                if !p.exists() {
+                    bail!("Alerts config file not found: {}", p.display());
+                }
+                p
+            }
+            None => {
+                let default_path = Path::new("pgcrate.alerts.toml");
+                if default_path.exists() {
+                    default_path
+                } else {
+                    return Ok(AlertsConfig::default());
+                }
+            }
+        };
+
+        let contents = fs::read_to_string(config_path)?;
+        let config: AlertsConfig = toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", config_path.display(), e))?;
+        config
+            .validate()
+            .map_err(|e| anyhow::anyhow!("{}: {}", config_path.display(), e))?;
+
+        Ok(config)
+    }
+
+    /// Warning must come before critical (above it for cache hit ratio)
+    fn validate(&self) -> Result<(), anyhow::Error> {
+        let rising = [
+            ("sequences", self.sequences.warn, self.sequences.crit),
+            ("xid", self.xid.warn, self.xid.crit),
+            ("connections", self.connections.warn, self.connections.crit),
+            (
+                "replication lag_secs",
+                self.replication.warn_lag_secs,
+                self.replication.crit_lag_secs,
+            ),
+            (
+                "replication lag_bytes",
+                self.replication.warn_lag_bytes.map(|b| b as f64),
+                self.replication.crit_lag_bytes.map(|b| b as f64),
+            ),
+        ];
+        for (name, warn, crit) in rising {
+            if let (Some(w), Some(c)) = (warn, crit) {
+                if w > c {
+                    bail!("[{}] warning level {} is above critical {}", name, w, c);
+                }
+            }
+        }
+        if let (Some(w), Some(c)) = (self.cache.warn, self.cache.crit) {
+            if w < c {
+                bail!(
+                    "[cache] warning hit ratio {} is below critical {} (alerts fire when the ratio drops)",
+                    w,
+                    c
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Config {
     /// Load config from file, or return default if no config exists.
     /// If an explicit path is provided via --config, it MUST exist (error if not).
@@ -219,6 +486,35 @@ impl Config {
         Ok(())
     }
 
+    /// Connection names selected by `--fleet`: a `[fleets]` entry, or a
+    /// `*`/`?` glob over `[connections]` names. Sorted and deduplicated.
+    pub fn fleet_connections(&self, fleet: &str) -> Result<Vec<String>, anyhow::Error> {
+        let patterns = match self.fleets.get(fleet) {
+            Some(members) => members.clone(),
+            None => vec![fleet.to_string()],
+        };
+
+        let mut names = Vec::new();
+        for pattern in &patterns {
+            let matched: Vec<&String> = self
+                .connections
+                .keys()
+                .filter(|name| glob_matches(pattern, name))
+                .collect();
+            if matched.is_empty() {
+                bail!(
+                    "No connections in pgcrate.toml match '{}' (fleet '{}')",
+                    pattern,
+                    fleet
+                );
+            }
+            names.extend(matched.into_iter().cloned());
+        }
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
     /// Get database URL with resolution order: CLI > env > config
     pub fn get_database_url(&self, cli_url: Option<&str>) -> Option<String> {
         // CLI takes precedence
@@ -378,15 +674,126 @@ impl Config {
             .unwrap_or_default()
     }
 
-    /// Get path for a PostgreSQL tool (pg_dump, pg_restore, psql)
-    /// Returns configured path if set, otherwise returns the tool name (for PATH lookup)
+    /// Get model run concurrency (defaults to 1; never less than 1)
+    pub fn model_threads(&self) -> usize {
+        self.model
+            .as_ref()
+            .and_then(|m| m.threads)
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    /// Get default model grants (privilege -> roles) from [model.grants]
+    pub fn model_grants(&self) -> HashMap<String, Vec<String>> {
+        self.model
+            .as_ref()
+            .and_then(|m| m.grants.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get source loaded-at columns from [model.freshness]
+    pub fn model_freshness(&self) -> HashMap<String, String> {
+        self.model
+            .as_ref()
+            .and_then(|m| m.freshness.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get foreign sources from [model.foreign_sources], ordered by name
+    pub fn model_foreign_sources(&self) -> BTreeMap<String, ForeignSourceConfig> {
+        self.model
+            .as_ref()
+            .and_then(|m| m.foreign_sources.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get model template variables as strings (TOML strings are used verbatim)
+    pub fn model_vars(&self) -> HashMap<String, String> {
+        self.model
+            .as_ref()
+            .and_then(|m| m.vars.as_ref())
+            .map(|vars| {
+                vars.iter()
+                    .map(|(k, v)| {
+                        let value = match v {
+                            toml::Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        };
+                        (k.clone(), value)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Remote snapshot storage url from `[snapshot.storage]`
+    pub fn snapshot_storage_url(&self) -> Option<&str> {
+        self.snapshot
+            .as_ref()
+            .and_then(|s| s.storage.as_ref())
+            .map(|s| s.url.as_str())
+    }
+
+    pub fn snapshot_before_migrate(&self) -> bool {
+        self.migrate.as_ref().is_some_and(|m| m.snapshot_before)
+    }
+
+    pub fn snapshot_encryption(&self) -> Option<&SnapshotEncryptionConfig> {
+        self.snapshot.as_ref().and_then(|s| s.encryption.as_ref())
+    }
+
+    /// Get security sensitive_tables
+    pub fn sensitive_tables(&self) -> Vec<String> {
+        self.security
+            .as_ref()
+            .map(|s| s.sensitive_tables.clone())
+            .unwrap_or_default()
+    }
+
+    /// pgbouncer admin console URL: the active connection's `pooler_url`,
+    /// then `[pooler] url`, with `${VAR}` expanded
+    pub fn pooler_url(&self) -> Result<Option<String>> {
+        let connection = self
+            .active_connection
+            .as_ref()
+            .and_then(|name| self.connections.get(name).map(|c| (name, c)));
+        if let Some((name, url)) =
+            connection.and_then(|(name, c)| c.pooler_url.as_ref().map(|u| (name.as_str(), u)))
+        {
+            return crate::connection::expand_env_vars(name, url).map(Some);
+        }
+        self.pooler
+            .as_ref()
+            .and_then(|p| p.url.as_ref())
+            .map(|url| crate::connection::expand_env_vars("pooler", url))
+            .transpose()
+    }
+
+    /// Get path for an external tool (pg_dump, pg_restore, psql, aws, gcloud, age, gpg)
+    /// Returns the active connection's path (pg_dump, pg_restore), then [tools], otherwise
+    /// the tool name (for PATH lookup)
     pub fn tool_path(&self, tool: &str) -> String {
+        let connection = self
+            .active_connection
+            .as_ref()
+            .and_then(|name| self.connections.get(name));
+        if let Some(path) = connection.and_then(|c| match tool {
+            "pg_dump" => c.pg_dump_path.as_ref(),
+            "pg_restore" => c.pg_restore_path.as_ref(),
+            _ => None,
+        }) {
+            return path.clone();
+        }
         self.tools
             .as_ref()
             .and_then(|t| match tool {
                 "pg_dump" => t.pg_dump.as_ref(),
                 "pg_restore" => t.pg_restore.as_ref(),
                 "psql" => t.psql.as_ref(),
+                "aws" => t.aws.as_ref(),
+                "gcloud" => t.gcloud.as_ref(),
+                "age" => t.age.as_ref(),
+                "gpg" => t.gpg.as_ref(),
                 _ => None,
             })
             .cloned()
@@ -394,11 +801,38 @@ impl Config {
     }
 }
 
+/// Match `name` against a glob where `*` is any run of characters and `?`
+/// is one character
+pub fn glob_matches(pattern: &str, name: &str) -> bool {
+    fn matches(p: &[char], n: &[char]) -> bool {
+        match p.split_first() {
+            None => n.is_empty(),
+            Some(('*', rest)) => (0..=n.len()).any(|i| matches(rest, &n[i..])),
+            Some(('?', rest)) => !n.is_empty() && matches(rest, &n[1..]),
+            Some((c, rest)) => n.first() == Some(c) && matches(rest, &n[1..]),
+        }
+    }
+    let p: Vec<char> = pattern.chars().collect();
+    let n: Vec<char> = name.chars().collect();
+    matches(&p, &n)
+}
+
 /// Parsed database URL components
 #[derive(Debug, Clone)]
 pub struct ParsedDatabaseUrl {
     pub database_name: String,
     pub admin_url: String, // URL with dbname replaced by 'postgres'
+    /// Everything before the `/dbname`
+    server_url: String,
+    /// `?options`, or empty
+    query: String,
+}
+
+impl ParsedDatabaseUrl {
+    /// The same connection with a different database name
+    pub fn with_database(&self, database: &str) -> String {
+        format!("{}/{}{}", self.server_url, database, self.query)
+    }
 }
 
 /// Parse a database URL to extract the database name and generate an admin URL
@@ -422,17 +856,15 @@ pub fn parse_database_url(url: &str) -> Result<ParsedDatabaseUrl, anyhow::Error>
         bail!("Invalid database URL: no database name specified");
     }
 
-    // Build admin URL by replacing dbname with 'postgres'
-    let admin_base = format!("{}/postgres", &base_url[..last_slash]);
-    let admin_url = match query_string {
-        Some(qs) => format!("{}{}", admin_base, qs),
-        None => admin_base,
-    };
-
-    Ok(ParsedDatabaseUrl {
+    let mut parsed = ParsedDatabaseUrl {
         database_name: db_name.to_string(),
-        admin_url,
-    })
+        admin_url: String::new(),
+        server_url: base_url[..last_slash].to_string(),
+        query: query_string.unwrap_or("").to_string(),
+    };
+    // Build admin URL by replacing dbname with 'postgres'
+    parsed.admin_url = parsed.with_database("postgres");
+    Ok(parsed)
 }
 
 /// Check if a URL matches production patterns (for warning, not blocking)
@@ -469,6 +901,56 @@ pub fn url_matches_production_patterns(url: &str, config: &Config) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_alerts_config_parse_and_fallback() {
+        let config: AlertsConfig = toml::from_str(
+            r#"
+            [sequences]
+            warn = 50
+
+            [replication]
+            crit_lag_bytes = 1073741824
+
+            [ignore]
+            tables = ["public.audit_log", "archive.*"]
+            indexes = ["users_legacy_idx"]
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let seq = config.sequences.or(Thresholds {
+            warn: 70.0,
+            crit: 85.0,
+        });
+        assert_eq!(
+            seq,
+            Thresholds {
+                warn: 50.0,
+                crit: 85.0
+            }
+        );
+        assert_eq!(config.replication.crit_lag_bytes, Some(1_073_741_824));
+
+        assert!(config.ignore.table("public", "audit_log"));
+        assert!(!config.ignore.table("app", "audit_log"));
+        assert!(config.ignore.table("archive", "anything"));
+        assert!(config.ignore.index("public", "users_legacy_idx"));
+        assert!(config.ignore.index("app", "users_legacy_idx"));
+        assert!(!config.ignore.sequence("public", "users_id_seq"));
+    }
+
+    #[test]
+    fn test_alerts_config_rejects_inverted_thresholds() {
+        let config: AlertsConfig = toml::from_str("[xid]\nwarn = 2e9\ncrit = 1e9\n").unwrap();
+        assert!(config.validate().is_err());
+
+        let config: AlertsConfig = toml::from_str("[cache]\nwarn = 90\ncrit = 95\n").unwrap();
+        assert!(config.validate().is_err());
+
+        assert!(toml::from_str::<AlertsConfig>("[sequences]\nwarning = 50\n").is_err());
+    }
+
     #[test]
     fn test_default_config() {
         let config = Config::default();
@@ -514,6 +996,47 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("prod-*", "prod-east"));
+        assert!(glob_matches("prod-*", "prod-"));
+        assert!(!glob_matches("prod-*", "staging-prod"));
+        assert!(glob_matches("db?", "db1"));
+        assert!(!glob_matches("db?", "db10"));
+        assert!(glob_matches("*-replica", "prod-east-replica"));
+        assert!(glob_matches("billing", "billing"));
+    }
+
+    #[test]
+    fn test_fleet_connections() {
+        let config: Config = toml::from_str(
+            r#"
+            [connections.prod-east]
+            url = "postgres://east/app"
+            [connections.prod-west]
+            url = "postgres://west/app"
+            [connections.billing]
+            url = "postgres://billing/app"
+            [connections.staging]
+            url = "postgres://staging/app"
+
+            [fleets]
+            money = ["prod-*", "billing"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.fleet_connections("prod-*").unwrap(),
+            vec!["prod-east", "prod-west"]
+        );
+        assert_eq!(
+            config.fleet_connections("money").unwrap(),
+            vec!["billing", "prod-east", "prod-west"]
+        );
+        assert!(config.fleet_connections("dev-*").is_err());
+    }
+
     #[test]
     fn test_database_url_resolution() {
         let config = Config::default();
@@ -596,6 +1119,10 @@ mod tests {
             parsed.admin_url,
             "postgres://localhost/postgres?sslmode=require"
         );
+        assert_eq!(
+            parsed.with_database("scratch"),
+            "postgres://localhost/scratch?sslmode=require"
+        );
     }
 
     #[test]
@@ -636,10 +1163,14 @@ mod tests {
             [[rules]]
             table = "app.audit_logs"
             skip = true
+            [[rules]]
+            table = "app.events"
+            sample = 0.1
+            where = "created_at > now() - interval '90 days'"
         "#;
         let config: AnonymizeConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.seed, Some("test-seed".to_string()));
-        assert_eq!(config.rules.len(), 2);
+        assert_eq!(config.rules.len(), 3);
         assert_eq!(config.rules[0].table, "app.users");
         assert_eq!(
             config.rules[0]
@@ -651,6 +1182,11 @@ mod tests {
             "fake_email"
         );
         assert!(config.rules[1].skip);
+        assert_eq!(config.rules[2].sample, Some(0.1));
+        assert_eq!(
+            config.rules[2].where_clause.as_deref(),
+            Some("created_at > now() - interval '90 days'")
+        );
     }
 
     #[test]
@@ -687,6 +1223,131 @@ mod tests {
         assert!(demo_subset.data); // defaults to true
     }
 
+    #[test]
+    fn test_parse_snapshot_row_filters() {
+        let toml_str = r#"
+            [snapshot.recent]
+            tables = ["app.users", "app.orders"]
+
+            [snapshot.recent.where]
+            "app.orders" = "created_at > now() - interval '30 days'"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let recent = &config.snapshot.unwrap().profiles["recent"];
+        let filters = recent.row_filters.as_ref().unwrap();
+        assert_eq!(
+            filters["app.orders"],
+            "created_at > now() - interval '30 days'"
+        );
+    }
+
+    #[test]
+    fn test_parse_snapshot_storage() {
+        let toml_str = r#"
+            [snapshot.storage]
+            url = "s3://bucket/snapshots"
+
+            [snapshot.schema_only]
+            data = false
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.snapshot_storage_url(), Some("s3://bucket/snapshots"));
+        let profiles = &config.snapshot.as_ref().unwrap().profiles;
+        assert_eq!(profiles.keys().collect::<Vec<_>>(), vec!["schema_only"]);
+        assert_eq!(Config::default().snapshot_storage_url(), None);
+    }
+
+    #[test]
+    fn test_parse_migrate_snapshot_before() {
+        let config: Config = toml::from_str("[migrate]\nsnapshot_before = true\n").unwrap();
+        assert!(config.snapshot_before_migrate());
+        let config: Config = toml::from_str("[migrate]\n").unwrap();
+        assert!(!config.snapshot_before_migrate());
+        assert!(!Config::default().snapshot_before_migrate());
+    }
+
+    #[test]
+    fn test_parse_snapshot_encryption() {
+        let toml_str = r#"
+            [snapshot.encryption]
+            recipients = ["age1qyqszqgpqyqszqgpqyqszqgpqyqszqgp"]
+            identity = "keys/snapshots.txt"
+
+            [tools]
+            age = "/opt/age/bin/age"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let encryption = config.snapshot_encryption().unwrap();
+        assert_eq!(encryption.recipients.len(), 1);
+        assert_eq!(encryption.identity.as_deref(), Some("keys/snapshots.txt"));
+        assert_eq!(config.tool_path("age"), "/opt/age/bin/age");
+        assert_eq!(config.tool_path("gpg"), "gpg");
+        assert!(config.snapshot.as_ref().unwrap().profiles.is_empty());
+    }
+
+    #[test]
+    fn test_tool_path_per_connection() {
+        let toml_str = r#"
+            [tools]
+            pg_dump_path = "/usr/lib/postgresql/16/bin/pg_dump"
+            pg_restore = "/usr/lib/postgresql/16/bin/pg_restore"
+
+            [connections.legacy]
+            url = "postgres://legacy/app"
+            pg_dump_path = "/usr/lib/postgresql/13/bin/pg_dump"
+        "#;
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.tool_path("pg_dump"),
+            "/usr/lib/postgresql/16/bin/pg_dump"
+        );
+
+        config.active_connection = Some("legacy".to_string());
+        assert_eq!(
+            config.tool_path("pg_dump"),
+            "/usr/lib/postgresql/13/bin/pg_dump"
+        );
+        assert_eq!(
+            config.tool_path("pg_restore"),
+            "/usr/lib/postgresql/16/bin/pg_restore"
+        );
+        assert_eq!(config.tool_path("psql"), "psql");
+    }
+
+    #[test]
+    fn test_pooler_url_per_connection() {
+        let toml_str = r#"
+            [pooler]
+            url = "postgres://admin@bouncer:6432/pgbouncer"
+
+            [connections.east]
+            url = "postgres://east/app"
+            pooler_url = "postgres://admin@east-bouncer:6432/pgbouncer"
+
+            [connections.west]
+            url = "postgres://west/app"
+        "#;
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.pooler_url().unwrap().as_deref(),
+            Some("postgres://admin@bouncer:6432/pgbouncer")
+        );
+
+        config.active_connection = Some("east".to_string());
+        assert_eq!(
+            config.pooler_url().unwrap().as_deref(),
+            Some("postgres://admin@east-bouncer:6432/pgbouncer")
+        );
+
+        config.active_connection = Some("west".to_string());
+        assert_eq!(
+            config.pooler_url().unwrap().as_deref(),
+            Some("postgres://admin@bouncer:6432/pgbouncer")
+        );
+
+        assert_eq!(Config::default().pooler_url().unwrap(), None);
+    }
+
     #[test]
     fn test_default_models_dir() {
         let config = Config::default();
@@ -732,6 +1393,7 @@ mod tests {
         let mut config = Config::default();
         config.model = Some(ModelConfig {
             sources: Some(vec!["app.users".to_string(), "app.orders".to_string()]),
+            ..Default::default()
         });
         let sources = config.model_sources();
         assert_eq!(sources.len(), 2);
@@ -754,6 +1416,42 @@ mod tests {
         assert_eq!(sources[2], "staging.raw_events");
     }
 
+    #[test]
+    fn test_model_vars_from_toml() {
+        let toml_str = r#"
+            [model.vars]
+            env = "dev"
+            sample_pct = 10
+            strict = false
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let vars = config.model_vars();
+        assert_eq!(vars.get("env").map(String::as_str), Some("dev"));
+        assert_eq!(vars.get("sample_pct").map(String::as_str), Some("10"));
+        assert_eq!(vars.get("strict").map(String::as_str), Some("false"));
+    }
+
+    #[test]
+    fn test_model_grants_from_toml() {
+        let config: Config =
+            toml::from_str("[model.grants]\nselect = [\"analytics_ro\", \"reporting\"]\n").unwrap();
+        let grants = config.model_grants();
+        assert_eq!(
+            grants.get("select"),
+            Some(&vec!["analytics_ro".to_string(), "reporting".to_string()])
+        );
+        assert!(Config::default().model_grants().is_empty());
+    }
+
+    #[test]
+    fn test_model_threads() {
+        assert_eq!(Config::default().model_threads(), 1);
+        let config: Config = toml::from_str("[model]\nthreads = 4\n").unwrap();
+        assert_eq!(config.model_threads(), 4);
+        let config: Config = toml::from_str("[model]\nthreads = 0\n").unwrap();
+        assert_eq!(config.model_threads(), 1);
+    }
+
     #[test]
     fn test_validate_paths_rejects_models_traversal() {
         let mut config = Config::default();
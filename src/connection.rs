@@ -27,6 +27,12 @@ pub struct ConnectionConfig {
     /// Force read-only mode
     #[serde(default)]
     pub readonly: Option<bool>,
+    /// pg_dump matching this server's version (overrides [tools])
+    pub pg_dump_path: Option<String>,
+    /// pg_restore matching this server's version (overrides [tools])
+    pub pg_restore_path: Option<String>,
+    /// pgbouncer admin console in front of this server (overrides [pooler])
+    pub pooler_url: Option<String>,
 }
 
 /// Connection role (primary or replica)
@@ -189,7 +195,7 @@ fn resolve_url(name: &str, config: &ConnectionConfig) -> Result<String> {
 }
 
 /// Expand environment variables in a string (${VAR} syntax)
-fn expand_env_vars(conn_name: &str, template: &str) -> Result<String> {
+pub(crate) fn expand_env_vars(conn_name: &str, template: &str) -> Result<String> {
     let mut result = template.to_string();
     let mut start = 0;
 
@@ -0,0 +1,286 @@
+//! Five-field cron expressions (`minute hour day-of-month month day-of-week`)
+//! for `snapshot schedule`.
+//!
+//! Fields accept `*`, numbers, ranges (`1-5`), lists (`1,15`) and steps
+//! (`*/15`, `0-30/10`). Day of week is 0-7 with both 0 and 7 meaning Sunday.
+//! As in cron, when neither day field starts with `*` a day matching either
+//! one runs. Times are local.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+const DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// One field: the values it matches, and whether it starts with `*`
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    values: Vec<u32>,
+    any: bool,
+}
+
+impl Field {
+    fn parse(text: &str, name: &str, min: u32, max: u32) -> Result<Field> {
+        let mut values = Vec::new();
+        for part in text.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step
+                        .parse()
+                        .with_context(|| format!("invalid step '{}' in {} field", step, name))?;
+                    if step == 0 {
+                        bail!("step must be positive in {} field", name);
+                    }
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+            let number = |s: &str| -> Result<u32> {
+                let n: u32 = s
+                    .parse()
+                    .with_context(|| format!("invalid value '{}' in {} field", s, name))?;
+                if n < min || n > max {
+                    bail!("{} is out of range {}-{} in {} field", n, min, max, name);
+                }
+                Ok(n)
+            };
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((a, b)) = range.split_once('-') {
+                (number(a)?, number(b)?)
+            } else if step > 1 {
+                // `5/15` runs from 5 to the end of the range
+                (number(range)?, max)
+            } else {
+                let n = number(range)?;
+                (n, n)
+            };
+            if start > end {
+                bail!("invalid range '{}' in {} field", range, name);
+            }
+            values.extend((start..=end).step_by(step as usize));
+        }
+        values.sort_unstable();
+        values.dedup();
+        Ok(Field {
+            values,
+            any: text.starts_with('*'),
+        })
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        self.values.contains(&value)
+    }
+
+    /// Every value from `min` to `max` is included
+    fn is_full(&self, min: u32, max: u32) -> bool {
+        self.values == (min..=max).collect::<Vec<_>>()
+    }
+}
+
+/// A parsed cron expression
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    expr: String,
+    minute: Field,
+    hour: Field,
+    day: Field,
+    month: Field,
+    weekday: Field,
+}
+
+impl std::fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.expr)
+    }
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<CronSchedule> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            bail!(
+                "invalid cron expression '{}': expected 5 fields (minute hour day month weekday)",
+                expr
+            );
+        }
+        let mut weekday = Field::parse(fields[4], "day-of-week", 0, 7)
+            .with_context(|| format!("invalid cron expression '{}'", expr))?;
+        // 7 is Sunday too
+        if weekday.matches(7) {
+            weekday.values.retain(|d| *d != 7);
+            if !weekday.values.contains(&0) {
+                weekday.values.insert(0, 0);
+            }
+        }
+        let parse = |i: usize, name: &str, min: u32, max: u32| {
+            Field::parse(fields[i], name, min, max)
+                .with_context(|| format!("invalid cron expression '{}'", expr))
+        };
+        Ok(CronSchedule {
+            expr: fields.join(" "),
+            minute: parse(0, "minute", 0, 59)?,
+            hour: parse(1, "hour", 0, 23)?,
+            day: parse(2, "day-of-month", 1, 31)?,
+            month: parse(3, "month", 1, 12)?,
+            weekday,
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        if !self.month.matches(date.month()) {
+            return false;
+        }
+        let day = self.day.matches(date.day());
+        let weekday = self.weekday.matches(date.weekday().num_days_from_sunday());
+        if self.day.any || self.weekday.any {
+            day && weekday
+        } else {
+            day || weekday
+        }
+    }
+
+    /// First matching minute strictly after `after`, in naive local time
+    fn next_naive(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut date = after.date();
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        // Long enough for any valid day/month combination (e.g. Feb 29)
+        for _ in 0..366 * 8 {
+            if date >= start.date() && self.day_matches(date) {
+                for &hour in &self.hour.values {
+                    for &minute in &self.minute.values {
+                        let at = date.and_hms_opt(hour, minute, 0)?;
+                        if at >= start {
+                            return Some(at);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    /// Next run strictly after `after`, skipping local times that don't exist
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut naive = after.naive_local();
+        loop {
+            naive = self.next_naive(naive)?;
+            if let Some(at) = Local.from_local_datetime(&naive).earliest() {
+                if at > after {
+                    return Some(at);
+                }
+            }
+        }
+    }
+
+    /// systemd `OnCalendar=` equivalent
+    pub fn to_on_calendar(&self) -> Result<String> {
+        if !self.day.any && !self.weekday.any {
+            bail!(
+                "cron expression '{}' restricts both day-of-month and day-of-week, \
+                 which systemd timers can't express",
+                self.expr
+            );
+        }
+        let list = |field: &Field, min: u32, max: u32, width: usize| {
+            if field.is_full(min, max) {
+                "*".to_string()
+            } else {
+                field
+                    .values
+                    .iter()
+                    .map(|v| format!("{:0width$}", v, width = width))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }
+        };
+        let weekdays = if self.weekday.is_full(0, 6) {
+            String::new()
+        } else {
+            let names: Vec<&str> = self
+                .weekday
+                .values
+                .iter()
+                .map(|d| DAY_NAMES[*d as usize])
+                .collect();
+            format!("{} ", names.join(","))
+        };
+        Ok(format!(
+            "{}*-{}-{} {}:{}:00",
+            weekdays,
+            list(&self.month, 1, 12, 2),
+            list(&self.day, 1, 31, 2),
+            list(&self.hour, 0, 23, 2),
+            list(&self.minute, 0, 59, 2)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let cron = CronSchedule::parse("*/15 2,14 1-5 * 7").unwrap();
+        assert_eq!(cron.minute.values, vec![0, 15, 30, 45]);
+        assert_eq!(cron.hour.values, vec![2, 14]);
+        assert_eq!(cron.day.values, vec![1, 2, 3, 4, 5]);
+        assert!(cron.month.any);
+        assert_eq!(cron.weekday.values, vec![0]);
+
+        assert!(CronSchedule::parse("0 2 * *").is_err());
+        assert!(CronSchedule::parse("60 2 * * *").is_err());
+        assert!(CronSchedule::parse("0 5-2 * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    }
+
+    #[test]
+    fn test_next_run() {
+        let daily = CronSchedule::parse("0 2 * * *").unwrap();
+        assert_eq!(
+            daily.next_naive(at("2026-03-01 01:59")),
+            Some(at("2026-03-01 02:00"))
+        );
+        assert_eq!(
+            daily.next_naive(at("2026-03-01 02:00")),
+            Some(at("2026-03-02 02:00"))
+        );
+
+        // Mondays, or the 1st of the month
+        let either = CronSchedule::parse("30 6 1 * 1").unwrap();
+        assert_eq!(
+            either.next_naive(at("2026-03-01 07:00")),
+            Some(at("2026-03-02 06:30"))
+        );
+        assert_eq!(
+            either.next_naive(at("2026-03-30 07:00")),
+            Some(at("2026-04-01 06:30"))
+        );
+
+        let leap = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            leap.next_naive(at("2026-03-01 00:00")),
+            Some(at("2028-02-29 00:00"))
+        );
+    }
+
+    #[test]
+    fn test_on_calendar() {
+        let cron = |expr| CronSchedule::parse(expr).unwrap().to_on_calendar().unwrap();
+        assert_eq!(cron("0 2 * * *"), "*-*-* 02:00:00");
+        assert_eq!(
+            cron("*/30 * * * 1-5"),
+            "Mon,Tue,Wed,Thu,Fri *-*-* *:00,30:00"
+        );
+        assert_eq!(cron("15 3 1 1,7 *"), "*-01,07-01 03:15:00");
+        assert!(CronSchedule::parse("0 0 1 * 1")
+            .unwrap()
+            .to_on_calendar()
+            .is_err());
+    }
+}
@@ -5,6 +5,8 @@ use std::collections::HashMap;
 use std::time::SystemTime;
 use tokio_postgres::Client;
 
+use crate::commands::sequences::{get_sequences, SeqStatus, SequenceInfo};
+use crate::config::{AlertIgnore, Thresholds};
 use crate::introspect::{Constraint, ConstraintType, IdentityType, Index, Trigger};
 use crate::sql::quote_ident;
 
@@ -23,14 +25,14 @@ pub struct ColumnInfo {
     pub is_serial: bool,
     pub default: Option<String>,
     pub fk_reference: Option<String>, // e.g., "app.teams(id)"
+    pub comment: Option<String>,
 }
 
-/// Result of resolving an object name to a schema-qualified table
+/// Result of resolving an object name to a schema-qualified table, sequence, or type
 #[derive(Debug, Serialize)]
 pub struct ResolvedTable {
     pub schema: String,
     pub name: String,
-    #[allow(dead_code)] // Retained for potential future use (e.g., oid-based queries)
     pub oid: i64,
 }
 
@@ -41,6 +43,7 @@ pub struct TableDescribe {
     pub schema: String,
     #[allow(dead_code)] // Stored for completeness; callers use resolved.name
     pub name: String,
+    pub comment: Option<String>, // COMMENT ON TABLE
     pub columns: Vec<ColumnInfo>,
     pub indexes: Vec<Index>,
     pub constraints: Vec<Constraint>,
@@ -184,6 +187,8 @@ pub async fn describe_table(
     include_stats: bool,
     verbose: bool,
 ) -> Result<TableDescribe> {
+    let comment = get_table_comment(client, schema, name).await?;
+
     // Get primary key columns first (needed for column display)
     let pk_columns = get_primary_key_columns(client, schema, name).await?;
 
@@ -219,6 +224,7 @@ pub async fn describe_table(
     Ok(TableDescribe {
         schema: schema.to_string(),
         name: name.to_string(),
+        comment,
         columns,
         indexes,
         constraints,
@@ -229,6 +235,24 @@ pub async fn describe_table(
     })
 }
 
+/// Get the table's COMMENT ON TABLE text
+async fn get_table_comment(client: &Client, schema: &str, table: &str) -> Result<Option<String>> {
+    let row = client
+        .query_opt(
+            r#"
+            SELECT obj_description(c.oid, 'pg_class') AS comment
+            FROM pg_class c
+            JOIN pg_namespace n ON c.relnamespace = n.oid
+            WHERE n.nspname = $1
+              AND c.relname = $2
+            "#,
+            &[&schema, &table],
+        )
+        .await?;
+
+    Ok(row.and_then(|r| r.get("comment")))
+}
+
 /// Get primary key column names for a table
 async fn get_primary_key_columns(
     client: &Client,
@@ -277,7 +301,8 @@ async fn get_columns_with_fk(
                    a.attidentity AS identity,
                    CASE WHEN a.attidentity = '' AND d.adbin IS NOT NULL
                         AND pg_get_expr(d.adbin, d.adrelid) LIKE 'nextval(%'
-                        THEN true ELSE false END AS is_serial
+                        THEN true ELSE false END AS is_serial,
+                   col_description(a.attrelid, a.attnum) AS comment
             FROM pg_attribute a
             JOIN pg_class c ON a.attrelid = c.oid
             JOIN pg_namespace n ON c.relnamespace = n.oid
@@ -330,6 +355,7 @@ async fn get_columns_with_fk(
                 is_serial,
                 default,
                 fk_reference,
+                comment: row.get("comment"),
             }
         })
         .collect())
@@ -804,7 +830,11 @@ async fn get_inbound_foreign_keys(
 
 /// Get views and materialized views that depend on this table
 /// Views depend on tables through pg_rewrite rules, so we join through pg_rewrite
-async fn get_dependent_views(client: &Client, schema: &str, table: &str) -> Result<Vec<ViewRef>> {
+pub async fn get_dependent_views(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ViewRef>> {
     let rows = client
         .query(
             r#"
@@ -1196,6 +1226,11 @@ impl TableDescribe {
             output.push(String::new());
         }
 
+        if let Some(ref comment) = self.comment {
+            output.push(format!("Comment: {}", comment));
+            output.push(String::new());
+        }
+
         // Columns section
         output.push("Columns:".to_string());
         if self.columns.is_empty() {
@@ -1248,6 +1283,11 @@ impl TableDescribe {
                     parts.push(format!("DEFAULT {}", default));
                 }
 
+                // Comment last, SQL-style, since it can be long
+                if let Some(ref comment) = col.comment {
+                    parts.push(format!("-- {}", comment.replace('\n', " ")));
+                }
+
                 let suffix = if parts.is_empty() {
                     String::new()
                 } else {
@@ -1410,10 +1450,1024 @@ impl TableDescribe {
     }
 }
 
+// ============================================================================
+// Sequence and Type Inspection
+// ============================================================================
+
+/// A sequence's definition, owner, and usage (usage as `dba sequences` reports it)
+#[derive(Debug, Serialize)]
+pub struct SequenceDescribe {
+    #[serde(flatten)]
+    pub usage: SequenceInfo,
+    pub owner: String,
+    pub start_value: i64,
+    pub min_value: i64,
+    pub increment_by: i64,
+    pub cache_size: i64,
+    pub cycle: bool,
+    /// Column the sequence belongs to (schema.table.column), via serial,
+    /// identity, or OWNED BY
+    pub owned_by: Option<String>,
+    /// Whether `owned_by` is an identity column
+    pub identity: bool,
+}
+
+/// What kind of user-defined type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TypeKind {
+    Enum,
+    Domain,
+    Composite,
+}
+
+impl TypeKind {
+    fn from_typtype(typtype: i8) -> Self {
+        match typtype as u8 as char {
+            'e' => TypeKind::Enum,
+            'd' => TypeKind::Domain,
+            _ => TypeKind::Composite,
+        }
+    }
+}
+
+/// CHECK constraint on a domain
+#[derive(Debug, Serialize)]
+pub struct DomainConstraint {
+    pub name: String,
+    pub definition: String,
+}
+
+/// Attribute of a composite type
+#[derive(Debug, Serialize)]
+pub struct TypeAttribute {
+    pub name: String,
+    pub data_type: String,
+}
+
+/// Table column declared with a type (or an array of it)
+#[derive(Debug, Serialize)]
+pub struct TypeUsage {
+    pub schema: String,
+    pub table: String,
+    pub column: String,
+    pub data_type: String,
+}
+
+/// An enum, domain, or composite type with the columns that use it
+#[derive(Debug, Serialize)]
+pub struct TypeDescribe {
+    pub schema: String,
+    pub name: String,
+    pub kind: TypeKind,
+    pub owner: String,
+    /// Enum labels in sort order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+    /// Domain base type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_type: Option<String>,
+    /// Domain NOT NULL
+    pub not_null: bool,
+    /// Domain default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<DomainConstraint>,
+    /// Composite attributes in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<TypeAttribute>,
+    pub used_by: Vec<TypeUsage>,
+}
+
+/// Get a sequence's definition and usage
+pub async fn describe_sequence(
+    client: &Client,
+    resolved: &ResolvedTable,
+    thresholds: Thresholds,
+) -> Result<SequenceDescribe> {
+    // Usage comes from the diagnostic so percentages and status match it
+    let usage = get_sequences(
+        client,
+        thresholds.warn.round() as i32,
+        thresholds.crit.round() as i32,
+        &AlertIgnore::default(),
+    )
+    .await?
+    .sequences
+    .into_iter()
+    .find(|s| s.schema == resolved.schema && s.name == resolved.name);
+    let Some(usage) = usage else {
+        bail!(
+            "Sequence {}.{} is not visible in pg_sequences (missing privileges?)",
+            quote_ident(&resolved.schema),
+            quote_ident(&resolved.name)
+        );
+    };
+
+    let row = client
+        .query_one(
+            r#"
+            SELECT
+                pg_get_userbyid(c.relowner)::text AS owner,
+                s.seqstart, s.seqmin, s.seqincrement, s.seqcache, s.seqcycle,
+                (
+                    SELECT tn.nspname || '.' || tc.relname || '.' || a.attname
+                    FROM pg_depend d
+                    JOIN pg_class tc ON tc.oid = d.refobjid
+                    JOIN pg_namespace tn ON tn.oid = tc.relnamespace
+                    JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+                    WHERE d.classid = 'pg_class'::regclass
+                      AND d.refclassid = 'pg_class'::regclass
+                      AND d.objid = c.oid
+                      AND d.deptype IN ('a', 'i')
+                    LIMIT 1
+                ) AS owned_by,
+                EXISTS (
+                    SELECT 1 FROM pg_depend d
+                    WHERE d.classid = 'pg_class'::regclass
+                      AND d.objid = c.oid
+                      AND d.deptype = 'i'
+                ) AS identity
+            FROM pg_sequence s
+            JOIN pg_class c ON c.oid = s.seqrelid
+            WHERE c.oid = ($1::bigint)::oid
+            "#,
+            &[&resolved.oid],
+        )
+        .await?;
+
+    Ok(SequenceDescribe {
+        usage,
+        owner: row.get("owner"),
+        start_value: row.get("seqstart"),
+        min_value: row.get("seqmin"),
+        increment_by: row.get("seqincrement"),
+        cache_size: row.get("seqcache"),
+        cycle: row.get("seqcycle"),
+        owned_by: row.get("owned_by"),
+        identity: row.get("identity"),
+    })
+}
+
+/// Get an enum, domain, or composite type's definition and the columns using it
+pub async fn describe_type(client: &Client, resolved: &ResolvedTable) -> Result<TypeDescribe> {
+    let row = client
+        .query_one(
+            r#"
+            SELECT
+                t.typtype,
+                pg_get_userbyid(t.typowner)::text AS owner,
+                CASE WHEN t.typtype = 'd'
+                    THEN format_type(t.typbasetype, t.typtypmod) END AS base_type,
+                t.typnotnull,
+                t.typdefault
+            FROM pg_type t
+            WHERE t.oid = ($1::bigint)::oid
+            "#,
+            &[&resolved.oid],
+        )
+        .await?;
+    let kind = TypeKind::from_typtype(row.get("typtype"));
+
+    let values = if kind == TypeKind::Enum {
+        client
+            .query(
+                "SELECT enumlabel::text FROM pg_enum WHERE enumtypid = ($1::bigint)::oid ORDER BY enumsortorder",
+                &[&resolved.oid],
+            )
+            .await?
+            .iter()
+            .map(|r| r.get(0))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let constraints = if kind == TypeKind::Domain {
+        client
+            .query(
+                r#"
+                SELECT conname::text, pg_get_constraintdef(oid)
+                FROM pg_constraint
+                WHERE contypid = ($1::bigint)::oid
+                ORDER BY conname
+                "#,
+                &[&resolved.oid],
+            )
+            .await?
+            .iter()
+            .map(|r| DomainConstraint {
+                name: r.get(0),
+                definition: r.get(1),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let attributes = if kind == TypeKind::Composite {
+        client
+            .query(
+                r#"
+                SELECT a.attname::text, format_type(a.atttypid, a.atttypmod)
+                FROM pg_type t
+                JOIN pg_attribute a ON a.attrelid = t.typrelid
+                WHERE t.oid = ($1::bigint)::oid
+                  AND a.attnum > 0
+                  AND NOT a.attisdropped
+                ORDER BY a.attnum
+                "#,
+                &[&resolved.oid],
+            )
+            .await?
+            .iter()
+            .map(|r| TypeAttribute {
+                name: r.get(0),
+                data_type: r.get(1),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    // Partitions share their parent's columns, so only the parent is listed
+    let used_by = client
+        .query(
+            r#"
+            SELECT n.nspname::text, c.relname::text, a.attname::text,
+                   format_type(a.atttypid, a.atttypmod)
+            FROM pg_attribute a
+            JOIN pg_class c ON c.oid = a.attrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            JOIN pg_type t ON t.oid = ($1::bigint)::oid
+            WHERE a.atttypid IN (t.oid, t.typarray)
+              AND a.attnum > 0
+              AND NOT a.attisdropped
+              AND c.relkind IN ('r', 'p', 'f')
+              AND NOT c.relispartition
+            ORDER BY n.nspname, c.relname, a.attnum
+            "#,
+            &[&resolved.oid],
+        )
+        .await?
+        .iter()
+        .map(|r| TypeUsage {
+            schema: r.get(0),
+            table: r.get(1),
+            column: r.get(2),
+            data_type: r.get(3),
+        })
+        .collect();
+
+    Ok(TypeDescribe {
+        schema: resolved.schema.clone(),
+        name: resolved.name.clone(),
+        kind,
+        owner: row.get("owner"),
+        values,
+        base_type: row.get("base_type"),
+        not_null: row.get("typnotnull"),
+        default: row.get("typdefault"),
+        constraints,
+        attributes,
+        used_by,
+    })
+}
+
+impl SequenceDescribe {
+    /// Format the sequence for display
+    pub fn format(&self) -> String {
+        let u = &self.usage;
+        let mut output = vec![
+            format!("  Type:         {}", u.data_type),
+            format!("  Owner:        {}", self.owner),
+            format!(
+                "  Owned by:     {}",
+                match (&self.owned_by, self.identity) {
+                    (Some(col), true) => format!("{} (identity)", col),
+                    (Some(col), false) => col.clone(),
+                    (None, _) => "(none)".to_string(),
+                }
+            ),
+            String::new(),
+            format!("  Current:      {}", u.last_value),
+            format!("  Max:          {}", u.max_value),
+            format!("  Used:         {:.2}% {}", u.pct_used, u.status.emoji()),
+            String::new(),
+            format!(
+                "  Start {}, min {}, increment {}, cache {}{}",
+                self.start_value,
+                self.min_value,
+                self.increment_by,
+                self.cache_size,
+                if self.cycle { ", CYCLE" } else { "" }
+            ),
+        ];
+        if u.status != SeqStatus::Healthy {
+            output.push(String::new());
+            output.push(format!(
+                "  Upgrade before exhaustion: pgcrate dba fix sequence {}.{} --upgrade-to bigint",
+                u.schema, u.name
+            ));
+        }
+        output.join("\n")
+    }
+}
+
+impl TypeDescribe {
+    /// Format the type for display
+    pub fn format(&self) -> String {
+        let mut output = vec![format!("  Owner:        {}", self.owner)];
+
+        match self.kind {
+            TypeKind::Enum => {
+                output.push(String::new());
+                output.push(format!("Values ({}):", self.values.len()));
+                for value in &self.values {
+                    output.push(format!("  {}", value));
+                }
+            }
+            TypeKind::Domain => {
+                output.push(format!(
+                    "  Base type:    {}",
+                    self.base_type.as_deref().unwrap_or("?")
+                ));
+                if self.not_null {
+                    output.push("  NOT NULL".to_string());
+                }
+                if let Some(ref default) = self.default {
+                    output.push(format!("  Default:      {}", default));
+                }
+                if !self.constraints.is_empty() {
+                    output.push(String::new());
+                    output.push("Constraints:".to_string());
+                    for c in &self.constraints {
+                        output.push(format!("  {} {}", c.name, c.definition));
+                    }
+                }
+            }
+            TypeKind::Composite => {
+                output.push(String::new());
+                output.push("Attributes:".to_string());
+                let width = self
+                    .attributes
+                    .iter()
+                    .map(|a| a.name.len())
+                    .max()
+                    .unwrap_or(0);
+                for a in &self.attributes {
+                    output.push(format!("  {:width$}  {}", a.name, a.data_type));
+                }
+            }
+        }
+
+        output.push(String::new());
+        output.push("Used by:".to_string());
+        if self.used_by.is_empty() {
+            output.push("  (no table columns)".to_string());
+        }
+        for u in &self.used_by {
+            output.push(format!(
+                "  {}.{}.{} ({})",
+                u.schema, u.table, u.column, u.data_type
+            ));
+        }
+
+        output.join("\n")
+    }
+}
+
+// ============================================================================
+// Schema Summary
+// ============================================================================
+
+/// SQL condition leaving out objects installed by an extension; they belong
+/// to the extension, not the application
+fn not_extension_member(oid: &str) -> String {
+    format!(
+        "NOT EXISTS (SELECT 1 FROM pg_depend d WHERE d.objid = {} AND d.deptype = 'e')",
+        oid
+    )
+}
+
+/// Object counts and size for one schema
+#[derive(Debug, Serialize)]
+pub struct SchemaCounts {
+    pub schema: String,
+    pub owner: String,
+    /// Tables and partitioned tables (partitions are counted with their parent)
+    pub tables: i64,
+    pub views: i64,
+    pub materialized_views: i64,
+    pub sequences: i64,
+    pub functions: i64,
+    /// Tables, indexes, and TOAST, including partitions
+    pub total_bytes: i64,
+}
+
+/// Table in a schema summary
+#[derive(Debug, Serialize)]
+pub struct SchemaTable {
+    pub name: String,
+    /// table, partitioned table, or materialized view
+    pub kind: String,
+    /// Including indexes, TOAST, and (for partitioned tables) partitions
+    pub total_bytes: i64,
+    pub row_estimate: i64,
+}
+
+/// Objects owned by one role
+#[derive(Debug, Serialize)]
+pub struct OwnerCount {
+    pub owner: String,
+    pub objects: i64,
+}
+
+/// Relation or function in a schema
+#[derive(Debug, Serialize)]
+pub struct SchemaObject {
+    pub name: String,
+    pub kind: String,
+}
+
+/// Orientation summary for one schema
+#[derive(Debug, Serialize)]
+pub struct SchemaSummary {
+    #[serde(flatten)]
+    pub counts: SchemaCounts,
+    pub largest_tables: Vec<SchemaTable>,
+    pub owners: Vec<OwnerCount>,
+    /// Most recently created objects, newest first. Postgres does not record
+    /// creation times, so this orders by OID, which is assigned increasingly
+    /// until the OID counter wraps.
+    pub newest_objects: Vec<SchemaObject>,
+}
+
+fn relkind_name(relkind: i8) -> &'static str {
+    match relkind as u8 as char {
+        'r' => "table",
+        'p' => "partitioned table",
+        'v' => "view",
+        'm' => "materialized view",
+        'S' => "sequence",
+        'f' => "foreign table",
+        _ => "relation",
+    }
+}
+
+fn schema_counts_query(filter: &str) -> String {
+    format!(
+        r#"
+        SELECT
+            n.nspname::text AS schema,
+            pg_get_userbyid(n.nspowner)::text AS owner,
+            (SELECT count(*) FROM pg_class c
+             WHERE c.relnamespace = n.oid AND c.relkind IN ('r', 'p')
+               AND NOT c.relispartition AND {rel}) AS tables,
+            (SELECT count(*) FROM pg_class c
+             WHERE c.relnamespace = n.oid AND c.relkind = 'v' AND {rel}) AS views,
+            (SELECT count(*) FROM pg_class c
+             WHERE c.relnamespace = n.oid AND c.relkind = 'm' AND {rel}) AS materialized_views,
+            (SELECT count(*) FROM pg_class c
+             WHERE c.relnamespace = n.oid AND c.relkind = 'S' AND {rel}) AS sequences,
+            (SELECT count(*) FROM pg_proc p
+             WHERE p.pronamespace = n.oid AND {proc}) AS functions,
+            (SELECT COALESCE(sum(pg_total_relation_size(c.oid)), 0)::bigint FROM pg_class c
+             WHERE c.relnamespace = n.oid AND c.relkind IN ('r', 'm') AND {rel}) AS total_bytes
+        FROM pg_namespace n
+        WHERE {filter}
+        ORDER BY n.nspname
+        "#,
+        rel = not_extension_member("c.oid"),
+        proc = not_extension_member("p.oid"),
+        filter = filter,
+    )
+}
+
+fn schema_counts_from_row(row: &tokio_postgres::Row) -> SchemaCounts {
+    SchemaCounts {
+        schema: row.get("schema"),
+        owner: row.get("owner"),
+        tables: row.get("tables"),
+        views: row.get("views"),
+        materialized_views: row.get("materialized_views"),
+        sequences: row.get("sequences"),
+        functions: row.get("functions"),
+        total_bytes: row.get("total_bytes"),
+    }
+}
+
+/// Counts and size for every user schema
+pub async fn list_schemas(client: &Client) -> Result<Vec<SchemaCounts>> {
+    let query = schema_counts_query(
+        "n.nspname NOT LIKE 'pg\\_%' AND n.nspname NOT IN ('information_schema', 'pgcrate')",
+    );
+    let rows = client.query(&query, &[]).await?;
+    Ok(rows.iter().map(schema_counts_from_row).collect())
+}
+
+/// Summarize one schema, listing up to `limit` largest tables and newest objects
+pub async fn summarize_schema(client: &Client, schema: &str, limit: i64) -> Result<SchemaSummary> {
+    let Some(row) = client
+        .query_opt(&schema_counts_query("n.nspname = $1"), &[&schema])
+        .await?
+    else {
+        bail!("Schema {} not found", quote_ident(schema));
+    };
+    let counts = schema_counts_from_row(&row);
+
+    let largest_tables = client
+        .query(
+            &format!(
+                r#"
+                SELECT
+                    c.relname::text AS name,
+                    c.relkind,
+                    CASE WHEN c.relkind = 'p'
+                        THEN (SELECT COALESCE(sum(pg_total_relation_size(t.relid)), 0)
+                              FROM pg_partition_tree(c.oid) t)
+                        ELSE pg_total_relation_size(c.oid)
+                    END::bigint AS total_bytes,
+                    GREATEST(c.reltuples, 0)::bigint AS row_estimate
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = $1
+                  AND c.relkind IN ('r', 'p', 'm')
+                  AND NOT c.relispartition
+                  AND {}
+                ORDER BY total_bytes DESC, c.relname
+                LIMIT $2
+                "#,
+                not_extension_member("c.oid")
+            ),
+            &[&schema, &limit],
+        )
+        .await?
+        .iter()
+        .map(|r| SchemaTable {
+            name: r.get("name"),
+            kind: relkind_name(r.get("relkind")).to_string(),
+            total_bytes: r.get("total_bytes"),
+            row_estimate: r.get("row_estimate"),
+        })
+        .collect();
+
+    // Relation and function OIDs come from the same counter, so they sort together
+    let objects_cte = format!(
+        r#"
+        WITH objects AS (
+            SELECT c.oid, c.relname::text AS name, c.relowner AS owner,
+                   c.relkind::text AS kind
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1
+              AND c.relkind IN ('r', 'p', 'v', 'm', 'S', 'f')
+              AND NOT c.relispartition
+              AND {rel}
+            UNION ALL
+            SELECT p.oid, p.proname || '(' || pg_get_function_identity_arguments(p.oid) || ')',
+                   p.proowner, CASE p.prokind WHEN 'p' THEN 'procedure' ELSE 'function' END
+            FROM pg_proc p
+            JOIN pg_namespace n ON n.oid = p.pronamespace
+            WHERE n.nspname = $1
+              AND {proc}
+        )
+        "#,
+        rel = not_extension_member("c.oid"),
+        proc = not_extension_member("p.oid"),
+    );
+
+    let owners = client
+        .query(
+            &format!(
+                "{} SELECT pg_get_userbyid(owner)::text, count(*) FROM objects GROUP BY 1 ORDER BY 2 DESC, 1",
+                objects_cte
+            ),
+            &[&schema],
+        )
+        .await?
+        .iter()
+        .map(|r| OwnerCount {
+            owner: r.get(0),
+            objects: r.get(1),
+        })
+        .collect();
+
+    let newest_objects = client
+        .query(
+            &format!(
+                "{} SELECT name, kind FROM objects ORDER BY oid DESC LIMIT $2",
+                objects_cte
+            ),
+            &[&schema, &limit],
+        )
+        .await?
+        .iter()
+        .map(|r| {
+            let kind: String = r.get("kind");
+            SchemaObject {
+                name: r.get("name"),
+                kind: match kind.as_bytes() {
+                    [k] => relkind_name(*k as i8).to_string(),
+                    _ => kind,
+                },
+            }
+        })
+        .collect();
+
+    Ok(SchemaSummary {
+        counts,
+        largest_tables,
+        owners,
+        newest_objects,
+    })
+}
+
+impl SchemaCounts {
+    /// One-line object counts, e.g. "12 tables, 3 views, 8 functions"
+    pub fn format_counts(&self) -> String {
+        let count = |n: i64, noun: &str| format!("{} {}{}", n, noun, if n == 1 { "" } else { "s" });
+        let mut parts = vec![count(self.tables, "table")];
+        if self.views > 0 {
+            parts.push(count(self.views, "view"));
+        }
+        if self.materialized_views > 0 {
+            parts.push(count(self.materialized_views, "materialized view"));
+        }
+        if self.sequences > 0 {
+            parts.push(count(self.sequences, "sequence"));
+        }
+        parts.push(count(self.functions, "function"));
+        parts.join(", ")
+    }
+}
+
+/// Format the schema list for display
+pub fn format_schema_list(schemas: &[SchemaCounts]) -> String {
+    if schemas.is_empty() {
+        return "  (no user schemas)".to_string();
+    }
+    let width = schemas.iter().map(|s| s.schema.len()).max().unwrap_or(0);
+    schemas
+        .iter()
+        .map(|s| {
+            format!(
+                "  {:width$}  {:>10}  {}",
+                s.schema,
+                crate::snapshot::format_bytes(s.total_bytes as u64),
+                s.format_counts()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl SchemaSummary {
+    /// Format the schema summary for display
+    pub fn format(&self) -> String {
+        let c = &self.counts;
+        let mut output = vec![
+            format!("  Owner:        {}", c.owner),
+            format!(
+                "  Size:         {}",
+                crate::snapshot::format_bytes(c.total_bytes as u64)
+            ),
+            format!("  Objects:      {}", c.format_counts()),
+        ];
+
+        output.push(String::new());
+        output.push("Largest tables:".to_string());
+        if self.largest_tables.is_empty() {
+            output.push("  (none)".to_string());
+        }
+        let width = self
+            .largest_tables
+            .iter()
+            .map(|t| t.name.len())
+            .max()
+            .unwrap_or(0);
+        for t in &self.largest_tables {
+            let kind = if t.kind == "table" {
+                String::new()
+            } else {
+                format!("  ({})", t.kind)
+            };
+            output.push(format!(
+                "  {:width$}  {:>10}  ~{} rows{}",
+                t.name,
+                crate::snapshot::format_bytes(t.total_bytes as u64),
+                t.row_estimate,
+                kind
+            ));
+        }
+
+        if !self.owners.is_empty() {
+            output.push(String::new());
+            output.push("Owners:".to_string());
+            for o in &self.owners {
+                output.push(format!("  {} ({} objects)", o.owner, o.objects));
+            }
+        }
+
+        if !self.newest_objects.is_empty() {
+            output.push(String::new());
+            output.push("Newest objects (by OID):".to_string());
+            for o in &self.newest_objects {
+                output.push(format!("  {} {}", o.kind, o.name));
+            }
+        }
+
+        output.join("\n")
+    }
+}
+
+// ============================================================================
+// Object Search
+// ============================================================================
+
+/// Object kinds `inspect find` searches, in display order
+pub const FIND_KINDS: &[&str] = &["table", "view", "column", "function", "index", "constraint"];
+
+/// Object whose name matched an `inspect find` pattern
+#[derive(Debug, Serialize)]
+pub struct FindMatch {
+    /// table, view, column, function, index, or constraint
+    pub kind: String,
+    pub schema: String,
+    pub name: String,
+    /// Table a column, index, or constraint belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    /// Column type, function signature, index definition, or constraint definition
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl FindMatch {
+    /// schema.name, or schema.table.name for columns and constraints
+    pub fn qualified_name(&self) -> String {
+        match (self.kind.as_str(), &self.table) {
+            ("column" | "constraint", Some(table)) => {
+                format!("{}.{}.{}", self.schema, table, self.name)
+            }
+            _ => format!("{}.{}", self.schema, self.name),
+        }
+    }
+}
+
+/// Find objects whose names match `pattern`: a case-insensitive substring,
+/// or a case-insensitive POSIX regex when `regex` is set. `kinds` narrows the
+/// search to some of FIND_KINDS (empty means all).
+pub async fn find_objects(
+    client: &Client,
+    pattern: &str,
+    regex: bool,
+    kinds: &[String],
+    schema: Option<&str>,
+) -> Result<Vec<FindMatch>> {
+    let matches = |col: &str| {
+        if regex {
+            format!("{} ~* $1", col)
+        } else {
+            format!("strpos(lower({}), lower($1)) > 0", col)
+        }
+    };
+    // Partitions repeat their parent's columns, indexes, and constraints,
+    // and extension members are not the application's objects
+    let query = format!(
+        r#"
+        WITH found AS (
+            SELECT
+                CASE WHEN c.relkind IN ('v', 'm') THEN 'view' ELSE 'table' END AS kind,
+                n.nspname::text AS schema, c.relname::text AS name,
+                NULL::text AS tbl,
+                CASE c.relkind
+                    WHEN 'p' THEN 'partitioned' WHEN 'f' THEN 'foreign'
+                    WHEN 'm' THEN 'materialized' END AS detail
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relkind IN ('r', 'p', 'f', 'v', 'm')
+              AND NOT c.relispartition
+              AND {rel_ext}
+              AND {rel_match}
+            UNION ALL
+            SELECT 'column', n.nspname, a.attname, c.relname,
+                   format_type(a.atttypid, a.atttypmod)
+            FROM pg_attribute a
+            JOIN pg_class c ON c.oid = a.attrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relkind IN ('r', 'p', 'f', 'v', 'm')
+              AND NOT c.relispartition
+              AND a.attnum > 0
+              AND NOT a.attisdropped
+              AND {rel_ext}
+              AND {att_match}
+            UNION ALL
+            SELECT 'function', n.nspname,
+                   p.proname || '(' || pg_get_function_identity_arguments(p.oid) || ')',
+                   NULL,
+                   CASE WHEN p.prokind = 'p' THEN 'procedure'
+                        ELSE 'returns ' || pg_get_function_result(p.oid) END
+            FROM pg_proc p
+            JOIN pg_namespace n ON n.oid = p.pronamespace
+            WHERE p.prokind IN ('f', 'p', 'a', 'w')
+              AND {proc_ext}
+              AND {proc_match}
+            UNION ALL
+            SELECT 'index', n.nspname, ic.relname, c.relname, pg_get_indexdef(i.indexrelid)
+            FROM pg_index i
+            JOIN pg_class ic ON ic.oid = i.indexrelid
+            JOIN pg_class c ON c.oid = i.indrelid
+            JOIN pg_namespace n ON n.oid = ic.relnamespace
+            WHERE NOT c.relispartition
+              AND {rel_ext}
+              AND {idx_match}
+            UNION ALL
+            SELECT 'constraint', n.nspname, k.conname, c.relname, pg_get_constraintdef(k.oid)
+            FROM pg_constraint k
+            JOIN pg_class c ON c.oid = k.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE NOT c.relispartition
+              AND {rel_ext}
+              AND {con_match}
+        )
+        SELECT kind, schema, name, tbl, detail
+        FROM found
+        WHERE schema NOT LIKE 'pg\_%'
+          AND schema <> 'information_schema'
+          AND kind = ANY($2)
+          AND ($3::text IS NULL OR schema = $3)
+        ORDER BY array_position($4::text[], kind), schema, tbl NULLS FIRST, name
+        "#,
+        rel_ext = not_extension_member("c.oid"),
+        proc_ext = not_extension_member("p.oid"),
+        rel_match = matches("c.relname"),
+        att_match = matches("a.attname"),
+        proc_match = matches("p.proname"),
+        idx_match = matches("ic.relname"),
+        con_match = matches("k.conname"),
+    );
+
+    let all_kinds: Vec<String> = FIND_KINDS.iter().map(|k| k.to_string()).collect();
+    let kinds = if kinds.is_empty() { &all_kinds } else { kinds };
+    let rows = client
+        .query(&query, &[&pattern, &kinds, &schema, &all_kinds])
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|r| FindMatch {
+            kind: r.get("kind"),
+            schema: r.get("schema"),
+            name: r.get("name"),
+            table: r.get("tbl"),
+            detail: r.get("detail"),
+        })
+        .collect())
+}
+
+/// Format search matches for display, grouped by kind
+pub fn format_find_matches(matches: &[FindMatch]) -> String {
+    let width = matches
+        .iter()
+        .map(|m| m.qualified_name().len())
+        .max()
+        .unwrap_or(0);
+    let mut output = Vec::new();
+    let mut current_kind = "";
+    for m in matches {
+        if m.kind != current_kind {
+            if !output.is_empty() {
+                output.push(String::new());
+            }
+            current_kind = &m.kind;
+            output.push(format!("{}:", capitalize_kind(current_kind)));
+        }
+        let detail = match (&m.detail, m.kind.as_str()) {
+            (Some(detail), "index") => match detail.split_once(" USING ") {
+                Some((create, rest)) => format!(
+                    "on {}{} using {}",
+                    m.table.as_deref().unwrap_or(""),
+                    if create.starts_with("CREATE UNIQUE") {
+                        " (unique)"
+                    } else {
+                        ""
+                    },
+                    rest
+                ),
+                None => detail.clone(),
+            },
+            (Some(detail), _) => detail.clone(),
+            (None, _) => String::new(),
+        };
+        output.push(
+            format!("  {:width$}  {}", m.qualified_name(), detail)
+                .trim_end()
+                .to_string(),
+        );
+    }
+    output.join("\n")
+}
+
+fn capitalize_kind(kind: &str) -> &'static str {
+    match kind {
+        "table" => "Tables",
+        "view" => "Views",
+        "column" => "Columns",
+        "function" => "Functions",
+        "index" => "Indexes",
+        _ => "Constraints",
+    }
+}
+
 // ============================================================================
 // Name Resolution
 // ============================================================================
 
+/// Catalog lookups for one kind of named object
+struct ObjectLookup {
+    /// Used in error messages ("Table", "Sequence", "Type")
+    label: &'static str,
+    /// Selects (oid::bigint, schema, name) by $1 schema and $2 name
+    qualified: &'static str,
+    /// Selects (oid::bigint, schema, name) by $1 name, public first
+    unqualified: &'static str,
+}
+
+/// Ordinary tables (relkind 'r') and partitioned tables (relkind 'p')
+const TABLE_LOOKUP: ObjectLookup = ObjectLookup {
+    label: "Table",
+    qualified: r#"
+        SELECT c.oid::bigint, n.nspname, c.relname
+        FROM pg_class c
+        JOIN pg_namespace n ON c.relnamespace = n.oid
+        WHERE n.nspname = $1
+          AND c.relname = $2
+          AND c.relkind IN ('r', 'p')
+        "#,
+    unqualified: r#"
+        SELECT c.oid::bigint, n.nspname, c.relname
+        FROM pg_class c
+        JOIN pg_namespace n ON c.relnamespace = n.oid
+        WHERE c.relname = $1
+          AND c.relkind IN ('r', 'p')
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'pg_toast')
+        ORDER BY
+            CASE WHEN n.nspname = 'public' THEN 0 ELSE 1 END,
+            n.nspname
+        "#,
+};
+
+const SEQUENCE_LOOKUP: ObjectLookup = ObjectLookup {
+    label: "Sequence",
+    qualified: r#"
+        SELECT c.oid::bigint, n.nspname, c.relname
+        FROM pg_class c
+        JOIN pg_namespace n ON c.relnamespace = n.oid
+        WHERE n.nspname = $1
+          AND c.relname = $2
+          AND c.relkind = 'S'
+        "#,
+    unqualified: r#"
+        SELECT c.oid::bigint, n.nspname, c.relname
+        FROM pg_class c
+        JOIN pg_namespace n ON c.relnamespace = n.oid
+        WHERE c.relname = $1
+          AND c.relkind = 'S'
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'pg_toast')
+        ORDER BY
+            CASE WHEN n.nspname = 'public' THEN 0 ELSE 1 END,
+            n.nspname
+        "#,
+};
+
+/// Enums, domains, and standalone composite types (not table row types)
+const TYPE_LOOKUP: ObjectLookup = ObjectLookup {
+    label: "Type",
+    qualified: r#"
+        SELECT t.oid::bigint, n.nspname, t.typname
+        FROM pg_type t
+        JOIN pg_namespace n ON t.typnamespace = n.oid
+        LEFT JOIN pg_class c ON c.oid = t.typrelid
+        WHERE n.nspname = $1
+          AND t.typname = $2
+          AND (t.typtype IN ('e', 'd') OR (t.typtype = 'c' AND c.relkind = 'c'))
+        "#,
+    unqualified: r#"
+        SELECT t.oid::bigint, n.nspname, t.typname
+        FROM pg_type t
+        JOIN pg_namespace n ON t.typnamespace = n.oid
+        LEFT JOIN pg_class c ON c.oid = t.typrelid
+        WHERE t.typname = $1
+          AND (t.typtype IN ('e', 'd') OR (t.typtype = 'c' AND c.relkind = 'c'))
+          AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'pg_toast')
+        ORDER BY
+            CASE WHEN n.nspname = 'public' THEN 0 ELSE 1 END,
+            n.nspname
+        "#,
+};
+
 /// Resolve an object name to a schema-qualified table
 /// Supports:
 /// - Fully qualified: schema.table
@@ -1421,22 +2475,30 @@ impl TableDescribe {
 ///
 /// Only resolves ordinary tables (relkind 'r') and partitioned tables (relkind 'p')
 pub async fn resolve_table(client: &Client, object: &str) -> Result<ResolvedTable> {
+    resolve_object(client, object, &TABLE_LOOKUP).await
+}
+
+/// Resolve an object name to a schema-qualified sequence
+pub async fn resolve_sequence(client: &Client, object: &str) -> Result<ResolvedTable> {
+    resolve_object(client, object, &SEQUENCE_LOOKUP).await
+}
+
+/// Resolve an object name to a schema-qualified enum, domain, or composite type
+pub async fn resolve_type(client: &Client, object: &str) -> Result<ResolvedTable> {
+    resolve_object(client, object, &TYPE_LOOKUP).await
+}
+
+async fn resolve_object(
+    client: &Client,
+    object: &str,
+    lookup: &ObjectLookup,
+) -> Result<ResolvedTable> {
     let (schema, table) = parse_object_name(object);
 
     if let Some(schema) = schema {
         // Fully qualified: check if exists
         let row = client
-            .query_opt(
-                r#"
-                SELECT c.oid::bigint, n.nspname, c.relname
-                FROM pg_class c
-                JOIN pg_namespace n ON c.relnamespace = n.oid
-                WHERE n.nspname = $1
-                  AND c.relname = $2
-                  AND c.relkind IN ('r', 'p')
-                "#,
-                &[&schema, &table],
-            )
+            .query_opt(lookup.qualified, &[&schema, &table])
             .await?;
 
         match row {
@@ -1446,32 +2508,18 @@ pub async fn resolve_table(client: &Client, object: &str) -> Result<ResolvedTabl
                 name: row.get(2),
             }),
             None => bail!(
-                "Table {}.{} not found",
+                "{} {}.{} not found",
+                lookup.label,
                 quote_ident(schema),
                 quote_ident(table)
             ),
         }
     } else {
         // Unqualified: find all matches, error if ambiguous
-        let rows = client
-            .query(
-                r#"
-                SELECT c.oid::bigint, n.nspname, c.relname
-                FROM pg_class c
-                JOIN pg_namespace n ON c.relnamespace = n.oid
-                WHERE c.relname = $1
-                  AND c.relkind IN ('r', 'p')
-                  AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'pg_toast')
-                ORDER BY
-                    CASE WHEN n.nspname = 'public' THEN 0 ELSE 1 END,
-                    n.nspname
-                "#,
-                &[&table],
-            )
-            .await?;
+        let rows = client.query(lookup.unqualified, &[&table]).await?;
 
         match rows.len() {
-            0 => bail!("Table \"{}\" not found", table),
+            0 => bail!("{} \"{}\" not found", lookup.label, table),
             1 => {
                 let row = &rows[0];
                 Ok(ResolvedTable {
@@ -1484,7 +2532,8 @@ pub async fn resolve_table(client: &Client, object: &str) -> Result<ResolvedTabl
                 let mut schemas: Vec<String> = rows.iter().map(|r| r.get::<_, String>(1)).collect();
                 schemas.sort(); // Alphabetical order for stable error messages
                 bail!(
-                    "Table \"{}\" exists in multiple schemas: {}\nHint: Use fully qualified name (e.g., \"{}\".\"{}\")",
+                    "{} \"{}\" exists in multiple schemas: {}\nHint: Use fully qualified name (e.g., \"{}\".\"{}\")",
+                    lookup.label,
                     table,
                     schemas.join(", "),
                     schemas[0],
@@ -1649,6 +2698,7 @@ mod tests {
         let table = TableDescribe {
             schema: "public".to_string(),
             name: "users".to_string(),
+            comment: None,
             columns: vec![ColumnInfo {
                 name: "id".to_string(),
                 data_type: "integer".to_string(),
@@ -1658,6 +2708,7 @@ mod tests {
                 is_serial: true,
                 default: None,
                 fk_reference: None,
+                comment: None,
             }],
             indexes: vec![],
             constraints: vec![Constraint {
@@ -1690,6 +2741,7 @@ mod tests {
         let table = TableDescribe {
             schema: "public".to_string(),
             name: "users".to_string(),
+            comment: None,
             columns: vec![ColumnInfo {
                 name: "email".to_string(),
                 data_type: "text".to_string(),
@@ -1699,6 +2751,7 @@ mod tests {
                 is_serial: false,
                 default: None,
                 fk_reference: None,
+                comment: None,
             }],
             indexes: vec![],
             constraints: vec![Constraint {
@@ -1729,11 +2782,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_table_describe_format_shows_comments() {
+        let table = TableDescribe {
+            schema: "public".to_string(),
+            name: "users".to_string(),
+            comment: Some("Registered accounts".to_string()),
+            columns: vec![ColumnInfo {
+                name: "email".to_string(),
+                data_type: "text".to_string(),
+                nullable: false,
+                is_primary_key: false,
+                identity: None,
+                is_serial: false,
+                default: None,
+                fk_reference: None,
+                comment: Some("Login address,\nlowercased".to_string()),
+            }],
+            indexes: vec![],
+            constraints: vec![],
+            triggers: vec![],
+            stats: None,
+            details: None,
+            rls: None,
+        };
+
+        let output = table.format(false);
+        assert!(output.starts_with("Comment: Registered accounts\n\nColumns:"));
+        assert!(
+            output.contains("  email  text  NOT NULL  -- Login address, lowercased"),
+            "Column comment should follow the column on one line: {}",
+            output
+        );
+    }
+
+    #[test]
+    fn test_find_match_qualified_name() {
+        let found = |kind: &str, table: Option<&str>| FindMatch {
+            kind: kind.to_string(),
+            schema: "app".to_string(),
+            name: "email".to_string(),
+            table: table.map(|t| t.to_string()),
+            detail: None,
+        };
+        assert_eq!(
+            found("column", Some("users")).qualified_name(),
+            "app.users.email"
+        );
+        assert_eq!(found("index", Some("users")).qualified_name(), "app.email");
+        assert_eq!(found("table", None).qualified_name(), "app.email");
+    }
+
+    #[test]
+    fn test_schema_counts_format() {
+        let counts = SchemaCounts {
+            schema: "app".to_string(),
+            owner: "app_owner".to_string(),
+            tables: 12,
+            views: 1,
+            materialized_views: 0,
+            sequences: 0,
+            functions: 1,
+            total_bytes: 0,
+        };
+        assert_eq!(counts.format_counts(), "12 tables, 1 view, 1 function");
+    }
+
     #[test]
     fn test_table_describe_format_pk_and_unique_both_shown() {
         let table = TableDescribe {
             schema: "public".to_string(),
             name: "users".to_string(),
+            comment: None,
             columns: vec![
                 ColumnInfo {
                     name: "id".to_string(),
@@ -1744,6 +2864,7 @@ mod tests {
                     is_serial: true,
                     default: None,
                     fk_reference: None,
+                    comment: None,
                 },
                 ColumnInfo {
                     name: "email".to_string(),
@@ -1754,6 +2875,7 @@ mod tests {
                     is_serial: false,
                     default: None,
                     fk_reference: None,
+                    comment: None,
                 },
             ],
             indexes: vec![],
@@ -67,6 +67,15 @@ impl TimeoutConfig {
         format!("{}ms", d.as_millis())
     }
 
+    /// Timeout values for display/logging.
+    pub fn effective(&self) -> EffectiveTimeouts {
+        EffectiveTimeouts {
+            connect_timeout_ms: self.connect_timeout.as_millis() as u64,
+            statement_timeout_ms: self.statement_timeout.as_millis() as u64,
+            lock_timeout_ms: self.lock_timeout.as_millis() as u64,
+        }
+    }
+
     /// SQL to set session-level timeouts.
     pub fn session_setup_sql(&self) -> String {
         format!(
@@ -142,11 +151,7 @@ impl DiagnosticSession {
 
     /// Get effective timeout values for display/logging.
     pub fn effective_timeouts(&self) -> EffectiveTimeouts {
-        EffectiveTimeouts {
-            connect_timeout_ms: self.timeouts.connect_timeout.as_millis() as u64,
-            statement_timeout_ms: self.timeouts.statement_timeout.as_millis() as u64,
-            lock_timeout_ms: self.timeouts.lock_timeout.as_millis() as u64,
-        }
+        self.timeouts.effective()
     }
 }
 
@@ -168,7 +173,7 @@ impl std::fmt::Display for EffectiveTimeouts {
     }
 }
 
-/// Parse a duration string like "5s", "500ms", "1m".
+/// Parse a duration string like "5s", "500ms", "1m", "8h", "7d".
 pub fn parse_duration(s: &str) -> Result<Duration> {
     let s = s.trim();
     if s.is_empty() {
@@ -182,6 +187,10 @@ pub fn parse_duration(s: &str) -> Result<Duration> {
         (stripped, "s")
     } else if let Some(stripped) = s.strip_suffix('m') {
         (stripped, "m")
+    } else if let Some(stripped) = s.strip_suffix('h') {
+        (stripped, "h")
+    } else if let Some(stripped) = s.strip_suffix('d') {
+        (stripped, "d")
     } else {
         // Default to seconds if no unit
         (s, "s")
@@ -196,6 +205,8 @@ pub fn parse_duration(s: &str) -> Result<Duration> {
         "ms" => Duration::from_millis(num),
         "s" => Duration::from_secs(num),
         "m" => Duration::from_secs(num * 60),
+        "h" => Duration::from_secs(num * 3600),
+        "d" => Duration::from_secs(num * 86_400),
         _ => anyhow::bail!("Unknown duration unit: '{}'", unit),
     };
 
@@ -285,6 +296,12 @@ mod tests {
         assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
     }
 
+    #[test]
+    fn test_parse_duration_hours_and_days() {
+        assert_eq!(parse_duration("8h").unwrap(), Duration::from_secs(28_800));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::from_secs(604_800));
+    }
+
     #[test]
     fn test_parse_duration_no_unit_defaults_to_seconds() {
         assert_eq!(parse_duration("10").unwrap(), Duration::from_secs(10));
@@ -4,11 +4,17 @@
 //! Uses existing types from introspect.rs - no duplication.
 
 use crate::introspect::{
-    Column, Constraint, DatabaseSchema, EnumType, Extension, Function, IdentityType, Index,
-    MaterializedView, SchemaInfo, Sequence, Table, Trigger, View,
+    format_collation_create, format_composite_type_create, format_domain_create,
+    format_foreign_table_create, format_policy_create, format_server_create,
+    format_user_mapping_create, grant_object_keyword, policy_roles, push_policy_exprs, Collation,
+    Column, CompositeType, Constraint, ConstraintType, DatabaseSchema, Domain, EnumType, Extension,
+    ForeignServer, ForeignTable, Function, Grant, IdentityType, Index, MaterializedView, Policy,
+    Role, RoleMembership, SchemaInfo, Sequence, Table, Trigger, UserMapping, View,
 };
+use crate::sql::{quote_ident, quote_literal};
 use colored::Colorize;
-use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 // =============================================================================
 // Diff Result Types
@@ -27,6 +33,22 @@ pub struct SchemaDiff {
     pub removed_enums: Vec<EnumType>,
     pub modified_enums: Vec<EnumDiff>,
 
+    // Compared by definition, so a changed one is both added and removed
+    pub added_collations: Vec<Collation>,
+    pub removed_collations: Vec<Collation>,
+    pub added_domains: Vec<Domain>,
+    pub removed_domains: Vec<Domain>,
+    pub added_composite_types: Vec<CompositeType>,
+    pub removed_composite_types: Vec<CompositeType>,
+
+    // Also compared by definition
+    pub added_foreign_servers: Vec<ForeignServer>,
+    pub removed_foreign_servers: Vec<ForeignServer>,
+    pub added_user_mappings: Vec<UserMapping>,
+    pub removed_user_mappings: Vec<UserMapping>,
+    pub added_foreign_tables: Vec<ForeignTable>,
+    pub removed_foreign_tables: Vec<ForeignTable>,
+
     pub added_sequences: Vec<Sequence>,
     pub removed_sequences: Vec<Sequence>,
 
@@ -53,6 +75,26 @@ pub struct SchemaDiff {
     pub added_materialized_views: Vec<MaterializedView>,
     pub removed_materialized_views: Vec<MaterializedView>,
     pub modified_materialized_views: Vec<ViewDiff>,
+
+    pub modified_rls: Vec<RlsDiff>,
+    pub added_policies: Vec<Policy>,
+    pub removed_policies: Vec<Policy>,
+    pub modified_policies: Vec<PolicyDiff>,
+
+    pub modified_comments: Vec<CommentDiff>,
+
+    // Only populated when both sides were introspected with privileges
+    pub added_roles: Vec<Role>,
+    pub removed_roles: Vec<Role>,
+    pub modified_roles: Vec<RoleDiff>,
+
+    pub added_memberships: Vec<RoleMembership>,
+    pub removed_memberships: Vec<RoleMembership>,
+
+    pub modified_owners: Vec<OwnerDiff>,
+
+    pub added_grants: Vec<Grant>,
+    pub removed_grants: Vec<Grant>,
 }
 
 /// Diff for a single table
@@ -90,6 +132,82 @@ pub struct EnumDiff {
     pub removed_values: Vec<String>,
 }
 
+/// Table whose row-level security switches differ
+#[derive(Debug)]
+pub struct RlsDiff {
+    pub schema: String,
+    pub name: String,
+    pub from_enabled: bool,
+    pub to_enabled: bool,
+    pub from_forced: bool,
+    pub to_forced: bool,
+}
+
+/// Policy present on both sides with a different definition
+#[derive(Debug)]
+pub struct PolicyDiff {
+    pub from: Policy,
+    pub to: Policy,
+}
+
+impl PolicyDiff {
+    /// ALTER POLICY can change roles and expressions, but not the command,
+    /// permissiveness, or whether an expression is there at all
+    pub fn needs_recreate(&self) -> bool {
+        self.from.command != self.to.command
+            || self.from.permissive != self.to.permissive
+            || self.from.using_expr.is_none() != self.to.using_expr.is_none()
+            || self.from.with_check_expr.is_none() != self.to.with_check_expr.is_none()
+    }
+}
+
+/// Table or column (when `column` is set) whose comment differs
+#[derive(Debug)]
+pub struct CommentDiff {
+    pub schema: String,
+    pub table: String,
+    pub column: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// How one object differs from the source (--from) to the target (--to)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// Exists in the target only
+    Added,
+    /// Exists in the source only
+    Removed,
+    Modified,
+}
+
+impl ChangeKind {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "+",
+            ChangeKind::Removed => "-",
+            ChangeKind::Modified => "~",
+        }
+    }
+}
+
+/// Role whose attributes differ
+#[derive(Debug)]
+pub struct RoleDiff {
+    pub from: Role,
+    pub to: Role,
+}
+
+/// Object present on both sides with different owners
+#[derive(Debug)]
+pub struct OwnerDiff {
+    pub kind: String,
+    pub name: String,
+    pub from_owner: String,
+    pub to_owner: String,
+}
+
 /// Diff for a view (name only; definition comparison deferred to verbose mode)
 #[derive(Debug)]
 pub struct ViewDiff {
@@ -107,6 +225,18 @@ impl SchemaDiff {
             && self.added_enums.is_empty()
             && self.removed_enums.is_empty()
             && self.modified_enums.is_empty()
+            && self.added_collations.is_empty()
+            && self.removed_collations.is_empty()
+            && self.added_domains.is_empty()
+            && self.removed_domains.is_empty()
+            && self.added_composite_types.is_empty()
+            && self.removed_composite_types.is_empty()
+            && self.added_foreign_servers.is_empty()
+            && self.removed_foreign_servers.is_empty()
+            && self.added_user_mappings.is_empty()
+            && self.removed_user_mappings.is_empty()
+            && self.added_foreign_tables.is_empty()
+            && self.removed_foreign_tables.is_empty()
             && self.added_sequences.is_empty()
             && self.removed_sequences.is_empty()
             && self.added_tables.is_empty()
@@ -126,6 +256,19 @@ impl SchemaDiff {
             && self.added_materialized_views.is_empty()
             && self.removed_materialized_views.is_empty()
             && self.modified_materialized_views.is_empty()
+            && self.modified_rls.is_empty()
+            && self.added_policies.is_empty()
+            && self.removed_policies.is_empty()
+            && self.modified_policies.is_empty()
+            && self.modified_comments.is_empty()
+            && self.added_roles.is_empty()
+            && self.removed_roles.is_empty()
+            && self.modified_roles.is_empty()
+            && self.added_memberships.is_empty()
+            && self.removed_memberships.is_empty()
+            && self.modified_owners.is_empty()
+            && self.added_grants.is_empty()
+            && self.removed_grants.is_empty()
     }
 
     /// Get summary counts for display
@@ -142,6 +285,18 @@ impl SchemaDiff {
             indexes: self.added_indexes.len() + self.removed_indexes.len(),
             constraints: self.added_constraints.len() + self.removed_constraints.len(),
             enums: self.added_enums.len() + self.removed_enums.len() + self.modified_enums.len(),
+            types: self.added_collations.len()
+                + self.removed_collations.len()
+                + self.added_domains.len()
+                + self.removed_domains.len()
+                + self.added_composite_types.len()
+                + self.removed_composite_types.len(),
+            foreign: self.added_foreign_servers.len()
+                + self.removed_foreign_servers.len()
+                + self.added_user_mappings.len()
+                + self.removed_user_mappings.len()
+                + self.added_foreign_tables.len()
+                + self.removed_foreign_tables.len(),
             functions: self.added_functions.len() + self.removed_functions.len(),
             views: self.added_views.len() + self.removed_views.len() + self.modified_views.len(),
             triggers: self.added_triggers.len() + self.removed_triggers.len(),
@@ -151,8 +306,252 @@ impl SchemaDiff {
             materialized_views: self.added_materialized_views.len()
                 + self.removed_materialized_views.len()
                 + self.modified_materialized_views.len(),
+            policies: self.modified_rls.len()
+                + self.added_policies.len()
+                + self.removed_policies.len()
+                + self.modified_policies.len(),
+            comments: self.modified_comments.len(),
+            roles: self.added_roles.len()
+                + self.removed_roles.len()
+                + self.modified_roles.len()
+                + self.added_memberships.len()
+                + self.removed_memberships.len(),
+            owners: self.modified_owners.len(),
+            grants: self.added_grants.len() + self.removed_grants.len(),
         }
     }
+
+    /// Every differing object by kind and qualified name. An object listed as
+    /// both added and removed (e.g. a grant whose grant option changed) is modified.
+    pub fn changes(&self) -> BTreeMap<(&'static str, String), ChangeKind> {
+        use ChangeKind::{Added, Modified, Removed};
+
+        let mut changes = BTreeMap::new();
+        let mut record = |kind: &'static str, name: String, change: ChangeKind| {
+            changes
+                .entry((kind, name))
+                .and_modify(|c| {
+                    if *c != change {
+                        *c = Modified
+                    }
+                })
+                .or_insert(change);
+        };
+        let q = |schema: &str, name: &str| format!("{}.{}", schema, name);
+
+        for (list, change) in [
+            (&self.added_extensions, Added),
+            (&self.removed_extensions, Removed),
+        ] {
+            for e in list {
+                record("extension", e.name.clone(), change);
+            }
+        }
+        for (list, change) in [
+            (&self.added_schemas, Added),
+            (&self.removed_schemas, Removed),
+        ] {
+            for s in list {
+                record("schema", s.name.clone(), change);
+            }
+        }
+        for (list, change) in [(&self.added_enums, Added), (&self.removed_enums, Removed)] {
+            for e in list {
+                record("enum", q(&e.schema, &e.name), change);
+            }
+        }
+        for e in &self.modified_enums {
+            record("enum", q(&e.schema, &e.name), Modified);
+        }
+        for (list, change) in [
+            (&self.added_collations, Added),
+            (&self.removed_collations, Removed),
+        ] {
+            for c in list {
+                record("collation", q(&c.schema, &c.name), change);
+            }
+        }
+        for (list, change) in [
+            (&self.added_domains, Added),
+            (&self.removed_domains, Removed),
+        ] {
+            for d in list {
+                record("domain", q(&d.schema, &d.name), change);
+            }
+        }
+        for (list, change) in [
+            (&self.added_composite_types, Added),
+            (&self.removed_composite_types, Removed),
+        ] {
+            for t in list {
+                record("type", q(&t.schema, &t.name), change);
+            }
+        }
+        for (list, change) in [
+            (&self.added_foreign_servers, Added),
+            (&self.removed_foreign_servers, Removed),
+        ] {
+            for s in list {
+                record("server", s.name.clone(), change);
+            }
+        }
+        for (list, change) in [
+            (&self.added_user_mappings, Added),
+            (&self.removed_user_mappings, Removed),
+        ] {
+            for m in list {
+                record(
+                    "user mapping",
+                    format!("{} on {}", m.user, m.server),
+                    change,
+                );
+            }
+        }
+        for (list, change) in [
+            (&self.added_foreign_tables, Added),
+            (&self.removed_foreign_tables, Removed),
+        ] {
+            for t in list {
+                record("foreign table", q(&t.schema, &t.name), change);
+            }
+        }
+        for (list, change) in [
+            (&self.added_sequences, Added),
+            (&self.removed_sequences, Removed),
+        ] {
+            for s in list {
+                record("sequence", q(&s.schema, &s.name), change);
+            }
+        }
+        for (list, change) in [(&self.added_tables, Added), (&self.removed_tables, Removed)] {
+            for t in list {
+                record("table", q(&t.schema, &t.name), change);
+            }
+        }
+        // Columns separately, so changes to different columns don't collide
+        for t in &self.modified_tables {
+            let table = q(&t.schema, &t.name);
+            for c in &t.added_columns {
+                record("column", q(&table, &c.name), Added);
+            }
+            for c in &t.removed_columns {
+                record("column", q(&table, &c.name), Removed);
+            }
+            for c in &t.modified_columns {
+                record("column", q(&table, &c.name), Modified);
+            }
+        }
+        for (list, change) in [(&self.added_views, Added), (&self.removed_views, Removed)] {
+            for v in list {
+                record("view", q(&v.schema, &v.name), change);
+            }
+        }
+        for v in &self.modified_views {
+            record("view", q(&v.schema, &v.name), Modified);
+        }
+        for (list, change) in [
+            (&self.added_indexes, Added),
+            (&self.removed_indexes, Removed),
+        ] {
+            for i in list {
+                record("index", q(&i.schema, &i.name), change);
+            }
+        }
+        for (list, change) in [
+            (&self.added_constraints, Added),
+            (&self.removed_constraints, Removed),
+        ] {
+            for c in list {
+                record(
+                    "constraint",
+                    q(&q(&c.schema, &c.table_name), &c.name),
+                    change,
+                );
+            }
+        }
+        for (list, change) in [
+            (&self.added_triggers, Added),
+            (&self.removed_triggers, Removed),
+        ] {
+            for t in list {
+                record("trigger", q(&q(&t.schema, &t.table_name), &t.name), change);
+            }
+        }
+        for (list, change) in [
+            (&self.added_functions, Added),
+            (&self.removed_functions, Removed),
+        ] {
+            for f in list {
+                record("function", f.identity.clone(), change);
+            }
+        }
+        for (list, change) in [
+            (&self.added_materialized_views, Added),
+            (&self.removed_materialized_views, Removed),
+        ] {
+            for m in list {
+                record("materialized view", q(&m.schema, &m.name), change);
+            }
+        }
+        for m in &self.modified_materialized_views {
+            record("materialized view", q(&m.schema, &m.name), Modified);
+        }
+        for r in &self.modified_rls {
+            record("row-level security", q(&r.schema, &r.name), Modified);
+        }
+        for (list, change) in [
+            (&self.added_policies, Added),
+            (&self.removed_policies, Removed),
+        ] {
+            for p in list {
+                record("policy", q(&q(&p.schema, &p.table_name), &p.name), change);
+            }
+        }
+        for p in &self.modified_policies {
+            record(
+                "policy",
+                q(&q(&p.to.schema, &p.to.table_name), &p.to.name),
+                Modified,
+            );
+        }
+        for c in &self.modified_comments {
+            let table = q(&c.schema, &c.table);
+            let name = match c.column {
+                Some(ref column) => q(&table, column),
+                None => table,
+            };
+            record("comment", name, Modified);
+        }
+        for (list, change) in [(&self.added_roles, Added), (&self.removed_roles, Removed)] {
+            for r in list {
+                record("role", r.name.clone(), change);
+            }
+        }
+        for r in &self.modified_roles {
+            record("role", r.to.name.clone(), Modified);
+        }
+        for (list, change) in [
+            (&self.added_memberships, Added),
+            (&self.removed_memberships, Removed),
+        ] {
+            for m in list {
+                record("membership", format!("{} in {}", m.member, m.role), change);
+            }
+        }
+        for o in &self.modified_owners {
+            record("owner", format!("{} {}", o.kind, o.name), Modified);
+        }
+        for (list, change) in [(&self.added_grants, Added), (&self.removed_grants, Removed)] {
+            for g in list {
+                let name = format!(
+                    "{} on {} {} to {}",
+                    g.privilege, g.kind, g.object, g.grantee
+                );
+                record("grant", name, change);
+            }
+        }
+        changes
+    }
 }
 
 #[derive(Debug, Default)]
@@ -162,6 +561,10 @@ pub struct DiffSummary {
     pub indexes: usize,
     pub constraints: usize,
     pub enums: usize,
+    /// Domains, composite types, and collations
+    pub types: usize,
+    /// Foreign servers, user mappings, and foreign tables
+    pub foreign: usize,
     pub functions: usize,
     pub views: usize,
     pub triggers: usize,
@@ -169,6 +572,13 @@ pub struct DiffSummary {
     pub extensions: usize,
     pub schemas: usize,
     pub materialized_views: usize,
+    /// Policies and tables whose row-level security differs
+    pub policies: usize,
+    pub comments: usize,
+    /// Roles and role memberships
+    pub roles: usize,
+    pub owners: usize,
+    pub grants: usize,
 }
 
 // =============================================================================
@@ -212,6 +622,52 @@ pub fn diff_schemas(from: &DatabaseSchema, to: &DatabaseSchema) -> SchemaDiff {
         }
     }
 
+    // Collations, domains, and composite types (by definition)
+    diff_by_name(
+        &from.collations,
+        &to.collations,
+        format_collation_create,
+        &mut diff.added_collations,
+        &mut diff.removed_collations,
+    );
+    diff_by_name(
+        &from.domains,
+        &to.domains,
+        format_domain_create,
+        &mut diff.added_domains,
+        &mut diff.removed_domains,
+    );
+    diff_by_name(
+        &from.composite_types,
+        &to.composite_types,
+        format_composite_type_create,
+        &mut diff.added_composite_types,
+        &mut diff.removed_composite_types,
+    );
+
+    // Foreign servers, user mappings, and foreign tables (by definition)
+    diff_by_name(
+        &from.foreign_servers,
+        &to.foreign_servers,
+        format_server_create,
+        &mut diff.added_foreign_servers,
+        &mut diff.removed_foreign_servers,
+    );
+    diff_by_name(
+        &from.user_mappings,
+        &to.user_mappings,
+        format_user_mapping_create,
+        &mut diff.added_user_mappings,
+        &mut diff.removed_user_mappings,
+    );
+    diff_by_name(
+        &from.foreign_tables,
+        &to.foreign_tables,
+        format_foreign_table_create,
+        &mut diff.added_foreign_tables,
+        &mut diff.removed_foreign_tables,
+    );
+
     // Sequences (by qualified name)
     diff_by_name(
         &from.sequences,
@@ -229,7 +685,7 @@ pub fn diff_schemas(from: &DatabaseSchema, to: &DatabaseSchema) -> SchemaDiff {
     diff.added_tables = added_tables;
     diff.removed_tables = removed_tables;
 
-    // Check modified tables (column changes)
+    // Check modified tables (column changes), row-level security, and comments
     for (from_table, to_table) in common_tables {
         let table_diff = diff_table(from_table, to_table);
         if !table_diff.added_columns.is_empty()
@@ -238,6 +694,35 @@ pub fn diff_schemas(from: &DatabaseSchema, to: &DatabaseSchema) -> SchemaDiff {
         {
             diff.modified_tables.push(table_diff);
         }
+        if from_table.rls_enabled != to_table.rls_enabled
+            || from_table.rls_forced != to_table.rls_forced
+        {
+            diff.modified_rls.push(RlsDiff {
+                schema: to_table.schema.clone(),
+                name: to_table.name.clone(),
+                from_enabled: from_table.rls_enabled,
+                to_enabled: to_table.rls_enabled,
+                from_forced: from_table.rls_forced,
+                to_forced: to_table.rls_forced,
+            });
+        }
+        diff_comments(from_table, to_table, &mut diff.modified_comments);
+    }
+
+    // Policies (by schema.table.policy_name)
+    let (added_policies, removed_policies, common_policies) =
+        diff_by_key(&from.policies, &to.policies, |p| {
+            (p.schema.clone(), p.table_name.clone(), p.name.clone())
+        });
+    diff.added_policies = added_policies;
+    diff.removed_policies = removed_policies;
+    for (from_policy, to_policy) in common_policies {
+        if from_policy != to_policy {
+            diff.modified_policies.push(PolicyDiff {
+                from: from_policy.clone(),
+                to: to_policy.clone(),
+            });
+        }
     }
 
     // Views (by qualified name)
@@ -311,12 +796,79 @@ pub fn diff_schemas(from: &DatabaseSchema, to: &DatabaseSchema) -> SchemaDiff {
         }
     }
 
+    diff_privileges(from, to, &mut diff);
+
     // Sort all results for deterministic output
     sort_diff(&mut diff);
 
     diff
 }
 
+/// Compare roles, memberships, ownership, and grants (empty unless introspected)
+fn diff_privileges(from: &DatabaseSchema, to: &DatabaseSchema, diff: &mut SchemaDiff) {
+    let (from, to) = (&from.privileges, &to.privileges);
+
+    let (added_roles, removed_roles, common_roles) =
+        diff_by_key(&from.roles, &to.roles, |r| r.name.clone());
+    diff.added_roles = added_roles;
+    diff.removed_roles = removed_roles;
+    for (from_role, to_role) in common_roles {
+        if from_role != to_role {
+            diff.modified_roles.push(RoleDiff {
+                from: from_role.clone(),
+                to: to_role.clone(),
+            });
+        }
+    }
+
+    diff_by_name(
+        &from.memberships,
+        &to.memberships,
+        |m| (m.role.clone(), m.member.clone(), m.admin_option),
+        &mut diff.added_memberships,
+        &mut diff.removed_memberships,
+    );
+
+    // Owners of objects on one side only are covered by the object diff
+    let (_, _, common_owners) = diff_by_key(&from.owners, &to.owners, |o| {
+        (o.kind.clone(), o.name.clone())
+    });
+    for (from_owner, to_owner) in common_owners {
+        if from_owner.owner != to_owner.owner {
+            diff.modified_owners.push(OwnerDiff {
+                kind: from_owner.kind.clone(),
+                name: from_owner.name.clone(),
+                from_owner: from_owner.owner.clone(),
+                to_owner: to_owner.owner.clone(),
+            });
+        }
+    }
+
+    diff_by_name(
+        &from.grants,
+        &to.grants,
+        |g| {
+            (
+                g.kind.clone(),
+                g.object.clone(),
+                g.grantee.clone(),
+                g.privilege.clone(),
+                g.grantable,
+            )
+        },
+        &mut diff.added_grants,
+        &mut diff.removed_grants,
+    );
+    // Grants on objects only in the target go away with the object
+    let source_objects: HashSet<(&str, &str)> = from
+        .owners
+        .iter()
+        .map(|o| (o.kind.as_str(), o.name.as_str()))
+        .collect();
+    diff.added_grants
+        .retain(|g| source_objects.contains(&(g.kind.as_str(), g.object.as_str())));
+}
+
 /// Sort all diff results for deterministic output ordering
 fn sort_diff(diff: &mut SchemaDiff) {
     // Extensions by name
@@ -335,6 +887,34 @@ fn sort_diff(diff: &mut SchemaDiff) {
     diff.modified_enums
         .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
 
+    // Types by qualified name
+    diff.added_collations
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    diff.removed_collations
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    diff.added_domains
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    diff.removed_domains
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    diff.added_composite_types
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    diff.removed_composite_types
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+
+    // Foreign servers and user mappings by server, foreign tables by qualified name
+    diff.added_foreign_servers
+        .sort_by(|a, b| a.name.cmp(&b.name));
+    diff.removed_foreign_servers
+        .sort_by(|a, b| a.name.cmp(&b.name));
+    diff.added_user_mappings
+        .sort_by(|a, b| (&a.server, &a.user).cmp(&(&b.server, &b.user)));
+    diff.removed_user_mappings
+        .sort_by(|a, b| (&a.server, &a.user).cmp(&(&b.server, &b.user)));
+    diff.added_foreign_tables
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    diff.removed_foreign_tables
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+
     // Sequences by qualified name
     diff.added_sequences
         .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
@@ -392,6 +972,47 @@ fn sort_diff(diff: &mut SchemaDiff) {
         .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
     diff.modified_materialized_views
         .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+
+    // Row-level security and policies by schema.table(.name)
+    diff.modified_rls
+        .sort_by(|a, b| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)));
+    for policies in [&mut diff.added_policies, &mut diff.removed_policies] {
+        policies.sort_by(|a, b| {
+            (&a.schema, &a.table_name, &a.name).cmp(&(&b.schema, &b.table_name, &b.name))
+        });
+    }
+    diff.modified_policies.sort_by(|a, b| {
+        (&a.to.schema, &a.to.table_name, &a.to.name).cmp(&(
+            &b.to.schema,
+            &b.to.table_name,
+            &b.to.name,
+        ))
+    });
+
+    // Comments by table, table comment before its columns
+    diff.modified_comments
+        .sort_by(|a, b| (&a.schema, &a.table, &a.column).cmp(&(&b.schema, &b.table, &b.column)));
+
+    // Privileges by role, then object
+    diff.added_roles.sort_by(|a, b| a.name.cmp(&b.name));
+    diff.removed_roles.sort_by(|a, b| a.name.cmp(&b.name));
+    diff.modified_roles
+        .sort_by(|a, b| a.from.name.cmp(&b.from.name));
+    for memberships in [&mut diff.added_memberships, &mut diff.removed_memberships] {
+        memberships.sort_by(|a, b| (&a.role, &a.member).cmp(&(&b.role, &b.member)));
+    }
+    diff.modified_owners
+        .sort_by(|a, b| (&a.kind, &a.name).cmp(&(&b.kind, &b.name)));
+    for grants in [&mut diff.added_grants, &mut diff.removed_grants] {
+        grants.sort_by(|a, b| {
+            (&a.kind, &a.object, &a.grantee, &a.privilege).cmp(&(
+                &b.kind,
+                &b.object,
+                &b.grantee,
+                &b.privilege,
+            ))
+        });
+    }
 }
 
 /// Compare two tables and return column-level differences
@@ -447,6 +1068,34 @@ fn diff_table(from: &Table, to: &Table) -> TableDiff {
     diff
 }
 
+/// Collect table and column comments that differ between two tables
+/// (columns on one side only are covered by the column diff)
+fn diff_comments(from: &Table, to: &Table, comments: &mut Vec<CommentDiff>) {
+    if from.comment != to.comment {
+        comments.push(CommentDiff {
+            schema: to.schema.clone(),
+            table: to.name.clone(),
+            column: None,
+            from: from.comment.clone(),
+            to: to.comment.clone(),
+        });
+    }
+    for from_col in &from.columns {
+        let Some(to_col) = to.columns.iter().find(|c| c.name == from_col.name) else {
+            continue;
+        };
+        if from_col.comment != to_col.comment {
+            comments.push(CommentDiff {
+                schema: to.schema.clone(),
+                table: to.name.clone(),
+                column: Some(to_col.name.clone()),
+                from: from_col.comment.clone(),
+                to: to_col.comment.clone(),
+            });
+        }
+    }
+}
+
 /// Check if two columns are different
 fn is_column_different(from: &Column, to: &Column) -> bool {
     from.data_type != to.data_type
@@ -627,6 +1276,89 @@ pub fn format_diff(diff: &SchemaDiff, from_label: &str, to_label: &str) -> Strin
         }
     }
 
+    // Collations, domains, and composite types; a changed one is listed
+    // as removed and added
+    let mut types = Vec::new();
+    for (kind, schema, name, added) in diff
+        .added_collations
+        .iter()
+        .map(|c| ("collation", &c.schema, &c.name, true))
+        .chain(
+            diff.removed_collations
+                .iter()
+                .map(|c| ("collation", &c.schema, &c.name, false)),
+        )
+        .chain(
+            diff.added_domains
+                .iter()
+                .map(|d| ("domain", &d.schema, &d.name, true)),
+        )
+        .chain(
+            diff.removed_domains
+                .iter()
+                .map(|d| ("domain", &d.schema, &d.name, false)),
+        )
+        .chain(
+            diff.added_composite_types
+                .iter()
+                .map(|t| ("type", &t.schema, &t.name, true)),
+        )
+        .chain(
+            diff.removed_composite_types
+                .iter()
+                .map(|t| ("type", &t.schema, &t.name, false)),
+        )
+    {
+        let symbol = if added { "+".green() } else { "-".red() };
+        types.push(format!("  {} {} {}.{}", symbol, kind, schema, name));
+    }
+    if !types.is_empty() {
+        output.push(String::new());
+        output.push("Types:".to_string());
+        output.extend(types);
+    }
+
+    // Foreign servers, user mappings, and foreign tables, listed the same way
+    let mut foreign = Vec::new();
+    for (kind, name, added) in diff
+        .added_foreign_servers
+        .iter()
+        .map(|s| ("server", s.name.clone(), true))
+        .chain(
+            diff.removed_foreign_servers
+                .iter()
+                .map(|s| ("server", s.name.clone(), false)),
+        )
+        .chain(
+            diff.added_user_mappings
+                .iter()
+                .map(|m| ("user mapping", format!("{} on {}", m.user, m.server), true)),
+        )
+        .chain(
+            diff.removed_user_mappings
+                .iter()
+                .map(|m| ("user mapping", format!("{} on {}", m.user, m.server), false)),
+        )
+        .chain(
+            diff.added_foreign_tables
+                .iter()
+                .map(|t| ("foreign table", format!("{}.{}", t.schema, t.name), true)),
+        )
+        .chain(
+            diff.removed_foreign_tables
+                .iter()
+                .map(|t| ("foreign table", format!("{}.{}", t.schema, t.name), false)),
+        )
+    {
+        let symbol = if added { "+".green() } else { "-".red() };
+        foreign.push(format!("  {} {} {}", symbol, kind, name));
+    }
+    if !foreign.is_empty() {
+        output.push(String::new());
+        output.push("Foreign data:".to_string());
+        output.extend(foreign);
+    }
+
     // Sequences
     if !diff.added_sequences.is_empty() || !diff.removed_sequences.is_empty() {
         output.push(String::new());
@@ -814,6 +1546,135 @@ pub fn format_diff(diff: &SchemaDiff, from_label: &str, to_label: &str) -> Strin
         }
     }
 
+    // Row-level security
+    if !diff.modified_rls.is_empty() {
+        output.push(String::new());
+        output.push("Row-Level Security:".to_string());
+        for r in &diff.modified_rls {
+            output.push(format!(
+                "  {} {}.{} ({})",
+                "~".yellow(),
+                r.schema,
+                r.name,
+                format_rls_changes(r)
+            ));
+        }
+    }
+
+    // Policies
+    if !diff.added_policies.is_empty()
+        || !diff.removed_policies.is_empty()
+        || !diff.modified_policies.is_empty()
+    {
+        output.push(String::new());
+        output.push("Policies:".to_string());
+        for p in &diff.added_policies {
+            output.push(format!(
+                "  {} {} ON {}.{}",
+                "+".green(),
+                p.name,
+                p.schema,
+                p.table_name
+            ));
+        }
+        for p in &diff.removed_policies {
+            output.push(format!(
+                "  {} {} ON {}.{}",
+                "-".red(),
+                p.name,
+                p.schema,
+                p.table_name
+            ));
+        }
+        for p in &diff.modified_policies {
+            output.push(format!(
+                "  {} {} ON {}.{} ({})",
+                "~".yellow(),
+                p.to.name,
+                p.to.schema,
+                p.to.table_name,
+                format_policy_changes(&p.from, &p.to)
+            ));
+        }
+    }
+
+    // Comments
+    if !diff.modified_comments.is_empty() {
+        output.push(String::new());
+        output.push("Comments:".to_string());
+        for c in &diff.modified_comments {
+            let object = match c.column {
+                Some(ref column) => format!("column {}.{}.{}", c.schema, c.table, column),
+                None => format!("table {}.{}", c.schema, c.table),
+            };
+            let state = match (&c.from, &c.to) {
+                (Some(_), None) => "source only",
+                (None, Some(_)) => "target only",
+                _ => "text differs",
+            };
+            output.push(format!("  {} {} ({})", "~".yellow(), object, state));
+        }
+    }
+
+    // Roles and memberships
+    if !diff.added_roles.is_empty()
+        || !diff.removed_roles.is_empty()
+        || !diff.modified_roles.is_empty()
+        || !diff.added_memberships.is_empty()
+        || !diff.removed_memberships.is_empty()
+    {
+        output.push(String::new());
+        output.push("Roles:".to_string());
+        for role in &diff.added_roles {
+            output.push(format!("  {} {}", "+".green(), role.name));
+        }
+        for role in &diff.removed_roles {
+            output.push(format!("  {} {}", "-".red(), role.name));
+        }
+        for role in &diff.modified_roles {
+            output.push(format!(
+                "  {} {} ({})",
+                "~".yellow(),
+                role.from.name,
+                format_role_changes(&role.from, &role.to)
+            ));
+        }
+        for m in &diff.added_memberships {
+            output.push(format!("  {} {}", "+".green(), format_membership(m)));
+        }
+        for m in &diff.removed_memberships {
+            output.push(format!("  {} {}", "-".red(), format_membership(m)));
+        }
+    }
+
+    // Ownership
+    if !diff.modified_owners.is_empty() {
+        output.push(String::new());
+        output.push("Ownership:".to_string());
+        for o in &diff.modified_owners {
+            output.push(format!(
+                "  {} {} {}: {} → {}",
+                "~".yellow(),
+                o.kind,
+                o.name,
+                o.from_owner,
+                o.to_owner
+            ));
+        }
+    }
+
+    // Grants
+    if !diff.added_grants.is_empty() || !diff.removed_grants.is_empty() {
+        output.push(String::new());
+        output.push("Grants:".to_string());
+        for g in &diff.added_grants {
+            output.push(format!("  {} {}", "+".green(), format_grant(g)));
+        }
+        for g in &diff.removed_grants {
+            output.push(format!("  {} {}", "-".red(), format_grant(g)));
+        }
+    }
+
     // Summary
     let summary = diff.summary();
     let mut summary_parts = Vec::new();
@@ -833,6 +1694,12 @@ pub fn format_diff(diff: &SchemaDiff, from_label: &str, to_label: &str) -> Strin
     if summary.enums > 0 {
         summary_parts.push(format!("{} enums", summary.enums));
     }
+    if summary.types > 0 {
+        summary_parts.push(format!("{} types", summary.types));
+    }
+    if summary.foreign > 0 {
+        summary_parts.push(format!("{} foreign objects", summary.foreign));
+    }
     if summary.functions > 0 {
         summary_parts.push(format!("{} functions", summary.functions));
     }
@@ -854,6 +1721,21 @@ pub fn format_diff(diff: &SchemaDiff, from_label: &str, to_label: &str) -> Strin
     if summary.schemas > 0 {
         summary_parts.push(format!("{} schemas", summary.schemas));
     }
+    if summary.policies > 0 {
+        summary_parts.push(format!("{} policies", summary.policies));
+    }
+    if summary.comments > 0 {
+        summary_parts.push(format!("{} comments", summary.comments));
+    }
+    if summary.roles > 0 {
+        summary_parts.push(format!("{} roles", summary.roles));
+    }
+    if summary.owners > 0 {
+        summary_parts.push(format!("{} owners", summary.owners));
+    }
+    if summary.grants > 0 {
+        summary_parts.push(format!("{} grants", summary.grants));
+    }
 
     if !summary_parts.is_empty() {
         output.push(String::new());
@@ -863,6 +1745,56 @@ pub fn format_diff(diff: &SchemaDiff, from_label: &str, to_label: &str) -> Strin
     output.join("\n")
 }
 
+/// Format row-level security changes as a readable string
+fn format_rls_changes(r: &RlsDiff) -> String {
+    let mut changes = Vec::new();
+    if r.from_enabled != r.to_enabled {
+        let state = |on: bool| if on { "enabled" } else { "disabled" };
+        changes.push(format!(
+            "{} → {}",
+            state(r.from_enabled),
+            state(r.to_enabled)
+        ));
+    }
+    if r.from_forced != r.to_forced {
+        let state = |on: bool| if on { "forced" } else { "not forced" };
+        changes.push(format!("{} → {}", state(r.from_forced), state(r.to_forced)));
+    }
+    changes.join(", ")
+}
+
+/// Format policy changes as a readable string
+fn format_policy_changes(from: &Policy, to: &Policy) -> String {
+    let mut changes = Vec::new();
+    if from.command != to.command {
+        changes.push(format!("FOR {} → {}", from.command, to.command));
+    }
+    if from.permissive != to.permissive {
+        let kind = |permissive: bool| {
+            if permissive {
+                "PERMISSIVE"
+            } else {
+                "RESTRICTIVE"
+            }
+        };
+        changes.push(format!(
+            "{} → {}",
+            kind(from.permissive),
+            kind(to.permissive)
+        ));
+    }
+    if from.roles != to.roles {
+        changes.push(format!("TO {} → {}", policy_roles(from), policy_roles(to)));
+    }
+    if from.using_expr != to.using_expr {
+        changes.push("USING differs".to_string());
+    }
+    if from.with_check_expr != to.with_check_expr {
+        changes.push("WITH CHECK differs".to_string());
+    }
+    changes.join(", ")
+}
+
 /// Format column changes as a readable string
 fn format_column_changes(col: &ColumnDiff) -> String {
     let mut changes = Vec::new();
@@ -906,6 +1838,56 @@ fn format_column_changes(col: &ColumnDiff) -> String {
     changes.join(", ")
 }
 
+/// Role attributes as (enabled, disabled) keywords
+fn role_attributes(role: &Role) -> [(bool, &'static str, &'static str); 6] {
+    [
+        (role.login, "LOGIN", "NOLOGIN"),
+        (role.superuser, "SUPERUSER", "NOSUPERUSER"),
+        (role.createdb, "CREATEDB", "NOCREATEDB"),
+        (role.createrole, "CREATEROLE", "NOCREATEROLE"),
+        (role.replication, "REPLICATION", "NOREPLICATION"),
+        (role.bypassrls, "BYPASSRLS", "NOBYPASSRLS"),
+    ]
+}
+
+/// Format role attribute changes as a readable string
+fn format_role_changes(from: &Role, to: &Role) -> String {
+    role_attributes(from)
+        .iter()
+        .zip(role_attributes(to))
+        .filter(|(f, t)| f.0 != t.0)
+        .map(|(f, _)| {
+            if f.0 {
+                format!("{} → {}", f.1, f.2)
+            } else {
+                format!("{} → {}", f.2, f.1)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_membership(m: &RoleMembership) -> String {
+    let admin = if m.admin_option {
+        " (WITH ADMIN OPTION)"
+    } else {
+        ""
+    };
+    format!("{} member of {}{}", m.member, m.role, admin)
+}
+
+fn format_grant(g: &Grant) -> String {
+    let option = if g.grantable {
+        " (WITH GRANT OPTION)"
+    } else {
+        ""
+    };
+    format!(
+        "{} ON {} {} TO {}{}",
+        g.privilege, g.kind, g.object, g.grantee, option
+    )
+}
+
 /// Format identity type for display
 fn format_identity(identity: &Option<IdentityType>) -> &'static str {
     match identity {
@@ -915,6 +1897,639 @@ fn format_identity(identity: &Option<IdentityType>) -> &'static str {
     }
 }
 
+// =============================================================================
+// SQL Generation
+// =============================================================================
+
+/// Generate SQL that brings the target (--to) in line with the source (--from).
+///
+/// Objects only in the source are created, objects only in the target are
+/// dropped, and changed columns, enum values, views, policies, row-level
+/// security, and comments are altered in place.
+/// With `safe_only`, statements that can lose data (drops, column type
+/// changes, materialized view rebuilds) are left out and counted in a comment.
+pub fn diff_to_sql(diff: &SchemaDiff, source: &DatabaseSchema, safe_only: bool) -> String {
+    let mut parts = vec![
+        "-- Generated by pgcrate inspect diff: brings the target (--to) in line with the source (--from)"
+            .to_string(),
+    ];
+    if diff.is_empty() {
+        parts.push("-- Schemas are identical; nothing to do.".to_string());
+        return parts.join("\n");
+    }
+
+    let rebuilt_matviews: Vec<MaterializedView> = diff
+        .modified_materialized_views
+        .iter()
+        .filter_map(|v| {
+            source
+                .materialized_views
+                .iter()
+                .find(|m| m.schema == v.schema && m.name == v.name)
+                .cloned()
+        })
+        .collect();
+
+    let privileges = privilege_sql(diff);
+    let policies = policy_sql(diff, safe_only);
+    let destructive = destructive_sql(diff, &rebuilt_matviews, policies.drops, privileges.revokes);
+    let (alters, destructive_alters) = alter_sql(diff, source);
+
+    if safe_only {
+        let omitted = destructive
+            .iter()
+            .chain(&destructive_alters)
+            .chain(&privileges.dropped_roles)
+            .filter(|l| l.ends_with(';'))
+            .count();
+        if omitted > 0 {
+            parts.push(format!(
+                "-- --safe-only: omitted {} destructive statement{} (drops, revokes, type changes, rebuilds)",
+                omitted,
+                if omitted == 1 { "" } else { "s" }
+            ));
+        }
+    }
+
+    // Roles first: everything below may be owned by or granted to them
+    if !privileges.roles.is_empty() {
+        parts.push(String::new());
+        parts.extend(privileges.roles);
+    }
+
+    if !safe_only && !destructive.is_empty() {
+        parts.push(String::new());
+        parts.push(
+            "-- DESTRUCTIVE: drops and revokes for what exists only in the target".to_string(),
+        );
+        parts.extend(destructive);
+    }
+
+    // Containers and tables first, so added columns can use new types
+    let pre = DatabaseSchema {
+        extensions: diff.removed_extensions.clone(),
+        schemas: diff.removed_schemas.clone(),
+        collations: diff.removed_collations.clone(),
+        enums: diff.removed_enums.clone(),
+        domains: diff.removed_domains.clone(),
+        composite_types: diff.removed_composite_types.clone(),
+        foreign_servers: diff.removed_foreign_servers.clone(),
+        user_mappings: diff.removed_user_mappings.clone(),
+        sequences: diff.removed_sequences.clone(),
+        tables: diff.removed_tables.clone(),
+        foreign_tables: diff.removed_foreign_tables.clone(),
+        ..Default::default()
+    };
+    let (pre_sql, _) = crate::introspect::schema_to_sql(&pre);
+    if !pre_sql.is_empty() {
+        parts.push(String::new());
+        parts.push(pre_sql.trim_end().to_string());
+    }
+
+    // Type changes before the views below start depending on the columns
+    if !safe_only && !destructive_alters.is_empty() {
+        parts.push(String::new());
+        parts.push("-- DESTRUCTIVE: changes that can lose data".to_string());
+        parts.extend(destructive_alters);
+    }
+
+    if !alters.is_empty() {
+        parts.push(String::new());
+        parts.extend(alters);
+    }
+
+    // Everything that can depend on the tables and columns above
+    let mut matviews = diff.removed_materialized_views.clone();
+    if !safe_only {
+        matviews.extend(rebuilt_matviews);
+    }
+    let post = DatabaseSchema {
+        views: diff.removed_views.clone(),
+        indexes: diff.removed_indexes.clone(),
+        constraints: diff.removed_constraints.clone(),
+        triggers: diff.removed_triggers.clone(),
+        functions: diff.removed_functions.clone(),
+        materialized_views: matviews,
+        ..Default::default()
+    };
+    let (post_sql, _) = crate::introspect::schema_to_sql(&post);
+    if !post_sql.is_empty() {
+        parts.push(String::new());
+        parts.push(post_sql.trim_end().to_string());
+    }
+
+    // Policy expressions may call the functions created above
+    if !policies.creates.is_empty() {
+        parts.push(String::new());
+        parts.extend(policies.creates);
+    }
+
+    if !privileges.grants.is_empty() {
+        parts.push(String::new());
+        parts.extend(privileges.grants);
+    }
+
+    // Last, once nothing is owned by or granted to them
+    if !safe_only && !privileges.dropped_roles.is_empty() {
+        parts.push(String::new());
+        parts.push("-- DESTRUCTIVE: roles that exist only in the target".to_string());
+        parts.extend(privileges.dropped_roles);
+    }
+
+    parts.join("\n")
+}
+
+/// Role, membership, ownership, and grant statements, by where they run
+#[derive(Default)]
+struct PrivilegeSql {
+    /// CREATE/ALTER ROLE, before everything else
+    roles: Vec<String>,
+    /// Memberships and grants only in the target (destructive)
+    revokes: Vec<String>,
+    /// Memberships, grants, and owners from the source, after every object exists
+    grants: Vec<String>,
+    /// Roles only in the target (destructive), last
+    dropped_roles: Vec<String>,
+}
+
+fn privilege_sql(diff: &SchemaDiff) -> PrivilegeSql {
+    let mut sql = PrivilegeSql::default();
+    let grantee = |name: &str| {
+        if name == "PUBLIC" {
+            name.to_string()
+        } else {
+            quote_ident(name)
+        }
+    };
+    let attributes = |role: &Role| {
+        role_attributes(role)
+            .iter()
+            .map(|(on, yes, no)| if *on { *yes } else { *no })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut roles = vec!["-- Roles".to_string()];
+    for role in &diff.removed_roles {
+        roles.push(format!(
+            "CREATE ROLE {} WITH {};",
+            quote_ident(&role.name),
+            attributes(role)
+        ));
+    }
+    if diff.removed_roles.iter().any(|r| r.login) {
+        roles
+            .push("-- Passwords are not copied; set them with ALTER ROLE ... PASSWORD".to_string());
+    }
+    for role in &diff.modified_roles {
+        roles.push(format!(
+            "ALTER ROLE {} WITH {};",
+            quote_ident(&role.from.name),
+            attributes(&role.from)
+        ));
+    }
+    if roles.len() > 1 {
+        sql.roles = roles;
+    }
+
+    for m in &diff.added_memberships {
+        sql.revokes.push(format!(
+            "REVOKE {} FROM {};",
+            quote_ident(&m.role),
+            quote_ident(&m.member)
+        ));
+    }
+    for g in &diff.added_grants {
+        sql.revokes.push(format!(
+            "REVOKE {} ON {} {} FROM {};",
+            g.privilege,
+            grant_object_keyword(&g.kind),
+            g.object,
+            grantee(&g.grantee)
+        ));
+    }
+
+    let mut grants = vec!["-- Privileges".to_string()];
+    for m in &diff.removed_memberships {
+        let admin = if m.admin_option {
+            " WITH ADMIN OPTION"
+        } else {
+            ""
+        };
+        grants.push(format!(
+            "GRANT {} TO {}{};",
+            quote_ident(&m.role),
+            quote_ident(&m.member),
+            admin
+        ));
+    }
+    for g in &diff.removed_grants {
+        let option = if g.grantable {
+            " WITH GRANT OPTION"
+        } else {
+            ""
+        };
+        grants.push(format!(
+            "GRANT {} ON {} {} TO {}{};",
+            g.privilege,
+            grant_object_keyword(&g.kind),
+            g.object,
+            grantee(&g.grantee),
+            option
+        ));
+    }
+    for o in &diff.modified_owners {
+        grants.push(format!(
+            "ALTER {} {} OWNER TO {};",
+            o.kind.to_uppercase(),
+            o.name,
+            quote_ident(&o.from_owner)
+        ));
+    }
+    if grants.len() > 1 {
+        sql.grants = grants;
+    }
+
+    for role in &diff.added_roles {
+        sql.dropped_roles
+            .push(format!("DROP ROLE IF EXISTS {};", quote_ident(&role.name)));
+    }
+    sql
+}
+
+/// Row-level security statements, by where they run
+#[derive(Default)]
+struct PolicySql {
+    /// Policies only in the target or to recreate (destructive), before the
+    /// columns and functions they use are dropped
+    drops: Vec<String>,
+    /// RLS switches and policies from the source, after every object exists
+    creates: Vec<String>,
+}
+
+fn policy_sql(diff: &SchemaDiff, safe_only: bool) -> PolicySql {
+    let mut sql = PolicySql::default();
+
+    // Policies go away with their table
+    let dropped_tables: HashSet<(&str, &str)> = diff
+        .added_tables
+        .iter()
+        .map(|t| (t.schema.as_str(), t.name.as_str()))
+        .collect();
+    let drop_policy = |p: &Policy| {
+        format!(
+            "DROP POLICY IF EXISTS {} ON {}.{};",
+            quote_ident(&p.name),
+            quote_ident(&p.schema),
+            quote_ident(&p.table_name)
+        )
+    };
+    for p in &diff.added_policies {
+        if !dropped_tables.contains(&(p.schema.as_str(), p.table_name.as_str())) {
+            sql.drops.push(drop_policy(p));
+        }
+    }
+
+    let mut policies = Vec::new();
+    for p in &diff.modified_policies {
+        if p.needs_recreate() {
+            sql.drops.push(drop_policy(&p.to));
+            // Without the drop, CREATE would fail on the existing policy
+            if !safe_only {
+                policies.push(format_policy_create(&p.from));
+            }
+        } else {
+            policies.push(format_policy_alter(&p.from));
+        }
+    }
+    policies.extend(diff.removed_policies.iter().map(format_policy_create));
+
+    let mut switches = Vec::new();
+    let mut switch = |schema: &str, name: &str, action: &str| {
+        switches.push(format!(
+            "ALTER TABLE {}.{} {} ROW LEVEL SECURITY;",
+            quote_ident(schema),
+            quote_ident(name),
+            action
+        ))
+    };
+    for r in &diff.modified_rls {
+        if r.from_enabled != r.to_enabled {
+            let action = if r.from_enabled { "ENABLE" } else { "DISABLE" };
+            switch(&r.schema, &r.name, action);
+        }
+        if r.from_forced != r.to_forced {
+            let action = if r.from_forced { "FORCE" } else { "NO FORCE" };
+            switch(&r.schema, &r.name, action);
+        }
+    }
+    // Tables created from the source start with row-level security off
+    for t in &diff.removed_tables {
+        if t.rls_enabled {
+            switch(&t.schema, &t.name, "ENABLE");
+        }
+        if t.rls_forced {
+            switch(&t.schema, &t.name, "FORCE");
+        }
+    }
+
+    if !switches.is_empty() {
+        push_section(&mut sql.creates, "-- Row-level security", switches);
+    }
+    if !policies.is_empty() {
+        push_section(&mut sql.creates, "-- Policies", policies);
+    }
+    while sql.creates.last().is_some_and(|l| l.is_empty()) {
+        sql.creates.pop();
+    }
+    sql
+}
+
+/// ALTER POLICY for a policy whose command, permissiveness, and expression
+/// presence are unchanged
+fn format_policy_alter(p: &Policy) -> String {
+    let mut sql = format!(
+        "ALTER POLICY {} ON {}.{} TO {}",
+        quote_ident(&p.name),
+        quote_ident(&p.schema),
+        quote_ident(&p.table_name),
+        policy_roles(p)
+    );
+    push_policy_exprs(&mut sql, p);
+    sql
+}
+
+/// Policy drops, drops of objects only in the target, plus materialized
+/// views to rebuild, then `revokes`
+fn destructive_sql(
+    diff: &SchemaDiff,
+    rebuilt_matviews: &[MaterializedView],
+    policies: Vec<String>,
+    revokes: Vec<String>,
+) -> Vec<String> {
+    // Indexes, constraints, and triggers go away with their table
+    let dropped_tables: HashSet<(&str, &str)> = diff
+        .added_tables
+        .iter()
+        .map(|t| (t.schema.as_str(), t.name.as_str()))
+        .collect();
+    let survives =
+        |schema: &str, table: &str| -> bool { !dropped_tables.contains(&(schema, table)) };
+
+    let mut matviews = diff.added_materialized_views.clone();
+    matviews.extend(rebuilt_matviews.iter().cloned());
+    let drops = DatabaseSchema {
+        extensions: diff.added_extensions.clone(),
+        schemas: diff.added_schemas.clone(),
+        collations: diff.added_collations.clone(),
+        enums: diff.added_enums.clone(),
+        domains: diff.added_domains.clone(),
+        composite_types: diff.added_composite_types.clone(),
+        foreign_servers: diff.added_foreign_servers.clone(),
+        user_mappings: diff.added_user_mappings.clone(),
+        sequences: diff.added_sequences.clone(),
+        tables: diff.added_tables.clone(),
+        foreign_tables: diff.added_foreign_tables.clone(),
+        views: diff.added_views.clone(),
+        indexes: diff
+            .added_indexes
+            .iter()
+            .filter(|i| survives(&i.schema, &i.table_name))
+            .cloned()
+            .collect(),
+        constraints: diff
+            .added_constraints
+            .iter()
+            .filter(|c| {
+                c.constraint_type == ConstraintType::ForeignKey
+                    && survives(&c.schema, &c.table_name)
+            })
+            .cloned()
+            .collect(),
+        triggers: diff
+            .added_triggers
+            .iter()
+            .filter(|t| survives(&t.schema, &t.table_name))
+            .cloned()
+            .collect(),
+        functions: diff.added_functions.clone(),
+        materialized_views: matviews,
+        ..Default::default()
+    };
+
+    let mut lines = Vec::new();
+    if !policies.is_empty() {
+        push_section(&mut lines, "-- Policies", policies);
+    }
+    lines.extend(
+        crate::introspect::schema_to_drop_sql(&drops)
+            .lines()
+            .map(str::to_string),
+    );
+
+    // schema_to_drop_sql only drops foreign keys; the rest go after them
+    let other_constraints: Vec<String> = diff
+        .added_constraints
+        .iter()
+        .filter(|c| {
+            c.constraint_type != ConstraintType::ForeignKey && survives(&c.schema, &c.table_name)
+        })
+        .map(|c| {
+            format!(
+                "ALTER TABLE {}.{} DROP CONSTRAINT IF EXISTS {};",
+                quote_ident(&c.schema),
+                quote_ident(&c.table_name),
+                quote_ident(&c.name)
+            )
+        })
+        .collect();
+    if !other_constraints.is_empty() {
+        push_section(&mut lines, "-- Constraints", other_constraints);
+    }
+
+    let columns: Vec<String> = diff
+        .modified_tables
+        .iter()
+        .flat_map(|t| {
+            t.added_columns.iter().map(move |c| {
+                format!(
+                    "ALTER TABLE {}.{} DROP COLUMN {};",
+                    quote_ident(&t.schema),
+                    quote_ident(&t.name),
+                    quote_ident(&c.name)
+                )
+            })
+        })
+        .collect();
+    if !columns.is_empty() {
+        push_section(&mut lines, "-- Columns", columns);
+    }
+    if !revokes.is_empty() {
+        push_section(&mut lines, "-- Privileges", revokes);
+    }
+
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// In-place changes as (safe, destructive) statements
+fn alter_sql(diff: &SchemaDiff, source: &DatabaseSchema) -> (Vec<String>, Vec<String>) {
+    let mut safe = Vec::new();
+    let mut destructive = Vec::new();
+
+    // Enum values only in the source are added; Postgres cannot drop values
+    let mut enum_values = Vec::new();
+    for e in &diff.modified_enums {
+        let name = format!("{}.{}", quote_ident(&e.schema), quote_ident(&e.name));
+        for v in &e.removed_values {
+            enum_values.push(format!(
+                "ALTER TYPE {} ADD VALUE IF NOT EXISTS {};",
+                name,
+                quote_literal(v)
+            ));
+        }
+        for v in &e.added_values {
+            destructive.push(format!(
+                "-- {} value {} exists only in the target; Postgres cannot drop enum values",
+                name,
+                quote_literal(v)
+            ));
+        }
+    }
+    if !enum_values.is_empty() {
+        push_section(&mut safe, "-- Enum values", enum_values);
+    }
+
+    let mut columns = Vec::new();
+    for t in &diff.modified_tables {
+        let table = format!("{}.{}", quote_ident(&t.schema), quote_ident(&t.name));
+        for c in &t.removed_columns {
+            columns.push(format!(
+                "ALTER TABLE {} ADD COLUMN {};",
+                table,
+                crate::introspect::format_column_def(c)
+            ));
+            if let Some(ref comment) = c.comment {
+                columns.push(format!(
+                    "COMMENT ON COLUMN {}.{} IS {};",
+                    table,
+                    quote_ident(&c.name),
+                    quote_literal(comment)
+                ));
+            }
+        }
+        for c in &t.modified_columns {
+            let alter = format!(
+                "ALTER TABLE {} ALTER COLUMN {}",
+                table,
+                quote_ident(&c.name)
+            );
+            if c.from_type != c.to_type {
+                destructive.push(format!(
+                    "{} TYPE {} USING {}::{};",
+                    alter,
+                    c.from_type,
+                    quote_ident(&c.name),
+                    c.from_type
+                ));
+            }
+            if c.from_nullable != c.to_nullable {
+                let action = if c.from_nullable {
+                    "DROP NOT NULL"
+                } else {
+                    "SET NOT NULL"
+                };
+                columns.push(format!("{} {};", alter, action));
+            }
+            if c.from_default != c.to_default {
+                match c.from_default {
+                    Some(ref default) => {
+                        columns.push(format!("{} SET DEFAULT {};", alter, default))
+                    }
+                    None => columns.push(format!("{} DROP DEFAULT;", alter)),
+                }
+            }
+            if c.from_identity != c.to_identity {
+                let generated = |identity: &IdentityType| match identity {
+                    IdentityType::Always => "ALWAYS",
+                    IdentityType::ByDefault => "BY DEFAULT",
+                };
+                match (&c.from_identity, &c.to_identity) {
+                    (Some(from), None) => columns.push(format!(
+                        "{} ADD GENERATED {} AS IDENTITY;",
+                        alter,
+                        generated(from)
+                    )),
+                    (Some(from), Some(_)) => {
+                        columns.push(format!("{} SET GENERATED {};", alter, generated(from)))
+                    }
+                    // Drops the identity's sequence
+                    (None, _) => destructive.push(format!("{} DROP IDENTITY IF EXISTS;", alter)),
+                }
+            }
+        }
+    }
+    if !columns.is_empty() {
+        push_section(&mut safe, "-- Columns", columns);
+    }
+
+    let views: Vec<String> = diff
+        .modified_views
+        .iter()
+        .filter_map(|v| {
+            source
+                .views
+                .iter()
+                .find(|s| s.schema == v.schema && s.name == v.name)
+        })
+        .map(|v| {
+            format!(
+                "CREATE OR REPLACE VIEW {}.{} AS\n{};",
+                quote_ident(&v.schema),
+                quote_ident(&v.name),
+                v.definition.trim_end_matches(';').trim()
+            )
+        })
+        .collect();
+    if !views.is_empty() {
+        push_section(&mut safe, "-- Views", views);
+    }
+
+    let comments: Vec<String> = diff
+        .modified_comments
+        .iter()
+        .map(|c| {
+            let table = format!("{}.{}", quote_ident(&c.schema), quote_ident(&c.table));
+            let target = match c.column {
+                Some(ref column) => format!("COLUMN {}.{}", table, quote_ident(column)),
+                None => format!("TABLE {}", table),
+            };
+            let text = c.from.as_deref().map_or("NULL".to_string(), quote_literal);
+            format!("COMMENT ON {} IS {};", target, text)
+        })
+        .collect();
+    if !comments.is_empty() {
+        push_section(&mut safe, "-- Comments", comments);
+    }
+
+    while safe.last().is_some_and(|l| l.is_empty()) {
+        safe.pop();
+    }
+    (safe, destructive)
+}
+
+/// Append a commented block, separated from what came before by a blank line
+fn push_section(lines: &mut Vec<String>, header: &str, statements: Vec<String>) {
+    if lines.last().is_some_and(|l| !l.is_empty()) {
+        lines.push(String::new());
+    }
+    lines.push(header.to_string());
+    lines.extend(statements);
+    lines.push(String::new());
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -929,9 +2544,11 @@ mod tests {
             data_type: data_type.to_string(),
             nullable,
             default: None,
+            generated: false,
             identity: None,
             is_serial: false,
             is_primary_key: false,
+            comment: None,
         }
     }
 
@@ -946,6 +2563,9 @@ mod tests {
             parent_schema: None,
             parent_name: None,
             partition_bound: None,
+            comment: None,
+            rls_enabled: false,
+            rls_forced: false,
         }
     }
 
@@ -1081,6 +2701,247 @@ mod tests {
         assert!(diff.modified_enums[0].removed_values.is_empty());
     }
 
+    #[test]
+    fn test_diff_to_sql() {
+        let from = DatabaseSchema {
+            tables: vec![
+                make_table(
+                    "public",
+                    "users",
+                    vec![
+                        make_column("email", "text", false),
+                        make_column("name", "text", true),
+                    ],
+                ),
+                make_table(
+                    "public",
+                    "orders",
+                    vec![make_column("id", "integer", false)],
+                ),
+            ],
+            ..Default::default()
+        };
+        let to = DatabaseSchema {
+            tables: vec![
+                make_table(
+                    "public",
+                    "users",
+                    vec![
+                        make_column("email", "varchar(100)", true),
+                        make_column("legacy", "integer", true),
+                    ],
+                ),
+                make_table("public", "old", vec![]),
+            ],
+            ..Default::default()
+        };
+        let diff = diff_schemas(&from, &to);
+
+        let sql = diff_to_sql(&diff, &from, false);
+        assert!(sql.contains("CREATE TABLE \"public\".\"orders\""));
+        assert!(sql.contains("ALTER TABLE \"public\".\"users\" ADD COLUMN \"name\" text;"));
+        assert!(
+            sql.contains("ALTER TABLE \"public\".\"users\" ALTER COLUMN \"email\" SET NOT NULL;")
+        );
+        assert!(sql.contains(
+            "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"email\" TYPE text USING \"email\"::text;"
+        ));
+        assert!(sql.contains("ALTER TABLE \"public\".\"users\" DROP COLUMN \"legacy\";"));
+        assert!(sql.contains("DROP TABLE IF EXISTS \"public\".\"old\";"));
+        // Drops come before creates
+        assert!(sql.find("DROP TABLE").unwrap() < sql.find("CREATE TABLE").unwrap());
+
+        let safe = diff_to_sql(&diff, &from, true);
+        assert!(safe.contains("-- --safe-only: omitted 3 destructive statements"));
+        assert!(!safe.contains("DROP TABLE"));
+        assert!(!safe.contains("DROP COLUMN"));
+        assert!(!safe.contains(" TYPE text"));
+        assert!(safe.contains("ADD COLUMN \"name\" text;"));
+
+        let identical = diff_to_sql(&diff_schemas(&from, &from), &from, false);
+        assert!(identical.ends_with("-- Schemas are identical; nothing to do."));
+    }
+
+    #[test]
+    fn test_diff_to_sql_enum_values() {
+        let enum_type = |values: &[&str]| EnumType {
+            schema: "public".to_string(),
+            name: "status".to_string(),
+            values: values.iter().map(|v| v.to_string()).collect(),
+        };
+        let from = DatabaseSchema {
+            enums: vec![enum_type(&["active", "it's"])],
+            ..Default::default()
+        };
+        let to = DatabaseSchema {
+            enums: vec![enum_type(&["active", "pending"])],
+            ..Default::default()
+        };
+        let sql = diff_to_sql(&diff_schemas(&from, &to), &from, false);
+        assert!(sql.contains("ALTER TYPE \"public\".\"status\" ADD VALUE IF NOT EXISTS 'it''s';"));
+        assert!(sql.contains("value 'pending' exists only in the target"));
+    }
+
+    #[test]
+    fn test_diff_privileges() {
+        use crate::introspect::{ObjectOwner, Privileges};
+
+        let role = |name: &str, login: bool| Role {
+            name: name.to_string(),
+            login,
+            superuser: false,
+            createdb: false,
+            createrole: false,
+            replication: false,
+            bypassrls: false,
+        };
+        let owner = |name: &str, owner: &str| ObjectOwner {
+            kind: "table".to_string(),
+            name: name.to_string(),
+            owner: owner.to_string(),
+        };
+        let grant = |object: &str, grantee: &str, privilege: &str| Grant {
+            kind: "table".to_string(),
+            object: object.to_string(),
+            grantee: grantee.to_string(),
+            privilege: privilege.to_string(),
+            grantable: false,
+        };
+        let from = DatabaseSchema {
+            privileges: Privileges {
+                roles: vec![role("app", true), role("reader", false)],
+                memberships: vec![RoleMembership {
+                    role: "reader".to_string(),
+                    member: "app".to_string(),
+                    admin_option: false,
+                }],
+                owners: vec![owner("app.users", "app")],
+                grants: vec![grant("app.users", "reader", "SELECT")],
+            },
+            ..Default::default()
+        };
+        let to = DatabaseSchema {
+            privileges: Privileges {
+                roles: vec![role("app", false), role("legacy", false)],
+                memberships: vec![],
+                owners: vec![owner("app.users", "postgres"), owner("app.old", "postgres")],
+                grants: vec![
+                    grant("app.users", "PUBLIC", "SELECT"),
+                    grant("app.old", "legacy", "SELECT"),
+                ],
+            },
+            ..Default::default()
+        };
+
+        let diff = diff_schemas(&from, &to);
+        assert_eq!(diff.added_roles[0].name, "legacy");
+        assert_eq!(diff.removed_roles[0].name, "reader");
+        assert_eq!(
+            format_role_changes(&diff.modified_roles[0].from, &diff.modified_roles[0].to),
+            "LOGIN → NOLOGIN"
+        );
+        assert_eq!(diff.removed_memberships.len(), 1);
+        assert_eq!(diff.modified_owners[0].to_owner, "postgres");
+        // The grant on app.old goes away with the table
+        assert_eq!(diff.added_grants.len(), 1);
+        assert_eq!(diff.added_grants[0].grantee, "PUBLIC");
+        assert_eq!(diff.summary().grants, 2);
+
+        let sql = diff_to_sql(&diff, &from, false);
+        assert!(sql.contains("CREATE ROLE \"reader\" WITH NOLOGIN NOSUPERUSER"));
+        assert!(sql.contains("ALTER ROLE \"app\" WITH LOGIN NOSUPERUSER"));
+        assert!(sql.contains("REVOKE SELECT ON TABLE app.users FROM PUBLIC;"));
+        assert!(sql.contains("GRANT \"reader\" TO \"app\";"));
+        assert!(sql.contains("GRANT SELECT ON TABLE app.users TO \"reader\";"));
+        assert!(sql.contains("ALTER TABLE app.users OWNER TO \"app\";"));
+        assert!(sql.trim_end().ends_with("DROP ROLE IF EXISTS \"legacy\";"));
+
+        let safe = diff_to_sql(&diff, &from, true);
+        assert!(safe.contains("omitted 2 destructive statements"));
+        assert!(!safe.contains("REVOKE"));
+        assert!(!safe.contains("DROP ROLE"));
+    }
+
+    #[test]
+    fn test_diff_rls_policies_and_comments() {
+        let policy = |name: &str, command: &str, using: &str| Policy {
+            schema: "app".to_string(),
+            table_name: "notes".to_string(),
+            name: name.to_string(),
+            command: command.to_string(),
+            permissive: true,
+            roles: vec![],
+            using_expr: Some(using.to_string()),
+            with_check_expr: None,
+        };
+        let mut notes = make_table("app", "notes", vec![make_column("owner", "text", false)]);
+        notes.rls_enabled = true;
+        notes.comment = Some("Private notes".to_string());
+        notes.columns[0].comment = Some("Login name".to_string());
+        let from = DatabaseSchema {
+            tables: vec![notes],
+            policies: vec![
+                policy("own_rows", "ALL", "(owner = CURRENT_USER)"),
+                policy("read_all", "SELECT", "true"),
+                policy("by_role", "SELECT", "true"),
+            ],
+            ..Default::default()
+        };
+        let to = DatabaseSchema {
+            tables: vec![make_table(
+                "app",
+                "notes",
+                vec![make_column("owner", "text", false)],
+            )],
+            policies: vec![
+                policy("own_rows", "ALL", "(owner = SESSION_USER)"),
+                policy("by_role", "UPDATE", "true"),
+                policy("legacy", "SELECT", "false"),
+            ],
+            ..Default::default()
+        };
+
+        let diff = diff_schemas(&from, &to);
+        assert!(diff.modified_tables.is_empty());
+        assert_eq!(diff.modified_rls.len(), 1);
+        assert_eq!(diff.added_policies[0].name, "legacy");
+        assert_eq!(diff.removed_policies[0].name, "read_all");
+        assert_eq!(diff.modified_policies.len(), 2);
+        assert_eq!(diff.modified_comments.len(), 2);
+        assert_eq!(diff.modified_comments[0].column, None);
+        assert_eq!(diff.summary().policies, 5);
+        assert_eq!(diff.summary().comments, 2);
+
+        let formatted = format_diff(&diff, "from", "to");
+        assert!(formatted.contains("app.notes (enabled → disabled)"));
+        assert!(formatted.contains("by_role ON app.notes (FOR SELECT → UPDATE)"));
+        assert!(formatted.contains("column app.notes.owner (source only)"));
+
+        let sql = diff_to_sql(&diff, &from, false);
+        assert!(sql.contains("ALTER TABLE \"app\".\"notes\" ENABLE ROW LEVEL SECURITY;"));
+        assert!(sql.contains(
+            "CREATE POLICY \"read_all\" ON \"app\".\"notes\" AS PERMISSIVE FOR SELECT TO PUBLIC USING (true);"
+        ));
+        assert!(sql.contains(
+            "ALTER POLICY \"own_rows\" ON \"app\".\"notes\" TO PUBLIC USING ((owner = CURRENT_USER));"
+        ));
+        assert!(sql.contains("DROP POLICY IF EXISTS \"legacy\" ON \"app\".\"notes\";"));
+        assert!(sql.contains("DROP POLICY IF EXISTS \"by_role\" ON \"app\".\"notes\";"));
+        assert!(sql.contains("COMMENT ON TABLE \"app\".\"notes\" IS 'Private notes';"));
+        assert!(sql.contains("COMMENT ON COLUMN \"app\".\"notes\".\"owner\" IS 'Login name';"));
+        // Recreated policies are dropped before they are created again
+        assert!(
+            sql.find("DROP POLICY IF EXISTS \"by_role\"").unwrap()
+                < sql.find("CREATE POLICY \"by_role\"").unwrap()
+        );
+
+        let safe = diff_to_sql(&diff, &from, true);
+        assert!(safe.contains("omitted 2 destructive statements"));
+        assert!(!safe.contains("DROP POLICY"));
+        assert!(!safe.contains("CREATE POLICY \"by_role\""));
+        assert!(safe.contains("CREATE POLICY \"read_all\""));
+    }
+
     #[test]
     fn test_is_empty_with_differences() {
         let diff = SchemaDiff {
@@ -1089,4 +2950,43 @@ mod tests {
         };
         assert!(!diff.is_empty());
     }
+
+    #[test]
+    fn test_diff_domains() {
+        let domain = |check: &str| Domain {
+            schema: "app".to_string(),
+            name: "score".to_string(),
+            data_type: "integer".to_string(),
+            collation: None,
+            default: None,
+            not_null: false,
+            checks: vec![("score_range".to_string(), check.to_string())],
+        };
+        let from = DatabaseSchema {
+            domains: vec![domain("CHECK ((VALUE >= 0))")],
+            ..Default::default()
+        };
+        let to = DatabaseSchema {
+            domains: vec![domain("CHECK ((VALUE > 0))")],
+            ..Default::default()
+        };
+
+        let diff = diff_schemas(&from, &to);
+        assert_eq!(diff.summary().types, 2);
+        assert_eq!(
+            diff.changes().get(&("domain", "app.score".to_string())),
+            Some(&ChangeKind::Modified)
+        );
+
+        // Dropped as it is in the target, then created as in the source
+        let sql = diff_to_sql(&diff, &from, false);
+        let drop = sql
+            .find("DROP DOMAIN IF EXISTS \"app\".\"score\";")
+            .unwrap();
+        let create = sql.find("CHECK ((VALUE >= 0))").unwrap();
+        assert!(drop < create);
+        assert!(!sql.contains("VALUE > 0"));
+
+        assert!(diff_schemas(&from, &from).is_empty());
+    }
 }
@@ -31,6 +31,9 @@ pub const WARNING: i32 = 1;
 /// Exit code: critical findings
 pub const CRITICAL: i32 = 2;
 
+/// Exit code: Nagios UNKNOWN, the check could not run (`--format nagios` only)
+pub const UNKNOWN: i32 = 3;
+
 /// Exit code: general operational failure
 pub const OPERATIONAL_FAILURE: i32 = 10;
 
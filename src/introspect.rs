@@ -5,7 +5,7 @@
 //! - Convert the schema model to SQL CREATE statements
 //! - Support various output modes (single file, split by schema, split by table)
 
-use crate::sql::quote_ident;
+use crate::sql::{quote_ident, quote_literal};
 use anyhow::Result;
 use chrono::{DateTime, Duration, Utc};
 use serde::Serialize;
@@ -21,15 +21,74 @@ use tokio_postgres::Client;
 pub struct DatabaseSchema {
     pub extensions: Vec<Extension>,
     pub schemas: Vec<SchemaInfo>,
+    pub collations: Vec<Collation>,
     pub enums: Vec<EnumType>,
+    pub domains: Vec<Domain>,
+    pub composite_types: Vec<CompositeType>,
+    pub foreign_servers: Vec<ForeignServer>,
+    pub user_mappings: Vec<UserMapping>,
     pub sequences: Vec<Sequence>,
     pub tables: Vec<Table>,
+    pub foreign_tables: Vec<ForeignTable>,
     pub views: Vec<View>,
     pub indexes: Vec<Index>,
     pub constraints: Vec<Constraint>,
     pub triggers: Vec<Trigger>,
     pub functions: Vec<Function>,
     pub materialized_views: Vec<MaterializedView>,
+    pub policies: Vec<Policy>,
+    /// Roles, ownership, and grants; only filled by `introspect_privileges`
+    pub privileges: Privileges,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Privileges {
+    pub roles: Vec<Role>,
+    pub memberships: Vec<RoleMembership>,
+    pub owners: Vec<ObjectOwner>,
+    pub grants: Vec<Grant>,
+}
+
+/// Role and the attributes compared between databases (pg_* roles excluded)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Role {
+    pub name: String,
+    pub login: bool,
+    pub superuser: bool,
+    pub createdb: bool,
+    pub createrole: bool,
+    pub replication: bool,
+    pub bypassrls: bool,
+}
+
+/// `member` is granted `role`
+#[derive(Debug, Clone)]
+pub struct RoleMembership {
+    pub role: String,
+    pub member: String,
+    pub admin_option: bool,
+}
+
+/// Owner of a schema, relation, function, or type
+#[derive(Debug, Clone)]
+pub struct ObjectOwner {
+    /// schema, table, view, materialized view, foreign table, sequence,
+    /// function, procedure, type, or domain
+    pub kind: String,
+    /// Quoted, schema-qualified name (functions include argument types)
+    pub name: String,
+    pub owner: String,
+}
+
+/// One privilege held by a role other than the object's owner
+#[derive(Debug, Clone)]
+pub struct Grant {
+    pub kind: String,
+    pub object: String,
+    /// Role name, or PUBLIC
+    pub grantee: String,
+    pub privilege: String,
+    pub grantable: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +108,81 @@ pub struct EnumType {
     pub values: Vec<String>,
 }
 
+/// CREATE COLLATION
+#[derive(Debug, Clone)]
+pub struct Collation {
+    pub schema: String,
+    pub name: String,
+    /// libc, icu, or builtin
+    pub provider: String,
+    /// ICU or builtin locale
+    pub locale: Option<String>,
+    /// libc LC_COLLATE and LC_CTYPE
+    pub lc_collate: Option<String>,
+    pub lc_ctype: Option<String>,
+    pub deterministic: bool,
+}
+
+/// CREATE DOMAIN
+#[derive(Debug, Clone)]
+pub struct Domain {
+    pub schema: String,
+    pub name: String,
+    /// Base type as format_type prints it
+    pub data_type: String,
+    /// Quoted, schema-qualified collation, when not the base type's default
+    pub collation: Option<String>,
+    pub default: Option<String>,
+    pub not_null: bool,
+    /// CHECK constraints as (name, definition)
+    pub checks: Vec<(String, String)>,
+}
+
+/// Composite type (CREATE TYPE ... AS)
+#[derive(Debug, Clone)]
+pub struct CompositeType {
+    pub schema: String,
+    pub name: String,
+    /// (name, type) in attribute order; the type carries any COLLATE clause
+    pub attributes: Vec<(String, String)>,
+}
+
+/// Foreign server; its wrapper is expected to come from an extension
+#[derive(Debug, Clone)]
+pub struct ForeignServer {
+    pub name: String,
+    pub wrapper: String,
+    pub server_type: Option<String>,
+    pub version: Option<String>,
+    /// `key=value`, as stored in the catalog
+    pub options: Vec<String>,
+}
+
+/// User mapping. Only option names are read, since the values are usually
+/// credentials.
+#[derive(Debug, Clone)]
+pub struct UserMapping {
+    pub server: String,
+    /// Role name, or PUBLIC
+    pub user: String,
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForeignTable {
+    pub schema: String,
+    pub name: String,
+    pub server: String,
+    pub columns: Vec<Column>,
+    /// Per-column `key=value` options, for the columns that have any
+    pub column_options: Vec<(String, Vec<String>)>,
+    /// `key=value`, as stored in the catalog
+    pub options: Vec<String>,
+    /// Quoted, schema-qualified parent table when this is a partition
+    pub parent: Option<String>,
+    pub partition_bound: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Sequence {
     pub schema: String,
@@ -70,6 +204,12 @@ pub struct Table {
     pub parent_schema: Option<String>,
     pub parent_name: Option<String>,
     pub partition_bound: Option<String>,
+    /// COMMENT ON TABLE text
+    pub comment: Option<String>,
+    /// ENABLE ROW LEVEL SECURITY
+    pub rls_enabled: bool,
+    /// FORCE ROW LEVEL SECURITY (policies apply to the owner too)
+    pub rls_forced: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -83,9 +223,13 @@ pub struct Column {
     pub data_type: String,
     pub nullable: bool,
     pub default: Option<String>,
+    /// `default` is a stored generated column's expression
+    pub generated: bool,
     pub identity: Option<IdentityType>,
     pub is_serial: bool,
     pub is_primary_key: bool,
+    /// COMMENT ON COLUMN text
+    pub comment: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -165,6 +309,21 @@ pub struct Function {
     pub kind: FunctionKind,
 }
 
+/// Row-level security policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    pub schema: String,
+    pub table_name: String,
+    pub name: String,
+    /// SELECT, INSERT, UPDATE, DELETE, or ALL
+    pub command: String,
+    pub permissive: bool,
+    /// Roles the policy applies to; empty for PUBLIC
+    pub roles: Vec<String>,
+    pub using_expr: Option<String>,
+    pub with_check_expr: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct MaterializedView {
     pub schema: String,
@@ -231,6 +390,11 @@ pub struct GeneratedFile {
 pub struct FileStats {
     pub schema_count: usize,
     pub enum_count: usize,
+    /// Domains, composite types, and collations
+    pub type_count: usize,
+    /// Foreign servers and user mappings
+    pub server_count: usize,
+    pub foreign_table_count: usize,
     pub table_count: usize,
     pub column_count: usize,
     pub index_count: usize,
@@ -241,6 +405,8 @@ pub struct FileStats {
     pub matview_count: usize,
     pub extension_count: usize,
     pub sequence_count: usize,
+    pub policy_count: usize,
+    pub grant_count: usize,
 }
 
 // =============================================================================
@@ -262,8 +428,15 @@ pub async fn introspect(
     schema.schemas = get_schemas(client, options).await?;
     let schema_set: HashSet<String> = schema.schemas.iter().map(|s| s.name.clone()).collect();
 
-    // Get enums
+    // Get collations, enums, domains, and composite types
+    schema.collations = get_collations(client, &schema_set).await?;
     schema.enums = get_enums(client, &schema_set).await?;
+    schema.domains = get_domains(client, &schema_set).await?;
+    schema.composite_types = get_composite_types(client, &schema_set).await?;
+
+    // Get foreign servers and user mappings (not schema-scoped)
+    schema.foreign_servers = get_foreign_servers(client).await?;
+    schema.user_mappings = get_user_mappings(client).await?;
 
     // Get standalone sequences (not identity/serial)
     schema.sequences = get_sequences(client, &schema_set).await?;
@@ -271,6 +444,9 @@ pub async fn introspect(
     // Get tables (including partition info)
     schema.tables = get_tables(client, &schema_set).await?;
 
+    // Get foreign tables
+    schema.foreign_tables = get_foreign_tables(client, &schema_set).await?;
+
     // Get views
     schema.views = get_views(client, &schema_set).await?;
 
@@ -289,9 +465,173 @@ pub async fn introspect(
     // Get materialized views
     schema.materialized_views = get_materialized_views(client, &schema_set).await?;
 
+    // Get row-level security policies
+    schema.policies = get_policies(client, &schema_set).await?;
+
     Ok(schema)
 }
 
+/// Schemas, relations, functions, and types with their owners and ACLs
+/// (NULL ACLs expanded to the built-in defaults). Extension members are
+/// left out, as they are everywhere else in introspection.
+const PRIVILEGE_OBJECTS_CTE: &str = r#"
+    WITH objects AS (
+        SELECT 'schema' AS kind, n.nspname::text AS schema, quote_ident(n.nspname) AS name,
+               n.nspowner AS owner, coalesce(n.nspacl, acldefault('n', n.nspowner)) AS acl
+        FROM pg_namespace n
+        WHERE n.oid NOT IN (SELECT objid FROM pg_depend WHERE deptype = 'e')
+        UNION ALL
+        SELECT CASE c.relkind
+                   WHEN 'v' THEN 'view'
+                   WHEN 'm' THEN 'materialized view'
+                   WHEN 'f' THEN 'foreign table'
+                   WHEN 'S' THEN 'sequence'
+                   ELSE 'table'
+               END,
+               n.nspname::text, format('%I.%I', n.nspname, c.relname), c.relowner,
+               coalesce(c.relacl, acldefault(
+                   CASE WHEN c.relkind = 'S' THEN 's' ELSE 'r' END::"char", c.relowner))
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.relkind IN ('r', 'p', 'v', 'm', 'f', 'S')
+          AND NOT c.relispartition
+          AND c.oid NOT IN (SELECT objid FROM pg_depend WHERE deptype = 'e')
+        UNION ALL
+        SELECT CASE p.prokind WHEN 'p' THEN 'procedure' ELSE 'function' END,
+               n.nspname::text,
+               format('%I.%I(%s)', n.nspname, p.proname, pg_get_function_identity_arguments(p.oid)),
+               p.proowner, coalesce(p.proacl, acldefault('f', p.proowner))
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        WHERE p.prokind IN ('f', 'p')
+          AND p.oid NOT IN (SELECT objid FROM pg_depend WHERE deptype = 'e')
+        UNION ALL
+        SELECT CASE t.typtype WHEN 'd' THEN 'domain' ELSE 'type' END,
+               n.nspname::text, format('%I.%I', n.nspname, t.typname), t.typowner,
+               coalesce(t.typacl, acldefault('T', t.typowner))
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        WHERE (t.typtype IN ('e', 'd')
+               OR (t.typtype = 'c' AND EXISTS (
+                   SELECT 1 FROM pg_class c WHERE c.oid = t.typrelid AND c.relkind = 'c')))
+          AND t.oid NOT IN (SELECT objid FROM pg_depend WHERE deptype = 'e')
+    ),
+    filtered AS (
+        SELECT * FROM objects
+        WHERE schema NOT LIKE 'pg_%'
+          AND schema NOT IN ('information_schema', 'pgcrate')
+    )
+"#;
+
+/// Roles, memberships, ownership, and grants, for `inspect diff --include-privileges`
+pub async fn introspect_privileges(
+    client: &Client,
+    options: &IntrospectOptions,
+) -> Result<Privileges, anyhow::Error> {
+    let roles = client
+        .query(
+            "SELECT rolname::text AS name, rolcanlogin, rolsuper, rolcreatedb,
+                    rolcreaterole, rolreplication, rolbypassrls
+             FROM pg_roles
+             WHERE rolname NOT LIKE 'pg\\_%'
+             ORDER BY rolname",
+            &[],
+        )
+        .await?
+        .iter()
+        .map(|row| Role {
+            name: row.get("name"),
+            login: row.get("rolcanlogin"),
+            superuser: row.get("rolsuper"),
+            createdb: row.get("rolcreatedb"),
+            createrole: row.get("rolcreaterole"),
+            replication: row.get("rolreplication"),
+            bypassrls: row.get("rolbypassrls"),
+        })
+        .collect();
+
+    let memberships = client
+        .query(
+            "SELECT r.rolname::text AS role, m.rolname::text AS member,
+                    bool_or(am.admin_option) AS admin_option
+             FROM pg_auth_members am
+             JOIN pg_roles r ON r.oid = am.roleid
+             JOIN pg_roles m ON m.oid = am.member
+             WHERE m.rolname NOT LIKE 'pg\\_%'
+             GROUP BY 1, 2
+             ORDER BY 1, 2",
+            &[],
+        )
+        .await?
+        .iter()
+        .map(|row| RoleMembership {
+            role: row.get("role"),
+            member: row.get("member"),
+            admin_option: row.get("admin_option"),
+        })
+        .collect();
+
+    let owners = client
+        .query(
+            &format!(
+                "{}
+                 SELECT kind, schema, name, pg_get_userbyid(owner)::text AS owner
+                 FROM filtered
+                 ORDER BY kind, name",
+                PRIVILEGE_OBJECTS_CTE
+            ),
+            &[],
+        )
+        .await?
+        .iter()
+        .filter(|row| options.should_include_schema(row.get("schema")))
+        .map(|row| ObjectOwner {
+            kind: row.get("kind"),
+            name: row.get("name"),
+            owner: row.get("owner"),
+        })
+        .collect();
+
+    // The owner's own privileges are implicit, so only other grantees count;
+    // the same privilege from several grantors is one grant
+    let grants = client
+        .query(
+            &format!(
+                "{}
+                 SELECT o.kind, o.schema, o.name,
+                        CASE WHEN a.grantee = 0 THEN 'PUBLIC'
+                             ELSE pg_get_userbyid(a.grantee)::text END AS grantee,
+                        a.privilege_type::text AS privilege,
+                        bool_or(a.is_grantable) AS is_grantable
+                 FROM filtered o
+                 CROSS JOIN LATERAL aclexplode(o.acl) a
+                 WHERE a.grantee <> o.owner
+                 GROUP BY 1, 2, 3, 4, 5
+                 ORDER BY 1, 3, 4, 5",
+                PRIVILEGE_OBJECTS_CTE
+            ),
+            &[],
+        )
+        .await?
+        .iter()
+        .filter(|row| options.should_include_schema(row.get("schema")))
+        .map(|row| Grant {
+            kind: row.get("kind"),
+            object: row.get("name"),
+            grantee: row.get("grantee"),
+            privilege: row.get("privilege"),
+            grantable: row.get("is_grantable"),
+        })
+        .collect();
+
+    Ok(Privileges {
+        roles,
+        memberships,
+        owners,
+        grants,
+    })
+}
+
 async fn get_extensions(client: &Client) -> Result<Vec<Extension>, anyhow::Error> {
     let rows = client
         .query(
@@ -397,6 +737,155 @@ async fn get_enums(
         .collect())
 }
 
+async fn get_collations(
+    client: &Client,
+    schemas: &HashSet<String>,
+) -> Result<Vec<Collation>, anyhow::Error> {
+    // The ICU locale moved from collcollate to colliculocale (PG 15), then
+    // to colllocale (PG 17); reading the row as JSON works on all of them
+    let rows = client
+        .query(
+            "SELECT n.nspname AS schema, c.collname AS name,
+                    CASE c.collprovider WHEN 'i' THEN 'icu' WHEN 'b' THEN 'builtin'
+                                        ELSE 'libc' END AS provider,
+                    coalesce(to_jsonb(c)->>'colllocale', to_jsonb(c)->>'colliculocale',
+                             CASE WHEN c.collprovider = 'i' THEN c.collcollate::text END)
+                        AS locale,
+                    CASE WHEN c.collprovider = 'c' THEN c.collcollate::text END AS lc_collate,
+                    CASE WHEN c.collprovider = 'c' THEN c.collctype::text END AS lc_ctype,
+                    c.collisdeterministic AS deterministic
+             FROM pg_collation c
+             JOIN pg_namespace n ON c.collnamespace = n.oid
+             WHERE c.oid NOT IN (SELECT objid FROM pg_depend WHERE deptype = 'e')
+             ORDER BY n.nspname, c.collname",
+            &[],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let schema: String = row.get("schema");
+            if schemas.contains(&schema) {
+                Some(Collation {
+                    schema,
+                    name: row.get("name"),
+                    provider: row.get("provider"),
+                    locale: row.get("locale"),
+                    lc_collate: row.get("lc_collate"),
+                    lc_ctype: row.get("lc_ctype"),
+                    deterministic: row.get("deterministic"),
+                })
+            } else {
+                None
+            }
+        })
+        .collect())
+}
+
+async fn get_domains(
+    client: &Client,
+    schemas: &HashSet<String>,
+) -> Result<Vec<Domain>, anyhow::Error> {
+    let rows = client
+        .query(
+            "SELECT n.nspname AS schema, t.typname AS name,
+                    format_type(t.typbasetype, t.typtypmod) AS data_type,
+                    CASE WHEN t.typcollation <> 0 AND t.typcollation <> bt.typcollation
+                         THEN format('%I.%I', cn.nspname, co.collname) END AS collation,
+                    t.typdefault AS default_expr,
+                    t.typnotnull AS not_null,
+                    coalesce((SELECT array_agg(c.conname::text ORDER BY c.conname)
+                              FROM pg_constraint c
+                              WHERE c.contypid = t.oid AND c.contype = 'c'), '{}') AS check_names,
+                    coalesce((SELECT array_agg(pg_get_constraintdef(c.oid) ORDER BY c.conname)
+                              FROM pg_constraint c
+                              WHERE c.contypid = t.oid AND c.contype = 'c'), '{}') AS check_defs
+             FROM pg_type t
+             JOIN pg_namespace n ON t.typnamespace = n.oid
+             JOIN pg_type bt ON t.typbasetype = bt.oid
+             LEFT JOIN pg_collation co ON t.typcollation = co.oid
+             LEFT JOIN pg_namespace cn ON co.collnamespace = cn.oid
+             WHERE t.typtype = 'd'
+               AND t.oid NOT IN (SELECT objid FROM pg_depend WHERE deptype = 'e')
+             ORDER BY n.nspname, t.typname",
+            &[],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let schema: String = row.get("schema");
+            if schemas.contains(&schema) {
+                let names: Vec<String> = row.get("check_names");
+                let defs: Vec<String> = row.get("check_defs");
+                Some(Domain {
+                    schema,
+                    name: row.get("name"),
+                    data_type: row.get("data_type"),
+                    collation: row.get("collation"),
+                    default: row.get("default_expr"),
+                    not_null: row.get("not_null"),
+                    checks: names.into_iter().zip(defs).collect(),
+                })
+            } else {
+                None
+            }
+        })
+        .collect())
+}
+
+async fn get_composite_types(
+    client: &Client,
+    schemas: &HashSet<String>,
+) -> Result<Vec<CompositeType>, anyhow::Error> {
+    // Only standalone composite types (relkind 'c'); every table has one too.
+    // Creation order, so a type comes after the types its attributes use
+    let rows = client
+        .query(
+            "SELECT n.nspname AS schema, t.typname AS name,
+                    array_agg(a.attname::text ORDER BY a.attnum) AS attr_names,
+                    array_agg(format_type(a.atttypid, a.atttypmod)
+                              || CASE WHEN a.attcollation <> 0
+                                       AND a.attcollation <> at.typcollation
+                                      THEN ' COLLATE ' || format('%I.%I', cn.nspname, co.collname)
+                                      ELSE '' END
+                              ORDER BY a.attnum) AS attr_types
+             FROM pg_type t
+             JOIN pg_namespace n ON t.typnamespace = n.oid
+             JOIN pg_class c ON t.typrelid = c.oid AND c.relkind = 'c'
+             JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+             JOIN pg_type at ON a.atttypid = at.oid
+             LEFT JOIN pg_collation co ON a.attcollation = co.oid
+             LEFT JOIN pg_namespace cn ON co.collnamespace = cn.oid
+             WHERE t.typtype = 'c'
+               AND t.oid NOT IN (SELECT objid FROM pg_depend WHERE deptype = 'e')
+             GROUP BY t.oid, n.nspname, t.typname
+             ORDER BY t.oid",
+            &[],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let schema: String = row.get("schema");
+            if schemas.contains(&schema) {
+                let names: Vec<String> = row.get("attr_names");
+                let types: Vec<String> = row.get("attr_types");
+                Some(CompositeType {
+                    schema,
+                    name: row.get("name"),
+                    attributes: names.into_iter().zip(types).collect(),
+                })
+            } else {
+                None
+            }
+        })
+        .collect())
+}
+
 async fn get_sequences(
     client: &Client,
     schemas: &HashSet<String>,
@@ -416,7 +905,7 @@ async fn get_sequences(
              WHERE NOT EXISTS (
                  SELECT 1 FROM pg_depend d
                  WHERE d.objid = c.oid
-                   AND d.deptype = 'a'
+                   AND d.deptype IN ('a', 'i')
                    AND d.classid = 'pg_class'::regclass
              )
              ORDER BY n.nspname, c.relname",
@@ -468,6 +957,9 @@ async fn get_tables(
                          WHERE i.inhrelid = c.oid)
                     END AS parent_name,
                     pg_get_expr(c.relpartbound, c.oid) AS partition_bound,
+                    obj_description(c.oid, 'pg_class') AS comment,
+                    c.relrowsecurity AS rls_enabled,
+                    c.relforcerowsecurity AS rls_forced,
                     CASE WHEN pt.partstrat IS NOT NULL THEN pt.partstrat END AS partition_strategy,
                     CASE WHEN pt.partattrs IS NOT NULL THEN
                         (SELECT array_agg(a.attname ORDER BY pos)
@@ -502,6 +994,7 @@ async fn get_tables(
         let partition_bound: Option<String> = row.get("partition_bound");
         let partition_strategy: Option<i8> = row.get("partition_strategy");
         let partition_columns: Option<Vec<String>> = row.get("partition_columns");
+        let comment: Option<String> = row.get("comment");
 
         // Get primary key for this table
         let primary_key = get_table_primary_key(client, &schema, &table_name).await?;
@@ -537,12 +1030,148 @@ async fn get_tables(
             parent_schema,
             parent_name,
             partition_bound,
+            comment,
+            rls_enabled: row.get("rls_enabled"),
+            rls_forced: row.get("rls_forced"),
+        });
+    }
+
+    Ok(tables)
+}
+
+async fn get_foreign_servers(client: &Client) -> Result<Vec<ForeignServer>, anyhow::Error> {
+    let rows = client
+        .query(
+            "SELECT s.srvname AS name, w.fdwname AS wrapper,
+                    s.srvtype AS server_type, s.srvversion AS version,
+                    coalesce(s.srvoptions, '{}') AS options
+             FROM pg_foreign_server s
+             JOIN pg_foreign_data_wrapper w ON s.srvfdw = w.oid
+             WHERE s.oid NOT IN (SELECT objid FROM pg_depend WHERE deptype = 'e')
+             ORDER BY s.srvname",
+            &[],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ForeignServer {
+            name: row.get("name"),
+            wrapper: row.get("wrapper"),
+            server_type: row.get("server_type"),
+            version: row.get("version"),
+            options: row.get("options"),
+        })
+        .collect())
+}
+
+async fn get_user_mappings(client: &Client) -> Result<Vec<UserMapping>, anyhow::Error> {
+    // Option values never leave the server
+    let rows = client
+        .query(
+            "SELECT um.srvname::text AS server,
+                    CASE WHEN um.umuser = 0 THEN 'PUBLIC' ELSE um.usename::text END AS user_name,
+                    ARRAY(SELECT split_part(o, '=', 1) FROM unnest(um.umoptions) o) AS options
+             FROM pg_user_mappings um
+             ORDER BY 1, 2",
+            &[],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| UserMapping {
+            server: row.get("server"),
+            user: row.get("user_name"),
+            options: row.get("options"),
+        })
+        .collect())
+}
+
+async fn get_foreign_tables(
+    client: &Client,
+    schemas: &HashSet<String>,
+) -> Result<Vec<ForeignTable>, anyhow::Error> {
+    let rows = client
+        .query(
+            "SELECT n.nspname AS schema,
+                    c.relname AS name,
+                    s.srvname AS server,
+                    coalesce(ft.ftoptions, '{}') AS options,
+                    CASE WHEN c.relispartition THEN
+                        (SELECT format('%I.%I', pn.nspname, pc.relname)
+                         FROM pg_inherits i
+                         JOIN pg_class pc ON i.inhparent = pc.oid
+                         JOIN pg_namespace pn ON pc.relnamespace = pn.oid
+                         WHERE i.inhrelid = c.oid)
+                    END AS parent,
+                    pg_get_expr(c.relpartbound, c.oid) AS partition_bound
+             FROM pg_foreign_table ft
+             JOIN pg_class c ON ft.ftrelid = c.oid
+             JOIN pg_namespace n ON c.relnamespace = n.oid
+             JOIN pg_foreign_server s ON ft.ftserver = s.oid
+             WHERE c.oid NOT IN (SELECT objid FROM pg_depend WHERE deptype = 'e')
+             ORDER BY n.nspname, c.relname",
+            &[],
+        )
+        .await?;
+
+    let mut tables = Vec::new();
+    for row in rows {
+        let schema: String = row.get("schema");
+        if !schemas.contains(&schema) {
+            continue;
+        }
+        let name: String = row.get("name");
+        let columns = get_table_columns(client, &schema, &name, &[]).await?;
+        let column_options = client
+            .query(
+                "SELECT a.attname::text AS name, a.attfdwoptions AS options
+                 FROM pg_attribute a
+                 JOIN pg_class c ON a.attrelid = c.oid
+                 JOIN pg_namespace n ON c.relnamespace = n.oid
+                 WHERE n.nspname = $1
+                   AND c.relname = $2
+                   AND a.attnum > 0
+                   AND NOT a.attisdropped
+                   AND a.attfdwoptions IS NOT NULL
+                 ORDER BY a.attnum",
+                &[&schema, &name],
+            )
+            .await?
+            .iter()
+            .map(|r| (r.get("name"), r.get("options")))
+            .collect();
+
+        tables.push(ForeignTable {
+            schema,
+            name,
+            server: row.get("server"),
+            columns,
+            column_options,
+            options: row.get("options"),
+            parent: row.get("parent"),
+            partition_bound: row.get("partition_bound"),
         });
     }
 
     Ok(tables)
 }
 
+/// Columns of any relation (table, view or materialized view) in attnum order;
+/// empty if it doesn't exist
+pub async fn relation_columns(
+    client: &Client,
+    schema: &str,
+    name: &str,
+) -> Result<Vec<Column>, anyhow::Error> {
+    let pk_columns = get_table_primary_key(client, schema, name)
+        .await?
+        .map(|pk| pk.columns)
+        .unwrap_or_default();
+    get_table_columns(client, schema, name, &pk_columns).await
+}
+
 async fn get_table_columns(
     client: &Client,
     schema: &str,
@@ -556,6 +1185,8 @@ async fn get_table_columns(
                     NOT a.attnotnull AS nullable,
                     pg_get_expr(d.adbin, d.adrelid) AS default_expr,
                     a.attidentity AS identity,
+                    a.attgenerated = 's' AS generated,
+                    col_description(a.attrelid, a.attnum) AS comment,
                     CASE WHEN a.attidentity = '' AND d.adbin IS NOT NULL
                          AND pg_get_expr(d.adbin, d.adrelid) LIKE 'nextval(%'
                          THEN true ELSE false END AS is_serial
@@ -600,9 +1231,11 @@ async fn get_table_columns(
                 data_type: row.get("data_type"),
                 nullable: row.get("nullable"),
                 default,
+                generated: row.get("generated"),
                 identity,
                 is_serial,
                 is_primary_key,
+                comment: row.get("comment"),
             }
         })
         .collect())
@@ -909,6 +1542,54 @@ async fn get_materialized_views(
     Ok(matviews)
 }
 
+async fn get_policies(
+    client: &Client,
+    schemas: &HashSet<String>,
+) -> Result<Vec<Policy>, anyhow::Error> {
+    let rows = client
+        .query(
+            "SELECT n.nspname AS schema,
+                    c.relname AS table_name,
+                    p.polname AS name,
+                    CASE p.polcmd
+                        WHEN 'r' THEN 'SELECT'
+                        WHEN 'a' THEN 'INSERT'
+                        WHEN 'w' THEN 'UPDATE'
+                        WHEN 'd' THEN 'DELETE'
+                        ELSE 'ALL'
+                    END AS command,
+                    p.polpermissive AS permissive,
+                    ARRAY(SELECT rolname::text FROM pg_roles
+                          WHERE oid = ANY(p.polroles) ORDER BY 1) AS roles,
+                    pg_get_expr(p.polqual, p.polrelid) AS using_expr,
+                    pg_get_expr(p.polwithcheck, p.polrelid) AS with_check_expr
+             FROM pg_policy p
+             JOIN pg_class c ON p.polrelid = c.oid
+             JOIN pg_namespace n ON c.relnamespace = n.oid
+             WHERE n.nspname NOT LIKE 'pg_%'
+               AND n.nspname != 'information_schema'
+               AND n.nspname != 'pgcrate'
+             ORDER BY n.nspname, c.relname, p.polname",
+            &[],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .filter(|row| schemas.contains(&row.get::<_, String>("schema")))
+        .map(|row| Policy {
+            schema: row.get("schema"),
+            table_name: row.get("table_name"),
+            name: row.get("name"),
+            command: row.get("command"),
+            permissive: row.get("permissive"),
+            roles: row.get("roles"),
+            using_expr: row.get("using_expr"),
+            with_check_expr: row.get("with_check_expr"),
+        })
+        .collect())
+}
+
 // =============================================================================
 // SQL Generation
 // =============================================================================
@@ -935,7 +1616,8 @@ fn generate_single_file(
     let timestamp = base_time.format("%Y%m%d%H%M%S");
     let filename = format!("{}_initial_schema.sql", timestamp);
 
-    let (up_sql, stats) = schema_to_sql(schema);
+    let (up_sql, stats) = full_schema_sql(schema);
+    // Policies and grants go away with their objects
     let down_sql = schema_to_drop_sql(schema);
 
     let content = format_migration_file(database_url, &base_time, &up_sql, &down_sql);
@@ -947,6 +1629,37 @@ fn generate_single_file(
     }
 }
 
+/// Everything in one script: objects, then policies, then privileges
+fn full_schema_sql(schema: &DatabaseSchema) -> (String, FileStats) {
+    let (mut sql, mut stats) = schema_to_sql(schema);
+    let (policies_sql, policy_stats) = policies_to_sql(schema);
+    let (privileges_sql, privilege_stats) = privileges_to_sql(schema);
+    for part in [policies_sql, privileges_sql] {
+        if !part.is_empty() {
+            sql.push('\n');
+            sql.push_str(&part);
+        }
+    }
+    stats.policy_count = policy_stats.policy_count;
+    stats.grant_count = privilege_stats.grant_count;
+    (sql, stats)
+}
+
+/// Declarative schema file: the whole schema as one ordered script, with no
+/// timestamp or source, so regenerating an unchanged database rewrites the
+/// same bytes
+pub fn format_declarative_file(schema: &DatabaseSchema) -> (String, FileStats) {
+    let (sql, stats) = full_schema_sql(schema);
+    let content = format!(
+        "-- Declarative schema generated by pgcrate generate --declarative\n\
+         -- Edit this file, then run `pgcrate plan` for the migration that applies it\n\
+         \n\
+         {}\n",
+        sql.trim()
+    );
+    (content, stats)
+}
+
 fn generate_by_schema(
     schema: &DatabaseSchema,
     base_time: DateTime<Utc>,
@@ -992,6 +1705,21 @@ fn generate_by_schema(
         });
     }
 
+    // Servers and user mappings aren't in any schema, so they get their own file
+    if has_servers(schema) {
+        let servers = DatabaseSchema {
+            foreign_servers: schema.foreign_servers.clone(),
+            user_mappings: schema.user_mappings.clone(),
+            ..Default::default()
+        };
+        files.push(sub_schema_file(
+            &servers,
+            "foreign_servers",
+            base_time + Duration::seconds(files.len() as i64),
+            database_url,
+        ));
+    }
+
     // Generate a file per schema
     for schema_name in &schema_names {
         let filtered = filter_schema_by_name(schema, schema_name);
@@ -1003,7 +1731,13 @@ fn generate_by_schema(
         let timestamp = (base_time + Duration::seconds(files.len() as i64)).format("%Y%m%d%H%M%S");
         let filename = format!("{}_schema_{}.sql", timestamp, schema_name);
 
-        let (up_sql, stats) = schema_to_sql(&filtered);
+        let (mut up_sql, mut stats) = schema_to_sql(&filtered);
+        let (policies_sql, policy_stats) = policies_to_sql(&filtered);
+        if !policies_sql.is_empty() {
+            up_sql.push('\n');
+            up_sql.push_str(&policies_sql);
+        }
+        stats.policy_count = policy_stats.policy_count;
         let down_sql = schema_to_drop_sql(&filtered);
 
         let content = format_migration_file(
@@ -1020,6 +1754,11 @@ fn generate_by_schema(
         });
     }
 
+    // Grants can cross schemas, so they come once everything exists
+    if let Some(file) = privileges_file(schema, &files, base_time, database_url) {
+        files.push(file);
+    }
+
     files
 }
 
@@ -1030,10 +1769,11 @@ fn generate_by_table(
 ) -> Vec<GeneratedFile> {
     let mut files = Vec::new();
 
-    // First file: extensions + schemas + enums + standalone sequences
+    // First file: extensions + schemas + types + servers + standalone sequences
     if !schema.extensions.is_empty()
         || !schema.schemas.is_empty()
-        || !schema.enums.is_empty()
+        || has_types(schema)
+        || has_servers(schema)
         || !schema.sequences.is_empty()
     {
         let timestamp = base_time.format("%Y%m%d%H%M%S");
@@ -1066,25 +1806,11 @@ fn generate_by_table(
             stats.schema_count = schema.schemas.len();
         }
 
-        // Enums
-        if !schema.enums.is_empty() {
-            up_parts.push("-- Types (enums)".to_string());
-            for e in &schema.enums {
-                let values: Vec<String> = e
-                    .values
-                    .iter()
-                    .map(|v| format!("'{}'", v.replace('\'', "''")))
-                    .collect();
-                up_parts.push(format!(
-                    "CREATE TYPE {}.{} AS ENUM ({});",
-                    quote_ident(&e.schema),
-                    quote_ident(&e.name),
-                    values.join(", ")
-                ));
-            }
-            up_parts.push(String::new());
-            stats.enum_count = schema.enums.len();
-        }
+        // Collations, enums, domains, and composite types
+        push_type_sections(&mut up_parts, schema, &mut stats);
+
+        // Foreign servers and user mappings
+        push_server_sections(&mut up_parts, schema, &mut stats);
 
         // Sequences
         if !schema.sequences.is_empty() {
@@ -1104,13 +1830,8 @@ fn generate_by_table(
                 quote_ident(&seq.name)
             ));
         }
-        for e in schema.enums.iter().rev() {
-            down_parts.push(format!(
-                "DROP TYPE IF EXISTS {}.{};",
-                quote_ident(&e.schema),
-                quote_ident(&e.name)
-            ));
-        }
+        down_parts.extend(server_drop_statements(schema));
+        down_parts.extend(type_drop_statements(schema));
         for s in schema.schemas.iter().rev() {
             down_parts.push(format!("DROP SCHEMA IF EXISTS {};", quote_ident(&s.name)));
         }
@@ -1257,6 +1978,20 @@ fn generate_by_table(
         });
     }
 
+    // Foreign tables file (after the tables, since they can be partitions)
+    if !schema.foreign_tables.is_empty() {
+        let foreign = DatabaseSchema {
+            foreign_tables: schema.foreign_tables.clone(),
+            ..Default::default()
+        };
+        files.push(sub_schema_file(
+            &foreign,
+            "foreign_tables",
+            base_time + Duration::seconds(files.len() as i64),
+            database_url,
+        ));
+    }
+
     // Views file
     if !schema.views.is_empty() {
         let timestamp = (base_time + Duration::seconds(files.len() as i64)).format("%Y%m%d%H%M%S");
@@ -1482,9 +2217,75 @@ fn generate_by_table(
         });
     }
 
+    // Policies file (policy expressions may call the functions above)
+    if schema.tables.iter().any(|t| t.rls_enabled || t.rls_forced) || !schema.policies.is_empty() {
+        let timestamp = (base_time + Duration::seconds(files.len() as i64)).format("%Y%m%d%H%M%S");
+        let filename = format!("{}_policies.sql", timestamp);
+
+        let (up_sql, stats) = policies_to_sql(schema);
+        let down_sql = policies_to_drop_sql(schema);
+
+        let content = format_migration_file(
+            database_url,
+            &(base_time + Duration::seconds(files.len() as i64)),
+            &up_sql,
+            &down_sql,
+        );
+
+        files.push(GeneratedFile {
+            filename,
+            content,
+            stats,
+        });
+    }
+
+    if let Some(file) = privileges_file(schema, &files, base_time, database_url) {
+        files.push(file);
+    }
+
     files
 }
 
+/// Split file `<timestamp>_<name>.sql` creating everything in `schema`
+fn sub_schema_file(
+    schema: &DatabaseSchema,
+    name: &str,
+    time: DateTime<Utc>,
+    database_url: &str,
+) -> GeneratedFile {
+    let (up_sql, stats) = schema_to_sql(schema);
+    let down_sql = schema_to_drop_sql(schema);
+    GeneratedFile {
+        filename: format!("{}_{}.sql", time.format("%Y%m%d%H%M%S"), name),
+        content: format_migration_file(database_url, &time, &up_sql, &down_sql),
+        stats,
+    }
+}
+
+/// Last file of a split: grants on every generated object
+fn privileges_file(
+    schema: &DatabaseSchema,
+    files: &[GeneratedFile],
+    base_time: DateTime<Utc>,
+    database_url: &str,
+) -> Option<GeneratedFile> {
+    let (up_sql, stats) = privileges_to_sql(schema);
+    if up_sql.is_empty() {
+        return None;
+    }
+
+    let time = base_time + Duration::seconds(files.len() as i64);
+    let filename = format!("{}_privileges.sql", time.format("%Y%m%d%H%M%S"));
+    let down_sql = privileges_to_drop_sql(schema);
+    let content = format_migration_file(database_url, &time, &up_sql, &down_sql);
+
+    Some(GeneratedFile {
+        filename,
+        content,
+        stats,
+    })
+}
+
 /// Convert schema model to SQL CREATE statements
 pub fn schema_to_sql(schema: &DatabaseSchema) -> (String, FileStats) {
     let mut parts = Vec::new();
@@ -1513,25 +2314,11 @@ pub fn schema_to_sql(schema: &DatabaseSchema) -> (String, FileStats) {
         stats.schema_count = schema.schemas.len();
     }
 
-    // Enums
-    if !schema.enums.is_empty() {
-        parts.push("-- Types (enums)".to_string());
-        for e in &schema.enums {
-            let values: Vec<String> = e
-                .values
-                .iter()
-                .map(|v| format!("'{}'", v.replace('\'', "''")))
-                .collect();
-            parts.push(format!(
-                "CREATE TYPE {}.{} AS ENUM ({});",
-                quote_ident(&e.schema),
-                quote_ident(&e.name),
-                values.join(", ")
-            ));
-        }
-        parts.push(String::new());
-        stats.enum_count = schema.enums.len();
-    }
+    // Collations, enums, domains, and composite types
+    push_type_sections(&mut parts, schema, &mut stats);
+
+    // Foreign servers and user mappings
+    push_server_sections(&mut parts, schema, &mut stats);
 
     // Sequences
     if !schema.sequences.is_empty() {
@@ -1568,6 +2355,16 @@ pub fn schema_to_sql(schema: &DatabaseSchema) -> (String, FileStats) {
         }
     }
 
+    // Foreign tables (after tables, since they can be partitions)
+    if !schema.foreign_tables.is_empty() {
+        parts.push("-- Foreign tables".to_string());
+        for table in &schema.foreign_tables {
+            parts.push(format_foreign_table_create(table));
+            parts.push(String::new());
+        }
+        stats.foreign_table_count = schema.foreign_tables.len();
+    }
+
     // Views
     if !schema.views.is_empty() {
         parts.push("-- Views".to_string());
@@ -1636,6 +2433,16 @@ pub fn schema_to_sql(schema: &DatabaseSchema) -> (String, FileStats) {
         stats.fk_count = fks.len();
     }
 
+    // Functions (before the triggers that call them)
+    if !schema.functions.is_empty() {
+        parts.push("-- Functions".to_string());
+        for func in &schema.functions {
+            parts.push(func.definition.clone());
+            parts.push(String::new());
+        }
+        stats.function_count = schema.functions.len();
+    }
+
     // Triggers
     if !schema.triggers.is_empty() {
         parts.push("-- Triggers".to_string());
@@ -1646,16 +2453,6 @@ pub fn schema_to_sql(schema: &DatabaseSchema) -> (String, FileStats) {
         stats.trigger_count = schema.triggers.len();
     }
 
-    // Functions
-    if !schema.functions.is_empty() {
-        parts.push("-- Functions".to_string());
-        for func in &schema.functions {
-            parts.push(func.definition.clone());
-            parts.push(String::new());
-        }
-        stats.function_count = schema.functions.len();
-    }
-
     // Materialized Views
     if !schema.materialized_views.is_empty() {
         parts.push("-- Materialized Views".to_string());
@@ -1696,19 +2493,6 @@ pub fn schema_to_drop_sql(schema: &DatabaseSchema) -> String {
         parts.push(String::new());
     }
 
-    // Functions
-    if !schema.functions.is_empty() {
-        parts.push("-- Functions".to_string());
-        for func in schema.functions.iter().rev() {
-            let keyword = match func.kind {
-                FunctionKind::Function => "FUNCTION",
-                FunctionKind::Procedure => "PROCEDURE",
-            };
-            parts.push(format!("DROP {} IF EXISTS {};", keyword, func.identity));
-        }
-        parts.push(String::new());
-    }
-
     // Triggers
     if !schema.triggers.is_empty() {
         parts.push("-- Triggers".to_string());
@@ -1723,6 +2507,19 @@ pub fn schema_to_drop_sql(schema: &DatabaseSchema) -> String {
         parts.push(String::new());
     }
 
+    // Functions
+    if !schema.functions.is_empty() {
+        parts.push("-- Functions".to_string());
+        for func in schema.functions.iter().rev() {
+            let keyword = match func.kind {
+                FunctionKind::Function => "FUNCTION",
+                FunctionKind::Procedure => "PROCEDURE",
+            };
+            parts.push(format!("DROP {} IF EXISTS {};", keyword, func.identity));
+        }
+        parts.push(String::new());
+    }
+
     // Foreign Keys
     let fks: Vec<&Constraint> = schema
         .constraints
@@ -1769,6 +2566,19 @@ pub fn schema_to_drop_sql(schema: &DatabaseSchema) -> String {
         parts.push(String::new());
     }
 
+    // Foreign tables
+    if !schema.foreign_tables.is_empty() {
+        parts.push("-- Foreign tables".to_string());
+        for table in schema.foreign_tables.iter().rev() {
+            parts.push(format!(
+                "DROP FOREIGN TABLE IF EXISTS {}.{};",
+                quote_ident(&table.schema),
+                quote_ident(&table.name)
+            ));
+        }
+        parts.push(String::new());
+    }
+
     // Tables (partitions first, then regular tables)
     // Partitioned parent tables need CASCADE to drop their partitions
     let partition_tables: Vec<&Table> = schema.tables.iter().filter(|t| t.is_partition).collect();
@@ -1813,16 +2623,19 @@ pub fn schema_to_drop_sql(schema: &DatabaseSchema) -> String {
         parts.push(String::new());
     }
 
-    // Enums
-    if !schema.enums.is_empty() {
+    // User mappings and foreign servers
+    let servers = server_drop_statements(schema);
+    if !servers.is_empty() {
+        parts.push("-- Foreign servers".to_string());
+        parts.extend(servers);
+        parts.push(String::new());
+    }
+
+    // Composite types, domains, enums, and collations
+    let types = type_drop_statements(schema);
+    if !types.is_empty() {
         parts.push("-- Types".to_string());
-        for e in schema.enums.iter().rev() {
-            parts.push(format!(
-                "DROP TYPE IF EXISTS {}.{};",
-                quote_ident(&e.schema),
-                quote_ident(&e.name)
-            ));
-        }
+        parts.extend(types);
         parts.push(String::new());
     }
 
@@ -1846,11 +2659,188 @@ pub fn schema_to_drop_sql(schema: &DatabaseSchema) -> String {
     parts.join("\n")
 }
 
+/// Row-level security switches and policies. Policy expressions may call
+/// functions, so this runs after everything in `schema_to_sql`.
+pub fn policies_to_sql(schema: &DatabaseSchema) -> (String, FileStats) {
+    let mut parts = Vec::new();
+    let stats = FileStats {
+        policy_count: schema.policies.len(),
+        ..Default::default()
+    };
+
+    let mut switches = Vec::new();
+    for table in &schema.tables {
+        let name = format!(
+            "{}.{}",
+            quote_ident(&table.schema),
+            quote_ident(&table.name)
+        );
+        if table.rls_enabled {
+            switches.push(format!("ALTER TABLE {} ENABLE ROW LEVEL SECURITY;", name));
+        }
+        if table.rls_forced {
+            switches.push(format!("ALTER TABLE {} FORCE ROW LEVEL SECURITY;", name));
+        }
+    }
+    if !switches.is_empty() {
+        parts.push("-- Row-level security".to_string());
+        parts.extend(switches);
+        parts.push(String::new());
+    }
+
+    if !schema.policies.is_empty() {
+        parts.push("-- Policies".to_string());
+        for policy in &schema.policies {
+            parts.push(format_policy_create(policy));
+        }
+        parts.push(String::new());
+    }
+
+    (parts.join("\n"), stats)
+}
+
+/// Undo `policies_to_sql` for tables that stay
+fn policies_to_drop_sql(schema: &DatabaseSchema) -> String {
+    let mut parts = Vec::new();
+
+    if !schema.policies.is_empty() {
+        parts.push("-- Policies".to_string());
+        for policy in schema.policies.iter().rev() {
+            parts.push(format!(
+                "DROP POLICY IF EXISTS {} ON {}.{};",
+                quote_ident(&policy.name),
+                quote_ident(&policy.schema),
+                quote_ident(&policy.table_name)
+            ));
+        }
+        parts.push(String::new());
+    }
+
+    let mut switches = Vec::new();
+    for table in schema.tables.iter().rev() {
+        let name = format!(
+            "{}.{}",
+            quote_ident(&table.schema),
+            quote_ident(&table.name)
+        );
+        if table.rls_forced {
+            switches.push(format!("ALTER TABLE {} NO FORCE ROW LEVEL SECURITY;", name));
+        }
+        if table.rls_enabled {
+            switches.push(format!("ALTER TABLE {} DISABLE ROW LEVEL SECURITY;", name));
+        }
+    }
+    if !switches.is_empty() {
+        parts.push("-- Row-level security".to_string());
+        parts.extend(switches);
+    }
+
+    parts.join("\n")
+}
+
+/// GRANT and REVOKE statements that reproduce `schema.privileges`, each
+/// paired with the statement that undoes it. Only departures from the
+/// built-in defaults are emitted: grants to roles other than the owner, and
+/// revokes of EXECUTE on routines or USAGE on types from PUBLIC. Ownership
+/// is left to whoever runs the migration.
+fn privilege_statements(schema: &DatabaseSchema) -> Vec<(String, String)> {
+    // What PUBLIC holds unless revoked
+    let public_default = |kind: &str| match kind {
+        "function" | "procedure" => Some("EXECUTE"),
+        "type" | "domain" => Some("USAGE"),
+        _ => None,
+    };
+    let grantee = |name: &str| {
+        if name == "PUBLIC" {
+            name.to_string()
+        } else {
+            quote_ident(name)
+        }
+    };
+    let mut statements = Vec::new();
+
+    for owner in &schema.privileges.owners {
+        let Some(privilege) = public_default(&owner.kind) else {
+            continue;
+        };
+        let public = schema.privileges.grants.iter().any(|g| {
+            g.kind == owner.kind
+                && g.object == owner.name
+                && g.grantee == "PUBLIC"
+                && g.privilege == privilege
+        });
+        if !public {
+            let on = format!(
+                "{} ON {} {}",
+                privilege,
+                grant_object_keyword(&owner.kind),
+                owner.name
+            );
+            statements.push((
+                format!("REVOKE {} FROM PUBLIC;", on),
+                format!("GRANT {} TO PUBLIC;", on),
+            ));
+        }
+    }
+
+    for g in &schema.privileges.grants {
+        if g.grantee == "PUBLIC" && public_default(&g.kind) == Some(g.privilege.as_str()) {
+            continue;
+        }
+        let on = format!(
+            "{} ON {} {}",
+            g.privilege,
+            grant_object_keyword(&g.kind),
+            g.object
+        );
+        let option = if g.grantable {
+            " WITH GRANT OPTION"
+        } else {
+            ""
+        };
+        statements.push((
+            format!("GRANT {} TO {}{};", on, grantee(&g.grantee), option),
+            format!("REVOKE {} FROM {};", on, grantee(&g.grantee)),
+        ));
+    }
+    statements
+}
+
+/// Privileges on the generated objects, after everything else exists.
+/// Grantees must already exist.
+pub fn privileges_to_sql(schema: &DatabaseSchema) -> (String, FileStats) {
+    let statements = privilege_statements(schema);
+    let stats = FileStats {
+        grant_count: statements.len(),
+        ..Default::default()
+    };
+    if statements.is_empty() {
+        return (String::new(), stats);
+    }
+
+    let mut parts = vec!["-- Privileges".to_string()];
+    parts.extend(statements.into_iter().map(|(grant, _)| grant));
+    parts.push(String::new());
+    (parts.join("\n"), stats)
+}
+
+/// Undo `privileges_to_sql` for objects that stay
+fn privileges_to_drop_sql(schema: &DatabaseSchema) -> String {
+    let statements = privilege_statements(schema);
+    if statements.is_empty() {
+        return String::new();
+    }
+
+    let mut parts = vec!["-- Privileges".to_string()];
+    parts.extend(statements.into_iter().rev().map(|(_, revoke)| revoke));
+    parts.join("\n")
+}
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
 
-fn format_migration_file(
+pub(crate) fn format_migration_file(
     database_url: &str,
     timestamp: &DateTime<Utc>,
     up_sql: &str,
@@ -1967,10 +2957,39 @@ fn format_table_create(table: &Table) -> String {
         }
     }
 
+    parts.extend(format_table_comments(table));
     parts.join("\n")
 }
 
-fn format_column_def(col: &Column) -> String {
+/// COMMENT ON statements for a table and its columns
+fn format_table_comments(table: &Table) -> Vec<String> {
+    let qualified = format!(
+        "{}.{}",
+        quote_ident(&table.schema),
+        quote_ident(&table.name)
+    );
+    let mut parts = Vec::new();
+    if let Some(ref comment) = table.comment {
+        parts.push(format!(
+            "COMMENT ON TABLE {} IS {};",
+            qualified,
+            quote_literal(comment)
+        ));
+    }
+    for col in &table.columns {
+        if let Some(ref comment) = col.comment {
+            parts.push(format!(
+                "COMMENT ON COLUMN {}.{} IS {};",
+                qualified,
+                quote_ident(&col.name),
+                quote_literal(comment)
+            ));
+        }
+    }
+    parts
+}
+
+pub(crate) fn format_column_def(col: &Column) -> String {
     let mut parts = Vec::new();
 
     parts.push(quote_ident(&col.name));
@@ -2009,9 +3028,13 @@ fn format_column_def(col: &Column) -> String {
         parts.push("NOT NULL".to_string());
     }
 
-    // Default
+    // Default, or the expression of a generated column
     if let Some(ref default) = col.default {
-        parts.push(format!("DEFAULT {}", default));
+        if col.generated {
+            parts.push(format!("GENERATED ALWAYS AS ({}) STORED", default));
+        } else {
+            parts.push(format!("DEFAULT {}", default));
+        }
     }
 
     parts.join(" ")
@@ -2043,8 +3066,360 @@ fn format_sequence_create(seq: &Sequence) -> String {
     format!("{};", parts.join("\n"))
 }
 
+fn has_types(schema: &DatabaseSchema) -> bool {
+    !schema.collations.is_empty()
+        || !schema.enums.is_empty()
+        || !schema.domains.is_empty()
+        || !schema.composite_types.is_empty()
+}
+
+/// Collations first (domains and attributes can use them), then enums and
+/// domains, then composite types, whose attributes can be any of those
+fn push_type_sections(parts: &mut Vec<String>, schema: &DatabaseSchema, stats: &mut FileStats) {
+    if !schema.collations.is_empty() {
+        parts.push("-- Collations".to_string());
+        parts.extend(schema.collations.iter().map(format_collation_create));
+        parts.push(String::new());
+    }
+
+    if !schema.enums.is_empty() {
+        parts.push("-- Types (enums)".to_string());
+        for e in &schema.enums {
+            let values: Vec<String> = e
+                .values
+                .iter()
+                .map(|v| format!("'{}'", v.replace('\'', "''")))
+                .collect();
+            parts.push(format!(
+                "CREATE TYPE {}.{} AS ENUM ({});",
+                quote_ident(&e.schema),
+                quote_ident(&e.name),
+                values.join(", ")
+            ));
+        }
+        parts.push(String::new());
+        stats.enum_count = schema.enums.len();
+    }
+
+    if !schema.domains.is_empty() {
+        parts.push("-- Domains".to_string());
+        parts.extend(schema.domains.iter().map(format_domain_create));
+        parts.push(String::new());
+    }
+
+    if !schema.composite_types.is_empty() {
+        parts.push("-- Types (composite)".to_string());
+        for t in &schema.composite_types {
+            parts.push(format_composite_type_create(t));
+            parts.push(String::new());
+        }
+    }
+
+    stats.type_count =
+        schema.collations.len() + schema.domains.len() + schema.composite_types.len();
+}
+
+/// Drops for `push_type_sections`, in reverse
+fn type_drop_statements(schema: &DatabaseSchema) -> Vec<String> {
+    let qualified =
+        |schema: &str, name: &str| format!("{}.{}", quote_ident(schema), quote_ident(name));
+    let mut drops = Vec::new();
+    for t in schema.composite_types.iter().rev() {
+        drops.push(format!(
+            "DROP TYPE IF EXISTS {};",
+            qualified(&t.schema, &t.name)
+        ));
+    }
+    for d in schema.domains.iter().rev() {
+        drops.push(format!(
+            "DROP DOMAIN IF EXISTS {};",
+            qualified(&d.schema, &d.name)
+        ));
+    }
+    for e in schema.enums.iter().rev() {
+        drops.push(format!(
+            "DROP TYPE IF EXISTS {};",
+            qualified(&e.schema, &e.name)
+        ));
+    }
+    for c in schema.collations.iter().rev() {
+        drops.push(format!(
+            "DROP COLLATION IF EXISTS {};",
+            qualified(&c.schema, &c.name)
+        ));
+    }
+    drops
+}
+
+/// Placeholder for user mapping option values
+const REDACTED: &str = "<redacted>";
+
+fn has_servers(schema: &DatabaseSchema) -> bool {
+    !schema.foreign_servers.is_empty() || !schema.user_mappings.is_empty()
+}
+
+/// Servers, then the user mappings on them
+fn push_server_sections(parts: &mut Vec<String>, schema: &DatabaseSchema, stats: &mut FileStats) {
+    if !schema.foreign_servers.is_empty() {
+        parts.push("-- Foreign servers".to_string());
+        parts.extend(schema.foreign_servers.iter().map(format_server_create));
+        parts.push(String::new());
+    }
+
+    if !schema.user_mappings.is_empty() {
+        parts.push(format!(
+            "-- User mappings (option values are {}; fill them in before running)",
+            REDACTED
+        ));
+        parts.extend(schema.user_mappings.iter().map(format_user_mapping_create));
+        parts.push(String::new());
+    }
+
+    stats.server_count = schema.foreign_servers.len() + schema.user_mappings.len();
+}
+
+/// Drops for `push_server_sections`, in reverse
+fn server_drop_statements(schema: &DatabaseSchema) -> Vec<String> {
+    let mut drops = Vec::new();
+    for m in schema.user_mappings.iter().rev() {
+        drops.push(format!(
+            "DROP USER MAPPING IF EXISTS FOR {} SERVER {};",
+            mapping_user(&m.user),
+            quote_ident(&m.server)
+        ));
+    }
+    for server in schema.foreign_servers.iter().rev() {
+        drops.push(format!(
+            "DROP SERVER IF EXISTS {};",
+            quote_ident(&server.name)
+        ));
+    }
+    drops
+}
+
+fn mapping_user(user: &str) -> String {
+    if user == "PUBLIC" {
+        user.to_string()
+    } else {
+        quote_ident(user)
+    }
+}
+
+/// ` OPTIONS (...)` from catalog `key=value` options; empty without options
+fn format_fdw_options(options: &[String]) -> String {
+    if options.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = options
+        .iter()
+        .map(|option| {
+            let (key, value) = option.split_once('=').unwrap_or((option.as_str(), ""));
+            format!("{} {}", quote_ident(key), quote_literal(value))
+        })
+        .collect();
+    format!(" OPTIONS ({})", pairs.join(", "))
+}
+
+pub(crate) fn format_server_create(server: &ForeignServer) -> String {
+    let mut sql = format!("CREATE SERVER {}", quote_ident(&server.name));
+    if let Some(ref server_type) = server.server_type {
+        sql.push_str(&format!(" TYPE {}", quote_literal(server_type)));
+    }
+    if let Some(ref version) = server.version {
+        sql.push_str(&format!(" VERSION {}", quote_literal(version)));
+    }
+    sql.push_str(&format!(
+        " FOREIGN DATA WRAPPER {}{};",
+        quote_ident(&server.wrapper),
+        format_fdw_options(&server.options)
+    ));
+    sql
+}
+
+pub(crate) fn format_user_mapping_create(mapping: &UserMapping) -> String {
+    let options: Vec<String> = mapping
+        .options
+        .iter()
+        .map(|key| format!("{}={}", key, REDACTED))
+        .collect();
+    format!(
+        "CREATE USER MAPPING FOR {} SERVER {}{};",
+        mapping_user(&mapping.user),
+        quote_ident(&mapping.server),
+        format_fdw_options(&options)
+    )
+}
+
+pub(crate) fn format_foreign_table_create(table: &ForeignTable) -> String {
+    let name = format!(
+        "{}.{}",
+        quote_ident(&table.schema),
+        quote_ident(&table.name)
+    );
+    let server = format!(
+        "SERVER {}{};",
+        quote_ident(&table.server),
+        format_fdw_options(&table.options)
+    );
+    if let Some(ref parent) = table.parent {
+        return format!(
+            "CREATE FOREIGN TABLE {} PARTITION OF {}\n    {}\n{}",
+            name,
+            parent,
+            table.partition_bound.as_deref().unwrap_or("DEFAULT"),
+            server
+        );
+    }
+
+    let columns: Vec<String> = table
+        .columns
+        .iter()
+        .map(|c| {
+            let options = table
+                .column_options
+                .iter()
+                .find(|(name, _)| *name == c.name)
+                .map(|(_, options)| format_fdw_options(options))
+                .unwrap_or_default();
+            format!("    {}{}", format_column_def(c), options)
+        })
+        .collect();
+    format!(
+        "CREATE FOREIGN TABLE {} (\n{}\n) {}",
+        name,
+        columns.join(",\n"),
+        server
+    )
+}
+
+pub(crate) fn format_collation_create(c: &Collation) -> String {
+    let mut options = vec![format!("provider = {}", c.provider)];
+    match (&c.lc_collate, &c.lc_ctype) {
+        (Some(collate), Some(ctype)) if collate == ctype => {
+            options.push(format!("locale = {}", quote_literal(collate)))
+        }
+        (collate, ctype) => {
+            if let Some(collate) = collate {
+                options.push(format!("lc_collate = {}", quote_literal(collate)));
+            }
+            if let Some(ctype) = ctype {
+                options.push(format!("lc_ctype = {}", quote_literal(ctype)));
+            }
+        }
+    }
+    if let Some(ref locale) = c.locale {
+        options.push(format!("locale = {}", quote_literal(locale)));
+    }
+    if !c.deterministic {
+        options.push("deterministic = false".to_string());
+    }
+    format!(
+        "CREATE COLLATION {}.{} ({});",
+        quote_ident(&c.schema),
+        quote_ident(&c.name),
+        options.join(", ")
+    )
+}
+
+pub(crate) fn format_domain_create(d: &Domain) -> String {
+    let mut sql = format!(
+        "CREATE DOMAIN {}.{} AS {}",
+        quote_ident(&d.schema),
+        quote_ident(&d.name),
+        d.data_type
+    );
+    if let Some(ref collation) = d.collation {
+        sql.push_str(&format!(" COLLATE {}", collation));
+    }
+    if let Some(ref default) = d.default {
+        sql.push_str(&format!(" DEFAULT {}", default));
+    }
+    if d.not_null {
+        sql.push_str(" NOT NULL");
+    }
+    for (name, definition) in &d.checks {
+        sql.push_str(&format!(
+            "\n    CONSTRAINT {} {}",
+            quote_ident(name),
+            definition
+        ));
+    }
+    sql.push(';');
+    sql
+}
+
+pub(crate) fn format_composite_type_create(t: &CompositeType) -> String {
+    let attributes: Vec<String> = t
+        .attributes
+        .iter()
+        .map(|(name, data_type)| format!("    {} {}", quote_ident(name), data_type))
+        .collect();
+    format!(
+        "CREATE TYPE {}.{} AS (\n{}\n);",
+        quote_ident(&t.schema),
+        quote_ident(&t.name),
+        attributes.join(",\n")
+    )
+}
+
+pub(crate) fn format_policy_create(p: &Policy) -> String {
+    let mut sql = format!(
+        "CREATE POLICY {} ON {}.{} AS {} FOR {} TO {}",
+        quote_ident(&p.name),
+        quote_ident(&p.schema),
+        quote_ident(&p.table_name),
+        if p.permissive {
+            "PERMISSIVE"
+        } else {
+            "RESTRICTIVE"
+        },
+        p.command,
+        policy_roles(p)
+    );
+    push_policy_exprs(&mut sql, p);
+    sql
+}
+
+/// Policy roles for display and SQL; no roles means PUBLIC
+pub(crate) fn policy_roles(p: &Policy) -> String {
+    if p.roles.is_empty() {
+        "PUBLIC".to_string()
+    } else {
+        p.roles
+            .iter()
+            .map(|r| quote_ident(r))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+pub(crate) fn push_policy_exprs(sql: &mut String, p: &Policy) {
+    if let Some(ref using) = p.using_expr {
+        sql.push_str(&format!(" USING ({})", using));
+    }
+    if let Some(ref check) = p.with_check_expr {
+        sql.push_str(&format!(" WITH CHECK ({})", check));
+    }
+    sql.push(';');
+}
+
+/// Object type keyword in GRANT and REVOKE
+pub(crate) fn grant_object_keyword(kind: &str) -> &'static str {
+    match kind {
+        "schema" => "SCHEMA",
+        "sequence" => "SEQUENCE",
+        "function" => "FUNCTION",
+        "procedure" => "PROCEDURE",
+        "type" => "TYPE",
+        "domain" => "DOMAIN",
+        // Views, materialized views, and foreign tables are granted as tables
+        _ => "TABLE",
+    }
+}
+
 fn filter_schema_by_name(schema: &DatabaseSchema, name: &str) -> DatabaseSchema {
     DatabaseSchema {
+        privileges: Privileges::default(),
         extensions: Vec::new(), // Extensions are global, handled separately
         schemas: schema
             .schemas
@@ -2052,12 +3427,39 @@ fn filter_schema_by_name(schema: &DatabaseSchema, name: &str) -> DatabaseSchema
             .filter(|s| s.name == name)
             .cloned()
             .collect(),
+        collations: schema
+            .collations
+            .iter()
+            .filter(|c| c.schema == name)
+            .cloned()
+            .collect(),
         enums: schema
             .enums
             .iter()
             .filter(|e| e.schema == name)
             .cloned()
             .collect(),
+        domains: schema
+            .domains
+            .iter()
+            .filter(|d| d.schema == name)
+            .cloned()
+            .collect(),
+        composite_types: schema
+            .composite_types
+            .iter()
+            .filter(|t| t.schema == name)
+            .cloned()
+            .collect(),
+        // Servers are global, handled separately
+        foreign_servers: Vec::new(),
+        user_mappings: Vec::new(),
+        foreign_tables: schema
+            .foreign_tables
+            .iter()
+            .filter(|t| t.schema == name)
+            .cloned()
+            .collect(),
         sequences: schema
             .sequences
             .iter()
@@ -2106,13 +3508,20 @@ fn filter_schema_by_name(schema: &DatabaseSchema, name: &str) -> DatabaseSchema
             .filter(|m| m.schema == name)
             .cloned()
             .collect(),
+        policies: schema
+            .policies
+            .iter()
+            .filter(|p| p.schema == name)
+            .cloned()
+            .collect(),
     }
 }
 
 fn is_schema_empty(schema: &DatabaseSchema) -> bool {
     schema.tables.is_empty()
+        && schema.foreign_tables.is_empty()
         && schema.views.is_empty()
-        && schema.enums.is_empty()
+        && !has_types(schema)
         && schema.functions.is_empty()
         && schema.triggers.is_empty()
         && schema.sequences.is_empty()
@@ -2167,9 +3576,11 @@ mod tests {
             data_type: "integer".to_string(),
             nullable: false,
             default: None,
+            generated: false,
             identity: None,
             is_serial: true,
             is_primary_key: false,
+            comment: None,
         };
         assert_eq!(format_column_def(&col), "\"id\" SERIAL");
     }
@@ -2181,9 +3592,11 @@ mod tests {
             data_type: "integer".to_string(),
             nullable: false,
             default: None,
+            generated: false,
             identity: None,
             is_serial: true,
             is_primary_key: true,
+            comment: None,
         };
         assert_eq!(format_column_def(&col), "\"id\" SERIAL PRIMARY KEY");
     }
@@ -2195,9 +3608,11 @@ mod tests {
             data_type: "integer".to_string(),
             nullable: false,
             default: None,
+            generated: false,
             identity: Some(IdentityType::Always),
             is_serial: false,
             is_primary_key: false,
+            comment: None,
         };
         assert_eq!(
             format_column_def(&col),
@@ -2205,6 +3620,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_column_def_generated() {
+        let col = Column {
+            name: "doubled".to_string(),
+            data_type: "integer".to_string(),
+            nullable: true,
+            default: Some("(id * 2)".to_string()),
+            generated: true,
+            identity: None,
+            is_serial: false,
+            is_primary_key: false,
+            comment: None,
+        };
+        assert_eq!(
+            format_column_def(&col),
+            "\"doubled\" integer GENERATED ALWAYS AS ((id * 2)) STORED"
+        );
+    }
+
     #[test]
     fn test_format_column_def_with_default() {
         let col = Column {
@@ -2212,13 +3646,335 @@ mod tests {
             data_type: "timestamp with time zone".to_string(),
             nullable: false,
             default: Some("now()".to_string()),
+            generated: false,
             identity: None,
             is_serial: false,
             is_primary_key: false,
+            comment: None,
         };
         assert_eq!(
             format_column_def(&col),
             "\"created_at\" timestamp with time zone NOT NULL DEFAULT now()"
         );
     }
+
+    #[test]
+    fn test_format_table_create_with_comments() {
+        let table = Table {
+            schema: "app".to_string(),
+            name: "users".to_string(),
+            columns: vec![Column {
+                name: "email".to_string(),
+                data_type: "text".to_string(),
+                nullable: true,
+                default: None,
+                generated: false,
+                identity: None,
+                is_serial: false,
+                is_primary_key: false,
+                comment: Some("Login address, lowercased".to_string()),
+            }],
+            primary_key: None,
+            partition_info: None,
+            is_partition: false,
+            parent_schema: None,
+            parent_name: None,
+            partition_bound: None,
+            comment: Some("One row per account's owner".to_string()),
+            rls_enabled: false,
+            rls_forced: false,
+        };
+        assert_eq!(
+            format_table_create(&table),
+            "CREATE TABLE \"app\".\"users\" (\n    \"email\" text\n);\n\
+             COMMENT ON TABLE \"app\".\"users\" IS 'One row per account''s owner';\n\
+             COMMENT ON COLUMN \"app\".\"users\".\"email\" IS 'Login address, lowercased';"
+        );
+    }
+
+    #[test]
+    fn test_policies_and_privileges_to_sql() {
+        let grant = |kind: &str, object: &str, grantee: &str, privilege: &str| Grant {
+            kind: kind.to_string(),
+            object: object.to_string(),
+            grantee: grantee.to_string(),
+            privilege: privilege.to_string(),
+            grantable: false,
+        };
+        let owner = |kind: &str, name: &str| ObjectOwner {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            owner: "app_owner".to_string(),
+        };
+        let schema = DatabaseSchema {
+            tables: vec![Table {
+                schema: "app".to_string(),
+                name: "notes".to_string(),
+                columns: Vec::new(),
+                primary_key: None,
+                partition_info: None,
+                is_partition: false,
+                parent_schema: None,
+                parent_name: None,
+                partition_bound: None,
+                comment: None,
+                rls_enabled: true,
+                rls_forced: false,
+            }],
+            policies: vec![Policy {
+                schema: "app".to_string(),
+                table_name: "notes".to_string(),
+                name: "own_notes".to_string(),
+                command: "ALL".to_string(),
+                permissive: true,
+                roles: vec!["app_user".to_string()],
+                using_expr: Some("(author = CURRENT_USER)".to_string()),
+                with_check_expr: None,
+            }],
+            privileges: Privileges {
+                owners: vec![
+                    owner("function", "app.private()"),
+                    owner("function", "app.open()"),
+                ],
+                grants: vec![
+                    grant("table", "app.notes", "app_user", "SELECT"),
+                    grant("function", "app.open()", "PUBLIC", "EXECUTE"),
+                    grant("type", "app.mood", "PUBLIC", "USAGE"),
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (policies, stats) = policies_to_sql(&schema);
+        assert_eq!(stats.policy_count, 1);
+        assert_eq!(
+            policies,
+            "-- Row-level security\n\
+             ALTER TABLE \"app\".\"notes\" ENABLE ROW LEVEL SECURITY;\n\n\
+             -- Policies\n\
+             CREATE POLICY \"own_notes\" ON \"app\".\"notes\" AS PERMISSIVE FOR ALL \
+             TO \"app_user\" USING ((author = CURRENT_USER));\n"
+        );
+
+        // PUBLIC's default EXECUTE and USAGE need no statement
+        let (privileges, stats) = privileges_to_sql(&schema);
+        assert_eq!(stats.grant_count, 2);
+        assert_eq!(
+            privileges,
+            "-- Privileges\n\
+             REVOKE EXECUTE ON FUNCTION app.private() FROM PUBLIC;\n\
+             GRANT SELECT ON TABLE app.notes TO \"app_user\";\n"
+        );
+        assert_eq!(
+            privileges_to_drop_sql(&schema),
+            "-- Privileges\n\
+             REVOKE SELECT ON TABLE app.notes FROM \"app_user\";\n\
+             GRANT EXECUTE ON FUNCTION app.private() TO PUBLIC;"
+        );
+
+        let files = generate_files(&schema, SplitMode::Table, Utc::now(), "postgres:///app");
+        let names: Vec<&str> = files
+            .iter()
+            .map(|f| f.filename.split_once('_').unwrap().1)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["app_notes.sql", "policies.sql", "privileges.sql"]
+        );
+    }
+
+    #[test]
+    fn test_format_declarative_file() {
+        let schema = DatabaseSchema {
+            triggers: vec![Trigger {
+                schema: "app".to_string(),
+                table_name: "notes".to_string(),
+                name: "touch".to_string(),
+                definition: "CREATE TRIGGER touch BEFORE UPDATE ON app.notes \
+                             FOR EACH ROW EXECUTE FUNCTION app.touch()"
+                    .to_string(),
+            }],
+            functions: vec![Function {
+                schema: "app".to_string(),
+                identity: "app.touch()".to_string(),
+                definition: "CREATE FUNCTION app.touch() RETURNS trigger LANGUAGE plpgsql \
+                             AS $$BEGIN RETURN NEW; END$$;"
+                    .to_string(),
+                kind: FunctionKind::Function,
+            }],
+            ..Default::default()
+        };
+
+        let (content, stats) = format_declarative_file(&schema);
+        assert_eq!(stats.function_count, 1);
+        // Nothing changes between runs, and functions precede their triggers
+        assert!(!content.contains("Generated at"));
+        assert_eq!(content, format_declarative_file(&schema).0);
+        assert!(content.find("CREATE FUNCTION") < content.find("CREATE TRIGGER"));
+        assert!(!content.contains("-- up"));
+        assert!(content.ends_with(";\n"));
+    }
+
+    #[test]
+    fn test_type_sql() {
+        let schema = DatabaseSchema {
+            collations: vec![Collation {
+                schema: "app".to_string(),
+                name: "ci".to_string(),
+                provider: "icu".to_string(),
+                locale: Some("und-u-ks-level2".to_string()),
+                lc_collate: None,
+                lc_ctype: None,
+                deterministic: false,
+            }],
+            enums: vec![EnumType {
+                schema: "app".to_string(),
+                name: "mood".to_string(),
+                values: vec!["ok".to_string()],
+            }],
+            domains: vec![Domain {
+                schema: "app".to_string(),
+                name: "email".to_string(),
+                data_type: "text".to_string(),
+                collation: Some("app.ci".to_string()),
+                default: None,
+                not_null: true,
+                checks: vec![(
+                    "email_at".to_string(),
+                    "CHECK ((VALUE ~~ '%@%'::text))".to_string(),
+                )],
+            }],
+            composite_types: vec![CompositeType {
+                schema: "app".to_string(),
+                name: "contact".to_string(),
+                attributes: vec![
+                    ("address".to_string(), "app.email".to_string()),
+                    ("mood".to_string(), "app.mood".to_string()),
+                ],
+            }],
+            privileges: Privileges {
+                owners: vec![ObjectOwner {
+                    kind: "domain".to_string(),
+                    name: "app.email".to_string(),
+                    owner: "app_owner".to_string(),
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (sql, stats) = schema_to_sql(&schema);
+        assert_eq!(stats.type_count, 3);
+        assert_eq!(
+            sql,
+            "-- Collations\n\
+             CREATE COLLATION \"app\".\"ci\" (provider = icu, locale = 'und-u-ks-level2', \
+             deterministic = false);\n\n\
+             -- Types (enums)\n\
+             CREATE TYPE \"app\".\"mood\" AS ENUM ('ok');\n\n\
+             -- Domains\n\
+             CREATE DOMAIN \"app\".\"email\" AS text COLLATE app.ci NOT NULL\n    \
+             CONSTRAINT \"email_at\" CHECK ((VALUE ~~ '%@%'::text));\n\n\
+             -- Types (composite)\n\
+             CREATE TYPE \"app\".\"contact\" AS (\n    \
+             \"address\" app.email,\n    \
+             \"mood\" app.mood\n);\n"
+        );
+        assert_eq!(
+            schema_to_drop_sql(&schema),
+            "-- Types\n\
+             DROP TYPE IF EXISTS \"app\".\"contact\";\n\
+             DROP DOMAIN IF EXISTS \"app\".\"email\";\n\
+             DROP TYPE IF EXISTS \"app\".\"mood\";\n\
+             DROP COLLATION IF EXISTS \"app\".\"ci\";\n"
+        );
+
+        // No grant to PUBLIC, so its default USAGE was revoked
+        let (privileges, _) = privileges_to_sql(&schema);
+        assert_eq!(
+            privileges,
+            "-- Privileges\nREVOKE USAGE ON DOMAIN app.email FROM PUBLIC;\n"
+        );
+    }
+
+    #[test]
+    fn test_foreign_data_sql() {
+        let column = |name: &str, data_type: &str| Column {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            default: None,
+            generated: false,
+            identity: None,
+            is_serial: false,
+            is_primary_key: false,
+            comment: None,
+        };
+        let schema = DatabaseSchema {
+            schemas: vec![SchemaInfo {
+                name: "app".to_string(),
+            }],
+            foreign_servers: vec![ForeignServer {
+                name: "billing".to_string(),
+                wrapper: "postgres_fdw".to_string(),
+                server_type: None,
+                version: None,
+                options: vec!["host=db.internal".to_string(), "dbname=billing".to_string()],
+            }],
+            user_mappings: vec![UserMapping {
+                server: "billing".to_string(),
+                user: "PUBLIC".to_string(),
+                options: vec!["user".to_string(), "password".to_string()],
+            }],
+            foreign_tables: vec![ForeignTable {
+                schema: "app".to_string(),
+                name: "invoices".to_string(),
+                server: "billing".to_string(),
+                columns: vec![column("id", "bigint"), column("total", "numeric")],
+                column_options: vec![("total".to_string(), vec!["column_name=amount".to_string()])],
+                options: vec!["table_name=invoices".to_string()],
+                parent: None,
+                partition_bound: None,
+            }],
+            ..Default::default()
+        };
+
+        let (sql, stats) = schema_to_sql(&schema);
+        assert_eq!(stats.server_count, 2);
+        assert_eq!(stats.foreign_table_count, 1);
+        assert_eq!(
+            sql,
+            "-- Schemas\n\
+             CREATE SCHEMA IF NOT EXISTS \"app\";\n\n\
+             -- Foreign servers\n\
+             CREATE SERVER \"billing\" FOREIGN DATA WRAPPER \"postgres_fdw\" \
+             OPTIONS (\"host\" 'db.internal', \"dbname\" 'billing');\n\n\
+             -- User mappings (option values are <redacted>; fill them in before running)\n\
+             CREATE USER MAPPING FOR PUBLIC SERVER \"billing\" \
+             OPTIONS (\"user\" '<redacted>', \"password\" '<redacted>');\n\n\
+             -- Foreign tables\n\
+             CREATE FOREIGN TABLE \"app\".\"invoices\" (\n    \
+             \"id\" bigint,\n    \
+             \"total\" numeric OPTIONS (\"column_name\" 'amount')\n\
+             ) SERVER \"billing\" OPTIONS (\"table_name\" 'invoices');\n"
+        );
+        assert_eq!(
+            schema_to_drop_sql(&schema),
+            "-- Foreign tables\n\
+             DROP FOREIGN TABLE IF EXISTS \"app\".\"invoices\";\n\n\
+             -- Foreign servers\n\
+             DROP USER MAPPING IF EXISTS FOR PUBLIC SERVER \"billing\";\n\
+             DROP SERVER IF EXISTS \"billing\";\n\n\
+             -- Schemas\n\
+             DROP SCHEMA IF EXISTS \"app\";\n"
+        );
+
+        let files = generate_files(&schema, SplitMode::Schema, Utc::now(), "postgres:///app");
+        let names: Vec<&str> = files
+            .iter()
+            .map(|f| f.filename.split_once('_').unwrap().1)
+            .collect();
+        assert_eq!(names, vec!["foreign_servers.sql", "schema_app.sql"]);
+    }
 }
@@ -6,6 +6,7 @@ mod anonymize;
 mod commands;
 mod config;
 mod connection;
+mod cron;
 mod describe;
 mod diagnostic;
 mod diff;
@@ -15,15 +16,19 @@ mod help;
 mod introspect;
 mod migrations;
 mod model;
+mod nagios;
 mod output;
 mod reason_codes;
 mod redact;
 mod seed;
+mod seed_generate;
 mod snapshot;
+mod snapshot_encryption;
+mod snapshot_storage;
 mod sql;
 mod suggest;
 mod tips;
-use config::Config;
+use config::{AlertsConfig, Config};
 use diagnostic::{setup_ctrlc_handler, DiagnosticSession, TimeoutConfig};
 use output::{HelpResponse, JsonError, LlmHelpResponse, Output, VersionResponse};
 
@@ -71,20 +76,32 @@ fn json_supported(command: &Commands) -> bool {
         Commands::Dba { .. } => true,
         // Inspect commands all support JSON
         Commands::Inspect { .. } => true,
+        Commands::Comment { .. } => true,
         // Operations
         Commands::Context => true,
         Commands::Capabilities => true,
         Commands::Sql { .. } => true,
         Commands::Snapshot { command } => matches!(
             command,
-            SnapshotCommands::List | SnapshotCommands::Info { .. }
+            SnapshotCommands::List
+                | SnapshotCommands::Info { .. }
+                | SnapshotCommands::Diff { .. }
+                | SnapshotCommands::Preflight
         ),
         // Schema management
-        Commands::Migrate { command } => matches!(command, MigrateCommands::Status),
+        Commands::Migrate { command } => matches!(
+            command,
+            MigrateCommands::Status | MigrateCommands::Conflicts { .. }
+        ),
         Commands::Model { command } => matches!(
             command,
-            ModelCommands::Status { .. } | ModelCommands::Show { .. }
+            ModelCommands::Status { .. }
+                | ModelCommands::Show { .. }
+                | ModelCommands::Test { .. }
+                | ModelCommands::Plan { .. }
         ),
+        Commands::Seed { command } => matches!(command, SeedCommands::Diff { .. }),
+        Commands::Plan { .. } => true,
         Commands::Status => true,
         _ => false,
     }
@@ -147,6 +164,10 @@ struct Cli {
     #[arg(long, global = true)]
     snapshot_config: Option<PathBuf>,
 
+    /// Path to dba alert thresholds file (default: ./pgcrate.alerts.toml)
+    #[arg(long, global = true)]
+    alerts_config: Option<PathBuf>,
+
     // Timeout options for diagnostic commands
     /// Connection timeout (e.g., "5s", "500ms"). Default: 5s
     #[arg(long = "connect-timeout", global = true, value_name = "DURATION")]
@@ -164,6 +185,18 @@ struct Cli {
     #[arg(long = "no-redact", global = true)]
     no_redact: bool,
 
+    /// Re-run a dba diagnostic every SECS seconds (default: 2); NDJSON with --json
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "2",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    watch: Option<u64>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -182,7 +215,7 @@ enum Commands {
         #[command(subcommand)]
         command: ModelCommands,
     },
-    /// Load seed data from CSV or SQL files
+    /// Load seed data from CSV, JSONL, Parquet or SQL files
     Seed {
         #[command(subcommand)]
         command: SeedCommands,
@@ -234,6 +267,38 @@ enum Commands {
         /// Exclude these schemas (can be specified multiple times)
         #[arg(long = "exclude-schema", value_name = "SCHEMA")]
         exclude_schemas: Vec<String>,
+        /// Leave out GRANT and REVOKE statements
+        #[arg(long)]
+        no_privileges: bool,
+        /// Write the whole schema to one canonical file instead of migrations
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["split_by", "output"])]
+        declarative: Option<PathBuf>,
+        /// Write one migration with only what the database has beyond the existing
+        /// migrations (replays them in a scratch database; needs --read-write)
+        #[arg(long, conflicts_with_all = ["split_by", "declarative"])]
+        delta: bool,
+    },
+    /// Propose the migration that brings the database in line with a declarative schema file
+    /// (loads it into a scratch database; needs --read-write)
+    Plan {
+        /// Declarative schema file (from `generate --declarative`)
+        #[arg(default_value = "schema.sql")]
+        file: PathBuf,
+        /// Write the migration to the migrations directory under this name
+        #[arg(long, value_name = "NAME")]
+        name: Option<String>,
+        /// Include only these schemas (can be specified multiple times)
+        #[arg(long = "schema", value_name = "SCHEMA")]
+        schemas: Vec<String>,
+        /// Exclude these schemas (can be specified multiple times)
+        #[arg(long = "exclude-schema", value_name = "SCHEMA")]
+        exclude_schemas: Vec<String>,
+        /// Leave out destructive statements (drops, type changes)
+        #[arg(long)]
+        safe_only: bool,
+        /// Don't compare GRANT and REVOKE statements
+        #[arg(long)]
+        no_privileges: bool,
     },
     /// Show migration status (alias for `migrate status`)
     Status,
@@ -243,6 +308,15 @@ enum Commands {
     Dba {
         #[command(subcommand)]
         command: Option<DbaCommands>,
+        /// Output format: json (same as --json), nagios for a plugin status line with exit 0/1/2/3,
+        /// or dot, mermaid, json-graph for the `locks --blocking` wait-for graph
+        #[arg(
+            long,
+            global = true,
+            value_name = "FORMAT",
+            value_parser = ["json", "nagios", "dot", "mermaid", "json-graph"]
+        )]
+        format: Option<String>,
     },
 
     // ===== Inspection =====
@@ -251,6 +325,11 @@ enum Commands {
         #[command(subcommand)]
         command: InspectCommands,
     },
+    /// Manage table and column comments (COMMENT ON)
+    Comment {
+        #[command(subcommand)]
+        command: CommentCommands,
+    },
 
     // ===== Operations =====
     /// Show connection context, server info, extensions, and privileges
@@ -291,6 +370,33 @@ enum Commands {
         /// Confirm you want to bootstrap (recreates local database if it exists)
         #[arg(long)]
         yes: bool,
+        /// Continue an interrupted bootstrap, skipping tables already transferred
+        #[arg(long)]
+        resume: bool,
+        /// Create the database and apply migrations without copying any data
+        #[arg(long, conflicts_with_all = ["tables", "resume"])]
+        schema_only: bool,
+        /// Copy only these tables, e.g. app.countries,app.plans
+        #[arg(long, value_name = "TABLES", value_delimiter = ',')]
+        tables: Vec<String>,
+    },
+    /// Extract a referentially consistent subset of a source database as a SQL dump
+    Subset {
+        /// Source database URL to extract rows from
+        #[arg(long, required = true)]
+        from: String,
+        /// Table to start from, e.g. app.users (repeatable)
+        #[arg(long, required = true, value_name = "TABLE")]
+        anchor: Vec<String>,
+        /// Row filter for the --anchor at the same position (default: all rows)
+        #[arg(long = "where", value_name = "SQL")]
+        filters: Vec<String>,
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Show row counts per table without writing a dump
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Database management commands
     Db {
@@ -318,6 +424,12 @@ enum MigrateCommands {
         /// Show what would run without running
         #[arg(long)]
         dry_run: bool,
+        /// Apply all pending migrations in one transaction (all or nothing)
+        #[arg(long)]
+        single_transaction: bool,
+        /// Save a pre-migrate-<version> snapshot first (default: [migrate] snapshot_before)
+        #[arg(long)]
+        snapshot: bool,
     },
     /// Roll back applied migrations
     Down {
@@ -360,19 +472,41 @@ enum MigrateCommands {
         #[arg(long)]
         dry_run: bool,
     },
+    /// Detect migrations on this branch that conflict with a base branch (uses git)
+    Conflicts {
+        /// Base branch or ref to compare against
+        #[arg(long, default_value = "main")]
+        base: String,
+    },
 }
 
 /// Shared selection arguments for model commands
 #[derive(Args, Clone)]
 struct SelectionArgs {
     /// Select models by name or selector (can repeat). Examples:
-    /// analytics.users, tag:daily, deps:analytics.orders, downstream:staging.raw, tree:analytics.orders
+    /// analytics.users, tag:daily, deps:analytics.orders, downstream:staging.raw, tree:analytics.orders,
+    /// state:modified (model run --state)
     #[arg(long, short = 's')]
     select: Vec<String>,
 
     /// Exclude models by name or selector (can repeat)
     #[arg(long, short = 'e')]
     exclude: Vec<String>,
+
+    /// Use a named selector from models/selectors.toml (can repeat; same as -s selector:NAME)
+    #[arg(long, value_name = "NAME")]
+    selector: Vec<String>,
+}
+
+impl SelectionArgs {
+    /// `--select` entries plus `--selector` names as `selector:NAME`
+    fn selects(&self) -> Vec<String> {
+        self.select
+            .iter()
+            .cloned()
+            .chain(self.selector.iter().map(|name| format!("selector:{name}")))
+            .collect()
+    }
 }
 
 #[derive(Subcommand)]
@@ -390,6 +524,30 @@ enum ModelCommands {
         /// Force full refresh for incremental models (drop and recreate)
         #[arg(long)]
         full_refresh: bool,
+        /// Fail models whose SELECT doesn't match their columns: contract; create tables with the declared types
+        #[arg(long)]
+        enforce_contracts: bool,
+        /// Rebuild views even if their SQL and upstream models are unchanged since the last run
+        #[arg(long)]
+        force: bool,
+        /// Set a model variable for ${var:KEY} placeholders (can repeat; overrides [model.vars])
+        #[arg(long = "var", value_name = "KEY=VALUE", value_parser = model::parse_var_assignment)]
+        vars: Vec<(String, String)>,
+        /// Number of models to run concurrently within a DAG layer (default: [model] threads, or 1)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        threads: Option<u64>,
+        /// Keep running independent models after a failure (downstream models are skipped)
+        #[arg(long)]
+        keep_going: bool,
+        /// Manifest from a previous run (file or directory) for state:modified / state:new
+        #[arg(long, value_name = "PATH")]
+        state: Option<PathBuf>,
+        /// Don't rebuild unselected upstream models recorded in the --state manifest
+        #[arg(long, requires = "state")]
+        defer: bool,
+        /// Set up postgres_fdw servers and import tables for [model.foreign_sources] first
+        #[arg(long)]
+        init_sources: bool,
         /// Initialize models directory if missing
         #[arg(long)]
         init: bool,
@@ -397,10 +555,54 @@ enum ModelCommands {
         #[arg(short = 'y', long)]
         yes: bool,
     },
+    /// Re-run models that failed or were skipped in the last `model run` (from target/run_results.json)
+    Retry,
+    /// List models that need to run (never built, changed, or with newer upstream/source data)
+    Plan {
+        #[command(flatten)]
+        selection: SelectionArgs,
+    },
+    /// Clone built models from one schema into another (views over the originals, or copies)
+    Clone {
+        /// Schema holding the built models, e.g. analytics
+        #[arg(long, value_name = "SCHEMA")]
+        from_schema: String,
+        /// Schema to create the clones in, e.g. analytics_dev
+        #[arg(long, value_name = "SCHEMA")]
+        to_schema: String,
+        #[command(flatten)]
+        selection: SelectionArgs,
+        /// Copy tables and materialized views with CREATE TABLE ... AS instead of creating views
+        #[arg(long)]
+        copy: bool,
+        /// Show the SQL without running it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Record changes to snapshot models (SCD Type-2 history with valid_from/valid_to/is_current)
+    Snapshot {
+        /// Snapshot models to capture (same as --select). Examples: snapshots.customers
+        #[arg(value_name = "MODEL")]
+        models: Vec<String>,
+        #[command(flatten)]
+        selection: SelectionArgs,
+        /// Show the SQL without running it
+        #[arg(long)]
+        dry_run: bool,
+        /// Set a model variable for ${var:KEY} placeholders (can repeat; overrides [model.vars])
+        #[arg(long = "var", value_name = "KEY=VALUE", value_parser = model::parse_var_assignment)]
+        vars: Vec<(String, String)>,
+        /// Initialize models directory if missing
+        #[arg(long)]
+        init: bool,
+    },
     /// Compile models to target/compiled/
     Compile {
         #[command(flatten)]
         selection: SelectionArgs,
+        /// Set a model variable for ${var:KEY} placeholders (can repeat; overrides [model.vars])
+        #[arg(long = "var", value_name = "KEY=VALUE", value_parser = model::parse_var_assignment)]
+        vars: Vec<(String, String)>,
         /// Initialize models directory if missing
         #[arg(long)]
         init: bool,
@@ -409,6 +611,9 @@ enum ModelCommands {
     Test {
         #[command(flatten)]
         selection: SelectionArgs,
+        /// Save failing rows to pgcrate_test_failures.<schema>_<model>__<test> tables
+        #[arg(long)]
+        store_failures: bool,
         /// Initialize models directory if missing
         #[arg(long)]
         init: bool,
@@ -417,6 +622,9 @@ enum ModelCommands {
     Docs {
         #[command(flatten)]
         selection: SelectionArgs,
+        /// Output format: markdown (default), or html for a static site with columns from the database
+        #[arg(long, default_value = "markdown")]
+        format: String,
     },
     /// Show model dependency graph
     Graph {
@@ -425,13 +633,16 @@ enum ModelCommands {
         /// Output format: ascii (default), dot, json, mermaid
         #[arg(long, default_value = "ascii")]
         format: String,
+        /// Show column-level lineage (upstream.column -> model.column) parsed from SELECT lists
+        #[arg(long)]
+        columns: bool,
     },
     /// Lint models for dependency and qualification issues
     Lint {
         #[command(subcommand)]
         command: LintCommands,
     },
-    /// Run all lints (deps + qualify)
+    /// Run all lints (deps + qualify) and check columns: contracts against the database
     Check {
         #[command(flatten)]
         selection: SelectionArgs,
@@ -449,7 +660,7 @@ enum ModelCommands {
     New {
         /// Model id (schema.name)
         id: String,
-        /// Materialization type: view, table, incremental
+        /// Materialization type: view, table, incremental, ephemeral, materialized_view, snapshot
         #[arg(long, default_value = "view")]
         materialized: String,
         /// Skip prompts (e.g., overwrite confirmation)
@@ -459,6 +670,18 @@ enum ModelCommands {
         #[arg(long)]
         force: bool,
     },
+    /// Declare a schema's tables as sources (models/sources.toml), with optional staging stubs
+    ImportSources {
+        /// Schema to import tables from (can be specified multiple times)
+        #[arg(long = "schema", value_name = "SCHEMA", required = true)]
+        schemas: Vec<String>,
+        /// Also write a staging view per table (models/staging/<schema>_<table>.sql)
+        #[arg(long)]
+        staging: bool,
+        /// Overwrite existing staging models
+        #[arg(long, requires = "staging")]
+        force: bool,
+    },
     /// Show compiled SQL for a model (does not execute)
     Show {
         /// Model id (schema.name)
@@ -542,6 +765,12 @@ enum SnapshotCommands {
         /// Omit privilege (GRANT/REVOKE) statements from dump
         #[arg(long)]
         no_privileges: bool,
+        /// Encrypt for this age recipient or GPG key (repeatable; default: [snapshot.encryption])
+        #[arg(long = "encrypt-key", value_name = "KEY")]
+        encrypt_keys: Vec<String>,
+        /// Write a plain dump without pg_dump (automatic for plain when pg_dump is missing)
+        #[arg(long)]
+        native: bool,
         /// Show what would be saved without creating a snapshot
         #[arg(long)]
         dry_run: bool,
@@ -562,14 +791,73 @@ enum SnapshotCommands {
         /// Skip role pre-flight check and restore without ownership
         #[arg(long)]
         no_owner: bool,
+        /// Restore into a new database instead (default name: pgcrate_tmp_<name>)
+        #[arg(long, value_name = "DB")]
+        as_new_db: Option<Option<String>>,
+        /// With --as-new-db: let `snapshot prune` drop the database after this long (e.g. 8h, 7d)
+        #[arg(long, value_name = "DURATION", requires = "as_new_db")]
+        ttl: Option<String>,
+    },
+    /// Drop databases from `restore --as-new-db` whose --ttl has passed
+    Prune {
+        /// Show what would be dropped without dropping anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Save snapshots on a cron schedule, keeping the newest N (logs NDJSON)
+    Schedule {
+        /// Cron expression in local time: "minute hour day month weekday"
+        #[arg(long, value_name = "EXPR", required_unless_present = "now")]
+        cron: Option<String>,
+        /// Snapshot name prefix; snapshots are named <prefix>-<YYYYmmdd-HHMMSS>
+        #[arg(long, default_value = "scheduled")]
+        prefix: String,
+        /// Keep only the newest N snapshots with this prefix
+        #[arg(long, value_name = "N")]
+        keep: Option<usize>,
+        /// Snapshot profile to use (from pgcrate.snapshot.toml)
+        #[arg(long)]
+        profile: Option<String>,
+        /// Dump format: 'custom' (default, binary) or 'plain' (readable SQL)
+        #[arg(long)]
+        format: Option<String>,
+        /// Save one snapshot now and exit (for cron or systemd to run)
+        #[arg(long, conflicts_with = "systemd")]
+        now: bool,
+        /// Print a systemd service and timer instead of running
+        #[arg(long)]
+        systemd: bool,
     },
     /// List all snapshots
     List,
+    /// Check that pg_dump and pg_restore can handle this server's version
+    Preflight,
     /// Show detailed information about a snapshot
     Info {
         /// Snapshot name
         name: String,
     },
+    /// Compare a snapshot's schema with the current database or another snapshot
+    Diff {
+        /// Snapshot name
+        name: String,
+        /// Second snapshot to compare against (default: the current database)
+        #[arg(conflicts_with = "against_db")]
+        other: Option<String>,
+        /// Compare against the current database (the default)
+        #[arg(long)]
+        against_db: bool,
+        /// Only compare these schemas (can be specified multiple times)
+        #[arg(long = "schema", value_name = "SCHEMA")]
+        schemas: Vec<String>,
+        /// Exclude these schemas (can be specified multiple times)
+        #[arg(
+            long = "exclude-schema",
+            value_name = "SCHEMA",
+            conflicts_with = "schemas"
+        )]
+        exclude_schemas: Vec<String>,
+    },
     /// Delete a snapshot
     Delete {
         /// Snapshot name to delete
@@ -592,10 +880,40 @@ enum AnonymizeCommands {
         /// Output file path (default: stdout)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Include CREATE statements so the dump loads into an empty database (no pg_dump needed)
+        #[arg(long)]
+        schema: bool,
         /// Preview what would be anonymized without writing output
         #[arg(long)]
         dry_run: bool,
     },
+    /// Anonymize this (non-production) database in place with UPDATEs
+    Apply {
+        /// Anonymization seed (overrides env and file)
+        #[arg(long)]
+        seed: Option<String>,
+        /// Rows updated per transaction
+        #[arg(long, default_value_t = 10000, value_parser = clap::value_parser!(i64).range(1..))]
+        batch_size: i64,
+        /// Show what would be changed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Confirm overwriting data in place
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Flag likely PII columns and write suggested rules for review
+    Scan {
+        /// Rules file to write, or - for stdout (default: pgcrate.anonymize.toml)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Values sampled per text column
+        #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(i64).range(1..))]
+        sample: i64,
+        /// Overwrite an existing rules file
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -604,9 +922,15 @@ enum SeedCommands {
     Run {
         /// Specific seeds to run (`schema.table` or just `table` if unique)
         seeds: Vec<String>,
+        /// Also load seeds scoped to this environment in seeds.toml (`env = [...]`)
+        #[arg(long, value_name = "NAME")]
+        environment: Option<String>,
         /// Show what would be loaded without loading
         #[arg(long)]
         dry_run: bool,
+        /// Rows sent per COPY message when loading data seeds
+        #[arg(long, value_name = "N", default_value_t = 10_000, value_parser = clap::value_parser!(u64).range(1..))]
+        chunk_size: u64,
     },
     /// List available seed files
     List,
@@ -615,10 +939,38 @@ enum SeedCommands {
         /// Specific seeds to validate (`schema.table` or just `table` if unique)
         seeds: Vec<String>,
     },
-    /// Compare seed files to database state
+    /// Compare seed files to database state, row by row
     Diff {
         /// Specific seeds to compare (`schema.table` or just `table` if unique)
         seeds: Vec<String>,
+        /// Insert, update and delete rows so each table matches its seed file
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Write a table's current rows to a CSV seed
+    Export {
+        /// Table to export (`schema.table`, or `table` in public)
+        table: String,
+        /// Only export rows matching this SQL condition
+        #[arg(long = "where", value_name = "SQL")]
+        where_clause: Option<String>,
+    },
+    /// Generate deterministic fake rows for a table
+    Generate {
+        /// Table to generate rows for (`schema.table`, or `table` in public)
+        table: String,
+        /// Number of rows
+        #[arg(long, value_name = "N", default_value_t = 100)]
+        rows: usize,
+        /// Seed text; the same seed always generates the same rows
+        #[arg(long, value_name = "TEXT", default_value = "pgcrate")]
+        seed: String,
+        /// Insert rows into the table instead of writing a CSV seed
+        #[arg(long)]
+        insert: bool,
+        /// Overwrite an existing CSV seed
+        #[arg(long, conflicts_with = "insert")]
+        force: bool,
     },
 }
 
@@ -641,11 +993,38 @@ enum FixCommands {
         #[arg(long)]
         verify: bool,
     },
-    /// Drop unused or duplicate index
+    /// Drop unused or duplicate index, or create a missing one
     Index {
         /// Index to drop (schema.index)
-        #[arg(long, value_name = "INDEX")]
-        drop: String,
+        #[arg(
+            long,
+            value_name = "INDEX",
+            required_unless_present_any = ["create", "dedupe"],
+            conflicts_with_all = ["create", "dedupe"]
+        )]
+        drop: Option<String>,
+        /// Table to create an index on (schema.table)
+        #[arg(
+            long,
+            value_name = "TABLE",
+            requires = "columns",
+            conflicts_with = "dedupe"
+        )]
+        create: Option<String>,
+        /// Columns for --create, comma-separated
+        #[arg(
+            long,
+            value_name = "COLUMNS",
+            value_delimiter = ',',
+            requires = "create"
+        )]
+        columns: Vec<String>,
+        /// Name for the new index (default: idx_<table>_<columns>)
+        #[arg(long, value_name = "NAME", requires = "create")]
+        name: Option<String>,
+        /// Drop duplicate and prefix-covered indexes, keeping constraint-backed ones
+        #[arg(long)]
+        dedupe: bool,
         /// Show what would be done without executing
         #[arg(long)]
         dry_run: bool,
@@ -679,6 +1058,107 @@ enum FixCommands {
         #[arg(long)]
         verify: bool,
     },
+    /// Set a table's autovacuum scale factor (defaults to `dba autovacuum`'s proposal)
+    Autovacuum {
+        /// Table to tune (schema.table)
+        table: String,
+        /// autovacuum_vacuum_scale_factor to set
+        #[arg(long, value_name = "FACTOR")]
+        scale_factor: Option<f64>,
+        /// Also set autovacuum_vacuum_threshold
+        #[arg(long, value_name = "TUPLES")]
+        threshold: Option<i64>,
+        /// Show what would be done without executing
+        #[arg(long)]
+        dry_run: bool,
+        /// Confirm execution (required for fixes)
+        #[arg(long)]
+        yes: bool,
+        /// Run verification after fix
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Create upcoming time-range partitions and retire old ones
+    Partition {
+        /// Partitioned table (schema.table)
+        #[arg(long, value_name = "TABLE")]
+        table: String,
+        /// Future partitions to have after the fix (default: 2)
+        #[arg(long, value_name = "N", default_value_t = commands::partitions::DEFAULT_AHEAD)]
+        create_ahead: i64,
+        /// Detach partitions that ended more than N intervals ago
+        #[arg(long, value_name = "N")]
+        retain: Option<i64>,
+        /// Drop detached partitions instead of keeping them as tables
+        #[arg(long, requires = "retain")]
+        drop: bool,
+        /// Show what would be done without executing
+        #[arg(long)]
+        dry_run: bool,
+        /// Confirm execution (required for fixes)
+        #[arg(long)]
+        yes: bool,
+        /// Run verification after fix
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Freeze the tables closest to XID wraparound, oldest first
+    Xid {
+        /// Only tables with at least this XID age (default: autovacuum_freeze_max_age)
+        #[arg(long, value_name = "AGE")]
+        min_age: Option<i64>,
+        /// Maximum number of tables to freeze (default: 10)
+        #[arg(long, default_value_t = commands::fix::xid::DEFAULT_LIMIT)]
+        limit: usize,
+        /// Maintenance window (e.g. 30m, 2h); work that does not fit is skipped
+        #[arg(long, value_name = "DURATION")]
+        window: Option<String>,
+        /// Show what would be done without executing
+        #[arg(long)]
+        dry_run: bool,
+        /// Confirm execution (required for fixes)
+        #[arg(long)]
+        yes: bool,
+        /// Run verification after fix
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Refresh planner statistics with ANALYZE
+    Analyze {
+        /// Table to analyze (schema.table)
+        #[arg(required_unless_present = "all_stale", conflicts_with = "all_stale")]
+        table: Option<String>,
+        /// Analyze every table `dba stats-age` reports as stale
+        #[arg(long)]
+        all_stale: bool,
+        /// Tables per ANALYZE statement with --all-stale (default: 10)
+        #[arg(long, default_value_t = commands::fix::analyze::DEFAULT_BATCH_SIZE)]
+        batch_size: usize,
+        /// Show what would be done without executing
+        #[arg(long)]
+        dry_run: bool,
+        /// Confirm execution (required for fixes)
+        #[arg(long)]
+        yes: bool,
+        /// Run verification after fix
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Roll back an orphaned prepared transaction (connect to its database first)
+    Prepared {
+        /// Global identifier (gid) of the prepared transaction to roll back
+        #[arg(long, value_name = "GID")]
+        rollback: String,
+        /// Show what would be done without executing
+        #[arg(long)]
+        dry_run: bool,
+        /// Confirm execution (required for fixes)
+        #[arg(long)]
+        yes: bool,
+        /// Run verification after fix
+        #[arg(long)]
+        verify: bool,
+    },
     /// Rebuild bloated index via REINDEX
     Bloat {
         /// Index to reindex (schema.index)
@@ -698,6 +1178,23 @@ enum FixCommands {
     },
 }
 
+/// Recorded-metrics flags shared by triage, bloat, sequences, and xid
+#[derive(Args, Clone, Default)]
+struct TrendArgs {
+    /// Also record this run's metrics as a named baseline (writes to pgcrate.metrics)
+    #[arg(long, value_name = "NAME")]
+    baseline: Option<String>,
+    /// Show changes against recorded metrics: a window (e.g. 7d) or a baseline name
+    #[arg(long, value_name = "SINCE|NAME")]
+    compare: Option<String>,
+}
+
+impl TrendArgs {
+    fn is_empty(&self) -> bool {
+        self.baseline.is_none() && self.compare.is_none()
+    }
+}
+
 /// DBA diagnostic and remediation commands
 #[derive(Subcommand, Clone)]
 enum DbaCommands {
@@ -709,6 +1206,18 @@ enum DbaCommands {
         /// Show SQL queries used by triage (for debugging/learning)
         #[arg(long)]
         show_sql: bool,
+        /// Check XID age, sequences, and bloat in every database in the cluster
+        #[arg(long, conflicts_with_all = ["include_fixes", "show_sql"])]
+        all_databases: bool,
+        /// Triage every connection in a [fleets] entry or matching a glob (e.g. 'prod-*')
+        #[arg(
+            long,
+            value_name = "PATTERN",
+            conflicts_with_all = ["include_fixes", "show_sql", "all_databases"]
+        )]
+        fleet: Option<String>,
+        #[command(flatten)]
+        trend: TrendArgs,
     },
     /// Inspect blocking locks and long transactions
     Locks {
@@ -727,6 +1236,15 @@ enum DbaCommands {
         /// Terminate connection for PID (pg_terminate_backend)
         #[arg(long, value_name = "PID")]
         kill: Option<i32>,
+        /// Terminate sessions idle in transaction for at least DURATION (e.g. 10m)
+        #[arg(long, value_name = "DURATION", conflicts_with_all = ["cancel", "kill"])]
+        kill_idle: Option<String>,
+        /// With --kill-idle: only sessions whose application_name matches (glob: *, ?)
+        #[arg(long, value_name = "PATTERN", requires = "kill_idle")]
+        application: Option<String>,
+        /// With --kill-idle: only sessions whose user matches (glob: *, ?)
+        #[arg(long, value_name = "PATTERN", requires = "kill_idle")]
+        user: Option<String>,
         /// Actually execute cancel/kill (default is dry-run)
         #[arg(long)]
         execute: bool,
@@ -742,12 +1260,42 @@ enum DbaCommands {
         /// Show all sequences, not just problematic ones
         #[arg(long)]
         all: bool,
+        #[command(flatten)]
+        trend: TrendArgs,
+    },
+    /// Unlogged tables, tables without primary keys, and replica identity problems
+    Persistence,
+    /// Prepared (two-phase) transactions left open past a threshold
+    Prepared {
+        /// Report prepared transactions at least this old (default: 5m)
+        #[arg(long, value_name = "DURATION")]
+        older_than: Option<String>,
+        /// Include ROLLBACK PREPARED fix actions for stale transactions
+        #[arg(long)]
+        include_actions: bool,
+    },
+    /// Check partitioned tables for missing future partitions, default rows and skew
+    Partitions {
+        /// Only this partitioned table (schema.table)
+        #[arg(long, value_name = "TABLE")]
+        table: Option<String>,
+        /// Future partitions expected ahead of now for time-range tables (default: 2)
+        #[arg(long, value_name = "N", default_value_t = commands::partitions::DEFAULT_AHEAD)]
+        ahead: i64,
+        /// Largest/median partition size ratio reported as skew (default: 10)
+        #[arg(long, value_name = "RATIO", default_value_t = commands::partitions::DEFAULT_SKEW_RATIO)]
+        skew: f64,
+        /// Include CREATE TABLE ... PARTITION OF fix actions for missing partitions
+        #[arg(long)]
+        include_actions: bool,
     },
     /// Monitor transaction ID (XID) age to prevent wraparound
     Xid {
         /// Number of tables to show (default: 10)
         #[arg(long, default_value = "10")]
         tables: usize,
+        #[command(flatten)]
+        trend: TrendArgs,
     },
     /// Analyze missing, unused, and duplicate indexes
     Indexes {
@@ -757,6 +1305,12 @@ enum DbaCommands {
         /// Number of unused indexes to show (default: 20)
         #[arg(long, default_value = "20")]
         unused_limit: usize,
+        /// Only check foreign keys without a supporting index
+        #[arg(long)]
+        fk_missing: bool,
+        /// Include CREATE INDEX CONCURRENTLY fix actions for missing FK indexes
+        #[arg(long)]
+        include_actions: bool,
     },
     /// Monitor table bloat and vacuum health
     Vacuum {
@@ -764,14 +1318,19 @@ enum DbaCommands {
         #[arg(long, value_name = "TABLE")]
         table: Option<String>,
         /// Warning threshold percentage (default: 10)
-        #[arg(long, value_name = "PCT")]
+        #[arg(long, value_name = "PCT", conflicts_with = "progress")]
         threshold: Option<f64>,
+        /// Show running VACUUM, VACUUM FULL, and CLUSTER with phase and ETA
+        #[arg(long)]
+        progress: bool,
     },
     /// Estimate table and index bloat
     Bloat {
         /// Number of items to show (default: 10)
         #[arg(long, default_value = "10")]
         limit: usize,
+        #[command(flatten)]
+        trend: TrendArgs,
     },
     /// Analyze buffer cache hit ratios
     Cache {
@@ -781,6 +1340,8 @@ enum DbaCommands {
     },
     /// Monitor streaming replication health
     Replication,
+    /// Replication slot health: retained WAL, inactive slots, xmin horizon, subscription lag
+    Slots,
     /// Show top queries from pg_stat_statements
     Queries {
         /// Sort by: total (default), mean, calls
@@ -790,6 +1351,30 @@ enum DbaCommands {
         #[arg(long, default_value = "10")]
         limit: usize,
     },
+    /// Manage pg_stat_statements: filter, sort, deltas, reset
+    Statements {
+        /// Sort by: total (default), mean, calls, rows, temp, wal
+        #[arg(long, value_name = "FIELD")]
+        by: Option<String>,
+        /// Number of statements to show (default: 10)
+        #[arg(long, default_value = "10")]
+        limit: usize,
+        /// Only statements run by this role
+        #[arg(long, value_name = "ROLE")]
+        user: Option<String>,
+        /// Only statements run in this database
+        #[arg(long, value_name = "NAME")]
+        database: Option<String>,
+        /// Only statements whose text matches this regex (case-insensitive)
+        #[arg(long, value_name = "REGEX", conflicts_with = "reset")]
+        matching: Option<String>,
+        /// Show counter deltas over a window (e.g. 30s, 5m) instead of totals
+        #[arg(long, value_name = "DURATION", conflicts_with = "reset")]
+        since: Option<String>,
+        /// Reset counters (scoped by --user/--database; requires --read-write and --primary)
+        #[arg(long)]
+        reset: bool,
+    },
     /// Analyze connection usage vs max_connections
     Connections {
         /// Group by user
@@ -802,6 +1387,13 @@ enum DbaCommands {
         #[arg(long)]
         by_application: bool,
     },
+    /// pgbouncer pool saturation, client wait times, and per-pool stats
+    Pooler {
+        /// pgbouncer admin console URL (default: [pooler] url in pgcrate.toml,
+        /// or the connection's host and port when it looks pooled)
+        #[arg(long, value_name = "URL")]
+        admin_url: Option<String>,
+    },
     /// Analyze query execution plan
     Explain {
         /// SQL query to explain
@@ -815,6 +1407,12 @@ enum DbaCommands {
         /// Include structured fix actions in JSON output
         #[arg(long)]
         include_actions: bool,
+        /// Plan baseline file: written on first run, compared against afterwards
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<std::path::PathBuf>,
+        /// Overwrite the --baseline file with the current plan
+        #[arg(long, requires = "baseline")]
+        update_baseline: bool,
     },
     /// Analyze disk usage (tables, indexes, TOAST)
     Storage {
@@ -833,6 +1431,24 @@ enum DbaCommands {
         #[command(subcommand)]
         command: FixCommands,
     },
+    /// Run actions from a saved `dba triage --include-fixes --json` plan, in order
+    Apply {
+        /// Plan file (triage JSON output or an array of actions)
+        #[arg(long, value_name = "FILE")]
+        plan: PathBuf,
+        /// Only run these action ids, comma-separated (required for risky fixes and SQL actions)
+        #[arg(long, value_name = "IDS", value_delimiter = ',')]
+        only: Vec<String>,
+        /// Show what each action would do without executing
+        #[arg(long)]
+        dry_run: bool,
+        /// Confirm execution (required for fixes)
+        #[arg(long)]
+        yes: bool,
+        /// Run each action's verification steps after it is applied
+        #[arg(long)]
+        verify: bool,
+    },
     /// Show tables with stale statistics (may cause poor query plans)
     StatsAge {
         /// Warning threshold in days (default: 7)
@@ -844,10 +1460,59 @@ enum DbaCommands {
     },
     /// Analyze checkpoint frequency and health
     Checkpoints,
+    /// WAL generation rate, checkpoint causes, max_wal_size pressure, and archive lag
+    Wal {
+        /// Also measure the current WAL rate over this many seconds
+        #[arg(long, value_name = "SECONDS")]
+        sample: Option<u64>,
+    },
+    /// Buffer I/O by backend type and context (pg_stat_io on PG16+)
+    Io,
+    /// Live monitor: active sessions, locks, TPS, cache hit, replication lag
+    Top {
+        /// Seconds between refreshes (default: 2)
+        #[arg(long, default_value = "2", value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+        /// Print a single frame and exit (also used when stdout is not a terminal)
+        #[arg(long)]
+        once: bool,
+    },
+    /// Temp file usage by database and query (work_mem spills)
+    Temp {
+        /// Number of queries to show (default: 10)
+        #[arg(long, default_value = "10")]
+        limit: usize,
+    },
+    /// Per-table autovacuum tuning: dead tuple rate vs. scale factor and threshold
+    Autovacuum {
+        /// Only this table (schema.table)
+        #[arg(long, value_name = "TABLE")]
+        table: Option<String>,
+        /// Number of tables to show (default: 20)
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
     /// Show currently running autovacuum operations
     AutovacuumProgress,
     /// Review PostgreSQL configuration settings
     Config,
+    /// Record triage, bloat, sequence, and XID metrics into pgcrate.metrics for `dba trend`
+    Record {
+        /// Tag this recording as a named baseline (compare with --compare NAME)
+        #[arg(long, value_name = "NAME")]
+        baseline: Option<String>,
+    },
+    /// Show how a recorded metric changed over time (omit METRIC to list recorded metrics)
+    Trend {
+        /// Metric to show (e.g. table_bloat_bytes, sequence_pct_used, database_xid_age)
+        metric: Option<String>,
+        /// Window to look back over (e.g. 24h, 7d)
+        #[arg(long, default_value = "7d", conflicts_with = "baseline")]
+        since: String,
+        /// Compare the latest recording against a named baseline instead
+        #[arg(long, value_name = "NAME")]
+        baseline: Option<String>,
+    },
 }
 
 /// Schema and permission inspection commands
@@ -867,24 +1532,143 @@ enum InspectCommands {
         #[arg(long)]
         no_stats: bool,
     },
-    /// Compare two database schemas and show differences
-    Diff {
-        /// Source database URL (default: DATABASE_URL)
+    /// Find tables, columns, views, functions, indexes, and constraints by name
+    Find {
+        /// Case-insensitive substring (or regex with --regex) to match names against
+        pattern: String,
+        /// Treat the pattern as a case-insensitive POSIX regular expression
         #[arg(long)]
-        from: Option<String>,
-        /// Target database URL (required)
-        #[arg(long)]
-        to: String,
-        /// Only compare these schemas (can be specified multiple times)
-        #[arg(long = "schema", value_name = "SCHEMA")]
-        schemas: Vec<String>,
-        /// Exclude these schemas (can be specified multiple times)
+        regex: bool,
+        /// Only these object kinds, comma-separated
+        #[arg(
+            long = "type",
+            value_name = "KINDS",
+            value_delimiter = ',',
+            value_parser = ["table", "view", "column", "function", "index", "constraint"]
+        )]
+        kinds: Vec<String>,
+        /// Only this schema
+        #[arg(long)]
+        schema: Option<String>,
+    },
+    /// Export the FK, view, trigger-function, and type dependency graph of a schema
+    Graph {
+        /// Schema to graph
+        #[arg(long)]
+        schema: String,
+        /// Output format: dot (default), mermaid, json
+        #[arg(long, default_value = "dot")]
+        format: String,
+    },
+    /// Summarize a schema: object counts, size, largest tables, owners, newest objects
+    Schema {
+        /// Schema to summarize (default: list every user schema)
+        name: Option<String>,
+        /// Largest tables and newest objects to show (default: 10)
+        #[arg(long, default_value = "10")]
+        limit: i64,
+    },
+    /// Profile a table's columns: null fraction, distinct counts, min/max, top values
+    Profile {
+        /// Table to profile (schema.name or just name)
+        object: String,
+        /// Most rows to read; larger tables are sampled
+        #[arg(long, default_value = "10000", value_parser = clap::value_parser!(i64).range(1..))]
+        sample: i64,
+        /// Most common values to show per column
+        #[arg(long, default_value = "5")]
+        top: i64,
+    },
+    /// Show publications: published operations, tables, row filters, and column lists
+    Publications {
+        /// Only this publication
+        name: Option<String>,
+    },
+    /// Show subscriptions: status, apply lag, publications, and table sync state
+    Subscriptions {
+        /// Only this subscription
+        name: Option<String>,
+    },
+    /// Show a sequence's owner column, current value, and % consumed
+    Sequence {
+        /// Sequence to describe (schema.name or just name)
+        object: String,
+    },
+    /// Show an enum, domain, or composite type and the columns using it
+    Type {
+        /// Type to describe (schema.name or just name)
+        object: String,
+    },
+    /// Compare two database schemas and show differences
+    Diff {
+        /// Source database URL (default: DATABASE_URL)
+        #[arg(long)]
+        from: Option<String>,
+        /// Target database URL (required)
+        #[arg(long)]
+        to: String,
+        /// Only compare these schemas (can be specified multiple times)
+        #[arg(long = "schema", value_name = "SCHEMA")]
+        schemas: Vec<String>,
+        /// Exclude these schemas (can be specified multiple times)
+        #[arg(
+            long = "exclude-schema",
+            value_name = "SCHEMA",
+            conflicts_with = "schemas"
+        )]
+        exclude_schemas: Vec<String>,
+        /// Output format: text (default), or sql to print statements that bring --to in line with --from
+        #[arg(long, default_value = "text")]
+        format: String,
+        /// With --format sql, leave out drops and changes that can lose data
+        #[arg(long)]
+        safe_only: bool,
+        /// Also compare roles, role memberships, object ownership, and grants
+        #[arg(long)]
+        include_privileges: bool,
+    },
+    /// Three-way schema diff: changes only in ours, only in theirs, or conflicting vs a base
+    Diff3 {
+        /// Common ancestor database URL (e.g. the last promoted release)
+        #[arg(long)]
+        base: String,
+        /// Our database URL, e.g. staging (default: DATABASE_URL)
+        #[arg(long)]
+        ours: Option<String>,
+        /// Their database URL, e.g. production (required)
+        #[arg(long)]
+        theirs: String,
+        /// Only compare these schemas (can be specified multiple times)
+        #[arg(long = "schema", value_name = "SCHEMA")]
+        schemas: Vec<String>,
+        /// Exclude these schemas (can be specified multiple times)
         #[arg(
             long = "exclude-schema",
             value_name = "SCHEMA",
             conflicts_with = "schemas"
         )]
         exclude_schemas: Vec<String>,
+        /// Also compare roles, role memberships, object ownership, and grants
+        #[arg(long)]
+        include_privileges: bool,
+    },
+    /// Compare a table's rows between two databases (added, removed, changed)
+    DataDiff {
+        /// Source database URL (default: DATABASE_URL)
+        #[arg(long)]
+        from: Option<String>,
+        /// Target database URL (required)
+        #[arg(long)]
+        to: String,
+        /// Table to compare (schema.table)
+        #[arg(long)]
+        table: String,
+        /// Comma-separated columns identifying a row (default: primary key)
+        #[arg(long)]
+        key: Option<String>,
+        /// Keys to list per category (default: 20)
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
     },
     /// List and inspect PostgreSQL extensions
     Extensions {
@@ -904,6 +1688,14 @@ enum InspectCommands {
         #[arg(long, value_name = "NAME")]
         describe: Option<String>,
     },
+    /// Audit superusers, non-expiring passwords, PUBLIC schema grants, login-role
+    /// ownership, and sensitive tables without row-level security
+    SecurityAudit {
+        /// Sensitive table (schema.table, globs allowed) that must have RLS (repeatable;
+        /// adds to [security] sensitive_tables)
+        #[arg(long = "sensitive", value_name = "TABLE")]
+        sensitive: Vec<String>,
+    },
     /// Show grants/permissions on database objects
     Grants {
         /// Table to show grants for (schema.table)
@@ -914,6 +1706,31 @@ enum InspectCommands {
         /// Show what a specific role can access
         #[arg(long)]
         role: Option<String>,
+        /// Role × privilege grid for the schema and its objects (requires --schema)
+        #[arg(long, requires = "schema", conflicts_with_all = ["object", "role"])]
+        matrix: bool,
+        /// Matrix output format: table (default), csv, json
+        #[arg(long, default_value = "table", requires = "matrix")]
+        format: String,
+    },
+}
+
+/// Table and column comment commands
+#[derive(Subcommand)]
+enum CommentCommands {
+    /// Set the comment on a table or column
+    Set {
+        /// schema.table or schema.table.column (a bare name is a table)
+        target: String,
+        /// Comment text (an empty string clears the comment)
+        #[arg(required_unless_present = "clear")]
+        text: Option<String>,
+        /// Remove the comment
+        #[arg(long, conflicts_with = "text")]
+        clear: bool,
+        /// Print the COMMENT ON statement without running it
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
@@ -937,7 +1754,7 @@ async fn main() {
     }
 
     // Use try_parse to handle clap errors in JSON mode
-    let cli = match Cli::try_parse() {
+    let mut cli = match Cli::try_parse() {
         Ok(cli) => cli,
         Err(e) => {
             // Handle meta UX flags (--help, --version) in JSON mode
@@ -966,6 +1783,25 @@ async fn main() {
         }
     };
 
+    // `dba --format` rides on the JSON envelope; nagios rewrites it as a status line
+    let nagios_mode = match &cli.command {
+        Commands::Dba {
+            format: Some(format),
+            ..
+        } => {
+            // Graph formats are handled by `dba locks` itself
+            if format == "json" || format == "nagios" {
+                cli.json = true;
+            }
+            format == "nagios"
+        }
+        _ => false,
+    };
+    if nagios_mode {
+        nagios::enable();
+    }
+    let json_mode = json_mode || cli.json;
+
     let output = Output::new(cli.json, cli.quiet, cli.verbose);
 
     // Gate unsupported commands in JSON mode
@@ -974,7 +1810,16 @@ async fn main() {
         std::process::exit(exit_codes::OPERATIONAL_FAILURE);
     }
 
-    if let Err(e) = run(cli, &output).await {
+    let result = run(cli, &output).await;
+    if nagios_mode {
+        // A check result exits from DiagnosticOutput::print; reaching here
+        // means the command failed or has no status to report
+        match result {
+            Err(e) => nagios::print_unknown(&format!("{e:#}")),
+            Ok(()) => nagios::print_unknown("this command has no check result"),
+        }
+    }
+    if let Err(e) = result {
         if json_mode {
             // JSON mode: output structured error to stdout
             // Only include details if source error is non-empty
@@ -988,6 +1833,12 @@ async fn main() {
                     "suggestions": model_err.suggestions,
                 });
                 println!("{}", serde_json::to_string_pretty(&payload).unwrap());
+            } else if e
+                .downcast_ref::<crate::snapshot::ToolVersionMismatch>()
+                .is_some()
+            {
+                JsonError::with_code("tool_version_mismatch", e.to_string(), format!("{e:#}"))
+                    .print();
             } else {
                 // Use full error chain for details (same as human mode)
                 let full_chain = format!("{e:#}");
@@ -1004,6 +1855,10 @@ async fn main() {
 }
 
 async fn run(cli: Cli, output: &Output) -> Result<()> {
+    if cli.watch.is_some() && !matches!(cli.command, Commands::Dba { .. }) {
+        anyhow::bail!("--watch is only supported for dba diagnostics");
+    }
+
     match cli.command {
         Commands::Migrate { command } => {
             // Handle migrate subcommands
@@ -1017,13 +1872,27 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         .context("Failed to load configuration")?;
                     commands::new_migration(&name, &config, with_down)?;
                 }
-                MigrateCommands::Up { yes: _, dry_run } => {
+                MigrateCommands::Up {
+                    yes: _,
+                    dry_run,
+                    single_transaction,
+                    snapshot,
+                } => {
                     let config = Config::load(cli.config_path.as_deref())
                         .context("Failed to load configuration")?;
                     let database_url = config
                         .get_database_url(cli.database_url.as_deref())
                         .context("DATABASE_URL not set")?;
-                    commands::up(&database_url, &config, cli.quiet, cli.verbose, dry_run).await?;
+                    commands::up(
+                        &database_url,
+                        &config,
+                        cli.quiet,
+                        cli.verbose,
+                        dry_run,
+                        single_transaction,
+                        snapshot || config.snapshot_before_migrate(),
+                    )
+                    .await?;
                 }
                 MigrateCommands::Down {
                     steps,
@@ -1077,6 +1946,14 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     )
                     .await?;
                 }
+                MigrateCommands::Conflicts { base } => {
+                    let config = Config::load(cli.config_path.as_deref())
+                        .context("Failed to load configuration")?;
+                    let exit_code = commands::migrate_conflicts(&config, &base, output)?;
+                    if exit_code != 0 {
+                        std::process::exit(exit_code);
+                    }
+                }
             }
         }
         Commands::Model { command } => {
@@ -1084,12 +1961,17 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 Config::load(cli.config_path.as_deref()).context("Failed to load configuration")?;
             let cwd = std::env::current_dir().context("get current directory")?;
             match command {
-                ModelCommands::Compile { selection, init } => {
+                ModelCommands::Compile {
+                    selection,
+                    vars,
+                    init,
+                } => {
                     commands::model::compile(
                         &cwd,
                         &config,
-                        &selection.select,
+                        &selection.selects(),
                         &selection.exclude,
+                        &vars,
                         init,
                         cli.quiet,
                     )?;
@@ -1099,6 +1981,14 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     selection,
                     dry_run,
                     full_refresh,
+                    enforce_contracts,
+                    force,
+                    vars,
+                    threads,
+                    keep_going,
+                    state,
+                    defer,
+                    init_sources,
                     init,
                     yes: _,
                 } => {
@@ -1106,7 +1996,7 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         .get_database_url(cli.database_url.as_deref())
                         .context("DATABASE_URL not set")?;
                     // Merge positional models with --select flag
-                    let mut select = selection.select.clone();
+                    let mut select = selection.selects();
                     select.extend(models);
                     commands::model::run(
                         &cwd,
@@ -1114,15 +2004,99 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         &database_url,
                         &select,
                         &selection.exclude,
+                        &vars,
                         dry_run,
                         full_refresh,
+                        enforce_contracts,
+                        force,
+                        threads.map(|n| n as usize),
+                        keep_going,
+                        state.as_deref(),
+                        defer,
+                        init_sources,
+                        init,
+                        cli.quiet,
+                        cli.verbose,
+                    )
+                    .await?;
+                }
+                ModelCommands::Retry => {
+                    let database_url = config
+                        .get_database_url(cli.database_url.as_deref())
+                        .context("DATABASE_URL not set")?;
+                    commands::model::retry(&cwd, &config, &database_url, cli.quiet, cli.verbose)
+                        .await?;
+                }
+                ModelCommands::Plan { selection } => {
+                    let database_url = config
+                        .get_database_url(cli.database_url.as_deref())
+                        .context("DATABASE_URL not set")?;
+                    commands::model::plan(
+                        &cwd,
+                        &config,
+                        &database_url,
+                        &selection.selects(),
+                        &selection.exclude,
+                        cli.quiet,
+                        cli.json,
+                    )
+                    .await?;
+                }
+                ModelCommands::Clone {
+                    from_schema,
+                    to_schema,
+                    selection,
+                    copy,
+                    dry_run,
+                } => {
+                    let database_url = config
+                        .get_database_url(cli.database_url.as_deref())
+                        .context("DATABASE_URL not set")?;
+                    commands::model::clone_schema(
+                        &cwd,
+                        &config,
+                        &database_url,
+                        &from_schema,
+                        &to_schema,
+                        &selection.selects(),
+                        &selection.exclude,
+                        copy,
+                        dry_run,
+                        cli.quiet,
+                    )
+                    .await?;
+                }
+                ModelCommands::Snapshot {
+                    models,
+                    selection,
+                    dry_run,
+                    vars,
+                    init,
+                } => {
+                    let database_url = config
+                        .get_database_url(cli.database_url.as_deref())
+                        .context("DATABASE_URL not set")?;
+                    let mut select = selection.selects();
+                    select.extend(models);
+                    commands::model::snapshot(
+                        &cwd,
+                        &config,
+                        &database_url,
+                        &select,
+                        &selection.exclude,
+                        &vars,
+                        dry_run,
                         init,
                         cli.quiet,
                         cli.verbose,
                     )
                     .await?;
                 }
-                ModelCommands::Test { selection, init } => {
+                ModelCommands::Test {
+                    selection,
+                    store_failures,
+                    init,
+                } => {
                     let database_url = config
                         .get_database_url(cli.database_url.as_deref())
                         .context("DATABASE_URL not set")?;
@@ -1130,32 +2104,43 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         &cwd,
                         &config,
                         &database_url,
-                        &selection.select,
+                        &selection.selects(),
                         &selection.exclude,
+                        store_failures,
                         init,
                         cli.quiet,
+                        cli.json,
                     )
                     .await?;
                     if exit_code != 0 {
                         std::process::exit(exit_code);
                     }
                 }
-                ModelCommands::Docs { selection } => {
+                ModelCommands::Docs { selection, format } => {
+                    let database_url = config.get_database_url(cli.database_url.as_deref());
                     commands::model::docs(
                         &cwd,
                         &config,
-                        &selection.select,
+                        database_url.as_deref(),
+                        &selection.selects(),
                         &selection.exclude,
+                        &format,
                         cli.quiet,
-                    )?;
+                    )
+                    .await?;
                 }
-                ModelCommands::Graph { selection, format } => {
+                ModelCommands::Graph {
+                    selection,
+                    format,
+                    columns,
+                } => {
                     commands::model::graph(
                         &cwd,
                         &config,
-                        &selection.select,
+                        &selection.selects(),
                         &selection.exclude,
                         &format,
+                        columns,
                         cli.quiet,
                     )?;
                 }
@@ -1164,7 +2149,7 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         LintCommands::Deps { selection, fix } => commands::model::lint_deps(
                             &cwd,
                             &config,
-                            &selection.select,
+                            &selection.selects(),
                             &selection.exclude,
                             fix,
                             cli.quiet,
@@ -1172,7 +2157,7 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         LintCommands::Qualify { selection, fix } => commands::model::lint_qualify(
                             &cwd,
                             &config,
-                            &selection.select,
+                            &selection.selects(),
                             &selection.exclude,
                             fix,
                             cli.quiet,
@@ -1183,13 +2168,16 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     }
                 }
                 ModelCommands::Check { selection } => {
+                    let database_url = config.get_database_url(cli.database_url.as_deref());
                     let exit_code = commands::model::check(
                         &cwd,
                         &config,
-                        &selection.select,
+                        database_url.as_deref(),
+                        &selection.selects(),
                         &selection.exclude,
                         cli.quiet,
-                    )?;
+                    )
+                    .await?;
                     if exit_code != 0 {
                         std::process::exit(exit_code);
                     }
@@ -1213,6 +2201,25 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         cli.quiet,
                     )?;
                 }
+                ModelCommands::ImportSources {
+                    schemas,
+                    staging,
+                    force,
+                } => {
+                    let database_url = config
+                        .get_database_url(cli.database_url.as_deref())
+                        .context("DATABASE_URL not set")?;
+                    commands::model::import_sources(
+                        &cwd,
+                        &config,
+                        &database_url,
+                        &schemas,
+                        staging,
+                        force,
+                        cli.quiet,
+                    )
+                    .await?;
+                }
                 ModelCommands::Show { id } => {
                     let database_url = config
                         .get_database_url(cli.database_url.as_deref())
@@ -1228,7 +2235,7 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         &cwd,
                         &config,
                         &database_url,
-                        &selection.select,
+                        &selection.selects(),
                         &selection.exclude,
                         cli.quiet,
                         cli.json,
@@ -1290,11 +2297,17 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 &seeds_dir,
             )?;
         }
-        Commands::Dba { ref command } => {
+        Commands::Dba {
+            ref command,
+            format: ref dba_format,
+        } => {
             // Handle `pgcrate dba` (no subcommand) as alias for triage
             let dba_cmd = command.clone().unwrap_or(DbaCommands::Triage {
                 include_fixes: false,
                 show_sql: false,
+                all_databases: false,
+                fleet: None,
+                trend: TrendArgs::default(),
             });
 
             // Doctor has its own connection handling, handle it separately
@@ -1314,16 +2327,81 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 return Ok(());
             }
 
+            // Fleet triage opens its own session per connection
+            if let DbaCommands::Triage {
+                fleet: Some(ref fleet),
+                ref trend,
+                ..
+            } = dba_cmd
+            {
+                if !trend.is_empty() {
+                    anyhow::bail!("--fleet cannot be combined with --baseline or --compare");
+                }
+                if cli.watch.is_some() {
+                    anyhow::bail!("--fleet cannot be combined with --watch");
+                }
+                if cli.database_url.is_some() || cli.connection.is_some() || cli.env_var.is_some() {
+                    anyhow::bail!("--fleet selects its own connections; drop -d, -C, and --env");
+                }
+
+                let config = Config::load(cli.config_path.as_deref())
+                    .context("Failed to load configuration")?;
+                let alerts = AlertsConfig::load(cli.alerts_config.as_deref())?;
+                let timeout_config = parse_timeout_config(&cli)?;
+                let result = commands::fleet::run_fleet_triage(
+                    &config,
+                    fleet,
+                    cli.allow_primary,
+                    &timeout_config,
+                    &alerts,
+                )
+                .await?;
+
+                if cli.json {
+                    commands::fleet::print_json(&result, Some(timeout_config.effective()))?;
+                } else {
+                    commands::fleet::print_human(&result, cli.quiet);
+                }
+
+                let exit_code = result.overall_status.exit_code();
+                if exit_code != 0 {
+                    std::process::exit(exit_code);
+                }
+                return Ok(());
+            }
+
             // Determine if we need read-write access
             let needs_write = match &dba_cmd {
-                DbaCommands::Fix { .. } => true,
-                DbaCommands::Locks { cancel, kill, .. } => cancel.is_some() || kill.is_some(),
+                DbaCommands::Fix { .. } | DbaCommands::Apply { .. } => true,
+                DbaCommands::Locks {
+                    cancel,
+                    kill,
+                    kill_idle,
+                    execute,
+                    ..
+                } => cancel.is_some() || kill.is_some() || (kill_idle.is_some() && *execute),
+                DbaCommands::Statements { reset, .. } => *reset,
                 _ => false,
             };
 
+            // Recording metrics creates and writes pgcrate.metrics
+            let records_metrics = match &dba_cmd {
+                DbaCommands::Record { .. } => true,
+                DbaCommands::Triage { trend, .. }
+                | DbaCommands::Bloat { trend, .. }
+                | DbaCommands::Sequences { trend, .. }
+                | DbaCommands::Xid { trend, .. } => trend.baseline.is_some(),
+                _ => false,
+            };
+            if records_metrics && (!cli.read_write || !cli.allow_primary) {
+                anyhow::bail!("Recording metrics requires --read-write and --primary flags");
+            }
+            let needs_write = needs_write || records_metrics;
+
             // Common setup for all other DBA commands
-            let config =
+            let mut config =
                 Config::load(cli.config_path.as_deref()).context("Failed to load configuration")?;
+            let alerts = AlertsConfig::load(cli.alerts_config.as_deref())?;
             let conn_result = connection::resolve_and_validate(
                 &config,
                 cli.database_url.as_deref(),
@@ -1333,6 +2411,7 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 needs_write || cli.read_write,
                 cli.quiet,
             )?;
+            config.active_connection = cli.connection.clone();
 
             let timeout_config = parse_timeout_config(&cli)?;
             let session = DiagnosticSession::connect(&conn_result.url, timeout_config).await?;
@@ -1345,18 +2424,122 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
             let client = session.client();
             let timeouts = Some(session.effective_timeouts());
 
+            let graph_format = dba_format
+                .as_deref()
+                .and_then(commands::lock_graph::GraphFormat::from_str);
+            if let Some(format) = dba_format.as_deref().filter(|_| graph_format.is_some()) {
+                if !matches!(dba_cmd, DbaCommands::Locks { .. }) {
+                    anyhow::bail!(
+                        "--format {} is only supported by `dba locks --blocking`",
+                        format
+                    );
+                }
+                if cli.watch.is_some() {
+                    anyhow::bail!("--format {} cannot be combined with --watch", format);
+                }
+            }
+
+            if let Some(interval) = cli.watch {
+                use commands::watch::WatchTarget;
+                let target = match dba_cmd {
+                    DbaCommands::Triage {
+                        include_fixes: false,
+                        show_sql: false,
+                        all_databases: false,
+                        fleet: None,
+                        ref trend,
+                    } if trend.is_empty() => WatchTarget::Triage,
+                    DbaCommands::Locks {
+                        blocking,
+                        long_tx,
+                        idle_in_tx,
+                        cancel: None,
+                        kill: None,
+                        kill_idle: None,
+                        ..
+                    } => WatchTarget::Locks(commands::locks::LocksOptions::new(
+                        blocking, long_tx, idle_in_tx,
+                    )),
+                    DbaCommands::Connections {
+                        by_user,
+                        by_database,
+                        by_application,
+                    } => WatchTarget::Connections {
+                        by_user,
+                        by_database,
+                        by_application,
+                    },
+                    DbaCommands::Replication => WatchTarget::Replication,
+                    _ => anyhow::bail!(
+                        "--watch supports read-only dba triage, locks, connections, and replication"
+                    ),
+                };
+                return commands::watch::run_watch(
+                    client,
+                    target,
+                    commands::watch::WatchOptions {
+                        interval: std::time::Duration::from_secs(interval),
+                        json: cli.json,
+                        quiet: cli.quiet,
+                        redact: !cli.no_redact,
+                        timeouts,
+                        alerts,
+                    },
+                )
+                .await;
+            }
+
             match dba_cmd {
                 DbaCommands::Doctor { .. } => unreachable!(), // Handled above
 
+                DbaCommands::Triage {
+                    all_databases: true,
+                    ref trend,
+                    ..
+                } => {
+                    if !trend.is_empty() {
+                        anyhow::bail!(
+                            "--all-databases cannot be combined with --baseline or --compare"
+                        );
+                    }
+                    let result = commands::cluster_triage::run_cluster_triage(
+                        client,
+                        &conn_result.url,
+                        &session.timeouts,
+                        &alerts,
+                    )
+                    .await?;
+
+                    if cli.json {
+                        commands::cluster_triage::print_json(&result, timeouts)?;
+                    } else {
+                        commands::cluster_triage::print_human(&result, cli.quiet);
+                    }
+
+                    let exit_code = result.overall_status.exit_code();
+                    if exit_code != 0 {
+                        std::process::exit(exit_code);
+                    }
+                }
+
                 DbaCommands::Triage {
                     include_fixes,
                     show_sql,
+                    ref trend,
+                    ..
                 } => {
                     if show_sql {
                         commands::triage::print_triage_queries();
                     }
 
-                    let mut results = commands::triage::run_triage(client).await;
+                    let mut results = commands::triage::run_triage(client, &alerts).await;
+                    results.comparison = commands::trend::baseline_and_compare(
+                        client,
+                        &commands::trend::triage_samples(&results),
+                        trend.baseline.as_deref(),
+                        trend.compare.as_deref(),
+                    )
+                    .await?;
 
                     if include_fixes {
                         let actions = commands::triage::generate_fix_actions(
@@ -1376,6 +2559,9 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         commands::triage::print_json(&results, timeouts)?;
                     } else {
                         commands::triage::print_human(&results, cli.quiet);
+                        if let Some(ref comparison) = results.comparison {
+                            commands::trend::print_comparison(comparison, cli.quiet);
+                        }
                     }
 
                     let exit_code = results.exit_code();
@@ -1387,6 +2573,7 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 DbaCommands::Vacuum {
                     ref table,
                     threshold,
+                    progress,
                 } => {
                     let (schema_filter, table_filter) = if let Some(ref t) = table {
                         if let Some((s, tbl)) = t.split_once('.') {
@@ -1398,13 +2585,30 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         (None, None)
                     };
 
-                    let result = commands::vacuum::run_vacuum(
-                        client,
-                        schema_filter,
-                        table_filter,
-                        threshold,
-                    )
-                    .await?;
+                    if progress {
+                        let result = commands::vacuum_progress::run_vacuum_progress(
+                            client,
+                            schema_filter,
+                            table_filter,
+                        )
+                        .await?;
+                        if cli.json {
+                            commands::vacuum_progress::print_json(&result, timeouts)?;
+                        } else {
+                            commands::vacuum_progress::print_human(&result, cli.quiet);
+                        }
+                        // No exit code - this is purely informational
+                        return Ok(());
+                    }
+
+                    let result = commands::vacuum::run_vacuum(
+                        client,
+                        schema_filter,
+                        table_filter,
+                        threshold,
+                        &alerts.ignore,
+                    )
+                    .await?;
 
                     if cli.json {
                         commands::vacuum::print_json(&result, timeouts)?;
@@ -1421,13 +2625,24 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     }
                 }
 
-                DbaCommands::Bloat { limit } => {
-                    let result = commands::bloat::get_bloat(client, limit).await?;
+                DbaCommands::Bloat { limit, ref trend } => {
+                    let mut result =
+                        commands::bloat::get_bloat(client, limit, &alerts.ignore).await?;
+                    result.comparison = commands::trend::baseline_and_compare(
+                        client,
+                        &commands::trend::bloat_samples(&result),
+                        trend.baseline.as_deref(),
+                        trend.compare.as_deref(),
+                    )
+                    .await?;
 
                     if cli.json {
                         commands::bloat::print_json(&result, timeouts)?;
                     } else {
                         commands::bloat::print_human(&result, cli.quiet);
+                        if let Some(ref comparison) = result.comparison {
+                            commands::trend::print_comparison(comparison, cli.quiet);
+                        }
                     }
 
                     if let Some(code) = exit_codes::for_finding(
@@ -1440,7 +2655,7 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 }
 
                 DbaCommands::Cache { limit } => {
-                    let result = commands::cache::run_cache(client, limit).await?;
+                    let result = commands::cache::run_cache(client, limit, &alerts).await?;
 
                     if cli.json {
                         commands::cache::print_json(&result, timeouts)?;
@@ -1458,7 +2673,8 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 }
 
                 DbaCommands::Replication => {
-                    let result = commands::replication::get_replication(client).await?;
+                    let result =
+                        commands::replication::get_replication(client, &alerts.replication).await?;
 
                     if cli.json {
                         commands::replication::print_json(&result, timeouts)?;
@@ -1475,6 +2691,78 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     }
                 }
 
+                DbaCommands::Slots => {
+                    let result = commands::slots::run_slots(client).await?;
+
+                    if cli.json {
+                        commands::slots::print_json(&result, timeouts)?;
+                    } else {
+                        commands::slots::print_human(&result, cli.quiet);
+                    }
+
+                    if let Some(code) = exit_codes::for_finding(
+                        cli.json,
+                        result.overall_status == commands::slots::SlotStatus::Critical,
+                        result.overall_status == commands::slots::SlotStatus::Warning,
+                    ) {
+                        std::process::exit(code);
+                    }
+                }
+
+                DbaCommands::Statements {
+                    ref by,
+                    limit,
+                    ref user,
+                    ref database,
+                    ref matching,
+                    ref since,
+                    reset,
+                } => {
+                    let sort_by = by
+                        .as_ref()
+                        .map(|s| {
+                            commands::statements::StatementSort::from_str(s).ok_or_else(|| {
+                                anyhow::anyhow!(
+                                    "Invalid --by value '{}'. Use: total, mean, calls, rows, temp, wal",
+                                    s
+                                )
+                            })
+                        })
+                        .transpose()?
+                        .unwrap_or_default();
+                    let since = since
+                        .as_deref()
+                        .map(diagnostic::parse_duration)
+                        .transpose()?;
+                    if reset && (!cli.read_write || !cli.allow_primary) {
+                        anyhow::bail!("--reset requires --read-write and --primary flags");
+                    }
+
+                    let filters = commands::statements::StatementFilters {
+                        user: user.clone(),
+                        database: database.clone(),
+                        matching: matching.clone(),
+                    };
+                    let result = commands::statements::run_statements(
+                        client, sort_by, limit, filters, since, reset,
+                    )
+                    .await?;
+
+                    if cli.json {
+                        commands::statements::print_json(&result, timeouts)?;
+                    } else {
+                        commands::statements::print_human(&result, cli.quiet);
+                    }
+
+                    if let Some(code) = exit_codes::for_finding(
+                        cli.json,
+                        result.overall_status == commands::queries::QueryStatus::Critical,
+                        result.overall_status == commands::queries::QueryStatus::Warning,
+                    ) {
+                        std::process::exit(code);
+                    }
+                }
+
                 DbaCommands::Queries { ref by, limit } => {
                     let sort_by = by
                         .as_ref()
@@ -1511,13 +2799,15 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     by_database,
                     by_application,
                 } => {
-                    let result = commands::connections::run_connections(
+                    let mut result = commands::connections::run_connections(
                         client,
                         by_user,
                         by_database,
                         by_application,
+                        &alerts,
                     )
                     .await?;
+                    result.pooler = commands::pooler::detect_pooler(client, &conn_result.url).await;
 
                     if cli.json {
                         commands::connections::print_json(&result, timeouts)?;
@@ -1534,11 +2824,48 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     }
                 }
 
+                DbaCommands::Pooler { ref admin_url } => {
+                    let detection = commands::pooler::detect_pooler(client, &conn_result.url).await;
+                    let admin_url = match (admin_url.clone(), config.pooler_url()?) {
+                        (Some(url), _) | (None, Some(url)) => url,
+                        (None, None) if detection.is_some() => {
+                            commands::pooler::admin_url_from(&conn_result.url)?
+                        }
+                        (None, None) => anyhow::bail!(
+                            "No pgbouncer admin console configured, and the connection does not look pooled.\n\
+                             Hint: Pass --admin-url, or set [pooler] url (or pooler_url on a connection) in pgcrate.toml"
+                        ),
+                    };
+                    let result = commands::pooler::run_pooler(
+                        client,
+                        &admin_url,
+                        detection,
+                        &session.timeouts,
+                    )
+                    .await?;
+
+                    if cli.json {
+                        commands::pooler::print_json(&result, timeouts)?;
+                    } else {
+                        commands::pooler::print_human(&result, cli.quiet);
+                    }
+
+                    if let Some(code) = exit_codes::for_finding(
+                        cli.json,
+                        result.overall_status == commands::pooler::PoolStatus::Critical,
+                        result.overall_status == commands::pooler::PoolStatus::Warning,
+                    ) {
+                        std::process::exit(code);
+                    }
+                }
+
                 DbaCommands::Explain {
                     query,
                     file,
                     analyze,
                     include_actions,
+                    baseline,
+                    update_baseline,
                 } => {
                     // Get query from argument or file
                     let sql = if let Some(ref path) = file {
@@ -1563,21 +2890,25 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         result.actions = Some(actions);
                     }
 
+                    if let Some(ref path) = baseline {
+                        result.baseline = Some(commands::plan_baseline::check(
+                            path,
+                            &sql,
+                            &result.plan_json,
+                            update_baseline,
+                        )?);
+                    }
+
                     if cli.json {
                         commands::explain::print_json(&result, timeouts)?;
                     } else {
                         commands::explain::print_human(&result, cli.verbose);
                     }
 
-                    // Exit code based on issues
-                    let has_critical = result
-                        .issues
-                        .iter()
-                        .any(|i| matches!(i.severity, commands::explain::IssueSeverity::Critical));
-                    let has_warning = result
-                        .issues
-                        .iter()
-                        .any(|i| matches!(i.severity, commands::explain::IssueSeverity::Warning));
+                    // Exit code based on issues and baseline regressions
+                    let severity = result.max_severity();
+                    let has_critical = severity == Some(commands::explain::IssueSeverity::Critical);
+                    let has_warning = severity == Some(commands::explain::IssueSeverity::Warning);
 
                     if let Some(code) = exit_codes::for_finding(cli.json, has_critical, has_warning)
                     {
@@ -1659,35 +2990,77 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     }
                     FixCommands::Index {
                         drop,
+                        create,
+                        columns,
+                        name: index_name,
+                        dedupe,
                         dry_run,
                         yes,
                         verify,
                     } => {
-                        let (schema, name) = if let Some((s, n)) = drop.split_once('.') {
-                            (s, n)
-                        } else {
-                            ("public", drop.as_str())
-                        };
-
-                        if !cli.read_write && !cli.allow_primary {
+                        if !cli.read_write || !cli.allow_primary {
                             anyhow::bail!("Fix commands require --read-write and --primary flags");
                         }
 
-                        let mut result = commands::fix::index::execute_drop(
-                            client,
-                            schema,
-                            name,
-                            *dry_run || !*yes,
-                        )
-                        .await?;
-
-                        if *verify && result.executed && result.success {
-                            let verify_steps = commands::fix::index::get_verify_steps(name);
-                            let verification =
-                                commands::fix::verify::run_verification(&verify_steps);
-                            result.verification = Some(verification);
+                        if *dedupe {
+                            let mut result =
+                                commands::fix::index::execute_dedupe(client, *dry_run || !*yes)
+                                    .await?;
+                            if *verify && result.fix.executed && result.fix.success {
+                                let verify_steps = commands::fix::index::get_dedupe_verify_steps();
+                                result.fix.verification =
+                                    Some(commands::fix::verify::run_verification(&verify_steps));
+                            }
+                            if cli.json {
+                                commands::fix::index::print_dedupe_json(&result, timeouts)?;
+                            } else {
+                                commands::fix::index::print_dedupe_human(&result, cli.quiet);
+                            }
+                            if !result.fix.success {
+                                std::process::exit(1);
+                            }
+                            return Ok(());
                         }
 
+                        let result = if let Some(table) = create {
+                            let (schema, name) = table.split_once('.').unwrap_or(("public", table));
+                            let mut result = commands::fix::index::execute_create(
+                                client,
+                                schema,
+                                name,
+                                columns,
+                                index_name.as_deref(),
+                                *dry_run || !*yes,
+                            )
+                            .await?;
+                            if *verify && result.executed && result.success {
+                                let index = index_name.clone().unwrap_or_else(|| {
+                                    commands::fix::index::default_index_name(name, columns)
+                                });
+                                let verify_steps =
+                                    commands::fix::index::get_create_verify_steps(&index);
+                                result.verification =
+                                    Some(commands::fix::verify::run_verification(&verify_steps));
+                            }
+                            result
+                        } else {
+                            let drop = drop.as_deref().unwrap_or_default();
+                            let (schema, name) = drop.split_once('.').unwrap_or(("public", drop));
+                            let mut result = commands::fix::index::execute_drop(
+                                client,
+                                schema,
+                                name,
+                                *dry_run || !*yes,
+                            )
+                            .await?;
+                            if *verify && result.executed && result.success {
+                                let verify_steps = commands::fix::index::get_verify_steps(name);
+                                result.verification =
+                                    Some(commands::fix::verify::run_verification(&verify_steps));
+                            }
+                            result
+                        };
+
                         if cli.json {
                             commands::fix::index::print_json(&result, timeouts)?;
                         } else {
@@ -1754,6 +3127,189 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                             std::process::exit(1);
                         }
                     }
+                    FixCommands::Autovacuum {
+                        table,
+                        scale_factor,
+                        threshold,
+                        dry_run,
+                        yes,
+                        verify,
+                    } => {
+                        let (schema, name) = if let Some((s, n)) = table.split_once('.') {
+                            (s, n)
+                        } else {
+                            ("public", table.as_str())
+                        };
+
+                        if !cli.read_write || !cli.allow_primary {
+                            anyhow::bail!("Fix commands require --read-write and --primary flags");
+                        }
+
+                        let mut result = commands::fix::autovacuum::execute_tune(
+                            client,
+                            schema,
+                            name,
+                            *scale_factor,
+                            *threshold,
+                            *dry_run || !*yes,
+                        )
+                        .await?;
+
+                        if *verify && result.executed && result.success {
+                            let verify_steps =
+                                commands::fix::autovacuum::get_verify_steps(schema, name);
+                            let verification =
+                                commands::fix::verify::run_verification(&verify_steps);
+                            result.verification = Some(verification);
+                        }
+
+                        if cli.json {
+                            commands::fix::autovacuum::print_json(&result, timeouts)?;
+                        } else {
+                            commands::fix::autovacuum::print_human(&result, cli.quiet);
+                        }
+
+                        if !result.success {
+                            std::process::exit(1);
+                        }
+                    }
+                    FixCommands::Partition {
+                        table,
+                        create_ahead,
+                        retain,
+                        drop,
+                        dry_run,
+                        yes,
+                        verify,
+                    } => {
+                        let (schema, name) = if let Some((s, n)) = table.split_once('.') {
+                            (s, n)
+                        } else {
+                            ("public", table.as_str())
+                        };
+
+                        if !cli.read_write || !cli.allow_primary {
+                            anyhow::bail!("Fix commands require --read-write and --primary flags");
+                        }
+
+                        let mut result = commands::fix::partition::execute_maintenance(
+                            client,
+                            schema,
+                            name,
+                            *create_ahead,
+                            *retain,
+                            *drop,
+                            *dry_run || !*yes,
+                        )
+                        .await?;
+
+                        if *verify && result.executed && result.success {
+                            let verify_steps = commands::fix::partition::get_verify_steps(
+                                schema,
+                                name,
+                                *create_ahead,
+                            );
+                            let verification =
+                                commands::fix::verify::run_verification(&verify_steps);
+                            result.verification = Some(verification);
+                        }
+
+                        if cli.json {
+                            commands::fix::partition::print_json(&result, timeouts)?;
+                        } else {
+                            commands::fix::partition::print_human(&result, cli.quiet);
+                        }
+
+                        if !result.success {
+                            std::process::exit(1);
+                        }
+                    }
+                    FixCommands::Analyze {
+                        table,
+                        all_stale: _,
+                        batch_size,
+                        dry_run,
+                        yes,
+                        verify,
+                    } => {
+                        if !cli.read_write || !cli.allow_primary {
+                            anyhow::bail!("Fix commands require --read-write and --primary flags");
+                        }
+                        let target = table
+                            .as_deref()
+                            .map(|t| t.split_once('.').unwrap_or(("public", t)));
+
+                        let mut result = commands::fix::analyze::execute_analyze(
+                            client,
+                            target,
+                            *batch_size,
+                            *dry_run || !*yes,
+                        )
+                        .await?;
+
+                        if *verify && result.fix.executed && result.fix.success {
+                            let verify_steps = commands::fix::analyze::get_verify_steps(
+                                target.map(|(_, name)| name),
+                            );
+                            let verification =
+                                commands::fix::verify::run_verification(&verify_steps);
+                            result.fix.verification = Some(verification);
+                        }
+
+                        if cli.json {
+                            commands::fix::analyze::print_json(&result, timeouts)?;
+                        } else {
+                            commands::fix::analyze::print_human(&result, cli.quiet);
+                        }
+
+                        if !result.fix.success {
+                            std::process::exit(1);
+                        }
+                    }
+                    FixCommands::Xid {
+                        min_age,
+                        limit,
+                        window,
+                        dry_run,
+                        yes,
+                        verify,
+                    } => {
+                        if !cli.read_write || !cli.allow_primary {
+                            anyhow::bail!("Fix commands require --read-write and --primary flags");
+                        }
+                        let window = window
+                            .as_deref()
+                            .map(diagnostic::parse_duration)
+                            .transpose()?;
+
+                        let mut result = commands::fix::xid::execute_freeze(
+                            client,
+                            &conn_result.url,
+                            *min_age,
+                            *limit,
+                            window,
+                            *dry_run || !*yes,
+                            !cli.json && !cli.quiet,
+                        )
+                        .await?;
+
+                        if *verify && result.fix.executed && result.fix.success {
+                            let verify_steps = commands::fix::xid::get_verify_steps();
+                            let verification =
+                                commands::fix::verify::run_verification(&verify_steps);
+                            result.fix.verification = Some(verification);
+                        }
+
+                        if cli.json {
+                            commands::fix::xid::print_json(&result, timeouts)?;
+                        } else {
+                            commands::fix::xid::print_human(&result, cli.quiet);
+                        }
+
+                        if !result.fix.success {
+                            std::process::exit(1);
+                        }
+                    }
                     FixCommands::Bloat {
                         index,
                         blocking,
@@ -1802,14 +3358,106 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                             std::process::exit(1);
                         }
                     }
+                    FixCommands::Prepared {
+                        rollback,
+                        dry_run,
+                        yes,
+                        verify,
+                    } => {
+                        if !cli.read_write || !cli.allow_primary {
+                            anyhow::bail!("Fix commands require --read-write and --primary flags");
+                        }
+
+                        let mut result = commands::fix::prepared::execute_rollback(
+                            client,
+                            rollback,
+                            *dry_run || !*yes,
+                        )
+                        .await?;
+
+                        if *verify && result.fix.executed && result.fix.success {
+                            let verify_steps = commands::fix::prepared::get_verify_steps(rollback);
+                            let verification =
+                                commands::fix::verify::run_verification(&verify_steps);
+                            result.fix.verification = Some(verification);
+                        }
+
+                        if cli.json {
+                            commands::fix::prepared::print_json(&result, timeouts)?;
+                        } else {
+                            commands::fix::prepared::print_human(&result, cli.quiet);
+                        }
+
+                        if !result.fix.success {
+                            std::process::exit(1);
+                        }
+                    }
                 },
 
+                DbaCommands::Apply {
+                    ref plan,
+                    ref only,
+                    dry_run,
+                    yes,
+                    verify,
+                } => {
+                    if !cli.read_write || !cli.allow_primary {
+                        anyhow::bail!("Fix commands require --read-write and --primary flags");
+                    }
+                    // Children inherit the session's flags, timeouts and config
+                    let mut global_args = Vec::new();
+                    for (flag, given) in [
+                        ("--read-write", cli.read_write),
+                        ("--primary", cli.allow_primary),
+                    ] {
+                        if given {
+                            global_args.push(flag.to_string());
+                        }
+                    }
+                    for (flag, value) in [
+                        (
+                            "--config",
+                            cli.config_path.as_ref().map(|p| p.display().to_string()),
+                        ),
+                        ("--connect-timeout", cli.connect_timeout.clone()),
+                        ("--statement-timeout", cli.statement_timeout.clone()),
+                        ("--lock-timeout", cli.lock_timeout.clone()),
+                    ] {
+                        if let Some(value) = value {
+                            global_args.push(flag.to_string());
+                            global_args.push(value);
+                        }
+                    }
+
+                    let result = commands::fix::apply::execute_apply(
+                        plan,
+                        only,
+                        &conn_result.url,
+                        &global_args,
+                        dry_run || !yes,
+                        verify,
+                    )?;
+
+                    if cli.json {
+                        commands::fix::apply::print_json(&result, timeouts)?;
+                    } else {
+                        commands::fix::apply::print_human(&result, cli.quiet);
+                    }
+
+                    if !result.success {
+                        std::process::exit(1);
+                    }
+                }
+
                 DbaCommands::Locks {
                     blocking,
                     long_tx,
                     idle_in_tx,
                     cancel,
                     kill,
+                    ref kill_idle,
+                    ref application,
+                    ref user,
                     execute,
                 } => {
                     // Handle cancel/kill operations (redact by default)
@@ -1826,75 +3474,69 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                             .await?;
                         return Ok(());
                     }
-
-                    // Determine what to show (default: show blocking if nothing specified)
-                    let show_blocking = blocking || (long_tx.is_none() && !idle_in_tx);
-                    let show_long_tx = long_tx.is_some();
-                    let show_idle = idle_in_tx;
-
-                    let mut result = commands::locks::LocksResult {
-                        blocking_chains: vec![],
-                        long_transactions: vec![],
-                        idle_in_transaction: vec![],
-                    };
-
-                    if show_blocking {
-                        result.blocking_chains =
-                            commands::locks::get_blocking_chains(client).await?;
-                    }
-                    if show_long_tx {
-                        let min_minutes = long_tx.unwrap_or(5);
-                        result.long_transactions =
-                            commands::locks::get_long_transactions(client, min_minutes).await?;
+                    if let Some(ref min_idle) = kill_idle {
+                        if execute && (!cli.read_write || !cli.allow_primary) {
+                            anyhow::bail!(
+                                "--kill-idle --execute requires --read-write and --primary flags"
+                            );
+                        }
+                        let opts = commands::locks::KillIdleOptions {
+                            min_idle: diagnostic::parse_duration(min_idle)
+                                .with_context(|| format!("Invalid --kill-idle '{}'", min_idle))?,
+                            application: application.clone(),
+                            user: user.clone(),
+                            execute,
+                        };
+                        let result =
+                            commands::locks::kill_idle(client, &opts, should_redact).await?;
+                        if cli.json {
+                            commands::locks::print_kill_idle_json(&result, timeouts)?;
+                        } else {
+                            commands::locks::print_kill_idle(&result);
+                        }
+                        return Ok(());
                     }
-                    if show_idle {
-                        result.idle_in_transaction =
-                            commands::locks::get_idle_in_transaction(client).await?;
+                    if let Some(graph_format) = graph_format {
+                        use commands::lock_graph::GraphFormat;
+                        let graph =
+                            commands::lock_graph::get_wait_graph(client, should_redact).await?;
+                        match graph_format {
+                            GraphFormat::Dot => commands::lock_graph::print_dot(&graph),
+                            GraphFormat::Mermaid => commands::lock_graph::print_mermaid(&graph),
+                            GraphFormat::JsonGraph => {
+                                commands::lock_graph::print_json(&graph, timeouts)?
+                            }
+                        }
+                        return Ok(());
                     }
 
-                    if should_redact {
-                        result.redact();
-                    }
+                    let opts = commands::locks::LocksOptions::new(blocking, long_tx, idle_in_tx);
+                    let result = commands::locks::run_locks(client, opts, should_redact).await?;
 
                     if cli.json {
                         commands::locks::print_json(&result, timeouts)?;
                     } else {
-                        if show_blocking {
-                            commands::locks::print_blocking_chains(
-                                &result.blocking_chains,
-                                cli.quiet,
-                            );
-                        }
-                        if show_long_tx {
-                            if show_blocking && !result.blocking_chains.is_empty() {
-                                println!();
-                            }
-                            commands::locks::print_long_transactions(
-                                &result.long_transactions,
-                                cli.quiet,
-                            );
-                        }
-                        if show_idle {
-                            if (show_blocking && !result.blocking_chains.is_empty())
-                                || (show_long_tx && !result.long_transactions.is_empty())
-                            {
-                                println!();
-                            }
-                            commands::locks::print_idle_in_transaction(
-                                &result.idle_in_transaction,
-                                cli.quiet,
-                            );
-                        }
+                        commands::locks::print_human(&result, opts, cli.quiet);
                     }
                 }
 
-                DbaCommands::Xid { tables } => {
-                    let result = commands::xid::run_xid(client, tables).await?;
+                DbaCommands::Xid { tables, ref trend } => {
+                    let mut result = commands::xid::run_xid(client, tables, &alerts).await?;
+                    result.comparison = commands::trend::baseline_and_compare(
+                        client,
+                        &commands::trend::xid_samples(&result),
+                        trend.baseline.as_deref(),
+                        trend.compare.as_deref(),
+                    )
+                    .await?;
 
                     if cli.json {
                         commands::xid::print_json(&result, timeouts)?;
                     } else {
                         commands::xid::print_human(&result);
+                        if let Some(ref comparison) = result.comparison {
+                            commands::trend::print_comparison(comparison, cli.quiet);
+                        }
                     }
 
                     if let Some(code) = exit_codes::for_finding(
@@ -1906,13 +3548,29 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     }
                 }
 
-                DbaCommands::Sequences { warn, crit, all } => {
-                    let result = commands::sequences::run_sequences(client, warn, crit).await?;
+                DbaCommands::Sequences {
+                    warn,
+                    crit,
+                    all,
+                    ref trend,
+                } => {
+                    let mut result =
+                        commands::sequences::run_sequences(client, warn, crit, &alerts).await?;
+                    result.comparison = commands::trend::baseline_and_compare(
+                        client,
+                        &commands::trend::sequence_samples(&result),
+                        trend.baseline.as_deref(),
+                        trend.compare.as_deref(),
+                    )
+                    .await?;
 
                     if cli.json {
                         commands::sequences::print_json(&result, timeouts)?;
                     } else {
                         commands::sequences::print_human(&result, cli.quiet, all);
+                        if let Some(ref comparison) = result.comparison {
+                            commands::trend::print_comparison(comparison, cli.quiet);
+                        }
                     }
 
                     if let Some(code) = exit_codes::for_finding(
@@ -1924,54 +3582,261 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     }
                 }
 
+                DbaCommands::Partitions {
+                    ref table,
+                    ahead,
+                    skew,
+                    include_actions,
+                } => {
+                    let table = table
+                        .as_deref()
+                        .map(|t| t.split_once('.').unwrap_or(("public", t)));
+                    let mut result = commands::partitions::run_partitions(
+                        client,
+                        table,
+                        ahead,
+                        skew,
+                        &alerts.ignore,
+                    )
+                    .await?;
+
+                    if include_actions {
+                        let actions = commands::partitions::generate_actions(
+                            &result,
+                            cli.read_write,
+                            cli.allow_primary,
+                        );
+                        result.actions = Some(actions);
+                    }
+
+                    if cli.json {
+                        commands::partitions::print_json(&result, timeouts)?;
+                    } else {
+                        commands::partitions::print_human(&result, cli.quiet);
+                    }
+
+                    if let Some(code) = exit_codes::for_finding(
+                        cli.json,
+                        result.overall_status == commands::partitions::PartitionStatus::Critical,
+                        result.overall_status == commands::partitions::PartitionStatus::Warning,
+                    ) {
+                        std::process::exit(code);
+                    }
+                }
+
+                DbaCommands::Prepared {
+                    ref older_than,
+                    include_actions,
+                } => {
+                    let threshold = match older_than {
+                        Some(s) => diagnostic::parse_duration(s)?,
+                        None => commands::prepared::DEFAULT_THRESHOLD,
+                    };
+                    let mut result = commands::prepared::run_prepared(client, threshold).await?;
+
+                    if include_actions {
+                        let actions = commands::prepared::generate_actions(
+                            &result,
+                            cli.read_write,
+                            cli.allow_primary,
+                        );
+                        result.actions = Some(actions);
+                    }
+
+                    if cli.json {
+                        commands::prepared::print_json(&result, timeouts)?;
+                    } else {
+                        commands::prepared::print_human(&result, cli.quiet);
+                    }
+
+                    if let Some(code) = exit_codes::for_finding(
+                        cli.json,
+                        result.overall_status == commands::prepared::PreparedStatus::Critical,
+                        result.overall_status == commands::prepared::PreparedStatus::Warning,
+                    ) {
+                        std::process::exit(code);
+                    }
+                }
+
+                DbaCommands::Persistence => {
+                    let result =
+                        commands::persistence::run_persistence(client, &alerts.ignore).await?;
+
+                    if cli.json {
+                        commands::persistence::print_json(&result, timeouts)?;
+                    } else {
+                        commands::persistence::print_human(&result, cli.quiet);
+                    }
+
+                    if let Some(code) = exit_codes::for_finding(
+                        cli.json,
+                        result.overall_status == commands::persistence::PersistenceStatus::Critical,
+                        result.overall_status == commands::persistence::PersistenceStatus::Warning,
+                    ) {
+                        std::process::exit(code);
+                    }
+                }
+
                 DbaCommands::Indexes {
                     missing_limit,
                     unused_limit,
+                    fk_missing,
+                    include_actions,
                 } => {
+                    let mut result = commands::indexes::run_indexes(
+                        client,
+                        missing_limit,
+                        unused_limit,
+                        fk_missing,
+                        &alerts.ignore,
+                    )
+                    .await?;
+
+                    if include_actions {
+                        let actions = commands::indexes::generate_fk_actions(
+                            &result,
+                            cli.read_write,
+                            cli.allow_primary,
+                        );
+                        result.actions = Some(actions);
+                    }
+
+                    if cli.json {
+                        commands::indexes::print_json(&result, timeouts)?;
+                    } else {
+                        commands::indexes::print_human(&result, cli.verbose);
+                    }
+                }
+
+                DbaCommands::StatsAge { threshold, limit } => {
+                    let result =
+                        commands::stats_age::run_stats_age(client, threshold, limit).await?;
+
+                    if cli.json {
+                        commands::stats_age::print_json(&result, timeouts)?;
+                    } else {
+                        commands::stats_age::print_human(&result, cli.quiet);
+                    }
+
+                    // Exit code based on status
+                    if let Some(code) = exit_codes::for_finding(
+                        cli.json,
+                        result.overall_status == commands::stats_age::StatsStatus::Critical,
+                        result.overall_status == commands::stats_age::StatsStatus::Warning,
+                    ) {
+                        std::process::exit(code);
+                    }
+                }
+
+                DbaCommands::Checkpoints => {
+                    let result = commands::checkpoints::run_checkpoints(client).await?;
+
+                    if cli.json {
+                        commands::checkpoints::print_json(&result, timeouts)?;
+                    } else {
+                        commands::checkpoints::print_human(&result, cli.quiet);
+                    }
+
+                    // Exit code based on status
+                    if let Some(code) = exit_codes::for_finding(
+                        cli.json,
+                        result.overall_status == commands::checkpoints::CheckpointStatus::Critical,
+                        result.overall_status == commands::checkpoints::CheckpointStatus::Warning,
+                    ) {
+                        std::process::exit(code);
+                    }
+                }
+
+                DbaCommands::Wal { sample } => {
                     let result =
-                        commands::indexes::run_indexes(client, missing_limit, unused_limit).await?;
+                        commands::wal::run_wal(client, sample.map(std::time::Duration::from_secs))
+                            .await?;
+
+                    if cli.json {
+                        commands::wal::print_json(&result, timeouts)?;
+                    } else {
+                        commands::wal::print_human(&result, cli.quiet);
+                    }
+
+                    // Exit code based on status
+                    if let Some(code) = exit_codes::for_finding(
+                        cli.json,
+                        result.overall_status == commands::wal::WalStatus::Critical,
+                        result.overall_status == commands::wal::WalStatus::Warning,
+                    ) {
+                        std::process::exit(code);
+                    }
+                }
+
+                DbaCommands::Io => {
+                    let result = commands::io::run_io(client).await?;
+
+                    if cli.json {
+                        commands::io::print_json(&result, timeouts)?;
+                    } else {
+                        commands::io::print_human(&result, cli.quiet);
+                    }
+
+                    if let Some(code) = exit_codes::for_finding(
+                        cli.json,
+                        result.overall_status == commands::io::IoStatus::Critical,
+                        result.overall_status == commands::io::IoStatus::Warning,
+                    ) {
+                        std::process::exit(code);
+                    }
+                }
 
+                DbaCommands::Top { interval, once } => {
                     if cli.json {
-                        commands::indexes::print_json(&result, timeouts)?;
-                    } else {
-                        commands::indexes::print_human(&result, cli.verbose);
+                        anyhow::bail!("dba top is interactive and has no JSON output");
                     }
+                    // Cancel/terminate keys follow the same gate as `dba locks --kill-idle --execute`
+                    commands::top::run_top(
+                        client,
+                        std::time::Duration::from_secs(interval),
+                        cli.read_write && cli.allow_primary,
+                        !cli.no_redact,
+                        once,
+                    )
+                    .await?;
                 }
 
-                DbaCommands::StatsAge { threshold, limit } => {
-                    let result =
-                        commands::stats_age::run_stats_age(client, threshold, limit).await?;
+                DbaCommands::Temp { limit } => {
+                    let result = commands::temp::run_temp(client, limit).await?;
 
                     if cli.json {
-                        commands::stats_age::print_json(&result, timeouts)?;
+                        commands::temp::print_json(&result, timeouts)?;
                     } else {
-                        commands::stats_age::print_human(&result, cli.quiet);
+                        commands::temp::print_human(&result, cli.quiet);
                     }
 
                     // Exit code based on status
                     if let Some(code) = exit_codes::for_finding(
                         cli.json,
-                        result.overall_status == commands::stats_age::StatsStatus::Critical,
-                        result.overall_status == commands::stats_age::StatsStatus::Warning,
+                        result.overall_status == commands::temp::TempStatus::Critical,
+                        result.overall_status == commands::temp::TempStatus::Warning,
                     ) {
                         std::process::exit(code);
                     }
                 }
 
-                DbaCommands::Checkpoints => {
-                    let result = commands::checkpoints::run_checkpoints(client).await?;
+                DbaCommands::Autovacuum { ref table, limit } => {
+                    let table = table
+                        .as_deref()
+                        .map(|t| t.split_once('.').unwrap_or(("public", t)));
+                    let result = commands::autovacuum::run_autovacuum(client, table, limit).await?;
 
                     if cli.json {
-                        commands::checkpoints::print_json(&result, timeouts)?;
+                        commands::autovacuum::print_json(&result, timeouts)?;
                     } else {
-                        commands::checkpoints::print_human(&result, cli.quiet);
+                        commands::autovacuum::print_human(&result, cli.quiet);
                     }
 
                     // Exit code based on status
                     if let Some(code) = exit_codes::for_finding(
                         cli.json,
-                        result.overall_status == commands::checkpoints::CheckpointStatus::Critical,
-                        result.overall_status == commands::checkpoints::CheckpointStatus::Warning,
+                        result.overall_status == commands::autovacuum::AutovacuumStatus::Critical,
+                        result.overall_status == commands::autovacuum::AutovacuumStatus::Warning,
                     ) {
                         std::process::exit(code);
                     }
@@ -2007,6 +3872,53 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         std::process::exit(code);
                     }
                 }
+
+                DbaCommands::Record { ref baseline } => {
+                    let result =
+                        commands::trend::run_record(client, baseline.as_deref(), &alerts).await?;
+
+                    if cli.json {
+                        commands::trend::print_record_json(&result, timeouts)?;
+                    } else {
+                        commands::trend::print_record_human(&result, cli.quiet);
+                    }
+                }
+
+                DbaCommands::Trend {
+                    ref metric,
+                    ref since,
+                    ref baseline,
+                } => match metric {
+                    Some(metric) => {
+                        let reference = match baseline {
+                            Some(name) => commands::trend::Reference::Baseline(name.clone()),
+                            None => {
+                                let window = diagnostic::parse_duration(since)
+                                    .with_context(|| format!("Invalid --since '{}'", since))?;
+                                commands::trend::Reference::Since {
+                                    label: since.clone(),
+                                    secs: window.as_secs(),
+                                }
+                            }
+                        };
+                        let result = commands::trend::run_trend(client, metric, &reference).await?;
+
+                        if cli.json {
+                            commands::trend::print_trend_json(&result, timeouts)?;
+                        } else {
+                            commands::trend::print_trend_human(&result, cli.quiet);
+                        }
+                    }
+                    None => {
+                        let metrics = commands::trend::list_metrics(client).await?;
+
+                        if cli.json {
+                            commands::trend::print_trend_json(&metrics, timeouts)?;
+                        } else {
+                            commands::trend::print_metrics_human(&metrics);
+                        }
+                    }
+                },
             }
         }
         Commands::Inspect { command } => {
@@ -2040,18 +3952,135 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     )
                     .await?;
                 }
+                InspectCommands::Find {
+                    pattern,
+                    regex,
+                    kinds,
+                    schema,
+                } => {
+                    commands::find(
+                        &conn_result.url,
+                        &pattern,
+                        regex,
+                        &kinds,
+                        schema.as_deref(),
+                        output,
+                    )
+                    .await?;
+                }
+                InspectCommands::Graph { schema, format } => {
+                    commands::schema_graph::schema_graph(
+                        &conn_result.url,
+                        &schema,
+                        &format,
+                        output,
+                    )
+                    .await?;
+                }
+                InspectCommands::Profile {
+                    object,
+                    sample,
+                    top,
+                } => {
+                    commands::profile::profile(&conn_result.url, &object, sample, top, output)
+                        .await?;
+                }
+                InspectCommands::Publications { name } => {
+                    commands::logical_replication::publications(
+                        &conn_result.url,
+                        name.as_deref(),
+                        output,
+                    )
+                    .await?;
+                }
+                InspectCommands::Subscriptions { name } => {
+                    commands::logical_replication::subscriptions(
+                        &conn_result.url,
+                        name.as_deref(),
+                        output,
+                    )
+                    .await?;
+                }
+                InspectCommands::Schema { name, limit } => {
+                    commands::describe_schema(&conn_result.url, name.as_deref(), limit, output)
+                        .await?;
+                }
+                InspectCommands::Sequence { object } => {
+                    let alerts = AlertsConfig::load(cli.alerts_config.as_deref())?;
+                    commands::describe_sequence(
+                        &conn_result.url,
+                        &object,
+                        alerts.sequences.or(commands::sequences::DEFAULT_THRESHOLDS),
+                        output,
+                    )
+                    .await?;
+                }
+                InspectCommands::Type { object } => {
+                    commands::describe_type(&conn_result.url, &object, output).await?;
+                }
                 InspectCommands::Diff {
                     from,
                     to,
                     schemas,
                     exclude_schemas,
+                    format,
+                    safe_only,
+                    include_privileges,
                 } => {
+                    if safe_only && format != "sql" {
+                        anyhow::bail!("--safe-only requires --format sql");
+                    }
                     let exit_code = commands::diff(
                         from.as_deref().unwrap_or(&conn_result.url),
                         &to,
                         output,
                         &schemas,
                         &exclude_schemas,
+                        &format,
+                        safe_only,
+                        include_privileges,
+                    )
+                    .await?;
+                    if exit_code != 0 {
+                        std::process::exit(exit_code);
+                    }
+                }
+                InspectCommands::Diff3 {
+                    base,
+                    ours,
+                    theirs,
+                    schemas,
+                    exclude_schemas,
+                    include_privileges,
+                } => {
+                    let exit_code = commands::diff3::diff3(
+                        &base,
+                        ours.as_deref().unwrap_or(&conn_result.url),
+                        &theirs,
+                        &schemas,
+                        &exclude_schemas,
+                        include_privileges,
+                        output,
+                    )
+                    .await?;
+                    if exit_code != 0 {
+                        std::process::exit(exit_code);
+                    }
+                }
+                InspectCommands::DataDiff {
+                    from,
+                    to,
+                    table,
+                    key,
+                    limit,
+                } => {
+                    let exit_code = commands::data_diff::data_diff(
+                        from.as_deref().unwrap_or(&conn_result.url),
+                        &to,
+                        &table,
+                        key.as_deref(),
+                        limit,
+                        output,
                     )
                     .await?;
                     if exit_code != 0 {
@@ -2072,17 +4101,62 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         commands::role_list(&conn_result.url, users, groups, cli.quiet).await?;
                     }
                 }
+                InspectCommands::SecurityAudit { sensitive } => {
+                    let mut patterns = config.sensitive_tables();
+                    patterns.extend(sensitive);
+                    commands::security_audit::security_audit(&conn_result.url, &patterns, output)
+                        .await?;
+                }
                 InspectCommands::Grants {
                     object,
                     schema,
                     role,
+                    matrix,
+                    format,
                 } => {
-                    commands::grants(
-                        &conn_result.url,
-                        object.as_deref(),
-                        schema.as_deref(),
-                        role.as_deref(),
+                    if matrix {
+                        let schema = schema.as_deref().unwrap_or_default();
+                        commands::grants_matrix(&conn_result.url, schema, &format, output).await?;
+                    } else {
+                        commands::grants(
+                            &conn_result.url,
+                            object.as_deref(),
+                            schema.as_deref(),
+                            role.as_deref(),
+                            cli.quiet,
+                        )
+                        .await?;
+                    }
+                }
+            }
+        }
+        Commands::Comment { command } => {
+            let config =
+                Config::load(cli.config_path.as_deref()).context("Failed to load configuration")?;
+            match command {
+                CommentCommands::Set {
+                    target,
+                    text,
+                    clear,
+                    dry_run,
+                } => {
+                    // Setting a comment is a write (implies --read-write), unless --dry-run
+                    let conn_result = connection::resolve_and_validate(
+                        &config,
+                        cli.database_url.as_deref(),
+                        cli.connection.as_deref(),
+                        cli.env_var.as_deref(),
+                        cli.allow_primary,
+                        cli.read_write || !dry_run,
                         cli.quiet,
+                    )?;
+                    let text = if clear { None } else { text };
+                    commands::comment_set(
+                        &conn_result.url,
+                        &target,
+                        text.as_deref(),
+                        dry_run,
+                        output,
                     )
                     .await?;
                 }
@@ -2209,11 +4283,15 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
             }
         }
         Commands::Snapshot { command } => {
-            let config =
+            let mut config =
                 Config::load(cli.config_path.as_deref()).context("Failed to load configuration")?;
-            let database_url = config
-                .get_database_url(cli.database_url.as_deref())
-                .context("DATABASE_URL not set. Use -d flag, set DATABASE_URL env var, or add to pgcrate.toml")?;
+            // -C also picks the connection's pg_dump/pg_restore
+            let (database_url, _) = config.resolve_database_url(
+                cli.database_url.as_deref(),
+                cli.connection.as_deref(),
+                cli.env_var.as_deref(),
+            )?;
+            config.active_connection = cli.connection.clone();
 
             match command {
                 SnapshotCommands::Save {
@@ -2223,10 +4301,13 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     format,
                     no_owner,
                     no_privileges,
+                    encrypt_keys,
+                    native,
                     dry_run,
                 } => {
                     let format_str = format
                         .as_deref()
+                        .or(native.then_some("plain"))
                         .or_else(|| {
                             config
                                 .snapshot
@@ -2243,6 +4324,8 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         format_str,
                         no_owner,
                         no_privileges,
+                        &encrypt_keys,
+                        native,
                         &config,
                         cli.quiet,
                         cli.verbose,
@@ -2256,8 +4339,18 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     dry_run,
                     to,
                     no_owner,
+                    as_new_db,
+                    ttl,
                 } => {
                     let target_url = to.as_deref().unwrap_or(&database_url);
+                    let new_db = as_new_db.map(|db| {
+                        db.unwrap_or_else(|| format!("pgcrate_tmp_{}", name.replace('-', "_")))
+                    });
+                    let ttl = ttl
+                        .as_deref()
+                        .map(diagnostic::parse_duration)
+                        .transpose()
+                        .context("Invalid --ttl")?;
                     commands::snapshot_restore(
                         target_url,
                         &name,
@@ -2267,15 +4360,71 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         yes,
                         dry_run,
                         no_owner,
+                        new_db.as_deref(),
+                        ttl,
+                    )
+                    .await?;
+                }
+                SnapshotCommands::Prune { dry_run } => {
+                    commands::snapshot_prune(&database_url, cli.quiet, dry_run).await?;
+                }
+                SnapshotCommands::Schedule {
+                    cron,
+                    prefix,
+                    keep,
+                    profile,
+                    format,
+                    now,
+                    systemd,
+                } => {
+                    let options = commands::ScheduleOptions {
+                        prefix: &prefix,
+                        keep,
+                        profile: profile.as_deref(),
+                        format: format.as_deref(),
+                    };
+                    commands::snapshot_schedule(
+                        &database_url,
+                        &config,
+                        cron.as_deref(),
+                        &options,
+                        now,
+                        systemd,
+                        cli.verbose,
                     )
                     .await?;
                 }
                 SnapshotCommands::List => {
                     commands::snapshot_list(&config, cli.quiet, cli.json)?;
                 }
+                SnapshotCommands::Preflight => {
+                    commands::snapshot_preflight(&database_url, &config, cli.quiet, cli.json)
+                        .await?;
+                }
                 SnapshotCommands::Info { name } => {
                     commands::snapshot_info(&name, &config, cli.quiet, cli.json)?;
                 }
+                SnapshotCommands::Diff {
+                    name,
+                    other,
+                    against_db: _,
+                    schemas,
+                    exclude_schemas,
+                } => {
+                    let exit_code = commands::snapshot_diff(
+                        &database_url,
+                        &name,
+                        other.as_deref(),
+                        &config,
+                        output,
+                        &schemas,
+                        &exclude_schemas,
+                    )
+                    .await?;
+                    if exit_code != 0 {
+                        std::process::exit(exit_code);
+                    }
+                }
                 SnapshotCommands::Delete { name, yes } => {
                     commands::snapshot_delete(&name, &config, cli.quiet, yes)?;
                 }
@@ -2304,6 +4453,7 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 AnonymizeCommands::Dump {
                     seed,
                     output: out,
+                    schema,
                     dry_run,
                 } => {
                     commands::anonymize_dump(
@@ -2312,12 +4462,41 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         cli.anonymize_config.as_deref(),
                         seed.as_deref(),
                         out.as_deref(),
+                        schema,
                         dry_run,
                         cli.quiet,
                         cli.verbose,
                     )
                     .await?;
                 }
+                AnonymizeCommands::Apply {
+                    seed,
+                    batch_size,
+                    dry_run,
+                    yes,
+                } => {
+                    commands::anonymize_apply(
+                        &database_url,
+                        &config,
+                        cli.anonymize_config.as_deref(),
+                        seed.as_deref(),
+                        batch_size,
+                        dry_run,
+                        yes,
+                        cli.quiet,
+                    )
+                    .await?;
+                }
+                AnonymizeCommands::Scan {
+                    output: out,
+                    sample,
+                    force,
+                } => {
+                    let out = out
+                        .or_else(|| cli.anonymize_config.clone())
+                        .unwrap_or_else(|| PathBuf::from("pgcrate.anonymize.toml"));
+                    commands::anonymize_scan(&database_url, &out, sample, force, cli.quiet).await?;
+                }
             }
         }
         Commands::Seed { command } => {
@@ -2325,7 +4504,12 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 Config::load(cli.config_path.as_deref()).context("Failed to load configuration")?;
 
             match command {
-                SeedCommands::Run { seeds, dry_run } => {
+                SeedCommands::Run {
+                    seeds,
+                    environment,
+                    dry_run,
+                    chunk_size,
+                } => {
                     // dry_run can run without a database connection, but if DATABASE_URL is
                     // available we use it to resolve target tables for clearer output.
                     let database_url = config
@@ -2334,7 +4518,16 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     if !dry_run && database_url.is_empty() {
                         anyhow::bail!("DATABASE_URL not set. Use -d flag, set DATABASE_URL env var, or add to pgcrate.toml");
                     }
-                    commands::seed_run(&database_url, &config, seeds, dry_run, cli.quiet).await?;
+                    commands::seed_run(
+                        &database_url,
+                        &config,
+                        seeds,
+                        environment.as_deref(),
+                        dry_run,
+                        chunk_size as usize,
+                        cli.quiet,
+                    )
+                    .await?;
                 }
                 SeedCommands::List => {
                     commands::seed_list(&config, cli.quiet)?;
@@ -2347,15 +4540,61 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         .unwrap_or_default();
                     commands::seed_validate(&database_url, &config, seeds, cli.quiet).await?;
                 }
-                SeedCommands::Diff { seeds } => {
+                SeedCommands::Diff { seeds, apply } => {
+                    let database_url = config
+                        .get_database_url(cli.database_url.as_deref())
+                        .context("DATABASE_URL not set. Use -d flag, set DATABASE_URL env var, or add to pgcrate.toml")?;
+                    commands::seed_diff(&database_url, &config, seeds, apply, cli.json, cli.quiet)
+                        .await?;
+                }
+                SeedCommands::Export {
+                    table,
+                    where_clause,
+                } => {
+                    let database_url = config
+                        .get_database_url(cli.database_url.as_deref())
+                        .context("DATABASE_URL not set. Use -d flag, set DATABASE_URL env var, or add to pgcrate.toml")?;
+                    commands::seed_export(
+                        &database_url,
+                        &config,
+                        &table,
+                        where_clause.as_deref(),
+                        cli.quiet,
+                    )
+                    .await?;
+                }
+                SeedCommands::Generate {
+                    table,
+                    rows,
+                    seed,
+                    insert,
+                    force,
+                } => {
                     let database_url = config
                         .get_database_url(cli.database_url.as_deref())
                         .context("DATABASE_URL not set. Use -d flag, set DATABASE_URL env var, or add to pgcrate.toml")?;
-                    commands::seed_diff(&database_url, &config, seeds, cli.quiet).await?;
+                    commands::seed_generate(
+                        &database_url,
+                        &config,
+                        &table,
+                        rows,
+                        &seed,
+                        insert,
+                        force,
+                        cli.quiet,
+                    )
+                    .await?;
                 }
             }
         }
-        Commands::Bootstrap { from, dry_run, yes } => {
+        Commands::Bootstrap {
+            from,
+            dry_run,
+            yes,
+            resume,
+            schema_only,
+            tables,
+        } => {
             let config =
                 Config::load(cli.config_path.as_deref()).context("Failed to load configuration")?;
             let database_url = config
@@ -2371,6 +4610,26 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 cli.verbose,
                 dry_run,
                 yes,
+                resume,
+                schema_only,
+                &tables,
+            )
+            .await?;
+        }
+        Commands::Subset {
+            from,
+            anchor,
+            filters,
+            output,
+            dry_run,
+        } => {
+            commands::subset(
+                &from,
+                &anchor,
+                &filters,
+                output.as_deref(),
+                dry_run,
+                cli.quiet,
             )
             .await?;
         }
@@ -2389,6 +4648,21 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
             commands::status(&conn_result.url, &config, output).await?;
         }
         cmd => {
+            // These create (and drop) a scratch database on the target server
+            let scratch_command = match &cmd {
+                Commands::Plan { .. } => Some("plan"),
+                Commands::Generate { delta: true, .. } => Some("generate --delta"),
+                _ => None,
+            };
+            if let Some(command) = scratch_command {
+                if !cli.read_write {
+                    anyhow::bail!(
+                        "{} requires --read-write: it creates a scratch database on the server",
+                        command
+                    );
+                }
+            }
+
             // Load config file for other commands
             let config =
                 Config::load(cli.config_path.as_deref()).context("Failed to load configuration")?;
@@ -2411,6 +4685,9 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                     dry_run,
                     schemas,
                     exclude_schemas,
+                    no_privileges,
+                    declarative,
+                    delta,
                 } => {
                     commands::generate(
                         &conn_result.url,
@@ -2421,14 +4698,42 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                         dry_run,
                         &schemas,
                         &exclude_schemas,
+                        no_privileges,
+                        declarative.as_deref(),
+                        delta,
+                    )
+                    .await?;
+                }
+                Commands::Plan {
+                    file,
+                    name,
+                    schemas,
+                    exclude_schemas,
+                    safe_only,
+                    no_privileges,
+                } => {
+                    let exit_code = commands::plan(
+                        &conn_result.url,
+                        &config,
+                        &file,
+                        name.as_deref(),
+                        &schemas,
+                        &exclude_schemas,
+                        safe_only,
+                        no_privileges,
+                        output,
                     )
                     .await?;
+                    if exit_code != 0 {
+                        std::process::exit(exit_code);
+                    }
                 }
                 Commands::Migrate { .. }
                 | Commands::Model { .. }
                 | Commands::Init { .. }
                 | Commands::Dba { .. }
                 | Commands::Inspect { .. }
+                | Commands::Comment { .. }
                 | Commands::Context
                 | Commands::Capabilities
                 | Commands::Sql { .. }
@@ -2438,6 +4743,7 @@ async fn run(cli: Cli, output: &Output) -> Result<()> {
                 | Commands::Anonymize { .. }
                 | Commands::Seed { .. }
                 | Commands::Bootstrap { .. }
+                | Commands::Subset { .. }
                 | Commands::Status => unreachable!(),
             }
         }
@@ -71,7 +71,7 @@ pub fn discover_migrations(dir: &Path) -> Result<Vec<Migration>, anyhow::Error>
 
 /// Parse migration filename to extract version and name.
 /// Expected format: 14-digit timestamp followed by `_name.sql`
-fn parse_migration_filename(filename: &str) -> Result<(String, String), anyhow::Error> {
+pub(crate) fn parse_migration_filename(filename: &str) -> Result<(String, String), anyhow::Error> {
     if filename.ends_with(".up.sql") || filename.ends_with(".down.sql") {
         bail!("Invalid migration filename: {}. Single-file migrations must end with .sql and contain both sections.",
             filename
@@ -116,6 +116,15 @@ fn parse_migration_filename(filename: &str) -> Result<(String, String), anyhow::
 /// Parse a migration file into up/down SQL sections.
 fn parse_migration_file(path: &Path) -> Result<(String, Option<String>), anyhow::Error> {
     let content = fs::read_to_string(path)?;
+    parse_migration_content(&content, &path.display())
+}
+
+/// Parse migration file content into up/down SQL sections.
+/// `path` is only used to label error messages.
+pub(crate) fn parse_migration_content(
+    content: &str,
+    path: &dyn std::fmt::Display,
+) -> Result<(String, Option<String>), anyhow::Error> {
     let lines: Vec<&str> = content.lines().collect();
 
     let mut up_idx: Option<usize> = None;
@@ -127,7 +136,7 @@ fn parse_migration_file(path: &Path) -> Result<(String, Option<String>), anyhow:
             if up_idx.is_some() {
                 bail!(
                     "{}: multiple `-- up` markers found. Each migration must have exactly one.",
-                    path.display()
+                    path
                 );
             }
             up_idx = Some(i);
@@ -135,7 +144,7 @@ fn parse_migration_file(path: &Path) -> Result<(String, Option<String>), anyhow:
             if down_idx.is_some() {
                 bail!(
                     "{}: multiple `-- down` markers found. Use one down section per file.",
-                    path.display()
+                    path
                 );
             }
             down_idx = Some(i);
@@ -145,7 +154,7 @@ fn parse_migration_file(path: &Path) -> Result<(String, Option<String>), anyhow:
     let up_idx = up_idx.ok_or_else(|| {
         anyhow::anyhow!(
             "{}: missing `-- up` marker. Add `-- up` and `-- down` markers to the file.",
-            path.display()
+            path
         )
     })?;
 
@@ -153,7 +162,7 @@ fn parse_migration_file(path: &Path) -> Result<(String, Option<String>), anyhow:
         if down_marker < up_idx {
             bail!(
                 "{}: `-- down` appears before `-- up`. Place `-- up` first.",
-                path.display()
+                path
             );
         }
     }
@@ -164,7 +173,7 @@ fn parse_migration_file(path: &Path) -> Result<(String, Option<String>), anyhow:
         if !trimmed.is_empty() && !trimmed.starts_with("--") {
             bail!(
                 "{}: only comments or blank lines are allowed before the `-- up` marker.",
-                path.display()
+                path
             );
         }
     }
@@ -188,6 +197,146 @@ fn section_is_effectively_empty(section: &str) -> bool {
     })
 }
 
+/// Statement prefixes PostgreSQL refuses to run inside a transaction block,
+/// paired with the label shown to the user.
+const NON_TRANSACTIONAL_PATTERNS: &[(&str, &str)] = &[
+    (
+        r"^create\s+(unique\s+)?index\s+concurrently\b",
+        "CREATE INDEX CONCURRENTLY",
+    ),
+    (r"^drop\s+index\s+concurrently\b", "DROP INDEX CONCURRENTLY"),
+    (r"^reindex\b.*\bconcurrently\b", "REINDEX CONCURRENTLY"),
+    (r"^vacuum\b", "VACUUM"),
+    (r"^(create|drop)\s+database\b", "CREATE/DROP DATABASE"),
+    (r"^(create|drop)\s+tablespace\b", "CREATE/DROP TABLESPACE"),
+    (r"^alter\s+system\b", "ALTER SYSTEM"),
+    (
+        r"^(create|drop)\s+subscription\b",
+        "CREATE/DROP SUBSCRIPTION",
+    ),
+    (
+        r"^(begin|commit|rollback|end|abort|start\s+transaction)\b",
+        "transaction control (BEGIN/COMMIT/ROLLBACK)",
+    ),
+];
+
+/// Find statements in `sql` that cannot run inside a transaction block.
+///
+/// Comments, string literals, and dollar-quoted bodies are ignored so that
+/// `BEGIN ... END` inside function bodies or DO blocks is not flagged.
+/// Returns the distinct labels in first-seen order.
+pub fn non_transactional_statements(sql: &str) -> Vec<&'static str> {
+    let patterns: Vec<(regex::Regex, &str)> = NON_TRANSACTIONAL_PATTERNS
+        .iter()
+        .map(|(re, label)| {
+            (
+                regex::RegexBuilder::new(re)
+                    .case_insensitive(true)
+                    .dot_matches_new_line(true)
+                    .build()
+                    .expect("valid pattern"),
+                *label,
+            )
+        })
+        .collect();
+
+    let mut found = Vec::new();
+    for statement in strip_comments_and_literals(sql).split(';') {
+        let statement = statement.trim();
+        for (re, label) in &patterns {
+            if re.is_match(statement) && !found.contains(label) {
+                found.push(*label);
+            }
+        }
+    }
+    found
+}
+
+/// Replace comments, string literals, and dollar-quoted bodies with spaces,
+/// leaving only top-level SQL tokens (including quoted identifiers) and
+/// statement separators.
+pub(crate) fn strip_comments_and_literals(sql: &str) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = String::with_capacity(sql.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c == '-' && next == Some('-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            let mut depth = 0;
+            while i < chars.len() {
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    depth += 1;
+                    i += 2;
+                } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+            out.push(' ');
+        } else if c == '\'' || c == '"' {
+            let start = i;
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == c {
+                    // Doubled quote is an escaped quote
+                    if chars.get(i + 1) == Some(&c) {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+            if c == '"' {
+                // Quoted identifiers are kept so callers can still see object names
+                out.extend(&chars[start..i.min(chars.len())]);
+            } else {
+                out.push(' ');
+            }
+        } else if c == '$' {
+            // Dollar quote tag: $$ or $tag$
+            let tag_end = chars[i + 1..]
+                .iter()
+                .position(|ch| !(ch.is_alphanumeric() || *ch == '_'))
+                .map(|p| i + 1 + p);
+            match tag_end {
+                Some(end) if chars[end] == '$' => {
+                    let tag: String = chars[i..=end].iter().collect();
+                    let rest: String = chars[end + 1..].iter().collect();
+                    let close = rest.find(&tag).map(|p| rest[..p].chars().count());
+                    i = match close {
+                        Some(n) => end + 1 + n + tag.chars().count(),
+                        None => chars.len(),
+                    };
+                    out.push(' ');
+                }
+                _ => {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+
+    out
+}
+
 /// Load migrations (alias for discover_migrations for callers that expect the older name).
 pub fn load_migrations(dir: &Path) -> Result<Vec<Migration>, anyhow::Error> {
     discover_migrations(dir)
@@ -323,6 +472,39 @@ SELECT 2;",
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_non_transactional_detects_concurrent_index() {
+        let sql = "CREATE TABLE t (id int);\ncreate unique index concurrently idx on t (id);";
+        assert_eq!(
+            non_transactional_statements(sql),
+            vec!["CREATE INDEX CONCURRENTLY"]
+        );
+    }
+
+    #[test]
+    fn test_non_transactional_detects_transaction_control() {
+        let sql = "BEGIN;\nCREATE TABLE t (id int);\nCOMMIT;";
+        assert_eq!(
+            non_transactional_statements(sql),
+            vec!["transaction control (BEGIN/COMMIT/ROLLBACK)"]
+        );
+    }
+
+    #[test]
+    fn test_non_transactional_ignores_function_bodies_and_comments() {
+        let sql = "\
+-- VACUUM later
+CREATE FUNCTION f() RETURNS void AS $body$
+BEGIN
+    PERFORM 1;
+END;
+$body$ LANGUAGE plpgsql;
+DO $$ BEGIN RAISE NOTICE 'commit;'; END $$;
+/* CREATE INDEX CONCURRENTLY x ON t (id); */
+INSERT INTO notes VALUES ('vacuum; begin');";
+        assert!(non_transactional_statements(sql).is_empty());
+    }
+
     #[test]
     fn test_error_on_duplicate_version() {
         use std::fs;
@@ -0,0 +1,113 @@
+//! Schema clones: expose models built in one schema (e.g. production
+//! `analytics`) under another (`analytics_dev`) so downstream work can start
+//! from real data without rebuilding the DAG.
+//!
+//! Clones are views over the original relations (zero-copy). With copying,
+//! relations holding data are instead copied with `CREATE TABLE ... AS`.
+
+use crate::sql::quote_ident;
+
+use super::Relation;
+
+/// How a relation is cloned
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CloneKind {
+    /// `CREATE VIEW ... AS SELECT * FROM <original>`
+    View,
+    /// `CREATE TABLE ... AS TABLE <original>`
+    Copy,
+}
+
+impl CloneKind {
+    /// View clones for views; copies for tables and materialized views when requested
+    pub fn for_relkind(relkind: &str, copy: bool) -> CloneKind {
+        if copy && relkind != "v" {
+            CloneKind::Copy
+        } else {
+            CloneKind::View
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CloneKind::View => "view",
+            CloneKind::Copy => "copy",
+        }
+    }
+}
+
+/// DROP statement for an existing relation of the given pg_class relkind
+fn drop_sql(rel: &Relation, relkind: &str) -> String {
+    let kind = match relkind {
+        "v" => "VIEW",
+        "m" => "MATERIALIZED VIEW",
+        "f" => "FOREIGN TABLE",
+        _ => "TABLE",
+    };
+    format!(
+        "DROP {} {}.{} CASCADE",
+        kind,
+        quote_ident(&rel.schema),
+        quote_ident(&rel.name)
+    )
+}
+
+/// SQL that (re)creates `target` as a clone of `original`, replacing whatever
+/// relation `target` currently is (`existing` relkind)
+pub fn clone_sql(
+    original: &Relation,
+    target: &Relation,
+    kind: CloneKind,
+    existing: Option<&str>,
+) -> String {
+    let from = format!(
+        "{}.{}",
+        quote_ident(&original.schema),
+        quote_ident(&original.name)
+    );
+    let to = format!(
+        "{}.{}",
+        quote_ident(&target.schema),
+        quote_ident(&target.name)
+    );
+    let create = match kind {
+        CloneKind::View => format!("CREATE VIEW {} AS SELECT * FROM {}", to, from),
+        CloneKind::Copy => format!("CREATE TABLE {} AS TABLE {}", to, from),
+    };
+    match existing {
+        Some(relkind) => format!(
+            "BEGIN;\n{};\n{};\nCOMMIT;",
+            drop_sql(target, relkind),
+            create
+        ),
+        None => format!("{};", create),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone_sql() {
+        let original = Relation::parse("analytics.users").unwrap();
+        let target = Relation::parse("analytics_dev.users").unwrap();
+        assert_eq!(
+            clone_sql(&original, &target, CloneKind::View, None),
+            "CREATE VIEW \"analytics_dev\".\"users\" AS SELECT * FROM \"analytics\".\"users\";"
+        );
+        assert_eq!(
+            clone_sql(&original, &target, CloneKind::Copy, Some("v")),
+            "BEGIN;\nDROP VIEW \"analytics_dev\".\"users\" CASCADE;\n\
+             CREATE TABLE \"analytics_dev\".\"users\" AS TABLE \"analytics\".\"users\";\nCOMMIT;"
+        );
+    }
+
+    #[test]
+    fn test_clone_kind_for_relkind() {
+        assert_eq!(CloneKind::for_relkind("r", false), CloneKind::View);
+        assert_eq!(CloneKind::for_relkind("r", true), CloneKind::Copy);
+        assert_eq!(CloneKind::for_relkind("m", true), CloneKind::Copy);
+        assert_eq!(CloneKind::for_relkind("v", true), CloneKind::View);
+    }
+}
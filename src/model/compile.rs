@@ -1,8 +1,10 @@
 use anyhow::{Context, Result};
+use regex::Regex;
 use std::fs;
 use std::path::PathBuf;
 
-use super::{Materialized, Model, Project};
+use super::{get_ephemeral_deps, Materialized, Model, Project, Relation};
+use crate::sql::quote_ident;
 
 /// Output from compiling a model
 #[derive(Debug)]
@@ -23,6 +25,25 @@ pub fn compile_model(project: &Project, model: &Model) -> Result<CompileOutput>
                 model.id, uk, body
             )
         }
+        Materialized::Ephemeral => format!(
+            "-- ephemeral model: {} (inlined into downstream models as {})\n{};\n",
+            model.id,
+            ephemeral_cte_name(&model.id),
+            body
+        ),
+        Materialized::Matview => {
+            let mut sql = format!("CREATE MATERIALIZED VIEW {} AS\n{};\n", model.id, body);
+            if let Some(index_sql) = generate_unique_index_sql(model) {
+                sql.push_str(&format!("{};\n", index_sql));
+            }
+            sql
+        }
+        Materialized::Snapshot => format!(
+            "-- snapshot model: {} (unique_key: {})\n-- Note: later runs close changed rows and insert new versions at runtime in execute.rs\n{};\n",
+            model.id,
+            model.header.unique_key.join(", "),
+            generate_create_sql(model)
+        ),
     };
 
     let out_dir = project.root.join("target/compiled").join(&model.id.schema);
@@ -46,16 +67,220 @@ pub fn generate_create_sql(model: &Model) -> String {
         Materialized::Table | Materialized::Incremental => {
             format!("CREATE TABLE {} AS\n{}", model.id, body)
         }
+        Materialized::Ephemeral => format!(
+            "-- ephemeral model {} is not created; it is inlined as {}\n{}",
+            model.id,
+            ephemeral_cte_name(&model.id),
+            body
+        ),
+        Materialized::Matview => {
+            format!("CREATE MATERIALIZED VIEW {} AS\n{}", model.id, body)
+        }
+        Materialized::Snapshot => {
+            let valid_from = model
+                .header
+                .snapshot
+                .as_ref()
+                .map_or_else(|| "now()".to_string(), |c| c.valid_from_sql("s"));
+            format!(
+                "CREATE TABLE {} AS\nSELECT s.*, {} AS valid_from, NULL::timestamptz AS valid_to, true AS is_current\nFROM (\n{}\n) AS s",
+                model.id, valid_from, body
+            )
+        }
     }
 }
 
+/// Generate the unique index for a materialized view model (enables concurrent refresh)
+pub fn generate_unique_index_sql(model: &Model) -> Option<String> {
+    if model.header.unique_index.is_empty() {
+        return None;
+    }
+    let cols: Vec<String> = model
+        .header
+        .unique_index
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect();
+    Some(format!(
+        "CREATE UNIQUE INDEX {} ON {}.{} ({})",
+        quote_ident(&format!("{}_unique_idx", model.id.name)),
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name),
+        cols.join(", ")
+    ))
+}
+
+/// Generate the refresh statement for an existing materialized view model.
+/// Uses CONCURRENTLY when a unique index is configured so readers aren't blocked.
+pub fn generate_refresh_sql(model: &Model) -> String {
+    let concurrently = if model.header.unique_index.is_empty() {
+        ""
+    } else {
+        "CONCURRENTLY "
+    };
+    format!(
+        "REFRESH MATERIALIZED VIEW {}{}.{}",
+        concurrently,
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name)
+    )
+}
+
 /// Generate full SQL to execute a model (drops + create, for compile output and display)
 pub fn generate_run_sql(model: &Model) -> String {
     let create_sql = generate_create_sql(model);
-    format!(
-        "DROP VIEW IF EXISTS {} CASCADE;\nDROP TABLE IF EXISTS {} CASCADE;\n{}",
-        model.id, model.id, create_sql
-    )
+    match model.header.materialized {
+        // Snapshot tables hold history and are never dropped
+        Materialized::Ephemeral | Materialized::Snapshot => create_sql,
+        Materialized::Matview => {
+            let mut sql = format!(
+                "DROP MATERIALIZED VIEW IF EXISTS {} CASCADE;\n{}",
+                model.id, create_sql
+            );
+            if let Some(index_sql) = generate_unique_index_sql(model) {
+                sql.push_str(&format!(";\n{}", index_sql));
+            }
+            sql
+        }
+        _ => format!(
+            "DROP VIEW IF EXISTS {} CASCADE;\nDROP TABLE IF EXISTS {} CASCADE;\n{}",
+            model.id, model.id, create_sql
+        ),
+    }
+}
+
+/// CTE name used when an ephemeral model is inlined into a downstream model
+pub fn ephemeral_cte_name(rel: &Relation) -> String {
+    format!("__ephemeral_{}_{}", rel.schema, rel.name)
+}
+
+/// Return a copy of the project where each model's SQL has its ephemeral
+/// dependencies inlined as CTEs, with references rewritten to the CTE names.
+/// Lint commands use the original project so they still see the declared refs.
+pub fn inline_ephemeral(project: &Project) -> Project {
+    let mut out = project.clone();
+    for (rel, model) in out.models.iter_mut() {
+        if !model.header.materialized.is_materialized() {
+            continue;
+        }
+        let ephemeral = get_ephemeral_deps(project, rel);
+        if ephemeral.is_empty() {
+            continue;
+        }
+
+        let ctes: Vec<String> = ephemeral
+            .iter()
+            .map(|dep| {
+                let body = project.models[dep].body_sql.trim().trim_end_matches(';');
+                format!(
+                    "{} AS (\n{}\n)",
+                    ephemeral_cte_name(dep),
+                    replace_relation_refs(body.trim(), &ephemeral)
+                )
+            })
+            .collect();
+        let inline = |sql: &str| prepend_ctes(&replace_relation_refs(sql, &ephemeral), &ctes);
+
+        if model.base_sql.is_none() {
+            model.body_sql = inline(&model.body_sql);
+        }
+        model.base_sql = model.base_sql.as_deref().map(inline);
+        model.incremental_sql = model.incremental_sql.as_deref().map(inline);
+    }
+    out
+}
+
+/// Add CTEs to the front of a query, merging into an existing WITH clause.
+fn prepend_ctes(sql: &str, ctes: &[String]) -> String {
+    let with_re = Regex::new(r"(?is)^\s*with(\s+recursive)?\s").unwrap();
+    let list = ctes.join(",\n");
+    match with_re.find(sql) {
+        Some(m) => format!("{}{},\n{}", &sql[..m.end()], list, &sql[m.end()..]),
+        None => format!("WITH {}\n{}", list, sql.trim_start()),
+    }
+}
+
+/// Replace `schema.name` references to the given relations with their CTE names,
+/// skipping comments, string literals, and dollar-quoted bodies.
+fn replace_relation_refs(sql: &str, rels: &[Relation]) -> String {
+    let patterns: Vec<(Regex, String)> = rels
+        .iter()
+        .map(|r| {
+            let re = Regex::new(&format!(
+                r#"(?i)^"?{}"?\s*\.\s*"?{}"?"#,
+                regex::escape(&r.schema),
+                regex::escape(&r.name)
+            ))
+            .unwrap();
+            (re, ephemeral_cte_name(r))
+        })
+        .collect();
+    let dollar_re = Regex::new(r"^\$([A-Za-z_][A-Za-z0-9_]*)?\$").unwrap();
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    let mut out = String::with_capacity(sql.len());
+    let mut i = 0;
+    while i < sql.len() {
+        let rest = &sql[i..];
+
+        // Copy comments and literals through unchanged
+        let skip = if rest.starts_with("--") {
+            Some(rest.find('\n').unwrap_or(rest.len()))
+        } else if rest.starts_with("/*") {
+            Some(rest.find("*/").map(|n| n + 2).unwrap_or(rest.len()))
+        } else if rest.starts_with('\'') {
+            let mut end = rest.len();
+            let mut chars = rest.char_indices().skip(1).peekable();
+            while let Some((j, c)) = chars.next() {
+                if c == '\'' {
+                    if chars.peek().map(|&(_, n)| n) == Some('\'') {
+                        chars.next();
+                    } else {
+                        end = j + 1;
+                        break;
+                    }
+                }
+            }
+            Some(end)
+        } else if let Some(m) = dollar_re.find(rest) {
+            let tag = m.as_str();
+            Some(
+                rest[tag.len()..]
+                    .find(tag)
+                    .map(|n| tag.len() + n + tag.len())
+                    .unwrap_or(rest.len()),
+            )
+        } else {
+            None
+        };
+        if let Some(len) = skip {
+            out.push_str(&rest[..len]);
+            i += len;
+            continue;
+        }
+
+        let at_boundary = !out
+            .chars()
+            .next_back()
+            .is_some_and(|c| is_ident_char(c) || c == '.');
+        if at_boundary {
+            let matched = patterns.iter().find_map(|(re, name)| {
+                let m = re.find(rest)?;
+                let next = rest[m.end()..].chars().next();
+                (!next.is_some_and(is_ident_char)).then_some((m.end(), name))
+            });
+            if let Some((len, name)) = matched {
+                out.push_str(name);
+                i += len;
+                continue;
+            }
+        }
+
+        let c = rest.chars().next().unwrap();
+        out.push(c);
+        i += c.len_utf8();
+    }
+    out
 }
 
 #[cfg(test)]
@@ -76,10 +301,21 @@ mod tests {
                 deps: Vec::new(),
                 unique_key: Vec::new(),
                 tests: Vec::new(),
+                test_configs: Vec::new(),
                 tags: Vec::new(),
                 watermark: None,
                 lookback: None,
                 incremental_filter: None,
+                unique_index: Vec::new(),
+                strategy: None,
+                partition_by: Vec::new(),
+                on_schema_change: None,
+                pre_hook: Vec::new(),
+                post_hook: Vec::new(),
+                grants: Default::default(),
+                snapshot: None,
+                columns: Vec::new(),
+                description: None,
             },
             body_sql: body.into(),
             base_sql: None,
@@ -119,6 +355,124 @@ mod tests {
         assert!(sql.contains("CREATE TABLE"));
     }
 
+    fn add_model(project: &mut Project, id: &str, mat: Materialized, deps: &[&str], body: &str) {
+        let mut model = make_model(mat, body);
+        model.id = Relation::parse(id).unwrap();
+        model.header.deps = deps.iter().map(|d| Relation::parse(d).unwrap()).collect();
+        project.models.insert(model.id.clone(), model);
+    }
+
+    fn empty_project() -> Project {
+        Project {
+            root: PathBuf::new(),
+            models: std::collections::HashMap::new(),
+            sources: std::collections::HashSet::new(),
+            exposures: Vec::new(),
+            selectors: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_inline_ephemeral_adds_cte_and_rewrites_refs() {
+        let mut project = empty_project();
+        add_model(
+            &mut project,
+            "staging.paid_orders",
+            Materialized::Ephemeral,
+            &["app.orders"],
+            "SELECT * FROM app.orders WHERE status = 'paid';",
+        );
+        add_model(
+            &mut project,
+            "marts.revenue",
+            Materialized::Table,
+            &["staging.paid_orders"],
+            "SELECT sum(o.total) FROM staging.paid_orders o -- staging.paid_orders\nWHERE 'staging.paid_orders' <> ''",
+        );
+
+        let inlined = inline_ephemeral(&project);
+        let sql = &inlined.models[&Relation::parse("marts.revenue").unwrap()].body_sql;
+        assert_eq!(
+            sql,
+            "WITH __ephemeral_staging_paid_orders AS (\n\
+             SELECT * FROM app.orders WHERE status = 'paid'\n)\n\
+             SELECT sum(o.total) FROM __ephemeral_staging_paid_orders o -- staging.paid_orders\n\
+             WHERE 'staging.paid_orders' <> ''"
+        );
+        // The ephemeral model itself is left as-is
+        let eph = &inlined.models[&Relation::parse("staging.paid_orders").unwrap()];
+        assert!(eph.body_sql.starts_with("SELECT * FROM app.orders"));
+    }
+
+    #[test]
+    fn test_inline_ephemeral_chains_and_merges_with_clause() {
+        let mut project = empty_project();
+        add_model(
+            &mut project,
+            "s.a",
+            Materialized::Ephemeral,
+            &[],
+            "SELECT 1 AS x",
+        );
+        add_model(
+            &mut project,
+            "s.b",
+            Materialized::Ephemeral,
+            &["s.a"],
+            "SELECT x FROM \"s\".\"a\"",
+        );
+        add_model(
+            &mut project,
+            "m.out",
+            Materialized::View,
+            &["s.b"],
+            "with t as (select * from s.b) SELECT * FROM t JOIN s.bb ON true",
+        );
+
+        let inlined = inline_ephemeral(&project);
+        let sql = &inlined.models[&Relation::parse("m.out").unwrap()].body_sql;
+        assert_eq!(
+            sql,
+            "with __ephemeral_s_a AS (\nSELECT 1 AS x\n),\n\
+             __ephemeral_s_b AS (\nSELECT x FROM __ephemeral_s_a\n),\n\
+             t as (select * from __ephemeral_s_b) SELECT * FROM t JOIN s.bb ON true"
+        );
+    }
+
+    #[test]
+    fn test_run_sql_ephemeral_has_no_ddl() {
+        let model = make_model(Materialized::Ephemeral, "SELECT 1");
+        let sql = generate_run_sql(&model);
+        assert!(!sql.contains("DROP"));
+        assert!(!sql.contains("CREATE"));
+        assert!(sql.contains("__ephemeral_analytics_users"));
+    }
+
+    #[test]
+    fn test_run_sql_materialized_view_with_unique_index() {
+        let mut model = make_model(Materialized::Matview, "SELECT 1 AS id");
+        model.header.unique_index = vec!["id".into()];
+        let sql = generate_run_sql(&model);
+        assert!(sql.contains("DROP MATERIALIZED VIEW IF EXISTS analytics.users CASCADE"));
+        assert!(sql.contains("CREATE MATERIALIZED VIEW analytics.users AS\nSELECT 1 AS id"));
+        assert!(sql
+            .ends_with(r#"CREATE UNIQUE INDEX "users_unique_idx" ON "analytics"."users" ("id")"#));
+        assert_eq!(
+            generate_refresh_sql(&model),
+            r#"REFRESH MATERIALIZED VIEW CONCURRENTLY "analytics"."users""#
+        );
+    }
+
+    #[test]
+    fn test_refresh_sql_without_unique_index_is_blocking() {
+        let model = make_model(Materialized::Matview, "SELECT 1");
+        assert_eq!(
+            generate_refresh_sql(&model),
+            r#"REFRESH MATERIALIZED VIEW "analytics"."users""#
+        );
+        assert!(generate_unique_index_sql(&model).is_none());
+    }
+
     #[test]
     fn test_run_sql_strips_semicolon() {
         let model = make_model(Materialized::View, "SELECT 1;");
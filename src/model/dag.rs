@@ -1,13 +1,29 @@
 use anyhow::{anyhow, bail, Context, Result};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs;
 use std::path::Path;
 use walkdir::WalkDir;
 
-use super::{parse_model_file, Model, Project, Relation};
+use super::exposure::load_exposures;
+use super::fdw::foreign_source_relations;
+use super::parse::normalize_privilege;
+use super::select::load_selector_definitions;
+use super::sources::load_sources;
+use super::{parse_model_source, template, Model, Project, Relation};
 use crate::config::Config;
 
-/// Load a project from filesystem using config for paths and sources.
+/// Load a project from filesystem using config for paths, sources, and variables.
 pub fn load_project(root: &Path, config: &Config) -> Result<Project> {
+    load_project_with_vars(root, config, &[])
+}
+
+/// Load a project, expanding `${macro:...}` calls from models/macros/ and
+/// `${var:name}` placeholders from `[model.vars]` (command-line overrides win).
+pub fn load_project_with_vars(
+    root: &Path,
+    config: &Config,
+    overrides: &[(String, String)],
+) -> Result<Project> {
     let models_dir = root.join(config.models_dir());
     if !models_dir.is_dir() {
         bail!(
@@ -16,15 +32,34 @@ pub fn load_project(root: &Path, config: &Config) -> Result<Project> {
         );
     }
 
-    let sources: HashSet<Relation> = config
+    let mut sources: HashSet<Relation> = config
         .model_sources()
         .iter()
         .map(|s| Relation::parse(s))
         .collect::<Result<_>>()
         .context("parse sources from config")?;
+    sources.extend(foreign_source_relations(config)?);
+    sources.extend(load_sources(&models_dir)?);
+
+    let macros_dir = models_dir.join(template::MACROS_DIR);
+    let mut ctx = template::TemplateContext {
+        vars: config.model_vars(),
+        macros: template::load_macros(&macros_dir)?,
+    };
+    ctx.vars.extend(overrides.iter().cloned());
+
+    let mut default_grants: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (privilege, roles) in config.model_grants() {
+        let privilege = normalize_privilege(&privilege).context("[model.grants]")?;
+        default_grants.insert(privilege, roles);
+    }
 
     let mut models = HashMap::<Relation, Model>::new();
     for entry in WalkDir::new(&models_dir).into_iter().filter_map(|e| e.ok()) {
+        // Macro definitions live alongside models but are not models themselves
+        if entry.path().starts_with(&macros_dir) {
+            continue;
+        }
         if !entry.file_type().is_file() {
             continue;
         }
@@ -33,7 +68,21 @@ pub fn load_project(root: &Path, config: &Config) -> Result<Project> {
         }
 
         let rel = model_id_from_path(&models_dir, entry.path())?;
-        let parsed = parse_model_file(entry.path())?;
+        let text = fs::read_to_string(entry.path())
+            .with_context(|| format!("read model: {}", entry.path().display()))?;
+        let text = template::render(&text, &ctx)
+            .with_context(|| format!("render model: {}", entry.path().display()))?;
+        let mut parsed = parse_model_source(&text, entry.path())?;
+        if parsed.header.materialized.is_materialized() {
+            // Header grants override [model.grants] per privilege
+            for (privilege, roles) in &default_grants {
+                parsed
+                    .header
+                    .grants
+                    .entry(privilege.clone())
+                    .or_insert_with(|| roles.clone());
+            }
+        }
         let model = Model {
             id: rel.clone(),
             path: entry.path().to_path_buf(),
@@ -47,10 +96,15 @@ pub fn load_project(root: &Path, config: &Config) -> Result<Project> {
         }
     }
 
+    let exposures = load_exposures(&models_dir, &models, &sources)?;
+    let selectors = load_selector_definitions(&models_dir)?;
+
     Ok(Project {
         root: root.to_path_buf(),
         models,
         sources,
+        exposures,
+        selectors,
     })
 }
 
@@ -214,6 +268,37 @@ pub fn get_upstream_order(project: &Project, target: &Relation) -> Result<Vec<Re
     Ok(order)
 }
 
+/// Ephemeral models a model depends on (directly or through other ephemeral
+/// models), ordered so each one comes after the ephemeral models it uses.
+pub fn get_ephemeral_deps(project: &Project, target: &Relation) -> Vec<Relation> {
+    fn visit(
+        project: &Project,
+        rel: &Relation,
+        visited: &mut HashSet<Relation>,
+        order: &mut Vec<Relation>,
+    ) {
+        let Some(model) = project.models.get(rel) else {
+            return;
+        };
+        for dep in &model.header.deps {
+            let is_ephemeral = project
+                .models
+                .get(dep)
+                .is_some_and(|m| !m.header.materialized.is_materialized());
+            if is_ephemeral && visited.insert(dep.clone()) {
+                visit(project, dep, visited, order);
+                order.push(dep.clone());
+            }
+        }
+    }
+
+    let mut visited: HashSet<Relation> = HashSet::new();
+    visited.insert(target.clone());
+    let mut order: Vec<Relation> = Vec::new();
+    visit(project, target, &mut visited, &mut order);
+    order
+}
+
 /// Get execution order for a model and all its downstream dependents.
 /// Returns models in DAG order (the target model first, then dependents).
 pub fn get_downstream_order(project: &Project, target: &Relation) -> Result<Vec<Relation>> {
@@ -271,6 +356,8 @@ mod tests {
         let mut project = Project {
             root: PathBuf::new(),
             sources: HashSet::new(),
+            exposures: Vec::new(),
+            selectors: Default::default(),
             models: HashMap::new(),
         };
         for (name, deps) in models {
@@ -280,10 +367,21 @@ mod tests {
                 deps: deps.iter().map(|d| Relation::parse(d).unwrap()).collect(),
                 unique_key: Vec::new(),
                 tests: Vec::new(),
+                test_configs: Vec::new(),
                 tags: Vec::new(),
                 watermark: None,
                 lookback: None,
                 incremental_filter: None,
+                unique_index: Vec::new(),
+                strategy: None,
+                partition_by: Vec::new(),
+                on_schema_change: None,
+                pre_hook: Vec::new(),
+                post_hook: Vec::new(),
+                grants: Default::default(),
+                snapshot: None,
+                columns: Vec::new(),
+                description: None,
             };
             project.models.insert(
                 rel.clone(),
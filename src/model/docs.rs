@@ -0,0 +1,377 @@
+//! Static HTML documentation for `model docs --format html`.
+//!
+//! Pages are self-contained (inline CSS, DAG drawn as inline SVG) so the
+//! `target/docs/` directory can be opened from disk or served as-is.
+
+use anyhow::Result;
+use std::fmt::Write;
+
+use super::{topo_sort_layers, Model, Project, Relation, TestSeverity};
+
+/// A column shown on a model page: from the database when reachable,
+/// otherwise from the model's `columns:` contract
+#[derive(Clone, Debug)]
+pub struct DocColumn {
+    pub name: String,
+    pub data_type: String,
+    /// Unknown for contract columns
+    pub nullable: Option<bool>,
+}
+
+const STYLE: &str = "body{font-family:-apple-system,system-ui,sans-serif;margin:0;color:#1f2933}\
+header{background:#1f2933;color:#fff;padding:12px 24px}header a{color:#fff;text-decoration:none}\
+main{padding:16px 24px;max-width:1100px}a{color:#2563eb}\
+table{border-collapse:collapse;margin:8px 0}th,td{border:1px solid #d2d6dc;padding:4px 10px;text-align:left}\
+th{background:#f4f5f7}pre{background:#f4f5f7;padding:12px;overflow-x:auto}\
+.muted{color:#6b7280}.tag{background:#e0e7ff;border-radius:4px;padding:1px 6px;margin-right:4px}\
+svg a:hover rect{fill:#dbeafe}";
+
+const NODE_WIDTH: usize = 200;
+const NODE_HEIGHT: usize = 32;
+const LAYER_GAP: usize = 60;
+const ROW_GAP: usize = 16;
+const MARGIN: usize = 16;
+
+/// Render `index.html`: the DAG of the selected models and a model table
+pub fn render_index(project: &Project, models: &[Relation]) -> Result<String> {
+    let mut body = String::new();
+    body.push_str("<h1>Models</h1>\n<h2>Dependency Graph</h2>\n");
+    body.push_str(&render_dag(project, models)?);
+    body.push_str("<h2>Models</h2>\n<table>\n<tr><th>Model</th><th>Materialized</th><th>Description</th></tr>\n");
+    let layers = topo_sort_layers(project)?;
+    for rel in layers.iter().flatten().filter(|rel| models.contains(rel)) {
+        let model = &project.models[rel];
+        let _ = writeln!(
+            body,
+            "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td></tr>",
+            page_path(rel),
+            escape(&rel.to_string()),
+            model.header.materialized.as_str(),
+            escape(model.header.description.as_deref().unwrap_or("")),
+        );
+    }
+    body.push_str("</table>\n");
+
+    let exposures = project.exposures_for(models);
+    if !exposures.is_empty() {
+        body.push_str("<h2>Exposures</h2>\n<table>\n<tr><th>Exposure</th><th>Type</th><th>Owner</th><th>Depends on</th><th>Description</th></tr>\n");
+        for exposure in exposures {
+            let name = match &exposure.url {
+                Some(url) => format!("<a href=\"{}\">{}</a>", escape(url), escape(&exposure.name)),
+                None => escape(&exposure.name),
+            };
+            let deps: Vec<String> = exposure
+                .depends_on
+                .iter()
+                .map(|rel| {
+                    if project.models.contains_key(rel) {
+                        format!(
+                            "<a href=\"{}\">{}</a>",
+                            page_path(rel),
+                            escape(&rel.to_string())
+                        )
+                    } else {
+                        escape(&rel.to_string())
+                    }
+                })
+                .collect();
+            let _ = writeln!(
+                body,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                name,
+                escape(&exposure.kind),
+                escape(exposure.owner.as_deref().unwrap_or("")),
+                deps.join(", "),
+                escape(exposure.description.as_deref().unwrap_or("")),
+            );
+        }
+        body.push_str("</table>\n");
+    }
+    Ok(page("Models", "", &body))
+}
+
+/// Render the page for one model
+pub fn render_model(project: &Project, model: &Model, columns: Option<&[DocColumn]>) -> String {
+    let header = &model.header;
+    let mut body = String::new();
+    let _ = writeln!(body, "<h1>{}</h1>", escape(&model.id.to_string()));
+    let _ = write!(
+        body,
+        "<p><strong>Materialized as:</strong> {}",
+        header.materialized.as_str()
+    );
+    for tag in &header.tags {
+        let _ = write!(body, " <span class=\"tag\">{}</span>", escape(tag));
+    }
+    body.push_str("</p>\n");
+    if let Some(description) = &header.description {
+        let _ = writeln!(body, "<p>{}</p>", escape(description));
+    }
+
+    body.push_str("<h2>Columns</h2>\n");
+    match columns {
+        Some(columns) if !columns.is_empty() => {
+            body.push_str("<table>\n<tr><th>Column</th><th>Type</th><th>Nullable</th></tr>\n");
+            for column in columns {
+                let nullable = match column.nullable {
+                    Some(true) => "yes",
+                    Some(false) => "no",
+                    None => "",
+                };
+                let _ = writeln!(
+                    body,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape(&column.name),
+                    escape(&column.data_type),
+                    nullable
+                );
+            }
+            body.push_str("</table>\n");
+        }
+        _ => body.push_str(
+            "<p class=\"muted\">Not available (run the model and set DATABASE_URL, or declare columns:)</p>\n",
+        ),
+    }
+
+    let upstream: Vec<&Relation> = header.deps.iter().collect();
+    let downstream: Vec<&Relation> = {
+        let mut rels: Vec<&Relation> = project
+            .models
+            .values()
+            .filter(|m| m.header.deps.contains(&model.id))
+            .map(|m| &m.id)
+            .collect();
+        rels.sort();
+        rels
+    };
+    for (title, rels) in [("Depends on", upstream), ("Used by", downstream)] {
+        if rels.is_empty() {
+            continue;
+        }
+        let _ = writeln!(body, "<h2>{}</h2>\n<ul>", title);
+        for rel in rels {
+            if project.models.contains_key(rel) {
+                let _ = writeln!(
+                    body,
+                    "<li><a href=\"../{}\">{}</a></li>",
+                    page_path(rel),
+                    escape(&rel.to_string())
+                );
+            } else {
+                let _ = writeln!(
+                    body,
+                    "<li>{} <span class=\"muted\">(source)</span></li>",
+                    escape(&rel.to_string())
+                );
+            }
+        }
+        body.push_str("</ul>\n");
+    }
+
+    let exposures = project.exposures_for(std::slice::from_ref(&model.id));
+    if !exposures.is_empty() {
+        body.push_str("<h2>Exposures</h2>\n<ul>\n");
+        for exposure in exposures {
+            let _ = write!(
+                body,
+                "<li>{} <span class=\"muted\">({}",
+                escape(&exposure.name),
+                escape(&exposure.kind)
+            );
+            if let Some(owner) = &exposure.owner {
+                let _ = write!(body, ", owner: {}", escape(owner));
+            }
+            body.push_str(")</span></li>\n");
+        }
+        body.push_str("</ul>\n");
+    }
+
+    if !header.tests.is_empty() {
+        body.push_str("<h2>Tests</h2>\n<table>\n<tr><th>Test</th><th>Severity</th></tr>\n");
+        for (i, test) in header.tests.iter().enumerate() {
+            let severity = match header.test_config(i).severity {
+                TestSeverity::Error => "error",
+                TestSeverity::Warn => "warn",
+            };
+            let _ = writeln!(
+                body,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                escape(&test.description()),
+                severity
+            );
+        }
+        body.push_str("</table>\n");
+    }
+
+    let _ = writeln!(
+        body,
+        "<h2>SQL</h2>\n<pre><code>{}</code></pre>",
+        escape(&model.body_sql)
+    );
+    page(&model.id.to_string(), "../", &body)
+}
+
+/// Path of a model page relative to the docs root
+pub fn page_path(rel: &Relation) -> String {
+    format!("{}/{}.html", rel.schema, rel.name)
+}
+
+/// Inline SVG of the selected models, one column per DAG layer
+fn render_dag(project: &Project, models: &[Relation]) -> Result<String> {
+    let layers: Vec<Vec<Relation>> = topo_sort_layers(project)?
+        .into_iter()
+        .map(|layer| {
+            layer
+                .into_iter()
+                .filter(|rel| models.contains(rel))
+                .collect()
+        })
+        .filter(|layer: &Vec<Relation>| !layer.is_empty())
+        .collect();
+    let position = |rel: &Relation| {
+        layers.iter().enumerate().find_map(|(x, layer)| {
+            layer.iter().position(|r| r == rel).map(|y| {
+                (
+                    MARGIN + x * (NODE_WIDTH + LAYER_GAP),
+                    MARGIN + y * (NODE_HEIGHT + ROW_GAP),
+                )
+            })
+        })
+    };
+    let tallest = layers.iter().map(|l| l.len()).max().unwrap_or(0);
+    let width = 2 * MARGIN + layers.len() * (NODE_WIDTH + LAYER_GAP);
+    let height = 2 * MARGIN + tallest * (NODE_HEIGHT + ROW_GAP);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" font-size=\"12\">"
+    );
+    for rel in layers.iter().flatten() {
+        let (x2, y2) = position(rel).unwrap();
+        for dep in &project.models[rel].header.deps {
+            let Some((x1, y1)) = position(dep) else {
+                continue;
+            };
+            let (x1, y1) = (x1 + NODE_WIDTH, y1 + NODE_HEIGHT / 2);
+            let y2 = y2 + NODE_HEIGHT / 2;
+            let mid = (x1 + x2) / 2;
+            let _ = writeln!(
+                svg,
+                "<path d=\"M{x1},{y1} C{mid},{y1} {mid},{y2} {x2},{y2}\" fill=\"none\" stroke=\"#9ca3af\"/>"
+            );
+        }
+    }
+    for rel in layers.iter().flatten() {
+        let (x, y) = position(rel).unwrap();
+        let _ = writeln!(
+            svg,
+            "<a href=\"{}\"><rect x=\"{x}\" y=\"{y}\" width=\"{NODE_WIDTH}\" height=\"{NODE_HEIGHT}\" rx=\"4\" fill=\"#eff6ff\" stroke=\"#2563eb\"/>\
+             <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text></a>",
+            page_path(rel),
+            x + NODE_WIDTH / 2,
+            y + NODE_HEIGHT / 2 + 4,
+            escape(&rel.to_string())
+        );
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+fn page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<header><a href=\"{}index.html\">pgcrate models</a></header>\n<main>\n{}</main>\n</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        root,
+        body
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_project;
+
+    #[test]
+    fn test_model_page_links_and_escapes() {
+        let project = test_project(vec![
+            (
+                "staging.users",
+                "-- materialized: view\n-- description: Users & their <emails>\n\nSELECT 1 AS id\n",
+            ),
+            (
+                "marts.totals",
+                "-- materialized: table\n-- deps: staging.users, app.events\n-- tests: unique(id) {severity: warn}\n\nSELECT id FROM staging.users WHERE id < 2\n",
+            ),
+        ]);
+        let users = &project.models[&Relation::parse("staging.users").unwrap()];
+        let html = render_model(&project, users, None);
+        assert!(html.contains("Users &amp; their &lt;emails&gt;"));
+        assert!(html.contains("<a href=\"../marts/totals.html\">marts.totals</a>"));
+
+        let totals = &project.models[&Relation::parse("marts.totals").unwrap()];
+        let columns = [DocColumn {
+            name: "id".to_string(),
+            data_type: "integer".to_string(),
+            nullable: Some(false),
+        }];
+        let html = render_model(&project, totals, Some(&columns));
+        assert!(html.contains("<tr><td>id</td><td>integer</td><td>no</td></tr>"));
+        assert!(html.contains("app.events <span class=\"muted\">(source)</span>"));
+        assert!(html.contains("<tr><td>unique(id)</td><td>warn</td></tr>"));
+        assert!(html.contains("WHERE id &lt; 2"));
+    }
+
+    #[test]
+    fn test_index_draws_selected_models() {
+        let project = test_project(vec![
+            ("staging.users", "-- materialized: view\n\nSELECT 1 AS id\n"),
+            (
+                "marts.totals",
+                "-- materialized: table\n-- deps: staging.users\n\nSELECT id FROM staging.users\n",
+            ),
+        ]);
+        let all: Vec<Relation> = vec![
+            Relation::parse("staging.users").unwrap(),
+            Relation::parse("marts.totals").unwrap(),
+        ];
+        let html = render_index(&project, &all).unwrap();
+        assert!(html.contains("<a href=\"marts/totals.html\"><rect"));
+        assert_eq!(html.matches("<path ").count(), 1);
+
+        let html = render_index(&project, &all[1..]).unwrap();
+        assert!(!html.contains("staging/users.html"));
+        assert_eq!(html.matches("<path ").count(), 0);
+    }
+
+    #[test]
+    fn test_exposures_listed_on_index_and_model_pages() {
+        let mut project = test_project(vec![(
+            "marts.totals",
+            "-- materialized: table\n\nSELECT 1 AS id\n",
+        )]);
+        let totals = Relation::parse("marts.totals").unwrap();
+        project.exposures.push(crate::model::Exposure {
+            name: "kpis".to_string(),
+            kind: "dashboard".to_string(),
+            owner: Some("analytics".to_string()),
+            url: Some("https://bi.example.com/d/1".to_string()),
+            description: None,
+            depends_on: vec![totals.clone()],
+        });
+        let html = render_index(&project, std::slice::from_ref(&totals)).unwrap();
+        assert!(html.contains("<a href=\"https://bi.example.com/d/1\">kpis</a>"));
+        assert!(html.contains("<a href=\"marts/totals.html\">marts.totals</a></td>"));
+
+        let html = render_model(&project, &project.models[&totals], None);
+        assert!(html.contains("kpis <span class=\"muted\">(dashboard, owner: analytics)</span>"));
+    }
+}
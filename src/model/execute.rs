@@ -1,10 +1,15 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use regex::Regex;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::fmt;
 use tokio_postgres::{error::ErrorPosition, error::SqlState, Client, Error as PgError};
 
-use super::{generate_create_sql, Materialized, Model};
+use super::{
+    generate_create_sql, generate_refresh_sql, generate_unique_index_sql, IncrementalStrategy,
+    Materialized, Model, OnSchemaChange, SnapshotConfig, SnapshotStrategy, SNAPSHOT_COLUMNS,
+};
+use crate::commands::role::{table_grants, TableGrant};
 use crate::sql::quote_ident;
 use crate::suggest::{best_match, levenshtein};
 
@@ -24,12 +29,39 @@ async fn get_pg_major_version(client: &Client) -> Result<u32> {
 pub struct ExecuteResult {
     pub rows_affected: Option<u64>,
     pub incremental: Option<IncrementalSummary>,
+    pub matview: Option<MatviewAction>,
+    /// GRANT/REVOKE statements issued to match the model's grants
+    pub grant_changes: usize,
+    pub snapshot: Option<SnapshotSummary>,
+}
+
+/// Row versions written by a snapshot model on this run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotSummary {
+    /// The snapshot table was created (every source row inserted as current)
+    pub created: bool,
+    /// Keys with no current version before this run
+    pub inserted: u64,
+    /// Keys whose current version was closed and replaced by a new one
+    pub changed: u64,
+    /// Keys closed because they disappeared from the source (invalidate_hard_deletes)
+    pub invalidated: u64,
+}
+
+/// What happened to a materialized view model on this run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatviewAction {
+    Created,
+    Refreshed,
+    RefreshedConcurrently,
 }
 
 #[derive(Debug, Clone)]
 pub struct IncrementalSummary {
     pub action: IncrementalAction,
     pub inserted: u64,
+    /// Columns altered by on_schema_change, e.g. "added discount numeric"
+    pub schema_changes: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -37,6 +69,9 @@ pub enum IncrementalAction {
     CreatedTable,
     Merged,
     Upserted,
+    Appended,
+    DeleteInserted,
+    Overwritten,
 }
 
 #[derive(Debug, Serialize)]
@@ -92,17 +127,251 @@ pub async fn ensure_schema(client: &Client, schema: &str) -> Result<bool> {
 }
 
 /// Execute a single model against the database.
+///
+/// With `enforce_contracts`, a model with a `columns:` contract fails before
+/// anything runs if its SELECT doesn't match, and tables are created with the
+/// declared column types.
 pub async fn execute_model(
     client: &Client,
     model: &Model,
     full_refresh: bool,
+    enforce_contracts: bool,
+) -> Result<ExecuteResult> {
+    // Ephemeral models only exist inlined inside downstream models
+    if !model.header.materialized.is_materialized() {
+        return Ok(ExecuteResult::default());
+    }
+
+    let enforce_contracts = enforce_contracts && !model.header.columns.is_empty();
+    if enforce_contracts {
+        let violations = contract_violations(client, model).await?;
+        if !violations.is_empty() {
+            bail!(
+                "contract violated for {}:\n  {}\nhint: update the SELECT or the model's columns: header",
+                model.id,
+                violations.join("\n  ")
+            );
+        }
+    }
+
+    run_hooks(client, model, &model.pre_hook_sql()).await?;
+    let mut result = build_model(client, model, full_refresh, enforce_contracts).await?;
+    result.grant_changes = apply_grants(client, model).await?;
+    run_hooks(client, model, &model.post_hook_sql()).await?;
+    Ok(result)
+}
+
+/// Differences between a model's `columns:` contract and the columns its SQL
+/// produces, using the database's type names. Empty when the contract holds.
+///
+/// Types are compared without modifiers, so `numeric(10,2)` matches any numeric.
+pub async fn contract_violations(client: &Client, model: &Model) -> Result<Vec<String>> {
+    let body = model.first_run_sql().trim().trim_end_matches(';').trim();
+    let actual = get_query_columns(client, body)
+        .await
+        .with_context(|| format!("describe columns of {}", model.id))?;
+    let declared: Vec<&str> = model
+        .header
+        .columns
+        .iter()
+        .map(|c| c.data_type.as_str())
+        .collect();
+    let rows = client
+        .query(
+            "SELECT format_type(to_regtype(x.t), NULL) FROM unnest($1::text[]) WITH ORDINALITY AS x(t, i) ORDER BY x.i",
+            &[&declared],
+        )
+        .await
+        .context("resolve contract types")?;
+
+    let mut violations = Vec::new();
+    for (contract, row) in model.header.columns.iter().zip(&rows) {
+        let Some(expected) = row.get::<_, Option<String>>(0) else {
+            violations.push(format!(
+                "unknown type for {}: {}",
+                contract.name, contract.data_type
+            ));
+            continue;
+        };
+        match actual.iter().find(|(name, _)| *name == contract.name) {
+            None => violations.push(format!("missing column {} ({})", contract.name, expected)),
+            Some((_, got)) if *got != expected => violations.push(format!(
+                "{}: declared {}, SELECT returns {}",
+                contract.name, expected, got
+            )),
+            Some(_) => {}
+        }
+    }
+    for (name, data_type) in &actual {
+        if !model.header.columns.iter().any(|c| &c.name == name) {
+            violations.push(format!("undeclared column {} ({})", name, data_type));
+        }
+    }
+    Ok(violations)
+}
+
+/// CREATE TABLE with the contract's column types, filled from the model's SELECT
+pub fn generate_contract_create_sql(model: &Model, body: &str) -> String {
+    let table = format!(
+        "{}.{}",
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name)
+    );
+    let definitions: Vec<String> = model
+        .header
+        .columns
+        .iter()
+        .map(|c| format!("    {} {}", quote_ident(&c.name), c.data_type))
+        .collect();
+    let col_list: Vec<String> = model
+        .header
+        .columns
+        .iter()
+        .map(|c| quote_ident(&c.name))
+        .collect();
+    format!(
+        "CREATE TABLE {} (\n{}\n);\nINSERT INTO {} ({})\nSELECT {} FROM (\n{}\n) AS s",
+        table,
+        definitions.join(",\n"),
+        table,
+        col_list.join(", "),
+        col_list.join(", "),
+        body
+    )
+}
+
+/// Bring privileges on the model in line with its grants, returning the number of
+/// GRANT/REVOKE statements run. Privileges not named in grants are left alone.
+async fn apply_grants(client: &Client, model: &Model) -> Result<usize> {
+    if model.header.grants.is_empty() {
+        return Ok(0);
+    }
+    let current = table_grants(client, &model.id.schema, &model.id.name)
+        .await
+        .with_context(|| format!("inspect grants on {}", model.id))?;
+    let owner: String = client
+        .query_one(
+            "SELECT pg_get_userbyid(c.relowner)::text FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2",
+            &[&model.id.schema, &model.id.name],
+        )
+        .await
+        .with_context(|| format!("get owner of {}", model.id))?
+        .get(0);
+
+    let statements = grant_statements(model, &current, &owner);
+    for sql in &statements {
+        if let Err(e) = client.batch_execute(sql).await {
+            return Err(build_model_execution_error(client, model, sql, &e)
+                .await
+                .into());
+        }
+    }
+    Ok(statements.len())
+}
+
+/// GRANT/REVOKE statements that turn `current` into the model's desired grants.
+/// The owner's own privileges are never revoked.
+fn grant_statements(model: &Model, current: &[TableGrant], owner: &str) -> Vec<String> {
+    let table = format!(
+        "{}.{}",
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name)
+    );
+    let grantee_sql = |role: &str| {
+        if role == "PUBLIC" {
+            role.to_string()
+        } else {
+            quote_ident(role)
+        }
+    };
+
+    let mut statements = Vec::new();
+    for (privilege, roles) in &model.header.grants {
+        let desired: Vec<String> = roles
+            .iter()
+            .map(|r| {
+                if r.eq_ignore_ascii_case("public") {
+                    "PUBLIC".to_string()
+                } else {
+                    r.clone()
+                }
+            })
+            .collect();
+        let held: Vec<&str> = current
+            .iter()
+            .filter(|g| &g.privilege == privilege && g.grantee != owner)
+            .map(|g| g.grantee.as_str())
+            .collect();
+
+        for role in desired.iter().filter(|r| !held.contains(&r.as_str())) {
+            statements.push(format!(
+                "GRANT {} ON {} TO {}",
+                privilege,
+                table,
+                grantee_sql(role)
+            ));
+        }
+        for role in held.iter().filter(|r| !desired.iter().any(|d| d == *r)) {
+            statements.push(format!(
+                "REVOKE {} ON {} FROM {}",
+                privilege,
+                table,
+                grantee_sql(role)
+            ));
+        }
+    }
+    statements
+}
+
+/// Run pre_hook/post_hook statements in order
+async fn run_hooks(client: &Client, model: &Model, hooks: &[String]) -> Result<()> {
+    for hook in hooks {
+        if let Err(e) = client.batch_execute(hook).await {
+            return Err(build_model_execution_error(client, model, hook, &e)
+                .await
+                .into());
+        }
+    }
+    Ok(())
+}
+
+/// Create, refresh or merge the model's database object
+async fn build_model(
+    client: &Client,
+    model: &Model,
+    full_refresh: bool,
+    enforce_contracts: bool,
 ) -> Result<ExecuteResult> {
     // Handle incremental models specially
     if matches!(model.header.materialized, Materialized::Incremental) {
-        let summary = execute_incremental(client, model, full_refresh).await?;
+        let summary = execute_incremental(client, model, full_refresh, enforce_contracts).await?;
         return Ok(ExecuteResult {
             rows_affected: None,
             incremental: Some(summary),
+            matview: None,
+            grant_changes: 0,
+            snapshot: None,
+        });
+    }
+
+    if matches!(model.header.materialized, Materialized::Snapshot) {
+        let summary = execute_snapshot(client, model).await?;
+        return Ok(ExecuteResult {
+            snapshot: Some(summary),
+            ..Default::default()
+        });
+    }
+
+    if matches!(model.header.materialized, Materialized::Matview) {
+        let action = execute_materialized_view(client, model, full_refresh).await?;
+        return Ok(ExecuteResult {
+            rows_affected: None,
+            incremental: None,
+            matview: Some(action),
+            grant_changes: 0,
+            snapshot: None,
         });
     }
 
@@ -112,6 +381,7 @@ pub async fn execute_model(
     // So we must check the object type first and drop appropriately.
     let existing_view = view_exists(client, &model.id.schema, &model.id.name).await?;
     let existing_table = table_exists(client, &model.id.schema, &model.id.name).await?;
+    drop_existing_matview(client, model).await?;
 
     if existing_view {
         let drop_sql = format!(
@@ -137,7 +407,13 @@ pub async fn execute_model(
         }
     }
 
-    let create_sql = generate_create_sql(model);
+    let create_sql =
+        if enforce_contracts && matches!(model.header.materialized, Materialized::Table) {
+            let body = model.body_sql.trim().trim_end_matches(';').trim();
+            generate_contract_create_sql(model, body)
+        } else {
+            generate_create_sql(model)
+        };
     if let Err(e) = client.batch_execute(&create_sql).await {
         return Err(build_model_execution_error(client, model, &create_sql, &e)
             .await
@@ -147,9 +423,136 @@ pub async fn execute_model(
     Ok(ExecuteResult {
         rows_affected: None,
         incremental: None,
+        matview: None,
+        grant_changes: 0,
+        snapshot: None,
     })
 }
 
+/// Prefix for the comment pgcrate stores on materialized views it creates
+const MATVIEW_COMMENT_PREFIX: &str = "pgcrate:definition_sha256=";
+
+/// Hash of everything that defines a materialized view model (query + unique index)
+fn matview_definition_hash(model: &Model) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(generate_create_sql(model).as_bytes());
+    if let Some(index_sql) = generate_unique_index_sql(model) {
+        hasher.update(index_sql.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Create or refresh a materialized view model.
+///
+/// An existing materialized view is refreshed only when it was created from the
+/// same definition (tracked via a hash in its comment); otherwise it is rebuilt,
+/// since REFRESH would keep serving the old query.
+async fn execute_materialized_view(
+    client: &Client,
+    model: &Model,
+    full_refresh: bool,
+) -> Result<MatviewAction> {
+    let hash = matview_definition_hash(model);
+    let existing = matview_comment(client, &model.id.schema, &model.id.name).await?;
+
+    if let Some(comment) = &existing {
+        let unchanged = comment.as_deref() == Some(&format!("{MATVIEW_COMMENT_PREFIX}{hash}"));
+        if unchanged && !full_refresh {
+            let refresh_sql = generate_refresh_sql(model);
+            if let Err(e) = client.batch_execute(&refresh_sql).await {
+                return Err(build_model_execution_error(client, model, &refresh_sql, &e)
+                    .await
+                    .into());
+            }
+            return Ok(if model.header.unique_index.is_empty() {
+                MatviewAction::Refreshed
+            } else {
+                MatviewAction::RefreshedConcurrently
+            });
+        }
+    }
+
+    // Rebuild: drop whatever currently has this name, then create
+    let qualified = format!(
+        "{}.{}",
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name)
+    );
+    let drop_sql = if existing.is_some() {
+        Some(format!("DROP MATERIALIZED VIEW {} CASCADE", qualified))
+    } else if view_exists(client, &model.id.schema, &model.id.name).await? {
+        Some(format!("DROP VIEW {} CASCADE", qualified))
+    } else if table_exists(client, &model.id.schema, &model.id.name).await? {
+        Some(format!("DROP TABLE {} CASCADE", qualified))
+    } else {
+        None
+    };
+    if let Some(drop_sql) = drop_sql {
+        if let Err(e) = client.batch_execute(&drop_sql).await {
+            return Err(build_model_execution_error(client, model, &drop_sql, &e)
+                .await
+                .into());
+        }
+    }
+
+    let mut statements = vec![generate_create_sql(model)];
+    statements.extend(generate_unique_index_sql(model));
+    statements.push(format!(
+        "COMMENT ON MATERIALIZED VIEW {} IS '{}{}'",
+        qualified, MATVIEW_COMMENT_PREFIX, hash
+    ));
+    for sql in &statements {
+        if let Err(e) = client.batch_execute(sql).await {
+            return Err(build_model_execution_error(client, model, sql, &e)
+                .await
+                .into());
+        }
+    }
+
+    Ok(MatviewAction::Created)
+}
+
+/// Look up a materialized view: None if it doesn't exist, Some(comment) if it does
+async fn matview_comment(
+    client: &Client,
+    schema: &str,
+    name: &str,
+) -> Result<Option<Option<String>>> {
+    let row = client
+        .query_opt(
+            "SELECT obj_description(c.oid, 'pg_class')
+             FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind = 'm'",
+            &[&schema, &name],
+        )
+        .await
+        .context("check materialized view exists")?;
+    Ok(row.map(|r| r.get(0)))
+}
+
+/// Drop a materialized view with the model's name (handles matview -> view/table changes).
+/// information_schema doesn't list materialized views, so the view/table checks miss them.
+async fn drop_existing_matview(client: &Client, model: &Model) -> Result<()> {
+    if matview_comment(client, &model.id.schema, &model.id.name)
+        .await?
+        .is_none()
+    {
+        return Ok(());
+    }
+    let drop_sql = format!(
+        "DROP MATERIALIZED VIEW {}.{} CASCADE",
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name)
+    );
+    if let Err(e) = client.batch_execute(&drop_sql).await {
+        return Err(build_model_execution_error(client, model, &drop_sql, &e)
+            .await
+            .into());
+    }
+    Ok(())
+}
+
 /// Check if a table (not view) exists in the database
 async fn table_exists(client: &Client, schema: &str, name: &str) -> Result<bool> {
     let row = client
@@ -195,11 +598,192 @@ async fn get_table_columns(client: &Client, schema: &str, name: &str) -> Result<
     Ok(rows.iter().map(|r| r.get(0)).collect())
 }
 
+/// Get (column name, type) pairs for an existing table, in column order
+async fn get_table_column_types(
+    client: &Client,
+    schema: &str,
+    name: &str,
+) -> Result<Vec<(String, String)>> {
+    let rows = client
+        .query(
+            "SELECT a.attname::text, format_type(a.atttypid, NULL)
+             FROM pg_attribute a
+             JOIN pg_class c ON c.oid = a.attrelid
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2 AND a.attnum > 0 AND NOT a.attisdropped
+             ORDER BY a.attnum",
+            &[&schema, &name],
+        )
+        .await
+        .context("get table column types")?;
+    Ok(rows.iter().map(|r| (r.get(0), r.get(1))).collect())
+}
+
+/// Get (column name, type) pairs produced by a query, without running it
+async fn get_query_columns(client: &Client, body: &str) -> Result<Vec<(String, String)>> {
+    let probe = format!("SELECT * FROM (\n{}\n) AS __schema_probe LIMIT 0", body);
+    let stmt = client.prepare(&probe).await?;
+    let names: Vec<String> = stmt
+        .columns()
+        .iter()
+        .map(|c| c.name().to_string())
+        .collect();
+    let oids: Vec<u32> = stmt.columns().iter().map(|c| c.type_().oid()).collect();
+    let rows = client
+        .query(
+            "SELECT format_type(t.oid, NULL) FROM unnest($1::oid[]) WITH ORDINALITY AS t(oid, i) ORDER BY t.i",
+            &[&oids],
+        )
+        .await
+        .context("format column types")?;
+    Ok(names
+        .into_iter()
+        .zip(rows.iter().map(|r| r.get::<_, String>(0)))
+        .collect())
+}
+
+/// Column differences between an incremental model's SELECT and its table
+#[derive(Debug, Default, PartialEq)]
+struct ColumnChange {
+    /// In the SELECT but not the table: (name, type)
+    added: Vec<(String, String)>,
+    /// In the table but not the SELECT
+    removed: Vec<String>,
+    /// In both with different types: (name, new type)
+    retyped: Vec<(String, String)>,
+}
+
+impl ColumnChange {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.retyped.is_empty()
+    }
+
+    /// Human-readable list of the changes a policy applied
+    fn describe(&self, policy: OnSchemaChange) -> Vec<String> {
+        let mut out: Vec<String> = self
+            .added
+            .iter()
+            .map(|(c, t)| format!("added {} {}", c, t))
+            .collect();
+        if policy == OnSchemaChange::SyncAllColumns {
+            out.extend(self.removed.iter().map(|c| format!("dropped {}", c)));
+            out.extend(
+                self.retyped
+                    .iter()
+                    .map(|(c, t)| format!("changed {} to {}", c, t)),
+            );
+        }
+        out
+    }
+}
+
+fn diff_columns(source: &[(String, String)], target: &[(String, String)]) -> ColumnChange {
+    let mut change = ColumnChange::default();
+    for (name, ty) in source {
+        match target.iter().find(|(t, _)| t == name) {
+            None => change.added.push((name.clone(), ty.clone())),
+            Some((_, target_ty)) if target_ty != ty => {
+                change.retyped.push((name.clone(), ty.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    for (name, _) in target {
+        if !source.iter().any(|(s, _)| s == name) {
+            change.removed.push(name.clone());
+        }
+    }
+    change
+}
+
+/// ALTER TABLE statements that apply `policy` to a column change
+fn schema_change_sql(
+    model: &Model,
+    change: &ColumnChange,
+    policy: OnSchemaChange,
+) -> Result<Vec<String>> {
+    let table = format!(
+        "{}.{}",
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name)
+    );
+    let add = change
+        .added
+        .iter()
+        .map(|(c, t)| format!("ALTER TABLE {} ADD COLUMN {} {}", table, quote_ident(c), t));
+
+    match policy {
+        OnSchemaChange::Fail => {
+            let mut details = Vec::new();
+            if !change.added.is_empty() {
+                let cols: Vec<&str> = change.added.iter().map(|(c, _)| c.as_str()).collect();
+                details.push(format!("added: {}", cols.join(", ")));
+            }
+            if !change.removed.is_empty() {
+                details.push(format!("removed: {}", change.removed.join(", ")));
+            }
+            if !change.retyped.is_empty() {
+                let cols: Vec<String> = change
+                    .retyped
+                    .iter()
+                    .map(|(c, t)| format!("{} ({})", c, t))
+                    .collect();
+                details.push(format!("type changed: {}", cols.join(", ")));
+            }
+            bail!(
+                "schema change detected for incremental model {} ({})\n\
+                 Set on_schema_change: append_new_columns or sync_all_columns, or rerun with --full-refresh",
+                model.id,
+                details.join("; ")
+            );
+        }
+        OnSchemaChange::AppendNewColumns => Ok(add.collect()),
+        OnSchemaChange::SyncAllColumns => {
+            let removed_keys: Vec<&String> = change
+                .removed
+                .iter()
+                .filter(|c| model.header.unique_key.contains(c))
+                .collect();
+            if !removed_keys.is_empty() {
+                bail!(
+                    "cannot sync columns of {}: unique_key column(s) removed from the SELECT: {}\n\
+                     Rerun with --full-refresh to rebuild the table",
+                    model.id,
+                    removed_keys
+                        .iter()
+                        .map(|c| c.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            let mut statements: Vec<String> = add.collect();
+            statements.extend(
+                change
+                    .removed
+                    .iter()
+                    .map(|c| format!("ALTER TABLE {} DROP COLUMN {}", table, quote_ident(c))),
+            );
+            statements.extend(change.retyped.iter().map(|(c, t)| {
+                format!(
+                    "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{}",
+                    table,
+                    quote_ident(c),
+                    t,
+                    quote_ident(c),
+                    t
+                )
+            }));
+            Ok(statements)
+        }
+    }
+}
+
 /// Execute an incremental model using MERGE (PostgreSQL 17+)
 async fn execute_incremental(
     client: &Client,
     model: &Model,
     full_refresh: bool,
+    enforce_contracts: bool,
 ) -> Result<IncrementalSummary> {
     let unique_key = &model.header.unique_key;
 
@@ -233,34 +817,96 @@ async fn execute_incremental(
         };
         let body = body.trim().trim_end_matches(';').trim();
 
-        let pg_version = get_pg_major_version(client).await?;
-        let columns = get_table_columns(client, &model.id.schema, &model.id.name).await?;
-
-        if pg_version >= 17 {
-            // PostgreSQL 17+: Use MERGE with RETURNING merge_action()
-            let merge_sql = generate_merge_sql(model, &columns, body, unique_key);
-            let counts_sql = wrap_merge_for_counts(&merge_sql);
-            let row = client
-                .query_one(&counts_sql, &[])
-                .await
-                .with_context(|| format!("merge into {}", model.id))?;
-            let inserted: i64 = row.get(0);
-            Ok(IncrementalSummary {
-                action: IncrementalAction::Merged,
-                inserted: inserted.max(0) as u64,
-            })
-        } else {
-            // PostgreSQL 9.5-16: Use INSERT ON CONFLICT (upsert)
-            let upsert_sql = generate_upsert_sql(model, &columns, body, unique_key);
-            let row = client
-                .query_one(&upsert_sql, &[])
+        let mut columns = get_table_columns(client, &model.id.schema, &model.id.name).await?;
+        let mut schema_changes = Vec::new();
+        if let Some(policy) = model.header.on_schema_change {
+            let source = get_query_columns(client, body)
                 .await
-                .with_context(|| format!("upsert into {}", model.id))?;
-            let affected: i64 = row.get(0);
-            Ok(IncrementalSummary {
-                action: IncrementalAction::Upserted,
-                inserted: affected.max(0) as u64,
-            })
+                .with_context(|| format!("describe columns of {}", model.id))?;
+            let target = get_table_column_types(client, &model.id.schema, &model.id.name).await?;
+            let change = diff_columns(&source, &target);
+            if !change.is_empty() {
+                let statements = schema_change_sql(model, &change, policy)?;
+                if !statements.is_empty() {
+                    let sql = statements.join(";\n") + ";";
+                    if let Err(e) = client.batch_execute(&sql).await {
+                        return Err(build_model_execution_error(client, model, &sql, &e)
+                            .await
+                            .into());
+                    }
+                }
+                schema_changes = change.describe(policy);
+                columns = get_table_columns(client, &model.id.schema, &model.id.name).await?;
+            }
+            // Only merge columns the SELECT still produces
+            columns.retain(|c| source.iter().any(|(name, _)| name == c));
+        }
+
+        match model.header.strategy.unwrap_or(IncrementalStrategy::Merge) {
+            IncrementalStrategy::Merge => {
+                let pg_version = get_pg_major_version(client).await?;
+                if pg_version >= 17 {
+                    // PostgreSQL 17+: Use MERGE with RETURNING merge_action()
+                    let merge_sql = generate_merge_sql(model, &columns, body, unique_key);
+                    let counts_sql = wrap_merge_for_counts(&merge_sql);
+                    let row = client
+                        .query_one(&counts_sql, &[])
+                        .await
+                        .with_context(|| format!("merge into {}", model.id))?;
+                    let inserted: i64 = row.get(0);
+                    Ok(IncrementalSummary {
+                        action: IncrementalAction::Merged,
+                        inserted: inserted.max(0) as u64,
+                        schema_changes,
+                    })
+                } else {
+                    // PostgreSQL 9.5-16: Use INSERT ON CONFLICT (upsert)
+                    let upsert_sql = generate_upsert_sql(model, &columns, body, unique_key);
+                    let row = client
+                        .query_one(&upsert_sql, &[])
+                        .await
+                        .with_context(|| format!("upsert into {}", model.id))?;
+                    let affected: i64 = row.get(0);
+                    Ok(IncrementalSummary {
+                        action: IncrementalAction::Upserted,
+                        inserted: affected.max(0) as u64,
+                        schema_changes,
+                    })
+                }
+            }
+            IncrementalStrategy::Append => {
+                let sql = generate_insert_sql(model, &columns, body);
+                let inserted = match client.execute(&sql, &[]).await {
+                    Ok(n) => n,
+                    Err(e) => {
+                        return Err(build_model_execution_error(client, model, &sql, &e)
+                            .await
+                            .into())
+                    }
+                };
+                Ok(IncrementalSummary {
+                    action: IncrementalAction::Appended,
+                    inserted,
+                    schema_changes,
+                })
+            }
+            IncrementalStrategy::DeleteInsert => {
+                let inserted = replace_rows(client, model, &columns, body, unique_key).await?;
+                Ok(IncrementalSummary {
+                    action: IncrementalAction::DeleteInserted,
+                    inserted,
+                    schema_changes,
+                })
+            }
+            IncrementalStrategy::InsertOverwrite => {
+                let inserted =
+                    replace_rows(client, model, &columns, body, &model.header.partition_by).await?;
+                Ok(IncrementalSummary {
+                    action: IncrementalAction::Overwritten,
+                    inserted,
+                    schema_changes,
+                })
+            }
         }
     } else {
         // First run OR full refresh: use @base section
@@ -268,6 +914,7 @@ async fn execute_incremental(
         let body = body.trim().trim_end_matches(';').trim();
 
         // Drop view first if it exists (handles view->table materialization change)
+        drop_existing_matview(client, model).await?;
         if view_exists {
             let drop_view_sql = format!(
                 "DROP VIEW {}.{} CASCADE",
@@ -294,7 +941,15 @@ async fn execute_incremental(
                     .into());
             }
         }
-        let sql = generate_first_run_sql(model, body, unique_key);
+        let sql = if enforce_contracts {
+            let mut sql = generate_contract_create_sql(model, body) + ";";
+            if let Some(pk_sql) = primary_key_sql(model, unique_key) {
+                sql.push_str(&format!("\n{};", pk_sql));
+            }
+            sql
+        } else {
+            generate_first_run_sql(model, body, unique_key)
+        };
         if let Err(e) = client.batch_execute(&sql).await {
             return Err(build_model_execution_error(client, model, &sql, &e)
                 .await
@@ -314,6 +969,7 @@ async fn execute_incremental(
         Ok(IncrementalSummary {
             action: IncrementalAction::CreatedTable,
             inserted: count.max(0) as u64,
+            schema_changes: Vec::new(),
         })
     }
 }
@@ -325,17 +981,27 @@ pub fn generate_first_run_sql(model: &Model, body: &str, unique_key: &[String])
         quote_ident(&model.id.schema),
         quote_ident(&model.id.name)
     );
+    let mut sql = format!("CREATE TABLE {} AS\n{};", qualified_table, body);
+    // append / insert_overwrite models without a key get no primary key
+    if let Some(pk_sql) = primary_key_sql(model, unique_key) {
+        sql.push_str(&format!("\n{};", pk_sql));
+    }
+    sql
+}
+
+/// ALTER TABLE adding the incremental model's unique_key as its primary key
+fn primary_key_sql(model: &Model, unique_key: &[String]) -> Option<String> {
+    if unique_key.is_empty() {
+        return None;
+    }
     let pk_cols: Vec<String> = unique_key.iter().map(|k| quote_ident(k)).collect();
-    let constraint_name = format!("{}_pkey", model.id.name);
-    format!(
-        "CREATE TABLE {} AS\n{};\n\
-         ALTER TABLE {} ADD CONSTRAINT {} PRIMARY KEY ({});",
-        qualified_table,
-        body,
-        qualified_table,
-        quote_ident(&constraint_name),
+    Some(format!(
+        "ALTER TABLE {}.{} ADD CONSTRAINT {} PRIMARY KEY ({})",
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name),
+        quote_ident(&format!("{}_pkey", model.id.name)),
         pk_cols.join(", ")
-    )
+    ))
 }
 
 /// Generate MERGE SQL for incremental model (PostgreSQL 15+)
@@ -398,6 +1064,275 @@ pub fn generate_merge_sql(
     sql
 }
 
+/// Generate INSERT SQL copying `columns` from the model's SELECT (append strategy)
+pub fn generate_insert_sql(model: &Model, columns: &[String], body: &str) -> String {
+    let col_list: Vec<String> = columns.iter().map(|c| quote_ident(c)).collect();
+    format!(
+        "INSERT INTO {}.{} ({})\nSELECT {} FROM (\n{}\n) AS s",
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name),
+        col_list.join(", "),
+        col_list.join(", "),
+        body
+    )
+}
+
+/// Name of the temp table holding new rows for delete+insert / insert_overwrite
+const STAGING_TABLE: &str = "__pgcrate_incremental";
+
+/// Generate DELETE SQL removing target rows whose `match_cols` values appear in the
+/// staged new rows (NULL-safe, so NULL partitions are replaced too)
+pub fn generate_delete_matching_sql(model: &Model, match_cols: &[String]) -> String {
+    let predicate: Vec<String> = match_cols
+        .iter()
+        .map(|c| {
+            format!(
+                "t.{} IS NOT DISTINCT FROM s.{}",
+                quote_ident(c),
+                quote_ident(c)
+            )
+        })
+        .collect();
+    let distinct_cols: Vec<String> = match_cols.iter().map(|c| quote_ident(c)).collect();
+    format!(
+        "DELETE FROM {}.{} AS t\nUSING (SELECT DISTINCT {} FROM {}) AS s\nWHERE {}",
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name),
+        distinct_cols.join(", "),
+        quote_ident(STAGING_TABLE),
+        predicate.join(" AND ")
+    )
+}
+
+/// Statements for delete+insert / insert_overwrite: stage the new rows in a temp
+/// table, delete target rows matching `match_cols`, then insert the staged rows
+pub fn generate_replace_sql(
+    model: &Model,
+    columns: &[String],
+    body: &str,
+    match_cols: &[String],
+) -> Vec<String> {
+    vec![
+        format!(
+            "CREATE TEMP TABLE {} ON COMMIT DROP AS\n{}",
+            quote_ident(STAGING_TABLE),
+            body
+        ),
+        generate_delete_matching_sql(model, match_cols),
+        generate_insert_sql(
+            model,
+            columns,
+            &format!("SELECT * FROM {}", quote_ident(STAGING_TABLE)),
+        ),
+    ]
+}
+
+/// Run [`generate_replace_sql`] in one transaction. Returns rows inserted.
+async fn replace_rows(
+    client: &Client,
+    model: &Model,
+    columns: &[String],
+    body: &str,
+    match_cols: &[String],
+) -> Result<u64> {
+    let statements = generate_replace_sql(model, columns, body, match_cols);
+
+    client.batch_execute("BEGIN").await.context("begin")?;
+    let mut inserted = 0;
+    for sql in &statements {
+        match client.execute(sql.as_str(), &[]).await {
+            Ok(n) => inserted = n,
+            Err(e) => {
+                let _ = client.batch_execute("ROLLBACK").await;
+                return Err(build_model_execution_error(client, model, sql, &e)
+                    .await
+                    .into());
+            }
+        }
+    }
+    client.batch_execute("COMMIT").await.context("commit")?;
+    Ok(inserted)
+}
+
+/// Name of the temp table holding the source rows of a snapshot run
+const SNAPSHOT_STAGING_TABLE: &str = "__pgcrate_snapshot";
+
+/// Create a snapshot table on first run; afterwards close changed rows and insert
+/// their new versions. History is never dropped, so `--full-refresh` does not apply.
+async fn execute_snapshot(client: &Client, model: &Model) -> Result<SnapshotSummary> {
+    let config = model
+        .header
+        .snapshot
+        .as_ref()
+        .with_context(|| format!("{} has no snapshot settings", model.id))?;
+    let body = model.body_sql.trim().trim_end_matches(';').trim();
+
+    let source = get_query_columns(client, body)
+        .await
+        .with_context(|| format!("describe columns of {}", model.id))?;
+    let source: Vec<String> = source.into_iter().map(|(name, _)| name).collect();
+    if let Some(col) = source
+        .iter()
+        .find(|c| SNAPSHOT_COLUMNS.contains(&c.as_str()))
+    {
+        bail!(
+            "snapshot {} selects a column named {col}, which is reserved for snapshot history ({})",
+            model.id,
+            SNAPSHOT_COLUMNS.join(", ")
+        );
+    }
+
+    if !table_exists(client, &model.id.schema, &model.id.name).await? {
+        drop_existing_matview(client, model).await?;
+        if view_exists(client, &model.id.schema, &model.id.name).await? {
+            let drop_sql = format!(
+                "DROP VIEW {}.{} CASCADE",
+                quote_ident(&model.id.schema),
+                quote_ident(&model.id.name)
+            );
+            if let Err(e) = client.batch_execute(&drop_sql).await {
+                return Err(build_model_execution_error(client, model, &drop_sql, &e)
+                    .await
+                    .into());
+            }
+        }
+        let create_sql = generate_create_sql(model);
+        let inserted = match client.execute(create_sql.as_str(), &[]).await {
+            Ok(n) => n,
+            Err(e) => {
+                return Err(build_model_execution_error(client, model, &create_sql, &e)
+                    .await
+                    .into())
+            }
+        };
+        return Ok(SnapshotSummary {
+            created: true,
+            inserted,
+            ..Default::default()
+        });
+    }
+
+    let target = get_table_columns(client, &model.id.schema, &model.id.name).await?;
+    if let Some(missing) = SNAPSHOT_COLUMNS
+        .iter()
+        .find(|c| !target.iter().any(|t| t == *c))
+    {
+        bail!(
+            "{} exists but is not a snapshot table (missing column {missing})\n\
+             hint: rename the model or drop the table to start a new history",
+            model.id
+        );
+    }
+
+    let statements = generate_snapshot_sql(model, config, &source, body);
+    client.batch_execute("BEGIN").await.context("begin")?;
+    let mut counts = Vec::with_capacity(statements.len());
+    for sql in &statements {
+        match client.execute(sql.as_str(), &[]).await {
+            Ok(n) => counts.push(n),
+            Err(e) => {
+                let _ = client.batch_execute("ROLLBACK").await;
+                return Err(build_model_execution_error(client, model, sql, &e)
+                    .await
+                    .into());
+            }
+        }
+    }
+    client.batch_execute("COMMIT").await.context("commit")?;
+
+    // counts: [stage, close changed, insert versions, (invalidate deletes)]
+    let changed = counts[1];
+    Ok(SnapshotSummary {
+        created: false,
+        inserted: counts[2].saturating_sub(changed),
+        changed,
+        invalidated: counts.get(3).copied().unwrap_or(0),
+    })
+}
+
+/// Statements for a snapshot run after the first: stage the source rows, close the
+/// current version of changed keys, insert new versions for keys without a current
+/// row, and (with invalidate_hard_deletes) close keys missing from the source
+pub fn generate_snapshot_sql(
+    model: &Model,
+    config: &SnapshotConfig,
+    columns: &[String],
+    body: &str,
+) -> Vec<String> {
+    let table = format!(
+        "{}.{}",
+        quote_ident(&model.id.schema),
+        quote_ident(&model.id.name)
+    );
+    let staging = quote_ident(SNAPSHOT_STAGING_TABLE);
+    let unique_key = &model.header.unique_key;
+    let key_match: Vec<String> = unique_key
+        .iter()
+        .map(|k| format!("t.{} = s.{}", quote_ident(k), quote_ident(k)))
+        .collect();
+    let key_match = key_match.join(" AND ");
+    let valid_from = config.valid_from_sql("s");
+
+    let changed = match &config.strategy {
+        SnapshotStrategy::Timestamp { updated_at } => format!(
+            "s.{} > t.{}",
+            quote_ident(updated_at),
+            quote_ident(updated_at)
+        ),
+        SnapshotStrategy::Check { columns: checked } => {
+            let checked: Vec<&String> = if checked.is_empty() {
+                columns.iter().filter(|c| !unique_key.contains(c)).collect()
+            } else {
+                checked.iter().collect()
+            };
+            let target: Vec<String> = checked
+                .iter()
+                .map(|c| format!("t.{}", quote_ident(c)))
+                .collect();
+            let source: Vec<String> = checked
+                .iter()
+                .map(|c| format!("s.{}", quote_ident(c)))
+                .collect();
+            format!(
+                "ROW({}) IS DISTINCT FROM ROW({})",
+                target.join(", "),
+                source.join(", ")
+            )
+        }
+    };
+
+    let col_list: Vec<String> = columns.iter().map(|c| quote_ident(c)).collect();
+    let source_cols: Vec<String> = columns
+        .iter()
+        .map(|c| format!("s.{}", quote_ident(c)))
+        .collect();
+
+    let mut statements = vec![
+        format!("CREATE TEMP TABLE {} ON COMMIT DROP AS\n{}", staging, body),
+        format!(
+            "UPDATE {} AS t\nSET valid_to = {}, is_current = false\nFROM {} AS s\nWHERE {} AND t.is_current AND {}",
+            table, valid_from, staging, key_match, changed
+        ),
+        format!(
+            "INSERT INTO {} ({}, valid_from, valid_to, is_current)\nSELECT {}, {}, NULL, true\nFROM {} AS s\nWHERE NOT EXISTS (SELECT 1 FROM {} AS t WHERE {} AND t.is_current)",
+            table,
+            col_list.join(", "),
+            source_cols.join(", "),
+            valid_from,
+            staging,
+            table,
+            key_match
+        ),
+    ];
+    if config.invalidate_hard_deletes {
+        statements.push(format!(
+            "UPDATE {} AS t\nSET valid_to = now(), is_current = false\nWHERE t.is_current AND NOT EXISTS (SELECT 1 FROM {} AS s WHERE {})",
+            table, staging, key_match
+        ));
+    }
+    statements
+}
+
 fn wrap_merge_for_counts(merge_sql: &str) -> String {
     let merge_sql = merge_sql.trim_end_matches(';');
     format!(
@@ -683,7 +1618,7 @@ impl std::error::Error for ModelExecutionError {}
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::{ModelHeader, Relation};
+    use crate::model::{ColumnContract, ModelHeader, Relation};
     use std::path::PathBuf;
 
     fn make_incremental_model(body: &str, unique_key: Vec<String>) -> Model {
@@ -698,10 +1633,21 @@ mod tests {
                 deps: Vec::new(),
                 unique_key,
                 tests: Vec::new(),
+                test_configs: Vec::new(),
                 tags: Vec::new(),
                 watermark: None,
                 lookback: None,
                 incremental_filter: None,
+                unique_index: Vec::new(),
+                strategy: None,
+                partition_by: Vec::new(),
+                on_schema_change: None,
+                pre_hook: Vec::new(),
+                post_hook: Vec::new(),
+                grants: Default::default(),
+                snapshot: None,
+                columns: Vec::new(),
+                description: None,
             },
             body_sql: body.into(),
             base_sql: Some(body.into()),
@@ -852,4 +1798,231 @@ mod tests {
         assert!(sql.contains(r#""order" = EXCLUDED."order""#));
         assert!(sql.contains(r#""user" = EXCLUDED."user""#));
     }
+
+    fn cols(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(c, t)| (c.to_string(), t.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_columns() {
+        let source = cols(&[("id", "integer"), ("total", "numeric"), ("note", "text")]);
+        let target = cols(&[("id", "integer"), ("total", "bigint"), ("old", "text")]);
+        let change = diff_columns(&source, &target);
+        assert_eq!(change.added, cols(&[("note", "text")]));
+        assert_eq!(change.removed, vec!["old".to_string()]);
+        assert_eq!(change.retyped, cols(&[("total", "numeric")]));
+        assert!(diff_columns(&target, &target).is_empty());
+    }
+
+    #[test]
+    fn test_schema_change_sql_policies() {
+        let model = make_incremental_model("SELECT 1", vec!["id".into()]);
+        let change = diff_columns(
+            &cols(&[("id", "integer"), ("note", "text")]),
+            &cols(&[("id", "integer"), ("old", "text")]),
+        );
+
+        let err = schema_change_sql(&model, &change, OnSchemaChange::Fail).unwrap_err();
+        assert!(err.to_string().contains("added: note"));
+        assert!(err.to_string().contains("removed: old"));
+
+        let sql = schema_change_sql(&model, &change, OnSchemaChange::AppendNewColumns).unwrap();
+        assert_eq!(
+            sql,
+            vec![r#"ALTER TABLE "analytics"."users" ADD COLUMN "note" text"#]
+        );
+
+        let sql = schema_change_sql(&model, &change, OnSchemaChange::SyncAllColumns).unwrap();
+        assert_eq!(sql.len(), 2);
+        assert_eq!(
+            sql[1],
+            r#"ALTER TABLE "analytics"."users" DROP COLUMN "old""#
+        );
+    }
+
+    #[test]
+    fn test_schema_change_sql_sync_refuses_to_drop_unique_key() {
+        let model = make_incremental_model("SELECT 1", vec!["id".into()]);
+        let change = diff_columns(&cols(&[("uid", "integer")]), &cols(&[("id", "integer")]));
+        let err = schema_change_sql(&model, &change, OnSchemaChange::SyncAllColumns).unwrap_err();
+        assert!(err.to_string().contains("unique_key"));
+    }
+
+    fn grant(grantee: &str, privilege: &str) -> TableGrant {
+        TableGrant {
+            grantee: grantee.into(),
+            privilege: privilege.into(),
+            grantable: false,
+        }
+    }
+
+    #[test]
+    fn test_grant_statements_diff() {
+        let mut model = make_incremental_model("SELECT 1", vec!["id".into()]);
+        model
+            .header
+            .grants
+            .insert("SELECT".into(), vec!["reader".into(), "public".into()]);
+        model.header.grants.insert("INSERT".into(), Vec::new());
+
+        let current = vec![
+            grant("owner", "SELECT"),
+            grant("owner", "INSERT"),
+            grant("reader", "SELECT"),
+            grant("stale", "SELECT"),
+            grant("etl", "INSERT"),
+            grant("etl", "UPDATE"),
+        ];
+        let sql = grant_statements(&model, &current, "owner");
+        assert_eq!(
+            sql,
+            vec![
+                r#"REVOKE INSERT ON "analytics"."users" FROM "etl""#,
+                r#"GRANT SELECT ON "analytics"."users" TO PUBLIC"#,
+                r#"REVOKE SELECT ON "analytics"."users" FROM "stale""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grant_statements_idempotent() {
+        let mut model = make_incremental_model("SELECT 1", vec!["id".into()]);
+        model
+            .header
+            .grants
+            .insert("SELECT".into(), vec!["reader".into()]);
+        let current = vec![grant("owner", "SELECT"), grant("reader", "SELECT")];
+        assert!(grant_statements(&model, &current, "owner").is_empty());
+    }
+
+    #[test]
+    fn test_first_run_sql_without_unique_key() {
+        let model = make_incremental_model("SELECT 1 AS id", Vec::new());
+        let sql = generate_first_run_sql(&model, "SELECT 1 AS id", &[]);
+        assert_eq!(
+            sql,
+            "CREATE TABLE \"analytics\".\"users\" AS\nSELECT 1 AS id;"
+        );
+    }
+
+    #[test]
+    fn test_insert_sql() {
+        let model = make_incremental_model("SELECT id, name FROM src", Vec::new());
+        let sql = generate_insert_sql(
+            &model,
+            &["id".into(), "name".into()],
+            "SELECT id, name FROM src",
+        );
+        assert_eq!(
+            sql,
+            "INSERT INTO \"analytics\".\"users\" (\"id\", \"name\")\n\
+             SELECT \"id\", \"name\" FROM (\nSELECT id, name FROM src\n) AS s"
+        );
+    }
+
+    #[test]
+    fn test_replace_sql_stages_deletes_then_inserts() {
+        let model = make_incremental_model("SELECT day, total FROM src", Vec::new());
+        let sql = generate_replace_sql(
+            &model,
+            &["day".into(), "total".into()],
+            "SELECT day, total FROM src",
+            &["day".into()],
+        );
+        assert_eq!(sql.len(), 3);
+        assert!(sql[0].starts_with("CREATE TEMP TABLE \"__pgcrate_incremental\" ON COMMIT DROP"));
+        assert_eq!(
+            sql[1],
+            "DELETE FROM \"analytics\".\"users\" AS t\n\
+             USING (SELECT DISTINCT \"day\" FROM \"__pgcrate_incremental\") AS s\n\
+             WHERE t.\"day\" IS NOT DISTINCT FROM s.\"day\""
+        );
+        assert!(sql[2].contains("SELECT * FROM \"__pgcrate_incremental\""));
+    }
+
+    #[test]
+    fn test_snapshot_sql_timestamp_strategy() {
+        let mut model =
+            make_incremental_model("SELECT id, name, updated_at FROM src", vec!["id".into()]);
+        model.header.materialized = Materialized::Snapshot;
+        let config = SnapshotConfig {
+            strategy: SnapshotStrategy::Timestamp {
+                updated_at: "updated_at".into(),
+            },
+            invalidate_hard_deletes: false,
+        };
+        let columns = vec![
+            "id".to_string(),
+            "name".to_string(),
+            "updated_at".to_string(),
+        ];
+        let sql = generate_snapshot_sql(
+            &model,
+            &config,
+            &columns,
+            "SELECT id, name, updated_at FROM src",
+        );
+        assert_eq!(sql.len(), 3);
+        assert!(sql[0].starts_with("CREATE TEMP TABLE \"__pgcrate_snapshot\" ON COMMIT DROP"));
+        assert_eq!(
+            sql[1],
+            "UPDATE \"analytics\".\"users\" AS t\n\
+             SET valid_to = s.\"updated_at\"::timestamptz, is_current = false\n\
+             FROM \"__pgcrate_snapshot\" AS s\n\
+             WHERE t.\"id\" = s.\"id\" AND t.is_current AND s.\"updated_at\" > t.\"updated_at\""
+        );
+        assert!(sql[2].starts_with(
+            "INSERT INTO \"analytics\".\"users\" (\"id\", \"name\", \"updated_at\", valid_from, valid_to, is_current)"
+        ));
+        assert!(sql[2].contains("WHERE NOT EXISTS"));
+    }
+
+    #[test]
+    fn test_snapshot_sql_check_all_with_hard_deletes() {
+        let mut model =
+            make_incremental_model("SELECT id, name, email FROM src", vec!["id".into()]);
+        model.header.materialized = Materialized::Snapshot;
+        let config = SnapshotConfig {
+            strategy: SnapshotStrategy::Check {
+                columns: Vec::new(),
+            },
+            invalidate_hard_deletes: true,
+        };
+        let columns = vec!["id".to_string(), "name".to_string(), "email".to_string()];
+        let sql =
+            generate_snapshot_sql(&model, &config, &columns, "SELECT id, name, email FROM src");
+        assert_eq!(sql.len(), 4);
+        // All non-key columns are compared
+        assert!(sql[1].contains(
+            "ROW(t.\"name\", t.\"email\") IS DISTINCT FROM ROW(s.\"name\", s.\"email\")"
+        ));
+        assert!(sql[1].contains("SET valid_to = now()"));
+        assert!(sql[3].contains("SET valid_to = now(), is_current = false"));
+        assert!(sql[3].contains("WHERE t.is_current AND NOT EXISTS"));
+    }
+
+    #[test]
+    fn test_contract_create_sql() {
+        let mut model = make_incremental_model("SELECT id, amount FROM src", vec!["id".into()]);
+        model.header.columns = vec![
+            ColumnContract {
+                name: "id".into(),
+                data_type: "bigint".into(),
+            },
+            ColumnContract {
+                name: "amount".into(),
+                data_type: "numeric(10,2)".into(),
+            },
+        ];
+        let sql = generate_contract_create_sql(&model, "SELECT id, amount FROM src");
+        assert_eq!(
+            sql,
+            "CREATE TABLE \"analytics\".\"users\" (\n    \"id\" bigint,\n    \"amount\" numeric(10,2)\n);\n\
+             INSERT INTO \"analytics\".\"users\" (\"id\", \"amount\")\n\
+             SELECT \"id\", \"amount\" FROM (\nSELECT id, amount FROM src\n) AS s"
+        );
+    }
 }
@@ -0,0 +1,167 @@
+//! Exposures: dashboards, services and other consumers outside the project
+//! that depend on models, declared in `<models_dir>/exposures.toml`:
+//!
+//! ```toml
+//! [exposures.weekly_kpis]
+//! type = "dashboard"
+//! owner = "analytics@example.com"
+//! url = "https://bi.example.com/d/42"
+//! description = "Leadership KPIs"
+//! depends_on = ["marts.user_stats"]
+//! ```
+//!
+//! Exposures are never built; they show what breaks when a model changes.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use super::{Model, Relation};
+
+/// Exposures file name inside the models directory
+pub const EXPOSURES_FILE: &str = "exposures.toml";
+
+const EXPOSURE_TYPES: &[&str] = &["dashboard", "notebook", "analysis", "ml", "application"];
+
+/// A downstream consumer of one or more models
+#[derive(Clone, Debug, PartialEq)]
+pub struct Exposure {
+    pub name: String,
+    /// dashboard, notebook, analysis, ml or application
+    pub kind: String,
+    pub owner: Option<String>,
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub depends_on: Vec<Relation>,
+}
+
+impl Exposure {
+    /// Whether the exposure reads any of the given models
+    pub fn depends_on_any(&self, models: &[Relation]) -> bool {
+        self.depends_on.iter().any(|rel| models.contains(rel))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExposuresFile {
+    #[serde(default)]
+    exposures: BTreeMap<String, ExposureEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExposureEntry {
+    #[serde(rename = "type")]
+    kind: String,
+    owner: Option<String>,
+    url: Option<String>,
+    description: Option<String>,
+    depends_on: Vec<String>,
+}
+
+/// Load `exposures.toml` from the models directory (none if the file is absent).
+/// Every `depends_on` entry must be a model or a declared source.
+pub fn load_exposures(
+    models_dir: &Path,
+    models: &HashMap<Relation, Model>,
+    sources: &HashSet<Relation>,
+) -> Result<Vec<Exposure>> {
+    let path = models_dir.join(EXPOSURES_FILE);
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+    parse_exposures(&content, models, sources).with_context(|| format!("parse {}", path.display()))
+}
+
+fn parse_exposures(
+    content: &str,
+    models: &HashMap<Relation, Model>,
+    sources: &HashSet<Relation>,
+) -> Result<Vec<Exposure>> {
+    let file: ExposuresFile = toml::from_str(content)?;
+    let mut exposures = Vec::new();
+    for (name, entry) in file.exposures {
+        if !EXPOSURE_TYPES.contains(&entry.kind.as_str()) {
+            bail!(
+                "exposure {name}: unknown type '{}' (expected one of: {})",
+                entry.kind,
+                EXPOSURE_TYPES.join(", ")
+            );
+        }
+        if entry.depends_on.is_empty() {
+            bail!("exposure {name}: depends_on must list at least one model");
+        }
+        let mut depends_on = Vec::new();
+        for dep in &entry.depends_on {
+            let rel = Relation::parse(dep).with_context(|| format!("exposure {name}"))?;
+            if !models.contains_key(&rel) && !sources.contains(&rel) {
+                bail!("exposure {name}: depends on unknown model {rel}");
+            }
+            depends_on.push(rel);
+        }
+        exposures.push(Exposure {
+            name,
+            kind: entry.kind,
+            owner: entry.owner,
+            url: entry.url,
+            description: entry.description,
+            depends_on,
+        });
+    }
+    Ok(exposures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources() -> HashSet<Relation> {
+        HashSet::from([Relation::parse("app.users").unwrap()])
+    }
+
+    #[test]
+    fn test_parse_exposures() {
+        let exposures = parse_exposures(
+            r#"
+[exposures.kpis]
+type = "dashboard"
+owner = "analytics"
+depends_on = ["app.users"]
+
+[exposures.api]
+type = "application"
+depends_on = ["app.users"]
+"#,
+            &HashMap::new(),
+            &sources(),
+        )
+        .unwrap();
+        let names: Vec<&str> = exposures.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["api", "kpis"]);
+        assert_eq!(exposures[1].owner.as_deref(), Some("analytics"));
+        assert!(exposures[1].depends_on_any(&[Relation::parse("app.users").unwrap()]));
+    }
+
+    #[test]
+    fn test_parse_exposures_rejects_unknown_refs_and_types() {
+        let err = parse_exposures(
+            "[exposures.kpis]\ntype = \"dashboard\"\ndepends_on = [\"marts.missing\"]\n",
+            &HashMap::new(),
+            &sources(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown model marts.missing"));
+
+        let err = parse_exposures(
+            "[exposures.kpis]\ntype = \"report\"\ndepends_on = [\"app.users\"]\n",
+            &HashMap::new(),
+            &sources(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown type 'report'"));
+    }
+}
@@ -0,0 +1,647 @@
+//! Column-level lineage: which upstream columns feed each model column.
+//!
+//! Lineage is read from the model's SELECT list. A column's sources are the
+//! columns referenced by its expression, traced through CTEs and subqueries to
+//! the relations in FROM. Filters, join conditions and GROUP BY are ignored.
+//! `*` expands to an upstream model's own columns; for sources (whose columns
+//! aren't known without a database) it is recorded as `source.*`.
+
+use anyhow::{bail, Context, Result};
+use sqlparser::ast::{
+    Expr, FunctionArg, FunctionArgExpr, FunctionArguments, Ident, ObjectName, Query, Select,
+    SelectItem, SelectItemQualifiedWildcardKind, SetExpr, Statement, TableAlias, TableFactor,
+    TableWithJoins,
+};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::{topo_sort, Model, Project, Relation};
+
+/// An upstream column, `schema.table.column`
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ColumnRef {
+    pub relation: Relation,
+    pub column: String,
+}
+
+impl std::fmt::Display for ColumnRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.relation, self.column)
+    }
+}
+
+/// One output column of a model and the upstream columns it is derived from
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnLineage {
+    pub column: String,
+    pub sources: BTreeSet<ColumnRef>,
+}
+
+/// Output columns of each analyzed model
+pub type LineageMap = BTreeMap<Relation, Vec<ColumnLineage>>;
+
+/// Column lineage for every model that parses, in dependency order.
+///
+/// Models whose SQL can't be analyzed are returned in the error list instead.
+pub fn project_column_lineage(project: &Project) -> Result<(LineageMap, Vec<(Relation, String)>)> {
+    let mut lineage = BTreeMap::new();
+    let mut errors = Vec::new();
+    for rel in topo_sort(project)? {
+        let model = &project.models[&rel];
+        match model_column_lineage(project, model, &lineage) {
+            Ok(columns) => {
+                lineage.insert(rel, columns);
+            }
+            Err(e) => errors.push((rel, format!("{:#}", e))),
+        }
+    }
+    Ok((lineage, errors))
+}
+
+/// Column lineage for a single model.
+///
+/// `upstream` holds the lineage of models already analyzed; it is used to
+/// expand `*` and to resolve unqualified columns when several relations are
+/// joined.
+pub fn model_column_lineage(
+    project: &Project,
+    model: &Model,
+    upstream: &LineageMap,
+) -> Result<Vec<ColumnLineage>> {
+    let dialect = PostgreSqlDialect {};
+    let mut statements = Parser::parse_sql(&dialect, &model.body_sql)
+        .with_context(|| format!("parse SQL for {}", model.id))?;
+    if statements.len() != 1 {
+        bail!(
+            "expected exactly one SQL statement, found {}",
+            statements.len()
+        );
+    }
+    let query = match statements.remove(0) {
+        Statement::Query(q) => q,
+        other => bail!("unsupported statement kind in model (expected query): {other}"),
+    };
+    let analyzer = Analyzer { project, upstream };
+    analyzer.query(&query, &mut Vec::new())
+}
+
+/// A relation visible in a SELECT's FROM clause
+enum ScopeEntry {
+    /// A table or view outside this query
+    Relation { alias: String, relation: Relation },
+    /// A CTE or subquery, already traced to upstream columns
+    Derived {
+        alias: String,
+        columns: Vec<ColumnLineage>,
+    },
+}
+
+impl ScopeEntry {
+    fn alias(&self) -> &str {
+        match self {
+            ScopeEntry::Relation { alias, .. } | ScopeEntry::Derived { alias, .. } => alias,
+        }
+    }
+}
+
+struct Analyzer<'a> {
+    project: &'a Project,
+    upstream: &'a LineageMap,
+}
+
+impl Analyzer<'_> {
+    fn query(
+        &self,
+        query: &Query,
+        ctes: &mut Vec<HashMap<String, Vec<ColumnLineage>>>,
+    ) -> Result<Vec<ColumnLineage>> {
+        let pushed = query.with.is_some();
+        if let Some(with) = &query.with {
+            ctes.push(HashMap::new());
+            for cte in &with.cte_tables {
+                let columns = self.query(&cte.query, ctes)?;
+                let columns = rename_columns(columns, &cte.alias);
+                ctes.last_mut()
+                    .unwrap()
+                    .insert(ident_name(&cte.alias.name), columns);
+            }
+        }
+        let result = self.setexpr(&query.body, ctes);
+        if pushed {
+            ctes.pop();
+        }
+        result
+    }
+
+    fn setexpr(
+        &self,
+        setexpr: &SetExpr,
+        ctes: &mut Vec<HashMap<String, Vec<ColumnLineage>>>,
+    ) -> Result<Vec<ColumnLineage>> {
+        match setexpr {
+            SetExpr::Select(select) => self.select(select, ctes),
+            SetExpr::Query(query) => self.query(query, ctes),
+            SetExpr::SetOperation { left, right, .. } => {
+                // Output names come from the left side; sources merge by position
+                let mut columns = self.setexpr(left, ctes)?;
+                let right = self.setexpr(right, ctes)?;
+                for (column, other) in columns.iter_mut().zip(right) {
+                    column.sources.extend(other.sources);
+                }
+                Ok(columns)
+            }
+            SetExpr::Values(_) => Ok(Vec::new()),
+            other => bail!("unsupported query body for column lineage: {other}"),
+        }
+    }
+
+    fn select(
+        &self,
+        select: &Select,
+        ctes: &mut Vec<HashMap<String, Vec<ColumnLineage>>>,
+    ) -> Result<Vec<ColumnLineage>> {
+        let mut scope = Vec::new();
+        for table in &select.from {
+            self.table_with_joins(table, ctes, &mut scope)?;
+        }
+
+        let mut columns = Vec::new();
+        for item in &select.projection {
+            match item {
+                SelectItem::UnnamedExpr(expr) => columns.push(ColumnLineage {
+                    column: output_name(expr),
+                    sources: self.expr_sources(expr, &scope, ctes)?,
+                }),
+                SelectItem::ExprWithAlias { expr, alias } => columns.push(ColumnLineage {
+                    column: ident_name(alias),
+                    sources: self.expr_sources(expr, &scope, ctes)?,
+                }),
+                SelectItem::Wildcard(_) => {
+                    for entry in &scope {
+                        columns.extend(self.expand_wildcard(entry));
+                    }
+                }
+                SelectItem::QualifiedWildcard(
+                    SelectItemQualifiedWildcardKind::ObjectName(name),
+                    _,
+                ) => {
+                    let qualifier = object_name_parts(name);
+                    if let Some(entry) = find_qualified(&scope, &qualifier) {
+                        columns.extend(self.expand_wildcard(entry));
+                    }
+                }
+                SelectItem::QualifiedWildcard(SelectItemQualifiedWildcardKind::Expr(_), _) => {}
+            }
+        }
+        Ok(columns)
+    }
+
+    fn table_with_joins(
+        &self,
+        table: &TableWithJoins,
+        ctes: &mut Vec<HashMap<String, Vec<ColumnLineage>>>,
+        scope: &mut Vec<ScopeEntry>,
+    ) -> Result<()> {
+        self.table_factor(&table.relation, ctes, scope)?;
+        for join in &table.joins {
+            self.table_factor(&join.relation, ctes, scope)?;
+        }
+        Ok(())
+    }
+
+    fn table_factor(
+        &self,
+        tf: &TableFactor,
+        ctes: &mut Vec<HashMap<String, Vec<ColumnLineage>>>,
+        scope: &mut Vec<ScopeEntry>,
+    ) -> Result<()> {
+        match tf {
+            TableFactor::Table { name, alias, .. } => {
+                let parts = object_name_parts(name);
+                let Some(table) = parts.last().cloned() else {
+                    return Ok(());
+                };
+                let alias_name = alias.as_ref().map(|a| ident_name(&a.name));
+                if parts.len() == 1 {
+                    if let Some(columns) = ctes.iter().rev().find_map(|scope| scope.get(&table)) {
+                        let columns = match alias {
+                            Some(a) => rename_columns(columns.clone(), a),
+                            None => columns.clone(),
+                        };
+                        scope.push(ScopeEntry::Derived {
+                            alias: alias_name.unwrap_or(table),
+                            columns,
+                        });
+                        return Ok(());
+                    }
+                }
+                let relation = self.resolve_relation(&parts);
+                scope.push(ScopeEntry::Relation {
+                    alias: alias_name.unwrap_or(table),
+                    relation,
+                });
+            }
+            TableFactor::Derived {
+                subquery, alias, ..
+            } => {
+                let columns = self.query(subquery, ctes)?;
+                if let Some(alias) = alias {
+                    scope.push(ScopeEntry::Derived {
+                        alias: ident_name(&alias.name),
+                        columns: rename_columns(columns, alias),
+                    });
+                }
+            }
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => self.table_with_joins(table_with_joins, ctes, scope)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Qualify a FROM-clause name; unqualified names match a unique model, else `public`
+    fn resolve_relation(&self, parts: &[String]) -> Relation {
+        let n = parts.len();
+        if n >= 2 {
+            return Relation {
+                schema: parts[n - 2].clone(),
+                name: parts[n - 1].clone(),
+            };
+        }
+        let name = &parts[0];
+        let mut candidates = self.project.models.keys().filter(|rel| &rel.name == name);
+        match (candidates.next(), candidates.next()) {
+            (Some(rel), None) => rel.clone(),
+            _ => Relation {
+                schema: "public".to_string(),
+                name: name.clone(),
+            },
+        }
+    }
+
+    fn expand_wildcard(&self, entry: &ScopeEntry) -> Vec<ColumnLineage> {
+        match entry {
+            ScopeEntry::Derived { columns, .. } => columns.clone(),
+            ScopeEntry::Relation { relation, .. } => match self.upstream.get(relation) {
+                Some(upstream) => upstream
+                    .iter()
+                    .map(|c| ColumnLineage {
+                        column: c.column.clone(),
+                        sources: BTreeSet::from([ColumnRef {
+                            relation: relation.clone(),
+                            column: c.column.clone(),
+                        }]),
+                    })
+                    .collect(),
+                None => vec![ColumnLineage {
+                    column: "*".to_string(),
+                    sources: BTreeSet::from([ColumnRef {
+                        relation: relation.clone(),
+                        column: "*".to_string(),
+                    }]),
+                }],
+            },
+        }
+    }
+
+    /// Whether a relation in scope is known to have the column
+    fn has_column(&self, entry: &ScopeEntry, column: &str) -> Option<bool> {
+        match entry {
+            ScopeEntry::Derived { columns, .. } => Some(columns.iter().any(|c| c.column == column)),
+            ScopeEntry::Relation { relation, .. } => self
+                .upstream
+                .get(relation)
+                .map(|columns| columns.iter().any(|c| c.column == column)),
+        }
+    }
+
+    /// Trace a column reference to upstream columns
+    fn resolve_column(
+        &self,
+        qualifier: &[String],
+        column: &str,
+        scope: &[ScopeEntry],
+    ) -> Vec<ColumnRef> {
+        let entry = if qualifier.is_empty() {
+            // Prefer the one relation known to have the column, then a lone
+            // relation, then the one relation whose columns are unknown
+            let known: Vec<&ScopeEntry> = scope
+                .iter()
+                .filter(|e| self.has_column(e, column) == Some(true))
+                .collect();
+            let unknown: Vec<&ScopeEntry> = scope
+                .iter()
+                .filter(|e| self.has_column(e, column).is_none())
+                .collect();
+            match (known.as_slice(), unknown.as_slice(), scope) {
+                ([entry], _, _) => Some(*entry),
+                ([], [entry], _) => Some(*entry),
+                (_, _, [entry]) => Some(entry),
+                _ => None,
+            }
+        } else {
+            find_qualified(scope, qualifier)
+        };
+        match entry {
+            Some(ScopeEntry::Relation { relation, .. }) => vec![ColumnRef {
+                relation: relation.clone(),
+                column: column.to_string(),
+            }],
+            Some(ScopeEntry::Derived { columns, .. }) => columns
+                .iter()
+                .filter(|c| c.column == column)
+                .flat_map(|c| c.sources.iter().cloned())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn expr_sources(
+        &self,
+        expr: &Expr,
+        scope: &[ScopeEntry],
+        ctes: &mut Vec<HashMap<String, Vec<ColumnLineage>>>,
+    ) -> Result<BTreeSet<ColumnRef>> {
+        let mut refs = Vec::new();
+        let mut subqueries = Vec::new();
+        collect_column_refs(expr, &mut refs, &mut subqueries);
+        let mut sources = BTreeSet::new();
+        for parts in refs {
+            let (column, qualifier) = parts.split_last().unwrap();
+            sources.extend(self.resolve_column(qualifier, column, scope));
+        }
+        for query in subqueries {
+            for column in self.query(query, ctes)? {
+                sources.extend(column.sources);
+            }
+        }
+        Ok(sources)
+    }
+}
+
+/// Collect column references (as name parts) and scalar subqueries in an expression
+fn collect_column_refs<'a>(
+    expr: &'a Expr,
+    refs: &mut Vec<Vec<String>>,
+    subqueries: &mut Vec<&'a Query>,
+) {
+    let mut walk = |e: &'a Expr| collect_column_refs(e, refs, subqueries);
+    match expr {
+        Expr::Identifier(ident) => refs.push(vec![ident_name(ident)]),
+        Expr::CompoundIdentifier(idents) => refs.push(idents.iter().map(ident_name).collect()),
+        Expr::Subquery(q) => subqueries.push(q),
+        Expr::CompoundFieldAccess { root, .. } => walk(root),
+        Expr::BinaryOp { left, right, .. }
+        | Expr::AnyOp { left, right, .. }
+        | Expr::AllOp { left, right, .. } => {
+            walk(left);
+            walk(right);
+        }
+        Expr::Like { expr, pattern, .. }
+        | Expr::ILike { expr, pattern, .. }
+        | Expr::SimilarTo { expr, pattern, .. } => {
+            walk(expr);
+            walk(pattern);
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::Extract { expr, .. }
+        | Expr::Collate { expr, .. }
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::IsTrue(expr)
+        | Expr::IsNotTrue(expr)
+        | Expr::IsFalse(expr)
+        | Expr::IsNotFalse(expr)
+        | Expr::IsUnknown(expr)
+        | Expr::IsNotUnknown(expr) => walk(expr),
+        Expr::IsDistinctFrom(a, b) | Expr::IsNotDistinctFrom(a, b) => {
+            walk(a);
+            walk(b);
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            walk(expr);
+            walk(low);
+            walk(high);
+        }
+        Expr::InList { expr, list, .. } => {
+            walk(expr);
+            for item in list {
+                walk(item);
+            }
+        }
+        Expr::Tuple(items) => {
+            for item in items {
+                walk(item);
+            }
+        }
+        Expr::Position { expr, r#in } => {
+            walk(expr);
+            walk(r#in);
+        }
+        Expr::Substring {
+            expr,
+            substring_from,
+            substring_for,
+            ..
+        } => {
+            walk(expr);
+            for e in [substring_from, substring_for].into_iter().flatten() {
+                walk(e);
+            }
+        }
+        Expr::Trim {
+            expr, trim_what, ..
+        } => {
+            walk(expr);
+            if let Some(e) = trim_what {
+                walk(e);
+            }
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            else_result,
+            ..
+        } => {
+            if let Some(op) = operand {
+                walk(op);
+            }
+            for when in conditions {
+                walk(&when.condition);
+                walk(&when.result);
+            }
+            if let Some(e) = else_result {
+                walk(e);
+            }
+        }
+        Expr::Function(f) => {
+            if let FunctionArguments::List(list) = &f.args {
+                for arg in &list.args {
+                    let arg = match arg {
+                        FunctionArg::Unnamed(a) => a,
+                        FunctionArg::Named { arg, .. } | FunctionArg::ExprNamed { arg, .. } => arg,
+                    };
+                    if let FunctionArgExpr::Expr(e) = arg {
+                        walk(e);
+                    }
+                }
+            }
+            if let Some(filter) = &f.filter {
+                walk(filter);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Name Postgres gives an unaliased SELECT expression
+fn output_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) => ident_name(ident),
+        Expr::CompoundIdentifier(idents) => idents.last().map(ident_name).unwrap_or_default(),
+        Expr::Function(f) => object_name_parts(&f.name).pop().unwrap_or_default(),
+        Expr::Cast { expr, .. } | Expr::Nested(expr) => output_name(expr),
+        Expr::Case { .. } => "case".to_string(),
+        _ => "?column?".to_string(),
+    }
+}
+
+/// Unquoted identifiers fold to lower case, as in Postgres
+fn ident_name(ident: &Ident) -> String {
+    if ident.quote_style.is_some() {
+        ident.value.clone()
+    } else {
+        ident.value.to_lowercase()
+    }
+}
+
+fn object_name_parts(name: &ObjectName) -> Vec<String> {
+    name.0
+        .iter()
+        .filter_map(|p| p.as_ident())
+        .map(ident_name)
+        .collect()
+}
+
+/// Apply `alias(a, b, ...)` column renames
+fn rename_columns(mut columns: Vec<ColumnLineage>, alias: &TableAlias) -> Vec<ColumnLineage> {
+    for (column, name) in columns.iter_mut().zip(&alias.columns) {
+        column.column = ident_name(&name.name);
+    }
+    columns
+}
+
+/// Find the FROM entry a qualifier (`alias` or `schema.table`) refers to
+fn find_qualified<'s>(scope: &'s [ScopeEntry], qualifier: &[String]) -> Option<&'s ScopeEntry> {
+    match qualifier {
+        [alias] => scope.iter().find(|e| e.alias() == alias),
+        [.., schema, table] => scope.iter().find(|e| match e {
+            ScopeEntry::Relation { relation, .. } => {
+                &relation.schema == schema && &relation.name == table
+            }
+            ScopeEntry::Derived { .. } => false,
+        }),
+        [] => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_project;
+
+    fn sources_of(lineage: &[ColumnLineage], column: &str) -> Vec<String> {
+        lineage
+            .iter()
+            .find(|c| c.column == column)
+            .unwrap_or_else(|| panic!("no column {column}"))
+            .sources
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_aliases_joins_and_expressions() {
+        let project = test_project(vec![(
+            "marts.orders",
+            "-- materialized: view\n\
+             SELECT o.id, u.Email AS user_email, o.amount * o.qty AS total, count(*) AS n \
+             FROM app.orders o JOIN app.users u ON u.id = o.user_id GROUP BY 1, 2, 3",
+        )]);
+        let (lineage, errors) = project_column_lineage(&project).unwrap();
+        assert!(errors.is_empty());
+        let columns = &lineage[&Relation::parse("marts.orders").unwrap()];
+        assert_eq!(sources_of(columns, "id"), vec!["app.orders.id"]);
+        assert_eq!(sources_of(columns, "user_email"), vec!["app.users.email"]);
+        assert_eq!(
+            sources_of(columns, "total"),
+            vec!["app.orders.amount", "app.orders.qty"]
+        );
+        assert!(sources_of(columns, "n").is_empty());
+    }
+
+    #[test]
+    fn test_ctes_subqueries_and_upstream_wildcards() {
+        let project = test_project(vec![
+            (
+                "staging.users",
+                "-- materialized: view\nSELECT id, lower(email) AS email FROM app.users",
+            ),
+            (
+                "marts.emails",
+                "-- materialized: view\n-- deps: staging.users\n\
+                 WITH u AS (SELECT * FROM staging.users) \
+                 SELECT s.email AS address, s.id FROM (SELECT id, email FROM u) s",
+            ),
+        ]);
+        let (lineage, _) = project_column_lineage(&project).unwrap();
+        let staging = &lineage[&Relation::parse("staging.users").unwrap()];
+        assert_eq!(sources_of(staging, "email"), vec!["app.users.email"]);
+        let marts = &lineage[&Relation::parse("marts.emails").unwrap()];
+        assert_eq!(sources_of(marts, "address"), vec!["staging.users.email"]);
+        assert_eq!(sources_of(marts, "id"), vec!["staging.users.id"]);
+    }
+
+    #[test]
+    fn test_unqualified_columns_resolve_by_known_columns() {
+        let project = test_project(vec![
+            (
+                "staging.a",
+                "-- materialized: view\nSELECT 1 AS a_id, 2 AS shared",
+            ),
+            (
+                "marts.x",
+                "-- materialized: view\n-- deps: staging.a\n\
+                 SELECT a_id, other, shared FROM staging.a JOIN app.b ON true",
+            ),
+        ]);
+        let (lineage, _) = project_column_lineage(&project).unwrap();
+        let columns = &lineage[&Relation::parse("marts.x").unwrap()];
+        assert_eq!(sources_of(columns, "a_id"), vec!["staging.a.a_id"]);
+        // Not a column of staging.a, so it must come from the source
+        assert_eq!(sources_of(columns, "other"), vec!["app.b.other"]);
+        assert_eq!(sources_of(columns, "shared"), vec!["staging.a.shared"]);
+    }
+
+    #[test]
+    fn test_source_wildcard_and_union() {
+        let project = test_project(vec![(
+            "marts.all_events",
+            "-- materialized: view\n\
+             SELECT * FROM app.events UNION ALL SELECT * FROM app.archived_events",
+        )]);
+        let (lineage, _) = project_column_lineage(&project).unwrap();
+        let columns = &lineage[&Relation::parse("marts.all_events").unwrap()];
+        assert_eq!(
+            sources_of(columns, "*"),
+            vec!["app.archived_events.*", "app.events.*"]
+        );
+    }
+}
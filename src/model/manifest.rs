@@ -0,0 +1,255 @@
+//! Run manifests: checksums of each model's compiled SQL and header.
+//!
+//! `model run` writes `target/manifest.json` after a successful run. A manifest
+//! saved from another environment (e.g. production) can be passed back with
+//! `--state PATH` to select only models that changed since (`state:modified`)
+//! or that did not exist then (`state:new`).
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{generate_run_sql, Model, ModelHeader, Project, Relation, SnapshotStrategy};
+
+/// Manifest location relative to the project root
+pub const MANIFEST_PATH: &str = "target/manifest.json";
+
+const MANIFEST_VERSION: u32 = 1;
+
+/// Snapshot of every model in a project, keyed by `schema.name`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Manifest {
+    pub version: u32,
+    pub models: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ManifestEntry {
+    /// sha256 of the compiled SQL and parsed header
+    pub checksum: String,
+    pub materialized: String,
+    pub deps: Vec<String>,
+}
+
+impl Manifest {
+    /// Build a manifest describing the project as currently compiled
+    pub fn from_project(project: &Project) -> Self {
+        let models = project
+            .models
+            .iter()
+            .map(|(rel, model)| {
+                let entry = ManifestEntry {
+                    checksum: model_checksum(model),
+                    materialized: model.header.materialized.as_str().to_string(),
+                    deps: model.header.deps.iter().map(|d| d.to_string()).collect(),
+                };
+                (rel.to_string(), entry)
+            })
+            .collect();
+        Self {
+            version: MANIFEST_VERSION,
+            models,
+        }
+    }
+
+    /// Load a manifest from a file, or from `manifest.json` inside a directory
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = if path.is_dir() {
+            path.join("manifest.json")
+        } else {
+            path.to_path_buf()
+        };
+        let content = fs::read_to_string(&file)
+            .with_context(|| format!("read state manifest {}", file.display()))?;
+        let manifest: Manifest = serde_json::from_str(&content)
+            .with_context(|| format!("parse state manifest {}", file.display()))?;
+        if manifest.version != MANIFEST_VERSION {
+            bail!(
+                "unsupported manifest version {} in {} (expected {})",
+                manifest.version,
+                file.display(),
+                MANIFEST_VERSION
+            );
+        }
+        Ok(manifest)
+    }
+
+    /// Write the manifest to `target/manifest.json` under the project root
+    pub fn write(&self, root: &Path) -> Result<PathBuf> {
+        let path = root.join(MANIFEST_PATH);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self).context("serialize manifest")?;
+        fs::write(&path, json + "\n").with_context(|| format!("write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Whether the model is recorded in this manifest
+    pub fn contains(&self, rel: &Relation) -> bool {
+        self.models.contains_key(&rel.to_string())
+    }
+
+    /// Whether the model is new or its checksum differs from this manifest
+    pub fn is_modified(&self, model: &Model) -> bool {
+        self.models
+            .get(&model.id.to_string())
+            .is_none_or(|entry| entry.checksum != model_checksum(model))
+    }
+}
+
+/// Checksum of a model's compiled SQL plus the header directives that affect
+/// its build
+pub fn model_checksum(model: &Model) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(generate_run_sql(model).as_bytes());
+    if let Some(incremental_sql) = &model.incremental_sql {
+        hasher.update(b"\n-- @incremental\n");
+        hasher.update(incremental_sql.as_bytes());
+    }
+    for line in build_directives(&model.header) {
+        hasher.update(b"\n");
+        hasher.update(line.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// `key: value` lines for the header directives that change how a model is
+/// built, in a fixed order. Listed field by field so that checksums survive
+/// new header fields and changes to derived traits; tests, tags, and the
+/// description don't change the build and are left out.
+fn build_directives(header: &ModelHeader) -> Vec<String> {
+    let list = |values: &[String]| serde_json::to_string(values).unwrap_or_default();
+    let deps: Vec<String> = header.deps.iter().map(|d| d.to_string()).collect();
+    let snapshot = header.snapshot.as_ref().map(|s| {
+        let strategy = match &s.strategy {
+            SnapshotStrategy::Timestamp { updated_at } => format!("timestamp {}", updated_at),
+            SnapshotStrategy::Check { columns } => format!("check {}", list(columns)),
+        };
+        format!(
+            "{} invalidate_hard_deletes={}",
+            strategy, s.invalidate_hard_deletes
+        )
+    });
+    let columns: Vec<String> = header
+        .columns
+        .iter()
+        .map(|c| format!("{} {}", c.name, c.data_type))
+        .collect();
+    vec![
+        format!("materialized: {}", header.materialized.as_str()),
+        format!("deps: {}", list(&deps)),
+        format!("unique_key: {}", list(&header.unique_key)),
+        format!(
+            "watermark: {}",
+            header.watermark.as_deref().map(list).unwrap_or_default()
+        ),
+        format!(
+            "lookback: {}",
+            header.lookback.as_deref().unwrap_or_default()
+        ),
+        format!(
+            "incremental_filter: {}",
+            header.incremental_filter.as_deref().unwrap_or_default()
+        ),
+        format!("unique_index: {}", list(&header.unique_index)),
+        format!(
+            "strategy: {}",
+            header.strategy.map(|s| s.as_str()).unwrap_or_default()
+        ),
+        format!("partition_by: {}", list(&header.partition_by)),
+        format!(
+            "on_schema_change: {}",
+            header
+                .on_schema_change
+                .map(|o| o.as_str())
+                .unwrap_or_default()
+        ),
+        format!("pre_hook: {}", list(&header.pre_hook)),
+        format!("post_hook: {}", list(&header.post_hook)),
+        format!(
+            "grants: {}",
+            serde_json::to_string(&header.grants).unwrap_or_default()
+        ),
+        format!("snapshot: {}", snapshot.unwrap_or_default()),
+        format!("columns: {}", list(&columns)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_project;
+
+    #[test]
+    fn test_unchanged_model_is_not_modified() {
+        let project = test_project(vec![("a.x", "-- materialized: view\nSELECT 1")]);
+        let manifest = Manifest::from_project(&project);
+        let model = &project.models[&Relation::parse("a.x").unwrap()];
+        assert!(!manifest.is_modified(model));
+    }
+
+    #[test]
+    fn test_changed_sql_and_header_are_modified() {
+        let before = Manifest::from_project(&test_project(vec![(
+            "a.x",
+            "-- materialized: view\nSELECT 1",
+        )]));
+
+        let project = test_project(vec![("a.x", "-- materialized: view\nSELECT 2")]);
+        assert!(before.is_modified(&project.models[&Relation::parse("a.x").unwrap()]));
+
+        let mut project = test_project(vec![("a.x", "-- materialized: view\nSELECT 1")]);
+        let model = project
+            .models
+            .get_mut(&Relation::parse("a.x").unwrap())
+            .unwrap();
+        model.header.unique_key.push("id".to_string());
+        assert!(before.is_modified(model));
+    }
+
+    #[test]
+    fn test_header_fields_outside_the_build_are_not_modified() {
+        let before = Manifest::from_project(&test_project(vec![(
+            "a.x",
+            "-- materialized: view\nSELECT 1",
+        )]));
+
+        let mut project = test_project(vec![("a.x", "-- materialized: view\nSELECT 1")]);
+        let model = project
+            .models
+            .get_mut(&Relation::parse("a.x").unwrap())
+            .unwrap();
+        model.header.tags.push("daily".to_string());
+        model.header.description = Some("Daily rollup".to_string());
+        assert!(!before.is_modified(model));
+    }
+
+    #[test]
+    fn test_new_model_is_modified() {
+        let before = Manifest::from_project(&test_project(vec![]));
+        let project = test_project(vec![("a.x", "-- materialized: view\nSELECT 1")]);
+        let rel = Relation::parse("a.x").unwrap();
+        assert!(!before.contains(&rel));
+        assert!(before.is_modified(&project.models[&rel]));
+    }
+
+    #[test]
+    fn test_write_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = Manifest::from_project(&test_project(vec![(
+            "a.x",
+            "-- materialized: view\nSELECT 1",
+        )]));
+        let path = manifest.write(dir.path()).unwrap();
+        assert_eq!(Manifest::load(&path).unwrap(), manifest);
+        // A directory containing manifest.json also works
+        assert_eq!(
+            Manifest::load(&dir.path().join("target")).unwrap(),
+            manifest
+        );
+    }
+}
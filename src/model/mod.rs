@@ -1,26 +1,53 @@
+mod clone;
 mod compile;
 mod dag;
+mod docs;
 mod execute;
+mod exposure;
+mod fdw;
+mod lineage;
 pub mod lint;
+mod manifest;
 mod parse;
+mod plan;
+mod run_results;
 pub mod select;
+mod sources;
+mod state;
+mod template;
 
 use anyhow::{bail, Result};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::path::PathBuf;
 
-pub use compile::{compile_model, generate_create_sql, generate_run_sql};
+pub use clone::{clone_sql, CloneKind};
+pub use compile::{
+    compile_model, generate_create_sql, generate_refresh_sql, generate_run_sql,
+    generate_unique_index_sql, inline_ephemeral,
+};
 pub use dag::{
-    get_downstream_order, get_upstream_order, load_project, topo_sort, topo_sort_layers,
+    get_downstream_order, get_ephemeral_deps, get_upstream_order, load_project,
+    load_project_with_vars, topo_sort, topo_sort_layers,
 };
+pub use docs::{page_path, render_index, render_model, DocColumn};
 pub use execute::{
-    ensure_schema, execute_model, generate_first_run_sql, generate_merge_sql, generate_upsert_sql,
-    IncrementalAction, ModelExecutionError,
+    contract_violations, ensure_schema, execute_model, generate_first_run_sql, generate_insert_sql,
+    generate_merge_sql, generate_replace_sql, generate_upsert_sql, ExecuteResult,
+    IncrementalAction, MatviewAction, ModelExecutionError,
 };
+pub use exposure::Exposure;
+pub use fdw::{foreign_sources, init_foreign_sources};
+pub use lineage::{project_column_lineage, ColumnLineage};
 pub use lint::{lint_deps, qualify_model_sql, rewrite_deps_line, rewrite_model_body_sql};
-pub use parse::parse_model_file;
-pub use select::apply_selectors;
+pub use manifest::Manifest;
+pub use parse::parse_model_source;
+pub use plan::plan_models;
+pub use run_results::{ModelRunResult, RunInvocation, RunResults, RunStatus};
+pub use select::{apply_selectors, apply_selectors_with_state};
+pub use sources::{add_sources, staging_model_sql};
+pub use state::{is_unchanged, load_model_state, record_model_state};
+pub use template::{has_template_placeholders, parse_var_assignment};
 
 /// A schema-qualified relation (schema.name)
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -63,6 +90,12 @@ pub enum Materialized {
     View,
     Table,
     Incremental,
+    /// Not created in the database; inlined as a CTE into downstream models
+    Ephemeral,
+    /// CREATE MATERIALIZED VIEW on first run, REFRESH afterwards
+    Matview,
+    /// SCD Type-2 history table maintained by `pgcrate model snapshot`
+    Snapshot,
 }
 
 impl Materialized {
@@ -71,6 +104,9 @@ impl Materialized {
             "view" => Ok(Self::View),
             "table" => Ok(Self::Table),
             "incremental" => Ok(Self::Incremental),
+            "ephemeral" => Ok(Self::Ephemeral),
+            "materialized_view" => Ok(Self::Matview),
+            "snapshot" => Ok(Self::Snapshot),
             other => bail!("invalid materialized value: {other}"),
         }
     }
@@ -80,6 +116,130 @@ impl Materialized {
             Materialized::View => "view",
             Materialized::Table => "table",
             Materialized::Incremental => "incremental",
+            Materialized::Ephemeral => "ephemeral",
+            Materialized::Matview => "materialized_view",
+            Materialized::Snapshot => "snapshot",
+        }
+    }
+
+    /// Whether this materialization creates a database object
+    pub fn is_materialized(&self) -> bool {
+        !matches!(self, Materialized::Ephemeral)
+    }
+}
+
+/// How an incremental model writes new rows into an existing table
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IncrementalStrategy {
+    /// Update matching unique_key rows and insert new ones (MERGE, or upsert before PG 17)
+    Merge,
+    /// Insert every row; nothing is updated or removed
+    Append,
+    /// Delete rows whose unique_key appears in the new data, then insert it
+    DeleteInsert,
+    /// Replace whole partitions: delete rows whose partition_by values appear in the
+    /// new data, then insert it
+    InsertOverwrite,
+}
+
+impl IncrementalStrategy {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim() {
+            "merge" => Ok(Self::Merge),
+            "append" => Ok(Self::Append),
+            "delete+insert" => Ok(Self::DeleteInsert),
+            "insert_overwrite" => Ok(Self::InsertOverwrite),
+            other => bail!(
+                "invalid strategy: {other} (expected merge, append, delete+insert or insert_overwrite)"
+            ),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IncrementalStrategy::Merge => "merge",
+            IncrementalStrategy::Append => "append",
+            IncrementalStrategy::DeleteInsert => "delete+insert",
+            IncrementalStrategy::InsertOverwrite => "insert_overwrite",
+        }
+    }
+
+    /// Whether the strategy matches rows by unique_key
+    pub fn requires_unique_key(&self) -> bool {
+        matches!(self, Self::Merge | Self::DeleteInsert)
+    }
+}
+
+/// How a snapshot model detects that a source row changed
+#[derive(Clone, Debug, PartialEq)]
+pub enum SnapshotStrategy {
+    /// A newer value in this column means a new version
+    Timestamp { updated_at: String },
+    /// Any difference in these columns means a new version (empty = every column)
+    Check { columns: Vec<String> },
+}
+
+/// Settings for a snapshot model (SCD Type-2 history)
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotConfig {
+    pub strategy: SnapshotStrategy,
+    /// Close the current version of rows whose key disappeared from the source
+    pub invalidate_hard_deletes: bool,
+}
+
+impl SnapshotConfig {
+    /// SQL for when a new version of source row `alias` becomes valid
+    pub fn valid_from_sql(&self, alias: &str) -> String {
+        match &self.strategy {
+            SnapshotStrategy::Timestamp { updated_at } => {
+                format!("{}.{}::timestamptz", alias, sql_quote_ident(updated_at))
+            }
+            SnapshotStrategy::Check { .. } => "now()".to_string(),
+        }
+    }
+}
+
+/// Columns pgcrate adds to snapshot tables to track each row version
+pub const SNAPSHOT_COLUMNS: [&str; 3] = ["valid_from", "valid_to", "is_current"];
+
+/// A column declared in a model's `columns:` contract
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnContract {
+    pub name: String,
+    /// Type as written in the header, e.g. `numeric(10,2)`
+    pub data_type: String,
+}
+
+/// How an incremental model reacts when its SELECT columns differ from the table
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OnSchemaChange {
+    /// Abort the run, reporting added and removed columns
+    Fail,
+    /// ALTER TABLE ADD COLUMN for new columns; removed columns are left in place
+    AppendNewColumns,
+    /// Add new columns, drop removed columns and change column types to match
+    SyncAllColumns,
+}
+
+impl OnSchemaChange {
+    /// Parse a header value; `ignore` (the default) yields `None`
+    pub fn parse(s: &str) -> Result<Option<Self>> {
+        match s.trim() {
+            "ignore" => Ok(None),
+            "fail" => Ok(Some(Self::Fail)),
+            "append_new_columns" => Ok(Some(Self::AppendNewColumns)),
+            "sync_all_columns" => Ok(Some(Self::SyncAllColumns)),
+            other => bail!(
+                "invalid on_schema_change value: {other} (expected ignore, fail, append_new_columns or sync_all_columns)"
+            ),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OnSchemaChange::Fail => "fail",
+            OnSchemaChange::AppendNewColumns => "append_new_columns",
+            OnSchemaChange::SyncAllColumns => "sync_all_columns",
         }
     }
 }
@@ -104,6 +264,108 @@ pub enum Test {
     },
 }
 
+/// How seriously a failing test is taken
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TestSeverity {
+    /// Failures past error_if fail `model test`; past warn_if they warn
+    #[default]
+    Error,
+    /// Failures only ever warn
+    Warn,
+}
+
+/// Comparison applied to a test's failure count, e.g. `>100`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Threshold {
+    pub op: ThresholdOp,
+    pub value: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThresholdOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+impl Threshold {
+    /// Parse `>N`, `>=N`, `<N`, `<=N`, `=N` or `!=N`
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (op, rest) = [
+            (">=", ThresholdOp::Ge),
+            ("<=", ThresholdOp::Le),
+            ("!=", ThresholdOp::Ne),
+            ("<>", ThresholdOp::Ne),
+            (">", ThresholdOp::Gt),
+            ("<", ThresholdOp::Lt),
+            ("==", ThresholdOp::Eq),
+            ("=", ThresholdOp::Eq),
+        ]
+        .iter()
+        .find_map(|(prefix, op)| s.strip_prefix(prefix).map(|rest| (*op, rest)))
+        .ok_or_else(|| anyhow::anyhow!("invalid threshold: {s} (expected e.g. >100 or !=0)"))?;
+        let value = rest
+            .trim()
+            .parse()
+            .map_err(|_| anyhow::anyhow!("invalid threshold: {s} (expected e.g. >100 or !=0)"))?;
+        Ok(Self { op, value })
+    }
+
+    pub fn matches(&self, failures: i64) -> bool {
+        match self.op {
+            ThresholdOp::Gt => failures > self.value,
+            ThresholdOp::Ge => failures >= self.value,
+            ThresholdOp::Lt => failures < self.value,
+            ThresholdOp::Le => failures <= self.value,
+            ThresholdOp::Eq => failures == self.value,
+            ThresholdOp::Ne => failures != self.value,
+        }
+    }
+}
+
+impl Default for Threshold {
+    /// Any failure at all
+    fn default() -> Self {
+        Self {
+            op: ThresholdOp::Ne,
+            value: 0,
+        }
+    }
+}
+
+/// Severity and thresholds for one test, from a `{severity: warn, error_if: >100}`
+/// block after the test in the `tests:` header
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct TestConfig {
+    pub severity: TestSeverity,
+    pub warn_if: Threshold,
+    pub error_if: Threshold,
+}
+
+/// Result of a test once its failure count is judged against its config
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestOutcome {
+    Pass,
+    Warn,
+    Error,
+}
+
+impl TestConfig {
+    pub fn outcome(&self, failures: i64) -> TestOutcome {
+        if self.severity == TestSeverity::Error && self.error_if.matches(failures) {
+            TestOutcome::Error
+        } else if self.warn_if.matches(failures) {
+            TestOutcome::Warn
+        } else {
+            TestOutcome::Pass
+        }
+    }
+}
+
 /// Escape a string value for SQL (double single quotes)
 fn sql_escape_string(s: &str) -> String {
     s.replace('\'', "''")
@@ -163,6 +425,62 @@ impl Test {
         }
     }
 
+    /// Generate SQL selecting the rows that violate this test (for `--store-failures`).
+    /// For unique, one row per duplicated key with its count.
+    pub fn failures_sql(&self, model: &Relation) -> String {
+        match self {
+            Test::NotNull { column } => format!(
+                "SELECT * FROM {} WHERE {} IS NULL",
+                model,
+                sql_quote_ident(column)
+            ),
+            Test::Unique { .. } => self.to_sql(model),
+            Test::AcceptedValues { column, values } => {
+                let escaped: Vec<String> = values
+                    .iter()
+                    .map(|v| format!("'{}'", sql_escape_string(v)))
+                    .collect();
+                format!(
+                    "SELECT * FROM {} WHERE {} NOT IN ({})",
+                    model,
+                    sql_quote_ident(column),
+                    escaped.join(", ")
+                )
+            }
+            Test::Relationships {
+                column,
+                target_table,
+                target_column,
+            } => format!(
+                "SELECT m.* FROM {} m \
+                 WHERE m.{} IS NOT NULL \
+                 AND NOT EXISTS (SELECT 1 FROM {} t WHERE t.{} = m.{})",
+                model,
+                sql_quote_ident(column),
+                target_table,
+                sql_quote_ident(target_column),
+                sql_quote_ident(column)
+            ),
+        }
+    }
+
+    /// Table name for stored failures: `<schema>_<model>__<test>_<columns>`
+    pub fn failures_table_name(&self, model: &Relation) -> String {
+        let (kind, columns) = match self {
+            Test::NotNull { column } => ("not_null", vec![column.as_str()]),
+            Test::Unique { columns } => ("unique", columns.iter().map(String::as_str).collect()),
+            Test::AcceptedValues { column, .. } => ("accepted_values", vec![column.as_str()]),
+            Test::Relationships { column, .. } => ("relationships", vec![column.as_str()]),
+        };
+        format!(
+            "{}_{}__{}_{}",
+            model.schema,
+            model.name,
+            kind,
+            columns.join("_")
+        )
+    }
+
     pub fn description(&self) -> String {
         match self {
             Test::NotNull { column } => format!("not_null({})", column),
@@ -191,6 +509,8 @@ pub struct ModelHeader {
     pub deps: Vec<Relation>,
     pub unique_key: Vec<String>,
     pub tests: Vec<Test>,
+    /// Severity/thresholds for `tests`, by position (missing entries use the defaults)
+    pub test_configs: Vec<TestConfig>,
     pub tags: Vec<String>,
     /// For incremental models: column(s) to use as watermark for filtering
     /// e.g., "updated_at" or "updated_at, id" for compound watermark
@@ -201,6 +521,35 @@ pub struct ModelHeader {
     /// For incremental models: custom filter predicate (mutually exclusive with watermark)
     /// e.g., "created_at > current_date - interval '7 days'"
     pub incremental_filter: Option<String>,
+    /// For materialized views: column(s) of a unique index, enabling REFRESH ... CONCURRENTLY
+    pub unique_index: Vec<String>,
+    /// For incremental models: how new rows are written (None = merge)
+    pub strategy: Option<IncrementalStrategy>,
+    /// For insert_overwrite: column(s) identifying the partitions to replace
+    pub partition_by: Vec<String>,
+    /// For incremental models: what to do when the SELECT's columns drift from the table
+    /// (None = ignore: merge only the table's existing columns)
+    pub on_schema_change: Option<OnSchemaChange>,
+    /// SQL run before the model is built (one entry per `-- pre_hook:` line)
+    pub pre_hook: Vec<String>,
+    /// SQL run after the model is built (one entry per `-- post_hook:` line)
+    pub post_hook: Vec<String>,
+    /// Desired grantees per privilege (e.g. SELECT -> [analytics_ro]), from the
+    /// `grants:` header merged over `[model.grants]`
+    pub grants: BTreeMap<String, Vec<String>>,
+    /// For snapshot models: change detection and hard-delete handling
+    pub snapshot: Option<SnapshotConfig>,
+    /// Declared output columns (`columns:` header), enforced with `--enforce-contracts`
+    pub columns: Vec<ColumnContract>,
+    /// Free-text description for `model docs` (`description:` lines are joined)
+    pub description: Option<String>,
+}
+
+impl ModelHeader {
+    /// Severity and thresholds for the test at `index` in `tests`
+    pub fn test_config(&self, index: usize) -> TestConfig {
+        self.test_configs.get(index).copied().unwrap_or_default()
+    }
 }
 
 /// A SQL model with its metadata
@@ -233,6 +582,24 @@ impl Model {
         sql.replace("${this}", &self.id.to_string())
     }
 
+    /// Pre-hook SQL with ${this} substituted
+    pub fn pre_hook_sql(&self) -> Vec<String> {
+        self.header
+            .pre_hook
+            .iter()
+            .map(|h| h.replace("${this}", &self.id.to_string()))
+            .collect()
+    }
+
+    /// Post-hook SQL with ${this} substituted
+    pub fn post_hook_sql(&self) -> Vec<String> {
+        self.header
+            .post_hook
+            .iter()
+            .map(|h| h.replace("${this}", &self.id.to_string()))
+            .collect()
+    }
+
     /// Generate the watermark filter WHERE clause for incremental runs
     /// Returns None if no watermark is configured or if it's first run
     pub fn watermark_filter_sql(&self) -> Option<String> {
@@ -290,6 +657,48 @@ pub struct Project {
     pub root: PathBuf,
     pub models: HashMap<Relation, Model>,
     pub sources: HashSet<Relation>,
+    /// Consumers declared in exposures.toml, sorted by name
+    pub exposures: Vec<Exposure>,
+    /// Named selection expressions from selectors.toml
+    pub selectors: BTreeMap<String, Vec<String>>,
+}
+
+impl Project {
+    /// Exposures that read any of the given models
+    pub fn exposures_for(&self, models: &[Relation]) -> Vec<&Exposure> {
+        self.exposures
+            .iter()
+            .filter(|e| e.depends_on_any(models))
+            .collect()
+    }
+}
+
+/// Project of `(schema.name, model source)` pairs, for unit tests
+#[cfg(test)]
+pub(crate) fn test_project(models: Vec<(&str, &str)>) -> Project {
+    let mut project = Project {
+        root: PathBuf::new(),
+        sources: HashSet::new(),
+        exposures: Vec::new(),
+        selectors: Default::default(),
+        models: HashMap::new(),
+    };
+    for (name, source) in models {
+        let rel = Relation::parse(name).unwrap();
+        let parsed = parse_model_source(source, std::path::Path::new("test.sql")).unwrap();
+        project.models.insert(
+            rel.clone(),
+            Model {
+                id: rel,
+                path: PathBuf::new(),
+                header: parsed.header,
+                body_sql: parsed.body_sql,
+                base_sql: parsed.base_sql,
+                incremental_sql: parsed.incremental_sql,
+            },
+        );
+    }
+    project
 }
 
 #[cfg(test)]
@@ -342,6 +751,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_materialized_parse_ephemeral() {
+        let mat = Materialized::parse("ephemeral").unwrap();
+        assert_eq!(mat, Materialized::Ephemeral);
+        assert!(!mat.is_materialized());
+        assert!(Materialized::View.is_materialized());
+    }
+
+    #[test]
+    fn test_materialized_parse_materialized_view() {
+        let mat = Materialized::parse("materialized_view").unwrap();
+        assert_eq!(mat, Materialized::Matview);
+        assert_eq!(mat.as_str(), "materialized_view");
+    }
+
     #[test]
     fn test_materialized_parse_invalid() {
         let err = Materialized::parse("unknown").unwrap_err();
@@ -434,6 +858,77 @@ mod tests {
         assert!(sql.contains("t.\"id\" = m.\"user_id\""));
     }
 
+    #[test]
+    fn test_threshold_parse_and_outcome() {
+        assert_eq!(
+            Threshold::parse(">100").unwrap(),
+            Threshold {
+                op: ThresholdOp::Gt,
+                value: 100
+            }
+        );
+        assert_eq!(Threshold::parse(">= 5").unwrap().op, ThresholdOp::Ge);
+        assert!(Threshold::parse("100").is_err());
+        assert!(Threshold::parse(">many").is_err());
+
+        let default = TestConfig::default();
+        assert_eq!(default.outcome(0), TestOutcome::Pass);
+        assert_eq!(default.outcome(1), TestOutcome::Error);
+
+        let config = TestConfig {
+            error_if: Threshold::parse(">100").unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(config.outcome(50), TestOutcome::Warn);
+        assert_eq!(config.outcome(101), TestOutcome::Error);
+
+        let config = TestConfig {
+            severity: TestSeverity::Warn,
+            ..Default::default()
+        };
+        assert_eq!(config.outcome(1000), TestOutcome::Warn);
+    }
+
+    #[test]
+    fn test_failures_sql_selects_violating_rows() {
+        let model = Relation {
+            schema: "analytics".into(),
+            name: "orders".into(),
+        };
+        let test = Test::NotNull {
+            column: "id".into(),
+        };
+        assert_eq!(
+            test.failures_sql(&model),
+            "SELECT * FROM analytics.orders WHERE \"id\" IS NULL"
+        );
+        assert_eq!(
+            test.failures_table_name(&model),
+            "analytics_orders__not_null_id"
+        );
+
+        let test = Test::Unique {
+            columns: vec!["user_id".into(), "day".into()],
+        };
+        assert!(test.failures_sql(&model).contains("HAVING COUNT(*) > 1"));
+        assert_eq!(
+            test.failures_table_name(&model),
+            "analytics_orders__unique_user_id_day"
+        );
+
+        let test = Test::Relationships {
+            column: "user_id".into(),
+            target_table: Relation {
+                schema: "app".into(),
+                name: "users".into(),
+            },
+            target_column: "id".into(),
+        };
+        assert!(test
+            .failures_sql(&model)
+            .starts_with("SELECT m.* FROM analytics.orders m"));
+    }
+
     #[test]
     fn test_sql_quote_ident_special_chars() {
         // Verify identifier quoting handles edge cases
@@ -478,10 +973,21 @@ mod tests {
                 deps: Vec::new(),
                 unique_key: vec!["date".into()],
                 tests: Vec::new(),
+                test_configs: Vec::new(),
                 tags: Vec::new(),
                 watermark: None,
                 lookback: None,
                 incremental_filter: None,
+                unique_index: Vec::new(),
+                strategy: None,
+                partition_by: Vec::new(),
+                on_schema_change: None,
+                pre_hook: Vec::new(),
+                post_hook: Vec::new(),
+                grants: Default::default(),
+                snapshot: None,
+                columns: Vec::new(),
+                description: None,
             },
             body_sql: "SELECT * FROM orders".into(),
             base_sql: base.map(|s| s.to_string()),
@@ -543,10 +1049,21 @@ mod tests {
                 deps: Vec::new(),
                 unique_key: vec!["id".into()],
                 tests: Vec::new(),
+                test_configs: Vec::new(),
                 tags: Vec::new(),
                 watermark: watermark.map(|v| v.into_iter().map(|s| s.to_string()).collect()),
                 lookback: lookback.map(|s| s.to_string()),
                 incremental_filter: None,
+                unique_index: Vec::new(),
+                strategy: None,
+                partition_by: Vec::new(),
+                on_schema_change: None,
+                pre_hook: Vec::new(),
+                post_hook: Vec::new(),
+                grants: Default::default(),
+                snapshot: None,
+                columns: Vec::new(),
+                description: None,
             },
             body_sql: "SELECT * FROM source".into(),
             base_sql: None,
@@ -1,9 +1,11 @@
 use anyhow::{anyhow, bail, Context, Result};
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
-use super::{Materialized, ModelHeader, Relation, Test};
+use super::{
+    ColumnContract, IncrementalStrategy, Materialized, ModelHeader, OnSchemaChange, Relation,
+    SnapshotConfig, SnapshotStrategy, Test, TestConfig, TestSeverity, Threshold,
+};
 
 /// Parsed model file result
 #[derive(Debug)]
@@ -14,11 +16,9 @@ pub struct ParsedModel {
     pub incremental_sql: Option<String>,
 }
 
-/// Parse a model file into header, body SQL, and optional sections
-pub fn parse_model_file(path: &Path) -> Result<ParsedModel> {
-    let text =
-        fs::read_to_string(path).with_context(|| format!("read model: {}", path.display()))?;
-
+/// Parse model source text (already templated) into header, body SQL, and optional sections.
+/// `path` is used for error messages.
+pub fn parse_model_source(text: &str, path: &Path) -> Result<ParsedModel> {
     let mut header_lines: Vec<&str> = Vec::new();
     let mut body_lines: Vec<&str> = Vec::new();
     let mut lines = text.lines();
@@ -161,6 +161,13 @@ fn save_section(
 /// Parse header lines into ModelHeader
 pub fn parse_header_block(lines: &[&str]) -> Result<ModelHeader> {
     let mut kv: HashMap<String, String> = HashMap::new();
+    // Hooks may repeat; each line is one hook, run in order
+    let mut pre_hook: Vec<String> = Vec::new();
+    let mut post_hook: Vec<String> = Vec::new();
+    // The columns contract may span several lines
+    let mut column_lines: Vec<&str> = Vec::new();
+    // Long descriptions may also span several lines
+    let mut description_lines: Vec<&str> = Vec::new();
     for line in lines {
         let s = line.trim();
         if !s.starts_with("--") {
@@ -170,7 +177,16 @@ pub fn parse_header_block(lines: &[&str]) -> Result<ModelHeader> {
         let Some((k, v)) = s.split_once(':') else {
             continue;
         };
-        kv.insert(k.trim().to_string(), v.trim().to_string());
+        let (k, v) = (k.trim(), v.trim());
+        match k {
+            "pre_hook" if !v.is_empty() => pre_hook.push(v.to_string()),
+            "post_hook" if !v.is_empty() => post_hook.push(v.to_string()),
+            "columns" if !v.is_empty() => column_lines.push(v),
+            "description" if !v.is_empty() => description_lines.push(v),
+            _ => {
+                kv.insert(k.to_string(), v.to_string());
+            }
+        }
     }
 
     let materialized = kv.get("materialized").ok_or_else(|| {
@@ -181,7 +197,7 @@ pub fn parse_header_block(lines: &[&str]) -> Result<ModelHeader> {
             anyhow!("missing required header key: materialized (use 'materialized', not '{}')",
                 kv.keys().find(|k| *k == "mat" || *k == "material").unwrap())
         } else {
-            anyhow!("missing required header key: materialized. Valid keys: materialized, deps, unique_key, tests, tags, watermark, lookback")
+            anyhow!("missing required header key: materialized. Valid keys: materialized, deps, unique_key, tests, tags, watermark, lookback, unique_index, strategy, partition_by, on_schema_change, pre_hook, post_hook, grants, updated_at, check_cols, invalidate_hard_deletes, columns, description")
         }
     })?;
     let materialized = Materialized::parse(materialized)?;
@@ -197,9 +213,9 @@ pub fn parse_header_block(lines: &[&str]) -> Result<ModelHeader> {
         .transpose()?
         .unwrap_or_default();
 
-    let tests = kv
+    let (tests, test_configs) = kv
         .get("tests")
-        .map(|s| parse_tests(s))
+        .map(|s| parse_tests_with_config(s))
         .transpose()?
         .unwrap_or_default();
 
@@ -222,8 +238,51 @@ pub fn parse_header_block(lines: &[&str]) -> Result<ModelHeader> {
     // Parse custom incremental filter predicate
     let incremental_filter = kv.get("incremental_filter").map(|s| s.to_string());
 
-    if matches!(materialized, Materialized::Incremental) && unique_key.is_empty() {
-        bail!("materialized: incremental requires unique_key");
+    // Parse unique index columns for materialized views
+    let unique_index = kv
+        .get("unique_index")
+        .map(|s| parse_ident_list(s))
+        .transpose()?
+        .unwrap_or_default();
+
+    let on_schema_change = kv
+        .get("on_schema_change")
+        .map(|s| OnSchemaChange::parse(s))
+        .transpose()?
+        .flatten();
+
+    if matches!(materialized, Materialized::Ephemeral) && !tests.is_empty() {
+        bail!("tests are not supported on ephemeral models (nothing is created to test); add them to a downstream model");
+    }
+
+    let strategy = kv
+        .get("strategy")
+        .map(|s| IncrementalStrategy::parse(s))
+        .transpose()?;
+    let partition_by = kv
+        .get("partition_by")
+        .map(|s| parse_ident_list(s))
+        .transpose()?
+        .unwrap_or_default();
+
+    if matches!(materialized, Materialized::Incremental)
+        && unique_key.is_empty()
+        && strategy
+            .unwrap_or(IncrementalStrategy::Merge)
+            .requires_unique_key()
+    {
+        bail!(
+            "materialized: incremental requires unique_key (or strategy: append / insert_overwrite)"
+        );
+    }
+    if strategy.is_some() && !matches!(materialized, Materialized::Incremental) {
+        bail!("strategy is only valid for incremental models");
+    }
+    if strategy == Some(IncrementalStrategy::InsertOverwrite) && partition_by.is_empty() {
+        bail!("strategy: insert_overwrite requires partition_by");
+    }
+    if !partition_by.is_empty() && strategy != Some(IncrementalStrategy::InsertOverwrite) {
+        bail!("partition_by is only valid with strategy: insert_overwrite");
     }
 
     // Validate watermark/lookback/incremental_filter only on incremental models
@@ -236,6 +295,31 @@ pub fn parse_header_block(lines: &[&str]) -> Result<ModelHeader> {
     if incremental_filter.is_some() && !matches!(materialized, Materialized::Incremental) {
         bail!("incremental_filter is only valid for incremental models");
     }
+    let grants = kv
+        .get("grants")
+        .map(|s| parse_grants(s))
+        .transpose()?
+        .unwrap_or_default();
+
+    if matches!(materialized, Materialized::Ephemeral) && !grants.is_empty() {
+        bail!("grants are not supported on ephemeral models (nothing is created to grant on)");
+    }
+    if matches!(materialized, Materialized::Ephemeral)
+        && (!pre_hook.is_empty() || !post_hook.is_empty())
+    {
+        bail!("pre_hook/post_hook are not supported on ephemeral models (nothing is built to hook into)");
+    }
+    if on_schema_change.is_some() && !matches!(materialized, Materialized::Incremental) {
+        bail!("on_schema_change is only valid for incremental models");
+    }
+    if !unique_index.is_empty() && !matches!(materialized, Materialized::Matview) {
+        bail!("unique_index is only valid for materialized_view models");
+    }
+    let snapshot = parse_snapshot_config(&kv, &materialized, &unique_key)?;
+    let columns = parse_columns(&column_lines)?;
+    if matches!(materialized, Materialized::Ephemeral) && !columns.is_empty() {
+        bail!("columns contracts are not supported on ephemeral models (nothing is created to enforce them on)");
+    }
     if lookback.is_some() && watermark.is_none() {
         bail!("lookback requires watermark to be set");
     }
@@ -249,13 +333,181 @@ pub fn parse_header_block(lines: &[&str]) -> Result<ModelHeader> {
         deps,
         unique_key,
         tests,
+        test_configs,
         tags,
         watermark,
         lookback,
         incremental_filter,
+        unique_index,
+        strategy,
+        partition_by,
+        on_schema_change,
+        pre_hook,
+        post_hook,
+        grants,
+        snapshot,
+        columns,
+        description: (!description_lines.is_empty()).then(|| description_lines.join(" ")),
     })
 }
 
+/// Parse `columns:` lines (`id bigint, amount numeric(10,2)`) into a contract.
+/// Commas inside parentheses belong to the type.
+fn parse_columns(lines: &[&str]) -> Result<Vec<ColumnContract>> {
+    let mut columns: Vec<ColumnContract> = Vec::new();
+    for line in lines {
+        let mut depth = 0usize;
+        let mut start = 0;
+        let mut entries = Vec::new();
+        for (i, ch) in line.char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    entries.push(&line[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        entries.push(&line[start..]);
+
+        for entry in entries.into_iter().map(str::trim).filter(|e| !e.is_empty()) {
+            let Some((name, data_type)) = entry.split_once(char::is_whitespace) else {
+                bail!("invalid columns entry (expected 'name type'): {}", entry);
+            };
+            let name = name.trim_matches('"').to_string();
+            if columns.iter().any(|c| c.name == name) {
+                bail!("duplicate column in columns: {}", name);
+            }
+            columns.push(ColumnContract {
+                name,
+                data_type: data_type.trim().to_string(),
+            });
+        }
+    }
+    Ok(columns)
+}
+
+/// Parse `updated_at` / `check_cols` / `invalidate_hard_deletes` for snapshot models
+fn parse_snapshot_config(
+    kv: &HashMap<String, String>,
+    materialized: &Materialized,
+    unique_key: &[String],
+) -> Result<Option<SnapshotConfig>> {
+    let updated_at = kv.get("updated_at");
+    let check_cols = kv.get("check_cols");
+    let invalidate = kv.get("invalidate_hard_deletes");
+
+    if !matches!(materialized, Materialized::Snapshot) {
+        if updated_at.is_some() || check_cols.is_some() || invalidate.is_some() {
+            bail!("updated_at, check_cols and invalidate_hard_deletes are only valid for snapshot models");
+        }
+        return Ok(None);
+    }
+
+    if unique_key.is_empty() {
+        bail!("materialized: snapshot requires unique_key");
+    }
+    let strategy = match (updated_at, check_cols) {
+        (Some(col), None) => {
+            let cols = parse_ident_list(col)?;
+            let [updated_at] = cols.as_slice() else {
+                bail!("updated_at must name a single column");
+            };
+            SnapshotStrategy::Timestamp {
+                updated_at: updated_at.clone(),
+            }
+        }
+        (None, Some(cols)) if cols.trim().eq_ignore_ascii_case("all") => {
+            SnapshotStrategy::Check {
+                columns: Vec::new(),
+            }
+        }
+        (None, Some(cols)) => {
+            let columns = parse_ident_list(cols)?;
+            if columns.is_empty() {
+                bail!("check_cols requires at least one column (or 'all')");
+            }
+            SnapshotStrategy::Check { columns }
+        }
+        (Some(_), Some(_)) => {
+            bail!("updated_at and check_cols are mutually exclusive; use one or the other")
+        }
+        (None, None) => bail!(
+            "materialized: snapshot requires updated_at (timestamp strategy) or check_cols (check strategy)"
+        ),
+    };
+
+    let invalidate_hard_deletes = match invalidate.map(|v| v.trim().to_ascii_lowercase()) {
+        None => false,
+        Some(v) if v == "true" => true,
+        Some(v) if v == "false" => false,
+        Some(v) => bail!("invalid invalidate_hard_deletes value: {v} (expected true or false)"),
+    };
+
+    Ok(Some(SnapshotConfig {
+        strategy,
+        invalidate_hard_deletes,
+    }))
+}
+
+/// Table privileges that can be managed with `grants:`
+const GRANTABLE_PRIVILEGES: &[&str] = &[
+    "SELECT",
+    "INSERT",
+    "UPDATE",
+    "DELETE",
+    "TRUNCATE",
+    "REFERENCES",
+    "TRIGGER",
+];
+
+/// Normalize a privilege name to its SQL keyword (e.g. `select` -> `SELECT`)
+pub fn normalize_privilege(s: &str) -> Result<String> {
+    let privilege = s.trim().to_uppercase();
+    if !GRANTABLE_PRIVILEGES.contains(&privilege.as_str()) {
+        bail!(
+            "invalid privilege '{}' (expected one of: {})",
+            s.trim(),
+            GRANTABLE_PRIVILEGES.join(", ").to_lowercase()
+        );
+    }
+    Ok(privilege)
+}
+
+/// Parse `select(role_a, role_b), insert(etl)` into privilege -> roles.
+/// An empty list (`select()`) means no role should hold the privilege.
+fn parse_grants(s: &str) -> Result<BTreeMap<String, Vec<String>>> {
+    let mut grants = BTreeMap::new();
+    let mut remaining = s.trim();
+    while !remaining.is_empty() {
+        let Some(open) = remaining.find('(') else {
+            bail!(
+                "invalid grants syntax (expected 'privilege(role, ...)'): {}",
+                remaining
+            );
+        };
+        let Some(close) = remaining[open..].find(')').map(|i| open + i) else {
+            bail!("invalid grants syntax (missing closing paren): {}", s);
+        };
+        let privilege = normalize_privilege(&remaining[..open])?;
+        let roles = parse_ident_list(&remaining[open + 1..close])?;
+        if grants.insert(privilege.clone(), roles).is_some() {
+            bail!(
+                "duplicate privilege in grants: {}",
+                privilege.to_lowercase()
+            );
+        }
+        remaining = remaining[close + 1..].trim_start();
+        remaining = remaining
+            .strip_prefix(',')
+            .unwrap_or(remaining)
+            .trim_start();
+    }
+    Ok(grants)
+}
+
 fn parse_rel_list(s: &str) -> Result<Vec<Relation>> {
     let s = s.trim();
     if s.is_empty() {
@@ -435,13 +687,21 @@ fn parse_column_ref(s: &str) -> Result<(super::Relation, String)> {
     ))
 }
 
+#[cfg(test)]
 fn parse_tests(s: &str) -> Result<Vec<Test>> {
+    Ok(parse_tests_with_config(s)?.0)
+}
+
+/// Parse the `tests:` header. Each test may be followed by a config block, e.g.
+/// `unique(email) {severity: warn}` or `not_null(id) {warn_if: >0, error_if: >100}`.
+fn parse_tests_with_config(s: &str) -> Result<(Vec<Test>, Vec<TestConfig>)> {
     let s = s.trim();
     if s.is_empty() {
-        return Ok(Vec::new());
+        return Ok((Vec::new(), Vec::new()));
     }
 
     let mut tests = Vec::new();
+    let mut configs = Vec::new();
     let mut remaining = s;
 
     while !remaining.is_empty() {
@@ -531,10 +791,50 @@ fn parse_tests(s: &str) -> Result<Vec<Test>> {
             }
             _ => bail!("unknown test type: {}. Valid types: not_null, unique, accepted_values, relationships", name),
         };
+
+        let mut config = TestConfig::default();
+        if let Some(block) = remaining.trim_start().strip_prefix('{') {
+            let Some(close) = block.find('}') else {
+                bail!("invalid test config (missing closing brace): {}", s);
+            };
+            config = parse_test_config(&block[..close])
+                .with_context(|| format!("config for test {}", test.description()))?;
+            remaining = &block[close + 1..];
+        }
         tests.push(test);
+        configs.push(config);
     }
 
-    Ok(tests)
+    Ok((tests, configs))
+}
+
+/// Parse `severity: warn, warn_if: >10, error_if: >100`
+fn parse_test_config(s: &str) -> Result<TestConfig> {
+    let mut config = TestConfig::default();
+    for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let Some((key, value)) = entry.split_once(':') else {
+            bail!(
+                "invalid test config entry (expected 'key: value'): {}",
+                entry
+            );
+        };
+        let value = value.trim();
+        match key.trim() {
+            "severity" => {
+                config.severity = match value.to_lowercase().as_str() {
+                    "warn" => TestSeverity::Warn,
+                    "error" => TestSeverity::Error,
+                    other => bail!("invalid severity: {other} (expected warn or error)"),
+                }
+            }
+            "warn_if" => config.warn_if = Threshold::parse(value)?,
+            "error_if" => config.error_if = Threshold::parse(value)?,
+            other => {
+                bail!("unknown test config key: {other} (expected severity, warn_if or error_if)")
+            }
+        }
+    }
+    Ok(config)
 }
 
 #[cfg(test)]
@@ -607,6 +907,263 @@ mod tests {
         assert_eq!(header.unique_key, vec!["id"]);
     }
 
+    #[test]
+    fn test_parse_header_block_ephemeral_rejects_tests() {
+        let lines = vec!["-- materialized: ephemeral", "-- tests: not_null(id)"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("not supported on ephemeral"));
+    }
+
+    #[test]
+    fn test_parse_header_block_materialized_view_unique_index() {
+        let lines = vec![
+            "-- materialized: materialized_view",
+            "-- unique_index: day, region",
+        ];
+        let header = parse_header_block(&lines).unwrap();
+        assert_eq!(header.materialized, Materialized::Matview);
+        assert_eq!(header.unique_index, vec!["day", "region"]);
+
+        let lines = vec!["-- materialized: table", "-- unique_index: day"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("only valid for materialized_view"));
+    }
+
+    #[test]
+    fn test_parse_header_block_on_schema_change() {
+        let lines = vec![
+            "-- materialized: incremental",
+            "-- unique_key: id",
+            "-- on_schema_change: append_new_columns",
+        ];
+        let header = parse_header_block(&lines).unwrap();
+        assert_eq!(
+            header.on_schema_change,
+            Some(OnSchemaChange::AppendNewColumns)
+        );
+
+        let lines = vec![
+            "-- materialized: incremental",
+            "-- unique_key: id",
+            "-- on_schema_change: ignore",
+        ];
+        assert_eq!(parse_header_block(&lines).unwrap().on_schema_change, None);
+
+        let lines = vec!["-- materialized: table", "-- on_schema_change: fail"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("only valid for incremental"));
+
+        let lines = vec![
+            "-- materialized: incremental",
+            "-- unique_key: id",
+            "-- on_schema_change: append",
+        ];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("append_new_columns"));
+    }
+
+    #[test]
+    fn test_parse_header_block_hooks_repeat_in_order() {
+        let lines = vec![
+            "-- materialized: table",
+            "-- pre_hook: SET LOCAL work_mem = '256MB'",
+            "-- post_hook: ANALYZE ${this}",
+            "-- post_hook: GRANT SELECT ON ${this} TO analytics_ro",
+        ];
+        let header = parse_header_block(&lines).unwrap();
+        assert_eq!(header.pre_hook, vec!["SET LOCAL work_mem = '256MB'"]);
+        assert_eq!(
+            header.post_hook,
+            vec!["ANALYZE ${this}", "GRANT SELECT ON ${this} TO analytics_ro"]
+        );
+
+        let lines = vec![
+            "-- materialized: ephemeral",
+            "-- post_hook: ANALYZE ${this}",
+        ];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("ephemeral"));
+    }
+
+    #[test]
+    fn test_parse_header_block_grants() {
+        let lines = vec![
+            "-- materialized: table",
+            "-- grants: select(analytics_ro, reporting), insert()",
+        ];
+        let header = parse_header_block(&lines).unwrap();
+        assert_eq!(
+            header.grants.get("SELECT"),
+            Some(&vec!["analytics_ro".to_string(), "reporting".to_string()])
+        );
+        assert_eq!(header.grants.get("INSERT"), Some(&Vec::new()));
+
+        let lines = vec!["-- materialized: table", "-- grants: usage(app)"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("invalid privilege 'usage'"));
+
+        let lines = vec!["-- materialized: table", "-- grants: select app"];
+        assert!(parse_header_block(&lines).is_err());
+    }
+
+    #[test]
+    fn test_parse_header_block_incremental_strategy() {
+        let lines = vec!["-- materialized: incremental", "-- strategy: append"];
+        let header = parse_header_block(&lines).unwrap();
+        assert_eq!(header.strategy, Some(IncrementalStrategy::Append));
+        assert!(header.unique_key.is_empty());
+
+        let lines = vec!["-- materialized: incremental", "-- strategy: delete+insert"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("requires unique_key"));
+
+        let lines = vec![
+            "-- materialized: incremental",
+            "-- strategy: insert_overwrite",
+        ];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("requires partition_by"));
+
+        let lines = vec![
+            "-- materialized: incremental",
+            "-- strategy: insert_overwrite",
+            "-- partition_by: day",
+        ];
+        let header = parse_header_block(&lines).unwrap();
+        assert_eq!(header.partition_by, vec!["day"]);
+
+        let lines = vec!["-- materialized: table", "-- strategy: append"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("only valid for incremental"));
+    }
+
+    #[test]
+    fn test_parse_header_block_snapshot() {
+        let lines = vec![
+            "-- materialized: snapshot",
+            "-- unique_key: id",
+            "-- updated_at: updated_at",
+            "-- invalidate_hard_deletes: true",
+        ];
+        let header = parse_header_block(&lines).unwrap();
+        assert_eq!(header.materialized, Materialized::Snapshot);
+        assert_eq!(
+            header.snapshot,
+            Some(SnapshotConfig {
+                strategy: SnapshotStrategy::Timestamp {
+                    updated_at: "updated_at".to_string()
+                },
+                invalidate_hard_deletes: true,
+            })
+        );
+
+        let lines = vec![
+            "-- materialized: snapshot",
+            "-- unique_key: id",
+            "-- check_cols: all",
+        ];
+        let header = parse_header_block(&lines).unwrap();
+        let config = header.snapshot.unwrap();
+        assert_eq!(
+            config.strategy,
+            SnapshotStrategy::Check {
+                columns: Vec::new()
+            }
+        );
+        assert!(!config.invalidate_hard_deletes);
+
+        let lines = vec!["-- materialized: snapshot", "-- updated_at: updated_at"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("requires unique_key"));
+
+        let lines = vec!["-- materialized: snapshot", "-- unique_key: id"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("requires updated_at"));
+
+        let lines = vec![
+            "-- materialized: snapshot",
+            "-- unique_key: id",
+            "-- updated_at: updated_at",
+            "-- check_cols: name",
+        ];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("mutually exclusive"));
+
+        let lines = vec!["-- materialized: table", "-- updated_at: updated_at"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("only valid for snapshot"));
+    }
+
+    #[test]
+    fn test_parse_header_block_description_lines_join() {
+        let lines = vec![
+            "-- materialized: view",
+            "-- description: Users with",
+            "-- description: their latest order",
+        ];
+        let header = parse_header_block(&lines).unwrap();
+        assert_eq!(
+            header.description.as_deref(),
+            Some("Users with their latest order")
+        );
+        let header = parse_header_block(&["-- materialized: view"]).unwrap();
+        assert_eq!(header.description, None);
+    }
+
+    #[test]
+    fn test_parse_header_block_columns_contract() {
+        let lines = vec![
+            "-- materialized: table",
+            "-- columns: id bigint, amount numeric(10,2)",
+            "-- columns: \"label\" character varying",
+        ];
+        let header = parse_header_block(&lines).unwrap();
+        let columns: Vec<(&str, &str)> = header
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.data_type.as_str()))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("id", "bigint"),
+                ("amount", "numeric(10,2)"),
+                ("label", "character varying")
+            ]
+        );
+
+        let lines = vec!["-- materialized: table", "-- columns: id"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("expected 'name type'"));
+
+        let lines = vec!["-- materialized: table", "-- columns: id int, id bigint"];
+        let err = parse_header_block(&lines).unwrap_err();
+        assert!(err.to_string().contains("duplicate column"));
+
+        let lines = vec!["-- materialized: ephemeral", "-- columns: id int"];
+        assert!(parse_header_block(&lines).is_err());
+    }
+
+    #[test]
+    fn test_parse_tests_with_config() {
+        let (tests, configs) = parse_tests_with_config(
+            "not_null(id), unique(email) {severity: warn}, accepted_values(s, ['a']) {warn_if: >0, error_if: >100}",
+        )
+        .unwrap();
+        assert_eq!(tests.len(), 3);
+        assert_eq!(configs[0], TestConfig::default());
+        assert_eq!(configs[1].severity, TestSeverity::Warn);
+        assert_eq!(configs[2].error_if, Threshold::parse(">100").unwrap());
+        assert_eq!(configs[2].warn_if, Threshold::parse(">0").unwrap());
+
+        let err = parse_tests_with_config("unique(email) {severity: fatal}").unwrap_err();
+        assert!(format!("{:#}", err).contains("invalid severity"));
+        let err = parse_tests_with_config("unique(email) {limit: 5}").unwrap_err();
+        assert!(format!("{:#}", err).contains("unknown test config key"));
+        let err = parse_tests_with_config("unique(email) {severity: warn").unwrap_err();
+        assert!(err.to_string().contains("missing closing brace"));
+    }
+
     #[test]
     fn test_parse_header_block_missing_materialized() {
         let lines = vec!["-- deps:"];
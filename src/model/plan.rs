@@ -0,0 +1,293 @@
+//! Run planning: which models need to run, judged from the build state in
+//! `pgcrate.model_state` and the freshness of the sources they read.
+//!
+//! A model needs to run when it was never built, its SQL changed, an upstream
+//! model will run or was rebuilt after it, or (for models that hold data) a
+//! source it reads was loaded after it was built. Source load times come from
+//! the `[model.freshness]` column for the source; data-holding models that
+//! read a source without one always run.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::manifest::model_checksum;
+use super::state::BuildState;
+use super::{topo_sort, Materialized, Model, Project, Relation};
+
+/// Why a model needs to run
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlanReason {
+    NeverBuilt,
+    Modified,
+    /// An upstream model needs to run in this plan
+    UpstreamPending(Relation),
+    /// An upstream model was rebuilt after this model
+    UpstreamRebuilt(Relation),
+    /// A source was loaded after this model was built
+    SourceUpdated(Relation),
+    /// A source has no `[model.freshness]` column, so its load time is unknown
+    SourceFreshnessUnknown(Relation),
+}
+
+impl PlanReason {
+    /// Stable identifier for JSON output
+    pub fn code(&self) -> &'static str {
+        match self {
+            PlanReason::NeverBuilt => "never_built",
+            PlanReason::Modified => "modified",
+            PlanReason::UpstreamPending(_) => "upstream_pending",
+            PlanReason::UpstreamRebuilt(_) => "upstream_rebuilt",
+            PlanReason::SourceUpdated(_) => "source_updated",
+            PlanReason::SourceFreshnessUnknown(_) => "source_freshness_unknown",
+        }
+    }
+
+    /// The upstream relation behind the reason, if any
+    pub fn relation(&self) -> Option<&Relation> {
+        match self {
+            PlanReason::NeverBuilt | PlanReason::Modified => None,
+            PlanReason::UpstreamPending(rel)
+            | PlanReason::UpstreamRebuilt(rel)
+            | PlanReason::SourceUpdated(rel)
+            | PlanReason::SourceFreshnessUnknown(rel) => Some(rel),
+        }
+    }
+}
+
+impl fmt::Display for PlanReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanReason::NeverBuilt => write!(f, "never built"),
+            PlanReason::Modified => write!(f, "SQL changed since last build"),
+            PlanReason::UpstreamPending(rel) => write!(f, "upstream {} needs to run", rel),
+            PlanReason::UpstreamRebuilt(rel) => write!(f, "upstream {} rebuilt since", rel),
+            PlanReason::SourceUpdated(rel) => write!(f, "source {} updated since", rel),
+            PlanReason::SourceFreshnessUnknown(rel) => {
+                write!(f, "source {} has no [model.freshness] column", rel)
+            }
+        }
+    }
+}
+
+/// A materialized model and whether it needs to run
+#[derive(Clone, Debug)]
+pub struct PlannedModel {
+    pub id: Relation,
+    pub materialized: Materialized,
+    pub built_at: Option<DateTime<Utc>>,
+    /// None when the model is up to date
+    pub reason: Option<PlanReason>,
+}
+
+/// Plan every materialized model (except snapshots) in DAG order.
+///
+/// `loaded` holds the latest load time of sources with a `[model.freshness]`
+/// column. Snapshot models count as sources: `model run` never builds them.
+pub fn plan_models(
+    project: &Project,
+    state: &HashMap<Relation, BuildState>,
+    loaded: &HashMap<Relation, DateTime<Utc>>,
+) -> Result<Vec<PlannedModel>> {
+    let mut pending: HashSet<Relation> = HashSet::new();
+    let mut planned = Vec::new();
+    for rel in topo_sort(project)? {
+        let model = &project.models[&rel];
+        match model.header.materialized {
+            Materialized::Snapshot => continue,
+            Materialized::Ephemeral => {
+                // Inlined downstream: pending whenever something it reads is
+                if model.header.deps.iter().any(|d| pending.contains(d)) {
+                    pending.insert(rel);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        let own = state.get(&rel);
+        let reason = match own {
+            None => Some(PlanReason::NeverBuilt),
+            Some(own) if own.checksum != model_checksum(model) => Some(PlanReason::Modified),
+            Some(own) => model
+                .header
+                .deps
+                .iter()
+                .find(|d| pending.contains(*d))
+                .map(|d| PlanReason::UpstreamPending(d.clone()))
+                .or_else(|| stale_input(project, model, own, state, loaded)),
+        };
+        if reason.is_some() {
+            pending.insert(rel.clone());
+        }
+        planned.push(PlannedModel {
+            id: rel,
+            materialized: model.header.materialized.clone(),
+            built_at: own.map(|s| s.built_at),
+            reason,
+        });
+    }
+    Ok(planned)
+}
+
+/// First input that changed after `own` was built. Views only care about
+/// rebuilt upstream models (rebuilding drops them); models holding data also
+/// care about the data their inputs read, traced through views.
+fn stale_input(
+    project: &Project,
+    model: &Model,
+    own: &BuildState,
+    state: &HashMap<Relation, BuildState>,
+    loaded: &HashMap<Relation, DateTime<Utc>>,
+) -> Option<PlanReason> {
+    let holds_data = !matches!(model.header.materialized, Materialized::View);
+    let mut seen = HashSet::new();
+    let mut stack: Vec<(&Relation, bool)> = model.header.deps.iter().map(|d| (d, true)).collect();
+    while let Some((dep, direct)) = stack.pop() {
+        if !seen.insert(dep) {
+            continue;
+        }
+        let upstream = project
+            .models
+            .get(dep)
+            .filter(|m| !matches!(m.header.materialized, Materialized::Snapshot));
+        match upstream {
+            // Look through models that don't hold their own data
+            Some(m) if !m.header.materialized.is_materialized() => {
+                stack.extend(m.header.deps.iter().map(|d| (d, direct)));
+            }
+            Some(m) => {
+                let rebuilt = state.get(dep).is_some_and(|up| up.built_at > own.built_at);
+                if rebuilt && (direct || holds_data) {
+                    return Some(PlanReason::UpstreamRebuilt(dep.clone()));
+                }
+                if holds_data && matches!(m.header.materialized, Materialized::View) {
+                    stack.extend(m.header.deps.iter().map(|d| (d, false)));
+                }
+            }
+            None if holds_data => match loaded.get(dep) {
+                None => return Some(PlanReason::SourceFreshnessUnknown(dep.clone())),
+                Some(at) if *at > own.built_at => {
+                    return Some(PlanReason::SourceUpdated(dep.clone()))
+                }
+                Some(_) => {}
+            },
+            None => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_project;
+    use chrono::TimeZone;
+
+    fn at(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, minute, 0).unwrap()
+    }
+
+    fn built(project: &Project, name: &str, minute: u32) -> (Relation, BuildState) {
+        let rel = Relation::parse(name).unwrap();
+        let checksum = model_checksum(&project.models[&rel]);
+        (
+            rel,
+            BuildState {
+                checksum,
+                built_at: at(minute),
+            },
+        )
+    }
+
+    fn project() -> Project {
+        test_project(vec![
+            ("staging.users", "-- materialized: view\n-- deps: app.users\n\nSELECT id FROM app.users\n"),
+            ("marts.user_counts", "-- materialized: table\n-- deps: staging.users\n\nSELECT count(*) FROM staging.users\n"),
+            ("marts.latest", "-- materialized: view\n-- deps: marts.user_counts\n\nSELECT * FROM marts.user_counts\n"),
+        ])
+    }
+
+    fn reasons(plan: &[PlannedModel]) -> Vec<(String, Option<String>)> {
+        plan.iter()
+            .map(|p| (p.id.to_string(), p.reason.as_ref().map(|r| r.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_up_to_date_and_source_updates() {
+        let project = project();
+        let state = HashMap::from([
+            built(&project, "staging.users", 0),
+            built(&project, "marts.user_counts", 2),
+            built(&project, "marts.latest", 3),
+        ]);
+        let app_users = Relation::parse("app.users").unwrap();
+
+        let loaded = HashMap::from([(app_users.clone(), at(1))]);
+        let plan = plan_models(&project, &state, &loaded).unwrap();
+        assert!(
+            plan.iter().all(|p| p.reason.is_none()),
+            "{:?}",
+            reasons(&plan)
+        );
+
+        // Source loaded after the table was built (read through the view)
+        let loaded = HashMap::from([(app_users.clone(), at(5))]);
+        let plan = plan_models(&project, &state, &loaded).unwrap();
+        assert_eq!(
+            reasons(&plan),
+            vec![
+                ("staging.users".to_string(), None),
+                (
+                    "marts.user_counts".to_string(),
+                    Some("source app.users updated since".to_string())
+                ),
+                (
+                    "marts.latest".to_string(),
+                    Some("upstream marts.user_counts needs to run".to_string())
+                ),
+            ]
+        );
+
+        let plan = plan_models(&project, &state, &HashMap::new()).unwrap();
+        assert_eq!(
+            plan[1].reason,
+            Some(PlanReason::SourceFreshnessUnknown(app_users))
+        );
+    }
+
+    #[test]
+    fn test_plan_never_built_modified_and_rebuilt() {
+        let project = project();
+        let loaded = HashMap::from([(Relation::parse("app.users").unwrap(), at(0))]);
+
+        let mut state = HashMap::from([
+            built(&project, "staging.users", 0),
+            built(&project, "marts.user_counts", 4),
+            built(&project, "marts.latest", 3),
+        ]);
+        let plan = plan_models(&project, &state, &loaded).unwrap();
+        assert_eq!(plan[0].reason, None);
+        assert_eq!(plan[1].reason, None);
+        assert_eq!(
+            plan[2].reason,
+            Some(PlanReason::UpstreamRebuilt(
+                Relation::parse("marts.user_counts").unwrap()
+            ))
+        );
+
+        state
+            .get_mut(&Relation::parse("staging.users").unwrap())
+            .unwrap()
+            .checksum = "stale".to_string();
+        state.remove(&Relation::parse("marts.latest").unwrap());
+        let plan = plan_models(&project, &state, &loaded).unwrap();
+        assert_eq!(plan[0].reason, Some(PlanReason::Modified));
+        assert_eq!(
+            plan[1].reason.as_ref().map(|r| r.code()),
+            Some("upstream_pending")
+        );
+        assert_eq!(plan[2].reason, Some(PlanReason::NeverBuilt));
+    }
+}
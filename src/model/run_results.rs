@@ -0,0 +1,150 @@
+//! Run results: what happened to each model in the last `model run`.
+//!
+//! `model run` writes `target/run_results.json` after every invocation, including
+//! failed ones. `model retry` reads it back to re-run only the models that failed
+//! or were skipped, with the original run's options.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Run results location relative to the project root
+pub const RUN_RESULTS_PATH: &str = "target/run_results.json";
+
+const RUN_RESULTS_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunResults {
+    pub version: u32,
+    pub invocation: RunInvocation,
+    pub results: Vec<ModelRunResult>,
+}
+
+/// Options the run was started with, reused by `model retry`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RunInvocation {
+    pub selectors: Vec<String>,
+    pub excludes: Vec<String>,
+    pub vars: Vec<(String, String)>,
+    pub full_refresh: bool,
+    pub enforce_contracts: bool,
+    pub threads: Option<usize>,
+    pub keep_going: bool,
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ModelRunResult {
+    /// `schema.name`
+    pub model: String,
+    pub status: RunStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Success,
+    Error,
+    /// Not run: an upstream model failed, or the run stopped first
+    Skipped,
+    /// Not rebuilt: the view's SQL and upstream models are unchanged
+    Unchanged,
+}
+
+impl RunResults {
+    pub fn new(invocation: RunInvocation, results: Vec<ModelRunResult>) -> Self {
+        Self {
+            version: RUN_RESULTS_VERSION,
+            invocation,
+            results,
+        }
+    }
+
+    /// Load `target/run_results.json` under the project root
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(RUN_RESULTS_PATH);
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("read {} (run `pgcrate model run` first)", path.display()))?;
+        let results: RunResults = serde_json::from_str(&content)
+            .with_context(|| format!("parse run results {}", path.display()))?;
+        if results.version != RUN_RESULTS_VERSION {
+            bail!(
+                "unsupported run results version {} in {} (expected {})",
+                results.version,
+                path.display(),
+                RUN_RESULTS_VERSION
+            );
+        }
+        Ok(results)
+    }
+
+    /// Write `target/run_results.json` under the project root
+    pub fn write(&self, root: &Path) -> Result<PathBuf> {
+        let path = root.join(RUN_RESULTS_PATH);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self).context("serialize run results")?;
+        fs::write(&path, json + "\n").with_context(|| format!("write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Models that failed or were skipped, in run order (unchanged views are done)
+    pub fn unfinished(&self) -> Vec<String> {
+        self.results
+            .iter()
+            .filter(|r| matches!(r.status, RunStatus::Error | RunStatus::Skipped))
+            .map(|r| r.model.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_load_and_unfinished() {
+        let dir = tempfile::tempdir().unwrap();
+        let results = RunResults::new(
+            RunInvocation {
+                selectors: vec!["tag:daily".to_string()],
+                vars: vec![("day".to_string(), "2024-01-01".to_string())],
+                full_refresh: true,
+                ..Default::default()
+            },
+            vec![
+                ModelRunResult {
+                    model: "a.x".to_string(),
+                    status: RunStatus::Success,
+                    message: None,
+                },
+                ModelRunResult {
+                    model: "a.y".to_string(),
+                    status: RunStatus::Error,
+                    message: Some("division by zero".to_string()),
+                },
+                ModelRunResult {
+                    model: "a.z".to_string(),
+                    status: RunStatus::Skipped,
+                    message: None,
+                },
+            ],
+        );
+        results.write(dir.path()).unwrap();
+        let loaded = RunResults::load(dir.path()).unwrap();
+        assert_eq!(loaded, results);
+        assert_eq!(loaded.unfinished(), vec!["a.y", "a.z"]);
+    }
+
+    #[test]
+    fn test_load_missing_mentions_model_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = RunResults::load(dir.path()).unwrap_err();
+        assert!(format!("{:#}", err).contains("pgcrate model run"));
+    }
+}
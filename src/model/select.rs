@@ -1,8 +1,19 @@
-use anyhow::{bail, Result};
-use std::collections::HashSet;
+use anyhow::{bail, Context, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
 use std::str::FromStr;
 
-use super::{Project, Relation};
+use super::{Manifest, Project, Relation};
+
+/// Named selector definitions file inside the models directory
+pub const SELECTORS_FILE: &str = "selectors.toml";
+
+/// Prefix for using a named selector from selectors.toml: selector:nightly
+const NAMED_PREFIX: &str = "selector:";
+
+/// Prefix inside a definition that turns an entry into an exclude
+const EXCLUDE_PREFIX: &str = "exclude:";
 
 /// A selector for filtering models
 #[derive(Clone, Debug, PartialEq)]
@@ -17,6 +28,17 @@ pub enum Selector {
     Downstream(Relation),
     /// Full lineage (up + down): tree:schema.name
     Tree(Relation),
+    /// Models compared against a `--state` manifest: state:modified, state:new
+    State(StateSelector),
+}
+
+/// Comparison against a previous run's manifest
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StateSelector {
+    /// New models, or models whose compiled SQL or header changed
+    Modified,
+    /// Models absent from the manifest
+    New,
 }
 
 impl FromStr for Selector {
@@ -57,10 +79,21 @@ impl FromStr for Selector {
             return Ok(Selector::Tree(Relation::parse(model)?));
         }
 
+        if let Some(state) = s.strip_prefix("state:") {
+            return match state.trim() {
+                "modified" => Ok(Selector::State(StateSelector::Modified)),
+                "new" => Ok(Selector::State(StateSelector::New)),
+                other => bail!(
+                    "invalid state selector 'state:{}': expected 'state:modified' or 'state:new'",
+                    other
+                ),
+            };
+        }
+
         // No prefix - must be exact model match
         if !s.contains('.') {
             bail!(
-                "invalid selector '{}': expected 'schema.name' or prefix like 'tag:', 'deps:', 'downstream:', 'tree:'",
+                "invalid selector '{}': expected 'schema.name' or prefix like 'tag:', 'deps:', 'downstream:', 'tree:', 'state:', 'selector:'",
                 s
             );
         }
@@ -69,8 +102,81 @@ impl FromStr for Selector {
     }
 }
 
+/// Load named selectors from `selectors.toml` in the models directory
+/// (none if the file is absent):
+///
+/// ```toml
+/// nightly = ["tag:daily", "exclude:tag:slow"]
+/// ```
+pub fn load_selector_definitions(models_dir: &Path) -> Result<BTreeMap<String, Vec<String>>> {
+    let path = models_dir.join(SELECTORS_FILE);
+    if !path.is_file() {
+        return Ok(BTreeMap::new());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+    parse_selector_definitions(&content).with_context(|| format!("parse {}", path.display()))
+}
+
+fn parse_selector_definitions(content: &str) -> Result<BTreeMap<String, Vec<String>>> {
+    let definitions: BTreeMap<String, Vec<String>> = toml::from_str(content)?;
+    for (name, entries) in &definitions {
+        for entry in entries {
+            let expr = entry.strip_prefix(EXCLUDE_PREFIX).unwrap_or(entry);
+            if expr.trim_start().starts_with(NAMED_PREFIX) {
+                bail!("selector {name}: named selectors can't reference other selectors ({entry})");
+            }
+            expr.parse::<Selector>()
+                .with_context(|| format!("selector {name}"))?;
+        }
+    }
+    Ok(definitions)
+}
+
+/// Replace `selector:NAME` entries with the selects and excludes they define
+fn expand_named_selectors(
+    project: &Project,
+    selectors: &[String],
+    excludes: &[String],
+) -> Result<(Vec<String>, Vec<String>)> {
+    let mut selects = Vec::new();
+    let mut excludes = excludes.to_vec();
+    if let Some(named) = excludes.iter().find(|e| e.trim().starts_with(NAMED_PREFIX)) {
+        bail!("named selectors can only be used with --select/--selector, not --exclude: {named}");
+    }
+    for selector in selectors {
+        let Some(name) = selector.trim().strip_prefix(NAMED_PREFIX) else {
+            selects.push(selector.clone());
+            continue;
+        };
+        let name = name.trim();
+        let Some(entries) = project.selectors.get(name) else {
+            let known: Vec<&str> = project.selectors.keys().map(String::as_str).collect();
+            if known.is_empty() {
+                bail!("unknown selector '{name}': no {SELECTORS_FILE} in the models directory");
+            }
+            bail!(
+                "unknown selector '{name}' (defined in {SELECTORS_FILE}: {})",
+                known.join(", ")
+            );
+        };
+        for entry in entries {
+            match entry.strip_prefix(EXCLUDE_PREFIX) {
+                Some(exclude) => excludes.push(exclude.to_string()),
+                None => selects.push(entry.clone()),
+            }
+        }
+    }
+    Ok((selects, excludes))
+}
+
 /// Resolve a selector to a set of model relations
-pub fn resolve_selector(project: &Project, selector: &Selector) -> Result<HashSet<Relation>> {
+///
+/// `state:` selectors need the manifest passed with `--state`.
+pub fn resolve_selector(
+    project: &Project,
+    selector: &Selector,
+    state: Option<&Manifest>,
+) -> Result<HashSet<Relation>> {
     let mut result = HashSet::new();
 
     match selector {
@@ -111,6 +217,20 @@ pub fn resolve_selector(project: &Project, selector: &Selector) -> Result<HashSe
             result.extend(upstream);
             result.extend(downstream);
         }
+        Selector::State(kind) => {
+            let Some(manifest) = state else {
+                bail!("state selectors require a manifest: pass --state PATH (e.g. a production target/manifest.json)");
+            };
+            for (rel, model) in &project.models {
+                let matched = match kind {
+                    StateSelector::Modified => manifest.is_modified(model),
+                    StateSelector::New => !manifest.contains(rel),
+                };
+                if matched {
+                    result.insert(rel.clone());
+                }
+            }
+        }
     }
 
     Ok(result)
@@ -123,6 +243,18 @@ pub fn apply_selectors(
     selectors: &[String],
     excludes: &[String],
 ) -> Result<Vec<Relation>> {
+    apply_selectors_with_state(project, selectors, excludes, None)
+}
+
+/// Like [`apply_selectors`], resolving `state:` selectors against `state`
+pub fn apply_selectors_with_state(
+    project: &Project,
+    selectors: &[String],
+    excludes: &[String],
+    state: Option<&Manifest>,
+) -> Result<Vec<Relation>> {
+    let (selectors, excludes) = expand_named_selectors(project, selectors, excludes)?;
+
     // Parse selectors
     let parsed_selectors: Vec<Selector> = selectors
         .iter()
@@ -142,7 +274,7 @@ pub fn apply_selectors(
         // Union of all selector matches
         let mut set = HashSet::new();
         for selector in &parsed_selectors {
-            let matches = resolve_selector(project, selector)?;
+            let matches = resolve_selector(project, selector, state)?;
             set.extend(matches);
         }
         set
@@ -150,7 +282,7 @@ pub fn apply_selectors(
 
     // Apply excludes
     for exclude in &parsed_excludes {
-        let to_remove = resolve_selector(project, exclude)?;
+        let to_remove = resolve_selector(project, exclude, state)?;
         for rel in to_remove {
             selected.remove(&rel);
         }
@@ -206,6 +338,29 @@ mod tests {
         assert!(matches!(sel, Selector::Tree(r) if r.schema == "analytics" && r.name == "orders"));
     }
 
+    #[test]
+    fn test_parse_state() {
+        let sel: Selector = "state:modified".parse().unwrap();
+        assert_eq!(sel, Selector::State(StateSelector::Modified));
+        let sel: Selector = "state:new".parse().unwrap();
+        assert_eq!(sel, Selector::State(StateSelector::New));
+        let err = "state:stale".parse::<Selector>().unwrap_err();
+        assert!(err.to_string().contains("state:modified"));
+    }
+
+    #[test]
+    fn test_state_selector_requires_manifest() {
+        let project = Project {
+            root: std::path::PathBuf::new(),
+            models: std::collections::HashMap::new(),
+            sources: HashSet::new(),
+            exposures: Vec::new(),
+            selectors: Default::default(),
+        };
+        let err = apply_selectors(&project, &["state:modified".to_string()], &[]).unwrap_err();
+        assert!(err.to_string().contains("--state"));
+    }
+
     #[test]
     fn test_parse_empty_tag_error() {
         let err = "tag:".parse::<Selector>().unwrap_err();
@@ -229,4 +384,46 @@ mod tests {
         let err = "a.b.c".parse::<Selector>().unwrap_err();
         assert!(err.to_string().contains("expected schema"));
     }
+
+    #[test]
+    fn test_parse_selector_definitions() {
+        let defs = parse_selector_definitions(
+            "nightly = [\"tag:daily\", \"exclude:tag:slow\"]\nall_marts = [\"tree:marts.users\"]\n",
+        )
+        .unwrap();
+        assert_eq!(defs["nightly"], vec!["tag:daily", "exclude:tag:slow"]);
+
+        let err = parse_selector_definitions("bad = [\"users\"]\n").unwrap_err();
+        assert!(format!("{:#}", err).contains("selector bad"));
+        let err = parse_selector_definitions("a = [\"selector:b\"]\n").unwrap_err();
+        assert!(err.to_string().contains("can't reference other selectors"));
+    }
+
+    #[test]
+    fn test_expand_named_selectors() {
+        let mut project = Project {
+            root: std::path::PathBuf::new(),
+            models: std::collections::HashMap::new(),
+            sources: HashSet::new(),
+            exposures: Vec::new(),
+            selectors: Default::default(),
+        };
+        project.selectors.insert(
+            "nightly".to_string(),
+            vec!["tag:daily".to_string(), "exclude:tag:slow".to_string()],
+        );
+        let (selects, excludes) = expand_named_selectors(
+            &project,
+            &["selector:nightly".to_string(), "a.b".to_string()],
+            &["tag:x".to_string()],
+        )
+        .unwrap();
+        assert_eq!(selects, vec!["tag:daily", "a.b"]);
+        assert_eq!(excludes, vec!["tag:x", "tag:slow"]);
+
+        let err =
+            expand_named_selectors(&project, &["selector:weekly".to_string()], &[]).unwrap_err();
+        assert!(err.to_string().contains("unknown selector 'weekly'"));
+        assert!(err.to_string().contains("nightly"));
+    }
 }
@@ -0,0 +1,167 @@
+//! Sources declared in `<models_dir>/sources.toml`, in addition to
+//! `[model] sources` in pgcrate.toml:
+//!
+//! ```toml
+//! [sources.raw]
+//! tables = ["customers", "orders"]
+//! ```
+//!
+//! `model import-sources` writes this file from the tables in the database.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::Relation;
+
+/// Sources file name inside the models directory
+pub const SOURCES_FILE: &str = "sources.toml";
+
+const SOURCES_HEADER: &str =
+    "# Tables models can depend on, by schema. Written by `pgcrate model import-sources`.\n\n";
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+struct SourcesFile {
+    #[serde(default)]
+    sources: BTreeMap<String, SourceSchema>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+struct SourceSchema {
+    tables: BTreeSet<String>,
+}
+
+impl SourcesFile {
+    fn relations(&self) -> Vec<Relation> {
+        self.sources
+            .iter()
+            .flat_map(|(schema, s)| {
+                s.tables.iter().map(|table| Relation {
+                    schema: schema.clone(),
+                    name: table.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn read_sources_file(path: &Path) -> Result<SourcesFile> {
+    if !path.is_file() {
+        return Ok(SourcesFile::default());
+    }
+    let content = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("parse {}", path.display()))
+}
+
+/// Load `sources.toml` from the models directory (none if the file is absent)
+pub fn load_sources(models_dir: &Path) -> Result<Vec<Relation>> {
+    Ok(read_sources_file(&models_dir.join(SOURCES_FILE))?.relations())
+}
+
+/// Add relations to `sources.toml`, keeping the ones already declared.
+/// Returns the file path and how many relations were new.
+pub fn add_sources(models_dir: &Path, relations: &[Relation]) -> Result<(PathBuf, usize)> {
+    let path = models_dir.join(SOURCES_FILE);
+    let mut file = read_sources_file(&path)?;
+    let mut added = 0;
+    for rel in relations {
+        if file
+            .sources
+            .entry(rel.schema.clone())
+            .or_default()
+            .tables
+            .insert(rel.name.clone())
+        {
+            added += 1;
+        }
+    }
+    let content = format!("{}{}", SOURCES_HEADER, toml::to_string(&file)?);
+    fs::write(&path, content).with_context(|| format!("write {}", path.display()))?;
+    Ok((path, added))
+}
+
+/// Stub staging model over a source table: a view selecting every column
+pub fn staging_model_sql(source: &Relation, columns: &[String]) -> String {
+    let columns = if columns.is_empty() {
+        "    *".to_string()
+    } else {
+        columns
+            .iter()
+            .map(|c| format!("    {}", sql_name(c)))
+            .collect::<Vec<_>>()
+            .join(",\n")
+    };
+    format!(
+        "-- materialized: view\n\
+         -- deps: {source}\n\
+         -- description: Staging view over source {source}\n\n\
+         SELECT\n{columns}\n\
+         FROM {}.{}\n",
+        sql_name(&source.schema),
+        sql_name(&source.name)
+    )
+}
+
+/// Identifiers stay bare unless Postgres would fold or reject them
+fn sql_name(name: &str) -> String {
+    let bare = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if bare {
+        name.to_string()
+    } else {
+        crate::sql::quote_ident(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rel(s: &str) -> Relation {
+        Relation::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_add_and_load_sources() {
+        let dir = std::env::temp_dir().join(format!("pgcrate_sources_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(SOURCES_FILE),
+            "[sources.raw]\ntables = [\"orders\"]\n",
+        )
+        .unwrap();
+
+        let (_, added) = add_sources(&dir, &[rel("raw.orders"), rel("raw.customers")]).unwrap();
+        assert_eq!(added, 1);
+        assert_eq!(
+            load_sources(&dir).unwrap(),
+            vec![rel("raw.customers"), rel("raw.orders")]
+        );
+
+        fs::write(dir.join(SOURCES_FILE), "[sources.raw]\nviews = []\n").unwrap();
+        assert!(load_sources(&dir).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_staging_model_sql() {
+        let sql = staging_model_sql(
+            &rel("raw.orders"),
+            &["id".to_string(), "Customer Id".to_string()],
+        );
+        assert_eq!(
+            sql,
+            "-- materialized: view\n\
+             -- deps: raw.orders\n\
+             -- description: Staging view over source raw.orders\n\n\
+             SELECT\n    id,\n    \"Customer Id\"\n\
+             FROM raw.orders\n"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Build state: the checksum each model was last built from, kept in
+//! `pgcrate.model_state` so `model run` can skip views that wouldn't change.
+//!
+//! Only views are skipped. Tables, incremental models and materialized views
+//! hold data, so they are rebuilt (or refreshed) on every run.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use tokio_postgres::Client;
+
+use super::manifest::model_checksum;
+use super::{Materialized, Model, Project, Relation};
+
+const MODEL_STATE_TABLE: &str = r#"
+CREATE SCHEMA IF NOT EXISTS pgcrate;
+CREATE TABLE IF NOT EXISTS pgcrate.model_state (
+    schema_name TEXT NOT NULL,
+    model_name TEXT NOT NULL,
+    checksum TEXT NOT NULL,
+    built_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (schema_name, model_name)
+)
+"#;
+
+/// When and from what a model was last built
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuildState {
+    pub checksum: String,
+    pub built_at: DateTime<Utc>,
+}
+
+/// Load the recorded state of models whose relation still exists
+pub async fn load_model_state(client: &Client) -> Result<HashMap<Relation, BuildState>> {
+    client
+        .batch_execute(MODEL_STATE_TABLE)
+        .await
+        .context("create pgcrate.model_state")?;
+    let rows = client
+        .query(
+            "SELECT schema_name, model_name, checksum, built_at
+             FROM pgcrate.model_state
+             WHERE to_regclass(format('%I.%I', schema_name, model_name)) IS NOT NULL",
+            &[],
+        )
+        .await
+        .context("read pgcrate.model_state")?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            (
+                Relation {
+                    schema: row.get(0),
+                    name: row.get(1),
+                },
+                BuildState {
+                    checksum: row.get(2),
+                    built_at: row.get(3),
+                },
+            )
+        })
+        .collect())
+}
+
+/// Record that a model was just built from its current SQL
+pub async fn record_model_state(client: &Client, model: &Model) -> Result<()> {
+    client
+        .execute(
+            "INSERT INTO pgcrate.model_state (schema_name, model_name, checksum, built_at)
+             VALUES ($1, $2, $3, clock_timestamp())
+             ON CONFLICT (schema_name, model_name)
+             DO UPDATE SET checksum = EXCLUDED.checksum, built_at = EXCLUDED.built_at",
+            &[&model.id.schema, &model.id.name, &model_checksum(model)],
+        )
+        .await
+        .with_context(|| format!("record build state for {}", model.id))?;
+    Ok(())
+}
+
+/// Whether a view can be left as-is: its SQL and build directives match what
+/// it was last built from (`model_checksum`, which doesn't depend on how
+/// pgcrate represents the header), and nothing upstream has been rebuilt since.
+///
+/// `rebuilt` holds models already rebuilt in this run; rebuilding drops
+/// dependent views (`CASCADE`), so those must be recreated.
+pub fn is_unchanged(
+    project: &Project,
+    model: &Model,
+    state: &HashMap<Relation, BuildState>,
+    rebuilt: &HashSet<Relation>,
+) -> bool {
+    if !matches!(model.header.materialized, Materialized::View) {
+        return false;
+    }
+    let Some(own) = state.get(&model.id) else {
+        return false;
+    };
+    if own.checksum != model_checksum(model) {
+        return false;
+    }
+    !upstream_changed(project, model, own, state, rebuilt)
+}
+
+fn upstream_changed(
+    project: &Project,
+    model: &Model,
+    own: &BuildState,
+    state: &HashMap<Relation, BuildState>,
+    rebuilt: &HashSet<Relation>,
+) -> bool {
+    model.header.deps.iter().any(|dep| {
+        if rebuilt.contains(dep) {
+            return true;
+        }
+        match project.models.get(dep) {
+            // Ephemeral models are never built; look through them
+            Some(upstream) if !upstream.header.materialized.is_materialized() => {
+                upstream_changed(project, upstream, own, state, rebuilt)
+            }
+            Some(_) => state
+                .get(dep)
+                .is_none_or(|upstream| upstream.built_at > own.built_at),
+            None => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_project;
+    use chrono::TimeZone;
+
+    fn built(project: &Project, name: &str, minute: u32) -> (Relation, BuildState) {
+        let rel = Relation::parse(name).unwrap();
+        let checksum = model_checksum(&project.models[&rel]);
+        let built_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, minute, 0).unwrap();
+        (rel, BuildState { checksum, built_at })
+    }
+
+    fn project() -> Project {
+        test_project(vec![
+            ("staging.users", "-- materialized: table\n\nSELECT 1 AS id\n"),
+            ("staging.base", "-- materialized: ephemeral\n-- deps: staging.users\n\nSELECT id FROM staging.users\n"),
+            ("marts.users", "-- materialized: view\n-- deps: staging.base\n\nSELECT id FROM staging.base\n"),
+        ])
+    }
+
+    #[test]
+    fn test_unchanged_view_is_skipped() {
+        let project = project();
+        let view = &project.models[&Relation::parse("marts.users").unwrap()];
+        let state = HashMap::from([
+            built(&project, "staging.users", 0),
+            built(&project, "marts.users", 1),
+        ]);
+        assert!(is_unchanged(&project, view, &state, &HashSet::new()));
+
+        // Tables always run
+        let table = &project.models[&Relation::parse("staging.users").unwrap()];
+        assert!(!is_unchanged(&project, table, &state, &HashSet::new()));
+    }
+
+    #[test]
+    fn test_changed_sql_or_upstream_rebuilds_view() {
+        let project = project();
+        let view = &project.models[&Relation::parse("marts.users").unwrap()];
+        let mut state = HashMap::from([
+            built(&project, "staging.users", 0),
+            built(&project, "marts.users", 1),
+        ]);
+
+        // Upstream rebuilt in this run (through the ephemeral model)
+        let rebuilt = HashSet::from([Relation::parse("staging.users").unwrap()]);
+        assert!(!is_unchanged(&project, view, &state, &rebuilt));
+
+        // Upstream rebuilt by an earlier run after this view
+        let (rel, later) = built(&project, "staging.users", 2);
+        state.insert(rel, later);
+        assert!(!is_unchanged(&project, view, &state, &HashSet::new()));
+
+        // SQL changed since the view was built
+        let mut state = HashMap::from([
+            built(&project, "staging.users", 0),
+            built(&project, "marts.users", 1),
+        ]);
+        state.get_mut(&view.id).unwrap().checksum = "stale".to_string();
+        assert!(!is_unchanged(&project, view, &state, &HashSet::new()));
+    }
+
+    #[test]
+    fn test_description_change_keeps_view_skipped() {
+        let project = project();
+        let state = HashMap::from([
+            built(&project, "staging.users", 0),
+            built(&project, "marts.users", 1),
+        ]);
+
+        let mut view = project.models[&Relation::parse("marts.users").unwrap()].clone();
+        view.header.description = Some("One row per user".to_string());
+        view.header.tags.push("daily".to_string());
+        assert!(is_unchanged(&project, &view, &state, &HashSet::new()));
+    }
+}
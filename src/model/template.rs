@@ -0,0 +1,463 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory (under the models dir) holding macro definitions
+pub const MACROS_DIR: &str = "macros";
+
+/// Macros may call other macros, but not without bound
+const MAX_MACRO_DEPTH: usize = 16;
+
+/// A reusable SQL fragment defined in models/macros/<name>.sql
+#[derive(Clone, Debug)]
+pub struct Macro {
+    pub args: Vec<String>,
+    pub body: String,
+    pub path: PathBuf,
+}
+
+/// Values available when expanding placeholders in model source
+#[derive(Clone, Debug, Default)]
+pub struct TemplateContext {
+    pub vars: HashMap<String, String>,
+    pub macros: HashMap<String, Macro>,
+}
+
+/// Load macro definitions from a directory (missing directory = no macros).
+///
+/// Each file defines one macro named after the file. An optional
+/// `-- args: a, b` header declares parameters, referenced as `${a}` in the body.
+pub fn load_macros(dir: &Path) -> Result<HashMap<String, Macro>> {
+    let mut macros = HashMap::new();
+    if !dir.is_dir() {
+        return Ok(macros);
+    }
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("read macros dir: {}", dir.display()))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().and_then(|s| s.to_str()) == Some("sql"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        if !is_ident(&name) {
+            bail!(
+                "invalid macro name '{}': use letters, numbers, and underscores ({})",
+                name,
+                path.display()
+            );
+        }
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("read macro: {}", path.display()))?;
+        let m = parse_macro(&text, &path)?;
+        macros.insert(name, m);
+    }
+
+    Ok(macros)
+}
+
+fn parse_macro(text: &str, path: &Path) -> Result<Macro> {
+    let mut args = Vec::new();
+    let mut body_lines = Vec::new();
+    let mut in_header = true;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if in_header {
+            if trimmed.is_empty() {
+                continue;
+            }
+            if let Some(comment) = trimmed.strip_prefix("--") {
+                if let Some(list) = comment.trim().strip_prefix("args:") {
+                    args = list
+                        .split(',')
+                        .map(|a| a.trim().to_string())
+                        .filter(|a| !a.is_empty())
+                        .collect();
+                }
+                continue;
+            }
+            in_header = false;
+        }
+        body_lines.push(line);
+    }
+
+    for arg in &args {
+        if !is_ident(arg) || arg == "this" {
+            bail!("invalid macro argument '{}' in {}", arg, path.display());
+        }
+    }
+
+    let body = body_lines
+        .join("\n")
+        .trim()
+        .trim_end_matches(';')
+        .trim()
+        .to_string();
+    if body.is_empty() {
+        bail!("macro body is empty: {}", path.display());
+    }
+
+    Ok(Macro {
+        args,
+        body,
+        path: path.to_path_buf(),
+    })
+}
+
+fn is_ident(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Expand `${var:name}` and `${macro:name(args)}` placeholders in model source text.
+///
+/// Values are inserted verbatim (no quoting), so string values need quotes in
+/// the SQL: `WHERE created_at >= '${var:start_date}'`. Other placeholders such
+/// as `${this}` are left untouched for later stages.
+pub fn render(text: &str, ctx: &TemplateContext) -> Result<String> {
+    let mut undefined: BTreeSet<String> = BTreeSet::new();
+    let out = expand(text, ctx, 0, &mut undefined)?;
+
+    if !undefined.is_empty() {
+        let names: Vec<String> = undefined.into_iter().collect();
+        bail!(
+            "undefined model variable(s): {}\n\
+             hint: define them under [model.vars] in pgcrate.toml or pass --var {}=<value>",
+            names.join(", "),
+            names[0]
+        );
+    }
+
+    Ok(out)
+}
+
+fn expand(
+    text: &str,
+    ctx: &TemplateContext,
+    depth: usize,
+    undefined: &mut BTreeSet<String>,
+) -> Result<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        if let Some(call) = after.strip_prefix("macro:") {
+            let (name, args, consumed) = parse_macro_call(call)?;
+            out.push_str(&expand_macro(&name, &args, ctx, depth, undefined)?);
+            rest = &call[consumed..];
+            continue;
+        }
+
+        let Some(end) = after.find('}') else {
+            // Unterminated placeholder: keep the remainder as-is
+            out.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+
+        let inner = &after[..end];
+        match inner.strip_prefix("var:") {
+            Some(name) => {
+                let name = name.trim();
+                if name.is_empty() {
+                    bail!("empty variable name in placeholder: ${{{}}}", inner);
+                }
+                match ctx.vars.get(name) {
+                    Some(value) => out.push_str(value),
+                    None => {
+                        undefined.insert(name.to_string());
+                    }
+                }
+            }
+            None => out.push_str(&rest[start..start + 2 + end + 1]),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+
+    Ok(out)
+}
+
+/// Parse `name(arg, ...)}` following `${macro:`.
+/// Returns the name, raw argument strings, and bytes consumed (including the closing brace).
+fn parse_macro_call(s: &str) -> Result<(String, Vec<String>, usize)> {
+    let snippet = || s.lines().next().unwrap_or_default().to_string();
+
+    if let Some(end) = s.find('}').filter(|&e| !s[..e].contains('(')) {
+        // No argument list: ${macro:name}
+        let name = s[..end].trim().to_string();
+        if !is_ident(&name) {
+            bail!("invalid macro call: ${{macro:{}}}", &s[..end]);
+        }
+        return Ok((name, Vec::new(), end + 1));
+    }
+
+    let open = s
+        .find('(')
+        .ok_or_else(|| anyhow!("unterminated macro call: ${{macro:{}", snippet()))?;
+    let name = s[..open].trim().to_string();
+    if !is_ident(&name) {
+        bail!("invalid macro call: ${{macro:{}", snippet());
+    }
+
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut close = None;
+
+    for (i, ch) in s[open + 1..].char_indices() {
+        if let Some(q) = quote {
+            current.push(ch);
+            if ch == q {
+                quote = None;
+            }
+            continue;
+        }
+        match ch {
+            '\'' | '"' => {
+                quote = Some(ch);
+                current.push(ch);
+            }
+            '(' => {
+                depth += 1;
+                current.push(ch);
+            }
+            ')' if depth == 0 => {
+                close = Some(open + 1 + i);
+                break;
+            }
+            ')' => {
+                depth -= 1;
+                current.push(ch);
+            }
+            ',' if depth == 0 => {
+                args.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(ch),
+        }
+    }
+
+    let close = close.ok_or_else(|| anyhow!("unbalanced parentheses in ${{macro:{}", snippet()))?;
+    let tail = &s[close + 1..];
+    let brace = tail
+        .find(|c: char| !c.is_whitespace())
+        .filter(|&i| tail[i..].starts_with('}'))
+        .ok_or_else(|| anyhow!("expected '}}' after macro call ${{macro:{}", &s[..=close]))?;
+
+    if !current.trim().is_empty() || !args.is_empty() {
+        args.push(current.trim().to_string());
+    }
+
+    Ok((name, args, close + 1 + brace + 1))
+}
+
+fn expand_macro(
+    name: &str,
+    args: &[String],
+    ctx: &TemplateContext,
+    depth: usize,
+    undefined: &mut BTreeSet<String>,
+) -> Result<String> {
+    if depth >= MAX_MACRO_DEPTH {
+        bail!(
+            "macro expansion too deep (>{} levels) at '{}'; check for recursive macros",
+            MAX_MACRO_DEPTH,
+            name
+        );
+    }
+
+    let Some(m) = ctx.macros.get(name) else {
+        let mut known: Vec<&str> = ctx.macros.keys().map(|k| k.as_str()).collect();
+        known.sort();
+        if known.is_empty() {
+            bail!(
+                "unknown macro '{}': no macros defined (add models/{}/{}.sql)",
+                name,
+                MACROS_DIR,
+                name
+            );
+        }
+        bail!("unknown macro '{}'. Available: {}", name, known.join(", "));
+    };
+
+    if args.len() != m.args.len() {
+        bail!(
+            "macro '{}' takes {} argument(s) ({}), got {}",
+            name,
+            m.args.len(),
+            m.args.join(", "),
+            args.len()
+        );
+    }
+
+    let mut body = m.body.clone();
+    for (param, value) in m.args.iter().zip(args) {
+        let value = expand(value, ctx, depth + 1, undefined)?;
+        body = body.replace(&format!("${{{}}}", param), &value);
+    }
+
+    expand(&body, ctx, depth + 1, undefined)
+        .with_context(|| format!("expand macro '{}' ({})", name, m.path.display()))
+}
+
+/// Whether text contains any `${var:...}` or `${macro:...}` placeholders
+pub fn has_template_placeholders(text: &str) -> bool {
+    text.contains("${var:") || text.contains("${macro:")
+}
+
+/// Parse a `key=value` assignment from the command line
+pub fn parse_var_assignment(s: &str) -> Result<(String, String)> {
+    let Some((key, value)) = s.split_once('=') else {
+        bail!("invalid variable '{}': expected KEY=VALUE", s);
+    };
+    let key = key.trim();
+    if key.is_empty() {
+        bail!("invalid variable '{}': name cannot be empty", s);
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> TemplateContext {
+        TemplateContext {
+            vars: pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            macros: HashMap::new(),
+        }
+    }
+
+    fn with_macro(mut ctx: TemplateContext, name: &str, text: &str) -> TemplateContext {
+        let m = parse_macro(text, Path::new("macros/test.sql")).unwrap();
+        ctx.macros.insert(name.to_string(), m);
+        ctx
+    }
+
+    #[test]
+    fn test_render_substitutes_vars() {
+        let sql = "SELECT * FROM app.events TABLESAMPLE SYSTEM (${var:sample_pct})";
+        let out = render(sql, &vars(&[("sample_pct", "10")])).unwrap();
+        assert_eq!(out, "SELECT * FROM app.events TABLESAMPLE SYSTEM (10)");
+    }
+
+    #[test]
+    fn test_render_leaves_this_untouched() {
+        let sql =
+            "SELECT * FROM src WHERE id > (SELECT MAX(id) FROM ${this}) AND d >= '${var:start}'";
+        let out = render(sql, &vars(&[("start", "2024-01-01")])).unwrap();
+        assert_eq!(
+            out,
+            "SELECT * FROM src WHERE id > (SELECT MAX(id) FROM ${this}) AND d >= '2024-01-01'"
+        );
+    }
+
+    #[test]
+    fn test_render_reports_all_undefined() {
+        let err = render("SELECT ${var:b}, ${var:a}, ${var:b}", &vars(&[])).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("undefined model variable(s): a, b"));
+        assert!(msg.contains("--var a=<value>"));
+    }
+
+    #[test]
+    fn test_render_unterminated_placeholder_kept() {
+        let out = render("SELECT '${var:x'", &vars(&[])).unwrap();
+        assert_eq!(out, "SELECT '${var:x'");
+    }
+
+    #[test]
+    fn test_render_expands_macro_with_args() {
+        let ctx = with_macro(
+            vars(&[]),
+            "cents_to_dollars",
+            "-- args: amount\n\n(${amount} / 100.0)::numeric(12, 2);\n",
+        );
+        let out = render(
+            "SELECT ${macro:cents_to_dollars(o.total_cents)} AS total FROM app.orders o",
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "SELECT (o.total_cents / 100.0)::numeric(12, 2) AS total FROM app.orders o"
+        );
+    }
+
+    #[test]
+    fn test_render_macro_args_with_nested_parens_commas_and_vars() {
+        let ctx = with_macro(
+            vars(&[("default_rate", "0.2")]),
+            "safe_div",
+            "-- args: num, den\nCASE WHEN ${den} = 0 THEN NULL ELSE ${num}::numeric / ${den} END",
+        );
+        let out = render(
+            "${macro:safe_div(coalesce(a, ${var:default_rate}), greatest(b, 'x,y'))}",
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "CASE WHEN greatest(b, 'x,y') = 0 THEN NULL ELSE coalesce(a, 0.2)::numeric / greatest(b, 'x,y') END"
+        );
+    }
+
+    #[test]
+    fn test_render_macro_without_args_and_nested_macro() {
+        let ctx = with_macro(vars(&[]), "now_utc", "now() AT TIME ZONE 'UTC'");
+        let ctx = with_macro(ctx, "today_utc", "(${macro:now_utc})::date");
+        let out = render("SELECT ${macro:today_utc()}", &ctx).unwrap();
+        assert_eq!(out, "SELECT (now() AT TIME ZONE 'UTC')::date");
+    }
+
+    #[test]
+    fn test_render_macro_errors() {
+        let ctx = with_macro(vars(&[]), "double", "-- args: x\n${x} * 2");
+        let err = render("${macro:double(1, 2)}", &ctx).unwrap_err();
+        assert!(err.to_string().contains("takes 1 argument(s)"));
+
+        let err = render("${macro:triple(1)}", &ctx).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unknown macro 'triple'. Available: double"));
+
+        let err = render("${macro:double(1}", &ctx).unwrap_err();
+        assert!(err.to_string().contains("unbalanced parentheses"));
+    }
+
+    #[test]
+    fn test_render_recursive_macro_is_bounded() {
+        let ctx = with_macro(vars(&[]), "loop_forever", "${macro:loop_forever}");
+        let err = render("${macro:loop_forever}", &ctx).unwrap_err();
+        assert!(format!("{:#}", err).contains("too deep"));
+    }
+
+    #[test]
+    fn test_parse_var_assignment() {
+        assert_eq!(
+            parse_var_assignment("env=dev").unwrap(),
+            ("env".to_string(), "dev".to_string())
+        );
+        assert_eq!(
+            parse_var_assignment("filter=a=b").unwrap(),
+            ("filter".to_string(), "a=b".to_string())
+        );
+        assert!(parse_var_assignment("novalue").is_err());
+        assert!(parse_var_assignment("=x").is_err());
+    }
+}
@@ -0,0 +1,280 @@
+//! Nagios plugin output for `dba --format nagios`.
+//!
+//! Monitoring systems in the Nagios family (Icinga, Naemon, Sensu checks)
+//! read one status line and the exit code:
+//!
+//! ```text
+//! WARNING - locks: 1 warning: pid 4242 | blocking_chains=1 long_transactions=0
+//! ```
+//!
+//! Exit codes are 0 (OK), 1 (WARNING), 2 (CRITICAL) and 3 (UNKNOWN, the check
+//! could not run). The line is built from the same envelope `--json` prints,
+//! so every diagnostic that supports JSON supports this format.
+
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::exit_codes;
+use crate::output::Severity;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Non-healthy items named in the status line before "(+N more)"
+const MAX_NAMED_ITEMS: usize = 3;
+/// How deep perfdata looks for numbers inside `data`
+const MAX_PERFDATA_DEPTH: usize = 3;
+/// Keys tried, in order, to name a non-healthy item
+const NAME_KEYS: &[&str] = &[
+    "label",
+    "name",
+    "slot_name",
+    "subscription",
+    "sequence",
+    "table",
+    "index",
+    "database",
+    "setting",
+    "application_name",
+    "pid",
+];
+
+/// Switch diagnostic output to Nagios plugin format
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Print a finished check and exit with its plugin code
+pub fn print_and_exit(schema_id: &str, severity: Severity, ok: bool, data: &Value) -> ! {
+    let (line, code) = status_line(schema_id, severity, ok, data);
+    println!("{}", line);
+    std::process::exit(code);
+}
+
+/// Print an UNKNOWN result (the check itself failed) and exit 3
+pub fn print_unknown(message: &str) -> ! {
+    let first_line = message.lines().next().unwrap_or_default();
+    println!("UNKNOWN - {}", first_line);
+    std::process::exit(exit_codes::UNKNOWN);
+}
+
+/// Build the plugin line and exit code for a diagnostic envelope
+pub fn status_line(schema_id: &str, severity: Severity, ok: bool, data: &Value) -> (String, i32) {
+    let (label, code) = match (ok, severity) {
+        (false, _) | (_, Severity::Error) => ("UNKNOWN", exit_codes::UNKNOWN),
+        (true, Severity::Healthy) => ("OK", exit_codes::HEALTHY),
+        (true, Severity::Warning) => ("WARNING", exit_codes::WARNING),
+        (true, Severity::Critical) => ("CRITICAL", exit_codes::CRITICAL),
+    };
+    let check = check_name(schema_id);
+
+    let mut items = Vec::new();
+    collect_items(data, &mut items);
+    let critical = items.iter().filter(|(s, _)| s == "critical").count();
+    let warning = items.len() - critical;
+
+    let message = if items.is_empty() {
+        match severity {
+            Severity::Healthy => format!("{}: no issues", check),
+            _ => format!("{}: {}", check, label.to_lowercase()),
+        }
+    } else {
+        // Critical items first
+        items.sort_by_key(|(s, _)| s != "critical");
+        let mut counts = Vec::new();
+        if critical > 0 {
+            counts.push(format!("{} critical", critical));
+        }
+        if warning > 0 {
+            counts.push(format!("{} warning", warning));
+        }
+        let mut names: Vec<String> = items
+            .iter()
+            .take(MAX_NAMED_ITEMS)
+            .map(|(_, n)| n.clone())
+            .collect();
+        if items.len() > MAX_NAMED_ITEMS {
+            names.push(format!("(+{} more)", items.len() - MAX_NAMED_ITEMS));
+        }
+        format!("{}: {}: {}", check, counts.join(", "), names.join(", "))
+    };
+
+    let mut perf = Vec::new();
+    collect_perfdata(data, "", 0, &mut perf);
+    let line = if perf.is_empty() {
+        format!("{} - {}", label, sanitize(&message))
+    } else {
+        format!("{} - {} | {}", label, sanitize(&message), perf.join(" "))
+    };
+    (line, code)
+}
+
+/// "pgcrate.diagnostics.locks" -> "locks", "pgcrate.fix.sequence" -> "fix sequence"
+fn check_name(schema_id: &str) -> String {
+    let rest = schema_id.strip_prefix("pgcrate.").unwrap_or(schema_id);
+    let rest = rest.strip_prefix("diagnostics.").unwrap_or(rest);
+    rest.replace('.', " ")
+}
+
+/// The text before " | " must not contain a pipe or line breaks
+fn sanitize(message: &str) -> String {
+    message
+        .replace('|', "/")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Objects with a warning or critical `status`, named by their first
+/// identifying key. Items inside a matched object are not listed again.
+fn collect_items(value: &Value, items: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            let status = map.get("status").and_then(|s| s.as_str());
+            if let Some(status @ ("warning" | "critical")) = status {
+                items.push((status.to_string(), item_name(map)));
+                return;
+            }
+            for v in map.values() {
+                collect_items(v, items);
+            }
+        }
+        Value::Array(values) => {
+            for v in values {
+                collect_items(v, items);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn item_name(map: &serde_json::Map<String, Value>) -> String {
+    let name = NAME_KEYS
+        .iter()
+        .find_map(|k| match map.get(*k) {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Number(n)) => Some(format!("{} {}", k, n)),
+            _ => None,
+        })
+        .unwrap_or_else(|| "item".to_string());
+    match map.get("summary").and_then(|s| s.as_str()) {
+        Some(summary) => format!("{} ({})", name, summary),
+        None => name,
+    }
+}
+
+/// Numbers outside arrays become `path=value`; arrays become their length
+fn collect_perfdata(value: &Value, prefix: &str, depth: usize, perf: &mut Vec<String>) {
+    let Value::Object(map) = value else {
+        return;
+    };
+    for (key, v) in map {
+        let label = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match v {
+            Value::Number(n) => {
+                let uom = if key.ends_with("_pct") { "%" } else { "" };
+                let value = match n.as_f64() {
+                    Some(f) if n.is_f64() => format!("{:.2}", f)
+                        .trim_end_matches('0')
+                        .trim_end_matches('.')
+                        .to_string(),
+                    _ => n.to_string(),
+                };
+                perf.push(format!("{}={}{}", perf_label(&label), value, uom));
+            }
+            Value::Array(values) if depth == 0 => {
+                perf.push(format!("{}={}", perf_label(&label), values.len()));
+            }
+            Value::Object(_) if depth + 1 < MAX_PERFDATA_DEPTH => {
+                collect_perfdata(v, &label, depth + 1, perf);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Labels with spaces, quotes or '=' must be single-quoted
+fn perf_label(label: &str) -> String {
+    if label.contains([' ', '\'', '=']) {
+        format!("'{}'", label.replace('\'', "''"))
+    } else {
+        label.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_healthy_line_with_perfdata() {
+        let data = json!({
+            "stats": {"total": 12, "usage_pct": 12.5, "status": "healthy"},
+            "by_user": [{"user": "app"}],
+            "overall_status": "healthy"
+        });
+        let (line, code) = status_line(
+            "pgcrate.diagnostics.connections",
+            Severity::Healthy,
+            true,
+            &data,
+        );
+        assert_eq!(code, 0);
+        assert_eq!(
+            line,
+            "OK - connections: no issues | by_user=1 stats.total=12 stats.usage_pct=12.5%"
+        );
+    }
+
+    #[test]
+    fn test_names_non_healthy_items_critical_first() {
+        let data = json!({
+            "checks": [
+                {"label": "CONNECTIONS", "status": "warning", "summary": "85/100"},
+                {"label": "XID AGE", "status": "critical", "summary": "1.5B | high"},
+                {"label": "LOCKS", "status": "healthy"}
+            ]
+        });
+        let (line, code) = status_line(
+            "pgcrate.diagnostics.triage",
+            Severity::Critical,
+            true,
+            &data,
+        );
+        assert_eq!(code, 2);
+        assert_eq!(
+            line,
+            "CRITICAL - triage: 1 critical, 1 warning: XID AGE (1.5B / high), CONNECTIONS (85/100) | checks=3"
+        );
+    }
+
+    #[test]
+    fn test_truncates_item_list_and_handles_errors() {
+        let items: Vec<Value> = (0..5)
+            .map(|i| json!({"name": format!("t{}", i), "status": "warning"}))
+            .collect();
+        let data = json!({ "tables": items });
+        let (line, code) =
+            status_line("pgcrate.diagnostics.vacuum", Severity::Warning, true, &data);
+        assert_eq!(code, 1);
+        assert!(line.starts_with("WARNING - vacuum: 5 warning: t0, t1, t2, (+2 more)"));
+
+        let (line, code) = status_line("pgcrate.fix.sequence", Severity::Error, true, &json!({}));
+        assert_eq!(code, 3);
+        assert_eq!(line, "UNKNOWN - fix sequence: unknown");
+    }
+
+    #[test]
+    fn test_perf_label_quoting() {
+        assert_eq!(perf_label("stats.total"), "stats.total");
+        assert_eq!(perf_label("by state"), "'by state'");
+    }
+}
@@ -6,6 +6,16 @@
 
 use serde::Serialize;
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// When set, diagnostic JSON prints as one line per result (NDJSON)
+static NDJSON: AtomicBool = AtomicBool::new(false);
+
+/// Switch diagnostic JSON output to one compact line per result, for
+/// `--watch --json` streams
+pub fn set_ndjson(enabled: bool) {
+    NDJSON.store(enabled, Ordering::Relaxed);
+}
 
 /// Output mode for the CLI
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -161,6 +171,17 @@ impl JsonError {
         }
     }
 
+    /// Error with a specific code instead of `internal_error`
+    pub fn with_code(
+        code: &'static str,
+        message: impl Into<String>,
+        details: impl Into<String>,
+    ) -> Self {
+        let mut error = Self::with_details(message, details);
+        error.errors[0].code = code;
+        error
+    }
+
     /// Print this error as JSON to stdout
     /// Panics if serialization fails (should never happen for JsonError)
     pub fn print(&self) {
@@ -184,12 +205,29 @@ pub struct MigrationInfo {
     pub version: String,
     pub name: String,
     pub has_down: bool,
+    /// Execution metadata from pgcrate.schema_migrations (absent if never run)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution: Option<MigrationExecution>,
+}
+
+/// Audit metadata for the most recent run of a migration
+#[derive(Debug, Serialize)]
+pub struct MigrationExecution {
+    pub success: bool,
+    pub applied_at: Option<String>,
+    pub applied_by: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub pgcrate_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct StatusCounts {
     pub applied: usize,
     pub pending: usize,
+    /// Pending migrations whose last run failed
+    pub failed: usize,
     pub total: usize,
 }
 
@@ -201,6 +239,9 @@ pub struct DiffResponse {
     pub summary: DiffSummaryJson,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub formatted_diff: Option<String>,
+    /// Statements that bring the target in line with the source (--format sql)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sql: Option<String>,
 }
 
 #[derive(Debug, Serialize, Default)]
@@ -210,6 +251,8 @@ pub struct DiffSummaryJson {
     pub indexes: usize,
     pub constraints: usize,
     pub enums: usize,
+    pub types: usize,
+    pub foreign: usize,
     pub functions: usize,
     pub views: usize,
     pub triggers: usize,
@@ -217,6 +260,11 @@ pub struct DiffSummaryJson {
     pub extensions: usize,
     pub schemas: usize,
     pub materialized_views: usize,
+    pub policies: usize,
+    pub comments: usize,
+    pub roles: usize,
+    pub owners: usize,
+    pub grants: usize,
 }
 
 impl From<&crate::diff::DiffSummary> for DiffSummaryJson {
@@ -227,6 +275,8 @@ impl From<&crate::diff::DiffSummary> for DiffSummaryJson {
             indexes: s.indexes,
             constraints: s.constraints,
             enums: s.enums,
+            types: s.types,
+            foreign: s.foreign,
             functions: s.functions,
             views: s.views,
             triggers: s.triggers,
@@ -234,6 +284,11 @@ impl From<&crate::diff::DiffSummary> for DiffSummaryJson {
             extensions: s.extensions,
             schemas: s.schemas,
             materialized_views: s.materialized_views,
+            policies: s.policies,
+            comments: s.comments,
+            roles: s.roles,
+            owners: s.owners,
+            grants: s.grants,
         }
     }
 }
@@ -251,6 +306,85 @@ pub struct DescribeResponse {
     pub dependencies: Option<crate::describe::Dependencies>,
 }
 
+/// JSON success response wrapper for inspect sequence
+#[derive(Debug, Serialize)]
+pub struct SequenceDescribeResponse {
+    pub ok: bool,
+    pub sequence: crate::describe::SequenceDescribe,
+}
+
+/// JSON success response wrapper for inspect find
+#[derive(Debug, Serialize)]
+pub struct FindResponse {
+    pub ok: bool,
+    pub pattern: String,
+    pub regex: bool,
+    pub matches: Vec<crate::describe::FindMatch>,
+}
+
+/// JSON success response wrapper for inspect profile
+#[derive(Debug, Serialize)]
+pub struct ProfileResponse {
+    pub ok: bool,
+    pub profile: crate::commands::profile::TableProfile,
+}
+
+/// JSON success response wrapper for inspect publications
+#[derive(Debug, Serialize)]
+pub struct PublicationsResponse {
+    pub ok: bool,
+    pub publications: Vec<crate::commands::logical_replication::Publication>,
+}
+
+/// JSON success response wrapper for inspect subscriptions
+#[derive(Debug, Serialize)]
+pub struct SubscriptionsResponse {
+    pub ok: bool,
+    pub subscriptions: Vec<crate::commands::logical_replication::Subscription>,
+}
+
+/// JSON success response wrapper for inspect security-audit
+#[derive(Debug, Serialize)]
+pub struct SecurityAuditResponse {
+    pub ok: bool,
+    pub audit: crate::commands::security_audit::SecurityAudit,
+}
+
+/// JSON success response wrapper for inspect schema
+#[derive(Debug, Serialize)]
+pub struct SchemaDescribeResponse {
+    pub ok: bool,
+    /// Set when a schema was named
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<crate::describe::SchemaSummary>,
+    /// Every user schema, when none was named
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schemas: Option<Vec<crate::describe::SchemaCounts>>,
+}
+
+/// JSON success response wrapper for inspect type
+#[derive(Debug, Serialize)]
+pub struct TypeDescribeResponse {
+    pub ok: bool,
+    #[serde(rename = "type")]
+    pub type_: crate::describe::TypeDescribe,
+}
+
+/// JSON success response wrapper for comment set
+#[derive(Debug, Serialize)]
+pub struct CommentResponse {
+    pub ok: bool,
+    pub schema: String,
+    pub table: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    /// New comment; null when cleared
+    pub comment: Option<String>,
+    pub sql: String,
+    /// False with --dry-run
+    pub executed: bool,
+}
+
 // =============================================================================
 // Diagnostic Output (versioned JSON for diagnostic commands)
 // =============================================================================
@@ -411,6 +545,18 @@ impl<T: Serialize> DiagnosticOutput<T> {
 
     /// Print this output as JSON to stdout.
     pub fn print(&self) -> Result<(), serde_json::Error> {
+        if crate::nagios::enabled() {
+            let data = serde_json::to_value(&self.data)?;
+            crate::nagios::print_and_exit(self.schema_id, self.severity, self.ok, &data);
+        }
+        if NDJSON.load(Ordering::Relaxed) {
+            let line = serde_json::to_string(self)?;
+            // The reader went away (e.g. `| head`); end the stream quietly
+            if writeln!(io::stdout(), "{}", line).is_err() {
+                std::process::exit(0);
+            }
+            return Ok(());
+        }
         let json = serde_json::to_string_pretty(self)?;
         println!("{}", json);
         Ok(())
@@ -420,11 +566,16 @@ impl<T: Serialize> DiagnosticOutput<T> {
 /// Schema IDs for diagnostic commands.
 pub mod schema {
     pub const TRIAGE: &str = "pgcrate.diagnostics.triage";
+    pub const CLUSTER_TRIAGE: &str = "pgcrate.diagnostics.cluster_triage";
+    pub const FLEET_TRIAGE: &str = "pgcrate.diagnostics.fleet_triage";
     pub const LOCKS: &str = "pgcrate.diagnostics.locks";
+    pub const LOCKS_GRAPH: &str = "pgcrate.diagnostics.locks_graph";
+    pub const KILL_IDLE: &str = "pgcrate.diagnostics.kill_idle";
     pub const XID: &str = "pgcrate.diagnostics.xid";
     pub const SEQUENCES: &str = "pgcrate.diagnostics.sequences";
     pub const INDEXES: &str = "pgcrate.diagnostics.indexes";
     pub const VACUUM: &str = "pgcrate.diagnostics.vacuum";
+    pub const VACUUM_PROGRESS: &str = "pgcrate.diagnostics.vacuum_progress";
     pub const BLOAT: &str = "pgcrate.diagnostics.bloat";
     pub const CACHE: &str = "pgcrate.diagnostics.cache";
     pub const REPLICATION: &str = "pgcrate.diagnostics.replication";
@@ -432,12 +583,24 @@ pub mod schema {
     pub const CAPABILITIES: &str = "pgcrate.diagnostics.capabilities";
     pub const QUERIES: &str = "pgcrate.diagnostics.queries";
     pub const CONNECTIONS: &str = "pgcrate.diagnostics.connections";
+    pub const POOLER: &str = "pgcrate.diagnostics.pooler";
     pub const EXPLAIN: &str = "pgcrate.diagnostics.explain";
     pub const STORAGE: &str = "pgcrate.diagnostics.storage";
     pub const STATS_AGE: &str = "pgcrate.diagnostics.stats_age";
     pub const CHECKPOINTS: &str = "pgcrate.diagnostics.checkpoints";
+    pub const AUTOVACUUM: &str = "pgcrate.diagnostics.autovacuum";
     pub const AUTOVACUUM_PROGRESS: &str = "pgcrate.diagnostics.autovacuum_progress";
     pub const CONFIG: &str = "pgcrate.diagnostics.config";
+    pub const WAL: &str = "pgcrate.diagnostics.wal";
+    pub const TEMP: &str = "pgcrate.diagnostics.temp";
+    pub const SLOTS: &str = "pgcrate.diagnostics.slots";
+    pub const IO: &str = "pgcrate.diagnostics.io";
+    pub const RECORD: &str = "pgcrate.diagnostics.record";
+    pub const TREND: &str = "pgcrate.diagnostics.trend";
+    pub const STATEMENTS: &str = "pgcrate.diagnostics.statements";
+    pub const PARTITIONS: &str = "pgcrate.diagnostics.partitions";
+    pub const PERSISTENCE: &str = "pgcrate.diagnostics.persistence";
+    pub const PREPARED: &str = "pgcrate.diagnostics.prepared";
 }
 
 // =============================================================================
@@ -11,10 +11,36 @@ use std::path::{Path, PathBuf};
 pub enum SeedType {
     /// CSV file with optional schema sidecar
     Csv,
+    /// JSON Lines file (one object per line) with optional schema sidecar
+    Jsonl,
+    /// Parquet file with optional schema sidecar
+    Parquet,
     /// SQL file executed directly
     Sql,
 }
 
+impl SeedType {
+    /// Seed type for a file extension, if it is a seed file
+    pub fn from_extension(ext: &str) -> Option<SeedType> {
+        match ext {
+            "csv" => Some(SeedType::Csv),
+            "jsonl" => Some(SeedType::Jsonl),
+            "parquet" => Some(SeedType::Parquet),
+            "sql" => Some(SeedType::Sql),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SeedType::Csv => "csv",
+            SeedType::Jsonl => "jsonl",
+            SeedType::Parquet => "parquet",
+            SeedType::Sql => "sql",
+        }
+    }
+}
+
 /// A discovered seed file
 #[derive(Debug, Clone)]
 pub struct SeedFile {
@@ -24,9 +50,9 @@ pub struct SeedFile {
     pub table: String,
     /// Full path to the seed file
     pub path: PathBuf,
-    /// Type of seed (CSV or SQL)
+    /// Type of seed (CSV, JSONL, Parquet or SQL)
     pub seed_type: SeedType,
-    /// Path to schema file if it exists (for CSV seeds)
+    /// Path to schema file if it exists (for data seeds)
     pub schema_path: Option<PathBuf>,
 }
 
@@ -55,7 +81,259 @@ pub struct SeedSchema {
     pub primary_key: Option<Vec<String>>,
 }
 
-/// Parsed CSV seed ready for loading
+/// Seeds config file in the seeds directory (per-seed load strategies)
+pub const SEEDS_CONFIG_FILE: &str = "seeds.toml";
+
+/// How a data seed is loaded into its table
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeedStrategy {
+    /// Empty the table, then load (default)
+    #[default]
+    Truncate,
+    /// Load rows without removing existing ones
+    Append,
+    /// INSERT ... ON CONFLICT (key) DO UPDATE, keeping rows not in the seed
+    Upsert,
+}
+
+impl SeedStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeedStrategy::Truncate => "truncate",
+            SeedStrategy::Append => "append",
+            SeedStrategy::Upsert => "upsert",
+        }
+    }
+}
+
+/// Per-seed settings from `seeds.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedConfig {
+    #[serde(default)]
+    pub strategy: SeedStrategy,
+    /// Conflict key for upsert (defaults to the sidecar's primary_key)
+    pub key: Option<Vec<String>>,
+    /// Environments the seed loads in (`seed run --environment`); unset loads everywhere
+    pub env: Option<Vec<String>>,
+    /// File column → table column renames
+    pub columns: Option<HashMap<String, String>>,
+    /// Values for table columns missing from the file (or NULL in it)
+    pub defaults: Option<HashMap<String, String>>,
+    /// Value transforms per table column, applied in order
+    pub transforms: Option<HashMap<String, Vec<String>>>,
+}
+
+impl SeedConfig {
+    /// Whether the seed loads for `seed run --environment <environment>`.
+    /// Environment-scoped seeds are skipped when no environment is given.
+    pub fn loads_in(&self, environment: Option<&str>) -> bool {
+        match (&self.env, environment) {
+            (None, _) => true,
+            (Some(envs), Some(environment)) => envs.iter().any(|e| e == environment),
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Whether any settings apply only to data seeds
+    pub fn has_data_settings(&self) -> bool {
+        self.strategy != SeedStrategy::Truncate
+            || self.key.is_some()
+            || self.columns.is_some()
+            || self.defaults.is_some()
+            || self.transforms.is_some()
+    }
+}
+
+/// A value transform from `seeds.toml`
+#[derive(Debug, Clone, PartialEq)]
+enum Transform {
+    Trim,
+    Lower,
+    Upper,
+    EmptyAsNull,
+    /// `null_if:<text>`
+    NullIf(String),
+    /// `date:<chrono format>`, written as YYYY-MM-DD
+    Date(String),
+    /// `timestamp:<chrono format>`, written as YYYY-MM-DD HH:MM:SS (with offset if parsed)
+    Timestamp(String),
+}
+
+impl Transform {
+    fn parse(spec: &str) -> Result<Transform> {
+        let (name, arg) = match spec.split_once(':') {
+            Some((name, arg)) => (name, Some(arg.to_string())),
+            None => (spec, None),
+        };
+        Ok(match (name, arg) {
+            ("trim", None) => Transform::Trim,
+            ("lower", None) => Transform::Lower,
+            ("upper", None) => Transform::Upper,
+            ("empty_as_null", None) => Transform::EmptyAsNull,
+            ("null_if", Some(text)) => Transform::NullIf(text),
+            ("date", Some(format)) => Transform::Date(format),
+            ("timestamp", Some(format)) => Transform::Timestamp(format),
+            _ => anyhow::bail!(
+                "unknown transform '{}' (expected trim, lower, upper, empty_as_null, \
+                 null_if:<text>, date:<format> or timestamp:<format>)",
+                spec
+            ),
+        })
+    }
+
+    fn apply(&self, value: Option<String>) -> Result<Option<String>> {
+        use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        Ok(match self {
+            Transform::Trim => Some(value.trim().to_string()),
+            Transform::Lower => Some(value.to_lowercase()),
+            Transform::Upper => Some(value.to_uppercase()),
+            Transform::EmptyAsNull => Some(value).filter(|v| !v.is_empty()),
+            Transform::NullIf(text) => Some(value).filter(|v| v != text),
+            Transform::Date(format) => Some(
+                NaiveDate::parse_from_str(&value, format)
+                    .with_context(|| format!("'{}' doesn't match date format '{}'", value, format))?
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            ),
+            Transform::Timestamp(format) => Some(match DateTime::parse_from_str(&value, format) {
+                Ok(at) => at.format("%Y-%m-%d %H:%M:%S%:z").to_string(),
+                Err(_) => NaiveDateTime::parse_from_str(&value, format)
+                    .with_context(|| {
+                        format!("'{}' doesn't match timestamp format '{}'", value, format)
+                    })?
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+            }),
+        })
+    }
+}
+
+/// Apply `seeds.toml` column renames, transforms and defaults to a parsed data
+/// seed. Changed columns have their types re-inferred unless the schema sidecar
+/// sets them.
+pub fn apply_seed_config(seed: &mut ParsedCsvSeed, config: &SeedConfig) -> Result<()> {
+    let mut changed: Vec<usize> = Vec::new();
+
+    let mut renames: Vec<(&String, &String)> = config.columns.iter().flatten().collect();
+    renames.sort();
+    for (from, to) in renames {
+        let Some(i) = seed.columns.iter().position(|c| &c.name == from) else {
+            anyhow::bail!(
+                "seed '{}': columns maps '{}', which is not in the file",
+                seed.name,
+                from
+            );
+        };
+        if seed.columns.iter().any(|c| &c.name == to) {
+            anyhow::bail!(
+                "seed '{}': columns maps '{}' onto existing column '{}'",
+                seed.name,
+                from,
+                to
+            );
+        }
+        seed.columns[i].name = to.clone();
+        changed.push(i);
+    }
+
+    let mut transforms: Vec<(&String, &Vec<String>)> = config.transforms.iter().flatten().collect();
+    transforms.sort();
+    for (column, specs) in transforms {
+        let Some(i) = seed.columns.iter().position(|c| &c.name == column) else {
+            anyhow::bail!(
+                "seed '{}': transforms column '{}' is not in the seed",
+                seed.name,
+                column
+            );
+        };
+        let specs = specs
+            .iter()
+            .map(|spec| Transform::parse(spec))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("seed '{}', column '{}'", seed.name, column))?;
+        for (row_num, row) in seed.rows.iter_mut().enumerate() {
+            for transform in &specs {
+                row[i] = transform.apply(row[i].take()).with_context(|| {
+                    format!(
+                        "seed '{}', column '{}', row {}",
+                        seed.name,
+                        column,
+                        row_num + 1
+                    )
+                })?;
+            }
+        }
+        changed.push(i);
+    }
+
+    let mut defaults: Vec<(&String, &String)> = config.defaults.iter().flatten().collect();
+    defaults.sort();
+    for (column, default) in defaults {
+        let i = match seed.columns.iter().position(|c| &c.name == column) {
+            Some(i) => i,
+            None => {
+                seed.columns.push(SeedColumn {
+                    name: column.clone(),
+                    pg_type: String::new(),
+                });
+                for row in &mut seed.rows {
+                    row.push(None);
+                }
+                seed.columns.len() - 1
+            }
+        };
+        for row in &mut seed.rows {
+            if row[i].is_none() {
+                row[i] = Some(default.clone());
+            }
+        }
+        changed.push(i);
+    }
+
+    let sample_size = std::cmp::min(100, seed.rows.len());
+    for i in changed {
+        let name = &seed.columns[i].name;
+        seed.columns[i].pg_type = match seed.schema_def.as_ref().and_then(|s| s.columns.get(name)) {
+            Some(pg_type) => pg_type.clone(),
+            None => {
+                let values: Vec<&str> = seed.rows[..sample_size]
+                    .iter()
+                    .filter_map(|row| row[i].as_deref())
+                    .collect();
+                infer_type(&values)
+            }
+        };
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeedsConfigFile {
+    #[serde(default)]
+    seeds: HashMap<String, SeedConfig>,
+}
+
+/// Load `seeds.toml` from the seeds directory, keyed by `schema.table`
+/// (empty if the file is absent)
+pub fn load_seed_configs(seeds_dir: &Path) -> Result<HashMap<String, SeedConfig>> {
+    let path = seeds_dir.join(SEEDS_CONFIG_FILE);
+    if !path.is_file() {
+        return Ok(HashMap::new());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+    let file: SeedsConfigFile =
+        toml::from_str(&content).with_context(|| format!("parse {}", path.display()))?;
+    Ok(file.seeds)
+}
+
+/// Parsed data seed (CSV, JSONL or Parquet) ready for loading
 #[derive(Debug)]
 pub struct ParsedCsvSeed {
     pub schema: String,
@@ -64,7 +342,7 @@ pub struct ParsedCsvSeed {
     pub columns: Vec<SeedColumn>,
     pub rows: Vec<Vec<Option<String>>>,
     pub schema_def: Option<SeedSchema>,
-    /// Raw CSV content for COPY loading
+    /// Raw CSV content (empty for JSONL and Parquet seeds)
     pub csv_content: String,
 }
 
@@ -156,28 +434,28 @@ pub fn discover_seeds(seeds_dir: &Path) -> Result<Vec<SeedFile>> {
                 }
             }
 
-            match (ext, stem) {
-                (Some("csv"), Some(table)) => {
-                    let schema_path = schema_dir.join(format!("{}.schema.toml", table));
+            match (ext.and_then(SeedType::from_extension), stem) {
+                (Some(SeedType::Sql), Some(table)) => {
                     seeds.push(SeedFile {
                         schema: schema.clone(),
                         table: table.to_string(),
                         path,
-                        seed_type: SeedType::Csv,
-                        schema_path: if schema_path.exists() {
-                            Some(schema_path)
-                        } else {
-                            None
-                        },
+                        seed_type: SeedType::Sql,
+                        schema_path: None,
                     });
                 }
-                (Some("sql"), Some(table)) => {
+                (Some(seed_type), Some(table)) => {
+                    let schema_path = schema_dir.join(format!("{}.schema.toml", table));
                     seeds.push(SeedFile {
                         schema: schema.clone(),
                         table: table.to_string(),
                         path,
-                        seed_type: SeedType::Sql,
-                        schema_path: None,
+                        seed_type,
+                        schema_path: if schema_path.exists() {
+                            Some(schema_path)
+                        } else {
+                            None
+                        },
                     });
                 }
                 _ => {
@@ -195,7 +473,7 @@ pub fn discover_seeds(seeds_dir: &Path) -> Result<Vec<SeedFile>> {
     Ok(seeds)
 }
 
-/// Parse a seed file (CSV or SQL)
+/// Parse a seed file (CSV, JSONL, Parquet or SQL)
 pub fn parse_seed(seed_file: &SeedFile) -> Result<ParsedSeed> {
     match seed_file.seed_type {
         SeedType::Csv => {
@@ -207,6 +485,24 @@ pub fn parse_seed(seed_file: &SeedFile) -> Result<ParsedSeed> {
             )?;
             Ok(ParsedSeed::Csv(parsed))
         }
+        SeedType::Jsonl => {
+            let parsed = parse_jsonl_seed(
+                &seed_file.path,
+                seed_file.schema_path.as_deref(),
+                &seed_file.schema,
+                &seed_file.table,
+            )?;
+            Ok(ParsedSeed::Csv(parsed))
+        }
+        SeedType::Parquet => {
+            let parsed = parse_parquet_seed(
+                &seed_file.path,
+                seed_file.schema_path.as_deref(),
+                &seed_file.schema,
+                &seed_file.table,
+            )?;
+            Ok(ParsedSeed::Csv(parsed))
+        }
         SeedType::Sql => {
             let parsed = parse_sql_seed(&seed_file.path, &seed_file.schema, &seed_file.table)?;
             Ok(ParsedSeed::Sql(parsed))
@@ -224,15 +520,7 @@ pub fn parse_csv_seed(
     let name = format!("{}.{}", schema, table);
 
     // Load schema file if it exists
-    let seed_schema = if let Some(sp) = schema_path {
-        let content = fs::read_to_string(sp)
-            .with_context(|| format!("read schema file: {}", sp.display()))?;
-        let parsed: SeedSchema = toml::from_str(&content)
-            .with_context(|| format!("parse schema file: {}", sp.display()))?;
-        Some(parsed)
-    } else {
-        None
-    };
+    let seed_schema = load_seed_schema(schema_path)?;
 
     // Read CSV content for COPY
     let csv_content =
@@ -308,6 +596,300 @@ pub fn parse_csv_seed(
     })
 }
 
+/// Load a `.schema.toml` sidecar if the seed has one
+fn load_seed_schema(schema_path: Option<&Path>) -> Result<Option<SeedSchema>> {
+    let Some(sp) = schema_path else {
+        return Ok(None);
+    };
+    let content =
+        fs::read_to_string(sp).with_context(|| format!("read schema file: {}", sp.display()))?;
+    let parsed: SeedSchema =
+        toml::from_str(&content).with_context(|| format!("parse schema file: {}", sp.display()))?;
+    Ok(Some(parsed))
+}
+
+/// Parse a JSON Lines seed: one object per line, columns are the union of keys.
+/// Types come from the JSON values (strings are inferred like CSV values);
+/// nested objects and arrays load as jsonb.
+pub fn parse_jsonl_seed(
+    path: &Path,
+    schema_path: Option<&Path>,
+    schema: &str,
+    table: &str,
+) -> Result<ParsedCsvSeed> {
+    let name = format!("{}.{}", schema, table);
+    let seed_schema = load_seed_schema(schema_path)?;
+    let content =
+        fs::read_to_string(path).with_context(|| format!("read JSONL file: {}", path.display()))?;
+
+    let mut objects = Vec::new();
+    let mut headers: Vec<String> = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: serde_json::Value = serde_json::from_str(line)
+            .with_context(|| format!("parse JSONL line {}: {}", i + 1, path.display()))?;
+        let serde_json::Value::Object(object) = value else {
+            anyhow::bail!("JSONL line {} is not an object: {}", i + 1, path.display());
+        };
+        for key in object.keys() {
+            if !headers.contains(key) {
+                headers.push(key.clone());
+            }
+        }
+        objects.push(object);
+    }
+    if headers.is_empty() {
+        anyhow::bail!("JSONL file has no columns: {}", path.display());
+    }
+
+    let sample_size = std::cmp::min(100, objects.len());
+    let columns: Vec<SeedColumn> = headers
+        .iter()
+        .map(|name| {
+            let pg_type = seed_schema
+                .as_ref()
+                .and_then(|s| s.columns.get(name).cloned())
+                .unwrap_or_else(|| {
+                    let values: Vec<&serde_json::Value> = objects[..sample_size]
+                        .iter()
+                        .filter_map(|o| o.get(name))
+                        .filter(|v| !v.is_null())
+                        .collect();
+                    infer_json_type(&values)
+                });
+            SeedColumn {
+                name: name.clone(),
+                pg_type,
+            }
+        })
+        .collect();
+
+    let rows = objects
+        .iter()
+        .map(|object| {
+            headers
+                .iter()
+                .map(|h| match object.get(h) {
+                    None | Some(serde_json::Value::Null) => None,
+                    Some(serde_json::Value::String(s)) => Some(s.clone()),
+                    Some(other) => Some(other.to_string()),
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(ParsedCsvSeed {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        name,
+        columns,
+        rows,
+        schema_def: seed_schema,
+        csv_content: String::new(),
+    })
+}
+
+/// Infer PostgreSQL type from a sample of (non-null) JSON values
+fn infer_json_type(values: &[&serde_json::Value]) -> String {
+    use serde_json::Value;
+    if values.is_empty() {
+        return "text".to_string();
+    }
+    if values.iter().all(|v| v.is_boolean()) {
+        return "boolean".to_string();
+    }
+    if values.iter().all(|v| v.is_i64() || v.is_u64()) {
+        return "bigint".to_string();
+    }
+    if values.iter().all(|v| v.is_number()) {
+        return "numeric".to_string();
+    }
+    if values.iter().all(|v| v.is_object() || v.is_array()) {
+        return "jsonb".to_string();
+    }
+    let strings: Option<Vec<&str>> = values
+        .iter()
+        .map(|v| match v {
+            Value::String(s) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect();
+    match strings {
+        Some(strings) => infer_type(&strings),
+        None => "text".to_string(),
+    }
+}
+
+/// Parse a Parquet seed. Column types map from the Parquet schema (logical
+/// types first, then physical); nested columns load as jsonb.
+pub fn parse_parquet_seed(
+    path: &Path,
+    schema_path: Option<&Path>,
+    schema: &str,
+    table: &str,
+) -> Result<ParsedCsvSeed> {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let name = format!("{}.{}", schema, table);
+    let seed_schema = load_seed_schema(schema_path)?;
+    let file = File::open(path).with_context(|| format!("open seed file: {}", path.display()))?;
+    let reader = SerializedFileReader::new(file)
+        .with_context(|| format!("read Parquet file: {}", path.display()))?;
+
+    let fields = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .root_schema()
+        .get_fields()
+        .to_vec();
+    let columns: Vec<SeedColumn> = fields
+        .iter()
+        .map(|field| {
+            let name = field.name().to_string();
+            let pg_type = seed_schema
+                .as_ref()
+                .and_then(|s| s.columns.get(&name).cloned())
+                .unwrap_or_else(|| parquet_pg_type(field));
+            SeedColumn { name, pg_type }
+        })
+        .collect();
+
+    let mut rows = Vec::new();
+    for row in reader
+        .get_row_iter(None)
+        .with_context(|| format!("read Parquet rows: {}", path.display()))?
+    {
+        let row = row.with_context(|| format!("read Parquet row: {}", path.display()))?;
+        rows.push(
+            row.get_column_iter()
+                .zip(&columns)
+                .map(|((_, field), column)| parquet_field_text(field, &column.pg_type))
+                .collect(),
+        );
+    }
+
+    Ok(ParsedCsvSeed {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        name,
+        columns,
+        rows,
+        schema_def: seed_schema,
+        csv_content: String::new(),
+    })
+}
+
+/// PostgreSQL type for a top-level Parquet column
+fn parquet_pg_type(field: &parquet::schema::types::Type) -> String {
+    use parquet::basic::{ConvertedType, LogicalType, Repetition, Type as Physical};
+
+    let info = field.get_basic_info();
+    if !field.is_primitive() || (info.has_repetition() && info.repetition() == Repetition::REPEATED)
+    {
+        return "jsonb".to_string();
+    }
+    let pg_type = match info.logical_type() {
+        Some(LogicalType::String) | Some(LogicalType::Enum) => Some("text".to_string()),
+        Some(LogicalType::Json) => Some("jsonb".to_string()),
+        Some(LogicalType::Uuid) => Some("uuid".to_string()),
+        Some(LogicalType::Date) => Some("date".to_string()),
+        Some(LogicalType::Decimal { scale, precision }) => {
+            Some(format!("numeric({},{})", precision, scale))
+        }
+        Some(LogicalType::Timestamp {
+            is_adjusted_to_u_t_c,
+            ..
+        }) => Some(
+            if is_adjusted_to_u_t_c {
+                "timestamptz"
+            } else {
+                "timestamp"
+            }
+            .to_string(),
+        ),
+        Some(LogicalType::Integer {
+            bit_width,
+            is_signed,
+        }) => Some(
+            match (bit_width, is_signed) {
+                (8 | 16, true) => "smallint",
+                (8 | 16, false) | (32, true) => "integer",
+                (64, false) => "numeric",
+                _ => "bigint",
+            }
+            .to_string(),
+        ),
+        _ => None,
+    };
+    if let Some(pg_type) = pg_type {
+        return pg_type;
+    }
+    match info.converted_type() {
+        ConvertedType::UTF8 | ConvertedType::ENUM => return "text".to_string(),
+        ConvertedType::JSON => return "jsonb".to_string(),
+        ConvertedType::DATE => return "date".to_string(),
+        ConvertedType::DECIMAL => {
+            return format!("numeric({},{})", field.get_precision(), field.get_scale())
+        }
+        ConvertedType::TIMESTAMP_MILLIS | ConvertedType::TIMESTAMP_MICROS => {
+            return "timestamptz".to_string()
+        }
+        _ => {}
+    }
+    match field.get_physical_type() {
+        Physical::BOOLEAN => "boolean",
+        Physical::INT32 => "integer",
+        Physical::INT64 => "bigint",
+        Physical::INT96 => "timestamptz",
+        Physical::FLOAT => "real",
+        Physical::DOUBLE => "double precision",
+        Physical::BYTE_ARRAY | Physical::FIXED_LEN_BYTE_ARRAY => "bytea",
+    }
+    .to_string()
+}
+
+/// Text form of a Parquet value as PostgreSQL accepts it for `pg_type`
+fn parquet_field_text(field: &parquet::record::Field, pg_type: &str) -> Option<String> {
+    use chrono::{DateTime, NaiveDate};
+    use parquet::record::Field;
+
+    let text = match field {
+        Field::Null => return None,
+        Field::Str(s) => s.clone(),
+        Field::Bytes(bytes) => {
+            let hex = hex::encode(bytes.data());
+            if pg_type == "uuid" && hex.len() == 32 {
+                format!(
+                    "{}-{}-{}-{}-{}",
+                    &hex[..8],
+                    &hex[8..12],
+                    &hex[12..16],
+                    &hex[16..20],
+                    &hex[20..]
+                )
+            } else {
+                format!("\\x{}", hex)
+            }
+        }
+        Field::Date(days) => NaiveDate::from_ymd_opt(1970, 1, 1)
+            .and_then(|epoch| epoch.checked_add_signed(chrono::Duration::days(*days as i64)))
+            .map(|d| d.to_string())?,
+        Field::TimestampMillis(ms) => DateTime::from_timestamp_millis(*ms)?.to_rfc3339(),
+        Field::TimestampMicros(us) => DateTime::from_timestamp_micros(*us)?.to_rfc3339(),
+        Field::Float(v) => v.to_string(),
+        Field::Double(v) => v.to_string(),
+        Field::Group(_) | Field::ListInternal(_) | Field::MapInternal(_) => {
+            field.to_json_value().to_string()
+        }
+        // Booleans, integers and decimals print as PostgreSQL literals
+        other => other.to_string(),
+    };
+    Some(text)
+}
+
 /// Parse a SQL seed file
 pub fn parse_sql_seed(path: &Path, schema: &str, table: &str) -> Result<ParsedSqlSeed> {
     let name = format!("{}.{}", schema, table);
@@ -511,6 +1093,105 @@ mod tests {
         assert!(demo_users.schema_path.is_none());
     }
 
+    #[test]
+    fn test_parse_jsonl_seed() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        fs::write(
+            &path,
+            r#"{"id": 1, "kind": "click", "at": "2024-01-01T10:00:00Z", "meta": {"x": 1}}
+
+{"id": 2, "kind": null, "score": 1.5, "meta": [1, 2]}
+"#,
+        )
+        .unwrap();
+
+        let seed = parse_jsonl_seed(&path, None, "public", "events").unwrap();
+        let types: Vec<(&str, &str)> = seed
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.pg_type.as_str()))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("at", "timestamptz"),
+                ("id", "bigint"),
+                ("kind", "text"),
+                ("meta", "jsonb"),
+                ("score", "numeric"),
+            ]
+        );
+        assert_eq!(seed.rows.len(), 2);
+        assert_eq!(seed.rows[0][3].as_deref(), Some(r#"{"x":1}"#));
+        assert_eq!(seed.rows[1][2], None);
+        assert_eq!(seed.rows[1][0], None);
+
+        fs::write(&path, "[1, 2]\n").unwrap();
+        let err = parse_jsonl_seed(&path, None, "public", "events").unwrap_err();
+        assert!(err.to_string().contains("line 1 is not an object"));
+    }
+
+    #[test]
+    fn test_parse_parquet_seed() {
+        use parquet::data_type::{ByteArray, ByteArrayType, Int32Type, Int64Type};
+        use parquet::file::writer::SerializedFileWriter;
+        use parquet::schema::parser::parse_message_type;
+        use std::sync::Arc;
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("orders.parquet");
+        let schema = Arc::new(
+            parse_message_type(
+                "message orders {
+                    required int64 id;
+                    optional binary status (UTF8);
+                    required int32 ordered_on (DATE);
+                }",
+            )
+            .unwrap(),
+        );
+        let mut writer =
+            SerializedFileWriter::new(File::create(&path).unwrap(), schema, Default::default())
+                .unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+        let mut column = row_group.next_column().unwrap().unwrap();
+        column
+            .typed::<Int64Type>()
+            .write_batch(&[1, 2], None, None)
+            .unwrap();
+        column.close().unwrap();
+        let mut column = row_group.next_column().unwrap().unwrap();
+        column
+            .typed::<ByteArrayType>()
+            .write_batch(&[ByteArray::from("paid")], Some(&[1, 0]), None)
+            .unwrap();
+        column.close().unwrap();
+        let mut column = row_group.next_column().unwrap().unwrap();
+        column
+            .typed::<Int32Type>()
+            .write_batch(&[19723, 19724], None, None)
+            .unwrap();
+        column.close().unwrap();
+        row_group.close().unwrap();
+        writer.close().unwrap();
+
+        let seed = parse_parquet_seed(&path, None, "public", "orders").unwrap();
+        let types: Vec<&str> = seed.columns.iter().map(|c| c.pg_type.as_str()).collect();
+        assert_eq!(types, vec!["bigint", "text", "date"]);
+        assert_eq!(
+            seed.rows,
+            vec![
+                vec![
+                    Some("1".to_string()),
+                    Some("paid".to_string()),
+                    Some("2024-01-01".to_string())
+                ],
+                vec![Some("2".to_string()), None, Some("2024-01-02".to_string())],
+            ]
+        );
+    }
+
     #[test]
     fn test_infer_type_boolean() {
         assert_eq!(infer_type(&["true", "false", "true"]), "boolean");
@@ -645,6 +1326,85 @@ mod tests {
         assert!(!is_json("{incomplete"));
     }
 
+    #[test]
+    fn test_apply_seed_config() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("signups.csv");
+        fs::write(
+            &path,
+            "E-mail,Signed Up,Plan\n A@X.COM ,03/15/2024,N/A\nb@x.com,12/01/2023,pro\n",
+        )
+        .unwrap();
+        let mut seed = parse_csv_seed(&path, None, "public", "signups").unwrap();
+        let config: SeedConfig = toml::from_str(
+            r#"
+            columns = { "E-mail" = "email", "Signed Up" = "signed_up_on", "Plan" = "plan" }
+            defaults = { source = "import", plan = "free" }
+            transforms = { email = ["trim", "lower"], signed_up_on = ["date:%m/%d/%Y"], plan = ["null_if:N/A"] }
+            "#,
+        )
+        .unwrap();
+        apply_seed_config(&mut seed, &config).unwrap();
+
+        let names: Vec<_> = seed.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["email", "signed_up_on", "plan", "source"]);
+        assert_eq!(seed.columns[1].pg_type, "date");
+        let row = |i: usize| -> Vec<&str> {
+            seed.rows[i].iter().map(|v| v.as_deref().unwrap()).collect()
+        };
+        assert_eq!(row(0), vec!["a@x.com", "2024-03-15", "free", "import"]);
+        assert_eq!(row(1), vec!["b@x.com", "2023-12-01", "pro", "import"]);
+
+        let mut seed = parse_csv_seed(&path, None, "public", "signups").unwrap();
+        let config: SeedConfig =
+            toml::from_str("transforms = { \"Signed Up\" = [\"date:%Y-%m-%d\"] }").unwrap();
+        let err = apply_seed_config(&mut seed, &config).unwrap_err();
+        assert!(format!("{:#}", err).contains("row 1"), "{:#}", err);
+
+        let config: SeedConfig =
+            toml::from_str("transforms = { \"Plan\" = [\"reverse\"] }").unwrap();
+        assert!(apply_seed_config(&mut seed, &config).is_err());
+    }
+
+    #[test]
+    fn test_load_seed_configs() {
+        let tmp = TempDir::new().unwrap();
+        assert!(load_seed_configs(tmp.path()).unwrap().is_empty());
+
+        fs::write(
+            tmp.path().join(SEEDS_CONFIG_FILE),
+            r#"
+[seeds."public.users"]
+strategy = "upsert"
+key = ["email"]
+
+[seeds."public.events"]
+strategy = "append"
+
+[seeds."public.demo_users"]
+env = ["dev", "test"]
+"#,
+        )
+        .unwrap();
+        let configs = load_seed_configs(tmp.path()).unwrap();
+        assert_eq!(configs["public.users"].strategy, SeedStrategy::Upsert);
+        assert_eq!(configs["public.users"].key, Some(vec!["email".to_string()]));
+        assert_eq!(configs["public.events"].strategy, SeedStrategy::Append);
+        assert!(configs["public.users"].loads_in(None));
+        let demo = &configs["public.demo_users"];
+        assert_eq!(demo.strategy, SeedStrategy::Truncate);
+        assert!(demo.loads_in(Some("dev")));
+        assert!(!demo.loads_in(Some("prod")));
+        assert!(!demo.loads_in(None));
+
+        fs::write(
+            tmp.path().join(SEEDS_CONFIG_FILE),
+            "[seeds.\"public.users\"]\nstrategy = \"merge\"\n",
+        )
+        .unwrap();
+        assert!(load_seed_configs(tmp.path()).is_err());
+    }
+
     #[test]
     fn test_parse_schema_toml() {
         let toml_str = r#"
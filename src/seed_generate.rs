@@ -0,0 +1,356 @@
+//! Synthetic seed data: deterministic fake rows for a table, generated from
+//! its introspected columns (types, NOT NULL, enums, unique and foreign keys).
+//!
+//! Every value is derived from a hash of the seed text, table, column and row
+//! number, so the same inputs always produce the same rows. Names and emails
+//! use the same pools as the anonymize fakers.
+
+use anyhow::{bail, Result};
+use chrono::{Duration, NaiveDate};
+use sha2::{Digest, Sha256};
+
+const FIRST_NAMES: &[&str] = &[
+    "Alice", "Bob", "Carol", "David", "Emma", "Frank", "Grace", "Henry", "Iris", "Jack", "Karen",
+    "Leo", "Mia", "Noah", "Olivia",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Smith",
+    "Johnson",
+    "Williams",
+    "Brown",
+    "Jones",
+    "Garcia",
+    "Miller",
+    "Davis",
+    "Rodriguez",
+    "Martinez",
+    "Wilson",
+    "Anderson",
+    "Taylor",
+    "Thomas",
+    "Moore",
+];
+
+/// Kind of value generated for a column, from its Postgres type
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueKind {
+    Integer,
+    Decimal,
+    Boolean,
+    Date,
+    Timestamp {
+        with_tz: bool,
+    },
+    Uuid,
+    Json,
+    Text,
+    Enum(Vec<String>),
+    /// Arrays, ranges, geometry etc.: NULL when allowed
+    Unsupported,
+}
+
+impl ValueKind {
+    /// Map a `format_type()` string (domains resolved to their base type) to a kind
+    pub fn from_type(data_type: &str, enum_labels: Vec<String>) -> ValueKind {
+        if !enum_labels.is_empty() {
+            return ValueKind::Enum(enum_labels);
+        }
+        if data_type.ends_with("[]") {
+            return ValueKind::Unsupported;
+        }
+        // Drop the modifier: "timestamp(3) with time zone", "numeric(10,2)"
+        let base = match (data_type.find('('), data_type.find(')')) {
+            (Some(open), Some(close)) if close > open => {
+                format!("{}{}", &data_type[..open], &data_type[close + 1..])
+            }
+            _ => data_type.to_string(),
+        };
+        match base.as_str() {
+            "smallint" | "integer" | "bigint" => ValueKind::Integer,
+            "numeric" | "real" | "double precision" | "money" => ValueKind::Decimal,
+            "boolean" => ValueKind::Boolean,
+            "date" => ValueKind::Date,
+            "timestamp with time zone" => ValueKind::Timestamp { with_tz: true },
+            "timestamp without time zone" => ValueKind::Timestamp { with_tz: false },
+            "uuid" => ValueKind::Uuid,
+            "json" | "jsonb" => ValueKind::Json,
+            "text" | "character varying" | "character" | "citext" | "name" => ValueKind::Text,
+            _ => ValueKind::Unsupported,
+        }
+    }
+}
+
+/// A column to generate values for
+#[derive(Debug, Clone)]
+pub struct GenColumn {
+    pub name: String,
+    /// `format_type()` of the column, for error messages
+    pub data_type: String,
+    pub kind: ValueKind,
+    pub not_null: bool,
+    /// Single-column unique constraint or primary key
+    pub unique: bool,
+    /// Character limit of varchar(n)/char(n)
+    pub max_length: Option<usize>,
+    /// Values of the referenced column for a single-column foreign key
+    pub references: Option<Vec<String>>,
+}
+
+/// Deterministic 64-bit value for one cell
+fn cell_hash(seed: &str, table: &str, column: &str, n: u64) -> u64 {
+    let digest = Sha256::digest(format!("{seed}\x1f{table}\x1f{column}\x1f{n}").as_bytes());
+    u64::from_be_bytes(digest[..8].try_into().expect("8 bytes"))
+}
+
+fn text_value(column: &str, n: u64, h: u64, unique: bool) -> String {
+    let first = FIRST_NAMES[(h % FIRST_NAMES.len() as u64) as usize];
+    let last = LAST_NAMES[((h >> 8) % LAST_NAMES.len() as u64) as usize];
+    let suffix = if unique {
+        format!(" {n}")
+    } else {
+        String::new()
+    };
+    let lower = column.to_lowercase();
+    if lower.contains("email") {
+        format!(
+            "{}.{}{}@example.com",
+            first.to_lowercase(),
+            last.to_lowercase(),
+            n
+        )
+    } else if lower.contains("first_name") {
+        format!("{first}{suffix}")
+    } else if lower.contains("last_name") {
+        format!("{last}{suffix}")
+    } else if lower == "name" || lower.ends_with("_name") {
+        format!("{first} {last}{suffix}")
+    } else {
+        format!("{column} {n}")
+    }
+}
+
+fn uuid_value(h: u64, n: u64) -> String {
+    let bytes: Vec<u8> = Sha256::digest(format!("{h}:{n}").as_bytes())[..16].to_vec();
+    let hex = hex::encode(bytes);
+    // Version 4, RFC 4122 variant
+    format!(
+        "{}-{}-4{}-{}{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[13..16],
+        ["8", "9", "a", "b"][(h % 4) as usize],
+        &hex[17..20],
+        &hex[20..32]
+    )
+}
+
+fn value(
+    table: &str,
+    column: &GenColumn,
+    index: usize,
+    start: u64,
+    seed: &str,
+) -> Result<Option<String>> {
+    let n = start + index as u64;
+    let h = cell_hash(seed, table, &column.name, n);
+
+    if let Some(values) = &column.references {
+        if values.is_empty() {
+            if column.not_null {
+                bail!(
+                    "column '{}' references a table with no rows; seed that table first",
+                    column.name
+                );
+            }
+            return Ok(None);
+        }
+        if column.unique {
+            return match values.get(index) {
+                Some(v) => Ok(Some(v.clone())),
+                None => bail!(
+                    "column '{}' is unique but references only {} row(s)",
+                    column.name,
+                    values.len()
+                ),
+            };
+        }
+        return Ok(Some(values[(h % values.len() as u64) as usize].clone()));
+    }
+
+    // Roughly one in ten nullable values is NULL
+    if !column.not_null && !column.unique && h.is_multiple_of(10) {
+        return Ok(None);
+    }
+
+    let base_date = NaiveDate::from_ymd_opt(2024, 1, 1).expect("valid date");
+    let value = match &column.kind {
+        ValueKind::Integer if column.unique => n.to_string(),
+        ValueKind::Integer => (h % 1000 + 1).to_string(),
+        ValueKind::Decimal => format!("{}.{:02}", h % 10_000, (h >> 16) % 100),
+        ValueKind::Boolean => h.is_multiple_of(2).to_string(),
+        ValueKind::Date => (base_date + Duration::days((h % 365) as i64))
+            .format("%Y-%m-%d")
+            .to_string(),
+        ValueKind::Timestamp { with_tz } => {
+            let at = base_date.and_hms_opt(0, 0, 0).expect("valid time")
+                + Duration::seconds((h % (365 * 86_400)) as i64);
+            let tz = if *with_tz { "+00" } else { "" };
+            format!("{}{}", at.format("%Y-%m-%d %H:%M:%S"), tz)
+        }
+        ValueKind::Uuid => uuid_value(h, n),
+        ValueKind::Json => format!("{{\"n\": {n}}}"),
+        ValueKind::Text => {
+            let text = text_value(&column.name, n, h, column.unique);
+            match column.max_length {
+                Some(max) if text.chars().count() > max => {
+                    if column.unique {
+                        bail!(
+                            "column '{}' is {} and unique; generated values don't fit",
+                            column.name,
+                            column.data_type
+                        );
+                    }
+                    text.chars().take(max).collect()
+                }
+                _ => text,
+            }
+        }
+        ValueKind::Enum(labels) if column.unique => match labels.get(index) {
+            Some(label) => label.clone(),
+            None => bail!(
+                "column '{}' is unique but its enum has only {} value(s)",
+                column.name,
+                labels.len()
+            ),
+        },
+        ValueKind::Enum(labels) => labels[(h % labels.len() as u64) as usize].clone(),
+        ValueKind::Unsupported if column.not_null => bail!(
+            "can't generate values for NOT NULL column '{}' of type {}",
+            column.name,
+            column.data_type
+        ),
+        ValueKind::Unsupported => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+/// Generate `rows` rows for `table` (qualified name). Unique integer columns
+/// count up from `start`.
+pub fn generate_rows(
+    table: &str,
+    columns: &[GenColumn],
+    rows: usize,
+    start: u64,
+    seed: &str,
+) -> Result<Vec<Vec<Option<String>>>> {
+    (0..rows)
+        .map(|index| {
+            columns
+                .iter()
+                .map(|column| value(table, column, index, start, seed))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str) -> GenColumn {
+        GenColumn {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            kind: ValueKind::from_type(data_type, Vec::new()),
+            not_null: true,
+            unique: false,
+            max_length: None,
+            references: None,
+        }
+    }
+
+    #[test]
+    fn test_value_kind_from_type() {
+        assert_eq!(ValueKind::from_type("bigint", vec![]), ValueKind::Integer);
+        assert_eq!(
+            ValueKind::from_type("numeric(10,2)", vec![]),
+            ValueKind::Decimal
+        );
+        assert_eq!(
+            ValueKind::from_type("timestamp(3) with time zone", vec![]),
+            ValueKind::Timestamp { with_tz: true }
+        );
+        assert_eq!(
+            ValueKind::from_type("character varying(20)", vec![]),
+            ValueKind::Text
+        );
+        assert_eq!(
+            ValueKind::from_type("text[]", vec![]),
+            ValueKind::Unsupported
+        );
+        assert_eq!(
+            ValueKind::from_type("mood", vec!["happy".to_string()]),
+            ValueKind::Enum(vec!["happy".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_generate_rows_is_deterministic() {
+        let mut id = column("id", "integer");
+        id.unique = true;
+        let mut email = column("email", "character varying(255)");
+        email.unique = true;
+        email.max_length = Some(255);
+        let columns = vec![
+            id,
+            email,
+            column("name", "text"),
+            column("created_at", "timestamp with time zone"),
+            column("external_id", "uuid"),
+        ];
+        let rows = generate_rows("public.users", &columns, 3, 1, "pgcrate").unwrap();
+        assert_eq!(
+            rows,
+            generate_rows("public.users", &columns, 3, 1, "pgcrate").unwrap()
+        );
+        assert_ne!(
+            rows,
+            generate_rows("public.users", &columns, 3, 1, "other").unwrap()
+        );
+
+        let ids: Vec<_> = rows.iter().map(|r| r[0].clone().unwrap()).collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+        assert!(rows[1][1].as_ref().unwrap().ends_with("2@example.com"));
+        assert!(rows[0][2].as_ref().unwrap().contains(' '));
+        assert!(rows[0][3].as_ref().unwrap().ends_with("+00"));
+        let uuid = rows[0][4].as_ref().unwrap();
+        assert_eq!((uuid.len(), &uuid[14..15]), (36, "4"));
+    }
+
+    #[test]
+    fn test_generate_rows_constraints() {
+        // Foreign keys pick from referenced values
+        let mut user_id = column("user_id", "integer");
+        user_id.references = Some(vec!["7".to_string(), "9".to_string()]);
+        let rows = generate_rows("public.posts", &[user_id.clone()], 20, 1, "s").unwrap();
+        assert!(rows
+            .iter()
+            .all(|r| matches!(r[0].as_deref(), Some("7") | Some("9"))));
+
+        user_id.references = Some(Vec::new());
+        let err = generate_rows("public.posts", &[user_id], 1, 1, "s").unwrap_err();
+        assert!(err.to_string().contains("seed that table first"));
+
+        // Length limits truncate; nullable unsupported types are NULL
+        let mut code = column("code", "character(3)");
+        code.max_length = Some(3);
+        let mut tags = column("tags", "text[]");
+        tags.not_null = false;
+        let rows = generate_rows("public.items", &[code, tags.clone()], 2, 1, "s").unwrap();
+        assert_eq!(rows[0], vec![Some("cod".to_string()), None]);
+
+        tags.not_null = true;
+        assert!(generate_rows("public.items", &[tags], 1, 1, "s").is_err());
+    }
+}
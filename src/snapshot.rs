@@ -1,10 +1,13 @@
 use anyhow::{bail, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use crate::snapshot_encryption::Cipher;
+
 /// Snapshot dump format
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -44,6 +47,9 @@ impl SnapshotFormat {
     }
 }
 
+/// Rows kept by profile row filters, as COPY blocks for psql
+pub const FILTERED_ROWS_FILE: &str = "filtered.sql";
+
 /// Snapshot metadata stored in metadata.json
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SnapshotMetadata {
@@ -75,6 +81,13 @@ pub struct SnapshotMetadata {
     pub excluded_tables: Option<Vec<String>>,
     #[serde(default = "default_true")]
     pub include_data: bool,
+    /// Row filters applied at save time (`schema.table` -> condition); the
+    /// kept rows are in `filtered.sql`, loaded after the dump on restore
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub row_filters: BTreeMap<String, String>,
+    /// Tool the dump and filtered rows are encrypted with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<Cipher>,
     pub pgcrate_version: String,
 }
 
@@ -127,6 +140,8 @@ impl SnapshotMetadata {
             included_tables,
             excluded_tables,
             include_data,
+            row_filters: BTreeMap::new(),
+            encryption: None,
             pgcrate_version: env!("CARGO_PKG_VERSION").to_string(),
         }
     }
@@ -275,15 +290,81 @@ pub fn check_psql(path: &str) -> Result<()> {
 }
 
 pub fn get_pg_dump_version(path: &str) -> Result<String> {
+    get_tool_version(path)
+}
+
+/// Version a PostgreSQL client tool reports with `--version`
+pub fn get_tool_version(path: &str) -> Result<String> {
     let output = Command::new(path).arg("--version").output()?;
     if !output.status.success() {
-        bail!("Failed to get pg_dump version");
+        bail!("Failed to get {} version", path);
     }
-    Ok(String::from_utf8_lossy(&output.stdout)
+    Ok(parse_tool_version(&String::from_utf8_lossy(&output.stdout))
+        .unwrap_or_else(|| "unknown".to_string()))
+}
+
+/// `16.4` from `pg_dump (PostgreSQL) 16.4 (Ubuntu 16.4-1.pgdg22.04+1)`
+fn parse_tool_version(output: &str) -> Option<String> {
+    output
         .split_whitespace()
-        .last()
-        .unwrap_or("unknown")
-        .to_string())
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
+/// A client tool older than the server (or archive) it has to work with.
+/// pg_dump refuses newer servers, and pg_restore can't read archives from a
+/// newer pg_dump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolVersionMismatch {
+    pub tool: &'static str,
+    pub path: String,
+    pub tool_version: String,
+    /// What the tool works with, e.g. `server 17.2`
+    pub target: String,
+    pub required_major: u32,
+}
+
+impl std::fmt::Display for ToolVersionMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} ({}) is older than the {} it works with.\n\
+             Hint: Install {} {} or newer and set {}_path under [tools] or on the connection in pgcrate.toml.",
+            self.tool,
+            self.tool_version,
+            self.path,
+            self.target,
+            self.tool,
+            self.required_major,
+            self.tool
+        )
+    }
+}
+
+impl std::error::Error for ToolVersionMismatch {}
+
+/// Check that `tool_version` is at least the major version of `target_version`;
+/// unparseable versions pass
+pub fn check_tool_version(
+    tool: &'static str,
+    path: &str,
+    tool_version: &str,
+    target: &str,
+    target_version: &str,
+) -> std::result::Result<(), ToolVersionMismatch> {
+    match (
+        parse_major_version(tool_version),
+        parse_major_version(target_version),
+    ) {
+        (Some(have), Some(need)) if have < need => Err(ToolVersionMismatch {
+            tool,
+            path: path.to_string(),
+            tool_version: tool_version.to_string(),
+            target: format!("{} {}", target, target_version),
+            required_major: need,
+        }),
+        _ => Ok(()),
+    }
 }
 
 pub fn extract_host(database_url: &str) -> Option<String> {
@@ -414,6 +495,25 @@ mod tests {
         assert!(!should_warn_version_downgrade("14.1", "16.2"));
     }
 
+    #[test]
+    fn test_tool_version_check() {
+        assert_eq!(
+            parse_tool_version("pg_dump (PostgreSQL) 16.4 (Ubuntu 16.4-1.pgdg22.04+1)\n"),
+            Some("16.4".to_string())
+        );
+        assert_eq!(parse_tool_version("pg_dump"), None);
+
+        assert!(check_tool_version("pg_dump", "pg_dump", "17.0", "server", "16.4").is_ok());
+        assert!(check_tool_version("pg_dump", "pg_dump", "16.1", "server", "16.4").is_ok());
+        assert!(check_tool_version("pg_dump", "pg_dump", "unknown", "server", "16.4").is_ok());
+        let err = check_tool_version("pg_dump", "/usr/bin/pg_dump", "15.8", "server", "17.2")
+            .unwrap_err();
+        assert_eq!(err.required_major, 17);
+        assert!(err
+            .to_string()
+            .starts_with("pg_dump 15.8 (/usr/bin/pg_dump) is older than the server 17.2"));
+    }
+
     #[test]
     fn test_extract_host() {
         assert_eq!(
@@ -0,0 +1,293 @@
+//! Encrypted snapshots: dump files are piped through `age` or `gpg` before
+//! they reach disk or remote storage, and decrypted again on restore.
+//!
+//! ```toml
+//! [snapshot.encryption]
+//! recipients = ["age1..."]                  # or GPG key ids / emails
+//! identity = "keys/snapshots.txt"           # age only: key used to decrypt
+//! ```
+//!
+//! `snapshot save --encrypt-key` overrides the configured recipients. `age1…`
+//! and `ssh-…` recipients use age; anything else is a GPG key. GPG decrypts
+//! with the keys in the user's keyring.
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::process::{Child, Command, Stdio};
+
+use crate::config::Config;
+
+/// Tool a snapshot is encrypted with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cipher {
+    Age,
+    Gpg,
+}
+
+impl std::fmt::Display for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.tool())
+    }
+}
+
+impl Cipher {
+    pub fn tool(&self) -> &'static str {
+        match self {
+            Cipher::Age => "age",
+            Cipher::Gpg => "gpg",
+        }
+    }
+
+    fn for_recipient(recipient: &str) -> Cipher {
+        if recipient.starts_with("age1") || recipient.starts_with("ssh-") {
+            Cipher::Age
+        } else {
+            Cipher::Gpg
+        }
+    }
+}
+
+/// Recipients a new snapshot is encrypted for
+#[derive(Debug, Clone)]
+pub struct Encryption {
+    pub cipher: Cipher,
+    recipients: Vec<String>,
+    program: String,
+}
+
+/// Decrypts the files of an encrypted snapshot
+#[derive(Debug, Clone)]
+pub struct Decryption {
+    cipher: Cipher,
+    identity: Option<String>,
+    program: String,
+}
+
+/// Encryption for a new snapshot: `--encrypt-key` values, else the
+/// `[snapshot.encryption]` recipients; `None` when neither is set
+pub fn snapshot_encryption(keys: &[String], config: &Config) -> Result<Option<Encryption>> {
+    let recipients = if keys.is_empty() {
+        config
+            .snapshot_encryption()
+            .map(|e| e.recipients.clone())
+            .unwrap_or_default()
+    } else {
+        keys.to_vec()
+    };
+    if recipients.is_empty() {
+        return Ok(None);
+    }
+    Encryption::new(recipients, config).map(Some)
+}
+
+/// Spawn a filter reading stdin and writing stdout
+fn spawn(program: &str, tool: &str, args: Vec<String>, stdin: Stdio) -> Result<Child> {
+    Command::new(program)
+        .args(args)
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            anyhow!(
+                "{} not found ({}).\nHint: Install {}, add it to PATH, or configure [tools] in pgcrate.toml.",
+                program,
+                e,
+                tool
+            )
+        })
+}
+
+/// Wait for a filter whose stdout has been read
+fn finish(child: Child, what: &str) -> Result<()> {
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "{} failed:\n{}",
+            what,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+impl Encryption {
+    pub fn new(recipients: Vec<String>, config: &Config) -> Result<Self> {
+        let cipher = Cipher::for_recipient(&recipients[0]);
+        if let Some(other) = recipients
+            .iter()
+            .find(|r| Cipher::for_recipient(r) != cipher)
+        {
+            bail!(
+                "Encryption recipients mix age and GPG keys ('{}' and '{}')",
+                recipients[0],
+                other
+            );
+        }
+        Ok(Encryption {
+            cipher,
+            recipients,
+            program: config.tool_path(cipher.tool()),
+        })
+    }
+
+    fn encrypt_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = match self.cipher {
+            Cipher::Age => vec!["--encrypt".into()],
+            Cipher::Gpg => ["--batch", "--yes", "--quiet", "--trust-model", "always"]
+                .iter()
+                .map(|s| s.to_string())
+                .chain(["--output".into(), "-".into(), "--encrypt".into()])
+                .collect(),
+        };
+        for recipient in &self.recipients {
+            args.push("--recipient".into());
+            args.push(recipient.clone());
+        }
+        args
+    }
+
+    /// Start encrypting `stdin`; read the ciphertext from the child's stdout,
+    /// then pass it to [`Encryption::finish`]
+    pub fn spawn(&self, stdin: Stdio) -> Result<Child> {
+        spawn(
+            &self.program,
+            self.cipher.tool(),
+            self.encrypt_args(),
+            stdin,
+        )
+    }
+
+    pub fn finish(&self, child: Child) -> Result<()> {
+        finish(child, &format!("{} encryption", self.cipher))
+    }
+
+    /// Encrypt a small file held in memory
+    pub fn encrypt(&self, plain: &[u8]) -> Result<Vec<u8>> {
+        let mut child = self.spawn(Stdio::piped())?;
+        let mut stdin = child.stdin.take().expect("piped stdin");
+        let mut stdout = child.stdout.take().expect("piped stdout");
+        let plain = plain.to_vec();
+        let writer = std::thread::spawn(move || stdin.write_all(&plain));
+        let mut encrypted = Vec::new();
+        stdout.read_to_end(&mut encrypted)?;
+        let written = writer.join().expect("writer thread");
+        self.finish(child)?;
+        written?;
+        Ok(encrypted)
+    }
+}
+
+impl Decryption {
+    /// Decryption for a snapshot encrypted with `cipher`
+    pub fn new(cipher: Cipher, config: &Config) -> Result<Self> {
+        let identity = config
+            .snapshot_encryption()
+            .and_then(|e| e.identity.clone());
+        if cipher == Cipher::Age && identity.is_none() {
+            bail!(
+                "Snapshot is encrypted with age but no identity is configured.\n\
+                 Hint: Set identity = \"path/to/key.txt\" under [snapshot.encryption] in pgcrate.toml."
+            );
+        }
+        Ok(Decryption {
+            cipher,
+            identity,
+            program: config.tool_path(cipher.tool()),
+        })
+    }
+
+    fn decrypt_args(&self) -> Vec<String> {
+        match self.cipher {
+            Cipher::Age => vec![
+                "--decrypt".into(),
+                "--identity".into(),
+                self.identity.clone().unwrap_or_default(),
+            ],
+            Cipher::Gpg => ["--batch", "--quiet", "--decrypt"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+
+    /// Start decrypting `stdin`; read the plaintext from the child's stdout,
+    /// then pass it to [`Decryption::finish`]
+    pub fn spawn(&self, stdin: Stdio) -> Result<Child> {
+        spawn(
+            &self.program,
+            self.cipher.tool(),
+            self.decrypt_args(),
+            stdin,
+        )
+    }
+
+    pub fn finish(&self, child: Child) -> Result<()> {
+        finish(child, &format!("{} decryption", self.cipher))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipients(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn test_encryption_args() {
+        let config = Config::default();
+        let age = Encryption::new(recipients(&["age1abc", "ssh-ed25519 AAAA"]), &config).unwrap();
+        assert_eq!(age.cipher, Cipher::Age);
+        assert_eq!(age.program, "age");
+        assert_eq!(
+            age.encrypt_args(),
+            [
+                "--encrypt",
+                "--recipient",
+                "age1abc",
+                "--recipient",
+                "ssh-ed25519 AAAA"
+            ]
+        );
+
+        let gpg = Encryption::new(recipients(&["ops@example.com"]), &config).unwrap();
+        assert_eq!(gpg.cipher, Cipher::Gpg);
+        assert_eq!(
+            gpg.encrypt_args()[5..],
+            [
+                "--output",
+                "-",
+                "--encrypt",
+                "--recipient",
+                "ops@example.com"
+            ]
+        );
+
+        assert!(Encryption::new(recipients(&["age1abc", "ops@example.com"]), &config).is_err());
+    }
+
+    #[test]
+    fn test_decryption_requires_age_identity() {
+        let config = Config::default();
+        assert!(Decryption::new(Cipher::Age, &config).is_err());
+        let gpg = Decryption::new(Cipher::Gpg, &config).unwrap();
+        assert_eq!(gpg.decrypt_args(), ["--batch", "--quiet", "--decrypt"]);
+
+        let config: Config = toml::from_str(
+            r#"
+            [snapshot.encryption]
+            identity = "keys/age.txt"
+            "#,
+        )
+        .unwrap();
+        let age = Decryption::new(Cipher::Age, &config).unwrap();
+        assert_eq!(
+            age.decrypt_args(),
+            ["--decrypt", "--identity", "keys/age.txt"]
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! Remote snapshot storage: with `[snapshot.storage]` set, snapshots live in
+//! S3 or GCS instead of the local snapshots directory.
+//!
+//! ```toml
+//! [snapshot.storage]
+//! url = "s3://my-bucket/pgcrate/snapshots"   # or gs://bucket/prefix
+//! ```
+//!
+//! Objects are read and written with the `aws` and `gcloud` CLIs, so their
+//! usual credentials apply. Dumps stream through pipes in both directions and
+//! are never staged on local disk. Each snapshot is a prefix holding the dump
+//! and `metadata.json`; metadata is written last, so a snapshot with metadata
+//! is complete.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+
+use crate::config::Config;
+use crate::snapshot::{validate_snapshot_name, SnapshotMetadata};
+
+const METADATA_FILE: &str = "metadata.json";
+
+/// Object store behind a `[snapshot.storage]` url
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StorageBackend {
+    S3,
+    Gcs,
+}
+
+impl StorageBackend {
+    /// CLI used to talk to the store
+    pub fn tool(&self) -> &'static str {
+        match self {
+            StorageBackend::S3 => "aws",
+            StorageBackend::Gcs => "gcloud",
+        }
+    }
+}
+
+/// Snapshots stored under an `s3://` or `gs://` prefix
+#[derive(Debug, Clone)]
+pub struct SnapshotStorage {
+    pub backend: StorageBackend,
+    /// Base url, without a trailing slash
+    url: String,
+    /// Path of the `aws`/`gcloud` binary
+    program: String,
+}
+
+/// Remote storage configured in `[snapshot.storage]`, if any
+pub fn snapshot_storage(config: &Config) -> Result<Option<SnapshotStorage>> {
+    let Some(url) = config.snapshot_storage_url() else {
+        return Ok(None);
+    };
+    let mut storage = SnapshotStorage::parse(url).context("[snapshot.storage]")?;
+    storage.program = config.tool_path(storage.backend.tool());
+    Ok(Some(storage))
+}
+
+/// Snapshot names from `aws s3 ls` (`PRE name/`) or `gcloud storage ls`
+/// (`gs://bucket/prefix/name/`) output
+fn parse_listing(stdout: &str) -> Vec<String> {
+    let mut names: Vec<String> = stdout
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let dir = line
+                .strip_prefix("PRE ")
+                .unwrap_or(line)
+                .strip_suffix('/')?;
+            dir.rsplit('/').next().map(str::to_string)
+        })
+        .filter(|name| validate_snapshot_name(name).is_ok())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+impl SnapshotStorage {
+    pub fn parse(url: &str) -> Result<Self> {
+        let (backend, rest) = if let Some(rest) = url.strip_prefix("s3://") {
+            (StorageBackend::S3, rest)
+        } else if let Some(rest) = url.strip_prefix("gs://") {
+            (StorageBackend::Gcs, rest)
+        } else {
+            bail!("invalid url '{url}': expected s3://bucket/prefix or gs://bucket/prefix");
+        };
+        let rest = rest.trim_end_matches('/');
+        if rest.split('/').next().unwrap_or("").is_empty() {
+            bail!("invalid url '{url}': missing bucket name");
+        }
+        Ok(SnapshotStorage {
+            backend,
+            url: format!("{}{}", &url[..5], rest),
+            program: backend.tool().to_string(),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn object_url(&self, name: &str, file: &str) -> String {
+        format!("{}/{}/{}", self.url, name, file)
+    }
+
+    fn upload_args(&self, dest: &str) -> Vec<String> {
+        let args: &[&str] = match self.backend {
+            StorageBackend::S3 => &["s3", "cp", "--only-show-errors", "-", dest],
+            StorageBackend::Gcs => &["storage", "cp", "-", dest],
+        };
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn download_args(&self, src: &str) -> Vec<String> {
+        let args: &[&str] = match self.backend {
+            StorageBackend::S3 => &["s3", "cp", "--only-show-errors", src, "-"],
+            StorageBackend::Gcs => &["storage", "cat", src],
+        };
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn list_args(&self) -> Vec<String> {
+        let base = format!("{}/", self.url);
+        let args: &[&str] = match self.backend {
+            StorageBackend::S3 => &["s3", "ls", &base],
+            StorageBackend::Gcs => &["storage", "ls", &base],
+        };
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn delete_args(&self, name: &str) -> Vec<String> {
+        let prefix = format!("{}/{}/", self.url, name);
+        let args: &[&str] = match self.backend {
+            StorageBackend::S3 => &["s3", "rm", "--only-show-errors", "--recursive", &prefix],
+            StorageBackend::Gcs => &["storage", "rm", "--recursive", &prefix],
+        };
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn command(&self, args: Vec<String>) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(args);
+        cmd
+    }
+
+    fn spawn(&self, cmd: &mut Command) -> Result<Child> {
+        cmd.spawn().map_err(|e| {
+            anyhow!(
+                "{} not found ({}).\nHint: Install the {} CLI, add it to PATH, or configure [tools] in pgcrate.toml.",
+                self.program,
+                e,
+                self.backend.tool()
+            )
+        })
+    }
+
+    fn run(&self, args: Vec<String>, what: &str) -> Result<Vec<u8>> {
+        let child = self.spawn(
+            self.command(args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped()),
+        )?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "{} failed:\n{}",
+                what,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(output.stdout)
+    }
+
+    /// Stream `source` to `<name>/<file>`, returning the number of bytes sent
+    pub fn upload(&self, name: &str, file: &str, source: &mut dyn Read) -> Result<u64> {
+        let dest = self.object_url(name, file);
+        let mut child = self.spawn(
+            self.command(self.upload_args(&dest))
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped()),
+        )?;
+        let mut stdin = child.stdin.take().expect("piped stdin");
+        let copied = std::io::copy(source, &mut stdin);
+        drop(stdin);
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "upload to {} failed:\n{}",
+                dest,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        copied.with_context(|| format!("upload to {}", dest))
+    }
+
+    /// Start streaming `<name>/<file>`; read the dump from the child's stdout,
+    /// then pass it to [`SnapshotStorage::finish_download`]
+    pub fn download(&self, name: &str, file: &str) -> Result<Child> {
+        self.spawn(
+            self.command(self.download_args(&self.object_url(name, file)))
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped()),
+        )
+    }
+
+    pub fn finish_download(&self, child: Child, name: &str, file: &str) -> Result<()> {
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "download of {} failed:\n{}",
+                self.object_url(name, file),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Names of every snapshot prefix, complete or not
+    pub fn names(&self) -> Result<Vec<String>> {
+        let child = self.spawn(
+            self.command(self.list_args())
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped()),
+        )?;
+        let output = child.wait_with_output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() {
+            // Both CLIs fail when nothing exists under the prefix yet
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stdout.trim().is_empty()
+                && (stderr.trim().is_empty() || stderr.contains("matched no objects"))
+            {
+                return Ok(Vec::new());
+            }
+            bail!("listing {}/ failed:\n{}", self.url, stderr.trim());
+        }
+        Ok(parse_listing(&stdout))
+    }
+
+    pub fn load_metadata(&self, name: &str) -> Result<SnapshotMetadata> {
+        let what = format!("download of {}", self.object_url(name, METADATA_FILE));
+        let bytes = self.run(
+            self.download_args(&self.object_url(name, METADATA_FILE)),
+            &what,
+        )?;
+        serde_json::from_slice(&bytes).map_err(|e| anyhow!("Failed to parse metadata: {}", e))
+    }
+
+    pub fn save_metadata(&self, metadata: &SnapshotMetadata) -> Result<()> {
+        let json = serde_json::to_string_pretty(metadata)?;
+        self.upload(&metadata.name, METADATA_FILE, &mut json.as_bytes())?;
+        Ok(())
+    }
+
+    /// Metadata of every complete snapshot, newest first
+    pub fn list(&self) -> Result<Vec<SnapshotMetadata>> {
+        let mut snapshots: Vec<SnapshotMetadata> = self
+            .names()?
+            .iter()
+            .filter_map(|name| self.load_metadata(name).ok())
+            .collect();
+        snapshots.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+        Ok(snapshots)
+    }
+
+    /// Remove everything under the snapshot's prefix
+    pub fn delete(&self, name: &str) -> Result<()> {
+        let what = format!("deleting {}/{}/", self.url, name);
+        self.run(self.delete_args(name), &what)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_storage_url() {
+        let s3 = SnapshotStorage::parse("s3://bucket/pgcrate/snapshots/").unwrap();
+        assert_eq!(s3.backend, StorageBackend::S3);
+        assert_eq!(s3.url(), "s3://bucket/pgcrate/snapshots");
+        assert_eq!(s3.program, "aws");
+
+        let gcs = SnapshotStorage::parse("gs://bucket").unwrap();
+        assert_eq!(
+            (gcs.backend, gcs.url()),
+            (StorageBackend::Gcs, "gs://bucket")
+        );
+
+        assert!(SnapshotStorage::parse("s3://").is_err());
+        assert!(SnapshotStorage::parse("s3:///prefix").is_err());
+        assert!(SnapshotStorage::parse("azure://container").is_err());
+    }
+
+    #[test]
+    fn test_storage_commands() {
+        let s3 = SnapshotStorage::parse("s3://bucket/snaps").unwrap();
+        assert_eq!(
+            s3.upload_args(&s3.object_url("nightly", "dump.pgdump")),
+            [
+                "s3",
+                "cp",
+                "--only-show-errors",
+                "-",
+                "s3://bucket/snaps/nightly/dump.pgdump"
+            ]
+        );
+        assert_eq!(s3.list_args(), ["s3", "ls", "s3://bucket/snaps/"]);
+        assert_eq!(
+            s3.delete_args("nightly"),
+            [
+                "s3",
+                "rm",
+                "--only-show-errors",
+                "--recursive",
+                "s3://bucket/snaps/nightly/"
+            ]
+        );
+
+        let gcs = SnapshotStorage::parse("gs://bucket/snaps").unwrap();
+        assert_eq!(
+            gcs.download_args(&gcs.object_url("nightly", "metadata.json")),
+            ["storage", "cat", "gs://bucket/snaps/nightly/metadata.json"]
+        );
+        assert_eq!(
+            gcs.upload_args("gs://bucket/snaps/nightly/dump.sql"),
+            ["storage", "cp", "-", "gs://bucket/snaps/nightly/dump.sql"]
+        );
+    }
+
+    #[test]
+    fn test_parse_listing() {
+        let aws = "                           PRE nightly/\n                           PRE before-migration/\n2024-01-01 00:00:00        12 README\n";
+        assert_eq!(parse_listing(aws), ["before-migration", "nightly"]);
+
+        let gcloud = "gs://bucket/snaps/nightly/\ngs://bucket/snaps/notes.txt\ngs://bucket/snaps/bad name/\n";
+        assert_eq!(parse_listing(gcloud), ["nightly"]);
+    }
+}
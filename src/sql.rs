@@ -15,10 +15,25 @@ pub fn quote_ident(s: &str) -> String {
     format!("\"{}\"", s.replace('"', "\"\""))
 }
 
+/// Quote a string as a PostgreSQL literal, doubling embedded single quotes.
+///
+/// Assumes `standard_conforming_strings` is on (the default since 9.1), so
+/// backslashes need no escaping.
+pub fn quote_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_quote_literal() {
+        assert_eq!(quote_literal("users"), "'users'");
+        assert_eq!(quote_literal("it's"), "'it''s'");
+        assert_eq!(quote_literal("C:\\path"), "'C:\\path'");
+    }
+
     #[test]
     fn test_quote_ident_simple() {
         // All identifiers are now quoted
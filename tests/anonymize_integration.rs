@@ -179,6 +179,97 @@ fn test_anonymize_dump_with_toml_config() {
     drop_test_db(&base_url, "pgcrate_anon_toml");
 }
 
+#[test]
+fn test_anonymize_dump_with_schema() {
+    let base_url = get_test_db_url();
+    if !can_connect(&base_url) {
+        return;
+    }
+
+    let test_db = create_test_db(&base_url, "pgcrate_anon_schema").unwrap();
+    let target = create_test_db(&base_url, "pgcrate_anon_schema_target").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    setup_test_data(&test_db);
+    run_psql(
+        "CREATE TABLE sessions (id SERIAL PRIMARY KEY, user_id INT REFERENCES users(id), token TEXT);
+         INSERT INTO sessions (user_id, token) VALUES (1, 'secret-token')",
+        &test_db,
+    );
+    run_pgcrate(&["anonymize", "setup"], &test_db, temp_dir.path());
+    let toml_content = r#"
+        seed = "test-seed"
+        [[rules]]
+        table = "public.users"
+        columns = { email = "fake_email", name = "fake_name" }
+        [[rules]]
+        table = "public.sessions"
+        skip = true
+    "#;
+    fs::write(temp_dir.path().join("pgcrate.anonymize.toml"), toml_content).unwrap();
+
+    let dump_file = temp_dir.path().join("dump.sql");
+    let output = run_pgcrate(
+        &[
+            "anonymize",
+            "dump",
+            "--schema",
+            "--output",
+            dump_file.to_str().unwrap(),
+        ],
+        &test_db,
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = fs::read_to_string(&dump_file).unwrap();
+    assert!(!content.contains("secret"));
+
+    // Loads into an empty database on its own
+    let load = Command::new("psql")
+        .args([
+            &target,
+            "-v",
+            "ON_ERROR_STOP=1",
+            "-q",
+            "-f",
+            dump_file.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    assert!(
+        load.status.success(),
+        "{}",
+        String::from_utf8_lossy(&load.stderr)
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT COUNT(*) FROM users WHERE email NOT LIKE '%secret.com'",
+            &target
+        ),
+        "2"
+    );
+    assert_eq!(
+        run_psql_query("SELECT COUNT(*) FROM sessions", &target),
+        "0"
+    );
+    assert_eq!(
+        run_psql_query(
+            "INSERT INTO users (email, name) VALUES ('a@b.c', 'A') RETURNING id",
+            &target
+        )
+        .lines()
+        .next()
+        .unwrap_or_default(),
+        "3"
+    );
+
+    drop_test_db(&base_url, "pgcrate_anon_schema");
+    drop_test_db(&base_url, "pgcrate_anon_schema_target");
+}
+
 #[test]
 fn test_anonymize_dump_requires_seed() {
     let base_url = get_test_db_url();
@@ -240,3 +331,671 @@ fn test_anonymize_dump_with_cli_seed() {
 
     drop_test_db(&base_url, "pgcrate_anon_cli_seed");
 }
+
+#[test]
+fn test_anonymize_format_preserving_strategies() {
+    let base_url = get_test_db_url();
+    if !can_connect(&base_url) {
+        return;
+    }
+
+    let test_db = create_test_db(&base_url, "pgcrate_anon_formats").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    run_psql(
+        r#"
+        CREATE TABLE customers (
+            id INT PRIMARY KEY,
+            email TEXT,
+            phone TEXT,
+            born_on DATE,
+            signed_up_at TIMESTAMPTZ,
+            last_seen_at TIMESTAMPTZ
+        );
+        CREATE TABLE invoices (id INT PRIMARY KEY, email TEXT);
+        INSERT INTO customers VALUES
+            (1, 'john.doe@acme.com', '+44 20 7946 0958', '1980-05-17', '2024-01-10 09:00+00', '2024-01-10 10:00+00'),
+            (2, NULL, NULL, NULL, NULL, NULL);
+        INSERT INTO invoices VALUES (10, 'John.Doe@acme.com');
+        "#,
+        &test_db,
+    );
+    run_pgcrate(&["anonymize", "setup"], &test_db, temp_dir.path());
+
+    let toml_content = r#"
+        seed = "format-seed"
+        [[rules]]
+        table = "public.customers"
+        columns = { email = "hash_email_local", phone = "fake_phone", born_on = "shift_date", signed_up_at = "shift_date", last_seen_at = "shift_date" }
+        [[rules]]
+        table = "public.invoices"
+        columns = { email = "hash_email_local" }
+    "#;
+    fs::write(temp_dir.path().join("pgcrate.anonymize.toml"), toml_content).unwrap();
+
+    let dump_file = temp_dir.path().join("dump.sql");
+    let output = run_pgcrate(
+        &["anonymize", "dump", "--output", dump_file.to_str().unwrap()],
+        &test_db,
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Load the dump back into emptied tables to inspect the values
+    run_psql("TRUNCATE customers, invoices", &test_db);
+    let load = Command::new("psql")
+        .args([&test_db, "-v", "ON_ERROR_STOP=1", "-q", "-f"])
+        .arg(&dump_file)
+        .output()
+        .unwrap();
+    assert!(load.status.success());
+
+    let email = run_psql_query("SELECT email FROM customers WHERE id = 1", &test_db);
+    assert!(
+        email.starts_with("user_") && email.ends_with("@acme.com"),
+        "{}",
+        email
+    );
+    assert!(!email.contains("john"));
+    // Same address (any case) hashes the same in every table
+    assert_eq!(
+        run_psql_query("SELECT email FROM invoices", &test_db),
+        email
+    );
+
+    let phone = run_psql_query("SELECT phone FROM customers WHERE id = 1", &test_db);
+    assert_eq!(phone.len(), "+44 20 7946 0958".len());
+    assert!(phone.starts_with("+44 "), "{}", phone);
+    assert_ne!(phone, "+44 20 7946 0958");
+
+    // Dates move, but the row's timestamps move together
+    assert_eq!(
+        run_psql_query(
+            "SELECT born_on <> '1980-05-17' AND abs(born_on - '1980-05-17'::date) <= 30
+                    AND last_seen_at - signed_up_at = interval '1 hour'
+                    AND signed_up_at::date - born_on = '2024-01-10'::date - '1980-05-17'::date
+             FROM customers WHERE id = 1",
+            &test_db
+        ),
+        "t"
+    );
+
+    assert_eq!(
+        run_psql_query(
+            "SELECT email IS NULL AND phone IS NULL AND born_on IS NULL AND signed_up_at IS NULL
+             FROM customers WHERE id = 2",
+            &test_db
+        ),
+        "t"
+    );
+
+    drop_test_db(&base_url, "pgcrate_anon_formats");
+}
+
+#[test]
+fn test_anonymize_scan_suggests_rules() {
+    let base_url = get_test_db_url();
+    if !can_connect(&base_url) {
+        return;
+    }
+
+    let test_db = create_test_db(&base_url, "pgcrate_anon_scan").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    setup_test_data(&test_db);
+    run_psql(
+        r#"
+        CREATE TABLE products (id SERIAL PRIMARY KEY, name TEXT, sku TEXT);
+        INSERT INTO products (name, sku) VALUES ('Widget', 'W-1');
+        CREATE TABLE logins (id SERIAL PRIMARY KEY, contact TEXT, source inet);
+        INSERT INTO logins (contact, source) VALUES
+            ('a@example.com', '10.0.0.1'), ('b@example.org', '10.0.0.2');
+        "#,
+        &test_db,
+    );
+
+    let output = run_pgcrate(&["anonymize", "scan"], &test_db, temp_dir.path());
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let path = temp_dir.path().join("pgcrate.anonymize.toml");
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.contains("table = \"public.users\""));
+    assert!(content.contains(
+        "columns = { email = \"fake_email\", name = \"fake_name\", phone = \"fake_phone\" }"
+    ));
+    assert!(content.contains("# contact: 2 of 2 sampled values look like email"));
+    assert!(content.contains("contact = \"fake_email\", source = \"null\""));
+    assert!(!content.contains("public.products"));
+
+    // Refuses to overwrite without --force
+    let output = run_pgcrate(&["anonymize", "scan"], &test_db, temp_dir.path());
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+
+    // The suggestions are a valid rules file
+    fs::write(
+        &path,
+        content.replace("# seed = \"\"", "seed = \"scan-seed\""),
+    )
+    .unwrap();
+    run_pgcrate(&["anonymize", "setup"], &test_db, temp_dir.path());
+    let output = run_pgcrate(&["anonymize", "dump"], &test_db, temp_dir.path());
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let dump = String::from_utf8_lossy(&output.stdout);
+    assert!(!dump.contains("john.doe@secret.com"));
+    assert!(!dump.contains("a@example.com"));
+
+    drop_test_db(&base_url, "pgcrate_anon_scan");
+}
+
+#[test]
+fn test_anonymize_apply_in_place() {
+    let base_url = get_test_db_url();
+    if !can_connect(&base_url) {
+        return;
+    }
+
+    let test_db = create_test_db(&base_url, "pgcrate_anon_apply").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    run_psql(
+        r#"
+        CREATE TABLE users (id INT PRIMARY KEY, email TEXT NOT NULL, name TEXT);
+        INSERT INTO users SELECT i, 'user' || i || '@secret.com', 'Real Name ' || i
+            FROM generate_series(1, 25) i;
+        CREATE TABLE memberships (org TEXT, user_id INT, email TEXT, PRIMARY KEY (org, user_id));
+        INSERT INTO memberships SELECT 'org' || (i % 3), i, 'user' || i || '@secret.com'
+            FROM generate_series(1, 25) i;
+        CREATE TABLE audit_log (id SERIAL PRIMARY KEY, detail TEXT);
+        INSERT INTO audit_log (detail) VALUES ('user1 logged in');
+        "#,
+        &test_db,
+    );
+    run_pgcrate(&["anonymize", "setup"], &test_db, temp_dir.path());
+    let toml_content = r#"
+        seed = "apply-seed"
+        [[rules]]
+        table = "public.users"
+        columns = { email = "fake_email", name = "redact" }
+        [[rules]]
+        table = "public.memberships"
+        columns = { email = "fake_email" }
+        [[rules]]
+        table = "public.audit_log"
+        skip = true
+    "#;
+    fs::write(temp_dir.path().join("pgcrate.anonymize.toml"), toml_content).unwrap();
+
+    let output = run_pgcrate(&["anonymize", "apply"], &test_db, temp_dir.path());
+    assert!(!output.status.success(), "--yes should be required");
+
+    let output = run_pgcrate(
+        &["anonymize", "apply", "--dry-run"],
+        &test_db,
+        temp_dir.path(),
+    );
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("Would update 25 rows in public.users")
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT count(*) FROM users WHERE email LIKE '%@secret.com'",
+            &test_db
+        ),
+        "25"
+    );
+
+    let expected = run_psql_query(
+        "SELECT string_agg(pgcrate.anon_fake_email(email, 'apply-seed'), ',' ORDER BY id) FROM users",
+        &test_db,
+    );
+    let output = run_pgcrate(
+        &["anonymize", "apply", "--yes", "--batch-size", "10"],
+        &test_db,
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Every row was transformed exactly once, in both tables
+    assert_eq!(
+        run_psql_query(
+            "SELECT string_agg(email, ',' ORDER BY id) FROM users",
+            &test_db
+        ),
+        expected
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT string_agg(m.email, ',' ORDER BY m.user_id) FROM memberships m",
+            &test_db
+        ),
+        expected
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT count(*) FROM users WHERE name LIKE 'Real%'",
+            &test_db
+        ),
+        "0"
+    );
+    assert_eq!(
+        run_psql_query("SELECT count(*) FROM audit_log", &test_db),
+        "0"
+    );
+
+    // Never against URLs that look like production
+    let prod_url = format!("{}?application_name=prod", test_db);
+    let output = run_pgcrate(&["anonymize", "apply", "--yes"], &prod_url, temp_dir.path());
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("production patterns"));
+
+    drop_test_db(&base_url, "pgcrate_anon_apply");
+}
+
+#[test]
+fn test_anonymize_jsonb_paths() {
+    let base_url = get_test_db_url();
+    if !can_connect(&base_url) {
+        return;
+    }
+
+    let test_db = create_test_db(&base_url, "pgcrate_anon_jsonb").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    run_psql(
+        r#"
+        CREATE TABLE users (id INT PRIMARY KEY, profile JSONB);
+        INSERT INTO users VALUES
+            (1, '{"email": "jane@secret.com", "born": "1980-05-17", "address": {"street": "1 Main St", "city": "Springfield"}}'),
+            (2, '{"email": "joe@secret.com", "theme": "dark"}'),
+            (3, NULL);
+        "#,
+        &test_db,
+    );
+    run_pgcrate(&["anonymize", "setup"], &test_db, temp_dir.path());
+    let toml_content = r#"
+        seed = "jsonb-seed"
+        [[rules]]
+        table = "public.users"
+        columns = { "profile->'email'" = "fake_email", "profile->'born'" = "shift_date", "profile->'address'->>'street'" = "redact" }
+    "#;
+    fs::write(temp_dir.path().join("pgcrate.anonymize.toml"), toml_content).unwrap();
+
+    let dump_file = temp_dir.path().join("dump.sql");
+    let output = run_pgcrate(
+        &["anonymize", "dump", "--output", dump_file.to_str().unwrap()],
+        &test_db,
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let dump = fs::read_to_string(&dump_file).unwrap();
+    assert!(!dump.contains("secret.com"));
+    assert!(!dump.contains("Main St"));
+    assert!(dump.contains("Springfield"));
+
+    let expected = run_psql_query(
+        "SELECT pgcrate.anon_fake_email('jane@secret.com', 'jsonb-seed')",
+        &test_db,
+    );
+    let output = run_pgcrate(&["anonymize", "apply", "--yes"], &test_db, temp_dir.path());
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(
+        run_psql_query("SELECT profile->>'email' FROM users WHERE id = 1", &test_db),
+        expected
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT profile->'address'->>'city' = 'Springfield'
+                    AND profile->'address'->>'street' <> '1 Main St'
+                    AND (profile->>'born')::date <> '1980-05-17'
+                    AND abs((profile->>'born')::date - '1980-05-17'::date) <= 30
+             FROM users WHERE id = 1",
+            &test_db
+        ),
+        "t"
+    );
+    // Documents without a path are left as they were
+    assert_eq!(
+        run_psql_query(
+            "SELECT NOT profile ? 'address' AND NOT profile ? 'born' AND profile->>'theme' = 'dark'
+                    AND profile->>'email' NOT LIKE '%secret.com'
+             FROM users WHERE id = 2",
+            &test_db
+        ),
+        "t"
+    );
+    assert_eq!(
+        run_psql_query("SELECT profile IS NULL FROM users WHERE id = 3", &test_db),
+        "t"
+    );
+
+    drop_test_db(&base_url, "pgcrate_anon_jsonb");
+}
+
+#[test]
+fn test_anonymize_dump_sample_and_where() {
+    let base_url = get_test_db_url();
+    if !can_connect(&base_url) {
+        return;
+    }
+
+    let test_db = create_test_db(&base_url, "pgcrate_anon_sample").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    run_psql(
+        r#"
+        CREATE TABLE users (id INT PRIMARY KEY, email TEXT);
+        CREATE TABLE orders (id INT PRIMARY KEY, user_id INT REFERENCES users(id), created_at DATE);
+        CREATE TABLE order_notes (id SERIAL PRIMARY KEY, order_id INT NOT NULL REFERENCES orders(id));
+        INSERT INTO users SELECT i, 'user' || i || '@secret.com' FROM generate_series(1, 200) i;
+        INSERT INTO orders SELECT i, CASE WHEN i % 10 = 0 THEN NULL ELSE (i % 200) + 1 END,
+            DATE '2024-01-01' + i FROM generate_series(1, 600) i;
+        INSERT INTO order_notes (order_id) SELECT id FROM orders;
+        "#,
+        &test_db,
+    );
+    run_pgcrate(&["anonymize", "setup"], &test_db, temp_dir.path());
+    let toml_content = r#"
+        seed = "sample-seed"
+        [[rules]]
+        table = "public.users"
+        columns = { email = "fake_email" }
+        sample = 0.25
+        [[rules]]
+        table = "public.orders"
+        where = "created_at >= DATE '2024-06-01'"
+    "#;
+    fs::write(temp_dir.path().join("pgcrate.anonymize.toml"), toml_content).unwrap();
+
+    let output = run_pgcrate(
+        &["anonymize", "dump", "--dry-run"],
+        &test_db,
+        temp_dir.path(),
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("rows: sample 25%"), "{}", stdout);
+    assert!(stdout.contains("rows: where created_at >= DATE '2024-06-01'"));
+
+    let dump_file = temp_dir.path().join("dump.sql");
+    let output = run_pgcrate(
+        &["anonymize", "dump", "--output", dump_file.to_str().unwrap()],
+        &test_db,
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The sample is the same on every run
+    let again = temp_dir.path().join("again.sql");
+    run_pgcrate(
+        &["anonymize", "dump", "--output", again.to_str().unwrap()],
+        &test_db,
+        temp_dir.path(),
+    );
+    let strip = |p: &Path| -> String {
+        fs::read_to_string(p)
+            .unwrap()
+            .lines()
+            .filter(|l| !l.starts_with("-- Generated"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    assert_eq!(strip(&dump_file), strip(&again));
+
+    // The dump loads with every FK in place
+    run_psql("TRUNCATE users, orders, order_notes", &test_db);
+    let load = Command::new("psql")
+        .args([&test_db, "-v", "ON_ERROR_STOP=1", "-q", "-f"])
+        .arg(&dump_file)
+        .output()
+        .unwrap();
+    assert!(
+        load.status.success(),
+        "{}",
+        String::from_utf8_lossy(&load.stderr)
+    );
+
+    let users: i64 = run_psql_query("SELECT count(*) FROM users", &test_db)
+        .parse()
+        .unwrap();
+    assert!((20..=80).contains(&users), "sampled {} of 200 users", users);
+    assert_eq!(
+        run_psql_query(
+            "SELECT count(*) FROM orders WHERE created_at < DATE '2024-06-01'",
+            &test_db
+        ),
+        "0"
+    );
+    // Orders without a user are kept; orders of unsampled users are not
+    assert_eq!(
+        run_psql_query(
+            "SELECT count(*) FROM orders WHERE user_id IS NULL",
+            &test_db
+        ),
+        "45"
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT (SELECT count(*) FROM order_notes) = (SELECT count(*) FROM orders)",
+            &test_db
+        ),
+        "t"
+    );
+
+    drop_test_db(&base_url, "pgcrate_anon_sample");
+}
+
+/// Source tables with data, plus a project whose migrations create them
+fn setup_bootstrap_project(source: &str, workdir: &Path) {
+    let schema = r#"
+        CREATE TABLE users (id INT PRIMARY KEY, email TEXT NOT NULL);
+        CREATE TABLE posts (id INT PRIMARY KEY, user_id INT NOT NULL REFERENCES users(id));
+    "#;
+    run_psql(schema, source);
+    run_psql(
+        r#"
+        INSERT INTO users SELECT i, 'user' || i || '@secret.com' FROM generate_series(1, 50) i;
+        INSERT INTO posts SELECT i, (i % 50) + 1 FROM generate_series(1, 120) i;
+        "#,
+        source,
+    );
+    run_pgcrate(&["anonymize", "setup"], source, workdir);
+
+    fs::create_dir_all(workdir.join("db/migrations")).unwrap();
+    fs::write(
+        workdir.join("db/migrations/20240101000000_create_tables.sql"),
+        format!(
+            "-- up\n{}\n-- down\nDROP TABLE posts;\nDROP TABLE users;\n",
+            schema
+        ),
+    )
+    .unwrap();
+    fs::write(
+        workdir.join("pgcrate.toml"),
+        "[paths]\nmigrations = \"db/migrations\"\n",
+    )
+    .unwrap();
+    fs::write(
+        workdir.join("pgcrate.anonymize.toml"),
+        "seed = \"boot-seed\"\n[[rules]]\ntable = \"public.users\"\ncolumns = { email = \"fake_email\" }\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_bootstrap_resume() {
+    let base_url = get_test_db_url();
+    if !can_connect(&base_url) {
+        return;
+    }
+
+    let source = create_test_db(&base_url, "pgcrate_boot_source").unwrap();
+    drop_test_db(&base_url, "pgcrate_boot_target");
+    let target = source.replace("pgcrate_boot_source", "pgcrate_boot_target");
+    let temp_dir = TempDir::new().unwrap();
+
+    setup_bootstrap_project(&source, temp_dir.path());
+
+    let output = run_pgcrate(
+        &["bootstrap", "--from", &source, "--yes"],
+        &target,
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("[2/2] public.posts: 120 rows"),
+        "{}",
+        stderr
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT count(*) FROM users WHERE email LIKE '%@secret.com'",
+            &target
+        ),
+        "0"
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT to_regclass('pgcrate.bootstrap_progress') IS NULL",
+            &target
+        ),
+        "t"
+    );
+
+    // As if the connection dropped after users was transferred
+    run_psql(
+        r#"
+        TRUNCATE posts;
+        CREATE TABLE pgcrate.bootstrap_progress (
+            table_name TEXT PRIMARY KEY, row_count BIGINT NOT NULL, bytes BIGINT NOT NULL,
+            transferred_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        );
+        INSERT INTO pgcrate.bootstrap_progress VALUES ('public.users', 50, 0, now());
+        "#,
+        &target,
+    );
+    let output = run_pgcrate(
+        &["bootstrap", "--from", &source, "--yes"],
+        &target,
+        temp_dir.path(),
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--resume"));
+
+    let output = run_pgcrate(
+        &["bootstrap", "--from", &source, "--yes", "--resume"],
+        &target,
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("[1/2] public.users: already transferred"),
+        "{}",
+        stderr
+    );
+    assert_eq!(run_psql_query("SELECT count(*) FROM users", &target), "50");
+    assert_eq!(run_psql_query("SELECT count(*) FROM posts", &target), "120");
+
+    drop_test_db(&base_url, "pgcrate_boot_source");
+    drop_test_db(&base_url, "pgcrate_boot_target");
+}
+
+#[test]
+fn test_bootstrap_schema_only_and_tables() {
+    let base_url = get_test_db_url();
+    if !can_connect(&base_url) {
+        return;
+    }
+
+    let source = create_test_db(&base_url, "pgcrate_boot_modes").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    setup_bootstrap_project(&source, temp_dir.path());
+
+    drop_test_db(&base_url, "pgcrate_boot_schema");
+    let target = source.replace("pgcrate_boot_modes", "pgcrate_boot_schema");
+    let output = run_pgcrate(
+        &["bootstrap", "--from", &source, "--yes", "--schema-only"],
+        &target,
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT (SELECT count(*) FROM users) + (SELECT count(*) FROM posts)",
+            &target
+        ),
+        "0"
+    );
+
+    drop_test_db(&base_url, "pgcrate_boot_tables");
+    let target = source.replace("pgcrate_boot_modes", "pgcrate_boot_tables");
+    let output = run_pgcrate(
+        &[
+            "bootstrap",
+            "--from",
+            &source,
+            "--yes",
+            "--tables",
+            "public.missing",
+        ],
+        &target,
+        temp_dir.path(),
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("public.missing"));
+
+    let output = run_pgcrate(
+        &["bootstrap", "--from", &source, "--yes", "--tables", "users"],
+        &target,
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(run_psql_query("SELECT count(*) FROM users", &target), "50");
+    assert_eq!(run_psql_query("SELECT count(*) FROM posts", &target), "0");
+
+    drop_test_db(&base_url, "pgcrate_boot_modes");
+    drop_test_db(&base_url, "pgcrate_boot_schema");
+    drop_test_db(&base_url, "pgcrate_boot_tables");
+}
@@ -133,6 +133,96 @@ fn test_migrate_up_invalid_sql() {
     );
 }
 
+#[test]
+fn test_migrate_up_single_transaction_rolls_back_batch() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    std::fs::write(
+        project.path("db/migrations/20240103000000_broken.sql"),
+        "-- up\nCREATE TABL broken_syntax;\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate(&["migrate", "up", "--single-transaction"]);
+    assert!(!output.status.success(), "Should fail on invalid SQL");
+    assert!(
+        stderr(&output).contains("rolled back all 3 migration(s)"),
+        "stderr: {}",
+        stderr(&output)
+    );
+
+    // Earlier migrations in the batch must not be left behind
+    let tables = db.query(
+        "SELECT count(*) FROM pg_tables WHERE schemaname = 'public' AND tablename IN ('users', 'posts')",
+    );
+    assert_eq!(tables, "0");
+    let applied = db.query("SELECT count(*) FROM pgcrate.schema_migrations WHERE success");
+    assert_eq!(applied, "0");
+}
+
+#[test]
+fn test_migrate_up_snapshot_before() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    // --snapshot saves a snapshot named after the first pending migration
+    let output = project.run_pgcrate_ok(&["migrate", "up", "--snapshot"]);
+    assert!(
+        stdout(&output).contains("Snapshot saved: pre-migrate-20240101000000"),
+        "stdout: {}",
+        stdout(&output)
+    );
+    assert!(project.file_exists(".pgcrate/snapshots/pre-migrate-20240101000000/metadata.json"));
+
+    // [migrate] snapshot_before does the same; a failure prints the way back
+    let mut config = project.read_file("pgcrate.toml");
+    config.push_str("\n[migrate]\nsnapshot_before = true\n");
+    std::fs::write(project.path("pgcrate.toml"), config).unwrap();
+    std::fs::write(
+        project.path("db/migrations/20240103000000_broken.sql"),
+        "-- up\nCREATE TABL broken_syntax;\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate(&["migrate", "up"]);
+    assert!(!output.status.success(), "Should fail on invalid SQL");
+    assert!(
+        stderr(&output).contains("pgcrate snapshot restore pre-migrate-20240103000000 --yes"),
+        "stderr: {}",
+        stderr(&output)
+    );
+    assert!(project.file_exists(".pgcrate/snapshots/pre-migrate-20240103000000/metadata.json"));
+}
+
+#[test]
+fn test_migrate_up_single_transaction_rejects_concurrent_index() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    std::fs::write(
+        project.path("db/migrations/20240103000000_add_index.sql"),
+        "-- up\nCREATE INDEX CONCURRENTLY users_email_idx ON users (email);\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate(&["migrate", "up", "--single-transaction"]);
+    assert!(!output.status.success());
+    let err = stderr(&output);
+    assert!(
+        err.contains("20240103000000_add_index: CREATE INDEX CONCURRENTLY"),
+        "stderr: {}",
+        err
+    );
+
+    // Nothing should have been applied
+    let tables = db.query("SELECT count(*) FROM pg_tables WHERE tablename = 'users'");
+    assert_eq!(tables, "0");
+}
+
 // ============================================================================
 // migrate down
 // ============================================================================
@@ -227,6 +317,169 @@ fn test_migrate_status_json_output() {
     );
 }
 
+#[test]
+fn test_migrate_status_json_includes_execution_metadata() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    let output = project.run_pgcrate_ok(&["migrate", "status", "--json"]);
+    let json = parse_json(&output);
+
+    let first = &json["applied"][0]["execution"];
+    assert_eq!(first["success"], true);
+    assert!(first["duration_ms"].is_i64(), "duration_ms: {}", first);
+    assert!(first["applied_by"].is_string(), "applied_by: {}", first);
+    assert_eq!(first["pgcrate_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(json["counts"]["failed"], 0);
+}
+
+#[test]
+fn test_migrate_upgrade_leaves_applied_by_unknown_for_old_rows() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    // Tracking table from before execution metadata was recorded
+    db.run_sql_ok(
+        "CREATE SCHEMA pgcrate;
+         CREATE TABLE pgcrate.schema_migrations (
+             version TEXT PRIMARY KEY,
+             applied_at TIMESTAMPTZ DEFAULT now()
+         );
+         CREATE TABLE users (id SERIAL PRIMARY KEY, email TEXT NOT NULL UNIQUE, name TEXT, created_at TIMESTAMPTZ DEFAULT now());
+         INSERT INTO pgcrate.schema_migrations (version) VALUES ('20240101000000');",
+    );
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    assert_eq!(
+        db.query(
+            "SELECT string_agg(version || ':' || (applied_by IS NOT NULL), ',' ORDER BY version)
+             FROM pgcrate.schema_migrations"
+        ),
+        "20240101000000:false,20240101000001:true"
+    );
+}
+
+#[test]
+fn test_migrate_status_json_reports_failed_migration() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    std::fs::write(
+        project.path("db/migrations/20240103000000_broken.sql"),
+        "-- up\nCREATE TABL broken_syntax;\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate(&["migrate", "up"]);
+    assert!(!output.status.success(), "Should fail on invalid SQL");
+
+    let output = project.run_pgcrate_ok(&["migrate", "status", "--json"]);
+    let json = parse_json(&output);
+
+    assert_eq!(json["counts"]["failed"], 1);
+    let pending = json["pending"].as_array().unwrap();
+    let broken = pending
+        .iter()
+        .find(|m| m["name"] == "broken")
+        .expect("broken migration should be pending");
+    assert_eq!(broken["execution"]["success"], false);
+    assert!(broken["execution"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("syntax"));
+
+    // Fixing the file and re-running records success
+    std::fs::write(
+        project.path("db/migrations/20240103000000_broken.sql"),
+        "-- up\nCREATE TABLE fixed (id int);\n",
+    )
+    .unwrap();
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    let output = project.run_pgcrate_ok(&["migrate", "status", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(json["counts"]["failed"], 0);
+    assert_eq!(json["counts"]["pending"], 0);
+}
+
+// ============================================================================
+// migrate conflicts
+// ============================================================================
+
+fn git(project: &TestProject, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(project.dir.path())
+        .env("HOME", project.dir.path())
+        .output()
+        .expect("Failed to run git");
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_migrate_conflicts_detects_interleaved_and_same_table() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    git(&project, &["init", "-q", "-b", "main"]);
+    git(&project, &["add", "-A"]);
+    git(&project, &["commit", "-q", "-m", "initial"]);
+
+    // Feature branch adds an older-timestamped migration touching users
+    git(&project, &["checkout", "-q", "-b", "feature"]);
+    std::fs::write(
+        project.path("db/migrations/20240102000000_add_phone.sql"),
+        "-- up\nALTER TABLE users ADD COLUMN phone text;\n",
+    )
+    .unwrap();
+    git(&project, &["add", "-A"]);
+    git(&project, &["commit", "-q", "-m", "feature"]);
+
+    // Meanwhile main gains a newer migration on the same table
+    git(&project, &["checkout", "-q", "main"]);
+    std::fs::write(
+        project.path("db/migrations/20240103000000_add_nickname.sql"),
+        "-- up\nALTER TABLE public.users ADD COLUMN nickname text;\n",
+    )
+    .unwrap();
+    git(&project, &["add", "-A"]);
+    git(&project, &["commit", "-q", "-m", "main"]);
+    git(&project, &["checkout", "-q", "feature"]);
+
+    let output = project.run_pgcrate(&["migrate", "conflicts", "--base", "main", "--json"]);
+    assert_eq!(output.status.code(), Some(0), "stderr: {}", stderr(&output));
+    let json = parse_json(&output);
+
+    assert_eq!(json["branch_migrations"][0], "20240102000000_add_phone");
+    assert_eq!(json["base_migrations"][0], "20240103000000_add_nickname");
+    let kinds: Vec<&str> = json["conflicts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["kind"].as_str().unwrap())
+        .collect();
+    assert!(kinds.contains(&"out_of_order"), "conflicts: {}", json);
+    assert!(kinds.contains(&"same_table"), "conflicts: {}", json);
+
+    // Human mode exits 1 on warnings
+    let output = project.run_pgcrate(&["migrate", "conflicts", "--base", "main"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("same table public.users"));
+}
+
 // ============================================================================
 // migrate new
 // ============================================================================
@@ -167,6 +167,50 @@ fn test_model_status_json_output() {
     }
 }
 
+// ============================================================================
+// model docs
+// ============================================================================
+
+#[test]
+fn test_model_docs_html_site() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    let source = project.read_file("models/marts/user_stats.sql");
+    std::fs::write(
+        project.path("models/marts/user_stats.sql"),
+        format!("-- description: One row per user with post activity\n{source}"),
+    )
+    .unwrap();
+    project.run_pgcrate_ok(&["model", "run"]);
+
+    project.run_pgcrate_ok(&["model", "docs", "--format", "html"]);
+
+    let index = project.read_file("target/docs/index.html");
+    assert!(index.contains("<svg"), "{}", index);
+    assert!(
+        index.contains("href=\"marts/user_stats.html\""),
+        "{}",
+        index
+    );
+    assert!(
+        index.contains("One row per user with post activity"),
+        "{}",
+        index
+    );
+
+    // Columns come from the built relation in the database
+    let page = project.read_file("target/docs/marts/user_stats.html");
+    assert!(
+        page.contains("<td>post_count</td><td>bigint</td>"),
+        "{}",
+        page
+    );
+    assert!(page.contains("public.users"), "{}", page);
+}
+
 // ============================================================================
 // model graph
 // ============================================================================
@@ -194,6 +238,1260 @@ fn test_model_graph_shows_dependencies() {
     );
 }
 
+#[test]
+fn test_model_graph_columns_lineage() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    std::fs::write(
+        project.path("models/marts/user_emails.sql"),
+        "-- materialized: view\n-- deps: marts.user_stats\n\n\
+         SELECT s.user_id, upper(s.email) AS email_upper FROM marts.user_stats s\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["model", "graph", "--columns", "--format", "json"]);
+    let json = parse_json(&output);
+    let edges: Vec<(String, String)> = json["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| {
+            (
+                e["from"].as_str().unwrap().to_string(),
+                e["to"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    for (from, to) in [
+        ("public.users.email", "marts.user_stats.email"),
+        ("marts.user_stats.email", "marts.user_emails.email_upper"),
+        ("marts.user_stats.user_id", "marts.user_emails.user_id"),
+    ] {
+        assert!(
+            edges.contains(&(from.to_string(), to.to_string())),
+            "missing {from} -> {to}: {edges:?}"
+        );
+    }
+
+    let output = project.run_pgcrate_ok(&["model", "graph", "--columns", "--format", "dot"]);
+    assert!(
+        stdout(&output)
+            .contains("\"marts.user_stats.email\" -> \"marts.user_emails.email_upper\";"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_model_graph_includes_exposures() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    std::fs::write(
+        project.path("models/exposures.toml"),
+        "[exposures.weekly_kpis]\ntype = \"dashboard\"\nowner = \"analytics\"\n\
+         depends_on = [\"marts.user_stats\"]\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["model", "graph", "-s", "downstream:marts.user_stats"]);
+    assert!(
+        stdout(&output).contains("weekly_kpis (dashboard) <- [marts.user_stats]"),
+        "{}",
+        stdout(&output)
+    );
+
+    let output = project.run_pgcrate_ok(&["model", "graph", "--format", "json"]);
+    let json = parse_json(&output);
+    assert_eq!(json["exposures"][0]["name"], "weekly_kpis");
+    assert_eq!(json["exposures"][0]["depends_on"][0], "marts.user_stats");
+
+    // Exposures must point at known models
+    std::fs::write(
+        project.path("models/exposures.toml"),
+        "[exposures.weekly_kpis]\ntype = \"dashboard\"\ndepends_on = [\"marts.nope\"]\n",
+    )
+    .unwrap();
+    let output = project.run_pgcrate(&["model", "graph"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("depends on unknown model marts.nope"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+// ============================================================================
+// model variables
+// ============================================================================
+
+#[test]
+fn test_model_compile_resolves_vars_with_cli_override() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    let config = project.read_file("pgcrate.toml");
+    std::fs::write(
+        project.path("pgcrate.toml"),
+        format!("{config}\n[model.vars]\nmin_posts = 1\nenv = \"dev\"\n"),
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("models/marts/active_users.sql"),
+        "-- materialized: view\n-- deps: marts.user_stats\n\n\
+         SELECT user_id, '${var:env}' AS env FROM marts.user_stats \
+         WHERE post_count >= ${var:min_posts}\n",
+    )
+    .unwrap();
+
+    project.run_pgcrate_ok(&["model", "compile", "--var", "min_posts=5"]);
+
+    let compiled = project.read_file("target/compiled/marts/active_users.sql");
+    assert!(compiled.contains("'dev' AS env"), "{}", compiled);
+    assert!(compiled.contains("post_count >= 5"), "{}", compiled);
+    assert!(!compiled.contains("${var:"), "{}", compiled);
+}
+
+#[test]
+fn test_model_compile_undefined_var_fails() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    std::fs::write(
+        project.path("models/marts/sampled.sql"),
+        "-- materialized: view\n\nSELECT * FROM public.users WHERE random() < ${var:sample}\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate(&["model", "compile"]);
+    assert!(!output.status.success());
+    let err = stderr(&output);
+    assert!(
+        err.contains("undefined model variable(s): sample"),
+        "stderr: {}",
+        err
+    );
+}
+
+#[test]
+fn test_model_run_expands_macros() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    std::fs::create_dir_all(project.path("models/macros")).unwrap();
+    std::fs::write(
+        project.path("models/macros/cents_to_dollars.sql"),
+        "-- args: amount\n(${amount} / 100.0)::numeric(12, 2)\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("models/marts/prices.sql"),
+        "-- materialized: view\n\nSELECT ${macro:cents_to_dollars(1999)} AS price\n",
+    )
+    .unwrap();
+
+    project.run_pgcrate_ok(&["model", "run", "-s", "marts.prices"]);
+
+    assert_eq!(db.query("SELECT price FROM marts.prices"), "19.99");
+    // The macros directory does not define a model
+    assert_eq!(
+        db.query("SELECT count(*) FROM information_schema.schemata WHERE schema_name = 'macros'"),
+        "0"
+    );
+}
+
+// ============================================================================
+// ephemeral models
+// ============================================================================
+
+#[test]
+fn test_model_run_inlines_ephemeral_models() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok(
+        "INSERT INTO users (email, name) VALUES ('a@test.com', 'A'), ('b@test.com', 'B')",
+    );
+
+    std::fs::create_dir_all(project.path("models/staging")).unwrap();
+    std::fs::write(
+        project.path("models/staging/named_users.sql"),
+        "-- materialized: ephemeral\n-- deps: public.users\n\n\
+         SELECT id, name FROM public.users WHERE name IS NOT NULL\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("models/marts/user_names.sql"),
+        "-- materialized: table\n-- deps: staging.named_users\n\n\
+         SELECT count(*) AS n FROM staging.named_users\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "deps:marts.user_names"]);
+    assert!(stdout(&output).contains("ephemeral"), "{}", stdout(&output));
+
+    assert_eq!(db.query("SELECT n FROM marts.user_names"), "2");
+    // Nothing is created for the ephemeral model itself
+    assert_eq!(
+        db.query("SELECT to_regclass('staging.named_users') IS NULL"),
+        "t"
+    );
+}
+
+// ============================================================================
+// materialized views
+// ============================================================================
+
+#[test]
+fn test_model_run_materialized_view_creates_then_refreshes() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("INSERT INTO users (email, name) VALUES ('a@test.com', 'A')");
+
+    let model_path = project.path("models/marts/user_counts.sql");
+    std::fs::write(
+        &model_path,
+        "-- materialized: materialized_view\n-- deps: public.users\n-- unique_index: n\n\n\
+         SELECT count(*) AS n FROM public.users\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_counts"]);
+    assert!(stdout(&output).contains("created marts.user_counts"));
+    assert_eq!(db.query("SELECT n FROM marts.user_counts"), "1");
+
+    // Unchanged definition: refresh picks up new data without recreating
+    db.run_sql_ok("INSERT INTO users (email, name) VALUES ('b@test.com', 'B')");
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_counts"]);
+    assert!(
+        stdout(&output).contains("refreshed marts.user_counts concurrently"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(db.query("SELECT n FROM marts.user_counts"), "2");
+
+    // Changed definition: recreated
+    std::fs::write(
+        &model_path,
+        "-- materialized: materialized_view\n-- deps: public.users\n\n\
+         SELECT count(*) * 10 AS n FROM public.users\n",
+    )
+    .unwrap();
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_counts"]);
+    assert!(stdout(&output).contains("created marts.user_counts"));
+    assert_eq!(db.query("SELECT n FROM marts.user_counts"), "20");
+
+    let output = project.run_pgcrate_ok(&["model", "status", "-s", "marts.user_counts", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(json[0]["status"], "synced");
+    assert_eq!(json[0]["actual_type"], "MATERIALIZED VIEW");
+}
+
+// ============================================================================
+// incremental strategies
+// ============================================================================
+
+#[test]
+fn test_model_run_incremental_strategies() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok(
+        "CREATE TABLE public.events (day date, kind text, n int);
+         INSERT INTO public.events VALUES ('2024-01-01', 'a', 1), ('2024-01-02', 'a', 2)",
+    );
+
+    let write = |name: &str, header: &str, select: &str| {
+        std::fs::write(
+            project.path(&format!("models/marts/{}.sql", name)),
+            format!(
+                "-- materialized: incremental\n-- deps: public.events\n{}\n\n{}\n",
+                header, select
+            ),
+        )
+        .unwrap();
+    };
+    write(
+        "events_log",
+        "-- strategy: append",
+        "SELECT day, kind, n FROM public.events WHERE day = '2024-01-02'",
+    );
+    write(
+        "events_by_kind",
+        "-- strategy: delete+insert\n-- unique_key: kind",
+        "SELECT kind, sum(n)::int AS total FROM public.events GROUP BY kind",
+    );
+    write(
+        "events_by_day",
+        "-- strategy: insert_overwrite\n-- partition_by: day",
+        "SELECT day, kind, n FROM public.events WHERE day >= '2024-01-02'",
+    );
+
+    let select = [
+        "-s",
+        "marts.events_log",
+        "-s",
+        "marts.events_by_kind",
+        "-s",
+        "marts.events_by_day",
+    ];
+    let mut args = vec!["model", "run"];
+    args.extend(select);
+    project.run_pgcrate_ok(&args);
+
+    db.run_sql_ok("INSERT INTO public.events VALUES ('2024-01-02', 'b', 5)");
+    let output = project.run_pgcrate_ok(&args);
+    let out = stdout(&output);
+    assert!(out.contains("appended to marts.events_log"), "{}", out);
+    assert!(
+        out.contains("replaced keys in marts.events_by_kind"),
+        "{}",
+        out
+    );
+    assert!(
+        out.contains("overwrote partitions in marts.events_by_day"),
+        "{}",
+        out
+    );
+
+    // append: every run inserts again (1 + 2 rows)
+    assert_eq!(db.query("SELECT count(*) FROM marts.events_log"), "3");
+    // delete+insert: one row per key, with current totals
+    assert_eq!(
+        db.query(
+            "SELECT string_agg(kind || '=' || total, ',' ORDER BY kind) FROM marts.events_by_kind"
+        ),
+        "a=3,b=5"
+    );
+    // insert_overwrite: the 2024-01-02 partition is replaced, not duplicated
+    assert_eq!(db.query("SELECT count(*) FROM marts.events_by_day"), "2");
+}
+
+// ============================================================================
+// column contracts
+// ============================================================================
+
+#[test]
+fn test_model_run_enforce_contracts() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok(
+        "CREATE TABLE public.payments (id int, amount numeric);
+         INSERT INTO public.payments VALUES (1, 9.5)",
+    );
+    let config = project.read_file("pgcrate.toml");
+    std::fs::write(
+        project.path("pgcrate.toml"),
+        format!("{}\n[model]\nsources = [\"public.payments\"]\n", config),
+    )
+    .unwrap();
+    let model_path = project.path("models/marts/payment_facts.sql");
+    let write = |select: &str| {
+        std::fs::write(
+            &model_path,
+            format!(
+                "-- materialized: table\n-- deps: public.payments\n\
+                 -- columns: id integer, amount numeric(10,2)\n\n{}\n",
+                select
+            ),
+        )
+        .unwrap();
+    };
+    let run = [
+        "model",
+        "run",
+        "--enforce-contracts",
+        "-s",
+        "marts.payment_facts",
+    ];
+    let check = ["model", "check", "-s", "marts.payment_facts"];
+
+    write("SELECT id, amount FROM public.payments");
+    project.run_pgcrate_ok(&check);
+    project.run_pgcrate_ok(&run);
+    // The table uses the declared types, including modifiers
+    assert_eq!(
+        db.query(
+            "SELECT format_type(atttypid, atttypmod) FROM pg_attribute
+             WHERE attrelid = 'marts.payment_facts'::regclass AND attname = 'amount'"
+        ),
+        "numeric(10,2)"
+    );
+
+    write("SELECT id::bigint AS id, amount, 'x' AS note FROM public.payments");
+    let output = project.run_pgcrate(&run);
+    assert!(!output.status.success());
+    let err = stderr(&output);
+    assert!(
+        err.contains("contract violated for marts.payment_facts"),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains("id: declared integer, SELECT returns bigint"),
+        "{}",
+        err
+    );
+    assert!(err.contains("undeclared column note (text)"), "{}", err);
+
+    let output = project.run_pgcrate_fails(&check, 1);
+    assert!(
+        stdout(&output).contains("contract: id: declared integer"),
+        "{}",
+        stdout(&output)
+    );
+
+    // Without the flag the contract is not enforced
+    project.run_pgcrate_ok(&["model", "run", "-s", "marts.payment_facts"]);
+}
+
+// ============================================================================
+// snapshots
+// ============================================================================
+
+#[test]
+fn test_model_snapshot_tracks_history() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok(
+        "CREATE TABLE public.customers (id int, tier text, updated_at timestamptz);
+         INSERT INTO public.customers VALUES
+           (1, 'free', '2024-01-01'), (2, 'free', '2024-01-01'), (3, 'pro', '2024-01-01')",
+    );
+    std::fs::write(
+        project.path("models/marts/customers_history.sql"),
+        "-- materialized: snapshot\n-- deps: public.customers\n-- unique_key: id\n\
+         -- updated_at: updated_at\n-- invalidate_hard_deletes: true\n\n\
+         SELECT id, tier, updated_at FROM public.customers\n",
+    )
+    .unwrap();
+
+    // model run leaves snapshots to `model snapshot`
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "marts.customers_history"]);
+    assert!(
+        stdout(&output).contains("Skipped marts.customers_history (snapshot"),
+        "{}",
+        stdout(&output)
+    );
+
+    let args = ["model", "snapshot", "marts.customers_history"];
+    let output = project.run_pgcrate_ok(&args);
+    let out = stdout(&output);
+    assert!(
+        out.contains("created snapshot marts.customers_history; inserted 3 rows"),
+        "{}",
+        out
+    );
+
+    db.run_sql_ok(
+        "UPDATE public.customers SET tier = 'pro', updated_at = '2024-02-01' WHERE id = 1;
+         DELETE FROM public.customers WHERE id = 2;
+         INSERT INTO public.customers VALUES (4, 'free', '2024-02-01')",
+    );
+    let output = project.run_pgcrate_ok(&args);
+    let out = stdout(&output);
+    assert!(out.contains("1 new, 1 changed, 1 deleted"), "{}", out);
+
+    // Unchanged source: nothing new
+    let output = project.run_pgcrate_ok(&args);
+    assert!(
+        stdout(&output).contains("0 new, 0 changed, 0 deleted"),
+        "{}",
+        stdout(&output)
+    );
+
+    assert_eq!(
+        db.query(
+            "SELECT string_agg(id || ':' || tier || ':' || is_current, ',' ORDER BY id, valid_from)
+             FROM marts.customers_history"
+        ),
+        "1:free:false,1:pro:true,2:free:false,3:pro:true,4:free:true"
+    );
+    // The closed version ends when the new one starts
+    assert_eq!(
+        db.query(
+            "SELECT count(*) FROM marts.customers_history old
+             JOIN marts.customers_history new ON new.id = old.id AND new.is_current
+             WHERE old.id = 1 AND NOT old.is_current AND old.valid_to = new.valid_from"
+        ),
+        "1"
+    );
+}
+
+// ============================================================================
+// model test
+// ============================================================================
+
+#[test]
+fn test_model_test_store_failures() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok(
+        "CREATE TABLE public.signups (email text, plan text);
+         INSERT INTO public.signups VALUES
+           ('a@x.io', 'free'), ('a@x.io', 'pro'), ('b@x.io', 'trial')",
+    );
+    std::fs::write(
+        project.path("models/marts/signups.sql"),
+        "-- materialized: table\n-- deps: public.signups\n\
+         -- tests: unique(email), accepted_values(plan, ['free', 'pro'])\n\n\
+         SELECT email, plan FROM public.signups\n",
+    )
+    .unwrap();
+    project.run_pgcrate_ok(&["model", "run", "-s", "marts.signups"]);
+
+    let args = ["model", "test", "-s", "marts.signups", "--store-failures"];
+    let output = project.run_pgcrate_fails(&args, 1);
+    let out = stdout(&output);
+    assert!(
+        out.contains("stored 1 failing row in pgcrate_test_failures.marts_signups__unique_email"),
+        "{}",
+        out
+    );
+    assert_eq!(
+        db.query(
+            "SELECT email || '=' || cnt FROM pgcrate_test_failures.marts_signups__unique_email"
+        ),
+        "a@x.io=2"
+    );
+    assert_eq!(
+        db.query("SELECT email FROM pgcrate_test_failures.marts_signups__accepted_values_plan"),
+        "b@x.io"
+    );
+
+    // Once the test passes, its stale audit table is dropped
+    db.run_sql_ok("UPDATE public.signups SET plan = 'free' WHERE plan = 'trial'");
+    project.run_pgcrate_ok(&["model", "run", "-s", "marts.signups"]);
+    project.run_pgcrate_fails(&args, 1);
+    assert_eq!(
+        db.query(
+            "SELECT count(*) FROM pg_tables WHERE schemaname = 'pgcrate_test_failures'
+             AND tablename = 'marts_signups__accepted_values_plan'"
+        ),
+        "0"
+    );
+}
+
+#[test]
+fn test_model_test_severity_and_thresholds() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok(
+        "CREATE TABLE public.visits (id int, page text);
+         INSERT INTO public.visits VALUES (1, NULL), (1, 'home'), (2, NULL)",
+    );
+    let write = |tests: &str| {
+        std::fs::write(
+            project.path("models/marts/visits.sql"),
+            format!(
+                "-- materialized: table\n-- deps: public.visits\n-- tests: {}\n\n\
+                 SELECT id, page FROM public.visits\n",
+                tests
+            ),
+        )
+        .unwrap();
+    };
+    write("unique(id) {severity: warn}, not_null(page) {error_if: >5}");
+    project.run_pgcrate_ok(&["model", "run", "-s", "marts.visits"]);
+
+    // Only warnings: exit 0
+    let args = ["model", "test", "-s", "marts.visits"];
+    let output = project.run_pgcrate_ok(&args);
+    let out = stdout(&output);
+    assert!(out.contains("WARN (1 failure)"), "{}", out);
+    assert!(out.contains("0 passed, 2 warned, 0 failed"), "{}", out);
+
+    let output = project.run_pgcrate_ok(&["--json", "model", "test", "-s", "marts.visits"]);
+    let json = parse_json(&output);
+    assert_eq!(json["ok"], true);
+    assert_eq!(json["warned"], 2);
+    assert_eq!(json["tests"][1]["status"], "warn");
+    assert_eq!(json["tests"][1]["failures"], 2);
+
+    // Past error_if: exit 1
+    write("unique(id) {severity: warn}, not_null(page) {error_if: >1}");
+    let output = project.run_pgcrate_fails(&["--json", "model", "test", "-s", "marts.visits"], 1);
+    let json = parse_json(&output);
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["failed"], 1);
+    assert_eq!(json["tests"][0]["severity"], "warn");
+    assert_eq!(json["tests"][1]["status"], "fail");
+}
+
+// ============================================================================
+// hooks
+// ============================================================================
+
+#[test]
+fn test_model_run_hooks() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    std::fs::write(
+        project.path("models/marts/user_count.sql"),
+        "-- materialized: table\n-- deps: public.users\n\
+         -- pre_hook: CREATE TABLE IF NOT EXISTS public.hook_log (msg text)\n\
+         -- post_hook: INSERT INTO public.hook_log VALUES ('built')\n\
+         -- post_hook: COMMENT ON TABLE ${this} IS 'hooked'\n\n\
+         SELECT count(*) AS n FROM public.users\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_count", "--dry-run"]);
+    let out = stdout(&output);
+    assert!(
+        out.contains("-- pre_hook\nCREATE TABLE IF NOT EXISTS public.hook_log"),
+        "{}",
+        out
+    );
+    assert!(
+        out.contains("-- post_hook\nCOMMENT ON TABLE marts.user_count IS 'hooked';"),
+        "{}",
+        out
+    );
+
+    project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_count"]);
+    assert_eq!(db.query("SELECT msg FROM public.hook_log"), "built");
+    assert_eq!(
+        db.query("SELECT obj_description('marts.user_count'::regclass)"),
+        "hooked"
+    );
+}
+
+#[test]
+fn test_model_run_applies_grants_idempotently() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+    let reader = format!("{}_reader", db.name);
+    let stale = format!("{}_stale", db.name);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok(&format!(
+        "DROP ROLE IF EXISTS {reader}; CREATE ROLE {reader}; \
+         DROP ROLE IF EXISTS {stale}; CREATE ROLE {stale};"
+    ));
+
+    // [model.grants] supplies the default; the header overrides SELECT
+    let config = std::fs::read_to_string(project.path("pgcrate.toml")).unwrap();
+    std::fs::write(
+        project.path("pgcrate.toml"),
+        format!("{}\n[model.grants]\nselect = [\"{}\"]\n", config, stale),
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("models/marts/user_count.sql"),
+        format!(
+            "-- materialized: materialized_view\n-- deps: public.users\n\
+             -- grants: select({})\n\nSELECT count(*) AS n FROM public.users\n",
+            reader
+        ),
+    )
+    .unwrap();
+    db.run_sql_ok("CREATE SCHEMA IF NOT EXISTS marts");
+
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_count"]);
+    assert!(
+        stdout(&output).contains("1 grant change"),
+        "{}",
+        stdout(&output)
+    );
+    let has_select = |role: &str| {
+        db.query(&format!(
+            "SELECT has_table_privilege('{}', 'marts.user_count', 'SELECT')",
+            role
+        ))
+    };
+    assert_eq!(has_select(&reader), "t");
+    assert_eq!(has_select(&stale), "f");
+
+    // Extra grants made outside pgcrate are revoked; matching ones are kept
+    db.run_sql_ok(&format!("GRANT SELECT ON marts.user_count TO {stale}"));
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_count"]);
+    assert!(
+        stdout(&output).contains("1 grant change"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(has_select(&reader), "t");
+    assert_eq!(has_select(&stale), "f");
+
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_count"]);
+    assert!(
+        !stdout(&output).contains("grant change"),
+        "{}",
+        stdout(&output)
+    );
+
+    db.run_sql_ok(&format!(
+        "DROP MATERIALIZED VIEW marts.user_count; DROP ROLE {reader}; DROP ROLE {stale};"
+    ));
+}
+
+// ============================================================================
+// incremental schema changes
+// ============================================================================
+
+#[test]
+fn test_model_run_incremental_on_schema_change() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("INSERT INTO users (email, name) VALUES ('a@test.com', 'A')");
+
+    let model_path = project.path("models/marts/user_emails.sql");
+    let write_model = |policy: &str, select: &str| {
+        std::fs::write(
+            &model_path,
+            format!(
+                "-- materialized: incremental\n-- deps: public.users\n-- unique_key: id\n\
+                 -- on_schema_change: {}\n\n{}\n",
+                policy, select
+            ),
+        )
+        .unwrap();
+    };
+
+    write_model("fail", "SELECT id, email FROM public.users");
+    project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_emails"]);
+
+    // fail: a new column aborts the run
+    write_model("fail", "SELECT id, email, name FROM public.users");
+    let output = project.run_pgcrate(&["model", "run", "-s", "marts.user_emails"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("added: name"),
+        "{}",
+        stderr(&output)
+    );
+
+    // append_new_columns: the column is added and populated on merge
+    write_model(
+        "append_new_columns",
+        "SELECT id, email, name FROM public.users",
+    );
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_emails"]);
+    assert!(
+        stdout(&output).contains("schema: added name"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(db.query("SELECT name FROM marts.user_emails"), "A");
+
+    // sync_all_columns: a column missing from the SELECT is dropped
+    write_model("sync_all_columns", "SELECT id, name FROM public.users");
+    let output = project.run_pgcrate_ok(&["model", "run", "-s", "marts.user_emails"]);
+    assert!(
+        stdout(&output).contains("dropped email"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(
+        db.query(
+            "SELECT string_agg(column_name, ',' ORDER BY ordinal_position) \
+             FROM information_schema.columns \
+             WHERE table_schema = 'marts' AND table_name = 'user_emails'"
+        ),
+        "id,name"
+    );
+}
+
+// ============================================================================
+// parallel execution
+// ============================================================================
+
+#[test]
+fn test_model_run_parallel_threads() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("INSERT INTO users (email, name) VALUES ('a@test.com', 'A')");
+
+    for name in ["a", "b", "c"] {
+        std::fs::write(
+            project.path(&format!("models/marts/count_{}.sql", name)),
+            "-- materialized: table\n-- deps: public.users\n\n\
+             SELECT count(*) AS n FROM public.users\n",
+        )
+        .unwrap();
+    }
+    std::fs::write(
+        project.path("models/marts/count_total.sql"),
+        "-- materialized: table\n-- deps: marts.count_a, marts.count_b, marts.count_c\n\n\
+         SELECT (SELECT n FROM marts.count_a) + (SELECT n FROM marts.count_b) \
+         + (SELECT n FROM marts.count_c) AS n\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["model", "run", "--threads", "3"]);
+    let out = stdout(&output);
+    assert!(out.contains("5 model(s) executed"), "{}", out);
+    assert!(out.contains("s]"), "per-model timing: {}", out);
+    assert_eq!(db.query("SELECT n FROM marts.count_total"), "3");
+}
+
+#[test]
+fn test_model_run_keep_going_skips_downstream() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    std::fs::write(
+        project.path("models/marts/broken.sql"),
+        "-- materialized: table\n-- deps: public.users\n\n\
+         SELECT missing_column FROM public.users\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("models/marts/after_broken.sql"),
+        "-- materialized: table\n-- deps: marts.broken\n\n\
+         SELECT * FROM marts.broken\n",
+    )
+    .unwrap();
+
+    // Fail fast (default): the error surfaces and the run stops
+    let output = project.run_pgcrate(&["model", "run"]);
+    assert!(!output.status.success());
+
+    // --keep-going: independent models still run, dependents are skipped
+    let output = project.run_pgcrate(&["model", "run", "--keep-going", "--threads", "2"]);
+    assert!(!output.status.success());
+    let out = stdout(&output);
+    assert!(
+        out.contains("Skipped marts.after_broken (upstream model failed)"),
+        "{}",
+        out
+    );
+    assert!(
+        stderr(&output).contains("1 model(s) failed, 1 skipped"),
+        "{}",
+        stderr(&output)
+    );
+    assert_eq!(
+        db.query("SELECT to_regclass('marts.user_stats') IS NOT NULL"),
+        "t"
+    );
+}
+
+#[test]
+fn test_model_run_skips_unchanged_views() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    std::fs::write(
+        project.path("models/marts/user_emails.sql"),
+        "-- materialized: view\n-- deps: public.users\n\nSELECT id, email FROM public.users\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("models/marts/active_users.sql"),
+        "-- materialized: view\n-- deps: marts.user_stats\n\n\
+         SELECT user_id FROM marts.user_stats WHERE post_count > 0\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["model", "run"]);
+    assert!(
+        !stdout(&output).contains("(unchanged)"),
+        "{}",
+        stdout(&output)
+    );
+
+    // Second run: the view over a source is untouched; the view over a table
+    // is recreated because rebuilding the table drops it
+    let output = project.run_pgcrate_ok(&["model", "run"]);
+    let out = stdout(&output);
+    assert!(
+        out.contains("Skipped marts.user_emails (unchanged)"),
+        "{}",
+        out
+    );
+    assert!(!out.contains("Skipped marts.active_users"), "{}", out);
+    assert!(out.contains("1 unchanged"), "{}", out);
+    assert_eq!(
+        db.query("SELECT to_regclass('marts.active_users') IS NOT NULL"),
+        "t"
+    );
+
+    // --force rebuilds everything
+    let output = project.run_pgcrate_ok(&["model", "run", "--force"]);
+    assert!(
+        !stdout(&output).contains("(unchanged)"),
+        "{}",
+        stdout(&output)
+    );
+
+    // Changed SQL is rebuilt
+    std::fs::write(
+        project.path("models/marts/user_emails.sql"),
+        "-- materialized: view\n-- deps: public.users\n\nSELECT id, lower(email) AS email FROM public.users\n",
+    )
+    .unwrap();
+    let output = project.run_pgcrate_ok(&["model", "run"]);
+    assert!(
+        !stdout(&output).contains("(unchanged)"),
+        "{}",
+        stdout(&output)
+    );
+
+    // A dropped view is rebuilt even though its SQL didn't change
+    db.run_sql_ok("DROP VIEW marts.user_emails");
+    project.run_pgcrate_ok(&["model", "run"]);
+    assert_eq!(
+        db.query("SELECT to_regclass('marts.user_emails') IS NOT NULL"),
+        "t"
+    );
+}
+
+#[test]
+fn test_model_run_named_selector() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    std::fs::write(
+        project.path("models/marts/user_emails.sql"),
+        "-- materialized: view\n-- deps: public.users\n-- tags: daily\n\n\
+         SELECT id, email FROM public.users\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("models/selectors.toml"),
+        "nightly = [\"tag:daily\", \"exclude:marts.user_stats\"]\n",
+    )
+    .unwrap();
+
+    project.run_pgcrate_ok(&["model", "run", "--selector", "nightly"]);
+    assert_eq!(
+        db.query("SELECT to_regclass('marts.user_emails') IS NOT NULL"),
+        "t"
+    );
+    assert_eq!(
+        db.query("SELECT to_regclass('marts.user_stats') IS NULL"),
+        "t"
+    );
+
+    let output = project.run_pgcrate(&["model", "run", "--selector", "weekly"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("unknown selector 'weekly' (defined in selectors.toml: nightly)"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_model_run_init_sources_imports_foreign_tables() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("INSERT INTO public.users (email, name) VALUES ('a@example.com', 'A')");
+    // Loop back to the test database itself as the "remote" database
+    let config = project.read_file("pgcrate.toml");
+    std::fs::write(
+        project.path("pgcrate.toml"),
+        format!(
+            "{}\n[model.foreign_sources.remote_app]\nurl = \"{}\"\ntables = [\"users\"]\n",
+            config,
+            db.url()
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("models/marts/remote_users.sql"),
+        "-- materialized: table\n-- deps: remote_app.users\n\n\
+         SELECT count(*) AS n FROM remote_app.users\n",
+    )
+    .unwrap();
+
+    let output =
+        project.run_pgcrate_ok(&["model", "run", "--init-sources", "-s", "marts.remote_users"]);
+    assert!(
+        stdout(&output).contains("Initialized foreign source 'remote_app' (1 table(s) imported"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(db.query("SELECT n FROM marts.remote_users"), "1");
+
+    // Re-running keeps the existing foreign table
+    let output = project.run_pgcrate_ok(&["model", "run", "--init-sources", "marts.remote_users"]);
+    assert!(
+        stdout(&output).contains("(0 table(s) imported into remote_app)"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_model_plan_uses_build_state_and_source_freshness() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    let config = project.read_file("pgcrate.toml");
+    std::fs::write(
+        project.path("pgcrate.toml"),
+        format!(
+            "{}\n[model.freshness]\n\"public.users\" = \"created_at\"\n\"public.posts\" = \"created_at\"\n",
+            config
+        ),
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["--json", "model", "plan"]);
+    let json = parse_json(&output);
+    assert_eq!(json["run"], serde_json::json!(["marts.user_stats"]));
+    assert_eq!(json["models"][0]["reason"], "never_built");
+
+    project.run_pgcrate_ok(&["model", "run"]);
+    let json = parse_json(&project.run_pgcrate_ok(&["--json", "model", "plan"]));
+    assert_eq!(json["run"], serde_json::json!([]));
+    assert_eq!(json["models"][0]["needs_run"], false);
+
+    // New source rows arrive after the build
+    db.run_sql_ok("INSERT INTO public.users (email) VALUES ('late@example.com')");
+    let json = parse_json(&project.run_pgcrate_ok(&["--json", "model", "plan"]));
+    assert_eq!(json["run"], serde_json::json!(["marts.user_stats"]));
+    assert_eq!(json["models"][0]["reason"], "source_updated");
+    assert_eq!(json["models"][0]["upstream"], "public.users");
+
+    let output = project.run_pgcrate_ok(&["model", "plan"]);
+    assert!(
+        stdout(&output).contains("Plan: 1 of 1 model(s) need to run"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_model_clone_between_schemas() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("INSERT INTO public.users (email) VALUES ('a@example.com')");
+    project.run_pgcrate_ok(&["model", "run"]);
+
+    let output = project.run_pgcrate_ok(&[
+        "model",
+        "clone",
+        "--from-schema",
+        "marts",
+        "--to-schema",
+        "marts_dev",
+    ]);
+    assert!(
+        stdout(&output).contains("Cloned marts.user_stats -> marts_dev.user_stats (view)"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(
+        db.query(
+            "SELECT c.relkind FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = 'marts_dev' AND c.relname = 'user_stats'"
+        ),
+        "v"
+    );
+    assert_eq!(db.query("SELECT count(*) FROM marts_dev.user_stats"), "1");
+
+    // --copy replaces the view with a table copy
+    project.run_pgcrate_ok(&[
+        "model",
+        "clone",
+        "--from-schema",
+        "marts",
+        "--to-schema",
+        "marts_dev",
+        "--copy",
+    ]);
+    assert_eq!(
+        db.query(
+            "SELECT c.relkind FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = 'marts_dev' AND c.relname = 'user_stats'"
+        ),
+        "r"
+    );
+    assert_eq!(db.query("SELECT count(*) FROM marts_dev.user_stats"), "1");
+}
+
+#[test]
+fn test_model_retry_reruns_failed_and_skipped() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    let output = project.run_pgcrate(&["model", "retry"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("run_results.json"),
+        "{}",
+        stderr(&output)
+    );
+
+    std::fs::write(
+        project.path("models/marts/broken.sql"),
+        "-- materialized: table\n-- deps: public.users\n\n\
+         SELECT missing_column FROM public.users\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("models/marts/after_broken.sql"),
+        "-- materialized: table\n-- deps: marts.broken\n\n\
+         SELECT * FROM marts.broken\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate(&["model", "run", "--keep-going"]);
+    assert!(!output.status.success());
+    let results: serde_json::Value =
+        serde_json::from_str(&project.read_file("target/run_results.json")).unwrap();
+    assert_eq!(results["invocation"]["keep_going"], true);
+
+    // Fix the model and drop a table that succeeded: retry must not rebuild it
+    std::fs::write(
+        project.path("models/marts/broken.sql"),
+        "-- materialized: table\n-- deps: public.users\n\n\
+         SELECT id FROM public.users\n",
+    )
+    .unwrap();
+    db.run_sql_ok("DROP TABLE marts.user_stats");
+
+    let output = project.run_pgcrate_ok(&["model", "retry"]);
+    assert!(
+        stdout(&output).contains("Retrying 2 model(s)"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(
+        db.query("SELECT to_regclass('marts.after_broken') IS NOT NULL"),
+        "t"
+    );
+    assert_eq!(
+        db.query("SELECT to_regclass('marts.user_stats') IS NULL"),
+        "t"
+    );
+
+    let output = project.run_pgcrate_ok(&["model", "retry"]);
+    assert!(
+        stdout(&output).contains("Nothing to retry"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+// ============================================================================
+// state selection
+// ============================================================================
+
+#[test]
+fn test_model_run_state_modified_with_defer() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_models", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("INSERT INTO users (email, name) VALUES ('a@test.com', 'A')");
+    std::fs::write(
+        project.path("models/marts/user_totals.sql"),
+        "-- materialized: table\n-- deps: marts.user_stats\n\n\
+         SELECT count(*) AS n FROM marts.user_stats\n",
+    )
+    .unwrap();
+
+    // A full run writes the manifest; keep it as the "production" state
+    project.run_pgcrate_ok(&["model", "run"]);
+    let prod_state = project.path("prod-state");
+    std::fs::create_dir_all(&prod_state).unwrap();
+    std::fs::copy(
+        project.path("target/manifest.json"),
+        prod_state.join("manifest.json"),
+    )
+    .unwrap();
+
+    let state_arg = prod_state.to_str().unwrap();
+    let output =
+        project.run_pgcrate_ok(&["model", "run", "-s", "state:modified", "--state", state_arg]);
+    assert!(
+        stdout(&output).contains("No models found"),
+        "{}",
+        stdout(&output)
+    );
+
+    // Change only the downstream model; its upstream is deferred, not rebuilt
+    std::fs::write(
+        project.path("models/marts/user_totals.sql"),
+        "-- materialized: table\n-- deps: marts.user_stats\n\n\
+         SELECT count(*) * 10 AS n FROM marts.user_stats\n",
+    )
+    .unwrap();
+    let output = project.run_pgcrate_ok(&[
+        "model",
+        "run",
+        "-s",
+        "state:modified",
+        "--state",
+        state_arg,
+        "--defer",
+    ]);
+    let out = stdout(&output);
+    assert!(out.contains("1 model(s) executed"), "{}", out);
+    assert!(
+        out.contains("marts.user_stats (deferred to --state)"),
+        "{}",
+        out
+    );
+    assert_eq!(db.query("SELECT n FROM marts.user_totals"), "10");
+
+    // Without --state, state selectors are rejected
+    let output = project.run_pgcrate(&["model", "run", "-s", "state:modified"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--state"), "{}", stderr(&output));
+}
+
 // ============================================================================
 // Error cases
 // ============================================================================
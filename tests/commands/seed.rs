@@ -35,6 +35,21 @@ fn test_seed_run_inserts_data() {
     );
 }
 
+#[test]
+fn test_seed_run_chunk_size() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_seeds", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    let output = project.run_pgcrate_ok(&["seed", "run", "--chunk-size", "2"]);
+    assert!(stdout(&output).contains("3 rows ("), "{}", stdout(&output));
+    assert!(stdout(&output).contains("rows/s)"), "{}", stdout(&output));
+    assert_eq!(db.query("SELECT COUNT(*) FROM users"), "3");
+
+    project.run_pgcrate_fails(&["seed", "run", "--chunk-size", "0"], 2);
+}
+
 #[test]
 fn test_seed_run_json_output() {
     skip_if_no_db!();
@@ -54,6 +69,353 @@ fn test_seed_run_json_output() {
     // If no JSON support, that's okay for now - just verify command runs
 }
 
+#[test]
+fn test_seed_run_loads_jsonl_and_parquet() {
+    use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::sync::Arc;
+
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_seeds", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("CREATE TABLE public.tags (id bigint PRIMARY KEY, label text)");
+
+    std::fs::write(
+        project.path("db/seeds/public/posts.jsonl"),
+        r#"{"id": 1, "user_id": 1, "title": "Hello, world", "body": null}
+{"id": 2, "user_id": 2, "title": "Second \"post\"", "body": "multi\nline"}
+"#,
+    )
+    .unwrap();
+
+    let schema = Arc::new(
+        parse_message_type("message tags { required int64 id; optional binary label (UTF8); }")
+            .unwrap(),
+    );
+    let file = std::fs::File::create(project.path("db/seeds/public/tags.parquet")).unwrap();
+    let mut writer = SerializedFileWriter::new(file, schema, Default::default()).unwrap();
+    let mut row_group = writer.next_row_group().unwrap();
+    let mut column = row_group.next_column().unwrap().unwrap();
+    column
+        .typed::<Int64Type>()
+        .write_batch(&[10, 20], None, None)
+        .unwrap();
+    column.close().unwrap();
+    let mut column = row_group.next_column().unwrap().unwrap();
+    column
+        .typed::<ByteArrayType>()
+        .write_batch(&[ByteArray::from("rust")], Some(&[1, 0]), None)
+        .unwrap();
+    column.close().unwrap();
+    row_group.close().unwrap();
+    writer.close().unwrap();
+
+    let output = project.run_pgcrate_ok(&["seed", "list"]);
+    assert!(stdout(&output).contains("jsonl"), "{}", stdout(&output));
+    assert!(stdout(&output).contains("parquet"), "{}", stdout(&output));
+
+    project.run_pgcrate_ok(&["seed", "run"]);
+    assert_eq!(
+        db.query("SELECT title || '|' || coalesce(body, '<null>') FROM posts ORDER BY id"),
+        "Hello, world|<null>\nSecond \"post\"|multi\nline"
+    );
+    assert_eq!(
+        db.query("SELECT id || ':' || coalesce(label, '<null>') FROM tags ORDER BY id"),
+        "10:rust\n20:<null>"
+    );
+}
+
+#[test]
+fn test_seed_run_upsert_and_append_strategies() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_seeds", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok(
+        "INSERT INTO users (id, email, name) VALUES
+             (1, 'admin@example.com', 'Renamed'),
+             (4, 'dave@example.com', 'Dave')",
+    );
+    std::fs::write(
+        project.path("db/seeds/seeds.toml"),
+        "[seeds.\"public.users\"]\nstrategy = \"upsert\"\nkey = [\"id\"]\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["seed", "run", "--dry-run"]);
+    assert!(
+        stdout(&output).contains("csv, upsert on id"),
+        "{}",
+        stdout(&output)
+    );
+
+    project.run_pgcrate_ok(&["seed", "run"]);
+    assert_eq!(
+        db.query("SELECT id || ':' || name FROM users ORDER BY id"),
+        "1:Admin User\n2:Alice Smith\n3:Bob Jones\n4:Dave"
+    );
+
+    // Appending posts while users are truncated would lose the posts via CASCADE
+    std::fs::write(
+        project.path("db/seeds/public/posts.jsonl"),
+        "{\"id\": 1, \"user_id\": 1, \"title\": \"Hello\"}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("db/seeds/seeds.toml"),
+        "[seeds.\"public.posts\"]\nstrategy = \"append\"\n",
+    )
+    .unwrap();
+    let output = project.run_pgcrate(&["seed", "run"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("set strategy append or upsert for public.users"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_seed_run_orders_seeds_by_foreign_keys() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_seeds", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    // SQL seeds run with FK checks on; posts sorts before users alphabetically
+    std::fs::write(
+        project.path("db/seeds/public/posts.sql"),
+        "INSERT INTO public.posts (user_id, title) VALUES (2, 'Hello');\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["seed", "run", "--dry-run"]);
+    let out = stdout(&output);
+    assert!(
+        out.find("public.users").unwrap() < out.find("public.posts").unwrap(),
+        "{}",
+        out
+    );
+
+    project.run_pgcrate_ok(&["seed", "run"]);
+    assert_eq!(
+        db.query("SELECT u.name FROM posts p JOIN users u ON u.id = p.user_id"),
+        "Alice Smith"
+    );
+}
+
+#[test]
+fn test_seed_export_round_trips_csv() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_seeds", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    project.run_pgcrate_ok(&["seed", "run"]);
+    db.run_sql_ok("UPDATE users SET name = 'Alice, Admin' WHERE id = 2");
+
+    let output = project.run_pgcrate_ok(&["seed", "export", "public.users"]);
+    assert!(
+        stdout(&output).contains("Exported 3 row(s) from public.users"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(
+        project.read_file("db/seeds/public/users.csv"),
+        "id,email,name,is_admin,created_at\n\
+         1,admin@example.com,Admin User,true,2024-01-01 00:00:00+00\n\
+         2,alice@example.com,\"Alice, Admin\",false,2024-01-02 00:00:00+00\n\
+         3,bob@example.com,Bob Jones,false,2024-01-03 00:00:00+00\n"
+    );
+
+    // New seed for a table without one, filtered
+    project.run_pgcrate_ok(&["seed", "export", "posts", "--where", "user_id = 1"]);
+    assert_eq!(
+        project.read_file("db/seeds/public/posts.csv"),
+        "id,user_id,title,body,published_at,created_at\n"
+    );
+
+    project.run_pgcrate_ok(&["seed", "run"]);
+    assert_eq!(
+        db.query("SELECT name FROM users WHERE id = 2"),
+        "Alice, Admin"
+    );
+}
+
+#[test]
+fn test_seed_run_environment_scoped_seeds() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_seeds", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    std::fs::write(
+        project.path("db/seeds/public/posts.sql"),
+        "INSERT INTO public.posts (user_id, title) VALUES (1, 'Demo post');\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("db/seeds/seeds.toml"),
+        "[seeds.\"public.posts\"]\nenv = [\"dev\"]\n",
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate_ok(&["seed", "list"]);
+    assert!(
+        stdout(&output).contains("public.posts [sql] (env: dev)"),
+        "{}",
+        stdout(&output)
+    );
+
+    // Lookup data loads everywhere; demo data only in dev
+    let output = project.run_pgcrate_ok(&["seed", "run", "--environment", "prod"]);
+    assert!(
+        stdout(&output)
+            .contains("Skipping 1 seed(s) not scoped to environment 'prod': public.posts"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(db.query("SELECT COUNT(*) FROM users"), "3");
+    assert_eq!(db.query("SELECT COUNT(*) FROM posts"), "0");
+
+    project.run_pgcrate_ok(&["seed", "run", "--environment", "dev"]);
+    assert_eq!(db.query("SELECT title FROM posts"), "Demo post");
+}
+
+#[test]
+fn test_seed_generate_csv_and_insert() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_seeds", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    project.run_pgcrate_ok(&["seed", "run"]);
+
+    // CSV seed whose user_id values come from existing users
+    let output = project.run_pgcrate_ok(&["seed", "generate", "public.posts", "--rows", "5"]);
+    assert!(
+        stdout(&output).contains("Generated 5 row(s) for public.posts"),
+        "{}",
+        stdout(&output)
+    );
+    let csv = project.read_file("db/seeds/public/posts.csv");
+    assert!(
+        csv.starts_with("id,user_id,title,body,published_at,created_at\n1,"),
+        "{}",
+        csv
+    );
+    project.run_pgcrate_ok(&["seed", "generate", "posts", "--rows", "5", "--force"]);
+    assert_eq!(project.read_file("db/seeds/public/posts.csv"), csv);
+    project.run_pgcrate_fails(&["seed", "generate", "posts"], 10);
+
+    project.run_pgcrate_ok(&["seed", "run"]);
+    assert_eq!(
+        db.query("SELECT count(*) FROM posts p JOIN users u ON u.id = p.user_id"),
+        "5"
+    );
+
+    // Direct insert continues ids after the existing rows
+    let output = project.run_pgcrate_ok(&["seed", "generate", "users", "--rows", "4", "--insert"]);
+    assert!(
+        stdout(&output).contains("Inserted 4 row(s) into public.users"),
+        "{}",
+        stdout(&output)
+    );
+    assert_eq!(
+        db.query("SELECT count(DISTINCT email) || ':' || max(id) FROM users"),
+        "7:7"
+    );
+}
+
+#[test]
+fn test_seed_run_column_mapping_and_transforms() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_seeds", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    std::fs::write(
+        project.path("db/seeds/public/users.csv"),
+        "User ID,Email Address,Joined\n1,ADMIN@EXAMPLE.COM,01/31/2024\n2,alice@example.com,N/A\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project.path("db/seeds/seeds.toml"),
+        r#"[seeds."public.users"]
+columns = { "User ID" = "id", "Email Address" = "email", "Joined" = "created_at" }
+defaults = { is_admin = "false" }
+transforms = { email = ["lower"], created_at = ["null_if:N/A", "date:%m/%d/%Y"] }
+"#,
+    )
+    .unwrap();
+
+    project.run_pgcrate_ok(&["seed", "validate"]);
+    project.run_pgcrate_ok(&["seed", "run"]);
+    assert_eq!(
+        db.query(
+            "SELECT id || ':' || email || ':' || is_admin || ':' \
+             || coalesce(created_at::date::text, '<null>') FROM users ORDER BY id"
+        ),
+        "1:admin@example.com:false:2024-01-31\n2:alice@example.com:false:<null>"
+    );
+}
+
+#[test]
+fn test_seed_diff_rows_and_apply() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_seeds", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    project.run_pgcrate_ok(&["seed", "run"]);
+    let output = project.run_pgcrate_ok(&["seed", "diff"]);
+    assert!(
+        stdout(&output).contains("public.users: 3 rows (in sync)"),
+        "{}",
+        stdout(&output)
+    );
+
+    db.run_sql_ok(
+        "UPDATE users SET name = 'Alicia' WHERE id = 2;
+         DELETE FROM users WHERE id = 3;
+         INSERT INTO users (id, email, name) VALUES (9, 'eve@example.com', 'Eve')",
+    );
+    let output = project.run_pgcrate_ok(&["seed", "diff"]);
+    let out = stdout(&output);
+    assert!(
+        out.contains("3 rows in file, 3 in db (+1 added, -1 removed, ~1 changed)"),
+        "{}",
+        out
+    );
+    assert!(
+        out.contains("id=2 name: \"Alicia\" -> \"Alice Smith\""),
+        "{}",
+        out
+    );
+
+    let output = project.run_pgcrate_ok(&["seed", "diff", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(json["in_sync"], false);
+    let users = &json["seeds"][0];
+    assert_eq!(users["status"], "drift");
+    assert_eq!(users["key"][0], "id");
+    assert_eq!(users["added"][0]["key"]["id"], 3);
+    assert_eq!(users["removed"][0]["row"]["email"], "eve@example.com");
+    assert_eq!(users["changed"][0]["columns"]["name"]["db"], "Alicia");
+
+    project.run_pgcrate_ok(&["seed", "diff", "--apply"]);
+    assert_eq!(
+        db.query("SELECT string_agg(id || ':' || name, ',' ORDER BY id) FROM users"),
+        "1:Admin User,2:Alice Smith,3:Bob Jones"
+    );
+    let output = project.run_pgcrate_ok(&["seed", "diff", "--json"]);
+    assert_eq!(parse_json(&output)["in_sync"], true);
+}
+
 #[test]
 fn test_seed_run_no_seeds_dir() {
     skip_if_no_db!();
@@ -908,3 +908,1045 @@ fn test_describe_no_rls_section_when_disabled() {
 
     cleanup_test_db(&test_url);
 }
+
+/// Test inspect sequence for serial, identity, and standalone sequences
+#[test]
+fn test_inspect_sequence() {
+    let test_db = "pgcrate_describe_test_sequence";
+    let Some(test_url) = setup_test_db(test_db) else {
+        return;
+    };
+
+    let setup_sql = r#"
+        CREATE TABLE orders (id SERIAL PRIMARY KEY);
+        CREATE TABLE events (id INT GENERATED ALWAYS AS IDENTITY);
+        CREATE SEQUENCE ticket_seq AS smallint;
+        SELECT setval('ticket_seq', 30000);
+    "#;
+    let setup_result = run_psql(setup_sql, &test_url);
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let output = run_pgcrate(&["inspect", "sequence", "orders_id_seq"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "inspect sequence should succeed");
+    assert!(
+        stdout.contains("Owned by:     public.orders.id"),
+        "Should show owning column. stdout: {}",
+        stdout
+    );
+
+    let output = run_pgcrate(
+        &["inspect", "sequence", "public.events_id_seq", "--json"],
+        &test_url,
+    );
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect sequence --json");
+    assert_eq!(json["sequence"]["owned_by"], "public.events.id");
+    assert_eq!(json["sequence"]["identity"], true);
+
+    let output = run_pgcrate(&["inspect", "sequence", "ticket_seq", "--json"], &test_url);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect sequence --json");
+    let seq = &json["sequence"];
+    assert_eq!(seq["data_type"], "smallint");
+    assert_eq!(seq["last_value"], 30000);
+    assert_eq!(seq["status"], "critical", "91% of smallint used");
+    assert!(seq["owned_by"].is_null());
+
+    let output = run_pgcrate(&["inspect", "sequence", "orders"], &test_url);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "a table is not a sequence");
+    assert!(
+        stderr.contains("Sequence \"orders\" not found"),
+        "stderr: {}",
+        stderr
+    );
+
+    cleanup_test_db(&test_url);
+}
+
+/// Test inspect type for enums, domains, and composite types
+#[test]
+fn test_inspect_type() {
+    let test_db = "pgcrate_describe_test_type";
+    let Some(test_url) = setup_test_db(test_db) else {
+        return;
+    };
+
+    let setup_sql = r#"
+        CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy');
+        CREATE DOMAIN positive_int AS INT NOT NULL CHECK (VALUE > 0);
+        CREATE TYPE address AS (street TEXT, zip VARCHAR(10));
+        CREATE TABLE people (
+            id SERIAL PRIMARY KEY,
+            mood mood,
+            past_moods mood[],
+            age positive_int,
+            home address
+        );
+    "#;
+    let setup_result = run_psql(setup_sql, &test_url);
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let output = run_pgcrate(&["inspect", "type", "mood", "--json"], &test_url);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect type --json");
+    let ty = &json["type"];
+    assert_eq!(ty["kind"], "enum");
+    assert_eq!(ty["values"], serde_json::json!(["sad", "ok", "happy"]));
+    let columns: Vec<&str> = ty["used_by"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|u| u["column"].as_str().unwrap())
+        .collect();
+    assert_eq!(columns, vec!["mood", "past_moods"]);
+
+    let output = run_pgcrate(&["inspect", "type", "public.positive_int"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "inspect type should succeed");
+    assert!(stdout.contains("Domain: \"public\".\"positive_int\""));
+    assert!(stdout.contains("Base type:    integer"));
+    assert!(
+        stdout.contains("CHECK ((VALUE > 0))"),
+        "Should show domain constraint. stdout: {}",
+        stdout
+    );
+    assert!(stdout.contains("public.people.age"));
+
+    let output = run_pgcrate(&["inspect", "type", "address", "--json"], &test_url);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect type --json");
+    assert_eq!(json["type"]["kind"], "composite");
+    assert_eq!(
+        json["type"]["attributes"][1]["data_type"],
+        "character varying(10)"
+    );
+
+    // Table row types are not standalone composite types
+    let output = run_pgcrate(&["inspect", "type", "people"], &test_url);
+    assert!(
+        !output.status.success(),
+        "table row type is not inspectable"
+    );
+
+    cleanup_test_db(&test_url);
+}
+
+/// Test inspect schema summary and schema list
+#[test]
+fn test_inspect_schema() {
+    let test_db = "pgcrate_describe_test_schema";
+    let Some(test_url) = setup_test_db(test_db) else {
+        return;
+    };
+
+    let setup_sql = r#"
+        CREATE SCHEMA app;
+        CREATE TABLE app.small (id INT);
+        CREATE TABLE app.big (id SERIAL PRIMARY KEY, payload TEXT);
+        INSERT INTO app.big (payload) SELECT repeat('x', 100) FROM generate_series(1, 2000);
+        CREATE VIEW app.big_view AS SELECT id FROM app.big;
+        CREATE FUNCTION app.answer() RETURNS INT LANGUAGE sql AS 'SELECT 42';
+    "#;
+    let setup_result = run_psql(setup_sql, &test_url);
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let output = run_pgcrate(&["inspect", "schema", "app", "--json"], &test_url);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect schema --json");
+    let schema = &json["schema"];
+    assert_eq!(schema["tables"], 2);
+    assert_eq!(schema["views"], 1);
+    assert_eq!(schema["sequences"], 1);
+    assert_eq!(schema["functions"], 1);
+    assert!(schema["total_bytes"].as_i64().unwrap() > 0);
+    assert_eq!(schema["largest_tables"][0]["name"], "big");
+    assert_eq!(schema["owners"].as_array().unwrap().len(), 1);
+    assert_eq!(schema["owners"][0]["objects"], 5);
+    assert_eq!(schema["newest_objects"][0]["name"], "answer()");
+    assert_eq!(schema["newest_objects"][0]["kind"], "function");
+
+    let output = run_pgcrate(&["inspect", "schema"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "inspect schema should succeed");
+    assert!(
+        stdout.contains("2 tables, 1 view, 1 sequence, 1 function"),
+        "Should list app schema counts. stdout: {}",
+        stdout
+    );
+    assert!(stdout.contains("public"), "Should list public");
+
+    let output = run_pgcrate(&["inspect", "schema", "missing"], &test_url);
+    assert!(!output.status.success(), "missing schema should fail");
+
+    cleanup_test_db(&test_url);
+}
+
+/// Test inspect find across object kinds, with regex and type filters
+#[test]
+fn test_inspect_find() {
+    let test_db = "pgcrate_describe_test_find";
+    let Some(test_url) = setup_test_db(test_db) else {
+        return;
+    };
+
+    let setup_sql = r#"
+        CREATE SCHEMA app;
+        CREATE TABLE app.customers (
+            id SERIAL PRIMARY KEY,
+            email TEXT UNIQUE
+        );
+        CREATE VIEW app.customer_emails AS SELECT email FROM app.customers;
+        CREATE FUNCTION app.normalize_email(e TEXT) RETURNS TEXT LANGUAGE sql AS 'SELECT lower(e)';
+        CREATE TABLE public.events (at DATE, email TEXT) PARTITION BY RANGE (at);
+        CREATE TABLE public.events_2020 PARTITION OF public.events
+            FOR VALUES FROM ('2020-01-01') TO ('2021-01-01');
+    "#;
+    let setup_result = run_psql(setup_sql, &test_url);
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let output = run_pgcrate(&["inspect", "find", "EMAIL", "--json"], &test_url);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect find --json");
+    let found: Vec<(String, String)> = json["matches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| {
+            (
+                m["kind"].as_str().unwrap().to_string(),
+                format!(
+                    "{}.{}",
+                    m["table"].as_str().unwrap_or(m["schema"].as_str().unwrap()),
+                    m["name"].as_str().unwrap()
+                ),
+            )
+        })
+        .collect();
+    let has = |kind: &str, name: &str| found.contains(&(kind.to_string(), name.to_string()));
+    assert!(has("view", "app.customer_emails"), "found: {:?}", found);
+    assert!(has("column", "customers.email"), "found: {:?}", found);
+    assert!(has("column", "events.email"), "found: {:?}", found);
+    assert!(
+        has("function", "app.normalize_email(e text)"),
+        "found: {:?}",
+        found
+    );
+    assert!(has("index", "customers.customers_email_key"));
+    assert!(has("constraint", "customers.customers_email_key"));
+    // Partitions repeat their parent's columns
+    assert!(!found.iter().any(|(_, n)| n.starts_with("events_2020")));
+
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "find",
+            "^customer",
+            "--regex",
+            "--type",
+            "table,view",
+        ],
+        &test_url,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "inspect find should succeed");
+    assert!(
+        stdout.contains("Tables:\n  app.customers"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Views:\n  app.customer_emails"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(!stdout.contains("Columns:"), "stdout: {}", stdout);
+
+    let output = run_pgcrate(&["inspect", "find", "nothing_like_this"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("No objects match"));
+
+    cleanup_test_db(&test_url);
+}
+
+/// Test inspect graph across FK, view, trigger-function, and type dependencies
+#[test]
+fn test_inspect_graph() {
+    let test_db = "pgcrate_describe_test_graph";
+    let Some(test_url) = setup_test_db(test_db) else {
+        return;
+    };
+
+    let setup_sql = r#"
+        CREATE SCHEMA auth;
+        CREATE TABLE auth.users (id INT PRIMARY KEY);
+        CREATE SCHEMA app;
+        CREATE TYPE app.status AS ENUM ('open', 'closed');
+        CREATE TABLE app.orders (
+            id INT PRIMARY KEY,
+            user_id INT REFERENCES auth.users,
+            status app.status
+        );
+        CREATE TABLE app.items (id INT, order_id INT REFERENCES app.orders);
+        CREATE VIEW app.order_sizes AS
+            SELECT order_id, count(*) FROM app.items GROUP BY order_id;
+        CREATE FUNCTION app.touch() RETURNS trigger LANGUAGE plpgsql AS 'BEGIN RETURN NEW; END';
+        CREATE TRIGGER orders_touch BEFORE UPDATE ON app.orders
+            FOR EACH ROW EXECUTE FUNCTION app.touch();
+    "#;
+    let setup_result = run_psql(setup_sql, &test_url);
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let output = run_pgcrate(
+        &["inspect", "graph", "--schema", "app", "--json"],
+        &test_url,
+    );
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect graph --json");
+    let edges: Vec<(String, String, String)> = json["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| {
+            (
+                e["from"].as_str().unwrap().to_string(),
+                e["to"].as_str().unwrap().to_string(),
+                e["kind"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    let has = |from: &str, to: &str, kind: &str| {
+        edges.contains(&(from.to_string(), to.to_string(), kind.to_string()))
+    };
+    assert!(has("app.items", "app.orders", "foreign_key"), "{:?}", edges);
+    assert!(
+        has("app.orders", "auth.users", "foreign_key"),
+        "{:?}",
+        edges
+    );
+    assert!(has("app.order_sizes", "app.items", "view"), "{:?}", edges);
+    assert!(
+        has("app.orders", "app.touch", "trigger_function"),
+        "{:?}",
+        edges
+    );
+    assert!(has("app.orders", "app.status", "type"), "{:?}", edges);
+    assert_eq!(edges.len(), 5, "no duplicate or self edges: {:?}", edges);
+    let external = json["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|n| n["id"] == "auth.users")
+        .unwrap();
+    assert_eq!(external["in_schema"], false);
+
+    // Inbound FKs from other schemas are part of the blast radius
+    let output = run_pgcrate(&["inspect", "graph", "--schema", "auth"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "inspect graph should succeed");
+    assert!(
+        stdout.contains("\"app.orders\" -> \"auth.users\" [label=\"user_id -> id\"];"),
+        "stdout: {}",
+        stdout
+    );
+
+    let output = run_pgcrate(
+        &["inspect", "graph", "--schema", "app", "--format", "mermaid"],
+        &test_url,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("graph LR"), "stdout: {}", stdout);
+
+    let output = run_pgcrate(
+        &["inspect", "graph", "--schema", "app", "--format", "svg"],
+        &test_url,
+    );
+    assert!(!output.status.success(), "unknown format should fail");
+
+    cleanup_test_db(&test_url);
+}
+
+#[test]
+fn test_comment_set_and_inspect() {
+    let test_db = "pgcrate_describe_test_comment";
+    let Some(test_url) = setup_test_db(test_db) else {
+        return;
+    };
+
+    let setup_sql = r#"
+        CREATE SCHEMA app;
+        CREATE TABLE app.users (id INT PRIMARY KEY, email TEXT);
+    "#;
+    let setup_result = run_psql(setup_sql, &test_url);
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let output = run_pgcrate(
+        &["comment", "set", "app.users", "Registered accounts"],
+        &test_url,
+    );
+    assert!(
+        output.status.success(),
+        "comment set should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = run_pgcrate(
+        &["comment", "set", "app.users.email", "Owner's login"],
+        &test_url,
+    );
+    assert!(output.status.success(), "column comment should succeed");
+
+    let output = run_pgcrate(&["inspect", "table", "app.users", "--json"], &test_url);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect table --json");
+    assert_eq!(json["table"]["comment"], "Registered accounts");
+    let email = json["table"]["columns"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == "email")
+        .unwrap();
+    assert_eq!(email["comment"], "Owner's login");
+
+    let output = run_pgcrate(&["inspect", "table", "app.users"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Comment: Registered accounts"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("-- Owner's login"), "{}", stdout);
+
+    // --dry-run leaves the comment alone
+    let output = run_pgcrate(
+        &["comment", "set", "app.users", "--clear", "--dry-run"],
+        &test_url,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("COMMENT ON TABLE \"app\".\"users\" IS NULL;"),
+        "{}",
+        stdout
+    );
+    let output = run_pgcrate(&["comment", "set", "app.users", "--clear"], &test_url);
+    assert!(output.status.success(), "--clear should succeed");
+    let output = run_pgcrate(&["inspect", "table", "app.users", "--json"], &test_url);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect table --json");
+    assert!(json["table"]["comment"].is_null());
+
+    let output = run_pgcrate(&["comment", "set", "app.users.missing", "x"], &test_url);
+    assert!(!output.status.success(), "unknown column should fail");
+
+    cleanup_test_db(&test_url);
+}
+
+#[test]
+fn test_inspect_profile() {
+    let test_db = "pgcrate_describe_test_profile";
+    let Some(test_url) = setup_test_db(test_db) else {
+        return;
+    };
+
+    let setup_sql = r#"
+        CREATE TABLE orders (id INT PRIMARY KEY, status TEXT, paid BOOLEAN);
+        INSERT INTO orders
+            SELECT g, CASE WHEN g % 4 = 0 THEN NULL WHEN g % 2 = 0 THEN 'open' ELSE 'closed' END, g % 3 = 0
+            FROM generate_series(1, 100) g;
+    "#;
+    let setup_result = run_psql(setup_sql, &test_url);
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let output = run_pgcrate(&["inspect", "profile", "orders", "--json"], &test_url);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect profile --json");
+    let profile = &json["profile"];
+    assert_eq!(profile["rows_sampled"], 100);
+    assert_eq!(profile["sampled"], false);
+    let column = |name: &str| {
+        profile["columns"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["name"] == name)
+            .unwrap()
+            .clone()
+    };
+
+    let id = column("id");
+    assert_eq!(id["distinct"], 100);
+    assert_eq!(id["min"], "1");
+    assert_eq!(id["max"], "100");
+    assert!(id["top_values"].as_array().unwrap().is_empty());
+
+    let status = column("status");
+    assert_eq!(status["null_fraction"], 0.25);
+    assert_eq!(status["distinct"], 2);
+    assert_eq!(status["top_values"][0]["value"], "closed");
+    assert_eq!(status["top_values"][0]["count"], 50);
+
+    // No min/max for booleans, but top values still apply
+    let paid = column("paid");
+    assert!(paid["min"].is_null());
+    assert_eq!(paid["top_values"][0]["value"], "false");
+
+    // --sample bounds the rows read
+    let output = run_pgcrate(
+        &["inspect", "profile", "orders", "--sample", "10", "--json"],
+        &test_url,
+    );
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect profile --json");
+    assert_eq!(json["profile"]["rows_sampled"], 10);
+    assert_eq!(json["profile"]["sampled"], true);
+
+    let output = run_pgcrate(&["inspect", "profile", "orders"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Rows: 100 (all rows profiled)"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("nulls 25.0%"), "{}", stdout);
+
+    cleanup_test_db(&test_url);
+}
+
+#[test]
+fn test_inspect_publications_and_subscriptions() {
+    let test_db = "pgcrate_describe_test_publications";
+    let Some(test_url) = setup_test_db(test_db) else {
+        return;
+    };
+
+    let setup_sql = r#"
+        CREATE SCHEMA app;
+        CREATE TABLE app.orders (id INT PRIMARY KEY, status TEXT);
+        CREATE TABLE app.users (id INT PRIMARY KEY, email TEXT, password TEXT);
+        CREATE PUBLICATION app_pub
+            FOR TABLE app.orders WHERE (status <> 'draft'), app.users (id, email)
+            WITH (publish = 'insert, update');
+    "#;
+    let setup_result = run_psql(setup_sql, &test_url);
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let output = run_pgcrate(&["inspect", "publications", "--json"], &test_url);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect publications --json");
+    let publication = &json["publications"][0];
+    assert_eq!(publication["name"], "app_pub");
+    assert_eq!(
+        publication["operations"],
+        serde_json::json!(["insert", "update"])
+    );
+    assert_eq!(publication["tables"][0]["table"], "orders");
+    assert!(publication["tables"][0]["row_filter"]
+        .as_str()
+        .unwrap()
+        .contains("draft"));
+    assert_eq!(
+        publication["tables"][1]["columns"],
+        serde_json::json!(["id", "email"])
+    );
+
+    let output = run_pgcrate(&["inspect", "publications", "app_pub"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("app.users (id, email)"), "{}", stdout);
+
+    let output = run_pgcrate(&["inspect", "publications", "missing"], &test_url);
+    assert!(!output.status.success(), "unknown publication should fail");
+
+    let output = run_pgcrate(&["inspect", "subscriptions"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "inspect subscriptions should succeed"
+    );
+    assert!(stdout.contains("No subscriptions"), "{}", stdout);
+
+    // A subscription created without connecting stays disabled
+    let create = run_psql(
+        "CREATE SUBSCRIPTION app_sub CONNECTION 'dbname=postgres' PUBLICATION app_pub \
+         WITH (connect = false)",
+        &test_url,
+    );
+    if create.status.success() {
+        let output = run_pgcrate(&["inspect", "subscriptions", "--json"], &test_url);
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("inspect subscriptions --json");
+        let subscription = &json["subscriptions"][0];
+        assert_eq!(subscription["subscription"], "app_sub");
+        assert_eq!(subscription["enabled"], false);
+        assert_eq!(subscription["status"], "warning");
+        assert_eq!(subscription["publications"], serde_json::json!(["app_pub"]));
+
+        // No slot was created, so detach it before dropping
+        let _ = run_psql(
+            "ALTER SUBSCRIPTION app_sub SET (slot_name = NONE)",
+            &test_url,
+        );
+        let _ = run_psql("DROP SUBSCRIPTION app_sub", &test_url);
+    }
+
+    cleanup_test_db(&test_url);
+}
+
+/// Test inspect diff --format sql brings the target in line with the source
+#[test]
+fn test_inspect_diff_sql() {
+    let Some(source_url) = setup_test_db("pgcrate_describe_test_diff_src") else {
+        return;
+    };
+    let Some(target_url) = setup_test_db("pgcrate_describe_test_diff_tgt") else {
+        cleanup_test_db(&source_url);
+        return;
+    };
+
+    let source_sql = r#"
+        CREATE TABLE users (id INT PRIMARY KEY, email TEXT NOT NULL, name TEXT);
+        CREATE TABLE orders (id INT PRIMARY KEY, user_id INT REFERENCES users);
+        CREATE INDEX orders_user_idx ON orders (user_id);
+    "#;
+    assert!(run_psql(source_sql, &source_url).status.success());
+    let target_sql = r#"
+        CREATE TABLE users (id INT PRIMARY KEY, email VARCHAR(50), legacy INT);
+        CREATE TABLE old_audit (id INT);
+    "#;
+    assert!(run_psql(target_sql, &target_url).status.success());
+
+    // Safe-only leaves the target's extra table and column alone
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "diff",
+            "--to",
+            &target_url,
+            "--format",
+            "sql",
+            "--safe-only",
+        ],
+        &source_url,
+    );
+    assert_eq!(output.status.code(), Some(1), "schemas differ");
+    let sql = String::from_utf8_lossy(&output.stdout);
+    assert!(sql.contains("omitted 3 destructive statements"), "{}", sql);
+    assert!(!sql.contains("DROP TABLE"), "{}", sql);
+    assert!(
+        sql.contains("CREATE TABLE \"public\".\"orders\""),
+        "{}",
+        sql
+    );
+
+    let output = run_pgcrate(
+        &["inspect", "diff", "--to", &target_url, "--format", "sql"],
+        &source_url,
+    );
+    let sql = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        sql.contains("DROP TABLE IF EXISTS \"public\".\"old_audit\";"),
+        "{}",
+        sql
+    );
+    let apply = run_psql(&sql, &target_url);
+    assert!(
+        apply.status.success(),
+        "generated SQL should apply: {}",
+        String::from_utf8_lossy(&apply.stderr)
+    );
+
+    // Target now matches
+    let output = run_pgcrate(&["inspect", "diff", "--to", &target_url], &source_url);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    cleanup_test_db(&source_url);
+    cleanup_test_db(&target_url);
+}
+
+#[test]
+fn test_inspect_diff_rls_and_comments() {
+    let Some(source_url) = setup_test_db("pgcrate_describe_test_diff_rls_src") else {
+        return;
+    };
+    let Some(target_url) = setup_test_db("pgcrate_describe_test_diff_rls_tgt") else {
+        cleanup_test_db(&source_url);
+        return;
+    };
+
+    let source_sql = r#"
+        CREATE TABLE notes (id INT PRIMARY KEY, owner TEXT NOT NULL);
+        ALTER TABLE notes ENABLE ROW LEVEL SECURITY;
+        CREATE POLICY own_rows ON notes USING (owner = current_user);
+        COMMENT ON TABLE notes IS 'Private notes';
+        COMMENT ON COLUMN notes.owner IS 'Login name';
+    "#;
+    assert!(run_psql(source_sql, &source_url).status.success());
+    let target_sql = r#"
+        CREATE TABLE notes (id INT PRIMARY KEY, owner TEXT NOT NULL);
+        CREATE POLICY legacy ON notes FOR SELECT USING (true);
+        COMMENT ON TABLE notes IS 'Notes';
+    "#;
+    assert!(run_psql(target_sql, &target_url).status.success());
+
+    let output = run_pgcrate(&["inspect", "diff", "--to", &target_url], &source_url);
+    assert_eq!(output.status.code(), Some(1), "schemas differ");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Row-Level Security:"), "{}", stdout);
+    assert!(stdout.contains("own_rows ON public.notes"), "{}", stdout);
+    assert!(
+        stdout.contains("table public.notes (text differs)"),
+        "{}",
+        stdout
+    );
+
+    let output = run_pgcrate(
+        &["inspect", "diff", "--to", &target_url, "--format", "sql"],
+        &source_url,
+    );
+    let sql = String::from_utf8_lossy(&output.stdout);
+    let apply = run_psql(&sql, &target_url);
+    assert!(
+        apply.status.success(),
+        "generated SQL should apply: {}",
+        String::from_utf8_lossy(&apply.stderr)
+    );
+
+    let output = run_pgcrate(&["inspect", "diff", "--to", &target_url], &source_url);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    cleanup_test_db(&source_url);
+    cleanup_test_db(&target_url);
+}
+
+#[test]
+fn test_inspect_diff3() {
+    let Some(base_url) = setup_test_db("pgcrate_describe_test_diff3_base") else {
+        return;
+    };
+    let Some(ours_url) = setup_test_db("pgcrate_describe_test_diff3_ours") else {
+        cleanup_test_db(&base_url);
+        return;
+    };
+    let Some(theirs_url) = setup_test_db("pgcrate_describe_test_diff3_theirs") else {
+        cleanup_test_db(&base_url);
+        cleanup_test_db(&ours_url);
+        return;
+    };
+
+    let base_sql = "CREATE TABLE users (id INT PRIMARY KEY, name TEXT, email TEXT);";
+    for url in [&base_url, &ours_url, &theirs_url] {
+        assert!(run_psql(base_sql, url).status.success());
+    }
+    let ours_sql = r#"
+        CREATE TABLE invoices (id INT PRIMARY KEY);
+        ALTER TABLE users ALTER COLUMN email TYPE VARCHAR(100);
+    "#;
+    assert!(run_psql(ours_sql, &ours_url).status.success());
+    let theirs_sql = r#"
+        CREATE INDEX users_name_idx ON users (name);
+        ALTER TABLE users DROP COLUMN email;
+    "#;
+    assert!(run_psql(theirs_sql, &theirs_url).status.success());
+
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "diff3",
+            "--base",
+            &base_url,
+            "--ours",
+            &ours_url,
+            "--theirs",
+            &theirs_url,
+            "--json",
+        ],
+        &base_url,
+    );
+    assert_eq!(output.status.code(), Some(2), "conflicts exit 2");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect diff3 --json");
+    assert_eq!(json["only_ours"], 1);
+    assert_eq!(json["only_theirs"], 1);
+    assert_eq!(json["conflicts"], 1);
+    let conflict = &json["entries"][2];
+    assert_eq!(conflict["side"], "conflict");
+    assert_eq!(conflict["name"], "public.users.email");
+    assert_eq!(conflict["ours"], "modified");
+    assert_eq!(conflict["theirs"], "removed");
+
+    // Without the conflicting change, differences exit 1
+    assert!(
+        run_psql("ALTER TABLE users ADD COLUMN email TEXT", &theirs_url)
+            .status
+            .success()
+    );
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "diff3",
+            "--base",
+            &base_url,
+            "--theirs",
+            &theirs_url,
+        ],
+        &ours_url,
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("+ table public.invoices"), "{}", stdout);
+    assert!(
+        stdout.contains("+ index public.users_name_idx"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("~ column public.users.email"), "{}", stdout);
+
+    cleanup_test_db(&base_url);
+    cleanup_test_db(&ours_url);
+    cleanup_test_db(&theirs_url);
+}
+
+/// Test inspect data-diff reports added, removed, and changed rows
+#[test]
+fn test_inspect_data_diff() {
+    let Some(source_url) = setup_test_db("pgcrate_describe_test_data_diff_src") else {
+        return;
+    };
+    let Some(target_url) = setup_test_db("pgcrate_describe_test_data_diff_tgt") else {
+        cleanup_test_db(&source_url);
+        return;
+    };
+
+    let setup_sql = r#"
+        CREATE TABLE items (id INT PRIMARY KEY, name TEXT, created TIMESTAMPTZ);
+        INSERT INTO items
+            SELECT g, 'item ' || g, '2024-01-01'::timestamptz + g * interval '1 day'
+            FROM generate_series(1, 1000) g;
+    "#;
+    assert!(run_psql(setup_sql, &source_url).status.success());
+    assert!(run_psql(setup_sql, &target_url).status.success());
+
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "data-diff",
+            "--to",
+            &target_url,
+            "--table",
+            "public.items",
+        ],
+        &source_url,
+    );
+    assert_eq!(output.status.code(), Some(0), "identical tables");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Rows are identical."));
+
+    let change_sql = r#"
+        DELETE FROM items WHERE id = 10;
+        UPDATE items SET name = 'renamed' WHERE id = 20;
+        INSERT INTO items VALUES (5000, 'extra', NULL);
+    "#;
+    assert!(run_psql(change_sql, &target_url).status.success());
+
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "data-diff",
+            "--to",
+            &target_url,
+            "--table",
+            "items",
+            "--json",
+        ],
+        &source_url,
+    );
+    assert_eq!(output.status.code(), Some(1), "tables differ");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect data-diff --json");
+    assert_eq!(json["identical"], false);
+    assert_eq!(json["key"], serde_json::json!(["id"]));
+    assert_eq!(json["source_rows"], 1000);
+    assert_eq!(json["target_rows"], 1000);
+    assert_eq!(json["added_keys"], serde_json::json!(["(5000)"]));
+    assert_eq!(json["removed_keys"], serde_json::json!(["(10)"]));
+    assert_eq!(json["changed_keys"], serde_json::json!(["(20)"]));
+
+    // Tables without a primary key need --key
+    let nokey_sql = "CREATE TABLE events (name TEXT)";
+    assert!(run_psql(nokey_sql, &source_url).status.success());
+    assert!(run_psql(nokey_sql, &target_url).status.success());
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "data-diff",
+            "--to",
+            &target_url,
+            "--table",
+            "events",
+        ],
+        &source_url,
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--key"));
+
+    cleanup_test_db(&source_url);
+    cleanup_test_db(&target_url);
+}
+
+/// Test inspect diff --include-privileges compares ownership and grants
+#[test]
+fn test_inspect_diff_privileges() {
+    let Some(source_url) = setup_test_db("pgcrate_describe_test_diff_priv_src") else {
+        return;
+    };
+    let Some(target_url) = setup_test_db("pgcrate_describe_test_diff_priv_tgt") else {
+        cleanup_test_db(&source_url);
+        return;
+    };
+    let role = unique_db_name("pgcrate_diff_reader");
+    assert!(run_psql(&format!("CREATE ROLE {role}"), &source_url)
+        .status
+        .success());
+
+    let setup_sql = "CREATE SCHEMA app; CREATE TABLE app.users (id INT)";
+    assert!(run_psql(setup_sql, &source_url).status.success());
+    assert!(run_psql(setup_sql, &target_url).status.success());
+    let grant_sql =
+        format!("GRANT USAGE ON SCHEMA app TO {role}; GRANT SELECT ON app.users TO {role}");
+    assert!(run_psql(&grant_sql, &source_url).status.success());
+    let grant_sql = format!("GRANT UPDATE ON app.users TO {role}");
+    assert!(run_psql(&grant_sql, &target_url).status.success());
+
+    // Without the flag, privileges are invisible
+    let output = run_pgcrate(&["inspect", "diff", "--to", &target_url], &source_url);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "diff",
+            "--to",
+            &target_url,
+            "--include-privileges",
+        ],
+        &source_url,
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!("+ UPDATE ON table app.users TO {role}")),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(&format!("- SELECT ON table app.users TO {role}")),
+        "{}",
+        stdout
+    );
+
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "diff",
+            "--to",
+            &target_url,
+            "--include-privileges",
+            "--format",
+            "sql",
+        ],
+        &source_url,
+    );
+    let sql = String::from_utf8_lossy(&output.stdout);
+    let apply = run_psql(&sql, &target_url);
+    assert!(
+        apply.status.success(),
+        "generated SQL should apply: {}",
+        String::from_utf8_lossy(&apply.stderr)
+    );
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "diff",
+            "--to",
+            &target_url,
+            "--include-privileges",
+        ],
+        &source_url,
+    );
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    cleanup_test_db(&source_url);
+    cleanup_test_db(&target_url);
+    let _ = run_psql(&format!("DROP ROLE IF EXISTS {role}"), &get_test_db_url());
+}
+
+#[test]
+fn test_inspect_security_audit() {
+    let test_db = "pgcrate_describe_test_security_audit";
+    let Some(test_url) = setup_test_db(test_db) else {
+        return;
+    };
+
+    let setup_sql = r#"
+        CREATE SCHEMA app;
+        CREATE TABLE app.users (id INT PRIMARY KEY, email TEXT);
+        CREATE TABLE app.notes (id INT PRIMARY KEY, owner_id INT);
+        ALTER TABLE app.notes ENABLE ROW LEVEL SECURITY;
+        GRANT CREATE ON SCHEMA app TO PUBLIC;
+        CREATE SCHEMA pgapp;
+        GRANT CREATE ON SCHEMA pgapp TO PUBLIC;
+    "#;
+    let setup_result = run_psql(setup_sql, &test_url);
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "security-audit",
+            "--sensitive",
+            "app.*",
+            "--json",
+        ],
+        &test_url,
+    );
+    assert!(output.status.success(), "security-audit should succeed");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("inspect security-audit --json");
+    let findings = json["audit"]["findings"].as_array().unwrap();
+    let has = |check: &str, object: &str| {
+        findings
+            .iter()
+            .any(|f| f["check"] == check && f["object"] == object)
+    };
+    assert!(has("sensitive_without_rls", "app.users"), "{:?}", findings);
+    assert!(!has("sensitive_without_rls", "app.notes"), "{:?}", findings);
+    assert!(has("public_schema_grant", "app"), "{:?}", findings);
+    // Only pg_* (with a literal underscore) is treated as a system prefix
+    assert!(has("public_schema_grant", "pgapp"), "{:?}", findings);
+    assert_eq!(json["audit"]["overall"], "critical");
+
+    let output = run_pgcrate(&["inspect", "security-audit"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("SECURITY AUDIT:"), "{}", stdout);
+    assert!(
+        stdout.contains("No sensitive tables declared"),
+        "{}",
+        stdout
+    );
+
+    cleanup_test_db(&test_url);
+}
@@ -1,9 +1,10 @@
 //! Integration tests for DBA diagnostic commands (healthy state).
 //!
-//! Tests triage and sequences in their normal/healthy state.
+//! Tests triage (including `--all-databases` and `--fleet`) and sequences in
+//! their normal/healthy state, `--watch`, and `--format nagios`.
 //! Warning and critical state scenarios are covered in PGC-38.
 
-use crate::common::{parse_json, stdout, TestDatabase, TestProject};
+use crate::common::{parse_json, stderr, stdout, TestDatabase, TestProject};
 
 // ============================================================================
 // triage
@@ -113,6 +114,108 @@ fn test_triage_errors_on_stderr() {
     assert!(!out.is_empty(), "Triage output should be on stdout");
 }
 
+#[test]
+fn test_triage_all_databases() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok("CREATE SEQUENCE nearly_full AS integer MAXVALUE 100;");
+    db.run_sql_ok("SELECT setval('nearly_full', 95);");
+
+    let output = project.run_pgcrate(&["dba", "triage", "--all-databases", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(
+        json["schema_id"],
+        serde_json::json!("pgcrate.diagnostics.cluster_triage")
+    );
+    let data = json.get("data").expect("JSON should have data field");
+    assert_eq!(data["overall_status"], serde_json::json!("critical"));
+    assert!(data["oldest_xid_database"].is_string());
+
+    let databases = data["databases"].as_array().unwrap();
+    let this = databases
+        .iter()
+        .find(|d| d["database"] == db.name.as_str())
+        .expect("test database listed");
+    assert_eq!(this["connected"], serde_json::json!(true), "{}", this);
+    assert_eq!(this["status"], serde_json::json!("critical"));
+    assert_eq!(this["sequence_status"], serde_json::json!("critical"));
+    assert_eq!(
+        this["top_sequence"]["name"],
+        serde_json::json!("nearly_full")
+    );
+
+    // Templates are reported from pg_database without connecting
+    let template = databases
+        .iter()
+        .find(|d| d["database"] == "template1")
+        .expect("template1 listed");
+    assert_eq!(template["connected"], serde_json::json!(false));
+}
+
+#[test]
+fn test_triage_fleet() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    std::fs::write(
+        project.path("pgcrate.toml"),
+        format!(
+            r#"[connections.app-east]
+url = "{url}"
+
+[connections.app-west]
+url = "{url}"
+
+[connections.app-down]
+url = "postgres://localhost:1/nope"
+
+[connections.other]
+url = "{url}"
+
+[fleets]
+apps = ["app-east", "app-west"]
+"#,
+            url = db.url()
+        ),
+    )
+    .unwrap();
+
+    let output = project.run_pgcrate(&["--primary", "--json", "dba", "triage", "--fleet", "app-*"]);
+    let json = parse_json(&output);
+    assert_eq!(
+        json["schema_id"],
+        serde_json::json!("pgcrate.diagnostics.fleet_triage")
+    );
+    let data = json.get("data").expect("JSON should have data field");
+    let names: Vec<&str> = data["targets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["app-down", "app-east", "app-west"]);
+    assert_eq!(data["failed"], serde_json::json!(1));
+    // An unreachable target is never reported as healthy
+    assert_eq!(data["overall_status"], serde_json::json!("critical"));
+    let down = &data["targets"][0];
+    assert!(down["status"].is_null());
+    assert!(down["error"].is_string());
+    assert!(data["targets"][1]["triage"]["checks"].is_array());
+
+    // A [fleets] entry selects its members
+    let output = project.run_pgcrate(&["--primary", "--json", "dba", "triage", "--fleet", "apps"]);
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["targets"].as_array().unwrap().len(), 2);
+    assert_eq!(json["data"]["failed"], serde_json::json!(0));
+
+    let output = project.run_pgcrate(&["--primary", "dba", "triage", "--fleet", "db-*"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("No connections in pgcrate.toml match 'db-*'"));
+}
+
 // ============================================================================
 // sequences
 // ============================================================================
@@ -233,3 +336,242 @@ fn test_diagnostics_quiet_mode() {
     // Just verify it runs without error
     assert!(output.status.code().is_some());
 }
+
+// ============================================================================
+// Watch mode
+// ============================================================================
+
+#[test]
+fn test_watch_json_emits_ndjson() {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pgcrate"))
+        .args(["dba", "connections", "--watch=1", "--json"])
+        .current_dir(project.path(""))
+        .env_clear()
+        .env("DATABASE_URL", db.url())
+        .env("HOME", project.path(""))
+        .env("PATH", std::env::var("PATH").unwrap_or_default())
+        .env("PGCRATE_NON_INTERACTIVE", "1")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to spawn pgcrate");
+
+    // Each iteration is one complete JSON document on its own line
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    let first = lines.next().expect("first line").unwrap();
+    let second = lines.next().expect("second line").unwrap();
+    let _ = child.kill();
+    let _ = child.wait();
+
+    for line in [first, second] {
+        let json: serde_json::Value = serde_json::from_str(&line).expect("NDJSON line");
+        assert_eq!(json["schema_id"], "pgcrate.diagnostics.connections");
+        assert!(json["data"].is_object());
+    }
+}
+
+#[test]
+fn test_watch_rejects_unsupported_command() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "xid", "--watch"]);
+    assert!(!output.status.success());
+    let output = project.run_pgcrate(&["dba", "locks", "--cancel", "1", "--watch=5"]);
+    assert!(!output.status.success());
+}
+
+// ============================================================================
+// Nagios output
+// ============================================================================
+
+#[test]
+fn test_pooler_on_direct_connection() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    // A direct connection is not flagged as pooled
+    let output = project.run_pgcrate_ok(&["dba", "connections", "--json"]);
+    let json = parse_json(&output);
+    assert!(json["data"].get("pooler").is_none());
+
+    let output = project.run_pgcrate(&["dba", "pooler"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("No pgbouncer admin console configured"),
+        "{}",
+        stderr(&output)
+    );
+
+    // Postgres itself is not an admin console
+    let output = project.run_pgcrate(&["dba", "pooler", "--admin-url", db.url()]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("SHOW POOLS failed"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_persistence_audit() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok(
+        "CREATE UNLOGGED TABLE cache_entries (id int PRIMARY KEY);
+         CREATE TABLE event_log (at timestamptz, msg text);
+         CREATE TABLE orders_feed (order_id int, status text);
+         CREATE PUBLICATION feed_pub FOR TABLE orders_feed;",
+    );
+
+    let output = project.run_pgcrate(&["dba", "persistence", "--json"]);
+    assert_eq!(output.status.code(), Some(1), "critical in JSON mode");
+    let json = parse_json(&output);
+    assert_eq!(
+        json["schema_id"],
+        serde_json::json!("pgcrate.diagnostics.persistence")
+    );
+    let tables = json["data"]["tables"].as_array().unwrap();
+    let issues = |name: &str| {
+        tables
+            .iter()
+            .find(|t| t["table"] == serde_json::json!(name))
+            .unwrap_or_else(|| panic!("{} missing: {:?}", name, tables))["issues"]
+            .clone()
+    };
+    assert_eq!(issues("cache_entries"), serde_json::json!(["unlogged"]));
+    assert_eq!(issues("event_log"), serde_json::json!(["no_primary_key"]));
+    assert_eq!(
+        issues("orders_feed"),
+        serde_json::json!(["no_primary_key", "no_replica_identity"])
+    );
+    assert_eq!(tables[0]["table"], serde_json::json!("orders_feed"));
+    assert_eq!(
+        json["data"]["overall_status"],
+        serde_json::json!("critical")
+    );
+
+    let output = project.run_pgcrate(&["dba", "triage", "--json"]);
+    let json = parse_json(&output);
+    let check = json["data"]["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == serde_json::json!("persistence"))
+        .expect("persistence check")
+        .clone();
+    assert_eq!(check["status"], serde_json::json!("critical"));
+
+    // A replica identity index makes the publication usable
+    db.run_sql_ok(
+        "CREATE UNIQUE INDEX orders_feed_order_id ON orders_feed (order_id);
+         ALTER TABLE orders_feed ALTER COLUMN order_id SET NOT NULL;
+         ALTER TABLE orders_feed REPLICA IDENTITY USING INDEX orders_feed_order_id;",
+    );
+    let output = project.run_pgcrate(&["dba", "persistence", "--json"]);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "warnings exit 0 in JSON mode"
+    );
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["replica_identity_count"], serde_json::json!(0));
+    assert_eq!(json["data"]["no_primary_key_count"], serde_json::json!(2));
+}
+
+#[test]
+fn test_prepared_transactions() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "prepared", "--older-than", "1h", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(
+        json["schema_id"],
+        serde_json::json!("pgcrate.diagnostics.prepared")
+    );
+    assert!(json["data"]["max_prepared_transactions"].is_i64());
+    assert_eq!(json["data"]["threshold_secs"], serde_json::json!(3600.0));
+    assert!(json["data"]["transactions"].is_array());
+
+    // The fix is gated, and refuses a gid that is not prepared
+    let output = project.run_pgcrate(&["dba", "fix", "prepared", "--rollback", "nope", "--yes"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--read-write and --primary"));
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "prepared",
+        "--rollback",
+        "pgcrate-test-missing",
+        "--yes",
+    ]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("'pgcrate-test-missing' not found"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_nagios_format_status_line() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    let output = project.run_pgcrate(&["dba", "connections", "--format", "nagios"]);
+    let out = stdout(&output);
+    let line = out.trim_end();
+    assert_eq!(line.lines().count(), 1, "one status line: {}", out);
+
+    let expected_code = match line.split(" - ").next() {
+        Some("OK") => 0,
+        Some("WARNING") => 1,
+        Some("CRITICAL") => 2,
+        other => panic!("unexpected status {:?}: {}", other, line),
+    };
+    assert_eq!(output.status.code(), Some(expected_code), "{}", line);
+    assert!(line.contains(" - connections: "), "{}", line);
+    assert!(line.contains(" | "), "should include perfdata: {}", line);
+    assert!(line.contains("stats.usage_pct="), "{}", line);
+}
+
+#[test]
+fn test_nagios_format_unknown_on_failure() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&[
+        "dba",
+        "locks",
+        "--format",
+        "nagios",
+        "-d",
+        "postgres://localhost:1/unreachable",
+    ]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(
+        stdout(&output).starts_with("UNKNOWN - "),
+        "{}",
+        stdout(&output)
+    );
+}
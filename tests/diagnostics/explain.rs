@@ -0,0 +1,114 @@
+//! Plan baseline tests: `dba explain --baseline FILE`.
+
+use crate::common::{parse_json, stdout, TestDatabase, TestProject};
+
+const QUERY: &str = "SELECT * FROM plan_orders WHERE customer_id = 42";
+
+fn setup(db: &TestDatabase) {
+    db.run_sql_ok(
+        "CREATE TABLE plan_orders (id serial PRIMARY KEY, customer_id int NOT NULL);
+         INSERT INTO plan_orders (customer_id) SELECT g % 1000 FROM generate_series(1, 20000) g;
+         CREATE INDEX plan_orders_customer_idx ON plan_orders (customer_id);
+         ANALYZE plan_orders;",
+    );
+}
+
+#[test]
+fn test_explain_baseline_created_then_matches() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+    setup(&db);
+
+    let output =
+        project.run_pgcrate_ok(&["dba", "explain", QUERY, "--baseline", "orders.plan.json"]);
+    assert!(stdout(&output).contains("Recorded plan fingerprint"));
+    assert!(project.file_exists("orders.plan.json"));
+
+    let output = project.run_pgcrate_ok(&[
+        "dba",
+        "explain",
+        QUERY,
+        "--baseline",
+        "orders.plan.json",
+        "--json",
+    ]);
+    let json = parse_json(&output);
+    let baseline = &json["data"]["baseline"];
+    assert_eq!(baseline["status"], "compared");
+    assert_eq!(baseline["plan_changed"], false);
+    assert_eq!(baseline["changes"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_explain_baseline_flags_seq_scan_regression() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+    setup(&db);
+
+    project.run_pgcrate_ok(&["dba", "explain", QUERY, "--baseline", "orders.plan.json"]);
+
+    db.run_sql_ok("DROP INDEX plan_orders_customer_idx");
+
+    let output = project.run_pgcrate(&[
+        "dba",
+        "explain",
+        QUERY,
+        "--baseline",
+        "orders.plan.json",
+        "--json",
+    ]);
+    // JSON mode exits 1 for critical findings
+    assert_eq!(output.status.code(), Some(1), "seq scan should be critical");
+    let json = parse_json(&output);
+    assert_eq!(json["severity"], "critical");
+    let baseline = &json["data"]["baseline"];
+    assert_eq!(baseline["plan_changed"], true);
+    let kinds: Vec<&str> = baseline["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["kind"].as_str().unwrap())
+        .collect();
+    assert!(kinds.contains(&"seq_scan_appeared"), "kinds: {:?}", kinds);
+
+    // Accepting the new plan resets the comparison
+    project.run_pgcrate(&[
+        "dba",
+        "explain",
+        QUERY,
+        "--baseline",
+        "orders.plan.json",
+        "--update-baseline",
+    ]);
+    let output = project.run_pgcrate(&[
+        "dba",
+        "explain",
+        QUERY,
+        "--baseline",
+        "orders.plan.json",
+        "--json",
+    ]);
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["baseline"]["plan_changed"], false);
+}
+
+#[test]
+fn test_explain_baseline_rejects_different_query() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+    setup(&db);
+
+    project.run_pgcrate_ok(&["dba", "explain", QUERY, "--baseline", "orders.plan.json"]);
+    let output = project.run_pgcrate(&[
+        "dba",
+        "explain",
+        "SELECT * FROM plan_orders WHERE id = 1",
+        "--baseline",
+        "orders.plan.json",
+    ]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("different query"));
+}
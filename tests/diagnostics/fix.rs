@@ -1,9 +1,10 @@
 //! Integration tests for fix commands.
 //!
-//! Tests fix sequence, fix index, and fix vacuum commands
-//! including dry-run mode, gate checks, and safety blocks.
+//! Tests fix sequence, fix index, fix vacuum, fix autovacuum, fix partition,
+//! fix xid, fix analyze, and dba apply including dry-run mode, gate checks,
+//! and safety blocks.
 
-use crate::common::{parse_json, stdout, TestDatabase, TestProject};
+use crate::common::{parse_json, stderr, stdout, TestDatabase, TestProject};
 
 // ============================================================================
 // fix sequence
@@ -251,6 +252,326 @@ fn test_fix_index_json_output() {
     assert_eq!(data.get("executed"), Some(&serde_json::json!(false)));
 }
 
+#[test]
+fn test_fix_index_create_builds_valid_index() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok(
+        "CREATE TABLE orders (id serial PRIMARY KEY, status text, created_at timestamptz);",
+    );
+
+    let args = [
+        "--read-write",
+        "--primary",
+        "--json",
+        "dba",
+        "fix",
+        "index",
+        "--create",
+        "public.orders",
+        "--columns",
+        "status,created_at",
+    ];
+
+    // --read-write alone is not enough
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "dba",
+        "fix",
+        "index",
+        "--create",
+        "public.orders",
+        "--columns",
+        "status",
+        "--yes",
+    ]);
+    assert!(!output.status.success(), "Should fail without --primary");
+    assert!(stderr(&output).contains("require --read-write and --primary"));
+    assert_eq!(
+        db.query("SELECT count(*) FROM pg_indexes WHERE tablename = 'orders'"),
+        "1"
+    );
+
+    let output = project.run_pgcrate_ok(&args);
+    let json = parse_json(&output);
+    let data = json.get("data").expect("Should have data field");
+    assert_eq!(data["executed"], serde_json::json!(false));
+    assert_eq!(
+        data["sql"][0],
+        serde_json::json!("CREATE INDEX CONCURRENTLY \"idx_orders_status_created_at\" ON \"public\".\"orders\" (\"status\", \"created_at\");")
+    );
+
+    let mut execute = args.to_vec();
+    execute.push("--yes");
+    let output = project.run_pgcrate_ok(&execute);
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["success"], serde_json::json!(true));
+    assert_eq!(
+        db.query("SELECT indisvalid FROM pg_index WHERE indexrelid = 'idx_orders_status_created_at'::regclass"),
+        "t"
+    );
+
+    // A second run refuses to build the same index again
+    let output = project.run_pgcrate(&execute);
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("already exists"));
+}
+
+#[test]
+fn test_fix_index_create_rejects_unknown_column() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok("CREATE TABLE orders (id serial PRIMARY KEY, status text);");
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "index",
+        "--create",
+        "orders",
+        "--columns",
+        "status,missing_col",
+        "--dry-run",
+    ]);
+
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("missing_col"));
+}
+
+#[test]
+fn test_fix_index_create_replaces_invalid_index() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    // A failed concurrent build leaves an INVALID index behind
+    db.run_sql_ok("CREATE TABLE orders (id serial PRIMARY KEY, status text);");
+    db.run_sql_ok("INSERT INTO orders (status) VALUES ('new'), ('new');");
+    db.run_sql("CREATE UNIQUE INDEX CONCURRENTLY idx_orders_status ON orders (status);");
+    assert_eq!(
+        db.query(
+            "SELECT indisvalid FROM pg_index WHERE indexrelid = 'idx_orders_status'::regclass"
+        ),
+        "f"
+    );
+
+    let output = project.run_pgcrate_ok(&[
+        "--read-write",
+        "--primary",
+        "--json",
+        "dba",
+        "fix",
+        "index",
+        "--create",
+        "orders",
+        "--columns",
+        "status",
+        "--yes",
+    ]);
+
+    let json = parse_json(&output);
+    let data = json.get("data").expect("Should have data field");
+    assert_eq!(data["success"], serde_json::json!(true));
+    assert!(data["sql"][0]
+        .as_str()
+        .unwrap()
+        .starts_with("DROP INDEX CONCURRENTLY"));
+    assert_eq!(
+        db.query(
+            "SELECT indisvalid FROM pg_index WHERE indexrelid = 'idx_orders_status'::regclass"
+        ),
+        "t"
+    );
+}
+
+#[test]
+fn test_fix_index_dedupe_keeps_constraint_backed_indexes() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok(
+        "CREATE TABLE accounts (
+            id serial PRIMARY KEY,
+            email text CONSTRAINT accounts_email_key UNIQUE,
+            org_id int,
+            created_at timestamptz
+        );",
+    );
+    db.run_sql_ok("CREATE UNIQUE INDEX accounts_email_idx ON accounts (email);");
+    db.run_sql_ok("CREATE INDEX accounts_org_idx ON accounts (org_id);");
+    db.run_sql_ok("CREATE INDEX accounts_org_created_idx ON accounts (org_id, created_at);");
+
+    let args = [
+        "--read-write",
+        "--primary",
+        "--json",
+        "dba",
+        "fix",
+        "index",
+        "--dedupe",
+    ];
+
+    // Dropping indexes needs both gates, not just --read-write
+    let output = project.run_pgcrate(&["--read-write", "dba", "fix", "index", "--dedupe", "--yes"]);
+    assert!(!output.status.success(), "Should fail without --primary");
+    assert!(stderr(&output).contains("require --read-write and --primary"));
+    assert_eq!(
+        db.query("SELECT count(*) FROM pg_indexes WHERE tablename = 'accounts'"),
+        "5"
+    );
+
+    let output = project.run_pgcrate_ok(&args);
+    let json = parse_json(&output);
+    let data = json.get("data").expect("Should have data field");
+    assert_eq!(data["executed"], serde_json::json!(false));
+    assert!(data["reclaimable_bytes"].as_i64().unwrap() > 0);
+
+    let plan = data["plan"].as_array().expect("plan should be an array");
+    let find = |name: &str| plan.iter().find(|c| c["index"] == name).cloned();
+    // The standalone unique index goes; the one backing the constraint stays
+    let email = find("accounts_email_idx").expect("duplicate email index in plan");
+    assert_eq!(email["keep"], serde_json::json!("accounts_email_key"));
+    assert!(email.get("blocked_by").is_none());
+    let org = find("accounts_org_idx").expect("prefix index in plan");
+    assert_eq!(org["keep"], serde_json::json!("accounts_org_created_idx"));
+    assert!(find("accounts_email_key").is_none());
+
+    let mut execute = args.to_vec();
+    execute.push("--yes");
+    let output = project.run_pgcrate_ok(&execute);
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["success"], serde_json::json!(true));
+    assert_eq!(
+        db.query(
+            "SELECT string_agg(indexname, ',' ORDER BY indexname) FROM pg_indexes WHERE tablename = 'accounts'"
+        ),
+        "accounts_email_key,accounts_org_created_idx,accounts_pkey"
+    );
+}
+
+#[test]
+fn test_fix_index_dedupe_reports_blocked_duplicates() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    // Two unique constraints on the same column: neither index can be dropped
+    db.run_sql_ok("CREATE TABLE codes (code text CONSTRAINT codes_code_key UNIQUE);");
+    db.run_sql_ok("ALTER TABLE codes ADD CONSTRAINT codes_code_key2 UNIQUE (code);");
+
+    let output = project.run_pgcrate_ok(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "index",
+        "--dedupe",
+        "--yes",
+    ]);
+
+    let out = stdout(&output);
+    assert!(out.contains("KEEP"), "{}", out);
+    assert!(
+        out.contains("No duplicate or redundant indexes to drop"),
+        "{}",
+        out
+    );
+}
+
+// ============================================================================
+// fix analyze
+// ============================================================================
+
+#[test]
+fn test_fix_analyze_all_stale_in_batches() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    for table in ["stale_a", "stale_b", "stale_c"] {
+        db.run_sql_ok(&format!(
+            "CREATE TABLE {table} (id int) WITH (autovacuum_enabled = false);
+             INSERT INTO {table} SELECT generate_series(1, 2000);"
+        ));
+    }
+
+    let args = [
+        "--read-write",
+        "--primary",
+        "--json",
+        "dba",
+        "fix",
+        "analyze",
+        "--all-stale",
+        "--batch-size",
+        "2",
+    ];
+
+    let output = project.run_pgcrate_ok(&args);
+    let json = parse_json(&output);
+    let data = json.get("data").expect("Should have data field");
+    assert_eq!(data["executed"], serde_json::json!(false));
+    assert_eq!(data["tables"].as_array().unwrap().len(), 3, "{}", json);
+    let sql = data["sql"].as_array().unwrap();
+    assert_eq!(sql.len(), 2, "3 tables in batches of 2: {}", json);
+    assert!(sql[0].as_str().unwrap().contains(", "));
+
+    let mut execute = args.to_vec();
+    execute.push("--yes");
+    let output = project.run_pgcrate_ok(&execute);
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["success"], serde_json::json!(true));
+    assert_eq!(
+        db.query(
+            "SELECT count(*) FROM pg_stat_user_tables WHERE relname LIKE 'stale_%' AND last_analyze IS NOT NULL"
+        ),
+        "3"
+    );
+
+    // Nothing left to do
+    let output = project.run_pgcrate_ok(&args);
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["sql"], serde_json::json!([]));
+}
+
+#[test]
+fn test_fix_analyze_single_table() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok("CREATE TABLE \"Order Items\" (id int);");
+
+    let output = project.run_pgcrate_ok(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "analyze",
+        "public.Order Items",
+        "--dry-run",
+    ]);
+    assert!(stdout(&output).contains("ANALYZE \"public\".\"Order Items\";"));
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "analyze",
+        "public.missing",
+    ]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("not found"));
+}
+
 // ============================================================================
 // fix vacuum
 // ============================================================================
@@ -342,6 +663,366 @@ fn test_fix_vacuum_json_output() {
     assert_eq!(data.get("executed"), Some(&serde_json::json!(false)));
 }
 
+// ============================================================================
+// fix autovacuum
+// ============================================================================
+
+#[test]
+fn test_fix_autovacuum_sets_scale_factor() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("CREATE TABLE events (id serial PRIMARY KEY);");
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "autovacuum",
+        "public.events",
+        "--scale-factor",
+        "0.02",
+        "--threshold",
+        "1000",
+        "--dry-run",
+    ]);
+    assert!(output.status.success());
+    let out = stdout(&output);
+    assert!(out.contains("DRY RUN"), "Should indicate dry run mode");
+    assert!(
+        out.contains("ALTER TABLE \"public\".\"events\" SET (autovacuum_vacuum_scale_factor = 0.02, autovacuum_vacuum_threshold = 1000);"),
+        "Should show ALTER TABLE SQL: {}",
+        out
+    );
+    assert_eq!(
+        db.query("SELECT reloptions FROM pg_class WHERE relname = 'events'"),
+        ""
+    );
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "autovacuum",
+        "public.events",
+        "--scale-factor",
+        "0.02",
+        "--yes",
+    ]);
+    assert!(
+        output.status.success(),
+        "Fix should succeed: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        db.query("SELECT reloptions FROM pg_class WHERE relname = 'events'"),
+        "{autovacuum_vacuum_scale_factor=0.02}"
+    );
+
+    let output = project.run_pgcrate(&["dba", "autovacuum", "--table", "public.events", "--json"]);
+    let json = parse_json(&output);
+    let table = &json["data"]["tables"][0];
+    assert_eq!(table["scale_factor"], serde_json::json!(0.02));
+    assert_eq!(table["per_table_settings"], serde_json::json!(true));
+}
+
+#[test]
+fn test_fix_autovacuum_without_recommendation_needs_scale_factor() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("CREATE TABLE events (id serial PRIMARY KEY);");
+
+    // A small, idle table gets no proposal
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "autovacuum",
+        "public.events",
+        "--dry-run",
+    ]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--scale-factor"),
+        "Should suggest --scale-factor: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_fix_autovacuum_requires_both_gates() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("CREATE TABLE events (id serial PRIMARY KEY);");
+
+    // --read-write alone is not enough
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "dba",
+        "fix",
+        "autovacuum",
+        "public.events",
+        "--scale-factor",
+        "0.02",
+        "--yes",
+    ]);
+    assert!(!output.status.success(), "Should fail without --primary");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--primary"),
+        "Should name the missing gate: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(
+        db.query("SELECT reloptions FROM pg_class WHERE relname = 'events'"),
+        ""
+    );
+}
+
+// ============================================================================
+// fix partition
+// ============================================================================
+
+/// Monthly range-partitioned table whose partitions all ended in 2024
+fn create_stale_partitioned_events(db: &TestDatabase) {
+    db.run_sql_ok(
+        "CREATE TABLE events (id int, created_at date) PARTITION BY RANGE (created_at);
+         CREATE TABLE events_2024_01 PARTITION OF events FOR VALUES FROM ('2024-01-01') TO ('2024-02-01');
+         CREATE TABLE events_2024_02 PARTITION OF events FOR VALUES FROM ('2024-02-01') TO ('2024-03-01');",
+    );
+}
+
+#[test]
+fn test_fix_partition_creates_ahead_and_drops_expired() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    create_stale_partitioned_events(&db);
+
+    let args = [
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "partition",
+        "--table",
+        "public.events",
+        "--create-ahead",
+        "2",
+        "--retain",
+        "12",
+        "--drop",
+    ];
+    let output = project.run_pgcrate(&args);
+    assert!(output.status.success());
+    let out = stdout(&output);
+    assert!(
+        out.contains("DRY RUN"),
+        "Should default to dry run: {}",
+        out
+    );
+    assert!(
+        out.contains("PARTITION OF \"public\".\"events\""),
+        "{}",
+        out
+    );
+    assert!(
+        out.contains("DETACH PARTITION \"public\".\"events_2024_01\""),
+        "{}",
+        out
+    );
+    assert!(
+        out.contains("DROP TABLE \"public\".\"events_2024_02\""),
+        "{}",
+        out
+    );
+    assert_eq!(
+        db.query("SELECT count(*) FROM pg_inherits WHERE inhparent = 'events'::regclass"),
+        "2"
+    );
+
+    let mut confirmed = args.to_vec();
+    confirmed.extend(["--yes", "--json"]);
+    let output = project.run_pgcrate(&confirmed);
+    let json = parse_json(&output);
+    assert_eq!(json["schema_id"], "pgcrate.fix.partition");
+    assert_eq!(json["data"]["executed"], true);
+    assert_eq!(json["data"]["success"], true);
+
+    // Current month plus two ahead; the 2024 partitions are gone
+    assert_eq!(
+        db.query("SELECT count(*) FROM pg_inherits WHERE inhparent = 'events'::regclass"),
+        "3"
+    );
+    assert_eq!(
+        db.query("SELECT to_regclass('events_2024_01') IS NULL"),
+        "t"
+    );
+    db.run_sql_ok("INSERT INTO events VALUES (1, current_date)");
+
+    let output = project.run_pgcrate(&["dba", "partitions", "--table", "public.events", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(json["severity"], "healthy");
+
+    // Nothing left to do
+    let output = project.run_pgcrate(&confirmed);
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["sql"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_fix_partition_rolls_back_on_default_rows() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    create_stale_partitioned_events(&db);
+    db.run_sql_ok("CREATE TABLE events_default PARTITION OF events DEFAULT");
+    db.run_sql_ok("INSERT INTO events VALUES (1, current_date)");
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "partition",
+        "--table",
+        "public.events",
+        "--yes",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    assert!(out.contains("rolled back"), "{}", out);
+    // The later partitions were created in the same transaction and undone
+    assert_eq!(
+        db.query("SELECT count(*) FROM pg_inherits WHERE inhparent = 'events'::regclass"),
+        "3"
+    );
+}
+
+#[test]
+fn test_fix_partition_requires_range_time_key() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok("CREATE TABLE plain (id int)");
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "partition",
+        "--table",
+        "public.plain",
+        "--dry-run",
+    ]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("not a partitioned table"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+// ============================================================================
+// fix xid
+// ============================================================================
+
+#[test]
+fn test_fix_xid_plans_then_freezes_oldest_tables() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok("CREATE TABLE old_events (id int); INSERT INTO old_events VALUES (1);");
+    // Burn a few XIDs so old_events is older than the table created next
+    for _ in 0..3 {
+        db.run_sql_ok("SELECT txid_current()");
+    }
+    db.run_sql_ok("CREATE TABLE new_events (id int); INSERT INTO new_events VALUES (1);");
+
+    let args = [
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "xid",
+        "--min-age",
+        "0",
+        "--limit",
+        "2",
+    ];
+    let output = project.run_pgcrate(&args);
+    assert!(output.status.success());
+    let out = stdout(&output);
+    assert!(out.contains("DRY RUN"), "{}", out);
+    assert!(
+        out.contains("VACUUM (FREEZE, VERBOSE) \"public\".\"old_events\";"),
+        "{}",
+        out
+    );
+
+    let mut confirmed = args.to_vec();
+    confirmed.extend(["--yes", "--json"]);
+    let output = project.run_pgcrate(&confirmed);
+    let json = parse_json(&output);
+    assert_eq!(json["schema_id"], "pgcrate.fix.xid");
+    assert_eq!(json["data"]["executed"], true);
+    assert_eq!(json["data"]["success"], true);
+    let plan = json["data"]["plan"].as_array().unwrap();
+    assert_eq!(plan.len(), 2);
+    assert_eq!(plan[0]["table"], "old_events");
+    assert_eq!(plan[0]["outcome"], "frozen");
+    assert!(
+        plan[0]["xid_age_after"].as_i64().unwrap() < plan[0]["xid_age"].as_i64().unwrap(),
+        "{}",
+        plan[0]
+    );
+}
+
+#[test]
+fn test_fix_xid_skips_work_outside_window() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok("CREATE TABLE events (id int)");
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "fix",
+        "xid",
+        "--min-age",
+        "0",
+        "--window",
+        "0s",
+        "--yes",
+        "--json",
+    ]);
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["success"], true);
+    assert_eq!(json["data"]["window_secs"], 0);
+    let plan = json["data"]["plan"].as_array().unwrap();
+    assert!(!plan.is_empty());
+    assert!(plan.iter().all(|s| s["outcome"] == "skipped"));
+}
+
 // ============================================================================
 // vacuum diagnostic
 // ============================================================================
@@ -403,6 +1084,194 @@ fn test_triage_include_fixes_json() {
     );
 }
 
+#[test]
+fn test_triage_surfaces_stale_stats_with_analyze_action() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok(
+        "CREATE TABLE events (id int) WITH (autovacuum_enabled = false);
+         INSERT INTO events SELECT generate_series(1, 2000);",
+    );
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "triage",
+        "--include-fixes",
+        "--json",
+    ]);
+    let json = parse_json(&output);
+    let data = json.get("data").expect("Should have data field");
+
+    let check = data["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == "table_stats")
+        .expect("table_stats check present");
+    assert_eq!(check["status"], serde_json::json!("warning"));
+    assert!(check["summary"]
+        .as_str()
+        .unwrap()
+        .contains("1 never analyzed"));
+
+    let action = data["actions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["action_id"] == "fix.analyze.all_stale")
+        .expect("analyze action present");
+    assert!(action["sql_preview"][0]
+        .as_str()
+        .unwrap()
+        .contains("\"public\".\"events\""));
+}
+
+// ============================================================================
+// apply
+// ============================================================================
+
+#[test]
+fn test_apply_plan_from_triage() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok(
+        "CREATE TABLE events (id int) WITH (autovacuum_enabled = false);
+         INSERT INTO events SELECT generate_series(1, 2000);",
+    );
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "triage",
+        "--include-fixes",
+        "--json",
+    ]);
+    std::fs::write(project.path("plan.json"), &output.stdout).unwrap();
+
+    let args = [
+        "--read-write",
+        "--primary",
+        "--json",
+        "dba",
+        "apply",
+        "--plan",
+        "plan.json",
+        "--only",
+        "fix.analyze.all_stale",
+    ];
+
+    // Without --yes every selected action is a dry run
+    let output = project.run_pgcrate_ok(&args);
+    let json = parse_json(&output);
+    let data = json.get("data").expect("Should have data field");
+    assert_eq!(data["dry_run"], serde_json::json!(true));
+    let actions = data["actions"].as_array().unwrap();
+    let analyze = actions
+        .iter()
+        .find(|a| a["action_id"] == "fix.analyze.all_stale")
+        .unwrap();
+    assert_eq!(analyze["status"], serde_json::json!("planned"), "{}", json);
+    assert!(actions
+        .iter()
+        .filter(|a| a["action_id"] != "fix.analyze.all_stale")
+        .all(|a| a["status"] == "skipped"));
+    assert_eq!(
+        db.query("SELECT count(*) FROM pg_stat_user_tables WHERE relname = 'events' AND last_analyze IS NOT NULL"),
+        "0"
+    );
+
+    let mut execute = args.to_vec();
+    execute.push("--yes");
+    let output = project.run_pgcrate_ok(&execute);
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["applied"], serde_json::json!(1), "{}", json);
+    assert_eq!(json["data"]["failed"], serde_json::json!(0));
+    assert_eq!(
+        db.query("SELECT count(*) FROM pg_stat_user_tables WHERE relname = 'events' AND last_analyze IS NOT NULL"),
+        "1"
+    );
+}
+
+#[test]
+fn test_apply_rejects_unknown_action_id() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    std::fs::write(project.path("plan.json"), r#"{"actions": []}"#).unwrap();
+
+    let output = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "apply",
+        "--plan",
+        "plan.json",
+        "--only",
+        "fix.missing",
+    ]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Unknown action id(s) in --only: fix.missing"));
+}
+
+#[test]
+fn test_apply_ignores_plan_risk_for_sql() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok("CREATE TABLE victims (id int);");
+    std::fs::write(
+        project.path("plan.json"),
+        r#"{"actions": [
+            {"action_id": "sql.low", "action_type": "fix", "args": ["sql"],
+             "risk": "low", "gates": {}, "sql_preview": ["DROP TABLE victims"]},
+            {"action_id": "diag.sql", "action_type": "diagnostic", "args": ["sql"],
+             "risk": "low", "gates": {}, "sql_preview": ["DROP TABLE victims"]}
+        ]}"#,
+    )
+    .unwrap();
+
+    let args = [
+        "--read-write",
+        "--primary",
+        "--json",
+        "dba",
+        "apply",
+        "--plan",
+        "plan.json",
+        "--yes",
+    ];
+
+    // SQL actions need approval whatever risk the plan declares
+    let output = project.run_pgcrate_ok(&args);
+    let json = parse_json(&output);
+    assert_eq!(json["data"]["skipped"], serde_json::json!(2), "{}", json);
+
+    // Only fix actions may run their SQL preview
+    let mut only = args.to_vec();
+    only.extend(["--only", "diag.sql"]);
+    let output = project.run_pgcrate(&only);
+    assert!(!output.status.success());
+    let json = parse_json(&output);
+    assert!(json["data"]["actions"][1]["summary"]
+        .as_str()
+        .unwrap()
+        .contains("non-fix action"));
+
+    assert_eq!(
+        db.query("SELECT count(*) FROM pg_tables WHERE tablename = 'victims'"),
+        "1"
+    );
+}
+
 // ============================================================================
 // Execution tests (actually run fixes)
 // ============================================================================
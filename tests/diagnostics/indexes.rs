@@ -2,7 +2,9 @@
 //!
 //! Tests verify that `pgcrate indexes` correctly detects:
 //! - Duplicate indexes (same columns, same order)
-//! - Missing FK indexes (foreign keys without supporting index)
+//! - Redundant indexes (leading prefix of another index)
+//! - Missing FK indexes (foreign keys without supporting index), with parent
+//!   activity and CREATE INDEX CONCURRENTLY actions
 //!
 //! Note: Unused index detection is not tested here because it requires
 //! accumulated pg_stat_user_indexes data, which is flaky in test environments.
@@ -170,6 +172,83 @@ fn test_indexes_no_missing_when_fk_has_index() {
     }
 }
 
+#[test]
+fn test_indexes_fk_missing_impact_and_actions() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    db.run_sql_ok("CREATE TABLE fk_accounts (id SERIAL PRIMARY KEY)");
+    db.run_sql_ok(
+        "CREATE TABLE fk_events (
+            id SERIAL PRIMARY KEY,
+            account_id INTEGER REFERENCES fk_accounts(id) ON DELETE CASCADE
+        )",
+    );
+    // A partial index does not support the FK
+    db.run_sql_ok("CREATE INDEX fk_events_recent_idx ON fk_events(account_id) WHERE id > 1000");
+    db.run_sql_ok(
+        "INSERT INTO fk_accounts SELECT FROM generate_series(1, 20);
+         INSERT INTO fk_events (account_id) SELECT (g % 10) + 1 FROM generate_series(1, 20000) g;
+         DELETE FROM fk_accounts WHERE id = 20;",
+    );
+
+    let output = project.run_pgcrate(&[
+        "dba",
+        "indexes",
+        "--fk-missing",
+        "--include-actions",
+        "--json",
+    ]);
+    let json = parse_json(&output);
+    let data = &json["data"];
+    assert_eq!(data["missing"], serde_json::json!([]));
+    assert_eq!(data["duplicates"], serde_json::json!([]));
+
+    let fk = data["fk_without_indexes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|fk| fk["table"] == "fk_events")
+        .expect("fk_events FK should be flagged despite the partial index");
+    assert_eq!(fk["parent_deletes"], serde_json::json!(1));
+    assert_eq!(fk["on_delete"], serde_json::json!("cascade"));
+    assert_eq!(fk["status"], serde_json::json!("warning"));
+    let sql = fk["sql"].as_str().unwrap();
+    assert_eq!(
+        sql,
+        "CREATE INDEX CONCURRENTLY \"idx_fk_events_account_id_fk\" ON \"public\".\"fk_events\" (\"account_id\");"
+    );
+
+    let actions = data["actions"].as_array().expect("actions array");
+    let action = actions
+        .iter()
+        .find(|a| a["sql_preview"][0] == sql)
+        .expect("action for the FK index");
+    assert_eq!(action["mutates"], serde_json::json!(true));
+
+    // The action's command creates the index and clears the finding
+    let args: Vec<String> = action["args"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a.as_str().unwrap().to_string())
+        .collect();
+    let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    project.run_pgcrate_ok(&args);
+
+    let output = project.run_pgcrate(&["dba", "indexes", "--fk-missing", "--json"]);
+    let json = parse_json(&output);
+    let flagged = json["data"]["fk_without_indexes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|fk| fk["table"] == "fk_events");
+    assert!(!flagged, "FK should be covered after creating the index");
+}
+
 // ============================================================================
 // JSON structure
 // ============================================================================
@@ -238,6 +317,50 @@ fn test_indexes_respects_missing_limit() {
     }
 }
 
+// ============================================================================
+// Redundant index detection
+// ============================================================================
+
+#[test]
+fn test_indexes_detects_prefix_redundant_index() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok(
+        "CREATE TABLE prefix_test (
+            id SERIAL PRIMARY KEY,
+            user_id INTEGER,
+            created_at TIMESTAMPTZ
+        )",
+    );
+    db.run_sql_ok("CREATE INDEX prefix_test_user_idx ON prefix_test(user_id)");
+    db.run_sql_ok("CREATE INDEX prefix_test_user_created_idx ON prefix_test(user_id, created_at)");
+    // Unique and reordered indexes are not redundant
+    db.run_sql_ok("CREATE UNIQUE INDEX prefix_test_created_uniq ON prefix_test(created_at)");
+    db.run_sql_ok("CREATE INDEX prefix_test_created_user_idx ON prefix_test(created_at, user_id)");
+
+    let output = project.run_pgcrate(&["dba", "indexes", "--json"]);
+    let json = parse_json(&output);
+    let redundant = json["data"]["redundant"]
+        .as_array()
+        .expect("data.redundant should be an array");
+
+    let names: Vec<&str> = redundant
+        .iter()
+        .filter_map(|r| r["index"].as_str())
+        .collect();
+    assert_eq!(names, vec!["prefix_test_user_idx"], "{}", json);
+    assert_eq!(
+        redundant[0]["covered_by"],
+        serde_json::json!("prefix_test_user_created_idx")
+    );
+    assert_eq!(
+        redundant[0]["covered_by_columns"],
+        serde_json::json!("user_id, created_at")
+    );
+}
+
 // ============================================================================
 // Primary key handling
 // ============================================================================
@@ -4,6 +4,7 @@
 //! - Long-running transactions
 //! - Idle-in-transaction sessions
 //! - Blocking lock chains
+//! - Active sessions in the `dba top` monitor
 //!
 //! These tests use background psql processes to create real lock scenarios.
 
@@ -379,3 +380,212 @@ fn test_locks_json_with_blocking_flag() {
     // Verify it's valid JSON
     let _ = parse_json(&output);
 }
+
+// ============================================================================
+// Kill idle-in-transaction sessions
+// ============================================================================
+
+#[test]
+fn test_locks_kill_idle_dry_run_then_execute() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    use std::io::Write;
+    let app = format!("reap-{}", std::process::id());
+    let mut session = Command::new("psql")
+        .args([db.url()])
+        .env("PGAPPNAME", &app)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn psql");
+    {
+        let stdin = session.stdin.as_mut().expect("Failed to get stdin");
+        writeln!(stdin, "BEGIN;").unwrap();
+        writeln!(stdin, "SELECT 1;").unwrap();
+        stdin.flush().unwrap();
+    }
+    thread::sleep(Duration::from_millis(1500));
+
+    let pattern = format!("{}*", &app[..app.len() - 1]);
+    let dry_run = project.run_pgcrate(&[
+        "dba",
+        "locks",
+        "--kill-idle",
+        "1s",
+        "--application",
+        &pattern,
+        "--json",
+    ]);
+    let without_flags = project.run_pgcrate(&[
+        "dba",
+        "locks",
+        "--kill-idle",
+        "1s",
+        "--application",
+        &pattern,
+        "--execute",
+    ]);
+    let executed = project.run_pgcrate(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "locks",
+        "--kill-idle",
+        "1s",
+        "--application",
+        &pattern,
+        "--execute",
+        "--json",
+    ]);
+
+    let _ = session.kill();
+    let _ = session.wait();
+
+    let json = parse_json(&dry_run);
+    assert_eq!(json["schema_id"], "pgcrate.diagnostics.kill_idle");
+    assert_eq!(json["data"]["executed"], false);
+    let sessions = json["data"]["sessions"].as_array().unwrap();
+    assert_eq!(sessions.len(), 1, "dry run: {}", json);
+    assert_eq!(sessions[0]["application_name"], app.as_str());
+
+    assert!(!without_flags.status.success());
+    assert!(stderr(&without_flags).contains("requires --read-write and --primary"));
+
+    let json = parse_json(&executed);
+    assert_eq!(json["data"]["executed"], true);
+    assert_eq!(json["data"]["terminated"], 1, "execute: {}", json);
+    assert_eq!(json["data"]["sessions"][0]["terminated"], true);
+    assert!(json["data"]["skipped"].is_null(), "execute: {}", json);
+}
+
+// ============================================================================
+// Wait-for graph
+// ============================================================================
+
+#[test]
+fn test_locks_graph_formats_show_waiter_edge() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("INSERT INTO users (email, name) VALUES ('graph@test.com', 'Graph Test')");
+
+    use std::io::Write;
+    let mut holder = spawn_interactive_psql(db.url());
+    {
+        let stdin = holder.stdin.as_mut().expect("Failed to get stdin");
+        writeln!(stdin, "BEGIN;").unwrap();
+        writeln!(
+            stdin,
+            "SELECT * FROM users WHERE email = 'graph@test.com' FOR UPDATE;"
+        )
+        .unwrap();
+        stdin.flush().unwrap();
+    }
+    thread::sleep(Duration::from_millis(300));
+
+    let mut waiter = spawn_interactive_psql(db.url());
+    {
+        let stdin = waiter.stdin.as_mut().expect("Failed to get stdin");
+        writeln!(stdin, "BEGIN;").unwrap();
+        writeln!(
+            stdin,
+            "SELECT * FROM users WHERE email = 'graph@test.com' FOR UPDATE;"
+        )
+        .unwrap();
+        stdin.flush().unwrap();
+    }
+    thread::sleep(Duration::from_millis(500));
+
+    let dot = project.run_pgcrate(&["dba", "locks", "--blocking", "--format", "dot"]);
+    let mermaid = project.run_pgcrate(&["dba", "locks", "--blocking", "--format", "mermaid"]);
+    let graph = project.run_pgcrate(&["dba", "locks", "--blocking", "--format", "json-graph"]);
+
+    let _ = holder.kill();
+    let _ = holder.wait();
+    let _ = waiter.kill();
+    let _ = waiter.wait();
+
+    let out = stdout(&dot);
+    assert!(out.starts_with("digraph locks {"), "dot output: {}", out);
+    assert!(
+        out.contains(" -> "),
+        "dot output should have an edge: {}",
+        out
+    );
+
+    let out = stdout(&mermaid);
+    assert!(out.starts_with("graph LR"), "mermaid output: {}", out);
+    assert!(
+        out.contains("-->"),
+        "mermaid output should have an edge: {}",
+        out
+    );
+
+    let json = parse_json(&graph);
+    assert_eq!(json["schema_id"], "pgcrate.diagnostics.locks_graph");
+    let edges = json["data"]["edges"].as_array().unwrap();
+    assert!(!edges.is_empty(), "expected an edge: {}", json);
+    // pg_stat_activity is cluster-wide, so parallel tests may add roots
+    assert!(!json["data"]["roots"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_graph_format_rejected_outside_locks() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "xid", "--format", "dot"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("only supported by `dba locks --blocking`"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+// ============================================================================
+// dba top
+// ============================================================================
+
+#[test]
+fn test_top_once_shows_active_session() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let mut session = spawn_psql_session(db.url(), "SELECT pg_sleep(5), 'top_probe_secret'");
+    thread::sleep(Duration::from_millis(300));
+
+    let output = project.run_pgcrate(&["dba", "top", "--once", "--interval", "1"]);
+
+    let _ = session.kill();
+    let _ = session.wait();
+
+    let out = stdout(&output);
+    assert!(
+        output.status.success(),
+        "top --once should succeed: {}",
+        stderr(&output)
+    );
+    assert!(out.contains("TPS:"), "{}", out);
+    assert!(out.contains("Connections:"), "{}", out);
+    assert!(out.contains("pg_sleep(5)"), "{}", out);
+    // Query text is redacted by default
+    assert!(!out.contains("top_probe_secret"), "{}", out);
+}
+
+#[test]
+fn test_top_rejects_json() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "top", "--once", "--json"]);
+    assert!(!output.status.success());
+}
@@ -1,7 +1,12 @@
 //! Integration tests for new diagnostic commands:
 //! - stats-age: Tables with stale statistics
 //! - checkpoints: Checkpoint frequency and health
+//! - wal: WAL rate, max_wal_size pressure, and archiving
+//! - io: Buffer I/O by backend type and context
+//! - temp: Temp file usage and work_mem spills
+//! - autovacuum: Per-table autovacuum tuning
 //! - autovacuum-progress: Currently running autovacuum
+//! - vacuum --progress: Running VACUUM / VACUUM FULL / CLUSTER with ETA
 //! - config: PostgreSQL configuration review
 
 use crate::common::{parse_json, stdout, TestDatabase, TestProject};
@@ -114,6 +119,37 @@ fn test_stats_age_respects_limit() {
     }
 }
 
+#[test]
+fn test_stats_age_flags_never_analyzed_and_modified_tables() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    // Autovacuum off so nothing analyzes behind the test's back
+    for table in ["never_analyzed", "heavily_modified", "fresh_stats"] {
+        db.run_sql_ok(&format!(
+            "CREATE TABLE {table} (id int, v int) WITH (autovacuum_enabled = false);
+             INSERT INTO {table} SELECT g, 0 FROM generate_series(1, 2000) g;"
+        ));
+    }
+    db.run_sql_ok("ANALYZE heavily_modified; ANALYZE fresh_stats;");
+    db.run_sql_ok("UPDATE heavily_modified SET v = 1 WHERE id <= 1500;");
+
+    let output = project.run_pgcrate(&["dba", "stats-age", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(json["severity"], serde_json::json!("critical"));
+    let tables = json["data"]["tables"].as_array().expect("data.tables");
+    let find = |name: &str| tables.iter().find(|t| t["table"] == name).cloned();
+
+    let never = find("never_analyzed").expect("never analyzed table listed");
+    assert_eq!(never["reason"], serde_json::json!("never_analyzed"));
+    let modified = find("heavily_modified").expect("modified table listed");
+    assert_eq!(modified["reason"], serde_json::json!("modified"));
+    assert_eq!(modified["status"], serde_json::json!("critical"));
+    assert_eq!(modified["n_mod_since_analyze"], serde_json::json!(1500));
+    assert!(find("fresh_stats").is_none(), "{}", json);
+}
+
 // ============================================================================
 // checkpoints
 // ============================================================================
@@ -189,6 +225,223 @@ fn test_checkpoints_shows_metrics() {
     );
 }
 
+// ============================================================================
+// wal
+// ============================================================================
+
+#[test]
+fn test_wal_json_structure() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "wal", "--sample", "1", "--json"]);
+    assert!(
+        output.status.code().unwrap_or(99) <= 2,
+        "wal should return valid exit code"
+    );
+
+    let json = parse_json(&output);
+    assert_eq!(
+        json.get("schema_id").and_then(|s| s.as_str()),
+        Some("pgcrate.diagnostics.wal")
+    );
+    let data = json.get("data").expect("Should have data field");
+    assert!(
+        data["settings"]["max_wal_size_bytes"].as_i64().unwrap_or(0) > 0,
+        "Should report max_wal_size: {}",
+        json
+    );
+    assert!(data["checkpoints"]["timed"].is_i64(), "{}", json);
+    assert!(data["sampled_bytes_per_sec"].is_number(), "{}", json);
+    assert!(data["warnings"].is_array(), "{}", json);
+}
+
+#[test]
+fn test_wal_shows_sections() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "wal"]);
+    let out = stdout(&output);
+    for section in [
+        "WAL Generation:",
+        "Checkpoints:",
+        "max_wal_size:",
+        "Archiving:",
+    ] {
+        assert!(out.contains(section), "Should show {}: {}", section, out);
+    }
+}
+
+// ============================================================================
+// io
+// ============================================================================
+
+#[test]
+fn test_io_json_structure() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    let output = project.run_pgcrate(&["dba", "io", "--json"]);
+    assert!(
+        output.status.code().unwrap_or(99) <= 2,
+        "io should return valid exit code"
+    );
+    let json = parse_json(&output);
+    assert_eq!(
+        json.get("schema_id").and_then(|v| v.as_str()),
+        Some("pgcrate.diagnostics.io")
+    );
+    let data = &json["data"];
+    let version = data["server_version_num"].as_i64().unwrap();
+    let expected_source = if version >= 160000 {
+        "pg_stat_io"
+    } else {
+        "pg_statio"
+    };
+    assert_eq!(data["source"], expected_source, "{}", json);
+
+    let rows = data["rows"].as_array().expect("rows array");
+    assert!(
+        rows.iter().any(|r| r["backend_type"] == "client backend"),
+        "client backends always do I/O: {}",
+        json
+    );
+    let summary = &data["summary"];
+    assert!(summary["hits"].as_i64().unwrap_or(0) > 0, "{}", json);
+    assert!(summary["profile"].is_string());
+    assert!(summary["checkpoint_bound"].is_boolean());
+    assert!(data["findings"].is_array());
+}
+
+#[test]
+fn test_io_human_output() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "io"]);
+    let out = stdout(&output);
+    assert!(out.contains("Workload:"), "{}", out);
+    assert!(out.contains("Written by:"), "{}", out);
+    assert!(out.contains("BACKEND"), "{}", out);
+}
+
+// ============================================================================
+// temp
+// ============================================================================
+
+#[test]
+fn test_temp_reports_database_spills() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    // A sort far larger than 64kB of work_mem spills to disk
+    db.run_sql_ok(
+        "SET work_mem = '64kB'; SELECT count(*) FROM (SELECT g FROM generate_series(1, 200000) g ORDER BY g DESC) s;",
+    );
+
+    let output = project.run_pgcrate(&["dba", "temp", "--json"]);
+    assert!(
+        output.status.code().unwrap_or(99) <= 2,
+        "temp should return valid exit code"
+    );
+    let json = parse_json(&output);
+    assert_eq!(
+        json.get("schema_id").and_then(|v| v.as_str()),
+        Some("pgcrate.diagnostics.temp")
+    );
+    let data = &json["data"];
+    assert!(data["settings"]["work_mem_bytes"].as_i64().unwrap_or(0) > 0);
+    assert!(data["statements_available"].is_boolean());
+
+    let databases = data["databases"].as_array().expect("databases array");
+    let current = databases
+        .iter()
+        .find(|d| d["current"] == true)
+        .expect("current database should have temp files");
+    assert!(current["temp_files"].as_i64().unwrap_or(0) > 0);
+    assert!(current["temp_bytes"].as_i64().unwrap_or(0) > 0);
+}
+
+#[test]
+fn test_temp_shows_sections() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    let output = project.run_pgcrate(&["dba", "temp"]);
+    let out = stdout(&output);
+    assert!(out.contains("work_mem:"), "{}", out);
+    assert!(out.contains("By database:"), "{}", out);
+    assert!(out.contains("By query:"), "{}", out);
+}
+
+// ============================================================================
+// autovacuum
+// ============================================================================
+
+#[test]
+fn test_autovacuum_json_structure() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok(
+        "CREATE TABLE events (id serial PRIMARY KEY) WITH (autovacuum_vacuum_scale_factor = 0.05, autovacuum_vacuum_threshold = 500);",
+    );
+
+    let output = project.run_pgcrate(&["dba", "autovacuum", "--json"]);
+    assert!(
+        output.status.code().unwrap_or(99) <= 2,
+        "autovacuum should return valid exit code"
+    );
+    let json = parse_json(&output);
+    assert_eq!(
+        json.get("schema_id").and_then(|v| v.as_str()),
+        Some("pgcrate.diagnostics.autovacuum")
+    );
+    let data = &json["data"];
+    assert!(data["defaults"]["scale_factor"].as_f64().is_some());
+
+    // Tables with their own settings are listed even when small
+    let tables = data["tables"].as_array().expect("tables array");
+    let events = tables
+        .iter()
+        .find(|t| t["table"] == "events")
+        .expect("events should be listed");
+    assert_eq!(events["scale_factor"], serde_json::json!(0.05));
+    assert_eq!(events["threshold"], serde_json::json!(500));
+    assert_eq!(events["trigger_tuples"], serde_json::json!(500));
+    assert_eq!(events["per_table_settings"], serde_json::json!(true));
+}
+
+#[test]
+fn test_autovacuum_human_output() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+    db.run_sql_ok("CREATE TABLE events (id serial PRIMARY KEY) WITH (autovacuum_enabled = false);");
+
+    let output = project.run_pgcrate(&["dba", "autovacuum", "--table", "public.events"]);
+    let out = stdout(&output);
+    assert!(out.contains("AUTOVACUUM TUNING"), "{}", out);
+    assert!(out.contains("public.events"), "{}", out);
+    assert!(out.contains("off"), "Should show autovacuum off: {}", out);
+}
+
 // ============================================================================
 // autovacuum-progress
 // ============================================================================
@@ -250,6 +503,42 @@ fn test_autovacuum_progress_json_structure() {
     );
 }
 
+#[test]
+fn test_vacuum_progress_json_structure() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok("CREATE TABLE events (id serial PRIMARY KEY);");
+
+    let output = project.run_pgcrate_ok(&[
+        "dba",
+        "vacuum",
+        "--progress",
+        "--table",
+        "public.events",
+        "--json",
+    ]);
+
+    let json = parse_json(&output);
+    assert_eq!(
+        json.get("schema_id").and_then(|s| s.as_str()),
+        Some("pgcrate.diagnostics.vacuum_progress")
+    );
+    assert_eq!(
+        json.get("severity").and_then(|s| s.as_str()),
+        Some("healthy")
+    );
+
+    // Nothing is vacuuming the fresh table
+    let data = json.get("data").expect("Should have data field");
+    assert_eq!(data["operations"], serde_json::json!([]), "{}", json);
+    assert_eq!(data["count"], serde_json::json!(0));
+
+    let output = project.run_pgcrate_ok(&["dba", "vacuum", "--progress"]);
+    assert!(stdout(&output).contains("No VACUUM"));
+}
+
 #[test]
 fn test_autovacuum_progress_empty_is_ok() {
     skip_if_no_db!();
@@ -1,8 +1,12 @@
 mod basic;
 mod bloat;
+mod explain;
 mod fix;
 mod indexes;
 mod locks;
 mod maintenance;
+mod partitions;
 mod replication;
 mod sequences_scenarios;
+mod statements;
+mod trend;
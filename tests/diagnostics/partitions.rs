@@ -0,0 +1,128 @@
+//! Partitioned table health tests: `dba partitions`.
+
+use crate::common::{parse_json, stdout, TestDatabase, TestProject};
+
+/// Monthly range-partitioned table whose partitions all ended in 2024
+fn create_stale_events(db: &TestDatabase, with_default: bool) {
+    db.run_sql_ok(
+        "CREATE TABLE events (id int, created_at date) PARTITION BY RANGE (created_at);
+         CREATE TABLE events_2024_01 PARTITION OF events FOR VALUES FROM ('2024-01-01') TO ('2024-02-01');
+         CREATE TABLE events_2024_02 PARTITION OF events FOR VALUES FROM ('2024-02-01') TO ('2024-03-01');",
+    );
+    if with_default {
+        db.run_sql_ok("CREATE TABLE events_default PARTITION OF events DEFAULT");
+    }
+}
+
+#[test]
+fn test_partitions_no_partitioned_tables() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "partitions"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("No partitioned tables found"));
+}
+
+#[test]
+fn test_partitions_missing_future_and_default_rows() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    create_stale_events(&db, true);
+    db.run_sql_ok("INSERT INTO events VALUES (1, current_date)");
+
+    let output = project.run_pgcrate(&["dba", "partitions"]);
+    assert_eq!(output.status.code(), Some(2));
+    let out = stdout(&output);
+    assert!(
+        out.contains("No partition covers the current time"),
+        "{}",
+        out
+    );
+    assert!(out.contains("events_default holds rows"), "{}", out);
+    assert!(
+        out.contains("PARTITION OF \"public\".\"events\""),
+        "{}",
+        out
+    );
+
+    let output = project.run_pgcrate(&["dba", "partitions", "--include-actions", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(json["schema_id"], "pgcrate.diagnostics.partitions");
+    assert_eq!(json["severity"], "critical");
+    let table = &json["data"]["tables"][0];
+    assert_eq!(table["strategy"], "range");
+    assert_eq!(table["default_partition"]["has_rows"], true);
+    assert_eq!(table["coverage"]["interval"], "1 mon");
+    assert_eq!(table["coverage"]["covers_now"], false);
+    // The current month plus the two expected ahead of it
+    let missing = table["coverage"]["missing"].as_array().unwrap();
+    assert_eq!(missing.len(), 3);
+
+    let actions = json["data"]["actions"].as_array().unwrap();
+    assert_eq!(actions.len(), 3);
+    assert_eq!(actions[0]["mutates"], true);
+    assert_eq!(actions[0]["risk"], "medium");
+    // Not available without --read-write --primary
+    assert_eq!(actions[0]["available"], false);
+}
+
+#[test]
+fn test_partitions_fix_actions_restore_coverage() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    create_stale_events(&db, false);
+
+    let output = project.run_pgcrate(&["dba", "partitions", "--json", "--ahead", "3"]);
+    let json = parse_json(&output);
+    let missing = json["data"]["tables"][0]["coverage"]["missing"]
+        .as_array()
+        .unwrap()
+        .clone();
+    assert_eq!(missing.len(), 4);
+    for part in &missing {
+        db.run_sql_ok(part["sql"].as_str().unwrap());
+    }
+    db.run_sql_ok("INSERT INTO events VALUES (1, current_date)");
+
+    let output = project.run_pgcrate(&["dba", "partitions", "--json", "--ahead", "3"]);
+    let json = parse_json(&output);
+    assert_eq!(json["severity"], "healthy");
+    let coverage = &json["data"]["tables"][0]["coverage"];
+    assert_eq!(coverage["covers_now"], true);
+    assert_eq!(coverage["future_partitions"], 3);
+    assert!(coverage["missing"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_partitions_detects_size_skew() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok(
+        "CREATE TABLE tenants_data (tenant text, payload int) PARTITION BY LIST (tenant);
+         CREATE TABLE tenants_a PARTITION OF tenants_data FOR VALUES IN ('a');
+         CREATE TABLE tenants_b PARTITION OF tenants_data FOR VALUES IN ('b');
+         CREATE TABLE tenants_c PARTITION OF tenants_data FOR VALUES IN ('c');
+         INSERT INTO tenants_data VALUES ('a', 1), ('b', 1);
+         INSERT INTO tenants_data SELECT 'c', g FROM generate_series(1, 300000) g;",
+    );
+
+    let output = project.run_pgcrate(&["dba", "partitions"]);
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    assert!(out.contains("Partition tenants_c is"), "{}", out);
+
+    let output = project.run_pgcrate(&["dba", "partitions", "--json"]);
+    let json = parse_json(&output);
+    let table = &json["data"]["tables"][0];
+    assert_eq!(table["strategy"], "list");
+    assert_eq!(table["skew"]["largest"], "tenants_c");
+    assert!(table.get("coverage").is_none());
+}
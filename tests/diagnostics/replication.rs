@@ -134,3 +134,84 @@ url = "{}"
         out
     );
 }
+
+// ============================================================================
+// dba slots
+// ============================================================================
+
+#[test]
+fn test_slots_reports_physical_slot() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::empty(&db);
+
+    std::fs::write(
+        project.path("pgcrate.toml"),
+        format!(
+            r#"[database]
+url = "{}"
+"#,
+            db.url()
+        ),
+    )
+    .unwrap();
+
+    // Slots are cluster-wide; drop it before asserting so a failure cannot leak it
+    let slot = format!("pgcrate_test_slot_{}", std::process::id());
+    db.run_sql_ok(&format!(
+        "SELECT pg_create_physical_replication_slot('{}', true)",
+        slot
+    ));
+    let output = project.run_pgcrate(&["dba", "slots", "--json"]);
+    db.run_sql_ok(&format!("SELECT pg_drop_replication_slot('{}')", slot));
+
+    let json = parse_json(&output);
+    assert_eq!(
+        json.get("schema_id"),
+        Some(&serde_json::json!("pgcrate.diagnostics.slots"))
+    );
+    let data = json.get("data").expect("JSON should have data field");
+    assert!(data.get("subscriptions").unwrap().is_array());
+    assert!(data.get("autovacuum_freeze_max_age").unwrap().is_i64());
+
+    let entry = data["slots"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["slot_name"] == slot.as_str())
+        .expect("created slot should be listed");
+    assert_eq!(entry["slot_type"], "physical");
+    assert_eq!(entry["active"], false);
+    assert!(entry["retained_bytes"].as_i64().unwrap() >= 0);
+}
+
+#[test]
+fn test_slots_flags_disabled_subscription() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::empty(&db);
+
+    std::fs::write(
+        project.path("pgcrate.toml"),
+        format!(
+            r#"[database]
+url = "{}"
+"#,
+            db.url()
+        ),
+    )
+    .unwrap();
+
+    // connect = false creates a disabled subscription without a publisher
+    db.run_sql_ok(
+        "CREATE SUBSCRIPTION test_sub CONNECTION 'dbname=unused' PUBLICATION test_pub WITH (connect = false)",
+    );
+    let output = project.run_pgcrate(&["dba", "slots"]);
+    db.run_sql_ok("ALTER SUBSCRIPTION test_sub SET (slot_name = NONE)");
+    db.run_sql_ok("DROP SUBSCRIPTION test_sub");
+
+    let out = stdout(&output);
+    assert_eq!(output.status.code(), Some(1), "warning exit code: {}", out);
+    assert!(out.contains("test_sub (disabled)"), "{}", out);
+    assert!(out.contains("Disabled;"), "{}", out);
+}
@@ -221,6 +221,63 @@ fn test_sequences_custom_thresholds() {
     );
 }
 
+#[test]
+fn test_sequences_alerts_file_thresholds_and_ignore() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    project.run_pgcrate_ok(&["migrate", "up"]);
+
+    create_sequence_at_percentage(&db, "test_seq_alerts", 50);
+
+    // pgcrate.alerts.toml in the working directory is picked up automatically
+    std::fs::write(
+        project.path("pgcrate.alerts.toml"),
+        "[sequences]\nwarn = 40\ncrit = 60\n",
+    )
+    .unwrap();
+    let output = project.run_pgcrate(&["dba", "sequences"]);
+    assert_eq!(
+        output.status.code(),
+        Some(1),
+        "50% with warn = 40 in alerts file should be warning (exit 1)\nstderr: {}",
+        stderr(&output)
+    );
+
+    // CLI flags take precedence over the file
+    let output = project.run_pgcrate(&["dba", "sequences", "--warn", "70"]);
+    assert_eq!(output.status.code(), Some(0), "--warn should override file");
+
+    // Ignored sequences produce no findings
+    std::fs::write(
+        project.path("pgcrate.alerts.toml"),
+        "[sequences]\nwarn = 40\ncrit = 60\n\n[ignore]\nsequences = [\"public.test_seq_alerts\"]\n",
+    )
+    .unwrap();
+    let output = project.run_pgcrate(&["dba", "sequences"]);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "ignored sequence should not be reported\nstdout: {}",
+        stdout(&output)
+    );
+
+    // Invalid thresholds are rejected
+    std::fs::write(
+        project.path("pgcrate.alerts.toml"),
+        "[sequences]\nwarn = 90\ncrit = 60\n",
+    )
+    .unwrap();
+    let output = project.run_pgcrate(&["dba", "sequences"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("warn"),
+        "expected threshold error: {}",
+        stderr(&output)
+    );
+}
+
 // ============================================================================
 // JSON output
 // ============================================================================
@@ -0,0 +1,61 @@
+//! pg_stat_statements management tests: `dba statements`.
+//!
+//! The test server does not preload pg_stat_statements, so these cover the
+//! setup check and flag gating rather than the statistics themselves.
+
+use crate::common::{parse_json, stderr, stdout, TestDatabase, TestProject};
+
+#[test]
+fn test_statements_reports_missing_extension_with_fix() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let enabled = db.query("SELECT current_setting('shared_preload_libraries')");
+    if enabled.contains("pg_stat_statements") {
+        eprintln!("Skipping: pg_stat_statements is preloaded on this server");
+        return;
+    }
+
+    let output = project.run_pgcrate(&["dba", "statements"]);
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    assert!(out.contains("pg_stat_statements is not ready"), "{}", out);
+    assert!(out.contains("shared_preload_libraries"), "{}", out);
+
+    let output = project.run_pgcrate(&["dba", "statements", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(json["schema_id"], "pgcrate.diagnostics.statements");
+    assert_eq!(json["severity"], "warning");
+    assert_eq!(json["data"]["extension"]["preloaded"], false);
+    assert!(!json["data"]["extension"]["fix"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_statements_reset_requires_write_flags() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "statements", "--reset"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--reset requires --read-write and --primary"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_statements_rejects_invalid_sort() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "statements", "--by", "bogus"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Invalid --by value"));
+}
@@ -0,0 +1,139 @@
+//! Recorded metrics tests: `dba record`, `dba trend`, and `--baseline`/`--compare`.
+
+use crate::common::{parse_json, stderr, stdout, TestDatabase, TestProject};
+
+// ============================================================================
+// dba record / dba trend
+// ============================================================================
+
+#[test]
+fn test_record_then_trend_shows_growth() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok("CREATE SEQUENCE trend_seq AS SMALLINT MAXVALUE 1000");
+    db.run_sql_ok("SELECT setval('trend_seq', 100)");
+    project.run_pgcrate_ok(&["--read-write", "--primary", "dba", "record"]);
+
+    db.run_sql_ok("SELECT setval('trend_seq', 500)");
+    let output = project.run_pgcrate_ok(&["--read-write", "--primary", "dba", "record", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(json["schema_id"], "pgcrate.diagnostics.record");
+    assert!(
+        json["data"]["metrics"]["sequence_pct_used"]
+            .as_u64()
+            .unwrap()
+            >= 1
+    );
+
+    let output = project.run_pgcrate_ok(&["dba", "trend", "sequence_pct_used", "--since", "1h"]);
+    let out = stdout(&output);
+    assert!(
+        out.contains("sequence usage on public.trend_seq grew 400.0% (10.0% -> 50.0%)"),
+        "unexpected trend output: {}",
+        out
+    );
+
+    let output = project.run_pgcrate_ok(&["dba", "trend", "sequence_pct_used", "--json"]);
+    let json = parse_json(&output);
+    assert_eq!(json["schema_id"], "pgcrate.diagnostics.trend");
+    let change = json["data"]["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["object"] == "public.trend_seq")
+        .expect("trend_seq change");
+    assert_eq!(change["previous"], 10.0);
+    assert_eq!(change["current"], 50.0);
+}
+
+#[test]
+fn test_trend_without_recordings_fails() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    let output = project.run_pgcrate(&["dba", "trend", "table_bloat_bytes"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("No metrics recorded yet"));
+
+    project.run_pgcrate_ok(&["--read-write", "--primary", "dba", "record"]);
+    let output = project.run_pgcrate(&["dba", "trend", "not_a_metric"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Unknown metric 'not_a_metric'"));
+}
+
+#[test]
+fn test_recording_metrics_requires_gates() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    for args in [
+        &["dba", "record"][..],
+        &["--read-write", "dba", "record"],
+        &["dba", "sequences", "--baseline", "pre-deploy"],
+        &["--primary", "dba", "triage", "--baseline", "pre-deploy"],
+    ] {
+        let output = project.run_pgcrate(args);
+        assert!(!output.status.success(), "{:?} should fail", args);
+        assert!(
+            stderr(&output).contains("requires --read-write and --primary"),
+            "{:?}: {}",
+            args,
+            stderr(&output)
+        );
+    }
+    assert_eq!(
+        db.query("SELECT to_regclass('pgcrate.metrics') IS NULL"),
+        "t"
+    );
+}
+
+// ============================================================================
+// --baseline / --compare on existing commands
+// ============================================================================
+
+#[test]
+fn test_sequences_compare_against_baseline() {
+    skip_if_no_db!();
+    let db = TestDatabase::new();
+    let project = TestProject::from_fixture("with_migrations", &db);
+
+    db.run_sql_ok("CREATE SEQUENCE compare_seq AS SMALLINT MAXVALUE 1000");
+    db.run_sql_ok("SELECT setval('compare_seq', 200)");
+    project.run_pgcrate_ok(&[
+        "--read-write",
+        "--primary",
+        "dba",
+        "sequences",
+        "--baseline",
+        "pre-deploy",
+    ]);
+
+    db.run_sql_ok("SELECT setval('compare_seq', 300)");
+    let output = project.run_pgcrate_ok(&[
+        "dba",
+        "sequences",
+        "--all",
+        "--compare",
+        "pre-deploy",
+        "--json",
+    ]);
+    let json = parse_json(&output);
+    let comparison = &json["data"]["comparison"];
+    assert_eq!(comparison["against"], "baseline 'pre-deploy'");
+    let change = &comparison["changes"][0];
+    assert_eq!(change["object"], "public.compare_seq");
+    assert_eq!(change["previous"], 20.0);
+    assert_eq!(change["current"], 30.0);
+    assert_eq!(change["delta_pct"], 50.0);
+
+    let output = project.run_pgcrate_ok(&["dba", "sequences", "--all", "--compare", "pre-deploy"]);
+    assert!(stdout(&output).contains("COMPARED TO BASELINE 'PRE-DEPLOY':"));
+
+    let output = project.run_pgcrate(&["dba", "xid", "--compare", "missing"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("No recorded metrics for the baseline 'missing'"));
+}
@@ -614,3 +614,269 @@ migrations = "migrations"
     let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", source_db), &db_url);
     let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", target_db), &db_url);
 }
+
+/// Test that generate emits RLS policies and grants, unless --no-privileges
+#[test]
+fn test_generate_policies_and_privileges() {
+    let db_url = get_test_db_url();
+
+    let test_db = "pgcrate_gen_test_privileges";
+    let test_url = db_url
+        .rsplit_once('/')
+        .map(|(base, _)| format!("{}/{}", base, test_db))
+        .unwrap_or_else(|| format!("{}/{}", db_url, test_db));
+
+    // Drop and create test database
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+    let create_result = run_psql(&format!("CREATE DATABASE {}", test_db), &db_url);
+    if !create_result.status.success() {
+        eprintln!("Skipping test: could not create test database");
+        return;
+    }
+
+    let setup_sql = r#"
+        DO $$ BEGIN
+            IF NOT EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'pgcrate_gen_reader') THEN
+                CREATE ROLE pgcrate_gen_reader;
+            END IF;
+        END $$;
+        CREATE TABLE notes (id INT PRIMARY KEY, author TEXT);
+        COMMENT ON TABLE notes IS 'Private notes';
+        ALTER TABLE notes ENABLE ROW LEVEL SECURITY;
+        CREATE POLICY own_notes ON notes USING (author = current_user);
+        GRANT SELECT ON notes TO pgcrate_gen_reader;
+        CREATE FUNCTION secret() RETURNS int LANGUAGE sql AS 'SELECT 1';
+        REVOKE EXECUTE ON FUNCTION secret() FROM PUBLIC;
+    "#;
+    let setup_result = run_psql(setup_sql, &test_url);
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let temp_dir = std::env::temp_dir().join("pgcrate_test_privileges");
+    for (args, with_privileges) in [(vec![], true), (vec!["--no-privileges"], false)] {
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        std::fs::create_dir_all(&temp_dir).expect("Failed to create temp dir");
+
+        let mut full_args = vec!["generate", "--output", temp_dir.to_str().unwrap()];
+        full_args.extend(args);
+        let output = run_pgcrate(&full_args, &test_url);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            output.status.success(),
+            "generate should succeed. stderr: {}",
+            stderr
+        );
+
+        let file = std::fs::read_dir(&temp_dir)
+            .expect("Failed to read temp dir")
+            .filter_map(|e| e.ok())
+            .next()
+            .expect("Should generate a file");
+        let content = std::fs::read_to_string(file.path()).expect("Failed to read generated file");
+
+        assert!(content.contains("COMMENT ON TABLE \"public\".\"notes\" IS 'Private notes';"));
+        assert!(content.contains("ALTER TABLE \"public\".\"notes\" ENABLE ROW LEVEL SECURITY;"));
+        assert!(content.contains("CREATE POLICY \"own_notes\" ON \"public\".\"notes\""));
+        assert_eq!(
+            content.contains("GRANT SELECT ON TABLE public.notes TO \"pgcrate_gen_reader\";"),
+            with_privileges,
+            "content: {}",
+            content
+        );
+        assert_eq!(
+            content.contains("REVOKE EXECUTE ON FUNCTION public.secret() FROM PUBLIC;"),
+            with_privileges,
+            "content: {}",
+            content
+        );
+    }
+
+    // Cleanup
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+    let _ = run_psql("DROP ROLE IF EXISTS pgcrate_gen_reader", &db_url);
+}
+
+/// Test the declarative workflow: generate --declarative, edit, plan
+#[test]
+fn test_generate_declarative_and_plan() {
+    let db_url = get_test_db_url();
+
+    let test_db = "pgcrate_gen_test_declarative";
+    let test_url = db_url
+        .rsplit_once('/')
+        .map(|(base, _)| format!("{}/{}", base, test_db))
+        .unwrap_or_else(|| format!("{}/{}", db_url, test_db));
+
+    // Drop and create test database
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+    let create_result = run_psql(&format!("CREATE DATABASE {}", test_db), &db_url);
+    if !create_result.status.success() {
+        eprintln!("Skipping test: could not create test database");
+        return;
+    }
+
+    let setup_result = run_psql(
+        "CREATE TABLE notes (id INT PRIMARY KEY, body TEXT)",
+        &test_url,
+    );
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    let temp_dir = std::env::temp_dir().join("pgcrate_test_declarative");
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    std::fs::create_dir_all(&temp_dir).expect("Failed to create temp dir");
+    let schema_file = temp_dir.join("schema.sql");
+    let schema_path = schema_file.to_str().unwrap();
+
+    let output = run_pgcrate(&["generate", "--declarative", schema_path], &test_url);
+    assert!(
+        output.status.success(),
+        "generate --declarative should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = std::fs::read_to_string(&schema_file).expect("Failed to read schema file");
+    assert!(content.contains("CREATE TABLE \"public\".\"notes\""));
+    assert!(!content.contains("-- up"));
+
+    // In sync right after generating
+    // The scratch database is a write on the server
+    let output = run_pgcrate(&["plan", schema_path], &test_url);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("requires --read-write"),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_pgcrate(&["--read-write", "plan", schema_path], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(0), "stdout: {}", stdout);
+    assert!(stdout.contains("Database matches"), "stdout: {}", stdout);
+
+    // An added column shows up in the proposed migration
+    std::fs::write(
+        &schema_file,
+        content.replace("\"body\" text", "\"body\" text,\n    \"pinned\" boolean"),
+    )
+    .expect("Failed to edit schema file");
+    let output = run_pgcrate(&["--read-write", "plan", schema_path, "--json"], &test_url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "stdout: {}", stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["identical"], false);
+    assert!(
+        json["sql"]
+            .as_str()
+            .unwrap()
+            .contains("ADD COLUMN \"pinned\" boolean"),
+        "sql: {}",
+        json["sql"]
+    );
+
+    // Cleanup
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+}
+
+/// Test generate --delta: only what the database has beyond the migrations
+#[test]
+fn test_generate_delta() {
+    let db_url = get_test_db_url();
+
+    let test_db = "pgcrate_gen_test_delta";
+    let test_url = db_url
+        .rsplit_once('/')
+        .map(|(base, _)| format!("{}/{}", base, test_db))
+        .unwrap_or_else(|| format!("{}/{}", db_url, test_db));
+
+    // Drop and create test database
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+    let create_result = run_psql(&format!("CREATE DATABASE {}", test_db), &db_url);
+    if !create_result.status.success() {
+        eprintln!("Skipping test: could not create test database");
+        return;
+    }
+
+    let temp_dir = std::env::temp_dir().join("pgcrate_test_delta");
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    let migrations_dir = temp_dir.join("migrations");
+    let output_dir = temp_dir.join("out");
+    std::fs::create_dir_all(&migrations_dir).expect("Failed to create migrations dir");
+    std::fs::write(
+        migrations_dir.join("20240101000000_create_notes.sql"),
+        "-- up\nCREATE TABLE notes (id INT PRIMARY KEY, body TEXT);\n\n-- down\nDROP TABLE notes;\n",
+    )
+    .expect("Failed to write migration");
+    std::fs::write(
+        temp_dir.join("pgcrate.toml"),
+        "[paths]\nmigrations = \"migrations\"\n",
+    )
+    .expect("Failed to write config");
+
+    // Run from temp_dir so the relative migrations path resolves
+    let run_delta = |read_write: &[&str]| {
+        Command::new(pgcrate_binary())
+            .args(read_write)
+            .args([
+                "generate",
+                "--delta",
+                "--no-privileges",
+                "--config",
+                "pgcrate.toml",
+                "--output",
+                "out",
+            ])
+            .env("DATABASE_URL", &test_url)
+            .current_dir(&temp_dir)
+            .output()
+            .expect("Failed to execute pgcrate")
+    };
+
+    // Applied by the migration, plus one change made by hand
+    let setup_result = run_psql(
+        "CREATE TABLE notes (id INT PRIMARY KEY, body TEXT); CREATE TABLE tags (name TEXT)",
+        &test_url,
+    );
+    assert!(setup_result.status.success(), "Setup should succeed");
+
+    // The scratch database is a write on the server
+    let output = run_delta(&[]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("requires --read-write"),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_delta(&["--read-write"]);
+    assert!(
+        output.status.success(),
+        "generate --delta should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let files: Vec<_> = std::fs::read_dir(&output_dir)
+        .expect("Failed to read output dir")
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1, "files: {:?}", files);
+    assert!(files[0].to_str().unwrap().ends_with("_delta.sql"));
+    let content = std::fs::read_to_string(&files[0]).expect("Failed to read delta");
+    assert!(
+        content.contains("CREATE TABLE \"public\".\"tags\""),
+        "{}",
+        content
+    );
+    assert!(!content.contains("\"notes\""), "{}", content);
+
+    // Nothing to generate once the migrations cover everything
+    let _ = run_psql("DROP TABLE tags", &test_url);
+    let _ = std::fs::remove_dir_all(&output_dir);
+    let output = run_delta(&["--read-write"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Nothing to generate"));
+    assert!(!output_dir.exists());
+
+    // Cleanup
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+}
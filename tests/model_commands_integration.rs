@@ -259,3 +259,60 @@ SELECT 1 AS id
         .unwrap()
         .contains("CREATE TABLE"));
 }
+
+#[test]
+fn test_model_import_sources_declares_tables_and_staging_stubs() {
+    let dir = create_temp_project_dir("import_sources");
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let schema = format!("raw_{}", nanos % 1_000_000);
+    let db_url = get_test_db_url();
+
+    let pgcrate = |args: &[&str]| {
+        Command::new(pgcrate_binary())
+            .current_dir(&dir)
+            .env("DATABASE_URL", &db_url)
+            .args(args)
+            .output()
+            .expect("Failed to execute pgcrate")
+    };
+
+    let setup = format!(
+        "CREATE SCHEMA {schema}; CREATE TABLE {schema}.orders (id INT PRIMARY KEY, amount NUMERIC)"
+    );
+    let out = pgcrate(&["sql", "--allow-write", "-c", &setup]);
+    assert!(
+        out.status.success(),
+        "setup should succeed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let out = pgcrate(&["model", "import-sources", "--schema", &schema, "--staging"]);
+    assert!(
+        out.status.success(),
+        "model import-sources should succeed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let sources = fs::read_to_string(dir.join("models/sources.toml")).expect("sources.toml");
+    assert!(sources.contains(&format!("[sources.{schema}]")));
+    assert!(sources.contains("tables = [\"orders\"]"));
+
+    let stub = fs::read_to_string(dir.join(format!("models/staging/{schema}_orders.sql")))
+        .expect("staging stub");
+    assert!(stub.contains(&format!("-- deps: {schema}.orders")));
+    assert!(stub.contains("    id,\n    amount\n"));
+
+    // The stub depends on a declared source, so the project runs
+    let out = pgcrate(&["model", "run"]);
+    assert!(
+        out.status.success(),
+        "model run should succeed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let cleanup = format!("DROP SCHEMA {schema} CASCADE");
+    let _ = pgcrate(&["sql", "--allow-write", "-c", &cleanup]);
+}
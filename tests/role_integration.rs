@@ -265,3 +265,97 @@ fn test_grants_table_not_found() {
         stderr
     );
 }
+
+/// Test grants --schema --matrix in table, CSV, and JSON form
+#[test]
+fn test_grants_matrix() {
+    let db_url = get_test_db_url();
+
+    let role_name = unique_name("test_role");
+    let schema_name = unique_name("test_schema");
+
+    let setup_sql = format!(
+        r#"
+        DROP ROLE IF EXISTS {role_name};
+        CREATE ROLE {role_name};
+        DROP SCHEMA IF EXISTS {schema_name} CASCADE;
+        CREATE SCHEMA {schema_name};
+        CREATE TABLE {schema_name}.orders (id int);
+        GRANT USAGE ON SCHEMA {schema_name} TO {role_name};
+        GRANT SELECT ON {schema_name}.orders TO {role_name} WITH GRANT OPTION;
+        GRANT INSERT ON {schema_name}.orders TO {role_name};
+        "#
+    );
+    let setup = run_psql(&setup_sql, &db_url);
+    assert!(setup.status.success(), "Setup should succeed");
+
+    let output = run_pgcrate(
+        &["inspect", "grants", "--schema", &schema_name, "--matrix"],
+        &db_url,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "grants --matrix should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("ROLE") && stdout.contains("REFERENCES"),
+        "Should show the privilege header. stdout: {}",
+        stdout
+    );
+
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "grants",
+            "--schema",
+            &schema_name,
+            "--matrix",
+            "--format",
+            "csv",
+        ],
+        &db_url,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!("{role_name},{schema_name},schema,Y,,,,,,,,")),
+        "Should show schema usage. stdout: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains(&format!("{role_name},orders,table,,,G,Y,,,,,")),
+        "Should show table privileges with grant option. stdout: {}",
+        stdout
+    );
+
+    let output = run_pgcrate(
+        &[
+            "inspect",
+            "grants",
+            "--schema",
+            &schema_name,
+            "--matrix",
+            "--json",
+        ],
+        &db_url,
+    );
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("grants --matrix --json");
+    assert_eq!(json["ok"], true);
+    let rows = json["rows"].as_array().unwrap();
+    assert!(
+        rows.iter()
+            .any(|r| r["grantee"] == role_name.as_str() && r["object"] == "orders"),
+        "{:?}",
+        rows
+    );
+
+    // --matrix needs a schema
+    let output = run_pgcrate(&["inspect", "grants", "--matrix"], &db_url);
+    assert!(!output.status.success());
+
+    let cleanup =
+        format!("DROP SCHEMA IF EXISTS {schema_name} CASCADE; DROP ROLE IF EXISTS {role_name};");
+    let _ = run_psql(&cleanup, &db_url);
+}
@@ -627,6 +627,12 @@ fn test_snapshot_info() {
     let workdir = temp_dir.path();
 
     setup_test_data(&test_url);
+    // A failed attempt is recorded but not applied
+    run_psql(
+        "ALTER TABLE pgcrate.schema_migrations ADD COLUMN success BOOLEAN NOT NULL DEFAULT true;
+         INSERT INTO pgcrate.schema_migrations (version, success) VALUES ('20250103000000', false);",
+        &test_url,
+    );
 
     // Save a snapshot with message
     let output = run_pgcrate(
@@ -688,6 +694,8 @@ fn test_snapshot_info() {
     assert_eq!(json["format"], "custom");
     assert_eq!(json["include_owner"], true);
     assert_eq!(json["include_privileges"], true);
+    assert_eq!(json["applied_migrations"], 2);
+    assert_eq!(json["migration_version"], "20250102000000");
 
     // Cleanup
     let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
@@ -1145,3 +1153,778 @@ fn test_snapshot_plain_no_owner_warning() {
     // Cleanup
     let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
 }
+
+/// Stand-in for the aws CLI: `s3://<key>` maps to `<dir>/bucket/<key>`
+const FAKE_AWS: &str = r#"#!/bin/sh
+[ "$1" = "--version" ] && { echo "aws-cli/2.0.0"; exit 0; }
+root="$(dirname "$0")/bucket"
+op="$2"
+shift 2
+src=""
+dst=""
+for arg; do
+  case "$arg" in
+    --*) ;;
+    *) if [ -z "$src" ]; then src="$arg"; else dst="$arg"; fi ;;
+  esac
+done
+local_path() { echo "$root/${1#s3://}"; }
+case "$op" in
+  cp)
+    if [ "$src" = "-" ]; then
+      f="$(local_path "$dst")"
+      mkdir -p "$(dirname "$f")"
+      cat > "$f"
+    else
+      f="$(local_path "$src")"
+      [ -f "$f" ] || { echo "fatal error: $src not found" >&2; exit 1; }
+      cat "$f"
+    fi ;;
+  ls)
+    d="$(local_path "$src")"
+    [ -d "$d" ] || exit 1
+    for e in "$d"*/; do
+      [ -d "$e" ] && echo "                           PRE $(basename "$e")/"
+    done
+    exit 0 ;;
+  rm) rm -rf "$(local_path "$src")" ;;
+esac
+"#;
+
+/// Test save/list/restore/delete against remote storage ([snapshot.storage])
+#[test]
+fn test_snapshot_remote_storage() {
+    if !has_pg_dump() {
+        eprintln!("Skipping test: pg_dump not found");
+        return;
+    }
+
+    let db_url = get_test_db_url();
+    let test_db = "pgcrate_snap_test_remote";
+
+    let test_url = match create_test_db(&db_url, test_db) {
+        Some(url) => url,
+        None => {
+            eprintln!("Skipping test: could not create test database");
+            return;
+        }
+    };
+
+    if !can_pg_dump(&test_url) {
+        let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+        return;
+    }
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let workdir = temp_dir.path();
+
+    let aws = workdir.join("fake-aws");
+    std::fs::write(&aws, FAKE_AWS).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&aws, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    std::fs::write(
+        workdir.join("pgcrate.toml"),
+        format!(
+            "[snapshot.storage]\nurl = \"s3://test-bucket/snaps\"\n\n[tools]\naws = \"{}\"\n",
+            aws.display()
+        ),
+    )
+    .unwrap();
+
+    setup_test_data(&test_url);
+
+    let output = run_pgcrate(&["snapshot", "list"], &test_url, workdir);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("No snapshots found"),
+        "empty bucket lists no snapshots: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Save streams the dump into the bucket; nothing is written locally
+    let output = run_pgcrate(&["snapshot", "save", "remote-test"], &test_url, workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "snapshot save should succeed. stdout: {}, stderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Storage:  s3://test-bucket/snaps"));
+    let remote_dir = workdir.join("bucket/test-bucket/snaps/remote-test");
+    assert!(remote_dir.join("dump.pgdump").exists());
+    assert!(remote_dir.join("metadata.json").exists());
+    assert!(!workdir.join(".pgcrate/snapshots").exists());
+
+    let output = run_pgcrate(&["snapshot", "save", "remote-test"], &test_url, workdir);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("already exists in s3://test-bucket/snaps"));
+
+    let output = run_pgcrate(&["snapshot", "list"], &test_url, workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Snapshots (s3://test-bucket/snaps/)"));
+    assert!(stdout.contains("remote-test"));
+
+    // Restore streams the dump back into pg_restore
+    run_psql("DELETE FROM users", &test_url);
+    let output = run_pgcrate(
+        &["snapshot", "restore", "remote-test", "--yes"],
+        &test_url,
+        workdir,
+    );
+    assert!(
+        output.status.success(),
+        "snapshot restore should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let count = run_psql_query("SELECT COUNT(*) FROM users", &test_url);
+    assert_eq!(count, "2", "Should have 2 users after restore");
+
+    let output = run_pgcrate(&["snapshot", "info", "missing"], &test_url, workdir);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Available snapshots: remote-test"));
+
+    let output = run_pgcrate(
+        &["snapshot", "delete", "remote-test", "--yes"],
+        &test_url,
+        workdir,
+    );
+    assert!(output.status.success(), "snapshot delete should succeed");
+    assert!(!remote_dir.exists());
+
+    // Cleanup
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+}
+
+/// Test snapshot diff against the current database and another snapshot
+#[test]
+fn test_snapshot_diff() {
+    if !has_pg_dump() {
+        eprintln!("Skipping test: pg_dump not found");
+        return;
+    }
+
+    let db_url = get_test_db_url();
+    let test_db = "pgcrate_snap_test_diff";
+
+    let test_url = match create_test_db(&db_url, test_db) {
+        Some(url) => url,
+        None => {
+            eprintln!("Skipping test: could not create test database");
+            return;
+        }
+    };
+
+    if !can_pg_dump(&test_url) {
+        let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+        return;
+    }
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let workdir = temp_dir.path();
+
+    setup_test_data(&test_url);
+
+    let output = run_pgcrate(&["snapshot", "save", "before"], &test_url, workdir);
+    assert!(output.status.success(), "snapshot save should succeed");
+    let output = run_pgcrate(
+        &["snapshot", "save", "before-plain", "--format", "plain"],
+        &test_url,
+        workdir,
+    );
+    assert!(
+        output.status.success(),
+        "plain snapshot save should succeed"
+    );
+
+    // Unchanged database: identical
+    let output = run_pgcrate(&["snapshot", "diff", "before"], &test_url, workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "diff should find no changes. stdout: {}, stderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Schemas are identical"),
+        "stdout: {}",
+        stdout
+    );
+
+    run_psql("ALTER TABLE users ADD COLUMN email TEXT", &test_url);
+
+    let output = run_pgcrate(&["snapshot", "diff", "before"], &test_url, workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "stdout: {}", stdout);
+    assert!(stdout.contains("email"), "stdout: {}", stdout);
+
+    // Two snapshots, one of them plain
+    let output = run_pgcrate(&["snapshot", "save", "after"], &test_url, workdir);
+    assert!(output.status.success(), "snapshot save should succeed");
+    let output = run_pgcrate(
+        &["snapshot", "diff", "before-plain", "after", "--json"],
+        &test_url,
+        workdir,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "stdout: {}", stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(json["identical"], false);
+    assert_eq!(json["summary"]["columns"], 1);
+
+    // Temporary databases are dropped
+    let leftover = run_psql_query(
+        "SELECT count(*) FROM pg_database WHERE datname LIKE 'pgcrate_snapshot_diff_%'",
+        &db_url,
+    );
+    assert_eq!(leftover, "0");
+
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+}
+
+/// Wrapper running gpg against a throwaway keyring
+const GPG_WRAPPER: &str = r#"#!/bin/sh
+GNUPGHOME="$(dirname "$0")/gnupg" exec gpg "$@"
+"#;
+
+/// Test encrypted snapshots round-trip through gpg
+#[test]
+fn test_snapshot_encrypted() {
+    if !has_pg_dump() {
+        eprintln!("Skipping test: pg_dump not found");
+        return;
+    }
+
+    let db_url = get_test_db_url();
+    let test_db = "pgcrate_snap_test_encrypted";
+
+    let test_url = match create_test_db(&db_url, test_db) {
+        Some(url) => url,
+        None => {
+            eprintln!("Skipping test: could not create test database");
+            return;
+        }
+    };
+
+    if !can_pg_dump(&test_url) {
+        let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+        return;
+    }
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let workdir = temp_dir.path();
+
+    let gpg = workdir.join("gpg");
+    std::fs::write(&gpg, GPG_WRAPPER).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&gpg, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::create_dir(workdir.join("gnupg")).unwrap();
+        std::fs::set_permissions(
+            workdir.join("gnupg"),
+            std::fs::Permissions::from_mode(0o700),
+        )
+        .unwrap();
+    }
+    let keygen = Command::new(&gpg)
+        .args([
+            "--batch",
+            "--passphrase",
+            "",
+            "--quick-gen-key",
+            "pgcrate-test@example.com",
+            "default",
+            "default",
+            "never",
+        ])
+        .output();
+    if !keygen.is_ok_and(|o| o.status.success()) {
+        eprintln!("Skipping test: could not generate a gpg key");
+        let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+        return;
+    }
+    std::fs::write(
+        workdir.join("pgcrate.toml"),
+        format!("[tools]\ngpg = \"{}\"\n", gpg.display()),
+    )
+    .unwrap();
+
+    setup_test_data(&test_url);
+
+    let output = run_pgcrate(
+        &[
+            "snapshot",
+            "save",
+            "secret",
+            "--encrypt-key",
+            "pgcrate-test@example.com",
+        ],
+        &test_url,
+        workdir,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "encrypted save should succeed. stdout: {}, stderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Encrypt:  gpg"), "stdout: {}", stdout);
+
+    // The dump on disk is not a readable pg_dump archive
+    let dump = std::fs::read(workdir.join(".pgcrate/snapshots/secret/dump.pgdump")).unwrap();
+    assert!(!dump.starts_with(b"PGDMP"));
+
+    let output = run_pgcrate(&["snapshot", "info", "secret"], &test_url, workdir);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Encrypted:   gpg"));
+
+    run_psql("DELETE FROM users", &test_url);
+    let output = run_pgcrate(
+        &["snapshot", "restore", "secret", "--yes"],
+        &test_url,
+        workdir,
+    );
+    assert!(
+        output.status.success(),
+        "encrypted restore should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let count = run_psql_query("SELECT COUNT(*) FROM users", &test_url);
+    assert_eq!(count, "2", "Should have 2 users after restore");
+
+    // Restoring without the private key reports the decryption failure
+    std::fs::remove_dir_all(workdir.join("gnupg/private-keys-v1.d")).unwrap();
+    let _ = Command::new("gpgconf")
+        .env("GNUPGHOME", workdir.join("gnupg"))
+        .args(["--kill", "gpg-agent"])
+        .output();
+    let output = run_pgcrate(
+        &["snapshot", "restore", "secret", "--yes"],
+        &test_url,
+        workdir,
+    );
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("gpg decryption failed"),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+}
+
+/// Test restoring into a throwaway database and pruning it after its TTL
+#[test]
+fn test_snapshot_restore_as_new_db() {
+    if !has_pg_dump() {
+        eprintln!("Skipping test: pg_dump not found");
+        return;
+    }
+
+    let db_url = get_test_db_url();
+    let test_db = "pgcrate_snap_test_newdb";
+    let kept_db = "pgcrate_snap_test_newdb_kept";
+    let expired_db = "pgcrate_snap_test_newdb_expired";
+
+    let test_url = match create_test_db(&db_url, test_db) {
+        Some(url) => url,
+        None => {
+            eprintln!("Skipping test: could not create test database");
+            return;
+        }
+    };
+
+    if !can_pg_dump(&test_url) {
+        let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+        return;
+    }
+    for db in [kept_db, expired_db] {
+        let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", db), &db_url);
+    }
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let workdir = temp_dir.path();
+
+    setup_test_data(&test_url);
+    let output = run_pgcrate(&["snapshot", "save", "review"], &test_url, workdir);
+    assert!(output.status.success(), "snapshot save should succeed");
+    run_psql("DELETE FROM users", &test_url);
+
+    // No --yes needed: nothing is clobbered
+    let output = run_pgcrate(
+        &[
+            "snapshot",
+            "restore",
+            "review",
+            "--as-new-db",
+            kept_db,
+            "--ttl",
+            "1d",
+        ],
+        &test_url,
+        workdir,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "restore --as-new-db should succeed. stdout: {}, stderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let kept_url = test_url.replace(test_db, kept_db);
+    assert!(
+        stdout.contains(&format!("Connection URL: {}", kept_url)),
+        "stdout: {}",
+        stdout
+    );
+    assert_eq!(run_psql_query("SELECT COUNT(*) FROM users", &kept_url), "2");
+    assert_eq!(run_psql_query("SELECT COUNT(*) FROM users", &test_url), "0");
+
+    // An existing database is never overwritten
+    let output = run_pgcrate(
+        &["snapshot", "restore", "review", "--as-new-db", kept_db],
+        &test_url,
+        workdir,
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists"));
+
+    let output = run_pgcrate(
+        &[
+            "snapshot",
+            "restore",
+            "review",
+            "--as-new-db",
+            expired_db,
+            "--ttl",
+            "0s",
+        ],
+        &test_url,
+        workdir,
+    );
+    assert!(
+        output.status.success(),
+        "restore --as-new-db should succeed"
+    );
+
+    let output = run_pgcrate(&["snapshot", "prune", "--dry-run"], &test_url, workdir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!("Would drop {}", expired_db)),
+        "stdout: {}",
+        stdout
+    );
+    assert!(!stdout.contains(kept_db), "stdout: {}", stdout);
+
+    let output = run_pgcrate(&["snapshot", "prune"], &test_url, workdir);
+    assert!(output.status.success());
+    let exists = |db: &str| {
+        run_psql_query(
+            &format!("SELECT count(*) FROM pg_database WHERE datname = '{}'", db),
+            &db_url,
+        )
+    };
+    assert_eq!(exists(expired_db), "0");
+    assert_eq!(exists(kept_db), "1");
+
+    for db in [kept_db, test_db] {
+        let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", db), &db_url);
+    }
+}
+
+/// Test one-shot scheduled saves with retention, and the systemd units
+#[test]
+fn test_snapshot_schedule() {
+    if !has_pg_dump() {
+        eprintln!("Skipping test: pg_dump not found");
+        return;
+    }
+
+    let db_url = get_test_db_url();
+    let test_db = "pgcrate_snap_test_schedule";
+
+    let test_url = match create_test_db(&db_url, test_db) {
+        Some(url) => url,
+        None => {
+            eprintln!("Skipping test: could not create test database");
+            return;
+        }
+    };
+
+    if !can_pg_dump(&test_url) {
+        let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+        return;
+    }
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let workdir = temp_dir.path();
+    setup_test_data(&test_url);
+
+    let args = [
+        "snapshot", "schedule", "--now", "--prefix", "nightly", "--keep", "1",
+    ];
+    let first = run_pgcrate(&args, &test_url, workdir);
+    assert!(
+        first.status.success(),
+        "scheduled save should succeed: {}",
+        String::from_utf8_lossy(&first.stderr)
+    );
+    // Names have one-second resolution
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    let second = run_pgcrate(&args, &test_url, workdir);
+    assert!(second.status.success());
+
+    let events = |output: &std::process::Output| -> Vec<serde_json::Value> {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("Each line should be JSON"))
+            .collect()
+    };
+    let first = events(&first);
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0]["event"], "saved");
+    let old = first[0]["snapshot"].as_str().unwrap().to_string();
+    assert!(old.starts_with("nightly-"));
+    assert!(first[0]["size_bytes"].as_u64().unwrap() > 0);
+
+    let second = events(&second);
+    assert_eq!(second[0]["event"], "saved");
+    assert_eq!(second[1]["event"], "deleted");
+    assert_eq!(second[1]["snapshot"], old.as_str());
+
+    let remaining: Vec<_> = std::fs::read_dir(workdir.join(".pgcrate/snapshots"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(remaining, vec![second[0]["snapshot"].as_str().unwrap()]);
+
+    let output = run_pgcrate(
+        &[
+            "snapshot",
+            "schedule",
+            "--cron",
+            "0 2 * * *",
+            "--keep",
+            "7",
+            "--systemd",
+        ],
+        &test_url,
+        workdir,
+    );
+    assert!(output.status.success());
+    let units = String::from_utf8_lossy(&output.stdout);
+    assert!(units.contains("OnCalendar=*-*-* 02:00:00"), "{}", units);
+    assert!(units.contains("snapshot schedule --now --prefix scheduled --keep 7"));
+
+    let output = run_pgcrate(
+        &["snapshot", "schedule", "--cron", "0 25 * * *"],
+        &test_url,
+        workdir,
+    );
+    assert!(!output.status.success());
+
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+}
+
+#[test]
+fn test_snapshot_preflight_tool_versions() {
+    if !has_pg_dump() {
+        eprintln!("Skipping test: pg_dump not found");
+        return;
+    }
+
+    let db_url = get_test_db_url();
+    let test_db = "pgcrate_snap_test_preflight";
+    let test_url = match create_test_db(&db_url, test_db) {
+        Some(url) => url,
+        None => {
+            eprintln!("Skipping test: could not create test database");
+            return;
+        }
+    };
+    if !can_pg_dump(&test_url) {
+        let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+        return;
+    }
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let workdir = temp_dir.path();
+    setup_test_data(&test_url);
+
+    // A pg_dump far older than any supported server
+    let old_pg_dump = workdir.join("old_pg_dump");
+    std::fs::write(
+        &old_pg_dump,
+        "#!/bin/sh\necho 'pg_dump (PostgreSQL) 9.6.24'\n",
+    )
+    .unwrap();
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(&old_pg_dump, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(
+        workdir.join("pgcrate.toml"),
+        format!(
+            "[connections.legacy]\nurl = \"{}\"\npg_dump_path = \"{}\"\n",
+            test_url,
+            old_pg_dump.display()
+        ),
+    )
+    .unwrap();
+
+    let output = run_pgcrate(&["--json", "snapshot", "preflight"], &test_url, workdir);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["ok"], true);
+    assert_eq!(json["tools"][0]["tool"], "pg_dump");
+    assert_eq!(json["tools"][0]["compatible"], true);
+
+    let output = Command::new(pgcrate_binary())
+        .args(["--json", "-C", "legacy", "snapshot", "preflight"])
+        .env_remove("DATABASE_URL")
+        .current_dir(workdir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["errors"][0]["code"], "tool_version_mismatch");
+
+    let output = Command::new(pgcrate_binary())
+        .args(["-C", "legacy", "snapshot", "save", "too-old"])
+        .env_remove("DATABASE_URL")
+        .current_dir(workdir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("pg_dump 9.6.24") && stderr.contains("older than the server"),
+        "{}",
+        stderr
+    );
+
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+}
+
+/// Plain snapshots fall back to the built-in dump when pg_dump is missing
+#[test]
+fn test_snapshot_native_dump() {
+    let db_url = get_test_db_url();
+    let test_db = "pgcrate_snap_test_native";
+
+    let test_url = match create_test_db(&db_url, test_db) {
+        Some(url) => url,
+        None => {
+            eprintln!("Skipping test: could not create test database");
+            return;
+        }
+    };
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let workdir = temp_dir.path();
+    std::fs::write(
+        workdir.join("pgcrate.toml"),
+        "[tools]\npg_dump = \"/nonexistent/pg_dump\"\n",
+    )
+    .unwrap();
+
+    setup_test_data(&test_url);
+    run_psql(
+        r#"
+        CREATE TABLE posts (
+            id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+            user_id INT NOT NULL REFERENCES users(id),
+            title TEXT NOT NULL,
+            slug TEXT GENERATED ALWAYS AS (lower(title)) STORED
+        );
+        CREATE INDEX posts_user_id_idx ON posts (user_id);
+        INSERT INTO posts (user_id, title) VALUES (1, 'Hello'), (2, 'World');
+        "#,
+        &test_url,
+    );
+
+    // Custom format still needs pg_dump
+    let output = run_pgcrate(&["snapshot", "save", "custom-test"], &test_url, workdir);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--format plain"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_pgcrate(
+        &["snapshot", "save", "native-test", "--format", "plain"],
+        &test_url,
+        workdir,
+    );
+    assert!(
+        output.status.success(),
+        "Native save should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("built-in dump"));
+    let dump =
+        std::fs::read_to_string(workdir.join(".pgcrate/snapshots/native-test/dump.sql")).unwrap();
+    assert!(dump.starts_with("-- pgcrate native dump"));
+
+    run_psql(
+        "DELETE FROM posts; DELETE FROM users; DELETE FROM pgcrate.schema_migrations",
+        &test_url,
+    );
+    let output = run_pgcrate(
+        &["snapshot", "restore", "native-test", "--yes"],
+        &test_url,
+        workdir,
+    );
+    assert!(
+        output.status.success(),
+        "Restore should succeed. stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(
+        run_psql_query(
+            "SELECT string_agg(name || ':' || title || ':' || slug, ',' ORDER BY posts.id) \
+             FROM posts JOIN users ON users.id = posts.user_id",
+            &test_url
+        ),
+        "Alice:Hello:hello,Bob:World:world"
+    );
+    assert_eq!(
+        run_psql_query("SELECT COUNT(*) FROM pgcrate.schema_migrations", &test_url),
+        "2"
+    );
+    // Indexes, foreign keys and sequences come back too
+    assert_eq!(
+        run_psql_query(
+            "SELECT COUNT(*) FROM pg_indexes WHERE indexname = 'posts_user_id_idx'",
+            &test_url
+        ),
+        "1"
+    );
+    assert!(!run_psql(
+        "INSERT INTO posts (user_id, title) VALUES (99, 'x')",
+        &test_url
+    )
+    .status
+    .success());
+    assert_eq!(
+        run_psql_query(
+            "INSERT INTO users (name) VALUES ('Carol') RETURNING id",
+            &test_url
+        )
+        .lines()
+        .next()
+        .unwrap_or_default(),
+        "3"
+    );
+
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", test_db), &db_url);
+}
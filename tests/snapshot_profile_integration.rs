@@ -98,3 +98,124 @@ fn test_snapshot_save_with_profile() {
 
     run_psql("DROP DATABASE pgcrate_snap_profile", &base_url);
 }
+
+fn psql_query(sql: &str, db_url: &str) -> String {
+    let output = Command::new("psql")
+        .args([db_url, "-t", "-A", "-c", sql])
+        .output()
+        .expect("Failed to execute psql");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn test_snapshot_profile_row_filters() {
+    let base_url = get_test_db_url();
+    if !can_connect(&base_url) {
+        return;
+    }
+
+    let test_db = create_test_db(&base_url, "pgcrate_snap_row_filters");
+    let temp_dir = TempDir::new().unwrap();
+
+    run_psql(
+        "CREATE SCHEMA app;
+         CREATE TABLE app.users (id int PRIMARY KEY, active boolean NOT NULL);
+         CREATE TABLE app.orders (
+             id int PRIMARY KEY,
+             user_id int NOT NULL REFERENCES app.users(id),
+             total numeric,
+             doubled numeric GENERATED ALWAYS AS (total * 2) STORED
+         );
+         CREATE TABLE app.audit_log (id int);
+         INSERT INTO app.users SELECT g, g % 2 = 0 FROM generate_series(1, 10) g;
+         INSERT INTO app.orders SELECT g, g, g * 10 FROM generate_series(1, 10) g;
+         INSERT INTO app.audit_log VALUES (1);",
+        &test_db,
+    );
+
+    // Filtered parent and child: orders must load after users
+    let toml_content = r#"
+        [snapshot.recent]
+        schemas = ["app"]
+        exclude_tables = ["app.audit_log"]
+
+        [snapshot.recent.where]
+        "app.orders" = "user_id IN (SELECT id FROM app.users WHERE active) AND total > 20"
+        "app.users" = "active"
+    "#;
+    fs::write(temp_dir.path().join("pgcrate.snapshot.toml"), toml_content).unwrap();
+
+    let output = run_pgcrate(
+        &["snapshot", "save", "recent", "--profile", "recent"],
+        &test_db,
+        temp_dir.path(),
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "save failed: {}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Filtered: app.users (5 rows kept)"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Filtered: app.orders (4 rows kept)"),
+        "{}",
+        stdout
+    );
+    let snap_dir = temp_dir.path().join(".pgcrate/snapshots/recent");
+    assert!(snap_dir.join("filtered.sql").exists());
+
+    let output = run_pgcrate(&["snapshot", "info", "recent"], &test_db, temp_dir.path());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("app.users WHERE active"));
+
+    let output = run_pgcrate(
+        &["snapshot", "restore", "recent", "--yes"],
+        &test_db,
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "restore failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(psql_query("SELECT count(*) FROM app.users", &test_db), "5");
+    assert_eq!(
+        psql_query(
+            "SELECT string_agg(id::text, ',' ORDER BY id) FROM app.orders",
+            &test_db
+        ),
+        "4,6,8,10"
+    );
+    assert_eq!(
+        psql_query("SELECT doubled FROM app.orders WHERE id = 4", &test_db),
+        "80"
+    );
+    assert_eq!(
+        psql_query("SELECT to_regclass('app.audit_log') IS NULL", &test_db),
+        "t"
+    );
+
+    // Filters must name tables the profile dumps
+    let toml_content = r#"
+        [snapshot.bad]
+        tables = ["app.users"]
+
+        [snapshot.bad.where]
+        "app.orders" = "true"
+    "#;
+    fs::write(temp_dir.path().join("pgcrate.snapshot.toml"), toml_content).unwrap();
+    let output = run_pgcrate(
+        &["snapshot", "save", "bad", "--profile", "bad", "--dry-run"],
+        &test_db,
+        temp_dir.path(),
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not part of the snapshot profile"));
+
+    run_psql("DROP DATABASE pgcrate_snap_row_filters", &base_url);
+}
@@ -0,0 +1,216 @@
+//! Integration tests for `pgcrate subset`.
+//!
+//! These tests require a running PostgreSQL instance.
+//! Set TEST_DATABASE_URL or use the default postgres://localhost/postgres.
+//!
+//! Run with: cargo test --test subset_integration
+//!
+//! Note: Tests will be skipped if database is unavailable.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn get_test_db_url() -> String {
+    env::var("TEST_DATABASE_URL").unwrap_or_else(|_| "postgres://localhost/postgres".to_string())
+}
+
+/// Run pgcrate using the compiled binary
+fn run_pgcrate(args: &[&str], workdir: &Path) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_pgcrate"))
+        .args(args)
+        .env_remove("DATABASE_URL")
+        .current_dir(workdir)
+        .output()
+        .expect("Failed to execute pgcrate")
+}
+
+fn run_psql(sql: &str, db_url: &str) -> std::process::Output {
+    Command::new("psql")
+        .args([db_url, "-v", "ON_ERROR_STOP=1", "-c", sql])
+        .output()
+        .expect("Failed to execute psql")
+}
+
+fn run_psql_query(sql: &str, db_url: &str) -> String {
+    let output = Command::new("psql")
+        .args([db_url, "-t", "-A", "-c", sql])
+        .output()
+        .expect("Failed to execute psql");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Create a test database and return its URL
+fn create_test_db(db_url: &str, name: &str) -> Option<String> {
+    let test_url = db_url
+        .rsplit_once('/')
+        .map(|(base, _)| format!("{}/{}", base, name))
+        .unwrap_or_else(|| format!("{}/{}", db_url, name));
+
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", name), db_url);
+    let create_result = run_psql(&format!("CREATE DATABASE {}", name), db_url);
+    if !create_result.status.success() {
+        return None;
+    }
+    Some(test_url)
+}
+
+fn drop_test_db(db_url: &str, name: &str) {
+    let _ = run_psql(&format!("DROP DATABASE IF EXISTS {}", name), db_url);
+}
+
+const SCHEMA: &str = r#"
+    CREATE SCHEMA app;
+    CREATE TABLE app.countries (code TEXT PRIMARY KEY);
+    CREATE TABLE app.users (
+        id SERIAL PRIMARY KEY,
+        country TEXT NOT NULL REFERENCES app.countries(code)
+    );
+    CREATE TABLE app.products (id SERIAL PRIMARY KEY, name TEXT NOT NULL);
+    CREATE TABLE app.orders (
+        id SERIAL PRIMARY KEY,
+        user_id INT NOT NULL REFERENCES app.users(id)
+    );
+    CREATE TABLE app.order_items (
+        order_id INT NOT NULL REFERENCES app.orders(id),
+        product_id INT NOT NULL REFERENCES app.products(id),
+        PRIMARY KEY (order_id, product_id)
+    );
+"#;
+
+#[test]
+fn test_subset_follows_foreign_keys() {
+    let base_url = get_test_db_url();
+    let Some(source) = create_test_db(&base_url, "pgcrate_subset_source") else {
+        return;
+    };
+    let Some(target) = create_test_db(&base_url, "pgcrate_subset_target") else {
+        drop_test_db(&base_url, "pgcrate_subset_source");
+        return;
+    };
+
+    run_psql(SCHEMA, &source);
+    run_psql(
+        r#"
+        INSERT INTO app.countries VALUES ('US'), ('DE'), ('FR');
+        INSERT INTO app.users (country) SELECT CASE WHEN i % 2 = 0 THEN 'US' ELSE 'DE' END
+            FROM generate_series(1, 10) i;
+        INSERT INTO app.products (name) SELECT 'product ' || i FROM generate_series(1, 5) i;
+        INSERT INTO app.orders (user_id) SELECT (i % 10) + 1 FROM generate_series(1, 30) i;
+        INSERT INTO app.order_items SELECT o.id, (o.id % 5) + 1 FROM app.orders o;
+        "#,
+        &source,
+    );
+    run_psql(SCHEMA, &target);
+
+    let temp_dir = TempDir::new().unwrap();
+    let dump = temp_dir.path().join("subset.sql");
+    let output = run_pgcrate(
+        &[
+            "subset",
+            "--from",
+            &source,
+            "--anchor",
+            "app.users",
+            "--where",
+            "id % 5 = 0",
+            "-o",
+            dump.to_str().unwrap(),
+        ],
+        temp_dir.path(),
+    );
+    assert!(
+        output.status.success(),
+        "subset failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let sql = fs::read_to_string(&dump).unwrap();
+    assert!(sql.contains("-- Anchor: app.users WHERE id % 5 = 0"));
+
+    let load = Command::new("psql")
+        .args([
+            &target,
+            "-v",
+            "ON_ERROR_STOP=1",
+            "-q",
+            "-f",
+            dump.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    assert!(
+        load.status.success(),
+        "dump should load: {}",
+        String::from_utf8_lossy(&load.stderr)
+    );
+
+    // Users 5 and 10, their 6 orders and order items, and only referenced parents
+    assert_eq!(
+        run_psql_query(
+            "SELECT string_agg(id::text, ',' ORDER BY id) FROM app.users",
+            &target
+        ),
+        "5,10"
+    );
+    assert_eq!(
+        run_psql_query("SELECT count(*) FROM app.orders", &target),
+        "6"
+    );
+    assert_eq!(
+        run_psql_query("SELECT count(*) FROM app.order_items", &target),
+        "6"
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT string_agg(code, ',' ORDER BY code) FROM app.countries",
+            &target
+        ),
+        "DE,US"
+    );
+    assert_eq!(
+        run_psql_query(
+            "SELECT count(*) FROM app.products p WHERE NOT EXISTS (SELECT 1 FROM app.order_items i WHERE i.product_id = p.id)",
+            &target
+        ),
+        "0"
+    );
+    // Sequences continue past the loaded rows
+    run_psql("INSERT INTO app.users (country) VALUES ('US')", &target);
+    assert_eq!(
+        run_psql_query("SELECT max(id) FROM app.users", &target),
+        "11"
+    );
+
+    let output = run_pgcrate(
+        &[
+            "subset",
+            "--from",
+            &source,
+            "--anchor",
+            "app.orders",
+            "--where",
+            "id = 1",
+            "--dry-run",
+        ],
+        temp_dir.path(),
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Summary: 5 rows across 5 tables"),
+        "{}",
+        stdout
+    );
+
+    let output = run_pgcrate(
+        &["subset", "--from", &source, "--anchor", "app.missing"],
+        temp_dir.path(),
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Anchor table not found"));
+
+    drop_test_db(&base_url, "pgcrate_subset_source");
+    drop_test_db(&base_url, "pgcrate_subset_target");
+}